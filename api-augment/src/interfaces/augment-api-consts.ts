@@ -6,7 +6,7 @@
 import "@polkadot/api-base/types/consts";
 
 import type { ApiTypes, AugmentedConst } from "@polkadot/api-base/types";
-import type { Option, u128, u16, u32, u64, u8 } from "@polkadot/types-codec";
+import type { Option, bool, u128, u16, u32, u64, u8 } from "@polkadot/types-codec";
 import type { Codec } from "@polkadot/types-codec/types";
 import type { AccountId32, H256, Perbill } from "@polkadot/types/interfaces/runtime";
 import type {
@@ -77,6 +77,32 @@ declare module "@polkadot/api-base/types/consts" {
        * Default replication target
        **/
       defaultReplicationTarget: u32 & AugmentedConst<ApiType>;
+      /**
+       * Deposit held from the owner of a deletion list for each of its files, for the storage the
+       * list takes while it is pending.
+       *
+       * The deposit of each file is returned to the owner once the file is processed, whether it
+       * was deleted or skipped, and the deposit of the remaining files when the list is cancelled.
+       **/
+      deletionListItemDeposit: u128 & AugmentedConst<ApiType>;
+      /**
+       * Number of ticks to wait before retrying a failed deposit transfer for the first time.
+       *
+       * The wait doubles after every failed attempt.
+       **/
+      depositTransferRetryBaseDelay: u32 & AugmentedConst<ApiType>;
+      /**
+       * Fee held from the User when requesting to delete a file without a proof of inclusion.
+       *
+       * It pays whoever executes the deletion by submitting the proof for the pending file
+       * deletion request, and goes to the treasury if the request expires instead. It is capped at
+       * [`Config::MaxFileDeletionFee`].
+       **/
+      fileDeletionFee: u128 & AugmentedConst<ApiType>;
+      /**
+       * Maximum amount of unexpired access grants a bucket, or a file, can have at once.
+       **/
+      maxAccessGrantsPerTarget: u32 & AugmentedConst<ApiType>;
       /**
        * Maximum batch of storage requests that can be confirmed at once when calling `bsp_confirm_storing`.
        **/
@@ -89,14 +115,53 @@ declare module "@polkadot/api-base/types/consts" {
        * Maximum number of multiaddresses for a storage request.
        **/
       maxDataServerMultiAddresses: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of issuers that can have priority challenges in the fair-share queue at the same time.
+       **/
+      maxDeferredPriorityChallengeIssuers: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of priority challenges for file deletions an issuer can have in the fair-share queue.
+       **/
+      maxDeferredPriorityChallengesPerIssuer: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of priority challenges moved from the fair-share queue to the priority challenges queue per block.
+       **/
+      maxDeferredPriorityChallengesQueuedPerBlock: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum depth of the Merkle tree of a deletion list, which bounds the size of the proofs
+       * that a file is part of it. A deletion list holds at most `2^MaxDeletionListProofDepth` files.
+       **/
+      maxDeletionListProofDepth: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of failed deposit transfers that can be pending to be retried at the same time.
+       *
+       * Failed deposit transfers that don't fit are only notified, and have to be followed up manually.
+       **/
+      maxDepositTransferRetries: u32 & AugmentedConst<ApiType>;
+      /**
+       * Number of times a failed deposit transfer is retried before giving up on it.
+       **/
+      maxDepositTransferRetryAttempts: u32 & AugmentedConst<ApiType>;
       /**
        * Maximum number of expired items (per type) to clean up in a single block.
        **/
       maxExpiredItemsInBlock: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum amount of accounts that the owner of a file can grant read access to.
+       **/
+      maxFileAccessListSize: u32 & AugmentedConst<ApiType>;
+      /**
+       * Upper bound of the [`Config::FileDeletionFee`].
+       **/
+      maxFileDeletionFee: u128 & AugmentedConst<ApiType>;
       /**
        * Maximum byte size of a file path.
        **/
       maxFilePathSize: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum length of the notification preferences that a user can set, in bytes.
+       **/
+      maxNotificationPreferencesLength: u32 & AugmentedConst<ApiType>;
       /**
        * Maximum number of peer ids for a storage request.
        **/
@@ -105,6 +170,18 @@ declare module "@polkadot/api-base/types/consts" {
        * Maximum byte size of a peer id.
        **/
       maxPeerIdSize: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of storage requests and move bucket requests checked per block when processing
+       * the Providers that became insolvent or were deregistered.
+       **/
+      maxProviderStatusChangeItemsPerBlock: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of [`Event::StorageRequestExpiringSoon`] events to emit per block.
+       *
+       * The storage requests expiring in the same block are notified together, so this can be
+       * exceeded by up to [`Config::MaxExpiredItemsInBlock`].
+       **/
+      maxStorageRequestExpirationNotificationsPerBlock: u32 & AugmentedConst<ApiType>;
       /**
        * Maximum number of file deletion requests a user can have pending.
        **/
@@ -113,6 +190,15 @@ declare module "@polkadot/api-base/types/consts" {
        * Maximum number of move bucket requests a user can have pending.
        **/
       maxUserPendingMoveBucketRequests: u32 & AugmentedConst<ApiType>;
+      /**
+       * Minimum size a file is billed as by the BSPs storing it.
+       *
+       * Files smaller than this are paid for as if they were of this size, both in the payment
+       * streams with their BSPs and in the escrowed payment of storage requests, since every file
+       * costs Providers roughly the same overhead (i.e. in their Forest and in the challenges they
+       * have to answer) regardless of its size.
+       **/
+      minBillableFileSize: u64 & AugmentedConst<ApiType>;
       /**
        * Number of blocks required to pass between a BSP requesting to stop storing a file and it being able to confirm to stop storing it.
        **/
@@ -121,14 +207,52 @@ declare module "@polkadot/api-base/types/consts" {
        * Time-to-live for a move bucket request, after which the request is considered expired.
        **/
       moveBucketRequestTtl: u32 & AugmentedConst<ApiType>;
+      /**
+       * Deposit held from the User when creating a new MSP-only storage request (with a replication
+       * target of zero), instead of [`Config::StorageRequestCreationDeposit`].
+       **/
+      mspOnlyStorageRequestCreationDeposit: u128 & AugmentedConst<ApiType>;
       /**
        * Time-to-live for a pending file deletion request, after which a priority challenge is sent out to enforce the deletion.
        **/
       pendingFileDeletionRequestTtl: u32 & AugmentedConst<ApiType>;
+      /**
+       * Number of blocks over which the priority challenges for file deletions queued by each issuer are metered.
+       **/
+      priorityChallengesMeteringPeriod: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of priority challenges for file deletions an issuer can queue directly in a metering period.
+       *
+       * Once reached, the issuer's priority challenges are deferred to the fair-share queue instead.
+       **/
+      priorityChallengesSoftLimitPerIssuer: u32 & AugmentedConst<ApiType>;
       /**
        * Deposit held from the User when creating a new storage request
        **/
       storageRequestCreationDeposit: u128 & AugmentedConst<ApiType>;
+      /**
+       * Number of blocks before a storage request expires at which a [`Event::StorageRequestExpiringSoon`]
+       * event is emitted. Zero disables these notifications.
+       *
+       * Should be lower than [`Config::StorageRequestTtl`], otherwise storage requests could be
+       * created too late to be notified.
+       **/
+      storageRequestExpirationNotificationLeadTime: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum share of the storage request creation deposit paid to the BSPs that confirmed storing the
+       * file, as compensation for the data they already downloaded, when the User revokes the storage request.
+       *
+       * The share grows linearly with the ticks elapsed since the storage request was issued, reaching this
+       * maximum after [`Config::StorageRequestRevocationCompensationPeriod`] ticks. It is then scaled by the
+       * ratio of confirmed to required BSPs, and split equally among the confirmed ones. The rest of the
+       * deposit is returned to the User, who gets all of it back if no BSP confirmed yet.
+       **/
+      storageRequestRevocationCompensation: Perbill & AugmentedConst<ApiType>;
+      /**
+       * Amount of ticks after issuing a storage request that it takes for the compensation paid to the BSPs
+       * when it is revoked to reach the full [`Config::StorageRequestRevocationCompensation`] share.
+       **/
+      storageRequestRevocationCompensationPeriod: u32 & AugmentedConst<ApiType>;
       /**
        * Time-to-live for a storage request.
        **/
@@ -257,6 +381,16 @@ declare module "@polkadot/api-base/types/consts" {
        * The base deposit for a new payment stream. The actual deposit will be this constant + the deposit calculated using the `NewStreamDeposit` constant.
        **/
       baseDeposit: u128 & AugmentedConst<ApiType>;
+      /**
+       * The maximum relative change of the price per giga-unit per tick in a single adjustment towards the
+       * target set in [`FiatPriceTarget`].
+       **/
+      maxOraclePriceAdjustment: Perbill & AugmentedConst<ApiType>;
+      /**
+       * The maximum amount of blocks since the last update of the oracle feed for its price to be used.
+       * Older prices are considered stale, and the price per giga-unit per tick is then left as is.
+       **/
+      maxOraclePriceAge: u32 & AugmentedConst<ApiType>;
       /**
        * The maximum amount of Users that a Provider can charge in a single extrinsic execution.
        * This is used to prevent a Provider from charging too many Users in a single block, which could lead to a DoS attack.
@@ -270,6 +404,11 @@ declare module "@polkadot/api-base/types/consts" {
        * obtained from the `CurrentPricePerGigaUnitPerTick` storage.
        **/
       newStreamDeposit: u32 & AugmentedConst<ApiType>;
+      /**
+       * The number of ticks between two adjustments of the price per giga-unit per tick towards the target
+       * set in [`FiatPriceTarget`].
+       **/
+      oraclePriceAdjustmentPeriod: u32 & AugmentedConst<ApiType>;
       /**
        * The treasury account of the runtime, where a fraction of each payment goes.
        **/
@@ -345,10 +484,38 @@ declare module "@polkadot/api-base/types/consts" {
        * then the checkpoint challenge period needs to be at least 10 ticks.
        **/
       checkpointChallengePeriod: u32 & AugmentedConst<ApiType>;
+      /**
+       * The maximum number of ticks whose deadlines can be checked in a single block.
+       *
+       * The [`ChallengesTicker`] advances one tick per block regardless of how many Providers
+       * have to be marked as slashable. When the deadline checks fall behind (see
+       * [`TickToCheckForSlashableProviders`]), they catch up by checking up to this many ticks per
+       * block, keeping the execution of the `on_poll` hook bounded. The number of ticks still to
+       * be checked is recorded in [`TicksCatchUpBacklog`].
+       *
+       * Must be at least 2, so that the checks can catch up while the ticker keeps advancing.
+       **/
+      maxCatchUpTicksPerBlock: u32 & AugmentedConst<ApiType>;
       /**
        * The maximum number of custom challenges that can be made in a single checkpoint block.
        **/
       maxCustomChallengesPerBlock: u32 & AugmentedConst<ApiType>;
+      /**
+       * The maximum encoded size, in bytes, of a proof submitted by a Provider.
+       *
+       * Bounds the contribution of a proof submission to the PoV size of a block. Providers
+       * whose proofs would exceed it (i.e. because of many checkpoint challenges) are expected
+       * to check it before submitting, since the submission would fail.
+       **/
+      maxProofSize: u32 & AugmentedConst<ApiType>;
+      /**
+       * The maximum number of proofs a Provider can submit in a single batch, with
+       * [`Pallet::submit_proofs`].
+       *
+       * Each proof in a batch counts towards the weight of the submission as much as if it were
+       * submitted on its own.
+       **/
+      maxProofsPerBatch: u32 & AugmentedConst<ApiType>;
       /**
        * The maximum number of Providers that can be slashed per tick.
        *
@@ -383,6 +550,22 @@ declare module "@polkadot/api-base/types/consts" {
        * that there was at least one truly _not_ full block in the last [`Config::BlockFullnessPeriod`] blocks.
        **/
       minNotFullBlocksRatio: Perbill & AugmentedConst<ApiType>;
+      /**
+       * The number of proof submission deadlines a Provider can miss within a
+       * [`Config::MissedDeadlineWarningWindow`] before being marked as slashable.
+       *
+       * Missing one of these deadlines only warns the Provider: its deadline is pushed like when
+       * it is marked as slashable, but no failed proof submissions are accrued. Set to zero to
+       * mark Providers as slashable from the first missed deadline.
+       **/
+      missedDeadlinesAllowance: u32 & AugmentedConst<ApiType>;
+      /**
+       * The number of ticks since a Provider is first warned during which its missed deadlines
+       * count towards the [`Config::MissedDeadlinesAllowance`].
+       *
+       * Once the window is over, the next missed deadline starts a new window.
+       **/
+      missedDeadlineWarningWindow: u32 & AugmentedConst<ApiType>;
       /**
        * The number of random challenges that are generated per block, using the random seed
        * generated for that block.
@@ -407,6 +590,10 @@ declare module "@polkadot/api-base/types/consts" {
        * - The slashed funds are transferred.
        **/
       treasury: AccountId32 & AugmentedConst<ApiType>;
+      /**
+       * The priority in the transaction pool of proofs submitted as unsigned transactions.
+       **/
+      unsignedProofPriority: u64 & AugmentedConst<ApiType>;
       /**
        * Generic const
        **/
@@ -429,6 +616,31 @@ declare module "@polkadot/api-base/types/consts" {
        * Type that represents the byte limit of a bucket name.
        **/
       bucketNameLimit: u32 & AugmentedConst<ApiType>;
+      /**
+       * The amount of capacity that each leaf of a capacity attestation plot accounts for.
+       **/
+      capacityAttestationChunkSize: u64 & AugmentedConst<ApiType>;
+      /**
+       * The amount of leaves of a capacity attestation plot that are challenged.
+       **/
+      capacityAttestationSpotChecks: u32 & AugmentedConst<ApiType>;
+      /**
+       * The amount of blocks that a BSP has to commit to its capacity attestation plot, counting from
+       * the block in which it drew the plot's seed, and to answer its challenge, counting from the block
+       * in which it committed to it.
+       **/
+      capacityAttestationWindow: u32 & AugmentedConst<ApiType>;
+      /**
+       * The amount of blocks that a Storage Provider has to offload its data after requesting to decrease
+       * its capacity, before it can confirm the decrease.
+       **/
+      capacityDecreaseGracePeriod: u32 & AugmentedConst<ApiType>;
+      /**
+       * The amount of Storage Hub ticks between two consecutive capacity utilization samples.
+       *
+       * Setting this to zero disables sampling.
+       **/
+      capacityUtilizationSamplingPeriod: u32 & AugmentedConst<ApiType>;
       /**
        * The default value of the root of the Merkle Patricia Trie of the runtime
        **/
@@ -437,10 +649,20 @@ declare module "@polkadot/api-base/types/consts" {
        * The slope of the collateral vs storage capacity curve. In other terms, how many tokens a Storage Provider should add as collateral to increase its storage capacity in one unit of StorageDataUnit.
        **/
       depositPerData: u128 & AugmentedConst<ApiType>;
+      /**
+       * Type that represents the byte limit of the encryption metadata of a bucket.
+       **/
+      encryptionMetadataLimit: u32 & AugmentedConst<ApiType>;
       /**
        * The maximum amount of blocks after which a sign up request expires so the randomness cannot be chosen
        **/
       maxBlocksForRandomness: u32 & AugmentedConst<ApiType>;
+      /**
+       * Maximum number of capacity utilization samples kept on-chain.
+       *
+       * Once this limit is reached, the oldest sample is discarded to make room for the new one.
+       **/
+      maxCapacityUtilizationSamples: u32 & AugmentedConst<ApiType>;
       maxCommitmentSize: u32 & AugmentedConst<ApiType>;
       /**
        * Maximum number of expired items (per type) to clean up in a single block.
@@ -464,6 +686,15 @@ declare module "@polkadot/api-base/types/consts" {
        * The maximum number of protocols the MSP can support (at least within the runtime).
        **/
       maxProtocols: u32 & AugmentedConst<ApiType>;
+      /**
+       * The maximum reputation weight that a BSP can reach by submitting proofs.
+       **/
+      maxReputationWeight: u32 & AugmentedConst<ApiType>;
+      /**
+       * The maximum multiplier that can be applied to the base slash amount due to consecutive
+       * missed proof submission deadlines.
+       **/
+      maxSlashEscalationMultiplier: u32 & AugmentedConst<ApiType>;
       /**
        * The minimum amount of blocks between capacity changes for a SP
        **/
@@ -476,10 +707,45 @@ declare module "@polkadot/api-base/types/consts" {
        * the time when the tick has been reached.
        **/
       providerTopUpTtl: u32 & AugmentedConst<ApiType>;
+      /**
+       * The fraction of the reputation weight earned by a BSP (i.e. above [`Config::StartingReputationWeight`])
+       * that it loses every time it is slashed.
+       **/
+      reputationDecayOnSlash: Perbill & AugmentedConst<ApiType>;
+      /**
+       * The amount of proofs that a BSP has to submit in a row, without becoming slashable, to gain
+       * [`Config::ReputationWeightIncrease`] reputation weight.
+       *
+       * Setting this to zero disables the growth of reputation weights.
+       **/
+      reputationGrowthStreak: u32 & AugmentedConst<ApiType>;
+      /**
+       * The reputation weight that a BSP gains every [`Config::ReputationGrowthStreak`] proofs submitted in a row.
+       **/
+      reputationWeightIncrease: u32 & AugmentedConst<ApiType>;
+      /**
+       * Whether BSPs must attest their capacity (see [`crate::capacity_attestation`]) before
+       * signing up or increasing it.
+       **/
+      requireCapacityAttestation: bool & AugmentedConst<ApiType>;
       /**
        * The slash factor deducted from a Storage Provider's deposit for every single storage proof they fail to provide.
+       *
+       * This is the amount for Storage Providers storing exactly [`Config::SlashReferenceDataSize`] of data.
+       * It is scaled proportionally to the data stored by the Storage Provider, so it is lower for those
+       * storing less than that and higher for those storing more.
        **/
       slashAmountPerMaxFileSize: u128 & AugmentedConst<ApiType>;
+      /**
+       * The extra slash applied for every proof submission deadline missed in a row after the
+       * first one, as a fraction of the base slash amount.
+       **/
+      slashEscalationPerMissedDeadline: Perbill & AugmentedConst<ApiType>;
+      /**
+       * The amount of data for which a failed proof submission is slashed exactly
+       * [`Config::SlashAmountPerMaxFileSize`].
+       **/
+      slashReferenceDataSize: u64 & AugmentedConst<ApiType>;
       /**
        * The amount that a BSP receives as allocation of storage capacity when it deposits SpMinDeposit.
        **/
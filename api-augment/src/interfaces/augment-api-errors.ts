@@ -166,6 +166,18 @@ declare module "@polkadot/api-base/types/errors" {
        * BSP did not succeed threshold check.
        **/
       AboveThreshold: AugmentedError<ApiType>;
+      /**
+       * The expiration tick of the access grant has already been reached
+       **/
+      AccessGrantExpirationInThePast: AugmentedError<ApiType>;
+      /**
+       * The account has no access grant for the bucket or file
+       **/
+      AccessGrantNotFound: AugmentedError<ApiType>;
+      /**
+       * The bucket or file already has the maximum amount of access grants
+       **/
+      AccessGrantsFull: AugmentedError<ApiType>;
       /**
        * BSP has already confirmed storing the given file.
        **/
@@ -194,10 +206,18 @@ declare module "@polkadot/api-base/types/errors" {
        * BSP has not volunteered to store the given file.
        **/
       BspNotVolunteered: AugmentedError<ApiType>;
+      /**
+       * The bucket is already frozen
+       **/
+      BucketAlreadyFrozen: AugmentedError<ApiType>;
       /**
        * Action not allowed while the bucket is being moved.
        **/
       BucketIsBeingMoved: AugmentedError<ApiType>;
+      /**
+       * The bucket is frozen pending the resolution of a dispute
+       **/
+      BucketIsFrozen: AugmentedError<ApiType>;
       /**
        * Bucket is not private. Call `update_bucket_privacy` to make it private.
        **/
@@ -210,6 +230,18 @@ declare module "@polkadot/api-base/types/errors" {
        * Bucket does not exist
        **/
       BucketNotFound: AugmentedError<ApiType>;
+      /**
+       * The bucket is not frozen
+       **/
+      BucketNotFrozen: AugmentedError<ApiType>;
+      /**
+       * The offer to transfer the bucket to another MSP has expired
+       **/
+      BucketTransferOfferExpired: AugmentedError<ApiType>;
+      /**
+       * There is no offer to transfer the bucket to another MSP
+       **/
+      BucketTransferOfferNotFound: AugmentedError<ApiType>;
       /**
        * Cannot hold the required deposit from the user
        **/
@@ -218,6 +250,30 @@ declare module "@polkadot/api-base/types/errors" {
        * Collection ID was not found.
        **/
       CollectionNotFound: AugmentedError<ApiType>;
+      /**
+       * The issuer has reached the maximum number of deferred priority challenges for file deletions
+       **/
+      DeferredPriorityChallengesQueueFull: AugmentedError<ApiType>;
+      /**
+       * The account already registered a deletion list with this root
+       **/
+      DeletionListAlreadyRegistered: AugmentedError<ApiType>;
+      /**
+       * A deletion list must hold at least one file
+       **/
+      DeletionListEmpty: AugmentedError<ApiType>;
+      /**
+       * The account has no deletion list with this root
+       **/
+      DeletionListNotFound: AugmentedError<ApiType>;
+      /**
+       * The deletion list holds more files than its Merkle tree can with the maximum proof depth
+       **/
+      DeletionListTooLong: AugmentedError<ApiType>;
+      /**
+       * There is no failed deposit transfer pending to be retried with the given ID
+       **/
+      DepositTransferRetryNotFound: AugmentedError<ApiType>;
       /**
        * Divided by 0
        **/
@@ -226,6 +282,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Failed to fetch the dynamic-rate payment stream.
        **/
       DynamicRatePaymentStreamNotFound: AugmentedError<ApiType>;
+      /**
+       * Batch of files to delete is empty.
+       **/
+      EmptyFileDeletionBatch: AugmentedError<ApiType>;
       /**
        * Failed to verify proof: required to provide a proof of inclusion.
        **/
@@ -258,6 +318,22 @@ declare module "@polkadot/api-base/types/errors" {
        * Failed to convert to primitive type.
        **/
       FailedTypeConversion: AugmentedError<ApiType>;
+      /**
+       * The account already has read access to the file
+       **/
+      FileAccessAlreadyGranted: AugmentedError<ApiType>;
+      /**
+       * The access list of the file is full
+       **/
+      FileAccessListFull: AugmentedError<ApiType>;
+      /**
+       * The account does not have read access to the file
+       **/
+      FileAccessNotGranted: AugmentedError<ApiType>;
+      /**
+       * Files paid for through an escrow can't be moved, since the escrow is bound to their file key.
+       **/
+      FileHasEscrowedPayment: AugmentedError<ApiType>;
       /**
        * File key already pending deletion.
        **/
@@ -270,6 +346,22 @@ declare module "@polkadot/api-base/types/errors" {
        * The bounded vector that holds file metadata to process it is full but there's still more to process.
        **/
       FileMetadataProcessingQueueFull: AugmentedError<ApiType>;
+      /**
+       * A move of the file to a new location was already requested.
+       **/
+      FileMoveAlreadyRequested: AugmentedError<ApiType>;
+      /**
+       * No move of the file to a new location was requested.
+       **/
+      FileMoveRequestNotFound: AugmentedError<ApiType>;
+      /**
+       * The new location of the file is the same as its current one.
+       **/
+      FileMoveToSameLocation: AugmentedError<ApiType>;
+      /**
+       * The file was not moved to a new location.
+       **/
+      FileNotMoved: AugmentedError<ApiType>;
       /**
        * File size cannot be zero.
        **/
@@ -294,6 +386,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Bucket id and file key pair is invalid.
        **/
       InvalidBucketIdFileKeyPair: AugmentedError<ApiType>;
+      /**
+       * The proof that the file is part of the deletion list is invalid
+       **/
+      InvalidDeletionListProof: AugmentedError<ApiType>;
       /**
        * Metadata does not correspond to expected file key.
        **/
@@ -331,10 +427,18 @@ declare module "@polkadot/api-base/types/errors" {
        * The MSP is already storing the bucket.
        **/
       MspAlreadyStoringBucket: AugmentedError<ApiType>;
+      /**
+       * The MSP is not eligible to take new buckets or storage requests, as per the MSP eligibility list.
+       **/
+      MspNotEligible: AugmentedError<ApiType>;
       /**
        * Unauthorized operation, signer is not an MSP of the bucket id.
        **/
       MspNotStoringBucket: AugmentedError<ApiType>;
+      /**
+       * Storage requests with a replication target of zero (MSP-only) must specify an MSP.
+       **/
+      MspOnlyStorageRequestWithoutMsp: AugmentedError<ApiType>;
       /**
        * No file keys to confirm storing
        **/
@@ -371,10 +475,18 @@ declare module "@polkadot/api-base/types/errors" {
        * The MSP is trying to confirm to store a file from a storage request is not the one selected to store it.
        **/
       NotSelectedMsp: AugmentedError<ApiType>;
+      /**
+       * Operations not allowed for a provider that scheduled its sign off
+       **/
+      OperationNotAllowedForExitingProvider: AugmentedError<ApiType>;
       /**
        * Operations not allowed for insolvent provider
        **/
       OperationNotAllowedForInsolventProvider: AugmentedError<ApiType>;
+      /**
+       * Operations not allowed for a provider suspended by governance
+       **/
+      OperationNotAllowedForSuspendedProvider: AugmentedError<ApiType>;
       /**
        * Pending stop storing request already exists.
        **/
@@ -387,6 +499,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Root of the provider not found.
        **/
       ProviderRootNotFound: AugmentedError<ApiType>;
+      /**
+       * The replication target cannot be lower than the amount of BSPs that already confirmed storing the file
+       **/
+      ReplicationTargetBelowConfirmedBsps: AugmentedError<ApiType>;
       /**
        * Replication target cannot be zero.
        **/
@@ -395,6 +511,10 @@ declare module "@polkadot/api-base/types/errors" {
        * BSPs required for storage request cannot exceed the maximum allowed.
        **/
       ReplicationTargetExceedsMaximum: AugmentedError<ApiType>;
+      /**
+       * The new replication target of the storage request is the same as the current one
+       **/
+      ReplicationTargetUnchanged: AugmentedError<ApiType>;
       /**
        * The MSP is trying to confirm to store a file from a storage request that does not have a MSP assigned.
        **/
@@ -419,6 +539,10 @@ declare module "@polkadot/api-base/types/errors" {
        * No slot available found in blocks to insert storage request expiration time.
        **/
       StorageRequestExpiredNoSlotAvailable: AugmentedError<ApiType>;
+      /**
+       * BSPs cannot volunteer for a storage request with a replication target of zero (MSP-only).
+       **/
+      StorageRequestIsMspOnly: AugmentedError<ApiType>;
       /**
        * Not authorized to delete the storage request.
        **/
@@ -455,6 +579,26 @@ declare module "@polkadot/api-base/types/errors" {
        * Number of removed BSPs volunteered from storage request prefix did not match the expected number.
        **/
       UnexpectedNumberOfRemovedVolunteeredBsps: AugmentedError<ApiType>;
+      /**
+       * The size budget of the upload grant cannot be zero
+       **/
+      UploadGrantBudgetCannotBeZero: AugmentedError<ApiType>;
+      /**
+       * The size of the file exceeds the remaining budget of the upload grant
+       **/
+      UploadGrantBudgetExceeded: AugmentedError<ApiType>;
+      /**
+       * The expiration block of the upload grant has already been reached
+       **/
+      UploadGrantExpirationInThePast: AugmentedError<ApiType>;
+      /**
+       * The upload grant has expired
+       **/
+      UploadGrantExpired: AugmentedError<ApiType>;
+      /**
+       * The account has no upload grant for the bucket
+       **/
+      UploadGrantNotFound: AugmentedError<ApiType>;
       /**
        * A SP tried to stop storing files from a user that was supposedly insolvent, but the user is not insolvent.
        **/
@@ -742,10 +886,26 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when trying to create a new dynamic-rate payment stream with amount provided 0 or update the amount provided of an existing one to 0 (should use remove_dynamic_rate_payment_stream instead)
        **/
       AmountProvidedCantBeZero: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to deposit or withdraw zero funds to or from the auto top-up reserve
+       **/
+      AutoTopUpAmountCantBeZero: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to set an auto top-up threshold of zero (should clear it instead)
+       **/
+      AutoTopUpThresholdCantBeZero: AugmentedError<ApiType>;
+      /**
+       * Error thrown when the system can't hold the funds the User wants to add to its auto top-up reserve
+       **/
+      CannotHoldAutoTopUpReserve: AugmentedError<ApiType>;
       /**
        * Error thrown when the system can't hold funds from the User as a deposit for creating a new payment stream
        **/
       CannotHoldDeposit: AugmentedError<ApiType>;
+      /**
+       * Error thrown when the system can't hold the escrowed funds from the User
+       **/
+      CannotHoldEscrow: AugmentedError<ApiType>;
       /**
        * Error thrown when charging a payment stream would result in an overflow of the balance type
        **/
@@ -754,6 +914,34 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when a user tries to clear the flag of being without funds before the cooldown period has passed
        **/
       CooldownPeriodNotPassed: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to open an escrow with an ID that is already in use
+       **/
+      EscrowAlreadyExists: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to operate on an escrow that does not exist
+       **/
+      EscrowNotFound: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to open an escrow for zero Providers, for zero ticks or for a zero amount provided
+       **/
+      EscrowParamsCantBeZero: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to add a Provider to an escrow that already pays its target amount of Providers
+       **/
+      EscrowProvidersTargetReached: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to add a Provider to an escrow whose replication has already been settled
+       **/
+      EscrowReplicationAlreadySettled: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to set a target price in the reference currency of zero (should clear it instead)
+       **/
+      FiatPriceTargetCantBeZero: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to withdraw more funds than there are in the auto top-up reserve
+       **/
+      InsufficientAutoTopUpReserve: AugmentedError<ApiType>;
       /**
        * Error thrown when the new last chargeable tick number that is trying to be set is greater than the current tick number or smaller than the previous last chargeable tick number
        **/
@@ -778,6 +966,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when a user of this pallet tries to update, remove or charge a payment stream that does not exist.
        **/
       PaymentStreamNotFound: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to add a Provider to an escrow that already pays it
+       **/
+      ProviderAlreadyInEscrow: AugmentedError<ApiType>;
       /**
        * Error thrown when failing to get the payment account of a registered Provider
        **/
@@ -786,6 +978,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when a charge is attempted when the provider is marked as insolvent
        **/
       ProviderInsolvent: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to release or remove the escrowed payment of a Provider that is not in the escrow
+       **/
+      ProviderNotInEscrow: AugmentedError<ApiType>;
       /**
        * Error thrown when trying to create a new fixed-rate payment stream with rate 0 or update the rate of an existing one to 0 (should use remove_fixed_rate_payment_stream instead)
        **/
@@ -921,6 +1117,15 @@ declare module "@polkadot/api-base/types/errors" {
       [key: string]: AugmentedError<ApiType>;
     };
     proofsDealer: {
+      /**
+       * The challenge period override plus [`Config::ChallengeTicksTolerance`] is not shorter
+       * than [`Config::CheckpointChallengePeriod`].
+       **/
+      ChallengePeriodOverrideTooLong: AugmentedError<ApiType>;
+      /**
+       * The challenge period override is shorter than [`Config::MinChallengePeriod`].
+       **/
+      ChallengePeriodOverrideTooShort: AugmentedError<ApiType>;
       /**
        * `challenge` extrinsic errors
        * The ChallengesQueue is full. No more manual challenges can be made
@@ -952,6 +1157,10 @@ declare module "@polkadot/api-base/types/errors" {
        * There are no key proofs submitted.
        **/
       EmptyKeyProofs: AugmentedError<ApiType>;
+      /**
+       * A batch of proofs was submitted without any proof.
+       **/
+      EmptyProofBatch: AugmentedError<ApiType>;
       /**
        * Failed to apply delta to the forest proof partial trie.
        **/
@@ -974,6 +1183,11 @@ declare module "@polkadot/api-base/types/errors" {
        * The number of key proofs submitted does not match the number of keys proven in the forest proof.
        **/
       IncorrectNumberOfKeyProofs: AugmentedError<ApiType>;
+      /**
+       * The signature of a proof submitted as an unsigned transaction is not from the owner of
+       * the Provider, or not for the tick the Provider has to submit a proof for.
+       **/
+      InvalidProofSignature: AugmentedError<ApiType>;
       /**
        * There is at least one key proven in the forest proof, that does not have a corresponding
        * key proof.
@@ -1003,6 +1217,15 @@ declare module "@polkadot/api-base/types/errors" {
        * until some of the challenges in the queue are dispatched.
        **/
       PriorityChallengesQueueOverflow: AugmentedError<ApiType>;
+      /**
+       * The encoded size of the submitted proof exceeds [`Config::MaxProofSize`].
+       **/
+      ProofTooLarge: AugmentedError<ApiType>;
+      /**
+       * The Provider is registered but its status does not allow it to take on new duties
+       * (e.g. it is insolvent, suspended or exiting).
+       **/
+      ProviderNotActive: AugmentedError<ApiType>;
       /**
        * The root for the Provider could not be found.
        **/
@@ -1011,6 +1234,11 @@ declare module "@polkadot/api-base/types/errors" {
        * The provider stake could not be found.
        **/
       ProviderStakeNotFound: AugmentedError<ApiType>;
+      /**
+       * The retention policy would not keep challenge seeds for longer than
+       * [`Config::ChallengeTicksTolerance`], or would not keep any valid proof submitters.
+       **/
+      RetentionTooShort: AugmentedError<ApiType>;
       /**
        * The seed for the tick could not be found.
        * This should not be possible for a tick within the `ChallengeHistoryLength` range, as
@@ -1026,6 +1254,11 @@ declare module "@polkadot/api-base/types/errors" {
        * The limit of Providers that can submit a proof in a single tick has been reached.
        **/
       TooManyValidProofSubmitters: AugmentedError<ApiType>;
+      /**
+       * A proof in a batch is paired with a tick other than the one the Provider has to submit a
+       * proof for after the previous proofs in the batch.
+       **/
+      UnexpectedChallengesTick: AugmentedError<ApiType>;
       /**
        * After successfully applying delta for a set of mutations, the number of mutated keys is
        * not the same as the number of mutations expected to have been applied.
@@ -1059,6 +1292,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when a bucket ID already exists in storage.
        **/
       BucketAlreadyExists: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a bucket is already using the latest version of its value proposition.
+       **/
+      BucketAlreadyOnLatestValueProposition: AugmentedError<ApiType>;
       /**
        * Error thrown when a bucket has no value proposition.
        **/
@@ -1083,6 +1320,44 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when the runtime cannot hold the required deposit from the account to register it as a SP or change its capacity.
        **/
       CannotHoldDeposit: AugmentedError<ApiType>;
+      /**
+       * Error thrown when drawing the seed of a capacity attestation plot, committing to it or answering its
+       * challenge after the time to do so has passed.
+       **/
+      CapacityAttestationExpired: AugmentedError<ApiType>;
+      /**
+       * Error thrown when going on with a capacity attestation without having requested one.
+       **/
+      CapacityAttestationNotFound: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a SP requests to decrease its capacity while it already has a pending capacity decrease.
+       **/
+      CapacityDecreaseAlreadyRequested: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a SP tries to confirm a capacity decrease before its grace period has passed.
+       **/
+      CapacityDecreaseGracePeriodNotPassed: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a SP tries to confirm a capacity decrease that it has not requested.
+       **/
+      CapacityDecreaseNotRequested: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a BSP tries to sign up or increase its capacity beyond the capacity it has attested.
+       **/
+      CapacityNotAttested: AugmentedError<ApiType>;
+      /**
+       * Error thrown when answering a capacity challenge without having committed to a capacity attestation plot.
+       **/
+      CapacityPlotNotCommitted: AugmentedError<ApiType>;
+      /**
+       * Error thrown when drawing the seed of a capacity attestation plot that was already drawn.
+       **/
+      CapacityPlotSeedAlreadyDrawn: AugmentedError<ApiType>;
+      /**
+       * Error thrown when committing to a capacity attestation plot before drawing its seed, or after committing
+       * to it already.
+       **/
+      CapacityPlotSeedNotDrawn: AugmentedError<ApiType>;
       /**
        * Failed to delete a provider due to conditions not being met.
        *
@@ -1097,6 +1372,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when a fixed payment stream is not found.
        **/
       FixedRatePaymentStreamNotFound: AugmentedError<ApiType>;
+      /**
+       * Error thrown when the proofs answering a capacity challenge are invalid or do not match the challenged leaves.
+       **/
+      InvalidCapacityAttestationProof: AugmentedError<ApiType>;
       /**
        * Error thrown when failing to decode the owner Account ID from the received metadata.
        **/
@@ -1145,6 +1424,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when a user tries to change its capacity to less than its used storage.
        **/
       NewCapacityLessThanUsedStorage: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a SP requests to decrease its capacity to a value that is not less than its current capacity.
+       **/
+      NewCapacityNotLessThanCurrentCapacity: AugmentedError<ApiType>;
       /**
        * Error thrown when a SP tries to change its capacity but the new capacity is not enough to store the used storage.
        **/
@@ -1181,14 +1464,30 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when trying to update a payment stream that does not exist.
        **/
       PaymentStreamNotFound: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to suspend a SP that is already suspended.
+       **/
+      ProviderAlreadySuspended: AugmentedError<ApiType>;
       /**
        * Error thrown when an attempt was made to slash an unslashable Storage Provider.
        **/
       ProviderNotSlashable: AugmentedError<ApiType>;
+      /**
+       * Error thrown when trying to resume a SP that is not suspended.
+       **/
+      ProviderNotSuspended: AugmentedError<ApiType>;
       /**
        * Error thrown when a user tries to confirm a sign up but the randomness is too fresh to be used yet.
        **/
       RandomnessNotValidYet: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a SP tries to schedule its sign off but it has already scheduled it.
+       **/
+      SignOffAlreadyScheduled: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a SP tries to cancel its scheduled sign off but it has not scheduled it.
+       **/
+      SignOffNotScheduled: AugmentedError<ApiType>;
       /**
        * Error thrown when a user tries to sign off as a BSP but the sign off period has not passed yet.
        **/
@@ -1233,6 +1532,10 @@ declare module "@polkadot/api-base/types/errors" {
        * Error thrown when the value proposition id is not found.
        **/
       ValuePropositionNotFound: AugmentedError<ApiType>;
+      /**
+       * Error thrown when a value proposition has been superseded by a newer version.
+       **/
+      ValuePropositionSuperseded: AugmentedError<ApiType>;
       /**
        * Generic error
        **/
@@ -26,16 +26,23 @@ import type {
   FrameSupportDispatchDispatchInfo,
   FrameSupportMessagesProcessMessageError,
   FrameSupportTokensMiscBalanceStatus,
+  PalletFileSystemAccessGrantTarget,
+  PalletFileSystemDepositTransfer,
   PalletFileSystemEitherAccountIdOrMspId,
+  PalletFileSystemMspEligibilityListMode,
   PalletFileSystemRejectedStorageRequestReason,
   PalletNftsAttributeNamespace,
   PalletNftsPalletAttributes,
   PalletNftsPriceWithDirection,
   PalletProofsDealerProof,
+  PalletProofsDealerRetentionPolicy,
   PalletStorageProvidersStorageProviderId,
   PalletStorageProvidersTopUpMetadata,
   PalletStorageProvidersValueProposition,
+  PalletStorageProvidersValuePropositionVersion,
   PalletStorageProvidersValuePropositionWithId,
+  ShpTraitsProviderStatus,
+  ShpTraitsProviderStatusChange,
   ShpTraitsTrieMutation,
   ShpTraitsTrieRemoveMutation,
   SpRuntimeDispatchError,
@@ -397,6 +404,41 @@ declare module "@polkadot/api-base/types/events" {
           size_: u64;
         }
       >;
+      /**
+       * Notifies that the owner of a bucket gave `grantee` read access to `target` until tick
+       * `expires_at`, replacing any previous grant.
+       **/
+      AccessGrantCreated: AugmentedEvent<
+        ApiType,
+        [
+          owner: AccountId32,
+          target: PalletFileSystemAccessGrantTarget,
+          grantee: AccountId32,
+          expiresAt: u32
+        ],
+        {
+          owner: AccountId32;
+          target: PalletFileSystemAccessGrantTarget;
+          grantee: AccountId32;
+          expiresAt: u32;
+        }
+      >;
+      /**
+       * Notifies that the access grant of `grantee` to `target` expired and was removed.
+       **/
+      AccessGrantExpired: AugmentedEvent<
+        ApiType,
+        [target: PalletFileSystemAccessGrantTarget, grantee: AccountId32],
+        { target: PalletFileSystemAccessGrantTarget; grantee: AccountId32 }
+      >;
+      /**
+       * Notifies that the owner of a bucket revoked the access grant of `grantee` to `target`.
+       **/
+      AccessGrantRevoked: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, target: PalletFileSystemAccessGrantTarget, grantee: AccountId32],
+        { owner: AccountId32; target: PalletFileSystemAccessGrantTarget; grantee: AccountId32 }
+      >;
       /**
        * Notifies that a BSP's challenge cycle has been initialised, adding the first file
        * key(s) to the BSP's Merkle Patricia Forest.
@@ -406,6 +448,23 @@ declare module "@polkadot/api-base/types/events" {
         [who: AccountId32, bspId: H256],
         { who: AccountId32; bspId: H256 }
       >;
+      /**
+       * Notifies that a BSP that confirmed storing a file was paid a compensation, out of the storage
+       * request creation deposit, because its owner revoked the storage request.
+       **/
+      BspCompensatedForRevokedStorageRequest: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, bspId: H256, amount: u128],
+        { fileKey: H256; bspId: H256; amount: u128 }
+      >;
+      /**
+       * Notifies that a BSP moved a file to its new location in its forest.
+       **/
+      BspConfirmedFileMove: AugmentedEvent<
+        ApiType,
+        [bspId: H256, fileKey: H256, newFileKey: H256, newRoot: H256],
+        { bspId: H256; fileKey: H256; newFileKey: H256; newRoot: H256 }
+      >;
       /**
        * Notifies that a BSP confirmed storing a file(s).
        **/
@@ -439,6 +498,14 @@ declare module "@polkadot/api-base/types/events" {
         [bspId: H256, fileKey: H256, owner: AccountId32, location: Bytes],
         { bspId: H256; fileKey: H256; owner: AccountId32; location: Bytes }
       >;
+      /**
+       * Notifies that a bucket's default replication target has been updated.
+       **/
+      BucketDefaultReplicationTargetUpdated: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, bucketId: H256, replicationTarget: Option<u32>],
+        { who: AccountId32; bucketId: H256; replicationTarget: Option<u32> }
+      >;
       /**
        * Notifies that an empty bucket has been deleted.
        **/
@@ -447,6 +514,22 @@ declare module "@polkadot/api-base/types/events" {
         [who: AccountId32, bucketId: H256, maybeCollectionId: Option<u32>],
         { who: AccountId32; bucketId: H256; maybeCollectionId: Option<u32> }
       >;
+      /**
+       * Notifies that the encryption metadata of a bucket has been set or, if `None`, removed.
+       **/
+      BucketEncryptionMetadataUpdated: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, bucketId: H256, encryptionMetadata: Option<Bytes>],
+        { who: AccountId32; bucketId: H256; encryptionMetadata: Option<Bytes> }
+      >;
+      /**
+       * Notifies that a bucket has been frozen by governance pending the resolution of a dispute.
+       **/
+      BucketFrozen: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, owner: AccountId32, mspId: Option<H256>],
+        { bucketId: H256; owner: AccountId32; mspId: Option<H256> }
+      >;
       /**
        * Notifies that a bucket's privacy has been updated.
        **/
@@ -455,6 +538,176 @@ declare module "@polkadot/api-base/types/events" {
         [who: AccountId32, bucketId: H256, collectionId: Option<u32>, private: bool],
         { who: AccountId32; bucketId: H256; collectionId: Option<u32>; private: bool }
       >;
+      /**
+       * Notifies that the owner of a bucket accepted the offer of its MSP to transfer it to
+       * `new_msp_id`. It is followed by the [`Event::MoveBucketRequested`] that `new_msp_id` has
+       * to accept for the bucket to be moved.
+       **/
+      BucketTransferOfferAccepted: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, mspId: H256, newMspId: H256],
+        { bucketId: H256; mspId: H256; newMspId: H256 }
+      >;
+      /**
+       * Notifies that the MSP storing a bucket offered to transfer it to `new_msp_id`. The owner
+       * of the bucket can accept the offer until block `expires_at`.
+       **/
+      BucketTransferOffered: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, mspId: H256, newMspId: H256, owner: AccountId32, expiresAt: u32],
+        { bucketId: H256; mspId: H256; newMspId: H256; owner: AccountId32; expiresAt: u32 }
+      >;
+      /**
+       * Notifies that the owner of a bucket rejected the offer of its MSP to transfer it to
+       * `new_msp_id`.
+       **/
+      BucketTransferOfferRejected: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, mspId: H256, newMspId: H256],
+        { bucketId: H256; mspId: H256; newMspId: H256 }
+      >;
+      /**
+       * Notifies that a frozen bucket has been unfrozen by governance.
+       **/
+      BucketUnfrozen: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, owner: AccountId32, mspId: Option<H256>],
+        { bucketId: H256; owner: AccountId32; mspId: Option<H256> }
+      >;
+      /**
+       * Notifies that a deferred priority challenge for file deletion has been moved from the fair-share
+       * queue to the priority challenges queue.
+       **/
+      DeferredPriorityChallengeForFileDeletionQueued: AugmentedEvent<
+        ApiType,
+        [issuer: PalletFileSystemEitherAccountIdOrMspId, fileKey: H256],
+        { issuer: PalletFileSystemEitherAccountIdOrMspId; fileKey: H256 }
+      >;
+      /**
+       * Notifies that the owner of a deletion list cancelled it, after `executed` of its files
+       * were requested to be deleted.
+       **/
+      DeletionListCancelled: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, listRoot: H256, executed: u32],
+        { owner: AccountId32; listRoot: H256; executed: u32 }
+      >;
+      /**
+       * Notifies that all the files of a deletion list have been processed.
+       **/
+      DeletionListCompleted: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, listRoot: H256],
+        { owner: AccountId32; listRoot: H256 }
+      >;
+      /**
+       * Notifies that the file at `index` of a deletion list will be deleted. Behaves as
+       * [`Event::FileDeletionRequest`] for the file.
+       **/
+      DeletionListFileDeletionRequest: AugmentedEvent<
+        ApiType,
+        [
+          owner: AccountId32,
+          listRoot: H256,
+          index: u32,
+          fileKey: H256,
+          bucketId: H256,
+          mspId: Option<H256>,
+          proofOfInclusion: bool
+        ],
+        {
+          owner: AccountId32;
+          listRoot: H256;
+          index: u32;
+          fileKey: H256;
+          bucketId: H256;
+          mspId: Option<H256>;
+          proofOfInclusion: bool;
+        }
+      >;
+      /**
+       * Notifies that the file at `index` of a deletion list was skipped, since requesting its
+       * deletion failed with `error`, i.e. because it no longer belongs to the owner of the list.
+       **/
+      DeletionListItemSkipped: AugmentedEvent<
+        ApiType,
+        [
+          owner: AccountId32,
+          listRoot: H256,
+          index: u32,
+          fileKey: H256,
+          error: SpRuntimeDispatchError
+        ],
+        {
+          owner: AccountId32;
+          listRoot: H256;
+          index: u32;
+          fileKey: H256;
+          error: SpRuntimeDispatchError;
+        }
+      >;
+      /**
+       * Notifies that an account authorised the deletion of a list of `length` files, identified
+       * by the root of its Merkle tree, holding `deposit` for them.
+       **/
+      DeletionListRegistered: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, listRoot: H256, length: u32, deposit: u128],
+        { owner: AccountId32; listRoot: H256; length: u32; deposit: u128 }
+      >;
+      /**
+       * Notifies that a failed deposit transfer was given up on after
+       * [`Config::MaxDepositTransferRetryAttempts`] retries. It has to be followed up manually.
+       **/
+      DepositTransferAbandoned: AugmentedEvent<
+        ApiType,
+        [retryId: u32, transfer: PalletFileSystemDepositTransfer, error: SpRuntimeDispatchError],
+        { retryId: u32; transfer: PalletFileSystemDepositTransfer; error: SpRuntimeDispatchError }
+      >;
+      /**
+       * Notifies that a transfer of funds held or escrowed by this pallet failed.
+       *
+       * If there was room in the retry queue, it is retried in the next blocks under `retry_id`.
+       * Otherwise, it has to be followed up manually.
+       **/
+      DepositTransferFailed: AugmentedEvent<
+        ApiType,
+        [
+          transfer: PalletFileSystemDepositTransfer,
+          retryId: Option<u32>,
+          error: SpRuntimeDispatchError
+        ],
+        {
+          transfer: PalletFileSystemDepositTransfer;
+          retryId: Option<u32>;
+          error: SpRuntimeDispatchError;
+        }
+      >;
+      /**
+       * Notifies that a retry of a failed deposit transfer failed, and when it will be retried again.
+       **/
+      DepositTransferRetryFailed: AugmentedEvent<
+        ApiType,
+        [retryId: u32, attempts: u32, nextAttemptAt: u32, error: SpRuntimeDispatchError],
+        { retryId: u32; attempts: u32; nextAttemptAt: u32; error: SpRuntimeDispatchError }
+      >;
+      /**
+       * Notifies that a failed deposit transfer was retried successfully.
+       **/
+      DepositTransferRetrySucceeded: AugmentedEvent<
+        ApiType,
+        [retryId: u32, transfer: PalletFileSystemDepositTransfer],
+        { retryId: u32; transfer: PalletFileSystemDepositTransfer }
+      >;
+      /**
+       * Notifies that a failed deposit transfer was removed from the retry queue by governance,
+       * without executing it.
+       **/
+      DepositTransferWrittenOff: AugmentedEvent<
+        ApiType,
+        [retryId: u32, transfer: PalletFileSystemDepositTransfer],
+        { retryId: u32; transfer: PalletFileSystemDepositTransfer }
+      >;
       /**
        * Failed to decrease bucket size for expired file deletion request
        **/
@@ -483,6 +736,22 @@ declare module "@polkadot/api-base/types/events" {
         [user: AccountId32, fileKey: H256],
         { user: AccountId32; fileKey: H256 }
       >;
+      /**
+       * Notifies that the owner of a file granted read access to it to an account.
+       **/
+      FileAccessGranted: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, bucketId: H256, fileKey: H256, account: AccountId32],
+        { owner: AccountId32; bucketId: H256; fileKey: H256; account: AccountId32 }
+      >;
+      /**
+       * Notifies that the owner of a file revoked the read access to it of an account.
+       **/
+      FileAccessRevoked: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, bucketId: H256, fileKey: H256, account: AccountId32],
+        { owner: AccountId32; bucketId: H256; fileKey: H256; account: AccountId32 }
+      >;
       /**
        * Notifies that a file will be deleted.
        **/
@@ -503,6 +772,44 @@ declare module "@polkadot/api-base/types/events" {
           proofOfInclusion: bool;
         }
       >;
+      /**
+       * Notifies that a batch of files will be deleted.
+       **/
+      FileDeletionRequestsBatch: AugmentedEvent<
+        ApiType,
+        [user: AccountId32, bucketId: H256, mspId: Option<H256>, fileKeys: Vec<H256>],
+        { user: AccountId32; bucketId: H256; mspId: Option<H256>; fileKeys: Vec<H256> }
+      >;
+      /**
+       * Notifies that the MSP of a bucket moved a file to its new location in the bucket's forest.
+       **/
+      FileMoved: AugmentedEvent<
+        ApiType,
+        [mspId: H256, bucketId: H256, fileKey: H256, newFileKey: H256, newBucketRoot: H256],
+        { mspId: H256; bucketId: H256; fileKey: H256; newFileKey: H256; newBucketRoot: H256 }
+      >;
+      /**
+       * Notifies that the owner of a file requested to move it to a new location within its bucket.
+       **/
+      FileMoveRequested: AugmentedEvent<
+        ApiType,
+        [
+          owner: AccountId32,
+          bucketId: H256,
+          mspId: Option<H256>,
+          fileKey: H256,
+          newFileKey: H256,
+          newLocation: Bytes
+        ],
+        {
+          owner: AccountId32;
+          bucketId: H256;
+          mspId: Option<H256>;
+          fileKey: H256;
+          newFileKey: H256;
+          newLocation: Bytes;
+        }
+      >;
       /**
        * Notifies that a bucket has been moved to a new MSP.
        **/
@@ -519,6 +826,15 @@ declare module "@polkadot/api-base/types/events" {
         [bucketId: H256, mspId: H256],
         { bucketId: H256; mspId: H256 }
       >;
+      /**
+       * Notifies that a move bucket request has been cancelled because the MSP it targeted became
+       * insolvent or was deregistered.
+       **/
+      MoveBucketRequestCancelledByUnavailableMsp: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, mspId: H256, status: ShpTraitsProviderStatusChange],
+        { bucketId: H256; mspId: H256; status: ShpTraitsProviderStatusChange }
+      >;
       /**
        * Notifies that a bucket is being moved to a new MSP.
        **/
@@ -547,6 +863,22 @@ declare module "@polkadot/api-base/types/events" {
        * MSP acceptance. In such cases, a [`StorageRequestFulfilled`] event is emitted instead.
        **/
       MspAcceptedStorageRequest: AugmentedEvent<ApiType, [fileKey: H256], { fileKey: H256 }>;
+      /**
+       * Notifies that an MSP has been added to the MSP eligibility list.
+       **/
+      MspAddedToEligibilityList: AugmentedEvent<ApiType, [mspId: H256], { mspId: H256 }>;
+      /**
+       * Notifies that the MSP eligibility list is now interpreted with the given mode.
+       **/
+      MspEligibilityModeSet: AugmentedEvent<
+        ApiType,
+        [mode: PalletFileSystemMspEligibilityListMode],
+        { mode: PalletFileSystemMspEligibilityListMode }
+      >;
+      /**
+       * Notifies that an MSP has been removed from the MSP eligibility list.
+       **/
+      MspRemovedFromEligibilityList: AugmentedEvent<ApiType, [mspId: H256], { mspId: H256 }>;
       /**
        * Notifies that a MSP has stopped storing a bucket.
        **/
@@ -589,6 +921,18 @@ declare module "@polkadot/api-base/types/events" {
         [who: AccountId32, bucketId: H256, collectionId: u32],
         { who: AccountId32; bucketId: H256; collectionId: u32 }
       >;
+      /**
+       * Notifies that a new storage request has been issued with a replication target of zero, so it
+       * will not be replicated to any BSP.
+       *
+       * The file is only stored by the MSP, which means it has lower durability guarantees than
+       * a file backed up by BSPs. Emitted alongside [`Event::NewStorageRequest`].
+       **/
+      NewMspOnlyStorageRequest: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, mspId: H256],
+        { fileKey: H256; mspId: H256 }
+      >;
       /**
        * Notifies that a new file has been requested to be stored.
        **/
@@ -613,6 +957,23 @@ declare module "@polkadot/api-base/types/events" {
           peerIds: Vec<Bytes>;
         }
       >;
+      /**
+       * Notifies that a user set or, if `None`, cleared their notification preferences.
+       **/
+      NotificationPreferencesSet: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, preferences: Option<Bytes>],
+        { who: AccountId32; preferences: Option<Bytes> }
+      >;
+      /**
+       * Notifies that a priority challenge for file deletion has been deferred to the fair-share queue,
+       * since its issuer exceeded its soft limit for the current metering period.
+       **/
+      PriorityChallengeForFileDeletionDeferred: AugmentedEvent<
+        ApiType,
+        [issuer: PalletFileSystemEitherAccountIdOrMspId, fileKey: H256],
+        { issuer: PalletFileSystemEitherAccountIdOrMspId; fileKey: H256 }
+      >;
       /**
        * Notifies that a file key has been queued for a priority challenge for file deletion.
        **/
@@ -629,6 +990,15 @@ declare module "@polkadot/api-base/types/events" {
         [mspId: H256, user: AccountId32, fileKey: H256, bucketId: H256, proofOfInclusion: bool],
         { mspId: H256; user: AccountId32; fileKey: H256; bucketId: H256; proofOfInclusion: bool }
       >;
+      /**
+       * Notifies that all the storage requests and move bucket requests waiting on a Provider that
+       * became insolvent or was deregistered have been processed.
+       **/
+      ProviderStatusChangeProcessed: AugmentedEvent<
+        ApiType,
+        [providerId: H256, status: ShpTraitsProviderStatusChange],
+        { providerId: H256; status: ShpTraitsProviderStatusChange }
+      >;
       /**
        * Notifies that a SP has stopped storing a file because its owner has become insolvent.
        **/
@@ -637,6 +1007,18 @@ declare module "@polkadot/api-base/types/events" {
         [spId: H256, fileKey: H256, owner: AccountId32, location: Bytes, newRoot: H256],
         { spId: H256; fileKey: H256; owner: AccountId32; location: Bytes; newRoot: H256 }
       >;
+      /**
+       * Notifies that a storage request has been cancelled because the MSP it selected became
+       * insolvent or was deregistered before accepting it.
+       *
+       * The storage request creation deposit is returned to the owner, who can issue it again
+       * with another MSP.
+       **/
+      StorageRequestCancelledByUnavailableMsp: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, owner: AccountId32, mspId: H256, status: ShpTraitsProviderStatusChange],
+        { fileKey: H256; owner: AccountId32; mspId: H256; status: ShpTraitsProviderStatusChange }
+      >;
       /**
        * Notifies the expiration of a storage request. This means that the storage request has
        * been accepted by the MSP but the BSP target has not been reached (possibly 0 BSPs).
@@ -644,6 +1026,15 @@ declare module "@polkadot/api-base/types/events" {
        * of BSPs and choose to either delete the file and re-issue a storage request or continue.
        **/
       StorageRequestExpired: AugmentedEvent<ApiType, [fileKey: H256], { fileKey: H256 }>;
+      /**
+       * Notifies that a storage request will expire at block `expires_at`, which is
+       * [`Config::StorageRequestExpirationNotificationLeadTime`] blocks away or less.
+       **/
+      StorageRequestExpiringSoon: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, expiresAt: u32],
+        { fileKey: H256; expiresAt: u32 }
+      >;
       /**
        * Notifies that a storage request for a file key has been fulfilled.
        * This means that the storage request has been accepted by the MSP and the BSP target
@@ -661,12 +1052,103 @@ declare module "@polkadot/api-base/types/events" {
         [fileKey: H256, reason: PalletFileSystemRejectedStorageRequestReason],
         { fileKey: H256; reason: PalletFileSystemRejectedStorageRequestReason }
       >;
+      /**
+       * Notifies that an expired storage request could not be renewed automatically, i.e.
+       * because its bucket is being moved. Its renewal deposit is returned to its owner.
+       **/
+      StorageRequestRenewalFailed: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, error: SpRuntimeDispatchError],
+        { fileKey: H256; error: SpRuntimeDispatchError }
+      >;
+      /**
+       * Notifies that an expired storage request, whose owner opted into its automatic renewal,
+       * has been issued again for the `bsps_required` replicas it was missing.
+       **/
+      StorageRequestRenewed: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, bspsRequired: u32],
+        { fileKey: H256; bspsRequired: u32 }
+      >;
+      /**
+       * Notifies that the owner of an open storage request changed its replication target.
+       *
+       * If the new target was already reached, it is followed by [`Event::StorageRequestFulfilled`].
+       **/
+      StorageRequestReplicationTargetChanged: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, owner: AccountId32, previousReplicationTarget: u32, replicationTarget: u32],
+        {
+          fileKey: H256;
+          owner: AccountId32;
+          previousReplicationTarget: u32;
+          replicationTarget: u32;
+        }
+      >;
       /**
        * Notifies that a storage request has been revoked by the user who initiated it.
        * Note: the BSPs who confirmed the file are also issued a priority challenge to delete the
        * file.
        **/
       StorageRequestRevoked: AugmentedEvent<ApiType, [fileKey: H256], { fileKey: H256 }>;
+      /**
+       * Notifies that a BSP that volunteered for a storage request, without confirming storing the
+       * file, has been removed from it because it became insolvent or was deregistered.
+       *
+       * This frees its slot so that another BSP can volunteer.
+       **/
+      UnavailableBspVolunteerRemoved: AugmentedEvent<
+        ApiType,
+        [fileKey: H256, bspId: H256, status: ShpTraitsProviderStatusChange],
+        { fileKey: H256; bspId: H256; status: ShpTraitsProviderStatusChange }
+      >;
+      /**
+       * Notifies that the owner of a bucket allowed `grantee` to issue storage requests for files
+       * of up to `size_budget` in total into it until block `expires_at`, replacing any previous
+       * grant.
+       **/
+      UploadGrantCreated: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, bucketId: H256, grantee: AccountId32, sizeBudget: u64, expiresAt: u32],
+        {
+          owner: AccountId32;
+          bucketId: H256;
+          grantee: AccountId32;
+          sizeBudget: u64;
+          expiresAt: u32;
+        }
+      >;
+      /**
+       * Notifies that the owner of a bucket revoked the upload grant of `grantee`.
+       **/
+      UploadGrantRevoked: AugmentedEvent<
+        ApiType,
+        [owner: AccountId32, bucketId: H256, grantee: AccountId32],
+        { owner: AccountId32; bucketId: H256; grantee: AccountId32 }
+      >;
+      /**
+       * Notifies that `grantee` issued a storage request with its upload grant. It follows the
+       * [`Event::NewStorageRequest`] of the file, which is owned by the owner of the bucket.
+       **/
+      UploadGrantUsed: AugmentedEvent<
+        ApiType,
+        [
+          owner: AccountId32,
+          bucketId: H256,
+          grantee: AccountId32,
+          fileKey: H256,
+          size_: u64,
+          remainingBudget: u64
+        ],
+        {
+          owner: AccountId32;
+          bucketId: H256;
+          grantee: AccountId32;
+          fileKey: H256;
+          size_: u64;
+          remainingBudget: u64;
+        }
+      >;
       /**
        * Generic event
        **/
@@ -1192,6 +1674,40 @@ declare module "@polkadot/api-base/types/events" {
       [key: string]: AugmentedEvent<ApiType>;
     };
     paymentStreams: {
+      /**
+       * Event emitted when the free balance of a User is topped up from its auto top-up reserve. Provides information about
+       * the amount moved to its free balance and the amount left in the reserve.
+       **/
+      AutoTopUpExecuted: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, amount: u128, remainingReserve: u128],
+        { who: AccountId32; amount: u128; remainingReserve: u128 }
+      >;
+      /**
+       * Event emitted when a User adds funds to its auto top-up reserve. Provides information about the amount added.
+       **/
+      AutoTopUpReserveDeposited: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, amount: u128],
+        { who: AccountId32; amount: u128 }
+      >;
+      /**
+       * Event emitted when a User withdraws funds from its auto top-up reserve. Provides information about the amount withdrawn.
+       **/
+      AutoTopUpReserveWithdrawn: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, amount: u128],
+        { who: AccountId32; amount: u128 }
+      >;
+      /**
+       * Event emitted when a User enables, updates or disables the automatic top-up of its free balance. Provides information
+       * about the new threshold, if any.
+       **/
+      AutoTopUpThresholdSet: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, threshold: Option<u128>],
+        { who: AccountId32; threshold: Option<u128> }
+      >;
       /**
        * Event emitted when a dynamic-rate payment stream is created. Provides information about the User and Provider of the stream
        * and the initial amount provided.
@@ -1218,6 +1734,92 @@ declare module "@polkadot/api-base/types/events" {
         [userAccount: AccountId32, providerId: H256, newAmountProvided: u64],
         { userAccount: AccountId32; providerId: H256; newAmountProvided: u64 }
       >;
+      /**
+       * Event emitted when an escrow is closed. Provides information about the amount left in it that was refunded to the User.
+       **/
+      EscrowClosed: AugmentedEvent<
+        ApiType,
+        [escrowId: H256, userAccount: AccountId32, amountRefunded: u128],
+        { escrowId: H256; userAccount: AccountId32; amountRefunded: u128 }
+      >;
+      /**
+       * Event emitted when a User escrows the payment for a set of Providers. Provides information about the escrow,
+       * the amount of Providers it pays, the units each of them provides, at which rate and for how long, and the total
+       * amount held from the User.
+       **/
+      EscrowOpened: AugmentedEvent<
+        ApiType,
+        [
+          escrowId: H256,
+          userAccount: AccountId32,
+          providersTarget: u32,
+          amountProvided: u64,
+          ratePerProvider: u128,
+          duration: u32,
+          amountHeld: u128
+        ],
+        {
+          escrowId: H256;
+          userAccount: AccountId32;
+          providersTarget: u32;
+          amountProvided: u64;
+          ratePerProvider: u128;
+          duration: u32;
+          amountHeld: u128;
+        }
+      >;
+      /**
+       * Event emitted when escrowed funds are released to a Provider that proved it kept providing its service.
+       * Provides information about the amount released and the tick up to which the Provider has been paid.
+       **/
+      EscrowPaymentReleased: AugmentedEvent<
+        ApiType,
+        [
+          escrowId: H256,
+          userAccount: AccountId32,
+          providerId: H256,
+          amount: u128,
+          releasedUntilTick: u32
+        ],
+        {
+          escrowId: H256;
+          userAccount: AccountId32;
+          providerId: H256;
+          amount: u128;
+          releasedUntilTick: u32;
+        }
+      >;
+      /**
+       * Event emitted when a Provider starts being paid by an escrow. Provides information about the ticks it will be paid for.
+       **/
+      EscrowProviderAdded: AugmentedEvent<
+        ApiType,
+        [escrowId: H256, providerId: H256, startTick: u32, endTick: u32],
+        { escrowId: H256; providerId: H256; startTick: u32; endTick: u32 }
+      >;
+      /**
+       * Event emitted when a Provider stops being paid by an escrow before the end of its escrowed ticks.
+       * Provides information about the amount of its share that was refunded to the User.
+       **/
+      EscrowProviderRemoved: AugmentedEvent<
+        ApiType,
+        [escrowId: H256, providerId: H256, amountRefunded: u128],
+        { escrowId: H256; providerId: H256; amountRefunded: u128 }
+      >;
+      /**
+       * Event emitted when an escrow is settled with less Providers than its target. Provides information about the
+       * amount of missing Providers and the amount refunded to the User for them.
+       **/
+      EscrowShortfallRefunded: AugmentedEvent<
+        ApiType,
+        [escrowId: H256, userAccount: AccountId32, missingProviders: u32, amountRefunded: u128],
+        { escrowId: H256; userAccount: AccountId32; missingProviders: u32; amountRefunded: u128 }
+      >;
+      /**
+       * Event emitted when the target price per giga-unit per tick in the reference currency of the oracle feed is set
+       * or cleared. Provides information about the new target, if any.
+       **/
+      FiatPriceTargetSet: AugmentedEvent<ApiType, [target: Option<u128>], { target: Option<u128> }>;
       /**
        * Event emitted when a fixed-rate payment stream is created. Provides information about the Provider and User of the stream
        * and its initial rate.
@@ -1261,6 +1863,16 @@ declare module "@polkadot/api-base/types/events" {
         [providerId: H256, lastChargeableTick: u32, lastChargeablePriceIndex: u128],
         { providerId: H256; lastChargeableTick: u32; lastChargeablePriceIndex: u128 }
       >;
+      /**
+       * Event emitted when an adjustment of the price per giga-unit per tick towards the target price in the reference currency
+       * is skipped because the oracle feed is stale or has no price. Provides information about the last update of the feed, if any.
+       * The price per giga-unit per tick is left to the system manager until the feed is fresh again.
+       **/
+      OraclePriceStale: AugmentedEvent<
+        ApiType,
+        [lastUpdatedAt: Option<u32>],
+        { lastUpdatedAt: Option<u32> }
+      >;
       /**
        * Event emitted when a payment is charged. Provides information about the user that was charged,
        * the Provider that received the funds, the tick up to which it was charged and the amount that was charged.
@@ -1282,6 +1894,15 @@ declare module "@polkadot/api-base/types/events" {
           chargedAtTick: u32;
         }
       >;
+      /**
+       * Event emitted when the price per giga-unit per tick is adjusted towards the target price in the reference currency.
+       * Provides information about the previous and new prices, and the oracle price of the native token used to compute it.
+       **/
+      PriceAdjustedToFiatTarget: AugmentedEvent<
+        ApiType,
+        [previousPrice: u128, newPrice: u128, oraclePrice: u128],
+        { previousPrice: u128; newPrice: u128; oraclePrice: u128 }
+      >;
       /**
        * Event emitted when a User that has been flagged as not having enough funds to pay for their contracted services has paid all its outstanding debt.
        **/
@@ -1588,10 +2209,37 @@ declare module "@polkadot/api-base/types/events" {
       [key: string]: AugmentedEvent<ApiType>;
     };
     proofsDealer: {
+      /**
+       * The challenge period override of a Provider was set, or removed if `period` is `None`.
+       **/
+      ChallengePeriodOverrideSet: AugmentedEvent<
+        ApiType,
+        [providerId: H256, period: Option<u32>],
+        { providerId: H256; period: Option<u32> }
+      >;
       /**
        * The [`ChallengesTicker`] has been paused or unpaused.
        **/
       ChallengesTickerSet: AugmentedEvent<ApiType, [paused: bool], { paused: bool }>;
+      /**
+       * Summary of the keys removed from a Provider's Forest by the remove mutations of a checkpoint
+       * challenge round, emitted once the Provider submits the proof covering that round.
+       **/
+      CheckpointMutationsSummary: AugmentedEvent<
+        ApiType,
+        [provider: H256, checkpointTick: u32, removedKeys: Vec<H256>, newRoot: H256],
+        { provider: H256; checkpointTick: u32; removedKeys: Vec<H256>; newRoot: H256 }
+      >;
+      /**
+       * History that fell out of the retention policy was pruned, catching up after the retention
+       * was shortened. `caught_up` is `false` while there is still history left to prune, which
+       * continues in the following blocks.
+       **/
+      HistoryPruned: AugmentedEvent<
+        ApiType,
+        [challengeSeedsPrunedUpTo: u32, validProofSubmittersPrunedUpTo: u32, caughtUp: bool],
+        { challengeSeedsPrunedUpTo: u32; validProofSubmittersPrunedUpTo: u32; caughtUp: bool }
+      >;
       /**
        * A set of mutations has been applied to the Forest.
        **/
@@ -1660,6 +2308,24 @@ declare module "@polkadot/api-base/types/events" {
         [providerId: H256, proof: PalletProofsDealerProof, lastTickProven: u32],
         { providerId: H256; proof: PalletProofsDealerProof; lastTickProven: u32 }
       >;
+      /**
+       * A provider missed a proof submission deadline within its allowance, so it was warned
+       * instead of being marked as slashable. Its challenge deadline was forcefully pushed.
+       **/
+      ProviderWarned: AugmentedEvent<
+        ApiType,
+        [provider: H256, nextChallengeDeadline: u32, missedDeadlines: u32, windowEnd: u32],
+        { provider: H256; nextChallengeDeadline: u32; missedDeadlines: u32; windowEnd: u32 }
+      >;
+      /**
+       * The retention policy for the history kept by this pallet was set. `policy` is the one in
+       * effect from now on.
+       **/
+      RetentionPolicySet: AugmentedEvent<
+        ApiType,
+        [policy: PalletProofsDealerRetentionPolicy],
+        { policy: PalletProofsDealerRetentionPolicy }
+      >;
       /**
        * A provider was marked as slashable and their challenge deadline was forcefully pushed.
        **/
@@ -1668,6 +2334,23 @@ declare module "@polkadot/api-base/types/events" {
         [provider: H256, nextChallengeDeadline: u32],
         { provider: H256; nextChallengeDeadline: u32 }
       >;
+      /**
+       * The deadline checks caught up with the [`ChallengesTicker`] after having fallen behind.
+       **/
+      TicksCatchUpBacklogCleared: AugmentedEvent<
+        ApiType,
+        [challengesTicker: u32],
+        { challengesTicker: u32 }
+      >;
+      /**
+       * The deadline checks fell further behind the [`ChallengesTicker`]. `backlog` is the number
+       * of ticks whose deadlines are yet to be checked, starting at `next_tick_to_check`.
+       **/
+      TicksCatchUpBacklogGrew: AugmentedEvent<
+        ApiType,
+        [backlog: u32, nextTickToCheck: u32],
+        { backlog: u32; nextTickToCheck: u32 }
+      >;
       /**
        * Generic event
        **/
@@ -1687,6 +2370,15 @@ declare module "@polkadot/api-base/types/events" {
        * Event emitted when a BSP has been deleted.
        **/
       BspDeleted: AugmentedEvent<ApiType, [providerId: H256], { providerId: H256 }>;
+      /**
+       * Event emitted when the reputation weight of a BSP changed, either because it submitted enough proofs in a row
+       * or because it was slashed.
+       **/
+      BspReputationWeightChanged: AugmentedEvent<
+        ApiType,
+        [bspId: H256, oldWeight: u32, newWeight: u32],
+        { bspId: H256; oldWeight: u32; newWeight: u32 }
+      >;
       /**
        * Event emitted when a Backup Storage Provider has requested to sign up successfully. Provides information about
        * that BSP's account id, its multiaddresses, and the total data it can store according to its stake.
@@ -1723,6 +2415,31 @@ declare module "@polkadot/api-base/types/events" {
         [bucketId: H256, oldRoot: H256, newRoot: H256],
         { bucketId: H256; oldRoot: H256; newRoot: H256 }
       >;
+      /**
+       * Event emitted when a bucket is migrated to the latest version of its value proposition.
+       **/
+      BucketValuePropMigrated: AugmentedEvent<
+        ApiType,
+        [bucketId: H256, mspId: H256, previousValuePropId: H256, valuePropId: H256],
+        { bucketId: H256; mspId: H256; previousValuePropId: H256; valuePropId: H256 }
+      >;
+      /**
+       * Event emitted when an account has requested to attest its capacity.
+       **/
+      CapacityAttestationRequested: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, capacity: u64],
+        { who: AccountId32; capacity: u64 }
+      >;
+      /**
+       * Event emitted when an account has answered the challenge of its capacity attestation, attesting
+       * the given capacity.
+       **/
+      CapacityAttested: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, capacity: u64],
+        { who: AccountId32; capacity: u64 }
+      >;
       /**
        * Event emitted when a SP has changed its capacity successfully. Provides information about
        * that SP's account id, its old total data that could store, and the new total data.
@@ -1744,6 +2461,64 @@ declare module "@polkadot/api-base/types/events" {
           nextBlockWhenChangeAllowed: u32;
         }
       >;
+      /**
+       * Event emitted when a SP confirmed a capacity decrease but it was cancelled because its used capacity
+       * still exceeded the requested capacity.
+       **/
+      CapacityDecreaseCancelled: AugmentedEvent<
+        ApiType,
+        [
+          who: AccountId32,
+          providerId: PalletStorageProvidersStorageProviderId,
+          newCapacity: u64,
+          usedCapacity: u64
+        ],
+        {
+          who: AccountId32;
+          providerId: PalletStorageProvidersStorageProviderId;
+          newCapacity: u64;
+          usedCapacity: u64;
+        }
+      >;
+      /**
+       * Event emitted when a SP has requested to decrease its capacity. Provides information about the capacity
+       * it wants to decrease to, and the block from which it can confirm the decrease.
+       **/
+      CapacityDecreaseRequested: AugmentedEvent<
+        ApiType,
+        [
+          who: AccountId32,
+          providerId: PalletStorageProvidersStorageProviderId,
+          currentCapacity: u64,
+          newCapacity: u64,
+          confirmableAt: u32
+        ],
+        {
+          who: AccountId32;
+          providerId: PalletStorageProvidersStorageProviderId;
+          currentCapacity: u64;
+          newCapacity: u64;
+          confirmableAt: u32;
+        }
+      >;
+      /**
+       * Event emitted when an account has committed to a capacity attestation plot. Provides the block
+       * number after which the challenge has to be answered.
+       **/
+      CapacityPlotCommitted: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, plotRoot: H256, capacity: u64, deadline: u32],
+        { who: AccountId32; plotRoot: H256; capacity: u64; deadline: u32 }
+      >;
+      /**
+       * Event emitted when the seed of the capacity attestation plot of an account has been drawn. Provides
+       * the block number after which the account can no longer commit to the plot.
+       **/
+      CapacityPlotSeedDrawn: AugmentedEvent<
+        ApiType,
+        [who: AccountId32, seed: H256, deadline: u32],
+        { who: AccountId32; seed: H256; deadline: u32 }
+      >;
       /**
        * Event emitted when an MSP has been deleted.
        **/
@@ -1810,6 +2585,18 @@ declare module "@polkadot/api-base/types/events" {
        * and they have a capacity deficit (i.e. their capacity based on their stake is below their used capacity by the files it stores).
        **/
       ProviderInsolvent: AugmentedEvent<ApiType, [providerId: H256], { providerId: H256 }>;
+      /**
+       * Event emitted when the status of a SP changed because it was suspended or resumed by governance, or because
+       * it scheduled or cancelled its sign off.
+       *
+       * `status` is the status of the SP after the change, as returned by the `get_provider_status` runtime API.
+       * Insolvency is signaled by the `AwaitingTopUp` and `ProviderInsolvent` events instead.
+       **/
+      ProviderStatusChanged: AugmentedEvent<
+        ApiType,
+        [providerId: H256, status: ShpTraitsProviderStatus],
+        { providerId: H256; status: ShpTraitsProviderStatus }
+      >;
       /**
        * Event emitted when a sign up request has been canceled successfully. Provides information about
        * the account id of the user that canceled the request.
@@ -1820,8 +2607,20 @@ declare module "@polkadot/api-base/types/events" {
        **/
       Slashed: AugmentedEvent<
         ApiType,
-        [providerId: H256, amount: u128],
-        { providerId: H256; amount: u128 }
+        [
+          providerId: H256,
+          amount: u128,
+          accruedFailedProofSubmissions: u32,
+          consecutiveMissedDeadlines: u32,
+          usedCapacity: u64
+        ],
+        {
+          providerId: H256;
+          amount: u128;
+          accruedFailedProofSubmissions: u32;
+          consecutiveMissedDeadlines: u32;
+          usedCapacity: u64;
+        }
       >;
       /**
        * Event emitted when an SP has topped up its deposit based on slash amount.
@@ -1847,6 +2646,28 @@ declare module "@polkadot/api-base/types/events" {
         [mspId: H256, valuePropId: H256],
         { mspId: H256; valuePropId: H256 }
       >;
+      /**
+       * Event emitted when an MSP publishes a new version of one of its value propositions.
+       *
+       * The previous version is made unavailable, but the buckets using it keep it until their owners migrate them.
+       **/
+      ValuePropVersionPublished: AugmentedEvent<
+        ApiType,
+        [
+          mspId: H256,
+          previousValuePropId: H256,
+          valuePropId: H256,
+          valueProp: PalletStorageProvidersValueProposition,
+          version: PalletStorageProvidersValuePropositionVersion
+        ],
+        {
+          mspId: H256;
+          previousValuePropId: H256;
+          valuePropId: H256;
+          valueProp: PalletStorageProvidersValueProposition;
+          version: PalletStorageProvidersValuePropositionVersion;
+        }
+      >;
       /**
        * Generic event
        **/
@@ -41,11 +41,22 @@ import type {
   PalletBalancesBalanceLock,
   PalletBalancesReserveData,
   PalletCollatorSelectionCandidateInfo,
+  PalletFileSystemAccessGrant,
+  PalletFileSystemAccessGrantTarget,
+  PalletFileSystemBucketTransferOffer,
+  PalletFileSystemDeletionList,
+  PalletFileSystemDepositTransferRetry,
+  PalletFileSystemEitherAccountIdOrMspId,
+  PalletFileSystemFileMove,
   PalletFileSystemMoveBucketRequestMetadata,
+  PalletFileSystemMspEligibilityListMode,
   PalletFileSystemPendingFileDeletionRequest,
   PalletFileSystemPendingStopStoringRequest,
+  PalletFileSystemPriorityChallengeUsage,
+  PalletFileSystemProviderStatusChangeProgress,
   PalletFileSystemStorageRequestBspsMetadata,
   PalletFileSystemStorageRequestMetadata,
+  PalletFileSystemUploadGrant,
   PalletMessageQueueBookState,
   PalletMessageQueuePage,
   PalletNftsAttributeDeposit,
@@ -58,16 +69,25 @@ import type {
   PalletNftsItemMetadata,
   PalletNftsPendingSwap,
   PalletPaymentStreamsDynamicRatePaymentStream,
+  PalletPaymentStreamsEscrowedProvider,
   PalletPaymentStreamsFixedRatePaymentStream,
+  PalletPaymentStreamsPaymentEscrow,
   PalletPaymentStreamsProviderLastChargeableInfo,
   PalletProofsDealerProofSubmissionRecord,
+  PalletProofsDealerProviderWarningRecord,
+  PalletProofsDealerRetentionPolicy,
+  PalletProofsDealerSlashableProviderRecord,
   PalletStorageProvidersBackupStorageProvider,
   PalletStorageProvidersBucket,
+  PalletStorageProvidersCapacityUtilizationSample,
   PalletStorageProvidersMainStorageProvider,
+  PalletStorageProvidersPendingCapacityAttestation,
+  PalletStorageProvidersPendingCapacityDecrease,
   PalletStorageProvidersSignUpRequest,
   PalletStorageProvidersStorageProviderId,
   PalletStorageProvidersTopUpMetadata,
   PalletStorageProvidersValueProposition,
+  PalletStorageProvidersValuePropositionVersion,
   PalletTransactionPaymentReleases,
   PalletXcmQueryStatus,
   PalletXcmRemoteLockedFungibleRecord,
@@ -77,6 +97,7 @@ import type {
   PolkadotPrimitivesV8PersistedValidationData,
   PolkadotPrimitivesV8UpgradeGoAhead,
   PolkadotPrimitivesV8UpgradeRestriction,
+  ShpTraitsProviderStatusChange,
   ShpTraitsTrieRemoveMutation,
   SpConsensusAuraSr25519AppSr25519Public,
   SpCoreCryptoKeyTypeId,
@@ -302,6 +323,77 @@ declare module "@polkadot/api-base/types/storage" {
       [key: string]: QueryableStorageEntry<ApiType>;
     };
     fileSystem: {
+      /**
+       * Targets of the access grants expiring at each tick, to remove the grants once they expire.
+       *
+       * Entries of grants that were revoked or extended are left as is, and skipped when processed.
+       **/
+      accessGrantExpirations: AugmentedQuery<
+        ApiType,
+        (
+          arg1: u32 | AnyNumber | Uint8Array,
+          arg2:
+            | PalletFileSystemAccessGrantTarget
+            | { Bucket: any }
+            | { File: any }
+            | string
+            | Uint8Array
+        ) => Observable<Option<Null>>,
+        [u32, PalletFileSystemAccessGrantTarget]
+      > &
+        QueryableStorageEntry<ApiType, [u32, PalletFileSystemAccessGrantTarget]>;
+      /**
+       * Access grants of each bucket, or file of a bucket.
+       *
+       * A grant gives its grantee read access to the files of a private bucket, or to a single one, until
+       * it expires, without making the bucket public or holding an item of its read-access NFT collection.
+       * MSPs are expected to check it when serving downloads of files of private buckets.
+       *
+       * Expired grants are removed in `on_idle`, and can be revoked by the owner of the bucket before.
+       **/
+      accessGrants: AugmentedQuery<
+        ApiType,
+        (
+          arg:
+            | PalletFileSystemAccessGrantTarget
+            | { Bucket: any }
+            | { File: any }
+            | string
+            | Uint8Array
+        ) => Observable<Option<Vec<PalletFileSystemAccessGrant>>>,
+        [PalletFileSystemAccessGrantTarget]
+      > &
+        QueryableStorageEntry<ApiType, [PalletFileSystemAccessGrantTarget]>;
+      /**
+       * The size each BSP bills the owner of a file for through their payment stream, by BSP and
+       * file key.
+       *
+       * It is recorded when the BSP confirms storing the file and is paid through the payment
+       * stream, so that exactly the same amount is removed from the stream when the BSP stops
+       * storing the file, even if [`Config::MinBillableFileSize`] changed in between. Files confirmed
+       * before it was recorded were billed by their actual size.
+       **/
+      bspBilledFileSizes: AugmentedQuery<
+        ApiType,
+        (
+          arg1: H256 | string | Uint8Array,
+          arg2: H256 | string | Uint8Array
+        ) => Observable<Option<u64>>,
+        [H256, H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256, H256]>;
+      /**
+       * Default replication target of a bucket, set by its owner.
+       *
+       * Used for storage requests of files in the bucket that don't specify a replication target.
+       * If not set, [`Config::DefaultReplicationTarget`] is used instead.
+       **/
+      bucketDefaultReplicationTarget: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<u32>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * Bookkeeping of the buckets containing open storage requests.
        **/
@@ -314,6 +406,99 @@ declare module "@polkadot/api-base/types/storage" {
         [H256, H256]
       > &
         QueryableStorageEntry<ApiType, [H256, H256]>;
+      /**
+       * Offers to transfer a bucket to another MSP made by the MSP storing it, waiting for the owner of
+       * the bucket to respond.
+       *
+       * Once accepted by the owner, the offer becomes a regular move bucket request that the new MSP
+       * accepts or rejects. Replaced if the MSP makes a new offer for the bucket.
+       **/
+      bucketTransferOffers: AugmentedQuery<
+        ApiType,
+        (
+          arg: H256 | string | Uint8Array
+        ) => Observable<Option<PalletFileSystemBucketTransferOffer>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * Counter for the related counted storage map
+       **/
+      counterForDepositTransferRetries: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * Issuers with priority challenges in [`DeferredPriorityChallenges`], in the order they are served.
+       *
+       * The fair-share queue is drained in turns: one priority challenge of the first issuer is queued, and the
+       * issuer is moved to the back if it has more deferred priority challenges.
+       **/
+      deferredPriorityChallengeIssuers: AugmentedQuery<
+        ApiType,
+        () => Observable<Vec<PalletFileSystemEitherAccountIdOrMspId>>,
+        []
+      > &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * Fair-share queue of priority challenges for file deletions, by issuer.
+       *
+       * Holds the file keys of the priority challenges deferred because their issuer exceeded its soft limit,
+       * in the order they were issued.
+       **/
+      deferredPriorityChallenges: AugmentedQuery<
+        ApiType,
+        (
+          arg:
+            | PalletFileSystemEitherAccountIdOrMspId
+            | { AccountId: any }
+            | { MspId: any }
+            | string
+            | Uint8Array
+        ) => Observable<Vec<H256>>,
+        [PalletFileSystemEitherAccountIdOrMspId]
+      > &
+        QueryableStorageEntry<ApiType, [PalletFileSystemEitherAccountIdOrMspId]>;
+      /**
+       * Deletion lists registered by each account, by the root of their Merkle tree.
+       *
+       * A list is removed once all its files have been deleted, or when its owner cancels it.
+       **/
+      deletionLists: AugmentedQuery<
+        ApiType,
+        (
+          arg1: AccountId32 | string | Uint8Array,
+          arg2: H256 | string | Uint8Array
+        ) => Observable<Option<PalletFileSystemDeletionList>>,
+        [AccountId32, H256]
+      > &
+        QueryableStorageEntry<ApiType, [AccountId32, H256]>;
+      /**
+       * Failed deposit transfers pending to be retried in `on_idle`, by retry ID.
+       *
+       * Bounded by [`Config::MaxDepositTransferRetries`].
+       **/
+      depositTransferRetries: AugmentedQuery<
+        ApiType,
+        (
+          arg: u32 | AnyNumber | Uint8Array
+        ) => Observable<Option<PalletFileSystemDepositTransferRetry>>,
+        [u32]
+      > &
+        QueryableStorageEntry<ApiType, [u32]>;
+      /**
+       * Accounts granted read access to a file by its owner.
+       *
+       * Complements the read-access NFT collection of private buckets, which gates access to the whole bucket,
+       * so that owners can share single files. MSPs are expected to check it when serving downloads of files
+       * of private buckets.
+       *
+       * Removed when the owner requests the deletion of the file.
+       **/
+      fileAccessLists: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<Vec<AccountId32>>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * A map of blocks to expired file deletion requests.
        **/
@@ -325,6 +510,19 @@ declare module "@polkadot/api-base/types/storage" {
         [u32]
       > &
         QueryableStorageEntry<ApiType, [u32]>;
+      /**
+       * Buckets frozen by governance pending the resolution of a dispute, with the block they were
+       * frozen at.
+       *
+       * Frozen buckets can't take new files, be moved or deleted, and files can't be deleted from
+       * them, until they are unfrozen.
+       **/
+      frozenBuckets: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<u32>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * Maximum number replication target allowed to be set for a storage request to be fulfilled.
        **/
@@ -339,6 +537,47 @@ declare module "@polkadot/api-base/types/storage" {
         [u32]
       > &
         QueryableStorageEntry<ApiType, [u32]>;
+      /**
+       * Files moved to a new location.
+       *
+       * A mapping from the previous file key of a moved file to its move, once applied to the bucket's forest
+       * by its MSP. BSPs storing the file use it to apply the same move to their forest.
+       **/
+      movedFiles: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<PalletFileSystemFileMove>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * MSPs in the governance-managed eligibility list.
+       *
+       * Depending on [`MspEligibilityMode`], these are the only MSPs that can take new buckets, or the
+       * ones that cannot. MSPs that are not eligible keep the buckets they already store, but cannot
+       * be assigned new buckets (on creation or by moving them) nor new storage requests.
+       **/
+      mspEligibilityList: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<Null>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * How [`MspEligibilityList`] is interpreted: as a denylist (the default) or as an allowlist.
+       **/
+      mspEligibilityMode: AugmentedQuery<
+        ApiType,
+        () => Observable<PalletFileSystemMspEligibilityListMode>,
+        []
+      > &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * Next tick whose expired access grants are to be removed.
+       *
+       * Zero until the first access grant is created, from whose tick the cleanup starts.
+       **/
+      nextAccessGrantExpirationTickToCleanUp: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * A pointer to the earliest available block to insert a new file deletion request expiration.
        *
@@ -372,6 +611,19 @@ declare module "@polkadot/api-base/types/storage" {
         []
       > &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * A pointer to the next block whose expiring storage requests have not been notified yet.
+       *
+       * Advanced in `on_poll` up to current block + [`Config::StorageRequestExpirationNotificationLeadTime`],
+       * notifying at most [`Config::MaxStorageRequestExpirationNotificationsPerBlock`] storage requests per block.
+       **/
+      nextBlockToNotifyExpiringStorageRequests: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * The ID to assign to the next failed deposit transfer queued in [`DepositTransferRetries`].
+       **/
+      nextDepositTransferRetryId: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * A pointer to the starting block to clean up expired items.
        *
@@ -390,6 +642,18 @@ declare module "@polkadot/api-base/types/storage" {
         [H256]
       > &
         QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * Fees held for pending file deletion requests.
+       *
+       * A mapping from the file key of a pending file deletion request to the fee held from the user that
+       * requested it, to be paid out once the request is either executed or expired.
+       **/
+      pendingFileDeletionFees: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<u128>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * Pending file deletion requests.
        *
@@ -403,6 +667,18 @@ declare module "@polkadot/api-base/types/storage" {
         [AccountId32]
       > &
         QueryableStorageEntry<ApiType, [AccountId32]>;
+      /**
+       * Pending file move requests.
+       *
+       * A mapping from the file key of a file to the move to a new location requested by its owner, waiting
+       * for the MSP of the bucket to apply it to the bucket's forest.
+       **/
+      pendingFileMoveRequests: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<PalletFileSystemFileMove>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * Pending move bucket requests.
        *
@@ -436,6 +712,60 @@ declare module "@polkadot/api-base/types/storage" {
         [H256, H256]
       > &
         QueryableStorageEntry<ApiType, [H256, H256]>;
+      /**
+       * Priority challenges for file deletions queued directly by each issuer in its current metering period.
+       *
+       * Once an issuer reaches [`Config::PriorityChallengesSoftLimitPerIssuer`] in a period, its priority
+       * challenges are deferred to the fair-share queue until the next period starts.
+       **/
+      priorityChallengesUsage: AugmentedQuery<
+        ApiType,
+        (
+          arg:
+            | PalletFileSystemEitherAccountIdOrMspId
+            | { AccountId: any }
+            | { MspId: any }
+            | string
+            | Uint8Array
+        ) => Observable<Option<PalletFileSystemPriorityChallengeUsage>>,
+        [PalletFileSystemEitherAccountIdOrMspId]
+      > &
+        QueryableStorageEntry<ApiType, [PalletFileSystemEitherAccountIdOrMspId]>;
+      /**
+       * The Provider in [`ProvidersWithStatusChange`] currently being processed, and how far along it is.
+       **/
+      providerStatusChangeInProgress: AugmentedQuery<
+        ApiType,
+        () => Observable<Option<PalletFileSystemProviderStatusChangeProgress>>,
+        []
+      > &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * Providers that became insolvent or were deregistered, pending to be processed.
+       *
+       * Filled by the Providers pallet through [`shp_traits::OnProviderStatusChange`], and processed one
+       * Provider at a time in `on_idle`, cancelling or rerouting the storage requests and move bucket
+       * requests that were waiting on them.
+       **/
+      providersWithStatusChange: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<ShpTraitsProviderStatusChange>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The deposits held for the automatic renewal of storage requests whose owner opted into it.
+       *
+       * When such a storage request expires without reaching its replication target, the deposit is
+       * used to issue a new storage request for the missing replicas. It is returned to the owner if
+       * the storage request is fulfilled, revoked or rejected instead.
+       **/
+      storageRequestAutoRenewals: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<u128>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * A double map from storage request to BSP `AccountId`s that volunteered to store the file.
        *
@@ -475,6 +805,34 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       tickRangeToMaximumThreshold: AugmentedQuery<ApiType, () => Observable<u32>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * Upload grants of each bucket, by grantee.
+       *
+       * A grant lets its grantee issue storage requests for files of the bucket on behalf of its owner, who
+       * pays for them, until it expires or its size budget runs out. Removed when the owner revokes it.
+       **/
+      uploadGrants: AugmentedQuery<
+        ApiType,
+        (
+          arg1: H256 | string | Uint8Array,
+          arg2: AccountId32 | string | Uint8Array
+        ) => Observable<Option<PalletFileSystemUploadGrant>>,
+        [H256, AccountId32]
+      > &
+        QueryableStorageEntry<ApiType, [H256, AccountId32]>;
+      /**
+       * Pointer to the notification preferences of each user, as set by them.
+       *
+       * Its content is opaque to the runtime: it is expected to be a hash or a URI that off-chain services
+       * resolve to the user's contact preferences, to alert them of events concerning their files (i.e. expired
+       * or rejected storage requests, or the risk of becoming insolvent), without storing any of it on-chain.
+       **/
+      userNotificationPreferences: AugmentedQuery<
+        ApiType,
+        (arg: AccountId32 | string | Uint8Array) => Observable<Option<Bytes>>,
+        [AccountId32]
+      > &
+        QueryableStorageEntry<ApiType, [AccountId32]>;
       /**
        * Generic query
        **/
@@ -986,6 +1344,21 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       accumulatedPriceIndex: AugmentedQuery<ApiType, () => Observable<u128>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * The mapping from a User to the free balance it wants to keep to pay for its payment streams, for Users that enabled
+       * the automatic top-up of their free balance from their auto top-up reserve.
+       *
+       * The reserve is the balance held from the User with the [`HoldReason::AutoTopUpReserve`] reason.
+       *
+       * This storage is updated in:
+       * - [set_auto_top_up_threshold](crate::dispatchables::set_auto_top_up_threshold), which sets or clears the threshold.
+       **/
+      autoTopUpThresholds: AugmentedQuery<
+        ApiType,
+        (arg: AccountId32 | string | Uint8Array) => Observable<Option<u128>>,
+        [AccountId32]
+      > &
+        QueryableStorageEntry<ApiType, [AccountId32]>;
       /**
        * The current price per gigaunit per tick of the provided service, used to calculate the amount to charge for dynamic-rate payment streams.
        *
@@ -1015,6 +1388,41 @@ declare module "@polkadot/api-base/types/storage" {
         [H256, AccountId32]
       > &
         QueryableStorageEntry<ApiType, [H256, AccountId32]>;
+      /**
+       * The double mapping from an escrow ID, to the Providers it pays, to the ticks they are paid for.
+       *
+       * This storage is updated alongside [PaymentEscrows].
+       **/
+      escrowedProviders: AugmentedQuery<
+        ApiType,
+        (
+          arg1: H256 | string | Uint8Array,
+          arg2: H256 | string | Uint8Array
+        ) => Observable<Option<PalletPaymentStreamsEscrowedProvider>>,
+        [H256, H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256, H256]>;
+      /**
+       * The target price per giga-unit per tick, in the smallest unit of the reference currency of the
+       * [`Config::PriceOracle`] feed.
+       *
+       * When set, the price per giga-unit per tick tracks this target: every [`Config::OraclePriceAdjustmentPeriod`]
+       * ticks it is moved towards the target converted to the native token using the oracle feed, by at most
+       * [`Config::MaxOraclePriceAdjustment`]. When not set, the price is only updated by the system manager.
+       *
+       * This storage is updated in:
+       * - [set_fiat_price_target](crate::dispatchables::set_fiat_price_target), which sets or clears the target.
+       **/
+      fiatPriceTarget: AugmentedQuery<ApiType, () => Observable<Option<u128>>, []> &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * The price per giga-unit per tick resulting from the last adjustment towards [`FiatPriceTarget`].
+       *
+       * While the oracle feed is fresh, this price is enforced every tick, taking precedence over the one set by the
+       * system manager. It is cleared when the target is.
+       **/
+      fiatTrackedPrice: AugmentedQuery<ApiType, () => Observable<Option<u128>>, []> &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * The double mapping from a Provider, to its provided Users, to their fixed-rate payment streams.
        *
@@ -1051,6 +1459,12 @@ declare module "@polkadot/api-base/types/storage" {
         [H256]
       > &
         QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The last tick at which the price per giga-unit per tick was adjusted towards [`FiatPriceTarget`], or at which
+       * the adjustment was skipped because of a stale oracle feed.
+       **/
+      lastOraclePriceAdjustmentTick: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * The last tick that was processed by this pallet from the Proof Submitters interface.
        *
@@ -1071,6 +1485,39 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       onPollTicker: AugmentedQuery<ApiType, () => Observable<u32>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * The mapping from an escrow ID to the escrow holding payments from a User for a set of Providers.
+       *
+       * This storage is updated in:
+       * - [open_escrow](crate::PaymentEscrowInterface::open_escrow), which adds a new entry to the map.
+       * - [add_escrow_provider](crate::PaymentEscrowInterface::add_escrow_provider) and [remove_escrow_provider](crate::PaymentEscrowInterface::remove_escrow_provider),
+       * which update the entry's Providers.
+       * - [release_escrowed_payment](crate::dispatchables::release_escrowed_payment), which updates the entry's `amount_held`.
+       * - [settle_escrow_replication](crate::PaymentEscrowInterface::settle_escrow_replication), which refunds the share of the missing Providers.
+       *
+       * Escrows are removed once their replication has been settled and no Provider is left in them.
+       **/
+      paymentEscrows: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<PalletPaymentStreamsPaymentEscrow>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The mapping from a User to the amount it was found short of to pay one of its payment streams, for Users whose free
+       * balance has to be topped up from their auto top-up reserve in the next `on_poll` hook executions.
+       *
+       * This storage is updated in:
+       * - [charge_payment_streams](crate::dispatchables::charge_payment_streams), which adds the User if it can't pay one of its
+       * payment streams or its free balance falls below its threshold after being charged.
+       * - [do_process_pending_auto_top_ups](crate::utils::do_process_pending_auto_top_ups), which removes the Users it tops up.
+       **/
+      pendingAutoTopUps: AugmentedQuery<
+        ApiType,
+        (arg: AccountId32 | string | Uint8Array) => Observable<Option<u128>>,
+        [AccountId32]
+      > &
+        QueryableStorageEntry<ApiType, [AccountId32]>;
       /**
        * Mapping of Privileged Providers.
        *
@@ -1273,6 +1720,19 @@ declare module "@polkadot/api-base/types/storage" {
       [key: string]: QueryableStorageEntry<ApiType>;
     };
     proofsDealer: {
+      /**
+       * A mapping from Providers to the challenge period set for them by governance, which is used
+       * instead of the one derived from their stake.
+       *
+       * Meant for Providers whose proofs are particularly expensive to generate, like MSPs storing
+       * huge buckets. Removed when the Provider's challenge cycle is stopped.
+       **/
+      challengePeriodOverrides: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<u32>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * A queue of keys that have been challenged manually.
        *
@@ -1305,6 +1765,32 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       challengesTickerPaused: AugmentedQuery<ApiType, () => Observable<Option<Null>>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * A mapping from Providers to the number of proof submission deadlines they missed in a row.
+       *
+       * Unlike the record in [`SlashableProviders`], it is kept when the Provider is slashed, so
+       * that slashes keep escalating while the Provider keeps missing deadlines. It is only reset
+       * when the Provider submits a valid proof, or stops being challenged.
+       **/
+      consecutiveMissedDeadlines: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<u32>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The retention policy set by governance for the history kept by this pallet.
+       *
+       * If not set, the retention is given by [`Config::ChallengeHistoryLength`] and
+       * [`Config::TargetTicksStorageOfSubmitters`]. History that falls out of a shortened retention
+       * is pruned lazily, in the `on_idle` hook.
+       **/
+      historyRetentionPolicy: AugmentedQuery<
+        ApiType,
+        () => Observable<Option<PalletProofsDealerRetentionPolicy>>,
+        []
+      > &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * The challenge tick of the last checkpoint challenge round.
        *
@@ -1323,6 +1809,13 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       lastDeletedTick: AugmentedQuery<ApiType, () => Observable<u32>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * The last tick whose challenge seed was pruned from [`TickToChallengesSeed`].
+       *
+       * Seeds are pruned in order, so all seeds up to this tick are gone.
+       **/
+      lastPrunedChallengeSeedTick: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * The number of blocks that have been considered _not_ full in the last [`Config::BlockFullnessPeriod`].
        *
@@ -1381,17 +1874,36 @@ declare module "@polkadot/api-base/types/storage" {
         [H256]
       > &
         QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * A mapping from Providers that missed a proof submission deadline to their record of failed
+       * proof submissions (see [`SlashableProviderRecord`]).
+       *
+       * The record is removed once the Provider is slashed.
+       **/
       slashableProviders: AugmentedQuery<
         ApiType,
-        (arg: H256 | string | Uint8Array) => Observable<Option<u32>>,
+        (
+          arg: H256 | string | Uint8Array
+        ) => Observable<Option<PalletProofsDealerSlashableProviderRecord>>,
         [H256]
       > &
         QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The number of ticks, up to and including the current [`ChallengesTicker`], whose deadlines are
+       * yet to be checked, as of the end of the last challenges round.
+       *
+       * It is zero while [`TickToCheckForSlashableProviders`] is caught up with [`ChallengesTicker`].
+       * Otherwise, it is the backlog that subsequent blocks will work through, at most
+       * [`Config::MaxCatchUpTicksPerBlock`] ticks per block.
+       **/
+      ticksCatchUpBacklog: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
       /**
        * A mapping from challenges tick to a random seed used for generating the challenges in that tick.
        *
        * This is used to keep track of the challenges' seed in the past.
-       * This mapping goes back only [`ChallengeHistoryLengthFor`] blocks. Previous challenges are removed.
+       * This mapping goes back only as many ticks as the retention policy allows (see
+       * [`HistoryRetentionPolicy`]). Previous challenges are removed.
        **/
       tickToChallengesSeed: AugmentedQuery<
         ApiType,
@@ -1448,6 +1960,21 @@ declare module "@polkadot/api-base/types/storage" {
         [u32, H256]
       > &
         QueryableStorageEntry<ApiType, [u32, H256]>;
+      /**
+       * The challenges tick of the last proof each Provider submitted as an unsigned transaction
+       * (see [`Pallet::submit_proof_unsigned`]).
+       *
+       * It is recorded right before the transaction is dispatched, so it is kept even if the proof
+       * then fails verification. This way, a Provider gets at most one free proof submission
+       * included per challenges tick, instead of being able to fill blocks with failing proofs for
+       * free. A Provider's entry is removed when its challenge cycle is stopped.
+       **/
+      unsignedProofAttempts: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<u32>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * A mapping from tick to Providers, which is set if the Provider submitted a valid proof in that tick.
        *
@@ -1457,10 +1984,24 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       validProofSubmittersLastTicks: AugmentedQuery<
         ApiType,
-        (arg: u32 | AnyNumber | Uint8Array) => Observable<Option<BTreeSet<H256>>>,
+        (arg: u32 | AnyNumber | Uint8Array) => Observable<Option<Vec<H256>>>,
         [u32]
       > &
         QueryableStorageEntry<ApiType, [u32]>;
+      /**
+       * A mapping from Providers that were warned for missing a proof submission deadline to their
+       * current warning window (see [`ProviderWarningRecord`]).
+       *
+       * The record is replaced when a deadline is missed after the window is over.
+       **/
+      warnedProviders: AugmentedQuery<
+        ApiType,
+        (
+          arg: H256 | string | Uint8Array
+        ) => Observable<Option<PalletProofsDealerProviderWarningRecord>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * Generic query
        **/
@@ -1498,6 +2039,28 @@ declare module "@polkadot/api-base/types/storage" {
         [AccountId32]
       > &
         QueryableStorageEntry<ApiType, [AccountId32]>;
+      /**
+       * The mapping from an AccountId to the capacity that it has successfully attested.
+       *
+       * When [`Config::RequireCapacityAttestation`] is enabled, a BSP can only sign up or increase its capacity
+       * up to this amount.
+       *
+       * An attested capacity is only valid for the next capacity the BSP is set up with, so it is removed once the BSP
+       * signs up or changes its capacity (in any way), and when the BSP is removed.
+       *
+       * This storage is updated in:
+       * - [answer_capacity_challenge](crate::dispatchables::answer_capacity_challenge), which sets the attested capacity.
+       * - [confirm_sign_up](crate::dispatchables::confirm_sign_up), [change_capacity](crate::dispatchables::change_capacity),
+       * [confirm_capacity_decrease](crate::dispatchables::confirm_capacity_decrease),
+       * [bsp_sign_off](crate::dispatchables::bsp_sign_off) and [delete_provider](crate::dispatchables::delete_provider),
+       * as well as when the capacity of the BSP is reduced after being slashed or topping up its deposit, which remove it.
+       **/
+      attestedCapacities: AugmentedQuery<
+        ApiType,
+        (arg: AccountId32 | string | Uint8Array) => Observable<Option<u64>>,
+        [AccountId32]
+      > &
+        QueryableStorageEntry<ApiType, [AccountId32]>;
       /**
        * Storage providers currently awaited for to top up their deposit (providers whom have been slashed and as
        * a result have a capacity deficit, i.e. their capacity is below their used capacity).
@@ -1555,6 +2118,39 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       bspCount: AugmentedQuery<ApiType, () => Observable<u32>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * The amount of proofs that each BSP has submitted in a row since it was last slashed.
+       *
+       * Every [`Config::ReputationGrowthStreak`] proofs in a row, the reputation weight of the BSP increases.
+       *
+       * This storage is updated in:
+       * - The `on_poll` hook, which accounts for the proofs submitted by BSPs in the previous tick.
+       * - [slash](crate::dispatchables::slash), which resets the streak of the slashed BSP.
+       * - [bsp_sign_off](crate::dispatchables::bsp_sign_off) and [delete_provider](crate::dispatchables::delete_provider),
+       * which remove the entry of the removed BSP, if any.
+       **/
+      bspProofSubmissionStreaks: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<u32>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The mapping from a BucketId to the metadata describing how that bucket's contents are encrypted.
+       *
+       * Set by the owner of the bucket, so that the users reading its files know how to decrypt them.
+       * The runtime does not interpret it.
+       *
+       * This storage is updated in:
+       * - [update_bucket_encryption_metadata](shp_traits::MutateBucketsInterface::update_bucket_encryption_metadata), which sets or removes the entry of the corresponding bucket.
+       * - [remove_root_bucket](shp_traits::MutateBucketsInterface::remove_root_bucket), which removes the entry of the corresponding bucket.
+       **/
+      bucketEncryptionMetadata: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<Bytes>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * The mapping from a BucketId to that bucket's metadata.
        *
@@ -1572,6 +2168,38 @@ declare module "@polkadot/api-base/types/storage" {
         [H256]
       > &
         QueryableStorageEntry<ApiType, [H256]>;
+      /**
+       * The latest samples of the global BSP capacity utilization and storage price, ordered from oldest to newest.
+       *
+       * A new sample is taken in the `on_poll` hook every [`Config::CapacityUtilizationSamplingPeriod`] ticks.
+       * At most [`Config::MaxCapacityUtilizationSamples`] samples are kept, discarding the oldest one when full.
+       *
+       * This is exposed through the `capacity_utilization` runtime API so that external tools can follow how
+       * the system utilisation (and with it, the price of storage) evolves over time.
+       **/
+      capacityUtilizationSamples: AugmentedQuery<
+        ApiType,
+        () => Observable<Vec<PalletStorageProvidersCapacityUtilizationSample>>,
+        []
+      > &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * The set of Storage Providers that have scheduled their sign off.
+       *
+       * Exiting Storage Providers keep their current duties until they sign off, but can't take on new work.
+       *
+       * This storage is updated in:
+       * - [schedule_sign_off](crate::dispatchables::schedule_sign_off), which adds an entry.
+       * - [cancel_scheduled_sign_off](crate::dispatchables::cancel_scheduled_sign_off), which removes it.
+       * - [msp_sign_off](crate::dispatchables::msp_sign_off), [bsp_sign_off](crate::dispatchables::bsp_sign_off) and
+       * [delete_provider](crate::dispatchables::delete_provider), which remove the entry of the removed Storage Provider, if any.
+       **/
+      exitingProviders: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<Null>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * The total global reputation weight of all BSPs.
        **/
@@ -1595,6 +2223,28 @@ declare module "@polkadot/api-base/types/storage" {
         [PalletStorageProvidersStorageProviderId]
       > &
         QueryableStorageEntry<ApiType, [PalletStorageProvidersStorageProviderId]>;
+      /**
+       * The last tick whose proof submitters have been accounted for in the reputation weights of BSPs.
+       **/
+      lastReputationUpdateTick: AugmentedQuery<ApiType, () => Observable<u32>, []> &
+        QueryableStorageEntry<ApiType, []>;
+      /**
+       * Double mapping from a [`MainStorageProviderId`] and the lineage of a value proposition (the ID of its first version)
+       * to the ID of its latest version.
+       *
+       * Only lineages with more than one version are present in this mapping. New buckets can only be created with the latest
+       * version of a value proposition, while existing buckets keep the version they were created with until their owner
+       * migrates them.
+       **/
+      latestValuePropositionVersions: AugmentedQuery<
+        ApiType,
+        (
+          arg1: H256 | string | Uint8Array,
+          arg2: H256 | string | Uint8Array
+        ) => Observable<Option<H256>>,
+        [H256, H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256, H256]>;
       /**
        * The double mapping from a MainStorageProviderId to a BucketIds.
        *
@@ -1678,6 +2328,42 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       nextStartingShTickToCleanUp: AugmentedQuery<ApiType, () => Observable<u32>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * The mapping from an AccountId to the capacity attestation that it has requested and not completed yet.
+       *
+       * This storage is updated in:
+       * - [request_capacity_attestation](crate::dispatchables::request_capacity_attestation), which adds (or replaces)
+       * an entry.
+       * - [draw_capacity_plot_seed](crate::dispatchables::draw_capacity_plot_seed) and
+       * [commit_capacity_plot](crate::dispatchables::commit_capacity_plot), which move it to its next stage.
+       * - [answer_capacity_challenge](crate::dispatchables::answer_capacity_challenge), which removes it once answered.
+       **/
+      pendingCapacityAttestations: AugmentedQuery<
+        ApiType,
+        (
+          arg: AccountId32 | string | Uint8Array
+        ) => Observable<Option<PalletStorageProvidersPendingCapacityAttestation>>,
+        [AccountId32]
+      > &
+        QueryableStorageEntry<ApiType, [AccountId32]>;
+      /**
+       * The mapping from a Storage Provider to the capacity decrease that it has requested and not confirmed yet.
+       *
+       * This storage is updated in:
+       * - [request_capacity_decrease](crate::dispatchables::request_capacity_decrease), which adds an entry.
+       * - [confirm_capacity_decrease](crate::dispatchables::confirm_capacity_decrease), which removes it, either applying
+       * or cancelling the decrease.
+       * - [msp_sign_off](crate::dispatchables::msp_sign_off) and [bsp_sign_off](crate::dispatchables::bsp_sign_off), which
+       * remove the entry of the signed off Storage Provider, if any.
+       **/
+      pendingCapacityDecreases: AugmentedQuery<
+        ApiType,
+        (
+          arg: H256 | string | Uint8Array
+        ) => Observable<Option<PalletStorageProvidersPendingCapacityDecrease>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * A map of Storage Hub tick numbers to expired provider top up expired items.
        *
@@ -1715,6 +2401,23 @@ declare module "@polkadot/api-base/types/storage" {
         [AccountId32]
       > &
         QueryableStorageEntry<ApiType, [AccountId32]>;
+      /**
+       * The set of Storage Providers that have been suspended by governance.
+       *
+       * Suspended Storage Providers keep their current duties, but can't take on new work until they are resumed.
+       *
+       * This storage is updated in:
+       * - [suspend_provider](crate::dispatchables::suspend_provider), which adds an entry.
+       * - [resume_provider](crate::dispatchables::resume_provider), which removes it.
+       * - [msp_sign_off](crate::dispatchables::msp_sign_off), [bsp_sign_off](crate::dispatchables::bsp_sign_off) and
+       * [delete_provider](crate::dispatchables::delete_provider), which remove the entry of the removed Storage Provider, if any.
+       **/
+      suspendedProviders: AugmentedQuery<
+        ApiType,
+        (arg: H256 | string | Uint8Array) => Observable<Option<Null>>,
+        [H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256]>;
       /**
        * The total amount of storage capacity all BSPs have.
        *
@@ -1735,6 +2438,21 @@ declare module "@polkadot/api-base/types/storage" {
        **/
       usedBspsCapacity: AugmentedQuery<ApiType, () => Observable<u64>, []> &
         QueryableStorageEntry<ApiType, []>;
+      /**
+       * Double mapping from a [`MainStorageProviderId`] to the [`ValuePropositionVersion`] of each of its value propositions
+       * that were published as a new version of a previous one.
+       *
+       * Value propositions not present in this mapping are the first version of their own lineage.
+       **/
+      valuePropositionVersions: AugmentedQuery<
+        ApiType,
+        (
+          arg1: H256 | string | Uint8Array,
+          arg2: H256 | string | Uint8Array
+        ) => Observable<Option<PalletStorageProvidersValuePropositionVersion>>,
+        [H256, H256]
+      > &
+        QueryableStorageEntry<ApiType, [H256, H256]>;
       /**
        * Generic query
        **/
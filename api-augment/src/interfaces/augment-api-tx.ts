@@ -18,7 +18,11 @@ import type {
   CumulusPrimitivesCoreAggregateMessageOrigin,
   CumulusPrimitivesParachainInherentParachainInherentData,
   PalletBalancesAdjustmentDirection,
+  PalletFileSystemAccessGrantTarget,
   PalletFileSystemBucketMoveRequestResponse,
+  PalletFileSystemDepositTransferResolution,
+  PalletFileSystemFileDeletionRequestItem,
+  PalletFileSystemMspEligibilityListMode,
   PalletFileSystemStorageRequestMspBucketResponse,
   PalletNftsAttributeNamespace,
   PalletNftsCancelAttributesApprovalWitness,
@@ -32,6 +36,8 @@ import type {
   PalletNftsPreSignedMint,
   PalletNftsPriceWithDirection,
   PalletProofsDealerProof,
+  PalletProofsDealerRetentionPolicy,
+  PalletStorageProvidersCapacityAttestationPlotLeafProof,
   ShpFileKeyVerifierFileKeyProof,
   SpRuntimeMultiSignature,
   SpTrieStorageProofCompactProof,
@@ -419,6 +425,19 @@ declare module "@polkadot/api-base/types/submittable" {
       [key: string]: SubmittableExtrinsicFunction<ApiType>;
     };
     fileSystem: {
+      /**
+       * Used by a BSP storing a file moved by the MSP of its bucket to apply the same move to its forest.
+       *
+       * The forest proof must prove that the previous file key is in the BSP's forest and the new one
+       * is not. The former is replaced by the latter, holding the metadata of the file at its new location.
+       **/
+      bspConfirmFileMove: AugmentedSubmittable<
+        (
+          fileKey: H256 | string | Uint8Array,
+          forestProof: SpTrieStorageProofCompactProof | { encodedNodes?: any } | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, SpTrieStorageProofCompactProof]
+      >;
       /**
        * Executed by a BSP to confirm to stop storing a file.
        *
@@ -500,6 +519,56 @@ declare module "@polkadot/api-base/types/submittable" {
         (fileKey: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [H256]
       >;
+      /**
+       * Cancel a deletion list registered by the caller, so that none of its remaining files can be
+       * deleted through it. The deposit of its remaining files is returned to the caller.
+       **/
+      cancelDeletionList: AugmentedSubmittable<
+        (listRoot: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
+      /**
+       * Change the replication target of an open storage request.
+       *
+       * The target can be raised up to the maximum replication target, or lowered down to the
+       * amount of BSPs that already confirmed storing the file. If the new target is already
+       * reached (and the MSP, if any, accepted the storage request), the storage request is
+       * fulfilled right away. The storage request creation deposit is adjusted to the new target.
+       *
+       * If the payment of the BSPs was escrowed, the escrow is left as is: BSPs beyond the escrowed
+       * ones are paid through their payment streams with the owner, and the share of the missing
+       * ones is refunded once the storage request is closed.
+       *
+       * *Callable only by the owner of the storage request.*
+       **/
+      changeStorageRequestReplicationTarget: AugmentedSubmittable<
+        (
+          fileKey: H256 | string | Uint8Array,
+          replicationTarget: u32 | AnyNumber | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, u32]
+      >;
+      /**
+       * Give `grantee` read access to a bucket owned by the caller, or to a single file of it,
+       * until tick `expires_at`.
+       *
+       * Lets the owner of a private bucket share its files temporarily, without making the bucket
+       * public or transferring items of its read-access NFT collection. Replaces any previous
+       * grant of `grantee` for `target`. The grant is removed once it expires.
+       **/
+      createAccessGrant: AugmentedSubmittable<
+        (
+          target:
+            | PalletFileSystemAccessGrantTarget
+            | { Bucket: any }
+            | { File: any }
+            | string
+            | Uint8Array,
+          grantee: AccountId32 | string | Uint8Array,
+          expiresAt: u32 | AnyNumber | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [PalletFileSystemAccessGrantTarget, AccountId32, u32]
+      >;
       /**
        * Create and associate a collection with a bucket.
        **/
@@ -507,14 +576,38 @@ declare module "@polkadot/api-base/types/submittable" {
         (bucketId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [H256]
       >;
+      /**
+       * Create a bucket, optionally stored by an MSP.
+       *
+       * `encryption_metadata` describes how the files of the bucket are encrypted client-side,
+       * if they are. See [`Pallet::set_bucket_encryption_metadata`].
+       **/
       createBucket: AugmentedSubmittable<
         (
           mspId: Option<H256> | null | Uint8Array | H256 | string,
           name: Bytes | string | Uint8Array,
           private: bool | boolean | Uint8Array,
-          valuePropId: Option<H256> | null | Uint8Array | H256 | string
+          valuePropId: Option<H256> | null | Uint8Array | H256 | string,
+          encryptionMetadata: Option<Bytes> | null | Uint8Array | Bytes | string
         ) => SubmittableExtrinsic<ApiType>,
-        [Option<H256>, Bytes, bool, Option<H256>]
+        [Option<H256>, Bytes, bool, Option<H256>, Option<Bytes>]
+      >;
+      /**
+       * Allow `grantee` to issue storage requests for files of up to `size_budget` in total into a
+       * bucket owned by the caller, until block `expires_at`, with
+       * [`Pallet::issue_storage_request_with_grant`].
+       *
+       * The storage requests are issued on behalf of the caller, who owns the files and pays their
+       * deposits and storage. Replaces any previous grant of `grantee` for the bucket.
+       **/
+      createUploadGrant: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          grantee: AccountId32 | string | Uint8Array,
+          sizeBudget: u64 | AnyNumber | Uint8Array,
+          expiresAt: u32 | AnyNumber | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, AccountId32, u64, u32]
       >;
       /**
        * Dispatchable extrinsic that allows a User to delete any of their buckets if it is currently empty.
@@ -546,10 +639,153 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, H256, Bytes, u64, H256, Option<SpTrieStorageProofCompactProof>]
       >;
+      /**
+       * Request the deletion of a batch of files from a bucket owned by the caller.
+       *
+       * Behaves as calling [`Pallet::delete_file`] without a proof of inclusion for each file,
+       * with a single signed extrinsic covering all of them: the pending file deletion requests
+       * are either all created or none is.
+       **/
+      deleteFiles: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          files:
+            | Vec<PalletFileSystemFileDeletionRequestItem>
+            | (
+                | PalletFileSystemFileDeletionRequestItem
+                | { fileKey?: any; location?: any; size_?: any; fingerprint?: any }
+                | string
+                | Uint8Array
+              )[]
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Vec<PalletFileSystemFileDeletionRequestItem>]
+      >;
+      /**
+       * Request the deletion of the next file of a deletion list registered by `owner`.
+       *
+       * Behaves as `owner` calling [`Pallet::delete_file`] for the file, once `merkle_proof` proves
+       * that its file key is the next one of the list. Each file of the list can only be processed
+       * once, and the list is removed after its last file.
+       *
+       * If requesting the deletion of the file fails without a proof of inclusion, i.e. because the
+       * file no longer belongs to `owner`, the file is skipped so that it does not block the rest of
+       * the list. If it fails with a proof of inclusion, the call fails instead, so that a wrong
+       * proof cannot be used to skip a file. Either way, the deposit of the processed file is
+       * returned to `owner`.
+       *
+       * *Callable by anyone.*
+       **/
+      executeDeletionListItem: AugmentedSubmittable<
+        (
+          owner: AccountId32 | string | Uint8Array,
+          listRoot: H256 | string | Uint8Array,
+          bucketId: H256 | string | Uint8Array,
+          location: Bytes | string | Uint8Array,
+          size: u64 | AnyNumber | Uint8Array,
+          fingerprint: H256 | string | Uint8Array,
+          merkleProof: Vec<H256> | (H256 | string | Uint8Array)[],
+          maybeInclusionForestProof:
+            | Option<SpTrieStorageProofCompactProof>
+            | null
+            | Uint8Array
+            | SpTrieStorageProofCompactProof
+            | { encodedNodes?: any }
+            | string
+        ) => SubmittableExtrinsic<ApiType>,
+        [
+          AccountId32,
+          H256,
+          H256,
+          Bytes,
+          u64,
+          H256,
+          Vec<H256>,
+          Option<SpTrieStorageProofCompactProof>
+        ]
+      >;
+      /**
+       * Freeze a bucket pending the resolution of a dispute.
+       *
+       * While frozen, no new files can be added to the bucket, files can't be deleted from it, and
+       * the bucket can't be moved to another MSP, stopped being stored by its MSP or deleted. Files
+       * already stored are left untouched.
+       *
+       * *Callable only by root.*
+       **/
+      freezeBucket: AugmentedSubmittable<
+        (bucketId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
+      /**
+       * Grant `account` read access to a file owned by the caller.
+       *
+       * The file is identified by its metadata, from which its file key is computed, so that only its owner
+       * can manage its access list.
+       **/
+      grantFileAccess: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          location: Bytes | string | Uint8Array,
+          size: u64 | AnyNumber | Uint8Array,
+          fingerprint: H256 | string | Uint8Array,
+          account: AccountId32 | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Bytes, u64, H256, AccountId32]
+      >;
+      /**
+       * Issue a new storage request for a file, escrowing upfront the payment of all the BSPs
+       * required to fulfill it for `escrow_ticks` ticks.
+       *
+       * The cost is projected with the current price per giga-unit per tick. Each BSP that confirms
+       * storing the file is paid from the escrow (instead of through its payment stream with the
+       * User) as it proves storing it, and is moved to its payment stream once paid for all
+       * `escrow_ticks`. The share of the BSPs missing when the storage request is fulfilled, expires
+       * or is revoked is refunded to the User.
+       **/
+      issueEscrowedStorageRequest: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          location: Bytes | string | Uint8Array,
+          fingerprint: H256 | string | Uint8Array,
+          size: u64 | AnyNumber | Uint8Array,
+          mspId: Option<H256> | null | Uint8Array | H256 | string,
+          peerIds: Vec<Bytes> | (Bytes | string | Uint8Array)[],
+          replicationTarget: Option<u32> | null | Uint8Array | u32 | AnyNumber,
+          escrowTicks: u32 | AnyNumber | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Bytes, H256, u64, Option<H256>, Vec<Bytes>, Option<u32>, u32]
+      >;
       /**
        * Issue a new storage request for a file
+       *
+       * If `replication_target` is `None`, the bucket's default replication target is used. A
+       * replication target of zero issues an MSP-only storage request: no BSP will store the file,
+       * and the storage request is fulfilled as soon as the MSP accepts it. An MSP is required.
+       *
+       * If `auto_renew` is `true`, a second storage request creation deposit is held, so that if
+       * the storage request expires without reaching its replication target, a new one is issued
+       * for the missing replicas, paying its deposit with it.
        **/
       issueStorageRequest: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          location: Bytes | string | Uint8Array,
+          fingerprint: H256 | string | Uint8Array,
+          size: u64 | AnyNumber | Uint8Array,
+          mspId: Option<H256> | null | Uint8Array | H256 | string,
+          peerIds: Vec<Bytes> | (Bytes | string | Uint8Array)[],
+          replicationTarget: Option<u32> | null | Uint8Array | u32 | AnyNumber,
+          autoRenew: bool | boolean | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Bytes, H256, u64, Option<H256>, Vec<Bytes>, Option<u32>, bool]
+      >;
+      /**
+       * Issue a storage request for a file of a bucket the caller was given an upload grant for.
+       *
+       * Behaves as the owner of the bucket calling [`Pallet::issue_storage_request`] for the file,
+       * without auto-renewal, after deducting its size from the remaining budget of the grant.
+       **/
+      issueStorageRequestWithGrant: AugmentedSubmittable<
         (
           bucketId: H256 | string | Uint8Array,
           location: Bytes | string | Uint8Array,
@@ -561,6 +797,47 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, Bytes, H256, u64, Option<H256>, Vec<Bytes>, Option<u32>]
       >;
+      /**
+       * Migrate a bucket to the latest version of its value proposition.
+       *
+       * Buckets keep the version of the value proposition they were created with when their MSP
+       * publishes a new one, until their owner opts in to the new terms with this extrinsic. The
+       * fixed rate payment stream of the owner with the MSP is updated to the new price.
+       *
+       * *Callable only by the owner of the bucket.*
+       **/
+      migrateBucketValueProp: AugmentedSubmittable<
+        (bucketId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
+      /**
+       * Used by the MSP of a bucket to move a file to the new location requested by its owner.
+       *
+       * The forest proof must prove that the current file key is in the bucket's forest and the new
+       * one is not. The former is replaced by the latter, holding the metadata of the file at its new
+       * location.
+       **/
+      mspConfirmFileMove: AugmentedSubmittable<
+        (
+          fileKey: H256 | string | Uint8Array,
+          forestProof: SpTrieStorageProofCompactProof | { encodedNodes?: any } | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, SpTrieStorageProofCompactProof]
+      >;
+      /**
+       * Offer to transfer a bucket stored by the caller MSP to `new_msp_id`, i.e. when winding down.
+       *
+       * The owner of the bucket has [`Config::MoveBucketRequestTtl`] blocks to accept the offer with
+       * [`Pallet::respond_bucket_transfer_offer`], after which `new_msp_id` accepts or rejects the
+       * move as with any move bucket request. Replaces any previous offer for the bucket.
+       **/
+      mspOfferBucketTransfer: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          newMspId: H256 | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, H256]
+      >;
       mspRespondMoveBucketRequest: AugmentedSubmittable<
         (
           bucketId: H256 | string | Uint8Array,
@@ -611,6 +888,23 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [AccountId32, H256, u64, H256, SpTrieStorageProofCompactProof]
       >;
+      /**
+       * Authorise the deletion of a list of `length` files owned by the caller with a single
+       * signature, by registering the root of a Merkle tree over their file keys.
+       *
+       * Anyone can then request the deletion of the files of the list, in order, with
+       * [`Pallet::execute_deletion_list_item`] and a proof that each file is part of it. See
+       * [`Pallet::verify_deletion_list_proof`] for how the tree is built.
+       *
+       * Holds [`Config::DeletionListItemDeposit`] from the caller for each file of the list.
+       **/
+      registerDeletionList: AugmentedSubmittable<
+        (
+          listRoot: H256 | string | Uint8Array,
+          length: u32 | AnyNumber | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, u32]
+      >;
       requestMoveBucket: AugmentedSubmittable<
         (
           bucketId: H256 | string | Uint8Array,
@@ -618,6 +912,97 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, H256]
       >;
+      /**
+       * Request to move a file to a new location within its bucket, keeping its size and fingerprint.
+       *
+       * Since the location is part of the file's metadata, the file gets a new file key. The move is
+       * applied to the bucket's forest by its MSP with [`Pallet::msp_confirm_file_move`], after which
+       * BSPs storing the file can apply it to their forest with [`Pallet::bsp_confirm_file_move`].
+       *
+       * *Callable only by the owner of the bucket.*
+       **/
+      requestMoveFile: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          fileKey: H256 | string | Uint8Array,
+          location: Bytes | string | Uint8Array,
+          size: u64 | AnyNumber | Uint8Array,
+          fingerprint: H256 | string | Uint8Array,
+          newLocation: Bytes | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, H256, Bytes, u64, H256, Bytes]
+      >;
+      /**
+       * Resolve a failed deposit transfer pending to be retried, either retrying it right away or
+       * writing it off (removing it from the retry queue without executing it).
+       *
+       * Meant to follow up on deposit transfers that keep failing, or to write off the ones that
+       * were already settled by other means.
+       *
+       * *Callable only by root.*
+       **/
+      resolveDepositTransferRetry: AugmentedSubmittable<
+        (
+          retryId: u32 | AnyNumber | Uint8Array,
+          resolution:
+            | PalletFileSystemDepositTransferResolution
+            | "Retry"
+            | "WriteOff"
+            | number
+            | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [u32, PalletFileSystemDepositTransferResolution]
+      >;
+      /**
+       * Accept or reject, as the owner of a bucket, the offer of its MSP to transfer it to another
+       * MSP.
+       *
+       * Accepting the offer requests the move of the bucket to the new MSP, as
+       * [`Pallet::request_move_bucket`] would.
+       **/
+      respondBucketTransferOffer: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          response:
+            | PalletFileSystemBucketMoveRequestResponse
+            | "Accepted"
+            | "Rejected"
+            | number
+            | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, PalletFileSystemBucketMoveRequestResponse]
+      >;
+      /**
+       * Revoke the access grant of `grantee` to a bucket owned by the caller, or to a file of it,
+       * before it expires.
+       **/
+      revokeAccessGrant: AugmentedSubmittable<
+        (
+          target:
+            | PalletFileSystemAccessGrantTarget
+            | { Bucket: any }
+            | { File: any }
+            | string
+            | Uint8Array,
+          grantee: AccountId32 | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [PalletFileSystemAccessGrantTarget, AccountId32]
+      >;
+      /**
+       * Revoke the read access of `account` to a file owned by the caller.
+       *
+       * The file is identified by its metadata, the same way as in [`Pallet::grant_file_access`].
+       **/
+      revokeFileAccess: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          location: Bytes | string | Uint8Array,
+          size: u64 | AnyNumber | Uint8Array,
+          fingerprint: H256 | string | Uint8Array,
+          account: AccountId32 | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Bytes, u64, H256, AccountId32]
+      >;
       /**
        * Revoke storage request
        **/
@@ -625,6 +1010,51 @@ declare module "@polkadot/api-base/types/submittable" {
         (fileKey: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [H256]
       >;
+      /**
+       * Revoke the upload grant of `grantee` for a bucket owned by the caller.
+       *
+       * Storage requests already issued with the grant are not affected.
+       **/
+      revokeUploadGrant: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          grantee: AccountId32 | string | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, AccountId32]
+      >;
+      /**
+       * Set (or clear, if `None`) the default replication target of a bucket.
+       *
+       * Storage requests for files in this bucket that don't specify a replication target
+       * will use this value instead of [`Config::DefaultReplicationTarget`].
+       *
+       * *Callable only by the owner of the bucket.*
+       **/
+      setBucketDefaultReplicationTarget: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          replicationTarget: Option<u32> | null | Uint8Array | u32 | AnyNumber
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Option<u32>]
+      >;
+      /**
+       * Set (or clear, if `None`) the metadata describing how the contents of a bucket are
+       * encrypted.
+       *
+       * Files are encrypted client-side, so the runtime does not interpret this metadata. It is
+       * a standard place for the owner to record the encryption scheme and key-wrapping
+       * details, so that the SDKs reading the files of the bucket can discover how to decrypt
+       * them.
+       *
+       * *Callable only by the owner of the bucket.*
+       **/
+      setBucketEncryptionMetadata: AugmentedSubmittable<
+        (
+          bucketId: H256 | string | Uint8Array,
+          encryptionMetadata: Option<Bytes> | null | Uint8Array | Bytes | string
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Option<Bytes>]
+      >;
       setGlobalParameters: AugmentedSubmittable<
         (
           newMaxReplicationTarget: Option<u32> | null | Uint8Array | u32 | AnyNumber,
@@ -632,6 +1062,35 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [Option<u32>, Option<u32>]
       >;
+      /**
+       * Set how the MSP eligibility list is interpreted: as a denylist of MSPs that cannot take
+       * new buckets, or as an allowlist of the only MSPs that can.
+       *
+       * *Callable only by root.*
+       **/
+      setMspEligibilityMode: AugmentedSubmittable<
+        (
+          mode:
+            | PalletFileSystemMspEligibilityListMode
+            | "Denylist"
+            | "Allowlist"
+            | number
+            | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [PalletFileSystemMspEligibilityListMode]
+      >;
+      /**
+       * Set the pointer to the caller's notification preferences, or clear it if `None`.
+       *
+       * The preferences are expected to be a hash or a URI, which off-chain services resolve to the
+       * caller's contact preferences to alert them of events concerning their files.
+       **/
+      setNotificationPreferences: AugmentedSubmittable<
+        (
+          preferences: Option<Bytes> | null | Uint8Array | Bytes | string
+        ) => SubmittableExtrinsic<ApiType>,
+        [Option<Bytes>]
+      >;
       /**
        * Executed by a SP to stop storing a file from an insolvent user.
        *
@@ -656,6 +1115,15 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, H256, Bytes, AccountId32, H256, u64, SpTrieStorageProofCompactProof]
       >;
+      /**
+       * Unfreeze a bucket frozen with [`Pallet::freeze_bucket`], lifting all its restrictions.
+       *
+       * *Callable only by root.*
+       **/
+      unfreezeBucket: AugmentedSubmittable<
+        (bucketId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
       updateBucketPrivacy: AugmentedSubmittable<
         (
           bucketId: H256 | string | Uint8Array,
@@ -663,6 +1131,21 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, bool]
       >;
+      /**
+       * Add an MSP to (`listed` = `true`) or remove it from (`listed` = `false`) the MSP
+       * eligibility list.
+       *
+       * This does not affect the buckets the MSP already stores.
+       *
+       * *Callable only by root.*
+       **/
+      updateMspEligibilityList: AugmentedSubmittable<
+        (
+          mspId: H256 | string | Uint8Array,
+          listed: bool | boolean | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, bool]
+      >;
       /**
        * Generic tx
        **/
@@ -2105,6 +2588,27 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, AccountId32]
       >;
+      /**
+       * Dispatchable extrinsic that allows a User to add funds to its auto top-up reserve.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * Parameters:
+       * - `amount`: The amount to hold from the User's free balance and add to the reserve.
+       *
+       * This extrinsic will perform the following checks and logic:
+       * 1. Check that the extrinsic was signed and get the signer.
+       * 2. Check that the amount is not zero.
+       * 3. Check that the User has enough funds and hold them.
+       *
+       * The reserve is only used if the User enables the automatic top-up with [`set_auto_top_up_threshold`](Self::set_auto_top_up_threshold).
+       *
+       * Emits a `AutoTopUpReserveDeposited` event when successful.
+       **/
+      depositAutoTopUpReserve: AugmentedSubmittable<
+        (amount: u128 | AnyNumber | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [u128]
+      >;
       /**
        * Dispatchable extrinsic that allows a user flagged as without funds to pay the Providers that still have payment streams
        * with it, in order to recover as much of its deposits as possible.
@@ -2132,6 +2636,83 @@ declare module "@polkadot/api-base/types/submittable" {
         (providers: Vec<H256> | (H256 | string | Uint8Array)[]) => SubmittableExtrinsic<ApiType>,
         [Vec<H256>]
       >;
+      /**
+       * Dispatchable extrinsic that allows a Provider to release the payment escrowed for it, up to its last chargeable tick.
+       *
+       * The dispatch origin for this call must be Signed.
+       * The origin must be the account of a Provider that is being paid by the escrow.
+       *
+       * Parameters:
+       * - `escrow_id`: The ID of the escrow to release the payment from.
+       *
+       * This extrinsic will perform the following checks and logic:
+       * 1. Check that the extrinsic was signed and get the signer.
+       * 2. Check that the signer is a registered Provider that is being paid by the escrow.
+       * 3. Release to the Provider the escrowed payment for the ticks between the last one it was paid for and its last
+       * chargeable tick (capped at the end of its escrowed ticks), transferring a cut of it to the treasury.
+       * 4. If the Provider has been paid for all its escrowed ticks, remove it from the escrow, closing the escrow if it
+       * was the last one and its replication has been settled.
+       *
+       * Emits a `EscrowPaymentReleased` event when successful if there was something to release, and a `EscrowClosed` event if
+       * the escrow gets closed.
+       **/
+      releaseEscrowedPayment: AugmentedSubmittable<
+        (escrowId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
+      /**
+       * Dispatchable extrinsic that allows a User to enable, update or disable the automatic top-up of its free balance
+       * from its auto top-up reserve.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * Parameters:
+       * - `threshold`: The free balance the User wants to keep to pay for its payment streams. `None` disables the
+       * automatic top-up, leaving the funds in the reserve until they are withdrawn.
+       *
+       * This extrinsic will perform the following checks and logic:
+       * 1. Check that the extrinsic was signed and get the signer.
+       * 2. Check that the threshold is not zero.
+       * 3. Set or clear the threshold of the User.
+       *
+       * While enabled, whenever a Provider charges the User and it can't pay (or its free balance falls below the threshold),
+       * its free balance is topped up from the reserve in the `on_poll` hook of the next block, up to the greater of the
+       * threshold and the amount it could not pay. This happens well before the User is flagged as without funds, which
+       * only happens after it failed to pay for [`Config::NewStreamDeposit`] ticks.
+       *
+       * Emits a `AutoTopUpThresholdSet` event when successful.
+       **/
+      setAutoTopUpThreshold: AugmentedSubmittable<
+        (
+          threshold: Option<u128> | null | Uint8Array | u128 | AnyNumber
+        ) => SubmittableExtrinsic<ApiType>,
+        [Option<u128>]
+      >;
+      /**
+       * Dispatchable extrinsic that allows root to enable, update or disable the tracking of a target price per giga-unit
+       * per tick in the reference currency of the oracle feed.
+       *
+       * The dispatch origin for this call must be Root.
+       *
+       * Parameters:
+       * - `target`: The target price per giga-unit per tick, in the smallest unit of the reference currency of the
+       * oracle feed. `None` disables the tracking, leaving the price per giga-unit per tick to the system manager.
+       *
+       * This extrinsic will perform the following checks and logic:
+       * 1. Check that the extrinsic was executed by the root origin
+       * 2. Check that the target is not zero
+       * 3. Set or clear the target, clearing the price resulting from previous adjustments when disabling it
+       *
+       * The price per giga-unit per tick is adjusted towards the new target starting from the next adjustment period.
+       *
+       * Emits a `FiatPriceTargetSet` event when successful.
+       **/
+      setFiatPriceTarget: AugmentedSubmittable<
+        (
+          target: Option<u128> | null | Uint8Array | u128 | AnyNumber
+        ) => SubmittableExtrinsic<ApiType>,
+        [Option<u128>]
+      >;
       /**
        * Dispatchable extrinsic that allows root to update an existing dynamic-rate payment stream between a User and a Provider.
        *
@@ -2184,6 +2765,25 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [H256, AccountId32, u128]
       >;
+      /**
+       * Dispatchable extrinsic that allows a User to withdraw funds from its auto top-up reserve back to its free balance.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * Parameters:
+       * - `amount`: The amount to release from the reserve.
+       *
+       * This extrinsic will perform the following checks and logic:
+       * 1. Check that the extrinsic was signed and get the signer.
+       * 2. Check that the amount is not zero.
+       * 3. Check that the reserve of the User has at least that amount and release it.
+       *
+       * Emits a `AutoTopUpReserveWithdrawn` event when successful.
+       **/
+      withdrawAutoTopUpReserve: AugmentedSubmittable<
+        (amount: u128 | AnyNumber | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [u128]
+      >;
       /**
        * Generic tx
        **/
@@ -2765,6 +3365,43 @@ declare module "@polkadot/api-base/types/submittable" {
         (paused: bool | boolean | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [bool]
       >;
+      /**
+       * Set the challenge period of a Provider, overriding the one derived from its stake, or
+       * remove its override if `period` is `None`.
+       *
+       * Only callable by sudo.
+       *
+       * The new period applies from the next time the Provider's next challenge tick is
+       * calculated (i.e. its next proof submission), so its current deadline is not changed.
+       **/
+      setProviderChallengePeriodOverride: AugmentedSubmittable<
+        (
+          providerId: H256 | string | Uint8Array,
+          period: Option<u32> | null | Uint8Array | u32 | AnyNumber
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, Option<u32>]
+      >;
+      /**
+       * Set the retention policy for the history kept by this pallet, or go back to the one given
+       * by the pallet's constants if `policy` is `None`.
+       *
+       * Only callable by sudo.
+       *
+       * History that falls out of a shortened retention is pruned lazily, in the `on_idle` hook.
+       * Seeds that were already pruned are not restored when the retention is lengthened.
+       **/
+      setRetentionPolicy: AugmentedSubmittable<
+        (
+          policy:
+            | Option<PalletProofsDealerRetentionPolicy>
+            | null
+            | Uint8Array
+            | PalletProofsDealerRetentionPolicy
+            | { challengeSeeds?: any; validProofSubmitters?: any }
+            | string
+        ) => SubmittableExtrinsic<ApiType>,
+        [Option<PalletProofsDealerRetentionPolicy>]
+      >;
       /**
        * For a Provider to submit a proof.
        *
@@ -2803,6 +3440,69 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [PalletProofsDealerProof, Option<H256>]
       >;
+      /**
+       * For a Provider to submit proofs for several consecutive challenge ticks at once, i.e.
+       * after falling behind by more than one of its challenge periods.
+       *
+       * Works as [`Pallet::submit_proof`] for each of the `proofs`, in order. Each proof is
+       * paired with the challenges tick it is a response to, which has to be the tick the
+       * Provider should be submitting a proof for after the previous proofs in the batch are
+       * accepted. The Provider's [`ProviderToProofSubmissionRecord`] and deadline are only
+       * updated once, after all the proofs are verified.
+       *
+       * Either all the proofs are accepted or none is: the first one failing verification
+       * fails the whole submission, without accepting the proofs before it.
+       *
+       * Execution of this extrinsic should be refunded if all the proofs are valid.
+       **/
+      submitProofs: AugmentedSubmittable<
+        (
+          proofs:
+            | Vec<ITuple<[u32, PalletProofsDealerProof]>>
+            | [
+                u32 | AnyNumber | Uint8Array,
+                (
+                  | PalletProofsDealerProof
+                  | { forestProof?: any; keyProofs?: any }
+                  | string
+                  | Uint8Array
+                )
+              ][],
+          provider: Option<H256> | null | Uint8Array | H256 | string
+        ) => SubmittableExtrinsic<ApiType>,
+        [Vec<ITuple<[u32, PalletProofsDealerProof]>>, Option<H256>]
+      >;
+      /**
+       * Submit a proof for `provider` as an unsigned transaction.
+       *
+       * Works as [`Pallet::submit_proof`], but does not require the Provider to keep an account
+       * funded and track its nonce to submit proofs. Instead, the owner of the Provider signs the
+       * payload built by [`Pallet::unsigned_proof_payload`], which binds the proof to the tick
+       * the Provider has to submit a proof for, so that it cannot be replayed.
+       *
+       * The transaction is only valid while the proof is due, i.e. from the tick the Provider
+       * has to submit a proof for until its deadline, and only one can be included for each
+       * tick, even if its proof fails verification (see [`UnsignedProofAttempts`]). A Provider
+       * whose unsigned proof failed has to use [`Pallet::submit_proof`] instead.
+       **/
+      submitProofUnsigned: AugmentedSubmittable<
+        (
+          proof:
+            | PalletProofsDealerProof
+            | { forestProof?: any; keyProofs?: any }
+            | string
+            | Uint8Array,
+          provider: H256 | string | Uint8Array,
+          signature:
+            | SpRuntimeMultiSignature
+            | { Ed25519: any }
+            | { Sr25519: any }
+            | { Ecdsa: any }
+            | string
+            | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [PalletProofsDealerProof, H256, SpRuntimeMultiSignature]
+      >;
       /**
        * Generic tx
        **/
@@ -2847,6 +3547,35 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [u128, Bytes, u64]
       >;
+      /**
+       * Dispatchable extrinsic that allows an account to answer the challenge of the capacity attestation it
+       * committed to.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * Parameters:
+       * - `answer`: The proof of each challenged leaf of the plot and the leaf before it, in the order they are
+       * challenged in, as returned by [`CapacityPlot::answer_challenge`](crate::capacity_attestation::CapacityPlot::answer_challenge) for the
+       * leaves returned by the `query_capacity_challenge` runtime API.
+       *
+       * If every proof is valid, the committed capacity becomes the signer's attested capacity, which lets it sign
+       * up as a BSP or increase its capacity up to that amount when [`Config::RequireCapacityAttestation`] is enabled.
+       *
+       * Emits `CapacityAttested` event when successful.
+       **/
+      answerCapacityChallenge: AugmentedSubmittable<
+        (
+          answer:
+            | Vec<PalletStorageProvidersCapacityAttestationPlotLeafProof>
+            | (
+                | PalletStorageProvidersCapacityAttestationPlotLeafProof
+                | { leaf?: any; siblings?: any; previousLeaf?: any; previousSiblings?: any }
+                | string
+                | Uint8Array
+              )[]
+        ) => SubmittableExtrinsic<ApiType>,
+        [Vec<PalletStorageProvidersCapacityAttestationPlotLeafProof>]
+      >;
       /**
        * Dispatchable extrinsic that allows users to sign off as a Backup Storage Provider.
        *
@@ -2865,6 +3594,16 @@ declare module "@polkadot/api-base/types/submittable" {
        * Emits `BspSignOffSuccess` event when successful.
        **/
       bspSignOff: AugmentedSubmittable<() => SubmittableExtrinsic<ApiType>, []>;
+      /**
+       * Dispatchable extrinsic that allows a Storage Provider to cancel its scheduled sign off, so it can take on
+       * new work again.
+       *
+       * The dispatch origin for this call must be Signed.
+       * The origin must be the account of the Storage Provider.
+       *
+       * Emits `ProviderStatusChanged` event when successful.
+       **/
+      cancelScheduledSignOff: AugmentedSubmittable<() => SubmittableExtrinsic<ApiType>, []>;
       /**
        * Dispatchable extrinsic that allows a user with a pending Sign Up Request to cancel it, getting the deposit back.
        *
@@ -2910,6 +3649,41 @@ declare module "@polkadot/api-base/types/submittable" {
         (newCapacity: u64 | AnyNumber | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [u64]
       >;
+      /**
+       * Dispatchable extrinsic that allows an account to commit to the plot of the capacity attestation it
+       * requested.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * Parameters:
+       * - `plot_root`: The root of the plot of the signer for the capacity to attest, sealed with the seed drawn
+       * with [draw_capacity_plot_seed](crate::dispatchables::draw_capacity_plot_seed), as built by
+       * [`CapacityPlot`](crate::capacity_attestation::CapacityPlot).
+       *
+       * Once the randomness for this block is available, the signer has to answer the challenge with
+       * [answer_capacity_challenge](crate::dispatchables::answer_capacity_challenge) before
+       * [`Config::CapacityAttestationWindow`] blocks have passed.
+       *
+       * Emits `CapacityPlotCommitted` event when successful.
+       **/
+      commitCapacityPlot: AugmentedSubmittable<
+        (plotRoot: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
+      /**
+       * Dispatchable extrinsic that allows a Storage Provider to apply the capacity decrease it requested, once
+       * its grace period has passed.
+       *
+       * The dispatch origin for this call must be Signed.
+       * The origin must be the account of the Storage Provider.
+       *
+       * The pending decrease is removed either way: if the Storage Provider still uses more storage than the requested
+       * capacity, the decrease is cancelled and its capacity is left as is. Otherwise, its capacity is changed as in
+       * [change_capacity](crate::dispatchables::change_capacity), releasing the corresponding deposit.
+       *
+       * Emits `CapacityChanged` event when the decrease is applied, or `CapacityDecreaseCancelled` when it is cancelled.
+       **/
+      confirmCapacityDecrease: AugmentedSubmittable<() => SubmittableExtrinsic<ApiType>, []>;
       /**
        * Dispatchable extrinsic that allows users to confirm their sign up as a Storage Provider, either MSP or BSP.
        *
@@ -2959,6 +3733,19 @@ declare module "@polkadot/api-base/types/submittable" {
         (providerId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [H256]
       >;
+      /**
+       * Dispatchable extrinsic that allows an account to draw the seed of the plot of the capacity attestation it
+       * requested, from the randomness generated after requesting it.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * The signer then has to build its plot sealed with the seed, as [`CapacityPlot`](crate::capacity_attestation::CapacityPlot)
+       * does, and commit to it with [commit_capacity_plot](crate::dispatchables::commit_capacity_plot) before
+       * [`Config::CapacityAttestationWindow`] blocks have passed.
+       *
+       * Emits `CapacityPlotSeedDrawn` event when successful.
+       **/
+      drawCapacityPlotSeed: AugmentedSubmittable<() => SubmittableExtrinsic<ApiType>, []>;
       /**
        * Dispatchable extrinsic that allows to forcefully and automatically sing up a Backup Storage Provider.
        *
@@ -3055,6 +3842,32 @@ declare module "@polkadot/api-base/types/submittable" {
        * Emits `MspSignOffSuccess` event when successful.
        **/
       mspSignOff: AugmentedSubmittable<() => SubmittableExtrinsic<ApiType>, []>;
+      /**
+       * Dispatchable extrinsic only callable by an MSP that allows it to publish a new version of one of its
+       * value propositions.
+       *
+       * The dispatch origin for this call must be Signed.
+       * The origin must be the account of the MSP that owns the value proposition.
+       *
+       * Parameters:
+       * - `value_prop_id`: The ID of the latest version of the value proposition to supersede.
+       * - `price_per_giga_unit_of_data_per_block`, `commitment` and `bucket_data_limit`: The terms of the new version.
+       *
+       * The previous version is made unavailable, so new buckets can only be created with the new one. Existing buckets
+       * keep the version they were created with (they are grandfathered) until their owners migrate them to the latest
+       * version.
+       *
+       * Emits `ValuePropVersionPublished` event when successful.
+       **/
+      publishValuePropVersion: AugmentedSubmittable<
+        (
+          valuePropId: H256 | string | Uint8Array,
+          pricePerGigaUnitOfDataPerBlock: u128 | AnyNumber | Uint8Array,
+          commitment: Bytes | string | Uint8Array,
+          bucketDataLimit: u64 | AnyNumber | Uint8Array
+        ) => SubmittableExtrinsic<ApiType>,
+        [H256, u128, Bytes, u64]
+      >;
       /**
        * Dispatchable extrinsic that allows BSPs and MSPs to remove an existing multiaddress from their account.
        *
@@ -3109,6 +3922,46 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [u64, Vec<Bytes>, AccountId32]
       >;
+      /**
+       * Dispatchable extrinsic that allows an account to request to attest that it has `capacity` of storage
+       * available.
+       *
+       * The dispatch origin for this call must be Signed.
+       *
+       * Parameters:
+       * - `capacity`: The capacity to attest.
+       *
+       * Requesting replaces any previous pending attestation of the signer. Once the randomness for this block is
+       * available, the signer has to draw the seed of its plot with [draw_capacity_plot_seed](crate::dispatchables::draw_capacity_plot_seed)
+       * before [`Config::MaxBlocksForRandomness`] blocks have passed. See [`crate::capacity_attestation`] for the
+       * whole protocol.
+       *
+       * Emits `CapacityAttestationRequested` event when successful.
+       **/
+      requestCapacityAttestation: AugmentedSubmittable<
+        (capacity: u64 | AnyNumber | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [u64]
+      >;
+      /**
+       * Dispatchable extrinsic that allows a Storage Provider to request to decrease its capacity, even below the
+       * storage it currently uses.
+       *
+       * The dispatch origin for this call must be Signed.
+       * The origin must be the account of the Storage Provider.
+       *
+       * Parameters:
+       * - `new_capacity`: The total amount of data that the Storage Provider wants to be able to store after the decrease.
+       *
+       * Unlike [change_capacity](crate::dispatchables::change_capacity), the capacity is not changed right away: the
+       * Storage Provider has [`Config::CapacityDecreaseGracePeriod`] blocks to offload the data above `new_capacity`,
+       * after which it can apply the decrease with [confirm_capacity_decrease](crate::dispatchables::confirm_capacity_decrease).
+       *
+       * Emits `CapacityDecreaseRequested` event when successful.
+       **/
+      requestCapacityDecrease: AugmentedSubmittable<
+        (newCapacity: u64 | AnyNumber | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [u64]
+      >;
       /**
        * Dispatchable extrinsic that allows users to request to sign up as a Main Storage Provider.
        *
@@ -3148,6 +4001,30 @@ declare module "@polkadot/api-base/types/submittable" {
         ) => SubmittableExtrinsic<ApiType>,
         [u64, Vec<Bytes>, u128, Bytes, u64, AccountId32]
       >;
+      /**
+       * Dispatchable extrinsic that allows governance to resume a suspended Storage Provider.
+       *
+       * The dispatch origin for this call must be Root.
+       *
+       * Emits `ProviderStatusChanged` event when successful.
+       **/
+      resumeProvider: AugmentedSubmittable<
+        (providerId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
+      /**
+       * Dispatchable extrinsic that allows a Storage Provider to announce that it will sign off.
+       *
+       * The dispatch origin for this call must be Signed.
+       * The origin must be the account of the Storage Provider.
+       *
+       * The Storage Provider is marked as [`ProviderStatus::Exiting`]: it keeps its current duties (e.g. submitting
+       * proofs for the files it stores), but can't take on new work, so it can offload its data and then sign off with
+       * [msp_sign_off](crate::dispatchables::msp_sign_off) or [bsp_sign_off](crate::dispatchables::bsp_sign_off).
+       *
+       * Emits `ProviderStatusChanged` event when successful.
+       **/
+      scheduleSignOff: AugmentedSubmittable<() => SubmittableExtrinsic<ApiType>, []>;
       /**
        * Dispatchable extrinsic to slash a _slashable_ Storage Provider.
        *
@@ -3160,6 +4037,20 @@ declare module "@polkadot/api-base/types/submittable" {
         (providerId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
         [H256]
       >;
+      /**
+       * Dispatchable extrinsic that allows governance to suspend a Storage Provider.
+       *
+       * The dispatch origin for this call must be Root.
+       *
+       * The Storage Provider is marked as [`ProviderStatus::Suspended`]: it keeps its current duties, but can't take
+       * on new work until it is resumed with [resume_provider](crate::dispatchables::resume_provider).
+       *
+       * Emits `ProviderStatusChanged` event when successful.
+       **/
+      suspendProvider: AugmentedSubmittable<
+        (providerId: H256 | string | Uint8Array) => SubmittableExtrinsic<ApiType>,
+        [H256]
+      >;
       /**
        * Dispatchable extrinsic to top-up the deposit of a Storage Provider.
        *
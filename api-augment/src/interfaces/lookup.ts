@@ -1383,9 +1383,34 @@ export default {
         newCapacity: "u64",
         nextBlockWhenChangeAllowed: "u32"
       },
+      CapacityDecreaseRequested: {
+        who: "AccountId32",
+        providerId: "PalletStorageProvidersStorageProviderId",
+        currentCapacity: "u64",
+        newCapacity: "u64",
+        confirmableAt: "u32"
+      },
+      CapacityDecreaseCancelled: {
+        who: "AccountId32",
+        providerId: "PalletStorageProvidersStorageProviderId",
+        newCapacity: "u64",
+        usedCapacity: "u64"
+      },
+      ProviderStatusChanged: {
+        providerId: "H256",
+        status: "ShpTraitsProviderStatus"
+      },
+      BspReputationWeightChanged: {
+        bspId: "H256",
+        oldWeight: "u32",
+        newWeight: "u32"
+      },
       Slashed: {
         providerId: "H256",
-        amount: "u128"
+        amount: "u128",
+        accruedFailedProofSubmissions: "u32",
+        consecutiveMissedDeadlines: "u32",
+        usedCapacity: "u64"
       },
       AwaitingTopUp: {
         providerId: "H256",
@@ -1420,11 +1445,43 @@ export default {
         mspId: "H256",
         valuePropId: "H256"
       },
+      ValuePropVersionPublished: {
+        mspId: "H256",
+        previousValuePropId: "H256",
+        valuePropId: "H256",
+        valueProp: "PalletStorageProvidersValueProposition",
+        version: "PalletStorageProvidersValuePropositionVersion"
+      },
+      BucketValuePropMigrated: {
+        bucketId: "H256",
+        mspId: "H256",
+        previousValuePropId: "H256",
+        valuePropId: "H256"
+      },
       MspDeleted: {
         providerId: "H256"
       },
       BspDeleted: {
         providerId: "H256"
+      },
+      CapacityAttestationRequested: {
+        who: "AccountId32",
+        capacity: "u64"
+      },
+      CapacityPlotSeedDrawn: {
+        who: "AccountId32",
+        seed: "H256",
+        deadline: "u32"
+      },
+      CapacityPlotCommitted: {
+        who: "AccountId32",
+        plotRoot: "H256",
+        capacity: "u64",
+        deadline: "u32"
+      },
+      CapacityAttested: {
+        who: "AccountId32",
+        capacity: "u64"
       }
     }
   },
@@ -1491,6 +1548,16 @@ export default {
         collectionId: "Option<u32>",
         private: "bool"
       },
+      BucketDefaultReplicationTargetUpdated: {
+        who: "AccountId32",
+        bucketId: "H256",
+        replicationTarget: "Option<u32>"
+      },
+      BucketEncryptionMetadataUpdated: {
+        who: "AccountId32",
+        bucketId: "H256",
+        encryptionMetadata: "Option<Bytes>"
+      },
       NewCollectionAndAssociation: {
         who: "AccountId32",
         bucketId: "H256",
@@ -1508,6 +1575,10 @@ export default {
         size_: "u64",
         peerIds: "Vec<Bytes>"
       },
+      NewMspOnlyStorageRequest: {
+        fileKey: "H256",
+        mspId: "H256"
+      },
       MspAcceptedStorageRequest: {
         fileKey: "H256"
       },
@@ -1536,9 +1607,26 @@ export default {
       StorageRequestExpired: {
         fileKey: "H256"
       },
+      StorageRequestRenewed: {
+        fileKey: "H256",
+        bspsRequired: "u32"
+      },
+      StorageRequestRenewalFailed: {
+        fileKey: "H256",
+        error: "SpRuntimeDispatchError"
+      },
+      StorageRequestExpiringSoon: {
+        fileKey: "H256",
+        expiresAt: "u32"
+      },
       StorageRequestRevoked: {
         fileKey: "H256"
       },
+      BspCompensatedForRevokedStorageRequest: {
+        fileKey: "H256",
+        bspId: "H256",
+        amount: "u128"
+      },
       StorageRequestRejected: {
         fileKey: "H256",
         reason: "PalletFileSystemRejectedStorageRequestReason"
@@ -1565,6 +1653,14 @@ export default {
         location: "Bytes",
         newRoot: "H256"
       },
+      PriorityChallengeForFileDeletionDeferred: {
+        issuer: "PalletFileSystemEitherAccountIdOrMspId",
+        fileKey: "H256"
+      },
+      DeferredPriorityChallengeForFileDeletionQueued: {
+        issuer: "PalletFileSystemEitherAccountIdOrMspId",
+        fileKey: "H256"
+      },
       FailedToQueuePriorityChallenge: {
         user: "AccountId32",
         fileKey: "H256"
@@ -1576,6 +1672,49 @@ export default {
         mspId: "Option<H256>",
         proofOfInclusion: "bool"
       },
+      FileDeletionRequestsBatch: {
+        user: "AccountId32",
+        bucketId: "H256",
+        mspId: "Option<H256>",
+        fileKeys: "Vec<H256>"
+      },
+      FileMoveRequested: {
+        owner: "AccountId32",
+        bucketId: "H256",
+        mspId: "Option<H256>",
+        fileKey: "H256",
+        newFileKey: "H256",
+        newLocation: "Bytes"
+      },
+      FileMoved: {
+        mspId: "H256",
+        bucketId: "H256",
+        fileKey: "H256",
+        newFileKey: "H256",
+        newBucketRoot: "H256"
+      },
+      BspConfirmedFileMove: {
+        bspId: "H256",
+        fileKey: "H256",
+        newFileKey: "H256",
+        newRoot: "H256"
+      },
+      NotificationPreferencesSet: {
+        who: "AccountId32",
+        preferences: "Option<Bytes>"
+      },
+      FileAccessGranted: {
+        owner: "AccountId32",
+        bucketId: "H256",
+        fileKey: "H256",
+        account: "AccountId32"
+      },
+      FileAccessRevoked: {
+        owner: "AccountId32",
+        bucketId: "H256",
+        fileKey: "H256",
+        account: "AccountId32"
+      },
       ProofSubmittedForPendingFileDeletionRequest: {
         mspId: "H256",
         user: "AccountId32",
@@ -1610,6 +1749,161 @@ export default {
         fileKey: "H256",
         fileSize: "u64",
         error: "SpRuntimeDispatchError"
+      },
+      MspEligibilityModeSet: {
+        mode: "PalletFileSystemMspEligibilityListMode"
+      },
+      MspAddedToEligibilityList: {
+        mspId: "H256"
+      },
+      MspRemovedFromEligibilityList: {
+        mspId: "H256"
+      },
+      StorageRequestCancelledByUnavailableMsp: {
+        fileKey: "H256",
+        owner: "AccountId32",
+        mspId: "H256",
+        status: "ShpTraitsProviderStatusChange"
+      },
+      UnavailableBspVolunteerRemoved: {
+        fileKey: "H256",
+        bspId: "H256",
+        status: "ShpTraitsProviderStatusChange"
+      },
+      MoveBucketRequestCancelledByUnavailableMsp: {
+        bucketId: "H256",
+        mspId: "H256",
+        status: "ShpTraitsProviderStatusChange"
+      },
+      ProviderStatusChangeProcessed: {
+        providerId: "H256",
+        status: "ShpTraitsProviderStatusChange"
+      },
+      DepositTransferFailed: {
+        transfer: "PalletFileSystemDepositTransfer",
+        retryId: "Option<u32>",
+        error: "SpRuntimeDispatchError"
+      },
+      DepositTransferRetrySucceeded: {
+        retryId: "u32",
+        transfer: "PalletFileSystemDepositTransfer"
+      },
+      DepositTransferRetryFailed: {
+        retryId: "u32",
+        attempts: "u32",
+        nextAttemptAt: "u32",
+        error: "SpRuntimeDispatchError"
+      },
+      DepositTransferAbandoned: {
+        retryId: "u32",
+        transfer: "PalletFileSystemDepositTransfer",
+        error: "SpRuntimeDispatchError"
+      },
+      DepositTransferWrittenOff: {
+        retryId: "u32",
+        transfer: "PalletFileSystemDepositTransfer"
+      },
+      StorageRequestReplicationTargetChanged: {
+        fileKey: "H256",
+        owner: "AccountId32",
+        previousReplicationTarget: "u32",
+        replicationTarget: "u32"
+      },
+      BucketFrozen: {
+        bucketId: "H256",
+        owner: "AccountId32",
+        mspId: "Option<H256>"
+      },
+      BucketUnfrozen: {
+        bucketId: "H256",
+        owner: "AccountId32",
+        mspId: "Option<H256>"
+      },
+      DeletionListRegistered: {
+        owner: "AccountId32",
+        listRoot: "H256",
+        length: "u32",
+        deposit: "u128"
+      },
+      DeletionListFileDeletionRequest: {
+        owner: "AccountId32",
+        listRoot: "H256",
+        index: "u32",
+        fileKey: "H256",
+        bucketId: "H256",
+        mspId: "Option<H256>",
+        proofOfInclusion: "bool"
+      },
+      DeletionListItemSkipped: {
+        owner: "AccountId32",
+        listRoot: "H256",
+        index: "u32",
+        fileKey: "H256",
+        error: "SpRuntimeDispatchError"
+      },
+      DeletionListCompleted: {
+        owner: "AccountId32",
+        listRoot: "H256"
+      },
+      DeletionListCancelled: {
+        owner: "AccountId32",
+        listRoot: "H256",
+        executed: "u32"
+      },
+      UploadGrantCreated: {
+        owner: "AccountId32",
+        bucketId: "H256",
+        grantee: "AccountId32",
+        sizeBudget: "u64",
+        expiresAt: "u32"
+      },
+      UploadGrantRevoked: {
+        owner: "AccountId32",
+        bucketId: "H256",
+        grantee: "AccountId32"
+      },
+      UploadGrantUsed: {
+        _alias: {
+          size_: "size"
+        },
+        owner: "AccountId32",
+        bucketId: "H256",
+        grantee: "AccountId32",
+        fileKey: "H256",
+        size_: "u64",
+        remainingBudget: "u64"
+      },
+      BucketTransferOffered: {
+        bucketId: "H256",
+        mspId: "H256",
+        newMspId: "H256",
+        owner: "AccountId32",
+        expiresAt: "u32"
+      },
+      BucketTransferOfferAccepted: {
+        bucketId: "H256",
+        mspId: "H256",
+        newMspId: "H256"
+      },
+      BucketTransferOfferRejected: {
+        bucketId: "H256",
+        mspId: "H256",
+        newMspId: "H256"
+      },
+      AccessGrantCreated: {
+        owner: "AccountId32",
+        target: "PalletFileSystemAccessGrantTarget",
+        grantee: "AccountId32",
+        expiresAt: "u32"
+      },
+      AccessGrantRevoked: {
+        owner: "AccountId32",
+        target: "PalletFileSystemAccessGrantTarget",
+        grantee: "AccountId32"
+      },
+      AccessGrantExpired: {
+        target: "PalletFileSystemAccessGrantTarget",
+        grantee: "AccountId32"
       }
     }
   },
@@ -1660,6 +1954,12 @@ export default {
         provider: "H256",
         nextChallengeDeadline: "u32"
       },
+      ProviderWarned: {
+        provider: "H256",
+        nextChallengeDeadline: "u32",
+        missedDeadlines: "u32",
+        windowEnd: "u32"
+      },
       NoRecordOfLastSubmittedProof: {
         provider: "H256"
       },
@@ -1676,6 +1976,31 @@ export default {
       },
       ChallengesTickerSet: {
         paused: "bool"
+      },
+      CheckpointMutationsSummary: {
+        provider: "H256",
+        checkpointTick: "u32",
+        removedKeys: "Vec<H256>",
+        newRoot: "H256"
+      },
+      TicksCatchUpBacklogGrew: {
+        backlog: "u32",
+        nextTickToCheck: "u32"
+      },
+      TicksCatchUpBacklogCleared: {
+        challengesTicker: "u32"
+      },
+      RetentionPolicySet: {
+        policy: "PalletProofsDealerRetentionPolicy"
+      },
+      HistoryPruned: {
+        challengeSeedsPrunedUpTo: "u32",
+        validProofSubmittersPrunedUpTo: "u32",
+        caughtUp: "bool"
+      },
+      ChallengePeriodOverrideSet: {
+        providerId: "H256",
+        period: "Option<u32>"
       }
     }
   },
@@ -1813,6 +2138,72 @@ export default {
       UserSolvent: {
         who: "AccountId32"
       },
+      EscrowOpened: {
+        escrowId: "H256",
+        userAccount: "AccountId32",
+        providersTarget: "u32",
+        amountProvided: "u64",
+        ratePerProvider: "u128",
+        duration: "u32",
+        amountHeld: "u128"
+      },
+      EscrowProviderAdded: {
+        escrowId: "H256",
+        providerId: "H256",
+        startTick: "u32",
+        endTick: "u32"
+      },
+      EscrowPaymentReleased: {
+        escrowId: "H256",
+        userAccount: "AccountId32",
+        providerId: "H256",
+        amount: "u128",
+        releasedUntilTick: "u32"
+      },
+      EscrowProviderRemoved: {
+        escrowId: "H256",
+        providerId: "H256",
+        amountRefunded: "u128"
+      },
+      EscrowShortfallRefunded: {
+        escrowId: "H256",
+        userAccount: "AccountId32",
+        missingProviders: "u32",
+        amountRefunded: "u128"
+      },
+      EscrowClosed: {
+        escrowId: "H256",
+        userAccount: "AccountId32",
+        amountRefunded: "u128"
+      },
+      FiatPriceTargetSet: {
+        target: "Option<u128>"
+      },
+      PriceAdjustedToFiatTarget: {
+        previousPrice: "u128",
+        newPrice: "u128",
+        oraclePrice: "u128"
+      },
+      OraclePriceStale: {
+        lastUpdatedAt: "Option<u32>"
+      },
+      AutoTopUpReserveDeposited: {
+        who: "AccountId32",
+        amount: "u128"
+      },
+      AutoTopUpReserveWithdrawn: {
+        who: "AccountId32",
+        amount: "u128"
+      },
+      AutoTopUpThresholdSet: {
+        who: "AccountId32",
+        threshold: "Option<u128>"
+      },
+      AutoTopUpExecuted: {
+        who: "AccountId32",
+        amount: "u128",
+        remainingReserve: "u128"
+      },
       InconsistentTickProcessing: {
         lastProcessedTick: "u32",
         tickToProcess: "u32"
@@ -2119,7 +2510,15 @@ export default {
       "ProviderTopUpTtl",
       "DefaultReplicationTarget",
       "MinSeedPeriod",
-      "StakeToSeedPeriod"
+      "StakeToSeedPeriod",
+      "SlashReferenceDataSize",
+      "SlashEscalationPerMissedDeadline",
+      "MaxSlashEscalationMultiplier",
+      "RequireCapacityAttestation",
+      "MissedDeadlinesAllowance",
+      "MissedDeadlineWarningWindow",
+      "FileDeletionFee",
+      "MinBillableFileSize"
     ]
   },
   /**
@@ -2242,7 +2641,15 @@ export default {
       ProviderTopUpTtl: "u32",
       DefaultReplicationTarget: "u32",
       MinSeedPeriod: "u32",
-      StakeToSeedPeriod: "u128"
+      StakeToSeedPeriod: "u128",
+      SlashReferenceDataSize: "u64",
+      SlashEscalationPerMissedDeadline: "Perbill",
+      MaxSlashEscalationMultiplier: "u32",
+      RequireCapacityAttestation: "bool",
+      MissedDeadlinesAllowance: "u32",
+      MissedDeadlineWarningWindow: "u32",
+      FileDeletionFee: "u128",
+      MinBillableFileSize: "u64"
     }
   },
   /**
@@ -2652,13 +3059,18 @@ export default {
    * Lookup277: pallet_file_system::pallet::HoldReason
    **/
   PalletFileSystemHoldReason: {
-    _enum: ["StorageRequestCreationHold"]
+    _enum: [
+      "StorageRequestCreationHold",
+      "FileDeletionFeeHold",
+      "StorageRequestRenewalHold",
+      "DeletionListDepositHold"
+    ]
   },
   /**
    * Lookup278: pallet_payment_streams::pallet::HoldReason
    **/
   PalletPaymentStreamsHoldReason: {
-    _enum: ["PaymentStreamDeposit"]
+    _enum: ["PaymentStreamDeposit", "EscrowedPayment", "AutoTopUpReserve"]
   },
   /**
    * Lookup281: frame_support::traits::tokens::misc::IdAmount<Id, Balance>
@@ -3452,6 +3864,34 @@ export default {
       top_up_deposit: "Null",
       delete_provider: {
         providerId: "H256"
+      },
+      request_capacity_attestation: {
+        capacity: "u64"
+      },
+      answer_capacity_challenge: {
+        answer: "Vec<PalletStorageProvidersCapacityAttestationPlotLeafProof>"
+      },
+      publish_value_prop_version: {
+        valuePropId: "H256",
+        pricePerGigaUnitOfDataPerBlock: "u128",
+        commitment: "Bytes",
+        bucketDataLimit: "u64"
+      },
+      request_capacity_decrease: {
+        newCapacity: "u64"
+      },
+      confirm_capacity_decrease: "Null",
+      schedule_sign_off: "Null",
+      cancel_scheduled_sign_off: "Null",
+      suspend_provider: {
+        providerId: "H256"
+      },
+      resume_provider: {
+        providerId: "H256"
+      },
+      draw_capacity_plot_seed: "Null",
+      commit_capacity_plot: {
+        plotRoot: "H256"
       }
     }
   },
@@ -3464,7 +3904,8 @@ export default {
         mspId: "Option<H256>",
         name: "Bytes",
         private: "bool",
-        valuePropId: "Option<H256>"
+        valuePropId: "Option<H256>",
+        encryptionMetadata: "Option<Bytes>"
       },
       request_move_bucket: {
         bucketId: "H256",
@@ -3494,7 +3935,8 @@ export default {
         size_: "u64",
         mspId: "Option<H256>",
         peerIds: "Vec<Bytes>",
-        replicationTarget: "Option<u32>"
+        replicationTarget: "Option<u32>",
+        autoRenew: "bool"
       },
       revoke_storage_request: {
         fileKey: "H256"
@@ -3562,6 +4004,156 @@ export default {
       set_global_parameters: {
         newMaxReplicationTarget: "Option<u32>",
         tickRangeToMaximumThreshold: "Option<u32>"
+      },
+      set_bucket_default_replication_target: {
+        bucketId: "H256",
+        replicationTarget: "Option<u32>"
+      },
+      set_msp_eligibility_mode: {
+        mode: "PalletFileSystemMspEligibilityListMode"
+      },
+      update_msp_eligibility_list: {
+        mspId: "H256",
+        listed: "bool"
+      },
+      issue_escrowed_storage_request: {
+        _alias: {
+          size_: "size"
+        },
+        bucketId: "H256",
+        location: "Bytes",
+        fingerprint: "H256",
+        size_: "u64",
+        mspId: "Option<H256>",
+        peerIds: "Vec<Bytes>",
+        replicationTarget: "Option<u32>",
+        escrowTicks: "u32"
+      },
+      resolve_deposit_transfer_retry: {
+        retryId: "u32",
+        resolution: "PalletFileSystemDepositTransferResolution"
+      },
+      migrate_bucket_value_prop: {
+        bucketId: "H256"
+      },
+      change_storage_request_replication_target: {
+        fileKey: "H256",
+        replicationTarget: "u32"
+      },
+      freeze_bucket: {
+        bucketId: "H256"
+      },
+      unfreeze_bucket: {
+        bucketId: "H256"
+      },
+      delete_files: {
+        bucketId: "H256",
+        files: "Vec<PalletFileSystemFileDeletionRequestItem>"
+      },
+      request_move_file: {
+        _alias: {
+          size_: "size"
+        },
+        bucketId: "H256",
+        fileKey: "H256",
+        location: "Bytes",
+        size_: "u64",
+        fingerprint: "H256",
+        newLocation: "Bytes"
+      },
+      msp_confirm_file_move: {
+        fileKey: "H256",
+        forestProof: "SpTrieStorageProofCompactProof"
+      },
+      bsp_confirm_file_move: {
+        fileKey: "H256",
+        forestProof: "SpTrieStorageProofCompactProof"
+      },
+      set_notification_preferences: {
+        preferences: "Option<Bytes>"
+      },
+      grant_file_access: {
+        _alias: {
+          size_: "size"
+        },
+        bucketId: "H256",
+        location: "Bytes",
+        size_: "u64",
+        fingerprint: "H256",
+        account: "AccountId32"
+      },
+      revoke_file_access: {
+        _alias: {
+          size_: "size"
+        },
+        bucketId: "H256",
+        location: "Bytes",
+        size_: "u64",
+        fingerprint: "H256",
+        account: "AccountId32"
+      },
+      register_deletion_list: {
+        listRoot: "H256",
+        length: "u32"
+      },
+      execute_deletion_list_item: {
+        _alias: {
+          size_: "size"
+        },
+        owner: "AccountId32",
+        listRoot: "H256",
+        bucketId: "H256",
+        location: "Bytes",
+        size_: "u64",
+        fingerprint: "H256",
+        merkleProof: "Vec<H256>",
+        maybeInclusionForestProof: "Option<SpTrieStorageProofCompactProof>"
+      },
+      cancel_deletion_list: {
+        listRoot: "H256"
+      },
+      create_upload_grant: {
+        bucketId: "H256",
+        grantee: "AccountId32",
+        sizeBudget: "u64",
+        expiresAt: "u32"
+      },
+      revoke_upload_grant: {
+        bucketId: "H256",
+        grantee: "AccountId32"
+      },
+      issue_storage_request_with_grant: {
+        _alias: {
+          size_: "size"
+        },
+        bucketId: "H256",
+        location: "Bytes",
+        fingerprint: "H256",
+        size_: "u64",
+        mspId: "Option<H256>",
+        peerIds: "Vec<Bytes>",
+        replicationTarget: "Option<u32>"
+      },
+      msp_offer_bucket_transfer: {
+        bucketId: "H256",
+        newMspId: "H256"
+      },
+      respond_bucket_transfer_offer: {
+        bucketId: "H256",
+        response: "PalletFileSystemBucketMoveRequestResponse"
+      },
+      set_bucket_encryption_metadata: {
+        bucketId: "H256",
+        encryptionMetadata: "Option<Bytes>"
+      },
+      create_access_grant: {
+        target: "PalletFileSystemAccessGrantTarget",
+        grantee: "AccountId32",
+        expiresAt: "u32"
+      },
+      revoke_access_grant: {
+        target: "PalletFileSystemAccessGrantTarget",
+        grantee: "AccountId32"
       }
     }
   },
@@ -3617,6 +4209,22 @@ export default {
       },
       set_paused: {
         paused: "bool"
+      },
+      submit_proof_unsigned: {
+        proof: "PalletProofsDealerProof",
+        provider: "H256",
+        signature: "SpRuntimeMultiSignature"
+      },
+      set_provider_challenge_period_override: {
+        providerId: "H256",
+        period: "Option<u32>"
+      },
+      set_retention_policy: {
+        policy: "Option<PalletProofsDealerRetentionPolicy>"
+      },
+      submit_proofs: {
+        proofs: "Vec<(u32,PalletProofsDealerProof)>",
+        provider: "Option<H256>"
       }
     }
   },
@@ -3668,7 +4276,22 @@ export default {
       pay_outstanding_debt: {
         providers: "Vec<H256>"
       },
-      clear_insolvent_flag: "Null"
+      clear_insolvent_flag: "Null",
+      release_escrowed_payment: {
+        escrowId: "H256"
+      },
+      set_fiat_price_target: {
+        target: "Option<u128>"
+      },
+      deposit_auto_top_up_reserve: {
+        amount: "u128"
+      },
+      withdraw_auto_top_up_reserve: {
+        amount: "u128"
+      },
+      set_auto_top_up_threshold: {
+        threshold: "Option<u128>"
+      }
     }
   },
   /**
@@ -4076,7 +4699,23 @@ export default {
       MinSeedPeriod:
         "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinSeedPeriod,Option<u32>)",
       StakeToSeedPeriod:
-        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToSeedPeriod,Option<u128>)"
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToSeedPeriod,Option<u128>)",
+      SlashReferenceDataSize:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize,Option<u64>)",
+      SlashEscalationPerMissedDeadline:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline,Option<Perbill>)",
+      MaxSlashEscalationMultiplier:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier,Option<u32>)",
+      RequireCapacityAttestation:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation,Option<bool>)",
+      MissedDeadlinesAllowance:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance,Option<u32>)",
+      MissedDeadlineWarningWindow:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow,Option<u32>)",
+      FileDeletionFee:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee,Option<u128>)",
+      MinBillableFileSize:
+        "(StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize,Option<u64>)"
     }
   },
   /**
@@ -4374,6 +5013,8 @@ export default {
       "CannotHoldDeposit",
       "StorageStillInUse",
       "SignOffPeriodNotPassed",
+      "SignOffAlreadyScheduled",
+      "SignOffNotScheduled",
       "RandomnessNotValidYet",
       "SignUpRequestExpired",
       "NewCapacityLessThanUsedStorage",
@@ -4382,6 +5023,12 @@ export default {
       "NotEnoughTimePassed",
       "NewUsedCapacityExceedsStorageCapacity",
       "DepositTooLow",
+      "NewCapacityNotLessThanCurrentCapacity",
+      "CapacityDecreaseAlreadyRequested",
+      "CapacityDecreaseNotRequested",
+      "CapacityDecreaseGracePeriodNotPassed",
+      "ProviderAlreadySuspended",
+      "ProviderNotSuspended",
       "NotRegistered",
       "NoUserId",
       "NoBucketId",
@@ -4400,6 +5047,8 @@ export default {
       "ValuePropositionNotFound",
       "ValuePropositionAlreadyExists",
       "ValuePropositionNotAvailable",
+      "ValuePropositionSuperseded",
+      "BucketAlreadyOnLatestValueProposition",
       "FixedRatePaymentStreamNotFound",
       "MspAlreadyAssignedToBucket",
       "BucketSizeExceedsLimit",
@@ -4409,7 +5058,14 @@ export default {
       "DeleteProviderConditionsNotMet",
       "InvalidEncodedFileMetadata",
       "InvalidEncodedAccountId",
-      "PaymentStreamNotFound"
+      "PaymentStreamNotFound",
+      "CapacityNotAttested",
+      "CapacityAttestationNotFound",
+      "CapacityAttestationExpired",
+      "CapacityPlotSeedAlreadyDrawn",
+      "CapacityPlotSeedNotDrawn",
+      "CapacityPlotNotCommitted",
+      "InvalidCapacityAttestationProof"
     ]
   },
   /**
@@ -4472,6 +5128,8 @@ export default {
       "ReplicationTargetCannotBeZero",
       "ReplicationTargetExceedsMaximum",
       "MaxReplicationTargetSmallerThanDefault",
+      "MspOnlyStorageRequestWithoutMsp",
+      "StorageRequestIsMspOnly",
       "NotABsp",
       "NotAMsp",
       "NotASp",
@@ -4507,6 +5165,12 @@ export default {
       "NotFileOwner",
       "FileKeyAlreadyPendingDeletion",
       "MaxUserPendingDeletionRequestsReached",
+      "EmptyFileDeletionBatch",
+      "FileMoveToSameLocation",
+      "FileMoveAlreadyRequested",
+      "FileMoveRequestNotFound",
+      "FileNotMoved",
+      "FileHasEscrowedPayment",
       "MspNotStoringBucket",
       "FileKeyNotPendingDeletion",
       "FileSizeCannotBeZero",
@@ -4523,6 +5187,7 @@ export default {
       "MspAlreadyStoringBucket",
       "MoveBucketRequestNotFound",
       "BucketIsBeingMoved",
+      "MspNotEligible",
       "BspAlreadyDataServer",
       "BspDataServersExceeded",
       "FileMetadataProcessingQueueFull",
@@ -4538,7 +5203,34 @@ export default {
       "NoFileKeysToConfirm",
       "RootNotUpdated",
       "NoPrivacyChange",
-      "OperationNotAllowedForInsolventProvider"
+      "OperationNotAllowedForInsolventProvider",
+      "DeferredPriorityChallengesQueueFull",
+      "DepositTransferRetryNotFound",
+      "ReplicationTargetUnchanged",
+      "ReplicationTargetBelowConfirmedBsps",
+      "BucketIsFrozen",
+      "BucketAlreadyFrozen",
+      "BucketNotFrozen",
+      "FileAccessListFull",
+      "FileAccessAlreadyGranted",
+      "FileAccessNotGranted",
+      "OperationNotAllowedForSuspendedProvider",
+      "OperationNotAllowedForExitingProvider",
+      "DeletionListEmpty",
+      "DeletionListTooLong",
+      "DeletionListAlreadyRegistered",
+      "DeletionListNotFound",
+      "InvalidDeletionListProof",
+      "UploadGrantExpirationInThePast",
+      "UploadGrantBudgetCannotBeZero",
+      "UploadGrantNotFound",
+      "UploadGrantExpired",
+      "UploadGrantBudgetExceeded",
+      "BucketTransferOfferNotFound",
+      "BucketTransferOfferExpired",
+      "AccessGrantExpirationInThePast",
+      "AccessGrantsFull",
+      "AccessGrantNotFound"
     ]
   },
   /**
@@ -4576,7 +5268,15 @@ export default {
       "FailedToApplyDelta",
       "UnexpectedNumberOfRemoveMutations",
       "FailedToUpdateProviderAfterKeyRemoval",
-      "TooManyValidProofSubmitters"
+      "TooManyValidProofSubmitters",
+      "ProofTooLarge",
+      "ProviderNotActive",
+      "InvalidProofSignature",
+      "ChallengePeriodOverrideTooShort",
+      "ChallengePeriodOverrideTooLong",
+      "RetentionTooShort",
+      "EmptyProofBatch",
+      "UnexpectedChallengesTick"
     ]
   },
   /**
@@ -4626,7 +5326,20 @@ export default {
       "UserNotFlaggedAsWithoutFunds",
       "CooldownPeriodNotPassed",
       "UserHasRemainingDebt",
-      "ProviderInsolvent"
+      "ProviderInsolvent",
+      "EscrowAlreadyExists",
+      "EscrowNotFound",
+      "EscrowParamsCantBeZero",
+      "CannotHoldEscrow",
+      "EscrowProvidersTargetReached",
+      "EscrowReplicationAlreadySettled",
+      "ProviderAlreadyInEscrow",
+      "ProviderNotInEscrow",
+      "FiatPriceTargetCantBeZero",
+      "AutoTopUpAmountCantBeZero",
+      "CannotHoldAutoTopUpReserve",
+      "InsufficientAutoTopUpReserve",
+      "AutoTopUpThresholdCantBeZero"
     ]
   },
   /**
@@ -4824,5 +5537,288 @@ export default {
   /**
    * Lookup519: storage_hub_runtime::Runtime
    **/
-  StorageHubRuntimeRuntime: "Null"
+  StorageHubRuntimeRuntime: "Null",
+  /**
+   * Lookup520: pallet_file_system::types::MspEligibilityListMode
+   **/
+  PalletFileSystemMspEligibilityListMode: {
+    _enum: ["Denylist", "Allowlist"]
+  },
+  /**
+   * Lookup521: pallet_file_system::types::DepositTransferResolution
+   **/
+  PalletFileSystemDepositTransferResolution: {
+    _enum: ["Retry", "WriteOff"]
+  },
+  /**
+   * Lookup522: pallet_file_system::types::FileDeletionRequestItem<T>
+   **/
+  PalletFileSystemFileDeletionRequestItem: {
+    _alias: {
+      size_: "size"
+    },
+    fileKey: "H256",
+    location: "Bytes",
+    size_: "u64",
+    fingerprint: "H256"
+  },
+  /**
+   * Lookup523: pallet_file_system::types::AccessGrantTarget<T>
+   **/
+  PalletFileSystemAccessGrantTarget: {
+    _enum: {
+      Bucket: "H256",
+      File: {
+        bucketId: "H256",
+        fileKey: "H256"
+      }
+    }
+  },
+  /**
+   * Lookup524: shp_traits::ProviderStatusChange
+   **/
+  ShpTraitsProviderStatusChange: {
+    _enum: ["Insolvent", "Deregistered"]
+  },
+  /**
+   * Lookup525: pallet_file_system::types::DepositTransfer<T>
+   **/
+  PalletFileSystemDepositTransfer: {
+    _enum: {
+      StorageRequestCreationDeposit: {
+        owner: "AccountId32",
+        amount: "u128"
+      },
+      StorageRequestEscrow: {
+        fileKey: "H256"
+      },
+      StorageRequestRevocationCompensation: {
+        owner: "AccountId32",
+        bsp: "AccountId32",
+        amount: "u128"
+      },
+      StorageRequestRenewalDeposit: {
+        owner: "AccountId32",
+        amount: "u128"
+      },
+      FileDeletionFee: {
+        payer: "AccountId32",
+        recipient: "AccountId32",
+        amount: "u128"
+      },
+      DeletionListDeposit: {
+        owner: "AccountId32",
+        amount: "u128"
+      }
+    }
+  },
+  /**
+   * Lookup526: pallet_file_system::types::FileMove<T>
+   **/
+  PalletFileSystemFileMove: {
+    _alias: {
+      size_: "size"
+    },
+    owner: "AccountId32",
+    bucketId: "H256",
+    newFileKey: "H256",
+    newLocation: "Bytes",
+    size_: "u64",
+    fingerprint: "H256"
+  },
+  /**
+   * Lookup527: pallet_file_system::types::PriorityChallengeUsage<T>
+   **/
+  PalletFileSystemPriorityChallengeUsage: {
+    periodStart: "u32",
+    count: "u32"
+  },
+  /**
+   * Lookup528: pallet_file_system::types::ProviderStatusChangeProgress<T>
+   **/
+  PalletFileSystemProviderStatusChangeProgress: {
+    providerId: "H256",
+    status: "ShpTraitsProviderStatusChange",
+    moveBucketRequestsProcessed: "bool",
+    lastCheckedStorageRequest: "Option<H256>"
+  },
+  /**
+   * Lookup529: pallet_file_system::types::DepositTransferRetry<T>
+   **/
+  PalletFileSystemDepositTransferRetry: {
+    transfer: "PalletFileSystemDepositTransfer",
+    attempts: "u32",
+    nextAttemptAt: "u32"
+  },
+  /**
+   * Lookup530: pallet_file_system::types::DeletionList<T>
+   **/
+  PalletFileSystemDeletionList: {
+    length: "u32",
+    nextIndex: "u32",
+    itemDeposit: "u128"
+  },
+  /**
+   * Lookup531: pallet_file_system::types::UploadGrant<T>
+   **/
+  PalletFileSystemUploadGrant: {
+    owner: "AccountId32",
+    remainingBudget: "u64",
+    expiresAt: "u32"
+  },
+  /**
+   * Lookup532: pallet_file_system::types::BucketTransferOffer<T>
+   **/
+  PalletFileSystemBucketTransferOffer: {
+    mspId: "H256",
+    newMspId: "H256",
+    expiresAt: "u32"
+  },
+  /**
+   * Lookup533: pallet_file_system::types::AccessGrant<T>
+   **/
+  PalletFileSystemAccessGrant: {
+    grantee: "AccountId32",
+    expiresAt: "u32"
+  },
+  /**
+   * Lookup534: pallet_storage_providers::capacity_attestation::PlotLeafProof
+   **/
+  PalletStorageProvidersCapacityAttestationPlotLeafProof: {
+    leaf: "H256",
+    siblings: "Vec<H256>",
+    previousLeaf: "H256",
+    previousSiblings: "Vec<H256>"
+  },
+  /**
+   * Lookup535: shp_traits::ProviderStatus
+   **/
+  ShpTraitsProviderStatus: {
+    _enum: ["Active", "Insolvent", "Exiting", "Suspended"]
+  },
+  /**
+   * Lookup536: pallet_storage_providers::types::ValuePropositionVersion<T>
+   **/
+  PalletStorageProvidersValuePropositionVersion: {
+    lineage: "H256",
+    version: "u32"
+  },
+  /**
+   * Lookup537: pallet_storage_providers::types::CapacityUtilizationSample<T>
+   **/
+  PalletStorageProvidersCapacityUtilizationSample: {
+    tick: "u32",
+    totalCapacity: "u64",
+    usedCapacity: "u64",
+    pricePerGigaUnitOfDataPerTick: "u128"
+  },
+  /**
+   * Lookup538: pallet_storage_providers::types::PendingCapacityAttestation<T>
+   **/
+  PalletStorageProvidersPendingCapacityAttestation: {
+    capacity: "u64",
+    requestedAt: "u32",
+    stage: "PalletStorageProvidersCapacityAttestationStage"
+  },
+  /**
+   * Lookup539: pallet_storage_providers::types::PendingCapacityDecrease<T>
+   **/
+  PalletStorageProvidersPendingCapacityDecrease: {
+    newCapacity: "u64",
+    confirmableAt: "u32"
+  },
+  /**
+   * Lookup540: pallet_payment_streams::types::PaymentEscrow<T>
+   **/
+  PalletPaymentStreamsPaymentEscrow: {
+    userAccount: "AccountId32",
+    amountProvided: "u64",
+    ratePerProvider: "u128",
+    duration: "u32",
+    providersTarget: "u32",
+    providersCount: "u32",
+    amountHeld: "u128",
+    replicationSettled: "bool"
+  },
+  /**
+   * Lookup541: pallet_payment_streams::types::EscrowedProvider<T>
+   **/
+  PalletPaymentStreamsEscrowedProvider: {
+    startTick: "u32",
+    endTick: "u32",
+    releasedUntilTick: "u32"
+  },
+  /**
+   * Lookup542: pallet_proofs_dealer::types::RetentionPolicy<T>
+   **/
+  PalletProofsDealerRetentionPolicy: {
+    challengeSeeds: "u32",
+    validProofSubmitters: "u32"
+  },
+  /**
+   * Lookup543: pallet_proofs_dealer::types::SlashableProviderRecord
+   **/
+  PalletProofsDealerSlashableProviderRecord: {
+    accruedFailedProofSubmissions: "u32"
+  },
+  /**
+   * Lookup544: pallet_proofs_dealer::types::ProviderWarningRecord<T>
+   **/
+  PalletProofsDealerProviderWarningRecord: {
+    windowStart: "u32",
+    missedDeadlines: "u32"
+  },
+  /**
+   * Lookup545: pallet_storage_providers::types::CapacityAttestationStage<T>
+   **/
+  PalletStorageProvidersCapacityAttestationStage: {
+    _enum: {
+      Requested: "Null",
+      SeedDrawn: {
+        seed: "H256",
+        deadline: "u32"
+      },
+      Committed: {
+        seed: "H256",
+        plotRoot: "H256",
+        committedAt: "u32",
+        deadline: "u32"
+      }
+    }
+  },
+  /**
+   * Lookup546: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::SlashReferenceDataSize
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize: "Null",
+  /**
+   * Lookup547: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::SlashEscalationPerMissedDeadline
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline:
+    "Null",
+  /**
+   * Lookup548: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::MaxSlashEscalationMultiplier
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier:
+    "Null",
+  /**
+   * Lookup549: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::RequireCapacityAttestation
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation: "Null",
+  /**
+   * Lookup550: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::MissedDeadlinesAllowance
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance: "Null",
+  /**
+   * Lookup551: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::MissedDeadlineWarningWindow
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow:
+    "Null",
+  /**
+   * Lookup552: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::FileDeletionFee
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee: "Null",
+  /**
+   * Lookup553: storage_hub_runtime::configs::runtime_params::dynamic_params::runtime_config::MinBillableFileSize
+   **/
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize: "Null"
 };
@@ -69,22 +69,35 @@ import type {
   PalletCollatorSelectionCandidateInfo,
   PalletCollatorSelectionError,
   PalletCollatorSelectionEvent,
+  PalletFileSystemAccessGrant,
+  PalletFileSystemAccessGrantTarget,
   PalletFileSystemBucketMoveRequestResponse,
+  PalletFileSystemBucketTransferOffer,
   PalletFileSystemCall,
+  PalletFileSystemDeletionList,
+  PalletFileSystemDepositTransfer,
+  PalletFileSystemDepositTransferResolution,
+  PalletFileSystemDepositTransferRetry,
   PalletFileSystemEitherAccountIdOrMspId,
   PalletFileSystemError,
   PalletFileSystemEvent,
+  PalletFileSystemFileDeletionRequestItem,
   PalletFileSystemFileKeyWithProof,
+  PalletFileSystemFileMove,
   PalletFileSystemHoldReason,
   PalletFileSystemMoveBucketRequestMetadata,
+  PalletFileSystemMspEligibilityListMode,
   PalletFileSystemPendingFileDeletionRequest,
   PalletFileSystemPendingStopStoringRequest,
+  PalletFileSystemPriorityChallengeUsage,
+  PalletFileSystemProviderStatusChangeProgress,
   PalletFileSystemRejectedStorageRequest,
   PalletFileSystemRejectedStorageRequestReason,
   PalletFileSystemStorageRequestBspsMetadata,
   PalletFileSystemStorageRequestMetadata,
   PalletFileSystemStorageRequestMspAcceptedFileKeys,
   PalletFileSystemStorageRequestMspBucketResponse,
+  PalletFileSystemUploadGrant,
   PalletMessageQueueBookState,
   PalletMessageQueueCall,
   PalletMessageQueueError,
@@ -125,9 +138,11 @@ import type {
   PalletPaymentStreamsCall,
   PalletPaymentStreamsDynamicRatePaymentStream,
   PalletPaymentStreamsError,
+  PalletPaymentStreamsEscrowedProvider,
   PalletPaymentStreamsEvent,
   PalletPaymentStreamsFixedRatePaymentStream,
   PalletPaymentStreamsHoldReason,
+  PalletPaymentStreamsPaymentEscrow,
   PalletPaymentStreamsProviderLastChargeableInfo,
   PalletProofsDealerCall,
   PalletProofsDealerError,
@@ -135,6 +150,9 @@ import type {
   PalletProofsDealerKeyProof,
   PalletProofsDealerProof,
   PalletProofsDealerProofSubmissionRecord,
+  PalletProofsDealerProviderWarningRecord,
+  PalletProofsDealerRetentionPolicy,
+  PalletProofsDealerSlashableProviderRecord,
   PalletRandomnessCall,
   PalletRandomnessEvent,
   PalletSessionCall,
@@ -143,16 +161,22 @@ import type {
   PalletStorageProvidersBackupStorageProvider,
   PalletStorageProvidersBucket,
   PalletStorageProvidersCall,
+  PalletStorageProvidersCapacityAttestationPlotLeafProof,
+  PalletStorageProvidersCapacityAttestationStage,
+  PalletStorageProvidersCapacityUtilizationSample,
   PalletStorageProvidersError,
   PalletStorageProvidersEvent,
   PalletStorageProvidersHoldReason,
   PalletStorageProvidersMainStorageProvider,
   PalletStorageProvidersMainStorageProviderSignUpRequest,
+  PalletStorageProvidersPendingCapacityAttestation,
+  PalletStorageProvidersPendingCapacityDecrease,
   PalletStorageProvidersSignUpRequest,
   PalletStorageProvidersSignUpRequestSpParams,
   PalletStorageProvidersStorageProviderId,
   PalletStorageProvidersTopUpMetadata,
   PalletStorageProvidersValueProposition,
+  PalletStorageProvidersValuePropositionVersion,
   PalletStorageProvidersValuePropositionWithId,
   PalletSudoCall,
   PalletSudoError,
@@ -179,6 +203,8 @@ import type {
   ShpFileKeyVerifierFileKeyProof,
   ShpFileMetadataFileMetadata,
   ShpFileMetadataFingerprint,
+  ShpTraitsProviderStatus,
+  ShpTraitsProviderStatusChange,
   ShpTraitsTrieAddMutation,
   ShpTraitsTrieMutation,
   ShpTraitsTrieRemoveMutation,
@@ -222,20 +248,28 @@ import type {
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigCheckpointChallengePeriod,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigDecayRate,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigDefaultReplicationTarget,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigIdealUtilisationRate,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigLowerExponentFactor,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxPrice,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaximumTreasuryCut,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinChallengePeriod,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinPrice,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinSeedPeriod,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinimumTreasuryCut,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMostlyStablePrice,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParameters,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParametersKey,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParametersValue,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigProviderTopUpTtl,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashAmountPerMaxFileSize,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline,
+  StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToChallengePeriod,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToSeedPeriod,
   StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSystemUtilisationLowerThresholdPercentage,
@@ -363,22 +397,35 @@ declare module "@polkadot/types/types/registry" {
     PalletCollatorSelectionCandidateInfo: PalletCollatorSelectionCandidateInfo;
     PalletCollatorSelectionError: PalletCollatorSelectionError;
     PalletCollatorSelectionEvent: PalletCollatorSelectionEvent;
+    PalletFileSystemAccessGrant: PalletFileSystemAccessGrant;
+    PalletFileSystemAccessGrantTarget: PalletFileSystemAccessGrantTarget;
     PalletFileSystemBucketMoveRequestResponse: PalletFileSystemBucketMoveRequestResponse;
+    PalletFileSystemBucketTransferOffer: PalletFileSystemBucketTransferOffer;
     PalletFileSystemCall: PalletFileSystemCall;
+    PalletFileSystemDeletionList: PalletFileSystemDeletionList;
+    PalletFileSystemDepositTransfer: PalletFileSystemDepositTransfer;
+    PalletFileSystemDepositTransferResolution: PalletFileSystemDepositTransferResolution;
+    PalletFileSystemDepositTransferRetry: PalletFileSystemDepositTransferRetry;
     PalletFileSystemEitherAccountIdOrMspId: PalletFileSystemEitherAccountIdOrMspId;
     PalletFileSystemError: PalletFileSystemError;
     PalletFileSystemEvent: PalletFileSystemEvent;
+    PalletFileSystemFileDeletionRequestItem: PalletFileSystemFileDeletionRequestItem;
     PalletFileSystemFileKeyWithProof: PalletFileSystemFileKeyWithProof;
+    PalletFileSystemFileMove: PalletFileSystemFileMove;
     PalletFileSystemHoldReason: PalletFileSystemHoldReason;
     PalletFileSystemMoveBucketRequestMetadata: PalletFileSystemMoveBucketRequestMetadata;
+    PalletFileSystemMspEligibilityListMode: PalletFileSystemMspEligibilityListMode;
     PalletFileSystemPendingFileDeletionRequest: PalletFileSystemPendingFileDeletionRequest;
     PalletFileSystemPendingStopStoringRequest: PalletFileSystemPendingStopStoringRequest;
+    PalletFileSystemPriorityChallengeUsage: PalletFileSystemPriorityChallengeUsage;
+    PalletFileSystemProviderStatusChangeProgress: PalletFileSystemProviderStatusChangeProgress;
     PalletFileSystemRejectedStorageRequest: PalletFileSystemRejectedStorageRequest;
     PalletFileSystemRejectedStorageRequestReason: PalletFileSystemRejectedStorageRequestReason;
     PalletFileSystemStorageRequestBspsMetadata: PalletFileSystemStorageRequestBspsMetadata;
     PalletFileSystemStorageRequestMetadata: PalletFileSystemStorageRequestMetadata;
     PalletFileSystemStorageRequestMspAcceptedFileKeys: PalletFileSystemStorageRequestMspAcceptedFileKeys;
     PalletFileSystemStorageRequestMspBucketResponse: PalletFileSystemStorageRequestMspBucketResponse;
+    PalletFileSystemUploadGrant: PalletFileSystemUploadGrant;
     PalletMessageQueueBookState: PalletMessageQueueBookState;
     PalletMessageQueueCall: PalletMessageQueueCall;
     PalletMessageQueueError: PalletMessageQueueError;
@@ -419,9 +466,11 @@ declare module "@polkadot/types/types/registry" {
     PalletPaymentStreamsCall: PalletPaymentStreamsCall;
     PalletPaymentStreamsDynamicRatePaymentStream: PalletPaymentStreamsDynamicRatePaymentStream;
     PalletPaymentStreamsError: PalletPaymentStreamsError;
+    PalletPaymentStreamsEscrowedProvider: PalletPaymentStreamsEscrowedProvider;
     PalletPaymentStreamsEvent: PalletPaymentStreamsEvent;
     PalletPaymentStreamsFixedRatePaymentStream: PalletPaymentStreamsFixedRatePaymentStream;
     PalletPaymentStreamsHoldReason: PalletPaymentStreamsHoldReason;
+    PalletPaymentStreamsPaymentEscrow: PalletPaymentStreamsPaymentEscrow;
     PalletPaymentStreamsProviderLastChargeableInfo: PalletPaymentStreamsProviderLastChargeableInfo;
     PalletProofsDealerCall: PalletProofsDealerCall;
     PalletProofsDealerError: PalletProofsDealerError;
@@ -429,6 +478,9 @@ declare module "@polkadot/types/types/registry" {
     PalletProofsDealerKeyProof: PalletProofsDealerKeyProof;
     PalletProofsDealerProof: PalletProofsDealerProof;
     PalletProofsDealerProofSubmissionRecord: PalletProofsDealerProofSubmissionRecord;
+    PalletProofsDealerProviderWarningRecord: PalletProofsDealerProviderWarningRecord;
+    PalletProofsDealerRetentionPolicy: PalletProofsDealerRetentionPolicy;
+    PalletProofsDealerSlashableProviderRecord: PalletProofsDealerSlashableProviderRecord;
     PalletRandomnessCall: PalletRandomnessCall;
     PalletRandomnessEvent: PalletRandomnessEvent;
     PalletSessionCall: PalletSessionCall;
@@ -437,16 +489,22 @@ declare module "@polkadot/types/types/registry" {
     PalletStorageProvidersBackupStorageProvider: PalletStorageProvidersBackupStorageProvider;
     PalletStorageProvidersBucket: PalletStorageProvidersBucket;
     PalletStorageProvidersCall: PalletStorageProvidersCall;
+    PalletStorageProvidersCapacityAttestationPlotLeafProof: PalletStorageProvidersCapacityAttestationPlotLeafProof;
+    PalletStorageProvidersCapacityAttestationStage: PalletStorageProvidersCapacityAttestationStage;
+    PalletStorageProvidersCapacityUtilizationSample: PalletStorageProvidersCapacityUtilizationSample;
     PalletStorageProvidersError: PalletStorageProvidersError;
     PalletStorageProvidersEvent: PalletStorageProvidersEvent;
     PalletStorageProvidersHoldReason: PalletStorageProvidersHoldReason;
     PalletStorageProvidersMainStorageProvider: PalletStorageProvidersMainStorageProvider;
     PalletStorageProvidersMainStorageProviderSignUpRequest: PalletStorageProvidersMainStorageProviderSignUpRequest;
+    PalletStorageProvidersPendingCapacityAttestation: PalletStorageProvidersPendingCapacityAttestation;
+    PalletStorageProvidersPendingCapacityDecrease: PalletStorageProvidersPendingCapacityDecrease;
     PalletStorageProvidersSignUpRequest: PalletStorageProvidersSignUpRequest;
     PalletStorageProvidersSignUpRequestSpParams: PalletStorageProvidersSignUpRequestSpParams;
     PalletStorageProvidersStorageProviderId: PalletStorageProvidersStorageProviderId;
     PalletStorageProvidersTopUpMetadata: PalletStorageProvidersTopUpMetadata;
     PalletStorageProvidersValueProposition: PalletStorageProvidersValueProposition;
+    PalletStorageProvidersValuePropositionVersion: PalletStorageProvidersValuePropositionVersion;
     PalletStorageProvidersValuePropositionWithId: PalletStorageProvidersValuePropositionWithId;
     PalletSudoCall: PalletSudoCall;
     PalletSudoError: PalletSudoError;
@@ -473,6 +531,8 @@ declare module "@polkadot/types/types/registry" {
     ShpFileKeyVerifierFileKeyProof: ShpFileKeyVerifierFileKeyProof;
     ShpFileMetadataFileMetadata: ShpFileMetadataFileMetadata;
     ShpFileMetadataFingerprint: ShpFileMetadataFingerprint;
+    ShpTraitsProviderStatus: ShpTraitsProviderStatus;
+    ShpTraitsProviderStatusChange: ShpTraitsProviderStatusChange;
     ShpTraitsTrieAddMutation: ShpTraitsTrieAddMutation;
     ShpTraitsTrieMutation: ShpTraitsTrieMutation;
     ShpTraitsTrieRemoveMutation: ShpTraitsTrieRemoveMutation;
@@ -516,20 +576,28 @@ declare module "@polkadot/types/types/registry" {
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigCheckpointChallengePeriod: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigCheckpointChallengePeriod;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigDecayRate: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigDecayRate;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigDefaultReplicationTarget: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigDefaultReplicationTarget;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigIdealUtilisationRate: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigIdealUtilisationRate;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigLowerExponentFactor: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigLowerExponentFactor;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxPrice: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxPrice;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaximumTreasuryCut: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaximumTreasuryCut;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinChallengePeriod: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinChallengePeriod;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinPrice: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinPrice;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinSeedPeriod: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinSeedPeriod;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinimumTreasuryCut: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinimumTreasuryCut;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMostlyStablePrice: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMostlyStablePrice;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParameters: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParameters;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParametersKey: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParametersKey;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParametersValue: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigParametersValue;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigProviderTopUpTtl: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigProviderTopUpTtl;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashAmountPerMaxFileSize: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashAmountPerMaxFileSize;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline;
+    StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToChallengePeriod: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToChallengePeriod;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToSeedPeriod: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigStakeToSeedPeriod;
     StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSystemUtilisationLowerThresholdPercentage: StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSystemUtilisationLowerThresholdPercentage;
@@ -1853,10 +1853,39 @@ declare module "@polkadot/types/lookup" {
       readonly newCapacity: u64;
       readonly nextBlockWhenChangeAllowed: u32;
     } & Struct;
+    readonly isCapacityDecreaseRequested: boolean;
+    readonly asCapacityDecreaseRequested: {
+      readonly who: AccountId32;
+      readonly providerId: PalletStorageProvidersStorageProviderId;
+      readonly currentCapacity: u64;
+      readonly newCapacity: u64;
+      readonly confirmableAt: u32;
+    } & Struct;
+    readonly isCapacityDecreaseCancelled: boolean;
+    readonly asCapacityDecreaseCancelled: {
+      readonly who: AccountId32;
+      readonly providerId: PalletStorageProvidersStorageProviderId;
+      readonly newCapacity: u64;
+      readonly usedCapacity: u64;
+    } & Struct;
+    readonly isProviderStatusChanged: boolean;
+    readonly asProviderStatusChanged: {
+      readonly providerId: H256;
+      readonly status: ShpTraitsProviderStatus;
+    } & Struct;
+    readonly isBspReputationWeightChanged: boolean;
+    readonly asBspReputationWeightChanged: {
+      readonly bspId: H256;
+      readonly oldWeight: u32;
+      readonly newWeight: u32;
+    } & Struct;
     readonly isSlashed: boolean;
     readonly asSlashed: {
       readonly providerId: H256;
       readonly amount: u128;
+      readonly accruedFailedProofSubmissions: u32;
+      readonly consecutiveMissedDeadlines: u32;
+      readonly usedCapacity: u64;
     } & Struct;
     readonly isAwaitingTopUp: boolean;
     readonly asAwaitingTopUp: {
@@ -1899,6 +1928,21 @@ declare module "@polkadot/types/lookup" {
       readonly mspId: H256;
       readonly valuePropId: H256;
     } & Struct;
+    readonly isValuePropVersionPublished: boolean;
+    readonly asValuePropVersionPublished: {
+      readonly mspId: H256;
+      readonly previousValuePropId: H256;
+      readonly valuePropId: H256;
+      readonly valueProp: PalletStorageProvidersValueProposition;
+      readonly version: PalletStorageProvidersValuePropositionVersion;
+    } & Struct;
+    readonly isBucketValuePropMigrated: boolean;
+    readonly asBucketValuePropMigrated: {
+      readonly bucketId: H256;
+      readonly mspId: H256;
+      readonly previousValuePropId: H256;
+      readonly valuePropId: H256;
+    } & Struct;
     readonly isMspDeleted: boolean;
     readonly asMspDeleted: {
       readonly providerId: H256;
@@ -1907,6 +1951,29 @@ declare module "@polkadot/types/lookup" {
     readonly asBspDeleted: {
       readonly providerId: H256;
     } & Struct;
+    readonly isCapacityAttestationRequested: boolean;
+    readonly asCapacityAttestationRequested: {
+      readonly who: AccountId32;
+      readonly capacity: u64;
+    } & Struct;
+    readonly isCapacityPlotSeedDrawn: boolean;
+    readonly asCapacityPlotSeedDrawn: {
+      readonly who: AccountId32;
+      readonly seed: H256;
+      readonly deadline: u32;
+    } & Struct;
+    readonly isCapacityPlotCommitted: boolean;
+    readonly asCapacityPlotCommitted: {
+      readonly who: AccountId32;
+      readonly plotRoot: H256;
+      readonly capacity: u64;
+      readonly deadline: u32;
+    } & Struct;
+    readonly isCapacityAttested: boolean;
+    readonly asCapacityAttested: {
+      readonly who: AccountId32;
+      readonly capacity: u64;
+    } & Struct;
     readonly type:
       | "MspRequestSignUpSuccess"
       | "MspSignUpSuccess"
@@ -1916,6 +1983,10 @@ declare module "@polkadot/types/lookup" {
       | "MspSignOffSuccess"
       | "BspSignOffSuccess"
       | "CapacityChanged"
+      | "CapacityDecreaseRequested"
+      | "CapacityDecreaseCancelled"
+      | "ProviderStatusChanged"
+      | "BspReputationWeightChanged"
       | "Slashed"
       | "AwaitingTopUp"
       | "TopUpFulfilled"
@@ -1925,8 +1996,14 @@ declare module "@polkadot/types/lookup" {
       | "MultiAddressRemoved"
       | "ValuePropAdded"
       | "ValuePropUnavailable"
+      | "ValuePropVersionPublished"
+      | "BucketValuePropMigrated"
       | "MspDeleted"
-      | "BspDeleted";
+      | "BspDeleted"
+      | "CapacityAttestationRequested"
+      | "CapacityPlotSeedDrawn"
+      | "CapacityPlotCommitted"
+      | "CapacityAttested";
   }
 
   /** @name PalletStorageProvidersValuePropositionWithId (128) */
@@ -1990,6 +2067,18 @@ declare module "@polkadot/types/lookup" {
       readonly collectionId: Option<u32>;
       readonly private: bool;
     } & Struct;
+    readonly isBucketDefaultReplicationTargetUpdated: boolean;
+    readonly asBucketDefaultReplicationTargetUpdated: {
+      readonly who: AccountId32;
+      readonly bucketId: H256;
+      readonly replicationTarget: Option<u32>;
+    } & Struct;
+    readonly isBucketEncryptionMetadataUpdated: boolean;
+    readonly asBucketEncryptionMetadataUpdated: {
+      readonly who: AccountId32;
+      readonly bucketId: H256;
+      readonly encryptionMetadata: Option<Bytes>;
+    } & Struct;
     readonly isNewCollectionAndAssociation: boolean;
     readonly asNewCollectionAndAssociation: {
       readonly who: AccountId32;
@@ -2006,6 +2095,11 @@ declare module "@polkadot/types/lookup" {
       readonly size_: u64;
       readonly peerIds: Vec<Bytes>;
     } & Struct;
+    readonly isNewMspOnlyStorageRequest: boolean;
+    readonly asNewMspOnlyStorageRequest: {
+      readonly fileKey: H256;
+      readonly mspId: H256;
+    } & Struct;
     readonly isMspAcceptedStorageRequest: boolean;
     readonly asMspAcceptedStorageRequest: {
       readonly fileKey: H256;
@@ -2036,10 +2130,31 @@ declare module "@polkadot/types/lookup" {
     readonly asStorageRequestExpired: {
       readonly fileKey: H256;
     } & Struct;
+    readonly isStorageRequestRenewed: boolean;
+    readonly asStorageRequestRenewed: {
+      readonly fileKey: H256;
+      readonly bspsRequired: u32;
+    } & Struct;
+    readonly isStorageRequestRenewalFailed: boolean;
+    readonly asStorageRequestRenewalFailed: {
+      readonly fileKey: H256;
+      readonly error: SpRuntimeDispatchError;
+    } & Struct;
+    readonly isStorageRequestExpiringSoon: boolean;
+    readonly asStorageRequestExpiringSoon: {
+      readonly fileKey: H256;
+      readonly expiresAt: u32;
+    } & Struct;
     readonly isStorageRequestRevoked: boolean;
     readonly asStorageRequestRevoked: {
       readonly fileKey: H256;
     } & Struct;
+    readonly isBspCompensatedForRevokedStorageRequest: boolean;
+    readonly asBspCompensatedForRevokedStorageRequest: {
+      readonly fileKey: H256;
+      readonly bspId: H256;
+      readonly amount: u128;
+    } & Struct;
     readonly isStorageRequestRejected: boolean;
     readonly asStorageRequestRejected: {
       readonly fileKey: H256;
@@ -2071,6 +2186,16 @@ declare module "@polkadot/types/lookup" {
       readonly location: Bytes;
       readonly newRoot: H256;
     } & Struct;
+    readonly isPriorityChallengeForFileDeletionDeferred: boolean;
+    readonly asPriorityChallengeForFileDeletionDeferred: {
+      readonly issuer: PalletFileSystemEitherAccountIdOrMspId;
+      readonly fileKey: H256;
+    } & Struct;
+    readonly isDeferredPriorityChallengeForFileDeletionQueued: boolean;
+    readonly asDeferredPriorityChallengeForFileDeletionQueued: {
+      readonly issuer: PalletFileSystemEitherAccountIdOrMspId;
+      readonly fileKey: H256;
+    } & Struct;
     readonly isFailedToQueuePriorityChallenge: boolean;
     readonly asFailedToQueuePriorityChallenge: {
       readonly user: AccountId32;
@@ -2084,6 +2209,56 @@ declare module "@polkadot/types/lookup" {
       readonly mspId: Option<H256>;
       readonly proofOfInclusion: bool;
     } & Struct;
+    readonly isFileDeletionRequestsBatch: boolean;
+    readonly asFileDeletionRequestsBatch: {
+      readonly user: AccountId32;
+      readonly bucketId: H256;
+      readonly mspId: Option<H256>;
+      readonly fileKeys: Vec<H256>;
+    } & Struct;
+    readonly isFileMoveRequested: boolean;
+    readonly asFileMoveRequested: {
+      readonly owner: AccountId32;
+      readonly bucketId: H256;
+      readonly mspId: Option<H256>;
+      readonly fileKey: H256;
+      readonly newFileKey: H256;
+      readonly newLocation: Bytes;
+    } & Struct;
+    readonly isFileMoved: boolean;
+    readonly asFileMoved: {
+      readonly mspId: H256;
+      readonly bucketId: H256;
+      readonly fileKey: H256;
+      readonly newFileKey: H256;
+      readonly newBucketRoot: H256;
+    } & Struct;
+    readonly isBspConfirmedFileMove: boolean;
+    readonly asBspConfirmedFileMove: {
+      readonly bspId: H256;
+      readonly fileKey: H256;
+      readonly newFileKey: H256;
+      readonly newRoot: H256;
+    } & Struct;
+    readonly isNotificationPreferencesSet: boolean;
+    readonly asNotificationPreferencesSet: {
+      readonly who: AccountId32;
+      readonly preferences: Option<Bytes>;
+    } & Struct;
+    readonly isFileAccessGranted: boolean;
+    readonly asFileAccessGranted: {
+      readonly owner: AccountId32;
+      readonly bucketId: H256;
+      readonly fileKey: H256;
+      readonly account: AccountId32;
+    } & Struct;
+    readonly isFileAccessRevoked: boolean;
+    readonly asFileAccessRevoked: {
+      readonly owner: AccountId32;
+      readonly bucketId: H256;
+      readonly fileKey: H256;
+      readonly account: AccountId32;
+    } & Struct;
     readonly isProofSubmittedForPendingFileDeletionRequest: boolean;
     readonly asProofSubmittedForPendingFileDeletionRequest: {
       readonly mspId: H256;
@@ -2126,33 +2301,259 @@ declare module "@polkadot/types/lookup" {
       readonly fileSize: u64;
       readonly error: SpRuntimeDispatchError;
     } & Struct;
+    readonly isMspEligibilityModeSet: boolean;
+    readonly asMspEligibilityModeSet: {
+      readonly mode: PalletFileSystemMspEligibilityListMode;
+    } & Struct;
+    readonly isMspAddedToEligibilityList: boolean;
+    readonly asMspAddedToEligibilityList: {
+      readonly mspId: H256;
+    } & Struct;
+    readonly isMspRemovedFromEligibilityList: boolean;
+    readonly asMspRemovedFromEligibilityList: {
+      readonly mspId: H256;
+    } & Struct;
+    readonly isStorageRequestCancelledByUnavailableMsp: boolean;
+    readonly asStorageRequestCancelledByUnavailableMsp: {
+      readonly fileKey: H256;
+      readonly owner: AccountId32;
+      readonly mspId: H256;
+      readonly status: ShpTraitsProviderStatusChange;
+    } & Struct;
+    readonly isUnavailableBspVolunteerRemoved: boolean;
+    readonly asUnavailableBspVolunteerRemoved: {
+      readonly fileKey: H256;
+      readonly bspId: H256;
+      readonly status: ShpTraitsProviderStatusChange;
+    } & Struct;
+    readonly isMoveBucketRequestCancelledByUnavailableMsp: boolean;
+    readonly asMoveBucketRequestCancelledByUnavailableMsp: {
+      readonly bucketId: H256;
+      readonly mspId: H256;
+      readonly status: ShpTraitsProviderStatusChange;
+    } & Struct;
+    readonly isProviderStatusChangeProcessed: boolean;
+    readonly asProviderStatusChangeProcessed: {
+      readonly providerId: H256;
+      readonly status: ShpTraitsProviderStatusChange;
+    } & Struct;
+    readonly isDepositTransferFailed: boolean;
+    readonly asDepositTransferFailed: {
+      readonly transfer: PalletFileSystemDepositTransfer;
+      readonly retryId: Option<u32>;
+      readonly error: SpRuntimeDispatchError;
+    } & Struct;
+    readonly isDepositTransferRetrySucceeded: boolean;
+    readonly asDepositTransferRetrySucceeded: {
+      readonly retryId: u32;
+      readonly transfer: PalletFileSystemDepositTransfer;
+    } & Struct;
+    readonly isDepositTransferRetryFailed: boolean;
+    readonly asDepositTransferRetryFailed: {
+      readonly retryId: u32;
+      readonly attempts: u32;
+      readonly nextAttemptAt: u32;
+      readonly error: SpRuntimeDispatchError;
+    } & Struct;
+    readonly isDepositTransferAbandoned: boolean;
+    readonly asDepositTransferAbandoned: {
+      readonly retryId: u32;
+      readonly transfer: PalletFileSystemDepositTransfer;
+      readonly error: SpRuntimeDispatchError;
+    } & Struct;
+    readonly isDepositTransferWrittenOff: boolean;
+    readonly asDepositTransferWrittenOff: {
+      readonly retryId: u32;
+      readonly transfer: PalletFileSystemDepositTransfer;
+    } & Struct;
+    readonly isStorageRequestReplicationTargetChanged: boolean;
+    readonly asStorageRequestReplicationTargetChanged: {
+      readonly fileKey: H256;
+      readonly owner: AccountId32;
+      readonly previousReplicationTarget: u32;
+      readonly replicationTarget: u32;
+    } & Struct;
+    readonly isBucketFrozen: boolean;
+    readonly asBucketFrozen: {
+      readonly bucketId: H256;
+      readonly owner: AccountId32;
+      readonly mspId: Option<H256>;
+    } & Struct;
+    readonly isBucketUnfrozen: boolean;
+    readonly asBucketUnfrozen: {
+      readonly bucketId: H256;
+      readonly owner: AccountId32;
+      readonly mspId: Option<H256>;
+    } & Struct;
+    readonly isDeletionListRegistered: boolean;
+    readonly asDeletionListRegistered: {
+      readonly owner: AccountId32;
+      readonly listRoot: H256;
+      readonly length: u32;
+      readonly deposit: u128;
+    } & Struct;
+    readonly isDeletionListFileDeletionRequest: boolean;
+    readonly asDeletionListFileDeletionRequest: {
+      readonly owner: AccountId32;
+      readonly listRoot: H256;
+      readonly index: u32;
+      readonly fileKey: H256;
+      readonly bucketId: H256;
+      readonly mspId: Option<H256>;
+      readonly proofOfInclusion: bool;
+    } & Struct;
+    readonly isDeletionListItemSkipped: boolean;
+    readonly asDeletionListItemSkipped: {
+      readonly owner: AccountId32;
+      readonly listRoot: H256;
+      readonly index: u32;
+      readonly fileKey: H256;
+      readonly error: SpRuntimeDispatchError;
+    } & Struct;
+    readonly isDeletionListCompleted: boolean;
+    readonly asDeletionListCompleted: {
+      readonly owner: AccountId32;
+      readonly listRoot: H256;
+    } & Struct;
+    readonly isDeletionListCancelled: boolean;
+    readonly asDeletionListCancelled: {
+      readonly owner: AccountId32;
+      readonly listRoot: H256;
+      readonly executed: u32;
+    } & Struct;
+    readonly isUploadGrantCreated: boolean;
+    readonly asUploadGrantCreated: {
+      readonly owner: AccountId32;
+      readonly bucketId: H256;
+      readonly grantee: AccountId32;
+      readonly sizeBudget: u64;
+      readonly expiresAt: u32;
+    } & Struct;
+    readonly isUploadGrantRevoked: boolean;
+    readonly asUploadGrantRevoked: {
+      readonly owner: AccountId32;
+      readonly bucketId: H256;
+      readonly grantee: AccountId32;
+    } & Struct;
+    readonly isUploadGrantUsed: boolean;
+    readonly asUploadGrantUsed: {
+      readonly owner: AccountId32;
+      readonly bucketId: H256;
+      readonly grantee: AccountId32;
+      readonly fileKey: H256;
+      readonly size_: u64;
+      readonly remainingBudget: u64;
+    } & Struct;
+    readonly isBucketTransferOffered: boolean;
+    readonly asBucketTransferOffered: {
+      readonly bucketId: H256;
+      readonly mspId: H256;
+      readonly newMspId: H256;
+      readonly owner: AccountId32;
+      readonly expiresAt: u32;
+    } & Struct;
+    readonly isBucketTransferOfferAccepted: boolean;
+    readonly asBucketTransferOfferAccepted: {
+      readonly bucketId: H256;
+      readonly mspId: H256;
+      readonly newMspId: H256;
+    } & Struct;
+    readonly isBucketTransferOfferRejected: boolean;
+    readonly asBucketTransferOfferRejected: {
+      readonly bucketId: H256;
+      readonly mspId: H256;
+      readonly newMspId: H256;
+    } & Struct;
+    readonly isAccessGrantCreated: boolean;
+    readonly asAccessGrantCreated: {
+      readonly owner: AccountId32;
+      readonly target: PalletFileSystemAccessGrantTarget;
+      readonly grantee: AccountId32;
+      readonly expiresAt: u32;
+    } & Struct;
+    readonly isAccessGrantRevoked: boolean;
+    readonly asAccessGrantRevoked: {
+      readonly owner: AccountId32;
+      readonly target: PalletFileSystemAccessGrantTarget;
+      readonly grantee: AccountId32;
+    } & Struct;
+    readonly isAccessGrantExpired: boolean;
+    readonly asAccessGrantExpired: {
+      readonly target: PalletFileSystemAccessGrantTarget;
+      readonly grantee: AccountId32;
+    } & Struct;
     readonly type:
       | "NewBucket"
       | "BucketDeleted"
       | "MoveBucketRequested"
       | "BucketPrivacyUpdated"
+      | "BucketDefaultReplicationTargetUpdated"
+      | "BucketEncryptionMetadataUpdated"
       | "NewCollectionAndAssociation"
       | "NewStorageRequest"
+      | "NewMspOnlyStorageRequest"
       | "MspAcceptedStorageRequest"
       | "AcceptedBspVolunteer"
       | "BspConfirmedStoring"
       | "StorageRequestFulfilled"
       | "StorageRequestExpired"
+      | "StorageRequestRenewed"
+      | "StorageRequestRenewalFailed"
+      | "StorageRequestExpiringSoon"
       | "StorageRequestRevoked"
+      | "BspCompensatedForRevokedStorageRequest"
       | "StorageRequestRejected"
       | "BspRequestedToStopStoring"
       | "BspConfirmStoppedStoring"
       | "PriorityChallengeForFileDeletionQueued"
       | "SpStopStoringInsolventUser"
+      | "PriorityChallengeForFileDeletionDeferred"
+      | "DeferredPriorityChallengeForFileDeletionQueued"
       | "FailedToQueuePriorityChallenge"
       | "FileDeletionRequest"
+      | "FileDeletionRequestsBatch"
+      | "FileMoveRequested"
+      | "FileMoved"
+      | "BspConfirmedFileMove"
+      | "NotificationPreferencesSet"
+      | "FileAccessGranted"
+      | "FileAccessRevoked"
       | "ProofSubmittedForPendingFileDeletionRequest"
       | "BspChallengeCycleInitialised"
       | "MoveBucketRequestExpired"
       | "MoveBucketAccepted"
       | "MoveBucketRejected"
       | "MspStoppedStoringBucket"
-      | "FailedToDecreaseBucketSize";
+      | "FailedToDecreaseBucketSize"
+      | "MspEligibilityModeSet"
+      | "MspAddedToEligibilityList"
+      | "MspRemovedFromEligibilityList"
+      | "StorageRequestCancelledByUnavailableMsp"
+      | "UnavailableBspVolunteerRemoved"
+      | "MoveBucketRequestCancelledByUnavailableMsp"
+      | "ProviderStatusChangeProcessed"
+      | "DepositTransferFailed"
+      | "DepositTransferRetrySucceeded"
+      | "DepositTransferRetryFailed"
+      | "DepositTransferAbandoned"
+      | "DepositTransferWrittenOff"
+      | "StorageRequestReplicationTargetChanged"
+      | "BucketFrozen"
+      | "BucketUnfrozen"
+      | "DeletionListRegistered"
+      | "DeletionListFileDeletionRequest"
+      | "DeletionListItemSkipped"
+      | "DeletionListCompleted"
+      | "DeletionListCancelled"
+      | "UploadGrantCreated"
+      | "UploadGrantRevoked"
+      | "UploadGrantUsed"
+      | "BucketTransferOffered"
+      | "BucketTransferOfferAccepted"
+      | "BucketTransferOfferRejected"
+      | "AccessGrantCreated"
+      | "AccessGrantRevoked"
+      | "AccessGrantExpired";
   }
 
   /** @name PalletFileSystemRejectedStorageRequestReason (139) */
@@ -2207,6 +2608,13 @@ declare module "@polkadot/types/lookup" {
       readonly provider: H256;
       readonly nextChallengeDeadline: u32;
     } & Struct;
+    readonly isProviderWarned: boolean;
+    readonly asProviderWarned: {
+      readonly provider: H256;
+      readonly nextChallengeDeadline: u32;
+      readonly missedDeadlines: u32;
+      readonly windowEnd: u32;
+    } & Struct;
     readonly isNoRecordOfLastSubmittedProof: boolean;
     readonly asNoRecordOfLastSubmittedProof: {
       readonly provider: H256;
@@ -2228,16 +2636,54 @@ declare module "@polkadot/types/lookup" {
     readonly asChallengesTickerSet: {
       readonly paused: bool;
     } & Struct;
+    readonly isCheckpointMutationsSummary: boolean;
+    readonly asCheckpointMutationsSummary: {
+      readonly provider: H256;
+      readonly checkpointTick: u32;
+      readonly removedKeys: Vec<H256>;
+      readonly newRoot: H256;
+    } & Struct;
+    readonly isTicksCatchUpBacklogGrew: boolean;
+    readonly asTicksCatchUpBacklogGrew: {
+      readonly backlog: u32;
+      readonly nextTickToCheck: u32;
+    } & Struct;
+    readonly isTicksCatchUpBacklogCleared: boolean;
+    readonly asTicksCatchUpBacklogCleared: {
+      readonly challengesTicker: u32;
+    } & Struct;
+    readonly isRetentionPolicySet: boolean;
+    readonly asRetentionPolicySet: {
+      readonly policy: PalletProofsDealerRetentionPolicy;
+    } & Struct;
+    readonly isHistoryPruned: boolean;
+    readonly asHistoryPruned: {
+      readonly challengeSeedsPrunedUpTo: u32;
+      readonly validProofSubmittersPrunedUpTo: u32;
+      readonly caughtUp: bool;
+    } & Struct;
+    readonly isChallengePeriodOverrideSet: boolean;
+    readonly asChallengePeriodOverrideSet: {
+      readonly providerId: H256;
+      readonly period: Option<u32>;
+    } & Struct;
     readonly type:
       | "NewChallenge"
       | "ProofAccepted"
       | "NewChallengeSeed"
       | "NewCheckpointChallenge"
       | "SlashableProvider"
+      | "ProviderWarned"
       | "NoRecordOfLastSubmittedProof"
       | "NewChallengeCycleInitialised"
       | "MutationsApplied"
-      | "ChallengesTickerSet";
+      | "ChallengesTickerSet"
+      | "CheckpointMutationsSummary"
+      | "TicksCatchUpBacklogGrew"
+      | "TicksCatchUpBacklogCleared"
+      | "RetentionPolicySet"
+      | "HistoryPruned"
+      | "ChallengePeriodOverrideSet";
   }
 
   /** @name PalletProofsDealerProof (142) */
@@ -2374,6 +2820,85 @@ declare module "@polkadot/types/lookup" {
     readonly asUserSolvent: {
       readonly who: AccountId32;
     } & Struct;
+    readonly isEscrowOpened: boolean;
+    readonly asEscrowOpened: {
+      readonly escrowId: H256;
+      readonly userAccount: AccountId32;
+      readonly providersTarget: u32;
+      readonly amountProvided: u64;
+      readonly ratePerProvider: u128;
+      readonly duration: u32;
+      readonly amountHeld: u128;
+    } & Struct;
+    readonly isEscrowProviderAdded: boolean;
+    readonly asEscrowProviderAdded: {
+      readonly escrowId: H256;
+      readonly providerId: H256;
+      readonly startTick: u32;
+      readonly endTick: u32;
+    } & Struct;
+    readonly isEscrowPaymentReleased: boolean;
+    readonly asEscrowPaymentReleased: {
+      readonly escrowId: H256;
+      readonly userAccount: AccountId32;
+      readonly providerId: H256;
+      readonly amount: u128;
+      readonly releasedUntilTick: u32;
+    } & Struct;
+    readonly isEscrowProviderRemoved: boolean;
+    readonly asEscrowProviderRemoved: {
+      readonly escrowId: H256;
+      readonly providerId: H256;
+      readonly amountRefunded: u128;
+    } & Struct;
+    readonly isEscrowShortfallRefunded: boolean;
+    readonly asEscrowShortfallRefunded: {
+      readonly escrowId: H256;
+      readonly userAccount: AccountId32;
+      readonly missingProviders: u32;
+      readonly amountRefunded: u128;
+    } & Struct;
+    readonly isEscrowClosed: boolean;
+    readonly asEscrowClosed: {
+      readonly escrowId: H256;
+      readonly userAccount: AccountId32;
+      readonly amountRefunded: u128;
+    } & Struct;
+    readonly isFiatPriceTargetSet: boolean;
+    readonly asFiatPriceTargetSet: {
+      readonly target: Option<u128>;
+    } & Struct;
+    readonly isPriceAdjustedToFiatTarget: boolean;
+    readonly asPriceAdjustedToFiatTarget: {
+      readonly previousPrice: u128;
+      readonly newPrice: u128;
+      readonly oraclePrice: u128;
+    } & Struct;
+    readonly isOraclePriceStale: boolean;
+    readonly asOraclePriceStale: {
+      readonly lastUpdatedAt: Option<u32>;
+    } & Struct;
+    readonly isAutoTopUpReserveDeposited: boolean;
+    readonly asAutoTopUpReserveDeposited: {
+      readonly who: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly isAutoTopUpReserveWithdrawn: boolean;
+    readonly asAutoTopUpReserveWithdrawn: {
+      readonly who: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly isAutoTopUpThresholdSet: boolean;
+    readonly asAutoTopUpThresholdSet: {
+      readonly who: AccountId32;
+      readonly threshold: Option<u128>;
+    } & Struct;
+    readonly isAutoTopUpExecuted: boolean;
+    readonly asAutoTopUpExecuted: {
+      readonly who: AccountId32;
+      readonly amount: u128;
+      readonly remainingReserve: u128;
+    } & Struct;
     readonly isInconsistentTickProcessing: boolean;
     readonly asInconsistentTickProcessing: {
       readonly lastProcessedTick: u32;
@@ -2393,6 +2918,19 @@ declare module "@polkadot/types/lookup" {
       | "UserPaidAllDebts"
       | "UserPaidSomeDebts"
       | "UserSolvent"
+      | "EscrowOpened"
+      | "EscrowProviderAdded"
+      | "EscrowPaymentReleased"
+      | "EscrowProviderRemoved"
+      | "EscrowShortfallRefunded"
+      | "EscrowClosed"
+      | "FiatPriceTargetSet"
+      | "PriceAdjustedToFiatTarget"
+      | "OraclePriceStale"
+      | "AutoTopUpReserveDeposited"
+      | "AutoTopUpReserveWithdrawn"
+      | "AutoTopUpThresholdSet"
+      | "AutoTopUpExecuted"
       | "InconsistentTickProcessing";
   }
 
@@ -2766,6 +3304,14 @@ declare module "@polkadot/types/lookup" {
     readonly isDefaultReplicationTarget: boolean;
     readonly isMinSeedPeriod: boolean;
     readonly isStakeToSeedPeriod: boolean;
+    readonly isSlashReferenceDataSize: boolean;
+    readonly isSlashEscalationPerMissedDeadline: boolean;
+    readonly isMaxSlashEscalationMultiplier: boolean;
+    readonly isRequireCapacityAttestation: boolean;
+    readonly isMissedDeadlinesAllowance: boolean;
+    readonly isMissedDeadlineWarningWindow: boolean;
+    readonly isFileDeletionFee: boolean;
+    readonly isMinBillableFileSize: boolean;
     readonly type:
       | "SlashAmountPerMaxFileSize"
       | "StakeToChallengePeriod"
@@ -2787,7 +3333,15 @@ declare module "@polkadot/types/lookup" {
       | "ProviderTopUpTtl"
       | "DefaultReplicationTarget"
       | "MinSeedPeriod"
-      | "StakeToSeedPeriod";
+      | "StakeToSeedPeriod"
+      | "SlashReferenceDataSize"
+      | "SlashEscalationPerMissedDeadline"
+      | "MaxSlashEscalationMultiplier"
+      | "RequireCapacityAttestation"
+      | "MissedDeadlinesAllowance"
+      | "MissedDeadlineWarningWindow"
+      | "FileDeletionFee"
+      | "MinBillableFileSize";
   }
 
   /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashAmountPerMaxFileSize (177) */
@@ -2912,6 +3466,22 @@ declare module "@polkadot/types/lookup" {
     readonly asMinSeedPeriod: u32;
     readonly isStakeToSeedPeriod: boolean;
     readonly asStakeToSeedPeriod: u128;
+    readonly isSlashReferenceDataSize: boolean;
+    readonly asSlashReferenceDataSize: u64;
+    readonly isSlashEscalationPerMissedDeadline: boolean;
+    readonly asSlashEscalationPerMissedDeadline: Perbill;
+    readonly isMaxSlashEscalationMultiplier: boolean;
+    readonly asMaxSlashEscalationMultiplier: u32;
+    readonly isRequireCapacityAttestation: boolean;
+    readonly asRequireCapacityAttestation: bool;
+    readonly isMissedDeadlinesAllowance: boolean;
+    readonly asMissedDeadlinesAllowance: u32;
+    readonly isMissedDeadlineWarningWindow: boolean;
+    readonly asMissedDeadlineWarningWindow: u32;
+    readonly isFileDeletionFee: boolean;
+    readonly asFileDeletionFee: u128;
+    readonly isMinBillableFileSize: boolean;
+    readonly asMinBillableFileSize: u64;
     readonly type:
       | "SlashAmountPerMaxFileSize"
       | "StakeToChallengePeriod"
@@ -2933,7 +3503,15 @@ declare module "@polkadot/types/lookup" {
       | "ProviderTopUpTtl"
       | "DefaultReplicationTarget"
       | "MinSeedPeriod"
-      | "StakeToSeedPeriod";
+      | "StakeToSeedPeriod"
+      | "SlashReferenceDataSize"
+      | "SlashEscalationPerMissedDeadline"
+      | "MaxSlashEscalationMultiplier"
+      | "RequireCapacityAttestation"
+      | "MissedDeadlinesAllowance"
+      | "MissedDeadlineWarningWindow"
+      | "FileDeletionFee"
+      | "MinBillableFileSize";
   }
 
   /** @name FrameSystemPhase (202) */
@@ -3308,13 +3886,22 @@ declare module "@polkadot/types/lookup" {
   /** @name PalletFileSystemHoldReason (277) */
   interface PalletFileSystemHoldReason extends Enum {
     readonly isStorageRequestCreationHold: boolean;
-    readonly type: "StorageRequestCreationHold";
+    readonly isFileDeletionFeeHold: boolean;
+    readonly isStorageRequestRenewalHold: boolean;
+    readonly isDeletionListDepositHold: boolean;
+    readonly type:
+      | "StorageRequestCreationHold"
+      | "FileDeletionFeeHold"
+      | "StorageRequestRenewalHold"
+      | "DeletionListDepositHold";
   }
 
   /** @name PalletPaymentStreamsHoldReason (278) */
   interface PalletPaymentStreamsHoldReason extends Enum {
     readonly isPaymentStreamDeposit: boolean;
-    readonly type: "PaymentStreamDeposit";
+    readonly isEscrowedPayment: boolean;
+    readonly isAutoTopUpReserve: boolean;
+    readonly type: "PaymentStreamDeposit" | "EscrowedPayment" | "AutoTopUpReserve";
   }
 
   /** @name FrameSupportTokensMiscIdAmount (281) */
@@ -4338,6 +4925,41 @@ declare module "@polkadot/types/lookup" {
     readonly asDeleteProvider: {
       readonly providerId: H256;
     } & Struct;
+    readonly isRequestCapacityAttestation: boolean;
+    readonly asRequestCapacityAttestation: {
+      readonly capacity: u64;
+    } & Struct;
+    readonly isAnswerCapacityChallenge: boolean;
+    readonly asAnswerCapacityChallenge: {
+      readonly answer: Vec<PalletStorageProvidersCapacityAttestationPlotLeafProof>;
+    } & Struct;
+    readonly isPublishValuePropVersion: boolean;
+    readonly asPublishValuePropVersion: {
+      readonly valuePropId: H256;
+      readonly pricePerGigaUnitOfDataPerBlock: u128;
+      readonly commitment: Bytes;
+      readonly bucketDataLimit: u64;
+    } & Struct;
+    readonly isRequestCapacityDecrease: boolean;
+    readonly asRequestCapacityDecrease: {
+      readonly newCapacity: u64;
+    } & Struct;
+    readonly isConfirmCapacityDecrease: boolean;
+    readonly isScheduleSignOff: boolean;
+    readonly isCancelScheduledSignOff: boolean;
+    readonly isSuspendProvider: boolean;
+    readonly asSuspendProvider: {
+      readonly providerId: H256;
+    } & Struct;
+    readonly isResumeProvider: boolean;
+    readonly asResumeProvider: {
+      readonly providerId: H256;
+    } & Struct;
+    readonly isDrawCapacityPlotSeed: boolean;
+    readonly isCommitCapacityPlot: boolean;
+    readonly asCommitCapacityPlot: {
+      readonly plotRoot: H256;
+    } & Struct;
     readonly type:
       | "RequestMspSignUp"
       | "RequestBspSignUp"
@@ -4354,7 +4976,18 @@ declare module "@polkadot/types/lookup" {
       | "ForceBspSignUp"
       | "Slash"
       | "TopUpDeposit"
-      | "DeleteProvider";
+      | "DeleteProvider"
+      | "RequestCapacityAttestation"
+      | "AnswerCapacityChallenge"
+      | "PublishValuePropVersion"
+      | "RequestCapacityDecrease"
+      | "ConfirmCapacityDecrease"
+      | "ScheduleSignOff"
+      | "CancelScheduledSignOff"
+      | "SuspendProvider"
+      | "ResumeProvider"
+      | "DrawCapacityPlotSeed"
+      | "CommitCapacityPlot";
   }
 
   /** @name PalletFileSystemCall (339) */
@@ -4365,6 +4998,7 @@ declare module "@polkadot/types/lookup" {
       readonly name: Bytes;
       readonly private: bool;
       readonly valuePropId: Option<H256>;
+      readonly encryptionMetadata: Option<Bytes>;
     } & Struct;
     readonly isRequestMoveBucket: boolean;
     readonly asRequestMoveBucket: {
@@ -4398,6 +5032,7 @@ declare module "@polkadot/types/lookup" {
       readonly mspId: Option<H256>;
       readonly peerIds: Vec<Bytes>;
       readonly replicationTarget: Option<u32>;
+      readonly autoRenew: bool;
     } & Struct;
     readonly isRevokeStorageRequest: boolean;
     readonly asRevokeStorageRequest: {
@@ -4468,6 +5103,165 @@ declare module "@polkadot/types/lookup" {
       readonly newMaxReplicationTarget: Option<u32>;
       readonly tickRangeToMaximumThreshold: Option<u32>;
     } & Struct;
+    readonly isSetBucketDefaultReplicationTarget: boolean;
+    readonly asSetBucketDefaultReplicationTarget: {
+      readonly bucketId: H256;
+      readonly replicationTarget: Option<u32>;
+    } & Struct;
+    readonly isSetMspEligibilityMode: boolean;
+    readonly asSetMspEligibilityMode: {
+      readonly mode: PalletFileSystemMspEligibilityListMode;
+    } & Struct;
+    readonly isUpdateMspEligibilityList: boolean;
+    readonly asUpdateMspEligibilityList: {
+      readonly mspId: H256;
+      readonly listed: bool;
+    } & Struct;
+    readonly isIssueEscrowedStorageRequest: boolean;
+    readonly asIssueEscrowedStorageRequest: {
+      readonly bucketId: H256;
+      readonly location: Bytes;
+      readonly fingerprint: H256;
+      readonly size_: u64;
+      readonly mspId: Option<H256>;
+      readonly peerIds: Vec<Bytes>;
+      readonly replicationTarget: Option<u32>;
+      readonly escrowTicks: u32;
+    } & Struct;
+    readonly isResolveDepositTransferRetry: boolean;
+    readonly asResolveDepositTransferRetry: {
+      readonly retryId: u32;
+      readonly resolution: PalletFileSystemDepositTransferResolution;
+    } & Struct;
+    readonly isMigrateBucketValueProp: boolean;
+    readonly asMigrateBucketValueProp: {
+      readonly bucketId: H256;
+    } & Struct;
+    readonly isChangeStorageRequestReplicationTarget: boolean;
+    readonly asChangeStorageRequestReplicationTarget: {
+      readonly fileKey: H256;
+      readonly replicationTarget: u32;
+    } & Struct;
+    readonly isFreezeBucket: boolean;
+    readonly asFreezeBucket: {
+      readonly bucketId: H256;
+    } & Struct;
+    readonly isUnfreezeBucket: boolean;
+    readonly asUnfreezeBucket: {
+      readonly bucketId: H256;
+    } & Struct;
+    readonly isDeleteFiles: boolean;
+    readonly asDeleteFiles: {
+      readonly bucketId: H256;
+      readonly files: Vec<PalletFileSystemFileDeletionRequestItem>;
+    } & Struct;
+    readonly isRequestMoveFile: boolean;
+    readonly asRequestMoveFile: {
+      readonly bucketId: H256;
+      readonly fileKey: H256;
+      readonly location: Bytes;
+      readonly size_: u64;
+      readonly fingerprint: H256;
+      readonly newLocation: Bytes;
+    } & Struct;
+    readonly isMspConfirmFileMove: boolean;
+    readonly asMspConfirmFileMove: {
+      readonly fileKey: H256;
+      readonly forestProof: SpTrieStorageProofCompactProof;
+    } & Struct;
+    readonly isBspConfirmFileMove: boolean;
+    readonly asBspConfirmFileMove: {
+      readonly fileKey: H256;
+      readonly forestProof: SpTrieStorageProofCompactProof;
+    } & Struct;
+    readonly isSetNotificationPreferences: boolean;
+    readonly asSetNotificationPreferences: {
+      readonly preferences: Option<Bytes>;
+    } & Struct;
+    readonly isGrantFileAccess: boolean;
+    readonly asGrantFileAccess: {
+      readonly bucketId: H256;
+      readonly location: Bytes;
+      readonly size_: u64;
+      readonly fingerprint: H256;
+      readonly account: AccountId32;
+    } & Struct;
+    readonly isRevokeFileAccess: boolean;
+    readonly asRevokeFileAccess: {
+      readonly bucketId: H256;
+      readonly location: Bytes;
+      readonly size_: u64;
+      readonly fingerprint: H256;
+      readonly account: AccountId32;
+    } & Struct;
+    readonly isRegisterDeletionList: boolean;
+    readonly asRegisterDeletionList: {
+      readonly listRoot: H256;
+      readonly length: u32;
+    } & Struct;
+    readonly isExecuteDeletionListItem: boolean;
+    readonly asExecuteDeletionListItem: {
+      readonly owner: AccountId32;
+      readonly listRoot: H256;
+      readonly bucketId: H256;
+      readonly location: Bytes;
+      readonly size_: u64;
+      readonly fingerprint: H256;
+      readonly merkleProof: Vec<H256>;
+      readonly maybeInclusionForestProof: Option<SpTrieStorageProofCompactProof>;
+    } & Struct;
+    readonly isCancelDeletionList: boolean;
+    readonly asCancelDeletionList: {
+      readonly listRoot: H256;
+    } & Struct;
+    readonly isCreateUploadGrant: boolean;
+    readonly asCreateUploadGrant: {
+      readonly bucketId: H256;
+      readonly grantee: AccountId32;
+      readonly sizeBudget: u64;
+      readonly expiresAt: u32;
+    } & Struct;
+    readonly isRevokeUploadGrant: boolean;
+    readonly asRevokeUploadGrant: {
+      readonly bucketId: H256;
+      readonly grantee: AccountId32;
+    } & Struct;
+    readonly isIssueStorageRequestWithGrant: boolean;
+    readonly asIssueStorageRequestWithGrant: {
+      readonly bucketId: H256;
+      readonly location: Bytes;
+      readonly fingerprint: H256;
+      readonly size_: u64;
+      readonly mspId: Option<H256>;
+      readonly peerIds: Vec<Bytes>;
+      readonly replicationTarget: Option<u32>;
+    } & Struct;
+    readonly isMspOfferBucketTransfer: boolean;
+    readonly asMspOfferBucketTransfer: {
+      readonly bucketId: H256;
+      readonly newMspId: H256;
+    } & Struct;
+    readonly isRespondBucketTransferOffer: boolean;
+    readonly asRespondBucketTransferOffer: {
+      readonly bucketId: H256;
+      readonly response: PalletFileSystemBucketMoveRequestResponse;
+    } & Struct;
+    readonly isSetBucketEncryptionMetadata: boolean;
+    readonly asSetBucketEncryptionMetadata: {
+      readonly bucketId: H256;
+      readonly encryptionMetadata: Option<Bytes>;
+    } & Struct;
+    readonly isCreateAccessGrant: boolean;
+    readonly asCreateAccessGrant: {
+      readonly target: PalletFileSystemAccessGrantTarget;
+      readonly grantee: AccountId32;
+      readonly expiresAt: u32;
+    } & Struct;
+    readonly isRevokeAccessGrant: boolean;
+    readonly asRevokeAccessGrant: {
+      readonly target: PalletFileSystemAccessGrantTarget;
+      readonly grantee: AccountId32;
+    } & Struct;
     readonly type:
       | "CreateBucket"
       | "RequestMoveBucket"
@@ -4486,7 +5280,34 @@ declare module "@polkadot/types/lookup" {
       | "StopStoringForInsolventUser"
       | "DeleteFile"
       | "PendingFileDeletionRequestSubmitProof"
-      | "SetGlobalParameters";
+      | "SetGlobalParameters"
+      | "SetBucketDefaultReplicationTarget"
+      | "SetMspEligibilityMode"
+      | "UpdateMspEligibilityList"
+      | "IssueEscrowedStorageRequest"
+      | "ResolveDepositTransferRetry"
+      | "MigrateBucketValueProp"
+      | "ChangeStorageRequestReplicationTarget"
+      | "FreezeBucket"
+      | "UnfreezeBucket"
+      | "DeleteFiles"
+      | "RequestMoveFile"
+      | "MspConfirmFileMove"
+      | "BspConfirmFileMove"
+      | "SetNotificationPreferences"
+      | "GrantFileAccess"
+      | "RevokeFileAccess"
+      | "RegisterDeletionList"
+      | "ExecuteDeletionListItem"
+      | "CancelDeletionList"
+      | "CreateUploadGrant"
+      | "RevokeUploadGrant"
+      | "IssueStorageRequestWithGrant"
+      | "MspOfferBucketTransfer"
+      | "RespondBucketTransferOffer"
+      | "SetBucketEncryptionMetadata"
+      | "CreateAccessGrant"
+      | "RevokeAccessGrant";
   }
 
   /** @name PalletFileSystemBucketMoveRequestResponse (340) */
@@ -4540,7 +5361,35 @@ declare module "@polkadot/types/lookup" {
     readonly asSetPaused: {
       readonly paused: bool;
     } & Struct;
-    readonly type: "Challenge" | "SubmitProof" | "ForceInitialiseChallengeCycle" | "SetPaused";
+    readonly isSubmitProofUnsigned: boolean;
+    readonly asSubmitProofUnsigned: {
+      readonly proof: PalletProofsDealerProof;
+      readonly provider: H256;
+      readonly signature: SpRuntimeMultiSignature;
+    } & Struct;
+    readonly isSetProviderChallengePeriodOverride: boolean;
+    readonly asSetProviderChallengePeriodOverride: {
+      readonly providerId: H256;
+      readonly period: Option<u32>;
+    } & Struct;
+    readonly isSetRetentionPolicy: boolean;
+    readonly asSetRetentionPolicy: {
+      readonly policy: Option<PalletProofsDealerRetentionPolicy>;
+    } & Struct;
+    readonly isSubmitProofs: boolean;
+    readonly asSubmitProofs: {
+      readonly proofs: Vec<ITuple<[u32, PalletProofsDealerProof]>>;
+      readonly provider: Option<H256>;
+    } & Struct;
+    readonly type:
+      | "Challenge"
+      | "SubmitProof"
+      | "ForceInitialiseChallengeCycle"
+      | "SetPaused"
+      | "SubmitProofUnsigned"
+      | "SetProviderChallengePeriodOverride"
+      | "SetRetentionPolicy"
+      | "SubmitProofs";
   }
 
   /** @name PalletRandomnessCall (357) */
@@ -4598,6 +5447,26 @@ declare module "@polkadot/types/lookup" {
       readonly providers: Vec<H256>;
     } & Struct;
     readonly isClearInsolventFlag: boolean;
+    readonly isReleaseEscrowedPayment: boolean;
+    readonly asReleaseEscrowedPayment: {
+      readonly escrowId: H256;
+    } & Struct;
+    readonly isSetFiatPriceTarget: boolean;
+    readonly asSetFiatPriceTarget: {
+      readonly target: Option<u128>;
+    } & Struct;
+    readonly isDepositAutoTopUpReserve: boolean;
+    readonly asDepositAutoTopUpReserve: {
+      readonly amount: u128;
+    } & Struct;
+    readonly isWithdrawAutoTopUpReserve: boolean;
+    readonly asWithdrawAutoTopUpReserve: {
+      readonly amount: u128;
+    } & Struct;
+    readonly isSetAutoTopUpThreshold: boolean;
+    readonly asSetAutoTopUpThreshold: {
+      readonly threshold: Option<u128>;
+    } & Struct;
     readonly type:
       | "CreateFixedRatePaymentStream"
       | "UpdateFixedRatePaymentStream"
@@ -4608,7 +5477,12 @@ declare module "@polkadot/types/lookup" {
       | "ChargePaymentStreams"
       | "ChargeMultipleUsersPaymentStreams"
       | "PayOutstandingDebt"
-      | "ClearInsolventFlag";
+      | "ClearInsolventFlag"
+      | "ReleaseEscrowedPayment"
+      | "SetFiatPriceTarget"
+      | "DepositAutoTopUpReserve"
+      | "WithdrawAutoTopUpReserve"
+      | "SetAutoTopUpThreshold";
   }
 
   /** @name PalletBucketNftsCall (359) */
@@ -5162,6 +6036,59 @@ declare module "@polkadot/types/lookup" {
         Option<u128>
       ]
     >;
+    readonly isSlashReferenceDataSize: boolean;
+    readonly asSlashReferenceDataSize: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize,
+        Option<u64>
+      ]
+    >;
+    readonly isSlashEscalationPerMissedDeadline: boolean;
+    readonly asSlashEscalationPerMissedDeadline: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline,
+        Option<Perbill>
+      ]
+    >;
+    readonly isMaxSlashEscalationMultiplier: boolean;
+    readonly asMaxSlashEscalationMultiplier: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier,
+        Option<u32>
+      ]
+    >;
+    readonly isRequireCapacityAttestation: boolean;
+    readonly asRequireCapacityAttestation: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation,
+        Option<bool>
+      ]
+    >;
+    readonly isMissedDeadlinesAllowance: boolean;
+    readonly asMissedDeadlinesAllowance: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance,
+        Option<u32>
+      ]
+    >;
+    readonly isMissedDeadlineWarningWindow: boolean;
+    readonly asMissedDeadlineWarningWindow: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow,
+        Option<u32>
+      ]
+    >;
+    readonly isFileDeletionFee: boolean;
+    readonly asFileDeletionFee: ITuple<
+      [StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee, Option<u128>]
+    >;
+    readonly isMinBillableFileSize: boolean;
+    readonly asMinBillableFileSize: ITuple<
+      [
+        StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize,
+        Option<u64>
+      ]
+    >;
     readonly type:
       | "SlashAmountPerMaxFileSize"
       | "StakeToChallengePeriod"
@@ -5183,7 +6110,15 @@ declare module "@polkadot/types/lookup" {
       | "ProviderTopUpTtl"
       | "DefaultReplicationTarget"
       | "MinSeedPeriod"
-      | "StakeToSeedPeriod";
+      | "StakeToSeedPeriod"
+      | "SlashReferenceDataSize"
+      | "SlashEscalationPerMissedDeadline"
+      | "MaxSlashEscalationMultiplier"
+      | "RequireCapacityAttestation"
+      | "MissedDeadlinesAllowance"
+      | "MissedDeadlineWarningWindow"
+      | "FileDeletionFee"
+      | "MinBillableFileSize";
   }
 
   /** @name PalletSudoError (388) */
@@ -5518,6 +6453,8 @@ declare module "@polkadot/types/lookup" {
     readonly isCannotHoldDeposit: boolean;
     readonly isStorageStillInUse: boolean;
     readonly isSignOffPeriodNotPassed: boolean;
+    readonly isSignOffAlreadyScheduled: boolean;
+    readonly isSignOffNotScheduled: boolean;
     readonly isRandomnessNotValidYet: boolean;
     readonly isSignUpRequestExpired: boolean;
     readonly isNewCapacityLessThanUsedStorage: boolean;
@@ -5526,6 +6463,12 @@ declare module "@polkadot/types/lookup" {
     readonly isNotEnoughTimePassed: boolean;
     readonly isNewUsedCapacityExceedsStorageCapacity: boolean;
     readonly isDepositTooLow: boolean;
+    readonly isNewCapacityNotLessThanCurrentCapacity: boolean;
+    readonly isCapacityDecreaseAlreadyRequested: boolean;
+    readonly isCapacityDecreaseNotRequested: boolean;
+    readonly isCapacityDecreaseGracePeriodNotPassed: boolean;
+    readonly isProviderAlreadySuspended: boolean;
+    readonly isProviderNotSuspended: boolean;
     readonly isNotRegistered: boolean;
     readonly isNoUserId: boolean;
     readonly isNoBucketId: boolean;
@@ -5544,6 +6487,8 @@ declare module "@polkadot/types/lookup" {
     readonly isValuePropositionNotFound: boolean;
     readonly isValuePropositionAlreadyExists: boolean;
     readonly isValuePropositionNotAvailable: boolean;
+    readonly isValuePropositionSuperseded: boolean;
+    readonly isBucketAlreadyOnLatestValueProposition: boolean;
     readonly isFixedRatePaymentStreamNotFound: boolean;
     readonly isMspAlreadyAssignedToBucket: boolean;
     readonly isBucketSizeExceedsLimit: boolean;
@@ -5554,6 +6499,13 @@ declare module "@polkadot/types/lookup" {
     readonly isInvalidEncodedFileMetadata: boolean;
     readonly isInvalidEncodedAccountId: boolean;
     readonly isPaymentStreamNotFound: boolean;
+    readonly isCapacityNotAttested: boolean;
+    readonly isCapacityAttestationNotFound: boolean;
+    readonly isCapacityAttestationExpired: boolean;
+    readonly isCapacityPlotSeedAlreadyDrawn: boolean;
+    readonly isCapacityPlotSeedNotDrawn: boolean;
+    readonly isCapacityPlotNotCommitted: boolean;
+    readonly isInvalidCapacityAttestationProof: boolean;
     readonly type:
       | "AlreadyRegistered"
       | "SignUpNotRequested"
@@ -5565,6 +6517,8 @@ declare module "@polkadot/types/lookup" {
       | "CannotHoldDeposit"
       | "StorageStillInUse"
       | "SignOffPeriodNotPassed"
+      | "SignOffAlreadyScheduled"
+      | "SignOffNotScheduled"
       | "RandomnessNotValidYet"
       | "SignUpRequestExpired"
       | "NewCapacityLessThanUsedStorage"
@@ -5573,6 +6527,12 @@ declare module "@polkadot/types/lookup" {
       | "NotEnoughTimePassed"
       | "NewUsedCapacityExceedsStorageCapacity"
       | "DepositTooLow"
+      | "NewCapacityNotLessThanCurrentCapacity"
+      | "CapacityDecreaseAlreadyRequested"
+      | "CapacityDecreaseNotRequested"
+      | "CapacityDecreaseGracePeriodNotPassed"
+      | "ProviderAlreadySuspended"
+      | "ProviderNotSuspended"
       | "NotRegistered"
       | "NoUserId"
       | "NoBucketId"
@@ -5591,6 +6551,8 @@ declare module "@polkadot/types/lookup" {
       | "ValuePropositionNotFound"
       | "ValuePropositionAlreadyExists"
       | "ValuePropositionNotAvailable"
+      | "ValuePropositionSuperseded"
+      | "BucketAlreadyOnLatestValueProposition"
       | "FixedRatePaymentStreamNotFound"
       | "MspAlreadyAssignedToBucket"
       | "BucketSizeExceedsLimit"
@@ -5600,7 +6562,14 @@ declare module "@polkadot/types/lookup" {
       | "DeleteProviderConditionsNotMet"
       | "InvalidEncodedFileMetadata"
       | "InvalidEncodedAccountId"
-      | "PaymentStreamNotFound";
+      | "PaymentStreamNotFound"
+      | "CapacityNotAttested"
+      | "CapacityAttestationNotFound"
+      | "CapacityAttestationExpired"
+      | "CapacityPlotSeedAlreadyDrawn"
+      | "CapacityPlotSeedNotDrawn"
+      | "CapacityPlotNotCommitted"
+      | "InvalidCapacityAttestationProof";
   }
 
   /** @name PalletFileSystemStorageRequestMetadata (452) */
@@ -5652,6 +6621,8 @@ declare module "@polkadot/types/lookup" {
     readonly isReplicationTargetCannotBeZero: boolean;
     readonly isReplicationTargetExceedsMaximum: boolean;
     readonly isMaxReplicationTargetSmallerThanDefault: boolean;
+    readonly isMspOnlyStorageRequestWithoutMsp: boolean;
+    readonly isStorageRequestIsMspOnly: boolean;
     readonly isNotABsp: boolean;
     readonly isNotAMsp: boolean;
     readonly isNotASp: boolean;
@@ -5687,6 +6658,12 @@ declare module "@polkadot/types/lookup" {
     readonly isNotFileOwner: boolean;
     readonly isFileKeyAlreadyPendingDeletion: boolean;
     readonly isMaxUserPendingDeletionRequestsReached: boolean;
+    readonly isEmptyFileDeletionBatch: boolean;
+    readonly isFileMoveToSameLocation: boolean;
+    readonly isFileMoveAlreadyRequested: boolean;
+    readonly isFileMoveRequestNotFound: boolean;
+    readonly isFileNotMoved: boolean;
+    readonly isFileHasEscrowedPayment: boolean;
     readonly isMspNotStoringBucket: boolean;
     readonly isFileKeyNotPendingDeletion: boolean;
     readonly isFileSizeCannotBeZero: boolean;
@@ -5703,6 +6680,7 @@ declare module "@polkadot/types/lookup" {
     readonly isMspAlreadyStoringBucket: boolean;
     readonly isMoveBucketRequestNotFound: boolean;
     readonly isBucketIsBeingMoved: boolean;
+    readonly isMspNotEligible: boolean;
     readonly isBspAlreadyDataServer: boolean;
     readonly isBspDataServersExceeded: boolean;
     readonly isFileMetadataProcessingQueueFull: boolean;
@@ -5719,6 +6697,33 @@ declare module "@polkadot/types/lookup" {
     readonly isRootNotUpdated: boolean;
     readonly isNoPrivacyChange: boolean;
     readonly isOperationNotAllowedForInsolventProvider: boolean;
+    readonly isDeferredPriorityChallengesQueueFull: boolean;
+    readonly isDepositTransferRetryNotFound: boolean;
+    readonly isReplicationTargetUnchanged: boolean;
+    readonly isReplicationTargetBelowConfirmedBsps: boolean;
+    readonly isBucketIsFrozen: boolean;
+    readonly isBucketAlreadyFrozen: boolean;
+    readonly isBucketNotFrozen: boolean;
+    readonly isFileAccessListFull: boolean;
+    readonly isFileAccessAlreadyGranted: boolean;
+    readonly isFileAccessNotGranted: boolean;
+    readonly isOperationNotAllowedForSuspendedProvider: boolean;
+    readonly isOperationNotAllowedForExitingProvider: boolean;
+    readonly isDeletionListEmpty: boolean;
+    readonly isDeletionListTooLong: boolean;
+    readonly isDeletionListAlreadyRegistered: boolean;
+    readonly isDeletionListNotFound: boolean;
+    readonly isInvalidDeletionListProof: boolean;
+    readonly isUploadGrantExpirationInThePast: boolean;
+    readonly isUploadGrantBudgetCannotBeZero: boolean;
+    readonly isUploadGrantNotFound: boolean;
+    readonly isUploadGrantExpired: boolean;
+    readonly isUploadGrantBudgetExceeded: boolean;
+    readonly isBucketTransferOfferNotFound: boolean;
+    readonly isBucketTransferOfferExpired: boolean;
+    readonly isAccessGrantExpirationInThePast: boolean;
+    readonly isAccessGrantsFull: boolean;
+    readonly isAccessGrantNotFound: boolean;
     readonly type:
       | "StorageRequestAlreadyRegistered"
       | "StorageRequestNotFound"
@@ -5727,6 +6732,8 @@ declare module "@polkadot/types/lookup" {
       | "ReplicationTargetCannotBeZero"
       | "ReplicationTargetExceedsMaximum"
       | "MaxReplicationTargetSmallerThanDefault"
+      | "MspOnlyStorageRequestWithoutMsp"
+      | "StorageRequestIsMspOnly"
       | "NotABsp"
       | "NotAMsp"
       | "NotASp"
@@ -5762,6 +6769,12 @@ declare module "@polkadot/types/lookup" {
       | "NotFileOwner"
       | "FileKeyAlreadyPendingDeletion"
       | "MaxUserPendingDeletionRequestsReached"
+      | "EmptyFileDeletionBatch"
+      | "FileMoveToSameLocation"
+      | "FileMoveAlreadyRequested"
+      | "FileMoveRequestNotFound"
+      | "FileNotMoved"
+      | "FileHasEscrowedPayment"
       | "MspNotStoringBucket"
       | "FileKeyNotPendingDeletion"
       | "FileSizeCannotBeZero"
@@ -5778,6 +6791,7 @@ declare module "@polkadot/types/lookup" {
       | "MspAlreadyStoringBucket"
       | "MoveBucketRequestNotFound"
       | "BucketIsBeingMoved"
+      | "MspNotEligible"
       | "BspAlreadyDataServer"
       | "BspDataServersExceeded"
       | "FileMetadataProcessingQueueFull"
@@ -5793,7 +6807,34 @@ declare module "@polkadot/types/lookup" {
       | "NoFileKeysToConfirm"
       | "RootNotUpdated"
       | "NoPrivacyChange"
-      | "OperationNotAllowedForInsolventProvider";
+      | "OperationNotAllowedForInsolventProvider"
+      | "DeferredPriorityChallengesQueueFull"
+      | "DepositTransferRetryNotFound"
+      | "ReplicationTargetUnchanged"
+      | "ReplicationTargetBelowConfirmedBsps"
+      | "BucketIsFrozen"
+      | "BucketAlreadyFrozen"
+      | "BucketNotFrozen"
+      | "FileAccessListFull"
+      | "FileAccessAlreadyGranted"
+      | "FileAccessNotGranted"
+      | "OperationNotAllowedForSuspendedProvider"
+      | "OperationNotAllowedForExitingProvider"
+      | "DeletionListEmpty"
+      | "DeletionListTooLong"
+      | "DeletionListAlreadyRegistered"
+      | "DeletionListNotFound"
+      | "InvalidDeletionListProof"
+      | "UploadGrantExpirationInThePast"
+      | "UploadGrantBudgetCannotBeZero"
+      | "UploadGrantNotFound"
+      | "UploadGrantExpired"
+      | "UploadGrantBudgetExceeded"
+      | "BucketTransferOfferNotFound"
+      | "BucketTransferOfferExpired"
+      | "AccessGrantExpirationInThePast"
+      | "AccessGrantsFull"
+      | "AccessGrantNotFound";
   }
 
   /** @name PalletProofsDealerProofSubmissionRecord (467) */
@@ -5828,6 +6869,14 @@ declare module "@polkadot/types/lookup" {
     readonly isUnexpectedNumberOfRemoveMutations: boolean;
     readonly isFailedToUpdateProviderAfterKeyRemoval: boolean;
     readonly isTooManyValidProofSubmitters: boolean;
+    readonly isProofTooLarge: boolean;
+    readonly isProviderNotActive: boolean;
+    readonly isInvalidProofSignature: boolean;
+    readonly isChallengePeriodOverrideTooShort: boolean;
+    readonly isChallengePeriodOverrideTooLong: boolean;
+    readonly isRetentionTooShort: boolean;
+    readonly isEmptyProofBatch: boolean;
+    readonly isUnexpectedChallengesTick: boolean;
     readonly type:
       | "NotProvider"
       | "ChallengesQueueOverflow"
@@ -5852,7 +6901,15 @@ declare module "@polkadot/types/lookup" {
       | "FailedToApplyDelta"
       | "UnexpectedNumberOfRemoveMutations"
       | "FailedToUpdateProviderAfterKeyRemoval"
-      | "TooManyValidProofSubmitters";
+      | "TooManyValidProofSubmitters"
+      | "ProofTooLarge"
+      | "ProviderNotActive"
+      | "InvalidProofSignature"
+      | "ChallengePeriodOverrideTooShort"
+      | "ChallengePeriodOverrideTooLong"
+      | "RetentionTooShort"
+      | "EmptyProofBatch"
+      | "UnexpectedChallengesTick";
   }
 
   /** @name PalletPaymentStreamsFixedRatePaymentStream (475) */
@@ -5897,6 +6954,19 @@ declare module "@polkadot/types/lookup" {
     readonly isCooldownPeriodNotPassed: boolean;
     readonly isUserHasRemainingDebt: boolean;
     readonly isProviderInsolvent: boolean;
+    readonly isEscrowAlreadyExists: boolean;
+    readonly isEscrowNotFound: boolean;
+    readonly isEscrowParamsCantBeZero: boolean;
+    readonly isCannotHoldEscrow: boolean;
+    readonly isEscrowProvidersTargetReached: boolean;
+    readonly isEscrowReplicationAlreadySettled: boolean;
+    readonly isProviderAlreadyInEscrow: boolean;
+    readonly isProviderNotInEscrow: boolean;
+    readonly isFiatPriceTargetCantBeZero: boolean;
+    readonly isAutoTopUpAmountCantBeZero: boolean;
+    readonly isCannotHoldAutoTopUpReserve: boolean;
+    readonly isInsufficientAutoTopUpReserve: boolean;
+    readonly isAutoTopUpThresholdCantBeZero: boolean;
     readonly type:
       | "PaymentStreamAlreadyExists"
       | "PaymentStreamNotFound"
@@ -5915,7 +6985,20 @@ declare module "@polkadot/types/lookup" {
       | "UserNotFlaggedAsWithoutFunds"
       | "CooldownPeriodNotPassed"
       | "UserHasRemainingDebt"
-      | "ProviderInsolvent";
+      | "ProviderInsolvent"
+      | "EscrowAlreadyExists"
+      | "EscrowNotFound"
+      | "EscrowParamsCantBeZero"
+      | "CannotHoldEscrow"
+      | "EscrowProvidersTargetReached"
+      | "EscrowReplicationAlreadySettled"
+      | "ProviderAlreadyInEscrow"
+      | "ProviderNotInEscrow"
+      | "FiatPriceTargetCantBeZero"
+      | "AutoTopUpAmountCantBeZero"
+      | "CannotHoldAutoTopUpReserve"
+      | "InsufficientAutoTopUpReserve"
+      | "AutoTopUpThresholdCantBeZero";
   }
 
   /** @name PalletBucketNftsError (479) */
@@ -6136,4 +7219,272 @@ declare module "@polkadot/types/lookup" {
 
   /** @name StorageHubRuntimeRuntime (519) */
   type StorageHubRuntimeRuntime = Null;
+
+  /** @name PalletFileSystemMspEligibilityListMode (520) */
+  interface PalletFileSystemMspEligibilityListMode extends Enum {
+    readonly isDenylist: boolean;
+    readonly isAllowlist: boolean;
+    readonly type: "Denylist" | "Allowlist";
+  }
+
+  /** @name PalletFileSystemDepositTransferResolution (521) */
+  interface PalletFileSystemDepositTransferResolution extends Enum {
+    readonly isRetry: boolean;
+    readonly isWriteOff: boolean;
+    readonly type: "Retry" | "WriteOff";
+  }
+
+  /** @name PalletFileSystemFileDeletionRequestItem (522) */
+  interface PalletFileSystemFileDeletionRequestItem extends Struct {
+    readonly fileKey: H256;
+    readonly location: Bytes;
+    readonly size_: u64;
+    readonly fingerprint: H256;
+  }
+
+  /** @name PalletFileSystemAccessGrantTarget (523) */
+  interface PalletFileSystemAccessGrantTarget extends Enum {
+    readonly isBucket: boolean;
+    readonly asBucket: H256;
+    readonly isFile: boolean;
+    readonly asFile: {
+      readonly bucketId: H256;
+      readonly fileKey: H256;
+    } & Struct;
+    readonly type: "Bucket" | "File";
+  }
+
+  /** @name ShpTraitsProviderStatusChange (524) */
+  interface ShpTraitsProviderStatusChange extends Enum {
+    readonly isInsolvent: boolean;
+    readonly isDeregistered: boolean;
+    readonly type: "Insolvent" | "Deregistered";
+  }
+
+  /** @name PalletFileSystemDepositTransfer (525) */
+  interface PalletFileSystemDepositTransfer extends Enum {
+    readonly isStorageRequestCreationDeposit: boolean;
+    readonly asStorageRequestCreationDeposit: {
+      readonly owner: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly isStorageRequestEscrow: boolean;
+    readonly asStorageRequestEscrow: {
+      readonly fileKey: H256;
+    } & Struct;
+    readonly isStorageRequestRevocationCompensation: boolean;
+    readonly asStorageRequestRevocationCompensation: {
+      readonly owner: AccountId32;
+      readonly bsp: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly isStorageRequestRenewalDeposit: boolean;
+    readonly asStorageRequestRenewalDeposit: {
+      readonly owner: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly isFileDeletionFee: boolean;
+    readonly asFileDeletionFee: {
+      readonly payer: AccountId32;
+      readonly recipient: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly isDeletionListDeposit: boolean;
+    readonly asDeletionListDeposit: {
+      readonly owner: AccountId32;
+      readonly amount: u128;
+    } & Struct;
+    readonly type:
+      | "StorageRequestCreationDeposit"
+      | "StorageRequestEscrow"
+      | "StorageRequestRevocationCompensation"
+      | "StorageRequestRenewalDeposit"
+      | "FileDeletionFee"
+      | "DeletionListDeposit";
+  }
+
+  /** @name PalletFileSystemFileMove (526) */
+  interface PalletFileSystemFileMove extends Struct {
+    readonly owner: AccountId32;
+    readonly bucketId: H256;
+    readonly newFileKey: H256;
+    readonly newLocation: Bytes;
+    readonly size_: u64;
+    readonly fingerprint: H256;
+  }
+
+  /** @name PalletFileSystemPriorityChallengeUsage (527) */
+  interface PalletFileSystemPriorityChallengeUsage extends Struct {
+    readonly periodStart: u32;
+    readonly count: u32;
+  }
+
+  /** @name PalletFileSystemProviderStatusChangeProgress (528) */
+  interface PalletFileSystemProviderStatusChangeProgress extends Struct {
+    readonly providerId: H256;
+    readonly status: ShpTraitsProviderStatusChange;
+    readonly moveBucketRequestsProcessed: bool;
+    readonly lastCheckedStorageRequest: Option<H256>;
+  }
+
+  /** @name PalletFileSystemDepositTransferRetry (529) */
+  interface PalletFileSystemDepositTransferRetry extends Struct {
+    readonly transfer: PalletFileSystemDepositTransfer;
+    readonly attempts: u32;
+    readonly nextAttemptAt: u32;
+  }
+
+  /** @name PalletFileSystemDeletionList (530) */
+  interface PalletFileSystemDeletionList extends Struct {
+    readonly length: u32;
+    readonly nextIndex: u32;
+    readonly itemDeposit: u128;
+  }
+
+  /** @name PalletFileSystemUploadGrant (531) */
+  interface PalletFileSystemUploadGrant extends Struct {
+    readonly owner: AccountId32;
+    readonly remainingBudget: u64;
+    readonly expiresAt: u32;
+  }
+
+  /** @name PalletFileSystemBucketTransferOffer (532) */
+  interface PalletFileSystemBucketTransferOffer extends Struct {
+    readonly mspId: H256;
+    readonly newMspId: H256;
+    readonly expiresAt: u32;
+  }
+
+  /** @name PalletFileSystemAccessGrant (533) */
+  interface PalletFileSystemAccessGrant extends Struct {
+    readonly grantee: AccountId32;
+    readonly expiresAt: u32;
+  }
+
+  /** @name PalletStorageProvidersCapacityAttestationPlotLeafProof (534) */
+  interface PalletStorageProvidersCapacityAttestationPlotLeafProof extends Struct {
+    readonly leaf: H256;
+    readonly siblings: Vec<H256>;
+    readonly previousLeaf: H256;
+    readonly previousSiblings: Vec<H256>;
+  }
+
+  /** @name ShpTraitsProviderStatus (535) */
+  interface ShpTraitsProviderStatus extends Enum {
+    readonly isActive: boolean;
+    readonly isInsolvent: boolean;
+    readonly isExiting: boolean;
+    readonly isSuspended: boolean;
+    readonly type: "Active" | "Insolvent" | "Exiting" | "Suspended";
+  }
+
+  /** @name PalletStorageProvidersValuePropositionVersion (536) */
+  interface PalletStorageProvidersValuePropositionVersion extends Struct {
+    readonly lineage: H256;
+    readonly version: u32;
+  }
+
+  /** @name PalletStorageProvidersCapacityUtilizationSample (537) */
+  interface PalletStorageProvidersCapacityUtilizationSample extends Struct {
+    readonly tick: u32;
+    readonly totalCapacity: u64;
+    readonly usedCapacity: u64;
+    readonly pricePerGigaUnitOfDataPerTick: u128;
+  }
+
+  /** @name PalletStorageProvidersPendingCapacityAttestation (538) */
+  interface PalletStorageProvidersPendingCapacityAttestation extends Struct {
+    readonly capacity: u64;
+    readonly requestedAt: u32;
+    readonly stage: PalletStorageProvidersCapacityAttestationStage;
+  }
+
+  /** @name PalletStorageProvidersPendingCapacityDecrease (539) */
+  interface PalletStorageProvidersPendingCapacityDecrease extends Struct {
+    readonly newCapacity: u64;
+    readonly confirmableAt: u32;
+  }
+
+  /** @name PalletPaymentStreamsPaymentEscrow (540) */
+  interface PalletPaymentStreamsPaymentEscrow extends Struct {
+    readonly userAccount: AccountId32;
+    readonly amountProvided: u64;
+    readonly ratePerProvider: u128;
+    readonly duration: u32;
+    readonly providersTarget: u32;
+    readonly providersCount: u32;
+    readonly amountHeld: u128;
+    readonly replicationSettled: bool;
+  }
+
+  /** @name PalletPaymentStreamsEscrowedProvider (541) */
+  interface PalletPaymentStreamsEscrowedProvider extends Struct {
+    readonly startTick: u32;
+    readonly endTick: u32;
+    readonly releasedUntilTick: u32;
+  }
+
+  /** @name PalletProofsDealerRetentionPolicy (542) */
+  interface PalletProofsDealerRetentionPolicy extends Struct {
+    readonly challengeSeeds: u32;
+    readonly validProofSubmitters: u32;
+  }
+
+  /** @name PalletProofsDealerSlashableProviderRecord (543) */
+  interface PalletProofsDealerSlashableProviderRecord extends Struct {
+    readonly accruedFailedProofSubmissions: u32;
+  }
+
+  /** @name PalletProofsDealerProviderWarningRecord (544) */
+  interface PalletProofsDealerProviderWarningRecord extends Struct {
+    readonly windowStart: u32;
+    readonly missedDeadlines: u32;
+  }
+
+  /** @name PalletStorageProvidersCapacityAttestationStage (545) */
+  interface PalletStorageProvidersCapacityAttestationStage extends Enum {
+    readonly isRequested: boolean;
+    readonly isSeedDrawn: boolean;
+    readonly asSeedDrawn: {
+      readonly seed: H256;
+      readonly deadline: u32;
+    } & Struct;
+    readonly isCommitted: boolean;
+    readonly asCommitted: {
+      readonly seed: H256;
+      readonly plotRoot: H256;
+      readonly committedAt: u32;
+      readonly deadline: u32;
+    } & Struct;
+    readonly type: "Requested" | "SeedDrawn" | "Committed";
+  }
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize (546) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashReferenceDataSize = Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline (547) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigSlashEscalationPerMissedDeadline =
+    Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier (548) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMaxSlashEscalationMultiplier =
+    Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation (549) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigRequireCapacityAttestation =
+    Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance (550) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlinesAllowance =
+    Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow (551) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMissedDeadlineWarningWindow =
+    Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee (552) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigFileDeletionFee = Null;
+
+  /** @name StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize (553) */
+  type StorageHubRuntimeConfigsRuntimeParamsDynamicParamsRuntimeConfigMinBillableFileSize = Null;
 } // declare module
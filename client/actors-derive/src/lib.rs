@@ -0,0 +1,68 @@
+//! Derive macros for the StorageHub actors framework.
+//!
+//! They are re-exported by `shc-actors-framework`, which the generated code refers to, and should be
+//! used from there.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Type};
+
+/// Implements `EventBusMessage` for the type, so that it can be emitted on an event bus.
+///
+/// With `#[event_bus(response = T)]`, also implements `EventBusRequest` with `T` as its response,
+/// so that it can be sent as a request expecting a response of type `T`:
+///
+/// ```ignore
+/// #[derive(Clone, EventBusMessage)]
+/// #[event_bus(response = Option<u64>)]
+/// pub struct QueryFileSize {
+///     pub file_key: H256,
+/// }
+/// ```
+#[proc_macro_derive(EventBusMessage, attributes(event_bus))]
+pub fn derive_event_bus_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_event_bus_message(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_event_bus_message(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut response: Option<Type> = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("event_bus"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("response") {
+                response = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `event_bus` attribute, expected `response = <type>`"))
+            }
+        })?;
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let request = response.map(|response| {
+        quote! {
+            impl #impl_generics ::shc_actors_framework::request::EventBusRequest
+                for #name #ty_generics #where_clause
+            {
+                type Response = #response;
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::shc_actors_framework::event_bus::EventBusMessage
+            for #name #ty_generics #where_clause
+        {
+        }
+
+        #request
+    })
+}
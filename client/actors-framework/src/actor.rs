@@ -4,6 +4,8 @@ use futures::prelude::*;
 use crate::{
     constants::DEFAULT_ACTOR_COMMAND_QUEUE_WARNING_SIZE,
     event_bus::{EventBusMessage, ProvidesEventBus},
+    request::{EventBusRequest, RequestEnvelope},
+    supervisor::{supervise, SupervisionPolicy, SupervisionRegistry},
 };
 
 /// The [`Actor`] trait represents an actor, which runs on its own event loop and can handle messages.
@@ -41,6 +43,17 @@ pub trait Actor: Sized {
     {
         self.get_event_bus_provider().event_bus().emit(event)
     }
+
+    /// Emits `request` and returns a future resolving to its response. See [`crate::request`].
+    fn request<R: EventBusRequest>(
+        &self,
+        request: R,
+    ) -> impl std::future::Future<Output = anyhow::Result<R::Response>> + Send + 'static
+    where
+        Self::EventBusProvider: ProvidesEventBus<RequestEnvelope<R>>,
+    {
+        self.get_event_bus_provider().event_bus().request(request)
+    }
 }
 
 /// Trait representing an event loop for an actor.
@@ -89,6 +102,19 @@ pub struct ActorHandle<T: Actor> {
 }
 
 impl<T: Actor> ActorHandle<T> {
+    /// Creates a handle whose messages are sent through `sender`, for when they are not received
+    /// by a spawned actor (e.g. in tests).
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn from_sender(
+        sender: sc_utils::mpsc::TracingUnboundedSender<T::Message>,
+        event_bus_provider: T::EventBusProvider,
+    ) -> Self {
+        Self {
+            sender,
+            event_bus_provider,
+        }
+    }
+
     /// Sends a message to the actor.
     ///
     /// This method sends a `message` of type `T::Message` to the actor associated with this handle.
@@ -120,6 +146,7 @@ pub struct TaskSpawner {
     name: &'static str,
     group: Option<&'static str>,
     queue_size_warning: usize,
+    supervision: SupervisionRegistry,
 }
 
 impl Debug for TaskSpawner {
@@ -139,6 +166,7 @@ impl TaskSpawner {
             name,
             group: None,
             queue_size_warning: DEFAULT_ACTOR_COMMAND_QUEUE_WARNING_SIZE,
+            supervision: SupervisionRegistry::default(),
         }
     }
 
@@ -166,6 +194,52 @@ impl TaskSpawner {
     pub fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
         self.spawner.spawn(self.name, self.group, task);
     }
+
+    /// Restart counts and lifecycle events of the actors supervised by this spawner, and by every
+    /// spawner derived from it.
+    pub fn supervision(&self) -> &SupervisionRegistry {
+        &self.supervision
+    }
+
+    /// Spawns an actor whose event loop is restarted according to `policy` whenever it panics or
+    /// exits while its handles are still alive. See [`crate::supervisor`].
+    ///
+    /// `factory` builds a fresh instance of the actor on every (re)start. The returned handle keeps
+    /// the event bus provider of the first instance, so actors emitting events must be built around
+    /// the same provider (i.e. a clone of it captured by `factory`), for the listeners to keep
+    /// receiving their events after a restart.
+    pub fn spawn_supervised_actor<T, F>(
+        self,
+        mut factory: F,
+        policy: SupervisionPolicy,
+    ) -> ActorHandle<T>
+    where
+        T: Actor + Send + 'static,
+        F: FnMut() -> T + Send + 'static,
+    {
+        let (sender, receiver) =
+            sc_utils::mpsc::tracing_unbounded(self.name, self.queue_size_warning);
+        let actor = factory();
+        let event_bus_provider = actor.get_event_bus_provider().clone();
+
+        // Start with the instance already built, then fall back to the factory on restarts.
+        let mut first_instance = Some(actor);
+        let factory = move || first_instance.take().unwrap_or_else(&mut factory);
+
+        self.spawn(supervise(
+            self.name,
+            factory,
+            policy,
+            receiver,
+            self.queue_size_warning,
+            self.supervision.clone(),
+        ));
+
+        ActorHandle {
+            sender,
+            event_bus_provider,
+        }
+    }
 }
 
 /// Implements the [`SpawnableActor`] trait for any type that implements the [`Actor`] trait.
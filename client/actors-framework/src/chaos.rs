@@ -0,0 +1,291 @@
+//! Chaos testing hooks for the actors framework.
+//!
+//! Only compiled with the `chaos` feature. A scenario file (JSON) describes a list of faults,
+//! each one attached to a named injection point. Components call [`inject`] at their injection
+//! points and act on the returned [`FaultAction`], if any.
+//!
+//! The scenario is loaded once, from the path given in the [`CHAOS_SCENARIO_ENV_VAR`] environment
+//! variable. If the variable is not set, no faults are injected.
+//!
+//! Example scenario:
+//! ```json
+//! {
+//!     "seed": 42,
+//!     "faults": [
+//!         { "point": "event_bus::emit", "probability": 0.1, "action": "drop" },
+//!         { "point": "blockchain_service::command", "probability": 0.5, "action": { "delay": { "millis": 200 } } }
+//!     ]
+//! }
+//! ```
+
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+use sc_tracing::tracing::{info, warn};
+use serde::Deserialize;
+
+const LOG_TARGET: &str = "chaos";
+
+/// Environment variable holding the path to the chaos scenario file.
+pub const CHAOS_SCENARIO_ENV_VAR: &str = "STORAGEHUB_CHAOS_SCENARIO";
+
+/// Injection point for events emitted through an [`crate::event_bus::EventBus`].
+pub const EVENT_BUS_EMIT: &str = "event_bus::emit";
+
+/// Injection point for events received by an [`crate::event_bus::EventBusListener`], before
+/// being handed to the event handler.
+pub const EVENT_BUS_HANDLE: &str = "event_bus::handle";
+
+/// The action to take when a fault is triggered at an injection point.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultAction {
+    /// Drop whatever is being processed at the injection point (event, command, lock grant...).
+    Drop,
+    /// Delay the processing at the injection point.
+    Delay { millis: u64 },
+}
+
+impl FaultAction {
+    /// The delay to apply, if this is a [`FaultAction::Delay`].
+    pub fn delay(&self) -> Option<Duration> {
+        match self {
+            FaultAction::Delay { millis } => Some(Duration::from_millis(*millis)),
+            FaultAction::Drop => None,
+        }
+    }
+}
+
+/// A fault attached to an injection point.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fault {
+    /// The name of the injection point.
+    pub point: String,
+    /// Probability of the fault being triggered each time the injection point is hit.
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+    /// The action to take when the fault is triggered.
+    pub action: FaultAction,
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+/// A chaos scenario, as read from the scenario file.
+#[derive(Debug, Default, Deserialize)]
+pub struct ChaosScenario {
+    /// Seed for the pseudo-random generator, so that runs of a scenario are reproducible.
+    #[serde(default)]
+    pub seed: u64,
+    /// The faults to inject.
+    #[serde(default)]
+    pub faults: Vec<Fault>,
+}
+
+impl ChaosScenario {
+    /// Reads a scenario from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// The faults of a [`ChaosScenario`], along with the pseudo-random generator deciding when they
+/// are triggered.
+pub struct Chaos {
+    scenario: ChaosScenario,
+    rng_state: AtomicU64,
+}
+
+impl Chaos {
+    /// Creates the faults of `scenario`, seeding the pseudo-random generator with its seed.
+    pub fn new(scenario: ChaosScenario) -> Self {
+        Self {
+            rng_state: AtomicU64::new(scenario.seed),
+            scenario,
+        }
+    }
+
+    /// Hits the injection point `point`, returning the action of the first fault for that point
+    /// that is triggered, if any.
+    pub fn inject(&self, point: &str) -> Option<FaultAction> {
+        for fault in self.scenario.faults.iter().filter(|f| f.point == point) {
+            if self.next_f64() < fault.probability {
+                warn!(target: LOG_TARGET, "🐒 Injecting fault {:?} at {}", fault.action, point);
+                return Some(fault.action.clone());
+            }
+        }
+        None
+    }
+
+    /// Returns a pseudo-random number in `[0, 1)`.
+    ///
+    /// Uses a splitmix64 sequence, which is good enough for fault injection and keeps the
+    /// outcome of a scenario reproducible given its seed.
+    fn next_f64(&self) -> f64 {
+        let mut z = self
+            .rng_state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+static CHAOS: OnceLock<Chaos> = OnceLock::new();
+
+fn chaos() -> &'static Chaos {
+    CHAOS.get_or_init(|| {
+        let scenario = match std::env::var(CHAOS_SCENARIO_ENV_VAR) {
+            Ok(path) => match ChaosScenario::from_file(&path) {
+                Ok(scenario) => {
+                    info!(target: LOG_TARGET, "🐒 Loaded chaos scenario from {} with {} faults", path, scenario.faults.len());
+                    scenario
+                }
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Failed to load chaos scenario from {}: {:?}", path, e);
+                    ChaosScenario::default()
+                }
+            },
+            Err(_) => ChaosScenario::default(),
+        };
+
+        Chaos::new(scenario)
+    })
+}
+
+/// Installs a scenario programmatically instead of reading it from the scenario file.
+///
+/// Must be called before any injection point is hit. Returns `false` if a scenario was already
+/// installed.
+pub fn install(scenario: ChaosScenario) -> bool {
+    CHAOS.set(Chaos::new(scenario)).is_ok()
+}
+
+/// Hits the injection point `point` of the installed scenario. See [`Chaos::inject`].
+pub fn inject(point: &str) -> Option<FaultAction> {
+    chaos().inject(point)
+}
+
+/// Same as [`inject`], but applies [`FaultAction::Delay`] faults directly.
+///
+/// Returns `true` if whatever is being processed at the injection point should be dropped.
+pub async fn inject_async(point: &str) -> bool {
+    match inject(point) {
+        Some(FaultAction::Drop) => true,
+        Some(FaultAction::Delay { millis }) => {
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            false
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(faults: Vec<Fault>) -> ChaosScenario {
+        ChaosScenario { seed: 42, faults }
+    }
+
+    fn fault(point: &str, probability: f64, action: FaultAction) -> Fault {
+        Fault {
+            point: point.to_string(),
+            probability,
+            action,
+        }
+    }
+
+    #[test]
+    fn every_fault_kind_fires_at_every_point() {
+        let actions = [FaultAction::Drop, FaultAction::Delay { millis: 200 }];
+
+        for point in [EVENT_BUS_EMIT, EVENT_BUS_HANDLE, "some_service::some_point"] {
+            for action in &actions {
+                let chaos = Chaos::new(scenario(vec![fault(point, 1.0, action.clone())]));
+                assert_eq!(chaos.inject(point), Some(action.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn faults_only_fire_at_their_point() {
+        let chaos = Chaos::new(scenario(vec![fault(
+            EVENT_BUS_EMIT,
+            1.0,
+            FaultAction::Drop,
+        )]));
+
+        assert_eq!(chaos.inject(EVENT_BUS_EMIT), Some(FaultAction::Drop));
+        assert_eq!(chaos.inject(EVENT_BUS_HANDLE), None);
+    }
+
+    #[test]
+    fn no_fault_fires_without_a_scenario() {
+        let chaos = Chaos::new(ChaosScenario::default());
+
+        for _ in 0..100 {
+            assert_eq!(chaos.inject(EVENT_BUS_EMIT), None);
+            assert_eq!(chaos.inject(EVENT_BUS_HANDLE), None);
+        }
+    }
+
+    #[test]
+    fn faults_with_zero_probability_never_fire() {
+        let chaos = Chaos::new(scenario(vec![fault(
+            EVENT_BUS_EMIT,
+            0.0,
+            FaultAction::Drop,
+        )]));
+
+        for _ in 0..100 {
+            assert_eq!(chaos.inject(EVENT_BUS_EMIT), None);
+        }
+    }
+
+    #[test]
+    fn same_seed_triggers_the_same_faults() {
+        let faults = || vec![fault(EVENT_BUS_EMIT, 0.5, FaultAction::Drop)];
+        let first = Chaos::new(scenario(faults()));
+        let second = Chaos::new(scenario(faults()));
+
+        let first_run: Vec<_> = (0..50).map(|_| first.inject(EVENT_BUS_EMIT)).collect();
+        let second_run: Vec<_> = (0..50).map(|_| second.inject(EVENT_BUS_EMIT)).collect();
+
+        assert_eq!(first_run, second_run);
+        assert!(first_run.iter().any(Option::is_some));
+        assert!(first_run.iter().any(Option::is_none));
+    }
+
+    #[test]
+    fn scenario_is_parsed_from_json() {
+        let scenario: ChaosScenario = serde_json::from_str(
+            r#"{
+                "seed": 7,
+                "faults": [
+                    { "point": "event_bus::emit", "action": "drop" },
+                    { "point": "event_bus::handle", "probability": 0.5, "action": { "delay": { "millis": 200 } } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.seed, 7);
+        assert_eq!(scenario.faults[0].probability, 1.0);
+        assert_eq!(scenario.faults[0].action, FaultAction::Drop);
+        assert_eq!(
+            scenario.faults[1].action,
+            FaultAction::Delay { millis: 200 }
+        );
+    }
+}
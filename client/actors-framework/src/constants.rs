@@ -1,3 +1,5 @@
 pub const MAX_PENDING_EVENTS: usize = 2000;
 
+pub const MAX_CONCURRENT_EVENT_HANDLERS: usize = 2000;
+
 pub const DEFAULT_ACTOR_COMMAND_QUEUE_WARNING_SIZE: usize = 2000;
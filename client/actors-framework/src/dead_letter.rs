@@ -0,0 +1,393 @@
+//! Dead-letter queue of the events whose handler returned an error.
+//!
+//! By default, an [`EventBusListener`] only logs the errors returned by its event handler, and the
+//! event is lost. Listeners of critical events can instead be given a [`DeadLetterQueue`] with
+//! [`EventBusListener::with_dead_letter_queue`], which captures every failed event as a
+//! [`DeadLetter`]: the event serialized as JSON, the error returned with its context, and the task
+//! that failed to handle it.
+//!
+//! Dead letters can then be listed, discarded, or replayed into the listener of the task that failed
+//! to handle them, where they are queued like any newly received event. An event failing again
+//! after being replayed goes back to the dead-letter queue, with its replay count increased.
+//!
+//! The queue keeps at most [`DeadLetterRetention::max_entries`] dead letters, dropping the oldest
+//! ones first, and none older than [`DeadLetterRetention::max_age`].
+//!
+//! [`EventBusListener`]: crate::event_bus::EventBusListener
+//! [`EventBusListener::with_dead_letter_queue`]: crate::event_bus::EventBusListener::with_dead_letter_queue
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, Result};
+use sc_tracing::tracing::{error, warn};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::mpsc;
+
+const LOG_TARGET: &str = "dead-letter-queue";
+
+/// An event whose handler returned an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    /// Identifier of the dead letter, unique within its queue.
+    pub id: u64,
+    /// Type name of the event handler that failed to handle the event.
+    pub task: &'static str,
+    /// Type name of the event.
+    pub event: &'static str,
+    /// The event, serialized as JSON.
+    pub payload: Vec<u8>,
+    /// The error returned by the event handler, with its context.
+    pub error: String,
+    pub failed_at: SystemTime,
+    /// How many times the event was replayed before failing this time.
+    pub replays: u32,
+}
+
+/// How many dead letters a [`DeadLetterQueue`] keeps, and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadLetterRetention {
+    /// Maximum amount of dead letters kept. The oldest ones are dropped first.
+    pub max_entries: usize,
+    /// Maximum time a dead letter is kept after its event failed.
+    pub max_age: Duration,
+}
+
+impl Default for DeadLetterRetention {
+    fn default() -> Self {
+        Self {
+            max_entries: 1000,
+            max_age: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// The dead letters of a queue, by ascending id.
+struct DeadLetters {
+    entries: VecDeque<DeadLetter>,
+    next_id: u64,
+    retention: DeadLetterRetention,
+}
+
+impl DeadLetters {
+    fn new(retention: DeadLetterRetention) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            next_id: 0,
+            retention,
+        }
+    }
+
+    /// Adds a dead letter with the next id, returning it.
+    fn push(&mut self, dead_letter: DeadLetter, now: SystemTime) -> u64 {
+        let id = self.next_id;
+        self.next_id = self.next_id.saturating_add(1);
+        self.entries.push_back(DeadLetter { id, ..dead_letter });
+        self.prune(now);
+        id
+    }
+
+    /// Drops the dead letters exceeding the retention limits as of `now`.
+    fn prune(&mut self, now: SystemTime) {
+        let max_age = self.retention.max_age;
+        self.entries.retain(|dead_letter| {
+            now.duration_since(dead_letter.failed_at)
+                .map_or(true, |age| age <= max_age)
+        });
+
+        let excess = self
+            .entries
+            .len()
+            .saturating_sub(self.retention.max_entries);
+        if excess > 0 {
+            warn!(target: LOG_TARGET, "Dead-letter queue is full ({} entries), dropped the {} oldest.", self.retention.max_entries, excess);
+            self.entries.drain(..excess);
+        }
+    }
+
+    fn take(&mut self, id: u64) -> Option<DeadLetter> {
+        let position = self
+            .entries
+            .iter()
+            .position(|dead_letter| dead_letter.id == id)?;
+        self.entries.remove(position)
+    }
+
+    /// Puts back a dead letter previously taken from the queue.
+    fn restore(&mut self, dead_letter: DeadLetter) {
+        let position = self
+            .entries
+            .partition_point(|queued| queued.id < dead_letter.id);
+        self.entries.insert(position, dead_letter);
+    }
+}
+
+struct DeadLetterQueueInner {
+    dead_letters: DeadLetters,
+    /// Where to replay the dead letters of every listener registered, by task and event type names.
+    listeners: HashMap<(&'static str, &'static str), mpsc::UnboundedSender<DeadLetter>>,
+}
+
+/// Queue of the events whose handler returned an error, which can be listed and replayed.
+///
+/// Clones share the same queue, so it can be given to several listeners and queried from anywhere
+/// else (e.g. RPC methods).
+#[derive(Clone)]
+pub struct DeadLetterQueue {
+    inner: Arc<Mutex<DeadLetterQueueInner>>,
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::new(DeadLetterRetention::default())
+    }
+}
+
+impl DeadLetterQueue {
+    pub fn new(retention: DeadLetterRetention) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(DeadLetterQueueInner {
+                dead_letters: DeadLetters::new(retention),
+                listeners: HashMap::new(),
+            })),
+        }
+    }
+
+    /// The dead letters in the queue, from the oldest to the newest.
+    pub fn list(&self) -> Vec<DeadLetter> {
+        let mut inner = self.lock();
+        inner.dead_letters.prune(SystemTime::now());
+        inner.dead_letters.entries.iter().cloned().collect()
+    }
+
+    /// Removes the dead letter `id` from the queue and sends its event back to the listener of the
+    /// task that failed to handle it.
+    ///
+    /// The dead letter is kept in the queue if that listener is not running anymore.
+    pub fn replay(&self, id: u64) -> Result<()> {
+        let mut inner = self.lock();
+        let dead_letter = inner
+            .dead_letters
+            .take(id)
+            .ok_or_else(|| anyhow!("Dead letter {} not found", id))?;
+
+        let key = (dead_letter.task, dead_letter.event);
+        let Some(listener) = inner.listeners.get(&key) else {
+            inner.dead_letters.restore(dead_letter);
+            return Err(anyhow!("No {} listener for {} registered", key.1, key.0));
+        };
+
+        if let Err(mpsc::error::SendError(dead_letter)) = listener.send(dead_letter) {
+            inner.listeners.remove(&key);
+            inner.dead_letters.restore(dead_letter);
+            return Err(anyhow!("{} listener for {} is not running", key.1, key.0));
+        }
+
+        Ok(())
+    }
+
+    /// Removes the dead letter `id` from the queue without replaying it.
+    pub fn discard(&self, id: u64) -> Option<DeadLetter> {
+        self.lock().dead_letters.take(id)
+    }
+
+    /// Registers a listener of `T` events for the task `task`, returning where it captures the
+    /// events its handler fails on and where it receives them back when replayed.
+    ///
+    /// Dead letters are replayed to the last listener registered for the same task and event.
+    pub(crate) fn register<T>(
+        &self,
+        task: &'static str,
+    ) -> (DeadLetterCapture<T>, DeadLetterReplays<T>)
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let event = std::any::type_name::<T>();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.lock().listeners.insert((task, event), sender);
+
+        let capture = DeadLetterCapture {
+            queue: self.clone(),
+            task,
+            event,
+            serialize: |event| serde_json::to_vec(event),
+        };
+        let replays = DeadLetterReplays {
+            receiver,
+            deserialize: |payload| serde_json::from_slice(payload),
+        };
+        (capture, replays)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DeadLetterQueueInner> {
+        self.inner.lock().expect("Lock is not poisoned; qed")
+    }
+}
+
+/// Captures the events of a listener that its handler failed on into a [`DeadLetterQueue`].
+pub(crate) struct DeadLetterCapture<T> {
+    queue: DeadLetterQueue,
+    task: &'static str,
+    event: &'static str,
+    serialize: fn(&T) -> serde_json::Result<Vec<u8>>,
+}
+
+// Implemented manually since events don't need to be `Clone` for their capture to be.
+impl<T> Clone for DeadLetterCapture<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            task: self.task,
+            event: self.event,
+            serialize: self.serialize,
+        }
+    }
+}
+
+impl<T> DeadLetterCapture<T> {
+    /// Adds `event` to the dead-letter queue, after it was replayed `replays` times and its handler
+    /// failed with `error`.
+    pub fn capture(&self, event: &T, replays: u32, error: &anyhow::Error) {
+        let payload = match (self.serialize)(event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to serialize {} event for the dead-letter queue, it is lost: {:?}", self.event, e);
+                return;
+            }
+        };
+
+        let now = SystemTime::now();
+        let id = self.queue.lock().dead_letters.push(
+            DeadLetter {
+                id: 0,
+                task: self.task,
+                event: self.event,
+                payload,
+                error: format!("{:?}", error),
+                failed_at: now,
+                replays,
+            },
+            now,
+        );
+
+        warn!(target: LOG_TARGET, "{} failed to handle {} event, added to the dead-letter queue as {}: {:?}", self.task, self.event, id, error);
+    }
+}
+
+/// Receives the events of a listener replayed from a [`DeadLetterQueue`].
+pub(crate) struct DeadLetterReplays<T> {
+    receiver: mpsc::UnboundedReceiver<DeadLetter>,
+    deserialize: fn(&[u8]) -> serde_json::Result<T>,
+}
+
+impl<T> DeadLetterReplays<T> {
+    /// The next event replayed, with the amount of times it was replayed including this one.
+    ///
+    /// Dead letters that can't be deserialized anymore are dropped.
+    pub async fn recv(&mut self) -> Option<(T, u32)> {
+        loop {
+            let dead_letter = self.receiver.recv().await?;
+            match (self.deserialize)(&dead_letter.payload) {
+                Ok(event) => return Some((event, dead_letter.replays.saturating_add(1))),
+                Err(e) => {
+                    error!(target: LOG_TARGET, "Failed to deserialize dead letter {} as {} event, it is lost: {:?}", dead_letter.id, dead_letter.event, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dead_letter(failed_at: SystemTime) -> DeadLetter {
+        DeadLetter {
+            id: 0,
+            task: "task",
+            event: "event",
+            payload: b"1".to_vec(),
+            error: "error".into(),
+            failed_at,
+            replays: 0,
+        }
+    }
+
+    fn ids(dead_letters: &DeadLetters) -> Vec<u64> {
+        dead_letters.entries.iter().map(|d| d.id).collect()
+    }
+
+    #[test]
+    fn oldest_dead_letters_are_dropped_over_max_entries() {
+        let now = SystemTime::now();
+        let mut dead_letters = DeadLetters::new(DeadLetterRetention {
+            max_entries: 2,
+            ..Default::default()
+        });
+
+        assert_eq!(dead_letters.push(dead_letter(now), now), 0);
+        assert_eq!(dead_letters.push(dead_letter(now), now), 1);
+        assert_eq!(dead_letters.push(dead_letter(now), now), 2);
+        assert_eq!(ids(&dead_letters), vec![1, 2]);
+    }
+
+    #[test]
+    fn dead_letters_over_max_age_are_dropped() {
+        let now = SystemTime::now();
+        let mut dead_letters = DeadLetters::new(DeadLetterRetention {
+            max_age: Duration::from_secs(60),
+            ..Default::default()
+        });
+
+        dead_letters.push(dead_letter(now - Duration::from_secs(61)), now);
+        dead_letters.push(dead_letter(now - Duration::from_secs(60)), now);
+        assert_eq!(ids(&dead_letters), vec![1]);
+
+        dead_letters.prune(now + Duration::from_secs(1));
+        assert!(dead_letters.entries.is_empty());
+    }
+
+    #[test]
+    fn dead_letters_are_taken_by_id() {
+        let now = SystemTime::now();
+        let mut dead_letters = DeadLetters::new(DeadLetterRetention::default());
+        dead_letters.push(dead_letter(now), now);
+        dead_letters.push(dead_letter(now), now);
+
+        assert_eq!(dead_letters.take(0).map(|d| d.id), Some(0));
+        assert_eq!(dead_letters.take(0), None);
+        assert_eq!(ids(&dead_letters), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn replayed_dead_letters_reach_their_listener() {
+        let queue = DeadLetterQueue::default();
+        let (capture, mut replays) = queue.register::<u32>("task");
+
+        capture.capture(&7, 0, &anyhow!("failed"));
+        let dead_letter = queue.list().pop().expect("Event was captured");
+        assert_eq!(dead_letter.payload, b"7".to_vec());
+        assert!(dead_letter.error.contains("failed"));
+
+        queue
+            .replay(dead_letter.id)
+            .expect("Listener is registered");
+        assert!(queue.list().is_empty());
+        assert_eq!(replays.recv().await, Some((7, 1)));
+    }
+
+    #[test]
+    fn dead_letters_are_kept_when_their_listener_stopped() {
+        let queue = DeadLetterQueue::default();
+        let (capture, replays) = queue.register::<u32>("task");
+        capture.capture(&7, 0, &anyhow!("failed"));
+        drop(replays);
+
+        assert!(queue.replay(0).is_err());
+        assert_eq!(queue.list().len(), 1);
+        assert!(queue.replay(1).is_err());
+    }
+}
@@ -1,12 +1,18 @@
+use std::{collections::VecDeque, sync::Arc};
+
 use anyhow::Result;
 use sc_tracing::tracing::warn;
-use tokio::sync::broadcast;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::{broadcast, broadcast::error::RecvError, OwnedSemaphorePermit, Semaphore};
 
 use crate::{
     actor::{Actor, ActorHandle, TaskSpawner},
-    constants::MAX_PENDING_EVENTS,
+    constants::{MAX_CONCURRENT_EVENT_HANDLERS, MAX_PENDING_EVENTS},
+    dead_letter::{DeadLetterCapture, DeadLetterQueue, DeadLetterReplays},
 };
 
+pub use shc_actors_derive::EventBusMessage;
+
 pub trait EventBusMessage: Clone + Send + 'static {}
 
 #[derive(Clone)]
@@ -27,6 +33,13 @@ impl<T: EventBusMessage + Clone> EventBus<T> {
     }
 
     pub fn emit(&self, event: T) {
+        #[cfg(feature = "chaos")]
+        if let Some(crate::chaos::FaultAction::Drop) =
+            crate::chaos::inject(crate::chaos::EVENT_BUS_EMIT)
+        {
+            return;
+        }
+
         // We log that there is no listener.
         match self.sender.send(event) {
             Ok(_) => {}
@@ -73,10 +86,88 @@ pub trait EventHandler<E: EventBusMessage>: Clone + Send + 'static {
     }
 }
 
+/// What an [`EventBusListener`] does with a new event when its queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Drop the oldest event waiting in the queue to make room for the new one.
+    DropOldest,
+    /// Drop the new event.
+    DropNewest,
+    /// Stop receiving events until there is room in the queue.
+    ///
+    /// Emitters are never blocked, since the event bus is a broadcast channel. Events keep
+    /// accumulating in the event bus instead, and once more than [`MAX_PENDING_EVENTS`] are
+    /// pending there, the oldest ones are lost for this listener.
+    #[default]
+    Block,
+}
+
+/// Events received by an [`EventBusListener`] which are waiting for a free handler.
+struct ListenerQueue<T> {
+    events: VecDeque<T>,
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+}
+
+impl<T> ListenerQueue<T> {
+    fn new(capacity: usize, overflow_policy: OverflowPolicy) -> Self {
+        Self {
+            events: VecDeque::new(),
+            // An empty queue would never let any event through.
+            capacity: capacity.max(1),
+            overflow_policy,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.events.len() >= self.capacity
+    }
+
+    /// Whether the listener should stop receiving events until there is room in the queue.
+    fn is_blocked(&self) -> bool {
+        self.overflow_policy == OverflowPolicy::Block && self.is_full()
+    }
+
+    /// Adds an event to the queue, returning the event dropped to respect its capacity, if any.
+    fn push(&mut self, event: T) -> Option<T> {
+        if !self.is_full() {
+            self.events.push_back(event);
+            return None;
+        }
+
+        match self.overflow_policy {
+            OverflowPolicy::DropOldest => {
+                let oldest = self.events.pop_front();
+                self.events.push_back(event);
+                oldest
+            }
+            OverflowPolicy::DropNewest => Some(event),
+            // The listener does not receive events while blocked, so this is not reached.
+            OverflowPolicy::Block => {
+                self.events.push_back(event);
+                None
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.events.pop_front()
+    }
+}
+
 pub struct EventBusListener<T: EventBusMessage, E: EventHandler<T>> {
     spawner: TaskSpawner,
     receiver: broadcast::Receiver<T>,
     event_handler: E,
+    queue_capacity: usize,
+    overflow_policy: OverflowPolicy,
+    max_concurrent_handlers: usize,
+    dead_letter_capture: Option<DeadLetterCapture<T>>,
+    dead_letter_replays: Option<DeadLetterReplays<T>>,
 }
 
 impl<T: EventBusMessage, E: EventHandler<T> + Send + 'static> EventBusListener<T, E> {
@@ -85,25 +176,195 @@ impl<T: EventBusMessage, E: EventHandler<T> + Send + 'static> EventBusListener<T
             spawner: spawner.with_group("event-handler-worker"),
             event_handler,
             receiver,
+            queue_capacity: MAX_PENDING_EVENTS,
+            overflow_policy: OverflowPolicy::default(),
+            max_concurrent_handlers: MAX_CONCURRENT_EVENT_HANDLERS,
+            dead_letter_capture: None,
+            dead_letter_replays: None,
+        }
+    }
+
+    /// Sets the maximum amount of received events waiting for a free handler.
+    ///
+    /// Once the queue is full, new events are dealt with according to the [`OverflowPolicy`].
+    pub fn with_queue_capacity(self, queue_capacity: usize) -> Self {
+        Self {
+            queue_capacity,
+            ..self
+        }
+    }
+
+    /// Sets what to do with new events when the queue is full.
+    pub fn with_overflow_policy(self, overflow_policy: OverflowPolicy) -> Self {
+        Self {
+            overflow_policy,
+            ..self
+        }
+    }
+
+    /// Sets the maximum amount of events handled at the same time. Further events wait in the queue.
+    pub fn with_max_concurrent_handlers(self, max_concurrent_handlers: usize) -> Self {
+        Self {
+            max_concurrent_handlers: max_concurrent_handlers.max(1),
+            ..self
         }
     }
 
     async fn run(&mut self) {
-        while let Ok(event) = self.receiver.recv().await {
-            let mut cloned_event_handler = self.event_handler.clone();
-            self.spawner.spawn(async move {
-                match cloned_event_handler.handle_event(event).await {
-                    Ok(_) => {}
-                    Err(error) => {
-                        warn!("Task ended with error: {:?}", error);
+        let handler_slots = Arc::new(Semaphore::new(self.max_concurrent_handlers));
+        // Events are queued with the amount of times they were replayed from the dead-letter queue.
+        let mut queue = ListenerQueue::new(self.queue_capacity, self.overflow_policy);
+
+        loop {
+            let is_blocked = queue.is_blocked();
+
+            tokio::select! {
+                // Hand queued events to the handler first, to make room for new ones.
+                biased;
+
+                Ok(permit) = handler_slots.clone().acquire_owned(), if !queue.is_empty() => {
+                    if let Some((event, replays)) = queue.pop() {
+                        self.handle(event, replays, permit);
+                    }
+                }
+                Some((event, replays)) = next_replay(&mut self.dead_letter_replays), if !is_blocked => {
+                    if queue.push((event, replays)).is_some() {
+                        warn!(
+                            "Event queue of {} listener is full ({} events), dropped an event.",
+                            std::any::type_name::<T>(),
+                            self.queue_capacity
+                        );
                     }
                 }
-            });
+                received = self.receiver.recv(), if !is_blocked => match received {
+                    Ok(event) => {
+                        #[cfg(feature = "chaos")]
+                        if crate::chaos::inject_async(crate::chaos::EVENT_BUS_HANDLE).await {
+                            continue;
+                        }
+
+                        if queue.push((event, 0)).is_some() {
+                            warn!(
+                                "Event queue of {} listener is full ({} events), dropped an event.",
+                                std::any::type_name::<T>(),
+                                self.queue_capacity
+                            );
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(
+                            "{} listener fell behind the event bus and missed {} events.",
+                            std::any::type_name::<T>(),
+                            missed
+                        );
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
         }
     }
 
+    fn handle(&self, event: T, replays: u32, permit: OwnedSemaphorePermit) {
+        let mut cloned_event_handler = self.event_handler.clone();
+        let dead_letter_capture = self.dead_letter_capture.clone();
+        self.spawner.spawn(async move {
+            // The event is only kept for the dead-letter queue, if the listener has one.
+            let failed_event = dead_letter_capture.as_ref().map(|_| event.clone());
+            match cloned_event_handler.handle_event(event).await {
+                Ok(_) => {}
+                Err(error) => match (dead_letter_capture, failed_event) {
+                    (Some(capture), Some(event)) => capture.capture(&event, replays, &error),
+                    _ => warn!("Task ended with error: {:?}", error),
+                },
+            }
+
+            // The handler slot is freed once the event is handled.
+            drop(permit);
+        });
+    }
+
     pub fn start(mut self) {
         let spawner = self.spawner.with_group("event-bus-listener");
         spawner.spawn(async move { self.run().await });
     }
 }
+
+impl<T, E> EventBusListener<T, E>
+where
+    T: EventBusMessage + Serialize + DeserializeOwned,
+    E: EventHandler<T> + Send + 'static,
+{
+    /// Captures the events the handler fails on into `dead_letter_queue`, from which they can be
+    /// replayed into this listener. See [`crate::dead_letter`].
+    pub fn with_dead_letter_queue(self, dead_letter_queue: &DeadLetterQueue) -> Self {
+        let (capture, replays) = dead_letter_queue.register::<T>(std::any::type_name::<E>());
+        Self {
+            dead_letter_capture: Some(capture),
+            dead_letter_replays: Some(replays),
+            ..self
+        }
+    }
+}
+
+/// The next event replayed from the dead-letter queue of a listener, if it has one.
+async fn next_replay<T>(replays: &mut Option<DeadLetterReplays<T>>) -> Option<(T, u32)> {
+    match replays {
+        Some(replays) => replays.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_drop_oldest_keeps_newest_events() {
+        let mut queue = ListenerQueue::new(2, OverflowPolicy::DropOldest);
+
+        assert_eq!(queue.push(1), None);
+        assert_eq!(queue.push(2), None);
+        assert!(queue.is_full());
+        assert!(!queue.is_blocked());
+
+        assert_eq!(queue.push(3), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn queue_drop_newest_keeps_oldest_events() {
+        let mut queue = ListenerQueue::new(2, OverflowPolicy::DropNewest);
+
+        assert_eq!(queue.push(1), None);
+        assert_eq!(queue.push(2), None);
+        assert!(!queue.is_blocked());
+
+        assert_eq!(queue.push(3), Some(3));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn queue_block_blocks_until_there_is_room() {
+        let mut queue = ListenerQueue::new(2, OverflowPolicy::Block);
+
+        assert_eq!(queue.push(1), None);
+        assert!(!queue.is_blocked());
+        assert_eq!(queue.push(2), None);
+        assert!(queue.is_blocked());
+
+        assert_eq!(queue.pop(), Some(1));
+        assert!(!queue.is_blocked());
+    }
+
+    #[test]
+    fn queue_capacity_is_at_least_one() {
+        let mut queue = ListenerQueue::new(0, OverflowPolicy::DropNewest);
+
+        assert_eq!(queue.push(1), None);
+        assert_eq!(queue.push(2), Some(2));
+    }
+}
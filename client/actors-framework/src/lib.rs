@@ -1,3 +1,13 @@
+// Lets the derive macros refer to this crate by name from within it.
+extern crate self as shc_actors_framework;
+
 pub mod actor;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod constants;
+pub mod dead_letter;
 pub mod event_bus;
+pub mod request;
+pub mod supervisor;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -0,0 +1,240 @@
+//! Requests between actors and tasks over the event bus, expecting a response.
+//!
+//! A request is an [`EventBusMessage`] declaring the type of its response, by implementing
+//! [`EventBusRequest`], usually through the derive macro:
+//!
+//! ```ignore
+//! #[derive(Debug, Clone, EventBusMessage)]
+//! #[event_bus(response = Option<u64>)]
+//! pub struct QueryFileSize {
+//!     pub file_key: H256,
+//! }
+//! ```
+//!
+//! It is emitted wrapped in a [`RequestEnvelope`], which carries a correlation id and the channel
+//! to send the response back through, on an `EventBus<RequestEnvelope<R>>` provided like for any
+//! other event. [`EventBus::request`] (or [`Actor::request`](crate::actor::Actor::request)) emits
+//! the request and waits for the response. Tasks respond to requests by implementing
+//! [`RequestHandler`] and subscribing through the [`Responder`] adapter:
+//!
+//! ```ignore
+//! Responder(task).subscribe_to(&task_spawner, &actor_handle).start();
+//! ```
+//!
+//! Only the first response to a request is delivered, if several listeners respond to it. A request
+//! with no listener, or whose listeners all dropped it without responding, fails. Requesters that
+//! should not wait indefinitely for a response should wrap the request in a timeout.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::{anyhow, Result};
+use sc_tracing::tracing::debug;
+use tokio::sync::oneshot;
+
+use crate::event_bus::{EventBus, EventBusMessage, EventHandler};
+
+/// Correlation id of the next request emitted, shared by all event buses.
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An event expecting a response of type [`Self::Response`] from the listener handling it.
+pub trait EventBusRequest: EventBusMessage {
+    type Response: Send + 'static;
+}
+
+/// The response to a request, or the error its handler returned.
+type Reply<R> = std::result::Result<<R as EventBusRequest>::Response, String>;
+
+/// A request emitted on the event bus, with the channel to respond to it.
+pub struct RequestEnvelope<R: EventBusRequest> {
+    /// Identifier of the request, unique among all requests emitted by this node.
+    pub correlation_id: u64,
+    pub request: R,
+    reply: Arc<Mutex<Option<oneshot::Sender<Reply<R>>>>>,
+}
+
+// Implemented manually since responses don't need to be `Clone` for their envelope to be.
+impl<R: EventBusRequest> Clone for RequestEnvelope<R> {
+    fn clone(&self) -> Self {
+        Self {
+            correlation_id: self.correlation_id,
+            request: self.request.clone(),
+            reply: self.reply.clone(),
+        }
+    }
+}
+
+impl<R: EventBusRequest> EventBusMessage for RequestEnvelope<R> {}
+
+impl<R: EventBusRequest> RequestEnvelope<R> {
+    fn new(request: R) -> (Self, oneshot::Receiver<Reply<R>>) {
+        let (sender, receiver) = oneshot::channel();
+        let envelope = Self {
+            correlation_id: NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed),
+            request,
+            reply: Arc::new(Mutex::new(Some(sender))),
+        };
+        (envelope, receiver)
+    }
+
+    /// Sends `response` to the requester.
+    ///
+    /// Returns `false` if the request was already responded to, or the requester stopped waiting.
+    pub fn respond(&self, response: R::Response) -> bool {
+        self.reply(Ok(response))
+    }
+
+    /// Fails the request with `error`.
+    ///
+    /// Returns `false` if the request was already responded to, or the requester stopped waiting.
+    pub fn fail(&self, error: &anyhow::Error) -> bool {
+        self.reply(Err(format!("{:?}", error)))
+    }
+
+    fn reply(&self, reply: Reply<R>) -> bool {
+        let sender = self.reply.lock().expect("Lock is not poisoned; qed").take();
+        sender.is_some_and(|sender| sender.send(reply).is_ok())
+    }
+}
+
+impl<R: EventBusRequest> EventBus<RequestEnvelope<R>> {
+    /// Emits `request` and returns a future resolving to its response.
+    ///
+    /// The request is emitted right away, whether the future is polled or not.
+    pub fn request(
+        &self,
+        request: R,
+    ) -> impl Future<Output = Result<R::Response>> + Send + 'static {
+        let (envelope, response) = RequestEnvelope::new(request);
+        let correlation_id = envelope.correlation_id;
+        self.emit(envelope);
+
+        async move {
+            match response.await {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(error)) => Err(anyhow!(
+                    "{} request {} failed: {}",
+                    std::any::type_name::<R>(),
+                    correlation_id,
+                    error
+                )),
+                Err(_) => Err(anyhow!(
+                    "{} request {} was not responded to",
+                    std::any::type_name::<R>(),
+                    correlation_id
+                )),
+            }
+        }
+    }
+}
+
+/// A task responding to requests of type `R`.
+pub trait RequestHandler<R: EventBusRequest>: Clone + Send + 'static {
+    fn handle_request(&mut self, request: R) -> impl Future<Output = Result<R::Response>> + Send;
+}
+
+/// Adapter to subscribe a [`RequestHandler`] to the [`RequestEnvelope`]s of the requests it
+/// handles, responding to them with the result of the handler.
+#[derive(Clone)]
+pub struct Responder<H>(pub H);
+
+impl<R, H> EventHandler<RequestEnvelope<R>> for Responder<H>
+where
+    R: EventBusRequest,
+    H: RequestHandler<R>,
+{
+    async fn handle_event(&mut self, envelope: RequestEnvelope<R>) -> Result<()> {
+        match self.0.handle_request(envelope.request.clone()).await {
+            Ok(response) => {
+                if !envelope.respond(response) {
+                    debug!(
+                        "{} request {} was already responded to, or the requester stopped waiting.",
+                        std::any::type_name::<R>(),
+                        envelope.correlation_id
+                    );
+                }
+                Ok(())
+            }
+            Err(error) => {
+                envelope.fail(&error);
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+
+    #[derive(Debug, Clone, EventBusMessage)]
+    #[event_bus(response = u32)]
+    struct Double(u32);
+
+    #[derive(Clone)]
+    struct Doubler;
+
+    impl RequestHandler<Double> for Doubler {
+        async fn handle_request(&mut self, request: Double) -> Result<u32> {
+            if request.0 == 0 {
+                bail!("nothing to double");
+            }
+            Ok(request.0 * 2)
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_are_responded_to_by_their_handler() {
+        let event_bus = EventBus::<RequestEnvelope<Double>>::new();
+        let mut receiver = event_bus.subscribe();
+
+        let response = event_bus.request(Double(21));
+        let envelope = receiver.recv().await.expect("Request was emitted");
+        Responder(Doubler)
+            .handle_event(envelope)
+            .await
+            .expect("Request is handled");
+
+        assert_eq!(response.await.expect("Request is responded to"), 42);
+    }
+
+    #[tokio::test]
+    async fn failed_requests_carry_the_error_of_their_handler() {
+        let event_bus = EventBus::<RequestEnvelope<Double>>::new();
+        let mut receiver = event_bus.subscribe();
+
+        let response = event_bus.request(Double(0));
+        let envelope = receiver.recv().await.expect("Request was emitted");
+        assert!(Responder(Doubler).handle_event(envelope).await.is_err());
+
+        let error = response.await.expect_err("Request failed");
+        assert!(error.to_string().contains("nothing to double"));
+    }
+
+    #[tokio::test]
+    async fn requests_without_listener_fail() {
+        let event_bus = EventBus::<RequestEnvelope<Double>>::new();
+        assert!(event_bus.request(Double(21)).await.is_err());
+    }
+
+    #[test]
+    fn only_the_first_response_is_delivered() {
+        let (envelope, mut response) = RequestEnvelope::new(Double(21));
+        let other_listener_envelope = envelope.clone();
+
+        assert!(envelope.respond(42));
+        assert!(!other_listener_envelope.respond(43));
+        assert_eq!(response.try_recv(), Ok(Ok(42)));
+        assert_ne!(
+            envelope.correlation_id,
+            RequestEnvelope::new(Double(21)).0.correlation_id
+        );
+    }
+}
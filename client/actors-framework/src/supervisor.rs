@@ -0,0 +1,360 @@
+//! Supervision of actors, restarting their event loop when it panics or exits unexpectedly.
+//!
+//! A supervised actor is spawned with [`TaskSpawner::spawn_supervised_actor`], from a factory
+//! building a fresh instance of the actor for every (re)start, and a [`SupervisionPolicy`]. The
+//! [`ActorHandle`] returned stays valid across restarts: commands are forwarded to whichever
+//! instance is running. Commands being handled, or queued in the instance that stopped, are lost.
+//!
+//! Every restart, and the supervisor giving up, is emitted as an [`ActorLifecycleEvent`] and
+//! counted in the [`SupervisionRegistry`] shared by the [`TaskSpawner`]s.
+//!
+//! [`ActorHandle`]: crate::actor::ActorHandle
+//! [`TaskSpawner`]: crate::actor::TaskSpawner
+//! [`TaskSpawner::spawn_supervised_actor`]: crate::actor::TaskSpawner::spawn_supervised_actor
+
+use std::{
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{
+    future::{self, Either},
+    FutureExt, StreamExt,
+};
+use sc_tracing::tracing::{error, warn};
+use tokio::sync::broadcast;
+
+use crate::{
+    actor::{Actor, ActorEventLoop},
+    event_bus::{EventBus, EventBusMessage},
+};
+
+const LOG_TARGET: &str = "actor-supervisor";
+
+/// Delay before restarting a stopped actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Wait the same time before every restart.
+    Fixed(Duration),
+    /// Double the time waited after every restart, starting at `initial` and up to `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    /// The delay before the `restart`-th restart (starting at 1).
+    pub fn delay(&self, restart: u32) -> Duration {
+        match self {
+            Backoff::Fixed(delay) => *delay,
+            Backoff::Exponential { initial, max } => initial
+                .saturating_mul(2u32.saturating_pow(restart.saturating_sub(1)))
+                .min(*max),
+        }
+    }
+}
+
+/// How many times, and how fast, a stopped actor is restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupervisionPolicy {
+    /// Maximum amount of restarts. The supervisor gives up once the actor stops again after them.
+    pub max_restarts: u32,
+    pub backoff: Backoff,
+}
+
+impl Default for SupervisionPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            backoff: Backoff::Exponential {
+                initial: Duration::from_secs(1),
+                max: Duration::from_secs(60),
+            },
+        }
+    }
+}
+
+impl SupervisionPolicy {
+    pub fn new(max_restarts: u32) -> Self {
+        Self {
+            max_restarts,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_backoff(self, backoff: Backoff) -> Self {
+        Self { backoff, ..self }
+    }
+}
+
+/// Why the event loop of a supervised actor stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExitReason {
+    /// The event loop panicked, with the given message.
+    Panicked(String),
+    /// The event loop returned while its handles were still alive.
+    Exited,
+}
+
+/// Lifecycle of a supervised actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActorLifecycleEvent {
+    /// The actor stopped and is being restarted, for the `restarts`-th time.
+    Restarted {
+        actor: &'static str,
+        restarts: u32,
+        reason: ExitReason,
+    },
+    /// The actor stopped after `restarts` restarts, and won't be restarted again.
+    GaveUp {
+        actor: &'static str,
+        restarts: u32,
+        reason: ExitReason,
+    },
+}
+
+impl EventBusMessage for ActorLifecycleEvent {}
+
+/// Restart counts of the supervised actors, and the bus where their lifecycle events are emitted.
+///
+/// Shared by a [`TaskSpawner`](crate::actor::TaskSpawner) and all the spawners derived from it.
+#[derive(Clone, Default)]
+pub struct SupervisionRegistry {
+    restarts: Arc<Mutex<HashMap<&'static str, u32>>>,
+    lifecycle_events: EventBus<ActorLifecycleEvent>,
+}
+
+impl SupervisionRegistry {
+    /// Amount of restarts of every supervised actor, by actor name.
+    pub fn restart_counts(&self) -> HashMap<&'static str, u32> {
+        self.restarts
+            .lock()
+            .expect("Lock is not poisoned; qed")
+            .clone()
+    }
+
+    /// Subscribes to the lifecycle events of every supervised actor.
+    pub fn subscribe(&self) -> broadcast::Receiver<ActorLifecycleEvent> {
+        self.lifecycle_events.subscribe()
+    }
+
+    fn record_restart(&self, actor: &'static str) {
+        let mut restarts = self.restarts.lock().expect("Lock is not poisoned; qed");
+        *restarts.entry(actor).or_default() += 1;
+    }
+}
+
+/// Runs the actor built by `factory`, restarting it according to `policy` whenever its event loop
+/// stops while `receiver` is still open. Returns once every handle is dropped, or the supervisor
+/// gives up.
+pub(crate) async fn supervise<T, F>(
+    name: &'static str,
+    mut factory: F,
+    policy: SupervisionPolicy,
+    mut receiver: sc_utils::mpsc::TracingUnboundedReceiver<T::Message>,
+    queue_size_warning: usize,
+    registry: SupervisionRegistry,
+) where
+    T: Actor + Send + 'static,
+    F: FnMut() -> T + Send + 'static,
+{
+    let mut restarts = 0;
+
+    loop {
+        let (sender, instance_receiver) =
+            sc_utils::mpsc::tracing_unbounded(name, queue_size_warning);
+        let event_loop = T::EventLoop::new(factory(), instance_receiver);
+        let run = AssertUnwindSafe(event_loop.run()).catch_unwind();
+
+        let forward = async {
+            while let Some(message) = receiver.next().await {
+                if sender.unbounded_send(message).is_err() {
+                    // The instance stopped, so the message is lost.
+                    break;
+                }
+            }
+            // Close the channel of the instance, so that it stops once it handled every message.
+            drop(sender);
+        };
+
+        let reason = match future::select(Box::pin(run), Box::pin(forward)).await {
+            Either::Left((Ok(()), _)) => ExitReason::Exited,
+            Either::Left((Err(panic), _)) => ExitReason::Panicked(panic_message(panic)),
+            Either::Right(((), run)) => {
+                // Every handle was dropped. Let the instance handle the remaining messages.
+                if let Err(panic) = run.await {
+                    error!(target: LOG_TARGET, "Actor {} panicked while shutting down: {}", name, panic_message(panic));
+                }
+                return;
+            }
+        };
+
+        if restarts >= policy.max_restarts {
+            error!(
+                target: LOG_TARGET,
+                "Actor {} stopped ({:?}) after {} restarts, giving up",
+                name,
+                reason,
+                restarts
+            );
+            registry.lifecycle_events.emit(ActorLifecycleEvent::GaveUp {
+                actor: name,
+                restarts,
+                reason,
+            });
+            return;
+        }
+
+        restarts += 1;
+        registry.record_restart(name);
+        let delay = policy.backoff.delay(restarts);
+        warn!(
+            target: LOG_TARGET,
+            "Actor {} stopped ({:?}), restarting it in {:?} (restart {}/{})",
+            name,
+            reason,
+            delay,
+            restarts,
+            policy.max_restarts
+        );
+        registry
+            .lifecycle_events
+            .emit(ActorLifecycleEvent::Restarted {
+                actor: name,
+                restarts,
+                reason,
+            });
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn panic_message(panic: Box<dyn std::any::Any + Send>) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::EventLoop;
+
+    /// Panics when asked to, and reports every other message it handles.
+    struct FragileActor {
+        handled: tokio::sync::mpsc::UnboundedSender<u32>,
+    }
+
+    impl Actor for FragileActor {
+        type Message = Option<u32>;
+        type EventLoop = EventLoop<Self>;
+        type EventBusProvider = ();
+
+        fn handle_message(
+            &mut self,
+            message: Self::Message,
+        ) -> impl std::future::Future<Output = ()> + Send {
+            async move {
+                match message {
+                    Some(value) => self.handled.send(value).unwrap(),
+                    None => panic!("asked to panic"),
+                }
+            }
+        }
+
+        fn get_event_bus_provider(&self) -> &Self::EventBusProvider {
+            &()
+        }
+    }
+
+    fn spawn_fragile_actor(
+        policy: SupervisionPolicy,
+        registry: &SupervisionRegistry,
+    ) -> (
+        sc_utils::mpsc::TracingUnboundedSender<Option<u32>>,
+        tokio::sync::mpsc::UnboundedReceiver<u32>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        let (sender, receiver) = sc_utils::mpsc::tracing_unbounded("fragile-actor", 100);
+        let (handled, handled_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let supervisor = tokio::spawn(supervise::<FragileActor, _>(
+            "fragile-actor",
+            move || FragileActor {
+                handled: handled.clone(),
+            },
+            policy,
+            receiver,
+            100,
+            registry.clone(),
+        ));
+
+        (sender, handled_receiver, supervisor)
+    }
+
+    #[test]
+    fn exponential_backoff_is_capped() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+        };
+
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        assert_eq!(backoff.delay(2), Duration::from_secs(2));
+        assert_eq!(backoff.delay(3), Duration::from_secs(4));
+        assert_eq!(backoff.delay(4), Duration::from_secs(5));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn panicking_actor_is_restarted() {
+        let registry = SupervisionRegistry::default();
+        let mut lifecycle_events = registry.subscribe();
+        let (sender, mut handled, supervisor) =
+            spawn_fragile_actor(SupervisionPolicy::new(5), &registry);
+
+        sender.unbounded_send(None).unwrap();
+        assert_eq!(
+            lifecycle_events.recv().await.unwrap(),
+            ActorLifecycleEvent::Restarted {
+                actor: "fragile-actor",
+                restarts: 1,
+                reason: ExitReason::Panicked("asked to panic".to_string()),
+            }
+        );
+
+        // The same handle reaches the restarted instance.
+        sender.unbounded_send(Some(42)).unwrap();
+        assert_eq!(handled.recv().await, Some(42));
+        assert_eq!(registry.restart_counts().get("fragile-actor"), Some(&1));
+
+        // Dropping every handle stops the supervisor.
+        drop(sender);
+        supervisor.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn supervisor_gives_up_after_max_restarts() {
+        let registry = SupervisionRegistry::default();
+        let mut lifecycle_events = registry.subscribe();
+        let (sender, _handled, supervisor) = spawn_fragile_actor(
+            SupervisionPolicy::new(1).with_backoff(Backoff::Fixed(Duration::from_secs(1))),
+            &registry,
+        );
+
+        sender.unbounded_send(None).unwrap();
+        assert!(matches!(
+            lifecycle_events.recv().await.unwrap(),
+            ActorLifecycleEvent::Restarted { restarts: 1, .. }
+        ));
+
+        sender.unbounded_send(None).unwrap();
+        assert!(matches!(
+            lifecycle_events.recv().await.unwrap(),
+            ActorLifecycleEvent::GaveUp { restarts: 1, .. }
+        ));
+        supervisor.await.unwrap();
+        assert_eq!(registry.restart_counts().get("fragile-actor"), Some(&1));
+    }
+}
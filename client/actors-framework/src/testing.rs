@@ -0,0 +1,346 @@
+//! Utilities to test tasks and actors deterministically, without spawning the real services.
+//!
+//! Only compiled with the `testing` feature (or in this crate's own tests). It provides:
+//! - [`TestEventBusProvider`]: an in-memory event bus, to capture the events emitted by an actor or
+//!   to feed events to a task.
+//! - [`EventScript`]: a scripted sequence of events, replayed on an [`EventHandler`] with virtual
+//!   time, so that timeouts and delays in tasks do not slow tests down or make them flaky.
+//! - [`mock_actor_handle`] and [`CommandInterceptor`]: an [`ActorHandle`] whose commands are
+//!   intercepted instead of being handled by an actor, so that tests can assert on them and answer
+//!   them.
+//! - [`debug_snapshot`] and [`assert_debug_snapshot`]: helpers to compare sequences of events or
+//!   commands against an expected textual snapshot.
+//!
+//! Example:
+//! ```ignore
+//! #[tokio::test(start_paused = true)]
+//! async fn task_submits_proof_after_challenge() {
+//!     let (blockchain, mut commands) = mock_actor_handle::<BlockchainService>(event_bus_provider);
+//!     let task = BspSubmitProofTask::new(handler_with(blockchain));
+//!
+//!     let results = EventScript::new()
+//!         .then(challenge_event())
+//!         .after(Duration::from_secs(6), finalised_event())
+//!         .replay(task)
+//!         .await;
+//!     assert!(results.iter().all(Result::is_ok));
+//!
+//!     let command = commands.expect_command(|c| matches!(c, BlockchainServiceCommand::SubmitExtrinsic { .. }));
+//! }
+//! ```
+
+use std::{fmt::Debug, time::Duration};
+
+use anyhow::Result;
+use futures::StreamExt;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::{
+    actor::{Actor, ActorHandle},
+    event_bus::{EventBus, EventBusMessage, EventHandler, ProvidesEventBus},
+};
+
+/// Queue size after which the intercepted command channel warns. Irrelevant in tests.
+const INTERCEPTED_COMMANDS_QUEUE_WARNING_SIZE: usize = 1000;
+
+/// An event bus provider for a single event type, backed by an in-memory [`EventBus`].
+///
+/// Subscribes on creation, so that every event emitted afterwards can be collected.
+pub struct TestEventBusProvider<T: EventBusMessage> {
+    event_bus: EventBus<T>,
+    receiver: broadcast::Receiver<T>,
+}
+
+impl<T: EventBusMessage> Clone for TestEventBusProvider<T> {
+    fn clone(&self) -> Self {
+        Self {
+            event_bus: self.event_bus.clone(),
+            receiver: self.event_bus.subscribe(),
+        }
+    }
+}
+
+impl<T: EventBusMessage> Default for TestEventBusProvider<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: EventBusMessage> TestEventBusProvider<T> {
+    pub fn new() -> Self {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe();
+        Self {
+            event_bus,
+            receiver,
+        }
+    }
+
+    /// Emits an event, as the actor owning this event bus would.
+    pub fn emit(&self, event: T) {
+        self.event_bus.emit(event);
+    }
+
+    /// Returns the events emitted since the last call, in order.
+    pub fn take_emitted(&mut self) -> Vec<T> {
+        let mut events = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Lagged(skipped)) => {
+                    panic!("{} events were dropped by the test event bus", skipped)
+                }
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+        events
+    }
+}
+
+impl<T: EventBusMessage> ProvidesEventBus<T> for TestEventBusProvider<T> {
+    fn event_bus(&self) -> &EventBus<T> {
+        &self.event_bus
+    }
+}
+
+/// A scripted sequence of events, each one delivered after a given (virtual) delay since the
+/// previous one.
+pub struct EventScript<T: EventBusMessage> {
+    steps: Vec<(Duration, T)>,
+}
+
+impl<T: EventBusMessage> Default for EventScript<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: EventBusMessage> EventScript<T> {
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Delivers `event` right after the previous one.
+    pub fn then(self, event: T) -> Self {
+        self.after(Duration::ZERO, event)
+    }
+
+    /// Delivers `event` once `delay` has elapsed since the previous one.
+    pub fn after(mut self, delay: Duration, event: T) -> Self {
+        self.steps.push((delay, event));
+        self
+    }
+
+    /// Replays the script on `event_handler`, waiting for each event to be handled before
+    /// delivering the next one, and returns the result of handling each event.
+    ///
+    /// Delays are applied with [`tokio::time::sleep`], so they are virtual when the Tokio clock is
+    /// paused (i.e. `#[tokio::test(start_paused = true)]`), and the script runs instantly and
+    /// deterministically.
+    pub async fn replay<E: EventHandler<T>>(self, mut event_handler: E) -> Vec<Result<()>> {
+        let mut results = Vec::with_capacity(self.steps.len());
+        for (delay, event) in self.steps {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            results.push(event_handler.handle_event(event).await);
+        }
+        results
+    }
+}
+
+/// Intercepts the commands sent through an [`ActorHandle`] created with [`mock_actor_handle`].
+pub struct CommandInterceptor<M> {
+    receiver: sc_utils::mpsc::TracingUnboundedReceiver<M>,
+}
+
+impl<M: Debug> CommandInterceptor<M> {
+    /// Returns the next command sent, if any, without waiting.
+    pub fn try_next(&mut self) -> Option<M> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Waits for the next command to be sent. Returns `None` once all handles are dropped.
+    pub async fn next(&mut self) -> Option<M> {
+        self.receiver.next().await
+    }
+
+    /// Returns all the commands sent so far, in order.
+    pub fn drain(&mut self) -> Vec<M> {
+        std::iter::from_fn(|| self.try_next()).collect()
+    }
+
+    /// Returns the next command sent, panicking if there is none or if it doesn't match
+    /// `predicate`.
+    ///
+    /// Commands carrying a callback can be answered by the test through the returned command.
+    pub fn expect_command(&mut self, predicate: impl FnOnce(&M) -> bool) -> M {
+        match self.try_next() {
+            Some(command) if predicate(&command) => command,
+            Some(command) => panic!("Unexpected command sent to the actor: {:?}", command),
+            None => panic!("Expected a command to be sent to the actor, but none was"),
+        }
+    }
+
+    /// Panics if any command was sent and not consumed yet.
+    pub fn assert_no_commands(&mut self) {
+        let commands = self.drain();
+        assert!(
+            commands.is_empty(),
+            "Expected no commands to be sent to the actor, but got: {:?}",
+            commands
+        );
+    }
+}
+
+/// Creates an [`ActorHandle`] that is not backed by any actor. The commands sent through it are
+/// received by the returned [`CommandInterceptor`], and events are emitted through
+/// `event_bus_provider`.
+pub fn mock_actor_handle<A: Actor>(
+    event_bus_provider: A::EventBusProvider,
+) -> (ActorHandle<A>, CommandInterceptor<A::Message>) {
+    let (sender, receiver) = sc_utils::mpsc::tracing_unbounded(
+        "mock-actor-handle",
+        INTERCEPTED_COMMANDS_QUEUE_WARNING_SIZE,
+    );
+    (
+        ActorHandle::from_sender(sender, event_bus_provider),
+        CommandInterceptor { receiver },
+    )
+}
+
+/// Renders `items` as a snapshot: one `{:?}` line per item.
+pub fn debug_snapshot<T: Debug>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| format!("{:?}", item))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asserts that the snapshot of `items` matches `expected`.
+///
+/// Leading and trailing whitespace of each line of `expected` is ignored, as are empty lines, so
+/// that the expected snapshot can be written as an indented multi-line string.
+#[track_caller]
+pub fn assert_debug_snapshot<T: Debug>(items: &[T], expected: &str) {
+    let actual = debug_snapshot(items);
+    let expected = expected
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(
+        actual == expected,
+        "Snapshot mismatch.\n--- expected ---\n{}\n--- actual ---\n{}",
+        expected,
+        actual
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum TestEvent {
+        Ping(u32),
+    }
+
+    impl EventBusMessage for TestEvent {}
+
+    #[derive(Debug)]
+    enum TestCommand {
+        Pong(u32),
+    }
+
+    struct TestActor {
+        event_bus_provider: TestEventBusProvider<TestEvent>,
+    }
+
+    impl Actor for TestActor {
+        type Message = TestCommand;
+        type EventLoop = crate::actor::EventLoop<Self>;
+        type EventBusProvider = TestEventBusProvider<TestEvent>;
+
+        async fn handle_message(&mut self, _message: Self::Message) {}
+
+        fn get_event_bus_provider(&self) -> &Self::EventBusProvider {
+            &self.event_bus_provider
+        }
+    }
+
+    /// A task answering each ping with a pong command, and recording when it handled it.
+    #[derive(Clone)]
+    struct PongTask {
+        actor: ActorHandle<TestActor>,
+        handled_at: Arc<Mutex<Vec<Instant>>>,
+    }
+
+    impl EventHandler<TestEvent> for PongTask {
+        async fn handle_event(&mut self, event: TestEvent) -> Result<()> {
+            let TestEvent::Ping(n) = event;
+            self.handled_at.lock().unwrap().push(Instant::now());
+            self.actor.send(TestCommand::Pong(n)).await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scripted_events_are_replayed_in_virtual_time() {
+        let (actor, mut commands) = mock_actor_handle::<TestActor>(TestEventBusProvider::new());
+        let handled_at = Arc::new(Mutex::new(Vec::new()));
+        let task = PongTask {
+            actor,
+            handled_at: handled_at.clone(),
+        };
+
+        let start = Instant::now();
+        let results = EventScript::new()
+            .then(TestEvent::Ping(1))
+            .after(Duration::from_secs(60), TestEvent::Ping(2))
+            .replay(task)
+            .await;
+
+        assert!(results.iter().all(Result::is_ok));
+        let handled_at = handled_at.lock().unwrap();
+        assert_eq!(handled_at[0] - start, Duration::ZERO);
+        assert_eq!(handled_at[1] - start, Duration::from_secs(60));
+
+        assert_debug_snapshot(
+            &commands.drain(),
+            "
+            Pong(1)
+            Pong(2)
+            ",
+        );
+        commands.assert_no_commands();
+    }
+
+    #[test]
+    fn emitted_events_are_collected() {
+        let mut event_bus_provider = TestEventBusProvider::new();
+        event_bus_provider.emit(TestEvent::Ping(1));
+        event_bus_provider.emit(TestEvent::Ping(2));
+
+        assert_eq!(
+            event_bus_provider.take_emitted(),
+            vec![TestEvent::Ping(1), TestEvent::Ping(2)]
+        );
+        assert!(event_bus_provider.take_emitted().is_empty());
+    }
+
+    #[test]
+    fn expect_command_returns_matching_command() {
+        let (actor, mut commands) = mock_actor_handle::<TestActor>(TestEventBusProvider::new());
+        futures::executor::block_on(actor.send(TestCommand::Pong(7)));
+
+        let command = commands.expect_command(|c| matches!(c, TestCommand::Pong(7)));
+        assert!(matches!(command, TestCommand::Pong(7)));
+    }
+}
@@ -0,0 +1,289 @@
+//! Chaos fault injection points of the BlockchainService.
+//!
+//! Only compiled with the `chaos` feature. See [`shc_actors_framework::chaos`] for how faults are
+//! described and triggered. The BlockchainService hits these injection points:
+//! - [`COMMAND_POINT`]: for every command received. A [`FaultAction::Drop`] fault fails the
+//!   command, answering its requester with an error, and a [`FaultAction::Delay`] fault delays
+//!   processing it (and every command queued after it).
+//! - [`FOREST_ROOT_WRITE_LOCK_POINT`]: every time the Forest root write lock is about to be
+//!   granted. A [`FaultAction::Drop`] fault keeps the lock unassigned until the next check, and a
+//!   [`FaultAction::Delay`] fault keeps it unassigned until the first check after the delay,
+//!   simulating contention in both cases.
+
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use sc_tracing::tracing::debug;
+use shc_actors_framework::chaos::FaultAction;
+
+use crate::{commands::BlockchainServiceCommand, handler::LOG_TARGET};
+
+/// Injection point hit for every command received by the BlockchainService.
+pub(crate) const COMMAND_POINT: &str = "blockchain_service::command";
+
+/// Injection point hit every time the Forest root write lock is about to be granted.
+pub(crate) const FOREST_ROOT_WRITE_LOCK_POINT: &str = "blockchain_service::forest_root_write_lock";
+
+/// Applies the fault triggered at [`COMMAND_POINT`] to `command`, if any.
+///
+/// Returns the command if it still has to be processed.
+pub(crate) async fn apply_command_fault(
+    command: BlockchainServiceCommand,
+    fault: Option<FaultAction>,
+) -> Option<BlockchainServiceCommand> {
+    match fault {
+        None => Some(command),
+        Some(FaultAction::Delay { millis }) => {
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            Some(command)
+        }
+        Some(FaultAction::Drop) => command.fail(),
+    }
+}
+
+impl BlockchainServiceCommand {
+    /// Answers the requester of this command with an error, as if processing it failed.
+    ///
+    /// Only commands whose callback can carry an error can be failed. Any other command is
+    /// returned back, to be processed as usual, since dropping it would leave its requester
+    /// waiting on a callback that never arrives.
+    fn fail(self) -> Option<Self> {
+        let error = || anyhow!("Command dropped by an injected chaos fault");
+
+        // Sending only fails if the requester is gone, in which case there is no one to notify.
+        match self {
+            Self::SendExtrinsic { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::GetExtrinsicFromBlock { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::UnwatchExtrinsic { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueueSubmitProofRequest { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueueConfirmBspRequest { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueueMspRespondStorageRequest { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueueStopStoringForInsolventUserRequest { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueryStorageProviderId { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueryWorstCaseScenarioSlashableAmount { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QuerySlashAmountPerMaxFileSize { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueryCurrentPricePerGigaUnitPerTick { callback, .. } => {
+                let _ = callback.send(Err(error()));
+            }
+            Self::QueryPendingStorageRequestsForProvider { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryStorageRequestsByBucket { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryStorageRequest { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryHasFileReadAccess { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryChallengesFromSeed { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryForestChallengesFromSeed { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryLastCheckpointChallengeTick { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            Self::QueryMaxProofSize { callback, .. } => {
+                let _ = callback.send(Err(sp_api::ApiError::Application(error().into())));
+            }
+            command => {
+                debug!(
+                    target: LOG_TARGET,
+                    "🐒 Chaos fault cannot fail this command, processing it as usual"
+                );
+                return Some(command);
+            }
+        }
+
+        None
+    }
+}
+
+/// Keeps the Forest root write lock unassigned while a [`FaultAction::Delay`] fault triggered at
+/// [`FOREST_ROOT_WRITE_LOCK_POINT`] lasts.
+#[derive(Debug, Default)]
+pub(crate) struct ForestRootWriteLockFault {
+    /// Until when the lock is kept unassigned.
+    delayed_until: Option<Instant>,
+}
+
+impl ForestRootWriteLockFault {
+    /// Whether the lock has to be kept unassigned at `now`.
+    ///
+    /// `inject` hits the injection point, and is only called if no delay is ongoing, so that a
+    /// delay is not extended by the checks made while it lasts.
+    pub(crate) fn hold_lock(
+        &mut self,
+        now: Instant,
+        inject: impl FnOnce() -> Option<FaultAction>,
+    ) -> bool {
+        if let Some(delayed_until) = self.delayed_until {
+            if now < delayed_until {
+                return true;
+            }
+            self.delayed_until = None;
+            return false;
+        }
+
+        match inject() {
+            None => false,
+            Some(FaultAction::Drop) => true,
+            Some(FaultAction::Delay { millis }) => {
+                self.delayed_until = Some(now + Duration::from_millis(millis));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shc_actors_framework::chaos::{Chaos, ChaosScenario, Fault};
+
+    use super::*;
+
+    fn chaos(point: &str, action: FaultAction) -> Chaos {
+        Chaos::new(ChaosScenario {
+            seed: 0,
+            faults: vec![Fault {
+                point: point.to_string(),
+                probability: 1.0,
+                action,
+            }],
+        })
+    }
+
+    #[tokio::test]
+    async fn drop_fault_at_command_point_answers_with_an_error() {
+        let chaos = chaos(COMMAND_POINT, FaultAction::Drop);
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = BlockchainServiceCommand::UnwatchExtrinsic {
+            subscription_id: 1.into(),
+            callback,
+        };
+
+        let command = apply_command_fault(command, chaos.inject(COMMAND_POINT)).await;
+
+        assert!(command.is_none());
+        assert!(rx.await.expect("callback is answered").is_err());
+    }
+
+    #[tokio::test]
+    async fn drop_fault_at_command_point_answers_runtime_api_queries_with_an_error() {
+        let chaos = chaos(COMMAND_POINT, FaultAction::Drop);
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = BlockchainServiceCommand::QueryMaxProofSize { callback };
+
+        let command = apply_command_fault(command, chaos.inject(COMMAND_POINT)).await;
+
+        assert!(command.is_none());
+        assert!(matches!(
+            rx.await.expect("callback is answered"),
+            Err(sp_api::ApiError::Application(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn drop_fault_at_command_point_processes_commands_that_cannot_fail() {
+        let chaos = chaos(COMMAND_POINT, FaultAction::Drop);
+        let (callback, _rx) = tokio::sync::oneshot::channel();
+        let command = BlockchainServiceCommand::GetBestBlockInfo { callback };
+
+        let command = apply_command_fault(command, chaos.inject(COMMAND_POINT)).await;
+
+        assert!(matches!(
+            command,
+            Some(BlockchainServiceCommand::GetBestBlockInfo { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn delay_fault_at_command_point_delays_the_command() {
+        let chaos = chaos(COMMAND_POINT, FaultAction::Delay { millis: 200 });
+        let (callback, _rx) = tokio::sync::oneshot::channel();
+        let command = BlockchainServiceCommand::GetBestBlockInfo { callback };
+
+        let start = tokio::time::Instant::now();
+        let command = apply_command_fault(command, chaos.inject(COMMAND_POINT)).await;
+
+        assert!(command.is_some());
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn commands_are_untouched_without_a_fault_at_command_point() {
+        let chaos = chaos(FOREST_ROOT_WRITE_LOCK_POINT, FaultAction::Drop);
+        let (callback, mut rx) = tokio::sync::oneshot::channel();
+        let command = BlockchainServiceCommand::UnwatchExtrinsic {
+            subscription_id: 1.into(),
+            callback,
+        };
+
+        let command = apply_command_fault(command, chaos.inject(COMMAND_POINT)).await;
+
+        assert!(command.is_some());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn drop_fault_at_forest_root_write_lock_point_holds_the_lock_once() {
+        let chaos = chaos(FOREST_ROOT_WRITE_LOCK_POINT, FaultAction::Drop);
+        let mut fault = ForestRootWriteLockFault::default();
+        let now = Instant::now();
+
+        assert!(fault.hold_lock(now, || chaos.inject(FOREST_ROOT_WRITE_LOCK_POINT)));
+        // Without a fault triggered in the next check, the lock is granted.
+        assert!(!fault.hold_lock(now, || None));
+    }
+
+    #[test]
+    fn delay_fault_at_forest_root_write_lock_point_holds_the_lock_until_it_elapses() {
+        let chaos = chaos(
+            FOREST_ROOT_WRITE_LOCK_POINT,
+            FaultAction::Delay { millis: 200 },
+        );
+        let mut fault = ForestRootWriteLockFault::default();
+        let now = Instant::now();
+
+        assert!(fault.hold_lock(now, || chaos.inject(FOREST_ROOT_WRITE_LOCK_POINT)));
+        // Checks made while the delay lasts do not hit the injection point again.
+        assert!(fault.hold_lock(now + Duration::from_millis(100), || {
+            panic!("injection point hit during a delay")
+        }));
+        assert!(!fault.hold_lock(now + Duration::from_millis(200), || {
+            panic!("injection point hit when the delay elapsed")
+        }));
+    }
+
+    #[test]
+    fn forest_root_write_lock_is_granted_without_a_fault_at_its_point() {
+        let chaos = chaos(COMMAND_POINT, FaultAction::Delay { millis: 200 });
+        let mut fault = ForestRootWriteLockFault::default();
+
+        assert!(!fault.hold_lock(Instant::now(), || chaos
+            .inject(FOREST_ROOT_WRITE_LOCK_POINT)));
+    }
+}
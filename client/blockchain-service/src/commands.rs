@@ -8,8 +8,10 @@ use sp_api::ApiError;
 use sp_core::H256;
 
 use pallet_file_system_runtime_api::{
-    IsStorageRequestOpenToVolunteersError, QueryBspConfirmChunksToProveForFileError,
-    QueryFileEarliestVolunteerTickError, QueryMspConfirmChunksToProveForFileError,
+    IsStorageRequestOpenToVolunteersError, OpenStorageRequest,
+    QueryBspConfirmChunksToProveForFileError, QueryFileEarliestVolunteerTickError,
+    QueryMspConfirmChunksToProveForFileError, QueryStorageRequestReplicationStatusError,
+    StorageRequestReplicationStatus,
 };
 use pallet_payment_streams_runtime_api::GetUsersWithDebtOverThresholdError;
 use pallet_proofs_dealer_runtime_api::{
@@ -21,18 +23,21 @@ use pallet_storage_providers_runtime_api::{
 };
 use shc_actors_framework::actor::ActorHandle;
 use shc_common::types::{
-    BlockNumber, BucketId, ChunkId, ForestLeaf, MainStorageProviderId, ProofsDealerProviderId,
-    ProviderId, RandomnessOutput, StorageHubEventsVec, StorageProviderId, TickNumber,
-    TrieRemoveMutation,
+    BackupStorageProviderId, BlockNumber, BucketId, ChunkId, ForestLeaf, MainStorageProviderId,
+    ProofsDealerProviderId, ProviderId, RandomnessOutput, StorageHubEventsVec, StorageProviderId,
+    StorageRequestMetadata, TickNumber, TrieRemoveMutation,
 };
 use storage_hub_runtime::{AccountId, Balance, StorageDataUnit};
 
 use super::{
     handler::BlockchainService,
+    outbound_queue::OutboundExtrinsic,
+    pending_operations::OperationKey,
     transaction::SubmittedTransaction,
     types::{
-        BestBlockInfo, ConfirmStoringRequest, Extrinsic, ExtrinsicResult, RespondStorageRequest,
-        RetryStrategy, StopStoringForInsolventUserRequest, SubmitProofRequest, Tip,
+        BestBlockInfo, ConfirmStoringRequest, Extrinsic, ExtrinsicResult,
+        ForestRootWriteLockHolder, RespondStorageRequest, RetryStrategy,
+        StopStoringForInsolventUserRequest, SubmitProofRequest, Tip,
     },
 };
 
@@ -70,6 +75,52 @@ pub enum BlockchainServiceCommand {
         file_key: H256,
         callback: tokio::sync::oneshot::Sender<Result<bool, IsStorageRequestOpenToVolunteersError>>,
     },
+    QueryStorageRequestReplicationStatus {
+        file_key: H256,
+        callback: tokio::sync::oneshot::Sender<
+            Result<
+                StorageRequestReplicationStatus<TickNumber, u32>,
+                QueryStorageRequestReplicationStatusError,
+            >,
+        >,
+    },
+    QueryPendingStorageRequestsForProvider {
+        provider_id: ProviderId,
+        start_after: Option<H256>,
+        limit: u32,
+        callback: tokio::sync::oneshot::Sender<
+            Result<
+                Vec<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+                ApiError,
+            >,
+        >,
+    },
+    QueryStorageRequestsByBucket {
+        bucket_id: BucketId,
+        start_after: Option<H256>,
+        limit: u32,
+        callback: tokio::sync::oneshot::Sender<
+            Result<
+                Vec<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+                ApiError,
+            >,
+        >,
+    },
+    QueryStorageRequest {
+        file_key: H256,
+        callback: tokio::sync::oneshot::Sender<
+            Result<
+                Option<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+                ApiError,
+            >,
+        >,
+    },
+    QueryHasFileReadAccess {
+        bucket_id: BucketId,
+        file_key: H256,
+        account: AccountId,
+        callback: tokio::sync::oneshot::Sender<Result<bool, ApiError>>,
+    },
     QueryFileEarliestVolunteerTick {
         bsp_id: ProviderId,
         file_key: H256,
@@ -146,6 +197,9 @@ pub enum BlockchainServiceCommand {
     QueryLastCheckpointChallengeTick {
         callback: tokio::sync::oneshot::Sender<Result<BlockNumber, ApiError>>,
     },
+    QueryMaxProofSize {
+        callback: tokio::sync::oneshot::Sender<Result<u32, ApiError>>,
+    },
     QueryLastCheckpointChallenges {
         tick: BlockNumber,
         callback: tokio::sync::oneshot::Sender<
@@ -186,6 +240,9 @@ pub enum BlockchainServiceCommand {
     QuerySlashAmountPerMaxFileSize {
         callback: tokio::sync::oneshot::Sender<Result<Balance>>,
     },
+    QueryCurrentPricePerGigaUnitPerTick {
+        callback: tokio::sync::oneshot::Sender<Result<Balance>>,
+    },
     QueryMspIdOfBucketId {
         bucket_id: BucketId,
         callback: tokio::sync::oneshot::Sender<
@@ -196,6 +253,16 @@ pub enum BlockchainServiceCommand {
         forest_root_write_tx: tokio::sync::oneshot::Sender<()>,
         callback: tokio::sync::oneshot::Sender<Result<()>>,
     },
+    IsOperationPending {
+        key: OperationKey,
+        callback: tokio::sync::oneshot::Sender<bool>,
+    },
+    GetForestRootWriteLockHolder {
+        callback: tokio::sync::oneshot::Sender<Option<ForestRootWriteLockHolder>>,
+    },
+    GetOutboundExtrinsicQueue {
+        callback: tokio::sync::oneshot::Sender<Vec<OutboundExtrinsic>>,
+    },
 }
 
 /// Interface for interacting with the BlockchainService actor.
@@ -230,6 +297,61 @@ pub trait BlockchainServiceInterface {
         file_key: H256,
     ) -> Result<bool, IsStorageRequestOpenToVolunteersError>;
 
+    /// Query the replication progress of a storage request.
+    async fn query_storage_request_replication_status(
+        &self,
+        file_key: H256,
+    ) -> Result<
+        StorageRequestReplicationStatus<TickNumber, u32>,
+        QueryStorageRequestReplicationStatusError,
+    >;
+
+    /// Query a page of the open storage requests waiting on a Provider, starting after the
+    /// storage request of `start_after`.
+    ///
+    /// For an MSP, these are the ones it has not accepted yet, and for a BSP, the ones it
+    /// volunteered for but has not confirmed yet.
+    async fn query_pending_storage_requests_for_provider(
+        &self,
+        provider_id: ProviderId,
+        start_after: Option<H256>,
+        limit: u32,
+    ) -> Result<
+        Vec<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+        ApiError,
+    >;
+
+    /// Query a page of the open storage requests for files of a bucket, starting after the storage
+    /// request of `start_after`.
+    async fn query_storage_requests_by_bucket(
+        &self,
+        bucket_id: BucketId,
+        start_after: Option<H256>,
+        limit: u32,
+    ) -> Result<
+        Vec<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+        ApiError,
+    >;
+
+    /// Query the open storage request for `file_key`, along with the BSPs that volunteered for it.
+    async fn query_storage_request(
+        &self,
+        file_key: H256,
+    ) -> Result<
+        Option<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+        ApiError,
+    >;
+
+    /// Query whether `account` has read access to the file `file_key` of `bucket_id`, either
+    /// because the bucket is public, through the access list of the file, by holding an item of
+    /// the bucket's read-access NFT collection or through an unexpired access grant.
+    async fn query_has_file_read_access(
+        &self,
+        bucket_id: BucketId,
+        file_key: H256,
+        account: AccountId,
+    ) -> Result<bool, ApiError>;
+
     /// Query the earliest tick number that a file was volunteered for storage.
     async fn query_file_earliest_volunteer_tick(
         &self,
@@ -320,6 +442,9 @@ pub trait BlockchainServiceInterface {
     /// Query the last checkpoint tick.
     async fn query_last_checkpoint_challenge_tick(&self) -> Result<BlockNumber, ApiError>;
 
+    /// Query the maximum encoded size, in bytes, of a proof that can be submitted.
+    async fn query_max_proof_size(&self) -> Result<u32, ApiError>;
+
     /// Query the checkpoint challenges for a given tick.
     async fn query_last_checkpoint_challenges(
         &self,
@@ -364,6 +489,9 @@ pub trait BlockchainServiceInterface {
 
     async fn query_slash_amount_per_max_file_size(&self) -> Result<Balance>;
 
+    /// Query the current price per giga-unit of data stored per tick, paid to BSPs.
+    async fn query_current_price_per_giga_unit_per_tick(&self) -> Result<Balance>;
+
     /// Helper function to check if an extrinsic failed or succeeded in a block.
     fn extrinsic_result(extrinsic: Extrinsic) -> Result<ExtrinsicResult>;
 
@@ -387,6 +515,17 @@ pub trait BlockchainServiceInterface {
         &self,
         forest_root_write_tx: tokio::sync::oneshot::Sender<()>,
     ) -> Result<()>;
+
+    /// Check if there is an extrinsic sent by this node still in flight that operates on `key`.
+    ///
+    /// Used to defer or cancel sending an extrinsic that would conflict with it.
+    async fn is_operation_pending(&self, key: OperationKey) -> bool;
+
+    /// Get the task currently holding the Forest root write lock, if any.
+    async fn get_forest_root_write_lock_holder(&self) -> Option<ForestRootWriteLockHolder>;
+
+    /// Get the extrinsics sent by this node that are not finalised yet, by ascending nonce.
+    async fn get_outbound_extrinsic_queue(&self) -> Vec<OutboundExtrinsic>;
 }
 
 /// Implement the BlockchainServiceInterface for the ActorHandle<BlockchainService>.
@@ -475,6 +614,95 @@ where
         rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
     }
 
+    async fn query_storage_request_replication_status(
+        &self,
+        file_key: H256,
+    ) -> Result<
+        StorageRequestReplicationStatus<TickNumber, u32>,
+        QueryStorageRequestReplicationStatusError,
+    > {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        // Build command to send to blockchain service.
+        let message =
+            BlockchainServiceCommand::QueryStorageRequestReplicationStatus { file_key, callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
+    async fn query_pending_storage_requests_for_provider(
+        &self,
+        provider_id: ProviderId,
+        start_after: Option<H256>,
+        limit: u32,
+    ) -> Result<
+        Vec<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+        ApiError,
+    > {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        // Build command to send to blockchain service.
+        let message = BlockchainServiceCommand::QueryPendingStorageRequestsForProvider {
+            provider_id,
+            start_after,
+            limit,
+            callback,
+        };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
+    async fn query_storage_requests_by_bucket(
+        &self,
+        bucket_id: BucketId,
+        start_after: Option<H256>,
+        limit: u32,
+    ) -> Result<
+        Vec<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+        ApiError,
+    > {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        // Build command to send to blockchain service.
+        let message = BlockchainServiceCommand::QueryStorageRequestsByBucket {
+            bucket_id,
+            start_after,
+            limit,
+            callback,
+        };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
+    async fn query_storage_request(
+        &self,
+        file_key: H256,
+    ) -> Result<
+        Option<OpenStorageRequest<H256, StorageRequestMetadata, BackupStorageProviderId>>,
+        ApiError,
+    > {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        // Build command to send to blockchain service.
+        let message = BlockchainServiceCommand::QueryStorageRequest { file_key, callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
+    async fn query_has_file_read_access(
+        &self,
+        bucket_id: BucketId,
+        file_key: H256,
+        account: AccountId,
+    ) -> Result<bool, ApiError> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        // Build command to send to blockchain service.
+        let message = BlockchainServiceCommand::QueryHasFileReadAccess {
+            bucket_id,
+            file_key,
+            account,
+            callback,
+        };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
     async fn query_file_earliest_volunteer_tick(
         &self,
         bsp_id: ProviderId,
@@ -670,6 +898,13 @@ where
         rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
     }
 
+    async fn query_max_proof_size(&self) -> Result<u32, ApiError> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = BlockchainServiceCommand::QueryMaxProofSize { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
     async fn query_last_checkpoint_challenges(
         &self,
         tick: BlockNumber,
@@ -767,6 +1002,13 @@ where
         rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
     }
 
+    async fn query_current_price_per_giga_unit_per_tick(&self) -> Result<Balance> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = BlockchainServiceCommand::QueryCurrentPricePerGigaUnitPerTick { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
     fn extrinsic_result(extrinsic: Extrinsic) -> Result<ExtrinsicResult> {
         for ev in extrinsic.events {
             match ev.event {
@@ -869,4 +1111,25 @@ where
         self.send(message).await;
         rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
     }
+
+    async fn is_operation_pending(&self, key: OperationKey) -> bool {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = BlockchainServiceCommand::IsOperationPending { key, callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
+    async fn get_forest_root_write_lock_holder(&self) -> Option<ForestRootWriteLockHolder> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = BlockchainServiceCommand::GetForestRootWriteLockHolder { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
+
+    async fn get_outbound_extrinsic_queue(&self) -> Vec<OutboundExtrinsic> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = BlockchainServiceCommand::GetOutboundExtrinsicQueue { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from BlockchainService. Probably means BlockchainService has crashed.")
+    }
 }
@@ -6,9 +6,10 @@ use shc_common::types::{
     PeerIds, ProofsDealerProviderId, ProviderId, RandomnessOutput, StorageData, TrieMutation,
     TrieRemoveMutation,
 };
+use shc_telemetry::{flow_span, Flow, FlowSpan};
 use sp_core::H256;
 use sp_runtime::AccountId32;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::{oneshot, Mutex};
 
 use crate::types::{ConfirmStoringRequest, RespondStorageRequest};
@@ -97,6 +98,18 @@ pub struct AcceptedBspVolunteer {
 
 impl EventBusMessage for AcceptedBspVolunteer {}
 
+/// BSP confirmed storing files event.
+///
+/// This event is emitted when a BSP confirms storing files, only if this node is not managing a
+/// Provider (i.e. it is run by a user), to follow the replication of its storage requests.
+#[derive(Debug, Clone)]
+pub struct BspConfirmedStoring {
+    pub bsp_id: H256,
+    pub confirmed_file_keys: Vec<FileKey>,
+}
+
+impl EventBusMessage for BspConfirmedStoring {}
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub enum ForestWriteLockTaskData {
     SubmitProofRequest(ProcessSubmitProofRequestData),
@@ -129,6 +142,42 @@ impl From<ProcessStopStoringForInsolventUserRequestData> for ForestWriteLockTask
     }
 }
 
+impl ForestWriteLockTaskData {
+    /// Label of the kind of task that holds the Forest root write lock to process this data.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::SubmitProofRequest(_) => "submit_proof",
+            Self::ConfirmStoringRequest(_) => "confirm_storing",
+            Self::MspRespondStorageRequest(_) => "msp_respond_storage_request",
+            Self::StopStoringForInsolventUserRequest(_) => "stop_storing_for_insolvent_user",
+        }
+    }
+
+    /// Opens a span in the trace of each flow this data belongs to, recording how long its task
+    /// holds the Forest root write lock.
+    pub fn forest_root_write_lock_spans(&self) -> Vec<FlowSpan> {
+        const STEP: &str = "forest_root_write_lock";
+        match self {
+            Self::SubmitProofRequest(data) => vec![flow_span(
+                Flow::ProofSubmission,
+                STEP,
+                (data.provider_id, data.tick),
+            )],
+            Self::ConfirmStoringRequest(data) => data
+                .confirm_storing_requests
+                .iter()
+                .map(|request| flow_span(Flow::StorageRequest, STEP, request.file_key))
+                .collect(),
+            Self::MspRespondStorageRequest(data) => data
+                .respond_storing_requests
+                .iter()
+                .map(|request| flow_span(Flow::StorageRequest, STEP, request.file_key))
+                .collect(),
+            Self::StopStoringForInsolventUserRequest(_) => Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct ProcessSubmitProofRequestData {
     pub provider_id: ProofsDealerProviderId,
@@ -276,12 +325,55 @@ pub struct MoveBucketAccepted {
 }
 impl EventBusMessage for MoveBucketAccepted {}
 
+/// Move bucket accepted event in a finalised block.
+///
+/// This event is emitted when a finalised block is received by the Blockchain service, in which
+/// there is a `MoveBucketAccepted` event for a bucket moved to an MSP other than the one this node
+/// is tracking. The bucket might have been stored by this node before the move.
+#[derive(Debug, Clone)]
+pub struct FinalisedMoveBucketAccepted {
+    pub bucket_id: BucketId,
+    /// The MSP the bucket was moved to.
+    pub new_msp_id: ProviderId,
+}
+
+impl EventBusMessage for FinalisedMoveBucketAccepted {}
+
 #[derive(Debug, Clone)]
 pub struct MoveBucketExpired {
     pub bucket_id: BucketId,
     pub msp_id: ProviderId,
 }
 impl EventBusMessage for MoveBucketExpired {}
+
+/// Bucket frozen event.
+///
+/// This event is emitted when governance freezes a bucket stored by the MSP managed by this node,
+/// or owned by the user running it. No files can be added to or deleted from a frozen bucket, and
+/// it can't be moved, until it is unfrozen.
+#[derive(Debug, Clone)]
+pub struct BucketFrozen {
+    pub bucket_id: BucketId,
+    /// Account ID owner of the bucket.
+    pub owner: AccountId32,
+    /// MSP ID storing the bucket, if any.
+    pub msp_id: Option<ProviderId>,
+}
+impl EventBusMessage for BucketFrozen {}
+
+/// Bucket unfrozen event.
+///
+/// This event is emitted when governance unfreezes a bucket stored by the MSP managed by this
+/// node, or owned by the user running it.
+#[derive(Debug, Clone)]
+pub struct BucketUnfrozen {
+    pub bucket_id: BucketId,
+    /// Account ID owner of the bucket.
+    pub owner: AccountId32,
+    /// MSP ID storing the bucket, if any.
+    pub msp_id: Option<ProviderId>,
+}
+impl EventBusMessage for BucketUnfrozen {}
 /// BSP stopped storing a specific file.
 ///
 /// This event is emitted when a BSP confirm stop storing a file.
@@ -314,6 +406,62 @@ pub struct NotifyPeriod {}
 
 impl EventBusMessage for NotifyPeriod {}
 
+/// Safe mode entered event.
+///
+/// This event is emitted when no block has been finalised for longer than the partition timeout,
+/// so the node is assumed to be partitioned from the network. Until [`SafeModeExited`] is
+/// emitted, the Forest root write lock is not granted to any task.
+#[derive(Debug, Clone)]
+pub struct SafeModeEntered {
+    /// The last block finalised before the partition, if any since the node started.
+    pub last_finalised_block: Option<BlockNumber>,
+    /// Time elapsed without new finalised blocks.
+    pub since_last_finalised: Duration,
+}
+
+impl EventBusMessage for SafeModeEntered {}
+
+/// Safe mode exited event.
+///
+/// This event is emitted when a new block is finalised while the node is in safe mode.
+#[derive(Debug, Clone)]
+pub struct SafeModeExited {
+    /// The block whose finalisation took the node out of safe mode.
+    pub finalised_block: BlockNumber,
+    /// Time the node was in safe mode.
+    pub duration: Duration,
+}
+
+impl EventBusMessage for SafeModeExited {}
+
+/// An event emitted for a best block, re-emitted once that block is finalised.
+///
+/// Only emitted for the events tracked by the [reorg tracking](crate::reorg). Tasks that should
+/// only act on final data handle these instead of the event itself, e.g. through
+/// [`FinalisedOnly`](crate::reorg::FinalisedOnly).
+#[derive(Debug, Clone)]
+pub struct Finalised<E> {
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub event: E,
+}
+
+impl<E: EventBusMessage> EventBusMessage for Finalised<E> {}
+
+/// An event emitted for a best block, compensated because that block was reorged out.
+///
+/// Only emitted for the events tracked by the [reorg tracking](crate::reorg). Tasks that acted on
+/// the event should undo what they did, since the on-chain event it comes from is not part of the
+/// best chain anymore.
+#[derive(Debug, Clone)]
+pub struct Reverted<E> {
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub event: E,
+}
+
+impl<E: EventBusMessage> EventBusMessage for Reverted<E> {}
+
 /// The event bus provider for the BlockchainService actor.
 ///
 /// It holds the event buses for the different events that the BlockchainService actor
@@ -324,6 +472,7 @@ pub struct BlockchainServiceEventBusProvider {
     multiple_new_challenge_seeds_event_bus: EventBus<MultipleNewChallengeSeeds>,
     new_storage_request_event_bus: EventBus<NewStorageRequest>,
     accepted_bsp_volunteer_event_bus: EventBus<AcceptedBspVolunteer>,
+    bsp_confirmed_storing_event_bus: EventBus<BspConfirmedStoring>,
     process_submit_proof_request_event_bus: EventBus<ProcessSubmitProofRequest>,
     process_confirm_storage_request_event_bus: EventBus<ProcessConfirmStoringRequest>,
     process_msp_respond_storing_request_event_bus: EventBus<ProcessMspRespondStoringRequest>,
@@ -339,11 +488,24 @@ pub struct BlockchainServiceEventBusProvider {
     move_bucket_requested_event_bus: EventBus<MoveBucketRequested>,
     move_bucket_rejected_event_bus: EventBus<MoveBucketRejected>,
     move_bucket_accepted_event_bus: EventBus<MoveBucketAccepted>,
+    finalised_move_bucket_accepted_event_bus: EventBus<FinalisedMoveBucketAccepted>,
     move_bucket_expired_event_bus: EventBus<MoveBucketExpired>,
     move_bucket_requested_for_new_msp_event_bus: EventBus<MoveBucketRequestedForNewMsp>,
+    bucket_frozen_event_bus: EventBus<BucketFrozen>,
+    bucket_unfrozen_event_bus: EventBus<BucketUnfrozen>,
     bsp_stop_storing_event_bus: EventBus<BspConfirmStoppedStoring>,
     finalised_bsp_stop_storing_event_bus: EventBus<FinalisedBspConfirmStoppedStoring>,
     notify_period_event_bus: EventBus<NotifyPeriod>,
+    safe_mode_entered_event_bus: EventBus<SafeModeEntered>,
+    safe_mode_exited_event_bus: EventBus<SafeModeExited>,
+    finalised_new_storage_request_event_bus: EventBus<Finalised<NewStorageRequest>>,
+    reverted_new_storage_request_event_bus: EventBus<Reverted<NewStorageRequest>>,
+    finalised_accepted_bsp_volunteer_event_bus: EventBus<Finalised<AcceptedBspVolunteer>>,
+    reverted_accepted_bsp_volunteer_event_bus: EventBus<Reverted<AcceptedBspVolunteer>>,
+    finalised_bsp_confirmed_storing_event_bus: EventBus<Finalised<BspConfirmedStoring>>,
+    reverted_bsp_confirmed_storing_event_bus: EventBus<Reverted<BspConfirmedStoring>>,
+    finalised_move_bucket_requested_event_bus: EventBus<Finalised<MoveBucketRequested>>,
+    reverted_move_bucket_requested_event_bus: EventBus<Reverted<MoveBucketRequested>>,
 }
 
 impl BlockchainServiceEventBusProvider {
@@ -353,6 +515,7 @@ impl BlockchainServiceEventBusProvider {
             multiple_new_challenge_seeds_event_bus: EventBus::new(),
             new_storage_request_event_bus: EventBus::new(),
             accepted_bsp_volunteer_event_bus: EventBus::new(),
+            bsp_confirmed_storing_event_bus: EventBus::new(),
             process_submit_proof_request_event_bus: EventBus::new(),
             process_confirm_storage_request_event_bus: EventBus::new(),
             process_msp_respond_storing_request_event_bus: EventBus::new(),
@@ -367,11 +530,24 @@ impl BlockchainServiceEventBusProvider {
             move_bucket_requested_event_bus: EventBus::new(),
             move_bucket_rejected_event_bus: EventBus::new(),
             move_bucket_accepted_event_bus: EventBus::new(),
+            finalised_move_bucket_accepted_event_bus: EventBus::new(),
             move_bucket_expired_event_bus: EventBus::new(),
             move_bucket_requested_for_new_msp_event_bus: EventBus::new(),
+            bucket_frozen_event_bus: EventBus::new(),
+            bucket_unfrozen_event_bus: EventBus::new(),
             bsp_stop_storing_event_bus: EventBus::new(),
             finalised_bsp_stop_storing_event_bus: EventBus::new(),
             notify_period_event_bus: EventBus::new(),
+            safe_mode_entered_event_bus: EventBus::new(),
+            safe_mode_exited_event_bus: EventBus::new(),
+            finalised_new_storage_request_event_bus: EventBus::new(),
+            reverted_new_storage_request_event_bus: EventBus::new(),
+            finalised_accepted_bsp_volunteer_event_bus: EventBus::new(),
+            reverted_accepted_bsp_volunteer_event_bus: EventBus::new(),
+            finalised_bsp_confirmed_storing_event_bus: EventBus::new(),
+            reverted_bsp_confirmed_storing_event_bus: EventBus::new(),
+            finalised_move_bucket_requested_event_bus: EventBus::new(),
+            reverted_move_bucket_requested_event_bus: EventBus::new(),
         }
     }
 }
@@ -400,6 +576,12 @@ impl ProvidesEventBus<AcceptedBspVolunteer> for BlockchainServiceEventBusProvide
     }
 }
 
+impl ProvidesEventBus<BspConfirmedStoring> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<BspConfirmedStoring> {
+        &self.bsp_confirmed_storing_event_bus
+    }
+}
+
 impl ProvidesEventBus<ProcessSubmitProofRequest> for BlockchainServiceEventBusProvider {
     fn event_bus(&self) -> &EventBus<ProcessSubmitProofRequest> {
         &self.process_submit_proof_request_event_bus
@@ -486,6 +668,12 @@ impl ProvidesEventBus<MoveBucketAccepted> for BlockchainServiceEventBusProvider
     }
 }
 
+impl ProvidesEventBus<FinalisedMoveBucketAccepted> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<FinalisedMoveBucketAccepted> {
+        &self.finalised_move_bucket_accepted_event_bus
+    }
+}
+
 impl ProvidesEventBus<MoveBucketExpired> for BlockchainServiceEventBusProvider {
     fn event_bus(&self) -> &EventBus<MoveBucketExpired> {
         &self.move_bucket_expired_event_bus
@@ -498,6 +686,18 @@ impl ProvidesEventBus<MoveBucketRequestedForNewMsp> for BlockchainServiceEventBu
     }
 }
 
+impl ProvidesEventBus<BucketFrozen> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<BucketFrozen> {
+        &self.bucket_frozen_event_bus
+    }
+}
+
+impl ProvidesEventBus<BucketUnfrozen> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<BucketUnfrozen> {
+        &self.bucket_unfrozen_event_bus
+    }
+}
+
 impl ProvidesEventBus<BspConfirmStoppedStoring> for BlockchainServiceEventBusProvider {
     fn event_bus(&self) -> &EventBus<BspConfirmStoppedStoring> {
         &self.bsp_stop_storing_event_bus
@@ -515,3 +715,63 @@ impl ProvidesEventBus<NotifyPeriod> for BlockchainServiceEventBusProvider {
         &self.notify_period_event_bus
     }
 }
+
+impl ProvidesEventBus<SafeModeEntered> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<SafeModeEntered> {
+        &self.safe_mode_entered_event_bus
+    }
+}
+
+impl ProvidesEventBus<SafeModeExited> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<SafeModeExited> {
+        &self.safe_mode_exited_event_bus
+    }
+}
+
+impl ProvidesEventBus<Finalised<NewStorageRequest>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Finalised<NewStorageRequest>> {
+        &self.finalised_new_storage_request_event_bus
+    }
+}
+
+impl ProvidesEventBus<Reverted<NewStorageRequest>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Reverted<NewStorageRequest>> {
+        &self.reverted_new_storage_request_event_bus
+    }
+}
+
+impl ProvidesEventBus<Finalised<AcceptedBspVolunteer>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Finalised<AcceptedBspVolunteer>> {
+        &self.finalised_accepted_bsp_volunteer_event_bus
+    }
+}
+
+impl ProvidesEventBus<Reverted<AcceptedBspVolunteer>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Reverted<AcceptedBspVolunteer>> {
+        &self.reverted_accepted_bsp_volunteer_event_bus
+    }
+}
+
+impl ProvidesEventBus<Finalised<BspConfirmedStoring>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Finalised<BspConfirmedStoring>> {
+        &self.finalised_bsp_confirmed_storing_event_bus
+    }
+}
+
+impl ProvidesEventBus<Reverted<BspConfirmedStoring>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Reverted<BspConfirmedStoring>> {
+        &self.reverted_bsp_confirmed_storing_event_bus
+    }
+}
+
+impl ProvidesEventBus<Finalised<MoveBucketRequested>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Finalised<MoveBucketRequested>> {
+        &self.finalised_move_bucket_requested_event_bus
+    }
+}
+
+impl ProvidesEventBus<Reverted<MoveBucketRequested>> for BlockchainServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<Reverted<MoveBucketRequested>> {
+        &self.reverted_move_bucket_requested_event_bus
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::anyhow;
@@ -24,6 +25,7 @@ use sp_runtime::{
 use pallet_file_system_runtime_api::{
     FileSystemApi, IsStorageRequestOpenToVolunteersError, QueryBspConfirmChunksToProveForFileError,
     QueryFileEarliestVolunteerTickError, QueryMspConfirmChunksToProveForFileError,
+    QueryStorageRequestReplicationStatusError,
 };
 use pallet_payment_streams_runtime_api::{GetUsersWithDebtOverThresholdError, PaymentStreamsApi};
 use pallet_proofs_dealer_runtime_api::{
@@ -43,6 +45,7 @@ use shc_common::{
         TickNumber, BCSV_KEY_TYPE,
     },
 };
+use shc_telemetry::FlowSpan;
 use shp_file_metadata::FileKey;
 use storage_hub_runtime::RuntimeEvent;
 
@@ -50,11 +53,17 @@ use crate::{
     commands::BlockchainServiceCommand,
     events::{
         AcceptedBspVolunteer, BlockchainServiceEventBusProvider, BspConfirmStoppedStoring,
-        FinalisedBspConfirmStoppedStoring, FinalisedMspStoppedStoringBucket,
-        FinalisedTrieRemoveMutationsApplied, LastChargeableInfoUpdated, MoveBucketAccepted,
-        MoveBucketExpired, MoveBucketRejected, MoveBucketRequested, MoveBucketRequestedForNewMsp,
-        NewStorageRequest, SlashableProvider, SpStopStoringInsolventUser, UserWithoutFunds,
+        BspConfirmedStoring, BucketFrozen, BucketUnfrozen, LastChargeableInfoUpdated,
+        MoveBucketAccepted, MoveBucketExpired, MoveBucketRejected, MoveBucketRequested,
+        MoveBucketRequestedForNewMsp, NewStorageRequest, SlashableProvider,
+        SpStopStoringInsolventUser, UserWithoutFunds,
     },
+    outbound_queue::{OutboundExtrinsic, OutboundExtrinsicQueue},
+    pending_operations::PendingOperations,
+    reorg::ReorgTracker,
+    replay::ReplayRange,
+    runtime_api_cache::{CachedRuntimeApiQuery, RuntimeApiCache, RuntimeApiCacheConfig},
+    safe_mode::{SafeMode, SafeModeConfig, PARTITION_CHECK_INTERVAL},
     state::{
         BlockchainServiceStateStore, LastProcessedBlockNumberCf,
         OngoingProcessConfirmStoringRequestCf, OngoingProcessMspRespondStorageRequestCf,
@@ -63,8 +72,8 @@ use crate::{
     transaction::SubmittedTransaction,
     typed_store::{CFDequeAPI, ProvidesTypedDbSingleAccess},
     types::{
-        BestBlockInfo, ForestStorageSnapshotInfo, NewBlockNotificationKind,
-        StopStoringForInsolventUserRequest, SubmitProofRequest,
+        BestBlockInfo, ForestRootWriteLockHolder, ForestStorageSnapshotInfo,
+        NewBlockNotificationKind, StopStoringForInsolventUserRequest, SubmitProofRequest,
     },
 };
 
@@ -82,6 +91,9 @@ pub(crate) const SYNC_MODE_MIN_BLOCKS_BEHIND: BlockNumber = 5;
 /// up of proofs (see [`BlockchainService::proof_submission_catch_up`]).
 pub(crate) const CHECK_FOR_PENDING_PROOFS_PERIOD: BlockNumber = 4;
 
+/// Default time a task can hold the Forest root write lock before a warning is logged.
+pub const DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD: Duration = Duration::from_secs(120);
+
 /// The BlockchainService actor.
 ///
 /// This actor is responsible for sending extrinsics to the runtime and handling block import notifications.
@@ -111,6 +123,17 @@ where
     pub(crate) best_block: BestBlockInfo,
     /// Nonce counter for the extrinsics.
     pub(crate) nonce_counter: u32,
+    /// The extrinsics sent by this node that are still in flight, indexed by the file keys and
+    /// buckets they operate on.
+    ///
+    /// Used by tasks to avoid sending extrinsics that conflict with others still in flight.
+    pub(crate) pending_operations: PendingOperations,
+    /// The extrinsics sent by this node that are not finalised yet, kept in the persistent state
+    /// to resubmit them if they are lost. See [`crate::outbound_queue`].
+    pub(crate) outbound_queue: OutboundExtrinsicQueue,
+    /// The events emitted for best blocks that are neither finalised nor reorged out yet. See
+    /// [`crate::reorg`].
+    pub(crate) reorg_tracker: ReorgTracker,
     /// A registry of waiters for a block number.
     pub(crate) wait_for_block_request_by_number:
         BTreeMap<BlockNumber, Vec<tokio::sync::oneshot::Sender<()>>>,
@@ -137,6 +160,14 @@ where
     /// thread (Blockchain Service) and unlock it at the end of the spawned task. The alternative
     /// would be to send a [`MutexGuard`].
     pub(crate) forest_root_write_lock: Option<tokio::sync::oneshot::Receiver<()>>,
+    /// The task currently holding the Forest root write lock, if any.
+    pub(crate) forest_root_write_lock_holder: Option<ForestRootWriteLockHolder>,
+    /// Spans recording how long the current holder of the Forest root write lock holds it, in the
+    /// traces of the flows it belongs to. Closed when the lock is released.
+    pub(crate) forest_root_write_lock_spans: Vec<FlowSpan>,
+    /// How long a task can hold the Forest root write lock before a warning is logged on every
+    /// block, in case it is stuck.
+    pub(crate) forest_root_write_lock_warn_threshold: Duration,
     /// A persistent state store for the BlockchainService actor.
     pub(crate) persistent_state: BlockchainServiceStateStore,
     /// Pending submit proof requests. Note: this is not kept in the persistent state because of
@@ -147,6 +178,22 @@ where
     ///
     /// This is meant to be used for periodic, low priority tasks.
     pub(crate) notify_period: Option<u32>,
+    /// The finalised blocks to replay, if running in replay mode.
+    ///
+    /// In replay mode, the BlockchainService does not process new blocks nor submit extrinsics.
+    /// See [`crate::replay`].
+    pub(crate) replay_range: Option<ReplayRange>,
+    /// Whether the finalised blocks in [`Self::replay_range`] were already replayed.
+    pub(crate) replay_done: bool,
+    /// The responses of runtime API queries made on behalf of tasks, served again while they are
+    /// still valid to avoid calling into the runtime for every repeated query.
+    pub(crate) runtime_api_cache: RuntimeApiCache,
+    /// Detection of partitions from the network, pausing Forest root writes while the node seems
+    /// partitioned. See [`crate::safe_mode`].
+    pub(crate) safe_mode: SafeMode,
+    /// Ongoing chaos fault keeping the Forest root write lock unassigned. See [`crate::chaos`].
+    #[cfg(feature = "chaos")]
+    pub(crate) forest_root_write_lock_fault: crate::chaos::ForestRootWriteLockFault,
 }
 
 /// Event loop for the BlockchainService actor.
@@ -166,6 +213,7 @@ where
     Command(BlockchainServiceCommand),
     BlockImportNotification(BlockImportNotification<Block>),
     FinalityNotification(FinalityNotification<Block>),
+    PartitionCheck,
 }
 
 /// Implement the ActorEventLoop trait for the BlockchainServiceEventLoop.
@@ -193,6 +241,13 @@ where
         // Finality notification stream to be notified of blocks being finalised.
         let finality_notification_stream = self.actor.client.finality_notification_stream();
 
+        // Periodic tick to detect partitions from the network, during which no notification is
+        // received at all.
+        let partition_check_stream = stream::unfold((), |_| async {
+            tokio::time::sleep(PARTITION_CHECK_INTERVAL).await;
+            Some(((), ()))
+        });
+
         // Merging notification streams with command stream.
         let mut merged_stream = stream::select_all(vec![
             self.receiver.map(MergedEventLoopMessage::Command).boxed(),
@@ -202,12 +257,25 @@ where
             finality_notification_stream
                 .map(MergedEventLoopMessage::FinalityNotification)
                 .boxed(),
+            partition_check_stream
+                .map(|_| MergedEventLoopMessage::PartitionCheck)
+                .boxed(),
         ]);
 
         // Process incoming messages.
         while let Some(notification) = merged_stream.next().await {
             match notification {
                 MergedEventLoopMessage::Command(command) => {
+                    #[cfg(feature = "chaos")]
+                    let Some(command) = crate::chaos::apply_command_fault(
+                        command,
+                        shc_actors_framework::chaos::inject(crate::chaos::COMMAND_POINT),
+                    )
+                    .await
+                    else {
+                        continue;
+                    };
+
                     self.actor.handle_message(command).await;
                 }
                 MergedEventLoopMessage::BlockImportNotification(notification) => {
@@ -218,6 +286,9 @@ where
                 MergedEventLoopMessage::FinalityNotification(notification) => {
                     self.actor.handle_finality_notification(notification).await;
                 }
+                MergedEventLoopMessage::PartitionCheck => {
+                    self.actor.check_for_partition();
+                }
             };
         }
     }
@@ -238,6 +309,19 @@ where
     ) -> impl std::future::Future<Output = ()> + Send {
         async {
             match message {
+                BlockchainServiceCommand::SendExtrinsic {
+                    call,
+                    tip: _,
+                    callback,
+                } if self.replay_range.is_some() => {
+                    warn!(target: LOG_TARGET, "🔁 Replay mode: not submitting extrinsic {:?}", call);
+
+                    if let Err(e) = callback.send(Err(anyhow!(
+                        "Submitting extrinsics is stubbed out in replay mode"
+                    ))) {
+                        error!(target: LOG_TARGET, "Failed to send error message through channel: {:?}", e);
+                    }
+                }
                 BlockchainServiceCommand::SendExtrinsic {
                     call,
                     tip,
@@ -378,10 +462,16 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     // Current Tick should always return a value, unless there's an internal API error.
-                    let current_tick_result = self
-                        .client
-                        .runtime_api()
-                        .get_current_tick(current_block_hash);
+                    let current_tick_result = self.runtime_api_cache.get_or_call(
+                        current_block_hash,
+                        CachedRuntimeApiQuery::CurrentTick,
+                        (),
+                        || {
+                            self.client
+                                .runtime_api()
+                                .get_current_tick(current_block_hash)
+                        },
+                    );
 
                     let (tx, rx) = tokio::sync::oneshot::channel();
 
@@ -429,9 +519,13 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let earliest_block_to_change_capacity = self
-                        .client
-                        .runtime_api()
-                        .query_earliest_change_capacity_block(current_block_hash, &bsp_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::EarliestChangeCapacityBlock,
+                            &bsp_id,
+                            || self.client.runtime_api().query_earliest_change_capacity_block(current_block_hash, &bsp_id),
+                        )
                         .unwrap_or_else(|_| {
                             error!(target: LOG_TARGET, "Failed to query earliest block to change capacity");
                             Err(QueryEarliestChangeCapacityBlockError::InternalError)
@@ -469,6 +563,121 @@ where
                         }
                     }
                 }
+                BlockchainServiceCommand::QueryStorageRequestReplicationStatus {
+                    file_key,
+                    callback,
+                } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let replication_status = self
+                        .client
+                        .runtime_api()
+                        .query_storage_request_replication_status(current_block_hash, file_key)
+                        .unwrap_or_else(|_| {
+                            Err(QueryStorageRequestReplicationStatusError::InternalError)
+                        });
+
+                    match callback.send(replication_status) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Storage request replication status sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send storage request replication status: {:?}", e);
+                        }
+                    }
+                }
+                BlockchainServiceCommand::QueryPendingStorageRequestsForProvider {
+                    provider_id,
+                    start_after,
+                    limit,
+                    callback,
+                } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let storage_requests = self
+                        .client
+                        .runtime_api()
+                        .pending_storage_requests_for_provider(
+                            current_block_hash,
+                            provider_id,
+                            start_after,
+                            limit,
+                        );
+
+                    match callback.send(storage_requests) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Pending storage requests for provider sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send pending storage requests for provider: {:?}", e);
+                        }
+                    }
+                }
+                BlockchainServiceCommand::QueryStorageRequestsByBucket {
+                    bucket_id,
+                    start_after,
+                    limit,
+                    callback,
+                } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let storage_requests = self.client.runtime_api().storage_requests_by_bucket(
+                        current_block_hash,
+                        bucket_id,
+                        start_after,
+                        limit,
+                    );
+
+                    match callback.send(storage_requests) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Storage requests by bucket sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send storage requests by bucket: {:?}", e);
+                        }
+                    }
+                }
+                BlockchainServiceCommand::QueryStorageRequest { file_key, callback } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let storage_request = self
+                        .client
+                        .runtime_api()
+                        .query_storage_request(current_block_hash, file_key);
+
+                    match callback.send(storage_request) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Storage request sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send storage request: {:?}", e);
+                        }
+                    }
+                }
+                BlockchainServiceCommand::QueryHasFileReadAccess {
+                    bucket_id,
+                    file_key,
+                    account,
+                    callback,
+                } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let has_read_access = self.client.runtime_api().has_file_read_access(
+                        current_block_hash,
+                        bucket_id,
+                        file_key,
+                        account,
+                    );
+
+                    match callback.send(has_read_access) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "File read access sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send file read access: {:?}", e);
+                        }
+                    }
+                }
                 BlockchainServiceCommand::QueryFileEarliestVolunteerTick {
                     bsp_id,
                     file_key,
@@ -571,9 +780,17 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let multiaddresses = self
-                        .client
-                        .runtime_api()
-                        .query_provider_multiaddresses(current_block_hash, &provider_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::ProviderMultiaddresses,
+                            &provider_id,
+                            || {
+                                self.client
+                                    .runtime_api()
+                                    .query_provider_multiaddresses(current_block_hash, &provider_id)
+                            },
+                        )
                         .unwrap_or_else(|_| {
                             error!(target: LOG_TARGET, "Failed to query provider multiaddresses");
                             Err(QueryProviderMultiaddressesError::InternalError)
@@ -663,9 +880,13 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let challenge_period = self
-                        .client
-                        .runtime_api()
-                        .get_challenge_period(current_block_hash, &provider_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::ChallengePeriod,
+                            &provider_id,
+                            || self.client.runtime_api().get_challenge_period(current_block_hash, &provider_id),
+                        )
                         .unwrap_or_else(|_| {
                             error!(target: LOG_TARGET, "Failed to query challenge period for provider [{:?}]", provider_id);
                             Err(GetChallengePeriodError::InternalApiError)
@@ -713,6 +934,23 @@ where
                         }
                     }
                 }
+                BlockchainServiceCommand::QueryMaxProofSize { callback } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let max_proof_size = self
+                        .client
+                        .runtime_api()
+                        .get_max_proof_size(current_block_hash);
+
+                    match callback.send(max_proof_size) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Max proof size sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send max proof size: {:?}", e);
+                        }
+                    }
+                }
                 BlockchainServiceCommand::QueryLastCheckpointChallenges { tick, callback } => {
                     let current_block_hash = self.client.info().best_hash;
 
@@ -761,9 +999,18 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let capacity = self
-                        .client
-                        .runtime_api()
-                        .query_storage_provider_capacity(current_block_hash, &provider_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::StorageProviderCapacity,
+                            &provider_id,
+                            || {
+                                self.client.runtime_api().query_storage_provider_capacity(
+                                    current_block_hash,
+                                    &provider_id,
+                                )
+                            },
+                        )
                         .unwrap_or_else(|_| Err(QueryStorageProviderCapacityError::InternalError));
 
                     match callback.send(capacity) {
@@ -782,9 +1029,18 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let capacity = self
-                        .client
-                        .runtime_api()
-                        .query_available_storage_capacity(current_block_hash, &provider_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::AvailableStorageCapacity,
+                            &provider_id,
+                            || {
+                                self.client.runtime_api().query_available_storage_capacity(
+                                    current_block_hash,
+                                    &provider_id,
+                                )
+                            },
+                        )
                         .unwrap_or_else(|_| Err(QueryAvailableStorageCapacityError::InternalError));
 
                     match callback.send(capacity) {
@@ -873,9 +1129,18 @@ where
                         .unwrap_or_else(|| Self::caller_pub_key(self.keystore.clone()));
 
                     let provider_id = self
-                        .client
-                        .runtime_api()
-                        .get_storage_provider_id(current_block_hash, &node_pub_key.into())
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::StorageProviderId,
+                            &node_pub_key,
+                            || {
+                                self.client.runtime_api().get_storage_provider_id(
+                                    current_block_hash,
+                                    &node_pub_key.into(),
+                                )
+                            },
+                        )
                         .map_err(|_| anyhow!("Internal API error"));
 
                     match callback.send(provider_id) {
@@ -919,9 +1184,20 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let worst_case_scenario_slashable_amount = self
-                        .client
-                        .runtime_api()
-                        .get_worst_case_scenario_slashable_amount(current_block_hash, provider_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::WorstCaseScenarioSlashableAmount,
+                            &provider_id,
+                            || {
+                                self.client
+                                    .runtime_api()
+                                    .get_worst_case_scenario_slashable_amount(
+                                        current_block_hash,
+                                        provider_id,
+                                    )
+                            },
+                        )
                         .map_err(|_| anyhow!("Internal API error"));
 
                     match callback.send(worst_case_scenario_slashable_amount) {
@@ -936,9 +1212,17 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let slash_amount_per_max_file_size = self
-                        .client
-                        .runtime_api()
-                        .get_slash_amount_per_max_file_size(current_block_hash)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::SlashAmountPerMaxFileSize,
+                            (),
+                            || {
+                                self.client
+                                    .runtime_api()
+                                    .get_slash_amount_per_max_file_size(current_block_hash)
+                            },
+                        )
                         .map_err(|_| anyhow!("Internal API error"));
 
                     match callback.send(slash_amount_per_max_file_size) {
@@ -948,6 +1232,22 @@ where
                         }
                     }
                 }
+                BlockchainServiceCommand::QueryCurrentPricePerGigaUnitPerTick { callback } => {
+                    let current_block_hash = self.client.info().best_hash;
+
+                    let current_price = self
+                        .client
+                        .runtime_api()
+                        .get_current_price_per_giga_unit_per_tick(current_block_hash)
+                        .map_err(|_| anyhow!("Internal API error"));
+
+                    match callback.send(current_price) {
+                        Ok(_) => {}
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send back current price per giga-unit per tick: {:?}", e);
+                        }
+                    }
+                }
                 BlockchainServiceCommand::QueryMspIdOfBucketId {
                     bucket_id,
                     callback,
@@ -955,9 +1255,17 @@ where
                     let current_block_hash = self.client.info().best_hash;
 
                     let msp_id = self
-                        .client
-                        .runtime_api()
-                        .query_msp_id_of_bucket_id(current_block_hash, &bucket_id)
+                        .runtime_api_cache
+                        .get_or_call(
+                            current_block_hash,
+                            CachedRuntimeApiQuery::MspIdOfBucketId,
+                            &bucket_id,
+                            || {
+                                self.client
+                                    .runtime_api()
+                                    .query_msp_id_of_bucket_id(current_block_hash, &bucket_id)
+                            },
+                        )
                         .unwrap_or_else(|e| {
                             error!(target: LOG_TARGET, "{}", e);
                             Err(QueryMspIdOfBucketIdError::BucketNotFound)
@@ -995,6 +1303,41 @@ where
                         }
                     }
                 }
+                BlockchainServiceCommand::IsOperationPending { key, callback } => {
+                    let is_pending = self.pending_operations.is_pending(&key);
+                    if is_pending {
+                        debug!(target: LOG_TARGET, "Operations in flight for {:?}: {:?}", key, self.pending_operations.get(&key));
+                    }
+                    match callback.send(is_pending) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Pending operation status sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send pending operation status: {:?}", e);
+                        }
+                    }
+                }
+                BlockchainServiceCommand::GetOutboundExtrinsicQueue { callback } => {
+                    let extrinsics: Vec<OutboundExtrinsic> = self.outbound_queue.extrinsics();
+                    match callback.send(extrinsics) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Outbound extrinsic queue sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send outbound extrinsic queue: {:?}", e);
+                        }
+                    }
+                }
+                BlockchainServiceCommand::GetForestRootWriteLockHolder { callback } => {
+                    match callback.send(self.forest_root_write_lock_holder.clone()) {
+                        Ok(_) => {
+                            trace!(target: LOG_TARGET, "Forest root write lock holder sent successfully");
+                        }
+                        Err(e) => {
+                            error!(target: LOG_TARGET, "Failed to send Forest root write lock holder: {:?}", e);
+                        }
+                    }
+                }
             }
         }
     }
@@ -1016,7 +1359,14 @@ where
         forest_storage_handler: FSH,
         rocksdb_root_path: impl Into<PathBuf>,
         notify_period: Option<u32>,
+        forest_root_write_lock_warn_threshold: Duration,
+        replay_range: Option<ReplayRange>,
+        runtime_api_cache_config: RuntimeApiCacheConfig,
+        safe_mode_config: SafeModeConfig,
     ) -> Self {
+        let persistent_state = BlockchainServiceStateStore::new(rocksdb_root_path.into());
+        let outbound_queue = OutboundExtrinsicQueue::load(&persistent_state);
+
         Self {
             event_bus_provider: BlockchainServiceEventBusProvider::new(),
             client,
@@ -1025,14 +1375,26 @@ where
             _forest_storage_handler: forest_storage_handler,
             best_block: BestBlockInfo::default(),
             nonce_counter: 0,
+            pending_operations: PendingOperations::default(),
+            outbound_queue,
+            reorg_tracker: ReorgTracker::default(),
             wait_for_block_request_by_number: BTreeMap::new(),
             wait_for_tick_request_by_number: BTreeMap::new(),
             provider_id: None,
             forest_root_snapshots: BTreeMap::new(),
             forest_root_write_lock: None,
-            persistent_state: BlockchainServiceStateStore::new(rocksdb_root_path.into()),
+            forest_root_write_lock_holder: None,
+            forest_root_write_lock_spans: Vec::new(),
+            forest_root_write_lock_warn_threshold,
+            persistent_state,
             pending_submit_proof_requests: BTreeSet::new(),
             notify_period,
+            replay_range,
+            replay_done: false,
+            runtime_api_cache: RuntimeApiCache::new(runtime_api_cache_config),
+            safe_mode: SafeMode::new(safe_mode_config),
+            #[cfg(feature = "chaos")]
+            forest_root_write_lock_fault: Default::default(),
         }
     }
 
@@ -1053,7 +1415,11 @@ where
             NewBlockNotificationKind::Reorg {
                 old_best_block: _,
                 new_best_block,
+                retracted,
             } => {
+                // Compensate the events emitted for the blocks that are no longer in the best chain.
+                self.revert_retracted_blocks(&retracted);
+
                 // TODO: Handle catch up of reorgs.
                 new_best_block
             }
@@ -1061,8 +1427,26 @@ where
 
         info!(target: LOG_TARGET, "📥 Block import notification (#{}): {}", block_number, block_hash);
 
+        // Responses cached so far were queried at a block that is no longer the best one.
+        self.runtime_api_cache.invalidate();
+
         // Get provider IDs linked to keys in this node's keystore and update the nonce.
-        self.pre_block_processing_checks(&block_hash);
+        self.pre_block_processing_checks(&block_hash, &block_number);
+
+        // In replay mode, new blocks are only used to release the tasks waiting for them. The
+        // finalised blocks are replayed once the node is synced and the Provider it manages is known.
+        if let Some(replay_range) = self.replay_range {
+            self.notify_import_block_number(&block_number);
+            self.notify_tick_number(&block_hash);
+
+            if !self.replay_done {
+                self.replay_done = true;
+                if let Err(e) = self.replay_finalised_blocks(replay_range) {
+                    error!(target: LOG_TARGET, "Failed to replay finalised blocks: {:?}", e);
+                }
+            }
+            return;
+        }
 
         // If this is the first block import notification, we might need to catch up.
         // Check if we just came out of syncing mode.
@@ -1075,11 +1459,15 @@ where
         self.process_block_import(&block_hash, &block_number).await;
     }
 
-    fn pre_block_processing_checks(&mut self, block_hash: &H256) {
+    fn pre_block_processing_checks(&mut self, block_hash: &H256, block_number: &BlockNumber) {
         // We query the [`BlockchainService`] account nonce at this height
         // and update our internal counter if it's smaller than the result.
+        // Operations sent with a lower nonce are no longer in flight.
         self.check_nonce(&block_hash);
 
+        // Extrinsics whose mortality period is over can no longer be included.
+        self.pending_operations.prune_expired(*block_number);
+
         // Get Provider ID linked to keys in this node's keystore.
         self.get_provider_id(&block_hash);
     }
@@ -1184,6 +1572,9 @@ where
         // It is not guaranteed that the tick number will increase at every block import.
         self.notify_tick_number(&block_hash);
 
+        // Resubmit the extrinsics sent by this node that were lost.
+        self.resubmit_outbound_extrinsics(*block_number).await;
+
         // Process pending requests that update the forest root.
         self.check_pending_forest_root_writes();
 
@@ -1209,15 +1600,23 @@ where
                                 size,
                                 peer_ids,
                             },
-                        ) => self.emit(NewStorageRequest {
-                            who,
-                            file_key: FileKey::from(file_key.as_ref()),
-                            bucket_id,
-                            location,
-                            fingerprint: fingerprint.as_ref().into(),
-                            size,
-                            user_peer_ids: peer_ids,
-                        }),
+                        ) => {
+                            let event = NewStorageRequest {
+                                who,
+                                file_key: FileKey::from(file_key.as_ref()),
+                                bucket_id,
+                                location,
+                                fingerprint: fingerprint.as_ref().into(),
+                                size,
+                                user_peer_ids: peer_ids,
+                            };
+                            self.reorg_tracker.track(
+                                *block_hash,
+                                *block_number,
+                                event.clone().into(),
+                            );
+                            self.emit(event);
+                        }
                         // A Provider's challenge cycle has been initialised.
                         RuntimeEvent::ProofsDealer(
                             pallet_proofs_dealer::Event::NewChallengeCycleInitialised {
@@ -1359,10 +1758,16 @@ where
                             match self.provider_id {
                                 // As a BSP, this node is interested in the event to allow the new MSP to request files from it.
                                 Some(StorageProviderId::BackupStorageProvider(_)) => {
-                                    self.emit(MoveBucketRequested {
+                                    let event = MoveBucketRequested {
                                         bucket_id,
                                         new_msp_id,
-                                    });
+                                    };
+                                    self.reorg_tracker.track(
+                                        *block_hash,
+                                        *block_number,
+                                        event.clone().into(),
+                                    );
+                                    self.emit(event);
                                 }
                                 // As an MSP, this node is interested in the event only if this node is the new MSP.
                                 Some(StorageProviderId::MainStorageProvider(msp_id))
@@ -1427,6 +1832,33 @@ where
                                 }
                             }
                         }
+                        RuntimeEvent::FileSystem(
+                            pallet_file_system::Event::BspConfirmedStoring {
+                                who: _,
+                                bsp_id,
+                                confirmed_file_keys,
+                                skipped_file_keys: _,
+                                new_root: _,
+                            },
+                        ) => {
+                            // This event is relevant in case the node is run by a user, to follow the
+                            // replication of its storage requests.
+                            if self.provider_id.is_none() {
+                                let event = BspConfirmedStoring {
+                                    bsp_id,
+                                    confirmed_file_keys: confirmed_file_keys
+                                        .into_iter()
+                                        .map(|file_key| file_key.into())
+                                        .collect(),
+                                };
+                                self.reorg_tracker.track(
+                                    *block_hash,
+                                    *block_number,
+                                    event.clone().into(),
+                                );
+                                self.emit(event);
+                            }
+                        }
                         RuntimeEvent::FileSystem(
                             pallet_file_system::Event::AcceptedBspVolunteer {
                                 bsp_id,
@@ -1454,7 +1886,7 @@ where
                                 let multiaddress_vec: Vec<Multiaddr> =
                                     convert_raw_multiaddresses_to_multiaddr(multiaddresses);
 
-                                self.emit(AcceptedBspVolunteer {
+                                let event = AcceptedBspVolunteer {
                                     bsp_id,
                                     bucket_id,
                                     location,
@@ -1462,7 +1894,49 @@ where
                                     multiaddresses: multiaddress_vec,
                                     owner,
                                     size,
-                                })
+                                };
+                                self.reorg_tracker.track(
+                                    *block_hash,
+                                    *block_number,
+                                    event.clone().into(),
+                                );
+                                self.emit(event);
+                            }
+                        }
+                        RuntimeEvent::FileSystem(pallet_file_system::Event::BucketFrozen {
+                            bucket_id,
+                            owner,
+                            msp_id,
+                        }) => {
+                            if self.is_bucket_relevant(&owner, msp_id.as_ref()) {
+                                warn!(
+                                    target: LOG_TARGET,
+                                    "Bucket {:?} has been frozen pending dispute resolution",
+                                    bucket_id
+                                );
+                                self.emit(BucketFrozen {
+                                    bucket_id,
+                                    owner,
+                                    msp_id,
+                                });
+                            }
+                        }
+                        RuntimeEvent::FileSystem(pallet_file_system::Event::BucketUnfrozen {
+                            bucket_id,
+                            owner,
+                            msp_id,
+                        }) => {
+                            if self.is_bucket_relevant(&owner, msp_id.as_ref()) {
+                                info!(
+                                    target: LOG_TARGET,
+                                    "Bucket {:?} has been unfrozen",
+                                    bucket_id
+                                );
+                                self.emit(BucketUnfrozen {
+                                    bucket_id,
+                                    owner,
+                                    msp_id,
+                                });
                             }
                         }
                         // Ignore all other events.
@@ -1496,77 +1970,34 @@ where
 
         info!(target: LOG_TARGET, "📨 Finality notification #{}: {}", block_number, block_hash);
 
-        // Get events from storage.
-        match get_events_at_block(&self.client, &block_hash) {
-            Ok(block_events) => {
-                // Process the events.
-                for ev in block_events {
-                    match ev.event.clone() {
-                        // New storage request event coming from pallet-file-system.
-                        RuntimeEvent::ProofsDealer(
-                            pallet_proofs_dealer::Event::MutationsApplied {
-                                provider: provider_id,
-                                mutations,
-                                new_root,
-                            },
-                        ) => {
-                            // This event is relevant in case the Provider managed is a BSP.
-                            if let Some(StorageProviderId::BackupStorageProvider(managed_bsp_id)) =
-                                &self.provider_id
-                            {
-                                // We only emit the event if the Provider ID is the one that this node is managing.
-                                if provider_id == *managed_bsp_id {
-                                    self.emit(FinalisedTrieRemoveMutationsApplied {
-                                        provider_id,
-                                        mutations: mutations.clone().into(),
-                                        new_root,
-                                    })
-                                }
-                            }
-                        }
-                        RuntimeEvent::FileSystem(
-                            pallet_file_system::Event::MspStoppedStoringBucket {
-                                msp_id,
-                                owner,
-                                bucket_id,
-                            },
-                        ) => {
-                            // This event is relevant in case the Provider managed is an MSP.
-                            if let Some(StorageProviderId::MainStorageProvider(managed_msp_id)) =
-                                &self.provider_id
-                            {
-                                if msp_id == *managed_msp_id {
-                                    self.emit(FinalisedMspStoppedStoringBucket {
-                                        msp_id,
-                                        owner,
-                                        bucket_id,
-                                    })
-                                }
-                            }
-                        }
-                        RuntimeEvent::FileSystem(
-                            pallet_file_system::Event::BspConfirmStoppedStoring {
-                                bsp_id,
-                                file_key,
-                                new_root,
-                            },
-                        ) => {
-                            // This event is relevant in case the Provider managed is a BSP.
-                            if let Some(StorageProviderId::BackupStorageProvider(managed_bsp_id)) =
-                                &self.provider_id
-                            {
-                                if managed_bsp_id == &bsp_id {
-                                    self.emit(FinalisedBspConfirmStoppedStoring {
-                                        bsp_id,
-                                        file_key: file_key.into(),
-                                        new_root,
-                                    });
-                                }
-                            }
-                        }
-                        // Ignore all other events.
-                        _ => {}
-                    }
+        // Drop the cached responses, in case the finalised block pruned the fork they were queried at.
+        self.runtime_api_cache.invalidate();
+
+        // A new finalised block means the node is connected to the network, exiting safe mode if needed.
+        self.register_finalised_block_for_safe_mode(block_number);
+
+        // In replay mode, finalised blocks are neither processed nor recorded in the audit log.
+        if self.replay_range.is_some() {
+            return;
+        }
+
+        // Extrinsics included in a finalised block can no longer be lost.
+        self.prune_finalised_outbound_extrinsics(&block_hash);
+
+        // The events emitted for the newly finalised blocks can no longer be reverted.
+        let finalised: Vec<H256> = notification
+            .tree_route
+            .iter()
+            .copied()
+            .chain(std::iter::once(block_hash))
+            .collect();
+        self.finalise_tracked_blocks(&finalised, block_number);
+
+        match self.finalised_block_events(&block_hash) {
+            Ok(events) => {
+                self.record_finalised_block_audit(block_number, block_hash, &events);
+                for event in events {
+                    self.emit_finalised_block_event(event);
                 }
             }
             Err(e) => {
@@ -1574,7 +2005,7 @@ where
                 // TODO: This would happen if we're parsing a block authored with an older version of the runtime, using
                 // TODO: a node that has a newer version of the runtime, therefore the EventsVec type is different.
                 // TODO: Consider using runtime APIs for getting old data of previous blocks, and this just for current blocks.
-                error!(target: LOG_TARGET, "Failed to get events storage element: {:?}", e);
+                error!(target: LOG_TARGET, "{:?}", e);
             }
         }
     }
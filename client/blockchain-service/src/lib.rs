@@ -1,13 +1,21 @@
+#[cfg(feature = "chaos")]
+mod chaos;
 pub mod commands;
 pub mod events;
 pub mod handler;
+pub mod outbound_queue;
+pub mod pending_operations;
+pub mod reorg;
+pub mod replay;
+pub mod runtime_api_cache;
+pub mod safe_mode;
 pub mod state;
 pub mod transaction;
 pub mod typed_store;
 pub mod types;
 pub mod utils;
 
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use sc_service::RpcHandlers;
 use sp_keystore::KeystorePtr;
@@ -15,7 +23,10 @@ use sp_keystore::KeystorePtr;
 use shc_actors_framework::actor::{ActorHandle, ActorSpawner, TaskSpawner};
 use shc_common::types::ParachainClient;
 
-pub use self::handler::BlockchainService;
+pub use self::{
+    handler::BlockchainService, replay::ReplayRange, runtime_api_cache::RuntimeApiCacheConfig,
+    safe_mode::SafeModeConfig,
+};
 
 pub async fn spawn_blockchain_service<FSH>(
     task_spawner: &TaskSpawner,
@@ -25,6 +36,10 @@ pub async fn spawn_blockchain_service<FSH>(
     forest_storage_handler: FSH,
     rocksdb_root_path: impl Into<PathBuf>,
     notify_period: Option<u32>,
+    forest_root_write_lock_warn_threshold: Duration,
+    replay_range: Option<ReplayRange>,
+    runtime_api_cache_config: RuntimeApiCacheConfig,
+    safe_mode_config: SafeModeConfig,
 ) -> ActorHandle<BlockchainService<FSH>>
 where
     FSH: shc_forest_manager::traits::ForestStorageHandler + Clone + Send + Sync + 'static,
@@ -40,6 +55,10 @@ where
         forest_storage_handler,
         rocksdb_root_path,
         notify_period,
+        forest_root_write_lock_warn_threshold,
+        replay_range,
+        runtime_api_cache_config,
+        safe_mode_config,
     );
 
     task_spawner.spawn_actor(blockchain_service)
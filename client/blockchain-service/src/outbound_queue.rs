@@ -0,0 +1,441 @@
+//! Persistent queue of the extrinsics sent by this node, until they are finalised.
+//!
+//! The transaction pool only lives in memory, so an extrinsic sent by the
+//! [`BlockchainService`](crate::BlockchainService) is lost if the node restarts before it is
+//! included in a block, or if the pool drops it (e.g. while the node is disconnected). Every
+//! extrinsic sent is therefore kept in the [`OutboundExtrinsicCf`] of the persistent state, by
+//! nonce, and:
+//! - Marked as included in a block once the on-chain nonce of this node's account goes past its
+//!   nonce in the best block, and back to pending if a reorg reverts that.
+//! - Removed once the on-chain nonce goes past its nonce in a finalised block.
+//! - Resubmitted with the same nonce and a tip bumped by [`TIP_BUMP_PERCENT`] if its mortality
+//!   period ends before it is included, up to [`MAX_RESUBMISSIONS`] times. After that, it is given
+//!   up on and replaced by a `system.remark` with its nonce, so that the extrinsics with higher
+//!   nonces can still be included.
+//! - Resubmitted as is when the node restarts, if it was not included in the best block yet.
+//!
+//! The call of each extrinsic is persisted as opaque SCALE encoded bytes, tagged with the spec
+//! version of the runtime it was built for, since its encoding can change with the runtime. Entries
+//! built for another runtime version, or that can't be decoded, are replaced by a `system.remark`
+//! with their nonce when the node starts.
+//!
+//! Tasks watching the extrinsics they sent are not notified of their resubmissions, which have a
+//! different hash. They should rely on their own timeouts and retries instead.
+//!
+//! The state of the queue can be queried with
+//! [`BlockchainServiceInterface::get_outbound_extrinsic_queue`](crate::commands::BlockchainServiceInterface::get_outbound_extrinsic_queue).
+
+use std::collections::BTreeMap;
+
+use codec::{Compact, Decode, Encode};
+use sc_tracing::tracing::{debug, error, info, warn};
+use shc_common::types::BlockNumber;
+use shc_forest_manager::traits::ForestStorageHandler;
+use sp_api::ProvideRuntimeApi;
+use sp_core::H256;
+use sp_runtime::SaturatedConversion;
+use storage_hub_runtime::Balance;
+use substrate_frame_rpc_system::AccountNonceApi;
+
+use crate::{
+    handler::LOG_TARGET,
+    state::{BlockchainServiceStateStore, OutboundExtrinsicCf},
+    typed_store::ProvidesTypedDbAccess,
+    types::Tip,
+    BlockchainService,
+};
+
+/// Percentage by which the tip of an extrinsic is bumped every time it is resubmitted.
+pub const TIP_BUMP_PERCENT: Balance = 20;
+
+/// Maximum times an extrinsic whose mortality period ended is resubmitted before giving up on it.
+pub const MAX_RESUBMISSIONS: u32 = 5;
+
+/// Whether an [`OutboundExtrinsic`] was included in the best block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum OutboundExtrinsicStatus {
+    /// Sent to the transaction pool, but not included in the best block.
+    Pending,
+    /// Included in the best block, at the given block number or earlier.
+    InBlock(BlockNumber),
+}
+
+/// An extrinsic sent by this node that is not finalised yet.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct OutboundExtrinsic {
+    pub nonce: u32,
+    /// The SCALE encoded call of the extrinsic. See [`OutboundExtrinsic::decode_call`].
+    pub call: Vec<u8>,
+    /// The spec version of the runtime the call was encoded for.
+    pub spec_version: u32,
+    pub tip: Balance,
+    /// The hash of the last submission of the extrinsic.
+    pub extrinsic_hash: H256,
+    /// The block at which the extrinsic was last submitted.
+    pub sent_at: BlockNumber,
+    /// The last block at which the last submission of the extrinsic can be included, given its
+    /// mortality.
+    pub valid_until: BlockNumber,
+    /// How many times the extrinsic was resubmitted after its mortality period ended.
+    pub resubmissions: u32,
+    /// Whether this is a `system.remark` that replaced an extrinsic given up on, to take its nonce.
+    pub nonce_filler: bool,
+    pub status: OutboundExtrinsicStatus,
+}
+
+impl OutboundExtrinsic {
+    /// Decodes the call of the extrinsic, if it was encoded for the runtime this node was built
+    /// with.
+    pub fn decode_call(&self) -> Option<storage_hub_runtime::RuntimeCall> {
+        if self.spec_version != storage_hub_runtime::VERSION.spec_version {
+            return None;
+        }
+
+        storage_hub_runtime::RuntimeCall::decode(&mut self.call.as_slice()).ok()
+    }
+
+    /// A `system.remark` taking `nonce`, pending to be submitted.
+    pub fn nonce_filler(nonce: u32) -> Self {
+        Self {
+            nonce,
+            call: nonce_filler_call().encode(),
+            spec_version: storage_hub_runtime::VERSION.spec_version,
+            tip: 0,
+            extrinsic_hash: H256::zero(),
+            sent_at: 0,
+            valid_until: 0,
+            resubmissions: 0,
+            nonce_filler: true,
+            status: OutboundExtrinsicStatus::Pending,
+        }
+    }
+
+    /// Replaces the call of this extrinsic with a `system.remark`, keeping its nonce, tip and last
+    /// submission.
+    pub fn into_nonce_filler(self) -> Self {
+        Self {
+            tip: self.tip,
+            extrinsic_hash: self.extrinsic_hash,
+            sent_at: self.sent_at,
+            valid_until: self.valid_until,
+            status: self.status,
+            ..Self::nonce_filler(self.nonce)
+        }
+    }
+}
+
+/// The call sent in place of an extrinsic given up on, to take its nonce.
+fn nonce_filler_call() -> storage_hub_runtime::RuntimeCall {
+    frame_system::Call::remark { remark: Vec::new() }.into()
+}
+
+/// The extrinsics sent by this node that are not finalised yet, by nonce.
+///
+/// This is an in-memory copy of the [`OutboundExtrinsicCf`], which is written to on every change
+/// except for the [`OutboundExtrinsicStatus`], which is recomputed on every block.
+#[derive(Debug, Default)]
+pub struct OutboundExtrinsicQueue {
+    extrinsics: BTreeMap<u32, OutboundExtrinsic>,
+    /// Whether the extrinsics pending in the queue when the node started still have to be
+    /// resubmitted.
+    resubmit_on_restart: bool,
+}
+
+impl OutboundExtrinsicQueue {
+    /// Loads the queue from the persistent state.
+    ///
+    /// Extrinsics built for another runtime version, or that can't be decoded, are replaced by a
+    /// `system.remark` with their nonce, both in the queue and in the persistent state.
+    pub fn load(persistent_state: &BlockchainServiceStateStore) -> Self {
+        let mut extrinsics = BTreeMap::new();
+        let mut replaced = Vec::new();
+        for (nonce, extrinsic) in persistent_state.outbound_extrinsics() {
+            let extrinsic = match extrinsic {
+                Some(extrinsic) if extrinsic.decode_call().is_some() => extrinsic,
+                Some(extrinsic) => {
+                    replaced.push(nonce);
+                    extrinsic.into_nonce_filler()
+                }
+                None => {
+                    replaced.push(nonce);
+                    OutboundExtrinsic::nonce_filler(nonce)
+                }
+            };
+            extrinsics.insert(nonce, extrinsic);
+        }
+
+        if !replaced.is_empty() {
+            warn!(target: LOG_TARGET, "Replacing outbound extrinsics with nonces {:?} with remarks, since they were built for another runtime version", replaced);
+            let state_store_context = persistent_state.open_rw_context_with_overlay();
+            let outbound_extrinsics = state_store_context.access(&OutboundExtrinsicCf);
+            for nonce in replaced.iter() {
+                outbound_extrinsics.put(nonce, &extrinsics[nonce]);
+            }
+            drop(outbound_extrinsics);
+            state_store_context.commit();
+        }
+
+        if !extrinsics.is_empty() {
+            info!(target: LOG_TARGET, "📤 Loaded {} outbound extrinsic(s) not finalised before the node stopped", extrinsics.len());
+        }
+
+        Self {
+            resubmit_on_restart: !extrinsics.is_empty(),
+            extrinsics,
+        }
+    }
+
+    /// The extrinsics in the queue, by ascending nonce.
+    pub fn extrinsics(&self) -> Vec<OutboundExtrinsic> {
+        self.extrinsics.values().cloned().collect()
+    }
+
+    /// The nonce following the highest one in the queue, if any.
+    pub fn next_nonce(&self) -> Option<u32> {
+        self.extrinsics
+            .last_key_value()
+            .map(|(nonce, _)| nonce.saturating_add(1))
+    }
+
+    /// Updates the status of every extrinsic given the on-chain nonce of this node's account at the
+    /// best block, `block_number`.
+    pub fn update_status(&mut self, account_nonce: u32, block_number: BlockNumber) {
+        for extrinsic in self.extrinsics.values_mut() {
+            extrinsic.status = match extrinsic.status {
+                _ if extrinsic.nonce >= account_nonce => OutboundExtrinsicStatus::Pending,
+                OutboundExtrinsicStatus::InBlock(included_at) => {
+                    OutboundExtrinsicStatus::InBlock(included_at)
+                }
+                OutboundExtrinsicStatus::Pending => OutboundExtrinsicStatus::InBlock(block_number),
+            };
+        }
+    }
+
+    /// The nonces of the pending extrinsics to resubmit as of `block_number`, in ascending order.
+    ///
+    /// Those are the ones whose mortality period ended, or every pending one right after the node
+    /// restarted.
+    fn to_resubmit(&mut self, block_number: BlockNumber) -> Vec<u32> {
+        let resubmit_all = std::mem::take(&mut self.resubmit_on_restart);
+        self.extrinsics
+            .values()
+            .filter(|extrinsic| extrinsic.status == OutboundExtrinsicStatus::Pending)
+            .filter(|extrinsic| resubmit_all || extrinsic.valid_until < block_number)
+            .map(|extrinsic| extrinsic.nonce)
+            .collect()
+    }
+}
+
+/// The amount of `tip`.
+pub fn tip_amount(tip: &Tip) -> Balance {
+    // The tip is encoded as its compact amount, which is not otherwise exposed.
+    Compact::<Balance>::decode(&mut tip.encode().as_slice())
+        .map(|amount| amount.0)
+        .unwrap_or_default()
+}
+
+/// `tip` bumped by [`TIP_BUMP_PERCENT`], and at least by one.
+pub fn bumped_tip(tip: Balance) -> Balance {
+    tip.saturating_add((tip.saturating_mul(TIP_BUMP_PERCENT) / 100).max(1))
+}
+
+impl<FSH> BlockchainService<FSH>
+where
+    FSH: ForestStorageHandler + Clone + Send + Sync + 'static,
+{
+    /// Adds an extrinsic that was just sent to the outbound queue.
+    pub(crate) fn enqueue_outbound_extrinsic(&mut self, extrinsic: OutboundExtrinsic) {
+        let state_store_context = self.persistent_state.open_rw_context_with_overlay();
+        state_store_context
+            .access(&OutboundExtrinsicCf)
+            .put(&extrinsic.nonce, &extrinsic);
+        state_store_context.commit();
+
+        self.outbound_queue
+            .extrinsics
+            .insert(extrinsic.nonce, extrinsic);
+    }
+
+    /// Resubmits the pending extrinsics of the outbound queue whose mortality period ended as of
+    /// `block_number`, or every pending one if the node just restarted.
+    pub(crate) async fn resubmit_outbound_extrinsics(&mut self, block_number: BlockNumber) {
+        for nonce in self.outbound_queue.to_resubmit(block_number) {
+            let Some(mut extrinsic) = self.outbound_queue.extrinsics.get(&nonce).cloned() else {
+                continue;
+            };
+
+            // Extrinsics resubmitted after a restart were not necessarily expired.
+            let expired = extrinsic.valid_until < block_number;
+            if expired && extrinsic.resubmissions >= MAX_RESUBMISSIONS {
+                if extrinsic.nonce_filler {
+                    error!(target: LOG_TARGET, "🚨 Giving up on the remark taking nonce {} after {} resubmissions. The extrinsics with higher nonces can't be included until another one with this nonce is.", nonce, extrinsic.resubmissions);
+                    self.remove_outbound_extrinsics(|extrinsic| extrinsic.nonce == nonce);
+                    continue;
+                }
+
+                error!(target: LOG_TARGET, "🚨 Giving up on outbound extrinsic with nonce {} after {} resubmissions. Sending a remark with its nonce instead, so that the extrinsics with higher nonces can be included.", nonce, extrinsic.resubmissions);
+                extrinsic = extrinsic.into_nonce_filler();
+            }
+
+            // Every extrinsic in the queue was built for this runtime, as the ones loaded on start
+            // that were not are replaced.
+            let Some(call) = extrinsic.decode_call() else {
+                error!(target: LOG_TARGET, "Failed to decode outbound extrinsic with nonce {}. Skipping it.", nonce);
+                continue;
+            };
+
+            let tip = if expired {
+                bumped_tip(extrinsic.tip)
+            } else {
+                extrinsic.tip
+            };
+
+            match self.submit_extrinsic(call, nonce, Tip::from(tip)).await {
+                Ok(output) => {
+                    info!(target: LOG_TARGET, "📤 Resubmitted outbound extrinsic with nonce {} and tip {}: {:?}", nonce, tip, output.hash);
+                    self.enqueue_outbound_extrinsic(OutboundExtrinsic {
+                        tip,
+                        extrinsic_hash: output.hash,
+                        sent_at: block_number,
+                        valid_until: block_number
+                            .saturating_add(Self::extrinsic_mortality_period().saturated_into()),
+                        resubmissions: extrinsic.resubmissions.saturating_add(expired as u32),
+                        ..extrinsic
+                    });
+                }
+                Err(e) => {
+                    // It is resubmitted again on the next block, counting towards the limit if expired.
+                    warn!(target: LOG_TARGET, "Failed to resubmit outbound extrinsic with nonce {}: {:?}", nonce, e);
+                    if expired {
+                        self.enqueue_outbound_extrinsic(OutboundExtrinsic {
+                            resubmissions: extrinsic.resubmissions.saturating_add(1),
+                            ..extrinsic
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the extrinsics of the outbound queue included in the finalised block `block_hash`.
+    pub(crate) fn prune_finalised_outbound_extrinsics(&mut self, block_hash: &H256) {
+        let pub_key = Self::caller_pub_key(self.keystore.clone());
+        let account_nonce = match self
+            .client
+            .runtime_api()
+            .account_nonce(*block_hash, pub_key.into())
+        {
+            Ok(account_nonce) => account_nonce,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to get account nonce at finalised block {:?}: {:?}", block_hash, e);
+                return;
+            }
+        };
+
+        self.remove_outbound_extrinsics(|extrinsic| extrinsic.nonce < account_nonce);
+    }
+
+    fn remove_outbound_extrinsics(&mut self, f: impl Fn(&OutboundExtrinsic) -> bool) {
+        let removed: Vec<u32> = self
+            .outbound_queue
+            .extrinsics
+            .values()
+            .filter(|extrinsic| f(extrinsic))
+            .map(|extrinsic| extrinsic.nonce)
+            .collect();
+        if removed.is_empty() {
+            return;
+        }
+
+        let state_store_context = self.persistent_state.open_rw_context_with_overlay();
+        let outbound_extrinsics = state_store_context.access(&OutboundExtrinsicCf);
+        for nonce in removed.iter() {
+            outbound_extrinsics.delete(nonce);
+            self.outbound_queue.extrinsics.remove(nonce);
+        }
+        drop(outbound_extrinsics);
+        state_store_context.commit();
+
+        debug!(target: LOG_TARGET, "Removed outbound extrinsics with nonces {:?}, {} left", removed, self.outbound_queue.extrinsics.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outbound_extrinsic(call: &storage_hub_runtime::RuntimeCall) -> OutboundExtrinsic {
+        OutboundExtrinsic {
+            nonce: 7,
+            call: call.encode(),
+            spec_version: storage_hub_runtime::VERSION.spec_version,
+            tip: 100,
+            extrinsic_hash: H256::repeat_byte(1),
+            sent_at: 10,
+            valid_until: 42,
+            resubmissions: MAX_RESUBMISSIONS,
+            nonce_filler: false,
+            status: OutboundExtrinsicStatus::Pending,
+        }
+    }
+
+    #[test]
+    fn call_is_decoded_for_the_runtime_it_was_built_for() {
+        let call = frame_system::Call::remark {
+            remark: b"hello".to_vec(),
+        }
+        .into();
+
+        assert_eq!(outbound_extrinsic(&call).decode_call(), Some(call));
+    }
+
+    #[test]
+    fn call_is_not_decoded_for_another_runtime_version() {
+        let call = frame_system::Call::remark {
+            remark: b"hello".to_vec(),
+        }
+        .into();
+        let extrinsic = OutboundExtrinsic {
+            spec_version: storage_hub_runtime::VERSION.spec_version + 1,
+            ..outbound_extrinsic(&call)
+        };
+
+        assert_eq!(extrinsic.decode_call(), None);
+    }
+
+    #[test]
+    fn undecodable_call_is_not_decoded() {
+        let call = frame_system::Call::remark {
+            remark: b"hello".to_vec(),
+        }
+        .into();
+        let extrinsic = OutboundExtrinsic {
+            call: vec![u8::MAX; 3],
+            ..outbound_extrinsic(&call)
+        };
+
+        assert_eq!(extrinsic.decode_call(), None);
+    }
+
+    #[test]
+    fn nonce_filler_keeps_the_nonce_and_tip_of_the_extrinsic_it_replaces() {
+        let call = frame_system::Call::remark {
+            remark: b"hello".to_vec(),
+        }
+        .into();
+        let extrinsic = OutboundExtrinsic {
+            spec_version: storage_hub_runtime::VERSION.spec_version + 1,
+            ..outbound_extrinsic(&call)
+        };
+
+        let filler = extrinsic.clone().into_nonce_filler();
+
+        assert_eq!(filler.decode_call(), Some(nonce_filler_call()));
+        assert!(filler.nonce_filler);
+        assert_eq!(filler.resubmissions, 0);
+        assert_eq!(
+            (filler.nonce, filler.tip, filler.valid_until),
+            (extrinsic.nonce, extrinsic.tip, extrinsic.valid_until)
+        );
+    }
+}
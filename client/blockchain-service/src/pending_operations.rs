@@ -0,0 +1,190 @@
+//! Tracking of the extrinsics sent by this node that are still in flight.
+//!
+//! Every extrinsic sent by the [`BlockchainService`](crate::BlockchainService) that operates on a
+//! file key or a bucket is indexed here by those keys, until it is included in a block (i.e. the
+//! on-chain nonce of this node's account goes past it) or its mortality period ends. Tasks can then
+//! check if there is an operation in flight for a key before sending a conflicting extrinsic (e.g.
+//! a BSP stopping to store a file for which it is still confirming storage), and defer or cancel it
+//! instead of wasting fees on an extrinsic that is bound to fail.
+
+use std::collections::HashMap;
+
+use frame_support::traits::GetCallMetadata;
+use shc_common::types::{BlockNumber, BucketId};
+use sp_core::H256;
+
+/// A key that in-flight operations are indexed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationKey {
+    FileKey(H256),
+    Bucket(BucketId),
+}
+
+/// An extrinsic sent by this node that has not been included in a block yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingOperation {
+    pub extrinsic_hash: H256,
+    pub nonce: u32,
+    pub pallet_name: &'static str,
+    pub call_name: &'static str,
+    /// The block at which the extrinsic was sent.
+    pub sent_at: BlockNumber,
+    /// The last block at which the extrinsic can be included, given its mortality.
+    pub valid_until: BlockNumber,
+}
+
+/// The in-flight operations sent by this node, indexed by the keys they operate on.
+#[derive(Debug, Default)]
+pub struct PendingOperations {
+    operations: HashMap<OperationKey, Vec<PendingOperation>>,
+}
+
+impl PendingOperations {
+    /// Records `operation` as in flight for every key in `keys`.
+    pub fn insert(&mut self, keys: Vec<OperationKey>, operation: PendingOperation) {
+        for key in keys {
+            self.operations
+                .entry(key)
+                .or_default()
+                .push(operation.clone());
+        }
+    }
+
+    /// Whether there is any operation in flight for `key`.
+    pub fn is_pending(&self, key: &OperationKey) -> bool {
+        self.operations.contains_key(key)
+    }
+
+    /// The operations in flight for `key`, oldest first.
+    pub fn get(&self, key: &OperationKey) -> &[PendingOperation] {
+        self.operations
+            .get(key)
+            .map(|operations| operations.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Drops the operations whose nonce is below `account_nonce`, which means that they (or another
+    /// extrinsic with the same nonce) were already included in a block.
+    pub fn prune_included(&mut self, account_nonce: u32) {
+        self.retain(|operation| operation.nonce >= account_nonce);
+    }
+
+    /// Drops the operations that can no longer be included as of `block_number`.
+    pub fn prune_expired(&mut self, block_number: BlockNumber) {
+        self.retain(|operation| operation.valid_until >= block_number);
+    }
+
+    fn retain(&mut self, f: impl Fn(&PendingOperation) -> bool) {
+        self.operations.retain(|_, operations| {
+            operations.retain(|operation| f(operation));
+            !operations.is_empty()
+        });
+    }
+}
+
+/// Builds the [`PendingOperation`] for `call`, sent with `nonce` at block `sent_at`.
+pub fn pending_operation(
+    call: &storage_hub_runtime::RuntimeCall,
+    extrinsic_hash: H256,
+    nonce: u32,
+    sent_at: BlockNumber,
+    mortality_period: BlockNumber,
+) -> PendingOperation {
+    let metadata = call.get_call_metadata();
+    PendingOperation {
+        extrinsic_hash,
+        nonce,
+        pallet_name: metadata.pallet_name,
+        call_name: metadata.function_name,
+        sent_at,
+        valid_until: sent_at.saturating_add(mortality_period),
+    }
+}
+
+/// The file keys and buckets that `call` operates on.
+///
+/// Only calls that modify the state of a file or a bucket are taken into account.
+pub fn operation_keys(call: &storage_hub_runtime::RuntimeCall) -> Vec<OperationKey> {
+    use pallet_file_system::Call as FileSystemCall;
+
+    let storage_hub_runtime::RuntimeCall::FileSystem(call) = call else {
+        return Vec::new();
+    };
+
+    match call {
+        FileSystemCall::bsp_volunteer { file_key }
+        | FileSystemCall::bsp_confirm_stop_storing { file_key, .. }
+        | FileSystemCall::msp_confirm_file_move { file_key, .. }
+        | FileSystemCall::bsp_confirm_file_move { file_key, .. } => {
+            vec![OperationKey::FileKey(*file_key)]
+        }
+        FileSystemCall::bsp_confirm_storing {
+            file_keys_and_proofs,
+            ..
+        } => file_keys_and_proofs
+            .iter()
+            .map(|(file_key, _)| OperationKey::FileKey(*file_key))
+            .collect(),
+        FileSystemCall::bsp_request_stop_storing {
+            file_key,
+            bucket_id,
+            ..
+        }
+        | FileSystemCall::stop_storing_for_insolvent_user {
+            file_key,
+            bucket_id,
+            ..
+        }
+        | FileSystemCall::delete_file {
+            file_key,
+            bucket_id,
+            ..
+        }
+        | FileSystemCall::request_move_file {
+            file_key,
+            bucket_id,
+            ..
+        }
+        | FileSystemCall::pending_file_deletion_request_submit_proof {
+            file_key,
+            bucket_id,
+            ..
+        } => vec![
+            OperationKey::FileKey(*file_key),
+            OperationKey::Bucket(*bucket_id),
+        ],
+        FileSystemCall::msp_respond_storage_requests_multiple_buckets {
+            storage_request_msp_response,
+        } => storage_request_msp_response
+            .iter()
+            .flat_map(|bucket_response| {
+                let accepted = bucket_response.accept.iter().flat_map(|accepted| {
+                    accepted
+                        .file_keys_and_proofs
+                        .iter()
+                        .map(|file_key_with_proof| file_key_with_proof.file_key)
+                });
+                let rejected = bucket_response
+                    .reject
+                    .iter()
+                    .map(|rejected| rejected.file_key);
+
+                std::iter::once(OperationKey::Bucket(bucket_response.bucket_id))
+                    .chain(accepted.chain(rejected).map(OperationKey::FileKey))
+            })
+            .collect(),
+        FileSystemCall::delete_files { bucket_id, files } => {
+            std::iter::once(OperationKey::Bucket(*bucket_id))
+                .chain(
+                    files
+                        .iter()
+                        .map(|file| OperationKey::FileKey(file.file_key)),
+                )
+                .collect()
+        }
+        FileSystemCall::msp_stop_storing_bucket { bucket_id }
+        | FileSystemCall::msp_respond_move_bucket_request { bucket_id, .. }
+        | FileSystemCall::delete_bucket { bucket_id } => vec![OperationKey::Bucket(*bucket_id)],
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,361 @@
+//! Tracking of the events emitted for best blocks, until their blocks are finalised or reorged out.
+//!
+//! Most events are emitted by the BlockchainService as soon as the block they come from is
+//! imported as the new best block, so the tasks reacting to them might act on a block that is
+//! later reorged out (e.g. starting to receive a file for a storage request that never existed in
+//! the final chain). For the events that start such work, the BlockchainService keeps track of the
+//! best blocks they were emitted for and, once each of those blocks is:
+//! - Finalised: re-emits them wrapped in [`Finalised`], for the tasks that should only act on
+//!   final data.
+//! - Reorged out (retracted from the best chain): emits them wrapped in [`Reverted`], for the tasks
+//!   that acted on them to undo their work.
+//!
+//! The tracked events are [`NewStorageRequest`], [`AcceptedBspVolunteer`],
+//! [`BspConfirmedStoring`] and [`MoveBucketRequested`]. For instance, the BSP and MSP upload tasks
+//! handle the [`Reverted`] [`NewStorageRequest`] to stop receiving the files whose storage request
+//! was reorged out. Tasks opt into finalised-only delivery of
+//! any of them by subscribing through the [`FinalisedOnly`] adapter, instead of subscribing
+//! directly:
+//!
+//! ```ignore
+//! FinalisedOnly(task).subscribe_to(&task_spawner, &blockchain).start();
+//! ```
+
+use std::collections::HashMap;
+
+use sc_tracing::tracing::{debug, info};
+use shc_actors_framework::{
+    actor::Actor,
+    event_bus::{EventBusMessage, EventHandler},
+};
+use shc_common::types::BlockNumber;
+use shc_forest_manager::traits::ForestStorageHandler;
+use sp_core::H256;
+
+use crate::{
+    events::{
+        AcceptedBspVolunteer, BspConfirmedStoring, Finalised, MoveBucketRequested,
+        NewStorageRequest, Reverted,
+    },
+    handler::LOG_TARGET,
+    BlockchainService,
+};
+
+/// An event emitted for a best block that is tracked until the block is finalised or reorged out.
+#[derive(Debug, Clone)]
+pub(crate) enum TrackedEvent {
+    NewStorageRequest(NewStorageRequest),
+    AcceptedBspVolunteer(AcceptedBspVolunteer),
+    BspConfirmedStoring(BspConfirmedStoring),
+    MoveBucketRequested(MoveBucketRequested),
+}
+
+impl From<NewStorageRequest> for TrackedEvent {
+    fn from(event: NewStorageRequest) -> Self {
+        Self::NewStorageRequest(event)
+    }
+}
+
+impl From<AcceptedBspVolunteer> for TrackedEvent {
+    fn from(event: AcceptedBspVolunteer) -> Self {
+        Self::AcceptedBspVolunteer(event)
+    }
+}
+
+impl From<BspConfirmedStoring> for TrackedEvent {
+    fn from(event: BspConfirmedStoring) -> Self {
+        Self::BspConfirmedStoring(event)
+    }
+}
+
+impl From<MoveBucketRequested> for TrackedEvent {
+    fn from(event: MoveBucketRequested) -> Self {
+        Self::MoveBucketRequested(event)
+    }
+}
+
+/// The tracked events emitted for a best block, in the order they were emitted.
+#[derive(Debug, Clone)]
+pub(crate) struct TrackedBlock {
+    pub number: BlockNumber,
+    pub events: Vec<TrackedEvent>,
+}
+
+/// The best blocks processed by the BlockchainService that are neither finalised nor reorged out
+/// yet, with the tracked events emitted for them.
+///
+/// Blocks for which no tracked event was emitted are not kept.
+#[derive(Debug, Default)]
+pub(crate) struct ReorgTracker {
+    blocks: HashMap<H256, TrackedBlock>,
+}
+
+impl ReorgTracker {
+    /// Tracks `event`, emitted for the best block `block_hash`.
+    pub fn track(&mut self, block_hash: H256, block_number: BlockNumber, event: TrackedEvent) {
+        self.blocks
+            .entry(block_hash)
+            .or_insert_with(|| TrackedBlock {
+                number: block_number,
+                events: Vec::new(),
+            })
+            .events
+            .push(event);
+    }
+
+    /// Stops tracking the block `block_hash`, returning its tracked events.
+    pub fn take(&mut self, block_hash: &H256) -> Option<TrackedBlock> {
+        self.blocks.remove(block_hash)
+    }
+
+    /// Stops tracking every block up to `block_number`, returning them by ascending block number.
+    pub fn take_up_to(&mut self, block_number: BlockNumber) -> Vec<(H256, TrackedBlock)> {
+        let hashes: Vec<H256> = self
+            .blocks
+            .iter()
+            .filter(|(_, block)| block.number <= block_number)
+            .map(|(hash, _)| *hash)
+            .collect();
+
+        let mut blocks: Vec<(H256, TrackedBlock)> = hashes
+            .into_iter()
+            .filter_map(|hash| self.blocks.remove(&hash).map(|block| (hash, block)))
+            .collect();
+        blocks.sort_by_key(|(_, block)| block.number);
+        blocks
+    }
+}
+
+/// Adapter to subscribe an [`EventHandler`] to the [`Finalised`] version of the events it handles,
+/// so that it only acts on events from finalised blocks.
+#[derive(Clone)]
+pub struct FinalisedOnly<H>(pub H);
+
+impl<E, H> EventHandler<Finalised<E>> for FinalisedOnly<H>
+where
+    E: EventBusMessage,
+    H: EventHandler<E>,
+{
+    async fn handle_event(&mut self, event: Finalised<E>) -> anyhow::Result<()> {
+        self.0.handle_event(event.event).await
+    }
+}
+
+impl<FSH> BlockchainService<FSH>
+where
+    FSH: ForestStorageHandler + Clone + Send + Sync + 'static,
+{
+    /// Emits the [`Reverted`] events of the tracked blocks in `retracted`, which were reorged out
+    /// of the best chain, from the newest block to the oldest.
+    pub(crate) fn revert_retracted_blocks(&mut self, retracted: &[H256]) {
+        for block_hash in retracted {
+            let Some(block) = self.reorg_tracker.take(block_hash) else {
+                continue;
+            };
+
+            info!(target: LOG_TARGET, "↩️ Reverting {} event(s) emitted for block #{} ({}), reorged out", block.events.len(), block.number, block_hash);
+
+            for event in block.events.into_iter().rev() {
+                self.emit_reverted(block.number, *block_hash, event);
+            }
+        }
+    }
+
+    /// Emits the [`Finalised`] events of the tracked blocks in `finalised`, in ascending order,
+    /// finalised up to `block_number`.
+    ///
+    /// The tracked blocks left up to `block_number` are on forks that were discarded without this
+    /// node seeing them reorged out, so their [`Reverted`] events are emitted instead.
+    pub(crate) fn finalise_tracked_blocks(
+        &mut self,
+        finalised: &[H256],
+        block_number: BlockNumber,
+    ) {
+        for block_hash in finalised {
+            let Some(block) = self.reorg_tracker.take(block_hash) else {
+                continue;
+            };
+
+            debug!(target: LOG_TARGET, "Finalising {} event(s) emitted for block #{} ({})", block.events.len(), block.number, block_hash);
+
+            for event in block.events {
+                self.emit_finalised(block.number, *block_hash, event);
+            }
+        }
+
+        for (block_hash, block) in self
+            .reorg_tracker
+            .take_up_to(block_number)
+            .into_iter()
+            .rev()
+        {
+            info!(target: LOG_TARGET, "↩️ Reverting {} event(s) emitted for block #{} ({}), in a discarded fork", block.events.len(), block.number, block_hash);
+
+            for event in block.events.into_iter().rev() {
+                self.emit_reverted(block.number, block_hash, event);
+            }
+        }
+    }
+
+    fn emit_finalised(&self, block_number: BlockNumber, block_hash: H256, event: TrackedEvent) {
+        match event {
+            TrackedEvent::NewStorageRequest(event) => self.emit(Finalised {
+                block_number,
+                block_hash,
+                event,
+            }),
+            TrackedEvent::AcceptedBspVolunteer(event) => self.emit(Finalised {
+                block_number,
+                block_hash,
+                event,
+            }),
+            TrackedEvent::BspConfirmedStoring(event) => self.emit(Finalised {
+                block_number,
+                block_hash,
+                event,
+            }),
+            TrackedEvent::MoveBucketRequested(event) => self.emit(Finalised {
+                block_number,
+                block_hash,
+                event,
+            }),
+        }
+    }
+
+    fn emit_reverted(&self, block_number: BlockNumber, block_hash: H256, event: TrackedEvent) {
+        match event {
+            TrackedEvent::NewStorageRequest(event) => self.emit(Reverted {
+                block_number,
+                block_hash,
+                event,
+            }),
+            TrackedEvent::AcceptedBspVolunteer(event) => self.emit(Reverted {
+                block_number,
+                block_hash,
+                event,
+            }),
+            TrackedEvent::BspConfirmedStoring(event) => self.emit(Reverted {
+                block_number,
+                block_hash,
+                event,
+            }),
+            TrackedEvent::MoveBucketRequested(event) => self.emit(Reverted {
+                block_number,
+                block_hash,
+                event,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(bucket: u8) -> TrackedEvent {
+        MoveBucketRequested {
+            bucket_id: H256::repeat_byte(bucket),
+            new_msp_id: H256::repeat_byte(u8::MAX),
+        }
+        .into()
+    }
+
+    fn buckets(block: &TrackedBlock) -> Vec<u8> {
+        block
+            .events
+            .iter()
+            .map(|event| match event {
+                TrackedEvent::MoveBucketRequested(event) => event.bucket_id.0[0],
+                event => panic!("unexpected tracked event {:?}", event),
+            })
+            .collect()
+    }
+
+    fn hash(block: u8) -> H256 {
+        H256::repeat_byte(block)
+    }
+
+    #[test]
+    fn events_are_tracked_by_block_in_emission_order() {
+        let mut tracker = ReorgTracker::default();
+        tracker.track(hash(1), 1, event(1));
+        tracker.track(hash(1), 1, event(2));
+        tracker.track(hash(2), 2, event(3));
+
+        let block = tracker.take(&hash(1)).expect("block 1 is tracked");
+        assert_eq!(block.number, 1);
+        assert_eq!(buckets(&block), vec![1, 2]);
+        assert!(tracker.take(&hash(1)).is_none());
+        assert!(tracker.take(&hash(2)).is_some());
+    }
+
+    #[test]
+    fn reorg_below_tracked_depth_only_takes_tracked_retracted_blocks() {
+        let mut tracker = ReorgTracker::default();
+        // Blocks #1 to #3 of the old fork had no tracked events, unlike #4 and #5.
+        tracker.track(hash(4), 4, event(4));
+        tracker.track(hash(5), 5, event(5));
+        // A block of the new fork, which is kept.
+        tracker.track(hash(0xA3), 3, event(0xA3));
+
+        let retracted: Vec<TrackedBlock> = (1..=5)
+            .filter_map(|block| tracker.take(&hash(block)))
+            .collect();
+
+        assert_eq!(
+            retracted
+                .iter()
+                .map(|block| block.number)
+                .collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+        assert_eq!(
+            tracker
+                .take_up_to(BlockNumber::MAX)
+                .into_iter()
+                .map(|(hash, _)| hash)
+                .collect::<Vec<_>>(),
+            vec![hash(0xA3)]
+        );
+    }
+
+    #[test]
+    fn finalisation_prunes_blocks_up_to_the_finalised_number_in_ascending_order() {
+        let mut tracker = ReorgTracker::default();
+        tracker.track(hash(3), 3, event(3));
+        tracker.track(hash(1), 1, event(1));
+        tracker.track(hash(0xA2), 2, event(0xA2));
+        tracker.track(hash(2), 2, event(2));
+        tracker.track(hash(4), 4, event(4));
+
+        let pruned = tracker.take_up_to(3);
+
+        assert_eq!(
+            pruned
+                .iter()
+                .map(|(_, block)| block.number)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 2, 3]
+        );
+        assert!(tracker.take_up_to(3).is_empty());
+        let left = tracker.take(&hash(4)).expect("block 4 is not finalised");
+        assert_eq!(buckets(&left), vec![4]);
+    }
+
+    #[test]
+    fn event_reimported_on_the_new_fork_is_tracked_for_the_new_block() {
+        let mut tracker = ReorgTracker::default();
+        tracker.track(hash(5), 5, event(1));
+
+        // The old block is reorged out, and the same event is emitted for the new fork's block.
+        let reverted = tracker.take(&hash(5)).expect("old block is tracked");
+        assert_eq!(buckets(&reverted), vec![1]);
+        tracker.track(hash(0xA5), 5, event(1));
+
+        // Finalising the new fork's block no longer sees the old one.
+        assert!(tracker.take(&hash(5)).is_none());
+        let finalised = tracker.take(&hash(0xA5)).expect("new block is tracked");
+        assert_eq!(buckets(&finalised), vec![1]);
+        assert!(tracker.take_up_to(5).is_empty());
+    }
+}
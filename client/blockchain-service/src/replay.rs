@@ -0,0 +1,287 @@
+//! Audit log of the events emitted for finalised blocks, and deterministic replay of them.
+//!
+//! For every finalised block, the BlockchainService records the events it emits in the
+//! [`FinalisedBlockAuditLogCf`] of its persistent state, keeping the last
+//! [`FINALISED_BLOCK_AUDIT_LOG_RETENTION`] blocks.
+//!
+//! When started in replay mode (with a [`ReplayRange`]), the BlockchainService does not follow the
+//! chain. Once the node is synced and the Provider it manages is known, it processes the events of
+//! each finalised block in the range as it would have, re-emitting the resulting events to the
+//! tasks, and highlights the blocks where they diverge from the ones recorded in the audit log.
+//! Submitting extrinsics is stubbed out for as long as the node runs in replay mode, so that tasks
+//! reacting to the replayed events have no side effects on-chain.
+
+use anyhow::{anyhow, Result};
+use codec::{Decode, Encode};
+use sc_client_api::HeaderBackend;
+use sc_tracing::tracing::{error, info, warn};
+use shc_actors_framework::actor::Actor;
+use shc_common::{
+    blockchain_utils::get_events_at_block,
+    types::{BlockNumber, StorageProviderId},
+};
+use shc_forest_manager::traits::ForestStorageHandler;
+use sp_core::H256;
+use sp_runtime::SaturatedConversion;
+use storage_hub_runtime::RuntimeEvent;
+
+use crate::{
+    events::{
+        FinalisedBspConfirmStoppedStoring, FinalisedMoveBucketAccepted,
+        FinalisedMspStoppedStoringBucket, FinalisedTrieRemoveMutationsApplied,
+    },
+    handler::LOG_TARGET,
+    state::FinalisedBlockAuditLogCf,
+    typed_store::ProvidesTypedDbAccess,
+    BlockchainService,
+};
+
+/// Amount of finalised blocks for which the emitted events are kept in the audit log.
+///
+/// With a block every 6 seconds, this is roughly a week.
+pub const FINALISED_BLOCK_AUDIT_LOG_RETENTION: BlockNumber = 100_800;
+
+/// A range of finalised blocks to replay, both bounds inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayRange {
+    pub from: BlockNumber,
+    pub to: BlockNumber,
+}
+
+/// The events emitted for a finalised block, as recorded in the audit log.
+///
+/// Events are recorded by their debug representation, which is enough to spot divergences while
+/// not tying the persistent state to the encoding of every event.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct FinalisedBlockAuditRecord {
+    pub block_hash: H256,
+    pub events: Vec<String>,
+}
+
+/// An event the BlockchainService emits when processing a finalised block.
+#[derive(Debug, Clone)]
+pub(crate) enum FinalisedBlockEvent {
+    TrieRemoveMutationsApplied(FinalisedTrieRemoveMutationsApplied),
+    MspStoppedStoringBucket(FinalisedMspStoppedStoringBucket),
+    BspConfirmStoppedStoring(FinalisedBspConfirmStoppedStoring),
+    MoveBucketAccepted(FinalisedMoveBucketAccepted),
+}
+
+impl FinalisedBlockEvent {
+    fn describe(&self) -> String {
+        match self {
+            Self::TrieRemoveMutationsApplied(event) => format!("{:?}", event),
+            Self::MspStoppedStoringBucket(event) => format!("{:?}", event),
+            Self::BspConfirmStoppedStoring(event) => format!("{:?}", event),
+            Self::MoveBucketAccepted(event) => format!("{:?}", event),
+        }
+    }
+}
+
+impl<FSH> BlockchainService<FSH>
+where
+    FSH: ForestStorageHandler + Clone + Send + Sync + 'static,
+{
+    /// Computes the events to emit for the finalised block `block_hash`.
+    ///
+    /// Only depends on the runtime events of the block and the Provider managed by this node, so
+    /// that processing the same block again yields the same events.
+    pub(crate) fn finalised_block_events(
+        &self,
+        block_hash: &H256,
+    ) -> Result<Vec<FinalisedBlockEvent>> {
+        let block_events = get_events_at_block(&self.client, block_hash)
+            .map_err(|e| anyhow!("Failed to get events storage element: {:?}", e))?;
+
+        let mut events = Vec::new();
+        for ev in block_events {
+            match ev.event {
+                RuntimeEvent::ProofsDealer(pallet_proofs_dealer::Event::MutationsApplied {
+                    provider: provider_id,
+                    mutations,
+                    new_root,
+                }) => {
+                    // This event is relevant in case the Provider managed is a BSP.
+                    if let Some(StorageProviderId::BackupStorageProvider(managed_bsp_id)) =
+                        &self.provider_id
+                    {
+                        // We only emit the event if the Provider ID is the one that this node is managing.
+                        if provider_id == *managed_bsp_id {
+                            events.push(FinalisedBlockEvent::TrieRemoveMutationsApplied(
+                                FinalisedTrieRemoveMutationsApplied {
+                                    provider_id,
+                                    mutations: mutations.into(),
+                                    new_root,
+                                },
+                            ));
+                        }
+                    }
+                }
+                RuntimeEvent::FileSystem(pallet_file_system::Event::MspStoppedStoringBucket {
+                    msp_id,
+                    owner,
+                    bucket_id,
+                }) => {
+                    // This event is relevant in case the Provider managed is an MSP.
+                    if let Some(StorageProviderId::MainStorageProvider(managed_msp_id)) =
+                        &self.provider_id
+                    {
+                        if msp_id == *managed_msp_id {
+                            events.push(FinalisedBlockEvent::MspStoppedStoringBucket(
+                                FinalisedMspStoppedStoringBucket {
+                                    msp_id,
+                                    owner,
+                                    bucket_id,
+                                },
+                            ));
+                        }
+                    }
+                }
+                RuntimeEvent::FileSystem(pallet_file_system::Event::BspConfirmStoppedStoring {
+                    bsp_id,
+                    file_key,
+                    new_root,
+                }) => {
+                    // This event is relevant in case the Provider managed is a BSP.
+                    if let Some(StorageProviderId::BackupStorageProvider(managed_bsp_id)) =
+                        &self.provider_id
+                    {
+                        if managed_bsp_id == &bsp_id {
+                            events.push(FinalisedBlockEvent::BspConfirmStoppedStoring(
+                                FinalisedBspConfirmStoppedStoring {
+                                    bsp_id,
+                                    file_key: file_key.into(),
+                                    new_root,
+                                },
+                            ));
+                        }
+                    }
+                }
+                RuntimeEvent::FileSystem(pallet_file_system::Event::MoveBucketAccepted {
+                    bucket_id,
+                    msp_id,
+                }) => {
+                    // This event is relevant in case the Provider managed is an MSP, which might
+                    // have been storing the bucket moved away from it.
+                    if let Some(StorageProviderId::MainStorageProvider(managed_msp_id)) =
+                        &self.provider_id
+                    {
+                        if msp_id != *managed_msp_id {
+                            events.push(FinalisedBlockEvent::MoveBucketAccepted(
+                                FinalisedMoveBucketAccepted {
+                                    bucket_id,
+                                    new_msp_id: msp_id,
+                                },
+                            ));
+                        }
+                    }
+                }
+                // Ignore all other events.
+                _ => {}
+            }
+        }
+
+        Ok(events)
+    }
+
+    pub(crate) fn emit_finalised_block_event(&self, event: FinalisedBlockEvent) {
+        match event {
+            FinalisedBlockEvent::TrieRemoveMutationsApplied(event) => self.emit(event),
+            FinalisedBlockEvent::MspStoppedStoringBucket(event) => self.emit(event),
+            FinalisedBlockEvent::BspConfirmStoppedStoring(event) => self.emit(event),
+            FinalisedBlockEvent::MoveBucketAccepted(event) => self.emit(event),
+        }
+    }
+
+    /// Records the events emitted for a finalised block in the audit log, pruning the records that
+    /// fall out of the retention window.
+    pub(crate) fn record_finalised_block_audit(
+        &self,
+        block_number: BlockNumber,
+        block_hash: H256,
+        events: &[FinalisedBlockEvent],
+    ) {
+        let state_store_context = self.persistent_state.open_rw_context_with_overlay();
+        let audit_log = state_store_context.access(&FinalisedBlockAuditLogCf);
+        audit_log.put(
+            &block_number,
+            &FinalisedBlockAuditRecord {
+                block_hash,
+                events: events.iter().map(FinalisedBlockEvent::describe).collect(),
+            },
+        );
+        if let Some(expired) = block_number.checked_sub(FINALISED_BLOCK_AUDIT_LOG_RETENTION) {
+            audit_log.delete(&expired);
+        }
+        drop(audit_log);
+        state_store_context.commit();
+    }
+
+    /// Replays the finalised blocks in `range`, re-emitting the events they produce and comparing
+    /// them against the audit log.
+    ///
+    /// Returns the amount of blocks whose events diverge from the recorded ones.
+    pub(crate) fn replay_finalised_blocks(&self, range: ReplayRange) -> Result<u32> {
+        let finalised_number: BlockNumber = self.client.info().finalized_number.saturated_into();
+        if range.from > range.to || range.to > finalised_number {
+            return Err(anyhow!(
+                "Invalid replay range {}..={}: the last finalised block is #{}",
+                range.from,
+                range.to,
+                finalised_number
+            ));
+        }
+
+        info!(target: LOG_TARGET, "🔁 Replaying finalised blocks #{} to #{}, for Provider {:?}", range.from, range.to, self.provider_id);
+
+        let mut divergences = 0;
+        for block_number in range.from..=range.to {
+            let block_hash = self
+                .client
+                .hash(block_number)?
+                .ok_or_else(|| anyhow!("Block #{} not found", block_number))?;
+
+            let events = self.finalised_block_events(&block_hash)?;
+            let replayed: Vec<String> = events.iter().map(FinalisedBlockEvent::describe).collect();
+
+            let recorded = self
+                .persistent_state
+                .open_rw_context_with_overlay()
+                .access(&FinalisedBlockAuditLogCf)
+                .get(&block_number);
+
+            match recorded {
+                Some(record) if record.block_hash == block_hash && record.events == replayed => {
+                    info!(target: LOG_TARGET, "🔁 Block #{} ({}): {} event(s) replayed, matching the audit log", block_number, block_hash, replayed.len());
+                }
+                Some(record) => {
+                    divergences += 1;
+                    warn!(
+                        target: LOG_TARGET,
+                        "⚠️ Block #{} ({}) diverges from the audit log!\n  recorded (block {}): {:#?}\n  replayed: {:#?}",
+                        block_number,
+                        block_hash,
+                        record.block_hash,
+                        record.events,
+                        replayed
+                    );
+                }
+                None => {
+                    warn!(target: LOG_TARGET, "🔁 Block #{} ({}): {} event(s) replayed, not in the audit log: {:#?}", block_number, block_hash, replayed.len(), replayed);
+                }
+            }
+
+            for event in events {
+                self.emit_finalised_block_event(event);
+            }
+        }
+
+        if divergences > 0 {
+            error!(target: LOG_TARGET, "🔁 Replay of finalised blocks #{} to #{} done, {} block(s) diverge from the audit log", range.from, range.to, divergences);
+        } else {
+            info!(target: LOG_TARGET, "🔁 Replay of finalised blocks #{} to #{} done, no divergences from the audit log", range.from, range.to);
+        }
+
+        Ok(divergences)
+    }
+}
@@ -0,0 +1,186 @@
+//! Read-through cache of the responses of the runtime API queries made on behalf of tasks.
+//!
+//! Tasks tend to query the runtime for the same information (i.e. the current tick, the capacity
+//! of a Provider or the MSP of a bucket) in bursts, when many events are handled at once. The
+//! [`BlockchainService`](crate::BlockchainService) serves repeated queries from this cache instead
+//! of calling into the runtime every time.
+//!
+//! Responses are keyed by the block hash they were queried at, the query and its encoded
+//! arguments, so a cached response is always the one the runtime would return at that block. The
+//! whole cache is dropped on every new best or finalised block, and each response is served for at
+//! most the TTL configured for its query, which can be set to zero to never cache it.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use codec::{Decode, Encode};
+use shc_telemetry::record_runtime_api_cache_lookup;
+use sp_core::H256;
+
+/// Default time a cached response is served for, if no TTL is configured for its query.
+pub const DEFAULT_RUNTIME_API_CACHE_TTL: Duration = Duration::from_secs(6);
+
+/// The runtime API queries whose responses can be cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachedRuntimeApiQuery {
+    CurrentTick,
+    StorageProviderId,
+    ProviderMultiaddresses,
+    StorageProviderCapacity,
+    AvailableStorageCapacity,
+    EarliestChangeCapacityBlock,
+    MspIdOfBucketId,
+    ChallengePeriod,
+    WorstCaseScenarioSlashableAmount,
+    SlashAmountPerMaxFileSize,
+}
+
+impl CachedRuntimeApiQuery {
+    pub const ALL: [Self; 10] = [
+        Self::CurrentTick,
+        Self::StorageProviderId,
+        Self::ProviderMultiaddresses,
+        Self::StorageProviderCapacity,
+        Self::AvailableStorageCapacity,
+        Self::EarliestChangeCapacityBlock,
+        Self::MspIdOfBucketId,
+        Self::ChallengePeriod,
+        Self::WorstCaseScenarioSlashableAmount,
+        Self::SlashAmountPerMaxFileSize,
+    ];
+
+    /// The name of the query, as used in the configuration and the metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CurrentTick => "current-tick",
+            Self::StorageProviderId => "storage-provider-id",
+            Self::ProviderMultiaddresses => "provider-multiaddresses",
+            Self::StorageProviderCapacity => "storage-provider-capacity",
+            Self::AvailableStorageCapacity => "available-storage-capacity",
+            Self::EarliestChangeCapacityBlock => "earliest-change-capacity-block",
+            Self::MspIdOfBucketId => "msp-id-of-bucket-id",
+            Self::ChallengePeriod => "challenge-period",
+            Self::WorstCaseScenarioSlashableAmount => "worst-case-scenario-slashable-amount",
+            Self::SlashAmountPerMaxFileSize => "slash-amount-per-max-file-size",
+        }
+    }
+}
+
+impl fmt::Display for CachedRuntimeApiQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CachedRuntimeApiQuery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|query| query.as_str() == s)
+            .ok_or_else(|| format!("unknown runtime API query `{}`", s))
+    }
+}
+
+/// Configuration of the runtime API cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeApiCacheConfig {
+    /// Time a cached response is served for, if no TTL is configured for its query.
+    pub default_ttl: Duration,
+    /// Time the responses of specific queries are served for, overriding [`Self::default_ttl`].
+    pub query_ttls: HashMap<CachedRuntimeApiQuery, Duration>,
+}
+
+impl Default for RuntimeApiCacheConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl: DEFAULT_RUNTIME_API_CACHE_TTL,
+            query_ttls: HashMap::new(),
+        }
+    }
+}
+
+impl RuntimeApiCacheConfig {
+    /// The time the responses of `query` are served for. Zero means they are not cached.
+    pub fn ttl(&self, query: CachedRuntimeApiQuery) -> Duration {
+        self.query_ttls
+            .get(&query)
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+}
+
+/// A cached response, encoded so that responses of any type can be kept in the same map.
+struct CachedResponse {
+    encoded: Vec<u8>,
+    expires_at: Instant,
+}
+
+/// The cached runtime API responses, keyed by block hash, query and encoded arguments.
+pub struct RuntimeApiCache {
+    config: RuntimeApiCacheConfig,
+    responses: HashMap<(H256, CachedRuntimeApiQuery, Vec<u8>), CachedResponse>,
+}
+
+impl RuntimeApiCache {
+    pub fn new(config: RuntimeApiCacheConfig) -> Self {
+        Self {
+            config,
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Returns the response of `query` with `args` at `block_hash`, calling `call` to get it from
+    /// the runtime if it is not cached (or has expired) and caching it if the call succeeded.
+    pub fn get_or_call<A, R, E>(
+        &mut self,
+        block_hash: H256,
+        query: CachedRuntimeApiQuery,
+        args: A,
+        call: impl FnOnce() -> Result<R, E>,
+    ) -> Result<R, E>
+    where
+        A: Encode,
+        R: Encode + Decode,
+    {
+        let ttl = self.config.ttl(query);
+        if ttl.is_zero() {
+            return call();
+        }
+
+        let key = (block_hash, query, args.encode());
+        let now = Instant::now();
+
+        if let Some(cached) = self.responses.get(&key) {
+            if cached.expires_at > now {
+                if let Ok(response) = R::decode(&mut cached.encoded.as_slice()) {
+                    record_runtime_api_cache_lookup(query.as_str(), true);
+                    return Ok(response);
+                }
+            }
+        }
+
+        record_runtime_api_cache_lookup(query.as_str(), false);
+
+        let response = call()?;
+        self.responses.insert(
+            key,
+            CachedResponse {
+                encoded: response.encode(),
+                expires_at: now + ttl,
+            },
+        );
+
+        Ok(response)
+    }
+
+    /// Drops every cached response. Called on every new best or finalised block.
+    pub fn invalidate(&mut self) {
+        self.responses.clear();
+    }
+}
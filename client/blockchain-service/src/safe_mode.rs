@@ -0,0 +1,158 @@
+//! Safe mode of the BlockchainService, for when the node seems partitioned from the network.
+//!
+//! A node that loses connectivity to the chain keeps building on the last blocks it knows of, so
+//! any change it makes to the Forest of its Provider in the meantime is likely to diverge from
+//! what ends up on-chain. If no block is finalised for [`SafeModeConfig::partition_timeout`], the
+//! BlockchainService assumes the node is partitioned and enters safe mode:
+//! - The Forest root write lock is not granted, so no task submits proofs, confirms storing files
+//!   or otherwise mutates the Forest of the Provider.
+//! - Requests for the lock (proofs to submit, files to confirm, storage requests to respond to)
+//!   keep being queued as usual, to be processed once the node is out of safe mode.
+//! - Everything else, like serving files to users or answering queries, is unaffected.
+//!
+//! Safe mode is exited as soon as a new block is finalised. The BlockchainService then catches up
+//! with the proofs the Provider missed while partitioned and resumes granting the lock.
+//!
+//! Every transition emits a [`SafeModeEntered`](crate::events::SafeModeEntered) or
+//! [`SafeModeExited`](crate::events::SafeModeExited) event and is recorded in the metrics.
+
+use std::time::{Duration, Instant};
+
+use sc_client_api::HeaderBackend;
+use sc_tracing::tracing::{info, warn};
+use shc_actors_framework::actor::Actor;
+use shc_common::types::{BlockNumber, StorageProviderId};
+use shc_forest_manager::traits::ForestStorageHandler;
+use shc_telemetry::{record_safe_mode_entered, record_safe_mode_exited};
+
+use crate::{
+    events::{SafeModeEntered, SafeModeExited},
+    handler::LOG_TARGET,
+    BlockchainService,
+};
+
+/// Default time without new finalised blocks after which the node is considered partitioned.
+pub const DEFAULT_PARTITION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Interval at which the BlockchainService checks whether the node is partitioned.
+pub const PARTITION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Configuration of the safe mode of the BlockchainService.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafeModeConfig {
+    /// Time without new finalised blocks after which the node enters safe mode.
+    ///
+    /// Set it to zero to never enter safe mode.
+    pub partition_timeout: Duration,
+}
+
+impl Default for SafeModeConfig {
+    fn default() -> Self {
+        Self {
+            partition_timeout: DEFAULT_PARTITION_TIMEOUT,
+        }
+    }
+}
+
+/// Tracks the finalised blocks seen by the BlockchainService, to detect partitions.
+#[derive(Debug)]
+pub struct SafeMode {
+    config: SafeModeConfig,
+    /// The last block finalised, if any since the node started.
+    last_finalised_block: Option<BlockNumber>,
+    /// When the last block was finalised, or the node started if none was yet.
+    last_finalised_at: Instant,
+    /// When safe mode was entered, if the node is in safe mode.
+    active_since: Option<Instant>,
+}
+
+impl SafeMode {
+    pub fn new(config: SafeModeConfig) -> Self {
+        Self {
+            config,
+            last_finalised_block: None,
+            last_finalised_at: Instant::now(),
+            active_since: None,
+        }
+    }
+
+    /// Whether the node is in safe mode.
+    pub fn is_active(&self) -> bool {
+        self.active_since.is_some()
+    }
+
+    /// Enters safe mode if no block was finalised for longer than the partition timeout.
+    ///
+    /// Returns for how long no block was finalised if safe mode was just entered.
+    fn check_partition(&mut self) -> Option<Duration> {
+        if self.is_active() || self.config.partition_timeout.is_zero() {
+            return None;
+        }
+
+        let since_last_finalised = self.last_finalised_at.elapsed();
+        if since_last_finalised < self.config.partition_timeout {
+            return None;
+        }
+
+        self.active_since = Some(Instant::now());
+        Some(since_last_finalised)
+    }
+
+    /// Registers a newly finalised block, exiting safe mode if the node was in it.
+    ///
+    /// Returns for how long the node was in safe mode if it was just exited.
+    fn register_finalised_block(&mut self, block_number: BlockNumber) -> Option<Duration> {
+        self.last_finalised_block = Some(block_number);
+        self.last_finalised_at = Instant::now();
+
+        self.active_since.take().map(|since| since.elapsed())
+    }
+}
+
+impl<FSH> BlockchainService<FSH>
+where
+    FSH: ForestStorageHandler + Clone + Send + Sync + 'static,
+{
+    /// Enters safe mode if the node has not seen a finalised block for longer than the partition
+    /// timeout. Called periodically from the event loop, since a partitioned node gets no
+    /// notifications to react to.
+    pub(crate) fn check_for_partition(&mut self) {
+        let Some(since_last_finalised) = self.safe_mode.check_partition() else {
+            return;
+        };
+
+        let last_finalised_block = self.safe_mode.last_finalised_block;
+        warn!(target: LOG_TARGET, "🚧 No block finalised for {}s (last seen: {:?}). Node seems partitioned from the network, entering safe mode: Forest root writes are paused", since_last_finalised.as_secs(), last_finalised_block);
+
+        record_safe_mode_entered();
+        self.emit(SafeModeEntered {
+            last_finalised_block,
+            since_last_finalised,
+        });
+    }
+
+    /// Registers a newly finalised block for partition detection.
+    ///
+    /// If the node was in safe mode, exits it, catches up with the proofs the Provider missed
+    /// while partitioned and hands the Forest root write lock to the requests queued meanwhile.
+    pub(crate) fn register_finalised_block_for_safe_mode(&mut self, block_number: BlockNumber) {
+        let Some(duration) = self.safe_mode.register_finalised_block(block_number) else {
+            return;
+        };
+
+        info!(target: LOG_TARGET, "🚦 Block #{} finalised, exiting safe mode after {}s", block_number, duration.as_secs());
+
+        record_safe_mode_exited(duration);
+        self.emit(SafeModeExited {
+            finalised_block: block_number,
+            duration,
+        });
+
+        // Reconcile with the chain before resuming.
+        if let Some(StorageProviderId::BackupStorageProvider(bsp_id)) = self.provider_id {
+            let best_block_hash = self.client.info().best_hash;
+            self.proof_submission_catch_up(&best_block_hash, &bsp_id);
+        }
+        self.check_pending_forest_root_writes();
+    }
+}
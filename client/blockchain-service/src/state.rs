@@ -1,16 +1,19 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use log::info;
-use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use codec::Decode;
+use log::{info, warn};
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
 use shc_common::types::BlockNumber;
 
 use crate::events::ProcessMspRespondStoringRequestData;
+use crate::replay::FinalisedBlockAuditRecord;
 use crate::{
     events::{ProcessConfirmStoringRequestData, ProcessStopStoringForInsolventUserRequestData},
+    outbound_queue::OutboundExtrinsic,
     typed_store::{
         BufferedWriteSupport, CFDequeAPI, ProvidesDbContext, ProvidesTypedDbAccess,
-        ProvidesTypedDbSingleAccess, ScaleEncodedCf, SingleScaleEncodedValueCf, TypedCf,
-        TypedDbContext, TypedRocksDB,
+        ProvidesTypedDbSingleAccess, ReadableRocks, ScaleEncodedCf, SingleScaleEncodedValueCf,
+        TypedCf, TypedDbContext, TypedRocksDB,
     },
     types::{
         StopStoringForInsolventUserRequest, {ConfirmStoringRequest, RespondStorageRequest},
@@ -140,7 +143,27 @@ impl SingleScaleEncodedValueCf for PendingStopStoringForInsolventUserRequestRigh
         "pending_stop_storing_for_insolvent_user_request_right_index";
 }
 
-const ALL_COLUMN_FAMILIES: [&str; 13] = [
+/// Events emitted for each finalised block, kept for [`crate::replay::FINALISED_BLOCK_AUDIT_LOG_RETENTION`] blocks.
+#[derive(Default)]
+pub struct FinalisedBlockAuditLogCf;
+impl ScaleEncodedCf for FinalisedBlockAuditLogCf {
+    type Key = BlockNumber;
+    type Value = FinalisedBlockAuditRecord;
+
+    const SCALE_ENCODED_NAME: &'static str = "finalised_block_audit_log";
+}
+
+/// Extrinsics sent by this node that are not finalised yet, by nonce. See [`crate::outbound_queue`].
+#[derive(Default)]
+pub struct OutboundExtrinsicCf;
+impl ScaleEncodedCf for OutboundExtrinsicCf {
+    type Key = u32;
+    type Value = OutboundExtrinsic;
+
+    const SCALE_ENCODED_NAME: &'static str = "outbound_extrinsic";
+}
+
+const ALL_COLUMN_FAMILIES: [&str; 15] = [
     LastProcessedBlockNumberCf::NAME,
     OngoingProcessConfirmStoringRequestCf::NAME,
     PendingConfirmStoringRequestLeftIndexCf::NAME,
@@ -154,6 +177,8 @@ const ALL_COLUMN_FAMILIES: [&str; 13] = [
     PendingStopStoringForInsolventUserRequestLeftIndexCf::NAME,
     PendingStopStoringForInsolventUserRequestRightIndexCf::NAME,
     PendingStopStoringForInsolventUserRequestCf::NAME,
+    FinalisedBlockAuditLogCf::NAME,
+    OutboundExtrinsicCf::NAME,
 ];
 
 /// A persistent blockchain service state store.
@@ -194,6 +219,29 @@ impl BlockchainServiceStateStore {
             BufferedWriteSupport::new(&self.rocks),
         ))
     }
+
+    /// Reads all the entries of the [`OutboundExtrinsicCf`], by nonce.
+    ///
+    /// Entries are decoded fallibly, since they may have been written by a version of the node
+    /// with a different layout for them. Those that can't be decoded are `None`.
+    pub fn outbound_extrinsics(&self) -> BTreeMap<u32, Option<OutboundExtrinsic>> {
+        self.rocks
+            .iterator_cf(
+                self.rocks.cf_handle(OutboundExtrinsicCf::NAME),
+                IteratorMode::Start,
+            )
+            .filter_map(|(key, value)| {
+                let Ok(nonce) = u32::decode(&mut &key[..]) else {
+                    warn!(
+                        "Skipping outbound extrinsic with undecodable nonce {:?}",
+                        key
+                    );
+                    return None;
+                };
+                Some((nonce, OutboundExtrinsic::decode(&mut &value[..]).ok()))
+            })
+            .collect()
+    }
 }
 
 pub struct BlockchainServiceStateStoreRwContext<'a> {
@@ -2,7 +2,7 @@ use std::{
     cmp::{min, Ordering},
     future::Future,
     pin::Pin,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use codec::{Decode, Encode};
@@ -275,6 +275,32 @@ impl Default for RetryStrategy {
     }
 }
 
+/// The task currently holding the Forest root write lock.
+#[derive(Debug, Clone)]
+pub struct ForestRootWriteLockHolder {
+    /// Label of the kind of task holding the lock (i.e. `submit_proof`).
+    pub label: &'static str,
+    /// When the lock was handed to the task.
+    pub acquired_at: Instant,
+    /// The best block when the lock was handed to the task.
+    pub acquired_at_block: BlockNumber,
+}
+
+impl ForestRootWriteLockHolder {
+    pub fn new(label: &'static str, acquired_at_block: BlockNumber) -> Self {
+        Self {
+            label,
+            acquired_at: Instant::now(),
+            acquired_at_block,
+        }
+    }
+
+    /// How long the task has been holding the lock.
+    pub fn held_for(&self) -> Duration {
+        self.acquired_at.elapsed()
+    }
+}
+
 /// Minimum block information needed to register what is the current best block
 /// and detect reorgs.
 #[derive(Debug, Clone, Encode, Decode, Default, Copy)]
@@ -317,10 +343,12 @@ pub enum NewBlockNotificationKind {
     /// This fork causes a reorg, i.e. it is the new best block, but the previous best block
     /// is not the parent of this one.
     ///
-    /// The old best block (from the now non-best fork) is provided, as well as the new best block.
+    /// The old best block (from the now non-best fork) is provided, as well as the new best block
+    /// and the blocks retracted from the best chain, from the old best block backwards.
     Reorg {
         old_best_block: BestBlockInfo,
         new_best_block: BestBlockInfo,
+        retracted: Vec<H256>,
     },
 }
 
@@ -4,7 +4,8 @@ use anyhow::Result;
 use codec::Encode;
 use cumulus_primitives_core::BlockT;
 use pallet_proofs_dealer_runtime_api::{
-    GetChallengePeriodError, GetChallengeSeedError, GetProofSubmissionRecordError, ProofsDealerApi,
+    GetChallengePeriodError, GetProofSubmissionRecordError, GetProviderChallengeScheduleError,
+    ProofsDealerApi,
 };
 use pallet_storage_providers_runtime_api::StorageProvidersApi;
 use polkadot_runtime_common::BlockHashCount;
@@ -16,16 +17,17 @@ use shc_common::{
     blockchain_utils::get_events_at_block,
     types::{
         BlockNumber, MaxBatchMspRespondStorageRequests, ParachainClient, ProofsDealerProviderId,
-        StorageProviderId, BCSV_KEY_TYPE,
+        ProviderId, StorageProviderId, BCSV_KEY_TYPE,
     },
 };
 use shc_forest_manager::traits::ForestStorageHandler;
+use shc_telemetry::{record_forest_root_write_lock_held, record_forest_root_write_lock_released};
 use sp_api::ProvideRuntimeApi;
 use sp_core::{Blake2Hasher, Get, Hasher, H256};
 use sp_keystore::KeystorePtr;
 use sp_runtime::{
     generic::{self, SignedPayload},
-    SaturatedConversion,
+    AccountId32, SaturatedConversion,
 };
 use storage_hub_runtime::{Runtime, SignedExtra, UncheckedExtrinsic};
 use substrate_frame_rpc_system::AccountNonceApi;
@@ -40,12 +42,15 @@ use crate::{
         ProcessSubmitProofRequest, ProcessSubmitProofRequestData,
     },
     handler::LOG_TARGET,
+    outbound_queue::{tip_amount, OutboundExtrinsic, OutboundExtrinsicStatus},
+    pending_operations::{operation_keys, pending_operation},
+    runtime_api_cache::CachedRuntimeApiQuery,
     state::{
         OngoingProcessConfirmStoringRequestCf, OngoingProcessMspRespondStorageRequestCf,
         OngoingProcessStopStoringForInsolventUserRequestCf,
     },
     typed_store::{CFDequeAPI, ProvidesTypedDbSingleAccess},
-    types::{BestBlockInfo, Extrinsic, NewBlockNotificationKind, Tip},
+    types::{BestBlockInfo, Extrinsic, ForestRootWriteLockHolder, NewBlockNotificationKind, Tip},
     BlockchainService,
 };
 
@@ -80,7 +85,12 @@ where
     /// Notify tasks waiting for a tick number.
     pub(crate) fn notify_tick_number(&mut self, block_hash: &H256) {
         // Get the current tick number.
-        let tick_number = match self.client.runtime_api().get_current_tick(*block_hash) {
+        let tick_number = match self.runtime_api_cache.get_or_call(
+            *block_hash,
+            CachedRuntimeApiQuery::CurrentTick,
+            (),
+            || self.client.runtime_api().get_current_tick(*block_hash),
+        ) {
             Ok(current_tick) => current_tick,
             Err(_) => {
                 error!(target: LOG_TARGET, "CRITICAL❗️❗️ Failed to query current tick from runtime in block hash {:?} and block number {:?}. This should not happen.", block_hash, self.client.info().best_number);
@@ -153,6 +163,11 @@ where
         NewBlockNotificationKind::Reorg {
             old_best_block: last_best_block,
             new_best_block: new_block_info,
+            retracted: tree_route
+                .retracted()
+                .iter()
+                .map(|block| block.hash)
+                .collect(),
         }
     }
 
@@ -169,6 +184,15 @@ where
         if latest_nonce > self.nonce_counter {
             self.nonce_counter = latest_nonce
         }
+        self.pending_operations.prune_included(latest_nonce);
+
+        // The nonces of the extrinsics sent before the node restarted are still taken.
+        let block_number = self.client.info().best_number.saturated_into();
+        self.outbound_queue
+            .update_status(latest_nonce, block_number);
+        if let Some(next_nonce) = self.outbound_queue.next_nonce() {
+            self.nonce_counter = self.nonce_counter.max(next_nonce);
+        }
     }
 
     /// Get the Provider ID linked to the [`BCSV_KEY_TYPE`] key in this node's keystore.
@@ -220,6 +244,8 @@ where
     }
 
     /// Send an extrinsic to this node using an RPC call.
+    ///
+    /// The extrinsic is added to the outbound queue, to be resubmitted if it is lost.
     pub(crate) async fn send_extrinsic(
         &mut self,
         call: impl Into<storage_hub_runtime::RuntimeCall>,
@@ -231,6 +257,48 @@ where
         // TODO: Handle nonce overflow.
         let nonce = self.nonce_counter;
 
+        let call = call.into();
+        let operation_keys = operation_keys(&call);
+        let tip_amount = tip_amount(&tip);
+        let output = self.submit_extrinsic(call.clone(), nonce, tip).await?;
+
+        // Only update nonce after we are sure no errors
+        // occurred submitting the extrinsic.
+        self.nonce_counter += 1;
+
+        let sent_at: BlockNumber = self.client.info().best_number.saturated_into();
+        let mortality_period: BlockNumber = Self::extrinsic_mortality_period().saturated_into();
+
+        // Keep track of the file keys and buckets this extrinsic operates on until it is included.
+        if !operation_keys.is_empty() {
+            let operation = pending_operation(&call, output.hash, nonce, sent_at, mortality_period);
+            self.pending_operations.insert(operation_keys, operation);
+        }
+
+        // Keep the extrinsic until it is finalised, to resubmit it if it is lost.
+        self.enqueue_outbound_extrinsic(OutboundExtrinsic {
+            nonce,
+            call: call.encode(),
+            spec_version: storage_hub_runtime::VERSION.spec_version,
+            tip: tip_amount,
+            extrinsic_hash: output.hash,
+            sent_at,
+            valid_until: sent_at.saturating_add(mortality_period),
+            resubmissions: 0,
+            nonce_filler: false,
+            status: OutboundExtrinsicStatus::Pending,
+        });
+
+        Ok(output)
+    }
+
+    /// Submits an extrinsic with the given `nonce` to this node using an RPC call.
+    pub(crate) async fn submit_extrinsic(
+        &self,
+        call: storage_hub_runtime::RuntimeCall,
+        nonce: u32,
+        tip: Tip,
+    ) -> Result<RpcExtrinsicOutput> {
         // Construct the extrinsic.
         let extrinsic = self.construct_extrinsic(self.client.clone(), call, nonce, tip);
 
@@ -265,10 +333,6 @@ where
             return Err(anyhow::anyhow!("Error in RPC call: {}", error.to_string()));
         }
 
-        // Only update nonce after we are sure no errors
-        // occurred submitting the extrinsic.
-        self.nonce_counter += 1;
-
         Ok(RpcExtrinsicOutput {
             hash: id_hash,
             result,
@@ -276,6 +340,14 @@ where
         })
     }
 
+    /// The number of blocks during which the extrinsics sent by this node can be included.
+    pub(crate) fn extrinsic_mortality_period() -> u64 {
+        BlockHashCount::get()
+            .checked_next_power_of_two()
+            .map(|c| c / 2)
+            .unwrap_or(2) as u64
+    }
+
     /// Construct an extrinsic that can be applied to the runtime.
     pub fn construct_extrinsic(
         &self,
@@ -291,10 +363,7 @@ where
             .hash(0)
             .expect("Failed to get genesis block hash, always present; qed")
             .expect("Genesis block hash should never not be on-chain; qed");
-        let period = BlockHashCount::get()
-            .checked_next_power_of_two()
-            .map(|c| c / 2)
-            .unwrap_or(2) as u64;
+        let period = Self::extrinsic_mortality_period();
         let extra: SignedExtra = (
             frame_system::CheckNonZeroSender::<storage_hub_runtime::Runtime>::new(),
             frame_system::CheckSpecVersion::<storage_hub_runtime::Runtime>::new(),
@@ -542,10 +611,12 @@ where
                     // If we have a task writing to the runtime, we don't want to start another one.
                     self.forest_root_write_lock = Some(rx);
                     trace!(target: LOG_TARGET, "Waiting for current Forest root write task to finish");
+                    self.check_forest_root_write_lock_holder();
                     return;
                 }
                 Ok(_) => {
                     trace!(target: LOG_TARGET, "Forest root write task finished, lock is released!");
+                    self.clear_forest_root_write_lock_holder();
                     let state_store_context = self.persistent_state.open_rw_context_with_overlay();
                     state_store_context
                         .access_value(&OngoingProcessConfirmStoringRequestCf)
@@ -560,6 +631,7 @@ where
                 }
                 Err(TryRecvError::Closed) => {
                     error!(target: LOG_TARGET, "Forest root write task channel closed unexpectedly. Lock is released anyway!");
+                    self.clear_forest_root_write_lock_holder();
                     let state_store_context = self.persistent_state.open_rw_context_with_overlay();
                     state_store_context
                         .access_value(&OngoingProcessConfirmStoringRequestCf)
@@ -575,6 +647,24 @@ where
             }
         }
 
+        // While the node seems partitioned from the network, pending requests stay queued until
+        // it exits safe mode, to avoid changing the Forest root based on an outdated view of the chain.
+        if self.safe_mode.is_active() {
+            trace!(target: LOG_TARGET, "Node in safe mode, not granting the Forest root write lock");
+            return;
+        }
+
+        // Pending requests stay queued, so they are picked up in the next check.
+        #[cfg(feature = "chaos")]
+        if self
+            .forest_root_write_lock_fault
+            .hold_lock(std::time::Instant::now(), || {
+                shc_actors_framework::chaos::inject(crate::chaos::FOREST_ROOT_WRITE_LOCK_POINT)
+            })
+        {
+            return;
+        }
+
         // At this point we know that the lock is released and we can start processing new requests.
         let state_store_context = self.persistent_state.open_rw_context_with_overlay();
         let mut next_event_data = None;
@@ -724,6 +814,14 @@ where
 
         let data = data.into();
 
+        // Keep track of which task holds the lock and since when, to diagnose it being held for too long.
+        debug!(target: LOG_TARGET, "Forest root write lock acquired by {} task", data.label());
+        self.forest_root_write_lock_holder = Some(ForestRootWriteLockHolder::new(
+            data.label(),
+            self.best_block.number,
+        ));
+        self.forest_root_write_lock_spans = data.forest_root_write_lock_spans();
+
         // If this is a confirm storing request, respond storage request, or a stop storing for insolvent user request, we need to store it in the state store.
         match &data {
             ForestWriteLockTaskData::ConfirmStoringRequest(data) => {
@@ -783,6 +881,41 @@ where
         }
     }
 
+    /// Records how long the current holder of the Forest root write lock has been holding it, and
+    /// warns if it is over the threshold, as the task might be stuck and stalling every other
+    /// Forest-root-writing task.
+    fn check_forest_root_write_lock_holder(&self) {
+        let Some(holder) = &self.forest_root_write_lock_holder else {
+            return;
+        };
+
+        let held_for = holder.held_for();
+        record_forest_root_write_lock_held(holder.label, held_for);
+
+        if held_for > self.forest_root_write_lock_warn_threshold {
+            warn!(
+                target: LOG_TARGET,
+                "⏳ Forest root write lock held by {} task for {}s (since block #{}), exceeding the threshold of {}s. The task might be stuck, and no other Forest root write can proceed until it releases the lock.",
+                holder.label,
+                held_for.as_secs(),
+                holder.acquired_at_block,
+                self.forest_root_write_lock_warn_threshold.as_secs()
+            );
+        }
+    }
+
+    /// Records how long the lock was held by its last holder and forgets it.
+    fn clear_forest_root_write_lock_holder(&mut self) {
+        if let Some(holder) = self.forest_root_write_lock_holder.take() {
+            let held_for = holder.held_for();
+            debug!(target: LOG_TARGET, "Forest root write lock released by {} task after {}ms", holder.label, held_for.as_millis());
+            record_forest_root_write_lock_released(holder.label, held_for);
+        }
+
+        // Closes the spans of the flows the holder belonged to.
+        self.forest_root_write_lock_spans.clear();
+    }
+
     /// Emits a `MultipleNewChallengeSeeds` event with all the pending proof submissions for this provider.
     /// This is used to catch up to the latest proof submissions that were missed due to a node restart.
     /// Also, it can help to catch up to proofs in case there is a change in the BSP's stake (therefore
@@ -794,88 +927,50 @@ where
         current_block_hash: &H256,
         provider_id: &ProofsDealerProviderId,
     ) {
-        // Get the current challenge period for this provider.
-        let challenge_period = match self
+        // Get the challenge schedule of this provider, with the seeds of the ticks it has to submit
+        // proofs for up to the current tick.
+        let schedule = match self
             .client
             .runtime_api()
-            .get_challenge_period(*current_block_hash, provider_id)
+            .provider_challenge_schedule(*current_block_hash, provider_id)
         {
-            Ok(challenge_period_result) => match challenge_period_result {
-                Ok(challenge_period) => challenge_period,
+            Ok(schedule_result) => match schedule_result {
+                Ok(schedule) => schedule,
                 Err(e) => match e {
-                    GetChallengePeriodError::ProviderNotRegistered => {
+                    GetProviderChallengeScheduleError::ProviderNotRegistered => {
                         debug!(target: LOG_TARGET, "Provider [{:?}] is not registered", provider_id);
                         return;
                     }
-                    GetChallengePeriodError::InternalApiError => {
-                        error!(target: LOG_TARGET, "This should be impossible, we just checked the API error. \nInternal API error while getting challenge period for Provider [{:?}]", provider_id);
+                    GetProviderChallengeScheduleError::ProviderNotInitialised => {
+                        debug!(target: LOG_TARGET, "Provider [{:?}] has not been initialised in the challenge cycle", provider_id);
+                        return;
+                    }
+                    GetProviderChallengeScheduleError::ArithmeticOverflow => {
+                        error!(target: LOG_TARGET, "Arithmetic overflow while getting challenge schedule for Provider [{:?}]", provider_id);
+                        return;
+                    }
+                    GetProviderChallengeScheduleError::InternalApiError => {
+                        error!(target: LOG_TARGET, "This should be impossible, we just checked the API error. \nInternal API error while getting challenge schedule for Provider [{:?}]", provider_id);
                         return;
                     }
                 },
             },
             Err(e) => {
-                error!(target: LOG_TARGET, "Runtime API error while getting challenge period for Provider [{:?}]: {:?}", provider_id, e);
+                error!(target: LOG_TARGET, "Runtime API error while getting challenge schedule for Provider [{:?}]: {:?}", provider_id, e);
                 return;
             }
         };
 
-        // Get the current tick.
-        let current_tick = match self
-            .client
-            .runtime_api()
-            .get_current_tick(*current_block_hash)
+        // Seeds that are no longer stored are left out of the schedule, but this provider still
+        // has to submit a proof for the first tick in it.
+        let first_seed_tick = schedule.seeds.first().map(|(tick, _)| *tick);
+        if schedule.next_tick_to_submit_proof_for <= schedule.current_tick
+            && first_seed_tick != Some(schedule.next_tick_to_submit_proof_for)
         {
-            Ok(current_tick) => current_tick,
-            Err(e) => {
-                error!(target: LOG_TARGET, "Runtime API error while getting current tick for Provider [{:?}]: {:?}", provider_id, e);
-                return;
-            }
-        };
-
-        // Advance by `challenge_period` ticks and add the seed to the list of challenge seeds.
-        let mut challenge_seeds = Vec::new();
-        let mut next_challenge_tick = match Self::get_next_challenge_tick_for_provider(
-            &self,
-            provider_id,
-        ) {
-            Ok(next_challenge_tick) => next_challenge_tick,
-            Err(e) => {
-                error!(target: LOG_TARGET, "Failed to get next challenge tick for provider [{:?}]: {:?}", provider_id, e);
-                return;
-            }
-        };
-        while next_challenge_tick <= current_tick {
-            // Get the seed for the challenge tick.
-            let seed = match self
-                .client
-                .runtime_api()
-                .get_challenge_seed(*current_block_hash, next_challenge_tick)
-            {
-                Ok(seed_result) => match seed_result {
-                    Ok(seed) => seed,
-                    Err(e) => match e {
-                        GetChallengeSeedError::TickBeyondLastSeedStored => {
-                            error!(target: LOG_TARGET, "CRITICAL❗️❗️ Tick [{:?}] is beyond last seed stored and this provider needs to submit a proof for it.", next_challenge_tick);
-                            return;
-                        }
-                        GetChallengeSeedError::TickIsInTheFuture => {
-                            error!(target: LOG_TARGET, "CRITICAL❗️❗️ Tick [{:?}] is in the future. This should never happen. \nThis is a bug. Please report it to the StorageHub team.", next_challenge_tick);
-                            return;
-                        }
-                        GetChallengeSeedError::InternalApiError => {
-                            error!(target: LOG_TARGET, "This should be impossible, we just checked the API error. \nInternal API error while getting challenge seed for challenge tick [{:?}]: {:?}", next_challenge_tick, e);
-                            return;
-                        }
-                    },
-                },
-                Err(e) => {
-                    error!(target: LOG_TARGET, "Runtime API error while getting challenges from seed for challenge tick [{:?}]: {:?}", next_challenge_tick, e);
-                    return;
-                }
-            };
-            challenge_seeds.push((next_challenge_tick, seed));
-            next_challenge_tick += challenge_period;
+            error!(target: LOG_TARGET, "CRITICAL❗️❗️ Tick [{:?}] is beyond last seed stored and this provider needs to submit a proof for it.", schedule.next_tick_to_submit_proof_for);
+            return;
         }
+        let challenge_seeds = schedule.seeds;
 
         // Emit the `MultipleNewChallengeSeeds` event.
         if challenge_seeds.len() > 0 {
@@ -915,6 +1010,22 @@ where
             }
         }
     }
+
+    /// Whether a bucket concerns this node: either the MSP it manages stores it, or it is run by
+    /// the user owning it.
+    pub(crate) fn is_bucket_relevant(
+        &self,
+        owner: &AccountId32,
+        msp_id: Option<&ProviderId>,
+    ) -> bool {
+        match &self.provider_id {
+            Some(StorageProviderId::MainStorageProvider(managed_msp_id)) => {
+                msp_id == Some(managed_msp_id)
+            }
+            Some(StorageProviderId::BackupStorageProvider(_)) => false,
+            None => owner == &AccountId32::from(Self::caller_pub_key(self.keystore.clone())),
+        }
+    }
 }
 
 /// The output of an RPC transaction.
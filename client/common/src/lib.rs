@@ -1,3 +1,4 @@
 pub mod blockchain_utils;
 pub mod consts;
 pub mod types;
+pub mod upload_eta;
@@ -44,6 +44,8 @@ pub type StorageRequestMspAcceptedFileKeys =
     pallet_file_system::types::StorageRequestMspAcceptedFileKeys<Runtime>;
 pub type FileKeyWithProof = pallet_file_system::types::FileKeyWithProof<Runtime>;
 pub type PeerIds = pallet_file_system::types::PeerIds<Runtime>;
+pub type StorageRequestMetadata = pallet_file_system::types::StorageRequestMetadata<Runtime>;
+pub type ReplicationTarget = pallet_file_system::types::ReplicationTargetType<Runtime>;
 pub type BucketId = pallet_storage_providers::types::MerklePatriciaRoot<Runtime>;
 pub type StorageProviderId = pallet_storage_providers::types::StorageProviderId<Runtime>;
 pub type BackupStorageProviderId =
@@ -0,0 +1,247 @@
+//! Estimation of how long until a storage request reaches its replication target.
+//!
+//! BSPs become eligible to volunteer for a storage request progressively: some of them are eligible
+//! as soon as it is issued, and the threshold they have to meet grows linearly until every BSP is
+//! eligible, `TickRangeToMaximumThreshold` ticks later. The estimate combines that progression with
+//! the pace at which the storage request is being confirmed and, if available, with how long recent
+//! storage requests took to be fulfilled according to the indexer.
+//!
+//! The estimate comes with a confidence band, which narrows as confirmations arrive.
+
+use std::time::Duration;
+
+/// Ticks it takes a BSP to confirm storing a file once it is eligible to volunteer for it, used until
+/// the storage request is confirmed by some BSP (volunteering, receiving the file and confirming).
+pub const DEFAULT_CONFIRMATION_LATENCY_TICKS: u64 = 5;
+
+/// Relative width of each side of the confidence band when there is no history to derive it from.
+pub const DEFAULT_CONFIDENCE_SPREAD: f64 = 0.5;
+
+/// Minimum amount of fulfilled storage requests in the history for it to be taken into account.
+pub const MIN_HISTORY_SAMPLES: usize = 5;
+
+/// The replication progress of a storage request, as given by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicationProgress {
+    pub bsps_required: u32,
+    pub bsps_confirmed: u32,
+    /// Ticks elapsed since the storage request was issued.
+    pub ticks_since_requested: u64,
+    /// The number of BSPs currently registered.
+    pub number_of_bsps: u32,
+    pub max_replication_target: u32,
+    pub tick_range_to_maximum_threshold: u64,
+}
+
+/// How long recent storage requests took to be fulfilled.
+#[derive(Debug, Clone, Default)]
+pub struct FulfilmentHistory {
+    /// Sorted in ascending order.
+    durations: Vec<Duration>,
+}
+
+impl FulfilmentHistory {
+    pub fn new(mut durations: Vec<Duration>) -> Self {
+        durations.sort();
+        Self { durations }
+    }
+
+    pub fn len(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.durations.is_empty()
+    }
+
+    /// The duration below which `percentile` percent of the storage requests were fulfilled, if there
+    /// are enough samples.
+    fn percentile(&self, percentile: usize) -> Option<Duration> {
+        if self.durations.len() < MIN_HISTORY_SAMPLES {
+            return None;
+        }
+
+        let index = (self.durations.len() - 1) * percentile.min(100) / 100;
+        self.durations.get(index).copied()
+    }
+}
+
+/// Estimated time left until a storage request reaches its replication target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadEta {
+    pub expected: Duration,
+    /// Lower bound of the confidence band.
+    pub earliest: Duration,
+    /// Upper bound of the confidence band.
+    pub latest: Duration,
+}
+
+impl UploadEta {
+    /// The estimate for a storage request that already reached its replication target.
+    pub fn reached() -> Self {
+        Self {
+            expected: Duration::ZERO,
+            earliest: Duration::ZERO,
+            latest: Duration::ZERO,
+        }
+    }
+}
+
+/// Estimates the time left until a storage request reaches its replication target, with ticks
+/// lasting `tick_duration`.
+///
+/// Returns `None` if there are not enough BSPs registered to ever reach the replication target.
+pub fn estimate_upload_eta(
+    progress: &ReplicationProgress,
+    history: &FulfilmentHistory,
+    tick_duration: Duration,
+) -> Option<UploadEta> {
+    let remaining = progress
+        .bsps_required
+        .saturating_sub(progress.bsps_confirmed);
+    if remaining == 0 {
+        return Some(UploadEta::reached());
+    }
+    if progress.number_of_bsps < progress.bsps_required {
+        return None;
+    }
+
+    let elapsed_ticks = progress.ticks_since_requested;
+
+    // Ticks until enough BSPs are eligible to volunteer, plus the time it takes them to confirm.
+    let threshold_ticks = ticks_until_enough_eligible_bsps(progress).saturating_sub(elapsed_ticks)
+        as f64
+        + DEFAULT_CONFIRMATION_LATENCY_TICKS as f64;
+
+    // Once some BSPs confirmed, the pace at which they did it is a better hint of what is left.
+    let expected_ticks = if progress.bsps_confirmed > 0 && elapsed_ticks > 0 {
+        let ticks_per_confirmation = elapsed_ticks as f64 / progress.bsps_confirmed as f64;
+        (threshold_ticks + remaining as f64 * ticks_per_confirmation) / 2.0
+    } else {
+        threshold_ticks
+    };
+    let mut expected = tick_duration.mul_f64(expected_ticks);
+
+    // Without confirmations to go by, also take into account how long recent storage requests took.
+    let elapsed = tick_duration.saturating_mul(elapsed_ticks.try_into().unwrap_or(u32::MAX));
+    let median = history.percentile(50);
+    if let (0, Some(median)) = (progress.bsps_confirmed, median) {
+        expected = (expected + median.saturating_sub(elapsed)) / 2;
+    }
+
+    // The band is as wide as the spread of the history, if any, and narrows with each confirmation.
+    let (earliest_spread, latest_spread) =
+        match (median, history.percentile(10), history.percentile(90)) {
+            (Some(median), Some(low), Some(high)) if !median.is_zero() => (
+                1.0 - low.as_secs_f64() / median.as_secs_f64(),
+                high.as_secs_f64() / median.as_secs_f64() - 1.0,
+            ),
+            _ => (DEFAULT_CONFIDENCE_SPREAD, DEFAULT_CONFIDENCE_SPREAD),
+        };
+    let narrowing = ((progress.bsps_confirmed + 1) as f64).sqrt();
+
+    Some(UploadEta {
+        expected,
+        earliest: expected.mul_f64((1.0 - earliest_spread / narrowing).max(0.0)),
+        latest: expected.mul_f64(1.0 + latest_spread / narrowing),
+    })
+}
+
+/// Ticks since the storage request was issued until enough BSPs are eligible to volunteer for it to
+/// reach its replication target, assuming every BSP has the starting reputation weight.
+///
+/// A fraction `max_replication_target / (2 * number_of_bsps)` of the BSPs is eligible from the start,
+/// growing linearly until all of them are, after `tick_range_to_maximum_threshold` ticks.
+fn ticks_until_enough_eligible_bsps(progress: &ReplicationProgress) -> u64 {
+    let number_of_bsps = progress.number_of_bsps.max(1) as f64;
+    let starting_fraction =
+        (progress.max_replication_target as f64 / (2.0 * number_of_bsps)).min(1.0);
+    let required_fraction = (progress.bsps_required as f64 / number_of_bsps).min(1.0);
+
+    if required_fraction <= starting_fraction {
+        return 0;
+    }
+
+    let progression = (required_fraction - starting_fraction) / (1.0 - starting_fraction);
+    (progress.tick_range_to_maximum_threshold as f64 * progression).ceil() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_secs(6);
+
+    fn progress(bsps_confirmed: u32, ticks_since_requested: u64) -> ReplicationProgress {
+        ReplicationProgress {
+            bsps_required: 3,
+            bsps_confirmed,
+            ticks_since_requested,
+            number_of_bsps: 100,
+            max_replication_target: 10,
+            tick_range_to_maximum_threshold: 100,
+        }
+    }
+
+    #[test]
+    fn estimate_follows_the_threshold_progression() {
+        let history = FulfilmentHistory::default();
+
+        // 5% of the BSPs are eligible from the start, which is already enough.
+        let eta = estimate_upload_eta(&progress(0, 0), &history, TICK).unwrap();
+        assert_eq!(
+            eta.expected,
+            TICK * DEFAULT_CONFIRMATION_LATENCY_TICKS as u32
+        );
+        assert!(eta.earliest < eta.expected && eta.expected < eta.latest);
+
+        // With a bigger network, it takes some ticks for enough BSPs to be eligible.
+        let big_network = ReplicationProgress {
+            bsps_required: 50,
+            number_of_bsps: 1000,
+            ..progress(0, 0)
+        };
+        let eta = estimate_upload_eta(&big_network, &history, TICK).unwrap();
+        assert!(eta.expected > TICK * DEFAULT_CONFIRMATION_LATENCY_TICKS as u32);
+
+        // Not enough BSPs to ever reach the replication target.
+        let small_network = ReplicationProgress {
+            number_of_bsps: 2,
+            ..progress(0, 0)
+        };
+        assert_eq!(estimate_upload_eta(&small_network, &history, TICK), None);
+    }
+
+    #[test]
+    fn estimate_narrows_as_confirmations_arrive() {
+        let history = FulfilmentHistory::default();
+
+        let before = estimate_upload_eta(&progress(0, 10), &history, TICK).unwrap();
+        let after = estimate_upload_eta(&progress(2, 10), &history, TICK).unwrap();
+        assert!(after.latest - after.earliest < before.latest - before.earliest);
+
+        assert_eq!(
+            estimate_upload_eta(&progress(3, 10), &history, TICK),
+            Some(UploadEta::reached())
+        );
+    }
+
+    #[test]
+    fn estimate_takes_history_into_account() {
+        let history =
+            FulfilmentHistory::new((1..=10).map(|i| TICK * (100 + i)).collect::<Vec<_>>());
+
+        let without_history =
+            estimate_upload_eta(&progress(0, 0), &FulfilmentHistory::default(), TICK).unwrap();
+        let with_history = estimate_upload_eta(&progress(0, 0), &history, TICK).unwrap();
+        assert!(with_history.expected > without_history.expected);
+
+        // The history is tightly grouped, so the band is narrower than the default one.
+        assert!(
+            with_history.latest - with_history.earliest
+                < with_history
+                    .expected
+                    .mul_f64(2.0 * DEFAULT_CONFIDENCE_SPREAD)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use shc_actors_framework::actor::ActorHandle;
+
+use crate::handler::{ConfigWatcherService, ReloadableConfig};
+
+/// Commands that can be sent to the ConfigWatcherService actor.
+#[derive(Debug)]
+pub enum ConfigWatcherServiceCommand {
+    ReloadConfig {
+        callback: tokio::sync::oneshot::Sender<anyhow::Result<bool>>,
+    },
+    QueryConfig {
+        callback: tokio::sync::oneshot::Sender<ReloadableConfig>,
+    },
+}
+
+/// Interface for interacting with the ConfigWatcherService actor.
+#[async_trait]
+pub trait ConfigWatcherServiceInterface {
+    /// Reload the configuration file, returning whether any of the reloadable settings changed.
+    ///
+    /// Fails if the file cannot be loaded or is invalid, in which case the current settings are
+    /// kept.
+    async fn reload_config(&self) -> anyhow::Result<bool>;
+
+    /// Query the reloadable settings as of the last successful load of the configuration file.
+    async fn query_config(&self) -> ReloadableConfig;
+}
+
+#[async_trait]
+impl ConfigWatcherServiceInterface for ActorHandle<ConfigWatcherService> {
+    async fn reload_config(&self) -> anyhow::Result<bool> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = ConfigWatcherServiceCommand::ReloadConfig { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from ConfigWatcherService. Probably means ConfigWatcherService has crashed.")
+    }
+
+    async fn query_config(&self) -> ReloadableConfig {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = ConfigWatcherServiceCommand::QueryConfig { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from ConfigWatcherService. Probably means ConfigWatcherService has crashed.")
+    }
+}
@@ -0,0 +1,36 @@
+use shc_actors_framework::event_bus::{EventBus, EventBusMessage, ProvidesEventBus};
+
+use crate::handler::ReloadableConfig;
+
+/// Config updated event.
+///
+/// This event is emitted when the configuration file is reloaded and any of the reloadable
+/// settings changed. Only the settings that differ from `previous` have to be applied.
+#[derive(Debug, Clone)]
+pub struct ConfigUpdated {
+    /// The settings before the reload.
+    pub previous: ReloadableConfig,
+    /// The settings loaded from the configuration file.
+    pub config: ReloadableConfig,
+}
+
+impl EventBusMessage for ConfigUpdated {}
+
+#[derive(Clone, Default)]
+pub struct ConfigWatcherServiceEventBusProvider {
+    config_updated_event_bus: EventBus<ConfigUpdated>,
+}
+
+impl ConfigWatcherServiceEventBusProvider {
+    pub fn new() -> Self {
+        Self {
+            config_updated_event_bus: EventBus::new(),
+        }
+    }
+}
+
+impl ProvidesEventBus<ConfigUpdated> for ConfigWatcherServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<ConfigUpdated> {
+        &self.config_updated_event_bus
+    }
+}
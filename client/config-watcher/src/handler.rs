@@ -0,0 +1,245 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use futures::stream::{self, StreamExt};
+use log::{debug, error, info, warn};
+use shc_actors_framework::actor::{Actor, ActorEventLoop};
+use shc_volunteer_policy::VolunteerPolicy;
+use tokio::time::{interval, Duration};
+
+use crate::{
+    commands::ConfigWatcherServiceCommand,
+    events::{ConfigUpdated, ConfigWatcherServiceEventBusProvider},
+};
+
+const LOG_TARGET: &str = "config-watcher-service";
+
+/// Loads and validates the configuration file at a path, returning its reloadable settings.
+pub type ConfigLoader = Arc<dyn Fn(&Path) -> anyhow::Result<ReloadableConfig> + Send + Sync>;
+
+/// The settings of a provider node that can be changed while it runs.
+///
+/// The other settings of the configuration file only take effect when the node restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadableConfig {
+    /// Maximum bandwidth (bytes per second) used to serve files to all peers together.
+    pub max_upload_bandwidth: Option<u64>,
+    /// Maximum bandwidth (bytes per second) used to serve files to each peer.
+    pub max_peer_upload_bandwidth: Option<u64>,
+    /// Policy deciding which storage requests a BSP volunteers for. Only set for BSPs.
+    pub volunteer_policy: Option<VolunteerPolicy>,
+    /// Log filter directives set on top of the ones the node was started with.
+    pub log_filter: Option<String>,
+}
+
+/// Configuration of the [`ConfigWatcherService`].
+#[derive(Clone)]
+pub struct ConfigWatcherConfig {
+    /// Path of the configuration file.
+    pub path: PathBuf,
+    /// Period between the checks of the configuration file for changes. The file is only
+    /// reloaded on request if not set.
+    pub check_period: Option<Duration>,
+    /// The settings the node was started with.
+    pub initial_config: ReloadableConfig,
+    /// Loads the reloadable settings from the configuration file.
+    pub loader: ConfigLoader,
+}
+
+pub struct ConfigWatcherService {
+    config: ConfigWatcherConfig,
+    /// The event bus provider for the config watcher service.
+    /// Part of the actor framework, allows for emitting events.
+    event_bus_provider: ConfigWatcherServiceEventBusProvider,
+    /// The settings as of the last successful load of the configuration file.
+    current_config: ReloadableConfig,
+    /// The modification time of the configuration file as of the last check.
+    last_modified: Option<SystemTime>,
+}
+
+impl Actor for ConfigWatcherService {
+    type Message = ConfigWatcherServiceCommand;
+    type EventLoop = ConfigWatcherServiceEventLoop;
+    type EventBusProvider = ConfigWatcherServiceEventBusProvider;
+
+    fn handle_message(
+        &mut self,
+        message: Self::Message,
+    ) -> impl std::future::Future<Output = ()> + Send {
+        async {
+            match message {
+                ConfigWatcherServiceCommand::ReloadConfig { callback } => {
+                    let result = self.reload();
+                    match callback.send(result) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the result of the reload back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                ConfigWatcherServiceCommand::QueryConfig { callback } => {
+                    match callback.send(self.current_config.clone()) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the current config back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_event_bus_provider(&self) -> &Self::EventBusProvider {
+        &self.event_bus_provider
+    }
+}
+
+impl ConfigWatcherService {
+    /// Create a new [`ConfigWatcherService`].
+    pub fn new(config: ConfigWatcherConfig) -> Self {
+        let last_modified = modified_time(&config.path).ok();
+        let current_config = config.initial_config.clone();
+
+        Self {
+            config,
+            event_bus_provider: ConfigWatcherServiceEventBusProvider::new(),
+            current_config,
+            last_modified,
+        }
+    }
+
+    /// Reloads the configuration file, emitting a [`ConfigUpdated`] event if any of the reloadable
+    /// settings changed. Returns whether they changed.
+    fn reload(&mut self) -> anyhow::Result<bool> {
+        let config = (self.config.loader)(&self.config.path)?;
+
+        if config == self.current_config {
+            debug!(
+                target: LOG_TARGET,
+                "Configuration file {:?} reloaded, no reloadable setting changed",
+                self.config.path
+            );
+            return Ok(false);
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "🔧 Configuration file {:?} reloaded, applying the new settings: {:?}",
+            self.config.path,
+            config
+        );
+
+        let previous = std::mem::replace(&mut self.current_config, config.clone());
+        self.emit(ConfigUpdated { previous, config });
+
+        Ok(true)
+    }
+
+    /// Reloads the configuration file if it was modified since the last check.
+    fn check_config_file(&mut self) {
+        let modified = match modified_time(&self.config.path) {
+            Ok(modified) => modified,
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to check the configuration file {:?}: {:?}", self.config.path, e
+                );
+                return;
+            }
+        };
+
+        if self.last_modified == Some(modified) {
+            return;
+        }
+        self.last_modified = Some(modified);
+
+        if let Err(e) = self.reload() {
+            error!(
+                target: LOG_TARGET,
+                "Rejected the modified configuration file {:?}, keeping the current settings: {:#}",
+                self.config.path,
+                e
+            );
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> std::io::Result<SystemTime> {
+    std::fs::metadata(path)?.modified()
+}
+
+pub struct ConfigWatcherServiceEventLoop {
+    receiver: sc_utils::mpsc::TracingUnboundedReceiver<ConfigWatcherServiceCommand>,
+    actor: ConfigWatcherService,
+}
+
+enum MergedEventLoopMessage {
+    Command(ConfigWatcherServiceCommand),
+    Tick,
+}
+
+/// Since this actor can check the configuration file periodically, it needs to handle both
+/// periodic ticks and messages from other actors, hence the need for a custom `ActorEventLoop`.
+impl ActorEventLoop<ConfigWatcherService> for ConfigWatcherServiceEventLoop {
+    fn new(
+        actor: ConfigWatcherService,
+        receiver: sc_utils::mpsc::TracingUnboundedReceiver<ConfigWatcherServiceCommand>,
+    ) -> Self {
+        Self { actor, receiver }
+    }
+
+    async fn run(mut self) {
+        let ticker_stream = match self.actor.config.check_period {
+            Some(check_period) => {
+                info!(
+                    target: LOG_TARGET,
+                    "🔧 StorageHub's Config Watcher Service starting up, checking {:?} for changes every {:?}!",
+                    self.actor.config.path,
+                    check_period
+                );
+
+                stream::unfold(interval(check_period), |mut interval| {
+                    Box::pin(async move {
+                        interval.tick().await;
+                        Some((MergedEventLoopMessage::Tick, interval))
+                    })
+                })
+                .left_stream()
+            }
+            None => {
+                info!(
+                    target: LOG_TARGET,
+                    "🔧 StorageHub's Config Watcher Service starting up, reloading {:?} on request only!",
+                    self.actor.config.path
+                );
+
+                stream::pending().right_stream()
+            }
+        };
+
+        let mut merged_stream = stream::select(
+            self.receiver.map(MergedEventLoopMessage::Command),
+            ticker_stream,
+        );
+
+        loop {
+            match merged_stream.next().await {
+                Some(MergedEventLoopMessage::Command(command)) => {
+                    self.actor.handle_message(command).await;
+                }
+                Some(MergedEventLoopMessage::Tick) => {
+                    self.actor.check_config_file();
+                }
+                None => {
+                    warn!(target: LOG_TARGET, "ConfigWatcherService event loop terminated.");
+                    break;
+                }
+            }
+        }
+    }
+}
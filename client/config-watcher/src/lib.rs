@@ -0,0 +1,38 @@
+//! Reloading of the settings of a provider node while it runs.
+//!
+//! Most settings of a provider node are only read when it starts, but operators also need to tune
+//! some of them on a running node, like the bandwidth caps, the volunteering policy of a BSP or
+//! the log filter. The [`ConfigWatcherService`] reloads the provider configuration file whenever
+//! it changes (if it is watched periodically) or when asked to (i.e. through the
+//! `storagehubclient_reloadConfig` RPC method). A file that fails to load or validate is rejected
+//! as a whole, keeping the current settings. Otherwise, if any of the [`ReloadableConfig`]
+//! settings changed, a [`ConfigUpdated`] event is emitted, for the tasks of the node to apply them
+//! to the services they belong to.
+//!
+//! [`ConfigUpdated`]: events::ConfigUpdated
+
+use shc_actors_framework::actor::{ActorHandle, ActorSpawner, TaskSpawner};
+
+pub use self::handler::{
+    ConfigLoader, ConfigWatcherConfig, ConfigWatcherService, ReloadableConfig,
+};
+
+/// For defining the commands processed by the config watcher service.
+pub mod commands;
+/// For defining the events emitted by the config watcher service.
+pub mod events;
+/// For reloading the configuration file.
+pub mod handler;
+
+pub async fn spawn_config_watcher_service(
+    task_spawner: &TaskSpawner,
+    config: ConfigWatcherConfig,
+) -> ActorHandle<ConfigWatcherService> {
+    let task_spawner = task_spawner
+        .with_name("config-watcher-service")
+        .with_group("config");
+
+    let config_watcher_service = ConfigWatcherService::new(config);
+
+    task_spawner.spawn_actor(config_watcher_service)
+}
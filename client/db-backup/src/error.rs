@@ -0,0 +1,24 @@
+use std::{io, path::PathBuf};
+
+/// Error type for the creation and restoration of backups.
+#[derive(thiserror::Error, Debug)]
+pub enum BackupError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Failed to read or write the backup manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    #[error(transparent)]
+    Codec(#[from] codec::Error),
+    #[error("Unsupported backup manifest version: {0}")]
+    UnsupportedManifestVersion(u32),
+    #[error("Backup {0} not found")]
+    BackupNotFound(u64),
+    #[error("Database {database} is not part of backup {backup_id}")]
+    DatabaseNotInBackup { backup_id: u64, database: String },
+    #[error("Checksum mismatch for database {database} in backup {backup_id}")]
+    ChecksumMismatch { backup_id: u64, database: String },
+    #[error("Restored database {database} is inconsistent with the backup: {reason}")]
+    InconsistentRestore { database: String, reason: String },
+    #[error("Refusing to restore into {0:?}, which already exists")]
+    DestinationExists(PathBuf),
+}
@@ -0,0 +1,49 @@
+//! Online, incremental backups of the databases of a StorageHub client, with point-in-time
+//! restore.
+//!
+//! The databases backed up are the File Storage and every Forest Storage of the node (a single
+//! one for BSPs, one per bucket for MSPs), as laid out on disk by the RocksDB storage layer.
+//!
+//! Backups are taken while the node is running: each database is opened as a RocksDB secondary
+//! instance, caught up with the node's (primary) instance and read from there, so the node never
+//! stops writing to its databases. See [`rocksdb::OnlineSnapshot`].
+//!
+//! Backups are stored in a directory with a `manifest.json` listing all of them. Each backup is
+//! either a full copy of the databases, or an incremental one holding only the entries that
+//! changed since the previous backup. Every backup is labeled with the block it was taken at, so
+//! that the databases can be restored as they were at a given point in time, and the restored
+//! forest roots checked against the ones on-chain at that block. See
+//! [`rocksdb::restore_to_storage_path`].
+
+use std::path::PathBuf;
+
+mod error;
+pub mod rocksdb;
+pub mod store;
+pub mod types;
+
+pub use error::BackupError;
+pub use store::{BackupStore, DatabaseSnapshot};
+pub use types::{
+    BackupEntry, BackupManifest, ChainPoint, DatabaseBackup, DatabaseKind, RestoredDatabase,
+};
+
+const LOG_TARGET: &str = "db-backup";
+
+/// Configuration of the backups of a node's databases.
+#[derive(Debug, Clone)]
+pub struct DbBackupConfig {
+    /// The storage path of the node's RocksDB databases.
+    pub storage_path: String,
+    /// The directory where backups are stored.
+    pub backup_path: PathBuf,
+    /// Maximum amount of incremental backups in a row before taking a full one.
+    pub max_incremental_chain: u32,
+}
+
+impl DbBackupConfig {
+    pub fn open_store(&self) -> Result<BackupStore, BackupError> {
+        Ok(BackupStore::open(&self.backup_path)?
+            .with_max_incremental_chain(self.max_incremental_chain))
+    }
+}
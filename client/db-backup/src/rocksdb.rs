@@ -0,0 +1,325 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use kvdb::{DBKey, DBKeyValue, DBTransaction, DBValue, KeyValueDB};
+use kvdb_rocksdb::{Database, DatabaseConfig};
+use log::warn;
+use rocksdb::{ColumnFamily, Direction, IteratorMode, Options, DB};
+use shc_file_manager::rocksdb::{FILE_STORAGE_DB_COLUMNS, FILE_STORAGE_DB_PATH};
+use shc_forest_manager::rocksdb::{FOREST_STORAGE_DB_COLUMNS, FOREST_STORAGE_DB_PATH};
+
+use crate::{
+    error::BackupError,
+    store::{BackupStore, DatabaseSnapshot},
+    types::{BackupEntry, ChainPoint, DatabaseKind, RestoredDatabase},
+    LOG_TARGET,
+};
+
+/// Directory, in the backup directory, where the secondary instances of the databases are kept.
+const SECONDARY_INSTANCES_DIR: &str = ".secondary";
+
+/// A RocksDB database of the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseLocation {
+    pub kind: DatabaseKind,
+    pub path: PathBuf,
+    pub columns: u32,
+}
+
+impl DatabaseLocation {
+    /// The location of the database of `kind` for a node with the given `storage_path`.
+    pub fn new(storage_path: &str, kind: DatabaseKind) -> Self {
+        match kind {
+            DatabaseKind::FileStorage => Self {
+                path: Path::new(storage_path).join(FILE_STORAGE_DB_PATH),
+                columns: FILE_STORAGE_DB_COLUMNS,
+                kind,
+            },
+            // Forest Storages live next to the storage path, suffixed with their (debug formatted)
+            // key, as created by the forest storage handler.
+            DatabaseKind::Forest { ref forest_key } => Self {
+                path: PathBuf::from(format!("{}_{:?}", storage_path, forest_key))
+                    .join(FOREST_STORAGE_DB_PATH),
+                columns: FOREST_STORAGE_DB_COLUMNS,
+                kind,
+            },
+        }
+    }
+}
+
+/// Finds the File Storage and Forest Storage databases of a node with the given `storage_path`.
+pub fn discover_databases(storage_path: &str) -> Result<Vec<DatabaseLocation>, BackupError> {
+    let mut locations = Vec::new();
+
+    let file_storage = DatabaseLocation::new(storage_path, DatabaseKind::FileStorage);
+    if file_storage.path.exists() {
+        locations.push(file_storage);
+    }
+
+    let storage_path_buf = Path::new(storage_path);
+    let parent = match storage_path_buf.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let prefix = match storage_path_buf.file_name().and_then(|name| name.to_str()) {
+        Some(name) => format!("{}_", name),
+        None => return Ok(locations),
+    };
+
+    for dir_entry in fs::read_dir(parent)? {
+        let file_name = dir_entry?.file_name();
+        let maybe_forest_key = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(parse_forest_key);
+
+        if let Some(forest_key) = maybe_forest_key {
+            let location = DatabaseLocation::new(storage_path, DatabaseKind::Forest { forest_key });
+            if location.path.exists() {
+                locations.push(location);
+            }
+        }
+    }
+
+    Ok(locations)
+}
+
+/// Parses a forest key formatted as `Vec<u8>`'s debug representation, e.g. `[1, 2, 3]`.
+fn parse_forest_key(formatted: &str) -> Option<Vec<u8>> {
+    let inner = formatted.strip_prefix('[')?.strip_suffix(']')?;
+    if inner.is_empty() {
+        return Some(Vec::new());
+    }
+
+    inner
+        .split(", ")
+        .map(|byte| byte.parse::<u8>().ok())
+        .collect()
+}
+
+/// A database of the node opened as a RocksDB secondary instance, which can read it while the
+/// node keeps writing to it.
+///
+/// It is opened with the `rocksdb` crate rather than `kvdb-rocksdb`, which does not expose the
+/// sequence number of the database.
+struct SecondaryDatabase {
+    db: DB,
+}
+
+impl SecondaryDatabase {
+    /// Opens the database at `location` as a secondary instance, caught up with the node's
+    /// instance.
+    fn open(location: &DatabaseLocation, secondary_path: PathBuf) -> Result<Self, BackupError> {
+        fs::create_dir_all(&secondary_path)?;
+
+        // Secondary instances have to keep every file of the primary open.
+        let mut options = Options::default();
+        options.set_max_open_files(-1);
+
+        let db = DB::open_cf_as_secondary(
+            &options,
+            &location.path,
+            &secondary_path,
+            (0..location.columns).map(column_name),
+        )
+        .map_err(other_io_err)?;
+        db.try_catch_up_with_primary().map_err(other_io_err)?;
+
+        Ok(Self { db })
+    }
+
+    /// The sequence number of the last write to the database, as of the last catch up with the
+    /// node's instance.
+    fn sequence_number(&self) -> u64 {
+        self.db.latest_sequence_number()
+    }
+
+    fn column(&self, col: u32) -> io::Result<&ColumnFamily> {
+        self.db
+            .cf_handle(&column_name(col))
+            .ok_or_else(|| other_io_err(format!("No such column: {}", col)))
+    }
+}
+
+impl KeyValueDB for SecondaryDatabase {
+    fn get(&self, col: u32, key: &[u8]) -> io::Result<Option<DBValue>> {
+        self.db.get_cf(self.column(col)?, key).map_err(other_io_err)
+    }
+
+    fn get_by_prefix(&self, col: u32, prefix: &[u8]) -> io::Result<Option<DBValue>> {
+        self.iter_with_prefix(col, prefix)
+            .next()
+            .transpose()
+            .map(|entry| entry.map(|(_, value)| value))
+    }
+
+    fn write(&self, _transaction: DBTransaction) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Secondary instances are read-only",
+        ))
+    }
+
+    fn iter<'a>(&'a self, col: u32) -> Box<dyn Iterator<Item = io::Result<DBKeyValue>> + 'a> {
+        self.iter_with_prefix(col, &[])
+    }
+
+    fn iter_with_prefix<'a>(
+        &'a self,
+        col: u32,
+        prefix: &'a [u8],
+    ) -> Box<dyn Iterator<Item = io::Result<DBKeyValue>> + 'a> {
+        let column = match self.column(col) {
+            Ok(column) => column,
+            Err(e) => return Box::new(std::iter::once(Err(e))),
+        };
+
+        Box::new(
+            self.db
+                .iterator_cf(column, IteratorMode::From(prefix, Direction::Forward))
+                .map(|entry| {
+                    entry
+                        .map(|(key, value)| (DBKey::from_slice(&key), value.into_vec()))
+                        .map_err(other_io_err)
+                })
+                .take_while(move |entry| match entry {
+                    Ok((key, _)) => key.starts_with(prefix),
+                    Err(_) => true,
+                }),
+        )
+    }
+}
+
+/// The name of the column family of column `col`, as created by `kvdb-rocksdb`.
+fn column_name(col: u32) -> String {
+    format!("col{}", col)
+}
+
+fn other_io_err<E: ToString>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e.to_string())
+}
+
+/// The databases of a running node, caught up with the node's instances at the same point in
+/// time, ready to be backed up with [`OnlineSnapshot::back_up`].
+///
+/// Each database is kept open as a secondary instance, which does not see the node's later writes
+/// until it is caught up again. The node can then keep writing to its databases while they are
+/// read to back them up.
+pub struct OnlineSnapshot {
+    locations: Vec<DatabaseLocation>,
+    databases: Vec<SecondaryDatabase>,
+}
+
+impl OnlineSnapshot {
+    /// Catches up the databases of a running node at `locations` (see [`discover_databases`])
+    /// with the node's instances, keeping their secondary instances in `store`.
+    ///
+    /// Callers must prevent writes to the databases while this runs, by holding the read locks
+    /// of the File Storage and of every Forest Storage, so that they all reflect the node's state
+    /// at the same point in time. The locks can be released as soon as it returns.
+    pub fn take(
+        store: &BackupStore,
+        locations: Vec<DatabaseLocation>,
+    ) -> Result<Self, BackupError> {
+        let secondary_root = store.path().join(SECONDARY_INSTANCES_DIR);
+
+        let databases = locations
+            .iter()
+            .map(|location| {
+                SecondaryDatabase::open(location, secondary_root.join(location.kind.name()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            locations,
+            databases,
+        })
+    }
+
+    /// Backs up the databases into `store`, as of the block `at` they were caught up at.
+    ///
+    /// Databases whose sequence number did not change since the previous backup are not read
+    /// again.
+    pub fn back_up(&self, store: &BackupStore, at: ChainPoint) -> Result<BackupEntry, BackupError> {
+        let snapshots = self
+            .locations
+            .iter()
+            .zip(self.databases.iter())
+            .map(|(location, db)| DatabaseSnapshot {
+                kind: location.kind.clone(),
+                columns: location.columns,
+                sequence_number: Some(db.sequence_number()),
+                db,
+            })
+            .collect::<Vec<_>>();
+
+        store.create_backup(&snapshots, at)
+    }
+}
+
+/// Restores every database in backup `backup_id` for a node with the given `storage_path`.
+///
+/// The node must be stopped, and none of the databases in the backup may exist already.
+pub fn restore_to_storage_path(
+    store: &BackupStore,
+    backup_id: u64,
+    storage_path: &str,
+) -> Result<Vec<RestoredDatabase>, BackupError> {
+    let backup = store.backup(backup_id)?;
+
+    let locations = backup
+        .databases
+        .iter()
+        .map(|database| DatabaseLocation::new(storage_path, database.kind.clone()))
+        .collect::<Vec<_>>();
+    if let Some(location) = locations.iter().find(|location| location.path.exists()) {
+        return Err(BackupError::DestinationExists(location.path.clone()));
+    }
+
+    let mut restored = Vec::with_capacity(backup.databases.len());
+    for (database, location) in backup.databases.iter().zip(locations) {
+        fs::create_dir_all(&location.path)?;
+        let db = Database::open(
+            &DatabaseConfig::with_columns(location.columns),
+            &location.path,
+        )?;
+
+        match store.restore(backup_id, &database.name, &db) {
+            Ok(restored_database) => restored.push(restored_database),
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to restore database {}, leaving it at {:?} for inspection",
+                    database.name,
+                    location.path
+                );
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forest_keys_are_parsed_back_from_their_path() {
+        for forest_key in [
+            Vec::new(),
+            vec![0u8, 1, 255],
+            b":current_forest_key".to_vec(),
+        ] {
+            assert_eq!(
+                parse_forest_key(&format!("{:?}", forest_key)),
+                Some(forest_key)
+            );
+        }
+        assert_eq!(parse_forest_key("[1, 256]"), None);
+        assert_eq!(parse_forest_key("not_a_key"), None);
+    }
+}
@@ -0,0 +1,912 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode, IoReader};
+use kvdb::{DBTransaction, KeyValueDB};
+use kvdb_rocksdb::{Database, DatabaseConfig};
+use log::{debug, info};
+use shc_forest_manager::rocksdb::well_known_keys;
+use sp_core::{hashing::blake2_256, H256};
+
+use crate::{
+    error::BackupError,
+    types::{
+        BackupEntry, BackupManifest, ChainPoint, DatabaseBackup, DatabaseKind, RestoredDatabase,
+        MANIFEST_VERSION,
+    },
+    LOG_TARGET,
+};
+
+/// Name of the manifest file in the backup directory.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Directory, in the backup directory, holding the index of every backed up database.
+const INDEX_DIR: &str = "index";
+
+/// Columns of the index of a database.
+const INDEX_COLUMNS: u32 = 2;
+/// Column of the index with the digest of every entry of the database, by column and key.
+const INDEX_DIGESTS_COLUMN: u32 = 0;
+/// Column of the index with its metadata.
+const INDEX_META_COLUMN: u32 = 1;
+/// Key, in [`INDEX_META_COLUMN`], of the ID of the backup the index is up to date with.
+const INDEXED_BACKUP_KEY: &[u8] = b"indexed_backup";
+
+/// Default maximum amount of incremental backups in a row before taking a full one.
+pub const DEFAULT_MAX_INCREMENTAL_CHAIN: u32 = 24;
+
+/// Amount of restored entries written to the database at once.
+const RESTORE_BATCH_SIZE: usize = 1024;
+
+/// Amount of changes to the index of a database written to it at once.
+const INDEX_WRITE_BATCH_SIZE: usize = 1024;
+
+/// A change to a database, as stored in the backup files.
+#[derive(Debug, Encode, Decode)]
+enum DeltaRecord {
+    Put {
+        column: u32,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        column: u32,
+        key: Vec<u8>,
+    },
+}
+
+/// The digest of every entry of a database as of the last backup of it, by column and key,
+/// persisted in the backup directory to compute the changes for the next incremental backup
+/// without holding every key in memory.
+struct DatabaseIndex {
+    db: Database,
+}
+
+impl DatabaseIndex {
+    fn open(path: &Path) -> Result<Self, BackupError> {
+        fs::create_dir_all(path)?;
+        let db = Database::open(&DatabaseConfig::with_columns(INDEX_COLUMNS), path)?;
+
+        Ok(Self { db })
+    }
+
+    /// The backup the index is up to date with, if any.
+    fn indexed_backup(&self) -> Result<Option<u64>, BackupError> {
+        Ok(self
+            .db
+            .get(INDEX_META_COLUMN, INDEXED_BACKUP_KEY)?
+            .map(|backup_id| u64::decode(&mut &backup_id[..]))
+            .transpose()?)
+    }
+
+    /// The digest of every indexed entry, ordered by column and key.
+    fn digests(
+        &self,
+    ) -> impl Iterator<Item = Result<((u32, Vec<u8>), [u8; 32]), BackupError>> + '_ {
+        self.db.iter(INDEX_DIGESTS_COLUMN).map(|entry| {
+            let (index_key, digest) = entry?;
+            if index_key.len() < 4 {
+                return Err(codec::Error::from("Index key without a column").into());
+            }
+            let (column, key) = index_key.split_at(4);
+            let column = u32::from_be_bytes(column.try_into().expect("Split at 4 bytes; qed"));
+
+            Ok((
+                (column, key.to_vec()),
+                <[u8; 32]>::decode(&mut &digest[..])?,
+            ))
+        })
+    }
+
+    fn write(&self, transaction: DBTransaction) -> Result<(), BackupError> {
+        Ok(self.db.write(transaction)?)
+    }
+}
+
+/// The key of the digest of the entry `key` of `column` in the index, which orders the digests by
+/// column and then key.
+fn index_key(column: u32, key: &[u8]) -> Vec<u8> {
+    let mut index_key = column.to_be_bytes().to_vec();
+    index_key.extend_from_slice(key);
+    index_key
+}
+
+/// A database to back up.
+pub struct DatabaseSnapshot<'a> {
+    pub kind: DatabaseKind,
+    pub columns: u32,
+    /// The RocksDB sequence number of the database, if known. The database is not read if it did
+    /// not change since the previous backup.
+    pub sequence_number: Option<u64>,
+    /// The database, which should not be written to while it is being backed up.
+    pub db: &'a dyn KeyValueDB,
+}
+
+/// A directory holding backups of the client databases, and their manifest.
+///
+/// Each backup is stored in its own subdirectory, with a `<database>.delta` file holding the
+/// entries of each database (all of them or only the changed ones, depending on whether it is a
+/// full backup). The digest of every entry as of the latest backup is kept in the index of each
+/// database, under [`INDEX_DIR`].
+pub struct BackupStore {
+    path: PathBuf,
+    max_incremental_chain: u32,
+}
+
+impl BackupStore {
+    /// Opens the backup directory at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, BackupError> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+
+        Ok(Self {
+            path,
+            max_incremental_chain: DEFAULT_MAX_INCREMENTAL_CHAIN,
+        })
+    }
+
+    /// Sets the maximum amount of incremental backups in a row before taking a full one.
+    ///
+    /// Restoring an incremental backup requires every backup since the last full one, so the
+    /// longer the chain, the smaller the backups but the slower (and more fragile) the restore.
+    pub fn with_max_incremental_chain(mut self, max_incremental_chain: u32) -> Self {
+        self.max_incremental_chain = max_incremental_chain;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the manifest, which is empty if no backup has been taken yet.
+    pub fn manifest(&self) -> Result<BackupManifest, BackupError> {
+        let manifest_path = self.path.join(MANIFEST_FILE);
+        if !manifest_path.exists() {
+            return Ok(BackupManifest::default());
+        }
+
+        let manifest: BackupManifest = serde_json::from_slice(&fs::read(manifest_path)?)?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(BackupError::UnsupportedManifestVersion(manifest.version));
+        }
+
+        Ok(manifest)
+    }
+
+    /// All backups, oldest first.
+    pub fn list(&self) -> Result<Vec<BackupEntry>, BackupError> {
+        Ok(self.manifest()?.backups)
+    }
+
+    pub fn backup(&self, backup_id: u64) -> Result<BackupEntry, BackupError> {
+        self.manifest()?
+            .backups
+            .into_iter()
+            .find(|backup| backup.id == backup_id)
+            .ok_or(BackupError::BackupNotFound(backup_id))
+    }
+
+    /// The latest backup taken at or before `block_number`, to restore the databases as they were
+    /// at that point in time.
+    pub fn backup_at_block(&self, block_number: u32) -> Result<Option<BackupEntry>, BackupError> {
+        Ok(self
+            .manifest()?
+            .backups
+            .into_iter()
+            .filter(|backup| backup.at.block_number <= block_number)
+            .max_by_key(|backup| (backup.at.block_number, backup.id)))
+    }
+
+    /// Backs up `databases`, labeling the backup with the block `at`.
+    ///
+    /// The backup is incremental to the previous one, unless there is none or there are already
+    /// [`Self::with_max_incremental_chain`] incremental backups in a row. Databases that were not
+    /// in the previous backup are always backed up in full.
+    pub fn create_backup(
+        &self,
+        databases: &[DatabaseSnapshot<'_>],
+        at: ChainPoint,
+    ) -> Result<BackupEntry, BackupError> {
+        let mut manifest = self.manifest()?;
+        let id = manifest.backups.last().map_or(0, |backup| backup.id + 1);
+
+        let previous = manifest.backups.last();
+        let parent = match previous {
+            Some(previous)
+                if incremental_chain_length(&manifest, previous) < self.max_incremental_chain =>
+            {
+                Some(previous)
+            }
+            _ => None,
+        };
+
+        // Write the backup to a temporary directory, renamed once complete, so that an
+        // interrupted backup never ends up in the manifest.
+        let partial_dir = self.path.join(format!("{}.partial", backup_dir_name(id)));
+        if partial_dir.exists() {
+            fs::remove_dir_all(&partial_dir)?;
+        }
+        fs::create_dir_all(&partial_dir)?;
+
+        let mut database_backups = Vec::with_capacity(databases.len());
+        let mut indexes = Vec::with_capacity(databases.len());
+        for database in databases {
+            let name = database.kind.name();
+            let index = DatabaseIndex::open(&self.path.join(INDEX_DIR).join(&name))?;
+
+            // The index can only be diffed against if it is up to date with the parent backup,
+            // which it is not if a backup was interrupted after updating it.
+            let indexed_backup = index.indexed_backup()?;
+            let parent_backup = parent
+                .and_then(|parent| {
+                    parent
+                        .database(&name)
+                        .map(|database_backup| (parent.id, database_backup))
+                })
+                .filter(|(parent_id, _)| indexed_backup == Some(*parent_id));
+
+            let database_backup = match parent_backup {
+                Some((parent_id, parent_backup))
+                    if database.sequence_number.is_some()
+                        && database.sequence_number == parent_backup.sequence_number =>
+                {
+                    unchanged_database(&name, parent_id, parent_backup, &partial_dir)?
+                }
+                _ => backup_database(
+                    database,
+                    &name,
+                    parent_backup.map(|(parent_id, _)| parent_id),
+                    &index,
+                    &partial_dir,
+                )?,
+            };
+            debug!(
+                target: LOG_TARGET,
+                "Backed up database {} ({} puts, {} deletions)",
+                name,
+                database_backup.puts,
+                database_backup.deletions
+            );
+            database_backups.push(database_backup);
+            indexes.push(index);
+        }
+
+        // A directory left by a backup interrupted after being renamed, but before being added to
+        // the manifest.
+        let backup_dir = self.path.join(backup_dir_name(id));
+        if backup_dir.exists() {
+            fs::remove_dir_all(&backup_dir)?;
+        }
+        fs::rename(&partial_dir, backup_dir)?;
+
+        for index in indexes {
+            let mut set_indexed_backup = DBTransaction::new();
+            set_indexed_backup.put(INDEX_META_COLUMN, INDEXED_BACKUP_KEY, &id.encode());
+            index.write(set_indexed_backup)?;
+        }
+
+        let entry = BackupEntry {
+            id,
+            parent: parent.map(|parent| parent.id),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            at,
+            databases: database_backups,
+        };
+        manifest.backups.push(entry.clone());
+        self.write_manifest(&manifest)?;
+
+        info!(
+            target: LOG_TARGET,
+            "Created {} backup {} at block {}",
+            if entry.is_full() { "full" } else { "incremental" },
+            id,
+            at.block_number
+        );
+
+        Ok(entry)
+    }
+
+    /// Restores the database `name` as it was in backup `backup_id` into `dest`, which must be
+    /// empty.
+    ///
+    /// The checksum of every backup in the chain is checked before any entry is written, and the
+    /// restored database is checked against the amount of entries and forest root recorded when
+    /// the backup was taken.
+    pub fn restore(
+        &self,
+        backup_id: u64,
+        name: &str,
+        dest: &dyn KeyValueDB,
+    ) -> Result<RestoredDatabase, BackupError> {
+        let manifest = self.manifest()?;
+        let chain = restore_chain(&manifest, backup_id, name)?;
+
+        for (id, database_backup) in &chain {
+            self.verify_delta(*id, database_backup)?;
+        }
+        for (id, database_backup) in &chain {
+            self.apply_delta(*id, database_backup, dest)?;
+        }
+
+        let (_, database_backup) = chain
+            .last()
+            .expect("The chain has at least the requested backup; qed");
+        let mut entries = 0u64;
+        for column in 0..database_backup.columns {
+            for entry in dest.iter(column) {
+                entry?;
+                entries += 1;
+            }
+        }
+        if entries != database_backup.entries {
+            return Err(BackupError::InconsistentRestore {
+                database: name.to_string(),
+                reason: format!(
+                    "expected {} entries, restored {}",
+                    database_backup.entries, entries
+                ),
+            });
+        }
+
+        let forest_root = read_forest_root(&database_backup.kind, dest)?;
+        if forest_root != database_backup.forest_root {
+            return Err(BackupError::InconsistentRestore {
+                database: name.to_string(),
+                reason: format!(
+                    "expected forest root {:?}, restored {:?}",
+                    database_backup.forest_root, forest_root
+                ),
+            });
+        }
+
+        Ok(RestoredDatabase {
+            name: name.to_string(),
+            kind: database_backup.kind.clone(),
+            entries,
+            forest_root,
+        })
+    }
+
+    fn write_manifest(&self, manifest: &BackupManifest) -> Result<(), BackupError> {
+        let tmp_path = self.path.join(format!("{}.tmp", MANIFEST_FILE));
+        fs::write(&tmp_path, serde_json::to_vec_pretty(manifest)?)?;
+        fs::rename(tmp_path, self.path.join(MANIFEST_FILE))?;
+        Ok(())
+    }
+
+    fn delta_reader(&self, backup_id: u64, name: &str) -> Result<BufReader<File>, BackupError> {
+        let path = self
+            .path
+            .join(backup_dir_name(backup_id))
+            .join(format!("{}.delta", name));
+        Ok(BufReader::new(File::open(path)?))
+    }
+
+    fn verify_delta(
+        &self,
+        backup_id: u64,
+        database_backup: &DatabaseBackup,
+    ) -> Result<(), BackupError> {
+        let mut reader = self.delta_reader(backup_id, &database_backup.name)?;
+        let mut checksum = H256::zero();
+        for _ in 0..database_backup.puts + database_backup.deletions {
+            let record = DeltaRecord::decode(&mut IoReader(&mut reader))?;
+            checksum = chain_checksum(&checksum, &record);
+        }
+
+        if checksum != database_backup.checksum {
+            return Err(BackupError::ChecksumMismatch {
+                backup_id,
+                database: database_backup.name.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn apply_delta(
+        &self,
+        backup_id: u64,
+        database_backup: &DatabaseBackup,
+        dest: &dyn KeyValueDB,
+    ) -> Result<(), BackupError> {
+        let mut reader = self.delta_reader(backup_id, &database_backup.name)?;
+        let mut transaction = DBTransaction::new();
+        for _ in 0..database_backup.puts + database_backup.deletions {
+            match DeltaRecord::decode(&mut IoReader(&mut reader))? {
+                DeltaRecord::Put { column, key, value } => transaction.put(column, &key, &value),
+                DeltaRecord::Delete { column, key } => transaction.delete(column, &key),
+            }
+
+            if transaction.ops.len() >= RESTORE_BATCH_SIZE {
+                dest.write(std::mem::take(&mut transaction))?;
+            }
+        }
+        dest.write(transaction)?;
+
+        Ok(())
+    }
+}
+
+fn backup_dir_name(backup_id: u64) -> String {
+    format!("{:08}", backup_id)
+}
+
+/// The amount of backups that have to be restored to restore `backup`, i.e. the backup itself and
+/// every backup it is incremental to.
+fn incremental_chain_length(manifest: &BackupManifest, backup: &BackupEntry) -> u32 {
+    let mut length = 1;
+    let mut current = backup;
+    while let Some(parent) = current
+        .parent
+        .and_then(|parent_id| manifest.backups.iter().find(|b| b.id == parent_id))
+    {
+        length += 1;
+        current = parent;
+    }
+    length
+}
+
+/// The backups of the database `name` to apply, in order, to restore it as it was in backup
+/// `backup_id`.
+fn restore_chain(
+    manifest: &BackupManifest,
+    backup_id: u64,
+    name: &str,
+) -> Result<Vec<(u64, DatabaseBackup)>, BackupError> {
+    let mut chain = Vec::new();
+    let mut next = Some(backup_id);
+    while let Some(id) = next {
+        let backup = manifest
+            .backups
+            .iter()
+            .find(|backup| backup.id == id)
+            .ok_or(BackupError::BackupNotFound(id))?;
+        let database_backup =
+            backup
+                .database(name)
+                .ok_or_else(|| BackupError::DatabaseNotInBackup {
+                    backup_id: id,
+                    database: name.to_string(),
+                })?;
+        next = database_backup.parent;
+        chain.push((id, database_backup.clone()));
+    }
+    chain.reverse();
+
+    Ok(chain)
+}
+
+/// Writes the records of a backup to its `.delta` file.
+struct DeltaWriter {
+    writer: BufWriter<File>,
+    checksum: H256,
+    puts: u64,
+    deletions: u64,
+}
+
+impl DeltaWriter {
+    fn create(path: PathBuf) -> Result<Self, BackupError> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            checksum: H256::zero(),
+            puts: 0,
+            deletions: 0,
+        })
+    }
+
+    fn write(&mut self, record: DeltaRecord) -> Result<(), BackupError> {
+        self.writer.write_all(&record.encode())?;
+        self.checksum = chain_checksum(&self.checksum, &record);
+        match record {
+            DeltaRecord::Put { .. } => self.puts += 1,
+            DeltaRecord::Delete { .. } => self.deletions += 1,
+        }
+        Ok(())
+    }
+}
+
+/// Writes the backup of `database` in `backup_dir`, with the entries that changed since the
+/// `parent` backup according to `index`, or all of them if there is no parent, updating `index`
+/// along the way.
+///
+/// The database and the index are both read in order of column and key, so that the changes are
+/// found in a single pass over each. The index is marked as not up to date with any backup until
+/// the backup completes.
+fn backup_database(
+    database: &DatabaseSnapshot<'_>,
+    name: &str,
+    parent: Option<u64>,
+    index: &DatabaseIndex,
+    backup_dir: &Path,
+) -> Result<DatabaseBackup, BackupError> {
+    let full = parent.is_none();
+    let mut delta = DeltaWriter::create(backup_dir.join(format!("{}.delta", name)))?;
+    let mut index_update = DBTransaction::new();
+    let mut entries = 0u64;
+
+    let mut clear_indexed_backup = DBTransaction::new();
+    clear_indexed_backup.delete(INDEX_META_COLUMN, INDEXED_BACKUP_KEY);
+    index.write(clear_indexed_backup)?;
+
+    // Iterators read from a snapshot of the index, so it can be updated while iterating.
+    let mut indexed = index.digests().peekable();
+    for column in 0..database.columns {
+        for entry in database.db.iter(column) {
+            let (key, value) = entry?;
+            let digest = blake2_256(&value);
+            entries += 1;
+
+            let mut previous_digest = None;
+            while let Some(indexed_entry) = indexed.next_if(|indexed_entry| match indexed_entry {
+                Ok(((indexed_column, indexed_key), _)) => {
+                    (*indexed_column, indexed_key.as_slice()) <= (column, &key[..])
+                }
+                Err(_) => true,
+            }) {
+                let ((indexed_column, indexed_key), indexed_digest) = indexed_entry?;
+                if (indexed_column, indexed_key.as_slice()) == (column, &key[..]) {
+                    previous_digest = Some(indexed_digest);
+                } else {
+                    record_deletion(
+                        &mut delta,
+                        &mut index_update,
+                        full,
+                        indexed_column,
+                        indexed_key,
+                    )?;
+                }
+            }
+
+            if previous_digest != Some(digest) {
+                index_update.put(INDEX_DIGESTS_COLUMN, &index_key(column, &key), &digest);
+            }
+            if full || previous_digest != Some(digest) {
+                delta.write(DeltaRecord::Put {
+                    column,
+                    key: key.to_vec(),
+                    value,
+                })?;
+            }
+
+            if index_update.ops.len() >= INDEX_WRITE_BATCH_SIZE {
+                index.write(std::mem::take(&mut index_update))?;
+            }
+        }
+    }
+    for indexed_entry in indexed {
+        let ((indexed_column, indexed_key), _) = indexed_entry?;
+        record_deletion(
+            &mut delta,
+            &mut index_update,
+            full,
+            indexed_column,
+            indexed_key,
+        )?;
+    }
+    index.write(index_update)?;
+
+    delta.writer.flush()?;
+
+    Ok(DatabaseBackup {
+        name: name.to_string(),
+        kind: database.kind.clone(),
+        columns: database.columns,
+        parent,
+        entries,
+        puts: delta.puts,
+        deletions: delta.deletions,
+        checksum: delta.checksum,
+        forest_root: read_forest_root(&database.kind, database.db)?,
+        sequence_number: database.sequence_number,
+    })
+}
+
+/// Removes the entry `key` of `column`, which is no longer in the database, from the index. It
+/// only needs to be recorded as deleted in incremental backups, since full ones are restored into
+/// an empty database.
+fn record_deletion(
+    delta: &mut DeltaWriter,
+    index_update: &mut DBTransaction,
+    full: bool,
+    column: u32,
+    key: Vec<u8>,
+) -> Result<(), BackupError> {
+    index_update.delete(INDEX_DIGESTS_COLUMN, &index_key(column, &key));
+    if full {
+        return Ok(());
+    }
+    delta.write(DeltaRecord::Delete { column, key })
+}
+
+/// Writes the backup in `backup_dir` of the database `name`, which did not change since the
+/// `parent` backup. It holds no changes, so the database is not read.
+fn unchanged_database(
+    name: &str,
+    parent: u64,
+    parent_backup: &DatabaseBackup,
+    backup_dir: &Path,
+) -> Result<DatabaseBackup, BackupError> {
+    let delta = DeltaWriter::create(backup_dir.join(format!("{}.delta", name)))?;
+
+    Ok(DatabaseBackup {
+        parent: Some(parent),
+        puts: 0,
+        deletions: 0,
+        checksum: delta.checksum,
+        ..parent_backup.clone()
+    })
+}
+
+fn chain_checksum(checksum: &H256, record: &DeltaRecord) -> H256 {
+    H256(blake2_256(&(checksum, record).encode()))
+}
+
+fn read_forest_root(kind: &DatabaseKind, db: &dyn KeyValueDB) -> Result<Option<H256>, BackupError> {
+    if *kind == DatabaseKind::FileStorage {
+        return Ok(None);
+    }
+
+    Ok(db
+        .get(0, well_known_keys::ROOT)?
+        .filter(|root| root.len() == H256::len_bytes())
+        .map(|root| H256::from_slice(&root)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_dir(test_name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "shc-db-backup-{}-{}",
+            test_name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+
+    fn chain_point(block_number: u32) -> ChainPoint {
+        ChainPoint {
+            block_number,
+            block_hash: H256::repeat_byte(block_number as u8),
+        }
+    }
+
+    fn put(db: &dyn KeyValueDB, key: &[u8], value: &[u8]) {
+        let mut transaction = DBTransaction::new();
+        transaction.put(0, key, value);
+        db.write(transaction).unwrap();
+    }
+
+    fn delete(db: &dyn KeyValueDB, key: &[u8]) {
+        let mut transaction = DBTransaction::new();
+        transaction.delete(0, key);
+        db.write(transaction).unwrap();
+    }
+
+    fn entries(db: &dyn KeyValueDB) -> Vec<(Vec<u8>, Vec<u8>)> {
+        db.iter(0)
+            .map(|entry| {
+                let (key, value) = entry.unwrap();
+                (key.to_vec(), value)
+            })
+            .collect()
+    }
+
+    fn forest_snapshot(db: &dyn KeyValueDB) -> DatabaseSnapshot<'_> {
+        DatabaseSnapshot {
+            kind: DatabaseKind::Forest {
+                forest_key: b"forest".to_vec(),
+            },
+            columns: 1,
+            sequence_number: None,
+            db,
+        }
+    }
+
+    fn restored_entries(store: &BackupStore, backup: &BackupEntry) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let restored_db = kvdb_memorydb::create(1);
+        store
+            .restore(backup.id, &backup.databases[0].name, &restored_db)
+            .unwrap();
+        entries(&restored_db)
+    }
+
+    #[test]
+    fn incremental_backups_restore_each_point_in_time() {
+        let path = backup_dir("incremental");
+        let store = BackupStore::open(&path).unwrap();
+        let db = kvdb_memorydb::create(1);
+
+        put(&db, well_known_keys::ROOT, H256::repeat_byte(1).as_bytes());
+        put(&db, b"a", b"1");
+        put(&db, b"b", b"2");
+        let first = store
+            .create_backup(&[forest_snapshot(&db)], chain_point(10))
+            .unwrap();
+        let first_entries = entries(&db);
+
+        put(&db, well_known_keys::ROOT, H256::repeat_byte(2).as_bytes());
+        put(&db, b"b", b"3");
+        delete(&db, b"a");
+        let second = store
+            .create_backup(&[forest_snapshot(&db)], chain_point(20))
+            .unwrap();
+        let second_entries = entries(&db);
+
+        assert!(first.is_full());
+        assert_eq!(second.parent, Some(first.id));
+        let database = &second.databases[0];
+        assert_eq!((database.puts, database.deletions), (2, 1));
+        assert_eq!(database.forest_root, Some(H256::repeat_byte(2)));
+
+        for (block_number, expected_entries) in [(15, first_entries), (25, second_entries)] {
+            let backup = store.backup_at_block(block_number).unwrap().unwrap();
+            let restored_db = kvdb_memorydb::create(1);
+            store
+                .restore(backup.id, &backup.databases[0].name, &restored_db)
+                .unwrap();
+            assert_eq!(entries(&restored_db), expected_entries);
+        }
+        assert!(store.backup_at_block(5).unwrap().is_none());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn full_backup_taken_after_max_incremental_chain() {
+        let path = backup_dir("max-chain");
+        let store = BackupStore::open(&path)
+            .unwrap()
+            .with_max_incremental_chain(2);
+        let db = kvdb_memorydb::create(1);
+
+        let parents = (0..4)
+            .map(|i| {
+                put(&db, &[i], &[i]);
+                store
+                    .create_backup(&[forest_snapshot(&db)], chain_point(i as u32))
+                    .unwrap()
+                    .parent
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(parents, vec![None, Some(0), None, Some(2)]);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn database_with_unchanged_sequence_number_is_not_read() {
+        let path = backup_dir("sequence-number");
+        let store = BackupStore::open(&path).unwrap();
+        let db = kvdb_memorydb::create(1);
+
+        put(&db, b"a", b"1");
+        let first = store
+            .create_backup(
+                &[DatabaseSnapshot {
+                    sequence_number: Some(1),
+                    ..forest_snapshot(&db)
+                }],
+                chain_point(1),
+            )
+            .unwrap();
+
+        // A write the sequence number does not account for, so it is not backed up.
+        put(&db, b"b", b"2");
+        let second = store
+            .create_backup(
+                &[DatabaseSnapshot {
+                    sequence_number: Some(1),
+                    ..forest_snapshot(&db)
+                }],
+                chain_point(2),
+            )
+            .unwrap();
+        let database = &second.databases[0];
+        assert_eq!(database.parent, Some(first.id));
+        assert_eq!((database.puts, database.deletions), (0, 0));
+        assert_eq!(database.entries, 1);
+        assert_eq!(
+            restored_entries(&store, &second),
+            vec![(b"a".to_vec(), b"1".to_vec())]
+        );
+
+        let third = store
+            .create_backup(
+                &[DatabaseSnapshot {
+                    sequence_number: Some(2),
+                    ..forest_snapshot(&db)
+                }],
+                chain_point(3),
+            )
+            .unwrap();
+        let database = &third.databases[0];
+        assert_eq!((database.puts, database.deletions), (1, 0));
+        assert_eq!(restored_entries(&store, &third), entries(&db));
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn index_not_up_to_date_with_parent_falls_back_to_full_backup() {
+        let path = backup_dir("stale-index");
+        let store = BackupStore::open(&path).unwrap();
+        let db = kvdb_memorydb::create(1);
+
+        put(&db, b"a", b"1");
+        put(&db, b"b", b"2");
+        store
+            .create_backup(&[forest_snapshot(&db)], chain_point(1))
+            .unwrap();
+
+        // As if a backup was interrupted after updating the index.
+        let name = forest_snapshot(&db).kind.name();
+        let index = DatabaseIndex::open(&path.join(INDEX_DIR).join(&name)).unwrap();
+        let mut transaction = DBTransaction::new();
+        transaction.delete(INDEX_DIGESTS_COLUMN, &index_key(0, b"a"));
+        transaction.delete(INDEX_META_COLUMN, INDEXED_BACKUP_KEY);
+        index.write(transaction).unwrap();
+        drop(index);
+
+        delete(&db, b"b");
+        let second = store
+            .create_backup(&[forest_snapshot(&db)], chain_point(2))
+            .unwrap();
+        let database = &second.databases[0];
+        assert_eq!(database.parent, None);
+        assert_eq!((database.puts, database.deletions), (1, 0));
+        assert_eq!(restored_entries(&store, &second), entries(&db));
+
+        // The index is up to date again, so the next backup is incremental.
+        put(&db, b"c", b"3");
+        let third = store
+            .create_backup(&[forest_snapshot(&db)], chain_point(3))
+            .unwrap();
+        let database = &third.databases[0];
+        assert_eq!(database.parent, Some(second.id));
+        assert_eq!((database.puts, database.deletions), (1, 0));
+        assert_eq!(restored_entries(&store, &third), entries(&db));
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn corrupted_backup_is_not_restored() {
+        let path = backup_dir("corrupted");
+        let store = BackupStore::open(&path).unwrap();
+        let db = kvdb_memorydb::create(1);
+
+        put(&db, b"key", b"value");
+        let backup = store
+            .create_backup(&[forest_snapshot(&db)], chain_point(1))
+            .unwrap();
+        let name = &backup.databases[0].name;
+
+        let delta_path = path
+            .join(backup_dir_name(backup.id))
+            .join(format!("{}.delta", name));
+        let mut delta = fs::read(&delta_path).unwrap();
+        *delta.last_mut().unwrap() ^= 0xff;
+        fs::write(&delta_path, delta).unwrap();
+
+        let restored_db = kvdb_memorydb::create(1);
+        assert!(matches!(
+            store.restore(backup.id, name, &restored_db),
+            Err(BackupError::ChecksumMismatch { .. })
+        ));
+        assert!(entries(&restored_db).is_empty());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
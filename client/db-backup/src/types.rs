@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use sp_core::{hexdisplay::HexDisplay, H256};
+
+/// Version of the backup manifest format.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// The block at which a backup was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainPoint {
+    pub block_number: u32,
+    pub block_hash: H256,
+}
+
+/// The kind of a backed up database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DatabaseKind {
+    /// The File Storage, holding the metadata and chunks of the files stored by the node.
+    FileStorage,
+    /// A Forest Storage, identified by the key it has in the forest storage handler.
+    Forest { forest_key: Vec<u8> },
+}
+
+impl DatabaseKind {
+    /// A name identifying the database in a backup, which is also safe to use as a file name.
+    pub fn name(&self) -> String {
+        match self {
+            DatabaseKind::FileStorage => "file_storage".to_string(),
+            DatabaseKind::Forest { forest_key } => {
+                format!("forest_{}", HexDisplay::from(forest_key))
+            }
+        }
+    }
+}
+
+/// A database in a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseBackup {
+    pub name: String,
+    pub kind: DatabaseKind,
+    pub columns: u32,
+    /// The backup this one holds the changes from, or `None` if this is a full copy of the
+    /// database.
+    pub parent: Option<u64>,
+    /// The amount of entries in the database at the time of the backup.
+    pub entries: u64,
+    /// The amount of entries written to this backup.
+    pub puts: u64,
+    /// The amount of entries deleted since the parent backup.
+    pub deletions: u64,
+    /// Checksum of the entries in this backup, checked before restoring them.
+    pub checksum: H256,
+    /// The root of the forest, if this is a Forest Storage.
+    pub forest_root: Option<H256>,
+    /// The RocksDB sequence number of the database at the time of the backup, if known. The next
+    /// backup does not read the database again if it did not change.
+    #[serde(default)]
+    pub sequence_number: Option<u64>,
+}
+
+/// A backup of the client databases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub id: u64,
+    /// The backup this one is incremental to, or `None` if this is a full backup.
+    pub parent: Option<u64>,
+    /// UNIX timestamp (in seconds) of the creation of the backup.
+    pub created_at: u64,
+    /// The best block of the node when the backup was taken.
+    pub at: ChainPoint,
+    pub databases: Vec<DatabaseBackup>,
+}
+
+impl BackupEntry {
+    pub fn is_full(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    pub fn database(&self, name: &str) -> Option<&DatabaseBackup> {
+        self.databases.iter().find(|database| database.name == name)
+    }
+}
+
+/// The list of backups in a backup directory, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub version: u32,
+    pub backups: Vec<BackupEntry>,
+}
+
+impl Default for BackupManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            backups: Vec::new(),
+        }
+    }
+}
+
+/// A database restored from a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestoredDatabase {
+    pub name: String,
+    pub kind: DatabaseKind,
+    pub entries: u64,
+    /// The root of the restored forest, if this is a Forest Storage. It is checked to match the
+    /// one recorded when the backup was taken.
+    pub forest_root: Option<H256>,
+}
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use shc_actors_framework::actor::ActorHandle;
+
+use crate::handler::{DiskSpace, DiskWatchdogService};
+
+/// Commands that can be sent to the DiskWatchdogService actor.
+#[derive(Debug)]
+pub enum DiskWatchdogServiceCommand {
+    QueryDiskSpace {
+        callback: tokio::sync::oneshot::Sender<Option<DiskSpace>>,
+    },
+}
+
+/// Interface for interacting with the DiskWatchdogService actor.
+#[async_trait]
+pub trait DiskWatchdogServiceInterface {
+    /// Query the space of the disk as of the last check, if it was checked successfully.
+    async fn query_disk_space(&self) -> Option<DiskSpace>;
+}
+
+#[async_trait]
+impl DiskWatchdogServiceInterface for ActorHandle<DiskWatchdogService> {
+    async fn query_disk_space(&self) -> Option<DiskSpace> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = DiskWatchdogServiceCommand::QueryDiskSpace { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from DiskWatchdogService. Probably means DiskWatchdogService has crashed.")
+    }
+}
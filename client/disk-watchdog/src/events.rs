@@ -0,0 +1,33 @@
+use shc_actors_framework::event_bus::{EventBus, EventBusMessage, ProvidesEventBus};
+
+use crate::handler::DiskSpace;
+
+/// Disk space checked event.
+///
+/// This event is emitted every time the space of the disk the files are stored in is checked,
+/// whether it changed or not.
+#[derive(Debug, Clone)]
+pub struct DiskSpaceChecked {
+    pub disk_space: DiskSpace,
+}
+
+impl EventBusMessage for DiskSpaceChecked {}
+
+#[derive(Clone, Default)]
+pub struct DiskWatchdogServiceEventBusProvider {
+    disk_space_checked_event_bus: EventBus<DiskSpaceChecked>,
+}
+
+impl DiskWatchdogServiceEventBusProvider {
+    pub fn new() -> Self {
+        Self {
+            disk_space_checked_event_bus: EventBus::new(),
+        }
+    }
+}
+
+impl ProvidesEventBus<DiskSpaceChecked> for DiskWatchdogServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<DiskSpaceChecked> {
+        &self.disk_space_checked_event_bus
+    }
+}
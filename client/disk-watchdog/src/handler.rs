@@ -0,0 +1,190 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use futures::stream::{self, StreamExt};
+use log::{debug, error, info, warn};
+use shc_actors_framework::actor::{Actor, ActorEventLoop};
+use tokio::time::{interval, Duration};
+
+use crate::{
+    commands::DiskWatchdogServiceCommand,
+    events::{DiskSpaceChecked, DiskWatchdogServiceEventBusProvider},
+};
+
+const LOG_TARGET: &str = "disk-watchdog-service";
+
+/// Configuration of the [`DiskWatchdogService`].
+#[derive(Debug, Clone)]
+pub struct DiskWatchdogConfig {
+    /// Path in the disk whose space is checked.
+    pub storage_path: PathBuf,
+    /// Period between the checks of the disk space.
+    pub check_period: Duration,
+}
+
+/// Space of the disk the files are stored in (bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    /// Total space of the disk.
+    pub total_space: u64,
+    /// Space of the disk available to the node.
+    pub available_space: u64,
+}
+
+impl DiskSpace {
+    /// Reads the space of the disk `path` is in.
+    pub fn of_path(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            total_space: fs2::total_space(path)?,
+            available_space: fs2::available_space(path)?,
+        })
+    }
+}
+
+pub struct DiskWatchdogService {
+    config: DiskWatchdogConfig,
+    /// The event bus provider for the disk watchdog service.
+    /// Part of the actor framework, allows for emitting events.
+    event_bus_provider: DiskWatchdogServiceEventBusProvider,
+    /// The space of the disk as of the last successful check.
+    last_disk_space: Option<DiskSpace>,
+}
+
+impl Actor for DiskWatchdogService {
+    type Message = DiskWatchdogServiceCommand;
+    type EventLoop = DiskWatchdogServiceEventLoop;
+    type EventBusProvider = DiskWatchdogServiceEventBusProvider;
+
+    fn handle_message(
+        &mut self,
+        message: Self::Message,
+    ) -> impl std::future::Future<Output = ()> + Send {
+        async {
+            match message {
+                DiskWatchdogServiceCommand::QueryDiskSpace { callback } => {
+                    match callback.send(self.last_disk_space) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the disk space back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_event_bus_provider(&self) -> &Self::EventBusProvider {
+        &self.event_bus_provider
+    }
+}
+
+impl DiskWatchdogService {
+    /// Create a new [`DiskWatchdogService`].
+    pub fn new(config: DiskWatchdogConfig) -> Self {
+        Self {
+            config,
+            event_bus_provider: DiskWatchdogServiceEventBusProvider::new(),
+            last_disk_space: None,
+        }
+    }
+
+    /// Checks the space of the disk and emits a [`DiskSpaceChecked`] event with it.
+    fn check_disk_space(&mut self) {
+        let disk_space = match DiskSpace::of_path(&self.config.storage_path) {
+            Ok(disk_space) => disk_space,
+            Err(e) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to check the space of the disk at {:?}: {:?}",
+                    self.config.storage_path,
+                    e
+                );
+                return;
+            }
+        };
+
+        debug!(
+            target: LOG_TARGET,
+            "Disk at {:?} has {} of {} bytes available",
+            self.config.storage_path,
+            disk_space.available_space,
+            disk_space.total_space
+        );
+
+        if let Some(last_disk_space) = self.last_disk_space {
+            if last_disk_space.total_space != disk_space.total_space {
+                info!(
+                    target: LOG_TARGET,
+                    "💽 Disk at {:?} was resized from {} to {} bytes",
+                    self.config.storage_path,
+                    last_disk_space.total_space,
+                    disk_space.total_space
+                );
+            }
+        }
+
+        self.last_disk_space = Some(disk_space);
+        self.emit(DiskSpaceChecked { disk_space });
+    }
+}
+
+pub struct DiskWatchdogServiceEventLoop {
+    receiver: sc_utils::mpsc::TracingUnboundedReceiver<DiskWatchdogServiceCommand>,
+    actor: DiskWatchdogService,
+}
+
+enum MergedEventLoopMessage {
+    Command(DiskWatchdogServiceCommand),
+    Tick,
+}
+
+/// Since this actor checks the disk periodically, it needs to handle both periodic ticks and
+/// messages from other actors, hence the need for a custom `ActorEventLoop`.
+impl ActorEventLoop<DiskWatchdogService> for DiskWatchdogServiceEventLoop {
+    fn new(
+        actor: DiskWatchdogService,
+        receiver: sc_utils::mpsc::TracingUnboundedReceiver<DiskWatchdogServiceCommand>,
+    ) -> Self {
+        Self { actor, receiver }
+    }
+
+    async fn run(mut self) {
+        info!(
+            target: LOG_TARGET,
+            "💽 StorageHub's Disk Watchdog Service starting up, checking the disk at {:?} every {:?}!",
+            self.actor.config.storage_path,
+            self.actor.config.check_period
+        );
+
+        let ticker = interval(self.actor.config.check_period);
+        let ticker_stream = stream::unfold(ticker, |mut interval| {
+            Box::pin(async move {
+                interval.tick().await;
+                Some((MergedEventLoopMessage::Tick, interval))
+            })
+        });
+
+        let mut merged_stream = stream::select(
+            self.receiver.map(MergedEventLoopMessage::Command),
+            ticker_stream,
+        );
+
+        loop {
+            match merged_stream.next().await {
+                Some(MergedEventLoopMessage::Command(command)) => {
+                    self.actor.handle_message(command).await;
+                }
+                Some(MergedEventLoopMessage::Tick) => {
+                    self.actor.check_disk_space();
+                }
+                None => {
+                    warn!(target: LOG_TARGET, "DiskWatchdogService event loop terminated.");
+                    break;
+                }
+            }
+        }
+    }
+}
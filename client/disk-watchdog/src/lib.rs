@@ -0,0 +1,32 @@
+//! Monitoring of the free space of the disk the files of a provider are stored in.
+//!
+//! Operators resize the disks of their nodes, but the capacity declared on-chain does not follow.
+//! The [`DiskWatchdogService`] checks the total and available space of the storage path
+//! periodically and emits a [`DiskSpaceChecked`] event every time, for the tasks of the node to
+//! compare it with the on-chain capacity of the provider.
+//!
+//! [`DiskSpaceChecked`]: events::DiskSpaceChecked
+
+use shc_actors_framework::actor::{ActorHandle, ActorSpawner, TaskSpawner};
+
+pub use self::handler::{DiskSpace, DiskWatchdogConfig, DiskWatchdogService};
+
+/// For defining the commands processed by the disk watchdog service.
+pub mod commands;
+/// For defining the events emitted by the disk watchdog service.
+pub mod events;
+/// For checking the space of the disk periodically.
+pub mod handler;
+
+pub async fn spawn_disk_watchdog_service(
+    task_spawner: &TaskSpawner,
+    config: DiskWatchdogConfig,
+) -> ActorHandle<DiskWatchdogService> {
+    let task_spawner = task_spawner
+        .with_name("disk-watchdog-service")
+        .with_group("storage");
+
+    let disk_watchdog_service = DiskWatchdogService::new(config);
+
+    task_spawner.spawn_actor(disk_watchdog_service)
+}
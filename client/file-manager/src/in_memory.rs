@@ -6,8 +6,11 @@ use shc_common::types::{
     Chunk, ChunkId, ChunkWithId, FileKeyProof, FileMetadata, FileProof, HashT, HasherOutT, H_LENGTH,
 };
 
-use crate::traits::{
-    FileDataTrie, FileStorage, FileStorageError, FileStorageWriteError, FileStorageWriteOutcome,
+use crate::{
+    integrity::find_corrupt_chunks,
+    traits::{
+        FileDataTrie, FileStorage, FileStorageError, FileStorageWriteError, FileStorageWriteOutcome,
+    },
 };
 
 use codec::{Decode, Encode};
@@ -220,6 +223,31 @@ where
         Ok(())
     }
 
+    fn get_file_keys(&self) -> Result<Vec<HasherOutT<T>>, FileStorageError> {
+        Ok(self.metadata.keys().copied().collect())
+    }
+
+    fn find_corrupt_chunks(
+        &self,
+        file_key: &HasherOutT<T>,
+        chunk_ids: &[ChunkId],
+    ) -> Result<Vec<ChunkId>, FileStorageError> {
+        let metadata = self
+            .metadata
+            .get(file_key)
+            .ok_or(FileStorageError::FileDoesNotExist)?;
+        let file_data = self
+            .file_data
+            .get(file_key)
+            .ok_or(FileStorageError::FileDoesNotExist)?;
+
+        if metadata.fingerprint != file_data.get_root().as_ref().into() {
+            return Err(FileStorageError::IncompleteFile);
+        }
+
+        Ok(find_corrupt_chunks(file_data, metadata, chunk_ids))
+    }
+
     fn get_metadata(
         &self,
         file_key: &HasherOutT<T>,
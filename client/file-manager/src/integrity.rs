@@ -0,0 +1,75 @@
+use std::collections::BTreeSet;
+
+use log::debug;
+use shc_common::types::{ChunkId, FileMetadata, HasherOutT, H_LENGTH};
+use trie_db::TrieLayout;
+
+use crate::{traits::FileDataTrie, LOG_TARGET};
+
+/// Returns the chunks in `chunk_ids` of the complete file stored in `file_trie` that can't be
+/// proven against the fingerprint in its `metadata`, i.e. that are corrupted or missing.
+///
+/// The chunks are first proven all at once, and only one by one if that fails, to tell which of
+/// them are corrupted.
+pub(crate) fn find_corrupt_chunks<T, FDT>(
+    file_trie: &FDT,
+    metadata: &FileMetadata,
+    chunk_ids: &[ChunkId],
+) -> Vec<ChunkId>
+where
+    T: TrieLayout,
+    FDT: FileDataTrie<T>,
+    HasherOutT<T>: TryFrom<[u8; H_LENGTH]>,
+{
+    if let Some(proven) = prove_chunks(file_trie, metadata, chunk_ids) {
+        return chunk_ids
+            .iter()
+            .filter(|chunk_id| !proven.contains(chunk_id))
+            .copied()
+            .collect();
+    }
+
+    if chunk_ids.len() == 1 {
+        return chunk_ids.to_vec();
+    }
+
+    chunk_ids
+        .iter()
+        .filter(|chunk_id| {
+            prove_chunks(file_trie, metadata, &[**chunk_id])
+                .map_or(true, |proven| !proven.contains(chunk_id))
+        })
+        .copied()
+        .collect()
+}
+
+/// Generates a proof of `chunk_ids` from `file_trie` and verifies it against the fingerprint in
+/// `metadata`, returning the proven chunks. Returns `None` if the proof can't be generated or
+/// verified.
+fn prove_chunks<T, FDT>(
+    file_trie: &FDT,
+    metadata: &FileMetadata,
+    chunk_ids: &[ChunkId],
+) -> Option<BTreeSet<ChunkId>>
+where
+    T: TrieLayout,
+    FDT: FileDataTrie<T>,
+    HasherOutT<T>: TryFrom<[u8; H_LENGTH]>,
+{
+    let proof = file_trie
+        .generate_proof(&chunk_ids.to_vec())
+        .map_err(|e| {
+            debug!(target: LOG_TARGET, "Failed to generate proof of chunks {:?}: {:?}", chunk_ids, e);
+        })
+        .ok()?;
+
+    let proven = proof
+        .to_file_key_proof(metadata.clone())
+        .proven::<T>()
+        .map_err(|e| {
+            debug!(target: LOG_TARGET, "Failed to verify proof of chunks {:?}: {:?}", chunk_ids, e);
+        })
+        .ok()?;
+
+    Some(proven.into_iter().map(|leaf| leaf.key).collect())
+}
@@ -1,5 +1,6 @@
 mod error;
 pub mod in_memory;
+mod integrity;
 pub mod rocksdb;
 pub mod traits;
 
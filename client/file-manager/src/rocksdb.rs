@@ -12,6 +12,7 @@ use trie_db::{DBValue, Trie, TrieDBBuilder, TrieDBMutBuilder};
 
 use crate::{
     error::{other_io_error, ErrorT},
+    integrity,
     traits::{
         FileDataTrie, FileStorage, FileStorageError, FileStorageWriteError, FileStorageWriteOutcome,
     },
@@ -24,13 +25,19 @@ const ROOTS_COLUMN: u32 = 1;
 const CHUNKS_COLUMN: u32 = 2;
 const BUCKET_PREFIX_COLUMN: u32 = 3;
 
+/// Path of the File Storage database, relative to the storage path.
+pub const FILE_STORAGE_DB_PATH: &str = "storagehub/file_storage/";
+
+/// Number of columns of the File Storage database.
+pub const FILE_STORAGE_DB_COLUMNS: u32 = 4;
+
 /// Open the database on disk, creating it if it doesn't exist.
 fn open_or_creating_rocksdb(db_path: String) -> io::Result<kvdb_rocksdb::Database> {
     let mut path = PathBuf::new();
     path.push(db_path.as_str());
-    path.push("storagehub/file_storage/");
+    path.push(FILE_STORAGE_DB_PATH);
 
-    let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(4);
+    let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(FILE_STORAGE_DB_COLUMNS);
 
     let path_str = path
         .to_str()
@@ -660,6 +667,70 @@ where
         file_trie.stored_chunks_count()
     }
 
+    fn get_file_keys(&self) -> Result<Vec<HasherOutT<T>>, FileStorageError> {
+        let mut keys = Vec::new();
+
+        for entry in self.storage.db.iter(METADATA_COLUMN) {
+            let (key, _) = entry.map_err(|e| {
+                error!(target: LOG_TARGET, "{:?}", e);
+                FileStorageError::FailedToReadStorage
+            })?;
+
+            let key = convert_raw_bytes_to_hasher_out::<T>(key.to_vec()).map_err(|e| {
+                error!(target: LOG_TARGET, "{:?}", e);
+                FileStorageError::FailedToParseKey
+            })?;
+
+            keys.push(key);
+        }
+
+        Ok(keys)
+    }
+
+    fn find_corrupt_chunks(
+        &self,
+        key: &HasherOutT<T>,
+        chunk_ids: &[ChunkId],
+    ) -> Result<Vec<ChunkId>, FileStorageError> {
+        let metadata = self
+            .get_metadata(key)?
+            .ok_or(FileStorageError::FileDoesNotExist)?;
+
+        let final_root = convert_raw_bytes_to_hasher_out::<T>(
+            metadata.fingerprint.as_ref().to_vec(),
+        )
+        .map_err(|e| {
+            error!(target: LOG_TARGET, "{:?}", e);
+            FileStorageError::FailedToParseFingerprint
+        })?;
+
+        let raw_partial_root = self
+            .storage
+            .read(ROOTS_COLUMN, final_root.as_ref())
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "{:?}", e);
+                FileStorageError::FailedToReadStorage
+            })?
+            .ok_or(FileStorageError::IncompleteFile)?;
+
+        let partial_root = convert_raw_bytes_to_hasher_out::<T>(raw_partial_root).map_err(|e| {
+            error!(target: LOG_TARGET, "{:?}", e);
+            FileStorageError::FailedToParsePartialRoot
+        })?;
+
+        // The partial root only matches the fingerprint once all the chunks are written.
+        if partial_root != final_root {
+            return Err(FileStorageError::IncompleteFile);
+        }
+
+        let file_trie =
+            RocksDbFileDataTrie::<T, DB>::from_existing(self.storage.clone(), &final_root);
+
+        Ok(integrity::find_corrupt_chunks(
+            &file_trie, &metadata, chunk_ids,
+        ))
+    }
+
     fn get_metadata(&self, key: &HasherOutT<T>) -> Result<Option<FileMetadata>, FileStorageError> {
         let raw_metadata = self
             .storage
@@ -1352,4 +1423,89 @@ mod tests {
         assert!(file_storage.get_chunk(&key_2, &chunk_ids_2[0]).is_ok());
         assert!(file_storage.get_chunk(&key_3, &chunk_ids_3[0]).is_ok());
     }
+
+    #[test]
+    fn find_corrupt_chunks_works() {
+        let storage = StorageDb {
+            db: Arc::new(kvdb_memorydb::create(FILE_STORAGE_DB_COLUMNS)),
+            _marker: Default::default(),
+        };
+
+        // Chunks bigger than 32 bytes, stored in their own trie node.
+        let chunks = vec![
+            Chunk::from([5u8; 1024]),
+            Chunk::from([6u8; 1024]),
+            Chunk::from([7u8; 1024]),
+        ];
+        let chunk_ids: Vec<ChunkId> = (0..chunks.len() as u64).map(ChunkId::new).collect();
+
+        let mut file_trie =
+            RocksDbFileDataTrie::<LayoutV1<BlakeTwo256>, InMemory>::new(storage.clone());
+        for (chunk_id, chunk) in chunk_ids.iter().zip(chunks.iter()) {
+            file_trie.write_chunk(chunk_id, chunk).unwrap();
+        }
+
+        let file_metadata = FileMetadata {
+            file_size: 1024u64 * chunks.len() as u64,
+            fingerprint: file_trie.get_root().as_ref().into(),
+            owner: <AccountId32 as AsRef<[u8]>>::as_ref(&AccountId32::new([0u8; 32])).to_vec(),
+            location: "location".to_string().into_bytes(),
+            bucket_id: [1u8; 32].to_vec(),
+        };
+        let key = file_metadata.file_key::<BlakeTwo256>();
+
+        let mut file_storage =
+            RocksDbFileStorage::<LayoutV1<BlakeTwo256>, InMemory>::new(storage.clone());
+        file_storage
+            .insert_file_with_data(key, file_metadata, file_trie)
+            .unwrap();
+
+        assert_eq!(file_storage.get_file_keys().unwrap(), vec![key]);
+        assert!(file_storage
+            .find_corrupt_chunks(&key, &chunk_ids)
+            .unwrap()
+            .is_empty());
+
+        // Flip a bit of the second chunk on disk.
+        let (node_key, mut node) = storage
+            .db
+            .iter(CHUNKS_COLUMN)
+            .map(Result::unwrap)
+            .find(|(_, node)| node.ends_with(&chunks[1]))
+            .unwrap();
+        *node.last_mut().unwrap() ^= 1;
+        let mut transaction = DBTransaction::new();
+        transaction.put_vec(CHUNKS_COLUMN, &node_key, node);
+        storage.db.write(transaction).unwrap();
+
+        assert_eq!(
+            file_storage.find_corrupt_chunks(&key, &chunk_ids).unwrap(),
+            vec![chunk_ids[1]]
+        );
+    }
+
+    #[test]
+    fn find_corrupt_chunks_fails_for_incomplete_file() {
+        let storage = StorageDb {
+            db: Arc::new(kvdb_memorydb::create(FILE_STORAGE_DB_COLUMNS)),
+            _marker: Default::default(),
+        };
+
+        let file_metadata = FileMetadata {
+            file_size: 2048,
+            fingerprint: Fingerprint::from([1u8; 32]),
+            owner: <AccountId32 as AsRef<[u8]>>::as_ref(&AccountId32::new([0u8; 32])).to_vec(),
+            location: "location".to_string().into_bytes(),
+            bucket_id: [1u8; 32].to_vec(),
+        };
+        let key = file_metadata.file_key::<BlakeTwo256>();
+
+        let mut file_storage = RocksDbFileStorage::<LayoutV1<BlakeTwo256>, InMemory>::new(storage);
+        file_storage.insert_file(key, file_metadata).unwrap();
+
+        assert!(matches!(
+            file_storage.find_corrupt_chunks(&key, &[ChunkId::new(0)]),
+            Err(FileStorageError::IncompleteFile)
+        ));
+    }
 }
@@ -134,6 +134,20 @@ pub trait FileStorage<T: TrieLayout>: 'static {
 
     fn delete_files_with_prefix(&mut self, prefix: &[u8; 32]) -> Result<(), FileStorageError>;
 
+    /// Get the keys of all the files in storage, whether they are complete or not.
+    fn get_file_keys(&self) -> Result<Vec<HasherOutT<T>>, FileStorageError>;
+
+    /// Check the integrity of chunks of a complete file, by proving them against the fingerprint
+    /// of the file. Returns the chunks that could not be proven, i.e. that are corrupted or
+    /// missing from storage.
+    ///
+    /// Returns [`FileStorageError::IncompleteFile`] if the file is not complete.
+    fn find_corrupt_chunks(
+        &self,
+        key: &HasherOutT<T>,
+        chunk_ids: &[ChunkId],
+    ) -> Result<Vec<ChunkId>, FileStorageError>;
+
     /// Get metadata for a file.
     fn get_metadata(&self, key: &HasherOutT<T>) -> Result<Option<FileMetadata>, FileStorageError>;
 
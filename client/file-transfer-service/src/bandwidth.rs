@@ -0,0 +1,198 @@
+//! Throttling of the upstream bandwidth this node dedicates to serving files to other peers.
+//!
+//! The responses to download requests are rate limited with token buckets: a global one, shared by
+//! every peer, and one per peer, so that a single peer can't use up the whole global limit. Each
+//! bucket holds up to a second worth of bytes, which is the burst a peer can get after being idle.
+//!
+//! Responses are never dropped for exceeding the limits. The bytes of a response are taken from the
+//! buckets right away, which can leave them in debt, and the response is delayed until the debt is
+//! paid off at the rate of the limit. Since the tasks serving a file wait for the response of each
+//! chunk before reading the next one, this also slows down how fast files are read from storage.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use sc_network_types::PeerId;
+use serde::{Deserialize, Serialize};
+
+/// Upstream bandwidth limits of the file transfer service, in bytes per second. No limit is applied
+/// if not set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthLimits {
+    /// Limit of the bandwidth used to serve files to all peers together.
+    pub global: Option<u64>,
+    /// Limit of the bandwidth used to serve files to each peer.
+    pub per_peer: Option<u64>,
+}
+
+impl BandwidthLimits {
+    /// Whether any of the limits is zero, which would stop serving files altogether.
+    pub fn has_zero_limit(&self) -> bool {
+        self.global == Some(0) || self.per_peer == Some(0)
+    }
+}
+
+/// A token bucket refilled at `rate` bytes per second, holding up to a second worth of bytes.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: u64,
+    /// Available bytes, negative if the bucket is in debt.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket.
+    fn new(rate: u64, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: rate as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.rate as f64).min(self.rate as f64);
+        self.last_refill = now;
+    }
+
+    /// Takes `bytes` from the bucket, returning how long to wait until they are paid off.
+    fn reserve(&mut self, bytes: u64, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= bytes as f64;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate as f64)
+        }
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.rate as f64
+    }
+}
+
+/// Rate limiter of the responses to download requests, according to the [`BandwidthLimits`].
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    limits: BandwidthLimits,
+    global: Option<TokenBucket>,
+    peers: HashMap<PeerId, TokenBucket>,
+}
+
+impl BandwidthLimiter {
+    pub fn new(limits: BandwidthLimits) -> Self {
+        let now = Instant::now();
+        Self {
+            limits,
+            global: limits.global.map(|rate| TokenBucket::new(rate, now)),
+            peers: HashMap::new(),
+        }
+    }
+
+    /// The limits currently applied.
+    pub fn limits(&self) -> BandwidthLimits {
+        self.limits
+    }
+
+    /// Replaces the limits. The buckets start over full with the new limits.
+    pub fn set_limits(&mut self, limits: BandwidthLimits) {
+        *self = Self::new(limits);
+    }
+
+    /// Takes `bytes` sent to `peer_id` from the buckets, returning how long to delay sending them to
+    /// stay within the limits.
+    pub fn reserve(&mut self, peer_id: PeerId, bytes: u64, now: Instant) -> Duration {
+        let global_delay = self
+            .global
+            .as_mut()
+            .map_or(Duration::ZERO, |bucket| bucket.reserve(bytes, now));
+
+        let peer_delay = self.limits.per_peer.map_or(Duration::ZERO, |rate| {
+            self.peers
+                .entry(peer_id)
+                .or_insert_with(|| TokenBucket::new(rate, now))
+                .reserve(bytes, now)
+        });
+
+        global_delay.max(peer_delay)
+    }
+
+    /// Forgets the buckets of the peers that have been idle long enough for them to be full again,
+    /// as they would be created full anyway.
+    pub fn prune_idle_peers(&mut self, now: Instant) {
+        self.peers.retain(|_, bucket| !bucket.is_full(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_within_the_burst_are_not_delayed() {
+        let mut limiter = BandwidthLimiter::new(BandwidthLimits {
+            global: Some(1000),
+            per_peer: None,
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(PeerId::random(), 600, now), Duration::ZERO);
+        assert_eq!(limiter.reserve(PeerId::random(), 400, now), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve(PeerId::random(), 500, now),
+            Duration::from_millis(500)
+        );
+
+        // The debt is paid off over time.
+        let later = now + Duration::from_secs(2);
+        assert_eq!(
+            limiter.reserve(PeerId::random(), 500, later),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn per_peer_limit_only_delays_that_peer() {
+        let mut limiter = BandwidthLimiter::new(BandwidthLimits {
+            global: None,
+            per_peer: Some(100),
+        });
+        let (peer, other_peer) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(peer, 100, now), Duration::ZERO);
+        assert_eq!(limiter.reserve(peer, 50, now), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(other_peer, 100, now), Duration::ZERO);
+
+        // Only the bucket of the peer in debt is kept.
+        limiter.prune_idle_peers(now + Duration::from_millis(100));
+        assert_eq!(limiter.peers.len(), 2);
+        limiter.prune_idle_peers(now + Duration::from_secs(2));
+        assert!(limiter.peers.is_empty());
+    }
+
+    #[test]
+    fn the_biggest_delay_of_both_limits_applies() {
+        let mut limiter = BandwidthLimiter::new(BandwidthLimits {
+            global: Some(1000),
+            per_peer: Some(100),
+        });
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.reserve(PeerId::random(), 300, now),
+            Duration::from_secs(2)
+        );
+
+        limiter.set_limits(BandwidthLimits::default());
+        assert_eq!(limiter.reserve(PeerId::random(), 300, now), Duration::ZERO);
+    }
+}
@@ -0,0 +1,232 @@
+//! Negotiation and adaptive sizing of the chunks in which files are transferred between peers.
+//!
+//! Files are stored and proven in chunks of [`FILE_CHUNK_SIZE`], but sending each of them in its own
+//! request is slow for big files, specially between distant peers. Before uploading a file, the
+//! sender negotiates with the receiver the biggest transfer chunk size both of them support, within
+//! the bounds defined by the protocol. Each transfer chunk is a batch of consecutive file chunks,
+//! sent with a single proof, so on-chain chunk IDs are not affected (see
+//! [`FileMetadata::chunk_ids_in_transfer_chunk`](shc_common::types::FileMetadata)).
+//!
+//! The negotiated size is an upper bound: the sender adapts the size of each transfer chunk to the
+//! round-trip time and throughput measured with [`AdaptiveChunkSizer`].
+
+use std::time::Duration;
+
+use shc_common::types::FILE_CHUNK_SIZE;
+
+/// Smallest transfer chunk size allowed by the protocol: a single file chunk.
+pub const MIN_TRANSFER_CHUNK_SIZE: u64 = FILE_CHUNK_SIZE;
+
+/// Biggest transfer chunk size allowed by the protocol.
+pub const MAX_TRANSFER_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Default biggest transfer chunk size a node is willing to use.
+pub const DEFAULT_MAX_TRANSFER_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// Transfer chunk size used with peers that don't support chunk size negotiation.
+pub const LEGACY_TRANSFER_CHUNK_SIZE: u64 = FILE_CHUNK_SIZE;
+
+/// Minimum time the transfer of a chunk should take, so that the round-trip time of each request
+/// doesn't dominate the transfer.
+const TARGET_TRANSFER_CHUNK_DURATION: Duration = Duration::from_millis(500);
+
+/// How many times the lowest round-trip time measured the transfer of a chunk should take.
+const TARGET_TRANSFER_CHUNK_RTT_MULTIPLIER: u32 = 4;
+
+/// Weight of the latest measurement in the smoothed throughput.
+const THROUGHPUT_SMOOTHING_FACTOR: f64 = 0.25;
+
+/// Bounds of the transfer chunk sizes (in bytes) a node supports.
+///
+/// Both bounds are within the protocol bounds and multiples of [`FILE_CHUNK_SIZE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferChunkSizeBounds {
+    min: u64,
+    max: u64,
+}
+
+impl Default for TransferChunkSizeBounds {
+    fn default() -> Self {
+        Self::new(MIN_TRANSFER_CHUNK_SIZE, DEFAULT_MAX_TRANSFER_CHUNK_SIZE)
+    }
+}
+
+impl TransferChunkSizeBounds {
+    /// Creates the bounds, clamping them to the protocol bounds and rounding them down to a multiple
+    /// of [`FILE_CHUNK_SIZE`].
+    pub fn new(min: u64, max: u64) -> Self {
+        let min = align_down(min.clamp(MIN_TRANSFER_CHUNK_SIZE, MAX_TRANSFER_CHUNK_SIZE));
+        let max = align_down(max.clamp(min, MAX_TRANSFER_CHUNK_SIZE));
+
+        Self { min, max }
+    }
+
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Clamps `size` to these bounds, rounding it down to a multiple of [`FILE_CHUNK_SIZE`].
+    pub fn clamp(&self, size: u64) -> u64 {
+        align_down(size.clamp(self.min, self.max))
+    }
+
+    /// Agrees on a transfer chunk size with a peer supporting sizes between `peer_min` and
+    /// `peer_max`, as close as possible to the `preferred` size of the peer.
+    ///
+    /// Returns `None` if the sizes supported by both don't overlap.
+    pub fn negotiate(&self, peer_min: u64, peer_max: u64, preferred: u64) -> Option<u64> {
+        let min = align_up(self.min.max(peer_min));
+        let max = align_down(self.max.min(peer_max));
+
+        if min > max {
+            return None;
+        }
+
+        Some(align_down(preferred.clamp(min, max)))
+    }
+}
+
+/// Sender-side sizing of the transfer chunks of a file, adapted to the measured round-trip time and
+/// throughput of the transfer.
+///
+/// The size of each transfer chunk targets a transfer time of several times the round-trip time,
+/// so that the overhead of each request stays low, without sending chunks so big that a slow peer
+/// times out. It changes at most by a factor of two between chunks, to avoid oscillating.
+#[derive(Debug, Clone)]
+pub struct AdaptiveChunkSizer {
+    bounds: TransferChunkSizeBounds,
+    chunk_size: u64,
+    /// Lowest time a transfer chunk took, used as an estimate of the round-trip time.
+    min_rtt: Option<Duration>,
+    /// Smoothed throughput, in bytes per second.
+    throughput: Option<f64>,
+}
+
+impl AdaptiveChunkSizer {
+    /// Creates a sizer starting at `initial_chunk_size`, clamped to `bounds`.
+    pub fn new(bounds: TransferChunkSizeBounds, initial_chunk_size: u64) -> Self {
+        Self {
+            bounds,
+            chunk_size: bounds.clamp(initial_chunk_size),
+            min_rtt: None,
+            throughput: None,
+        }
+    }
+
+    /// The size of the next transfer chunk.
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// The smoothed throughput measured so far, in bytes per second.
+    pub fn throughput(&self) -> Option<f64> {
+        self.throughput
+    }
+
+    /// Records that a transfer chunk of `bytes` took `elapsed` to be sent and acknowledged, and
+    /// adapts the size of the next transfer chunks.
+    pub fn record_transfer(&mut self, bytes: u64, elapsed: Duration) {
+        let elapsed = elapsed.max(Duration::from_millis(1));
+
+        let min_rtt = self.min_rtt.map_or(elapsed, |min_rtt| min_rtt.min(elapsed));
+        self.min_rtt = Some(min_rtt);
+
+        let measured = bytes as f64 / elapsed.as_secs_f64();
+        let throughput = self.throughput.map_or(measured, |throughput| {
+            throughput + THROUGHPUT_SMOOTHING_FACTOR * (measured - throughput)
+        });
+        self.throughput = Some(throughput);
+
+        let target_duration =
+            TARGET_TRANSFER_CHUNK_DURATION.max(min_rtt * TARGET_TRANSFER_CHUNK_RTT_MULTIPLIER);
+        // Size for the lowest of the latest and the smoothed throughput, so that the chunk size
+        // drops as soon as the transfer slows down, but only grows once it is steadily faster.
+        let target_size = (throughput.min(measured) * target_duration.as_secs_f64()) as u64;
+
+        self.chunk_size = self
+            .bounds
+            .clamp(target_size.clamp(self.chunk_size / 2, self.chunk_size.saturating_mul(2)));
+    }
+
+    /// Records that the transfer of a chunk failed, halving the size of the next transfer chunks.
+    pub fn record_failure(&mut self) {
+        self.chunk_size = self.bounds.clamp(self.chunk_size / 2);
+    }
+}
+
+/// Rounds `size` down to a multiple of [`FILE_CHUNK_SIZE`], with a minimum of one file chunk.
+fn align_down(size: u64) -> u64 {
+    (size / FILE_CHUNK_SIZE).max(1) * FILE_CHUNK_SIZE
+}
+
+/// Rounds `size` up to a multiple of [`FILE_CHUNK_SIZE`].
+fn align_up(size: u64) -> u64 {
+    size.div_ceil(FILE_CHUNK_SIZE) * FILE_CHUNK_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_are_clamped_to_the_protocol_and_aligned() {
+        let bounds = TransferChunkSizeBounds::new(0, u64::MAX);
+        assert_eq!(bounds.min(), MIN_TRANSFER_CHUNK_SIZE);
+        assert_eq!(bounds.max(), MAX_TRANSFER_CHUNK_SIZE);
+
+        let bounds = TransferChunkSizeBounds::new(FILE_CHUNK_SIZE * 3 + 1, FILE_CHUNK_SIZE * 2);
+        assert_eq!(bounds.min(), FILE_CHUNK_SIZE * 3);
+        assert_eq!(bounds.max(), FILE_CHUNK_SIZE * 3);
+    }
+
+    #[test]
+    fn negotiate_picks_the_preferred_size_within_both_bounds() {
+        let bounds = TransferChunkSizeBounds::new(FILE_CHUNK_SIZE, FILE_CHUNK_SIZE * 64);
+
+        assert_eq!(
+            bounds.negotiate(FILE_CHUNK_SIZE, FILE_CHUNK_SIZE * 32, FILE_CHUNK_SIZE * 16),
+            Some(FILE_CHUNK_SIZE * 16)
+        );
+        assert_eq!(
+            bounds.negotiate(
+                FILE_CHUNK_SIZE,
+                MAX_TRANSFER_CHUNK_SIZE,
+                MAX_TRANSFER_CHUNK_SIZE
+            ),
+            Some(FILE_CHUNK_SIZE * 64)
+        );
+        assert_eq!(
+            bounds.negotiate(
+                FILE_CHUNK_SIZE * 128,
+                MAX_TRANSFER_CHUNK_SIZE,
+                FILE_CHUNK_SIZE
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn adaptive_chunk_sizer_grows_on_fast_transfers_and_shrinks_on_slow_ones() {
+        let bounds = TransferChunkSizeBounds::new(MIN_TRANSFER_CHUNK_SIZE, MAX_TRANSFER_CHUNK_SIZE);
+        let mut sizer = AdaptiveChunkSizer::new(bounds, FILE_CHUNK_SIZE * 64);
+
+        // Fast transfer: grows, but at most twice as big.
+        sizer.record_transfer(FILE_CHUNK_SIZE * 64, Duration::from_millis(10));
+        assert_eq!(sizer.chunk_size(), FILE_CHUNK_SIZE * 128);
+
+        // Slow transfers: shrinks, but at most by half each time.
+        for _ in 0..20 {
+            let chunk_size = sizer.chunk_size();
+            sizer.record_transfer(chunk_size, Duration::from_secs(10));
+            assert!(sizer.chunk_size() >= chunk_size / 2);
+        }
+        assert_eq!(sizer.chunk_size(), MIN_TRANSFER_CHUNK_SIZE);
+
+        sizer.record_failure();
+        assert_eq!(sizer.chunk_size(), MIN_TRANSFER_CHUNK_SIZE);
+    }
+}
@@ -4,13 +4,20 @@ use prost::Message;
 use thiserror::Error;
 
 use codec::Encode;
-use sc_network::{config::OutgoingResponse, Multiaddr, PeerId, ProtocolName, RequestFailure};
+use sc_network::{
+    config::OutgoingResponse, request_responses::OutboundFailure, Multiaddr, PeerId, ProtocolName,
+    RequestFailure,
+};
 use sc_tracing::tracing::error;
 
 use shc_actors_framework::actor::ActorHandle;
 use shc_common::types::{BucketId, ChunkId, DownloadRequestId, FileKey, FileKeyProof};
 
-use super::{schema, FileTransferService};
+use super::{
+    peer_score::PeerScoreError, schema, transfer_state::TransferStateError, BandwidthLimits,
+    FileTransferService, PeerScoreInfo, PeerSetDiagnostics, TransferChunkSizeBounds,
+    TransferOutcome, TransferProgress,
+};
 
 const LOG_TARGET: &str = "file-transfer-service";
 
@@ -53,6 +60,36 @@ pub enum FileTransferServiceCommand {
             futures::channel::oneshot::Receiver<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
         >,
     },
+    NegotiateChunkSize {
+        /// Peer ID to negotiate the transfer chunk size with, before uploading the file to it.
+        /// This Peer ID must be registered as a known address before the request can be made.
+        peer_id: PeerId,
+        /// File key of the file we are uploading.
+        file_key: FileKey,
+        /// Bucket ID is only required for Bucket operations.
+        /// See [`FileTransferServiceCommand::UploadRequest`].
+        bucket_id: Option<BucketId>,
+        /// Along with the response from the network, the transfer chunk size bounds of this node,
+        /// to check the agreed transfer chunk size against them.
+        callback: tokio::sync::oneshot::Sender<(
+            futures::channel::oneshot::Receiver<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+            TransferChunkSizeBounds,
+        )>,
+    },
+    NegotiateResumeOffset {
+        /// Peer ID to upload the file to, which might already have some of its chunks from a
+        /// previous, interrupted upload. This Peer ID must be registered as a known address before
+        /// the request can be made.
+        peer_id: PeerId,
+        /// File key of the file we are uploading.
+        file_key: FileKey,
+        /// Bucket ID is only required for Bucket operations.
+        /// See [`FileTransferServiceCommand::UploadRequest`].
+        bucket_id: Option<BucketId>,
+        callback: tokio::sync::oneshot::Sender<
+            futures::channel::oneshot::Receiver<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+        >,
+    },
     DownloadResponse {
         request_id: DownloadRequestId,
         file_key_proof: FileKeyProof,
@@ -82,6 +119,42 @@ pub enum FileTransferServiceCommand {
         grace_period_seconds: Option<u64>,
         callback: tokio::sync::oneshot::Sender<Result<(), RequestError>>,
     },
+    QueryPeerSet {
+        callback: tokio::sync::oneshot::Sender<PeerSetDiagnostics>,
+    },
+    RecordReceivedChunks {
+        /// Peer ID the chunks were uploaded by.
+        peer_id: PeerId,
+        /// File key of the file the chunks belong to.
+        file_key: FileKey,
+        /// Amount of chunks of the file.
+        chunks_count: u64,
+        /// Chunks written to the file storage of this node.
+        chunk_ids: Vec<ChunkId>,
+        callback: tokio::sync::oneshot::Sender<Result<(), RequestError>>,
+    },
+    QueryTransfers {
+        callback: tokio::sync::oneshot::Sender<Result<Vec<TransferProgress>, RequestError>>,
+    },
+    SetBandwidthLimit {
+        /// New limits of the bandwidth used to serve files to other peers.
+        limits: BandwidthLimits,
+        callback: tokio::sync::oneshot::Sender<Result<(), RequestError>>,
+    },
+    ReportPeer {
+        /// Peer ID of the peer the transfer was with.
+        peer_id: PeerId,
+        /// Outcome of the transfer, raising or lowering the score of the peer.
+        outcome: TransferOutcome,
+    },
+    QueryPeerScores {
+        callback: tokio::sync::oneshot::Sender<Vec<PeerScoreInfo>>,
+    },
+    ClearPeerScores {
+        /// Peer ID of the peer whose score to clear, or every peer if not set.
+        peer_id: Option<PeerId>,
+        callback: tokio::sync::oneshot::Sender<Result<(), RequestError>>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -113,6 +186,18 @@ pub enum RequestError {
     /// Bucket not registered for peer
     #[error("Bucket not registered for peer")]
     BucketNotRegisteredForPeer,
+    /// The transfer chunk sizes supported by both peers don't overlap.
+    #[error("No transfer chunk size supported by both peers")]
+    NoCommonChunkSize,
+    /// Failed to read or write the persisted state of the uploads to this node.
+    #[error("Failed to access the transfer state: {0}")]
+    TransferState(#[from] TransferStateError),
+    /// A bandwidth limit of zero would stop serving files altogether.
+    #[error("Bandwidth limits must be greater than zero")]
+    ZeroBandwidthLimit,
+    /// Failed to read or write the persisted scores of the peers.
+    #[error("Failed to access the peer scores: {0}")]
+    PeerScore(#[from] PeerScoreError),
 }
 
 /// Allows our ActorHandle to implement
@@ -141,6 +226,20 @@ pub trait FileTransferServiceInterface {
         request_id: DownloadRequestId,
     ) -> Result<schema::v1::provider::RemoteDownloadDataResponse, RequestError>;
 
+    async fn negotiate_chunk_size(
+        &self,
+        peer_id: PeerId,
+        file_key: FileKey,
+        bucket_id: Option<BucketId>,
+    ) -> Result<u64, RequestError>;
+
+    async fn negotiate_resume_offset(
+        &self,
+        peer_id: PeerId,
+        file_key: FileKey,
+        bucket_id: Option<BucketId>,
+    ) -> Result<u64, RequestError>;
+
     async fn add_known_address(
         &self,
         peer_id: PeerId,
@@ -173,6 +272,26 @@ pub trait FileTransferServiceInterface {
         &mut self,
         multiaddresses: Vec<Multiaddr>,
     ) -> Vec<PeerId>;
+
+    async fn query_peer_set(&self) -> PeerSetDiagnostics;
+
+    async fn record_received_chunks(
+        &self,
+        peer_id: PeerId,
+        file_key: FileKey,
+        chunks_count: u64,
+        chunk_ids: Vec<ChunkId>,
+    ) -> Result<(), RequestError>;
+
+    async fn query_transfers(&self) -> Result<Vec<TransferProgress>, RequestError>;
+
+    async fn set_bandwidth_limit(&self, limits: BandwidthLimits) -> Result<(), RequestError>;
+
+    async fn report_peer(&self, peer_id: PeerId, outcome: TransferOutcome);
+
+    async fn query_peer_scores(&self) -> Vec<PeerScoreInfo>;
+
+    async fn clear_peer_scores(&self, peer_id: Option<PeerId>) -> Result<(), RequestError>;
 }
 
 #[async_trait]
@@ -205,7 +324,7 @@ impl FileTransferServiceInterface for ActorHandle<FileTransferService> {
             "Failed to receive response from the NetworkService. Probably means the NetworkService has crashed.",
         );
 
-        match response {
+        let result = match response {
             Ok((data, _protocol_name)) => {
                 let response = schema::v1::provider::Response::decode(&data[..]);
                 match response {
@@ -221,7 +340,10 @@ impl FileTransferServiceInterface for ActorHandle<FileTransferService> {
                 }
             }
             Err(error) => Err(RequestError::RequestFailure(error)),
-        }
+        };
+
+        self.report_peer(peer_id, request_outcome(&result)).await;
+        result
     }
 
     /// Request a download of a file chunk to a peer.
@@ -252,7 +374,7 @@ impl FileTransferServiceInterface for ActorHandle<FileTransferService> {
             "Failed to receive response from the NetworkService. Probably means the NetworkService has crashed.",
         );
 
-        match response {
+        let result = match response {
             Ok((data, _protocol_name)) => {
                 let response = schema::v1::provider::Response::decode(&data[..]);
                 match response {
@@ -268,7 +390,78 @@ impl FileTransferServiceInterface for ActorHandle<FileTransferService> {
                 }
             }
             Err(error) => Err(RequestError::RequestFailure(error)),
-        }
+        };
+
+        self.report_peer(peer_id, request_outcome(&result)).await;
+        result
+    }
+
+    /// Negotiate with a peer the size of the chunks in which a file is uploaded to it, within the
+    /// transfer chunk size bounds of both peers.
+    /// This returns after receiving a response from the network, with the agreed transfer chunk
+    /// size in bytes.
+    async fn negotiate_chunk_size(
+        &self,
+        peer_id: PeerId,
+        file_key: FileKey,
+        bucket_id: Option<BucketId>,
+    ) -> Result<u64, RequestError> {
+        let (callback, file_transfer_rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::NegotiateChunkSize {
+            peer_id,
+            file_key,
+            bucket_id,
+            callback,
+        };
+        self.send(command).await;
+
+        // First we wait for the response from the FileTransferService.
+        // The response is another oneshot channel to wait for the response from the network.
+        let (network_rx, bounds) = file_transfer_rx.await.expect("Failed to receive response from FileTransferService. Probably means FileTransferService has crashed.");
+
+        // Now we wait on the actual response from the network.
+        let response = network_rx.await.expect(
+            "Failed to receive response from the NetworkService. Probably means the NetworkService has crashed.",
+        );
+
+        let result = decode_chunk_size_response(response, bounds);
+
+        self.report_peer(peer_id, request_outcome(&result)).await;
+        result
+    }
+
+    /// Ask a peer from which chunk to upload a file to it, in case it already has some of its chunks
+    /// from a previous, interrupted upload.
+    /// This returns after receiving a response from the network, with the ID of the first chunk
+    /// the peer is missing.
+    async fn negotiate_resume_offset(
+        &self,
+        peer_id: PeerId,
+        file_key: FileKey,
+        bucket_id: Option<BucketId>,
+    ) -> Result<u64, RequestError> {
+        let (callback, file_transfer_rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::NegotiateResumeOffset {
+            peer_id,
+            file_key,
+            bucket_id,
+            callback,
+        };
+        self.send(command).await;
+
+        // First we wait for the response from the FileTransferService.
+        // The response is another oneshot channel to wait for the response from the network.
+        let network_rx = file_transfer_rx.await.expect("Failed to receive response from FileTransferService. Probably means FileTransferService has crashed.");
+
+        // Now we wait on the actual response from the network.
+        let response = network_rx.await.expect(
+            "Failed to receive response from the NetworkService. Probably means the NetworkService has crashed.",
+        );
+
+        let result = decode_resume_offset_response(response);
+
+        self.report_peer(peer_id, request_outcome(&result)).await;
+        result
     }
 
     /// Respond to a download request of a file chunk with a [`FileKeyProof`].
@@ -419,4 +612,135 @@ impl FileTransferServiceInterface for ActorHandle<FileTransferService> {
         }
         peer_ids
     }
+
+    /// Query the desired peer set of this node, compared to the peers it is connected to.
+    /// This returns after the message has been processed by the service.
+    async fn query_peer_set(&self) -> PeerSetDiagnostics {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::QueryPeerSet { callback };
+        self.send(command).await;
+        rx.await.expect("Failed to query the peer set")
+    }
+
+    /// Tell the FileTransferService that chunks of a file uploaded by [`peer_id`] were written to
+    /// the file storage, so that the upload resumes after them if it is interrupted.
+    /// This returns after the message has been processed by the service.
+    async fn record_received_chunks(
+        &self,
+        peer_id: PeerId,
+        file_key: FileKey,
+        chunks_count: u64,
+        chunk_ids: Vec<ChunkId>,
+    ) -> Result<(), RequestError> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::RecordReceivedChunks {
+            peer_id,
+            file_key,
+            chunks_count,
+            chunk_ids,
+            callback,
+        };
+        self.send(command).await;
+        rx.await.expect("Failed to record received chunks")
+    }
+
+    /// Query the uploads to this node in progress, with how many of their chunks were received.
+    /// This returns after the message has been processed by the service.
+    async fn query_transfers(&self) -> Result<Vec<TransferProgress>, RequestError> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::QueryTransfers { callback };
+        self.send(command).await;
+        rx.await.expect("Failed to query the transfers")
+    }
+
+    /// Replace the limits of the bandwidth used to serve files to other peers.
+    /// This returns after the message has been processed by the service.
+    async fn set_bandwidth_limit(&self, limits: BandwidthLimits) -> Result<(), RequestError> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::SetBandwidthLimit { limits, callback };
+        self.send(command).await;
+        rx.await.expect("Failed to set the bandwidth limit")
+    }
+
+    /// Tell the FileTransferService the outcome of a transfer with [`peer_id`], e.g. that it sent
+    /// chunks that don't match their file, raising or lowering its score.
+    /// The outcome of the requests sent through this interface is already reported.
+    /// This returns as soon as the message has been dispatched (not processed) to the service.
+    async fn report_peer(&self, peer_id: PeerId, outcome: TransferOutcome) {
+        let command = FileTransferServiceCommand::ReportPeer { peer_id, outcome };
+        self.send(command).await;
+    }
+
+    /// Query the score of every peer this node transferred files with, and until when they are
+    /// banned, if they are.
+    /// This returns after the message has been processed by the service.
+    async fn query_peer_scores(&self) -> Vec<PeerScoreInfo> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::QueryPeerScores { callback };
+        self.send(command).await;
+        rx.await.expect("Failed to query the peer scores")
+    }
+
+    /// Forget the score, lifting the ban, of [`peer_id`], or of every peer if not set.
+    /// This returns after the message has been processed by the service.
+    async fn clear_peer_scores(&self, peer_id: Option<PeerId>) -> Result<(), RequestError> {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let command = FileTransferServiceCommand::ClearPeerScores { peer_id, callback };
+        self.send(command).await;
+        rx.await.expect("Failed to clear the peer scores")
+    }
+}
+
+/// The outcome of a request to a peer, as recorded in its score.
+fn request_outcome<T>(result: &Result<T, RequestError>) -> TransferOutcome {
+    match result {
+        // Not supporting the same transfer chunk sizes is not a misbehaviour.
+        Ok(_) | Err(RequestError::NoCommonChunkSize) => TransferOutcome::Success,
+        Err(RequestError::RequestFailure(RequestFailure::Network(OutboundFailure::Timeout))) => {
+            TransferOutcome::Timeout
+        }
+        Err(_) => TransferOutcome::Failure,
+    }
+}
+
+/// Decodes the response to a transfer chunk size negotiation into the agreed transfer chunk size.
+fn decode_chunk_size_response(
+    response: Result<(Vec<u8>, ProtocolName), RequestFailure>,
+    bounds: TransferChunkSizeBounds,
+) -> Result<u64, RequestError> {
+    let (data, _protocol_name) = response?;
+    let chunk_size = match schema::v1::provider::Response::decode(&data[..]) {
+        Ok(response) => match response.response {
+            Some(schema::v1::provider::response::Response::NegotiateChunkSizeResponse(
+                response,
+            )) => response.chunk_size,
+            _ => return Err(RequestError::UnexpectedResponse),
+        },
+        Err(error) => return Err(RequestError::DecodeError(error)),
+    };
+
+    // The peer must agree on a transfer chunk size that this node supports.
+    match chunk_size {
+        0 => Err(RequestError::NoCommonChunkSize),
+        chunk_size if bounds.clamp(chunk_size) != chunk_size => {
+            Err(RequestError::UnexpectedResponse)
+        }
+        chunk_size => Ok(chunk_size),
+    }
+}
+
+/// Decodes the response to a resume offset negotiation into the first chunk the peer is missing.
+fn decode_resume_offset_response(
+    response: Result<(Vec<u8>, ProtocolName), RequestFailure>,
+) -> Result<u64, RequestError> {
+    let (data, _protocol_name) = response?;
+    match schema::v1::provider::Response::decode(&data[..]) {
+        Ok(response) => match response.response {
+            Some(schema::v1::provider::response::Response::ResumeTransferResponse(response)) => {
+                Ok(response.next_chunk_id)
+            }
+            _ => Err(RequestError::UnexpectedResponse),
+        },
+        Err(error) => Err(RequestError::DecodeError(error)),
+    }
 }
@@ -24,16 +24,20 @@
 
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
+    str::FromStr,
     sync::Arc,
+    time::Instant,
 };
 
 use codec::{Decode, Encode};
 use futures::stream::{self, StreamExt};
 use prost::Message;
 use sc_network::{
+    config::MultiaddrWithPeerId,
     request_responses::{IncomingRequest, OutgoingResponse},
     service::traits::NetworkService,
-    IfDisconnected, NetworkPeers, NetworkRequest, ProtocolName, ReputationChange,
+    IfDisconnected, NetworkPeers, NetworkRequest, NetworkStatusProvider, ProtocolName,
+    ReputationChange,
 };
 use sc_network_types::PeerId;
 use sc_tracing::tracing::{debug, error, info, warn};
@@ -45,13 +49,27 @@ use tokio::time::{interval, Duration};
 use crate::events::RemoteUploadRequest;
 
 use super::{
+    bandwidth::{BandwidthLimiter, BandwidthLimits},
+    chunk_size::TransferChunkSizeBounds,
     commands::{FileTransferServiceCommand, RequestError},
     events::{FileTransferServiceEventBusProvider, RemoteDownloadRequest},
+    peer_score::{self, PeerScoreStore, PeerScores, PeerScoringConfig, TransferOutcome},
+    peer_set::{PeerSetManager, DEFAULT_MAX_MANAGED_PEERS},
     schema,
+    transfer_state::{TransferStateStore, TRANSFER_STATE_TTL},
 };
 
 const LOG_TARGET: &str = "file-transfer-service";
 
+/// Period between updates of the reserved peers according to the desired peer set.
+const PEER_SET_UPDATE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Period between prunings of the state of the stalled uploads to this node.
+const TRANSFER_STATE_PRUNE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Period between persistences of the scores of the peers that changed.
+const PEER_SCORES_FLUSH_PERIOD: Duration = Duration::from_secs(60);
+
 #[derive(Eq)]
 pub struct BucketIdWithExpiration {
     bucket_id: BucketId,
@@ -108,10 +126,27 @@ pub struct FileTransferService {
     /// The event bus provider for the file transfer service.
     /// Part of the actor framework, allows for emitting events.
     event_bus_provider: FileTransferServiceEventBusProvider,
-    /// Mapping from RequestId to a download pending response channel
+    /// Mapping from RequestId to the requesting peer and its download pending response channel
     download_pending_responses:
-        HashMap<DownloadRequestId, futures::channel::oneshot::Sender<OutgoingResponse>>,
+        HashMap<DownloadRequestId, (PeerId, futures::channel::oneshot::Sender<OutgoingResponse>)>,
     download_pending_response_nonce: DownloadRequestId,
+    /// Bounds of the transfer chunk sizes this node supports, both when sending and receiving files.
+    chunk_size_bounds: TransferChunkSizeBounds,
+    /// Desired peer set of this node, kept connected through the reserved peers of the network.
+    peer_set: PeerSetManager,
+    /// Last time the reserved peers were updated according to the desired peer set.
+    last_peer_set_update: Instant,
+    /// Persisted state of the uploads to this node, to resume them if they are interrupted.
+    transfer_states: TransferStateStore,
+    /// Last time the state of the stalled uploads was pruned, if it was since the node started.
+    last_transfer_state_prune: Option<Instant>,
+    /// Rate limiter of the responses to download requests, i.e. of the bandwidth used to serve
+    /// files to other peers.
+    bandwidth_limiter: BandwidthLimiter,
+    /// Scores of the peers by their transfers, banning the misbehaving ones.
+    peer_scores: PeerScores,
+    /// Last time the scores of the peers were persisted.
+    last_peer_scores_flush: Instant,
 }
 
 impl Actor for FileTransferService {
@@ -144,6 +179,9 @@ impl Actor for FileTransferService {
                     let mut request_data = Vec::new();
                     request.encode(&mut request_data);
 
+                    self.peer_set
+                        .record_transfer(peer_id.into(), Instant::now());
+
                     let (tx, rx) = futures::channel::oneshot::channel();
                     self.network.start_request(
                         peer_id.into(),
@@ -181,6 +219,88 @@ impl Actor for FileTransferService {
                     let mut request_data = Vec::new();
                     request.encode(&mut request_data);
 
+                    self.peer_set
+                        .record_transfer(peer_id.into(), Instant::now());
+
+                    let (tx, rx) = futures::channel::oneshot::channel();
+                    self.network.start_request(
+                        peer_id.into(),
+                        self.protocol_name.clone(),
+                        request_data,
+                        None,
+                        tx,
+                        IfDisconnected::ImmediateError,
+                    );
+
+                    match callback.send(rx) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::NegotiateChunkSize {
+                    peer_id,
+                    file_key,
+                    bucket_id,
+                    callback,
+                } => {
+                    let request = schema::v1::provider::request::Request::NegotiateChunkSizeRequest(
+                        schema::v1::provider::NegotiateChunkSizeRequest {
+                            file_key: file_key.encode(),
+                            preferred_chunk_size: self.chunk_size_bounds.max(),
+                            min_chunk_size: self.chunk_size_bounds.min(),
+                            max_chunk_size: self.chunk_size_bounds.max(),
+                            bucket_id: bucket_id.map(|id| id.encode()),
+                        },
+                    );
+
+                    // Serialize the request
+                    let mut request_data = Vec::new();
+                    request.encode(&mut request_data);
+
+                    self.peer_set
+                        .record_transfer(peer_id.into(), Instant::now());
+
+                    let (tx, rx) = futures::channel::oneshot::channel();
+                    self.network.start_request(
+                        peer_id.into(),
+                        self.protocol_name.clone(),
+                        request_data,
+                        None,
+                        tx,
+                        IfDisconnected::ImmediateError,
+                    );
+
+                    match callback.send((rx, self.chunk_size_bounds)) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::NegotiateResumeOffset {
+                    peer_id,
+                    file_key,
+                    bucket_id,
+                    callback,
+                } => {
+                    let request = schema::v1::provider::request::Request::ResumeTransferRequest(
+                        schema::v1::provider::ResumeTransferRequest {
+                            file_key: file_key.encode(),
+                            bucket_id: bucket_id.map(|id| id.encode()),
+                        },
+                    );
+
+                    // Serialize the request
+                    let mut request_data = Vec::new();
+                    request.encode(&mut request_data);
+
+                    self.peer_set
+                        .record_transfer(peer_id.into(), Instant::now());
+
                     let (tx, rx) = futures::channel::oneshot::channel();
                     self.network.start_request(
                         peer_id.into(),
@@ -214,6 +334,7 @@ impl Actor for FileTransferService {
 
                     let mut response_data = Vec::new();
                     response.encode(&mut response_data);
+                    let bytes = response_data.len() as u64;
 
                     let outgoing_response = OutgoingResponse {
                         sent_feedback: None,
@@ -222,42 +343,42 @@ impl Actor for FileTransferService {
                     };
 
                     // Tries to find the sender half of the response channel
-                    let maybe_pending_response =
-                        self.download_pending_responses.remove(&request_id).take();
-
-                    // Tries to send back the download response and then gets the request callback result.
-                    let request_callback_result = match maybe_pending_response {
-                        Some(pending_response_sender) => {
-                            // Tries to send download response back
-                            let pending_response_result =
-                                pending_response_sender.send(outgoing_response);
-
-                            // Checks if response was sent back
-                            match pending_response_result {
-                                Ok(()) => callback.send(Ok(())),
-                                Err(e) => {
-                                    error!(
-                                        target: LOG_TARGET,
-                                        "Failed to return Download Response {:?}", e
-                                    );
-                                    callback.send(Err(RequestError::DownloadResponseFailure(e)))
-                                }
-                            }
-                        }
-                        None => {
-                            error!(target: LOG_TARGET, "No pending response channel found for request id {:?}", request_id);
-                            callback.send(Err(RequestError::DownloadRequestIdNotFound))
+                    let Some((peer, pending_response_sender)) =
+                        self.download_pending_responses.remove(&request_id)
+                    else {
+                        error!(target: LOG_TARGET, "No pending response channel found for request id {:?}", request_id);
+                        if callback
+                            .send(Err(RequestError::DownloadRequestIdNotFound))
+                            .is_err()
+                        {
+                            error!(
+                                target: LOG_TARGET,
+                                "Failed to send the response back. Looks like the requester task is gone."
+                            );
                         }
+                        return;
                     };
 
-                    // Checks that request callback was returned correctly.
-                    match request_callback_result {
-                        Ok(()) => {}
-                        Err(_) => error!(
-                            target: LOG_TARGET,
-                            "Failed to send the response back. Looks like the requester task is gone."
-                        ),
-                    };
+                    // Delays the response as long as needed to stay within the bandwidth limits.
+                    let delay = self.bandwidth_limiter.reserve(peer, bytes, Instant::now());
+                    shc_telemetry::record_served_bytes(bytes, delay);
+
+                    if delay.is_zero() {
+                        send_download_response(
+                            pending_response_sender,
+                            outgoing_response,
+                            callback,
+                        );
+                    } else {
+                        tokio::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            send_download_response(
+                                pending_response_sender,
+                                outgoing_response,
+                                callback,
+                            );
+                        });
+                    }
                 }
 
                 FileTransferServiceCommand::AddKnownAddress {
@@ -265,6 +386,8 @@ impl Actor for FileTransferService {
                     multiaddress,
                     callback,
                 } => {
+                    self.peer_set
+                        .record_address(peer_id.into(), multiaddress.clone());
                     self.network.add_known_address(peer_id.into(), multiaddress);
                     // `add_known_address()` method doesn't return anything.
                     match callback.send(Ok(())) {
@@ -305,6 +428,12 @@ impl Actor for FileTransferService {
                                 self.peer_file_allow_list.remove(&(*peer_id, file_key));
                             }
                             self.peers_by_file.remove(&file_key);
+
+                            // The upload either completed or was abandoned, so there is nothing
+                            // left to resume.
+                            if let Err(e) = self.transfer_states.remove_file(&file_key) {
+                                warn!(target: LOG_TARGET, "Failed to remove the transfer state of file {:?}: {}", file_key, e);
+                            }
                             Ok(())
                         }
                         None => Err(RequestError::FileNotRegistered),
@@ -355,6 +484,114 @@ impl Actor for FileTransferService {
                         None => self.unregister_bucket(bucket_id),
                     };
 
+                    match callback.send(result) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::QueryPeerSet { callback } => {
+                    let connected_peers = match self.network.network_state().await {
+                        Ok(state) => state
+                            .connected_peers
+                            .keys()
+                            .filter_map(|peer_id| PeerId::from_str(peer_id).ok())
+                            .collect(),
+                        Err(()) => {
+                            warn!(target: LOG_TARGET, "Failed to get the network state, reporting no connected peers");
+                            HashSet::new()
+                        }
+                    };
+
+                    match callback.send(self.peer_set.diagnostics(&connected_peers)) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::RecordReceivedChunks {
+                    peer_id,
+                    file_key,
+                    chunks_count,
+                    chunk_ids,
+                    callback,
+                } => {
+                    // Chunks of files no longer registered, i.e. whose upload just completed, are
+                    // not recorded, as there is nothing left to resume.
+                    let result = if self.peer_file_allow_list.contains(&(peer_id, file_key)) {
+                        self.record_outcome(peer_id, TransferOutcome::Success);
+                        self.transfer_states
+                            .record_received_chunks(&peer_id, &file_key, chunks_count, &chunk_ids)
+                            .map(|_| ())
+                            .map_err(Into::into)
+                    } else {
+                        Ok(())
+                    };
+
+                    match callback.send(result) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::QueryTransfers { callback } => {
+                    let result = self.transfer_states.transfers().map_err(Into::into);
+
+                    match callback.send(result) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::SetBandwidthLimit { limits, callback } => {
+                    let result = if limits.has_zero_limit() {
+                        Err(RequestError::ZeroBandwidthLimit)
+                    } else {
+                        info!(target: LOG_TARGET, "Bandwidth limits set to {:?}", limits);
+                        self.bandwidth_limiter.set_limits(limits);
+                        Ok(())
+                    };
+
+                    match callback.send(result) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::ReportPeer { peer_id, outcome } => {
+                    self.record_outcome(peer_id, outcome);
+                }
+                FileTransferServiceCommand::QueryPeerScores { callback } => {
+                    match callback.send(self.peer_scores.scores()) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the response back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+                FileTransferServiceCommand::ClearPeerScores { peer_id, callback } => {
+                    let result = self.peer_scores.clear(peer_id).map_err(Into::into);
+                    match (&result, peer_id) {
+                        (Ok(()), Some(peer_id)) => {
+                            info!(target: LOG_TARGET, "Cleared the score of peer {}", peer_id)
+                        }
+                        (Ok(()), None) => {
+                            info!(target: LOG_TARGET, "Cleared the scores of every peer")
+                        }
+                        (Err(_), _) => {}
+                    }
+
                     match callback.send(result) {
                         Ok(()) => {}
                         Err(_) => error!(
@@ -434,6 +671,16 @@ impl ActorEventLoop<FileTransferService> for FileTransferServiceEventLoop {
                 Some(MergedEventLoopMessage::Tick) => {
                     // Handle expired buckets
                     self.actor.handle_expired_buckets();
+                    // Keep the desired peers connected
+                    self.actor.update_peer_set();
+                    // Forget the uploads that stalled
+                    self.actor.prune_transfer_states();
+                    // Persist the scores of the peers
+                    self.actor.flush_peer_scores();
+                    // Forget the bandwidth used by the peers that are no longer downloading
+                    self.actor
+                        .bandwidth_limiter
+                        .prune_idle_peers(Instant::now());
                 }
                 None => {
                     warn!(target: LOG_TARGET, "FileTransferService event loop terminated.");
@@ -450,6 +697,11 @@ impl FileTransferService {
         protocol_name: ProtocolName,
         request_receiver: async_channel::Receiver<IncomingRequest>,
         network: Arc<dyn NetworkService>,
+        chunk_size_bounds: TransferChunkSizeBounds,
+        transfer_states: TransferStateStore,
+        bandwidth_limits: BandwidthLimits,
+        peer_scores: PeerScoreStore,
+        peer_scoring_config: PeerScoringConfig,
     ) -> Self {
         Self {
             protocol_name,
@@ -463,6 +715,14 @@ impl FileTransferService {
             event_bus_provider: FileTransferServiceEventBusProvider::new(),
             download_pending_responses: HashMap::new(),
             download_pending_response_nonce: DownloadRequestId::new(0),
+            chunk_size_bounds,
+            peer_set: PeerSetManager::new(DEFAULT_MAX_MANAGED_PEERS),
+            last_peer_set_update: Instant::now(),
+            transfer_states,
+            last_transfer_state_prune: None,
+            bandwidth_limiter: BandwidthLimiter::new(bandwidth_limits),
+            peer_scores: PeerScores::new(peer_scoring_config, peer_scores),
+            last_peer_scores_flush: Instant::now(),
         }
     }
 
@@ -472,6 +732,32 @@ impl FileTransferService {
         payload: Vec<u8>,
         pending_response: futures::channel::oneshot::Sender<OutgoingResponse>,
     ) {
+        // Requests of banned peers are refused without even being decoded.
+        if self.peer_scores.is_banned(&peer, peer_score::now()) {
+            debug!(target: LOG_TARGET, "Refusing request from banned peer {}", peer);
+
+            if pending_response
+                .send(OutgoingResponse {
+                    result: Err(()),
+                    reputation_changes: Vec::new(),
+                    sent_feedback: None,
+                })
+                .is_err()
+            {
+                debug!(target: LOG_TARGET, "Failed to send request response back");
+            }
+
+            return;
+        }
+
+        if !self.peer_scores.record_request(peer, Instant::now()) {
+            debug!(target: LOG_TARGET, "Peer {} exceeded the maximum requests per minute", peer);
+
+            self.handle_bad_request(peer, pending_response);
+
+            return;
+        }
+
         let request = match schema::v1::provider::Request::decode(&payload[..]) {
             Ok(request) => request,
             Err(e) => {
@@ -480,7 +766,7 @@ impl FileTransferService {
                     "Failed to decode provider client request from {}: {:?}", peer, e
                 );
 
-                self.handle_bad_request(pending_response);
+                self.handle_bad_request(peer, pending_response);
 
                 return;
             }
@@ -498,7 +784,7 @@ impl FileTransferService {
                             e
                         );
 
-                        self.handle_bad_request(pending_response);
+                        self.handle_bad_request(peer, pending_response);
 
                         return;
                     }
@@ -513,7 +799,7 @@ impl FileTransferService {
                             e
                         );
 
-                        self.handle_bad_request(pending_response);
+                        self.handle_bad_request(peer, pending_response);
 
                         return;
                     }
@@ -558,7 +844,7 @@ impl FileTransferService {
                         file_key
                     );
 
-                    self.handle_bad_request(pending_response);
+                    self.handle_bad_request(peer, pending_response);
                 }
             }
             Some(schema::v1::provider::request::Request::RemoteDownloadDataRequest(r)) => {
@@ -573,7 +859,7 @@ impl FileTransferService {
                             e
                         );
 
-                        self.handle_bad_request(pending_response);
+                        self.handle_bad_request(peer, pending_response);
 
                         return;
                     }
@@ -591,7 +877,7 @@ impl FileTransferService {
                         peer, file_key, bucket_id
                     );
 
-                    self.handle_bad_request(pending_response);
+                    self.handle_bad_request(peer, pending_response);
 
                     return;
                 }
@@ -599,7 +885,7 @@ impl FileTransferService {
                 let chunk_id = ChunkId::new(r.file_chunk_id);
                 let request_id = self.download_pending_response_nonce.next();
                 self.download_pending_responses
-                    .insert(request_id.clone(), pending_response);
+                    .insert(request_id.clone(), (peer, pending_response));
 
                 self.emit(RemoteDownloadRequest {
                     file_key,
@@ -608,13 +894,159 @@ impl FileTransferService {
                     bucket_id,
                 });
             }
+            Some(schema::v1::provider::request::Request::NegotiateChunkSizeRequest(r)) => {
+                let file_key = match FileKey::decode(&mut r.file_key.as_slice()) {
+                    Ok(file_key) => file_key,
+                    Err(e) => {
+                        error!(
+                            target: LOG_TARGET,
+                            "Failed to deserialize file key from provider client request from {}: {:?}",
+                            peer,
+                            e
+                        );
+
+                        self.handle_bad_request(peer, pending_response);
+
+                        return;
+                    }
+                };
+
+                let bucket_id = match r.bucket_id {
+                    Some(ref bucket_id) => BucketId::decode(&mut bucket_id.as_slice()).ok(),
+                    None => None,
+                };
+
+                if !self.is_allowed(peer, file_key, bucket_id) {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Received unexpected chunk size negotiation request from {} for file key {:?}",
+                        peer,
+                        file_key
+                    );
+
+                    self.handle_bad_request(peer, pending_response);
+
+                    return;
+                }
+
+                // Zero means that there is no transfer chunk size supported by both peers.
+                let chunk_size = self
+                    .chunk_size_bounds
+                    .negotiate(r.min_chunk_size, r.max_chunk_size, r.preferred_chunk_size)
+                    .unwrap_or(0);
+
+                debug!(
+                    target: LOG_TARGET,
+                    "Agreed transfer chunk size of {} bytes with {} for file key {:?}",
+                    chunk_size,
+                    peer,
+                    file_key
+                );
+
+                let response = schema::v1::provider::response::Response::NegotiateChunkSizeResponse(
+                    schema::v1::provider::NegotiateChunkSizeResponse { chunk_size },
+                );
+
+                // Serialize the response
+                let mut response_data = Vec::new();
+                response.encode(&mut response_data);
+
+                let response = OutgoingResponse {
+                    result: Ok(response_data),
+                    reputation_changes: Vec::new(),
+                    sent_feedback: None,
+                };
+
+                // Send the response back.
+                if pending_response.send(response).is_err() {
+                    debug!(target: LOG_TARGET, "Failed to send request response back");
+                }
+            }
+            Some(schema::v1::provider::request::Request::ResumeTransferRequest(r)) => {
+                let file_key = match FileKey::decode(&mut r.file_key.as_slice()) {
+                    Ok(file_key) => file_key,
+                    Err(e) => {
+                        error!(
+                            target: LOG_TARGET,
+                            "Failed to deserialize file key from provider client request from {}: {:?}",
+                            peer,
+                            e
+                        );
+
+                        self.handle_bad_request(peer, pending_response);
+
+                        return;
+                    }
+                };
+
+                let bucket_id = match r.bucket_id {
+                    Some(ref bucket_id) => BucketId::decode(&mut bucket_id.as_slice()).ok(),
+                    None => None,
+                };
+
+                if !self.is_allowed(peer, file_key, bucket_id) {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Received unexpected resume transfer request from {} for file key {:?}",
+                        peer,
+                        file_key
+                    );
+
+                    self.handle_bad_request(peer, pending_response);
+
+                    return;
+                }
+
+                // Without any state for the upload, i.e. if it is a new one, it starts from the
+                // first chunk. Chunks sent again are ignored by the upper layers.
+                let next_chunk_id = match self.transfer_states.get(&peer, &file_key) {
+                    Ok(state) => state.map_or(0, |state| state.resume_offset()),
+                    Err(e) => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Failed to read the transfer state of file key {:?} from {}, restarting the upload: {}",
+                            file_key,
+                            peer,
+                            e
+                        );
+                        0
+                    }
+                };
+
+                debug!(
+                    target: LOG_TARGET,
+                    "Resuming upload from {} of file key {:?} from chunk {}",
+                    peer,
+                    file_key,
+                    next_chunk_id
+                );
+
+                let response = schema::v1::provider::response::Response::ResumeTransferResponse(
+                    schema::v1::provider::ResumeTransferResponse { next_chunk_id },
+                );
+
+                // Serialize the response
+                let mut response_data = Vec::new();
+                response.encode(&mut response_data);
+
+                let response = OutgoingResponse {
+                    result: Ok(response_data),
+                    reputation_changes: Vec::new(),
+                    sent_feedback: None,
+                };
+
+                // Send the response back.
+                if pending_response.send(response).is_err() {
+                    debug!(target: LOG_TARGET, "Failed to send request response back");
+                }
+            }
             None => {
                 error!(
                     target: LOG_TARGET,
                     "Received provider client request from {} with no request", peer
                 );
 
-                self.handle_bad_request(pending_response);
+                self.handle_bad_request(peer, pending_response);
 
                 return;
             }
@@ -634,10 +1066,13 @@ impl FileTransferService {
     }
 
     fn handle_bad_request(
-        &self,
+        &mut self,
+        peer: PeerId,
         pending_response: futures::channel::oneshot::Sender<OutgoingResponse>,
     ) {
         debug!(target: LOG_TARGET, "Bad request received. Lowering reputation.");
+        self.record_outcome(peer, TransferOutcome::Failure);
+
         let reputation_changes = vec![ReputationChange::new(-(1 << 12), "bad request")];
 
         let response = OutgoingResponse {
@@ -692,4 +1127,116 @@ impl FileTransferService {
             bucket_to_check = self.bucket_allow_list_grace_period_time.first();
         }
     }
+
+    /// Updates the reserved peers of the network, so that the peers relevant to the current
+    /// storage requests, buckets and transfers of this node are kept connected.
+    ///
+    /// Released peers are not disconnected, but left to the slot management of the network.
+    fn update_peer_set(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.last_peer_set_update) < PEER_SET_UPDATE_PERIOD {
+            return;
+        }
+        self.last_peer_set_update = now;
+
+        let changes = self.peer_set.update(
+            self.peer_file_allow_list
+                .iter()
+                .map(|(peer_id, _)| *peer_id),
+            self.peer_bucket_allow_list
+                .iter()
+                .map(|(peer_id, _)| *peer_id),
+            now,
+        );
+
+        for (peer_id, multiaddr) in changes.to_reserve {
+            if let Err(e) = self
+                .network
+                .add_reserved_peer(MultiaddrWithPeerId { multiaddr, peer_id })
+            {
+                warn!(target: LOG_TARGET, "Failed to reserve peer {:?}: {}", peer_id, e);
+            }
+        }
+        for peer_id in changes.to_release {
+            self.network.remove_reserved_peer(peer_id);
+        }
+    }
+
+    /// Removes the state of the uploads to this node that did not receive any chunk for longer
+    /// than [`TRANSFER_STATE_TTL`], i.e. that the sender abandoned.
+    fn prune_transfer_states(&mut self) {
+        // Pruned on the first tick too, to forget the uploads that stalled while the node was down.
+        let now = Instant::now();
+        if self
+            .last_transfer_state_prune
+            .is_some_and(|last| now.duration_since(last) < TRANSFER_STATE_PRUNE_PERIOD)
+        {
+            return;
+        }
+        self.last_transfer_state_prune = Some(now);
+
+        match self.transfer_states.prune_stale(TRANSFER_STATE_TTL) {
+            Ok(0) => {}
+            Ok(pruned) => {
+                debug!(target: LOG_TARGET, "Pruned the state of {} stalled uploads", pruned)
+            }
+            Err(e) => warn!(target: LOG_TARGET, "Failed to prune the transfer states: {}", e),
+        }
+    }
+
+    /// Records a transfer with `peer_id`, persisting the scores right away if the peer got banned,
+    /// so that the ban survives a restart.
+    fn record_outcome(&mut self, peer_id: PeerId, outcome: TransferOutcome) {
+        let Some(banned_until) = self.peer_scores.record(peer_id, outcome, peer_score::now())
+        else {
+            return;
+        };
+
+        warn!(
+            target: LOG_TARGET,
+            "Banned peer {} until {} (milliseconds since the UNIX epoch), last transfer being {:?}",
+            peer_id,
+            banned_until,
+            outcome
+        );
+        if let Err(e) = self.peer_scores.flush() {
+            warn!(target: LOG_TARGET, "Failed to persist the peer scores: {}", e);
+        }
+    }
+
+    /// Persists the scores of the peers that changed since the last time, every
+    /// [`PEER_SCORES_FLUSH_PERIOD`].
+    fn flush_peer_scores(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.last_peer_scores_flush) < PEER_SCORES_FLUSH_PERIOD {
+            return;
+        }
+        self.last_peer_scores_flush = now;
+
+        if let Err(e) = self.peer_scores.flush() {
+            warn!(target: LOG_TARGET, "Failed to persist the peer scores: {}", e);
+        }
+        self.peer_scores.prune_request_windows(now);
+    }
+}
+
+/// Sends back the response to a download request, reporting whether it was sent to `callback`.
+fn send_download_response(
+    pending_response_sender: futures::channel::oneshot::Sender<OutgoingResponse>,
+    outgoing_response: OutgoingResponse,
+    callback: tokio::sync::oneshot::Sender<Result<(), RequestError>>,
+) {
+    let result = pending_response_sender
+        .send(outgoing_response)
+        .map_err(|e| {
+            error!(target: LOG_TARGET, "Failed to return Download Response {:?}", e);
+            RequestError::DownloadResponseFailure(e)
+        });
+
+    if callback.send(result).is_err() {
+        error!(
+            target: LOG_TARGET,
+            "Failed to send the response back. Looks like the requester task is gone."
+        );
+    }
 }
@@ -10,16 +10,33 @@ use sc_service::Configuration;
 use shc_actors_framework::actor::{ActorHandle, ActorSpawner, TaskSpawner};
 use shc_common::types::{BlockHash, OpaqueBlock, ParachainClient};
 
+pub use self::bandwidth::BandwidthLimits;
+pub use self::chunk_size::TransferChunkSizeBounds;
 pub use self::handler::FileTransferService;
-
+pub use self::peer_score::{
+    PeerScoreInfo, PeerScoreStore, PeerScoringConfig, TransferOutcome, PEER_SCORES_DB_PATH,
+};
+pub use self::peer_set::{PeerSetDiagnostics, DEFAULT_MAX_MANAGED_PEERS};
+pub use self::transfer_state::{TransferProgress, TransferStateStore, TRANSFER_STATE_DB_PATH};
+
+/// For throttling the bandwidth used to serve files to other peers.
+pub mod bandwidth;
+/// For negotiating and adapting the size of the chunks in which files are transferred.
+pub mod chunk_size;
 /// For defining the commands processed by the file transfer service.
 pub mod commands;
 /// For defining the events emitted by the file transfer service.
 pub mod events;
 /// For incoming provider requests.
 pub mod handler;
+/// For scoring the peers by their transfers, banning the misbehaving ones.
+pub mod peer_score;
+/// For keeping connections to the peers relevant to the current storage responsibilities.
+pub mod peer_set;
 /// For defining the provider requests protocol schema.
 pub mod schema;
+/// For resuming uploads to this node that were interrupted, i.e. by a restart.
+pub mod transfer_state;
 
 // TODO determine ideal max request/response sizes (we could technically specify here usize::MAX)
 /// Max size of request packet. (1GB)
@@ -72,12 +89,26 @@ pub async fn spawn_file_transfer_service(
     request_receiver: async_channel::Receiver<IncomingRequest>,
     protocol_name: ProtocolName,
     network: Arc<dyn NetworkService>,
+    chunk_size_bounds: TransferChunkSizeBounds,
+    transfer_states: TransferStateStore,
+    bandwidth_limits: BandwidthLimits,
+    peer_scores: PeerScoreStore,
+    peer_scoring_config: PeerScoringConfig,
 ) -> ActorHandle<FileTransferService> {
     let task_spawner = task_spawner
         .with_name("file-transfer-service")
         .with_group("network");
 
-    let file_transfer_service = FileTransferService::new(protocol_name, request_receiver, network);
+    let file_transfer_service = FileTransferService::new(
+        protocol_name,
+        request_receiver,
+        network,
+        chunk_size_bounds,
+        transfer_states,
+        bandwidth_limits,
+        peer_scores,
+        peer_scoring_config,
+    );
 
     let file_transfer_service_handle = task_spawner.spawn_actor(file_transfer_service);
 
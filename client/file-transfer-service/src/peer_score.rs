@@ -0,0 +1,492 @@
+//! Scoring of the peers this node transfers files with, to ban the misbehaving ones.
+//!
+//! Every transfer with a peer is recorded as a [`TransferOutcome`]: successful ones raise the score
+//! of the peer, up to [`MAX_SCORE`], while failed, timed out and, above all, corrupt ones lower it.
+//! Requests sent by a peer beyond [`PeerScoringConfig::max_requests_per_minute`] count as failures
+//! too, so that peers requesting chunks endlessly end up like the ones sending invalid requests.
+//!
+//! Once the score of a peer falls to [`PeerScoringConfig::ban_threshold`] below zero, the peer is
+//! banned for [`PeerScoringConfig::ban_duration`]: the requests it sends to this node are refused
+//! without being processed. Requests sent by this node to a banned peer are not affected, as the
+//! tasks sending them already pick the peers to send them to. When the ban expires, the peer starts
+//! over with a score of zero.
+//!
+//! Scores are kept in memory and persisted periodically, and right away when a peer is banned, so
+//! that bans survive a restart of the node.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode};
+use kvdb::{DBTransaction, KeyValueDB};
+use sc_network_types::PeerId;
+use sc_tracing::tracing::warn;
+use serde::{Deserialize, Serialize};
+
+const LOG_TARGET: &str = "file-transfer-service";
+
+/// Path of the peer scores database, relative to the node's base path.
+pub const PEER_SCORES_DB_PATH: &str = "storagehub/file_transfer_peer_scores";
+
+/// Default amount of score below zero at which a peer is banned.
+pub const DEFAULT_BAN_THRESHOLD: u64 = 100;
+
+/// Default time a peer stays banned for, in seconds.
+pub const DEFAULT_BAN_DURATION: u64 = 60 * 60;
+
+/// Highest score a peer can reach, so that a long history of successful transfers doesn't shield
+/// a peer that starts misbehaving.
+pub const MAX_SCORE: i64 = 100;
+
+/// Period over which the requests of a peer are counted against
+/// [`PeerScoringConfig::max_requests_per_minute`].
+const REQUEST_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Column holding every [`PeerScore`], keyed by the peer ID.
+const SCORES_COLUMN: u32 = 0;
+const COLUMNS: u32 = 1;
+
+/// Error type for the peer score store.
+#[derive(thiserror::Error, Debug)]
+pub enum PeerScoreError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Codec(#[from] codec::Error),
+}
+
+/// The outcome of a transfer with a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOutcome {
+    /// The peer answered a request of this node, or sent chunks that were stored.
+    Success,
+    /// A request to the peer failed, or the peer sent an invalid or unexpected request.
+    Failure,
+    /// The peer sent chunks that do not match the fingerprint of their file.
+    Corrupt,
+    /// The peer did not answer a request of this node in time.
+    Timeout,
+}
+
+impl TransferOutcome {
+    /// The change of the score of a peer for a transfer with this outcome.
+    fn score_change(&self) -> i64 {
+        match self {
+            TransferOutcome::Success => 1,
+            TransferOutcome::Failure => -5,
+            TransferOutcome::Timeout => -10,
+            TransferOutcome::Corrupt => -50,
+        }
+    }
+}
+
+/// Thresholds of the peer scoring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerScoringConfig {
+    /// Amount of score below zero at which a peer is banned.
+    pub ban_threshold: u64,
+    /// Time a peer stays banned for.
+    pub ban_duration: Duration,
+    /// Maximum amount of requests a peer can send per minute, beyond which they count as failures.
+    /// Not limited if not set.
+    pub max_requests_per_minute: Option<u32>,
+}
+
+impl Default for PeerScoringConfig {
+    fn default() -> Self {
+        Self {
+            ban_threshold: DEFAULT_BAN_THRESHOLD,
+            ban_duration: Duration::from_secs(DEFAULT_BAN_DURATION),
+            max_requests_per_minute: None,
+        }
+    }
+}
+
+/// The transfers with a peer, and the resulting score.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct PeerScore {
+    score: i64,
+    successes: u64,
+    failures: u64,
+    corruptions: u64,
+    timeouts: u64,
+    /// Until when the peer is banned, in milliseconds since the UNIX epoch, if it is.
+    banned_until: Option<u64>,
+}
+
+impl PeerScore {
+    /// Records a transfer with `outcome`.
+    pub fn record(&mut self, outcome: TransferOutcome) {
+        match outcome {
+            TransferOutcome::Success => self.successes += 1,
+            TransferOutcome::Failure => self.failures += 1,
+            TransferOutcome::Corrupt => self.corruptions += 1,
+            TransferOutcome::Timeout => self.timeouts += 1,
+        }
+        self.score = (self.score + outcome.score_change()).min(MAX_SCORE);
+    }
+
+    /// The score of the peer.
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// Whether the peer is banned as of `now`, in milliseconds since the UNIX epoch.
+    pub fn is_banned(&self, now: u64) -> bool {
+        self.banned_until.is_some_and(|until| now < until)
+    }
+
+    /// Lifts the ban of the peer if it expired as of `now`, starting it over with a score of zero.
+    /// Returns whether it did.
+    fn lift_expired_ban(&mut self, now: u64) -> bool {
+        if self.banned_until.is_some_and(|until| now >= until) {
+            self.banned_until = None;
+            self.score = 0;
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Score of a peer, with the transfers it results from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerScoreInfo {
+    pub peer_id: String,
+    pub score: i64,
+    pub successes: u64,
+    pub failures: u64,
+    pub corruptions: u64,
+    pub timeouts: u64,
+    /// Until when the peer is banned, in milliseconds since the UNIX epoch, if it is.
+    pub banned_until: Option<u64>,
+}
+
+/// Persistent store of the [`PeerScore`] of every peer this node transferred files with.
+///
+/// Cheap to clone.
+#[derive(Clone)]
+pub struct PeerScoreStore {
+    db: Arc<dyn KeyValueDB>,
+}
+
+impl PeerScoreStore {
+    /// Opens the store at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PeerScoreError> {
+        let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(COLUMNS);
+        let db = kvdb_rocksdb::Database::open(&db_config, path.as_ref())?;
+
+        Ok(Self::new(Arc::new(db)))
+    }
+
+    /// A store that is not persisted, i.e. whose scores and bans are lost after a restart.
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(kvdb_memorydb::create(COLUMNS)))
+    }
+
+    /// Wraps `db`, which must have at least [`COLUMNS`] columns.
+    pub fn new(db: Arc<dyn KeyValueDB>) -> Self {
+        Self { db }
+    }
+
+    /// Every score in the store.
+    pub fn load(&self) -> Result<HashMap<PeerId, PeerScore>, PeerScoreError> {
+        let mut scores = HashMap::new();
+        for entry in self.db.iter(SCORES_COLUMN) {
+            let (key, value) = entry?;
+            let peer_id = PeerId::from_bytes(&key)
+                .map_err(|_| codec::Error::from("Invalid peer ID in peer score key"))?;
+            scores.insert(peer_id, PeerScore::decode(&mut value.as_slice())?);
+        }
+
+        Ok(scores)
+    }
+
+    /// Writes the score of every peer in `scores`, removing the peers whose score is `None`.
+    pub fn write<'a>(
+        &self,
+        scores: impl IntoIterator<Item = (&'a PeerId, Option<&'a PeerScore>)>,
+    ) -> Result<(), PeerScoreError> {
+        let mut transaction = DBTransaction::new();
+        for (peer_id, score) in scores {
+            match score {
+                Some(score) => {
+                    transaction.put_vec(SCORES_COLUMN, &peer_id.to_bytes(), score.encode())
+                }
+                None => transaction.delete(SCORES_COLUMN, &peer_id.to_bytes()),
+            }
+        }
+        self.db.write(transaction)?;
+
+        Ok(())
+    }
+
+    /// Removes the score of every peer.
+    pub fn clear(&self) -> Result<(), PeerScoreError> {
+        let mut transaction = DBTransaction::new();
+        transaction.delete_prefix(SCORES_COLUMN, &[]);
+        self.db.write(transaction)?;
+
+        Ok(())
+    }
+}
+
+/// Requests sent by a peer since `start`.
+#[derive(Debug)]
+struct RequestWindow {
+    start: Instant,
+    count: u32,
+}
+
+/// Scores of the peers this node transfers files with, and the bans resulting from them.
+pub struct PeerScores {
+    config: PeerScoringConfig,
+    store: PeerScoreStore,
+    scores: HashMap<PeerId, PeerScore>,
+    /// Peers whose score changed since the scores were last persisted.
+    dirty: HashSet<PeerId>,
+    /// Requests sent by each peer in the current [`REQUEST_RATE_WINDOW`]. Not persisted.
+    request_windows: HashMap<PeerId, RequestWindow>,
+}
+
+impl PeerScores {
+    /// Scores with `config`, starting from the ones persisted in `store`.
+    pub fn new(config: PeerScoringConfig, store: PeerScoreStore) -> Self {
+        let scores = store.load().unwrap_or_else(|e| {
+            warn!(target: LOG_TARGET, "Failed to load the peer scores, starting over: {}", e);
+            HashMap::new()
+        });
+
+        Self {
+            config,
+            store,
+            scores,
+            dirty: HashSet::new(),
+            request_windows: HashMap::new(),
+        }
+    }
+
+    /// Whether `peer_id` is banned as of `now`, in milliseconds since the UNIX epoch.
+    ///
+    /// An expired ban is lifted, starting the peer over with a score of zero.
+    pub fn is_banned(&mut self, peer_id: &PeerId, now: u64) -> bool {
+        let Some(score) = self.scores.get_mut(peer_id) else {
+            return false;
+        };
+
+        if score.lift_expired_ban(now) {
+            self.dirty.insert(*peer_id);
+        }
+        score.is_banned(now)
+    }
+
+    /// Records a transfer with `peer_id` with `outcome` at `now`, in milliseconds since the UNIX
+    /// epoch. Returns until when the peer is banned, if this transfer got it banned.
+    pub fn record(&mut self, peer_id: PeerId, outcome: TransferOutcome, now: u64) -> Option<u64> {
+        let score = self.scores.entry(peer_id).or_default();
+        score.lift_expired_ban(now);
+        score.record(outcome);
+        self.dirty.insert(peer_id);
+
+        if score.is_banned(now) || score.score > -(self.config.ban_threshold as i64) {
+            return None;
+        }
+
+        let banned_until = now.saturating_add(self.config.ban_duration.as_millis() as u64);
+        score.banned_until = Some(banned_until);
+        Some(banned_until)
+    }
+
+    /// Counts a request sent by `peer_id` at `now`. Returns `false` if the peer exceeded
+    /// [`PeerScoringConfig::max_requests_per_minute`].
+    pub fn record_request(&mut self, peer_id: PeerId, now: Instant) -> bool {
+        let Some(max_requests) = self.config.max_requests_per_minute else {
+            return true;
+        };
+
+        let window = self
+            .request_windows
+            .entry(peer_id)
+            .or_insert(RequestWindow {
+                start: now,
+                count: 0,
+            });
+        if now.saturating_duration_since(window.start) >= REQUEST_RATE_WINDOW {
+            *window = RequestWindow {
+                start: now,
+                count: 0,
+            };
+        }
+        window.count = window.count.saturating_add(1);
+
+        window.count <= max_requests
+    }
+
+    /// The score of `peer_id`, if any transfer with it was recorded.
+    pub fn get(&self, peer_id: &PeerId) -> Option<&PeerScore> {
+        self.scores.get(peer_id)
+    }
+
+    /// The score of every peer a transfer was recorded with.
+    pub fn scores(&self) -> Vec<PeerScoreInfo> {
+        self.scores
+            .iter()
+            .map(|(peer_id, score)| PeerScoreInfo {
+                peer_id: peer_id.to_base58(),
+                score: score.score,
+                successes: score.successes,
+                failures: score.failures,
+                corruptions: score.corruptions,
+                timeouts: score.timeouts,
+                banned_until: score.banned_until,
+            })
+            .collect()
+    }
+
+    /// Forgets the score, and lifts the ban, of `peer_id`, or of every peer if not set.
+    pub fn clear(&mut self, peer_id: Option<PeerId>) -> Result<(), PeerScoreError> {
+        match peer_id {
+            Some(peer_id) => {
+                self.store.write([(&peer_id, None)])?;
+                self.scores.remove(&peer_id);
+                self.dirty.remove(&peer_id);
+                self.request_windows.remove(&peer_id);
+            }
+            None => {
+                self.store.clear()?;
+                self.scores.clear();
+                self.dirty.clear();
+                self.request_windows.clear();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Persists the scores that changed since they were last persisted.
+    pub fn flush(&mut self) -> Result<(), PeerScoreError> {
+        if self.dirty.is_empty() {
+            return Ok(());
+        }
+
+        self.store.write(
+            self.dirty
+                .iter()
+                .map(|peer_id| (peer_id, self.scores.get(peer_id))),
+        )?;
+        self.dirty.clear();
+
+        Ok(())
+    }
+
+    /// Forgets the requests counted in the windows that ended before `now`.
+    pub fn prune_request_windows(&mut self, now: Instant) {
+        self.request_windows
+            .retain(|_, window| now.saturating_duration_since(window.start) < REQUEST_RATE_WINDOW);
+    }
+}
+
+/// Milliseconds since the UNIX epoch, as used for the bans.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PeerScoringConfig {
+        PeerScoringConfig {
+            ban_threshold: 100,
+            ban_duration: Duration::from_secs(10),
+            max_requests_per_minute: Some(3),
+        }
+    }
+
+    #[test]
+    fn peers_are_banned_until_the_ban_expires() {
+        let mut scores = PeerScores::new(config(), PeerScoreStore::in_memory());
+        let peer = PeerId::random();
+
+        assert_eq!(scores.record(peer, TransferOutcome::Corrupt, 0), None);
+        assert!(!scores.is_banned(&peer, 0));
+        assert_eq!(
+            scores.record(peer, TransferOutcome::Corrupt, 0),
+            Some(10_000)
+        );
+        assert!(scores.is_banned(&peer, 9_999));
+        // Transfers while banned don't extend the ban.
+        assert_eq!(scores.record(peer, TransferOutcome::Failure, 5_000), None);
+
+        assert!(!scores.is_banned(&peer, 10_000));
+        assert_eq!(scores.get(&peer).unwrap().score(), 0);
+        assert_eq!(scores.get(&peer).unwrap().corruptions, 2);
+    }
+
+    #[test]
+    fn successes_are_capped() {
+        let mut scores = PeerScores::new(config(), PeerScoreStore::in_memory());
+        let peer = PeerId::random();
+
+        for _ in 0..1000 {
+            scores.record(peer, TransferOutcome::Success, 0);
+        }
+        assert_eq!(scores.get(&peer).unwrap().score(), MAX_SCORE);
+
+        // A corrupt peer with a long history is banned as fast as a new one.
+        scores.record(peer, TransferOutcome::Corrupt, 0);
+        scores.record(peer, TransferOutcome::Corrupt, 0);
+        scores.record(peer, TransferOutcome::Corrupt, 0);
+        assert_eq!(
+            scores.record(peer, TransferOutcome::Corrupt, 0),
+            Some(10_000)
+        );
+    }
+
+    #[test]
+    fn requests_beyond_the_rate_limit_are_reported() {
+        let mut scores = PeerScores::new(config(), PeerScoreStore::in_memory());
+        let (peer, other_peer) = (PeerId::random(), PeerId::random());
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(scores.record_request(peer, start));
+        }
+        assert!(!scores.record_request(peer, start + Duration::from_secs(59)));
+        assert!(scores.record_request(other_peer, start));
+        assert!(scores.record_request(peer, start + REQUEST_RATE_WINDOW));
+    }
+
+    #[test]
+    fn scores_are_persisted() {
+        let store = PeerScoreStore::in_memory();
+        let (peer, other_peer) = (PeerId::random(), PeerId::random());
+
+        let mut scores = PeerScores::new(config(), store.clone());
+        scores.record(peer, TransferOutcome::Corrupt, 0);
+        scores.record(peer, TransferOutcome::Corrupt, 0);
+        scores.record(other_peer, TransferOutcome::Timeout, 0);
+        scores.flush().unwrap();
+
+        let mut scores = PeerScores::new(config(), store.clone());
+        assert!(scores.is_banned(&peer, 1));
+        assert_eq!(scores.get(&other_peer).unwrap().score(), -10);
+
+        scores.clear(Some(peer)).unwrap();
+        assert!(!scores.is_banned(&peer, 1));
+        assert_eq!(store.load().unwrap().len(), 1);
+
+        scores.clear(None).unwrap();
+        assert!(scores.scores().is_empty());
+        assert!(store.load().unwrap().is_empty());
+    }
+}
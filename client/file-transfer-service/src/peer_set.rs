@@ -0,0 +1,300 @@
+//! Management of the peers this node keeps connections to, based on its current storage
+//! responsibilities.
+//!
+//! The desired peer set is derived from the registries of the [`FileTransferService`]:
+//! - Peers with an active transfer: peers this node recently sent a request to, i.e. it is
+//!   uploading a file to or downloading a file from them.
+//! - Peers of a storage request: peers allowed to upload a file to this node.
+//! - Peers of a bucket: peers allowed to download or upload files of a bucket this node stores,
+//!   i.e. while moving a bucket.
+//!
+//! Desired peers are kept connected by adding them to the reserved peers of the node, in that
+//! order of priority and up to a maximum. Under pressure, the peers with the lowest priority are
+//! released, and so are the peers that are no longer desired. Released peers are not disconnected
+//! right away, but they are left to the regular slot management of the network, which prunes them
+//! when it runs out of connection slots.
+//!
+//! [`FileTransferService`]: crate::FileTransferService
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use sc_network::Multiaddr;
+use sc_network_types::PeerId;
+use serde::{Deserialize, Serialize};
+
+/// Default maximum amount of desired peers kept connected at the same time.
+pub const DEFAULT_MAX_MANAGED_PEERS: usize = 50;
+
+/// Time after the last request to a peer during which it is considered to have an active
+/// transfer with this node.
+const ACTIVE_TRANSFER_PEER_TTL: Duration = Duration::from_secs(60);
+
+/// Why a peer is desired. Peers desired for several reasons get the highest priority among them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PeerPriority {
+    /// The peer is allowed to transfer files of a bucket this node stores.
+    Bucket,
+    /// The peer is allowed to upload a file of a storage request to this node.
+    StorageRequest,
+    /// This node recently sent a request to the peer.
+    ActiveTransfer,
+}
+
+/// Changes to apply to the reserved peers of the node, to keep the desired peers connected.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PeerSetChanges {
+    /// Peers to add to the reserved peers, with the address to connect to them.
+    pub to_reserve: Vec<(PeerId, Multiaddr)>,
+    /// Peers to remove from the reserved peers.
+    pub to_release: Vec<PeerId>,
+}
+
+/// A peer in the desired peer set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesiredPeer {
+    pub peer_id: String,
+    pub priority: PeerPriority,
+    /// Whether the peer is kept connected, i.e. it is a reserved peer of the node. Desired peers
+    /// are not reserved if their address is unknown, or if they are pruned under pressure.
+    pub reserved: bool,
+    /// Whether the node is currently connected to the peer.
+    pub connected: bool,
+}
+
+/// The desired peer set of the node, compared to the peers it is actually connected to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerSetDiagnostics {
+    /// Maximum amount of desired peers kept connected at the same time.
+    pub max_managed_peers: usize,
+    /// Desired peers, from the highest to the lowest priority.
+    pub desired_peers: Vec<DesiredPeer>,
+    /// Amount of peers the node is connected to.
+    pub connected_peers: usize,
+    /// Peers the node is connected to which are not desired.
+    pub undesired_connected_peers: Vec<String>,
+}
+
+/// Keeps track of the desired peer set of the node and of the peers reserved to keep it connected.
+pub struct PeerSetManager {
+    max_managed_peers: usize,
+    /// Last known address of each peer.
+    known_addresses: HashMap<PeerId, Multiaddr>,
+    /// Time of the last request sent to each peer.
+    active_transfers: HashMap<PeerId, Instant>,
+    /// Desired peers, as of the last update.
+    desired: HashMap<PeerId, PeerPriority>,
+    /// Peers reserved by this manager.
+    reserved: HashSet<PeerId>,
+}
+
+impl PeerSetManager {
+    pub fn new(max_managed_peers: usize) -> Self {
+        Self {
+            max_managed_peers,
+            known_addresses: HashMap::new(),
+            active_transfers: HashMap::new(),
+            desired: HashMap::new(),
+            reserved: HashSet::new(),
+        }
+    }
+
+    /// Records the address to connect to a peer.
+    pub fn record_address(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.known_addresses.insert(peer_id, address);
+    }
+
+    /// Records that a request was sent to a peer at `now`.
+    pub fn record_transfer(&mut self, peer_id: PeerId, now: Instant) {
+        self.active_transfers.insert(peer_id, now);
+    }
+
+    /// Derives the desired peer set from the peers of the current storage requests and buckets,
+    /// and the recent transfers, returning the changes to apply to the reserved peers.
+    pub fn update(
+        &mut self,
+        storage_request_peers: impl IntoIterator<Item = PeerId>,
+        bucket_peers: impl IntoIterator<Item = PeerId>,
+        now: Instant,
+    ) -> PeerSetChanges {
+        self.active_transfers
+            .retain(|_, last_request| now.duration_since(*last_request) < ACTIVE_TRANSFER_PEER_TTL);
+
+        let mut desired = HashMap::new();
+        let reasons = bucket_peers
+            .into_iter()
+            .map(|peer_id| (peer_id, PeerPriority::Bucket))
+            .chain(
+                storage_request_peers
+                    .into_iter()
+                    .map(|peer_id| (peer_id, PeerPriority::StorageRequest)),
+            )
+            .chain(
+                self.active_transfers
+                    .keys()
+                    .map(|peer_id| (*peer_id, PeerPriority::ActiveTransfer)),
+            );
+        for (peer_id, priority) in reasons {
+            let current = desired.entry(peer_id).or_insert(priority);
+            *current = (*current).max(priority);
+        }
+        self.desired = desired;
+
+        // Keep the peers with the highest priority connected, among the ones that can be reached.
+        let to_keep: HashSet<PeerId> = self
+            .desired_by_priority()
+            .into_iter()
+            .filter(|(peer_id, _)| self.known_addresses.contains_key(peer_id))
+            .take(self.max_managed_peers)
+            .map(|(peer_id, _)| peer_id)
+            .collect();
+
+        let changes = PeerSetChanges {
+            to_reserve: to_keep
+                .difference(&self.reserved)
+                .map(|peer_id| (*peer_id, self.known_addresses[peer_id].clone()))
+                .collect(),
+            to_release: self.reserved.difference(&to_keep).copied().collect(),
+        };
+        self.reserved = to_keep;
+
+        changes
+    }
+
+    /// Compares the desired peer set against the peers the node is connected to.
+    pub fn diagnostics(&self, connected_peers: &HashSet<PeerId>) -> PeerSetDiagnostics {
+        let desired_peers = self
+            .desired_by_priority()
+            .into_iter()
+            .map(|(peer_id, priority)| DesiredPeer {
+                peer_id: peer_id.to_base58(),
+                priority,
+                reserved: self.reserved.contains(&peer_id),
+                connected: connected_peers.contains(&peer_id),
+            })
+            .collect();
+
+        let mut undesired_connected_peers: Vec<String> = connected_peers
+            .iter()
+            .filter(|peer_id| !self.desired.contains_key(peer_id))
+            .map(|peer_id| peer_id.to_base58())
+            .collect();
+        undesired_connected_peers.sort();
+
+        PeerSetDiagnostics {
+            max_managed_peers: self.max_managed_peers,
+            desired_peers,
+            connected_peers: connected_peers.len(),
+            undesired_connected_peers,
+        }
+    }
+
+    /// The desired peers, from the highest to the lowest priority. Ties are broken by peer ID, so
+    /// that the same peers are kept across updates.
+    fn desired_by_priority(&self) -> Vec<(PeerId, PeerPriority)> {
+        let mut desired: Vec<_> = self
+            .desired
+            .iter()
+            .map(|(peer_id, priority)| (*peer_id, *priority))
+            .collect();
+        desired.sort_by(|(peer_a, priority_a), (peer_b, priority_b)| {
+            priority_b
+                .cmp(priority_a)
+                .then_with(|| peer_a.to_bytes().cmp(&peer_b.to_bytes()))
+        });
+        desired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_with_address() -> (PeerId, Multiaddr) {
+        let peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/30333".parse().unwrap();
+        (peer_id, address)
+    }
+
+    #[test]
+    fn desired_peers_with_known_address_are_reserved() {
+        let mut manager = PeerSetManager::new(DEFAULT_MAX_MANAGED_PEERS);
+        let (storage_request_peer, address) = peer_with_address();
+        let unknown_peer = PeerId::random();
+        manager.record_address(storage_request_peer, address.clone());
+
+        let changes = manager.update([storage_request_peer, unknown_peer], [], Instant::now());
+
+        assert_eq!(
+            changes,
+            PeerSetChanges {
+                to_reserve: vec![(storage_request_peer, address)],
+                to_release: vec![],
+            }
+        );
+
+        let diagnostics = manager.diagnostics(&HashSet::from([storage_request_peer]));
+        assert_eq!(diagnostics.desired_peers.len(), 2);
+        assert!(diagnostics
+            .desired_peers
+            .iter()
+            .any(|peer| peer.peer_id == storage_request_peer.to_base58()
+                && peer.reserved
+                && peer.connected));
+        assert!(diagnostics
+            .desired_peers
+            .iter()
+            .any(|peer| peer.peer_id == unknown_peer.to_base58() && !peer.reserved));
+    }
+
+    #[test]
+    fn lowest_priority_peers_are_released_under_pressure() {
+        let mut manager = PeerSetManager::new(1);
+        let (bucket_peer, bucket_peer_address) = peer_with_address();
+        let (transfer_peer, transfer_peer_address) = peer_with_address();
+        manager.record_address(bucket_peer, bucket_peer_address.clone());
+        manager.record_address(transfer_peer, transfer_peer_address.clone());
+
+        let now = Instant::now();
+        let changes = manager.update([], [bucket_peer], now);
+        assert_eq!(changes.to_reserve, vec![(bucket_peer, bucket_peer_address)]);
+
+        // A transfer with a new peer has a higher priority than the bucket peer.
+        manager.record_transfer(transfer_peer, now);
+        let changes = manager.update([], [bucket_peer], now);
+        assert_eq!(
+            changes,
+            PeerSetChanges {
+                to_reserve: vec![(transfer_peer, transfer_peer_address)],
+                to_release: vec![bucket_peer],
+            }
+        );
+    }
+
+    #[test]
+    fn peers_no_longer_desired_are_released() {
+        let mut manager = PeerSetManager::new(DEFAULT_MAX_MANAGED_PEERS);
+        let (transfer_peer, address) = peer_with_address();
+        manager.record_address(transfer_peer, address);
+
+        let now = Instant::now();
+        manager.record_transfer(transfer_peer, now);
+        manager.update([], [], now);
+
+        // The transfer is no longer active.
+        let changes = manager.update([], [], now + ACTIVE_TRANSFER_PEER_TTL);
+        assert_eq!(changes.to_release, vec![transfer_peer]);
+
+        let diagnostics = manager.diagnostics(&HashSet::from([transfer_peer]));
+        assert!(diagnostics.desired_peers.is_empty());
+        assert_eq!(
+            diagnostics.undesired_connected_peers,
+            vec![transfer_peer.to_base58()]
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Persisted state of the files being uploaded to this node, so that uploads can be resumed.
+//!
+//! For every (peer, file) pair, the receiving node keeps a bitmap of the chunks of the file it
+//! already received from that peer and wrote to its file storage. The state is persisted, so that it
+//! survives a restart of the node. Before uploading a file, the sender asks the receiver for the
+//! first chunk it is missing, i.e. the resume offset, and starts sending from there instead of from
+//! the first chunk.
+//!
+//! The state of a file is removed once the file is unregistered from the
+//! [`FileTransferService`](crate::FileTransferService), i.e. when its upload completes or is
+//! abandoned. The state of uploads that stall for longer than [`TRANSFER_STATE_TTL`] is pruned.
+
+use std::{
+    io,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode};
+use kvdb::{DBTransaction, KeyValueDB};
+use sc_network_types::PeerId;
+use serde::{Deserialize, Serialize};
+use shc_common::types::{ChunkId, FileKey};
+
+/// Path of the transfer state database, relative to the node's base path.
+pub const TRANSFER_STATE_DB_PATH: &str = "storagehub/file_transfer_state";
+
+/// Time after the last chunk received from a peer after which the state of its upload is pruned.
+pub const TRANSFER_STATE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Column holding every [`TransferState`], keyed by the file key followed by the peer ID, so that
+/// the state of a file for every peer can be removed at once.
+const TRANSFERS_COLUMN: u32 = 0;
+const COLUMNS: u32 = 1;
+
+/// Error type for the transfer state store.
+#[derive(thiserror::Error, Debug)]
+pub enum TransferStateError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Codec(#[from] codec::Error),
+}
+
+/// The chunks of a file received from a peer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TransferState {
+    /// Amount of chunks of the file.
+    chunks_count: u64,
+    /// Bitmap of the received chunks, where bit `i % 8` of byte `i / 8` is chunk `i`.
+    received: Vec<u8>,
+    /// Amount of bits set in `received`.
+    received_count: u64,
+    /// When the last chunk was received, in milliseconds since the UNIX epoch.
+    updated_at: u64,
+}
+
+impl TransferState {
+    /// State of a file of `chunks_count` chunks, none of which has been received yet.
+    pub fn new(chunks_count: u64) -> Self {
+        Self {
+            chunks_count,
+            received: vec![0; chunks_count.div_ceil(8) as usize],
+            received_count: 0,
+            updated_at: now(),
+        }
+    }
+
+    /// Marks `chunk_id` as received. Returns `false` if it already was, or if it is not a chunk of
+    /// the file.
+    pub fn record(&mut self, chunk_id: &ChunkId) -> bool {
+        let chunk_id = chunk_id.as_u64();
+        if chunk_id >= self.chunks_count || self.contains(chunk_id) {
+            return false;
+        }
+
+        self.received[(chunk_id / 8) as usize] |= 1 << (chunk_id % 8);
+        self.received_count += 1;
+        true
+    }
+
+    /// Whether chunk `chunk_id` has been received.
+    pub fn contains(&self, chunk_id: u64) -> bool {
+        chunk_id < self.chunks_count
+            && self.received[(chunk_id / 8) as usize] & (1 << (chunk_id % 8)) != 0
+    }
+
+    /// Amount of chunks of the file.
+    pub fn chunks_count(&self) -> u64 {
+        self.chunks_count
+    }
+
+    /// Amount of chunks received.
+    pub fn received_count(&self) -> u64 {
+        self.received_count
+    }
+
+    /// The first chunk that has not been received, from which the upload should resume, or
+    /// [`Self::chunks_count`] if every chunk has been received.
+    pub fn resume_offset(&self) -> u64 {
+        self.received
+            .iter()
+            .position(|byte| *byte != u8::MAX)
+            .map(|index| index as u64 * 8 + self.received[index].trailing_ones() as u64)
+            .map_or(self.chunks_count, |offset| offset.min(self.chunks_count))
+    }
+
+    /// Percentage (from 0 to 100) of the chunks of the file that have been received.
+    pub fn completion_percentage(&self) -> f64 {
+        if self.chunks_count == 0 {
+            return 100.0;
+        }
+
+        self.received_count as f64 * 100.0 / self.chunks_count as f64
+    }
+}
+
+/// Progress of the upload of a file from a peer to this node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub peer_id: String,
+    pub file_key: String,
+    pub chunks_count: u64,
+    pub received_chunks: u64,
+    /// Chunk from which the peer resumes the upload, if it reconnects.
+    pub resume_offset: u64,
+    /// Percentage (from 0 to 100) of the chunks of the file that have been received.
+    pub completion_percentage: f64,
+    /// When the last chunk was received, in milliseconds since the UNIX epoch.
+    pub updated_at: u64,
+}
+
+/// Persistent store of the [`TransferState`] of the files being uploaded to this node.
+///
+/// Cheap to clone.
+#[derive(Clone)]
+pub struct TransferStateStore {
+    db: Arc<dyn KeyValueDB>,
+}
+
+impl TransferStateStore {
+    /// Opens the store at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TransferStateError> {
+        let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(COLUMNS);
+        let db = kvdb_rocksdb::Database::open(&db_config, path.as_ref())?;
+
+        Ok(Self::new(Arc::new(db)))
+    }
+
+    /// A store that is not persisted, i.e. whose uploads start over after a restart.
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(kvdb_memorydb::create(COLUMNS)))
+    }
+
+    /// Wraps `db`, which must have at least [`COLUMNS`] columns.
+    pub fn new(db: Arc<dyn KeyValueDB>) -> Self {
+        Self { db }
+    }
+
+    /// The state of the upload of `file_key` from `peer_id`, if any.
+    pub fn get(
+        &self,
+        peer_id: &PeerId,
+        file_key: &FileKey,
+    ) -> Result<Option<TransferState>, TransferStateError> {
+        self.db
+            .get(TRANSFERS_COLUMN, &key(file_key, peer_id))?
+            .map(|value| TransferState::decode(&mut value.as_slice()))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Marks `chunk_ids` of `file_key`, a file of `chunks_count` chunks, as received from
+    /// `peer_id`, returning the updated state.
+    pub fn record_received_chunks(
+        &self,
+        peer_id: &PeerId,
+        file_key: &FileKey,
+        chunks_count: u64,
+        chunk_ids: &[ChunkId],
+    ) -> Result<TransferState, TransferStateError> {
+        let mut state = self
+            .get(peer_id, file_key)?
+            // The state is started over if the peer sends chunks of a file with another size.
+            .filter(|state| state.chunks_count == chunks_count)
+            .unwrap_or_else(|| TransferState::new(chunks_count));
+
+        for chunk_id in chunk_ids {
+            state.record(chunk_id);
+        }
+        state.updated_at = now();
+
+        let mut transaction = DBTransaction::new();
+        transaction.put_vec(TRANSFERS_COLUMN, &key(file_key, peer_id), state.encode());
+        self.db.write(transaction)?;
+
+        Ok(state)
+    }
+
+    /// Removes the state of the uploads of `file_key` from every peer.
+    pub fn remove_file(&self, file_key: &FileKey) -> Result<(), TransferStateError> {
+        let mut transaction = DBTransaction::new();
+        transaction.delete_prefix(TRANSFERS_COLUMN, &file_key.encode());
+        self.db.write(transaction)?;
+
+        Ok(())
+    }
+
+    /// Every upload to this node, i.e. from which at least a chunk has been received.
+    pub fn transfers(&self) -> Result<Vec<TransferProgress>, TransferStateError> {
+        let mut transfers = Vec::new();
+        for entry in self.db.iter(TRANSFERS_COLUMN) {
+            let (key, value) = entry?;
+            let state = TransferState::decode(&mut value.as_slice())?;
+            let (file_key, peer_id) = split_key(&key)?;
+
+            transfers.push(TransferProgress {
+                peer_id,
+                file_key,
+                chunks_count: state.chunks_count,
+                received_chunks: state.received_count,
+                resume_offset: state.resume_offset(),
+                completion_percentage: state.completion_percentage(),
+                updated_at: state.updated_at,
+            });
+        }
+
+        Ok(transfers)
+    }
+
+    /// Removes the state of the uploads that did not receive any chunk for longer than `ttl`,
+    /// returning how many were removed.
+    pub fn prune_stale(&self, ttl: Duration) -> Result<usize, TransferStateError> {
+        let updated_before = now().saturating_sub(ttl.as_millis() as u64);
+
+        let mut transaction = DBTransaction::new();
+        for entry in self.db.iter(TRANSFERS_COLUMN) {
+            let (key, value) = entry?;
+            let state = TransferState::decode(&mut value.as_slice())?;
+            if state.updated_at < updated_before {
+                transaction.delete(TRANSFERS_COLUMN, &key);
+            }
+        }
+
+        let pruned = transaction.ops.len();
+        self.db.write(transaction)?;
+
+        Ok(pruned)
+    }
+}
+
+fn key(file_key: &FileKey, peer_id: &PeerId) -> Vec<u8> {
+    let mut key = file_key.encode();
+    key.extend(peer_id.to_bytes());
+    key
+}
+
+/// Splits a key of [`TRANSFERS_COLUMN`] into the printable file key and peer ID.
+fn split_key(key: &[u8]) -> Result<(String, String), TransferStateError> {
+    let mut input = key;
+    let file_key = FileKey::decode(&mut input)?;
+    let peer_id = PeerId::from_bytes(input)
+        .map_err(|_| codec::Error::from("Invalid peer ID in transfer state key"))?;
+
+    Ok((
+        array_bytes::bytes2hex("0x", file_key.encode()),
+        peer_id.to_base58(),
+    ))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_offset_is_the_first_missing_chunk() {
+        let mut state = TransferState::new(20);
+        assert_eq!(state.resume_offset(), 0);
+
+        for chunk_id in (0..10).chain([12, 15]) {
+            assert!(state.record(&ChunkId::new(chunk_id)));
+        }
+        assert!(!state.record(&ChunkId::new(12)));
+        assert!(!state.record(&ChunkId::new(20)));
+
+        assert_eq!(state.received_count(), 12);
+        assert_eq!(state.resume_offset(), 10);
+        assert_eq!(state.completion_percentage(), 60.0);
+
+        for chunk_id in 10..20 {
+            state.record(&ChunkId::new(chunk_id));
+        }
+        assert_eq!(state.resume_offset(), 20);
+        assert_eq!(state.completion_percentage(), 100.0);
+    }
+
+    #[test]
+    fn store_keeps_the_state_per_peer_and_file() {
+        let store = TransferStateStore::in_memory();
+        let (peer, other_peer) = (PeerId::random(), PeerId::random());
+        let (file_key, other_file_key) = (FileKey::from([1u8; 32]), FileKey::from([2u8; 32]));
+
+        store
+            .record_received_chunks(&peer, &file_key, 4, &[ChunkId::new(0), ChunkId::new(1)])
+            .unwrap();
+        store
+            .record_received_chunks(&other_peer, &file_key, 4, &[ChunkId::new(0)])
+            .unwrap();
+        store
+            .record_received_chunks(&peer, &other_file_key, 2, &[ChunkId::new(1)])
+            .unwrap();
+
+        assert_eq!(
+            store
+                .get(&peer, &file_key)
+                .unwrap()
+                .unwrap()
+                .resume_offset(),
+            2
+        );
+        assert_eq!(
+            store
+                .get(&other_peer, &file_key)
+                .unwrap()
+                .unwrap()
+                .resume_offset(),
+            1
+        );
+        assert_eq!(
+            store
+                .get(&peer, &other_file_key)
+                .unwrap()
+                .unwrap()
+                .resume_offset(),
+            0
+        );
+        assert_eq!(store.transfers().unwrap().len(), 3);
+
+        store.remove_file(&file_key).unwrap();
+
+        assert!(store.get(&peer, &file_key).unwrap().is_none());
+        assert!(store.get(&other_peer, &file_key).unwrap().is_none());
+        assert_eq!(store.transfers().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prune_stale_removes_old_transfers() {
+        let store = TransferStateStore::in_memory();
+        let peer = PeerId::random();
+        let file_key = FileKey::from([1u8; 32]);
+
+        store
+            .record_received_chunks(&peer, &file_key, 4, &[ChunkId::new(0)])
+            .unwrap();
+
+        assert_eq!(store.prune_stale(TRANSFER_STATE_TTL).unwrap(), 0);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(store.prune_stale(Duration::ZERO).unwrap(), 1);
+        assert!(store.get(&peer, &file_key).unwrap().is_none());
+    }
+}
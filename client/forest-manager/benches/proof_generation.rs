@@ -0,0 +1,92 @@
+//! Benchmarks the generation of forest proofs on large forests, for different amounts of challenged
+//! keys and threads.
+//!
+//! Run with `cargo bench -p shc-forest-manager --bench proof_generation`.
+
+use std::{
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use shc_common::types::{FileMetadata, Fingerprint, HasherOutT, StorageProofsMerkleTrieLayout};
+use shc_forest_manager::{
+    in_memory::InMemoryForestStorage,
+    rocksdb::{RocksDBForestStorage, StorageDb},
+    set_proof_generation_threads,
+    traits::ForestStorage,
+};
+use sp_core::{blake2_256, H256};
+
+/// Amount of files in the benchmarked forests.
+const FOREST_SIZE: u64 = 100_000;
+/// Amounts of challenged keys to generate a proof for.
+const CHALLENGES: [usize; 4] = [16, 64, 256, 1024];
+/// Amount of proofs generated for each measurement, to average over.
+const ITERATIONS: u32 = 10;
+
+fn files_metadata(amount: u64) -> Vec<FileMetadata> {
+    (0..amount)
+        .map(|i| FileMetadata {
+            owner: b"owner".to_vec(),
+            bucket_id: b"bucket".to_vec(),
+            location: format!("location/{i}").into_bytes(),
+            file_size: i + 1,
+            fingerprint: Fingerprint::default(),
+        })
+        .collect()
+}
+
+fn challenges(amount: usize) -> Vec<HasherOutT<StorageProofsMerkleTrieLayout>> {
+    (0..amount)
+        .map(|i| H256(blake2_256(&i.to_le_bytes())))
+        .collect()
+}
+
+fn bench_forest<FS: ForestStorage<StorageProofsMerkleTrieLayout>>(name: &str, forest: &FS) {
+    let available_threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let mut threads = vec![1, 2, 4, available_threads];
+    threads.sort();
+    threads.dedup();
+
+    for amount in CHALLENGES {
+        let challenged_keys = challenges(amount);
+
+        for threads in &threads {
+            set_proof_generation_threads(NonZeroUsize::new(*threads));
+
+            let start = Instant::now();
+            for _ in 0..ITERATIONS {
+                forest
+                    .generate_proof(challenged_keys.clone())
+                    .expect("Generating the proof should succeed");
+            }
+            let average: Duration = start.elapsed() / ITERATIONS;
+
+            println!(
+                "{name}: {FOREST_SIZE} files, {amount} challenges, {threads} threads: {average:?}"
+            );
+        }
+    }
+}
+
+fn main() {
+    let files_metadata = files_metadata(FOREST_SIZE);
+
+    let mut in_memory = InMemoryForestStorage::<StorageProofsMerkleTrieLayout>::new();
+    in_memory
+        .insert_files_metadata(&files_metadata)
+        .expect("Inserting the files should succeed");
+    bench_forest("in-memory", &in_memory);
+
+    let storage = StorageDb {
+        db: Arc::new(kvdb_memorydb::create(1)),
+        _phantom: Default::default(),
+    };
+    let mut rocksdb = RocksDBForestStorage::<StorageProofsMerkleTrieLayout, _>::new(storage)
+        .expect("Creating the forest storage should succeed");
+    rocksdb
+        .insert_files_metadata(&files_metadata)
+        .expect("Inserting the files should succeed");
+    bench_forest("rocksdb", &rocksdb);
+}
@@ -1,14 +1,14 @@
 use codec::{Decode, Encode};
 use hash_db::Hasher;
 use shc_common::types::{FileMetadata, HasherOutT};
-use sp_trie::{recorder::Recorder, MemoryDB, TrieDBBuilder, TrieLayout, TrieMut};
-use trie_db::{Trie, TrieDBMutBuilder};
+use sp_trie::{MemoryDB, TrieDBBuilder, TrieLayout, TrieMut};
+use trie_db::{CError, Trie, TrieDBMutBuilder};
 
 use shc_common::types::ForestProof;
 
 use crate::{
     error::{ErrorT, ForestStorageError},
-    prove::prove,
+    prove::{generate_forest_proof, proof_generation_threads},
     traits::ForestStorage,
 };
 
@@ -37,6 +37,7 @@ impl<T: TrieLayout> Clone for InMemoryForestStorage<T> {
 impl<T: TrieLayout> ForestStorage<T> for InMemoryForestStorage<T>
 where
     <T::Hash as Hasher>::Out: TryFrom<[u8; 32]>,
+    CError<T>: Send,
 {
     fn root(&self) -> HasherOutT<T> {
         self.root
@@ -66,34 +67,12 @@ where
         &self,
         challenged_file_keys: Vec<HasherOutT<T>>,
     ) -> Result<ForestProof<T>, ErrorT<T>> {
-        let recorder: Recorder<T::Hash> = Recorder::default();
-
-        // A `TrieRecorder` is needed to create a proof of the "visited" leafs, by the end of this process.
-        let mut trie_recorder = recorder.as_trie_recorder(self.root);
-
-        let trie = TrieDBBuilder::<T>::new(&self.memdb, &self.root)
-            .with_recorder(&mut trie_recorder)
-            .build();
-
-        // Get the proven leaves or leaf
-        let proven = challenged_file_keys
-            .iter()
-            .map(|file_key| prove::<T>(&trie, file_key))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        // Drop the `trie_recorder` to release the `self` and `recorder`
-        drop(trie_recorder);
-
-        // Generate proof
-        let proof = recorder
-            .drain_storage_proof()
-            .to_compact_proof::<T::Hash>(self.root)?;
-
-        Ok(ForestProof {
-            proven,
-            proof,
-            root: self.root,
-        })
+        generate_forest_proof::<T, _>(
+            &self.memdb,
+            self.root,
+            challenged_file_keys,
+            proof_generation_threads(),
+        )
     }
 
     fn insert_files_metadata(
@@ -156,6 +135,21 @@ where
 
         Ok(files)
     }
+
+    fn get_all_files(&self) -> Result<Vec<(HasherOutT<T>, FileMetadata)>, ErrorT<T>> {
+        let trie = TrieDBBuilder::<T>::new(&self.memdb, &self.root).build();
+        let mut files = Vec::new();
+        let mut trie_iter = trie
+            .iter()
+            .map_err(|_| ForestStorageError::FailedToCreateTrieIterator)?;
+
+        while let Some((_, value)) = trie_iter.next().transpose()? {
+            let metadata = FileMetadata::decode(&mut &value[..])?;
+            files.push((metadata.file_key::<T::Hash>(), metadata));
+        }
+
+        Ok(files)
+    }
 }
 
 #[cfg(test)]
@@ -2,8 +2,12 @@ mod error;
 pub mod in_memory;
 pub(crate) mod prove;
 pub mod rocksdb;
+pub mod snapshot;
 pub mod traits;
 pub(crate) mod utils;
+pub mod write_batch;
+
+pub use prove::{proof_generation_threads, set_proof_generation_threads};
 
 #[cfg(test)]
 mod test_utils;
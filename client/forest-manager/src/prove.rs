@@ -1,11 +1,186 @@
-use shc_common::types::{HasherOutT, Leaf, Proven};
-use trie_db::{TrieIterator, TrieLayout};
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use hash_db::HashDBRef;
+use shc_common::types::{ForestProof, HasherOutT, Leaf, Proven};
+use sp_trie::{recorder::Recorder, StorageProof, TrieDBBuilder};
+use trie_db::{CError, DBValue, TrieIterator, TrieLayout};
 
 use crate::{
     error::{ErrorT, ForestStorageError},
     utils::convert_raw_bytes_to_hasher_out,
 };
 
+/// Maximum amount of threads used to generate a single forest proof, `0` meaning one per CPU.
+static PROOF_GENERATION_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Minimum amount of challenged keys for a forest proof to be generated in parallel.
+///
+/// Below this, spawning threads costs more than what is gained by proving the keys concurrently.
+pub(crate) const MIN_KEYS_FOR_PARALLEL_PROOF: usize = 16;
+
+/// Sets the maximum amount of threads used to generate a single forest proof.
+///
+/// If `None`, one thread per available CPU is used, which is the default.
+pub fn set_proof_generation_threads(threads: Option<NonZeroUsize>) {
+    PROOF_GENERATION_THREADS.store(threads.map_or(0, NonZeroUsize::get), Ordering::Relaxed);
+}
+
+/// The maximum amount of threads used to generate a single forest proof.
+pub fn proof_generation_threads() -> usize {
+    match PROOF_GENERATION_THREADS.load(Ordering::Relaxed) {
+        0 => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        threads => threads,
+    }
+}
+
+/// Generates the proof for `challenged_file_keys` in the trie with `root` stored in `db`, using up
+/// to `threads` threads.
+///
+/// The challenged keys are partitioned by the subtree of the root they fall in (i.e. by the first
+/// nibble of the key), and each group of subtrees is proven in its own thread, with its own
+/// recorder. Since every thread reads from the same `db`, which can't be modified while borrowed,
+/// the sub-proofs are all generated from the same state of the trie. They are then merged into a
+/// single compact proof, which contains exactly the same nodes as if the keys were proven one after
+/// the other.
+///
+/// The proven leaves are returned in the same order as `challenged_file_keys`.
+pub(crate) fn generate_forest_proof<T, DB>(
+    db: &DB,
+    root: HasherOutT<T>,
+    challenged_file_keys: Vec<HasherOutT<T>>,
+    threads: usize,
+) -> Result<ForestProof<T>, ErrorT<T>>
+where
+    T: TrieLayout,
+    DB: HashDBRef<T::Hash, DBValue> + Sync,
+    HasherOutT<T>: TryFrom<[u8; 32]>,
+    CError<T>: Send,
+{
+    let groups = partition_by_subtree::<T>(&challenged_file_keys, threads);
+
+    let sub_proofs = if groups.len() <= 1 {
+        let indexes = (0..challenged_file_keys.len()).collect::<Vec<_>>();
+        vec![prove_group::<T, DB>(
+            db,
+            &root,
+            &challenged_file_keys,
+            indexes,
+        )]
+    } else {
+        std::thread::scope(|scope| {
+            let handles = groups
+                .into_iter()
+                .map(|indexes| {
+                    let challenged_file_keys = &challenged_file_keys;
+                    let root = &root;
+                    scope.spawn(move || {
+                        prove_group::<T, DB>(db, root, challenged_file_keys, indexes)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .expect("Proving challenged keys should not panic; qed")
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let mut proven = Vec::with_capacity(challenged_file_keys.len());
+    let mut storage_proofs = Vec::with_capacity(sub_proofs.len());
+    for sub_proof in sub_proofs {
+        let (sub_proven, storage_proof) = sub_proof?;
+        proven.extend(sub_proven);
+        storage_proofs.push(storage_proof);
+    }
+    proven.sort_by_key(|(index, _)| *index);
+
+    let proof = StorageProof::merge(storage_proofs)
+        .to_compact_proof::<T::Hash>(root)
+        .map_err(|_| ForestStorageError::FailedToGenerateCompactProof)?;
+
+    Ok(ForestProof {
+        proven: proven.into_iter().map(|(_, proven)| proven).collect(),
+        proof,
+        root,
+    })
+}
+
+/// Splits `challenged_file_keys` into at most `threads` groups of indexes, so that the keys in each
+/// group fall in different subtrees of the root than the keys in the other groups.
+///
+/// Returns a single group if there are not enough keys to make proving them in parallel worth it.
+fn partition_by_subtree<T: TrieLayout>(
+    challenged_file_keys: &[HasherOutT<T>],
+    threads: usize,
+) -> Vec<Vec<usize>> {
+    if threads <= 1 || challenged_file_keys.len() < MIN_KEYS_FOR_PARALLEL_PROOF {
+        return vec![(0..challenged_file_keys.len()).collect()];
+    }
+
+    // Group the keys by the first nibble, which is the child of the root they are under.
+    let mut subtrees: [Vec<usize>; 16] = Default::default();
+    for (index, file_key) in challenged_file_keys.iter().enumerate() {
+        let first_nibble = file_key.as_ref().first().map_or(0, |byte| byte >> 4);
+        subtrees[first_nibble as usize].push(index);
+    }
+
+    // Assign consecutive subtrees to each group, until it has its share of the keys.
+    let keys_per_group = challenged_file_keys.len().div_ceil(threads);
+    let mut groups = vec![Vec::new()];
+    for subtree in subtrees.into_iter().filter(|subtree| !subtree.is_empty()) {
+        let group = groups
+            .last_mut()
+            .expect("There is always at least one group; qed");
+        if group.len() >= keys_per_group {
+            groups.push(subtree);
+        } else {
+            group.extend(subtree);
+        }
+    }
+
+    groups
+}
+
+/// Proves the challenged keys at `indexes`, recording the nodes visited in their own storage proof.
+fn prove_group<T, DB>(
+    db: &DB,
+    root: &HasherOutT<T>,
+    challenged_file_keys: &[HasherOutT<T>],
+    indexes: Vec<usize>,
+) -> Result<(Vec<(usize, Proven<HasherOutT<T>, ()>)>, StorageProof), ErrorT<T>>
+where
+    T: TrieLayout,
+    DB: HashDBRef<T::Hash, DBValue>,
+    HasherOutT<T>: TryFrom<[u8; 32]>,
+{
+    let recorder: Recorder<T::Hash> = Recorder::default();
+
+    // A `TrieRecorder` is needed to create a proof of the "visited" leafs, by the end of this process.
+    let mut trie_recorder = recorder.as_trie_recorder(*root);
+
+    let trie = TrieDBBuilder::<T>::new(db, root)
+        .with_recorder(&mut trie_recorder)
+        .build();
+
+    let proven = indexes
+        .into_iter()
+        .map(|index| Ok((index, prove::<T>(&trie, &challenged_file_keys[index])?)))
+        .collect::<Result<Vec<_>, ErrorT<T>>>()?;
+
+    // Drop the `trie_recorder` to release the `recorder`
+    drop(trie_recorder);
+
+    Ok((proven, recorder.drain_storage_proof()))
+}
+
 /// Determines the presence and relationship of a challenged file key within a trie structure,
 /// by attempting to find leaves that are exact matches or close neighbors to the challenged key.
 ///
@@ -93,6 +268,70 @@ mod tests {
     use sp_trie::LayoutV1;
     use trie_db::TrieDBBuilder;
 
+    #[test]
+    fn test_parallel_proof_matches_sequential_proof() {
+        let (memdb, root, mut keys) = build_merkle_patricia_forest::<LayoutV1<RefHasher>>();
+        assert!(keys.len() >= MIN_KEYS_FOR_PARALLEL_PROOF);
+
+        // Challenge keys that are not in the trie as well.
+        keys.extend(keys.clone().iter().map(|key| {
+            let mut challenge_key = *key;
+            challenge_key[31] ^= 0xff;
+            challenge_key
+        }));
+
+        let sequential =
+            generate_forest_proof::<LayoutV1<RefHasher>, _>(&memdb, root, keys.clone(), 1).unwrap();
+        let parallel =
+            generate_forest_proof::<LayoutV1<RefHasher>, _>(&memdb, root, keys.clone(), 4).unwrap();
+
+        assert_eq!(parallel.proof, sequential.proof);
+        assert_eq!(parallel.proven.len(), keys.len());
+        for (parallel_proven, sequential_proven) in
+            parallel.proven.iter().zip(sequential.proven.iter())
+        {
+            assert_eq!(
+                format!("{:?}", parallel_proven),
+                format!("{:?}", sequential_proven)
+            );
+        }
+    }
+
+    #[test]
+    fn test_partition_by_subtree_does_not_split_subtrees() {
+        let (_, _, keys) = build_merkle_patricia_forest::<LayoutV1<RefHasher>>();
+
+        let groups = partition_by_subtree::<LayoutV1<RefHasher>>(&keys, 4);
+        assert!(groups.len() > 1 && groups.len() <= 4);
+
+        let mut indexes = groups.iter().flatten().copied().collect::<Vec<_>>();
+        indexes.sort();
+        assert_eq!(indexes, (0..keys.len()).collect::<Vec<_>>());
+
+        let first_nibbles = |group: &Vec<usize>| {
+            group
+                .iter()
+                .map(|index| keys[*index][0] >> 4)
+                .collect::<std::collections::BTreeSet<_>>()
+        };
+        for (i, group) in groups.iter().enumerate() {
+            for other_group in &groups[i + 1..] {
+                assert!(first_nibbles(group).is_disjoint(&first_nibbles(other_group)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_partition_by_subtree_few_keys_single_group() {
+        let (_, _, keys) = build_merkle_patricia_forest::<LayoutV1<RefHasher>>();
+        let keys = &keys[..MIN_KEYS_FOR_PARALLEL_PROOF - 1];
+
+        assert_eq!(
+            partition_by_subtree::<LayoutV1<RefHasher>>(keys, 4),
+            vec![(0..keys.len()).collect::<Vec<_>>()]
+        );
+    }
+
     #[test]
     fn test_prove_challenge_exact_key_match() {
         let (memdb, root, keys) = build_merkle_patricia_forest::<LayoutV1<RefHasher>>();
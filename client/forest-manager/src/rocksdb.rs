@@ -4,28 +4,55 @@ use kvdb::{DBTransaction, KeyValueDB};
 use log::debug;
 use shc_common::types::{FileMetadata, ForestProof, HashT, HasherOutT};
 use sp_state_machine::{warn, Storage};
-use sp_trie::{
-    prefixed_key, recorder::Recorder, PrefixedMemoryDB, TrieDBBuilder, TrieLayout, TrieMut,
-};
+use sp_trie::{prefixed_key, PrefixedMemoryDB, TrieDBBuilder, TrieLayout, TrieMut};
 use std::{
     fs, io,
     path::{Path, PathBuf},
     sync::Arc,
 };
-use trie_db::{DBValue, Trie, TrieDBMutBuilder};
+use trie_db::{CError, DBValue, Trie, TrieDBMutBuilder};
 
 use crate::{
     error::{ErrorT, ForestStorageError},
-    prove::prove,
+    prove::{generate_forest_proof, proof_generation_threads},
     traits::ForestStorage,
     utils::convert_raw_bytes_to_hasher_out,
     LOG_TARGET,
 };
 
-mod well_known_keys {
+pub mod well_known_keys {
+    /// Key under which the root of the forest is stored.
     pub const ROOT: &[u8] = b":root";
 }
 
+/// Path of a Forest Storage database, relative to its storage path.
+pub const FOREST_STORAGE_DB_PATH: &str = "storagehub/forest_storage/";
+
+/// Number of columns of a Forest Storage database.
+pub const FOREST_STORAGE_DB_COLUMNS: u32 = 1;
+
+/// Default maximum size of the trie nodes kept in memory by a write batch before they are written
+/// to the database, in bytes.
+pub const DEFAULT_FOREST_WRITE_CACHE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Configuration of a [`RocksDBForestStorage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForestStorageConfig {
+    /// Maximum size of the trie nodes kept in memory by a write batch before they are written to
+    /// the database, in bytes.
+    ///
+    /// With `0`, the changes of every mutation are written right away, even within a write batch.
+    pub write_cache_size: usize,
+}
+
+impl Default for ForestStorageConfig {
+    fn default() -> Self {
+        Self {
+            write_cache_size: DEFAULT_FOREST_WRITE_CACHE_SIZE,
+        }
+    }
+}
+
 pub(crate) fn other_io_error(err: String) -> io::Error {
     io::Error::new(io::ErrorKind::Other, err)
 }
@@ -72,9 +99,9 @@ where
 fn open_or_creating_rocksdb(db_path: String) -> io::Result<kvdb_rocksdb::Database> {
     let mut path = PathBuf::new();
     path.push(db_path.as_str());
-    path.push("storagehub/forest_storage/");
+    path.push(FOREST_STORAGE_DB_PATH);
 
-    let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(1);
+    let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(FOREST_STORAGE_DB_COLUMNS);
 
     let path_str = path
         .to_str()
@@ -166,6 +193,13 @@ where
     overlay: PrefixedMemoryDB<HashT<T>>,
     /// Root hash of the forest.
     root: HasherOutT<T>,
+    /// Configuration of the forest.
+    config: ForestStorageConfig,
+    /// Whether a write batch is open, in which case mutations are only committed once the batch is
+    /// flushed, or once the overlay grows beyond [`ForestStorageConfig::write_cache_size`].
+    batching: bool,
+    /// Approximate size of the trie nodes written to the overlay since the last commit.
+    dirty_bytes: usize,
 }
 
 impl<T, DB> RocksDBForestStorage<T, DB>
@@ -177,6 +211,14 @@ where
     /// This will open the RocksDB database and read the storage [`ROOT`](`well_known_keys::ROOT`) from it.
     /// If the root hash is not found in storage, a new trie will be created and the root hash will be stored in storage.
     pub fn new(storage: StorageDb<T, DB>) -> Result<Self, ErrorT<T>> {
+        Self::with_config(storage, ForestStorageConfig::default())
+    }
+
+    /// Same as [`RocksDBForestStorage::new`], with the given configuration.
+    pub fn with_config(
+        storage: StorageDb<T, DB>,
+        config: ForestStorageConfig,
+    ) -> Result<Self, ErrorT<T>> {
         let maybe_root = storage.storage_root()?;
 
         let rocksdb_forest_storage = match maybe_root {
@@ -187,6 +229,9 @@ where
                     storage,
                     overlay: Default::default(),
                     root,
+                    config,
+                    batching: false,
+                    dirty_bytes: 0,
                 }
             }
             None => {
@@ -198,6 +243,9 @@ where
                     storage,
                     overlay: Default::default(),
                     root,
+                    config,
+                    batching: false,
+                    dirty_bytes: 0,
                 };
 
                 // Create a new trie
@@ -249,20 +297,33 @@ where
 
         // Write the changes to storage
         self.storage.write(transaction)?;
+        self.dirty_bytes = 0;
 
         debug!(target: LOG_TARGET, "Committed changes to storage, new root: {:?}", self.root);
 
         Ok(())
     }
 
+    /// Commit the changes of a mutation, unless a write batch is open and the overlay is still
+    /// within [`ForestStorageConfig::write_cache_size`].
+    fn commit_unless_batching(&mut self) -> Result<(), ErrorT<T>> {
+        if self.batching && self.dirty_bytes < self.config.write_cache_size {
+            return Ok(());
+        }
+
+        self.commit()
+    }
+
     /// Build [`DBTransaction`] from the overlay and clear it.
     fn changes(&mut self) -> DBTransaction {
         let mut transaction = DBTransaction::new();
 
         for (key, (value, rc)) in self.overlay.drain() {
-            if rc <= 0 {
+            // A node removed and inserted back (e.g. a file deleted and inserted again within a
+            // write batch) is left as it is in storage.
+            if rc < 0 {
                 transaction.delete(0, &key);
-            } else {
+            } else if rc > 0 {
                 transaction.put_vec(0, &key, value);
             }
         }
@@ -305,10 +366,12 @@ where
     }
 
     fn insert(&mut self, prefix: Prefix, value: &[u8]) -> HasherOutT<T> {
+        self.dirty_bytes += value.len();
         HashDB::insert(&mut self.overlay, prefix, value)
     }
 
     fn emplace(&mut self, key: HasherOutT<T>, prefix: Prefix, value: DBValue) {
+        self.dirty_bytes += value.len();
         HashDB::emplace(&mut self.overlay, key, prefix, value)
     }
 
@@ -317,11 +380,27 @@ where
     }
 }
 
+impl<T, DB> hash_db::HashDBRef<HashT<T>, DBValue> for RocksDBForestStorage<T, DB>
+where
+    T: TrieLayout + Send + Sync,
+    DB: KeyValueDB,
+    HasherOutT<T>: TryFrom<[u8; 32]>,
+{
+    fn get(&self, key: &HasherOutT<T>, prefix: Prefix) -> Option<DBValue> {
+        HashDB::get(self, key, prefix)
+    }
+
+    fn contains(&self, key: &HasherOutT<T>, prefix: Prefix) -> bool {
+        HashDB::contains(self, key, prefix)
+    }
+}
+
 impl<T, DB> ForestStorage<T> for RocksDBForestStorage<T, DB>
 where
     T: TrieLayout + Send + Sync + 'static,
     DB: KeyValueDB + 'static,
     HasherOutT<T>: TryFrom<[u8; 32]>,
+    CError<T>: Send,
 {
     fn root(&self) -> HasherOutT<T> {
         self.root
@@ -353,36 +432,12 @@ where
         &self,
         challenged_file_keys: Vec<HasherOutT<T>>,
     ) -> Result<ForestProof<T>, ErrorT<T>> {
-        let recorder: Recorder<T::Hash> = Recorder::default();
-
-        // A `TrieRecorder` is needed to create a proof of the "visited" leafs, by the end of this process.
-        let mut trie_recorder = recorder.as_trie_recorder(self.root);
-
-        let db = self.as_hash_db();
-        let trie = TrieDBBuilder::<T>::new(&db, &self.root)
-            .with_recorder(&mut trie_recorder)
-            .build();
-
-        // Get the proven leaves or leaf
-        let proven = challenged_file_keys
-            .iter()
-            .map(|file_key| prove::<_>(&trie, file_key))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        // Drop the `trie_recorder` to release the `self` and `recorder`
-        drop(trie_recorder);
-
-        // Generate proof
-        let proof = recorder
-            .drain_storage_proof()
-            .to_compact_proof::<T::Hash>(self.root)
-            .map_err(|_| ForestStorageError::FailedToGenerateCompactProof)?;
-
-        Ok(ForestProof {
-            proven,
-            proof,
-            root: self.root,
-        })
+        generate_forest_proof::<T, _>(
+            self,
+            self.root,
+            challenged_file_keys,
+            proof_generation_threads(),
+        )
     }
 
     fn insert_files_metadata(
@@ -419,7 +474,7 @@ where
 
         // Update the root and commit changes
         self.root = root;
-        self.commit()?;
+        self.commit_unless_batching()?;
 
         Ok(file_keys)
     }
@@ -439,11 +494,26 @@ where
         self.root = root;
 
         // Commit the changes to disk.
-        self.commit()?;
+        self.commit_unless_batching()?;
 
         Ok(())
     }
 
+    fn begin_write_batch(&mut self) {
+        self.batching = true;
+    }
+
+    fn flush_write_batch(&mut self) -> Result<(), ErrorT<T>> {
+        self.batching = false;
+
+        // Nothing to write if the mutations of the batch were already committed, or cancelled out.
+        if self.storage.storage_root()? == Some(self.root) {
+            return Ok(());
+        }
+
+        self.commit()
+    }
+
     fn get_files_by_user(
         &self,
         user: &sp_runtime::AccountId32,
@@ -465,6 +535,22 @@ where
 
         Ok(files)
     }
+
+    fn get_all_files(&self) -> Result<Vec<(HasherOutT<T>, FileMetadata)>, ErrorT<T>> {
+        let db = self.as_hash_db();
+        let trie = TrieDBBuilder::<T>::new(&db, &self.root).build();
+        let mut files = Vec::new();
+        let mut trie_iter = trie
+            .iter()
+            .map_err(|_| ForestStorageError::FailedToCreateTrieIterator)?;
+
+        while let Some((_, value)) = trie_iter.next().transpose()? {
+            let metadata = FileMetadata::decode(&mut &value[..])?;
+            files.push((metadata.file_key::<T::Hash>(), metadata));
+        }
+
+        Ok(files)
+    }
 }
 
 #[cfg(test)]
@@ -482,8 +568,11 @@ mod tests {
     use sp_core::H256;
     use sp_runtime::traits::BlakeTwo256;
     use sp_trie::LayoutV1;
+    use tokio::sync::RwLock;
     use trie_db::Trie;
 
+    use crate::write_batch::ForestWriteBatch;
+
     // Reusable function to setup a new `StorageDb` and `RocksDBForestStorage`.
     fn setup_storage<T, DB>() -> Result<RocksDBForestStorage<T, InMemory>, ErrorT<T>>
     where
@@ -856,4 +945,156 @@ mod tests {
             assert!(!forest_storage.contains_file_key(&key).unwrap());
         }
     }
+
+    fn files_metadata(count: u64) -> Vec<FileMetadata> {
+        (0..count)
+            .map(|i| FileMetadata {
+                bucket_id: "bucket".as_bytes().to_vec(),
+                location: format!("location_{}", i).into_bytes(),
+                owner: "Alice".as_bytes().to_vec(),
+                file_size: i + 1,
+                fingerprint: Fingerprint::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_batch_has_the_same_root_as_unbatched_mutations() {
+        type Layout = LayoutV1<BlakeTwo256>;
+        let files_metadata = files_metadata(20);
+
+        let mut unbatched = setup_storage::<Layout, InMemory>().unwrap();
+        for file_metadata in &files_metadata {
+            unbatched
+                .insert_files_metadata(&[file_metadata.clone()])
+                .unwrap();
+        }
+        let removed_file_key = files_metadata[3].file_key::<BlakeTwo256>();
+        unbatched.delete_file_key(&removed_file_key).unwrap();
+
+        let batched = Arc::new(RwLock::new(setup_storage::<Layout, InMemory>().unwrap()));
+        let initial_root = batched.try_read().unwrap().storage.storage_root().unwrap();
+        {
+            let mut batch = ForestWriteBatch::new(batched.clone().try_write_owned().unwrap());
+            for file_metadata in &files_metadata {
+                batch
+                    .insert_files_metadata(&[file_metadata.clone()])
+                    .unwrap();
+            }
+            // A file removed and inserted back within the batch keeps its nodes in storage.
+            batch.delete_file_key(&removed_file_key).unwrap();
+            batch
+                .insert_files_metadata(&[files_metadata[3].clone()])
+                .unwrap();
+            batch.delete_file_key(&removed_file_key).unwrap();
+
+            // Nothing is written to the database until the batch is flushed.
+            assert_eq!(batch.storage.storage_root().unwrap(), initial_root);
+            assert_eq!(batch.root(), unbatched.root());
+
+            // The forest stays locked until the batch is written.
+            assert!(batched.try_read().is_err());
+        }
+
+        let batched = batched.try_read().unwrap();
+        assert_eq!(
+            batched.storage.storage_root().unwrap(),
+            Some(unbatched.root())
+        );
+
+        // The forest reopened from the database has every file of the batch.
+        let reopened = RocksDBForestStorage::<Layout, InMemory>::new(StorageDb {
+            db: batched.storage.db.clone(),
+            _phantom: Default::default(),
+        })
+        .unwrap();
+        assert_eq!(reopened.root(), unbatched.root());
+        assert_eq!(
+            reopened.get_all_files().unwrap(),
+            unbatched.get_all_files().unwrap()
+        );
+        assert_eq!(
+            reopened
+                .generate_proof(vec![files_metadata[5].file_key::<BlakeTwo256>()])
+                .unwrap(),
+            unbatched
+                .generate_proof(vec![files_metadata[5].file_key::<BlakeTwo256>()])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_batch_reinserting_a_committed_file_keeps_its_nodes() {
+        type Layout = LayoutV1<BlakeTwo256>;
+        let files_metadata = files_metadata(5);
+        let file_key = files_metadata[0].file_key::<BlakeTwo256>();
+
+        let mut forest_storage = setup_storage::<Layout, InMemory>().unwrap();
+        forest_storage
+            .insert_files_metadata(&files_metadata[..4])
+            .unwrap();
+        let forest_storage = Arc::new(RwLock::new(forest_storage));
+
+        let mut batch = ForestWriteBatch::new(forest_storage.clone().try_write_owned().unwrap());
+        batch.delete_file_key(&file_key).unwrap();
+        batch
+            .insert_files_metadata(&[files_metadata[0].clone()])
+            .unwrap();
+        batch
+            .insert_files_metadata(&[files_metadata[4].clone()])
+            .unwrap();
+        batch.flush().unwrap();
+        let forest_storage = forest_storage.try_read().unwrap();
+        let root = forest_storage.root();
+
+        // Iterating over the reopened forest reads every node, which must all still be in storage.
+        let reopened = RocksDBForestStorage::<Layout, InMemory>::new(StorageDb {
+            db: forest_storage.storage.db.clone(),
+            _phantom: Default::default(),
+        })
+        .unwrap();
+        assert_eq!(reopened.root(), root);
+        assert!(reopened.contains_file_key(&file_key).unwrap());
+        assert_eq!(reopened.get_all_files().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_write_batch_without_write_cache_writes_every_mutation() {
+        type Layout = LayoutV1<BlakeTwo256>;
+        let files_metadata = files_metadata(3);
+
+        let storage = StorageDb {
+            db: Arc::new(kvdb_memorydb::create(1)),
+            _phantom: Default::default(),
+        };
+        let forest_storage = RocksDBForestStorage::<Layout, InMemory>::with_config(
+            storage,
+            ForestStorageConfig {
+                write_cache_size: 0,
+            },
+        )
+        .unwrap();
+        let forest_storage = Arc::new(RwLock::new(forest_storage));
+
+        let mut batch = ForestWriteBatch::new(forest_storage.clone().try_write_owned().unwrap());
+        for file_metadata in &files_metadata {
+            batch
+                .insert_files_metadata(&[file_metadata.clone()])
+                .unwrap();
+
+            // Every mutation is written right away, as if there was no batch.
+            assert_eq!(batch.storage.storage_root().unwrap(), Some(batch.root()));
+        }
+        batch.flush().unwrap();
+
+        assert_eq!(
+            forest_storage
+                .try_read()
+                .unwrap()
+                .get_all_files()
+                .unwrap()
+                .len(),
+            3
+        );
+    }
 }
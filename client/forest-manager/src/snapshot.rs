@@ -0,0 +1,272 @@
+//! Snapshots of a forest, to back it up or migrate it to another machine without copying the raw
+//! database files.
+//!
+//! A snapshot holds the root of the forest and its leaves (the metadata of every file in it),
+//! ordered by file key. The forest is rebuilt from the leaves on import, and the root of the
+//! rebuilt forest must match the one in the snapshot.
+//!
+//! The snapshot file is made of:
+//! - [`FOREST_SNAPSHOT_MAGIC`].
+//! - The version of the format, as a SCALE encoded `u16`. Only [`FOREST_SNAPSHOT_VERSION`] is
+//!   supported.
+//! - The SCALE encoded [`ForestSnapshot`].
+//! - A BLAKE2-256 checksum of everything before it.
+
+use std::{fs, io, path::Path};
+
+use codec::{Decode, DecodeAll, Encode};
+use shc_common::types::{FileMetadata, HasherOutT, StorageProofsMerkleTrieLayout};
+use sp_core::hashing::blake2_256;
+
+use crate::{error::ErrorT, in_memory::InMemoryForestStorage, traits::ForestStorage};
+
+/// Magic bytes at the start of every forest snapshot file.
+pub const FOREST_SNAPSHOT_MAGIC: [u8; 4] = *b"SHFS";
+
+/// Version of the forest snapshot format written by this node.
+pub const FOREST_SNAPSHOT_VERSION: u16 = 1;
+
+/// Size of the checksum at the end of a forest snapshot file.
+const CHECKSUM_LEN: usize = 32;
+
+type Root = HasherOutT<StorageProofsMerkleTrieLayout>;
+
+/// Error type for forest snapshots.
+#[derive(thiserror::Error, Debug)]
+pub enum SnapshotError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Codec(#[from] codec::Error),
+    #[error(transparent)]
+    Forest(#[from] ErrorT<StorageProofsMerkleTrieLayout>),
+    #[error("Not a forest snapshot")]
+    InvalidMagic,
+    #[error("Unsupported forest snapshot version {0}")]
+    UnsupportedVersion(u16),
+    #[error("Forest snapshot checksum mismatch, the snapshot is corrupted")]
+    ChecksumMismatch,
+    #[error("Forest root mismatch: snapshot root {expected:?}, rebuilt root {actual:?}")]
+    RootMismatch { expected: Root, actual: Root },
+    #[error("The forest to import the snapshot into is not empty")]
+    ForestNotEmpty,
+    #[error("Forest not found")]
+    ForestNotFound,
+}
+
+/// A snapshot of a forest.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ForestSnapshot {
+    /// Root of the forest.
+    pub root: Root,
+    /// Metadata of every file in the forest, ordered by file key.
+    pub leaves: Vec<FileMetadata>,
+}
+
+/// Summary of an exported or imported [`ForestSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    pub root: Root,
+    pub files: usize,
+}
+
+impl ForestSnapshot {
+    /// Takes a snapshot of `forest`.
+    pub fn from_forest<FS>(forest: &FS) -> Result<Self, SnapshotError>
+    where
+        FS: ForestStorage<StorageProofsMerkleTrieLayout>,
+    {
+        let leaves = forest
+            .get_all_files()?
+            .into_iter()
+            .map(|(_, metadata)| metadata)
+            .collect();
+
+        Ok(Self {
+            root: forest.root(),
+            leaves,
+        })
+    }
+
+    pub fn summary(&self) -> SnapshotSummary {
+        SnapshotSummary {
+            root: self.root,
+            files: self.leaves.len(),
+        }
+    }
+
+    /// Encodes the snapshot in the snapshot file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = FOREST_SNAPSHOT_MAGIC.to_vec();
+        FOREST_SNAPSHOT_VERSION.encode_to(&mut bytes);
+        self.encode_to(&mut bytes);
+
+        let checksum = blake2_256(&bytes);
+        bytes.extend(checksum);
+        bytes
+    }
+
+    /// Decodes a snapshot in the snapshot file format, checking its checksum.
+    ///
+    /// The root of the snapshot is not verified, see [`Self::verify_root`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        if !bytes.starts_with(&FOREST_SNAPSHOT_MAGIC) {
+            return Err(SnapshotError::InvalidMagic);
+        }
+        if bytes.len() < FOREST_SNAPSHOT_MAGIC.len() + CHECKSUM_LEN {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+
+        let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if blake2_256(content) != checksum {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+
+        let mut input = &content[FOREST_SNAPSHOT_MAGIC.len()..];
+        let version = u16::decode(&mut input)?;
+        if version != FOREST_SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        Ok(Self::decode_all(&mut input)?)
+    }
+
+    /// Writes the snapshot to a file at `path`.
+    ///
+    /// The snapshot is written to a temporary file first, so that a partially written snapshot is
+    /// never left at `path`.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), SnapshotError> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, self.to_bytes())?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+
+    /// Reads the snapshot in the file at `path`, checking its checksum and root.
+    pub fn read_from_file(path: &Path) -> Result<Self, SnapshotError> {
+        let snapshot = Self::from_bytes(&fs::read(path)?)?;
+        snapshot.verify_root()?;
+
+        Ok(snapshot)
+    }
+
+    /// Rebuilds the forest from the leaves of the snapshot, checking that its root matches the
+    /// root of the snapshot.
+    pub fn verify_root(&self) -> Result<(), SnapshotError> {
+        let mut forest = InMemoryForestStorage::<StorageProofsMerkleTrieLayout>::new();
+        forest.insert_files_metadata(&self.leaves)?;
+
+        self.check_root(forest.root())
+    }
+
+    /// Imports the snapshot into `forest`, which must be empty.
+    pub fn import_into<FS>(&self, forest: &mut FS) -> Result<(), SnapshotError>
+    where
+        FS: ForestStorage<StorageProofsMerkleTrieLayout>,
+    {
+        self.verify_root()?;
+
+        let empty_root = InMemoryForestStorage::<StorageProofsMerkleTrieLayout>::new().root();
+        if forest.root() != empty_root {
+            return Err(SnapshotError::ForestNotEmpty);
+        }
+
+        forest.insert_files_metadata(&self.leaves)?;
+
+        self.check_root(forest.root())
+    }
+
+    fn check_root(&self, actual: Root) -> Result<(), SnapshotError> {
+        if actual != self.root {
+            return Err(SnapshotError::RootMismatch {
+                expected: self.root,
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shc_common::types::Fingerprint;
+
+    use super::*;
+
+    fn forest_with_files(count: u8) -> InMemoryForestStorage<StorageProofsMerkleTrieLayout> {
+        let mut forest = InMemoryForestStorage::<StorageProofsMerkleTrieLayout>::new();
+        let files_metadata = (0..count)
+            .map(|i| FileMetadata {
+                owner: "owner".as_bytes().to_vec(),
+                bucket_id: "bucket".as_bytes().to_vec(),
+                location: vec![i],
+                file_size: 1024,
+                fingerprint: Fingerprint::default(),
+            })
+            .collect::<Vec<_>>();
+        forest.insert_files_metadata(&files_metadata).unwrap();
+        forest
+    }
+
+    #[test]
+    fn snapshot_is_imported_with_the_same_root() {
+        let forest = forest_with_files(10);
+        let snapshot = ForestSnapshot::from_forest(&forest).unwrap();
+        assert_eq!(snapshot.summary().files, 10);
+
+        let decoded = ForestSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        assert_eq!(decoded, snapshot);
+
+        let mut imported = InMemoryForestStorage::<StorageProofsMerkleTrieLayout>::new();
+        decoded.import_into(&mut imported).unwrap();
+        assert_eq!(imported.root(), forest.root());
+        assert_eq!(
+            imported.get_all_files().unwrap(),
+            forest.get_all_files().unwrap()
+        );
+    }
+
+    #[test]
+    fn corrupted_snapshot_is_rejected() {
+        let snapshot = ForestSnapshot::from_forest(&forest_with_files(3)).unwrap();
+        let mut bytes = snapshot.to_bytes();
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            ForestSnapshot::from_bytes(&bytes),
+            Err(SnapshotError::ChecksumMismatch)
+        ));
+
+        assert!(matches!(
+            ForestSnapshot::from_bytes(b"not a snapshot"),
+            Err(SnapshotError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn snapshot_with_wrong_root_is_not_imported() {
+        let mut snapshot = ForestSnapshot::from_forest(&forest_with_files(3)).unwrap();
+        snapshot.leaves.pop();
+
+        let mut forest = InMemoryForestStorage::<StorageProofsMerkleTrieLayout>::new();
+        assert!(matches!(
+            snapshot.import_into(&mut forest),
+            Err(SnapshotError::RootMismatch { .. })
+        ));
+        assert!(forest.get_all_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn snapshot_is_not_imported_into_a_non_empty_forest() {
+        let snapshot = ForestSnapshot::from_forest(&forest_with_files(3)).unwrap();
+
+        let mut forest = forest_with_files(1);
+        assert!(matches!(
+            snapshot.import_into(&mut forest),
+            Err(SnapshotError::ForestNotEmpty)
+        ));
+    }
+}
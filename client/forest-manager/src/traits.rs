@@ -1,4 +1,4 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use shc_common::types::{FileMetadata, ForestProof, HasherOutT, StorageProofsMerkleTrieLayout};
@@ -6,7 +6,10 @@ use sp_runtime::AccountId32;
 use tokio::sync::RwLock;
 use trie_db::TrieLayout;
 
-use crate::error::ErrorT;
+use crate::{
+    error::ErrorT,
+    snapshot::{ForestSnapshot, SnapshotError, SnapshotSummary},
+};
 
 /// Forest storage interface to be implemented by the storage providers.
 pub trait ForestStorage<T: TrieLayout>: 'static {
@@ -31,11 +34,24 @@ pub trait ForestStorage<T: TrieLayout>: 'static {
     ) -> Result<Vec<HasherOutT<T>>, ErrorT<T>>;
     /// Delete a file key.
     fn delete_file_key(&mut self, file_key: &HasherOutT<T>) -> Result<(), ErrorT<T>>;
+    /// Keep the changes of the following mutations in memory, instead of writing them to the
+    /// underlying database one mutation at a time, until [`Self::flush_write_batch`].
+    ///
+    /// Prefer a [`ForestWriteBatch`](crate::write_batch::ForestWriteBatch), which flushes the
+    /// batch when dropped. Does nothing for storages that are not persisted.
+    fn begin_write_batch(&mut self) {}
+    /// Write the changes of the mutations since [`Self::begin_write_batch`] to the underlying
+    /// database at once.
+    fn flush_write_batch(&mut self) -> Result<(), ErrorT<T>> {
+        Ok(())
+    }
     /// Get all the files that belong to a particular user.
     fn get_files_by_user(
         &self,
         user: &AccountId32,
     ) -> Result<Vec<(HasherOutT<T>, FileMetadata)>, ErrorT<T>>;
+    /// Get all the files in the forest, ordered by file key.
+    fn get_all_files(&self) -> Result<Vec<(HasherOutT<T>, FileMetadata)>, ErrorT<T>>;
 }
 
 /// Handler to manage file storage instances.
@@ -66,6 +82,36 @@ pub trait ForestStorageHandler {
         dest_key: &Self::Key,
     ) -> Option<Arc<RwLock<Self::FS>>>;
 
+    /// Export the forest storage instance for `key` to a snapshot file at `path`.
+    async fn export_snapshot(
+        &self,
+        key: &Self::Key,
+        path: &Path,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        let forest_storage = self.get(key).await.ok_or(SnapshotError::ForestNotFound)?;
+        let snapshot = ForestSnapshot::from_forest(&*forest_storage.read().await)?;
+        snapshot.write_to_file(path)?;
+
+        Ok(snapshot.summary())
+    }
+
+    /// Import the snapshot file at `path` into the forest storage instance for `key`, creating it
+    /// if it doesn't exist.
+    ///
+    /// The root of the snapshot is verified before importing it, and the forest storage instance
+    /// must be empty.
+    async fn import_snapshot(
+        &mut self,
+        key: &Self::Key,
+        path: &Path,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        let snapshot = ForestSnapshot::read_from_file(path)?;
+        let forest_storage = self.get_or_create(key).await;
+        snapshot.import_into(&mut *forest_storage.write().await)?;
+
+        Ok(snapshot.summary())
+    }
+
     /// Get or create forest storage instance.
     async fn get_or_create(&mut self, key: &Self::Key) -> Arc<RwLock<Self::FS>> {
         if let Some(forest_storage) = self.get(key).await {
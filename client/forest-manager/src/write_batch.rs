@@ -0,0 +1,96 @@
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use log::error;
+use tokio::sync::{OwnedRwLockWriteGuard, RwLock};
+use trie_db::TrieLayout;
+
+use crate::{error::ErrorT, traits::ForestStorage, LOG_TARGET};
+
+/// Write lock on a forest, in which its mutations are batched in memory and written to the
+/// underlying database at once when the lock is released.
+///
+/// Applying several mutations while holding it (e.g. removing every file proven in a checkpoint
+/// challenge) costs a single database write. Since the batch is written before the lock is
+/// released, nobody else can lock the forest while part of its changes are only in memory.
+/// Dereferences to the forest storage. The batch is flushed when dropped, logging any error, or
+/// explicitly with [`ForestWriteBatch::flush`] to handle it.
+pub struct ForestWriteBatch<T, FS>
+where
+    T: TrieLayout,
+    FS: ForestStorage<T> + ?Sized,
+{
+    forest: OwnedRwLockWriteGuard<FS>,
+    flushed: bool,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, FS> ForestWriteBatch<T, FS>
+where
+    T: TrieLayout,
+    FS: ForestStorage<T> + ?Sized,
+{
+    /// Waits for the write lock on `forest`, and opens a write batch on it.
+    pub async fn lock(forest: Arc<RwLock<FS>>) -> Self {
+        Self::new(forest.write_owned().await)
+    }
+
+    /// Opens a write batch on the forest held by the write lock `forest`.
+    pub fn new(mut forest: OwnedRwLockWriteGuard<FS>) -> Self {
+        forest.begin_write_batch();
+
+        Self {
+            forest,
+            flushed: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Writes the batched changes to the underlying database and releases the write lock.
+    pub fn flush(mut self) -> Result<(), ErrorT<T>> {
+        self.flushed = true;
+        self.forest.flush_write_batch()
+    }
+}
+
+impl<T, FS> Deref for ForestWriteBatch<T, FS>
+where
+    T: TrieLayout,
+    FS: ForestStorage<T> + ?Sized,
+{
+    type Target = FS;
+
+    fn deref(&self) -> &Self::Target {
+        &self.forest
+    }
+}
+
+impl<T, FS> DerefMut for ForestWriteBatch<T, FS>
+where
+    T: TrieLayout,
+    FS: ForestStorage<T> + ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.forest
+    }
+}
+
+impl<T, FS> Drop for ForestWriteBatch<T, FS>
+where
+    T: TrieLayout,
+    FS: ForestStorage<T> + ?Sized,
+{
+    fn drop(&mut self) {
+        // The write lock is only released once the fields are dropped, after this.
+        if self.flushed {
+            return;
+        }
+
+        if let Err(e) = self.forest.flush_write_batch() {
+            error!(target: LOG_TARGET, "CRITICAL❗️❗️ Failed to flush the forest write batch: {:?}", e);
+        }
+    }
+}
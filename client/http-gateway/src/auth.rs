@@ -0,0 +1,137 @@
+//! Authentication of the readers of the files of private buckets.
+//!
+//! Readers prove they control an account by signing, with its sr25519 key, the path of the
+//! requested file and an expiration time, sent in the headers of the request:
+//! - [`ACCOUNT_HEADER`]: the account, in SS58 or hex format.
+//! - [`EXPIRES_HEADER`]: the UNIX timestamp (in seconds) after which the signature is rejected.
+//! - [`SIGNATURE_HEADER`]: the hex encoded signature of [`signed_message`].
+//!
+//! Signed requests can be shared as is until they expire, like pre-signed URLs.
+
+use std::str::FromStr;
+
+use sp_core::{
+    sr25519::{Pair as Sr25519Pair, Public, Signature},
+    Pair,
+};
+use storage_hub_runtime::AccountId;
+
+use crate::error::GatewayError;
+
+pub const ACCOUNT_HEADER: &str = "x-storagehub-account";
+pub const EXPIRES_HEADER: &str = "x-storagehub-expires";
+pub const SIGNATURE_HEADER: &str = "x-storagehub-signature";
+
+/// The headers authenticating a reader, as sent in the request.
+#[derive(Debug, Clone, Copy)]
+pub struct Credentials<'a> {
+    pub account: &'a str,
+    pub expires: &'a str,
+    pub signature: &'a str,
+}
+
+/// The message signed by a reader to download the file at `path` until `expires`.
+pub fn signed_message(path: &str, expires: u64) -> Vec<u8> {
+    format!("{}\n{}", path, expires).into_bytes()
+}
+
+/// Verifies that `credentials` were signed for `path` by their account and have not expired as of
+/// `now` (a UNIX timestamp in seconds), returning the account.
+pub fn authenticate(
+    path: &str,
+    credentials: Credentials,
+    now: u64,
+) -> Result<AccountId, GatewayError> {
+    let account = AccountId::from_str(credentials.account)
+        .map_err(|_| GatewayError::InvalidCredentials("malformed account"))?;
+    let expires = credentials
+        .expires
+        .parse::<u64>()
+        .map_err(|_| GatewayError::InvalidCredentials("malformed expiration time"))?;
+    let signature = hex::decode(credentials.signature.trim_start_matches("0x"))
+        .ok()
+        .and_then(|signature| Signature::try_from(signature.as_slice()).ok())
+        .ok_or(GatewayError::InvalidCredentials("malformed signature"))?;
+
+    if expires < now {
+        return Err(GatewayError::InvalidCredentials("signature expired"));
+    }
+
+    let public = Public::from_raw(account.clone().into());
+    if !Sr25519Pair::verify(&signature, signed_message(path, expires), &public) {
+        return Err(GatewayError::InvalidCredentials("invalid signature"));
+    }
+
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::crypto::Ss58Codec;
+
+    use super::*;
+
+    const PATH: &str = "/0x0101010101010101010101010101010101010101010101010101010101010101/a/b";
+
+    fn sign(pair: &Sr25519Pair, path: &str, expires: u64) -> String {
+        hex::encode(pair.sign(&signed_message(path, expires)))
+    }
+
+    #[test]
+    fn valid_signatures_authenticate_their_account() {
+        let pair = Sr25519Pair::from_string("//Alice", None).unwrap();
+        let account = pair.public().to_ss58check();
+        let signature = sign(&pair, PATH, 100);
+
+        let credentials = Credentials {
+            account: &account,
+            expires: "100",
+            signature: &signature,
+        };
+        assert_eq!(
+            authenticate(PATH, credentials, 50).unwrap(),
+            AccountId::from(pair.public())
+        );
+    }
+
+    #[test]
+    fn expired_or_mismatched_signatures_are_rejected() {
+        let pair = Sr25519Pair::from_string("//Alice", None).unwrap();
+        let account = pair.public().to_ss58check();
+        let signature = sign(&pair, PATH, 100);
+        let credentials = Credentials {
+            account: &account,
+            expires: "100",
+            signature: &signature,
+        };
+
+        // Expired.
+        assert!(authenticate(PATH, credentials, 101).is_err());
+        // Signed for another file.
+        assert!(authenticate("/other", credentials, 50).is_err());
+        // Signed until another time.
+        assert!(authenticate(
+            PATH,
+            Credentials {
+                expires: "200",
+                ..credentials
+            },
+            50
+        )
+        .is_err());
+        // Signed by another account.
+        let other_account = Sr25519Pair::from_string("//Bob", None)
+            .unwrap()
+            .public()
+            .to_ss58check();
+        assert!(authenticate(
+            PATH,
+            Credentials {
+                account: &other_account,
+                ..credentials
+            },
+            50
+        )
+        .is_err());
+    }
+}
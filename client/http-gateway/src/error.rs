@@ -0,0 +1,14 @@
+use std::io;
+
+/// Error type for the HTTP Gateway.
+#[derive(thiserror::Error, Debug)]
+pub enum GatewayError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Failed to set up TLS: {0}")]
+    Tls(#[from] tokio_rustls::rustls::Error),
+    #[error("No private key found in {0}")]
+    MissingPrivateKey(String),
+    #[error("Invalid credentials: {0}")]
+    InvalidCredentials(&'static str),
+}
@@ -0,0 +1,27 @@
+//! HTTP(S) Gateway serving the files stored by an MSP to their readers.
+//!
+//! Files are requested at `/<bucket_id>/<location>` and read from the File Storage of the node as
+//! they are sent, with support for single byte ranges (`Range` header). The file is looked up in
+//! the Forest of the bucket, so only the files this MSP stores are served.
+//!
+//! Before serving a file, the read access of the requester is checked on-chain through the
+//! `has_file_read_access` runtime API: every file of a public bucket can be read, while the files
+//! of a private bucket can only be read by its owner, the accounts in the access list of the file,
+//! the holders of an item of the bucket's read-access NFT collection and the grantees of an
+//! unexpired access grant to the bucket or the file. Requesters authenticate
+//! by signing the request with the key of their account. See [`auth`].
+//!
+//! The requests served, bytes sent and downloads in progress are exported as Prometheus metrics.
+//! See [`metrics::GatewayMetrics`].
+
+pub mod auth;
+mod error;
+pub mod metrics;
+pub mod range;
+pub mod service;
+
+pub use error::GatewayError;
+pub use metrics::GatewayMetrics;
+pub use service::{HttpGateway, HttpGatewayConfig, TlsConfig};
+
+const LOG_TARGET: &str = "http-gateway";
@@ -0,0 +1,63 @@
+use substrate_prometheus_endpoint::{
+    register, Counter, CounterVec, Gauge, Opts, PrometheusError, Registry, U64,
+};
+
+/// Prometheus metrics of the files served by the HTTP Gateway.
+#[derive(Clone)]
+pub struct GatewayMetrics {
+    /// Requests served, by HTTP status code.
+    requests: CounterVec<U64>,
+    /// Bytes of files sent to readers.
+    bytes_served: Counter<U64>,
+    /// Downloads in progress.
+    active_downloads: Gauge<U64>,
+}
+
+impl GatewayMetrics {
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            requests: register(
+                CounterVec::new(
+                    Opts::new(
+                        "storagehub_http_gateway_requests_total",
+                        "Requests served by the HTTP Gateway, by status code",
+                    ),
+                    &["status"],
+                )?,
+                registry,
+            )?,
+            bytes_served: register(
+                Counter::new(
+                    "storagehub_http_gateway_bytes_served_total",
+                    "Bytes of files sent by the HTTP Gateway",
+                )?,
+                registry,
+            )?,
+            active_downloads: register(
+                Gauge::new(
+                    "storagehub_http_gateway_active_downloads",
+                    "Downloads in progress from the HTTP Gateway",
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    pub fn request_served(&self, status: u16) {
+        self.requests
+            .with_label_values(&[&status.to_string()])
+            .inc();
+    }
+
+    pub fn bytes_served(&self, bytes: u64) {
+        self.bytes_served.inc_by(bytes);
+    }
+
+    pub fn download_started(&self) {
+        self.active_downloads.inc();
+    }
+
+    pub fn download_finished(&self) {
+        self.active_downloads.dec();
+    }
+}
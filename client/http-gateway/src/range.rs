@@ -0,0 +1,114 @@
+//! Parsing of the `Range` header of download requests.
+
+/// An inclusive range of bytes of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// The amount of bytes in the range.
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// The part of a file requested through the `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestedRange {
+    /// The whole file, if no range was requested or it is not supported (i.e. several ranges, or
+    /// a unit other than bytes), in which case the header is ignored.
+    Full,
+    /// A single range of bytes, within the file.
+    Partial(ByteRange),
+    /// A range of bytes outside the file.
+    Unsatisfiable,
+}
+
+/// Parses the `Range` header `header` of a request for a file of `file_size` bytes.
+///
+/// Only single ranges of bytes are supported: `bytes=start-end`, `bytes=start-` and `bytes=-suffix`.
+/// An end past the end of the file is clamped to it.
+pub fn parse_range(header: Option<&str>, file_size: u64) -> RequestedRange {
+    let Some(spec) = header.and_then(|header| header.trim().strip_prefix("bytes=")) else {
+        return RequestedRange::Full;
+    };
+    if spec.contains(',') {
+        return RequestedRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RequestedRange::Full;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        ("", "") => return RequestedRange::Full,
+        // The last `suffix` bytes of the file.
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return RequestedRange::Unsatisfiable,
+            Ok(suffix) => (file_size.saturating_sub(suffix), file_size.checked_sub(1)),
+            Err(_) => return RequestedRange::Full,
+        },
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) => (start, file_size.checked_sub(1)),
+            Err(_) => return RequestedRange::Full,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => {
+                (start, file_size.checked_sub(1).map(|last| end.min(last)))
+            }
+            _ => return RequestedRange::Full,
+        },
+    };
+
+    match range {
+        (start, Some(end)) if start <= end => RequestedRange::Partial(ByteRange { start, end }),
+        _ => RequestedRange::Unsatisfiable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(start: u64, end: u64) -> RequestedRange {
+        RequestedRange::Partial(ByteRange { start, end })
+    }
+
+    #[test]
+    fn single_byte_ranges_are_parsed() {
+        assert_eq!(parse_range(Some("bytes=0-99"), 1000), partial(0, 99));
+        assert_eq!(parse_range(Some("bytes=500-"), 1000), partial(500, 999));
+        assert_eq!(parse_range(Some("bytes=-100"), 1000), partial(900, 999));
+        assert_eq!(parse_range(Some("bytes=-2000"), 1000), partial(0, 999));
+        assert_eq!(parse_range(Some("bytes=900-2000"), 1000), partial(900, 999));
+    }
+
+    #[test]
+    fn missing_or_unsupported_ranges_request_the_full_file() {
+        assert_eq!(parse_range(None, 1000), RequestedRange::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 1000), RequestedRange::Full);
+        assert_eq!(
+            parse_range(Some("bytes=0-1,5-6"), 1000),
+            RequestedRange::Full
+        );
+        assert_eq!(parse_range(Some("bytes=5-1"), 1000), RequestedRange::Full);
+        assert_eq!(parse_range(Some("bytes=a-"), 1000), RequestedRange::Full);
+    }
+
+    #[test]
+    fn ranges_outside_the_file_are_unsatisfiable() {
+        assert_eq!(
+            parse_range(Some("bytes=1000-"), 1000),
+            RequestedRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-0"), 1000),
+            RequestedRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=0-"), 0),
+            RequestedRange::Unsatisfiable
+        );
+    }
+}
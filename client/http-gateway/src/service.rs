@@ -0,0 +1,511 @@
+use std::{
+    convert::Infallible,
+    fs::File,
+    io::{self, BufReader},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::stream;
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Empty, Full, StreamBody};
+use hyper::{
+    body::{Bytes, Frame, Incoming},
+    header::{self, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use log::{debug, error, info, warn};
+use sp_core::H256;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+    sync::RwLock,
+};
+use tokio_rustls::{
+    rustls::{crypto::ring, ServerConfig},
+    TlsAcceptor,
+};
+
+use shc_blockchain_service::commands::BlockchainServiceInterface;
+use shc_common::types::{ChunkId, FileMetadata, StorageProofsMerkleTrieLayout, FILE_CHUNK_SIZE};
+use shc_file_manager::traits::FileStorage;
+use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use storage_hub_runtime::AccountId;
+
+use crate::{
+    auth::{authenticate, Credentials, ACCOUNT_HEADER, EXPIRES_HEADER, SIGNATURE_HEADER},
+    error::GatewayError,
+    metrics::GatewayMetrics,
+    range::{parse_range, ByteRange, RequestedRange},
+    LOG_TARGET,
+};
+
+/// Amount of file chunks read from the File Storage at once, and sent in a single frame.
+const CHUNKS_PER_FRAME: u64 = 64;
+
+type GatewayBody = UnsyncBoxBody<Bytes, io::Error>;
+
+/// Certificate and private key to serve the HTTP Gateway over TLS, in PEM format.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// Configuration of the HTTP Gateway.
+#[derive(Clone)]
+pub struct HttpGatewayConfig {
+    /// Address to listen for HTTP(S) requests on.
+    pub listen_addr: SocketAddr,
+    /// TLS certificate and key, to serve HTTPS instead of plain HTTP.
+    pub tls: Option<TlsConfig>,
+    /// Prometheus metrics of the files served, if the node exports metrics.
+    pub metrics: Option<GatewayMetrics>,
+}
+
+/// Serves the files stored by this MSP over HTTP(S), at `/<bucket_id>/<location>`.
+///
+/// The bucket ID is hex encoded, and the location of the file is percent-encoded. Readers of the
+/// files of private buckets authenticate as an account (see [`crate::auth`]), whose read access is
+/// checked on-chain. Anonymous readers are checked as an account no one controls, so they can only
+/// read the files of public buckets.
+pub struct HttpGateway<BS, FL, FSH> {
+    blockchain: BS,
+    file_storage: Arc<RwLock<FL>>,
+    forest_storage_handler: FSH,
+    config: HttpGatewayConfig,
+}
+
+impl<BS, FL, FSH> HttpGateway<BS, FL, FSH>
+where
+    BS: BlockchainServiceInterface + Send + Sync + 'static,
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+    FSH: ForestStorageHandler + Send + Sync + 'static,
+{
+    pub fn new(
+        blockchain: BS,
+        file_storage: Arc<RwLock<FL>>,
+        forest_storage_handler: FSH,
+        config: HttpGatewayConfig,
+    ) -> Self {
+        Self {
+            blockchain,
+            file_storage,
+            forest_storage_handler,
+            config,
+        }
+    }
+
+    /// Serves requests until the node shuts down.
+    pub async fn run(self) {
+        let tls_acceptor = match self.config.tls.as_ref().map(tls_acceptor).transpose() {
+            Ok(tls_acceptor) => tls_acceptor,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to start the HTTP Gateway: {}", e);
+                return;
+            }
+        };
+
+        let listener = match TcpListener::bind(self.config.listen_addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to start the HTTP Gateway on {}: {}", self.config.listen_addr, e);
+                return;
+            }
+        };
+
+        info!(
+            target: LOG_TARGET,
+            "🌐 HTTP Gateway listening on {}{}",
+            self.config.listen_addr,
+            if tls_acceptor.is_some() { " (TLS)" } else { "" }
+        );
+
+        let gateway = Arc::new(self);
+        loop {
+            let (stream, peer_addr) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Failed to accept HTTP Gateway connection: {}", e);
+                    continue;
+                }
+            };
+
+            let gateway = gateway.clone();
+            let tls_acceptor = tls_acceptor.clone();
+            tokio::spawn(async move {
+                match tls_acceptor {
+                    Some(tls_acceptor) => match tls_acceptor.accept(stream).await {
+                        Ok(stream) => gateway.serve_connection(stream, peer_addr).await,
+                        Err(e) => {
+                            debug!(target: LOG_TARGET, "TLS handshake with {} failed: {}", peer_addr, e)
+                        }
+                    },
+                    None => gateway.serve_connection(stream, peer_addr).await,
+                }
+            });
+        }
+    }
+
+    async fn serve_connection<I>(self: Arc<Self>, io: I, peer_addr: SocketAddr)
+    where
+        I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let service = service_fn(move |request| {
+            let gateway = self.clone();
+            async move { Ok::<_, Infallible>(gateway.handle(request).await) }
+        });
+
+        if let Err(e) = http1::Builder::new()
+            .serve_connection(TokioIo::new(io), service)
+            .await
+        {
+            debug!(target: LOG_TARGET, "HTTP Gateway connection with {} failed: {}", peer_addr, e);
+        }
+    }
+
+    async fn handle(self: Arc<Self>, request: Request<Incoming>) -> Response<GatewayBody> {
+        let response = match self.clone().serve_file(&request).await {
+            Ok(response) => response,
+            Err((status, message)) => {
+                debug!(target: LOG_TARGET, "{} {} -> {}: {}", request.method(), request.uri().path(), status, message);
+                text_response(status, message)
+            }
+        };
+
+        if let Some(metrics) = &self.config.metrics {
+            metrics.request_served(response.status().as_u16());
+        }
+        response
+    }
+
+    async fn serve_file(
+        self: Arc<Self>,
+        request: &Request<Incoming>,
+    ) -> Result<Response<GatewayBody>, (StatusCode, String)> {
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            return Err((
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Only GET and HEAD requests are supported".to_string(),
+            ));
+        }
+
+        let path = request.uri().path();
+        let (bucket_id, location) = parse_path(path).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                "Expected a path like /<bucket_id>/<location>".to_string(),
+            )
+        })?;
+
+        let (file_key, file_metadata) = self
+            .find_file(bucket_id, &location)
+            .await
+            .ok_or_else(|| (StatusCode::NOT_FOUND, "File not found".to_string()))?;
+
+        let account = match credentials(request) {
+            Some(credentials) => authenticate(path, credentials, unix_time())
+                .map_err(|e| (StatusCode::UNAUTHORIZED, e.to_string()))?,
+            None => AccountId::new([0; 32]),
+        };
+        let has_read_access = self
+            .blockchain
+            .query_has_file_read_access(bucket_id, file_key, account)
+            .await
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "Failed to check the read access to file {:?}: {:?}", file_key, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to check the read access to the file".to_string(),
+                )
+            })?;
+        if !has_read_access {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "No read access to the file".to_string(),
+            ));
+        }
+
+        let stored_chunks = self
+            .file_storage
+            .read()
+            .await
+            .stored_chunks_count(&file_key)
+            .unwrap_or_default();
+        if stored_chunks < file_metadata.chunks_count() {
+            return Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                "File is not fully stored yet".to_string(),
+            ));
+        }
+
+        let file_size = file_metadata.file_size;
+        let range_header = request
+            .headers()
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok());
+        let (status, range) = match parse_range(range_header, file_size) {
+            RequestedRange::Full => (
+                StatusCode::OK,
+                file_size
+                    .checked_sub(1)
+                    .map(|end| ByteRange { start: 0, end }),
+            ),
+            RequestedRange::Partial(range) => (StatusCode::PARTIAL_CONTENT, Some(range)),
+            RequestedRange::Unsatisfiable => {
+                let mut response = text_response(
+                    StatusCode::RANGE_NOT_SATISFIABLE,
+                    "Requested range is outside the file".to_string(),
+                );
+                response.headers_mut().insert(
+                    header::CONTENT_RANGE,
+                    header_value(format!("bytes */{}", file_size)),
+                );
+                return Ok(response);
+            }
+        };
+
+        let body = match range {
+            Some(range) if request.method() == Method::GET => self.download(file_key, range),
+            _ => empty_body(),
+        };
+        let mut response = Response::new(body);
+        *response.status_mut() = status;
+        let headers = response.headers_mut();
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        headers.insert(
+            header::CONTENT_LENGTH,
+            header_value(range.map(|range| range.size()).unwrap_or_default()),
+        );
+        if let (StatusCode::PARTIAL_CONTENT, Some(range)) = (status, range) {
+            headers.insert(
+                header::CONTENT_RANGE,
+                header_value(format!("bytes {}-{}/{}", range.start, range.end, file_size)),
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Finds the file at `location` in the Forest of `bucket_id`, if this MSP stores it.
+    async fn find_file(&self, bucket_id: H256, location: &[u8]) -> Option<(H256, FileMetadata)> {
+        let forest_key: FSH::Key = bucket_id.as_ref().to_vec().into();
+        let forest_storage = self.forest_storage_handler.get(&forest_key).await?;
+        let files = match forest_storage.read().await.get_all_files() {
+            Ok(files) => files,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to read the files of bucket {:?}: {:?}", bucket_id, e);
+                return None;
+            }
+        };
+
+        files
+            .into_iter()
+            .find(|(_, file_metadata)| file_metadata.location == location)
+    }
+
+    /// Streams the bytes of `range` of the file `file_key`, read from the File Storage as they are
+    /// sent.
+    fn download(self: Arc<Self>, file_key: H256, range: ByteRange) -> GatewayBody {
+        let download = Download::new(self.config.metrics.clone());
+
+        let frames = stream::unfold(
+            (self, download, range.start),
+            move |(gateway, download, next)| async move {
+                if next > range.end {
+                    return None;
+                }
+
+                match gateway.read_frame(&file_key, next, range.end).await {
+                    Ok(bytes) => {
+                        download.sent(bytes.len() as u64);
+                        let next = next + bytes.len() as u64;
+                        Some((Ok(Frame::data(bytes)), (gateway, download, next)))
+                    }
+                    Err(e) => {
+                        error!(target: LOG_TARGET, "Failed to read file {:?} to serve it: {}", file_key, e);
+                        // Ends the stream after the error, which aborts the response.
+                        Some((Err(e), (gateway, download, range.end + 1)))
+                    }
+                }
+            },
+        );
+
+        StreamBody::new(frames).boxed_unsync()
+    }
+
+    /// Reads the bytes of the file `file_key` from `start`, up to [`CHUNKS_PER_FRAME`] chunks and
+    /// `end` (inclusive).
+    async fn read_frame(&self, file_key: &H256, start: u64, end: u64) -> io::Result<Bytes> {
+        let first_chunk = start / FILE_CHUNK_SIZE;
+        let last_chunk = (end / FILE_CHUNK_SIZE).min(first_chunk + CHUNKS_PER_FRAME - 1);
+
+        let file_storage = self.file_storage.read().await;
+        let mut bytes =
+            Vec::with_capacity(((last_chunk - first_chunk + 1) * FILE_CHUNK_SIZE) as usize);
+        for chunk_id in first_chunk..=last_chunk {
+            let chunk = file_storage
+                .get_chunk(file_key, &ChunkId::new(chunk_id))
+                .map_err(|e| io::Error::other(format!("{:?}", e)))?;
+            bytes.extend_from_slice(&chunk);
+        }
+        drop(file_storage);
+
+        let offset = first_chunk * FILE_CHUNK_SIZE;
+        let from = (start - offset) as usize;
+        let to = ((end + 1 - offset) as usize).min(bytes.len());
+        if from >= to {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "file is shorter than its metadata",
+            ));
+        }
+
+        Ok(Bytes::copy_from_slice(&bytes[from..to]))
+    }
+}
+
+/// A download in progress, accounted for in the metrics until it is dropped.
+struct Download {
+    metrics: Option<GatewayMetrics>,
+}
+
+impl Download {
+    fn new(metrics: Option<GatewayMetrics>) -> Self {
+        if let Some(metrics) = &metrics {
+            metrics.download_started();
+        }
+        Self { metrics }
+    }
+
+    fn sent(&self, bytes: u64) {
+        if let Some(metrics) = &self.metrics {
+            metrics.bytes_served(bytes);
+        }
+    }
+}
+
+impl Drop for Download {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.download_finished();
+        }
+    }
+}
+
+fn tls_acceptor(tls: &TlsConfig) -> Result<TlsAcceptor, GatewayError> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(&tls.cert_path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(&tls.key_path)?))?
+        .ok_or_else(|| GatewayError::MissingPrivateKey(tls.key_path.display().to_string()))?;
+
+    let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Splits `path` into the bucket ID and the location of the requested file.
+fn parse_path(path: &str) -> Option<(H256, Vec<u8>)> {
+    let (bucket_id, location) = path.strip_prefix('/')?.split_once('/')?;
+    let bucket_id = hex::decode(bucket_id.trim_start_matches("0x")).ok()?;
+    if bucket_id.len() != H256::len_bytes() || location.is_empty() {
+        return None;
+    }
+
+    Some((H256::from_slice(&bucket_id), percent_decode(location)?))
+}
+
+fn percent_decode(encoded: &str) -> Option<Vec<u8>> {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = encoded.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(byte, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(decoded)
+}
+
+fn credentials(request: &Request<Incoming>) -> Option<Credentials<'_>> {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+
+    Some(Credentials {
+        account: header(ACCOUNT_HEADER)?,
+        expires: header(EXPIRES_HEADER)?,
+        signature: header(SIGNATURE_HEADER)?,
+    })
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
+fn header_value(value: impl ToString) -> HeaderValue {
+    HeaderValue::from_str(&value.to_string()).expect("Numbers and ranges are valid header values")
+}
+
+fn text_response(status: StatusCode, message: String) -> Response<GatewayBody> {
+    let mut response = Response::new(
+        Full::new(Bytes::from(message))
+            .map_err(|never| match never {})
+            .boxed_unsync(),
+    );
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    response
+}
+
+fn empty_body() -> GatewayBody {
+    Empty::new().map_err(|never| match never {}).boxed_unsync()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_split_into_bucket_and_location() {
+        let bucket_id = H256::repeat_byte(1);
+
+        assert_eq!(
+            parse_path(&format!("/{:?}/photos/cat%20%231.png", bucket_id)),
+            Some((bucket_id, b"photos/cat #1.png".to_vec()))
+        );
+        assert_eq!(
+            parse_path(&format!("/{}/a", hex::encode(bucket_id))),
+            Some((bucket_id, b"a".to_vec()))
+        );
+        assert_eq!(parse_path(&format!("/{:?}/", bucket_id)), None);
+        assert_eq!(parse_path(&format!("/{:?}", bucket_id)), None);
+        assert_eq!(parse_path("/0x0101/a"), None);
+        assert_eq!(parse_path(&format!("/{:?}/%zz", bucket_id)), None);
+    }
+}
@@ -0,0 +1,2 @@
+-- Remove slashable column from bsp table
+ALTER TABLE bsp DROP COLUMN slashable;
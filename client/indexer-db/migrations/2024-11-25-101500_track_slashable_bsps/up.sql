@@ -0,0 +1,2 @@
+-- Track whether each BSP is currently slashable, i.e. it missed its last proof submission deadline
+ALTER TABLE bsp ADD COLUMN slashable BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Drop the access_grant table
+DROP TABLE IF EXISTS access_grant;
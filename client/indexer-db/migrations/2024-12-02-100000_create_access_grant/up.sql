@@ -0,0 +1,18 @@
+-- Create Access Grant table
+CREATE TABLE access_grant (
+    id SERIAL PRIMARY KEY,
+    bucket_id INTEGER NOT NULL,
+    -- NULL if the grant gives access to every file of the bucket
+    file_key BYTEA,
+    grantee VARCHAR NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (bucket_id) REFERENCES bucket(id) ON DELETE CASCADE
+);
+
+-- Create index on bucket_id for faster lookups
+CREATE INDEX idx_access_grant_bucket_id ON access_grant(bucket_id);
+
+-- Create index on grantee for faster lookups
+CREATE INDEX idx_access_grant_grantee ON access_grant(grantee);
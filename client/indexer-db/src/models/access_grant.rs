@@ -0,0 +1,100 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use crate::{
+    schema::{access_grant, bucket},
+    DbConnection,
+};
+
+/// Table that holds the access grants given by the owners of private buckets, to the whole bucket
+/// or to a single file of it.
+#[derive(Debug, Queryable, Insertable, Selectable)]
+#[diesel(table_name = access_grant)]
+pub struct AccessGrant {
+    pub id: i32,
+    /// The ID of the bucket (column in the database) the grant gives access to.
+    pub bucket_id: i32,
+    /// The key of the file the grant gives access to, or `None` if it gives access to every file
+    /// of the bucket.
+    pub file_key: Option<Vec<u8>>,
+    /// Account given read access.
+    pub grantee: String,
+    /// Tick from which the grant no longer gives read access.
+    pub expires_at: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl AccessGrant {
+    /// Creates the access grant of `grantee`, or updates its expiration if it already has one to
+    /// the same target.
+    pub async fn upsert<'a>(
+        conn: &mut DbConnection<'a>,
+        bucket_id: i32,
+        file_key: Option<Vec<u8>>,
+        grantee: String,
+        expires_at: i64,
+    ) -> Result<Self, diesel::result::Error> {
+        let updated = diesel::update(access_grant::table)
+            .filter(access_grant::bucket_id.eq(bucket_id))
+            .filter(access_grant::file_key.is_not_distinct_from(file_key.clone()))
+            .filter(access_grant::grantee.eq(grantee.clone()))
+            .set((
+                access_grant::expires_at.eq(expires_at),
+                access_grant::updated_at.eq(diesel::dsl::now),
+            ))
+            .returning(AccessGrant::as_select())
+            .get_result(conn)
+            .await
+            .optional()?;
+        if let Some(grant) = updated {
+            return Ok(grant);
+        }
+
+        let grant = diesel::insert_into(access_grant::table)
+            .values((
+                access_grant::bucket_id.eq(bucket_id),
+                access_grant::file_key.eq(file_key),
+                access_grant::grantee.eq(grantee),
+                access_grant::expires_at.eq(expires_at),
+            ))
+            .returning(AccessGrant::as_select())
+            .get_result(conn)
+            .await?;
+        Ok(grant)
+    }
+
+    /// Deletes the access grant of `grantee` to the bucket `onchain_bucket_id`, or to its file
+    /// `file_key`, if any.
+    pub async fn delete<'a>(
+        conn: &mut DbConnection<'a>,
+        onchain_bucket_id: Vec<u8>,
+        file_key: Option<Vec<u8>>,
+        grantee: String,
+    ) -> Result<(), diesel::result::Error> {
+        let bucket_ids = bucket::table
+            .filter(bucket::onchain_bucket_id.eq(onchain_bucket_id))
+            .select(bucket::id);
+        diesel::delete(access_grant::table)
+            .filter(access_grant::bucket_id.eq_any(bucket_ids))
+            .filter(access_grant::file_key.is_not_distinct_from(file_key))
+            .filter(access_grant::grantee.eq(grantee))
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// The access grants given to `grantee`, by ascending expiration.
+    pub async fn list_by_grantee<'a>(
+        conn: &mut DbConnection<'a>,
+        grantee: String,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let grants = access_grant::table
+            .filter(access_grant::grantee.eq(grantee))
+            .order(access_grant::expires_at.asc())
+            .load(conn)
+            .await?;
+        Ok(grants)
+    }
+}
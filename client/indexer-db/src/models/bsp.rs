@@ -4,7 +4,7 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
 use crate::{
-    models::multiaddress::MultiAddress,
+    models::{multiaddress::MultiAddress, Pagination},
     schema::{bsp, bsp_file, bsp_multiaddress, file},
     DbConnection,
 };
@@ -24,6 +24,9 @@ pub struct Bsp {
     pub updated_at: NaiveDateTime,
     pub onchain_bsp_id: String,
     pub merkle_root: Vec<u8>,
+    /// Whether the BSP missed its last proof submission deadline and has not submitted an accepted
+    /// proof since.
+    pub slashable: bool,
 }
 
 /// Association table between BSP and MultiAddress
@@ -148,6 +151,19 @@ impl Bsp {
         Ok(())
     }
 
+    pub async fn update_slashable<'a>(
+        conn: &mut DbConnection<'a>,
+        onchain_bsp_id: String,
+        slashable: bool,
+    ) -> Result<(), diesel::result::Error> {
+        diesel::update(bsp::table)
+            .filter(bsp::onchain_bsp_id.eq(onchain_bsp_id))
+            .set(bsp::slashable.eq(slashable))
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
     pub async fn update_merkle_root<'a>(
         conn: &mut DbConnection<'a>,
         onchain_bsp_id: String,
@@ -160,6 +176,34 @@ impl Bsp {
             .await?;
         Ok(())
     }
+
+    pub async fn list<'a>(
+        conn: &mut DbConnection<'a>,
+        pagination: Pagination,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let bsps = bsp::table
+            .order(bsp::id.asc())
+            .offset(pagination.offset())
+            .limit(pagination.limit())
+            .load(conn)
+            .await?;
+        Ok(bsps)
+    }
+
+    /// The BSPs storing the file with the given ID (column in the database).
+    pub async fn get_by_file_id<'a>(
+        conn: &mut DbConnection<'a>,
+        file_id: i32,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let bsps = bsp::table
+            .inner_join(bsp_file::table.on(bsp_file::bsp_id.eq(bsp::id)))
+            .filter(bsp_file::file_id.eq(file_id))
+            .order(bsp::id.asc())
+            .select(Bsp::as_select())
+            .load(conn)
+            .await?;
+        Ok(bsps)
+    }
 }
 
 #[derive(Debug, Queryable, Insertable, Selectable)]
@@ -2,7 +2,7 @@ use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
-use crate::{schema::bucket, DbConnection};
+use crate::{models::Pagination, schema::bucket, DbConnection};
 
 /// Table that holds the Buckets.
 #[derive(Debug, Queryable, Insertable, Selectable)]
@@ -23,6 +23,15 @@ pub struct Bucket {
     pub merkle_root: Vec<u8>,
 }
 
+/// Filter for [`Bucket::list`]. Unset fields match any bucket.
+#[derive(Debug, Default, Clone)]
+pub struct BucketFilter {
+    pub account: Option<String>,
+    /// The ID of the MSP (column in the database) storing the bucket.
+    pub msp_id: Option<i32>,
+    pub private: Option<bool>,
+}
+
 impl Bucket {
     pub async fn create<'a>(
         conn: &mut DbConnection<'a>,
@@ -108,6 +117,14 @@ impl Bucket {
         Ok(())
     }
 
+    pub async fn get_by_id<'a>(
+        conn: &mut DbConnection<'a>,
+        id: i32,
+    ) -> Result<Self, diesel::result::Error> {
+        let bucket = bucket::table.find(id).first(conn).await?;
+        Ok(bucket)
+    }
+
     pub async fn get_by_onchain_bucket_id<'a>(
         conn: &mut DbConnection<'a>,
         onchain_bucket_id: Vec<u8>,
@@ -118,4 +135,30 @@ impl Bucket {
             .await?;
         Ok(bucket)
     }
+
+    pub async fn list<'a>(
+        conn: &mut DbConnection<'a>,
+        filter: BucketFilter,
+        pagination: Pagination,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let mut query = bucket::table.into_boxed();
+
+        if let Some(account) = filter.account {
+            query = query.filter(bucket::account.eq(account));
+        }
+        if let Some(msp_id) = filter.msp_id {
+            query = query.filter(bucket::msp_id.eq(msp_id));
+        }
+        if let Some(private) = filter.private {
+            query = query.filter(bucket::private.eq(private));
+        }
+
+        let buckets = query
+            .order(bucket::id.asc())
+            .offset(pagination.offset())
+            .limit(pagination.limit())
+            .load(conn)
+            .await?;
+        Ok(buckets)
+    }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
@@ -7,7 +9,7 @@ use sc_network::{Multiaddr, PeerId};
 use shc_common::types::{FileMetadata, Fingerprint};
 
 use crate::{
-    models::MultiAddress,
+    models::{MultiAddress, Pagination},
     schema::{bucket, file, file_peer_id},
     DbConnection,
 };
@@ -35,6 +37,60 @@ pub struct File {
     pub updated_at: NaiveDateTime,
 }
 
+/// Filter for [`File::list`]. Unset fields match any file.
+#[derive(Debug, Default, Clone)]
+pub struct FileFilter {
+    pub account: Option<Vec<u8>>,
+    /// The ID of the bucket (column in the database) the file belongs to.
+    pub bucket_id: Option<i32>,
+    /// Only match files at this step (see [`FileStorageRequestStep`]).
+    pub step: Option<i32>,
+    /// The ID of a BSP (column in the database) storing the file.
+    pub bsp_id: Option<i32>,
+}
+
+/// Scope of a file existence check (see [`File::find_by_fingerprint`]).
+pub enum FileExistenceScope {
+    /// Only look for the file in the bucket with the given on-chain ID.
+    Bucket(Vec<u8>),
+    /// Look for the file in any bucket, stored by any Provider.
+    Any,
+}
+
+/// A stored file matching a file existence check, together with the Providers storing it.
+#[derive(Debug)]
+pub struct FileExistenceMatch {
+    pub file_key: Vec<u8>,
+    pub onchain_bucket_id: Vec<u8>,
+    /// The on-chain ID of the MSP storing the file's bucket, if any.
+    pub onchain_msp_id: Option<String>,
+    /// The on-chain IDs of the BSPs storing the file.
+    pub onchain_bsp_ids: Vec<String>,
+}
+
+/// The storage proof status of a file, as given by the proofs submitted by the BSPs storing it.
+///
+/// An accepted proof from a BSP covers every file in its forest, so the file was last covered by
+/// a proof at the latest tick proven by any of the BSPs storing it.
+#[derive(Debug)]
+pub struct FileProofStatus {
+    pub file_key: Vec<u8>,
+    /// The last tick at which any BSP storing the file submitted an accepted proof, if any did.
+    pub last_tick_proven: Option<i64>,
+    /// Whether any of the BSPs storing the file is currently slashable.
+    pub has_slashable_provider: bool,
+    pub providers: Vec<ProviderProofStatus>,
+}
+
+/// The storage proof status of one of the BSPs storing a file.
+#[derive(Debug)]
+pub struct ProviderProofStatus {
+    pub onchain_bsp_id: String,
+    /// The last tick proven by the BSP. `0` if it has not submitted any accepted proof yet.
+    pub last_tick_proven: i64,
+    pub slashable: bool,
+}
+
 /// Association table between File and PeerId
 #[derive(Debug, Queryable, Insertable, Associations)]
 #[diesel(table_name = file_peer_id)]
@@ -109,7 +165,10 @@ impl File {
         let file_key = file_key.as_ref().to_vec();
         diesel::update(file::table)
             .filter(file::file_key.eq(file_key))
-            .set(file::step.eq(step as i32))
+            .set((
+                file::step.eq(step as i32),
+                file::updated_at.eq(diesel::dsl::now),
+            ))
             .execute(conn)
             .await?;
         Ok(())
@@ -151,6 +210,164 @@ impl File {
         Ok(files)
     }
 
+    /// How long the most recently fulfilled storage requests took, from being issued until being
+    /// fulfilled, for at most `limit` of them.
+    pub async fn recent_fulfilment_durations<'a>(
+        conn: &mut DbConnection<'a>,
+        limit: i64,
+    ) -> Result<Vec<Duration>, diesel::result::Error> {
+        let timestamps: Vec<(NaiveDateTime, NaiveDateTime)> = file::table
+            .filter(file::step.eq(FileStorageRequestStep::Stored as i32))
+            .order(file::updated_at.desc())
+            .limit(limit)
+            .select((file::created_at, file::updated_at))
+            .load(conn)
+            .await?;
+
+        Ok(timestamps
+            .into_iter()
+            .filter_map(|(created_at, updated_at)| (updated_at - created_at).to_std().ok())
+            .collect())
+    }
+
+    pub async fn list<'a>(
+        conn: &mut DbConnection<'a>,
+        filter: FileFilter,
+        pagination: Pagination,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        use crate::schema::bsp_file;
+
+        let mut query = file::table.into_boxed();
+
+        if let Some(account) = filter.account {
+            query = query.filter(file::account.eq(account));
+        }
+        if let Some(bucket_id) = filter.bucket_id {
+            query = query.filter(file::bucket_id.eq(bucket_id));
+        }
+        if let Some(step) = filter.step {
+            query = query.filter(file::step.eq(step));
+        }
+        if let Some(bsp_id) = filter.bsp_id {
+            query = query.filter(
+                file::id.eq_any(
+                    bsp_file::table
+                        .filter(bsp_file::bsp_id.eq(bsp_id))
+                        .select(bsp_file::file_id),
+                ),
+            );
+        }
+
+        let files = query
+            .order(file::id.asc())
+            .offset(pagination.offset())
+            .limit(pagination.limit())
+            .load(conn)
+            .await?;
+        Ok(files)
+    }
+
+    /// Find the stored files with the given fingerprint, within `scope`.
+    ///
+    /// Used to check if the exact same file is already stored before uploading it again. The
+    /// files are returned in the order they were requested, along with the Providers storing them,
+    /// all loaded in a single query.
+    pub async fn find_by_fingerprint<'a>(
+        conn: &mut DbConnection<'a>,
+        fingerprint: impl AsRef<[u8]>,
+        scope: FileExistenceScope,
+    ) -> Result<Vec<FileExistenceMatch>, diesel::result::Error> {
+        use crate::schema::{bsp, bsp_file, msp};
+
+        let fingerprint = fingerprint.as_ref().to_vec();
+        // One row per BSP storing each file, or a single one without BSP if no BSP stores it.
+        let mut query = file::table
+            .inner_join(bucket::table.on(file::bucket_id.eq(bucket::id)))
+            .left_join(msp::table.on(bucket::msp_id.eq(msp::id.nullable())))
+            .left_join(bsp_file::table.on(bsp_file::file_id.eq(file::id)))
+            .left_join(bsp::table.on(bsp_file::bsp_id.eq(bsp::id)))
+            .filter(file::fingerprint.eq(fingerprint))
+            .filter(file::step.eq(FileStorageRequestStep::Stored as i32))
+            .select((
+                file::id,
+                file::file_key,
+                bucket::onchain_bucket_id,
+                msp::onchain_msp_id.nullable(),
+                bsp::onchain_bsp_id.nullable(),
+            ))
+            .order((file::id.asc(), bsp::id.nullable().asc()))
+            .into_boxed();
+
+        if let FileExistenceScope::Bucket(onchain_bucket_id) = scope {
+            query = query.filter(bucket::onchain_bucket_id.eq(onchain_bucket_id));
+        }
+
+        let rows: Vec<(i32, Vec<u8>, Vec<u8>, Option<String>, Option<String>)> =
+            query.load(conn).await?;
+
+        // Rows of the same file are contiguous, since they are ordered by file.
+        let mut matches: Vec<(i32, FileExistenceMatch)> = Vec::new();
+        for (file_id, file_key, onchain_bucket_id, onchain_msp_id, onchain_bsp_id) in rows {
+            match matches.last_mut() {
+                Some((last_file_id, last_match)) if *last_file_id == file_id => {
+                    last_match.onchain_bsp_ids.extend(onchain_bsp_id);
+                }
+                _ => matches.push((
+                    file_id,
+                    FileExistenceMatch {
+                        file_key,
+                        onchain_bucket_id,
+                        onchain_msp_id,
+                        onchain_bsp_ids: onchain_bsp_id.into_iter().collect(),
+                    },
+                )),
+            }
+        }
+
+        Ok(matches
+            .into_iter()
+            .map(|(_, file_match)| file_match)
+            .collect())
+    }
+
+    /// Get the storage proof status of the file with the given key.
+    pub async fn get_proof_status<'a>(
+        conn: &mut DbConnection<'a>,
+        file_key: impl AsRef<[u8]>,
+    ) -> Result<FileProofStatus, diesel::result::Error> {
+        use crate::schema::{bsp, bsp_file};
+
+        let file = Self::get_by_file_key(conn, file_key).await?;
+
+        let providers: Vec<ProviderProofStatus> = bsp_file::table
+            .filter(bsp_file::file_id.eq(file.id))
+            .inner_join(bsp::table.on(bsp_file::bsp_id.eq(bsp::id)))
+            .select((bsp::onchain_bsp_id, bsp::last_tick_proven, bsp::slashable))
+            .order(bsp::id.asc())
+            .load::<(String, i64, bool)>(conn)
+            .await?
+            .into_iter()
+            .map(
+                |(onchain_bsp_id, last_tick_proven, slashable)| ProviderProofStatus {
+                    onchain_bsp_id,
+                    last_tick_proven,
+                    slashable,
+                },
+            )
+            .collect();
+
+        Ok(FileProofStatus {
+            file_key: file.file_key,
+            last_tick_proven: providers
+                .iter()
+                .map(|provider| provider.last_tick_proven)
+                .filter(|tick| *tick > 0)
+                .max(),
+            has_slashable_provider: providers.iter().any(|provider| provider.slashable),
+            providers,
+        })
+    }
+
     pub async fn get_bsp_peer_ids(
         &self,
         conn: &mut DbConnection<'_>,
@@ -191,3 +408,212 @@ impl File {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use diesel_async::AsyncConnection;
+
+    use super::*;
+    use crate::{
+        models::{Bsp, BspFile, Bucket, Msp},
+        setup_db_pool, DbPool,
+    };
+
+    /// Environment variable with the URL of a migrated database to run these tests against.
+    /// They are skipped if it is not set.
+    const TEST_DATABASE_URL_ENV_VAR: &str = "INDEXER_TEST_DATABASE_URL";
+
+    async fn test_db_pool() -> Option<DbPool> {
+        let Ok(database_url) = std::env::var(TEST_DATABASE_URL_ENV_VAR) else {
+            eprintln!("{TEST_DATABASE_URL_ENV_VAR} not set, skipping indexer database test");
+            return None;
+        };
+        Some(
+            setup_db_pool(database_url)
+                .await
+                .expect("database is reachable"),
+        )
+    }
+
+    async fn create_bsp(conn: &mut DbConnection<'_>, onchain_bsp_id: &str) -> Bsp {
+        Bsp::create(
+            conn,
+            format!("{onchain_bsp_id}-account"),
+            BigDecimal::from(1000),
+            vec![],
+            vec![],
+            onchain_bsp_id.to_string(),
+            BigDecimal::from(100),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn create_bucket(
+        conn: &mut DbConnection<'_>,
+        msp_id: Option<i32>,
+        onchain_bucket_id: &[u8],
+    ) -> Bucket {
+        Bucket::create(
+            conn,
+            msp_id,
+            "owner".to_string(),
+            onchain_bucket_id.to_vec(),
+            b"bucket".to_vec(),
+            None,
+            false,
+            vec![],
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn create_file(
+        conn: &mut DbConnection<'_>,
+        file_key: &[u8],
+        bucket_id: i32,
+        fingerprint: &[u8],
+        step: FileStorageRequestStep,
+    ) -> File {
+        File::create(
+            conn,
+            b"owner".to_vec(),
+            file_key.to_vec(),
+            bucket_id,
+            b"location".to_vec(),
+            fingerprint.to_vec(),
+            1024,
+            step,
+            vec![],
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn find_by_fingerprint_returns_stored_files_with_their_providers() {
+        let Some(pool) = test_db_pool().await else {
+            return;
+        };
+        let mut conn = pool.get().await.unwrap();
+        conn.begin_test_transaction().await.unwrap();
+
+        let msp = Msp::create(
+            &mut conn,
+            "fp-msp-account".to_string(),
+            BigDecimal::from(1000),
+            "value-prop".to_string(),
+            vec![],
+            "fp-msp".to_string(),
+        )
+        .await
+        .unwrap();
+        let bsp_1 = create_bsp(&mut conn, "fp-bsp-1").await;
+        let bsp_2 = create_bsp(&mut conn, "fp-bsp-2").await;
+        let bucket_with_msp = create_bucket(&mut conn, Some(msp.id), b"fp-bucket-1").await;
+        let bucket_without_msp = create_bucket(&mut conn, None, b"fp-bucket-2").await;
+
+        let fingerprint = b"fp-fingerprint";
+        let replicated = create_file(
+            &mut conn,
+            b"fp-file-1",
+            bucket_with_msp.id,
+            fingerprint,
+            FileStorageRequestStep::Stored,
+        )
+        .await;
+        BspFile::create(&mut conn, bsp_1.id, replicated.id)
+            .await
+            .unwrap();
+        BspFile::create(&mut conn, bsp_2.id, replicated.id)
+            .await
+            .unwrap();
+        create_file(
+            &mut conn,
+            b"fp-file-2",
+            bucket_without_msp.id,
+            fingerprint,
+            FileStorageRequestStep::Stored,
+        )
+        .await;
+        // Neither a file with an open storage request nor one with another fingerprint match.
+        create_file(
+            &mut conn,
+            b"fp-file-3",
+            bucket_with_msp.id,
+            fingerprint,
+            FileStorageRequestStep::Requested,
+        )
+        .await;
+        create_file(
+            &mut conn,
+            b"fp-file-4",
+            bucket_with_msp.id,
+            b"fp-other-fingerprint",
+            FileStorageRequestStep::Stored,
+        )
+        .await;
+
+        let matches = File::find_by_fingerprint(&mut conn, fingerprint, FileExistenceScope::Any)
+            .await
+            .unwrap();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].file_key, b"fp-file-1".to_vec());
+        assert_eq!(matches[0].onchain_bucket_id, b"fp-bucket-1".to_vec());
+        assert_eq!(matches[0].onchain_msp_id.as_deref(), Some("fp-msp"));
+        assert_eq!(matches[0].onchain_bsp_ids, vec!["fp-bsp-1", "fp-bsp-2"]);
+        assert_eq!(matches[1].file_key, b"fp-file-2".to_vec());
+        assert_eq!(matches[1].onchain_bucket_id, b"fp-bucket-2".to_vec());
+        assert_eq!(matches[1].onchain_msp_id, None);
+        assert!(matches[1].onchain_bsp_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn find_by_fingerprint_only_matches_files_of_the_bucket_in_scope() {
+        let Some(pool) = test_db_pool().await else {
+            return;
+        };
+        let mut conn = pool.get().await.unwrap();
+        conn.begin_test_transaction().await.unwrap();
+
+        let bucket_1 = create_bucket(&mut conn, None, b"fp-scope-bucket-1").await;
+        let bucket_2 = create_bucket(&mut conn, None, b"fp-scope-bucket-2").await;
+        let fingerprint = b"fp-scope-fingerprint";
+        for (file_key, bucket_id) in [
+            (b"fp-scope-file-1", bucket_1.id),
+            (b"fp-scope-file-2", bucket_2.id),
+        ] {
+            create_file(
+                &mut conn,
+                file_key,
+                bucket_id,
+                fingerprint,
+                FileStorageRequestStep::Stored,
+            )
+            .await;
+        }
+
+        let matches = File::find_by_fingerprint(
+            &mut conn,
+            fingerprint,
+            FileExistenceScope::Bucket(b"fp-scope-bucket-2".to_vec()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].file_key, b"fp-scope-file-2".to_vec());
+
+        let matches = File::find_by_fingerprint(
+            &mut conn,
+            fingerprint,
+            FileExistenceScope::Bucket(b"fp-scope-bucket-3".to_vec()),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches.is_empty());
+    }
+}
@@ -1,17 +1,21 @@
+pub mod access_grant;
 pub mod bsp;
 pub mod bucket;
 pub mod file;
 pub mod msp;
 pub mod multiaddress;
+pub mod pagination;
 pub mod payment_stream;
 pub mod peer_id;
 pub mod service_state;
 
+pub use access_grant::*;
 pub use bsp::*;
 pub use bucket::*;
 pub use file::*;
 pub use msp::*;
 pub use multiaddress::*;
+pub use pagination::*;
 pub use payment_stream::*;
 pub use peer_id::*;
 pub use service_state::*;
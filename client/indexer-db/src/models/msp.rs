@@ -4,7 +4,7 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
 use crate::{
-    models::multiaddress::MultiAddress,
+    models::{multiaddress::MultiAddress, Pagination},
     schema::{msp, msp_multiaddress},
     DbConnection,
 };
@@ -82,6 +82,14 @@ impl Msp {
         Ok(())
     }
 
+    pub async fn get_by_id<'a>(
+        conn: &mut DbConnection<'a>,
+        id: i32,
+    ) -> Result<Self, diesel::result::Error> {
+        let msp = msp::table.find(id).first(conn).await?;
+        Ok(msp)
+    }
+
     pub async fn get_by_onchain_msp_id<'a>(
         conn: &mut DbConnection<'a>,
         onchain_msp_id: String,
@@ -92,4 +100,17 @@ impl Msp {
             .await?;
         Ok(msp)
     }
+
+    pub async fn list<'a>(
+        conn: &mut DbConnection<'a>,
+        pagination: Pagination,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let msps = msp::table
+            .order(msp::id.asc())
+            .offset(pagination.offset())
+            .limit(pagination.limit())
+            .load(conn)
+            .await?;
+        Ok(msps)
+    }
 }
@@ -0,0 +1,37 @@
+/// Maximum number of rows a single list query can return.
+///
+/// Bounds the cost of list queries, whatever the page size asked for.
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Page of results to return from a list query, ordered by database ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    offset: i64,
+    limit: i64,
+}
+
+impl Pagination {
+    /// Creates a page of at most `limit` rows, skipping the first `offset` ones.
+    ///
+    /// `limit` is clamped to [`MAX_PAGE_SIZE`].
+    pub fn new(offset: i64, limit: i64) -> Self {
+        Self {
+            offset: offset.max(0),
+            limit: limit.clamp(0, MAX_PAGE_SIZE),
+        }
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self::new(0, MAX_PAGE_SIZE)
+    }
+}
@@ -2,7 +2,7 @@ use bigdecimal::BigDecimal;
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
-use crate::{schema::paymentstream, DbConnection};
+use crate::{models::Pagination, schema::paymentstream, DbConnection};
 
 #[derive(Debug, Queryable, Insertable, Selectable)]
 #[diesel(table_name = paymentstream)]
@@ -21,6 +21,13 @@ pub struct PaymentStream {
     pub charged_at_tick: i64,
 }
 
+/// Filter for [`PaymentStream::list`]. Unset fields match any payment stream.
+#[derive(Debug, Default, Clone)]
+pub struct PaymentStreamFilter {
+    pub account: Option<String>,
+    pub provider: Option<String>,
+}
+
 impl PaymentStream {
     pub async fn create<'a>(
         conn: &mut DbConnection<'a>,
@@ -73,4 +80,27 @@ impl PaymentStream {
             .await?;
         Ok(())
     }
+
+    pub async fn list<'a>(
+        conn: &mut DbConnection<'a>,
+        filter: PaymentStreamFilter,
+        pagination: Pagination,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let mut query = paymentstream::table.into_boxed();
+
+        if let Some(account) = filter.account {
+            query = query.filter(paymentstream::account.eq(account));
+        }
+        if let Some(provider) = filter.provider {
+            query = query.filter(paymentstream::provider.eq(provider));
+        }
+
+        let payment_streams = query
+            .order(paymentstream::id.asc())
+            .offset(pagination.offset())
+            .limit(pagination.limit())
+            .load(conn)
+            .await?;
+        Ok(payment_streams)
+    }
 }
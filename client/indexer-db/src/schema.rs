@@ -1,5 +1,17 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    access_grant (id) {
+        id -> Int4,
+        bucket_id -> Int4,
+        file_key -> Nullable<Bytea>,
+        grantee -> Varchar,
+        expires_at -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     bsp (id) {
         id -> Int4,
@@ -11,6 +23,7 @@ diesel::table! {
         updated_at -> Timestamp,
         onchain_bsp_id -> Varchar,
         merkle_root -> Bytea,
+        slashable -> Bool,
     }
 }
 
@@ -122,6 +135,7 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(access_grant -> bucket (bucket_id));
 diesel::joinable!(bsp_file -> file (file_id));
 diesel::joinable!(bsp_multiaddress -> bsp (bsp_id));
 diesel::joinable!(bsp_multiaddress -> multiaddress (multiaddress_id));
@@ -132,6 +146,7 @@ diesel::joinable!(msp_multiaddress -> msp (msp_id));
 diesel::joinable!(msp_multiaddress -> multiaddress (multiaddress_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    access_grant,
     bsp,
     bsp_file,
     bsp_multiaddress,
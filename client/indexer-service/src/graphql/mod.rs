@@ -0,0 +1,16 @@
+//! GraphQL API over the indexer database.
+//!
+//! Buckets, files (and their open storage requests), MSPs, BSPs and payment streams can be queried
+//! along with the entities they relate to, so that composite views (e.g. a bucket with its files,
+//! the Providers storing them and the owner's payment streams) take a single round trip.
+//!
+//! Every list field is paginated with `offset` and `limit` arguments, the latter capped at
+//! [`shc_indexer_db::models::MAX_PAGE_SIZE`]. The cost of a query is bounded by
+//! [`schema::MAX_QUERY_DEPTH`] and [`schema::MAX_QUERY_COMPLEXITY`], and queries exceeding them are
+//! rejected before resolving anything.
+
+pub mod schema;
+pub mod server;
+
+pub use schema::{indexer_schema, IndexerSchema};
+pub use server::IndexerGraphqlServer;
@@ -0,0 +1,658 @@
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, InputObject, Object, Schema, SchemaBuilder,
+};
+use diesel::result::Error as DieselError;
+
+use shc_indexer_db::{
+    models::{self, BucketFilter, FileFilter, FileStorageRequestStep, Pagination, MAX_PAGE_SIZE},
+    DbConnection, DbPool,
+};
+
+/// Maximum depth of the selections of a query.
+pub const MAX_QUERY_DEPTH: usize = 10;
+
+/// Maximum cost of a query.
+///
+/// Every field costs 1, and a list field costs the cost of its items' selection times the page
+/// size asked for ([`MAX_PAGE_SIZE`] if not given). This roughly bounds the amount of rows a query
+/// can load from the indexer database.
+pub const MAX_QUERY_COMPLEXITY: usize = 50_000;
+
+/// The GraphQL schema over the indexer database.
+pub type IndexerSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Builds the GraphQL schema, resolving queries against the indexer database behind `db_pool`.
+pub fn indexer_schema(db_pool: DbPool) -> IndexerSchema {
+    schema_builder().data(db_pool).finish()
+}
+
+/// The schema with its query cost limits, without the database pool to resolve queries with.
+pub(crate) fn schema_builder() -> SchemaBuilder<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+}
+
+/// Cost of a list field returning a page of at most `limit` items, each costing `child_complexity`.
+fn page_complexity(limit: Option<i64>, child_complexity: usize) -> usize {
+    page(None, limit).limit() as usize * child_complexity
+}
+
+/// The page of results asked for by the `offset` and `limit` arguments of a list field.
+fn page(offset: Option<i64>, limit: Option<i64>) -> Pagination {
+    Pagination::new(offset.unwrap_or_default(), limit.unwrap_or(MAX_PAGE_SIZE))
+}
+
+async fn connection<'a>(ctx: &Context<'a>) -> async_graphql::Result<DbConnection<'a>> {
+    Ok(ctx.data::<DbPool>()?.get().await?)
+}
+
+/// Turns a missing row into `None`.
+fn optional<T>(result: Result<T, DieselError>) -> async_graphql::Result<Option<T>> {
+    match result {
+        Ok(row) => Ok(Some(row)),
+        Err(DieselError::NotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    array_bytes::bytes2hex("0x", bytes)
+}
+
+fn from_hex(hex: &str) -> async_graphql::Result<Vec<u8>> {
+    array_bytes::hex2bytes(hex).map_err(|_| format!("Invalid hex string: {}", hex).into())
+}
+
+/// The step of the storage request of a file.
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStep {
+    /// The storage request of the file is still open.
+    Requested,
+    /// The storage request of the file was fulfilled.
+    Stored,
+}
+
+impl FileStep {
+    fn from_db(step: i32) -> Self {
+        if step == FileStorageRequestStep::Requested as i32 {
+            Self::Requested
+        } else {
+            Self::Stored
+        }
+    }
+
+    fn to_db(self) -> i32 {
+        match self {
+            Self::Requested => FileStorageRequestStep::Requested as i32,
+            Self::Stored => FileStorageRequestStep::Stored as i32,
+        }
+    }
+}
+
+/// Filter of the `buckets` query. Unset fields match any bucket.
+#[derive(InputObject, Debug, Default)]
+pub struct BucketsFilter {
+    /// The owner of the bucket.
+    pub account: Option<String>,
+    /// The on-chain ID of the MSP storing the bucket.
+    pub msp_id: Option<String>,
+    pub private: Option<bool>,
+}
+
+/// Filter of the `files` query. Unset fields match any file.
+#[derive(InputObject, Debug, Default)]
+pub struct FilesFilter {
+    /// The owner of the file, hex encoded.
+    pub account: Option<String>,
+    /// The on-chain ID of the bucket of the file, hex encoded.
+    pub bucket_id: Option<String>,
+    pub step: Option<FileStep>,
+}
+
+/// Filter of the `paymentStreams` query. Unset fields match any payment stream.
+#[derive(InputObject, Debug, Default)]
+pub struct PaymentStreamsFilter {
+    /// The user paying.
+    pub account: Option<String>,
+    /// The on-chain ID of the Provider being paid.
+    pub provider: Option<String>,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The MSP with the given on-chain ID.
+    async fn msp(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Msp>> {
+        let mut conn = connection(ctx).await?;
+        let msp = optional(models::Msp::get_by_onchain_msp_id(&mut conn, id).await)?;
+        Ok(msp.map(Msp))
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn msps(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<Msp>> {
+        let mut conn = connection(ctx).await?;
+        let msps = models::Msp::list(&mut conn, page(offset, limit)).await?;
+        Ok(msps.into_iter().map(Msp).collect())
+    }
+
+    /// The BSP with the given on-chain ID.
+    async fn bsp(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Bsp>> {
+        let mut conn = connection(ctx).await?;
+        let bsp = optional(models::Bsp::get_by_onchain_bsp_id(&mut conn, id).await)?;
+        Ok(bsp.map(Bsp))
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn bsps(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<Bsp>> {
+        let mut conn = connection(ctx).await?;
+        let bsps = models::Bsp::list(&mut conn, page(offset, limit)).await?;
+        Ok(bsps.into_iter().map(Bsp).collect())
+    }
+
+    /// The bucket with the given on-chain ID, hex encoded.
+    async fn bucket(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Bucket>> {
+        let onchain_bucket_id = from_hex(&id)?;
+        let mut conn = connection(ctx).await?;
+        let bucket =
+            optional(models::Bucket::get_by_onchain_bucket_id(&mut conn, onchain_bucket_id).await)?;
+        Ok(bucket.map(Bucket))
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn buckets(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<BucketsFilter>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<Bucket>> {
+        let filter = filter.unwrap_or_default();
+        let mut conn = connection(ctx).await?;
+
+        let msp_id = match filter.msp_id {
+            Some(onchain_msp_id) => {
+                match optional(models::Msp::get_by_onchain_msp_id(&mut conn, onchain_msp_id).await)?
+                {
+                    Some(msp) => Some(msp.id),
+                    // No bucket is stored by an unknown MSP.
+                    None => return Ok(Vec::new()),
+                }
+            }
+            None => None,
+        };
+
+        let buckets = models::Bucket::list(
+            &mut conn,
+            BucketFilter {
+                account: filter.account,
+                msp_id,
+                private: filter.private,
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(buckets.into_iter().map(Bucket).collect())
+    }
+
+    /// The file with the given file key, hex encoded.
+    async fn file(
+        &self,
+        ctx: &Context<'_>,
+        file_key: String,
+    ) -> async_graphql::Result<Option<File>> {
+        let file_key = from_hex(&file_key)?;
+        let mut conn = connection(ctx).await?;
+        let file = optional(models::File::get_by_file_key(&mut conn, file_key).await)?;
+        Ok(file.map(File))
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn files(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<FilesFilter>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<File>> {
+        let filter = filter.unwrap_or_default();
+        let account = filter.account.as_deref().map(from_hex).transpose()?;
+        let onchain_bucket_id = filter.bucket_id.as_deref().map(from_hex).transpose()?;
+        let mut conn = connection(ctx).await?;
+
+        let bucket_id = match onchain_bucket_id {
+            Some(onchain_bucket_id) => {
+                match optional(
+                    models::Bucket::get_by_onchain_bucket_id(&mut conn, onchain_bucket_id).await,
+                )? {
+                    Some(bucket) => Some(bucket.id),
+                    // An unknown bucket has no files.
+                    None => return Ok(Vec::new()),
+                }
+            }
+            None => None,
+        };
+
+        let files = models::File::list(
+            &mut conn,
+            FileFilter {
+                account,
+                bucket_id,
+                step: filter.step.map(FileStep::to_db),
+                bsp_id: None,
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(files.into_iter().map(File).collect())
+    }
+
+    /// The files whose storage request is still open, optionally only those of a bucket (its
+    /// on-chain ID, hex encoded).
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn storage_requests(
+        &self,
+        ctx: &Context<'_>,
+        bucket_id: Option<String>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<File>> {
+        self.files(
+            ctx,
+            Some(FilesFilter {
+                bucket_id,
+                step: Some(FileStep::Requested),
+                ..Default::default()
+            }),
+            offset,
+            limit,
+        )
+        .await
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn payment_streams(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<PaymentStreamsFilter>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<PaymentStream>> {
+        let filter = filter.unwrap_or_default();
+        let mut conn = connection(ctx).await?;
+        let payment_streams = models::PaymentStream::list(
+            &mut conn,
+            models::PaymentStreamFilter {
+                account: filter.account,
+                provider: filter.provider,
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(payment_streams.into_iter().map(PaymentStream).collect())
+    }
+}
+
+pub struct Msp(models::Msp);
+
+#[Object]
+impl Msp {
+    /// The on-chain ID of the MSP.
+    async fn id(&self) -> &str {
+        &self.0.onchain_msp_id
+    }
+
+    async fn account(&self) -> &str {
+        &self.0.account
+    }
+
+    /// The storage capacity of the MSP, in bytes.
+    async fn capacity(&self) -> String {
+        self.0.capacity.to_string()
+    }
+
+    async fn value_prop(&self) -> &str {
+        &self.0.value_prop
+    }
+
+    /// The buckets stored by the MSP.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn buckets(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<Bucket>> {
+        let mut conn = connection(ctx).await?;
+        let buckets = models::Bucket::list(
+            &mut conn,
+            BucketFilter {
+                msp_id: Some(self.0.id),
+                ..Default::default()
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(buckets.into_iter().map(Bucket).collect())
+    }
+
+    /// The payment streams paying the MSP.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn payment_streams(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<PaymentStream>> {
+        provider_payment_streams(ctx, self.0.onchain_msp_id.clone(), offset, limit).await
+    }
+}
+
+pub struct Bsp(models::Bsp);
+
+#[Object]
+impl Bsp {
+    /// The on-chain ID of the BSP.
+    async fn id(&self) -> &str {
+        &self.0.onchain_bsp_id
+    }
+
+    async fn account(&self) -> &str {
+        &self.0.account
+    }
+
+    /// The storage capacity of the BSP, in bytes.
+    async fn capacity(&self) -> String {
+        self.0.capacity.to_string()
+    }
+
+    async fn stake(&self) -> String {
+        self.0.stake.to_string()
+    }
+
+    /// The last tick for which the BSP submitted an accepted proof.
+    async fn last_tick_proven(&self) -> i64 {
+        self.0.last_tick_proven
+    }
+
+    /// Whether the BSP missed its last proof submission deadline.
+    async fn slashable(&self) -> bool {
+        self.0.slashable
+    }
+
+    /// The root of the BSP's forest, hex encoded.
+    async fn merkle_root(&self) -> String {
+        to_hex(&self.0.merkle_root)
+    }
+
+    /// The files stored by the BSP.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn files(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<File>> {
+        let mut conn = connection(ctx).await?;
+        let files = models::File::list(
+            &mut conn,
+            FileFilter {
+                bsp_id: Some(self.0.id),
+                ..Default::default()
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(files.into_iter().map(File).collect())
+    }
+
+    /// The payment streams paying the BSP.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn payment_streams(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<PaymentStream>> {
+        provider_payment_streams(ctx, self.0.onchain_bsp_id.clone(), offset, limit).await
+    }
+}
+
+async fn provider_payment_streams(
+    ctx: &Context<'_>,
+    provider: String,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> async_graphql::Result<Vec<PaymentStream>> {
+    let mut conn = connection(ctx).await?;
+    let payment_streams = models::PaymentStream::list(
+        &mut conn,
+        models::PaymentStreamFilter {
+            provider: Some(provider),
+            ..Default::default()
+        },
+        page(offset, limit),
+    )
+    .await?;
+    Ok(payment_streams.into_iter().map(PaymentStream).collect())
+}
+
+pub struct Bucket(models::Bucket);
+
+#[Object]
+impl Bucket {
+    /// The on-chain ID of the bucket, hex encoded.
+    async fn id(&self) -> String {
+        to_hex(&self.0.onchain_bucket_id)
+    }
+
+    /// The owner of the bucket.
+    async fn account(&self) -> &str {
+        &self.0.account
+    }
+
+    async fn name(&self) -> String {
+        String::from_utf8_lossy(&self.0.name).into_owned()
+    }
+
+    async fn private(&self) -> bool {
+        self.0.private
+    }
+
+    /// The ID of the bucket's read-access NFT collection, if any.
+    async fn collection_id(&self) -> Option<&str> {
+        self.0.collection_id.as_deref()
+    }
+
+    /// The root of the bucket's forest, hex encoded.
+    async fn merkle_root(&self) -> String {
+        to_hex(&self.0.merkle_root)
+    }
+
+    /// The MSP storing the bucket, if any.
+    async fn msp(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Msp>> {
+        let Some(msp_id) = self.0.msp_id else {
+            return Ok(None);
+        };
+        let mut conn = connection(ctx).await?;
+        let msp = optional(models::Msp::get_by_id(&mut conn, msp_id).await)?;
+        Ok(msp.map(Msp))
+    }
+
+    /// The files of the bucket.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn files(
+        &self,
+        ctx: &Context<'_>,
+        step: Option<FileStep>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<File>> {
+        let mut conn = connection(ctx).await?;
+        let files = models::File::list(
+            &mut conn,
+            FileFilter {
+                bucket_id: Some(self.0.id),
+                step: step.map(FileStep::to_db),
+                ..Default::default()
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(files.into_iter().map(File).collect())
+    }
+
+    /// The payment streams of the owner of the bucket.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn payment_streams(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> async_graphql::Result<Vec<PaymentStream>> {
+        let mut conn = connection(ctx).await?;
+        let payment_streams = models::PaymentStream::list(
+            &mut conn,
+            models::PaymentStreamFilter {
+                account: Some(self.0.account.clone()),
+                ..Default::default()
+            },
+            page(offset, limit),
+        )
+        .await?;
+        Ok(payment_streams.into_iter().map(PaymentStream).collect())
+    }
+}
+
+pub struct File(models::File);
+
+#[Object]
+impl File {
+    /// The file key, hex encoded.
+    async fn file_key(&self) -> String {
+        to_hex(&self.0.file_key)
+    }
+
+    /// The owner of the file, hex encoded.
+    async fn account(&self) -> String {
+        to_hex(&self.0.account)
+    }
+
+    async fn location(&self) -> String {
+        String::from_utf8_lossy(&self.0.location).into_owned()
+    }
+
+    /// The fingerprint of the file, hex encoded.
+    async fn fingerprint(&self) -> String {
+        to_hex(&self.0.fingerprint)
+    }
+
+    /// The size of the file, in bytes.
+    async fn size(&self) -> i64 {
+        self.0.size
+    }
+
+    async fn step(&self) -> FileStep {
+        FileStep::from_db(self.0.step)
+    }
+
+    async fn bucket(&self, ctx: &Context<'_>) -> async_graphql::Result<Bucket> {
+        let mut conn = connection(ctx).await?;
+        let bucket = models::Bucket::get_by_id(&mut conn, self.0.bucket_id).await?;
+        Ok(Bucket(bucket))
+    }
+
+    /// The BSPs storing the file.
+    async fn bsps(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Bsp>> {
+        let mut conn = connection(ctx).await?;
+        let bsps = models::Bsp::get_by_file_id(&mut conn, self.0.id).await?;
+        Ok(bsps.into_iter().map(Bsp).collect())
+    }
+}
+
+pub struct PaymentStream(models::PaymentStream);
+
+#[Object]
+impl PaymentStream {
+    /// The user paying.
+    async fn account(&self) -> &str {
+        &self.0.account
+    }
+
+    /// The on-chain ID of the Provider being paid.
+    async fn provider(&self) -> &str {
+        &self.0.provider
+    }
+
+    async fn total_amount_paid(&self) -> String {
+        self.0.total_amount_paid.to_string()
+    }
+
+    async fn last_tick_charged(&self) -> i64 {
+        self.0.last_tick_charged
+    }
+
+    async fn charged_at_tick(&self) -> i64 {
+        self.0.charged_at_tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn errors(query: &str) -> Vec<String> {
+        schema_builder()
+            .finish()
+            .execute(query)
+            .await
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn page_is_capped_at_max_page_size() {
+        assert_eq!(page(None, None), Pagination::new(0, MAX_PAGE_SIZE));
+        assert_eq!(page(Some(20), Some(10)), Pagination::new(20, 10));
+        assert_eq!(page(None, Some(MAX_PAGE_SIZE * 10)).limit(), MAX_PAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn queries_over_the_complexity_limit_are_rejected() {
+        let errors = errors("{ msps { buckets { files { fileKey size } } } }").await;
+        assert_eq!(errors, vec!["Query is too complex.".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn queries_over_the_depth_limit_are_rejected() {
+        let errors = errors(
+            "{ files(limit: 1) { bucket { msp { buckets(limit: 1) { files(limit: 1) { bucket { \
+             msp { buckets(limit: 1) { files(limit: 1) { bucket { id } } } } } } } } } } }",
+        )
+        .await;
+        assert_eq!(errors, vec!["Query is nested too deep.".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn queries_within_the_limits_are_resolved() {
+        // Resolving fails without a database to resolve against, after the limits are checked.
+        let errors =
+            errors("{ bucket(id: \"0x01\") { msp { id } files(limit: 10) { fileKey } } }").await;
+        assert_eq!(errors.len(), 1);
+        assert_ne!(errors[0], "Query is too complex.");
+        assert_ne!(errors[0], "Query is nested too deep.");
+    }
+}
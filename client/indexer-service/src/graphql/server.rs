@@ -0,0 +1,203 @@
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{self, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use log::{debug, error, info, warn};
+use tokio::net::TcpListener;
+
+use shc_indexer_db::DbPool;
+
+use crate::graphql::schema::{indexer_schema, IndexerSchema};
+
+const LOG_TARGET: &str = "indexer-graphql";
+
+/// Path the GraphQL API is served at.
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+/// Maximum size of the body of a GraphQL request, in bytes.
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+
+/// Serves the GraphQL API over the indexer database, at [`GRAPHQL_PATH`].
+///
+/// Queries are sent as the JSON body of `POST` requests (`{"query": ..., "variables": ...}`), and
+/// answered with the JSON GraphQL response.
+pub struct IndexerGraphqlServer {
+    schema: IndexerSchema,
+    listen_addr: SocketAddr,
+}
+
+impl IndexerGraphqlServer {
+    pub fn new(db_pool: DbPool, listen_addr: SocketAddr) -> Self {
+        Self {
+            schema: indexer_schema(db_pool),
+            listen_addr,
+        }
+    }
+
+    /// Serves requests until the node shuts down.
+    pub async fn run(self) {
+        let listener = match TcpListener::bind(self.listen_addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to start the indexer GraphQL server on {}: {}", self.listen_addr, e);
+                return;
+            }
+        };
+
+        info!(
+            target: LOG_TARGET,
+            "🔎 Indexer GraphQL API listening on http://{}{}", self.listen_addr, GRAPHQL_PATH
+        );
+
+        let server = Arc::new(self);
+        loop {
+            let (stream, peer_addr) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Failed to accept indexer GraphQL connection: {}", e);
+                    continue;
+                }
+            };
+
+            let server = server.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.handle(request).await) }
+                });
+
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    debug!(target: LOG_TARGET, "Indexer GraphQL connection with {} failed: {}", peer_addr, e);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        if request.uri().path() != GRAPHQL_PATH {
+            return response(
+                StatusCode::NOT_FOUND,
+                "text/plain; charset=utf-8",
+                "Not found",
+            );
+        }
+        if request.method() != Method::POST {
+            return response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "text/plain; charset=utf-8",
+                "Only POST requests are supported",
+            );
+        }
+
+        let body = match Limited::new(request.into_body(), MAX_REQUEST_SIZE)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(e) => {
+                return response(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "text/plain; charset=utf-8",
+                    format!("Failed to read the request: {}", e),
+                )
+            }
+        };
+
+        self.execute(&body).await
+    }
+
+    /// Executes the GraphQL request in `body`.
+    async fn execute(&self, body: &[u8]) -> Response<Full<Bytes>> {
+        let request: async_graphql::Request = match serde_json::from_slice(body) {
+            Ok(request) => request,
+            Err(e) => {
+                return response(
+                    StatusCode::BAD_REQUEST,
+                    "text/plain; charset=utf-8",
+                    format!("Invalid GraphQL request: {}", e),
+                )
+            }
+        };
+
+        let graphql_response = self.schema.execute(request).await;
+        match serde_json::to_vec(&graphql_response) {
+            Ok(json) => response(StatusCode::OK, "application/json", json),
+            Err(e) => response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "text/plain; charset=utf-8",
+                format!("Failed to encode the GraphQL response: {}", e),
+            ),
+        }
+    }
+}
+
+fn response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: impl Into<Bytes>,
+) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(body.into()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphql::schema::schema_builder;
+
+    fn server() -> IndexerGraphqlServer {
+        IndexerGraphqlServer {
+            schema: schema_builder().finish(),
+            listen_addr: ([127, 0, 0, 1], 0).into(),
+        }
+    }
+
+    async fn body_json(response: Response<Full<Bytes>>) -> serde_json::Value {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_are_rejected() {
+        let response = server().execute(b"not json").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn queries_are_answered_with_a_graphql_response() {
+        let response = server()
+            .execute(br#"{"query": "{ __type(name: \"Bucket\") { name } }"}"#)
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_json(response).await,
+            serde_json::json!({ "data": { "__type": { "name": "Bucket" } } })
+        );
+    }
+
+    #[tokio::test]
+    async fn query_errors_are_answered_in_the_graphql_response() {
+        let response = server()
+            .execute(br#"{"query": "{ msps { buckets { files { fileKey } } } }"}"#)
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = body_json(response).await;
+        assert_eq!(json["errors"][0]["message"], "Query is too complex.");
+    }
+}
@@ -301,16 +301,31 @@ impl IndexerService {
                 )
                 .await?;
             }
+            pallet_file_system::Event::StorageRequestRenewed { .. } => {}
+            pallet_file_system::Event::StorageRequestRenewalFailed { .. } => {}
             pallet_file_system::Event::StorageRequestRevoked { file_key } => {
                 File::delete(conn, file_key.as_ref().to_vec()).await?;
             }
+            pallet_file_system::Event::BspCompensatedForRevokedStorageRequest { .. } => {}
+            pallet_file_system::Event::NewMspOnlyStorageRequest { .. } => {}
             pallet_file_system::Event::MspAcceptedStorageRequest { .. } => {}
             pallet_file_system::Event::StorageRequestRejected { .. } => {}
             pallet_file_system::Event::BspRequestedToStopStoring { .. } => {}
             pallet_file_system::Event::PriorityChallengeForFileDeletionQueued { .. } => {}
+            pallet_file_system::Event::PriorityChallengeForFileDeletionDeferred { .. } => {}
+            pallet_file_system::Event::DeferredPriorityChallengeForFileDeletionQueued {
+                ..
+            } => {}
             pallet_file_system::Event::SpStopStoringInsolventUser { .. } => {}
             pallet_file_system::Event::FailedToQueuePriorityChallenge { .. } => {}
             pallet_file_system::Event::FileDeletionRequest { .. } => {}
+            pallet_file_system::Event::FileDeletionRequestsBatch { .. } => {}
+            pallet_file_system::Event::FileMoveRequested { .. } => {}
+            pallet_file_system::Event::FileMoved { .. } => {}
+            pallet_file_system::Event::BspConfirmedFileMove { .. } => {}
+            pallet_file_system::Event::NotificationPreferencesSet { .. } => {}
+            pallet_file_system::Event::FileAccessGranted { .. } => {}
+            pallet_file_system::Event::FileAccessRevoked { .. } => {}
             pallet_file_system::Event::ProofSubmittedForPendingFileDeletionRequest { .. } => {}
             pallet_file_system::Event::BspChallengeCycleInitialised { .. } => {}
             pallet_file_system::Event::MoveBucketRequestExpired { .. } => {}
@@ -324,6 +339,72 @@ impl IndexerService {
                 Bucket::delete(conn, bucket_id.as_ref().to_vec()).await?;
             }
             pallet_file_system::Event::FailedToDecreaseBucketSize { .. } => {}
+            pallet_file_system::Event::BucketDefaultReplicationTargetUpdated { .. } => {}
+            pallet_file_system::Event::BucketEncryptionMetadataUpdated { .. } => {}
+            pallet_file_system::Event::StorageRequestExpiringSoon { .. } => {}
+            pallet_file_system::Event::MspEligibilityModeSet { .. } => {}
+            pallet_file_system::Event::MspAddedToEligibilityList { .. } => {}
+            pallet_file_system::Event::MspRemovedFromEligibilityList { .. } => {}
+            pallet_file_system::Event::StorageRequestCancelledByUnavailableMsp {
+                file_key, ..
+            } => {
+                File::delete(conn, file_key.as_ref().to_vec()).await?;
+            }
+            pallet_file_system::Event::UnavailableBspVolunteerRemoved { .. } => {}
+            pallet_file_system::Event::MoveBucketRequestCancelledByUnavailableMsp { .. } => {}
+            pallet_file_system::Event::ProviderStatusChangeProcessed { .. } => {}
+            pallet_file_system::Event::DepositTransferFailed { .. } => {}
+            pallet_file_system::Event::DepositTransferRetrySucceeded { .. } => {}
+            pallet_file_system::Event::DepositTransferRetryFailed { .. } => {}
+            pallet_file_system::Event::DepositTransferAbandoned { .. } => {}
+            pallet_file_system::Event::DepositTransferWrittenOff { .. } => {}
+            pallet_file_system::Event::StorageRequestReplicationTargetChanged { .. } => {}
+            pallet_file_system::Event::BucketFrozen { .. } => {}
+            pallet_file_system::Event::BucketUnfrozen { .. } => {}
+            pallet_file_system::Event::DeletionListRegistered { .. } => {}
+            pallet_file_system::Event::DeletionListFileDeletionRequest { .. } => {}
+            pallet_file_system::Event::DeletionListCompleted { .. } => {}
+            pallet_file_system::Event::DeletionListItemSkipped { .. } => {}
+            pallet_file_system::Event::DeletionListCancelled { .. } => {}
+            pallet_file_system::Event::UploadGrantCreated { .. } => {}
+            pallet_file_system::Event::UploadGrantRevoked { .. } => {}
+            pallet_file_system::Event::UploadGrantUsed { .. } => {}
+            pallet_file_system::Event::BucketTransferOffered { .. } => {}
+            pallet_file_system::Event::BucketTransferOfferAccepted { .. } => {}
+            pallet_file_system::Event::BucketTransferOfferRejected { .. } => {}
+            pallet_file_system::Event::AccessGrantCreated {
+                owner: _,
+                target,
+                grantee,
+                expires_at,
+            } => {
+                let bucket =
+                    Bucket::get_by_onchain_bucket_id(conn, target.bucket_id().as_ref().to_vec())
+                        .await?;
+                AccessGrant::upsert(
+                    conn,
+                    bucket.id,
+                    access_grant_file_key(target),
+                    grantee.to_string(),
+                    (*expires_at).into(),
+                )
+                .await?;
+            }
+            pallet_file_system::Event::AccessGrantRevoked {
+                owner: _,
+                target,
+                grantee,
+            }
+            | pallet_file_system::Event::AccessGrantExpired { target, grantee } => {
+                // The bucket might have been deleted already, along with its grants.
+                AccessGrant::delete(
+                    conn,
+                    target.bucket_id().as_ref().to_vec(),
+                    access_grant_file_key(target),
+                    grantee.to_string(),
+                )
+                .await?;
+            }
             pallet_file_system::Event::__Ignore(_, _) => {}
         }
         Ok(())
@@ -389,6 +470,21 @@ impl IndexerService {
             pallet_payment_streams::Event::UserPaidSomeDebts { .. } => {}
             pallet_payment_streams::Event::UserSolvent { .. } => {}
             pallet_payment_streams::Event::InconsistentTickProcessing { .. } => {}
+            pallet_payment_streams::Event::EscrowOpened { .. } => {}
+            pallet_payment_streams::Event::EscrowProviderAdded { .. } => {}
+            pallet_payment_streams::Event::EscrowPaymentReleased { .. } => {}
+            pallet_payment_streams::Event::EscrowProviderRemoved { .. } => {}
+            pallet_payment_streams::Event::EscrowShortfallRefunded { .. } => {}
+            pallet_payment_streams::Event::EscrowClosed { .. } => {}
+            pallet_payment_streams::Event::FiatPriceTargetSet { .. } => {}
+            pallet_payment_streams::Event::PriceAdjustedToFiatTarget { .. } => {}
+            pallet_payment_streams::Event::OraclePriceStale { .. } => {}
+            // The auto top-up reserve only moves funds within the User's own account, it does not
+            // change any payment stream the indexer tracks.
+            pallet_payment_streams::Event::AutoTopUpReserveDeposited { .. } => {}
+            pallet_payment_streams::Event::AutoTopUpReserveWithdrawn { .. } => {}
+            pallet_payment_streams::Event::AutoTopUpThresholdSet { .. } => {}
+            pallet_payment_streams::Event::AutoTopUpExecuted { .. } => {}
             pallet_payment_streams::Event::__Ignore(_, _) => {}
         }
         Ok(())
@@ -413,13 +509,39 @@ impl IndexerService {
                     (*last_tick_proven).into(),
                 )
                 .await?;
+                Bsp::update_slashable(conn, provider.to_string(), false).await?;
             }
             pallet_proofs_dealer::Event::NewChallengeSeed { .. } => {}
             pallet_proofs_dealer::Event::NewCheckpointChallenge { .. } => {}
-            pallet_proofs_dealer::Event::SlashableProvider { .. } => {}
+            pallet_proofs_dealer::Event::SlashableProvider {
+                provider,
+                next_challenge_deadline: _,
+            } => {
+                Bsp::update_slashable(conn, provider.to_string(), true).await?;
+            }
+            pallet_proofs_dealer::Event::ProviderWarned { .. } => {}
             pallet_proofs_dealer::Event::NoRecordOfLastSubmittedProof { .. } => {}
             pallet_proofs_dealer::Event::NewChallengeCycleInitialised { .. } => {}
             pallet_proofs_dealer::Event::ChallengesTickerSet { .. } => {}
+            pallet_proofs_dealer::Event::CheckpointMutationsSummary {
+                provider,
+                checkpoint_tick: _,
+                removed_keys,
+                new_root,
+            } => {
+                Bsp::update_merkle_root(conn, provider.to_string(), new_root.as_ref().to_vec())
+                    .await?;
+
+                // The removed keys are no longer stored by the Provider.
+                for file_key in removed_keys {
+                    BspFile::delete(conn, file_key, provider.to_string()).await?;
+                }
+            }
+            pallet_proofs_dealer::Event::TicksCatchUpBacklogGrew { .. } => {}
+            pallet_proofs_dealer::Event::TicksCatchUpBacklogCleared { .. } => {}
+            pallet_proofs_dealer::Event::ChallengePeriodOverrideSet { .. } => {}
+            pallet_proofs_dealer::Event::RetentionPolicySet { .. } => {}
+            pallet_proofs_dealer::Event::HistoryPruned { .. } => {}
             pallet_proofs_dealer::Event::__Ignore(_, _) => {}
         }
         Ok(())
@@ -568,6 +690,8 @@ impl IndexerService {
             pallet_storage_providers::Event::TopUpFulfilled { .. } => {}
             pallet_storage_providers::Event::ValuePropAdded { .. } => {}
             pallet_storage_providers::Event::ValuePropUnavailable { .. } => {}
+            pallet_storage_providers::Event::ValuePropVersionPublished { .. } => {}
+            pallet_storage_providers::Event::BucketValuePropMigrated { .. } => {}
             pallet_storage_providers::Event::MultiAddressAdded { .. } => {}
             pallet_storage_providers::Event::MultiAddressRemoved { .. } => {}
             pallet_storage_providers::Event::ProviderInsolvent { .. } => {}
@@ -577,6 +701,14 @@ impl IndexerService {
             pallet_storage_providers::Event::BspDeleted { provider_id } => {
                 Bsp::delete(conn, provider_id.to_string()).await?;
             }
+            pallet_storage_providers::Event::CapacityAttestationRequested { .. } => {}
+            pallet_storage_providers::Event::CapacityPlotSeedDrawn { .. } => {}
+            pallet_storage_providers::Event::CapacityPlotCommitted { .. } => {}
+            pallet_storage_providers::Event::CapacityAttested { .. } => {}
+            pallet_storage_providers::Event::CapacityDecreaseRequested { .. } => {}
+            pallet_storage_providers::Event::CapacityDecreaseCancelled { .. } => {}
+            pallet_storage_providers::Event::ProviderStatusChanged { .. } => {}
+            pallet_storage_providers::Event::BspReputationWeightChanged { .. } => {}
             pallet_storage_providers::Event::__Ignore(_, _) => {}
         }
         Ok(())
@@ -648,6 +780,19 @@ impl ActorEventLoop<IndexerService> for IndexerServiceEventLoop {
     }
 }
 
+/// The key of the file an access grant to `target` gives access to, or `None` if it gives access
+/// to every file of the bucket.
+fn access_grant_file_key(
+    target: &pallet_file_system::types::AccessGrantTarget<storage_hub_runtime::Runtime>,
+) -> Option<Vec<u8>> {
+    match target {
+        pallet_file_system::types::AccessGrantTarget::Bucket(_) => None,
+        pallet_file_system::types::AccessGrantTarget::File { file_key, .. } => {
+            Some(file_key.as_ref().to_vec())
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum IndexBlockError {
     #[error("Database error: {0}")]
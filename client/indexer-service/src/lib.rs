@@ -1,12 +1,13 @@
+pub mod graphql;
 pub mod handler;
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use shc_actors_framework::actor::{ActorHandle, ActorSpawner, TaskSpawner};
 use shc_common::types::ParachainClient;
 use shc_indexer_db::DbPool;
 
-pub use self::handler::IndexerService;
+pub use self::{graphql::IndexerGraphqlServer, handler::IndexerService};
 
 pub async fn spawn_indexer_service(
     task_spawner: &TaskSpawner,
@@ -21,3 +22,16 @@ pub async fn spawn_indexer_service(
 
     task_spawner.spawn_actor(indexer_service)
 }
+
+/// Spawns the GraphQL API over the indexer database, listening on `listen_addr`.
+pub fn spawn_indexer_graphql_server(
+    task_spawner: &TaskSpawner,
+    db_pool: DbPool,
+    listen_addr: SocketAddr,
+) {
+    let task_spawner = task_spawner
+        .with_name("indexer-graphql")
+        .with_group("network");
+
+    task_spawner.spawn(IndexerGraphqlServer::new(db_pool, listen_addr).run());
+}
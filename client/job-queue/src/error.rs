@@ -0,0 +1,18 @@
+use std::io;
+
+use crate::types::{JobId, JobStatus};
+
+/// Error type for the job queue.
+#[derive(thiserror::Error, Debug)]
+pub enum JobQueueError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Codec(#[from] codec::Error),
+    #[error("Job {0} not found")]
+    JobNotFound(JobId),
+    #[error("No handler registered for jobs of kind `{0}`")]
+    UnknownJobKind(String),
+    #[error("Job {0} is already {1:?}")]
+    JobFinished(JobId, JobStatus),
+}
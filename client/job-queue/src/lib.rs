@@ -0,0 +1,27 @@
+//! Persistent queue of the long-running operations of a Provider node, such as downloading the
+//! files of a bucket moved to this MSP.
+//!
+//! Operations are defined as jobs (see [`JobDefinition`]), which are persisted when submitted, so
+//! that they survive restarts. Each kind of job is run by the [`JobHandler`] registered for it,
+//! which can save a checkpoint of its progress to pick up from where it left off if it is
+//! interrupted, retried or resumed after a restart. Failed jobs are retried with an exponential
+//! backoff, up to [`JobDefinition::MAX_ATTEMPTS`] times, and jobs can be cancelled while pending or
+//! running.
+//!
+//! The jobs are listed and inspected through the `storagehubclient_listJobs` and
+//! `storagehubclient_getJob` RPC methods. See [`queue::JobQueue`].
+
+mod error;
+pub mod queue;
+pub mod store;
+pub mod types;
+
+pub use error::JobQueueError;
+pub use queue::{JobContext, JobHandler, JobQueue};
+pub use store::JobStore;
+pub use types::{Job, JobDefinition, JobId, JobStatus};
+
+const LOG_TARGET: &str = "job-queue";
+
+/// Path of the job queue database, relative to the node's base path.
+pub const JOB_QUEUE_DB_PATH: &str = "storagehub/job_queue";
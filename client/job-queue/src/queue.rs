@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
+
+use codec::{Decode, Encode};
+use log::{debug, error, info, warn};
+use shc_actors_framework::actor::TaskSpawner;
+
+use crate::{
+    error::JobQueueError,
+    store::JobStore,
+    types::{Job, JobDefinition, JobId, JobStatus},
+    LOG_TARGET,
+};
+
+/// Delay before the first retry of a failed job, doubled on every subsequent retry.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(10);
+/// Maximum delay between retries of a failed job.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(600);
+
+/// Runs the jobs of the kind given by [`JobHandler::Job`].
+pub trait JobHandler: Clone + Send + Sync + 'static {
+    type Job: JobDefinition;
+
+    /// Runs a single attempt of the job defined by `job`.
+    ///
+    /// The job might have been interrupted or have failed before, so the handler should pick up
+    /// from the last checkpoint saved through `context`, if any, and save new ones as it makes
+    /// progress. Returning an error makes the job be retried, unless it ran out of attempts.
+    /// Long-running handlers should also check [`JobContext::is_cancelled`] regularly and return
+    /// early if the job was cancelled.
+    fn run(
+        &self,
+        job: Self::Job,
+        context: JobContext,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+type BoxedJobFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+/// A [`JobHandler`] with its job type erased, taking the encoded [`JobDefinition`].
+type ErasedJobHandler = Arc<dyn Fn(Vec<u8>, JobContext) -> BoxedJobFuture + Send + Sync>;
+
+/// Handle given to a [`JobHandler`] to save the progress of the job it runs and to check whether
+/// it was cancelled.
+#[derive(Clone)]
+pub struct JobContext {
+    id: JobId,
+    store: JobStore,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    /// The id of the job being run.
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// The last checkpoint saved for the job, if any.
+    pub fn checkpoint<C: Decode>(&self) -> Result<Option<C>, JobQueueError> {
+        let job = self
+            .store
+            .job(self.id)?
+            .ok_or(JobQueueError::JobNotFound(self.id))?;
+        if job.checkpoint.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(C::decode(&mut job.checkpoint.as_slice())?))
+    }
+
+    /// Persists `checkpoint` as the progress of the job, to be picked up from if it is interrupted
+    /// or retried.
+    pub fn save_checkpoint<C: Encode>(&self, checkpoint: &C) -> Result<(), JobQueueError> {
+        self.store
+            .update(self.id, |job| job.checkpoint = checkpoint.encode())?;
+
+        Ok(())
+    }
+
+    /// Whether the job was cancelled, in which case the handler should stop running it.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Queue running the long-running operations of the node as persistent jobs.
+///
+/// Cheap to clone, so that it can be shared between the tasks submitting jobs and the RPC methods
+/// inspecting them.
+#[derive(Clone)]
+pub struct JobQueue {
+    store: JobStore,
+    spawner: TaskSpawner,
+    handlers: Arc<RwLock<HashMap<&'static str, ErasedJobHandler>>>,
+    /// Cancellation flags of the jobs being run by this queue.
+    ///
+    /// Also guards the status transitions of those jobs, so that a job cancelled while it is being
+    /// run is not marked as running, completed or failed afterwards.
+    cancellations: Arc<Mutex<HashMap<JobId, Arc<AtomicBool>>>>,
+}
+
+impl JobQueue {
+    pub fn new(store: JobStore, spawner: TaskSpawner) -> Self {
+        Self {
+            store,
+            spawner,
+            handlers: Default::default(),
+            cancellations: Default::default(),
+        }
+    }
+
+    /// Registers `handler` to run the jobs of kind [`JobDefinition::KIND`], and resumes the ones
+    /// that were not finished when the node was last stopped.
+    pub fn register<H: JobHandler>(&self, handler: H) -> Result<(), JobQueueError> {
+        let kind = H::Job::KIND;
+        let erased: ErasedJobHandler = Arc::new(move |definition: Vec<u8>, context| {
+            let handler = handler.clone();
+            Box::pin(async move {
+                let job = H::Job::decode(&mut definition.as_slice())?;
+                handler.run(job, context).await
+            })
+        });
+        self.handlers
+            .write()
+            .expect("Job handlers lock poisoned")
+            .insert(kind, erased);
+
+        for job in self.store.jobs(None)? {
+            if job.kind == kind && !job.status.is_finished() {
+                info!(target: LOG_TARGET, "Resuming {} job {}", kind, job.id);
+                self.spawn(job.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Persists a new job for `definition` and starts running it.
+    pub fn submit<J: JobDefinition>(&self, definition: J) -> Result<JobId, JobQueueError> {
+        if self.handler(J::KIND).is_none() {
+            return Err(JobQueueError::UnknownJobKind(J::KIND.to_string()));
+        }
+
+        let id = self.store.insert(&definition)?;
+        info!(target: LOG_TARGET, "Submitted {} job {}", J::KIND, id);
+        self.spawn(id);
+
+        Ok(id)
+    }
+
+    /// Cancels the job with `id`.
+    ///
+    /// A job being run is only stopped once its handler notices it, but it is marked as cancelled
+    /// right away and it is not retried.
+    pub fn cancel(&self, id: JobId) -> Result<Job, JobQueueError> {
+        let cancellations = self
+            .cancellations
+            .lock()
+            .expect("Cancellations lock poisoned");
+        let job = self.store.job(id)?.ok_or(JobQueueError::JobNotFound(id))?;
+        if job.status.is_finished() {
+            return Err(JobQueueError::JobFinished(id, job.status));
+        }
+
+        if let Some(cancelled) = cancellations.get(&id) {
+            cancelled.store(true, Ordering::SeqCst);
+        }
+        info!(target: LOG_TARGET, "Cancelled {} job {}", job.kind, id);
+
+        self.store
+            .update(id, |job| job.status = JobStatus::Cancelled)
+    }
+
+    /// The job with `id`, if any.
+    pub fn job(&self, id: JobId) -> Result<Option<Job>, JobQueueError> {
+        self.store.job(id)
+    }
+
+    /// Every job, in order of submission, optionally only those with `status`.
+    pub fn jobs(&self, status: Option<&JobStatus>) -> Result<Vec<Job>, JobQueueError> {
+        self.store.jobs(status)
+    }
+
+    fn handler(&self, kind: &str) -> Option<ErasedJobHandler> {
+        self.handlers
+            .read()
+            .expect("Job handlers lock poisoned")
+            .get(kind)
+            .cloned()
+    }
+
+    fn spawn(&self, id: JobId) {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.cancellations
+            .lock()
+            .expect("Cancellations lock poisoned")
+            .insert(id, cancelled.clone());
+
+        let queue = self.clone();
+        self.spawner.spawn(async move {
+            if let Err(error) = queue.run(id, cancelled).await {
+                error!(target: LOG_TARGET, "Failed to run job {}: {:?}", id, error);
+            }
+            queue
+                .cancellations
+                .lock()
+                .expect("Cancellations lock poisoned")
+                .remove(&id);
+        });
+    }
+
+    /// Runs the job with `id` until it completes, fails in every attempt or is cancelled.
+    async fn run(&self, id: JobId, cancelled: Arc<AtomicBool>) -> Result<(), JobQueueError> {
+        loop {
+            let Some(job) = self.transition(id, &cancelled, |job| {
+                job.status = JobStatus::Running;
+                job.attempts += 1;
+            })?
+            else {
+                return Ok(());
+            };
+            let handler = self
+                .handler(&job.kind)
+                .ok_or_else(|| JobQueueError::UnknownJobKind(job.kind.clone()))?;
+
+            debug!(
+                target: LOG_TARGET,
+                "Running {} job {} (attempt {}/{})", job.kind, id, job.attempts, job.max_attempts
+            );
+            let context = JobContext {
+                id,
+                store: self.store.clone(),
+                cancelled: cancelled.clone(),
+            };
+            let result = handler(job.definition.clone(), context).await;
+
+            match result {
+                Ok(()) => {
+                    self.transition(id, &cancelled, |job| job.status = JobStatus::Completed)?;
+                    info!(target: LOG_TARGET, "Completed {} job {}", job.kind, id);
+                    return Ok(());
+                }
+                Err(job_error) if job.attempts >= job.max_attempts => {
+                    self.transition(id, &cancelled, |job| {
+                        job.status = JobStatus::Failed;
+                        job.last_error = Some(format!("{:?}", job_error));
+                    })?;
+                    error!(
+                        target: LOG_TARGET,
+                        "{} job {} failed after {} attempts: {:?}",
+                        job.kind, id, job.attempts, job_error
+                    );
+                    return Ok(());
+                }
+                Err(job_error) => {
+                    if self
+                        .transition(id, &cancelled, |job| {
+                            job.status = JobStatus::Pending;
+                            job.last_error = Some(format!("{:?}", job_error));
+                        })?
+                        .is_none()
+                    {
+                        return Ok(());
+                    }
+
+                    let delay = retry_delay(job.attempts);
+                    warn!(
+                        target: LOG_TARGET,
+                        "{} job {} failed, retrying in {:?}: {:?}", job.kind, id, delay, job_error
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Applies `f` to the job with `id`, unless it was cancelled, in which case `None` is returned.
+    fn transition(
+        &self,
+        id: JobId,
+        cancelled: &AtomicBool,
+        f: impl FnOnce(&mut Job),
+    ) -> Result<Option<Job>, JobQueueError> {
+        let _cancellations = self
+            .cancellations
+            .lock()
+            .expect("Cancellations lock poisoned");
+        if cancelled.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        // The job could also have been cancelled before this node was restarted.
+        let job = self.store.job(id)?.ok_or(JobQueueError::JobNotFound(id))?;
+        if job.status.is_finished() {
+            return Ok(None);
+        }
+
+        self.store.update(id, f).map(Some)
+    }
+}
+
+/// Delay before retrying a job that failed in its `attempts`th attempt.
+fn retry_delay(attempts: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_maximum() {
+        assert_eq!(retry_delay(1), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(2), RETRY_BASE_DELAY * 2);
+        assert_eq!(retry_delay(3), RETRY_BASE_DELAY * 4);
+        assert_eq!(retry_delay(40), MAX_RETRY_DELAY);
+    }
+}
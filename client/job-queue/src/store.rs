@@ -0,0 +1,218 @@
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode};
+use kvdb::{DBTransaction, KeyValueDB};
+
+use crate::{
+    error::JobQueueError,
+    types::{Job, JobDefinition, JobId, JobStatus},
+};
+
+/// Column holding every [`Job`], keyed by the big-endian encoded [`JobId`] so that they are
+/// iterated in order of submission.
+const JOBS_COLUMN: u32 = 0;
+/// Column holding the metadata of the queue.
+const METADATA_COLUMN: u32 = 1;
+const COLUMNS: u32 = 2;
+
+/// Id to assign to the next submitted job.
+const NEXT_JOB_ID_KEY: &[u8] = b"next_job_id";
+
+/// Persistent store of the jobs of the [`JobQueue`](crate::JobQueue).
+///
+/// Cheap to clone, so that it can be shared between the queue running the jobs and the RPC methods
+/// reading them.
+#[derive(Clone)]
+pub struct JobStore {
+    db: Arc<dyn KeyValueDB>,
+}
+
+impl JobStore {
+    /// Opens the store at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, JobQueueError> {
+        let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(COLUMNS);
+        let db = kvdb_rocksdb::Database::open(&db_config, path.as_ref())?;
+
+        Ok(Self::new(Arc::new(db)))
+    }
+
+    /// A store that is not persisted, i.e. whose jobs are lost on restart.
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(kvdb_memorydb::create(COLUMNS)))
+    }
+
+    /// Wraps `db`, which must have at least [`COLUMNS`] columns.
+    pub fn new(db: Arc<dyn KeyValueDB>) -> Self {
+        Self { db }
+    }
+
+    /// Persists a new pending job for `definition`, returning its id.
+    pub fn insert<J: JobDefinition>(&self, definition: &J) -> Result<JobId, JobQueueError> {
+        let id = self
+            .get::<JobId>(METADATA_COLUMN, NEXT_JOB_ID_KEY)?
+            .unwrap_or_default();
+        let now = now();
+        let job = Job {
+            id,
+            kind: J::KIND.to_string(),
+            definition: definition.encode(),
+            checkpoint: Vec::new(),
+            status: JobStatus::Pending,
+            attempts: 0,
+            max_attempts: J::MAX_ATTEMPTS,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut transaction = DBTransaction::new();
+        transaction.put_vec(JOBS_COLUMN, &id.to_be_bytes(), job.encode());
+        transaction.put_vec(
+            METADATA_COLUMN,
+            NEXT_JOB_ID_KEY,
+            id.saturating_add(1).encode(),
+        );
+        self.db.write(transaction)?;
+
+        Ok(id)
+    }
+
+    /// The job with `id`, if any.
+    pub fn job(&self, id: JobId) -> Result<Option<Job>, JobQueueError> {
+        self.get(JOBS_COLUMN, &id.to_be_bytes())
+    }
+
+    /// Every job, in order of submission, optionally only those with `status`.
+    pub fn jobs(&self, status: Option<&JobStatus>) -> Result<Vec<Job>, JobQueueError> {
+        let mut jobs = Vec::new();
+        for entry in self.db.iter(JOBS_COLUMN) {
+            let (_, value) = entry?;
+            let job = Job::decode(&mut value.as_slice())?;
+            if status.map_or(true, |status| job.status == *status) {
+                jobs.push(job);
+            }
+        }
+
+        Ok(jobs)
+    }
+
+    /// Applies `f` to the job with `id` and persists it, returning the updated job.
+    pub fn update(&self, id: JobId, f: impl FnOnce(&mut Job)) -> Result<Job, JobQueueError> {
+        let mut job = self.job(id)?.ok_or(JobQueueError::JobNotFound(id))?;
+        f(&mut job);
+        job.updated_at = now();
+
+        let mut transaction = DBTransaction::new();
+        transaction.put_vec(JOBS_COLUMN, &id.to_be_bytes(), job.encode());
+        self.db.write(transaction)?;
+
+        Ok(job)
+    }
+
+    /// Removes the finished jobs last updated before `updated_before` (in milliseconds since the
+    /// UNIX epoch), returning how many were removed.
+    pub fn prune_finished(&self, updated_before: u64) -> Result<usize, JobQueueError> {
+        let mut transaction = DBTransaction::new();
+        for job in self.jobs(None)? {
+            if job.status.is_finished() && job.updated_at < updated_before {
+                transaction.delete(JOBS_COLUMN, &job.id.to_be_bytes());
+            }
+        }
+        let pruned = transaction.ops.len();
+        self.db.write(transaction)?;
+
+        Ok(pruned)
+    }
+
+    fn get<T: Decode>(&self, column: u32, key: &[u8]) -> Result<Option<T>, JobQueueError> {
+        self.db
+            .get(column, key)?
+            .map(|value| T::decode(&mut value.as_slice()))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+/// Milliseconds since the UNIX epoch.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Encode, Decode)]
+    struct TestJob(u32);
+
+    impl JobDefinition for TestJob {
+        const KIND: &'static str = "test";
+        const MAX_ATTEMPTS: u32 = 2;
+    }
+
+    #[test]
+    fn jobs_are_listed_in_order_of_submission() {
+        let store = JobStore::in_memory();
+
+        let first = store.insert(&TestJob(1)).unwrap();
+        let second = store.insert(&TestJob(2)).unwrap();
+        assert_eq!((first, second), (0, 1));
+
+        let job = store.job(second).unwrap().unwrap();
+        assert_eq!(job.kind, "test");
+        assert_eq!(job.status, JobStatus::Pending);
+        assert_eq!(job.max_attempts, 2);
+        assert_eq!(
+            TestJob::decode(&mut job.definition.as_slice()).unwrap().0,
+            2
+        );
+
+        store
+            .update(first, |job| job.status = JobStatus::Completed)
+            .unwrap();
+
+        let ids = |status| {
+            store
+                .jobs(status)
+                .unwrap()
+                .into_iter()
+                .map(|job| job.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(None), vec![0, 1]);
+        assert_eq!(ids(Some(&JobStatus::Pending)), vec![1]);
+        assert_eq!(ids(Some(&JobStatus::Completed)), vec![0]);
+    }
+
+    #[test]
+    fn updating_a_missing_job_fails() {
+        let store = JobStore::in_memory();
+
+        assert!(matches!(
+            store.update(7, |job| job.attempts += 1),
+            Err(JobQueueError::JobNotFound(7))
+        ));
+    }
+
+    #[test]
+    fn only_finished_jobs_are_pruned() {
+        let store = JobStore::in_memory();
+
+        let finished = store.insert(&TestJob(1)).unwrap();
+        let pending = store.insert(&TestJob(2)).unwrap();
+        store
+            .update(finished, |job| job.status = JobStatus::Failed)
+            .unwrap();
+
+        assert_eq!(store.prune_finished(u64::MAX).unwrap(), 1);
+        assert!(store.job(finished).unwrap().is_none());
+        assert!(store.job(pending).unwrap().is_some());
+    }
+}
@@ -0,0 +1,67 @@
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// Identifier of a job, assigned in order of submission.
+pub type JobId = u64;
+
+/// A kind of long-running operation, whose instances are run as jobs by a
+/// [`JobHandler`](crate::JobHandler).
+///
+/// The definition is persisted SCALE-encoded when the job is submitted, so it has to contain
+/// everything needed to run the job again after a restart.
+pub trait JobDefinition: Encode + Decode + Send + 'static {
+    /// Name of this kind of job, which identifies the handler that runs it. It must not change
+    /// between versions of the node, since it is persisted along with the jobs.
+    const KIND: &'static str;
+    /// Amount of times a job is run before it is marked as failed.
+    const MAX_ATTEMPTS: u32 = 5;
+}
+
+/// Status of a job.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    /// Waiting to be run, either for the first time or to be retried.
+    Pending,
+    Running,
+    Completed,
+    /// Failed in every attempt. See [`Job::last_error`].
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job will not be run anymore.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// A job, as persisted in the [`JobStore`](crate::JobStore).
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: JobId,
+    /// [`JobDefinition::KIND`] of the job.
+    pub kind: String,
+    /// SCALE-encoded [`JobDefinition`] of the job.
+    #[serde(with = "sp_core::bytes")]
+    pub definition: Vec<u8>,
+    /// SCALE-encoded checkpoint of the progress of the job, as last saved by its handler.
+    #[serde(with = "sp_core::bytes")]
+    pub checkpoint: Vec<u8>,
+    pub status: JobStatus,
+    /// Amount of times the job was run.
+    pub attempts: u32,
+    /// Amount of times the job is run before it is marked as failed.
+    pub max_attempts: u32,
+    /// Error of the last failed attempt, if any.
+    pub last_error: Option<String>,
+    /// Milliseconds since the UNIX epoch at which the job was submitted.
+    pub created_at: u64,
+    /// Milliseconds since the UNIX epoch at which the job was last updated.
+    pub updated_at: u64,
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+
+use frame_system::Phase;
+use shc_common::types::{Balance, ProviderId, StorageHubEventsVec};
+use sp_runtime::AccountId32;
+use storage_hub_runtime::RuntimeEvent;
+
+use crate::types::Earnings;
+
+/// The earnings of `provider_id` in a block, given its events.
+///
+/// Charging a user splits the charged amount between the Provider and the treasury, but only the
+/// total is reported in the `PaymentStreamCharged` event. The treasury cut is the amount
+/// transferred from the charged user to `treasury_account` in the same extrinsic, since the
+/// previous `PaymentStreamCharged` event for that user (the transfers are made right before the
+/// event is emitted).
+pub fn block_earnings(
+    events: &StorageHubEventsVec,
+    provider_id: &ProviderId,
+    treasury_account: &AccountId32,
+) -> Earnings {
+    let mut earnings = Earnings::default();
+
+    // Amount transferred to the treasury by each user in the current phase, not yet attributed to
+    // a payment stream charge.
+    let mut pending_treasury_transfers: BTreeMap<AccountId32, Balance> = BTreeMap::new();
+    let mut current_phase: Option<Phase> = None;
+
+    for record in events {
+        if current_phase.as_ref() != Some(&record.phase) {
+            pending_treasury_transfers.clear();
+            current_phase = Some(record.phase.clone());
+        }
+
+        match &record.event {
+            RuntimeEvent::Balances(pallet_balances::Event::Transfer { from, to, amount })
+                if to == treasury_account =>
+            {
+                let transferred = pending_treasury_transfers.entry(from.clone()).or_default();
+                *transferred = transferred.saturating_add(*amount);
+            }
+            RuntimeEvent::PaymentStreams(pallet_payment_streams::Event::PaymentStreamCharged {
+                user_account,
+                provider_id: charged_provider_id,
+                amount,
+                ..
+            }) => {
+                let treasury_cut = pending_treasury_transfers
+                    .remove(user_account)
+                    .unwrap_or_default();
+
+                if charged_provider_id == provider_id {
+                    earnings.accrue(&Earnings {
+                        charged: *amount,
+                        treasury_cut: treasury_cut.min(*amount),
+                        charges: 1,
+                        ..Default::default()
+                    });
+                }
+            }
+            RuntimeEvent::Providers(pallet_storage_providers::Event::Slashed {
+                provider_id: slashed_provider_id,
+                amount,
+                ..
+            }) if slashed_provider_id == provider_id => {
+                earnings.accrue(&Earnings {
+                    slashed: *amount,
+                    slashes: 1,
+                    ..Default::default()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    earnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use frame_system::EventRecord;
+    use sp_core::H256;
+
+    fn record(extrinsic_index: u32, event: RuntimeEvent) -> Box<EventRecord<RuntimeEvent, H256>> {
+        Box::new(EventRecord {
+            phase: Phase::ApplyExtrinsic(extrinsic_index),
+            event,
+            topics: vec![],
+        })
+    }
+
+    fn transfer(from: &AccountId32, to: &AccountId32, amount: Balance) -> RuntimeEvent {
+        RuntimeEvent::Balances(pallet_balances::Event::Transfer {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+        })
+    }
+
+    fn charged(user: &AccountId32, provider_id: ProviderId, amount: Balance) -> RuntimeEvent {
+        RuntimeEvent::PaymentStreams(pallet_payment_streams::Event::PaymentStreamCharged {
+            user_account: user.clone(),
+            provider_id,
+            amount,
+            last_tick_charged: 10,
+            charged_at_tick: 10,
+        })
+    }
+
+    fn slashed(provider_id: ProviderId, amount: Balance) -> RuntimeEvent {
+        RuntimeEvent::Providers(pallet_storage_providers::Event::Slashed {
+            provider_id,
+            amount,
+            accrued_failed_proof_submissions: 1,
+            consecutive_missed_deadlines: 0,
+            used_capacity: 0,
+        })
+    }
+
+    #[test]
+    fn charges_of_the_provider_are_aggregated_with_their_treasury_cut() {
+        let provider_id = H256::repeat_byte(1);
+        let other_provider_id = H256::repeat_byte(2);
+        let provider_account = AccountId32::new([1; 32]);
+        let other_provider_account = AccountId32::new([2; 32]);
+        let treasury = AccountId32::new([0; 32]);
+        let alice = AccountId32::new([10; 32]);
+        let bob = AccountId32::new([11; 32]);
+
+        let events = vec![
+            // Alice is charged by this Provider.
+            record(0, transfer(&alice, &provider_account, 90)),
+            record(0, transfer(&alice, &treasury, 10)),
+            record(0, charged(&alice, provider_id, 100)),
+            // Bob is charged by another Provider in the same extrinsic.
+            record(0, transfer(&bob, &other_provider_account, 45)),
+            record(0, transfer(&bob, &treasury, 5)),
+            record(0, charged(&bob, other_provider_id, 50)),
+            // Bob is then charged by this Provider in another extrinsic.
+            record(1, transfer(&bob, &provider_account, 18)),
+            record(1, transfer(&bob, &treasury, 2)),
+            record(1, charged(&bob, provider_id, 20)),
+        ];
+
+        assert_eq!(
+            block_earnings(&events, &provider_id, &treasury),
+            Earnings {
+                charged: 120,
+                treasury_cut: 12,
+                charges: 2,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn treasury_transfers_are_not_attributed_across_extrinsics() {
+        let provider_id = H256::repeat_byte(1);
+        let treasury = AccountId32::new([0; 32]);
+        let alice = AccountId32::new([10; 32]);
+
+        let events = vec![
+            // Alice transfers to the treasury on her own.
+            record(0, transfer(&alice, &treasury, 1_000)),
+            // And is later charged without any treasury cut.
+            record(1, charged(&alice, provider_id, 100)),
+        ];
+
+        let earnings = block_earnings(&events, &provider_id, &treasury);
+        assert_eq!(earnings.charged, 100);
+        assert_eq!(earnings.treasury_cut, 0);
+        assert_eq!(earnings.net(), 100);
+    }
+
+    #[test]
+    fn only_slashes_of_the_provider_are_aggregated() {
+        let provider_id = H256::repeat_byte(1);
+        let treasury = AccountId32::new([0; 32]);
+
+        let events = vec![
+            record(0, slashed(provider_id, 30)),
+            record(1, slashed(H256::repeat_byte(2), 70)),
+        ];
+
+        let earnings = block_earnings(&events, &provider_id, &treasury);
+        assert_eq!(
+            earnings,
+            Earnings {
+                slashed: 30,
+                slashes: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(earnings.net(), -30);
+    }
+}
@@ -0,0 +1,20 @@
+use std::io;
+
+use shc_common::{blockchain_utils::EventsRetrievalError, types::BlockNumber};
+
+/// Error type for the aggregation of the earnings of a Provider.
+#[derive(thiserror::Error, Debug)]
+pub enum EarningsError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Codec(#[from] codec::Error),
+    #[error(transparent)]
+    Events(#[from] EventsRetrievalError),
+    #[error("Failed to read the state of block {0}: {1}")]
+    Blockchain(BlockNumber, sp_blockchain::Error),
+    #[error("Block {0} not found")]
+    BlockNotFound(BlockNumber),
+    #[error("{0} not found in the state of block {1}")]
+    StorageNotFound(&'static str, BlockNumber),
+}
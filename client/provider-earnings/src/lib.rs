@@ -0,0 +1,31 @@
+//! Local aggregation of the earnings of the Storage Provider managed by a node, for operators that
+//! don't run the indexer.
+//!
+//! Every finalised block is scanned for the events affecting the Provider:
+//! - `PaymentStreamCharged`: a user was charged for the Provider's services. Part of the charged
+//!   amount goes to the treasury, which is taken from the transfers made while charging the user.
+//!   See [`aggregation::block_earnings`].
+//! - `Slashed`: the Provider was slashed for failing to submit proofs.
+//!
+//! The earnings are aggregated per day (UTC, according to the timestamp of each block) and
+//! persisted, so that they survive restarts. On first run, the earnings of the last finalised
+//! blocks are backfilled. They are read through the `storagehubclient_getProviderEarnings` RPC
+//! method and exported as Prometheus metrics. See [`service::ProviderEarningsService`].
+
+pub mod aggregation;
+mod error;
+pub mod metrics;
+pub mod service;
+pub mod store;
+pub mod types;
+
+pub use error::EarningsError;
+pub use metrics::EarningsMetrics;
+pub use service::{ProviderEarningsConfig, ProviderEarningsService, DEFAULT_BACKFILL_DEPTH};
+pub use store::EarningsStore;
+pub use types::{DailyEarnings, Day, Earnings};
+
+const LOG_TARGET: &str = "provider-earnings";
+
+/// Path of the earnings database, relative to the node's base path.
+pub const PROVIDER_EARNINGS_DB_PATH: &str = "storagehub/provider_earnings";
@@ -0,0 +1,80 @@
+use shc_common::types::BlockNumber;
+use substrate_prometheus_endpoint::{
+    register, Gauge, GaugeVec, Opts, PrometheusError, Registry, F64, U64,
+};
+
+use crate::types::Earnings;
+
+/// Prometheus metrics of the earnings of the Provider managed by this node.
+///
+/// Balances are exported as floating point numbers, since they don't fit in the integer gauges.
+#[derive(Clone)]
+pub struct EarningsMetrics {
+    /// Earnings since the aggregation started, by kind.
+    total: GaugeVec<F64>,
+    /// Earnings of the current day, by kind.
+    today: GaugeVec<F64>,
+    /// Last block whose events were aggregated.
+    last_processed_block: Gauge<U64>,
+}
+
+impl EarningsMetrics {
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            total: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "storagehub_provider_earnings_total",
+                        "Earnings of this Provider since they started being aggregated",
+                    ),
+                    &["kind"],
+                )?,
+                registry,
+            )?,
+            today: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "storagehub_provider_earnings_today",
+                        "Earnings of this Provider in the current day (UTC)",
+                    ),
+                    &["kind"],
+                )?,
+                registry,
+            )?,
+            last_processed_block: register(
+                Gauge::new(
+                    "storagehub_provider_earnings_last_processed_block",
+                    "Last finalised block whose events were aggregated into the earnings of this Provider",
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    pub fn update(&self, total: &Earnings, today: &Earnings, last_processed_block: BlockNumber) {
+        Self::set(&self.total, total);
+        Self::set(&self.today, today);
+        self.last_processed_block.set(last_processed_block.into());
+    }
+
+    fn set(gauges: &GaugeVec<F64>, earnings: &Earnings) {
+        gauges
+            .with_label_values(&["charged"])
+            .set(earnings.charged as f64);
+        gauges
+            .with_label_values(&["treasury_cut"])
+            .set(earnings.treasury_cut as f64);
+        gauges
+            .with_label_values(&["slashed"])
+            .set(earnings.slashed as f64);
+        gauges
+            .with_label_values(&["net"])
+            .set(earnings.net() as f64);
+        gauges
+            .with_label_values(&["charges"])
+            .set(earnings.charges.into());
+        gauges
+            .with_label_values(&["slashes"])
+            .set(earnings.slashes.into());
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use codec::Decode;
+use frame_support::traits::Get;
+use futures::StreamExt;
+use log::{debug, error, info, warn};
+use sc_client_api::{BlockchainEvents, StorageKey, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, H256};
+use sp_runtime::{traits::Header, SaturatedConversion};
+
+use shc_blockchain_service::commands::BlockchainServiceInterface;
+use shc_common::{
+    blockchain_utils::get_events_at_block,
+    types::{BlockNumber, ParachainClient, ProviderId, StorageProviderId},
+};
+
+use crate::{
+    aggregation::block_earnings,
+    error::EarningsError,
+    metrics::EarningsMetrics,
+    store::EarningsStore,
+    types::{day_of_timestamp, Day},
+    LOG_TARGET,
+};
+
+/// Default amount of finalised blocks to backfill the earnings from on first run.
+///
+/// Around 30 days, with 6 seconds blocks.
+pub const DEFAULT_BACKFILL_DEPTH: BlockNumber = 30 * 24 * 60 * 10;
+
+/// Amount of blocks processed in a row while catching up, before updating the metrics and
+/// yielding to other tasks.
+const BLOCKS_PER_BATCH: usize = 1_000;
+
+/// Configuration of the aggregation of the earnings of the Provider managed by this node.
+#[derive(Clone)]
+pub struct ProviderEarningsConfig {
+    pub store: EarningsStore,
+    /// Prometheus metrics to export the earnings to, if the node exports metrics.
+    pub metrics: Option<EarningsMetrics>,
+    /// Amount of finalised blocks to backfill the earnings from on first run.
+    pub backfill_depth: BlockNumber,
+}
+
+/// Aggregates the earnings of the Provider managed by this node from every finalised block.
+///
+/// The Provider ID is taken from the Blockchain Service, so nothing is aggregated until the node
+/// is registered as a Provider. If the store holds the earnings of a different Provider (i.e. the
+/// node's identity changed), they are discarded and the aggregation starts over.
+pub struct ProviderEarningsService<BS> {
+    client: Arc<ParachainClient>,
+    blockchain: BS,
+    store: EarningsStore,
+    metrics: Option<EarningsMetrics>,
+    backfill_depth: BlockNumber,
+    provider_id: Option<ProviderId>,
+}
+
+impl<BS> ProviderEarningsService<BS>
+where
+    BS: BlockchainServiceInterface + Send + Sync,
+{
+    pub fn new(
+        client: Arc<ParachainClient>,
+        blockchain: BS,
+        config: ProviderEarningsConfig,
+    ) -> Self {
+        Self {
+            client,
+            blockchain,
+            store: config.store,
+            metrics: config.metrics,
+            backfill_depth: config.backfill_depth,
+            provider_id: None,
+        }
+    }
+
+    /// Aggregates the earnings of every block as it is finalised, until the node shuts down.
+    pub async fn run(mut self) {
+        let mut finality_notification_stream = self.client.finality_notification_stream();
+
+        while let Some(notification) = finality_notification_stream.next().await {
+            let block_number: BlockNumber = (*notification.header.number()).saturated_into();
+
+            if let Err(e) = self.catch_up(block_number).await {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to aggregate the earnings of finalised block #{}: {}", block_number, e
+                );
+            }
+        }
+    }
+
+    /// Aggregates the earnings of every block not processed yet, up to `finalised_block`.
+    ///
+    /// On first run, this backfills the earnings of the last `backfill_depth` finalised blocks.
+    async fn catch_up(&mut self, finalised_block: BlockNumber) -> Result<(), EarningsError> {
+        let Some(provider_id) = self.managed_provider_id().await? else {
+            debug!(target: LOG_TARGET, "Node is not registered as a Provider yet, not aggregating earnings");
+            return Ok(());
+        };
+
+        let from = match self.store.last_processed_block()? {
+            Some(last_processed_block) => last_processed_block.saturating_add(1),
+            None => {
+                let from = finalised_block.saturating_sub(self.backfill_depth);
+                info!(
+                    target: LOG_TARGET,
+                    "💰 Backfilling the earnings of Provider [{:?}] from finalised blocks #{} to #{}",
+                    provider_id,
+                    from,
+                    finalised_block
+                );
+                from
+            }
+        };
+
+        let mut skipped_blocks = 0;
+        for (processed, block_number) in (from..=finalised_block).enumerate() {
+            match self.process_block(&provider_id, block_number) {
+                Ok(()) => {}
+                Err(EarningsError::Io(e)) => return Err(EarningsError::Io(e)),
+                Err(e) => {
+                    // The state of old blocks might be pruned, or not decodable with the current
+                    // runtime types. Skip them instead of getting stuck.
+                    debug!(target: LOG_TARGET, "Skipping earnings of block #{}: {}", block_number, e);
+                    skipped_blocks += 1;
+                    self.store.record_block(
+                        &provider_id,
+                        block_number,
+                        Day::default(),
+                        &Default::default(),
+                    )?;
+                }
+            }
+
+            if (processed + 1) % BLOCKS_PER_BATCH == 0 {
+                self.update_metrics(block_number)?;
+                tokio::task::yield_now().await;
+            }
+        }
+
+        if skipped_blocks > 0 {
+            warn!(
+                target: LOG_TARGET,
+                "Skipped the earnings of {} blocks between #{} and #{}, whose state is not available",
+                skipped_blocks,
+                from,
+                finalised_block
+            );
+        }
+
+        self.update_metrics(finalised_block)
+    }
+
+    /// Aggregates the earnings of `provider_id` in block `block_number`.
+    fn process_block(
+        &self,
+        provider_id: &ProviderId,
+        block_number: BlockNumber,
+    ) -> Result<(), EarningsError> {
+        let block_hash = self
+            .client
+            .hash(block_number)
+            .map_err(|e| EarningsError::Blockchain(block_number, e))?
+            .ok_or(EarningsError::BlockNotFound(block_number))?;
+
+        let events = get_events_at_block(&self.client, &block_hash)?;
+        let treasury_account =
+            <storage_hub_runtime::Runtime as pallet_payment_streams::Config>::TreasuryAccount::get(
+            );
+        let earnings = block_earnings(&events, provider_id, &treasury_account);
+
+        let day = if earnings.is_empty() {
+            Day::default()
+        } else {
+            day_of_timestamp(self.timestamp_at(&block_hash, block_number)?)
+        };
+
+        self.store
+            .record_block(provider_id, block_number, day, &earnings)
+    }
+
+    /// The timestamp set in a block, in milliseconds since the UNIX epoch.
+    fn timestamp_at(
+        &self,
+        block_hash: &H256,
+        block_number: BlockNumber,
+    ) -> Result<u64, EarningsError> {
+        let timestamp_now_key = [twox_128(b"Timestamp"), twox_128(b"Now")].concat();
+
+        let raw_timestamp = self
+            .client
+            .storage(*block_hash, &StorageKey(timestamp_now_key))
+            .map_err(|e| EarningsError::Blockchain(block_number, e))?
+            .ok_or(EarningsError::StorageNotFound("Timestamp", block_number))?;
+
+        Ok(u64::decode(&mut raw_timestamp.0.as_slice())?)
+    }
+
+    /// The Provider ID of this node, once it is registered as a Provider.
+    async fn managed_provider_id(&mut self) -> Result<Option<ProviderId>, EarningsError> {
+        if self.provider_id.is_some() {
+            return Ok(self.provider_id);
+        }
+
+        let provider_id = match self.blockchain.query_storage_provider_id(None).await {
+            Ok(Some(StorageProviderId::BackupStorageProvider(id)))
+            | Ok(Some(StorageProviderId::MainStorageProvider(id))) => id,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to query the Provider ID of this node: {:?}", e);
+                return Ok(None);
+            }
+        };
+
+        match self.store.provider_id()? {
+            Some(stored_provider_id) if stored_provider_id != provider_id => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Discarding the earnings aggregated for Provider [{:?}], since this node now manages Provider [{:?}]",
+                    stored_provider_id,
+                    provider_id
+                );
+                self.store.clear()?;
+            }
+            _ => {}
+        }
+
+        self.provider_id = Some(provider_id);
+        Ok(self.provider_id)
+    }
+
+    fn update_metrics(&self, last_processed_block: BlockNumber) -> Result<(), EarningsError> {
+        let Some(metrics) = &self.metrics else {
+            return Ok(());
+        };
+
+        let now_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let today = self
+            .store
+            .day(day_of_timestamp(now_millis.saturated_into()))?;
+        metrics.update(&self.store.total()?, &today, last_processed_block);
+
+        Ok(())
+    }
+}
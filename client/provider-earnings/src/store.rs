@@ -0,0 +1,225 @@
+use std::{ops::RangeInclusive, path::Path, sync::Arc};
+
+use codec::{Decode, Encode};
+use kvdb::{DBTransaction, KeyValueDB};
+use shc_common::types::{BlockNumber, ProviderId};
+
+use crate::{
+    error::EarningsError,
+    types::{DailyEarnings, Day, Earnings},
+};
+
+/// Column holding the [`Earnings`] of each day, keyed by the big-endian encoded [`Day`] so that
+/// they are iterated in order.
+const DAILY_EARNINGS_COLUMN: u32 = 0;
+/// Column holding the metadata of the aggregation.
+const METADATA_COLUMN: u32 = 1;
+const COLUMNS: u32 = 2;
+
+/// Last block whose events were aggregated.
+const LAST_PROCESSED_BLOCK_KEY: &[u8] = b"last_processed_block";
+/// Provider whose earnings are aggregated.
+const PROVIDER_ID_KEY: &[u8] = b"provider_id";
+
+/// Persistent store of the daily earnings of the Provider managed by this node.
+///
+/// Cheap to clone, so that it can be shared between the service aggregating the earnings and the
+/// RPC methods reading them.
+#[derive(Clone)]
+pub struct EarningsStore {
+    db: Arc<dyn KeyValueDB>,
+}
+
+impl EarningsStore {
+    /// Opens the store at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EarningsError> {
+        let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(COLUMNS);
+        let db = kvdb_rocksdb::Database::open(&db_config, path.as_ref())?;
+
+        Ok(Self::new(Arc::new(db)))
+    }
+
+    /// Wraps `db`, which must have at least [`COLUMNS`] columns.
+    pub fn new(db: Arc<dyn KeyValueDB>) -> Self {
+        Self { db }
+    }
+
+    /// The last block whose events were aggregated, if any.
+    pub fn last_processed_block(&self) -> Result<Option<BlockNumber>, EarningsError> {
+        self.get(METADATA_COLUMN, LAST_PROCESSED_BLOCK_KEY)
+    }
+
+    /// The Provider whose earnings are aggregated, if any block was processed already.
+    pub fn provider_id(&self) -> Result<Option<ProviderId>, EarningsError> {
+        self.get(METADATA_COLUMN, PROVIDER_ID_KEY)
+    }
+
+    /// The earnings of `day`.
+    pub fn day(&self, day: Day) -> Result<Earnings, EarningsError> {
+        Ok(self
+            .get(DAILY_EARNINGS_COLUMN, &day.to_be_bytes())?
+            .unwrap_or_default())
+    }
+
+    /// The earnings of every day in `days` in which there were any, oldest first.
+    pub fn days(&self, days: RangeInclusive<Day>) -> Result<Vec<DailyEarnings>, EarningsError> {
+        let mut daily_earnings = Vec::new();
+        for entry in self.db.iter(DAILY_EARNINGS_COLUMN) {
+            let (key, value) = entry?;
+            let day = Day::from_be_bytes(key.as_ref().try_into().map_err(|_| {
+                codec::Error::from("Daily earnings key is not a big-endian encoded day")
+            })?);
+
+            if day < *days.start() {
+                continue;
+            }
+            if day > *days.end() {
+                break;
+            }
+
+            daily_earnings.push(DailyEarnings {
+                day,
+                earnings: Earnings::decode(&mut value.as_slice())?,
+            });
+        }
+
+        Ok(daily_earnings)
+    }
+
+    /// The earnings of every day aggregated so far.
+    pub fn total(&self) -> Result<Earnings, EarningsError> {
+        let mut total = Earnings::default();
+        for daily_earnings in self.days(Day::MIN..=Day::MAX)? {
+            total.accrue(&daily_earnings.earnings);
+        }
+
+        Ok(total)
+    }
+
+    /// Adds the `earnings` of `provider_id` in block `block_number`, made on `day`, and marks the
+    /// block as processed.
+    pub fn record_block(
+        &self,
+        provider_id: &ProviderId,
+        block_number: BlockNumber,
+        day: Day,
+        earnings: &Earnings,
+    ) -> Result<(), EarningsError> {
+        let mut transaction = DBTransaction::new();
+
+        if !earnings.is_empty() {
+            let mut day_earnings = self.day(day)?;
+            day_earnings.accrue(earnings);
+            transaction.put_vec(
+                DAILY_EARNINGS_COLUMN,
+                &day.to_be_bytes(),
+                day_earnings.encode(),
+            );
+        }
+        transaction.put_vec(
+            METADATA_COLUMN,
+            LAST_PROCESSED_BLOCK_KEY,
+            block_number.encode(),
+        );
+        transaction.put_vec(METADATA_COLUMN, PROVIDER_ID_KEY, provider_id.encode());
+
+        Ok(self.db.write(transaction)?)
+    }
+
+    /// Removes every aggregate, i.e. to start over for a different Provider.
+    pub fn clear(&self) -> Result<(), EarningsError> {
+        let mut transaction = DBTransaction::new();
+        transaction.delete_prefix(DAILY_EARNINGS_COLUMN, &[]);
+        transaction.delete_prefix(METADATA_COLUMN, &[]);
+
+        Ok(self.db.write(transaction)?)
+    }
+
+    fn get<T: Decode>(&self, column: u32, key: &[u8]) -> Result<Option<T>, EarningsError> {
+        self.db
+            .get(column, key)?
+            .map(|value| T::decode(&mut value.as_slice()))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sp_core::H256;
+
+    fn store() -> EarningsStore {
+        EarningsStore::new(Arc::new(kvdb_memorydb::create(COLUMNS)))
+    }
+
+    fn charged(amount: u128) -> Earnings {
+        Earnings {
+            charged: amount,
+            treasury_cut: amount / 10,
+            charges: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn blocks_are_aggregated_per_day() {
+        let store = store();
+        let provider_id = H256::repeat_byte(1);
+
+        assert_eq!(store.last_processed_block().unwrap(), None);
+
+        store
+            .record_block(&provider_id, 1, 100, &charged(100))
+            .unwrap();
+        store
+            .record_block(&provider_id, 2, 100, &charged(50))
+            .unwrap();
+        store
+            .record_block(&provider_id, 3, 101, &Earnings::default())
+            .unwrap();
+        store
+            .record_block(&provider_id, 4, 102, &charged(10))
+            .unwrap();
+
+        assert_eq!(store.last_processed_block().unwrap(), Some(4));
+        assert_eq!(store.provider_id().unwrap(), Some(provider_id));
+        assert_eq!(
+            store.day(100).unwrap(),
+            Earnings {
+                charged: 150,
+                treasury_cut: 15,
+                charges: 2,
+                ..Default::default()
+            }
+        );
+
+        // Days without earnings are skipped.
+        let days: Vec<Day> = store
+            .days(100..=102)
+            .unwrap()
+            .into_iter()
+            .map(|daily_earnings| daily_earnings.day)
+            .collect();
+        assert_eq!(days, vec![100, 102]);
+        assert_eq!(store.days(101..=101).unwrap(), vec![]);
+
+        assert_eq!(store.total().unwrap().charged, 160);
+    }
+
+    #[test]
+    fn clear_removes_every_aggregate() {
+        let store = store();
+        let provider_id = H256::repeat_byte(1);
+
+        store
+            .record_block(&provider_id, 1, 100, &charged(100))
+            .unwrap();
+        store.clear().unwrap();
+
+        assert_eq!(store.last_processed_block().unwrap(), None);
+        assert_eq!(store.provider_id().unwrap(), None);
+        assert_eq!(store.total().unwrap(), Earnings::default());
+    }
+}
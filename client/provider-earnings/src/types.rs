@@ -0,0 +1,60 @@
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use shc_common::types::Balance;
+
+/// Milliseconds in a day.
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// A day, as the number of days since the UNIX epoch (UTC).
+pub type Day = u32;
+
+/// The day of a timestamp in milliseconds since the UNIX epoch.
+pub fn day_of_timestamp(timestamp_millis: u64) -> Day {
+    (timestamp_millis / MILLIS_PER_DAY) as Day
+}
+
+/// Earnings of a Provider over a period of time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Earnings {
+    /// Total amount charged to users for the Provider's services, including the treasury cut.
+    pub charged: Balance,
+    /// Part of [`Earnings::charged`] that went to the treasury instead of the Provider.
+    pub treasury_cut: Balance,
+    /// Total amount slashed from the Provider's stake.
+    pub slashed: Balance,
+    /// Amount of payment stream charges.
+    pub charges: u32,
+    /// Amount of slashes.
+    pub slashes: u32,
+}
+
+impl Earnings {
+    /// What the Provider actually received, minus what it was slashed. Negative if it was slashed
+    /// more than it earned.
+    pub fn net(&self) -> i128 {
+        let received = self.charged.saturating_sub(self.treasury_cut);
+        (received as i128).saturating_sub(self.slashed as i128)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Adds `other` to these earnings.
+    pub fn accrue(&mut self, other: &Earnings) {
+        self.charged = self.charged.saturating_add(other.charged);
+        self.treasury_cut = self.treasury_cut.saturating_add(other.treasury_cut);
+        self.slashed = self.slashed.saturating_add(other.slashed);
+        self.charges = self.charges.saturating_add(other.charges);
+        self.slashes = self.slashes.saturating_add(other.slashes);
+    }
+}
+
+/// The earnings of a Provider in a given day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyEarnings {
+    pub day: Day,
+    pub earnings: Earnings,
+}
@@ -1,29 +1,57 @@
-use std::{fmt::Debug, fs::File, io::Read, io::Write, path::PathBuf, sync::Arc};
+use std::{fmt::Debug, fs::File, io::Read, io::Write, path::PathBuf, str::FromStr, sync::Arc};
 
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     proc_macros::rpc,
     types::error::{ErrorObjectOwned as JsonRpseeError, INTERNAL_ERROR_CODE, INTERNAL_ERROR_MSG},
+    Extensions,
 };
-use log::{debug, error, info};
+use log::{debug, error, info, LevelFilter};
+use sc_network_types::PeerId;
+use sc_rpc_api::check_if_safe;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use tokio::{fs, fs::create_dir_all, sync::RwLock};
 
+use pallet_file_system_runtime_api::FileSystemApi as FileSystemRuntimeApi;
 use pallet_proofs_dealer_runtime_api::ProofsDealerApi as ProofsDealerRuntimeApi;
+use shc_actors_framework::actor::ActorHandle;
 use shc_common::{
     consts::CURRENT_FOREST_KEY,
     types::{
-        BlockNumber, ChunkId, FileMetadata, ForestLeaf, HashT, KeyProof, KeyProofs,
-        ProofsDealerProviderId, Proven, RandomnessOutput, StorageProof,
-        StorageProofsMerkleTrieLayout, TrieRemoveMutation, BCSV_KEY_TYPE, FILE_CHUNK_SIZE,
+        BackupStorageProviderId, Balance, BlockNumber, BucketId, ChunkId, FileMetadata, ForestLeaf,
+        HashT, KeyProof, KeyProofs, MainStorageProviderId, ProofsDealerProviderId, Proven,
+        ProviderId, RandomnessOutput, ReplicationTarget, StorageProof,
+        StorageProofsMerkleTrieLayout, StorageRequestMetadata, TrieRemoveMutation, BCSV_KEY_TYPE,
+        FILE_CHUNK_SIZE,
     },
 };
+use shc_config_watcher::{commands::ConfigWatcherServiceInterface, ConfigWatcherService};
+use shc_db_backup::{
+    rocksdb::{discover_databases, OnlineSnapshot},
+    BackupEntry, BackupError, ChainPoint, DatabaseKind, DbBackupConfig,
+};
 use shc_file_manager::traits::{FileDataTrie, FileStorage, FileStorageError};
+use shc_file_transfer_service::{
+    commands::FileTransferServiceInterface, BandwidthLimits, FileTransferService, PeerScoreInfo,
+    PeerSetDiagnostics, TransferProgress,
+};
 use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_indexer_db::{
+    models::{
+        Bucket, FileExistenceScope, FileFilter, FileStorageRequestStep, Pagination, ServiceState,
+    },
+    DbConnection, DbPool,
+};
+use shc_job_queue::{Job, JobId, JobQueue, JobStatus};
+use shc_provider_earnings::{DailyEarnings, Day, EarningsStore};
+use shc_volunteer_policy::{VolunteerPolicy, VolunteerPolicyEngine};
 use sp_core::{sr25519::Pair as Sr25519Pair, Encode, Pair, H256};
 use sp_keystore::{Keystore, KeystorePtr};
-use sp_runtime::{traits::Block as BlockT, AccountId32, Deserialize, KeyTypeId, Serialize};
+use sp_runtime::{
+    traits::{Block as BlockT, SaturatedConversion},
+    AccountId32, Deserialize, KeyTypeId, Serialize,
+};
 
 const LOG_TARGET: &str = "storage-hub-client-rpc";
 
@@ -43,6 +71,13 @@ pub struct StorageHubClientRpcConfig<FL, FSH> {
     pub file_storage: Arc<RwLock<FL>>,
     pub forest_storage_handler: FSH,
     pub keystore: KeystorePtr,
+    pub db_backup: Option<DbBackupConfig>,
+    pub provider_earnings: Option<EarningsStore>,
+    pub file_transfer: Option<ActorHandle<FileTransferService>>,
+    pub job_queue: Option<JobQueue>,
+    pub volunteer_policy: Option<VolunteerPolicyEngine>,
+    pub indexer_db_pool: Option<DbPool>,
+    pub config_watcher: Option<ActorHandle<ConfigWatcherService>>,
 }
 
 impl<FL, FSH: Clone> Clone for StorageHubClientRpcConfig<FL, FSH> {
@@ -51,6 +86,13 @@ impl<FL, FSH: Clone> Clone for StorageHubClientRpcConfig<FL, FSH> {
             file_storage: self.file_storage.clone(),
             forest_storage_handler: self.forest_storage_handler.clone(),
             keystore: self.keystore.clone(),
+            db_backup: self.db_backup.clone(),
+            provider_earnings: self.provider_earnings.clone(),
+            file_transfer: self.file_transfer.clone(),
+            job_queue: self.job_queue.clone(),
+            volunteer_policy: self.volunteer_policy.clone(),
+            indexer_db_pool: self.indexer_db_pool.clone(),
+            config_watcher: self.config_watcher.clone(),
         }
     }
 }
@@ -69,8 +111,143 @@ where
             file_storage,
             forest_storage_handler,
             keystore,
+            db_backup: None,
+            provider_earnings: None,
+            file_transfer: None,
+            job_queue: None,
+            volunteer_policy: None,
+            indexer_db_pool: None,
+            config_watcher: None,
         }
     }
+
+    /// Enables the database backup RPC methods.
+    pub fn with_db_backup(mut self, db_backup: DbBackupConfig) -> Self {
+        self.db_backup = Some(db_backup);
+        self
+    }
+
+    /// Enables the RPC method to read the earnings aggregated locally by this Provider node.
+    pub fn with_provider_earnings(mut self, provider_earnings: EarningsStore) -> Self {
+        self.provider_earnings = Some(provider_earnings);
+        self
+    }
+
+    /// Enables the RPC method to inspect the peer set managed by the File Transfer Service.
+    pub fn with_file_transfer(mut self, file_transfer: ActorHandle<FileTransferService>) -> Self {
+        self.file_transfer = Some(file_transfer);
+        self
+    }
+
+    /// Enables the RPC methods to inspect and cancel the jobs of this Provider node.
+    pub fn with_job_queue(mut self, job_queue: JobQueue) -> Self {
+        self.job_queue = Some(job_queue);
+        self
+    }
+
+    /// Enables the RPC methods to read and replace the volunteering policy of this BSP node.
+    pub fn with_volunteer_policy(mut self, volunteer_policy: VolunteerPolicyEngine) -> Self {
+        self.volunteer_policy = Some(volunteer_policy);
+        self
+    }
+
+    /// Enables the RPC methods to query the buckets, files and providers tracked by the indexer.
+    pub fn with_indexer_db_pool(mut self, indexer_db_pool: DbPool) -> Self {
+        self.indexer_db_pool = Some(indexer_db_pool);
+        self
+    }
+
+    /// Enables the RPC method to reload the configuration file of this Provider node.
+    pub fn with_config_watcher(
+        mut self,
+        config_watcher: ActorHandle<ConfigWatcherService>,
+    ) -> Self {
+        self.config_watcher = Some(config_watcher);
+        self
+    }
+}
+
+/// A file stored by this node, as listed by the `getLocalFiles` RPC method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalFile {
+    pub file_key: H256,
+    pub file_metadata: FileMetadata,
+}
+
+/// A file tracked by the indexer, as listed by the `getIndexedBucketFiles` RPC method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedFile {
+    pub file_key: H256,
+    pub file_metadata: FileMetadata,
+    /// Whether the storage request of the file was fulfilled. Otherwise, it is still open.
+    pub fulfilled: bool,
+}
+
+/// A BSP storing a file according to the indexer, as returned by the `getIndexedFileBsps` RPC
+/// method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedFileBsp {
+    pub bsp_id: String,
+    /// The last tick proven by the BSP. `0` if it has not submitted any accepted proof yet.
+    pub last_tick_proven: i64,
+    pub slashable: bool,
+}
+
+/// A stored file with a given fingerprint according to the indexer, as returned by the
+/// `findIndexedFilesByFingerprint` RPC method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedFileMatch {
+    pub file_key: H256,
+    pub bucket_id: H256,
+    /// The MSP storing the bucket of the file, if any.
+    pub msp_id: Option<String>,
+    /// The BSPs storing the file.
+    pub bsp_ids: Vec<String>,
+}
+
+/// How far the indexer is in indexing the finalised blocks, as returned by the
+/// `getIndexerSyncStatus` RPC method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerSyncStatus {
+    /// The last block indexed. The indexer starts from genesis, and catches up with the finalised
+    /// blocks one by one.
+    pub last_indexed_block: BlockNumber,
+    /// The last block finalised, as seen by this node.
+    pub finalised_block: BlockNumber,
+}
+
+/// The proofs a Provider has yet to submit, as returned by the `getPendingProofs` RPC method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingProofs {
+    /// The current tick.
+    pub current_tick: BlockNumber,
+    /// The last tick for which the Provider submitted a valid proof.
+    pub last_tick_proven: BlockNumber,
+    /// The last tick in which the proof for the first pending tick is accepted.
+    pub next_deadline_tick: BlockNumber,
+    /// The ticks the Provider has to submit a proof for, up to the current tick, oldest first.
+    pub pending_ticks: Vec<BlockNumber>,
+}
+
+/// What issuing a storage request would cost its User at the current prices, as returned by the
+/// `estimateStorageRequestCost` RPC method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRequestCost {
+    /// Deposit held when issuing the storage request, returned once it is fulfilled, revoked or
+    /// expires.
+    pub creation_deposit: Balance,
+    /// Deposit held upfront for the payment streams of the BSPs, assuming the User does not pay any
+    /// of them yet.
+    pub payment_streams_deposit: Balance,
+    /// What the User pays per tick to all the BSPs, once the replication target is reached.
+    pub bsps_rate_per_tick: Balance,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -140,6 +317,33 @@ pub trait StorageHubClientApi {
         file_key: H256,
     ) -> RpcResult<Option<FileMetadata>>;
 
+    /// List the files in a forest of this node, ordered by file key.
+    ///
+    /// In the case of a BSP node, the forest key is empty since it only maintains a single forest.
+    /// In the case of an MSP node, the forest key is a bucket id.
+    ///
+    /// Only available with unsafe RPC methods enabled (`--rpc-methods=unsafe`), since it reads the
+    /// whole forest.
+    #[method(name = "getLocalFiles", with_extensions)]
+    async fn get_local_files(&self, forest_key: Option<H256>) -> RpcResult<Vec<LocalFile>>;
+
+    /// Get the proofs the Provider `provider_id` has yet to submit as of the best block, i.e. the
+    /// ticks it was challenged at and has not submitted a proof for yet.
+    #[method(name = "getPendingProofs")]
+    async fn get_pending_proofs(&self, provider_id: H256) -> RpcResult<PendingProofs>;
+
+    /// Estimate what issuing a storage request for a file of `size` bytes, to be replicated by
+    /// `replication_target` BSPs and optionally stored by the MSP `msp_id`, would cost as of the
+    /// best block. The payment of the MSP, which depends on the value proposition of the bucket,
+    /// is not included.
+    #[method(name = "estimateStorageRequestCost")]
+    async fn estimate_storage_request_cost(
+        &self,
+        size: u64,
+        replication_target: ReplicationTarget,
+        msp_id: Option<H256>,
+    ) -> RpcResult<StorageRequestCost>;
+
     // Note: this RPC method returns a Vec<u8> because the `ForestProof` struct is not serializable.
     // so we SCALE-encode it. The user of this RPC will have to decode it.
     #[method(name = "generateForestProof")]
@@ -165,6 +369,149 @@ pub trait StorageHubClientApi {
 
     #[method(name = "removeBcsvKeys")]
     async fn remove_bcsv_keys(&self, keystore_path: String) -> RpcResult<()>;
+
+    /// Back up the File Storage and Forest Storage databases of this node, without stopping it.
+    ///
+    /// The backup is incremental to the previous one when possible, and labeled with the current
+    /// best block so that it can be restored as of that point in time.
+    ///
+    /// Only available with unsafe RPC methods enabled (`--rpc-methods=unsafe`).
+    #[method(name = "createDbBackup", with_extensions)]
+    async fn create_db_backup(&self, ext: &Extensions) -> RpcResult<BackupEntry>;
+
+    /// List the database backups of this node, oldest first.
+    #[method(name = "listDbBackups")]
+    async fn list_db_backups(&self) -> RpcResult<Vec<BackupEntry>>;
+
+    /// Get the earnings of this Provider node per day, oldest first, as aggregated locally from
+    /// finalised blocks.
+    ///
+    /// Days are numbered since the UNIX epoch (UTC), and both bounds are inclusive. Days without
+    /// any earnings are omitted.
+    #[method(name = "getProviderEarnings")]
+    async fn get_provider_earnings(
+        &self,
+        from_day: Option<Day>,
+        to_day: Option<Day>,
+    ) -> RpcResult<Vec<DailyEarnings>>;
+
+    /// Get the peers this node wants to keep connected, according to its current storage
+    /// requests, buckets and transfers, compared to the peers it is actually connected to.
+    #[method(name = "getPeerSet")]
+    async fn get_peer_set(&self) -> RpcResult<PeerSetDiagnostics>;
+
+    /// Get the uploads to this node in progress, i.e. from which some chunks were received, with
+    /// the chunk from which they would resume if interrupted and their completion percentage.
+    #[method(name = "getInProgressTransfers")]
+    async fn get_in_progress_transfers(&self) -> RpcResult<Vec<TransferProgress>>;
+
+    /// Set the maximum bandwidth (bytes per second) used to serve files to all peers together
+    /// (`global`) and to each peer (`per_peer`), until the node restarts. Not limited if not set.
+    ///
+    /// Only available with unsafe RPC methods enabled (`--rpc-methods=unsafe`).
+    #[method(name = "setBandwidthLimit", with_extensions)]
+    async fn set_bandwidth_limit(
+        &self,
+        ext: &Extensions,
+        global: Option<u64>,
+        per_peer: Option<u64>,
+    ) -> RpcResult<()>;
+
+    /// Get the score of every peer this node transferred files with, with the successful, failed,
+    /// corrupt and timed out transfers it results from, and until when the peer is banned from
+    /// requesting files, if it is.
+    #[method(name = "getPeerScores")]
+    async fn get_peer_scores(&self) -> RpcResult<Vec<PeerScoreInfo>>;
+
+    /// Clear the score of the peer `peer_id` (in base58), lifting its ban, or of every peer if not
+    /// set.
+    #[method(name = "clearPeerScores", with_extensions)]
+    async fn clear_peer_scores(&self, ext: &Extensions, peer_id: Option<String>) -> RpcResult<()>;
+
+    /// List the long-running jobs of this Provider node (e.g. downloading the files of a bucket
+    /// moved to this MSP), in order of submission, optionally only those with `status`.
+    #[method(name = "listJobs")]
+    async fn list_jobs(&self, status: Option<JobStatus>) -> RpcResult<Vec<Job>>;
+
+    /// Get a long-running job of this Provider node, including its status, attempts and last
+    /// error.
+    #[method(name = "getJob")]
+    async fn get_job(&self, job_id: JobId) -> RpcResult<Option<Job>>;
+
+    /// Cancel a long-running job of this Provider node that is pending or running.
+    ///
+    /// Only available with unsafe RPC methods enabled (`--rpc-methods=unsafe`).
+    #[method(name = "cancelJob", with_extensions)]
+    async fn cancel_job(&self, ext: &Extensions, job_id: JobId) -> RpcResult<Job>;
+
+    /// Get the policy deciding which storage requests this BSP node volunteers for.
+    #[method(name = "getVolunteerPolicy")]
+    async fn get_volunteer_policy(&self) -> RpcResult<VolunteerPolicy>;
+
+    /// Replace the policy deciding which storage requests this BSP node volunteers for, until the
+    /// node restarts. It applies to the storage requests evaluated from then on.
+    ///
+    /// Only available with unsafe RPC methods enabled (`--rpc-methods=unsafe`).
+    #[method(name = "setVolunteerPolicy", with_extensions)]
+    async fn set_volunteer_policy(
+        &self,
+        ext: &Extensions,
+        policy: VolunteerPolicy,
+    ) -> RpcResult<()>;
+
+    /// List the files of a bucket tracked by the indexer, both stored and with an open storage
+    /// request, in the order they were requested.
+    ///
+    /// At most [`MAX_PAGE_SIZE`](shc_indexer_db::models::MAX_PAGE_SIZE) files are returned, after
+    /// skipping the first `offset` ones.
+    #[method(name = "getIndexedBucketFiles")]
+    async fn get_indexed_bucket_files(
+        &self,
+        bucket_id: H256,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> RpcResult<Vec<IndexedFile>>;
+
+    /// Get the BSPs storing a file, according to the indexer.
+    #[method(name = "getIndexedFileBsps")]
+    async fn get_indexed_file_bsps(&self, file_key: H256) -> RpcResult<Vec<IndexedFileBsp>>;
+
+    /// Find the stored files with the given fingerprint according to the indexer, in the bucket
+    /// `bucket_id` or in any bucket if not set.
+    ///
+    /// Used to check if the exact same file is already stored before uploading it again.
+    #[method(name = "findIndexedFilesByFingerprint")]
+    async fn find_indexed_files_by_fingerprint(
+        &self,
+        fingerprint: H256,
+        bucket_id: Option<H256>,
+    ) -> RpcResult<Vec<IndexedFileMatch>>;
+
+    /// Get how far the indexer is in indexing the finalised blocks.
+    #[method(name = "getIndexerSyncStatus")]
+    async fn get_indexer_sync_status(&self) -> RpcResult<IndexerSyncStatus>;
+
+    /// Set the log level of `target` (i.e. the log target of a single task, such as
+    /// `bsp-submit-proof-task`), on top of the current log filter of this node.
+    ///
+    /// Requires the node to be started with `--enable-log-reloading`. Only available with unsafe
+    /// RPC methods enabled (`--rpc-methods=unsafe`).
+    #[method(name = "setLogFilter", with_extensions)]
+    async fn set_log_filter(
+        &self,
+        ext: &Extensions,
+        target: String,
+        level: String,
+    ) -> RpcResult<()>;
+
+    /// Reload the provider configuration file of this node, applying the settings that can change
+    /// while it runs (the upload bandwidth caps, the volunteering policy and the log filter).
+    /// Returns whether any of them changed.
+    ///
+    /// The file is rejected as a whole if it is invalid, keeping the current settings. Requires the
+    /// node to be started with `--provider-config-file`.
+    #[method(name = "reloadConfig", with_extensions)]
+    async fn reload_config(&self, ext: &Extensions) -> RpcResult<bool>;
 }
 
 /// Stores the required objects to be used in our RPC method.
@@ -173,6 +520,13 @@ pub struct StorageHubClientRpc<FL, FSH, C, Block> {
     file_storage: Arc<RwLock<FL>>,
     forest_storage_handler: FSH,
     keystore: KeystorePtr,
+    db_backup: Option<DbBackupConfig>,
+    provider_earnings: Option<EarningsStore>,
+    file_transfer: Option<ActorHandle<FileTransferService>>,
+    job_queue: Option<JobQueue>,
+    volunteer_policy: Option<VolunteerPolicyEngine>,
+    indexer_db_pool: Option<DbPool>,
+    config_watcher: Option<ActorHandle<ConfigWatcherService>>,
     _block_marker: std::marker::PhantomData<Block>,
 }
 
@@ -190,6 +544,13 @@ where
             file_storage: storage_hub_client_rpc_config.file_storage,
             forest_storage_handler: storage_hub_client_rpc_config.forest_storage_handler,
             keystore: storage_hub_client_rpc_config.keystore,
+            db_backup: storage_hub_client_rpc_config.db_backup,
+            provider_earnings: storage_hub_client_rpc_config.provider_earnings,
+            file_transfer: storage_hub_client_rpc_config.file_transfer,
+            job_queue: storage_hub_client_rpc_config.job_queue,
+            volunteer_policy: storage_hub_client_rpc_config.volunteer_policy,
+            indexer_db_pool: storage_hub_client_rpc_config.indexer_db_pool,
+            config_watcher: storage_hub_client_rpc_config.config_watcher,
             _block_marker: Default::default(),
         }
     }
@@ -212,6 +573,20 @@ where
         RandomnessOutput,
         TrieRemoveMutation,
     >,
+    C::Api: FileSystemRuntimeApi<
+        Block,
+        BackupStorageProviderId,
+        MainStorageProviderId,
+        H256,
+        BlockNumber,
+        ChunkId,
+        BucketId,
+        ReplicationTarget,
+        ProviderId,
+        StorageRequestMetadata,
+        AccountId32,
+        Balance,
+    >,
     FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
     FSH: ForestStorageHandler + Send + Sync + 'static,
 {
@@ -452,6 +827,87 @@ where
             .map_err(into_rpc_error)?)
     }
 
+    async fn get_local_files(
+        &self,
+        ext: &Extensions,
+        forest_key: Option<H256>,
+    ) -> RpcResult<Vec<LocalFile>> {
+        check_if_safe(ext)?;
+
+        let forest_key = match forest_key {
+            Some(forest_key) => forest_key.as_ref().to_vec().into(),
+            None => CURRENT_FOREST_KEY.to_vec().into(),
+        };
+
+        let fs = self
+            .forest_storage_handler
+            .get(&forest_key)
+            .await
+            .ok_or_else(|| {
+                into_rpc_error(format!("Forest storage not found for key {:?}", forest_key))
+            })?;
+
+        let files = fs.read().await.get_all_files().map_err(into_rpc_error)?;
+        Ok(files
+            .into_iter()
+            .map(|(file_key, file_metadata)| LocalFile {
+                file_key,
+                file_metadata,
+            })
+            .collect())
+    }
+
+    async fn get_pending_proofs(&self, provider_id: H256) -> RpcResult<PendingProofs> {
+        let at_hash = self.client.info().best_hash;
+        let schedule = self
+            .client
+            .runtime_api()
+            .provider_challenge_schedule(at_hash, &provider_id)
+            .map_err(into_rpc_error)?
+            .map_err(into_rpc_error)?;
+
+        // The Provider is challenged every `challenge_period` ticks from the next tick it has to
+        // submit a proof for.
+        let pending_ticks = match schedule.challenge_period {
+            0 => Vec::new(),
+            challenge_period => {
+                std::iter::successors(Some(schedule.next_tick_to_submit_proof_for), |tick| {
+                    tick.checked_add(challenge_period)
+                })
+                .take_while(|tick| *tick <= schedule.current_tick)
+                .collect()
+            }
+        };
+
+        Ok(PendingProofs {
+            current_tick: schedule.current_tick,
+            last_tick_proven: schedule.last_tick_proven,
+            next_deadline_tick: schedule.next_deadline_tick,
+            pending_ticks,
+        })
+    }
+
+    async fn estimate_storage_request_cost(
+        &self,
+        size: u64,
+        replication_target: ReplicationTarget,
+        msp_id: Option<H256>,
+    ) -> RpcResult<StorageRequestCost> {
+        let at_hash = self.client.info().best_hash;
+        let estimate = self
+            .client
+            .runtime_api()
+            .estimate_storage_request_cost(at_hash, size, replication_target, msp_id)
+            .map_err(into_rpc_error)?
+            .map_err(into_rpc_error)?;
+
+        Ok(StorageRequestCost {
+            creation_deposit: estimate.creation_deposit,
+            payment_streams_deposit: estimate.payment_streams_deposit,
+            bsps_rate_per_tick: estimate.bsps_rate_per_tick,
+        })
+    }
+
     async fn generate_forest_proof(
         &self,
         forest_key: Option<H256>,
@@ -648,6 +1104,354 @@ where
 
         Ok(())
     }
+
+    async fn create_db_backup(&self, ext: &Extensions) -> RpcResult<BackupEntry> {
+        check_if_safe(ext)?;
+
+        let db_backup = self.db_backup_config()?;
+        let locations = discover_databases(&db_backup.storage_path).map_err(into_rpc_error)?;
+
+        let info = self.client.info();
+        let at = ChainPoint {
+            block_number: info.best_number.saturated_into(),
+            block_hash: H256::from_slice(info.best_hash.as_ref()),
+        };
+
+        // Hold the read locks of every Forest Storage and of the File Storage while catching up
+        // with the databases, so that none of them is written in the meantime and the backup is
+        // consistent across them. The Forest Storages are locked first, in the same order as tasks
+        // lock them.
+        let mut forest_storages = Vec::new();
+        for location in &locations {
+            if let DatabaseKind::Forest { forest_key } = &location.kind {
+                let key = forest_key.clone().into();
+                if let Some(forest_storage) = self.forest_storage_handler.get(&key).await {
+                    forest_storages.push(forest_storage);
+                }
+            }
+        }
+        let mut forest_storage_read_locks = Vec::with_capacity(forest_storages.len());
+        for forest_storage in &forest_storages {
+            forest_storage_read_locks.push(forest_storage.read().await);
+        }
+        let file_storage_read_lock = self.file_storage.read().await;
+
+        let (store, snapshot) = tokio::task::spawn_blocking(move || {
+            let store = db_backup.open_store()?;
+            let snapshot = OnlineSnapshot::take(&store, locations)?;
+            Ok::<_, BackupError>((store, snapshot))
+        })
+        .await
+        .map_err(into_rpc_error)?
+        .map_err(into_rpc_error)?;
+
+        // The snapshot does not see later writes, so the storages can be written again while it
+        // is copied.
+        drop(file_storage_read_lock);
+        drop(forest_storage_read_locks);
+
+        let backup = tokio::task::spawn_blocking(move || snapshot.back_up(&store, at))
+            .await
+            .map_err(into_rpc_error)?
+            .map_err(into_rpc_error)?;
+
+        info!(target: LOG_TARGET, "Database backup {} created at block {}", backup.id, at.block_number);
+
+        Ok(backup)
+    }
+
+    async fn list_db_backups(&self) -> RpcResult<Vec<BackupEntry>> {
+        let db_backup = self.db_backup_config()?;
+
+        db_backup
+            .open_store()
+            .and_then(|store| store.list())
+            .map_err(into_rpc_error)
+    }
+
+    async fn get_provider_earnings(
+        &self,
+        from_day: Option<Day>,
+        to_day: Option<Day>,
+    ) -> RpcResult<Vec<DailyEarnings>> {
+        let provider_earnings = self.provider_earnings.clone().ok_or_else(|| {
+            into_rpc_error(
+                "Provider earnings are not aggregated by this node. Start it with \
+                `--provider-earnings` to enable them.",
+            )
+        })?;
+
+        provider_earnings
+            .days(from_day.unwrap_or(Day::MIN)..=to_day.unwrap_or(Day::MAX))
+            .map_err(into_rpc_error)
+    }
+
+    async fn get_peer_set(&self) -> RpcResult<PeerSetDiagnostics> {
+        let file_transfer = self.file_transfer.as_ref().ok_or_else(|| {
+            into_rpc_error("The File Transfer Service is not running in this node.")
+        })?;
+
+        Ok(file_transfer.query_peer_set().await)
+    }
+
+    async fn get_in_progress_transfers(&self) -> RpcResult<Vec<TransferProgress>> {
+        let file_transfer = self.file_transfer.as_ref().ok_or_else(|| {
+            into_rpc_error("The File Transfer Service is not running in this node.")
+        })?;
+
+        file_transfer
+            .query_transfers()
+            .await
+            .map_err(into_rpc_error)
+    }
+
+    async fn set_bandwidth_limit(
+        &self,
+        ext: &Extensions,
+        global: Option<u64>,
+        per_peer: Option<u64>,
+    ) -> RpcResult<()> {
+        check_if_safe(ext)?;
+
+        let file_transfer = self.file_transfer.as_ref().ok_or_else(|| {
+            into_rpc_error("The File Transfer Service is not running in this node.")
+        })?;
+
+        file_transfer
+            .set_bandwidth_limit(BandwidthLimits { global, per_peer })
+            .await
+            .map_err(into_rpc_error)
+    }
+
+    async fn get_peer_scores(&self) -> RpcResult<Vec<PeerScoreInfo>> {
+        let file_transfer = self.file_transfer.as_ref().ok_or_else(|| {
+            into_rpc_error("The File Transfer Service is not running in this node.")
+        })?;
+
+        Ok(file_transfer.query_peer_scores().await)
+    }
+
+    async fn clear_peer_scores(&self, ext: &Extensions, peer_id: Option<String>) -> RpcResult<()> {
+        check_if_safe(ext)?;
+
+        let file_transfer = self.file_transfer.as_ref().ok_or_else(|| {
+            into_rpc_error("The File Transfer Service is not running in this node.")
+        })?;
+        let peer_id = peer_id
+            .map(|peer_id| PeerId::from_str(&peer_id))
+            .transpose()
+            .map_err(into_rpc_error)?;
+
+        file_transfer
+            .clear_peer_scores(peer_id)
+            .await
+            .map_err(into_rpc_error)
+    }
+
+    async fn list_jobs(&self, status: Option<JobStatus>) -> RpcResult<Vec<Job>> {
+        self.job_queue()?
+            .jobs(status.as_ref())
+            .map_err(into_rpc_error)
+    }
+
+    async fn get_job(&self, job_id: JobId) -> RpcResult<Option<Job>> {
+        self.job_queue()?.job(job_id).map_err(into_rpc_error)
+    }
+
+    async fn cancel_job(&self, ext: &Extensions, job_id: JobId) -> RpcResult<Job> {
+        check_if_safe(ext)?;
+
+        self.job_queue()?.cancel(job_id).map_err(into_rpc_error)
+    }
+
+    async fn get_volunteer_policy(&self) -> RpcResult<VolunteerPolicy> {
+        Ok(self.volunteer_policy()?.policy())
+    }
+
+    async fn set_volunteer_policy(
+        &self,
+        ext: &Extensions,
+        policy: VolunteerPolicy,
+    ) -> RpcResult<()> {
+        check_if_safe(ext)?;
+
+        self.volunteer_policy()?.set_policy(policy.clone());
+        info!(target: LOG_TARGET, "Volunteering policy set to {:?}", policy);
+        Ok(())
+    }
+
+    async fn get_indexed_bucket_files(
+        &self,
+        bucket_id: H256,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> RpcResult<Vec<IndexedFile>> {
+        let mut conn = self.indexer_db().await?;
+
+        let bucket = Bucket::get_by_onchain_bucket_id(&mut conn, bucket_id.as_ref().to_vec())
+            .await
+            .map_err(into_rpc_error)?;
+        let files = shc_indexer_db::models::File::list(
+            &mut conn,
+            FileFilter {
+                bucket_id: Some(bucket.id),
+                ..Default::default()
+            },
+            Pagination::new(
+                offset.unwrap_or_default(),
+                limit.unwrap_or(shc_indexer_db::models::MAX_PAGE_SIZE),
+            ),
+        )
+        .await
+        .map_err(into_rpc_error)?;
+
+        Ok(files
+            .into_iter()
+            .map(|file| {
+                let file_metadata = file.to_file_metadata(bucket.onchain_bucket_id.clone());
+                IndexedFile {
+                    file_key: file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>(),
+                    file_metadata,
+                    fulfilled: file.step == FileStorageRequestStep::Stored as i32,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_indexed_file_bsps(&self, file_key: H256) -> RpcResult<Vec<IndexedFileBsp>> {
+        let mut conn = self.indexer_db().await?;
+
+        let proof_status = shc_indexer_db::models::File::get_proof_status(&mut conn, file_key)
+            .await
+            .map_err(into_rpc_error)?;
+
+        Ok(proof_status
+            .providers
+            .into_iter()
+            .map(|provider| IndexedFileBsp {
+                bsp_id: provider.onchain_bsp_id,
+                last_tick_proven: provider.last_tick_proven,
+                slashable: provider.slashable,
+            })
+            .collect())
+    }
+
+    async fn find_indexed_files_by_fingerprint(
+        &self,
+        fingerprint: H256,
+        bucket_id: Option<H256>,
+    ) -> RpcResult<Vec<IndexedFileMatch>> {
+        let mut conn = self.indexer_db().await?;
+
+        let scope = match bucket_id {
+            Some(bucket_id) => FileExistenceScope::Bucket(bucket_id.as_ref().to_vec()),
+            None => FileExistenceScope::Any,
+        };
+        let matches =
+            shc_indexer_db::models::File::find_by_fingerprint(&mut conn, fingerprint, scope)
+                .await
+                .map_err(into_rpc_error)?;
+
+        matches
+            .into_iter()
+            .map(|file_match| {
+                Ok(IndexedFileMatch {
+                    file_key: h256_from_db(&file_match.file_key)?,
+                    bucket_id: h256_from_db(&file_match.onchain_bucket_id)?,
+                    msp_id: file_match.onchain_msp_id,
+                    bsp_ids: file_match.onchain_bsp_ids,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_indexer_sync_status(&self) -> RpcResult<IndexerSyncStatus> {
+        let mut conn = self.indexer_db().await?;
+
+        let service_state = ServiceState::get(&mut conn).await.map_err(into_rpc_error)?;
+
+        Ok(IndexerSyncStatus {
+            last_indexed_block: service_state
+                .last_processed_block
+                .try_into()
+                .map_err(into_rpc_error)?,
+            finalised_block: self.client.info().finalized_number.saturated_into(),
+        })
+    }
+
+    async fn set_log_filter(
+        &self,
+        ext: &Extensions,
+        target: String,
+        level: String,
+    ) -> RpcResult<()> {
+        check_if_safe(ext)?;
+
+        // Directives are separated by commas and the target from the level by `=`, so a target
+        // containing any of them would set filters other than the requested one.
+        if target.is_empty() || target.contains([',', '=', ' ']) {
+            return Err(into_rpc_error(format!("Invalid log target: {:?}", target)));
+        }
+        let level = LevelFilter::from_str(&level).map_err(into_rpc_error)?;
+
+        sc_tracing::logging::add_directives(&format!("{}={}", target, level));
+        sc_tracing::logging::reload_filter().map_err(|e| {
+            into_rpc_error(format!(
+                "Failed to reload the log filter: {}. Start the node with \
+                `--enable-log-reloading` to set log filters at runtime.",
+                e
+            ))
+        })?;
+
+        info!(target: LOG_TARGET, "Log level of target {} set to {}", target, level);
+
+        Ok(())
+    }
+
+    async fn reload_config(&self, ext: &Extensions) -> RpcResult<bool> {
+        check_if_safe(ext)?;
+
+        let config_watcher = self.config_watcher.as_ref().ok_or_else(|| {
+            into_rpc_error(
+                "This node has no configuration file to reload. Start it with \
+                `--provider-config-file` to reload its configuration while it runs.",
+            )
+        })?;
+
+        config_watcher.reload_config().await.map_err(into_rpc_error)
+    }
+}
+
+impl<FL, FSH, C, Block> StorageHubClientRpc<FL, FSH, C, Block> {
+    fn db_backup_config(&self) -> RpcResult<DbBackupConfig> {
+        self.db_backup.clone().ok_or_else(|| {
+            into_rpc_error(
+                "Database backups are not enabled in this node. Start it with `--db-backup-path` \
+                and the RocksDB storage layer to enable them.",
+            )
+        })
+    }
+
+    fn job_queue(&self) -> RpcResult<&JobQueue> {
+        self.job_queue
+            .as_ref()
+            .ok_or_else(|| into_rpc_error("This node does not run a Provider job queue."))
+    }
+
+    async fn indexer_db(&self) -> RpcResult<DbConnection<'_>> {
+        let indexer_db_pool = self.indexer_db_pool.as_ref().ok_or_else(|| {
+            into_rpc_error(
+                "This node has no access to the indexer database. Start it with `--database-url`.",
+            )
+        })?;
+        indexer_db_pool.get().await.map_err(into_rpc_error)
+    }
+
+    fn volunteer_policy(&self) -> RpcResult<&VolunteerPolicyEngine> {
+        self.volunteer_policy.as_ref().ok_or_else(|| {
+            into_rpc_error("This node is not a BSP node with a volunteering policy.")
+        })
+    }
 }
 
 /// Get the file name for the given public key and key type.
@@ -659,6 +1463,13 @@ fn key_file_name(public: &[u8], key_type: KeyTypeId) -> PathBuf {
     buf
 }
 
+/// Converts a hash stored in the indexer database into a [`H256`].
+fn h256_from_db(bytes: &[u8]) -> RpcResult<H256> {
+    <[u8; 32]>::try_from(bytes)
+        .map(H256::from)
+        .map_err(|_| into_rpc_error(format!("Invalid hash in the indexer database: {:?}", bytes)))
+}
+
 /// Converts into the expected kind of error for `jsonrpsee`'s `RpcResult<_>`.
 fn into_rpc_error(e: impl Debug) -> JsonRpseeError {
     JsonRpseeError::owned(
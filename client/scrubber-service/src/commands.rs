@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use shc_actors_framework::actor::ActorHandle;
+use shc_common::types::StorageProofsMerkleTrieLayout;
+use shc_file_manager::traits::FileStorage;
+
+use crate::handler::{ScrubStatus, ScrubberService};
+
+/// Commands that can be sent to the ScrubberService actor.
+#[derive(Debug)]
+pub enum ScrubberServiceCommand {
+    QueryScrubStatus {
+        callback: tokio::sync::oneshot::Sender<ScrubStatus>,
+    },
+}
+
+/// Interface for interacting with the ScrubberService actor.
+#[async_trait]
+pub trait ScrubberServiceInterface {
+    /// Query the progress of the current scrub pass.
+    async fn query_scrub_status(&self) -> ScrubStatus;
+}
+
+#[async_trait]
+impl<FL> ScrubberServiceInterface for ActorHandle<ScrubberService<FL>>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    async fn query_scrub_status(&self) -> ScrubStatus {
+        let (callback, rx) = tokio::sync::oneshot::channel();
+        let message = ScrubberServiceCommand::QueryScrubStatus { callback };
+        self.send(message).await;
+        rx.await.expect("Failed to receive response from ScrubberService. Probably means ScrubberService has crashed.")
+    }
+}
@@ -0,0 +1,37 @@
+use shc_actors_framework::event_bus::{EventBus, EventBusMessage, ProvidesEventBus};
+use shc_common::types::{ChunkId, FileMetadata};
+use sp_core::H256;
+
+/// Corrupt chunks detected event.
+///
+/// This event is emitted when the chunks of a complete file in the File Storage can't be proven
+/// against the fingerprint of the file anymore, because they are corrupted or missing. It is
+/// emitted once per file and scrub pass, after all its chunks have been checked.
+#[derive(Debug, Clone)]
+pub struct CorruptChunkDetected {
+    pub file_key: H256,
+    pub file_metadata: FileMetadata,
+    /// The chunks of the file that could not be proven.
+    pub chunk_ids: Vec<ChunkId>,
+}
+
+impl EventBusMessage for CorruptChunkDetected {}
+
+#[derive(Clone, Default)]
+pub struct ScrubberServiceEventBusProvider {
+    corrupt_chunk_detected_event_bus: EventBus<CorruptChunkDetected>,
+}
+
+impl ScrubberServiceEventBusProvider {
+    pub fn new() -> Self {
+        Self {
+            corrupt_chunk_detected_event_bus: EventBus::new(),
+        }
+    }
+}
+
+impl ProvidesEventBus<CorruptChunkDetected> for ScrubberServiceEventBusProvider {
+    fn event_bus(&self) -> &EventBus<CorruptChunkDetected> {
+        &self.corrupt_chunk_detected_event_bus
+    }
+}
@@ -0,0 +1,331 @@
+use std::sync::Arc;
+
+use futures::stream::{self, StreamExt};
+use log::{debug, error, info, warn};
+use shc_actors_framework::actor::{Actor, ActorEventLoop};
+use shc_common::types::{ChunkId, FileMetadata, StorageProofsMerkleTrieLayout};
+use shc_file_manager::traits::{FileStorage, FileStorageError};
+use sp_core::H256;
+use tokio::{
+    sync::RwLock,
+    time::{interval, Duration},
+};
+
+use crate::{
+    commands::ScrubberServiceCommand,
+    events::{CorruptChunkDetected, ScrubberServiceEventBusProvider},
+};
+
+const LOG_TARGET: &str = "scrubber-service";
+
+/// Period between the batches of chunks checked by the scrubber.
+const SCRUB_PERIOD: Duration = Duration::from_secs(1);
+
+/// Configuration of the [`ScrubberService`].
+#[derive(Debug, Clone, Copy)]
+pub struct ScrubberConfig {
+    /// Maximum amount of chunks checked per second.
+    pub chunks_per_second: u64,
+}
+
+/// Progress of the current scrub pass over the files in storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrubStatus {
+    /// Number of the current pass, starting at 1. Zero if no pass has started yet.
+    pub pass: u64,
+    /// Files fully checked in the current pass.
+    pub files_scrubbed: u64,
+    /// Files left to check in the current pass, not counting the one being checked.
+    pub files_left: u64,
+    /// Files with corrupt chunks found in the current pass.
+    pub corrupt_files: u64,
+}
+
+/// A file whose chunks are being checked.
+struct FileInScrub {
+    file_key: H256,
+    file_metadata: FileMetadata,
+    /// The next chunk to check.
+    next_chunk: u64,
+    /// The corrupt chunks found so far.
+    corrupt_chunks: Vec<ChunkId>,
+}
+
+pub struct ScrubberService<FL>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    /// The file storage layer whose files are checked.
+    file_storage: Arc<RwLock<FL>>,
+    config: ScrubberConfig,
+    /// The event bus provider for the scrubber service.
+    /// Part of the actor framework, allows for emitting events.
+    event_bus_provider: ScrubberServiceEventBusProvider,
+    /// Files left to check in the current pass.
+    files_to_scrub: Vec<H256>,
+    /// The file being checked, if any.
+    current_file: Option<FileInScrub>,
+    status: ScrubStatus,
+}
+
+impl<FL> Actor for ScrubberService<FL>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    type Message = ScrubberServiceCommand;
+    type EventLoop = ScrubberServiceEventLoop<FL>;
+    type EventBusProvider = ScrubberServiceEventBusProvider;
+
+    fn handle_message(
+        &mut self,
+        message: Self::Message,
+    ) -> impl std::future::Future<Output = ()> + Send {
+        async {
+            match message {
+                ScrubberServiceCommand::QueryScrubStatus { callback } => {
+                    match callback.send(self.status) {
+                        Ok(()) => {}
+                        Err(_) => error!(
+                            target: LOG_TARGET,
+                            "Failed to send the scrub status back. Looks like the requester task is gone."
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_event_bus_provider(&self) -> &Self::EventBusProvider {
+        &self.event_bus_provider
+    }
+}
+
+impl<FL> ScrubberService<FL>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    /// Create a new [`ScrubberService`].
+    pub fn new(file_storage: Arc<RwLock<FL>>, config: ScrubberConfig) -> Self {
+        Self {
+            file_storage,
+            config,
+            event_bus_provider: ScrubberServiceEventBusProvider::new(),
+            files_to_scrub: Vec::new(),
+            current_file: None,
+            status: ScrubStatus::default(),
+        }
+    }
+
+    /// Checks the next batch of up to [`ScrubberConfig::chunks_per_second`] chunks, moving on to
+    /// the next files as they are fully checked.
+    ///
+    /// At most one new pass is started per batch, so that a storage with no chunks to check does
+    /// not keep the scrubber busy.
+    async fn scrub_next_chunks(&mut self) {
+        let file_storage = self.file_storage.clone();
+        let file_storage = file_storage.read().await;
+
+        let mut chunks_left = self.config.chunks_per_second;
+        let mut new_pass_started = false;
+
+        while chunks_left > 0 {
+            if self.current_file.is_none() && !self.start_next_file(&*file_storage) {
+                if new_pass_started {
+                    break;
+                }
+                self.start_pass(&*file_storage);
+                new_pass_started = true;
+                continue;
+            }
+
+            let file = self
+                .current_file
+                .as_mut()
+                .expect("Current file was just set; qed");
+
+            let end = file
+                .next_chunk
+                .saturating_add(chunks_left)
+                .min(file.file_metadata.chunks_count());
+            let chunk_ids = (file.next_chunk..end).map(ChunkId::new).collect::<Vec<_>>();
+            chunks_left -= end - file.next_chunk;
+            file.next_chunk = end;
+
+            if !chunk_ids.is_empty() {
+                match file_storage.find_corrupt_chunks(&file.file_key, &chunk_ids) {
+                    Ok(corrupt_chunks) => file.corrupt_chunks.extend(corrupt_chunks),
+                    Err(FileStorageError::FileDoesNotExist)
+                    | Err(FileStorageError::IncompleteFile) => {
+                        // The file was deleted, or is being written again, since it was listed.
+                        debug!(target: LOG_TARGET, "Skipping file {:?}, no longer complete in storage", file.file_key);
+                        self.current_file = None;
+                        continue;
+                    }
+                    Err(e) => {
+                        error!(target: LOG_TARGET, "Failed to check the chunks of file {:?}: {:?}", file.file_key, e);
+                        self.current_file = None;
+                        continue;
+                    }
+                }
+            }
+
+            if end == file.file_metadata.chunks_count() {
+                self.finish_file();
+            }
+        }
+    }
+
+    /// Starts a new pass over all the files in storage.
+    fn start_pass(&mut self, file_storage: &FL) {
+        let file_keys = match file_storage.get_file_keys() {
+            Ok(file_keys) => file_keys,
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to list the files in storage: {:?}", e);
+                return;
+            }
+        };
+
+        if file_keys.is_empty() {
+            return;
+        }
+
+        if self.status.pass > 0 {
+            info!(
+                target: LOG_TARGET,
+                "🧽 Finished scrub pass {}: {} files checked, {} with corrupt chunks",
+                self.status.pass,
+                self.status.files_scrubbed,
+                self.status.corrupt_files
+            );
+        }
+
+        self.status = ScrubStatus {
+            pass: self.status.pass + 1,
+            files_left: file_keys.len() as u64,
+            ..Default::default()
+        };
+        self.files_to_scrub = file_keys;
+
+        debug!(
+            target: LOG_TARGET,
+            "Starting scrub pass {} over {} files", self.status.pass, self.status.files_left
+        );
+    }
+
+    /// Takes the next file of the current pass to check, skipping the ones no longer in storage.
+    /// Returns `false` if there are no files left in the current pass.
+    fn start_next_file(&mut self, file_storage: &FL) -> bool {
+        while let Some(file_key) = self.files_to_scrub.pop() {
+            self.status.files_left = self.files_to_scrub.len() as u64;
+
+            match file_storage.get_metadata(&file_key) {
+                Ok(Some(file_metadata)) => {
+                    self.current_file = Some(FileInScrub {
+                        file_key,
+                        file_metadata,
+                        next_chunk: 0,
+                        corrupt_chunks: Vec::new(),
+                    });
+                    return true;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!(target: LOG_TARGET, "Failed to get the metadata of file {:?}: {:?}", file_key, e);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Reports the corrupt chunks of the current file, if any, once it is fully checked.
+    fn finish_file(&mut self) {
+        let Some(file) = self.current_file.take() else {
+            return;
+        };
+
+        self.status.files_scrubbed += 1;
+
+        if file.corrupt_chunks.is_empty() {
+            return;
+        }
+
+        self.status.corrupt_files += 1;
+        warn!(
+            target: LOG_TARGET,
+            "🚨 Found {} corrupt chunks in file {:?}: {:?}",
+            file.corrupt_chunks.len(),
+            file.file_key,
+            file.corrupt_chunks
+        );
+
+        self.emit(CorruptChunkDetected {
+            file_key: file.file_key,
+            file_metadata: file.file_metadata,
+            chunk_ids: file.corrupt_chunks,
+        });
+    }
+}
+
+pub struct ScrubberServiceEventLoop<FL>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    receiver: sc_utils::mpsc::TracingUnboundedReceiver<ScrubberServiceCommand>,
+    actor: ScrubberService<FL>,
+}
+
+enum MergedEventLoopMessage {
+    Command(ScrubberServiceCommand),
+    Tick,
+}
+
+/// Since this actor checks the files in the background, it needs to handle both periodic ticks and
+/// messages from other actors, hence the need for a custom `ActorEventLoop`.
+impl<FL> ActorEventLoop<ScrubberService<FL>> for ScrubberServiceEventLoop<FL>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    fn new(
+        actor: ScrubberService<FL>,
+        receiver: sc_utils::mpsc::TracingUnboundedReceiver<ScrubberServiceCommand>,
+    ) -> Self {
+        Self { actor, receiver }
+    }
+
+    async fn run(mut self) {
+        info!(
+            target: LOG_TARGET,
+            "🧽 StorageHub's Scrubber Service starting up, checking up to {} chunks per second!",
+            self.actor.config.chunks_per_second
+        );
+
+        let ticker = interval(SCRUB_PERIOD);
+        let ticker_stream = stream::unfold(ticker, |mut interval| {
+            Box::pin(async move {
+                interval.tick().await;
+                Some((MergedEventLoopMessage::Tick, interval))
+            })
+        });
+
+        let mut merged_stream = stream::select(
+            self.receiver.map(MergedEventLoopMessage::Command),
+            ticker_stream,
+        );
+
+        loop {
+            match merged_stream.next().await {
+                Some(MergedEventLoopMessage::Command(command)) => {
+                    self.actor.handle_message(command).await;
+                }
+                Some(MergedEventLoopMessage::Tick) => {
+                    self.actor.scrub_next_chunks().await;
+                }
+                None => {
+                    warn!(target: LOG_TARGET, "ScrubberService event loop terminated.");
+                    break;
+                }
+            }
+        }
+    }
+}
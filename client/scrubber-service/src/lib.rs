@@ -0,0 +1,42 @@
+//! Background integrity scrubbing of the files in the File Storage.
+//!
+//! Silent corruption of the stored chunks would otherwise only be discovered when a proof of them
+//! fails on-chain. The [`ScrubberService`] goes over every complete file in storage, over and over,
+//! proving its chunks against the fingerprint of the file at a configured rate of chunks per
+//! second. The chunks that can't be proven are reported with a [`CorruptChunkDetected`] event once
+//! the whole file has been checked, for the tasks of the node to repair or drop the file.
+//!
+//! [`CorruptChunkDetected`]: events::CorruptChunkDetected
+
+use std::sync::Arc;
+
+use shc_actors_framework::actor::{ActorHandle, ActorSpawner, TaskSpawner};
+use shc_common::types::StorageProofsMerkleTrieLayout;
+use shc_file_manager::traits::FileStorage;
+use tokio::sync::RwLock;
+
+pub use self::handler::{ScrubStatus, ScrubberConfig, ScrubberService};
+
+/// For defining the commands processed by the scrubber service.
+pub mod commands;
+/// For defining the events emitted by the scrubber service.
+pub mod events;
+/// For going over the stored files, checking their integrity.
+pub mod handler;
+
+pub async fn spawn_scrubber_service<FL>(
+    task_spawner: &TaskSpawner,
+    file_storage: Arc<RwLock<FL>>,
+    config: ScrubberConfig,
+) -> ActorHandle<ScrubberService<FL>>
+where
+    FL: FileStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
+{
+    let task_spawner = task_spawner
+        .with_name("scrubber-service")
+        .with_group("storage");
+
+    let scrubber_service = ScrubberService::new(file_storage, config);
+
+    task_spawner.spawn_actor(scrubber_service)
+}
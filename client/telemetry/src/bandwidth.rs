@@ -0,0 +1,68 @@
+//! Metrics of the upstream bandwidth throttling of the FileTransferService.
+//!
+//! The responses to download requests served by the node are rate limited according to its
+//! bandwidth limits, delaying the ones that exceed them. The following metrics are recorded:
+//! - `storagehub.file_transfer.served_bytes`: counter of the bytes served to other peers.
+//! - `storagehub.file_transfer.throttled_bytes`: counter of the bytes served that were delayed to
+//!   stay within the bandwidth limits.
+//! - `storagehub.file_transfer.throttle_delay`: histogram of how long the delayed responses were
+//!   delayed for, in seconds.
+
+use std::{sync::OnceLock, time::Duration};
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram},
+};
+
+use crate::{is_enabled, INSTRUMENTATION_SCOPE};
+
+/// The instruments recording the metrics of the bandwidth throttling.
+struct BandwidthMetrics {
+    served_bytes: Counter<u64>,
+    throttled_bytes: Counter<u64>,
+    throttle_delay: Histogram<f64>,
+}
+
+fn bandwidth_metrics() -> &'static BandwidthMetrics {
+    static METRICS: OnceLock<BandwidthMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let meter = global::meter(INSTRUMENTATION_SCOPE);
+        BandwidthMetrics {
+            served_bytes: meter
+                .u64_counter("storagehub.file_transfer.served_bytes")
+                .with_unit("By")
+                .with_description("Bytes served to other peers by the FileTransferService.")
+                .build(),
+            throttled_bytes: meter
+                .u64_counter("storagehub.file_transfer.throttled_bytes")
+                .with_unit("By")
+                .with_description(
+                    "Bytes served to other peers that were delayed to stay within the bandwidth limits.",
+                )
+                .build(),
+            throttle_delay: meter
+                .f64_histogram("storagehub.file_transfer.throttle_delay")
+                .with_unit("s")
+                .with_description("Time the throttled responses were delayed for.")
+                .build(),
+        }
+    })
+}
+
+/// Records that `bytes` were served to another peer, after delaying them for `delay` to stay
+/// within the bandwidth limits.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_served_bytes(bytes: u64, delay: Duration) {
+    if !is_enabled() {
+        return;
+    }
+
+    let metrics = bandwidth_metrics();
+    metrics.served_bytes.add(bytes, &[]);
+    if !delay.is_zero() {
+        metrics.throttled_bytes.add(bytes, &[]);
+        metrics.throttle_delay.record(delay.as_secs_f64(), &[]);
+    }
+}
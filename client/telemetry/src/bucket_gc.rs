@@ -0,0 +1,49 @@
+//! Metrics of the garbage collection of the buckets moved away from an MSP.
+//!
+//! Once a bucket is moved to another MSP, the old MSP deletes its files and forest after a
+//! retention delay. The following metrics are recorded:
+//! - `storagehub.bucket_gc.reclaimed_bytes`: counter of the bytes of the files deleted.
+//! - `storagehub.bucket_gc.deleted_buckets`: counter of the buckets deleted.
+
+use std::sync::OnceLock;
+
+use opentelemetry::{global, metrics::Counter};
+
+use crate::{is_enabled, INSTRUMENTATION_SCOPE};
+
+/// The instruments recording the metrics of the bucket garbage collection.
+struct BucketGcMetrics {
+    reclaimed_bytes: Counter<u64>,
+    deleted_buckets: Counter<u64>,
+}
+
+fn bucket_gc_metrics() -> &'static BucketGcMetrics {
+    static METRICS: OnceLock<BucketGcMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let meter = global::meter(INSTRUMENTATION_SCOPE);
+        BucketGcMetrics {
+            reclaimed_bytes: meter
+                .u64_counter("storagehub.bucket_gc.reclaimed_bytes")
+                .with_unit("By")
+                .with_description("Bytes of the files of the buckets moved away that were deleted.")
+                .build(),
+            deleted_buckets: meter
+                .u64_counter("storagehub.bucket_gc.deleted_buckets")
+                .with_description("Buckets moved away whose files and forest were deleted.")
+                .build(),
+        }
+    })
+}
+
+/// Records that a bucket moved away was deleted, reclaiming `bytes`.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_bucket_reclaimed(bytes: u64) {
+    if !is_enabled() {
+        return;
+    }
+
+    let metrics = bucket_gc_metrics();
+    metrics.reclaimed_bytes.add(bytes, &[]);
+    metrics.deleted_buckets.add(1, &[]);
+}
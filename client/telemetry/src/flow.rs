@@ -0,0 +1,266 @@
+//! Tracing of the key flows of a StorageHub client across tasks.
+//!
+//! A flow (i.e. a storage request) is handled by several tasks reacting to different events, so a
+//! single tracing span can't follow it. Instead, each step of a flow opens a [`FlowSpan`] with
+//! [`flow_span`], passing the flow's correlation ID (i.e. the file key), compared by its `Debug`
+//! representation. The first step of a flow opens a root span for it, and every other step with
+//! the same correlation ID is recorded as a child of that root span, in the same trace. The root span is closed with [`end_flow`] once the
+//! flow is over.
+//!
+//! Each step also records the following metrics, labeled by flow, step and outcome:
+//! - `storagehub.flow.step.duration`: histogram of the duration of the step, in seconds.
+//! - `storagehub.flow.step.count`: counter of the times the step was handled.
+
+use std::{collections::HashMap, fmt::Debug, sync::OnceLock, time::Instant};
+
+use log::debug;
+use opentelemetry::{
+    global::{self, BoxedSpan},
+    metrics::{Counter, Histogram},
+    trace::{Span, Status, TraceContextExt, Tracer},
+    Context, KeyValue, Value,
+};
+use parking_lot::Mutex;
+
+use crate::{is_enabled, INSTRUMENTATION_SCOPE, LOG_TARGET};
+
+/// Attribute holding the name of the flow a span or metric belongs to.
+pub const FLOW_ATTRIBUTE: &str = "storagehub.flow";
+
+/// Attribute holding the name of the step of a flow a span or metric belongs to.
+pub const STEP_ATTRIBUTE: &str = "storagehub.flow.step";
+
+/// Attribute holding the correlation ID of the flow a span belongs to.
+pub const CORRELATION_ID_ATTRIBUTE: &str = "storagehub.correlation_id";
+
+/// Attribute holding the outcome (`ok` or `error`) of a step.
+pub const OUTCOME_ATTRIBUTE: &str = "storagehub.flow.outcome";
+
+/// Maximum amount of flows open at the same time.
+///
+/// Flows that are never ended (i.e. a storage request this node volunteered for but was not
+/// selected) would otherwise grow the amount of open flows indefinitely. Once this limit is
+/// reached, the steps of new flows are recorded as traces of their own.
+pub const MAX_OPEN_FLOWS: usize = 4096;
+
+/// The key flows of a StorageHub client that are traced across tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flow {
+    /// Handling of a storage request, from it being issued until this node confirms storing the
+    /// file. The correlation ID is the file key.
+    StorageRequest,
+    /// Generation and submission of a proof for a challenge. The correlation ID is the Provider
+    /// ID and the tick of the challenge.
+    ProofSubmission,
+    /// Transfer of a file's chunks between peers, either sending or receiving them. The
+    /// correlation ID is the file key.
+    FileTransfer,
+}
+
+impl Flow {
+    /// The name of the flow, used as the name of its root span and as the [`FLOW_ATTRIBUTE`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Flow::StorageRequest => "storage_request",
+            Flow::ProofSubmission => "proof_submission",
+            Flow::FileTransfer => "file_transfer",
+        }
+    }
+}
+
+/// The root spans of the flows currently open, by flow and correlation ID.
+fn open_flows() -> &'static Mutex<HashMap<(Flow, String), Context>> {
+    static OPEN_FLOWS: OnceLock<Mutex<HashMap<(Flow, String), Context>>> = OnceLock::new();
+    OPEN_FLOWS.get_or_init(Default::default)
+}
+
+/// The instruments recording the metrics of the steps of the flows.
+struct FlowMetrics {
+    step_duration: Histogram<f64>,
+    step_count: Counter<u64>,
+}
+
+fn flow_metrics() -> &'static FlowMetrics {
+    static FLOW_METRICS: OnceLock<FlowMetrics> = OnceLock::new();
+    FLOW_METRICS.get_or_init(|| {
+        let meter = global::meter(INSTRUMENTATION_SCOPE);
+        FlowMetrics {
+            step_duration: meter
+                .f64_histogram("storagehub.flow.step.duration")
+                .with_unit("s")
+                .with_description("Duration of the steps of the key flows of the node.")
+                .build(),
+            step_count: meter
+                .u64_counter("storagehub.flow.step.count")
+                .with_description("Times each step of the key flows of the node was handled.")
+                .build(),
+        }
+    })
+}
+
+/// Opens a span for a step of a flow, as a child of the flow's root span.
+///
+/// If the flow is not open yet, it is opened with this step. The span is closed when the returned
+/// [`FlowSpan`] is dropped.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn flow_span(flow: Flow, step: &'static str, correlation_id: impl Debug) -> FlowSpan {
+    if !is_enabled() {
+        return FlowSpan { inner: None };
+    }
+
+    let tracer = global::tracer(INSTRUMENTATION_SCOPE);
+    let correlation_id = format!("{:?}", correlation_id);
+    let attributes = vec![
+        KeyValue::new(FLOW_ATTRIBUTE, flow.as_str()),
+        KeyValue::new(CORRELATION_ID_ATTRIBUTE, correlation_id.clone()),
+    ];
+
+    let parent = {
+        let mut open_flows = open_flows().lock();
+        match open_flows.get(&(flow, correlation_id.clone())) {
+            Some(context) => context.clone(),
+            None if open_flows.len() < MAX_OPEN_FLOWS => {
+                let mut root = tracer.start_with_context(flow.as_str(), &Context::new());
+                root.set_attributes(attributes.clone());
+                let context = Context::new().with_span(root);
+                open_flows.insert((flow, correlation_id.clone()), context.clone());
+                context
+            }
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Too many open flows, tracing step {} of {} {} on its own",
+                    step,
+                    flow.as_str(),
+                    correlation_id
+                );
+                Context::new()
+            }
+        }
+    };
+
+    let mut span = tracer.start_with_context(format!("{}::{}", flow.as_str(), step), &parent);
+    span.set_attributes(attributes);
+    span.set_attribute(KeyValue::new(STEP_ATTRIBUTE, step));
+
+    FlowSpan {
+        inner: Some(ActiveFlowSpan {
+            span,
+            flow,
+            step,
+            started_at: Instant::now(),
+            failed: false,
+        }),
+    }
+}
+
+/// Closes the root span of a flow, once all of its steps are done.
+///
+/// Does nothing if the flow is not open.
+pub fn end_flow(flow: Flow, correlation_id: impl Debug) {
+    if let Some(context) = open_flows()
+        .lock()
+        .remove(&(flow, format!("{:?}", correlation_id)))
+    {
+        context.span().end();
+    }
+}
+
+/// The span of a step of a flow, opened with [`flow_span`].
+///
+/// Closed when dropped, recording the duration and outcome of the step.
+pub struct FlowSpan {
+    inner: Option<ActiveFlowSpan>,
+}
+
+struct ActiveFlowSpan {
+    span: BoxedSpan,
+    flow: Flow,
+    step: &'static str,
+    started_at: Instant,
+    failed: bool,
+}
+
+impl FlowSpan {
+    /// Sets an attribute on the span.
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<Value>) {
+        if let Some(inner) = &mut self.inner {
+            inner.span.set_attribute(KeyValue::new(key, value));
+        }
+    }
+
+    /// Marks the step as failed with the given error.
+    pub fn record_error(&mut self, error: impl Debug) {
+        if let Some(inner) = &mut self.inner {
+            inner.span.set_status(Status::error(format!("{:?}", error)));
+            inner.failed = true;
+        }
+    }
+
+    /// Marks the step as failed if `result` is an error.
+    pub fn record_result<T, E: Debug>(&mut self, result: &Result<T, E>) {
+        if let Err(error) = result {
+            self.record_error(error);
+        }
+    }
+
+    /// Marks the step as failed until [`FlowSpan::complete`] is called, for steps that return
+    /// early on their errors.
+    pub fn fail_unless_completed(&mut self) {
+        if let Some(inner) = &mut self.inner {
+            inner
+                .span
+                .set_status(Status::error("step did not complete"));
+            inner.failed = true;
+        }
+    }
+
+    /// Marks the step as successfully completed.
+    pub fn complete(&mut self) {
+        if let Some(inner) = &mut self.inner {
+            inner.span.set_status(Status::Ok);
+            inner.failed = false;
+        }
+    }
+}
+
+impl Drop for FlowSpan {
+    fn drop(&mut self) {
+        if let Some(mut inner) = self.inner.take() {
+            let outcome = if inner.failed { "error" } else { "ok" };
+            let attributes = [
+                KeyValue::new(FLOW_ATTRIBUTE, inner.flow.as_str()),
+                KeyValue::new(STEP_ATTRIBUTE, inner.step),
+                KeyValue::new(OUTCOME_ATTRIBUTE, outcome),
+            ];
+
+            let metrics = flow_metrics();
+            metrics
+                .step_duration
+                .record(inner.started_at.elapsed().as_secs_f64(), &attributes);
+            metrics.step_count.add(1, &attributes);
+
+            inner.span.end();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flow_span_does_nothing_if_not_initialised() {
+        let mut span = flow_span(Flow::StorageRequest, "volunteer", 1);
+        span.set_attribute("key", "value");
+        span.record_error("error");
+        span.fail_unless_completed();
+        span.complete();
+        assert!(span.inner.is_none());
+        drop(span);
+
+        assert!(open_flows().lock().is_empty());
+        end_flow(Flow::StorageRequest, 1);
+    }
+}
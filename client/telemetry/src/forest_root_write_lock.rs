@@ -0,0 +1,79 @@
+//! Metrics of the Forest root write lock of a Storage Provider.
+//!
+//! Only one task at a time can modify the Forest root of the Provider (i.e. submit a proof or
+//! confirm storing files), so a task holding the lock for too long stalls every other one. The
+//! following metrics are recorded, labeled by the kind of task holding the lock:
+//! - `storagehub.forest_root_write_lock.hold_duration`: histogram of how long the lock was held
+//!   by each task, in seconds.
+//! - `storagehub.forest_root_write_lock.held_for`: gauge of how long the current holder has been
+//!   holding the lock, in seconds. Reset to zero once released.
+
+use std::{sync::OnceLock, time::Duration};
+
+use opentelemetry::{
+    global,
+    metrics::{Gauge, Histogram},
+    KeyValue,
+};
+
+use crate::{is_enabled, INSTRUMENTATION_SCOPE};
+
+/// Attribute holding the kind of task holding the Forest root write lock.
+pub const HOLDER_ATTRIBUTE: &str = "storagehub.forest_root_write_lock.holder";
+
+/// The instruments recording the metrics of the Forest root write lock.
+struct ForestRootWriteLockMetrics {
+    hold_duration: Histogram<f64>,
+    held_for: Gauge<f64>,
+}
+
+fn forest_root_write_lock_metrics() -> &'static ForestRootWriteLockMetrics {
+    static METRICS: OnceLock<ForestRootWriteLockMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let meter = global::meter(INSTRUMENTATION_SCOPE);
+        ForestRootWriteLockMetrics {
+            hold_duration: meter
+                .f64_histogram("storagehub.forest_root_write_lock.hold_duration")
+                .with_unit("s")
+                .with_description("Time the Forest root write lock was held by each task.")
+                .build(),
+            held_for: meter
+                .f64_gauge("storagehub.forest_root_write_lock.held_for")
+                .with_unit("s")
+                .with_description(
+                    "Time the current holder of the Forest root write lock has been holding it.",
+                )
+                .build(),
+        }
+    })
+}
+
+/// Records that `holder` has been holding the Forest root write lock for `held_for`.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_forest_root_write_lock_held(holder: &'static str, held_for: Duration) {
+    if !is_enabled() {
+        return;
+    }
+
+    forest_root_write_lock_metrics().held_for.record(
+        held_for.as_secs_f64(),
+        &[KeyValue::new(HOLDER_ATTRIBUTE, holder)],
+    );
+}
+
+/// Records that `holder` released the Forest root write lock after holding it for `held_for`.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_forest_root_write_lock_released(holder: &'static str, held_for: Duration) {
+    if !is_enabled() {
+        return;
+    }
+
+    let attributes = [KeyValue::new(HOLDER_ATTRIBUTE, holder)];
+    let metrics = forest_root_write_lock_metrics();
+    metrics
+        .hold_duration
+        .record(held_for.as_secs_f64(), &attributes);
+    metrics.held_for.record(0.0, &attributes);
+}
@@ -0,0 +1,187 @@
+//! OpenTelemetry export of the traces and metrics of a StorageHub client.
+//!
+//! Prometheus metrics are scoped to a single service, which makes it hard to follow a request
+//! that goes through several tasks (i.e. a storage request being volunteered for, received over
+//! the network and confirmed on-chain). This crate exports to an OTLP collector:
+//! - Traces, with one span per step of the key flows of the node. The steps of the same flow
+//!   (i.e. everything related to a given file key) share a correlation context, so they are
+//!   grouped under the same trace even when handled by different tasks. See [`flow`].
+//! - Metrics of the duration and outcome of each of those steps.
+//! - Metrics of how long the Forest root write lock is held. See [`forest_root_write_lock`].
+//! - Metrics of the hits and misses of the runtime API cache. See [`runtime_api_cache`].
+//! - Metrics of the transitions in and out of safe mode, when the node seems partitioned from
+//!   the network. See [`safe_mode`].
+//! - Metrics of the bytes served to other peers and how many of them were throttled. See
+//!   [`bandwidth`].
+//! - Metrics of the bytes reclaimed by deleting the buckets moved away from an MSP. See
+//!   [`bucket_gc`].
+//!
+//! Every trace and metric carries resource attributes identifying the role of the node and, if it
+//! is already registered, its Provider ID.
+//!
+//! Nothing is recorded or exported until [`init_open_telemetry`] is called.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+use log::info;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    metrics::{PeriodicReader, SdkMeterProvider},
+    runtime,
+    trace::TracerProvider,
+    Resource,
+};
+
+pub mod bandwidth;
+pub mod bucket_gc;
+pub mod flow;
+pub mod forest_root_write_lock;
+pub mod runtime_api_cache;
+pub mod safe_mode;
+
+pub use bandwidth::record_served_bytes;
+pub use bucket_gc::record_bucket_reclaimed;
+pub use flow::{end_flow, flow_span, Flow, FlowSpan};
+pub use forest_root_write_lock::{
+    record_forest_root_write_lock_held, record_forest_root_write_lock_released,
+};
+pub use runtime_api_cache::record_runtime_api_cache_lookup;
+pub use safe_mode::{record_safe_mode_entered, record_safe_mode_exited};
+
+const LOG_TARGET: &str = "telemetry";
+
+/// Name of the tracer and meter used by the StorageHub client.
+pub const INSTRUMENTATION_SCOPE: &str = "storage-hub-client";
+
+/// Resource attribute holding the role of the node (`bsp`, `msp` or `user`).
+pub const NODE_ROLE_ATTRIBUTE: &str = "storagehub.node.role";
+
+/// Resource attribute holding the Provider ID of the node, if it is registered as a Provider.
+pub const PROVIDER_ID_ATTRIBUTE: &str = "storagehub.provider.id";
+
+/// Default interval between exports of the metrics.
+pub const DEFAULT_METRICS_EXPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Whether OpenTelemetry has been initialised, so that flows are recorded.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The providers set up by [`init_open_telemetry`], kept to flush them on shutdown.
+static PROVIDERS: OnceLock<(TracerProvider, SdkMeterProvider)> = OnceLock::new();
+
+/// Configuration of the OpenTelemetry export.
+#[derive(Debug, Clone)]
+pub struct OpenTelemetryConfig {
+    /// The gRPC endpoint of the OTLP collector (i.e. `http://localhost:4317`).
+    pub endpoint: String,
+    /// The `service.name` resource attribute.
+    pub service_name: String,
+    /// The role of the node (`bsp`, `msp` or `user`).
+    pub node_role: String,
+    /// The Provider ID of the node, if it is registered as a Provider.
+    pub provider_id: Option<String>,
+    /// Interval between exports of the metrics.
+    pub metrics_export_interval: Duration,
+}
+
+impl OpenTelemetryConfig {
+    fn resource(&self) -> Resource {
+        let mut attributes = vec![
+            KeyValue::new("service.name", self.service_name.clone()),
+            KeyValue::new(NODE_ROLE_ATTRIBUTE, self.node_role.clone()),
+        ];
+        if let Some(provider_id) = &self.provider_id {
+            attributes.push(KeyValue::new(PROVIDER_ID_ATTRIBUTE, provider_id.clone()));
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+/// Error type for the initialisation of the OpenTelemetry export.
+#[derive(thiserror::Error, Debug)]
+pub enum TelemetryError {
+    #[error("OpenTelemetry has already been initialised")]
+    AlreadyInitialised,
+    #[error("Failed to build the OTLP span exporter: {0}")]
+    SpanExporter(String),
+    #[error("Failed to build the OTLP metric exporter: {0}")]
+    MetricExporter(String),
+}
+
+/// Sets up the global tracer and meter providers to export to the OTLP collector in `config`,
+/// and starts recording flows.
+///
+/// Must be called from within a Tokio runtime, which is used to export in the background.
+pub fn init_open_telemetry(config: &OpenTelemetryConfig) -> Result<(), TelemetryError> {
+    if PROVIDERS.get().is_some() {
+        return Err(TelemetryError::AlreadyInitialised);
+    }
+
+    let resource = config.resource();
+
+    let span_exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(config.endpoint.clone())
+        .build()
+        .map_err(|e| TelemetryError::SpanExporter(e.to_string()))?;
+    let tracer_provider = TracerProvider::builder()
+        .with_batch_exporter(span_exporter, runtime::Tokio)
+        .with_resource(resource.clone())
+        .build();
+
+    let metric_exporter = MetricExporter::builder()
+        .with_tonic()
+        .with_endpoint(config.endpoint.clone())
+        .build()
+        .map_err(|e| TelemetryError::MetricExporter(e.to_string()))?;
+    let reader = PeriodicReader::builder(metric_exporter, runtime::Tokio)
+        .with_interval(config.metrics_export_interval)
+        .build();
+    let meter_provider = SdkMeterProvider::builder()
+        .with_reader(reader)
+        .with_resource(resource)
+        .build();
+
+    if PROVIDERS
+        .set((tracer_provider.clone(), meter_provider.clone()))
+        .is_err()
+    {
+        return Err(TelemetryError::AlreadyInitialised);
+    }
+
+    global::set_tracer_provider(tracer_provider);
+    global::set_meter_provider(meter_provider);
+    ENABLED.store(true, Ordering::Relaxed);
+
+    info!(
+        target: LOG_TARGET,
+        "Exporting OpenTelemetry traces and metrics to {} as a {} node (Provider ID: {:?})",
+        config.endpoint,
+        config.node_role,
+        config.provider_id
+    );
+
+    Ok(())
+}
+
+/// Flushes the pending traces and metrics and stops exporting them.
+pub fn shutdown_open_telemetry() {
+    ENABLED.store(false, Ordering::Relaxed);
+
+    if let Some((tracer_provider, meter_provider)) = PROVIDERS.get() {
+        let _ = tracer_provider.shutdown();
+        let _ = meter_provider.shutdown();
+    }
+}
+
+/// Whether OpenTelemetry has been initialised and flows are being recorded.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
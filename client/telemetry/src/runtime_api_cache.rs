@@ -0,0 +1,47 @@
+//! Metrics of the cache of runtime API responses kept by the BlockchainService.
+//!
+//! Tasks tend to query the runtime for the same information (i.e. the current tick or the
+//! capacity of a Provider) in bursts, so the BlockchainService serves repeated queries from a
+//! cache. The following metric is recorded, labeled by the query and whether it was a hit:
+//! - `storagehub.runtime_api_cache.lookups`: counter of the lookups made in the cache.
+
+use std::sync::OnceLock;
+
+use opentelemetry::{global, metrics::Counter, KeyValue};
+
+use crate::{is_enabled, INSTRUMENTATION_SCOPE};
+
+/// Attribute holding the runtime API query that was looked up in the cache.
+pub const QUERY_ATTRIBUTE: &str = "storagehub.runtime_api_cache.query";
+
+/// Attribute holding the result of the lookup (`hit` or `miss`).
+pub const RESULT_ATTRIBUTE: &str = "storagehub.runtime_api_cache.result";
+
+fn runtime_api_cache_lookups() -> &'static Counter<u64> {
+    static LOOKUPS: OnceLock<Counter<u64>> = OnceLock::new();
+    LOOKUPS.get_or_init(|| {
+        global::meter(INSTRUMENTATION_SCOPE)
+            .u64_counter("storagehub.runtime_api_cache.lookups")
+            .with_description(
+                "Lookups of runtime API responses in the cache of the BlockchainService.",
+            )
+            .build()
+    })
+}
+
+/// Records a lookup of `query` in the runtime API cache, and whether it was a `hit`.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_runtime_api_cache_lookup(query: &'static str, hit: bool) {
+    if !is_enabled() {
+        return;
+    }
+
+    runtime_api_cache_lookups().add(
+        1,
+        &[
+            KeyValue::new(QUERY_ATTRIBUTE, query),
+            KeyValue::new(RESULT_ATTRIBUTE, if hit { "hit" } else { "miss" }),
+        ],
+    );
+}
@@ -0,0 +1,83 @@
+//! Metrics of the safe mode of the BlockchainService.
+//!
+//! A node that stops seeing new finalised blocks assumes it is partitioned from the network and
+//! enters safe mode, pausing the tasks that write to the Forest root of its Provider until a new
+//! block is finalised. The following metrics are recorded:
+//! - `storagehub.safe_mode.transitions`: counter of the transitions in and out of safe mode,
+//!   labeled by the transition (`entered` or `exited`).
+//! - `storagehub.safe_mode.active`: gauge set to one while the node is in safe mode, zero otherwise.
+//! - `storagehub.safe_mode.duration`: histogram of how long the node stayed in safe mode each
+//!   time, in seconds.
+
+use std::{sync::OnceLock, time::Duration};
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, Gauge, Histogram},
+    KeyValue,
+};
+
+use crate::{is_enabled, INSTRUMENTATION_SCOPE};
+
+/// Attribute holding the transition of the safe mode (`entered` or `exited`).
+pub const TRANSITION_ATTRIBUTE: &str = "storagehub.safe_mode.transition";
+
+/// The instruments recording the metrics of the safe mode.
+struct SafeModeMetrics {
+    transitions: Counter<u64>,
+    active: Gauge<u64>,
+    duration: Histogram<f64>,
+}
+
+fn safe_mode_metrics() -> &'static SafeModeMetrics {
+    static METRICS: OnceLock<SafeModeMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let meter = global::meter(INSTRUMENTATION_SCOPE);
+        SafeModeMetrics {
+            transitions: meter
+                .u64_counter("storagehub.safe_mode.transitions")
+                .with_description("Transitions of the BlockchainService in and out of safe mode.")
+                .build(),
+            active: meter
+                .u64_gauge("storagehub.safe_mode.active")
+                .with_description("Whether the BlockchainService is in safe mode.")
+                .build(),
+            duration: meter
+                .f64_histogram("storagehub.safe_mode.duration")
+                .with_unit("s")
+                .with_description("Time the BlockchainService stayed in safe mode.")
+                .build(),
+        }
+    })
+}
+
+/// Records that the node entered safe mode.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_safe_mode_entered() {
+    if !is_enabled() {
+        return;
+    }
+
+    let metrics = safe_mode_metrics();
+    metrics
+        .transitions
+        .add(1, &[KeyValue::new(TRANSITION_ATTRIBUTE, "entered")]);
+    metrics.active.record(1, &[]);
+}
+
+/// Records that the node exited safe mode after being in it for `duration`.
+///
+/// Does nothing if OpenTelemetry has not been initialised.
+pub fn record_safe_mode_exited(duration: Duration) {
+    if !is_enabled() {
+        return;
+    }
+
+    let metrics = safe_mode_metrics();
+    metrics
+        .transitions
+        .add(1, &[KeyValue::new(TRANSITION_ATTRIBUTE, "exited")]);
+    metrics.active.record(0, &[]);
+    metrics.duration.record(duration.as_secs_f64(), &[]);
+}
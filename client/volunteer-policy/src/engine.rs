@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::policy::{VolunteerCandidate, VolunteerPolicy, VolunteerRejection, VolunteerRule};
+
+/// Evaluates storage requests against the current [`VolunteerPolicy`] of a BSP and the custom
+/// [`VolunteerRule`]s plugged into it.
+///
+/// Clones share the same policy, so a policy set through one of them (e.g. from the RPC methods)
+/// applies to every storage request evaluated afterwards by the others. Setting the policy lasts
+/// until the node restarts.
+#[derive(Clone, Default)]
+pub struct VolunteerPolicyEngine {
+    policy: Arc<RwLock<VolunteerPolicy>>,
+    rules: Vec<Arc<dyn VolunteerRule>>,
+}
+
+impl VolunteerPolicyEngine {
+    pub fn new(policy: VolunteerPolicy) -> Self {
+        Self {
+            policy: Arc::new(RwLock::new(policy)),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Adds a custom rule, evaluated after the rules of the policy and the rules added before it.
+    pub fn with_rule(mut self, rule: impl VolunteerRule + 'static) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// The current policy.
+    pub fn policy(&self) -> VolunteerPolicy {
+        self.policy.read().clone()
+    }
+
+    /// Replaces the current policy.
+    pub fn set_policy(&self, policy: VolunteerPolicy) {
+        *self.policy.write() = policy;
+    }
+
+    /// Evaluates `candidate` against the current policy and then the custom rules, returning the
+    /// first rule rejecting it.
+    pub fn evaluate(&self, candidate: &VolunteerCandidate) -> Result<(), VolunteerRejection> {
+        self.policy.read().evaluate(candidate)?;
+
+        for rule in self.rules.iter() {
+            rule.evaluate(candidate)
+                .map_err(|reason| VolunteerRejection {
+                    rule: rule.name(),
+                    reason,
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::H256;
+    use sp_runtime::AccountId32;
+
+    use super::*;
+
+    struct EvenFileSizes;
+
+    impl VolunteerRule for EvenFileSizes {
+        fn name(&self) -> &'static str {
+            "even-file-sizes"
+        }
+
+        fn evaluate(&self, candidate: &VolunteerCandidate) -> Result<(), String> {
+            if candidate.file_size % 2 == 0 {
+                Ok(())
+            } else {
+                Err("file size is odd".into())
+            }
+        }
+    }
+
+    fn candidate(file_size: u64) -> VolunteerCandidate {
+        VolunteerCandidate {
+            file_key: H256::repeat_byte(1),
+            bucket_id: H256::repeat_byte(2),
+            owner: AccountId32::new([3; 32]),
+            file_size,
+            capacity_headroom: None,
+            owner_over_debt_limit: None,
+            price_per_giga_unit_per_tick: None,
+        }
+    }
+
+    #[test]
+    fn policy_set_in_a_clone_applies_to_all() {
+        let engine = VolunteerPolicyEngine::new(VolunteerPolicy::default());
+        let rpc_engine = engine.clone();
+        assert!(engine.evaluate(&candidate(2048)).is_ok());
+
+        let policy = VolunteerPolicy {
+            max_file_size: Some(1024),
+            ..Default::default()
+        };
+        rpc_engine.set_policy(policy.clone());
+
+        assert_eq!(engine.policy(), policy);
+        assert_eq!(
+            engine.evaluate(&candidate(2048)).unwrap_err().rule,
+            "max-file-size"
+        );
+    }
+
+    #[test]
+    fn custom_rules_are_evaluated_after_the_policy() {
+        let engine = VolunteerPolicyEngine::new(VolunteerPolicy {
+            max_file_size: Some(1024),
+            ..Default::default()
+        })
+        .with_rule(EvenFileSizes);
+
+        assert!(engine.evaluate(&candidate(1000)).is_ok());
+        assert_eq!(
+            engine.evaluate(&candidate(1001)).unwrap_err().rule,
+            "even-file-sizes"
+        );
+        assert_eq!(
+            engine.evaluate(&candidate(2049)).unwrap_err().rule,
+            "max-file-size"
+        );
+    }
+}
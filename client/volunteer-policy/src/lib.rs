@@ -0,0 +1,17 @@
+//! Rules deciding which storage requests a BSP node volunteers for.
+//!
+//! Before volunteering for a storage request, a BSP evaluates it against the [`VolunteerPolicy`]
+//! configured by its operator: a maximum file size, an allowlist and a denylist of buckets, a
+//! denylist of owners and a maximum debt of the owners with this BSP, a minimum headroom left in
+//! the storage capacity after storing the file and a minimum price paid for the storage. Operators
+//! can plug their own rules on top of those with [`VolunteerRule`].
+//!
+//! The policy is set from the node's configuration when it starts, and can be read and replaced
+//! through the `storagehubclient_getVolunteerPolicy` and `storagehubclient_setVolunteerPolicy` RPC
+//! methods while it runs. See [`VolunteerPolicyEngine`].
+
+pub mod engine;
+pub mod policy;
+
+pub use engine::VolunteerPolicyEngine;
+pub use policy::{VolunteerCandidate, VolunteerPolicy, VolunteerRejection, VolunteerRule};
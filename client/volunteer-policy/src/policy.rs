@@ -0,0 +1,305 @@
+use std::{collections::BTreeSet, fmt};
+
+use serde::{Deserialize, Serialize};
+use shc_common::types::Balance;
+use sp_core::H256;
+use sp_runtime::AccountId32;
+
+/// Rules set by the operator of a BSP to decide which storage requests it volunteers for.
+///
+/// Every rule is optional, and the default policy volunteers for every storage request.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VolunteerPolicy {
+    /// Maximum size of the files to volunteer for (bytes).
+    pub max_file_size: Option<u64>,
+    /// Buckets whose files are the only ones to volunteer for. Any bucket if not set.
+    pub bucket_allowlist: Option<BTreeSet<H256>>,
+    /// Buckets whose files are never volunteered for.
+    pub bucket_denylist: BTreeSet<H256>,
+    /// Owners whose files are never volunteered for.
+    pub owner_denylist: BTreeSet<AccountId32>,
+    /// Maximum debt the owner of a file can have with this BSP for it to be volunteered for.
+    pub max_owner_debt: Option<Balance>,
+    /// Minimum storage capacity left unused after storing the file, out of the maximum storage
+    /// capacity of this BSP (bytes).
+    pub min_capacity_headroom: Option<u64>,
+    /// Minimum price paid per giga-unit of data stored per tick for the files to volunteer for.
+    pub min_price_per_giga_unit_per_tick: Option<Balance>,
+}
+
+/// A storage request a BSP could volunteer for, as evaluated by a [`VolunteerPolicy`].
+///
+/// The fields that are costly to look up are only needed by some of the rules of the policy, and are
+/// `None` when they were not looked up. The rules needing them reject the storage request if so.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolunteerCandidate {
+    pub file_key: H256,
+    pub bucket_id: H256,
+    pub owner: AccountId32,
+    pub file_size: u64,
+    /// Storage capacity left unused after storing the file, out of the maximum storage capacity of
+    /// this BSP (bytes).
+    pub capacity_headroom: Option<u64>,
+    /// Whether the debt of the owner with this BSP exceeds
+    /// [`max_owner_debt`](VolunteerPolicy::max_owner_debt).
+    pub owner_over_debt_limit: Option<bool>,
+    /// Current price paid per giga-unit of data stored per tick.
+    pub price_per_giga_unit_per_tick: Option<Balance>,
+}
+
+/// The reason a storage request is not volunteered for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolunteerRejection {
+    /// Name of the rule rejecting the storage request.
+    pub rule: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for VolunteerRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rejected by `{}` rule: {}", self.rule, self.reason)
+    }
+}
+
+/// A custom rule to decide whether a BSP volunteers for a storage request, evaluated after the
+/// rules of the [`VolunteerPolicy`].
+pub trait VolunteerRule: Send + Sync {
+    /// Name of the rule, reported when it rejects a storage request.
+    fn name(&self) -> &'static str;
+
+    /// Returns the reason to reject `candidate`, if any.
+    fn evaluate(&self, candidate: &VolunteerCandidate) -> Result<(), String>;
+}
+
+impl VolunteerPolicy {
+    /// Evaluates `candidate` against every rule of this policy, returning the first one rejecting
+    /// it.
+    pub fn evaluate(&self, candidate: &VolunteerCandidate) -> Result<(), VolunteerRejection> {
+        let reject = |rule, reason| Err(VolunteerRejection { rule, reason });
+
+        if let Some(max_file_size) = self.max_file_size {
+            if candidate.file_size > max_file_size {
+                return reject(
+                    "max-file-size",
+                    format!(
+                        "file size of {} bytes exceeds {} bytes",
+                        candidate.file_size, max_file_size
+                    ),
+                );
+            }
+        }
+
+        if let Some(bucket_allowlist) = &self.bucket_allowlist {
+            if !bucket_allowlist.contains(&candidate.bucket_id) {
+                return reject(
+                    "bucket-allowlist",
+                    format!("bucket {:?} is not allowed", candidate.bucket_id),
+                );
+            }
+        }
+
+        if self.bucket_denylist.contains(&candidate.bucket_id) {
+            return reject(
+                "bucket-denylist",
+                format!("bucket {:?} is denied", candidate.bucket_id),
+            );
+        }
+
+        if self.owner_denylist.contains(&candidate.owner) {
+            return reject(
+                "owner-denylist",
+                format!("owner {} is denied", candidate.owner),
+            );
+        }
+
+        if let Some(max_owner_debt) = self.max_owner_debt {
+            match candidate.owner_over_debt_limit {
+                Some(false) => {}
+                Some(true) => {
+                    return reject(
+                        "max-owner-debt",
+                        format!(
+                            "owner {} owes {} or more to this BSP",
+                            candidate.owner, max_owner_debt
+                        ),
+                    )
+                }
+                None => return reject("max-owner-debt", "debt of the owner is unknown".into()),
+            }
+        }
+
+        if let Some(min_capacity_headroom) = self.min_capacity_headroom {
+            match candidate.capacity_headroom {
+                Some(headroom) if headroom >= min_capacity_headroom => {}
+                Some(headroom) => {
+                    return reject(
+                        "min-capacity-headroom",
+                        format!(
+                            "{} bytes of capacity would be left, less than {} bytes",
+                            headroom, min_capacity_headroom
+                        ),
+                    )
+                }
+                None => {
+                    return reject(
+                        "min-capacity-headroom",
+                        "capacity headroom is unknown".into(),
+                    )
+                }
+            }
+        }
+
+        if let Some(min_price) = self.min_price_per_giga_unit_per_tick {
+            match candidate.price_per_giga_unit_per_tick {
+                Some(price) if price >= min_price => {}
+                Some(price) => {
+                    return reject(
+                        "min-price-per-giga-unit-per-tick",
+                        format!("price of {} is less than {}", price, min_price),
+                    )
+                }
+                None => {
+                    return reject(
+                        "min-price-per-giga-unit-per-tick",
+                        "price is unknown".into(),
+                    )
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate() -> VolunteerCandidate {
+        VolunteerCandidate {
+            file_key: H256::repeat_byte(1),
+            bucket_id: H256::repeat_byte(2),
+            owner: AccountId32::new([3; 32]),
+            file_size: 1024,
+            capacity_headroom: None,
+            owner_over_debt_limit: None,
+            price_per_giga_unit_per_tick: None,
+        }
+    }
+
+    fn rejected_by(
+        policy: &VolunteerPolicy,
+        candidate: &VolunteerCandidate,
+    ) -> Option<&'static str> {
+        policy
+            .evaluate(candidate)
+            .err()
+            .map(|rejection| rejection.rule)
+    }
+
+    #[test]
+    fn default_policy_volunteers_for_everything() {
+        assert_eq!(rejected_by(&VolunteerPolicy::default(), &candidate()), None);
+    }
+
+    #[test]
+    fn files_over_max_size_are_rejected() {
+        let policy = VolunteerPolicy {
+            max_file_size: Some(1024),
+            ..Default::default()
+        };
+        assert_eq!(rejected_by(&policy, &candidate()), None);
+
+        let large_file = VolunteerCandidate {
+            file_size: 1025,
+            ..candidate()
+        };
+        assert_eq!(rejected_by(&policy, &large_file), Some("max-file-size"));
+    }
+
+    #[test]
+    fn buckets_are_filtered_by_allowlist_and_denylist() {
+        let allowed = VolunteerPolicy {
+            bucket_allowlist: Some(BTreeSet::from([H256::repeat_byte(2)])),
+            ..Default::default()
+        };
+        assert_eq!(rejected_by(&allowed, &candidate()), None);
+
+        let not_allowed = VolunteerPolicy {
+            bucket_allowlist: Some(BTreeSet::from([H256::repeat_byte(9)])),
+            ..Default::default()
+        };
+        assert_eq!(
+            rejected_by(&not_allowed, &candidate()),
+            Some("bucket-allowlist")
+        );
+
+        let denied = VolunteerPolicy {
+            bucket_denylist: BTreeSet::from([H256::repeat_byte(2)]),
+            ..Default::default()
+        };
+        assert_eq!(rejected_by(&denied, &candidate()), Some("bucket-denylist"));
+    }
+
+    #[test]
+    fn denied_and_indebted_owners_are_rejected() {
+        let denied = VolunteerPolicy {
+            owner_denylist: BTreeSet::from([AccountId32::new([3; 32])]),
+            ..Default::default()
+        };
+        assert_eq!(rejected_by(&denied, &candidate()), Some("owner-denylist"));
+
+        let max_debt = VolunteerPolicy {
+            max_owner_debt: Some(100),
+            ..Default::default()
+        };
+        let owner_in_good_standing = VolunteerCandidate {
+            owner_over_debt_limit: Some(false),
+            ..candidate()
+        };
+        let owner_in_debt = VolunteerCandidate {
+            owner_over_debt_limit: Some(true),
+            ..candidate()
+        };
+        assert_eq!(rejected_by(&max_debt, &owner_in_good_standing), None);
+        assert_eq!(
+            rejected_by(&max_debt, &owner_in_debt),
+            Some("max-owner-debt")
+        );
+        assert_eq!(rejected_by(&max_debt, &candidate()), Some("max-owner-debt"));
+    }
+
+    #[test]
+    fn capacity_headroom_and_price_have_minimums() {
+        let policy = VolunteerPolicy {
+            min_capacity_headroom: Some(4096),
+            min_price_per_giga_unit_per_tick: Some(10),
+            ..Default::default()
+        };
+        let acceptable = VolunteerCandidate {
+            capacity_headroom: Some(4096),
+            price_per_giga_unit_per_tick: Some(10),
+            ..candidate()
+        };
+        assert_eq!(rejected_by(&policy, &acceptable), None);
+
+        let little_headroom = VolunteerCandidate {
+            capacity_headroom: Some(4095),
+            ..acceptable.clone()
+        };
+        assert_eq!(
+            rejected_by(&policy, &little_headroom),
+            Some("min-capacity-headroom")
+        );
+
+        let cheap = VolunteerCandidate {
+            price_per_giga_unit_per_tick: Some(9),
+            ..acceptable
+        };
+        assert_eq!(
+            rejected_by(&policy, &cheap),
+            Some("min-price-per-giga-unit-per-tick")
+        );
+    }
+}
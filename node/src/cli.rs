@@ -1,9 +1,26 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr};
 
 use clap::{Parser, ValueEnum};
-use storage_hub_runtime::StorageDataUnit;
-
-use crate::command::ProviderOptions;
+use serde::{Deserialize, Serialize};
+use sp_core::H256;
+use sp_runtime::AccountId32;
+use storage_hub_runtime::{Balance, StorageDataUnit};
+
+use crate::{
+    command::ProviderOptions,
+    config::{
+        self, ConfigError, FieldError, DEFAULT_BUCKET_GC_RETENTION_PERIOD,
+        DEFAULT_DB_BACKUP_MAX_INCREMENTAL_CHAIN, DEFAULT_EXTRINSIC_RETRY_TIMEOUT,
+        DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD, DEFAULT_FOREST_WRITE_CACHE_SIZE,
+        DEFAULT_MAX_TRANSFER_CHUNK_SIZE, DEFAULT_MIN_TRANSFER_CHUNK_SIZE,
+        DEFAULT_OTLP_METRICS_EXPORT_INTERVAL, DEFAULT_OTLP_SERVICE_NAME, DEFAULT_PARTITION_TIMEOUT,
+        DEFAULT_PEER_BAN_DURATION, DEFAULT_PEER_BAN_THRESHOLD,
+        DEFAULT_PROVIDER_EARNINGS_BACKFILL_DEPTH, DEFAULT_RUNTIME_API_CACHE_TTL,
+    },
+    db_backup::{ListDbBackupsCmd, RestoreDbBackupCmd},
+    forest_snapshot::{ExportForestSnapshotCmd, ImportForestSnapshotCmd},
+    identity::{ExportIdentityCmd, ImportIdentityCmd},
+};
 
 /// Sub-commands supported by the collator.
 #[derive(Debug, clap::Subcommand)]
@@ -42,9 +59,29 @@ pub enum Subcommand {
     /// The pallet benchmarking moved to the `pallet` sub-command.
     #[command(subcommand)]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+    /// Export the identity of this provider node (keys, provider ID, config) to an encrypted bundle,
+    /// to migrate the provider to a new node.
+    ExportIdentity(ExportIdentityCmd),
+
+    /// Import the identity of a provider node from an encrypted bundle.
+    ImportIdentity(ImportIdentityCmd),
+
+    /// List the backups of the File Storage and Forest Storage databases of a provider node.
+    ListDbBackups(ListDbBackupsCmd),
+
+    /// Restore the File Storage and Forest Storage databases of a provider node from a backup.
+    RestoreDbBackup(RestoreDbBackupCmd),
+
+    /// Export a forest of a provider node to a snapshot file, with its root and leaves.
+    ExportForestSnapshot(ExportForestSnapshotCmd),
+
+    /// Import a forest of a provider node from a snapshot file, verifying its root.
+    ImportForestSnapshot(ImportForestSnapshotCmd),
 }
 
-#[derive(ValueEnum, Clone, Debug, Eq, PartialEq)]
+#[derive(ValueEnum, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ProviderType {
     /// Main Storage Provider
     Msp,
@@ -54,9 +91,11 @@ pub enum ProviderType {
     User,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum StorageLayer {
     /// RocksDB with path.
+    #[serde(alias = "rocks-db")]
     RocksDB,
     /// In Memory
     Memory,
@@ -69,6 +108,15 @@ pub struct ProviderConfigurations {
     #[arg(long)]
     pub provider: bool,
 
+    /// Load the provider configuration from a TOML file, instead of the individual provider flags.
+    /// Implies `--provider`. Run with `--print-sample-config` to get a documented sample.
+    #[clap(long, value_name = "PATH")]
+    pub provider_config_file: Option<PathBuf>,
+
+    /// Print a sample provider configuration file, with every setting documented, and exit.
+    #[clap(long)]
+    pub print_sample_config: bool,
+
     /// Type of StorageHub provider.
     #[clap(
         long,
@@ -107,36 +155,319 @@ pub struct ProviderConfigurations {
     pub storage_path: Option<String>,
 
     /// Extrinsic retry timeout in seconds.
-    #[clap(long, default_value = "60")]
+    #[clap(long, default_value_t = DEFAULT_EXTRINSIC_RETRY_TIMEOUT)]
     pub extrinsic_retry_timeout: u64,
 
+    /// Time a task can hold the Forest root write lock before a warning is logged (in seconds).
+    /// While a task holds the lock, no other task can submit proofs or confirm storing files.
+    #[clap(long, default_value_t = DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD)]
+    pub forest_root_write_lock_warn_threshold: u64,
+
+    /// Time without new finalised blocks after which the node considers itself partitioned from
+    /// the network (in seconds). It then enters safe mode, pausing proof submissions, storage
+    /// confirmations and other Forest root writes until a new block is finalised.
+    /// Set it to 0 to never enter safe mode.
+    #[clap(long, default_value_t = DEFAULT_PARTITION_TIMEOUT)]
+    pub partition_timeout: u64,
+
     /// MSP charging fees period (in blocks).
     /// Setting it to 600 with a block every 6 seconds will charge user every hour.
     #[clap(long, required_if_eq_any([
         ("provider_type", "msp"),
     ]))]
     pub msp_charging_period: Option<u32>,
+
+    /// Time the files and forest of a bucket moved away from an MSP are kept for after the move is
+    /// finalised, before being deleted (in seconds).
+    #[clap(long, default_value_t = DEFAULT_BUCKET_GC_RETENTION_PERIOD)]
+    pub bucket_gc_retention_period: u64,
+
+    /// Directory where backups of the File Storage and Forest Storage databases are stored.
+    /// Enables the `storagehubclient_createDbBackup` and `storagehubclient_listDbBackups` RPC
+    /// methods. Only supported with the `rocks-db` storage layer.
+    #[clap(long)]
+    pub db_backup_path: Option<String>,
+
+    /// Maximum amount of incremental database backups in a row before taking a full one.
+    #[clap(long, default_value_t = DEFAULT_DB_BACKUP_MAX_INCREMENTAL_CHAIN)]
+    pub db_backup_max_incremental_chain: u32,
+
+    /// Aggregate the earnings of this Provider (payment stream charges, treasury cuts and slashes)
+    /// per day from finalised blocks, without needing the indexer. They are read through the
+    /// `storagehubclient_getProviderEarnings` RPC method and exported as Prometheus metrics.
+    #[clap(long)]
+    pub provider_earnings: bool,
+
+    /// Amount of finalised blocks to backfill the Provider earnings from on first run.
+    #[clap(long, default_value_t = DEFAULT_PROVIDER_EARNINGS_BACKFILL_DEPTH)]
+    pub provider_earnings_backfill_depth: u32,
+
+    /// gRPC endpoint of an OpenTelemetry (OTLP) collector to export traces and metrics to
+    /// (i.e. `http://localhost:4317`).
+    /// If not provided, OpenTelemetry export is disabled.
+    #[clap(long)]
+    pub otlp_endpoint: Option<String>,
+
+    /// Service name reported to the OpenTelemetry collector.
+    #[clap(long, default_value = DEFAULT_OTLP_SERVICE_NAME)]
+    pub otlp_service_name: String,
+
+    /// Interval between exports of the OpenTelemetry metrics (in seconds).
+    #[clap(long, default_value_t = DEFAULT_OTLP_METRICS_EXPORT_INTERVAL)]
+    pub otlp_metrics_export_interval: u64,
+
+    /// Smallest size of the chunks in which files are sent or received (bytes).
+    /// The size of each transfer is negotiated with the peer and adapted to the connection, within
+    /// these bounds. Rounded down to a multiple of the file chunk size.
+    #[clap(long, default_value_t = DEFAULT_MIN_TRANSFER_CHUNK_SIZE)]
+    pub min_transfer_chunk_size: u64,
+
+    /// Biggest size of the chunks in which files are sent or received (bytes).
+    #[clap(long, default_value_t = DEFAULT_MAX_TRANSFER_CHUNK_SIZE)]
+    pub max_transfer_chunk_size: u64,
+
+    /// Maximum bandwidth used to serve files to all peers together (bytes per second).
+    /// Responses to download requests are delayed to stay within it. Not limited if not provided.
+    #[clap(long)]
+    pub max_upload_bandwidth: Option<u64>,
+
+    /// Maximum bandwidth used to serve files to each peer (bytes per second).
+    /// Not limited if not provided.
+    #[clap(long)]
+    pub max_peer_upload_bandwidth: Option<u64>,
+
+    /// Amount of score below zero at which a peer is banned from requesting files.
+    /// Peers gain score with successful transfers, and lose it with failed, timed out and, above
+    /// all, corrupt ones. Scores are persisted with the RocksDB storage layer.
+    #[clap(long, default_value_t = DEFAULT_PEER_BAN_THRESHOLD)]
+    pub peer_ban_threshold: u64,
+
+    /// Time a peer stays banned from requesting files (in seconds).
+    #[clap(long, default_value_t = DEFAULT_PEER_BAN_DURATION)]
+    pub peer_ban_duration: u64,
+
+    /// Maximum amount of requests a peer can send per minute, beyond which they count as failed
+    /// transfers. Not limited if not provided.
+    #[clap(long)]
+    pub max_peer_requests_per_minute: Option<u32>,
+
+    /// Maximum amount of chunks of the stored files checked for corruption per second.
+    /// The stored files are proven against their fingerprints in the background, over and over,
+    /// and the corrupt ones are fetched again from other providers or, if that is not possible,
+    /// stopped being stored. The files are not checked if not provided.
+    #[clap(long)]
+    pub scrub_chunks_per_second: Option<u64>,
+
+    /// Period between the checks of the space of the disk the files are stored in (in seconds).
+    /// A warning is logged when the on-chain capacity of a BSP exceeds what the disk can hold, i.e.
+    /// the data it already stores plus the space available in the disk. Only supported with the
+    /// `rocks-db` storage layer. The disk is not checked if not provided.
+    #[clap(long)]
+    pub disk_watchdog_period: Option<u64>,
+
+    /// Lower the on-chain capacity of a BSP automatically, with a `change_capacity` extrinsic,
+    /// when it exceeds what the disk can hold. Requires `--disk-watchdog-period`.
+    #[clap(long)]
+    pub auto_reconcile_capacity: bool,
+
+    /// Maximum amount of threads used to generate a single Forest proof.
+    /// The challenged file keys are proven in parallel, grouped by the subtree of the Forest they
+    /// fall in. If not provided, one thread per available CPU is used.
+    #[clap(long)]
+    pub forest_proof_threads: Option<usize>,
+
+    /// Maximum size of the Forest changes batched in memory before being written (in bytes).
+    /// The mutations a task applies to the Forest while holding the Forest root write lock are
+    /// written to the database at once when it is done, unless they grow beyond this size.
+    /// Set it to 0 to write every mutation right away.
+    #[clap(long, default_value_t = DEFAULT_FOREST_WRITE_CACHE_SIZE)]
+    pub forest_write_cache_size: usize,
+
+    /// Time the responses of the runtime API queries made by tasks (i.e. the current tick or the
+    /// capacity of a Provider) are cached for (in milliseconds), to serve repeated queries without
+    /// calling into the runtime. They are dropped on every new best or finalised block anyway.
+    /// Set it to 0 to disable the cache.
+    #[clap(long, default_value_t = DEFAULT_RUNTIME_API_CACHE_TTL)]
+    pub runtime_api_cache_ttl: u64,
+
+    /// Time the responses of a specific runtime API query are cached for, as
+    /// `QUERY=MILLISECONDS` (can be repeated, or given as a comma-separated list), overriding
+    /// `--runtime-api-cache-ttl`. See `--print-sample-config` for the queries that are cached.
+    #[clap(
+        long = "runtime-api-cache-query-ttl",
+        value_name = "QUERY=MILLISECONDS",
+        value_delimiter = ',',
+        value_parser = parse_runtime_api_cache_query_ttl
+    )]
+    pub runtime_api_cache_query_ttls: Vec<(String, u64)>,
+
+    /// On-chain ID of a BSP run by the same operator as this one (can be repeated, or given as a
+    /// comma-separated list). Used with `--max-fleet-volunteers-per-file` to avoid several BSPs of
+    /// the fleet volunteering for the same file. This BSP can be included in the list.
+    #[clap(long = "fleet-bsp-id", value_name = "BSP_ID", value_delimiter = ',')]
+    pub fleet_bsp_ids: Vec<H256>,
+
+    /// Maximum amount of BSPs of the fleet (see `--fleet-bsp-id`) that volunteer for the same file.
+    /// This BSP skips volunteering for a file if that many BSPs of the fleet already volunteered
+    /// for it, or are going to volunteer for it in the same tick with precedence over this one.
+    #[clap(long, requires = "fleet_bsp_ids")]
+    pub max_fleet_volunteers_per_file: Option<u32>,
+
+    /// Maximum size of the files this BSP volunteers for, in bytes.
+    #[clap(long, value_name = "BYTES")]
+    pub volunteer_max_file_size: Option<u64>,
+
+    /// ID of a bucket whose files are the only ones this BSP volunteers for (can be repeated, or
+    /// given as a comma-separated list). Files of any bucket are volunteered for if not set.
+    #[clap(
+        long = "volunteer-bucket-allow",
+        value_name = "BUCKET_ID",
+        value_delimiter = ','
+    )]
+    pub volunteer_bucket_allowlist: Vec<H256>,
+
+    /// ID of a bucket whose files this BSP never volunteers for (can be repeated, or given as a
+    /// comma-separated list).
+    #[clap(
+        long = "volunteer-bucket-deny",
+        value_name = "BUCKET_ID",
+        value_delimiter = ','
+    )]
+    pub volunteer_bucket_denylist: Vec<H256>,
+
+    /// Account of an owner whose files this BSP never volunteers for (can be repeated, or given as
+    /// a comma-separated list of SS58 addresses).
+    #[clap(
+        long = "volunteer-owner-deny",
+        value_name = "ACCOUNT",
+        value_delimiter = ','
+    )]
+    pub volunteer_owner_denylist: Vec<AccountId32>,
+
+    /// Maximum debt the owner of a file can have with this BSP for it to volunteer for the file.
+    #[clap(long, value_name = "BALANCE")]
+    pub volunteer_max_owner_debt: Option<Balance>,
+
+    /// Minimum capacity left unused after storing a file for this BSP to volunteer for it, out of
+    /// `--max-storage-capacity`, in bytes.
+    #[clap(long, value_name = "BYTES")]
+    pub volunteer_min_capacity_headroom: Option<u64>,
+
+    /// Minimum current price per giga-unit of data stored per tick for this BSP to volunteer for
+    /// files.
+    #[clap(long, value_name = "BALANCE")]
+    pub volunteer_min_price_per_giga_unit_per_tick: Option<Balance>,
+
+    /// Address to serve the files stored by this MSP over HTTP on (i.e. `0.0.0.0:8080`), at
+    /// `/<bucket_id>/<location>`. The read access of the requester is checked on-chain before
+    /// serving a file. The HTTP Gateway is disabled if not provided.
+    #[clap(long)]
+    pub http_gateway_addr: Option<SocketAddr>,
+
+    /// Certificate (chain) to serve the HTTP Gateway over TLS with, in PEM format.
+    #[clap(long, requires = "http_gateway_tls_key")]
+    pub http_gateway_tls_cert: Option<PathBuf>,
+
+    /// Private key of the certificate to serve the HTTP Gateway over TLS with, in PEM format.
+    #[clap(long, requires = "http_gateway_tls_cert")]
+    pub http_gateway_tls_key: Option<PathBuf>,
+
+    /// Run in replay mode, for debugging: instead of following the chain, replay the events emitted
+    /// for the finalised blocks from this one to `--replay-to-block` (inclusive), once synced.
+    /// Tasks react to the replayed events as they did, but submitting extrinsics is stubbed out,
+    /// and blocks whose events diverge from the ones recorded when they were finalised are logged.
+    #[clap(long, requires = "replay_to_block")]
+    pub replay_from_block: Option<u32>,
+
+    /// Last finalised block to replay in replay mode. See `--replay-from-block`.
+    #[clap(long, requires = "replay_from_block")]
+    pub replay_to_block: Option<u32>,
 }
 
 impl ProviderConfigurations {
-    pub fn provider_options(&self) -> ProviderOptions {
-        ProviderOptions {
-            provider_type: self
-                .provider_type
-                .clone()
-                .expect("Provider type is required"),
+    /// The validated provider configuration, if running as a provider.
+    ///
+    /// It is loaded from the `--provider-config-file` if given, or built from the individual
+    /// provider flags otherwise.
+    pub fn provider_options(&self) -> Result<Option<ProviderOptions>, ConfigError> {
+        if let Some(path) = &self.provider_config_file {
+            return ProviderOptions::from_file(path).map(Some);
+        }
+
+        if !self.provider {
+            return Ok(None);
+        }
+
+        // Clap already errors out if the provider type is missing, this is just a safety net.
+        let provider_type = self.provider_type.clone().ok_or_else(|| {
+            ConfigError::Invalid(vec![FieldError::Missing {
+                field: "provider-type",
+                required_by: "with `--provider`",
+            }])
+        })?;
+
+        let options = ProviderOptions {
+            provider_type,
             storage_layer: self
                 .storage_layer
                 .clone()
-                .expect("Storage layer is required"),
+                .unwrap_or_else(config::default_storage_layer),
             storage_path: self.storage_path.clone(),
-            // We can default since the clap would have errored out if it was not provided when required.
-            // In any other case, max_storage_capacity is not required and can be set to default.
             max_storage_capacity: self.max_storage_capacity,
             jump_capacity: self.jump_capacity,
             extrinsic_retry_timeout: self.extrinsic_retry_timeout,
+            forest_root_write_lock_warn_threshold: self.forest_root_write_lock_warn_threshold,
+            partition_timeout: self.partition_timeout,
             msp_charging_period: self.msp_charging_period,
-        }
+            bucket_gc_retention_period: self.bucket_gc_retention_period,
+            db_backup_path: self.db_backup_path.clone(),
+            db_backup_max_incremental_chain: self.db_backup_max_incremental_chain,
+            provider_earnings: self.provider_earnings,
+            provider_earnings_backfill_depth: self.provider_earnings_backfill_depth,
+            otlp_endpoint: self.otlp_endpoint.clone(),
+            otlp_service_name: self.otlp_service_name.clone(),
+            otlp_metrics_export_interval: self.otlp_metrics_export_interval,
+            min_transfer_chunk_size: self.min_transfer_chunk_size,
+            max_transfer_chunk_size: self.max_transfer_chunk_size,
+            max_upload_bandwidth: self.max_upload_bandwidth,
+            max_peer_upload_bandwidth: self.max_peer_upload_bandwidth,
+            peer_ban_threshold: self.peer_ban_threshold,
+            peer_ban_duration: self.peer_ban_duration,
+            max_peer_requests_per_minute: self.max_peer_requests_per_minute,
+            scrub_chunks_per_second: self.scrub_chunks_per_second,
+            disk_watchdog_period: self.disk_watchdog_period,
+            auto_reconcile_capacity: self.auto_reconcile_capacity,
+            forest_proof_threads: self.forest_proof_threads,
+            forest_write_cache_size: self.forest_write_cache_size,
+            runtime_api_cache_ttl: self.runtime_api_cache_ttl,
+            runtime_api_cache_query_ttls: self
+                .runtime_api_cache_query_ttls
+                .iter()
+                .cloned()
+                .collect(),
+            fleet_bsp_ids: self.fleet_bsp_ids.clone(),
+            max_fleet_volunteers_per_file: self.max_fleet_volunteers_per_file,
+            volunteer_max_file_size: self.volunteer_max_file_size,
+            volunteer_bucket_allowlist: self.volunteer_bucket_allowlist.clone(),
+            volunteer_bucket_denylist: self.volunteer_bucket_denylist.clone(),
+            volunteer_owner_denylist: self.volunteer_owner_denylist.clone(),
+            volunteer_max_owner_debt: self.volunteer_max_owner_debt,
+            volunteer_min_capacity_headroom: self.volunteer_min_capacity_headroom,
+            volunteer_min_price_per_giga_unit_per_tick: self
+                .volunteer_min_price_per_giga_unit_per_tick,
+            http_gateway_addr: self.http_gateway_addr,
+            http_gateway_tls_cert: self.http_gateway_tls_cert.clone(),
+            http_gateway_tls_key: self.http_gateway_tls_key.clone(),
+            replay_from_block: self.replay_from_block,
+            replay_to_block: self.replay_to_block,
+            // Only reloadable from the provider configuration file.
+            config_watch_period: None,
+            log_filter: None,
+            config_file: None,
+        };
+        options.validate()?;
+
+        Ok(Some(options))
     }
 }
 
@@ -155,6 +486,11 @@ pub struct IndexerConfigurations {
     /// environment variable is not set, the node will abort.
     #[arg(long)]
     pub database_url: Option<String>,
+
+    /// Address to serve the GraphQL API over the indexer database on (i.e. `127.0.0.1:8090`), at
+    /// `/graphql`. Requires a database URL. The GraphQL API is disabled if not provided.
+    #[arg(long)]
+    pub indexer_graphql_addr: Option<SocketAddr>,
 }
 
 /// Block authoring scheme to be used by the dev service.
@@ -184,6 +520,19 @@ impl FromStr for Sealing {
     }
 }
 
+/// Parses a `QUERY=MILLISECONDS` pair of `--runtime-api-cache-query-ttl`. The query itself is
+/// validated along with the rest of the provider configuration.
+fn parse_runtime_api_cache_query_ttl(s: &str) -> Result<(String, u64), String> {
+    let (query, ttl) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `QUERY=MILLISECONDS`, got `{}`", s))?;
+    let ttl = ttl
+        .parse::<u64>()
+        .map_err(|e| format!("invalid TTL `{}`: {}", ttl, e))?;
+
+    Ok((query.to_string(), ttl))
+}
+
 const AFTER_HELP_EXAMPLE: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</></>
    <bold>parachain-template-node build-spec --disable-default-bootnode > plain-parachain-chainspec.json</>
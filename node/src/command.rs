@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf};
+
 use cumulus_client_service::storage_proof_size::HostFunctions as ReclaimHostFunctions;
 use cumulus_primitives_core::ParaId;
 use frame_benchmarking_cli::{BenchmarkCmd, SUBSTRATE_REFERENCE_HARDWARE};
@@ -7,32 +9,170 @@ use sc_cli::{
     NetworkParams, Result, RpcEndpoint, SharedParams, SubstrateCli,
 };
 use sc_service::config::{BasePath, PrometheusConfig};
-use storage_hub_runtime::{Block, StorageDataUnit};
+use serde::{Deserialize, Serialize};
+use sp_core::H256;
+use sp_runtime::AccountId32;
+use storage_hub_runtime::{Balance, Block, StorageDataUnit};
 
 use crate::{
     chain_spec,
     cli::{Cli, ProviderType, RelayChainCli, StorageLayer, Subcommand},
+    config,
     service::new_partial,
 };
 
 // TODO: Have specific StorageHub role options (i.e. ProviderOptions, UserOptions).
 /// Configuration for the provider.
-#[derive(Debug, Clone)]
+///
+/// Also the schema of the provider configuration file, whose fields are named like their flags.
+/// See [`crate::config`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct ProviderOptions {
     /// Provider type.
     pub provider_type: ProviderType,
     /// Storage layer.
+    #[serde(default = "config::default_storage_layer")]
     pub storage_layer: StorageLayer,
     /// RocksDB Path.
+    #[serde(default)]
     pub storage_path: Option<String>,
     /// Maximum storage capacity of the Storage Provider (bytes).
+    #[serde(default)]
     pub max_storage_capacity: Option<StorageDataUnit>,
     /// Jump capacity (bytes).
+    #[serde(default)]
     pub jump_capacity: Option<StorageDataUnit>,
     /// Extrinsic retry timeout in seconds.
+    #[serde(default = "config::default_extrinsic_retry_timeout")]
     pub extrinsic_retry_timeout: u64,
+    /// Time a task can hold the Forest root write lock before a warning is logged, in seconds.
+    #[serde(default = "config::default_forest_root_write_lock_warn_threshold")]
+    pub forest_root_write_lock_warn_threshold: u64,
+    /// Time without new finalised blocks before the node enters safe mode, in seconds.
+    #[serde(default = "config::default_partition_timeout")]
+    pub partition_timeout: u64,
     /// MSP charging fees frequency.
+    #[serde(default)]
     pub msp_charging_period: Option<u32>,
+    /// Time the files of a bucket moved away from an MSP are kept for, in seconds.
+    #[serde(default = "config::default_bucket_gc_retention_period")]
+    pub bucket_gc_retention_period: u64,
+    /// Directory where database backups are stored, if enabled.
+    #[serde(default)]
+    pub db_backup_path: Option<String>,
+    /// Maximum amount of incremental database backups in a row before taking a full one.
+    #[serde(default = "config::default_db_backup_max_incremental_chain")]
+    pub db_backup_max_incremental_chain: u32,
+    /// Whether to aggregate the earnings of this Provider locally.
+    #[serde(default)]
+    pub provider_earnings: bool,
+    /// Amount of finalised blocks to backfill the Provider earnings from on first run.
+    #[serde(default = "config::default_provider_earnings_backfill_depth")]
+    pub provider_earnings_backfill_depth: u32,
+    /// OpenTelemetry collector endpoint, if enabled.
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Service name reported to the OpenTelemetry collector.
+    #[serde(default = "config::default_otlp_service_name")]
+    pub otlp_service_name: String,
+    /// Interval between exports of the OpenTelemetry metrics in seconds.
+    #[serde(default = "config::default_otlp_metrics_export_interval")]
+    pub otlp_metrics_export_interval: u64,
+    /// Smallest transfer chunk size (bytes) to use when sending or receiving files.
+    #[serde(default = "config::default_min_transfer_chunk_size")]
+    pub min_transfer_chunk_size: u64,
+    /// Biggest transfer chunk size (bytes) to use when sending or receiving files.
+    #[serde(default = "config::default_max_transfer_chunk_size")]
+    pub max_transfer_chunk_size: u64,
+    /// Maximum bandwidth (bytes per second) used to serve files to all peers, unlimited if not set.
+    #[serde(default)]
+    pub max_upload_bandwidth: Option<u64>,
+    /// Maximum bandwidth (bytes per second) used to serve files to each peer, unlimited if not set.
+    #[serde(default)]
+    pub max_peer_upload_bandwidth: Option<u64>,
+    /// Amount of score below zero at which a peer is banned from requesting files.
+    #[serde(default = "config::default_peer_ban_threshold")]
+    pub peer_ban_threshold: u64,
+    /// Time a peer stays banned from requesting files in seconds.
+    #[serde(default = "config::default_peer_ban_duration")]
+    pub peer_ban_duration: u64,
+    /// Maximum amount of requests a peer can send per minute, unlimited if not set.
+    #[serde(default)]
+    pub max_peer_requests_per_minute: Option<u32>,
+    /// Maximum amount of chunks checked for corruption per second, not checked if not set.
+    #[serde(default)]
+    pub scrub_chunks_per_second: Option<u64>,
+    /// Period between the checks of the space of the disk in seconds, not checked if not set.
+    #[serde(default)]
+    pub disk_watchdog_period: Option<u64>,
+    /// Whether to lower the on-chain capacity of a BSP when it exceeds what the disk can hold.
+    #[serde(default)]
+    pub auto_reconcile_capacity: bool,
+    /// Maximum amount of threads used to generate a single Forest proof, one per CPU if not set.
+    #[serde(default)]
+    pub forest_proof_threads: Option<usize>,
+    /// Maximum size of the Forest changes batched in memory before being written, in bytes.
+    #[serde(default = "config::default_forest_write_cache_size")]
+    pub forest_write_cache_size: usize,
+    /// Time the responses of runtime API queries made by tasks are cached for, in milliseconds.
+    #[serde(default = "config::default_runtime_api_cache_ttl")]
+    pub runtime_api_cache_ttl: u64,
+    /// Time the responses of specific runtime API queries are cached for, in milliseconds.
+    #[serde(default)]
+    pub runtime_api_cache_query_ttls: BTreeMap<String, u64>,
+    /// The BSPs run by the same operator as this one, to coordinate volunteering with.
+    #[serde(default)]
+    pub fleet_bsp_ids: Vec<H256>,
+    /// Maximum amount of BSPs of the fleet that volunteer for the same file, if coordinating.
+    #[serde(default)]
+    pub max_fleet_volunteers_per_file: Option<u32>,
+    /// Maximum size of the files a BSP volunteers for, in bytes.
+    #[serde(default)]
+    pub volunteer_max_file_size: Option<u64>,
+    /// Buckets whose files are the only ones a BSP volunteers for. Any bucket if empty.
+    #[serde(default)]
+    pub volunteer_bucket_allowlist: Vec<H256>,
+    /// Buckets whose files a BSP never volunteers for.
+    #[serde(default)]
+    pub volunteer_bucket_denylist: Vec<H256>,
+    /// Owners whose files a BSP never volunteers for.
+    #[serde(default)]
+    pub volunteer_owner_denylist: Vec<AccountId32>,
+    /// Maximum debt with a BSP of the owners of the files it volunteers for.
+    #[serde(default)]
+    pub volunteer_max_owner_debt: Option<Balance>,
+    /// Minimum capacity left unused by a BSP after storing a file it volunteers for, in bytes.
+    #[serde(default)]
+    pub volunteer_min_capacity_headroom: Option<u64>,
+    /// Minimum price per giga-unit of data stored per tick of the files a BSP volunteers for.
+    #[serde(default)]
+    pub volunteer_min_price_per_giga_unit_per_tick: Option<Balance>,
+    /// Address to serve the files stored by an MSP over HTTP on, disabled if not set.
+    #[serde(default)]
+    pub http_gateway_addr: Option<SocketAddr>,
+    /// Certificate to serve the HTTP Gateway over TLS with, in PEM format.
+    #[serde(default)]
+    pub http_gateway_tls_cert: Option<PathBuf>,
+    /// Private key of the certificate to serve the HTTP Gateway over TLS with, in PEM format.
+    #[serde(default)]
+    pub http_gateway_tls_key: Option<PathBuf>,
+    /// First finalised block to replay, if running in replay mode.
+    #[serde(default)]
+    pub replay_from_block: Option<u32>,
+    /// Last finalised block to replay, if running in replay mode.
+    #[serde(default)]
+    pub replay_to_block: Option<u32>,
+    /// Period between the checks of the configuration file for changes in seconds, not checked if
+    /// not set. Only in the configuration file.
+    #[serde(default)]
+    pub config_watch_period: Option<u64>,
+    /// Log filter directives set on top of `--log`. Only in the configuration file.
+    #[serde(default)]
+    pub log_filter: Option<String>,
+    /// The configuration file these options were loaded from, if any. Not part of the schema.
+    #[serde(skip)]
+    pub config_file: Option<PathBuf>,
 }
 
 fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
@@ -243,15 +383,58 @@ pub fn run() -> Result<()> {
                 _ => Err("Benchmarking sub-command unsupported".into()),
             }
         }
+        Some(Subcommand::ExportIdentity(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| {
+                let partials = new_partial(&config, dev_service)?;
+                cmd.run(&config, partials.client)
+            })
+        }
+        Some(Subcommand::ImportIdentity(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| {
+                let partials = new_partial(&config, dev_service)?;
+                cmd.run(&config, partials.client)
+            })
+        }
+        Some(Subcommand::ListDbBackups(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|_config| cmd.run())
+        }
+        Some(Subcommand::RestoreDbBackup(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| {
+                let partials = new_partial(&config, dev_service)?;
+                cmd.run(&config, partials.client)
+            })
+        }
+        Some(Subcommand::ExportForestSnapshot(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|_config| cmd.run())
+        }
+        Some(Subcommand::ImportForestSnapshot(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|_config| cmd.run())
+        }
         None => {
+            if cli.provider_config.print_sample_config {
+                print!("{}", config::SAMPLE_PROVIDER_CONFIG);
+                return Ok(());
+            }
+
+            // Validate the provider configuration before starting anything.
+            let provider_options = cli
+                .provider_config
+                .provider_options()
+                .map_err(|e| sc_cli::Error::Input(e.to_string()))?;
+
             let runner = cli.create_runner(&cli.run.normalize())?;
-            let provider_options = if cli.provider_config.provider {
-                Some(cli.provider_config.provider_options())
-            } else {
-                None
-            };
 
             runner.run_node_until_exit(|config| async move {
+                if provider_options.is_some() {
+                    crate::identity::ensure_identity_not_exported(config.base_path.path())?;
+                }
+
 				let hwbench = (!cli.no_hardware_benchmarks)
 					.then_some(config.database.path().map(|database_path| {
 						let _ = std::fs::create_dir_all(database_path);
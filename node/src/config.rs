@@ -0,0 +1,653 @@
+//! Configuration of a provider node.
+//!
+//! The configuration is either loaded from a TOML file (`--provider-config-file`), or built from the
+//! individual provider flags. Either way, it is validated at startup, so that a misconfigured node
+//! fails right away with every offending field, instead of later on while running.
+//!
+//! When loaded from a file, some settings can be changed while the node runs by reloading it. See
+//! [`ProviderOptions::reloadable_config`].
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use log::LevelFilter;
+use shc_blockchain_service::runtime_api_cache::CachedRuntimeApiQuery;
+use shc_config_watcher::ReloadableConfig;
+use shc_volunteer_policy::VolunteerPolicy;
+
+use crate::{
+    cli::{ProviderType, StorageLayer},
+    command::ProviderOptions,
+};
+
+/// Default extrinsic retry timeout, in seconds.
+pub const DEFAULT_EXTRINSIC_RETRY_TIMEOUT: u64 = 60;
+/// Default time a task can hold the Forest root write lock before a warning is logged, in seconds.
+pub const DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD: u64 = 120;
+/// Default time without new finalised blocks before the node enters safe mode, in seconds.
+pub const DEFAULT_PARTITION_TIMEOUT: u64 = 120;
+/// Default time the files of a bucket moved away from an MSP are kept for, in seconds.
+pub const DEFAULT_BUCKET_GC_RETENTION_PERIOD: u64 = 86_400;
+/// Default maximum amount of incremental database backups in a row before taking a full one.
+pub const DEFAULT_DB_BACKUP_MAX_INCREMENTAL_CHAIN: u32 = 24;
+/// Default amount of finalised blocks to backfill the Provider earnings from on first run.
+pub const DEFAULT_PROVIDER_EARNINGS_BACKFILL_DEPTH: u32 = 432_000;
+/// Default service name reported to the OpenTelemetry collector.
+pub const DEFAULT_OTLP_SERVICE_NAME: &str = "storage-hub-node";
+/// Default interval between exports of the OpenTelemetry metrics, in seconds.
+pub const DEFAULT_OTLP_METRICS_EXPORT_INTERVAL: u64 = 30;
+/// Default smallest size of the chunks in which files are sent or received, in bytes.
+pub const DEFAULT_MIN_TRANSFER_CHUNK_SIZE: u64 = 1024;
+/// Default biggest size of the chunks in which files are sent or received, in bytes.
+pub const DEFAULT_MAX_TRANSFER_CHUNK_SIZE: u64 = 1024 * 1024;
+/// Default maximum size of the Forest changes batched in memory before being written, in bytes.
+pub const DEFAULT_FOREST_WRITE_CACHE_SIZE: usize =
+    shc_forest_manager::rocksdb::DEFAULT_FOREST_WRITE_CACHE_SIZE;
+/// Default amount of score below zero at which a peer is banned from requesting files.
+pub const DEFAULT_PEER_BAN_THRESHOLD: u64 =
+    shc_file_transfer_service::peer_score::DEFAULT_BAN_THRESHOLD;
+/// Default time a peer stays banned from requesting files, in seconds.
+pub const DEFAULT_PEER_BAN_DURATION: u64 =
+    shc_file_transfer_service::peer_score::DEFAULT_BAN_DURATION;
+/// Default time the responses of runtime API queries made by tasks are cached for, in milliseconds.
+pub const DEFAULT_RUNTIME_API_CACHE_TTL: u64 = 6000;
+
+/// Sample provider configuration file, printed by `--print-sample-config`.
+pub const SAMPLE_PROVIDER_CONFIG: &str = r#"# StorageHub provider configuration.
+#
+# Start the node with `--provider-config-file <PATH>` to use it, instead of the individual provider
+# flags. Every setting has the same name as its flag (i.e. `max-storage-capacity` is the
+# `--max-storage-capacity` flag). Settings with a default value can be omitted.
+
+# Type of StorageHub provider: `msp`, `bsp` or `user`.
+provider-type = "bsp"
+
+# Maximum storage capacity of the provider (bytes). Required for MSPs and BSPs.
+max-storage-capacity = 10737418240
+
+# Jump capacity (bytes). Required for MSPs and BSPs. Cannot be greater than `max-storage-capacity`.
+jump-capacity = 1073741824
+
+# Storage layer: `memory` or `rocks-db`.
+storage-layer = "rocks-db"
+
+# Storage location in the file system. Required with the `rocks-db` storage layer.
+storage-path = "/var/lib/storage-hub/storage"
+
+# Extrinsic retry timeout (seconds).
+extrinsic-retry-timeout = 60
+
+# Time a task can hold the Forest root write lock before a warning is logged (seconds).
+forest-root-write-lock-warn-threshold = 120
+
+# Time without new finalised blocks after which the node considers itself partitioned from the
+# network and enters safe mode, pausing proof submissions, storage confirmations and other Forest
+# root writes until a new block is finalised (seconds). Set it to 0 to never enter safe mode.
+partition-timeout = 120
+
+# MSP charging fees period (blocks). Required for MSPs.
+# Setting it to 600 with a block every 6 seconds will charge users every hour.
+# msp-charging-period = 600
+
+# Time the files and forest of a bucket moved away from an MSP are kept for after the move is
+# finalised, before being deleted (seconds).
+bucket-gc-retention-period = 86400
+
+# Directory where backups of the File Storage and Forest Storage databases are stored.
+# Only supported with the `rocks-db` storage layer.
+# db-backup-path = "/var/lib/storage-hub/backups"
+
+# Maximum amount of incremental database backups in a row before taking a full one.
+db-backup-max-incremental-chain = 24
+
+# Aggregate the earnings of this provider per day from finalised blocks, without the indexer.
+provider-earnings = false
+
+# Amount of finalised blocks to backfill the provider earnings from on first run.
+provider-earnings-backfill-depth = 432000
+
+# gRPC endpoint of an OpenTelemetry (OTLP) collector to export traces and metrics to.
+# OpenTelemetry export is disabled if not set.
+# otlp-endpoint = "http://localhost:4317"
+
+# Service name reported to the OpenTelemetry collector.
+otlp-service-name = "storage-hub-node"
+
+# Interval between exports of the OpenTelemetry metrics (seconds).
+otlp-metrics-export-interval = 30
+
+# Smallest size of the chunks in which files are sent or received (bytes).
+min-transfer-chunk-size = 1024
+
+# Biggest size of the chunks in which files are sent or received (bytes).
+# Cannot be smaller than `min-transfer-chunk-size`.
+max-transfer-chunk-size = 1048576
+
+# Maximum bandwidth used to serve files to all peers together (bytes per second).
+# Not limited if not set.
+# max-upload-bandwidth = 10485760
+
+# Maximum bandwidth used to serve files to each peer (bytes per second).
+# Not limited if not set.
+# max-peer-upload-bandwidth = 1048576
+
+# Amount of score below zero at which a peer is banned from requesting files. Peers gain score
+# with successful transfers, and lose it with failed, timed out and corrupt ones.
+peer-ban-threshold = 100
+
+# Time a peer stays banned from requesting files (seconds).
+peer-ban-duration = 3600
+
+# Maximum amount of requests a peer can send per minute, beyond which they count as failed.
+# Not limited if not set.
+# max-peer-requests-per-minute = 600
+
+# Maximum amount of chunks of the stored files checked for corruption per second, in the
+# background. The files are not checked if not set.
+# scrub-chunks-per-second = 1024
+
+# Period between the checks of the space of the disk the files are stored in (seconds). A warning
+# is logged when the on-chain capacity of a BSP exceeds what the disk can hold. Only supported with
+# the `rocks-db` storage layer. The disk is not checked if not set.
+# disk-watchdog-period = 600
+
+# Lower the on-chain capacity of a BSP automatically when it exceeds what the disk can hold.
+# Requires `disk-watchdog-period`.
+auto-reconcile-capacity = false
+
+# Maximum amount of threads used to generate a single Forest proof.
+# One thread per available CPU is used if not set.
+# forest-proof-threads = 4
+
+# Maximum size of the Forest changes batched in memory while a task mutates the Forest, before they
+# are written to the database (bytes). Set it to 0 to write every mutation right away.
+forest-write-cache-size = 67108864
+
+# Time the responses of runtime API queries made by tasks are cached for (milliseconds). They are
+# dropped on every new best or finalised block anyway. Set it to 0 to disable the cache.
+runtime-api-cache-ttl = 6000
+
+# Time the responses of specific runtime API queries are cached for (milliseconds), overriding
+# `runtime-api-cache-ttl`. The queries are `current-tick`, `storage-provider-id`,
+# `provider-multiaddresses`, `storage-provider-capacity`, `available-storage-capacity`,
+# `earliest-change-capacity-block`, `msp-id-of-bucket-id`, `challenge-period`,
+# `worst-case-scenario-slashable-amount` and `slash-amount-per-max-file-size`.
+# runtime-api-cache-query-ttls = { current-tick = 1000, provider-multiaddresses = 0 }
+
+# Fleet coordination, for operators running several BSPs. A BSP skips volunteering for a file if
+# `max-fleet-volunteers-per-file` BSPs of the fleet already volunteered for it, or are going to
+# volunteer for it in the same tick with precedence over this one. Both are required to enable it.
+# This BSP can be included in `fleet-bsp-ids`.
+# fleet-bsp-ids = [
+#   "0x0000000000000000000000000000000000000000000000000000000000000001",
+#   "0x0000000000000000000000000000000000000000000000000000000000000002",
+# ]
+# max-fleet-volunteers-per-file = 1
+
+# Volunteering policy of a BSP. Storage requests not meeting any of these rules are not volunteered
+# for. The policy can be replaced while the node runs with the `storagehubclient_setVolunteerPolicy`
+# RPC method, until it restarts or this file is reloaded. `volunteer-max-owner-debt` is the debt of the owner of the file with
+# this BSP, and `volunteer-min-capacity-headroom` is the capacity left unused after storing the file,
+# out of `max-storage-capacity`. Files of any bucket are volunteered for if
+# `volunteer-bucket-allowlist` is empty.
+# volunteer-max-file-size = 1073741824
+# volunteer-bucket-allowlist = ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+# volunteer-bucket-denylist = ["0x0000000000000000000000000000000000000000000000000000000000000002"]
+# volunteer-owner-denylist = ["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"]
+# volunteer-max-owner-debt = 1000000000000
+# volunteer-min-capacity-headroom = 1073741824
+# volunteer-min-price-per-giga-unit-per-tick = 1
+
+# Address to serve the files stored by an MSP over HTTP on, at `/<bucket_id>/<location>`. The read
+# access of the requester is checked on-chain before serving a file. Only supported for MSPs. The
+# HTTP Gateway is disabled if not set. It is served over TLS if both the certificate (chain) and its
+# private key are set, in PEM format.
+# http-gateway-addr = "0.0.0.0:8080"
+# http-gateway-tls-cert = "/etc/storage-hub/gateway.crt"
+# http-gateway-tls-key = "/etc/storage-hub/gateway.key"
+
+# Replay mode, for debugging. Instead of following the chain, replay the events emitted for these
+# finalised blocks (inclusive) once synced, without submitting extrinsics, logging the blocks whose
+# events diverge from the ones recorded when they were finalised. Both are required to enable it.
+# replay-from-block = 1000
+# replay-to-block = 1100
+
+# Period between the checks of this file for changes (seconds). When it changes, the upload
+# bandwidth caps, the volunteering policy and the log filter are reloaded while the node runs, as
+# long as the whole file is still valid. The other settings only take effect when the node
+# restarts. The file can also be reloaded with the `storagehubclient_reloadConfig` RPC method. The
+# file is not checked if not set.
+# config-watch-period = 10
+
+# Log filter directives set on top of the ones of the `--log` flag, as `target=level` pairs
+# separated by commas. Requires starting the node with `--enable-log-reloading`.
+# log-filter = "bsp-submit-proof-task=debug,file-transfer-service=trace"
+"#;
+
+/// Error loading or validating the configuration of a provider node.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read the provider configuration file {}: {1}", .0.display())]
+    Read(PathBuf, #[source] std::io::Error),
+    #[error("Failed to parse the provider configuration file {}: {1}", .0.display())]
+    Parse(PathBuf, #[source] toml::de::Error),
+    #[error("Invalid provider configuration:{}", .0.iter().map(|e| format!("\n  - {e}")).collect::<String>())]
+    Invalid(Vec<FieldError>),
+}
+
+/// A field of the provider configuration with an invalid value.
+///
+/// Fields are named like in the configuration file, which are also the names of their flags.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    #[error("`{field}` is required {required_by}")]
+    Missing {
+        field: &'static str,
+        required_by: &'static str,
+    },
+    #[error("`{field}` must be greater than zero")]
+    Zero { field: &'static str },
+    #[error("`{field}` ({value}) cannot be greater than `{bound_field}` ({bound})")]
+    AboveBound {
+        field: &'static str,
+        value: u64,
+        bound_field: &'static str,
+        bound: u64,
+    },
+    #[error("`{field}` is only supported {supported_with}")]
+    Unsupported {
+        field: &'static str,
+        supported_with: &'static str,
+    },
+    #[error("`{field}` has an unknown key `{key}`")]
+    UnknownKey { field: &'static str, key: String },
+    #[error("`{field}` is invalid: {reason}")]
+    Invalid { field: &'static str, reason: String },
+}
+
+impl ProviderOptions {
+    /// Loads the provider configuration from the TOML file at `path`, and validates it.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| ConfigError::Read(path.to_path_buf(), e))?;
+        let mut options: Self =
+            toml::from_str(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+        options.config_file = Some(path.to_path_buf());
+        options.validate()?;
+
+        Ok(options)
+    }
+
+    /// The volunteering policy of a BSP set by this configuration.
+    pub fn volunteer_policy(&self) -> VolunteerPolicy {
+        VolunteerPolicy {
+            max_file_size: self.volunteer_max_file_size,
+            bucket_allowlist: (!self.volunteer_bucket_allowlist.is_empty())
+                .then(|| self.volunteer_bucket_allowlist.iter().copied().collect()),
+            bucket_denylist: self.volunteer_bucket_denylist.iter().copied().collect(),
+            owner_denylist: self.volunteer_owner_denylist.iter().cloned().collect(),
+            max_owner_debt: self.volunteer_max_owner_debt,
+            min_capacity_headroom: self.volunteer_min_capacity_headroom,
+            min_price_per_giga_unit_per_tick: self.volunteer_min_price_per_giga_unit_per_tick,
+        }
+    }
+
+    /// The settings of this configuration that are applied while the node runs when the
+    /// configuration file is reloaded.
+    pub fn reloadable_config(&self) -> ReloadableConfig {
+        ReloadableConfig {
+            max_upload_bandwidth: self.max_upload_bandwidth,
+            max_peer_upload_bandwidth: self.max_peer_upload_bandwidth,
+            volunteer_policy: (self.provider_type == ProviderType::Bsp)
+                .then(|| self.volunteer_policy()),
+            log_filter: self.log_filter.clone(),
+        }
+    }
+
+    /// Checks that the provider configuration is consistent, returning every invalid field.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+        let is_storage_provider =
+            matches!(self.provider_type, ProviderType::Msp | ProviderType::Bsp);
+
+        if is_storage_provider {
+            match self.max_storage_capacity {
+                None => errors.push(FieldError::Missing {
+                    field: "max-storage-capacity",
+                    required_by: "for MSPs and BSPs",
+                }),
+                Some(0) => errors.push(FieldError::Zero {
+                    field: "max-storage-capacity",
+                }),
+                Some(_) => {}
+            }
+
+            match (self.jump_capacity, self.max_storage_capacity) {
+                (None, _) => errors.push(FieldError::Missing {
+                    field: "jump-capacity",
+                    required_by: "for MSPs and BSPs",
+                }),
+                (Some(jump_capacity), Some(max_storage_capacity))
+                    if jump_capacity > max_storage_capacity =>
+                {
+                    errors.push(FieldError::AboveBound {
+                        field: "jump-capacity",
+                        value: jump_capacity,
+                        bound_field: "max-storage-capacity",
+                        bound: max_storage_capacity,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        if self.provider_type == ProviderType::Msp {
+            match self.msp_charging_period {
+                None => errors.push(FieldError::Missing {
+                    field: "msp-charging-period",
+                    required_by: "for MSPs",
+                }),
+                Some(0) => errors.push(FieldError::Zero {
+                    field: "msp-charging-period",
+                }),
+                Some(_) => {}
+            }
+        } else if self.http_gateway_addr.is_some() {
+            errors.push(FieldError::Unsupported {
+                field: "http-gateway-addr",
+                supported_with: "for MSPs",
+            });
+        }
+
+        match (
+            &self.http_gateway_tls_cert,
+            &self.http_gateway_tls_key,
+            self.http_gateway_addr,
+        ) {
+            (Some(_), None, _) => errors.push(FieldError::Missing {
+                field: "http-gateway-tls-key",
+                required_by: "with `http-gateway-tls-cert`",
+            }),
+            (None, Some(_), _) => errors.push(FieldError::Missing {
+                field: "http-gateway-tls-cert",
+                required_by: "with `http-gateway-tls-key`",
+            }),
+            (Some(_), Some(_), None) => errors.push(FieldError::Missing {
+                field: "http-gateway-addr",
+                required_by: "with `http-gateway-tls-cert`",
+            }),
+            _ => {}
+        }
+
+        match self.storage_layer {
+            StorageLayer::RocksDB if self.storage_path.is_none() => {
+                errors.push(FieldError::Missing {
+                    field: "storage-path",
+                    required_by: "with the `rocks-db` storage layer",
+                })
+            }
+            StorageLayer::Memory if self.db_backup_path.is_some() => {
+                errors.push(FieldError::Unsupported {
+                    field: "db-backup-path",
+                    supported_with: "with the `rocks-db` storage layer",
+                })
+            }
+            _ => {}
+        }
+
+        if self.extrinsic_retry_timeout == 0 {
+            errors.push(FieldError::Zero {
+                field: "extrinsic-retry-timeout",
+            });
+        }
+        if self.otlp_metrics_export_interval == 0 {
+            errors.push(FieldError::Zero {
+                field: "otlp-metrics-export-interval",
+            });
+        }
+        if self.forest_proof_threads == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "forest-proof-threads",
+            });
+        }
+        if self.max_upload_bandwidth == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "max-upload-bandwidth",
+            });
+        }
+        if self.max_peer_upload_bandwidth == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "max-peer-upload-bandwidth",
+            });
+        }
+        if self.peer_ban_threshold == 0 {
+            errors.push(FieldError::Zero {
+                field: "peer-ban-threshold",
+            });
+        }
+        if self.peer_ban_duration == 0 {
+            errors.push(FieldError::Zero {
+                field: "peer-ban-duration",
+            });
+        }
+        if self.max_peer_requests_per_minute == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "max-peer-requests-per-minute",
+            });
+        }
+        if self.disk_watchdog_period == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "disk-watchdog-period",
+            });
+        }
+        if self.scrub_chunks_per_second == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "scrub-chunks-per-second",
+            });
+        }
+        if self.config_watch_period == Some(0) {
+            errors.push(FieldError::Zero {
+                field: "config-watch-period",
+            });
+        }
+        if let Some(Err(reason)) = self.log_filter.as_deref().map(validate_log_filter) {
+            errors.push(FieldError::Invalid {
+                field: "log-filter",
+                reason,
+            });
+        }
+        if self.provider_type == ProviderType::Bsp {
+            match (
+                self.fleet_bsp_ids.is_empty(),
+                self.max_fleet_volunteers_per_file,
+            ) {
+                (false, None) => errors.push(FieldError::Missing {
+                    field: "max-fleet-volunteers-per-file",
+                    required_by: "with `fleet-bsp-ids`",
+                }),
+                (true, Some(_)) => errors.push(FieldError::Missing {
+                    field: "fleet-bsp-ids",
+                    required_by: "with `max-fleet-volunteers-per-file`",
+                }),
+                (false, Some(0)) => errors.push(FieldError::Zero {
+                    field: "max-fleet-volunteers-per-file",
+                }),
+                _ => {}
+            }
+        } else {
+            if !self.fleet_bsp_ids.is_empty() {
+                errors.push(FieldError::Unsupported {
+                    field: "fleet-bsp-ids",
+                    supported_with: "for BSPs",
+                });
+            }
+            if self.max_fleet_volunteers_per_file.is_some() {
+                errors.push(FieldError::Unsupported {
+                    field: "max-fleet-volunteers-per-file",
+                    supported_with: "for BSPs",
+                });
+            }
+            for (field, is_set) in [
+                (
+                    "volunteer-max-file-size",
+                    self.volunteer_max_file_size.is_some(),
+                ),
+                (
+                    "volunteer-bucket-allowlist",
+                    !self.volunteer_bucket_allowlist.is_empty(),
+                ),
+                (
+                    "volunteer-bucket-denylist",
+                    !self.volunteer_bucket_denylist.is_empty(),
+                ),
+                (
+                    "volunteer-owner-denylist",
+                    !self.volunteer_owner_denylist.is_empty(),
+                ),
+                (
+                    "volunteer-max-owner-debt",
+                    self.volunteer_max_owner_debt.is_some(),
+                ),
+                (
+                    "volunteer-min-capacity-headroom",
+                    self.volunteer_min_capacity_headroom.is_some(),
+                ),
+                (
+                    "volunteer-min-price-per-giga-unit-per-tick",
+                    self.volunteer_min_price_per_giga_unit_per_tick.is_some(),
+                ),
+            ] {
+                if is_set {
+                    errors.push(FieldError::Unsupported {
+                        field,
+                        supported_with: "for BSPs",
+                    });
+                }
+            }
+        }
+
+        for query in self.runtime_api_cache_query_ttls.keys() {
+            if query.parse::<CachedRuntimeApiQuery>().is_err() {
+                errors.push(FieldError::UnknownKey {
+                    field: "runtime-api-cache-query-ttls",
+                    key: query.clone(),
+                });
+            }
+        }
+
+        if self.min_transfer_chunk_size > self.max_transfer_chunk_size {
+            errors.push(FieldError::AboveBound {
+                field: "min-transfer-chunk-size",
+                value: self.min_transfer_chunk_size,
+                bound_field: "max-transfer-chunk-size",
+                bound: self.max_transfer_chunk_size,
+            });
+        }
+
+        match (self.replay_from_block, self.replay_to_block) {
+            (Some(_), None) => errors.push(FieldError::Missing {
+                field: "replay-to-block",
+                required_by: "with `replay-from-block`",
+            }),
+            (None, Some(_)) => errors.push(FieldError::Missing {
+                field: "replay-from-block",
+                required_by: "with `replay-to-block`",
+            }),
+            (Some(from), Some(to)) if from > to => errors.push(FieldError::AboveBound {
+                field: "replay-from-block",
+                value: from.into(),
+                bound_field: "replay-to-block",
+                bound: to.into(),
+            }),
+            _ => {}
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+}
+
+/// Checks that `log_filter` is a list of `target=level` (or just `level`) directives separated by
+/// commas.
+fn validate_log_filter(log_filter: &str) -> Result<(), String> {
+    for directive in log_filter.split(',').map(str::trim) {
+        let (target, level) = match directive.split_once('=') {
+            Some((target, level)) => (Some(target.trim()), level.trim()),
+            None => (None, directive),
+        };
+
+        if target.is_some_and(|target| target.is_empty() || target.contains(char::is_whitespace)) {
+            return Err(format!("invalid target in directive {:?}", directive));
+        }
+        if LevelFilter::from_str(level).is_err() {
+            return Err(format!("invalid level in directive {:?}", directive));
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn default_storage_layer() -> StorageLayer {
+    StorageLayer::Memory
+}
+
+pub(crate) fn default_extrinsic_retry_timeout() -> u64 {
+    DEFAULT_EXTRINSIC_RETRY_TIMEOUT
+}
+
+pub(crate) fn default_forest_root_write_lock_warn_threshold() -> u64 {
+    DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD
+}
+
+pub(crate) fn default_partition_timeout() -> u64 {
+    DEFAULT_PARTITION_TIMEOUT
+}
+
+pub(crate) fn default_bucket_gc_retention_period() -> u64 {
+    DEFAULT_BUCKET_GC_RETENTION_PERIOD
+}
+
+pub(crate) fn default_db_backup_max_incremental_chain() -> u32 {
+    DEFAULT_DB_BACKUP_MAX_INCREMENTAL_CHAIN
+}
+
+pub(crate) fn default_provider_earnings_backfill_depth() -> u32 {
+    DEFAULT_PROVIDER_EARNINGS_BACKFILL_DEPTH
+}
+
+pub(crate) fn default_otlp_service_name() -> String {
+    DEFAULT_OTLP_SERVICE_NAME.to_string()
+}
+
+pub(crate) fn default_otlp_metrics_export_interval() -> u64 {
+    DEFAULT_OTLP_METRICS_EXPORT_INTERVAL
+}
+
+pub(crate) fn default_min_transfer_chunk_size() -> u64 {
+    DEFAULT_MIN_TRANSFER_CHUNK_SIZE
+}
+
+pub(crate) fn default_max_transfer_chunk_size() -> u64 {
+    DEFAULT_MAX_TRANSFER_CHUNK_SIZE
+}
+
+pub(crate) fn default_peer_ban_threshold() -> u64 {
+    DEFAULT_PEER_BAN_THRESHOLD
+}
+
+pub(crate) fn default_peer_ban_duration() -> u64 {
+    DEFAULT_PEER_BAN_DURATION
+}
+
+pub(crate) fn default_forest_write_cache_size() -> usize {
+    DEFAULT_FOREST_WRITE_CACHE_SIZE
+}
+
+pub(crate) fn default_runtime_api_cache_ttl() -> u64 {
+    DEFAULT_RUNTIME_API_CACHE_TTL
+}
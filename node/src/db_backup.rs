@@ -0,0 +1,273 @@
+//! Commands to list and restore the backups of a provider node's databases.
+//!
+//! Backups are taken while the node is running, through the `storagehubclient_createDbBackup` RPC
+//! method (see [`shc_db_backup`]). Restoring one requires the node to be stopped, and checks that
+//! the restored forests match the roots on-chain at the block the backup was taken at.
+
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use clap::ArgGroup;
+use codec::Decode;
+use log::{info, warn};
+use pallet_storage_providers::types::{Bucket, StorageProviderId};
+use pallet_storage_providers_runtime_api::StorageProvidersApi;
+use sc_cli::{CliConfiguration, KeystoreParams, SharedParams};
+use sc_client_api::{HeaderBackend, StorageProvider};
+use sc_service::Configuration;
+use shc_common::{consts::CURRENT_FOREST_KEY, types::ParachainClient};
+use shc_db_backup::{
+    rocksdb::restore_to_storage_path, BackupEntry, BackupError, BackupStore, DatabaseKind,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{storage::StorageKey, H256};
+use storage_hub_runtime::Runtime;
+
+use crate::identity::{keystore_path, query_provider_id, read_bcsv_keystore_entries};
+
+const LOG_TARGET: &str = "db-backup";
+
+/// List the backups of the File Storage and Forest Storage databases of a provider node.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ListDbBackupsCmd {
+    /// Directory where the database backups are stored.
+    #[arg(long, value_name = "PATH")]
+    pub db_backup_path: PathBuf,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+}
+
+impl ListDbBackupsCmd {
+    /// Run the listing.
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let backups = BackupStore::open(&self.db_backup_path)
+            .and_then(|store| store.list())
+            .map_err(to_cli_error)?;
+
+        if backups.is_empty() {
+            println!("No database backups in {:?}", self.db_backup_path);
+        }
+        for backup in backups {
+            println!(
+                "#{} {} at block #{} ({:?}), created at {}: {}",
+                backup.id,
+                match backup.parent {
+                    Some(parent) => format!("(incremental to #{})", parent),
+                    None => "(full)".to_string(),
+                },
+                backup.at.block_number,
+                backup.at.block_hash,
+                backup.created_at,
+                backup
+                    .databases
+                    .iter()
+                    .map(|database| database.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ListDbBackupsCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+}
+
+/// Restore the File Storage and Forest Storage databases of a provider node from a backup.
+///
+/// The node must be stopped, and the databases must not exist at the storage path yet.
+#[derive(Debug, Clone, clap::Parser)]
+#[clap(group(ArgGroup::new("backup").required(true).args(&["backup_id", "at_block"])))]
+pub struct RestoreDbBackupCmd {
+    /// Directory where the database backups are stored.
+    #[arg(long, value_name = "PATH")]
+    pub db_backup_path: PathBuf,
+
+    /// Storage location in the file system to restore the databases to.
+    #[arg(long)]
+    pub storage_path: String,
+
+    /// ID of the backup to restore.
+    #[arg(long)]
+    pub backup_id: Option<u64>,
+
+    /// Restore the latest backup taken at or before this block.
+    #[arg(long)]
+    pub at_block: Option<u32>,
+
+    /// Skip checking the restored forests against the roots on-chain at the block of the backup.
+    ///
+    /// The check reads the state at that block from this node's local chain database, so that
+    /// state must not have been pruned.
+    #[arg(long)]
+    pub skip_onchain_check: bool,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub keystore_params: KeystoreParams,
+}
+
+impl RestoreDbBackupCmd {
+    /// Run the restore.
+    pub fn run(&self, config: &Configuration, client: Arc<ParachainClient>) -> sc_cli::Result<()> {
+        let store = BackupStore::open(&self.db_backup_path).map_err(to_cli_error)?;
+        let backup = match (self.backup_id, self.at_block) {
+            (Some(backup_id), _) => store.backup(backup_id).map_err(to_cli_error)?,
+            (None, Some(block_number)) => store
+                .backup_at_block(block_number)
+                .map_err(to_cli_error)?
+                .ok_or_else(|| format!("No backup taken at or before block #{}", block_number))?,
+            (None, None) => return Err("Either a backup ID or a block is required".into()),
+        };
+
+        // Get the roots to check before restoring anything, so that nothing is written if they
+        // cannot be checked.
+        let onchain_roots = if self.skip_onchain_check {
+            warn!(target: LOG_TARGET, "Skipping the check of the restored forests against the on-chain roots");
+            BTreeMap::new()
+        } else {
+            onchain_forest_roots(config, &client, &backup)?
+        };
+
+        let restored =
+            restore_to_storage_path(&store, backup.id, &self.storage_path).map_err(to_cli_error)?;
+
+        let mismatches = restored
+            .iter()
+            .filter_map(|database| {
+                let onchain_root = onchain_roots.get(&database.name)?;
+                (database.forest_root != Some(*onchain_root)).then(|| {
+                    format!(
+                        "{} (restored {:?}, on-chain {:?})",
+                        database.name, database.forest_root, onchain_root
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        if !mismatches.is_empty() {
+            return Err(format!(
+                "The restored forests do not match the roots on-chain at block #{}: {}",
+                backup.at.block_number,
+                mismatches.join(", ")
+            )
+            .into());
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "Restored backup #{} (block #{}) to {}: {} databases",
+            backup.id,
+            backup.at.block_number,
+            self.storage_path,
+            restored.len()
+        );
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for RestoreDbBackupCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+/// The on-chain root of each forest in `backup`, at the block the backup was taken at.
+///
+/// The BSP forest is checked against the root of the BSP linked to this node's keys, and bucket
+/// forests against the root of their bucket. Forests whose Provider or bucket no longer existed
+/// at that block are not checked.
+fn onchain_forest_roots(
+    config: &Configuration,
+    client: &Arc<ParachainClient>,
+    backup: &BackupEntry,
+) -> sc_cli::Result<BTreeMap<String, H256>> {
+    let at_hash = backup.at.block_hash;
+    if client
+        .header(at_hash)
+        .map_err(|e| format!("Failed to read the header of the backup's block: {:?}", e))?
+        .is_none()
+    {
+        return Err(format!(
+            "Block {:?} of the backup is not in this node's chain. Sync the node first, or use \
+            `--skip-onchain-check`.",
+            at_hash
+        )
+        .into());
+    }
+
+    let mut roots = BTreeMap::new();
+    for database in &backup.databases {
+        let forest_key = match &database.kind {
+            DatabaseKind::Forest { forest_key } => forest_key,
+            DatabaseKind::FileStorage => continue,
+        };
+
+        let maybe_root = if forest_key.as_slice() == CURRENT_FOREST_KEY {
+            let keys = read_bcsv_keystore_entries(keystore_path(config)?)?;
+            match query_provider_id(client, at_hash, &keys)? {
+                Some(StorageProviderId::BackupStorageProvider(bsp_id)) => client
+                    .runtime_api()
+                    .get_bsp_info(at_hash, &bsp_id)
+                    .map_err(|e| {
+                        format!("Runtime API error while getting the BSP's info: {:?}", e)
+                    })?
+                    .ok()
+                    .map(|bsp_info| bsp_info.root),
+                _ => None,
+            }
+        } else if forest_key.len() == H256::len_bytes() {
+            let storage_key = StorageKey(
+                pallet_storage_providers::Buckets::<Runtime>::hashed_key_for(H256::from_slice(
+                    forest_key,
+                )),
+            );
+            client
+                .storage(at_hash, &storage_key)
+                .map_err(|e| {
+                    format!(
+                        "Failed to read the on-chain state at the backup's block, which may have been \
+                        pruned. Use `--skip-onchain-check` to restore without checking it: {:?}",
+                        e
+                    )
+                })?
+                .map(|data| Bucket::<Runtime>::decode(&mut data.0.as_slice()))
+                .transpose()
+                .map_err(|e| format!("Failed to decode bucket: {:?}", e))?
+                .map(|bucket| bucket.root)
+        } else {
+            None
+        };
+
+        match maybe_root {
+            Some(root) => {
+                roots.insert(database.name.clone(), root);
+            }
+            None => warn!(
+                target: LOG_TARGET,
+                "No on-chain root for forest {} at block #{}, it will not be checked",
+                database.name,
+                backup.at.block_number
+            ),
+        }
+    }
+
+    Ok(roots)
+}
+
+fn to_cli_error(e: BackupError) -> sc_cli::Error {
+    format!("Database backup error: {}", e).into()
+}
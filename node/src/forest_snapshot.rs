@@ -0,0 +1,162 @@
+//! Commands to export a provider node's forests to snapshot files and import them back, to back up
+//! a forest or migrate it to another machine without copying the raw database files.
+//!
+//! See [`shc_forest_manager::snapshot`] for the snapshot format. Both commands open the Forest
+//! Storage databases directly, so the node must be stopped.
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+use sc_cli::{CliConfiguration, SharedParams};
+use shc_common::{consts::CURRENT_FOREST_KEY, types::StorageProofsMerkleTrieLayout};
+use shc_forest_manager::{
+    rocksdb::RocksDBForestStorage,
+    snapshot::{SnapshotError, SnapshotSummary},
+    traits::ForestStorageHandler,
+};
+use sp_core::H256;
+
+use crate::services::forest_storage::ForestStorageCaching;
+
+const LOG_TARGET: &str = "forest-snapshot";
+
+type RocksDbForestStorageHandler = ForestStorageCaching<
+    Vec<u8>,
+    RocksDBForestStorage<StorageProofsMerkleTrieLayout, kvdb_rocksdb::Database>,
+>;
+
+/// Which forest of a provider node to export or import.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ForestParams {
+    /// Storage location in the file system of the provider node's databases.
+    #[arg(long)]
+    pub storage_path: String,
+
+    /// The bucket whose forest to export or import, for MSPs. The forest of the BSP is used if not
+    /// provided.
+    #[arg(long)]
+    pub bucket_id: Option<H256>,
+
+    /// Path of the snapshot file.
+    #[arg(long, value_name = "PATH")]
+    pub snapshot_path: PathBuf,
+}
+
+impl ForestParams {
+    fn forest_key(&self) -> Vec<u8> {
+        match self.bucket_id {
+            Some(bucket_id) => bucket_id.as_bytes().to_vec(),
+            None => CURRENT_FOREST_KEY.to_vec(),
+        }
+    }
+
+    /// Whether the forest has a database at the storage path, as created by the forest storage
+    /// handler.
+    fn forest_exists(&self) -> bool {
+        Path::new(&format!("{}_{:?}", self.storage_path, self.forest_key())).exists()
+    }
+
+    fn forest_storage_handler(&self) -> RocksDbForestStorageHandler {
+        RocksDbForestStorageHandler::new(self.storage_path.clone())
+    }
+}
+
+/// Export a forest of a provider node to a snapshot file.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportForestSnapshotCmd {
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub forest_params: ForestParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+}
+
+impl ExportForestSnapshotCmd {
+    /// Run the export.
+    pub fn run(&self) -> sc_cli::Result<()> {
+        if !self.forest_params.forest_exists() {
+            return Err(
+                format!("No forest to export at {}", self.forest_params.storage_path).into(),
+            );
+        }
+
+        let mut handler = self.forest_params.forest_storage_handler();
+        let forest_key = self.forest_params.forest_key();
+
+        let summary = futures::executor::block_on(async {
+            // The handler only knows about the forests it opened.
+            handler.get_or_create(&forest_key).await;
+            handler
+                .export_snapshot(&forest_key, &self.forest_params.snapshot_path)
+                .await
+        })
+        .map_err(to_cli_error)?;
+
+        log_summary("Exported", &self.forest_params, summary);
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ExportForestSnapshotCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+}
+
+/// Import a forest of a provider node from a snapshot file.
+///
+/// The root of the snapshot is verified before importing it, and the forest must be empty (i.e.
+/// not exist yet) at the storage path.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ImportForestSnapshotCmd {
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub forest_params: ForestParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+}
+
+impl ImportForestSnapshotCmd {
+    /// Run the import.
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let mut handler = self.forest_params.forest_storage_handler();
+        let forest_key = self.forest_params.forest_key();
+
+        let summary = futures::executor::block_on(
+            handler.import_snapshot(&forest_key, &self.forest_params.snapshot_path),
+        )
+        .map_err(to_cli_error)?;
+
+        log_summary("Imported", &self.forest_params, summary);
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ImportForestSnapshotCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+}
+
+fn log_summary(action: &str, forest_params: &ForestParams, summary: SnapshotSummary) {
+    info!(
+        target: LOG_TARGET,
+        "{} forest {} ({} files, root {:?}) with snapshot {:?}",
+        action,
+        match forest_params.bucket_id {
+            Some(bucket_id) => format!("of bucket {:?}", bucket_id),
+            None => "of the BSP".to_string(),
+        },
+        summary.files,
+        summary.root,
+        forest_params.snapshot_path
+    );
+}
+
+fn to_cli_error(e: SnapshotError) -> sc_cli::Error {
+    format!("Forest snapshot error: {}", e).into()
+}
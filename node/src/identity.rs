@@ -0,0 +1,831 @@
+//! Export and import of a Storage Provider's identity, to migrate a provider node to new hardware.
+//!
+//! The identity bundle holds everything a node needs to act as the same Storage Provider:
+//! - The libp2p node key, so that the peer ID (and hence the multiaddresses registered on-chain)
+//!   stays the same.
+//! - The keystore entries of [`BCSV_KEY_TYPE`], which the Provider uses to sign extrinsics.
+//! - The essential provider configuration (provider type, capacity, storage layer...), from which
+//!   importing prints the flags to start the new node with.
+//! - References to the forest storage, so that the operator knows what to move along with the bundle.
+//!
+//! The bundle is encrypted with a key derived from a password (Argon2id + XChaCha20-Poly1305).
+//!
+//! Safety interlocks, so that two live nodes never use the same identity:
+//! - Exporting opens the node's database, which fails if the node is still running.
+//! - After exporting, an [`EXPORTED_IDENTITY_MARKER`] file is written in the node's base path, and the
+//!   node refuses to start as a provider while it exists.
+//! - Importing refuses to overwrite an existing node key or [`BCSV_KEY_TYPE`] keys, unless forced.
+//! - Importing checks that the keys in the bundle are still linked on-chain to the Provider ID in
+//!   the bundle.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use clap::{ArgGroup, ValueEnum};
+use log::{info, warn};
+use pallet_storage_providers::types::StorageProviderId;
+use pallet_storage_providers_runtime_api::StorageProvidersApi;
+use rand::RngCore;
+use sc_cli::{CliConfiguration, KeystoreParams, SharedParams};
+use sc_client_api::HeaderBackend;
+use sc_network::config::{NodeKeyConfig, Secret};
+use sc_service::{config::KeystoreConfig, Configuration};
+use serde::{Deserialize, Serialize};
+use shc_common::types::{ParachainClient, BCSV_KEY_TYPE};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{sr25519, ByteArray, H256};
+use storage_hub_runtime::{Runtime, StorageDataUnit};
+
+use crate::cli::{ProviderType, StorageLayer};
+
+const LOG_TARGET: &str = "identity";
+
+/// Version of the identity bundle format.
+const IDENTITY_BUNDLE_VERSION: u32 = 1;
+
+/// Name of the file written in the node's base path after its identity is exported.
+pub const EXPORTED_IDENTITY_MARKER: &str = "identity-exported";
+
+/// Length of the salt used to derive the encryption key from the password.
+const SALT_LENGTH: usize = 16;
+
+/// Length of the XChaCha20-Poly1305 nonce.
+const NONCE_LENGTH: usize = 24;
+
+/// Permissions of the files holding secrets (keystore entries, node key and bundle), readable and
+/// writable by their owner only.
+#[cfg(unix)]
+const SECRET_FILE_MODE: u32 = 0o600;
+
+/// Essential provider configuration, carried in the bundle so that the new node is started with
+/// the same parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConfigSnapshot {
+    pub provider_type: ProviderType,
+    pub storage_layer: StorageLayer,
+    pub max_storage_capacity: Option<StorageDataUnit>,
+    pub jump_capacity: Option<StorageDataUnit>,
+    pub extrinsic_retry_timeout: u64,
+    pub msp_charging_period: Option<u32>,
+}
+
+impl ProviderConfigSnapshot {
+    /// The command line flags to start a node with this configuration.
+    fn to_cli_flags(&self, storage_path: Option<&str>) -> String {
+        let mut flags = vec![
+            "--provider".to_string(),
+            format!("--provider-type {}", cli_value(&self.provider_type)),
+            format!("--storage-layer {}", cli_value(&self.storage_layer)),
+            format!("--extrinsic-retry-timeout {}", self.extrinsic_retry_timeout),
+        ];
+        if let Some(path) = storage_path {
+            flags.push(format!("--storage-path {}", path));
+        }
+        if let Some(capacity) = self.max_storage_capacity {
+            flags.push(format!("--max-storage-capacity {}", capacity));
+        }
+        if let Some(jump_capacity) = self.jump_capacity {
+            flags.push(format!("--jump-capacity {}", jump_capacity));
+        }
+        if let Some(period) = self.msp_charging_period {
+            flags.push(format!("--msp-charging-period {}", period));
+        }
+        flags.join(" ")
+    }
+}
+
+/// References to the forest storage of the Provider.
+///
+/// The forest itself is not part of the bundle: it has to be moved (or rebuilt) separately, and
+/// its root must match the one on-chain for the Provider to be able to submit proofs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForestSnapshotReference {
+    /// Path of the RocksDB storage in the old node, if any.
+    pub storage_path: Option<String>,
+    /// The Provider's root on-chain, at [`IdentityBundle::exported_at_block`].
+    pub onchain_root: Option<H256>,
+}
+
+/// A keystore entry, as stored by the local keystore: the file name encodes the key type and the
+/// public key, and the content is the secret (phrase or seed).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreEntry {
+    pub file_name: String,
+    pub content: String,
+}
+
+impl KeystoreEntry {
+    /// The sr25519 public key of this entry, if it is a [`BCSV_KEY_TYPE`] key.
+    fn bcsv_public_key(&self) -> Option<sr25519::Public> {
+        let key_type_hex = array_bytes::bytes2hex("", BCSV_KEY_TYPE.0);
+        let public_hex = self.file_name.strip_prefix(&key_type_hex)?;
+        let public = array_bytes::hex2bytes(public_hex).ok()?;
+        sr25519::Public::from_slice(&public).ok()
+    }
+}
+
+/// The identity of a provider node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityBundle {
+    /// Version of the bundle format.
+    pub version: u32,
+    /// The chain this identity belongs to.
+    pub chain_id: String,
+    /// The genesis hash of the chain this identity belongs to.
+    pub genesis_hash: H256,
+    /// The Provider ID linked on-chain to the keys in the bundle, if registered.
+    pub provider_id: Option<H256>,
+    /// The block at which the bundle was exported.
+    pub exported_at_block: u32,
+    /// Hex-encoded libp2p node key (ed25519 secret).
+    pub node_key: Option<String>,
+    /// The [`BCSV_KEY_TYPE`] keystore entries.
+    pub keys: Vec<KeystoreEntry>,
+    /// Essential provider configuration.
+    pub provider_config: ProviderConfigSnapshot,
+    /// References to the forest storage.
+    pub forest: ForestSnapshotReference,
+}
+
+/// An encrypted [`IdentityBundle`], as written to disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedIdentityBundle {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// The password protecting the identity bundle.
+///
+/// Named apart from the keystore password flags, which are also accepted by the identity commands.
+#[derive(Debug, Clone, clap::Args)]
+#[clap(group(ArgGroup::new("bundle_password").required(true).args(&["bundle_password", "bundle_password_file"])))]
+pub struct BundlePasswordParams {
+    /// Password to encrypt/decrypt the identity bundle.
+    #[arg(long)]
+    pub bundle_password: Option<String>,
+
+    /// File containing the password to encrypt/decrypt the identity bundle.
+    #[arg(long, value_name = "PATH")]
+    pub bundle_password_file: Option<PathBuf>,
+}
+
+impl BundlePasswordParams {
+    fn read_password(&self) -> sc_cli::Result<String> {
+        match (&self.bundle_password, &self.bundle_password_file) {
+            (Some(password), _) => Ok(password.clone()),
+            (None, Some(path)) => Ok(fs::read_to_string(path)?.trim_end().to_string()),
+            (None, None) => Err("A bundle password or bundle password file is required".into()),
+        }
+    }
+}
+
+/// Export the identity of this provider node to an encrypted bundle.
+///
+/// The node must be stopped. Once exported, this node will refuse to start as a provider, to avoid
+/// two live nodes using the same identity.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportIdentityCmd {
+    /// Path of the bundle file to write.
+    #[arg(long, value_name = "PATH")]
+    pub output: PathBuf,
+
+    /// Type of StorageHub provider.
+    #[arg(long, value_enum, value_name = "PROVIDER_TYPE")]
+    pub provider_type: ProviderType,
+
+    /// Storage layer of the provider.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STORAGE_LAYER",
+        default_value = "memory"
+    )]
+    pub storage_layer: StorageLayer,
+
+    /// Storage location in the file system.
+    #[arg(long)]
+    pub storage_path: Option<String>,
+
+    /// Maximum storage capacity of the provider (bytes).
+    #[arg(long)]
+    pub max_storage_capacity: Option<StorageDataUnit>,
+
+    /// Jump capacity (bytes).
+    #[arg(long)]
+    pub jump_capacity: Option<StorageDataUnit>,
+
+    /// Extrinsic retry timeout in seconds.
+    #[arg(long, default_value = "60")]
+    pub extrinsic_retry_timeout: u64,
+
+    /// MSP charging fees period (in blocks).
+    #[arg(long)]
+    pub msp_charging_period: Option<u32>,
+
+    /// Export the identity even if its keys are not linked to a Provider on-chain.
+    #[arg(long)]
+    pub allow_unregistered: bool,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub password_params: BundlePasswordParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub keystore_params: KeystoreParams,
+}
+
+impl ExportIdentityCmd {
+    /// Run the export.
+    pub fn run(&self, config: &Configuration, client: Arc<ParachainClient>) -> sc_cli::Result<()> {
+        let keys = read_bcsv_keystore_entries(keystore_path(config)?)?;
+        if keys.is_empty() {
+            return Err("There are no BCSV keys in this node's keystore".into());
+        }
+
+        let best_hash = client.info().best_hash;
+        let storage_provider_id = query_provider_id(&client, best_hash, &keys)?;
+        if storage_provider_id.is_none() {
+            if !self.allow_unregistered {
+                return Err(
+                    "The BCSV keys in this node's keystore are not linked to any Provider \
+                    on-chain. Use `--allow-unregistered` to export them anyway."
+                        .into(),
+                );
+            }
+            warn!(target: LOG_TARGET, "Exporting an identity not linked to any Provider on-chain");
+        }
+
+        // Only BSPs have a single forest. MSPs have one per bucket, which live in the same storage.
+        let onchain_root = match storage_provider_id {
+            Some(StorageProviderId::BackupStorageProvider(bsp_id)) => client
+                .runtime_api()
+                .get_bsp_info(best_hash, &bsp_id)
+                .map_err(|e| format!("Runtime API error while getting the BSP's info: {:?}", e))?
+                .ok()
+                .map(|bsp_info| bsp_info.root),
+            _ => None,
+        };
+        let provider_id = storage_provider_id.map(|id| *id.inner());
+
+        let node_key = match &config.network.node_key {
+            NodeKeyConfig::Ed25519(Secret::File(path)) if path.exists() => {
+                Some(array_bytes::bytes2hex("", fs::read(path)?))
+            }
+            NodeKeyConfig::Ed25519(Secret::Input(key)) => {
+                Some(array_bytes::bytes2hex("", key.as_ref()))
+            }
+            _ => {
+                warn!(target: LOG_TARGET, "No node key found, the new node will have a different peer ID");
+                None
+            }
+        };
+
+        let bundle = IdentityBundle {
+            version: IDENTITY_BUNDLE_VERSION,
+            chain_id: config.chain_spec.id().to_string(),
+            genesis_hash: client.info().genesis_hash,
+            provider_id,
+            exported_at_block: client.info().best_number,
+            node_key,
+            keys,
+            provider_config: ProviderConfigSnapshot {
+                provider_type: self.provider_type.clone(),
+                storage_layer: self.storage_layer.clone(),
+                max_storage_capacity: self.max_storage_capacity,
+                jump_capacity: self.jump_capacity,
+                extrinsic_retry_timeout: self.extrinsic_retry_timeout,
+                msp_charging_period: self.msp_charging_period,
+            },
+            forest: ForestSnapshotReference {
+                storage_path: self.storage_path.clone(),
+                onchain_root,
+            },
+        };
+
+        let password = self.password_params.read_password()?;
+        write_bundle(&self.output, &bundle, &password)?;
+
+        // Interlock: this node must not run as a provider anymore.
+        fs::write(
+            config.base_path.path().join(EXPORTED_IDENTITY_MARKER),
+            format!("{:?}", self.output),
+        )?;
+
+        info!(
+            target: LOG_TARGET,
+            "🪪 Exported identity of Provider {:?} to {:?}. This node will refuse to start as a provider from now on.",
+            provider_id,
+            self.output
+        );
+        if let Some(storage_path) = &self.storage_path {
+            info!(target: LOG_TARGET, "📦 Remember to move the forest storage at {} to the new node", storage_path);
+        }
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ExportIdentityCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+/// Import the identity of a provider node from an encrypted bundle.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ImportIdentityCmd {
+    /// Path of the bundle file to read.
+    #[arg(long, value_name = "PATH")]
+    pub input: PathBuf,
+
+    /// Skip checking that the keys in the bundle are still linked on-chain to the Provider ID in
+    /// the bundle.
+    ///
+    /// The check uses this node's local chain database, so the node must be synced before importing.
+    #[arg(long)]
+    pub skip_onchain_check: bool,
+
+    /// Overwrite the node key and BCSV keys already present in this node.
+    #[arg(long)]
+    pub force: bool,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub password_params: BundlePasswordParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub keystore_params: KeystoreParams,
+}
+
+impl ImportIdentityCmd {
+    /// Run the import.
+    pub fn run(&self, config: &Configuration, client: Arc<ParachainClient>) -> sc_cli::Result<()> {
+        let password = self.password_params.read_password()?;
+        let bundle = read_bundle(&self.input, &password)?;
+
+        if bundle.genesis_hash != client.info().genesis_hash {
+            return Err(format!(
+                "The identity bundle belongs to chain {} (genesis {:?}), but this node runs {} (genesis {:?})",
+                bundle.chain_id,
+                bundle.genesis_hash,
+                config.chain_spec.id(),
+                client.info().genesis_hash
+            )
+            .into());
+        }
+
+        if !self.skip_onchain_check {
+            let best = client.info();
+            if best.best_number < bundle.exported_at_block {
+                return Err(format!(
+                    "This node is at block #{}, behind the block the bundle was exported at (#{}). \
+                    Sync the node before importing, or use `--skip-onchain-check`.",
+                    best.best_number, bundle.exported_at_block
+                )
+                .into());
+            }
+            let onchain_provider_id =
+                query_provider_id(&client, best.best_hash, &bundle.keys)?.map(|id| *id.inner());
+            if onchain_provider_id != bundle.provider_id {
+                return Err(format!(
+                    "The Provider ID in the bundle ({:?}) does not match the one on-chain ({:?})",
+                    bundle.provider_id, onchain_provider_id
+                )
+                .into());
+            }
+        }
+
+        let node_key_path = match &config.network.node_key {
+            NodeKeyConfig::Ed25519(Secret::File(path)) => Some(path.as_path()),
+            _ => None,
+        };
+        install_identity(&bundle, &keystore_path(config)?, node_key_path, self.force)?;
+
+        // Importing back into the node the identity was exported from re-enables it.
+        let marker = config.base_path.path().join(EXPORTED_IDENTITY_MARKER);
+        if marker.exists() {
+            fs::remove_file(marker)?;
+        }
+
+        info!(target: LOG_TARGET, "🪪 Imported identity of Provider {:?}", bundle.provider_id);
+        info!(
+            target: LOG_TARGET,
+            "🚀 Start the node with: {}",
+            bundle
+                .provider_config
+                .to_cli_flags(bundle.forest.storage_path.as_deref())
+        );
+        if let Some(root) = bundle.forest.onchain_root {
+            info!(target: LOG_TARGET, "🌳 The forest storage must be moved to this node, its root on-chain was {:?} at block #{}", root, bundle.exported_at_block);
+        }
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ImportIdentityCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+/// Fails if the identity of the node at `base_path` was exported, so that it does not run as a
+/// provider alongside the node the identity was imported into.
+pub fn ensure_identity_not_exported(base_path: &Path) -> sc_cli::Result<()> {
+    let marker = base_path.join(EXPORTED_IDENTITY_MARKER);
+    if marker.exists() {
+        return Err(format!(
+            "The identity of this node was exported to another node. Running both as the same \
+            provider would get it slashed. Remove {:?} if this node should keep running as the provider.",
+            marker
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn cli_value(value: &impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn keystore_path(config: &Configuration) -> sc_cli::Result<PathBuf> {
+    match &config.keystore {
+        KeystoreConfig::Path { path, .. } => Ok(path.clone()),
+        KeystoreConfig::InMemory => {
+            Err("The keystore is in memory, there is no identity to export or import".into())
+        }
+    }
+}
+
+pub(crate) fn read_bcsv_keystore_entries(
+    path: impl AsRef<Path>,
+) -> sc_cli::Result<Vec<KeystoreEntry>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(path)? {
+        let dir_entry = dir_entry?;
+        let file_name = dir_entry.file_name().to_string_lossy().to_string();
+        let entry = KeystoreEntry {
+            content: fs::read_to_string(dir_entry.path())?,
+            file_name,
+        };
+        if entry.bcsv_public_key().is_some() {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Writes the keys and node key of `bundle` into the keystore at `keystore_path` and the node key
+/// file at `node_key_path`, readable by their owner only.
+///
+/// Fails if there already are BCSV keys in the keystore or a node key, unless `force` is set.
+fn install_identity(
+    bundle: &IdentityBundle,
+    keystore_path: &Path,
+    node_key_path: Option<&Path>,
+    force: bool,
+) -> sc_cli::Result<()> {
+    // Interlock: do not silently replace the identity this node already has.
+    let existing_keys = read_bcsv_keystore_entries(keystore_path)?;
+    let has_node_key = node_key_path.map_or(false, |path| path.exists());
+    if !force && (!existing_keys.is_empty() || has_node_key) {
+        return Err(
+            "This node already has a node key or BCSV keys. Use `--force` to overwrite them."
+                .into(),
+        );
+    }
+
+    fs::create_dir_all(keystore_path)?;
+    for entry in &bundle.keys {
+        write_secret(
+            &keystore_path.join(&entry.file_name),
+            entry.content.as_bytes(),
+        )?;
+    }
+
+    match (&bundle.node_key, node_key_path) {
+        (Some(node_key), Some(path)) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            write_secret(
+                path,
+                &array_bytes::hex2bytes(node_key).map_err(|e| format!("{:?}", e))?,
+            )?;
+        }
+        (Some(_), None) => {
+            warn!(target: LOG_TARGET, "The node key is not read from a file, so it was not imported. Pass it with `--node-key` instead.");
+        }
+        (None, _) => {}
+    }
+
+    Ok(())
+}
+
+/// Writes `contents` to the file at `path`, readable and writable by its owner only.
+fn write_secret(path: &Path, contents: &[u8]) -> sc_cli::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(SECRET_FILE_MODE);
+        // The mode only applies to newly created files, so an overwritten file keeps its own.
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(SECRET_FILE_MODE))?;
+        }
+    }
+
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
+/// The Provider ID linked on-chain to any of the `keys`.
+pub(crate) fn query_provider_id(
+    client: &Arc<ParachainClient>,
+    block_hash: H256,
+    keys: &[KeystoreEntry],
+) -> sc_cli::Result<Option<StorageProviderId<Runtime>>> {
+    let mut provider_ids = Vec::new();
+    for key in keys.iter().filter_map(KeystoreEntry::bcsv_public_key) {
+        let maybe_provider_id = client
+            .runtime_api()
+            .get_storage_provider_id(block_hash, &key.into())
+            .map_err(|e| format!("Runtime API error while getting the Provider ID: {:?}", e))?;
+        if let Some(provider_id) = maybe_provider_id {
+            if !provider_ids.contains(&provider_id) {
+                provider_ids.push(provider_id);
+            }
+        }
+    }
+
+    match provider_ids.as_slice() {
+        [] => Ok(None),
+        [provider_id] => Ok(Some(*provider_id)),
+        _ => {
+            Err("The BCSV keys are linked to more than one Provider, which is not supported".into())
+        }
+    }
+}
+
+/// Encrypts `bundle` with `password` and writes it to the file at `path`.
+fn write_bundle(path: &Path, bundle: &IdentityBundle, password: &str) -> sc_cli::Result<()> {
+    let encrypted = encrypt_bundle(bundle, password)?;
+    write_secret(
+        path,
+        &serde_json::to_vec_pretty(&encrypted).map_err(to_cli_error)?,
+    )
+}
+
+/// Reads the bundle in the file at `path` and decrypts it with `password`.
+fn read_bundle(path: &Path, password: &str) -> sc_cli::Result<IdentityBundle> {
+    let encrypted: EncryptedIdentityBundle =
+        serde_json::from_slice(&fs::read(path)?).map_err(to_cli_error)?;
+    decrypt_bundle(&encrypted, password)
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> sc_cli::Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive the bundle encryption key: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt_bundle(
+    bundle: &IdentityBundle,
+    password: &str,
+) -> sc_cli::Result<EncryptedIdentityBundle> {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(password, &salt)?;
+    let plaintext = serde_json::to_vec(bundle).map_err(to_cli_error)?;
+    let ciphertext = XChaCha20Poly1305::new(&key.into())
+        .encrypt(XNonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| "Failed to encrypt the identity bundle")?;
+
+    Ok(EncryptedIdentityBundle {
+        version: IDENTITY_BUNDLE_VERSION,
+        salt: array_bytes::bytes2hex("", salt),
+        nonce: array_bytes::bytes2hex("", nonce),
+        ciphertext: array_bytes::bytes2hex("", ciphertext),
+    })
+}
+
+fn decrypt_bundle(
+    encrypted: &EncryptedIdentityBundle,
+    password: &str,
+) -> sc_cli::Result<IdentityBundle> {
+    if encrypted.version != IDENTITY_BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported identity bundle version {}, expected {}",
+            encrypted.version, IDENTITY_BUNDLE_VERSION
+        )
+        .into());
+    }
+
+    let decode = |hex: &str| {
+        array_bytes::hex2bytes(hex).map_err(|e| format!("Malformed identity bundle: {:?}", e))
+    };
+    let salt = decode(&encrypted.salt)?;
+    let nonce = decode(&encrypted.nonce)?;
+    let ciphertext = decode(&encrypted.ciphertext)?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Malformed identity bundle: invalid nonce length".into());
+    }
+
+    let key = derive_key(password, &salt)?;
+    let plaintext = XChaCha20Poly1305::new(&key.into())
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Failed to decrypt the identity bundle, is the password correct?")?;
+
+    serde_json::from_slice(&plaintext).map_err(to_cli_error)
+}
+
+fn to_cli_error(e: serde_json::Error) -> sc_cli::Error {
+    format!("Malformed identity bundle: {}", e).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(test_name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("shc-identity-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+
+    fn keystore_file_name(key_type: &[u8; 4], public: &[u8; 32]) -> String {
+        array_bytes::bytes2hex("", key_type) + &array_bytes::bytes2hex("", public)
+    }
+
+    fn test_bundle(keys: Vec<KeystoreEntry>, node_key: Option<String>) -> IdentityBundle {
+        IdentityBundle {
+            version: IDENTITY_BUNDLE_VERSION,
+            chain_id: "dev".to_string(),
+            genesis_hash: H256::repeat_byte(1),
+            provider_id: Some(H256::repeat_byte(2)),
+            exported_at_block: 10,
+            node_key,
+            keys,
+            provider_config: ProviderConfigSnapshot {
+                provider_type: ProviderType::Bsp,
+                storage_layer: StorageLayer::RocksDB,
+                max_storage_capacity: Some(1024),
+                jump_capacity: None,
+                extrinsic_retry_timeout: 60,
+                msp_charging_period: None,
+            },
+            forest: ForestSnapshotReference {
+                storage_path: Some("/storage".to_string()),
+                onchain_root: Some(H256::repeat_byte(3)),
+            },
+        }
+    }
+
+    #[cfg(unix)]
+    fn mode(path: &Path) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn exported_identity_is_imported_into_another_node() {
+        let dir = test_dir("roundtrip");
+        let old_keystore = dir.join("old").join("keystore");
+        fs::create_dir_all(&old_keystore).unwrap();
+        let bcsv_file_name = keystore_file_name(&BCSV_KEY_TYPE.0, &[7; 32]);
+        fs::write(old_keystore.join(&bcsv_file_name), "\"//Alice\"").unwrap();
+        // Only the BCSV keys are part of the identity.
+        fs::write(
+            old_keystore.join(keystore_file_name(b"aura", &[8; 32])),
+            "\"//Bob\"",
+        )
+        .unwrap();
+        let node_key = [9u8; 32];
+
+        // Export.
+        let keys = read_bcsv_keystore_entries(&old_keystore).unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].file_name, bcsv_file_name);
+        let bundle_path = dir.join("identity.json");
+        write_bundle(
+            &bundle_path,
+            &test_bundle(keys.clone(), Some(array_bytes::bytes2hex("", node_key))),
+            "correct horse battery staple",
+        )
+        .unwrap();
+
+        // Import.
+        let bundle = read_bundle(&bundle_path, "correct horse battery staple").unwrap();
+        let new_keystore = dir.join("new").join("keystore");
+        let new_node_key_path = dir.join("new").join("network").join("secret_ed25519");
+        install_identity(&bundle, &new_keystore, Some(&new_node_key_path), false).unwrap();
+
+        assert_eq!(read_bcsv_keystore_entries(&new_keystore).unwrap(), keys);
+        assert_eq!(fs::read(&new_node_key_path).unwrap(), node_key);
+        assert_eq!(bundle.provider_id, Some(H256::repeat_byte(2)));
+        assert_eq!(
+            bundle
+                .provider_config
+                .to_cli_flags(bundle.forest.storage_path.as_deref()),
+            "--provider --provider-type bsp --storage-layer rocks-db --extrinsic-retry-timeout 60 \
+            --storage-path /storage --max-storage-capacity 1024"
+        );
+
+        #[cfg(unix)]
+        {
+            assert_eq!(mode(&bundle_path), SECRET_FILE_MODE);
+            assert_eq!(mode(&new_keystore.join(&bcsv_file_name)), SECRET_FILE_MODE);
+            assert_eq!(mode(&new_node_key_path), SECRET_FILE_MODE);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn bundle_cannot_be_read_with_a_wrong_password() {
+        let dir = test_dir("wrong-password");
+        fs::create_dir_all(&dir).unwrap();
+        let bundle_path = dir.join("identity.json");
+        write_bundle(&bundle_path, &test_bundle(Vec::new(), None), "password").unwrap();
+
+        let error = read_bundle(&bundle_path, "not the password").unwrap_err();
+        assert!(error.to_string().contains("is the password correct?"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn importing_does_not_overwrite_an_existing_identity_unless_forced() {
+        let dir = test_dir("overwrite");
+        let keystore = dir.join("keystore");
+        let node_key_path = dir.join("secret_ed25519");
+        let bcsv_file_name = keystore_file_name(&BCSV_KEY_TYPE.0, &[7; 32]);
+        let bundle = test_bundle(
+            vec![KeystoreEntry {
+                file_name: bcsv_file_name.clone(),
+                content: "\"//Alice\"".to_string(),
+            }],
+            Some(array_bytes::bytes2hex("", [9u8; 32])),
+        );
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&node_key_path, [1u8; 32]).unwrap();
+
+        assert!(install_identity(&bundle, &keystore, Some(&node_key_path), false).is_err());
+        assert_eq!(fs::read(&node_key_path).unwrap(), [1u8; 32]);
+
+        install_identity(&bundle, &keystore, Some(&node_key_path), true).unwrap();
+        assert_eq!(fs::read(&node_key_path).unwrap(), [9u8; 32]);
+        // The existing file is also restricted to its owner when overwritten.
+        #[cfg(unix)]
+        assert_eq!(mode(&node_key_path), SECRET_FILE_MODE);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
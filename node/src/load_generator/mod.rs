@@ -0,0 +1,378 @@
+//! Load generator to benchmark the throughput of provider nodes on a local dev network.
+//!
+//! Only compiled with the `load-generator` feature. A user node reads a scenario file (JSON) from
+//! the path given in the [`LOAD_SCENARIO_ENV_VAR`] environment variable, and for the duration of
+//! the scenario:
+//! - Issues storage requests for files with random contents at the configured rate. The chunks of
+//!   those files are then uploaded to the MSP and the BSPs volunteering for them by the
+//!   [`UserSendsFileTask`](crate::tasks::user_sends_file::UserSendsFileTask), as usual.
+//! - Submits priority challenges for random keys at the configured rate.
+//!
+//! The latency and outcome of each storage request, chunk upload and challenge are recorded, and
+//! written as a JSON report to the path in the scenario once the run is over, so that runs can be
+//! compared to track regressions.
+//!
+//! Example scenario:
+//! ```json
+//! {
+//!     "seed": 42,
+//!     "duration_secs": 300,
+//!     "report_path": "/tmp/load-report.json",
+//!     "storage_requests": {
+//!         "rate_per_second": 2,
+//!         "bucket_id": "0x8e4d...",
+//!         "msp_id": "0x0000...0300",
+//!         "file_size": 1048576,
+//!         "peer_ids": ["12D3KooWMvbhtYjbhgjoDzbnf71SFznJAKBBkSGYEUtnpES1y9tM"]
+//!     },
+//!     "proof_challenges": { "rate_per_second": 0.5 }
+//! }
+//! ```
+
+pub mod report;
+pub mod scenario;
+
+use std::time::Duration;
+
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use log::{error, info, warn};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use shc_blockchain_service::{commands::BlockchainServiceInterface, types::Tip};
+use shc_common::types::{FileMetadata, HashT, StorageProofsMerkleTrieLayout, FILE_CHUNK_SIZE};
+use shc_file_manager::traits::{FileDataTrie, FileStorage};
+use shp_file_metadata::ChunkId;
+use sp_core::H256;
+use sp_runtime::AccountId32;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::services::{handler::StorageHubHandler, types::ShNodeType};
+
+use report::LoadReport;
+use scenario::{LoadScenario, StorageRequestLoad, LOAD_SCENARIO_ENV_VAR};
+
+pub use report::{record_sample, Subsystem};
+
+const LOG_TARGET: &str = "load-generator";
+
+/// Seeds of the random generators of each load are derived from the seed of the scenario, so that
+/// adding a load to a scenario does not change what the others submit.
+const STORAGE_REQUEST_SEED_SALT: u64 = 0x5354_4f52_4147_4531;
+const PROOF_CHALLENGE_SEED_SALT: u64 = 0x4348_414c_4c45_4e47;
+
+/// Runs the load scenario in [`LOAD_SCENARIO_ENV_VAR`] in the background, if set.
+pub fn spawn_if_configured<NT>(storage_hub_handler: StorageHubHandler<NT>)
+where
+    NT: ShNodeType + 'static,
+{
+    let Ok(path) = std::env::var(LOAD_SCENARIO_ENV_VAR) else {
+        return;
+    };
+
+    let scenario = match LoadScenario::from_file(&path) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            error!(target: LOG_TARGET, "Failed to load the load scenario from {}: {:?}", path, e);
+            return;
+        }
+    };
+
+    info!(target: LOG_TARGET, "🏋️ Running load scenario from {} for {} seconds", path, scenario.duration_secs);
+
+    let task_spawner = storage_hub_handler.task_spawner.clone();
+    task_spawner.spawn(async move {
+        LoadGenerator {
+            storage_hub_handler,
+            scenario,
+        }
+        .run()
+        .await
+    });
+}
+
+struct LoadGenerator<NT>
+where
+    NT: ShNodeType,
+{
+    storage_hub_handler: StorageHubHandler<NT>,
+    scenario: LoadScenario,
+}
+
+impl<NT> LoadGenerator<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    async fn run(self) {
+        if !report::start_recording() {
+            warn!(target: LOG_TARGET, "A load scenario is already running, skipping");
+            return;
+        }
+
+        let stop_at = Instant::now() + Duration::from_secs(self.scenario.duration_secs);
+        let drain_timeout = Duration::from_secs(self.scenario.drain_timeout_secs);
+
+        let storage_requests = self.scenario.storage_requests.clone().map(|load| {
+            let mut rng = StdRng::seed_from_u64(self.scenario.seed ^ STORAGE_REQUEST_SEED_SALT);
+            let storage_hub_handler = self.storage_hub_handler.clone();
+            let seed = self.scenario.seed;
+            generate_load(
+                Subsystem::StorageRequest,
+                load.rate_per_second,
+                stop_at,
+                drain_timeout,
+                move |index| {
+                    let mut data = vec![0u8; load.file_size as usize];
+                    rng.fill_bytes(&mut data);
+                    let location = format!("load-generator/{}/{}", seed, index);
+                    issue_storage_request(storage_hub_handler.clone(), load.clone(), location, data)
+                        .boxed()
+                },
+            )
+        });
+
+        let proof_challenges = self.scenario.proof_challenges.clone().map(|load| {
+            let mut rng = StdRng::seed_from_u64(self.scenario.seed ^ PROOF_CHALLENGE_SEED_SALT);
+            let storage_hub_handler = self.storage_hub_handler.clone();
+            generate_load(
+                Subsystem::ProofChallenge,
+                load.rate_per_second,
+                stop_at,
+                drain_timeout,
+                move |_| {
+                    let mut key = H256::zero();
+                    rng.fill_bytes(key.as_mut());
+                    submit_challenge(storage_hub_handler.clone(), key).boxed()
+                },
+            )
+        });
+
+        futures::join!(
+            async {
+                if let Some(storage_requests) = storage_requests {
+                    storage_requests.await
+                }
+            },
+            async {
+                if let Some(proof_challenges) = proof_challenges {
+                    proof_challenges.await
+                }
+            },
+        );
+
+        // Stopping the storage requests does not stop the uploads of the chunks of their files,
+        // so give them the same time to finish as the rest of the operations in flight.
+        if self.scenario.storage_requests.is_some() {
+            tokio::time::sleep(drain_timeout).await;
+        }
+
+        let Some(report) = report::finish_recording(self.scenario.seed) else {
+            return;
+        };
+        self.write_report(&report);
+    }
+
+    fn write_report(&self, report: &LoadReport) {
+        for (subsystem, figures) in &report.subsystems {
+            info!(
+                target: LOG_TARGET,
+                "🏋️ {:?}: {}/{} succeeded, {:.2} per second, latency (ms): {:?}",
+                subsystem,
+                figures.succeeded,
+                figures.attempted,
+                figures.throughput_per_sec,
+                figures.latency_ms
+            );
+        }
+
+        let result = serde_json::to_vec_pretty(report)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(std::fs::write(&self.scenario.report_path, json)?));
+        match result {
+            Ok(()) => {
+                info!(target: LOG_TARGET, "🏋️ Load report written to {:?}", self.scenario.report_path)
+            }
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to write the load report to {:?}: {:?}", self.scenario.report_path, e)
+            }
+        }
+    }
+}
+
+/// Starts an operation of a load every `1 / rate_per_second` seconds until `stop_at`, then waits
+/// up to `drain_timeout` for the operations in flight. The operations that do not finish in time
+/// are recorded as failed for `subsystem`.
+async fn generate_load<F>(
+    subsystem: Subsystem,
+    rate_per_second: f64,
+    stop_at: Instant,
+    drain_timeout: Duration,
+    mut next_operation: F,
+) where
+    F: FnMut(u64) -> BoxFuture<'static, ()>,
+{
+    let period = Duration::from_secs_f64(1.0 / rate_per_second).max(Duration::from_millis(1));
+    let mut interval = tokio::time::interval(period);
+    // Keep the rate steady instead of catching up with a burst if the node falls behind.
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut in_flight = FuturesUnordered::new();
+    let mut index = 0;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(stop_at) => break,
+            _ = interval.tick() => {
+                in_flight.push(next_operation(index));
+                index += 1;
+            }
+            Some(()) = in_flight.next() => {}
+        }
+    }
+
+    let _ = tokio::time::timeout(drain_timeout, async {
+        while in_flight.next().await.is_some() {}
+    })
+    .await;
+
+    for _ in 0..in_flight.len() {
+        report::record_sample(subsystem, drain_timeout, false, 0);
+    }
+}
+
+/// Stores a file with `data` in the File Storage of the node and issues a storage request for it,
+/// recording how long the storage request takes to be included in a block.
+async fn issue_storage_request<NT>(
+    storage_hub_handler: StorageHubHandler<NT>,
+    load: StorageRequestLoad,
+    location: String,
+    data: Vec<u8>,
+) where
+    NT: ShNodeType + 'static,
+{
+    let file_size = data.len() as u64;
+
+    // Preparing the file is not part of what is measured.
+    let call = match store_file(&storage_hub_handler, &load, &location, &data).await {
+        Ok(fingerprint) => storage_hub_runtime::RuntimeCall::FileSystem(
+            pallet_file_system::Call::issue_storage_request {
+                bucket_id: load.bucket_id,
+                location: location.into_bytes().try_into().unwrap_or_default(),
+                fingerprint,
+                size: file_size,
+                msp_id: Some(load.msp_id),
+                peer_ids: load
+                    .peer_ids
+                    .iter()
+                    .filter_map(|peer_id| peer_id.as_bytes().to_vec().try_into().ok())
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap_or_default(),
+                replication_target: load.replication_target,
+                auto_renew: false,
+            },
+        ),
+        Err(e) => {
+            error!(target: LOG_TARGET, "Failed to store the file at {} to issue a storage request: {:?}", location, e);
+            report::record_sample(Subsystem::StorageRequest, Duration::ZERO, false, 0);
+            return;
+        }
+    };
+
+    let started_at = Instant::now();
+    let result = submit_and_watch(&storage_hub_handler, call).await;
+    if let Err(e) = &result {
+        warn!(target: LOG_TARGET, "Storage request for {} failed: {:?}", location, e);
+    }
+    report::record_sample(
+        Subsystem::StorageRequest,
+        started_at.elapsed(),
+        result.is_ok(),
+        file_size,
+    );
+}
+
+/// Stores a file with `data` in the File Storage of the node, returning its fingerprint.
+async fn store_file<NT>(
+    storage_hub_handler: &StorageHubHandler<NT>,
+    load: &StorageRequestLoad,
+    location: &str,
+    data: &[u8],
+) -> anyhow::Result<H256>
+where
+    NT: ShNodeType + 'static,
+{
+    let owner: AccountId32 = storage_hub_handler
+        .blockchain
+        .get_node_public_key()
+        .await
+        .into();
+
+    let mut file_data_trie = storage_hub_handler
+        .file_storage
+        .read()
+        .await
+        .new_file_data_trie();
+    for (chunk_id, chunk) in data.chunks(FILE_CHUNK_SIZE as usize).enumerate() {
+        file_data_trie
+            .write_chunk(&ChunkId::new(chunk_id as u64), chunk)
+            .map_err(|e| anyhow::anyhow!("Failed to write chunk {}: {:?}", chunk_id, e))?;
+    }
+
+    let file_metadata = FileMetadata {
+        owner: <AccountId32 as AsRef<[u8]>>::as_ref(&owner).to_vec(),
+        bucket_id: load.bucket_id.as_ref().to_vec(),
+        file_size: data.len() as u64,
+        fingerprint: file_data_trie.get_root().as_ref().into(),
+        location: location.as_bytes().to_vec(),
+    };
+    let file_key = file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>();
+    let fingerprint = H256::from_slice(file_metadata.fingerprint.as_ref());
+
+    storage_hub_handler
+        .file_storage
+        .write()
+        .await
+        .insert_file_with_data(file_key, file_metadata, file_data_trie)
+        .map_err(|e| anyhow::anyhow!("Failed to insert file {:?}: {:?}", file_key, e))?;
+
+    Ok(fingerprint)
+}
+
+/// Submits a priority challenge for `key`, recording how long it takes to be included in a block.
+async fn submit_challenge<NT>(storage_hub_handler: StorageHubHandler<NT>, key: H256)
+where
+    NT: ShNodeType + 'static,
+{
+    let call =
+        storage_hub_runtime::RuntimeCall::ProofsDealer(pallet_proofs_dealer::Call::challenge {
+            key,
+        });
+
+    let started_at = Instant::now();
+    let result = submit_and_watch(&storage_hub_handler, call).await;
+    if let Err(e) = &result {
+        warn!(target: LOG_TARGET, "Challenge for key {:?} failed: {:?}", key, e);
+    }
+    report::record_sample(
+        Subsystem::ProofChallenge,
+        started_at.elapsed(),
+        result.is_ok(),
+        0,
+    );
+}
+
+async fn submit_and_watch<NT>(
+    storage_hub_handler: &StorageHubHandler<NT>,
+    call: storage_hub_runtime::RuntimeCall,
+) -> anyhow::Result<()>
+where
+    NT: ShNodeType + 'static,
+{
+    storage_hub_handler
+        .blockchain
+        .send_extrinsic(call, Tip::from(0))
+        .await?
+        .with_timeout(Duration::from_secs(
+            storage_hub_handler.provider_config.extrinsic_retry_timeout,
+        ))
+        .watch_for_success(&storage_hub_handler.blockchain)
+        .await
+}
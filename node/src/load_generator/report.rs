@@ -0,0 +1,176 @@
+//! Recording of the operations of a load generator run, and the report built from them.
+
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// A subsystem of the node the load generator measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    /// Storage requests, from submission until their inclusion in a block.
+    StorageRequest,
+    /// Uploads of file chunks to a Provider, from request until the Provider's response.
+    ChunkUpload,
+    /// Priority challenges, from submission until their inclusion in a block.
+    ProofChallenge,
+}
+
+#[derive(Debug, Default)]
+struct SubsystemSamples {
+    latencies: Vec<Duration>,
+    failed: u64,
+    bytes: u64,
+}
+
+/// The samples recorded during a load generator run.
+#[derive(Debug)]
+struct LoadRecorder {
+    started_at: Instant,
+    started_at_utc: chrono::DateTime<chrono::Utc>,
+    samples: BTreeMap<Subsystem, SubsystemSamples>,
+}
+
+/// The recorder of the run in progress, if any.
+static RECORDER: Mutex<Option<LoadRecorder>> = Mutex::new(None);
+
+/// Starts recording the operations of a run. Returns `false` if a run is already in progress.
+pub fn start_recording() -> bool {
+    let mut recorder = RECORDER.lock().expect("Load recorder lock poisoned");
+    if recorder.is_some() {
+        return false;
+    }
+
+    *recorder = Some(LoadRecorder {
+        started_at: Instant::now(),
+        started_at_utc: chrono::Utc::now(),
+        samples: BTreeMap::new(),
+    });
+    true
+}
+
+/// Records an operation of `subsystem` that took `latency` and moved `bytes`, and whether it
+/// succeeded. Failed operations do not count towards the latency and bytes figures.
+///
+/// Does nothing if no run is in progress.
+pub fn record_sample(subsystem: Subsystem, latency: Duration, success: bool, bytes: u64) {
+    let mut recorder = RECORDER.lock().expect("Load recorder lock poisoned");
+    let Some(recorder) = recorder.as_mut() else {
+        return;
+    };
+
+    let samples = recorder.samples.entry(subsystem).or_default();
+    if success {
+        samples.latencies.push(latency);
+        samples.bytes += bytes;
+    } else {
+        samples.failed += 1;
+    }
+}
+
+/// Stops recording and builds the report of the run, or returns `None` if no run was in progress.
+pub fn finish_recording(seed: u64) -> Option<LoadReport> {
+    let recorder = RECORDER
+        .lock()
+        .expect("Load recorder lock poisoned")
+        .take()?;
+    let duration = recorder.started_at.elapsed();
+
+    let subsystems = recorder
+        .samples
+        .into_iter()
+        .map(|(subsystem, samples)| (subsystem, SubsystemReport::new(samples, duration)))
+        .collect();
+
+    Some(LoadReport {
+        seed,
+        started_at: recorder.started_at_utc.to_rfc3339(),
+        duration_secs: duration.as_secs_f64(),
+        subsystems,
+    })
+}
+
+/// Report of a load generator run.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadReport {
+    /// Seed of the scenario that was run.
+    pub seed: u64,
+    /// When the run started (RFC 3339).
+    pub started_at: String,
+    /// How long the run took, including waiting for the operations in flight once the load stopped.
+    pub duration_secs: f64,
+    /// The figures of each subsystem that had operations during the run.
+    pub subsystems: BTreeMap<Subsystem, SubsystemReport>,
+}
+
+/// Figures of the operations of a subsystem during a load generator run.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubsystemReport {
+    /// Operations attempted.
+    pub attempted: u64,
+    /// Operations that succeeded.
+    pub succeeded: u64,
+    /// Operations that failed or did not finish in time.
+    pub failed: u64,
+    /// Successful operations per second over the whole run.
+    pub throughput_per_sec: f64,
+    /// Bytes moved by the successful operations.
+    pub bytes: u64,
+    /// Latency of the successful operations, if any.
+    pub latency_ms: Option<LatencySummary>,
+}
+
+impl SubsystemReport {
+    fn new(mut samples: SubsystemSamples, duration: Duration) -> Self {
+        let succeeded = samples.latencies.len() as u64;
+        samples.latencies.sort();
+
+        Self {
+            attempted: succeeded + samples.failed,
+            succeeded,
+            failed: samples.failed,
+            throughput_per_sec: succeeded as f64 / duration.as_secs_f64().max(f64::EPSILON),
+            bytes: samples.bytes,
+            latency_ms: LatencySummary::from_sorted(&samples.latencies),
+        }
+    }
+}
+
+/// Distribution of the latency of the operations of a subsystem, in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencySummary {
+    fn from_sorted(latencies: &[Duration]) -> Option<Self> {
+        let millis = |latency: &Duration| latency.as_secs_f64() * 1000.0;
+        // Nearest-rank percentile.
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * latencies.len() as f64).ceil() as usize;
+            millis(&latencies[rank.clamp(1, latencies.len()) - 1])
+        };
+
+        let first = latencies.first()?;
+        let last = latencies.last()?;
+        let total: Duration = latencies.iter().sum();
+
+        Some(Self {
+            min: millis(first),
+            mean: millis(&total) / latencies.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: millis(last),
+        })
+    }
+}
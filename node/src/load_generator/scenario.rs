@@ -0,0 +1,104 @@
+//! The load scenario run by the load generator, as read from the scenario file.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use sp_core::H256;
+
+/// Environment variable holding the path to the load scenario file.
+pub const LOAD_SCENARIO_ENV_VAR: &str = "STORAGEHUB_LOAD_SCENARIO";
+
+fn default_drain_timeout_secs() -> u64 {
+    60
+}
+
+fn default_file_size() -> u64 {
+    1024 * 1024
+}
+
+/// A load scenario, as read from the scenario file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadScenario {
+    /// Seed for the pseudo-random generator of file contents and challenges, so that runs of a
+    /// scenario submit the same load.
+    #[serde(default)]
+    pub seed: u64,
+    /// For how long to generate load.
+    pub duration_secs: u64,
+    /// How long to wait for the operations still in flight once the load stops, before writing
+    /// the report. Operations that do not finish in time are counted as failed.
+    #[serde(default = "default_drain_timeout_secs")]
+    pub drain_timeout_secs: u64,
+    /// Where to write the JSON report of the run.
+    pub report_path: PathBuf,
+    /// Storage requests to issue, if any. The chunks of their files are then uploaded to the
+    /// Providers by the user node as usual, which is reported as the chunk upload load.
+    #[serde(default)]
+    pub storage_requests: Option<StorageRequestLoad>,
+    /// Priority challenges to submit, if any.
+    #[serde(default)]
+    pub proof_challenges: Option<ProofChallengeLoad>,
+}
+
+impl LoadScenario {
+    /// Reads a scenario from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let scenario: Self = serde_json::from_str(&content)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(storage_requests) = &self.storage_requests {
+            validate_rate("storage_requests", storage_requests.rate_per_second)?;
+            if storage_requests.file_size == 0 {
+                anyhow::bail!("storage_requests.file_size must be greater than zero");
+            }
+        }
+        if let Some(proof_challenges) = &self.proof_challenges {
+            validate_rate("proof_challenges", proof_challenges.rate_per_second)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_rate(load: &str, rate_per_second: f64) -> anyhow::Result<()> {
+    if !rate_per_second.is_finite() || rate_per_second <= 0.0 {
+        anyhow::bail!(
+            "{}.rate_per_second must be a positive number, got {}",
+            load,
+            rate_per_second
+        );
+    }
+
+    Ok(())
+}
+
+/// Storage requests issued by the load generator, for files with random contents.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageRequestLoad {
+    /// How many storage requests to issue per second.
+    pub rate_per_second: f64,
+    /// The bucket to store the files in. It must be owned by the account of this node.
+    pub bucket_id: H256,
+    /// The MSP storing the bucket.
+    pub msp_id: H256,
+    /// Size of each file, in bytes.
+    #[serde(default = "default_file_size")]
+    pub file_size: u64,
+    /// Peer IDs of this node, for the Providers to download the files from.
+    #[serde(default)]
+    pub peer_ids: Vec<String>,
+    /// Replication target of the storage requests. Uses the default of the runtime if not set.
+    #[serde(default)]
+    pub replication_target: Option<u32>,
+}
+
+/// Priority challenges submitted by the load generator, for random keys.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProofChallengeLoad {
+    /// How many challenges to submit per second.
+    pub rate_per_second: f64,
+}
@@ -5,6 +5,12 @@
 mod chain_spec;
 mod cli;
 mod command;
+mod config;
+mod db_backup;
+mod forest_snapshot;
+mod identity;
+#[cfg(feature = "load-generator")]
+mod load_generator;
 mod rpc;
 mod service;
 mod services;
@@ -7,6 +7,7 @@
 
 use std::sync::Arc;
 
+use pallet_file_system_runtime_api::FileSystemApi as FileSystemRuntimeApi;
 use pallet_proofs_dealer_runtime_api::ProofsDealerApi as ProofsDealerRuntimeApi;
 use sc_consensus_manual_seal::{
     rpc::{ManualSeal, ManualSealApiServer},
@@ -14,7 +15,9 @@ use sc_consensus_manual_seal::{
 };
 use sc_transaction_pool_api::TransactionPool;
 use shc_common::types::{
-    BlockNumber, ForestLeaf, ProofsDealerProviderId, RandomnessOutput, TrieRemoveMutation,
+    BackupStorageProviderId, BlockNumber, BucketId, ChunkId, ForestLeaf, MainStorageProviderId,
+    ProofsDealerProviderId, ProviderId, RandomnessOutput, ReplicationTarget,
+    StorageRequestMetadata, TrieRemoveMutation,
 };
 use shc_forest_manager::traits::ForestStorageHandler;
 use shc_rpc::{StorageHubClientApiServer, StorageHubClientRpc, StorageHubClientRpcConfig};
@@ -63,6 +66,20 @@ where
         RandomnessOutput,
         TrieRemoveMutation,
     >,
+    C::Api: FileSystemRuntimeApi<
+        Block,
+        BackupStorageProviderId,
+        MainStorageProviderId,
+        H256,
+        BlockNumber,
+        ChunkId,
+        BucketId,
+        ReplicationTarget,
+        ProviderId,
+        StorageRequestMetadata,
+        AccountId,
+        Balance,
+    >,
     P: TransactionPool + Send + Sync + 'static,
     FL: FileStorageT,
     FSH: ForestStorageHandler + Send + Sync + 'static,
@@ -4,8 +4,15 @@
 use futures::{Stream, StreamExt};
 use log::info;
 use shc_indexer_db::DbPool;
-use shc_indexer_service::spawn_indexer_service;
-use std::{cell::RefCell, env, path::PathBuf, sync::Arc, time::Duration};
+use shc_indexer_service::{spawn_indexer_graphql_server, spawn_indexer_service};
+use std::{
+    cell::RefCell,
+    env,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_channel::Receiver;
 use chrono::Utc;
@@ -16,8 +23,22 @@ use cumulus_client_parachain_inherent::{MockValidationDataInherentDataProvider,
 use polkadot_primitives::{BlakeTwo256, HashT, HeadData};
 use sc_consensus_manual_seal::consensus::aura::AuraConsensusDataProvider;
 use shc_actors_framework::actor::TaskSpawner;
+use shc_blockchain_service::{
+    runtime_api_cache::CachedRuntimeApiQuery, ReplayRange, RuntimeApiCacheConfig, SafeModeConfig,
+};
 use shc_common::types::{BlockHash, OpaqueBlock, BCSV_KEY_TYPE};
+use shc_config_watcher::ConfigWatcherConfig;
+use shc_db_backup::DbBackupConfig;
+use shc_disk_watchdog::DiskWatchdogConfig;
+use shc_forest_manager::rocksdb::ForestStorageConfig;
+use shc_http_gateway::{GatewayMetrics, HttpGatewayConfig, TlsConfig};
+use shc_job_queue::{JobStore, JOB_QUEUE_DB_PATH};
+use shc_provider_earnings::{
+    EarningsMetrics, EarningsStore, ProviderEarningsConfig, PROVIDER_EARNINGS_DB_PATH,
+};
 use shc_rpc::StorageHubClientRpcConfig;
+use shc_scrubber_service::ScrubberConfig;
+use shc_telemetry::OpenTelemetryConfig;
 use sp_consensus_aura::Slot;
 use sp_core::H256;
 // Local Runtime Types
@@ -52,7 +73,10 @@ use sc_network::{
 use sc_service::{Configuration, PartialComponents, RpcHandlers, TFullBackend, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker, TelemetryWorkerHandle};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
-use shc_file_transfer_service::configure_file_transfer_network;
+use shc_file_transfer_service::{
+    configure_file_transfer_network, BandwidthLimits, PeerScoreStore, PeerScoringConfig,
+    TransferChunkSizeBounds, TransferStateStore, PEER_SCORES_DB_PATH, TRANSFER_STATE_DB_PATH,
+};
 use sp_keystore::{Keystore, KeystorePtr};
 use substrate_prometheus_endpoint::Registry;
 
@@ -61,12 +85,13 @@ use crate::{
     command::ProviderOptions,
     services::{
         builder::{Buildable, StorageHubBuilder, StorageLayerBuilder},
-        handler::{RunnableTasks, StorageHubHandler},
+        handler::{FleetCoordinationConfig, RunnableTasks, StorageHubHandler},
         types::{
             BspProvider, InMemoryStorageLayer, MspProvider, NoStorageLayer, RocksDbStorageLayer,
             ShNodeType, ShRole, ShStorageLayer, UserRole,
         },
     },
+    tasks::reload_config::set_log_filter,
 };
 
 //* These type definitions were moved from this file to the common crate to be used by'
@@ -198,6 +223,8 @@ async fn init_sh_builder<R, S>(
     network: Arc<dyn NetworkService>,
     keystore: KeystorePtr,
     maybe_db_pool: Option<DbPool>,
+    base_path: &Path,
+    prometheus_registry: Option<&Registry>,
 ) -> Option<(
     StorageHubBuilder<R, S>,
     StorageHubClientRpcConfig<<(R, S) as ShNodeType>::FL, <(R, S) as ShNodeType>::FSH>,
@@ -209,20 +236,69 @@ where
     StorageHubBuilder<R, S>: StorageLayerBuilder,
 {
     match provider_options {
-        Some(ProviderOptions {
-            provider_type,
-            storage_path,
-            max_storage_capacity,
-            jump_capacity,
-            extrinsic_retry_timeout,
-            msp_charging_period,
-            ..
-        }) => {
+        Some(
+            options @ ProviderOptions {
+                provider_type,
+                storage_layer,
+                storage_path,
+                max_storage_capacity,
+                jump_capacity,
+                extrinsic_retry_timeout,
+                forest_root_write_lock_warn_threshold,
+                msp_charging_period,
+                bucket_gc_retention_period,
+                db_backup_path,
+                db_backup_max_incremental_chain,
+                provider_earnings,
+                provider_earnings_backfill_depth,
+                otlp_endpoint,
+                otlp_service_name,
+                otlp_metrics_export_interval,
+                min_transfer_chunk_size,
+                max_transfer_chunk_size,
+                max_upload_bandwidth,
+                max_peer_upload_bandwidth,
+                peer_ban_threshold,
+                peer_ban_duration,
+                max_peer_requests_per_minute,
+                scrub_chunks_per_second,
+                disk_watchdog_period,
+                auto_reconcile_capacity,
+                forest_proof_threads,
+                forest_write_cache_size,
+                runtime_api_cache_ttl,
+                runtime_api_cache_query_ttls,
+                partition_timeout,
+                fleet_bsp_ids,
+                max_fleet_volunteers_per_file,
+                // Read through `ProviderOptions::volunteer_policy`.
+                volunteer_max_file_size: _,
+                volunteer_bucket_allowlist: _,
+                volunteer_bucket_denylist: _,
+                volunteer_owner_denylist: _,
+                volunteer_max_owner_debt: _,
+                volunteer_min_capacity_headroom: _,
+                volunteer_min_price_per_giga_unit_per_tick: _,
+                http_gateway_addr,
+                http_gateway_tls_cert,
+                http_gateway_tls_key,
+                replay_from_block,
+                replay_to_block,
+                config_watch_period,
+                log_filter,
+                config_file,
+            },
+        ) => {
             info!(
                 "Starting as a Storage Provider. Storage path: {:?}, Max storage capacity: {:?}, Jump capacity: {:?}, MSP charging period: {:?}",
                 storage_path, max_storage_capacity, jump_capacity, msp_charging_period,
             );
 
+            // Bound the threads used to generate Forest proofs, which otherwise use every CPU.
+            shc_forest_manager::set_proof_generation_threads(
+                forest_proof_threads.and_then(NonZeroUsize::new),
+            );
+
             // Start building the StorageHubHandler, if running as a provider.
             let task_spawner = TaskSpawner::new(task_manager.spawn_handle(), "sh-builder");
             let mut storage_hub_builder = StorageHubBuilder::<R, S>::new(task_spawner);
@@ -232,30 +308,259 @@ where
                 file_transfer_request_protocol
                     .expect("FileTransfer request protocol should already be initialised.");
 
+            // Persist the progress of the uploads to this node, to resume them after a restart. Only
+            // if the received chunks are persisted too.
+            let transfer_states = match storage_layer {
+                StorageLayer::RocksDB => {
+                    TransferStateStore::open(base_path.join(TRANSFER_STATE_DB_PATH))
+                        .unwrap_or_else(|e| {
+                            log::error!(
+                                "Failed to open the transfer state database, uploads will not resume after restarts: {}",
+                                e
+                            );
+                            TransferStateStore::in_memory()
+                        })
+                }
+                StorageLayer::Memory => TransferStateStore::in_memory(),
+            };
+
+            // Persist the scores of the peers, so that bans survive a restart.
+            let peer_scores = match storage_layer {
+                StorageLayer::RocksDB => PeerScoreStore::open(base_path.join(PEER_SCORES_DB_PATH))
+                    .unwrap_or_else(|e| {
+                        log::error!(
+                            "Failed to open the peer scores database, bans will not survive restarts: {}",
+                            e
+                        );
+                        PeerScoreStore::in_memory()
+                    }),
+                StorageLayer::Memory => PeerScoreStore::in_memory(),
+            };
+
             storage_hub_builder
                 .with_file_transfer(
                     file_transfer_request_receiver,
                     file_transfer_request_protocol_name,
                     network.clone(),
+                    TransferChunkSizeBounds::new(
+                        *min_transfer_chunk_size,
+                        *max_transfer_chunk_size,
+                    ),
+                    transfer_states,
+                    BandwidthLimits {
+                        global: *max_upload_bandwidth,
+                        per_peer: *max_peer_upload_bandwidth,
+                    },
+                    peer_scores,
+                    PeerScoringConfig {
+                        ban_threshold: *peer_ban_threshold,
+                        ban_duration: Duration::from_secs(*peer_ban_duration),
+                        max_requests_per_minute: *max_peer_requests_per_minute,
+                    },
                 )
                 .await;
 
             // Setup the `ShStorageLayer` and additional configuration parameters.
             storage_hub_builder
+                .with_forest_storage_config(ForestStorageConfig {
+                    write_cache_size: *forest_write_cache_size,
+                })
                 .setup_storage_layer(storage_path.clone())
                 .with_retry_timeout(*extrinsic_retry_timeout)
+                .with_forest_root_write_lock_warn_threshold(Duration::from_secs(
+                    *forest_root_write_lock_warn_threshold,
+                ))
                 .with_max_storage_capacity(*max_storage_capacity)
-                .with_jump_capacity(*jump_capacity);
+                .with_jump_capacity(*jump_capacity)
+                .with_replay_range(
+                    replay_from_block
+                        .zip(*replay_to_block)
+                        .map(|(from, to)| ReplayRange { from, to }),
+                )
+                .with_runtime_api_cache_config(RuntimeApiCacheConfig {
+                    default_ttl: Duration::from_millis(*runtime_api_cache_ttl),
+                    // The queries have already been validated along with the provider options.
+                    query_ttls: runtime_api_cache_query_ttls
+                        .iter()
+                        .filter_map(|(query, ttl)| {
+                            let query = query.parse::<CachedRuntimeApiQuery>().ok()?;
+                            Some((query, Duration::from_millis(*ttl)))
+                        })
+                        .collect(),
+                })
+                .with_safe_mode_config(SafeModeConfig {
+                    partition_timeout: Duration::from_secs(*partition_timeout),
+                });
+
+            // Persist the long-running operations of the node, to resume them after a restart.
+            let job_store = JobStore::open(base_path.join(JOB_QUEUE_DB_PATH)).unwrap_or_else(|e| {
+                log::error!(
+                    "Failed to open the job queue database, jobs will not survive restarts: {}",
+                    e
+                );
+                JobStore::in_memory()
+            });
+            storage_hub_builder.with_job_queue(job_store);
+
+            // Check the integrity of the stored files in the background, if enabled.
+            if *provider_type != ProviderType::User {
+                storage_hub_builder
+                    .with_scrubber(
+                        scrub_chunks_per_second
+                            .map(|chunks_per_second| ScrubberConfig { chunks_per_second }),
+                    )
+                    .await;
+            }
 
             // Setup specific configuration for the MSP node.
             if *provider_type == ProviderType::Msp {
                 storage_hub_builder
                     .with_notify_period(*msp_charging_period)
+                    .with_bucket_gc_retention_period(*bucket_gc_retention_period)
                     .with_indexer_db_pool(maybe_db_pool);
+
+                // Serve the stored files over HTTP(S), if enabled.
+                storage_hub_builder.with_http_gateway(http_gateway_addr.map(|listen_addr| {
+                    HttpGatewayConfig {
+                        listen_addr,
+                        tls: http_gateway_tls_cert
+                            .clone()
+                            .zip(http_gateway_tls_key.clone())
+                            .map(|(cert_path, key_path)| TlsConfig {
+                                cert_path,
+                                key_path,
+                            }),
+                        metrics: prometheus_registry.and_then(|registry| {
+                            GatewayMetrics::register(registry)
+                                .map_err(|e| {
+                                    log::warn!(
+                                        "Failed to register the HTTP Gateway metrics: {:?}",
+                                        e
+                                    )
+                                })
+                                .ok()
+                        }),
+                    }
+                }));
+            } else if *provider_type == ProviderType::User {
+                // Used to estimate how long storage requests take to be fulfilled.
+                storage_hub_builder.with_indexer_db_pool(maybe_db_pool);
+            } else if *provider_type == ProviderType::Bsp {
+                // Coordinate the volunteering with the other BSPs of the fleet, if configured.
+                storage_hub_builder.with_fleet_coordination(max_fleet_volunteers_per_file.map(
+                    |max_volunteers_per_file| FleetCoordinationConfig {
+                        fleet_bsp_ids: fleet_bsp_ids.iter().copied().collect(),
+                        max_volunteers_per_file,
+                    },
+                ));
+
+                // Only volunteer for the storage requests meeting the policy of the operator.
+                storage_hub_builder.with_volunteer_policy(options.volunteer_policy());
+
+                // Check that the on-chain capacity fits in the disk, only supported with the
+                // RocksDB storage layer.
+                match (disk_watchdog_period, storage_layer, storage_path) {
+                    (Some(check_period), StorageLayer::RocksDB, Some(storage_path)) => {
+                        storage_hub_builder
+                            .with_disk_watchdog(Some(DiskWatchdogConfig {
+                                storage_path: storage_path.into(),
+                                check_period: Duration::from_secs(*check_period),
+                            }))
+                            .await
+                            .with_auto_reconcile_capacity(*auto_reconcile_capacity);
+                    }
+                    (Some(_), _, _) => {
+                        log::warn!("The disk watchdog is only supported with the RocksDB storage layer. Ignoring `--disk-watchdog-period`.");
+                    }
+                    (None, _, _) => {}
+                }
             }
 
+            // Setup the export of OpenTelemetry traces and metrics, if enabled.
+            let node_role = match provider_type {
+                ProviderType::Bsp => "bsp",
+                ProviderType::Msp => "msp",
+                ProviderType::User => "user",
+            };
+            storage_hub_builder.with_open_telemetry(otlp_endpoint.as_ref().map(|endpoint| {
+                OpenTelemetryConfig {
+                    endpoint: endpoint.clone(),
+                    service_name: otlp_service_name.clone(),
+                    node_role: node_role.to_string(),
+                    provider_id: None,
+                    metrics_export_interval: Duration::from_secs(*otlp_metrics_export_interval),
+                }
+            }));
+
+            // Set the log filter of the configuration on top of the `--log` flag, if any.
+            if let Some(log_filter) = log_filter {
+                if let Err(e) = set_log_filter(Some(log_filter)) {
+                    log::warn!(
+                        "Failed to set the log filter {:?}: {}. Start the node with `--enable-log-reloading` to set it.",
+                        log_filter,
+                        e
+                    );
+                }
+            }
+
+            // Reload the settings that can change while the node runs from the configuration
+            // file, if loaded from one.
+            storage_hub_builder
+                .with_config_watcher(config_file.as_ref().map(|path| ConfigWatcherConfig {
+                    path: path.clone(),
+                    check_period: config_watch_period.map(Duration::from_secs),
+                    initial_config: options.reloadable_config(),
+                    loader: Arc::new(|path: &Path| {
+                        Ok(ProviderOptions::from_file(path)?.reloadable_config())
+                    }),
+                }))
+                .await;
+
             // Get the RPC configuration to use for this StorageHub node client.
-            let rpc_config = storage_hub_builder.create_rpc_config(keystore);
+            let mut rpc_config = storage_hub_builder.create_rpc_config(keystore);
+
+            // Enable the database backup RPC methods, only supported with the RocksDB storage layer.
+            match (db_backup_path, storage_layer, storage_path) {
+                (Some(backup_path), StorageLayer::RocksDB, Some(storage_path)) => {
+                    rpc_config = rpc_config.with_db_backup(DbBackupConfig {
+                        storage_path: storage_path.clone(),
+                        backup_path: backup_path.into(),
+                        max_incremental_chain: *db_backup_max_incremental_chain,
+                    });
+                }
+                (Some(_), _, _) => {
+                    log::warn!("Database backups are only supported with the RocksDB storage layer. Ignoring `--db-backup-path`.");
+                }
+                (None, _, _) => {}
+            }
+
+            // Aggregate the earnings of this Provider locally, if enabled.
+            if *provider_earnings && *provider_type != ProviderType::User {
+                match EarningsStore::open(base_path.join(PROVIDER_EARNINGS_DB_PATH)) {
+                    Ok(store) => {
+                        let metrics = prometheus_registry.and_then(|registry| {
+                            EarningsMetrics::register(registry)
+                                .map_err(|e| {
+                                    log::warn!(
+                                        "Failed to register the Provider earnings metrics: {:?}",
+                                        e
+                                    )
+                                })
+                                .ok()
+                        });
+
+                        storage_hub_builder.with_provider_earnings(Some(ProviderEarningsConfig {
+                            store: store.clone(),
+                            metrics,
+                            backfill_depth: *provider_earnings_backfill_depth,
+                        }));
+                        rpc_config = rpc_config.with_provider_earnings(store);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to open the Provider earnings database: {}", e);
+                    }
+                }
+            }
 
             Some((storage_hub_builder, rpc_config))
         }
@@ -287,6 +592,15 @@ where
         )
         .await;
 
+    // Start exporting OpenTelemetry traces and metrics, if enabled
+    sh_builder.init_open_telemetry().await;
+
+    // Start aggregating the earnings of this Provider, if enabled
+    sh_builder.spawn_provider_earnings(client.clone());
+
+    // Start serving the files stored by this MSP over HTTP(S), if enabled
+    sh_builder.spawn_http_gateway();
+
     // Build the StorageHubHandler
     let mut sh_handler = sh_builder.build();
 
@@ -354,6 +668,17 @@ where
         .await;
     }
 
+    if let Some(listen_addr) = indexer_config.indexer_graphql_addr {
+        let task_spawner = TaskSpawner::new(task_manager.spawn_handle(), "indexer-graphql");
+        spawn_indexer_graphql_server(
+            &task_spawner,
+            maybe_db_pool.clone().expect(
+                "Indexer GraphQL API is enabled but no database URL is provided (via CLI using --database-url or setting DATABASE_URL environment variable)",
+            ),
+            listen_addr,
+        );
+    }
+
     let signing_dev_key = config
         .dev_key_seed
         .clone()
@@ -471,6 +796,8 @@ where
             }
         };
 
+    let base_path = config.base_path.path().to_path_buf().clone();
+
     // If node is running as a Storage Provider, start building the StorageHubHandler using the StorageHubBuilder.
     let (sh_builder, maybe_storage_hub_client_rpc_config) = match init_sh_builder::<R, S>(
         &provider_options,
@@ -479,6 +806,8 @@ where
         network.clone(),
         keystore.clone(),
         maybe_db_pool,
+        &base_path,
+        prometheus_registry.as_ref(),
     )
     .await
     {
@@ -502,8 +831,6 @@ where
         })
     };
 
-    let base_path = config.base_path.path().to_path_buf().clone();
-
     let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
         rpc_builder,
         client: client.clone(),
@@ -757,6 +1084,17 @@ where
         .await;
     }
 
+    if let Some(listen_addr) = indexer_config.indexer_graphql_addr {
+        let task_spawner = TaskSpawner::new(task_manager.spawn_handle(), "indexer-graphql");
+        spawn_indexer_graphql_server(
+            &task_spawner,
+            maybe_db_pool.clone().expect(
+                "Indexer GraphQL API is enabled but no database URL is provided (via CLI using --database-url or setting DATABASE_URL environment variable)",
+            ),
+            listen_addr,
+        );
+    }
+
     // If we are a provider we update the network configuration with the file transfer protocol.
     let mut file_transfer_request_protocol = None;
     if provider_options.is_some() {
@@ -820,6 +1158,8 @@ where
         );
     }
 
+    let base_path = parachain_config.base_path.path().to_path_buf().clone();
+
     // If node is running as a Storage Provider, start building the StorageHubHandler using the StorageHubBuilder.
     let (sh_builder, maybe_storage_hub_client_rpc_config) = match init_sh_builder::<R, S>(
         &provider_options,
@@ -828,6 +1168,8 @@ where
         network.clone(),
         keystore.clone(),
         maybe_db_pool,
+        &base_path,
+        prometheus_registry.as_ref(),
     )
     .await
     {
@@ -851,8 +1193,6 @@ where
         })
     };
 
-    let base_path = parachain_config.base_path.path().to_path_buf().clone();
-
     let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
         rpc_builder,
         client: client.clone(),
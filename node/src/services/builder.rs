@@ -3,22 +3,38 @@ use sc_network::{config::IncomingRequest, service::traits::NetworkService, Proto
 use sc_service::RpcHandlers;
 use shc_indexer_db::DbPool;
 use sp_keystore::KeystorePtr;
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use storage_hub_runtime::StorageDataUnit;
 use tokio::sync::RwLock;
 
 use shc_actors_framework::actor::{ActorHandle, TaskSpawner};
-use shc_blockchain_service::{spawn_blockchain_service, BlockchainService};
-use shc_common::types::ParachainClient;
+use shc_blockchain_service::{
+    commands::BlockchainServiceInterface, handler::DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD,
+    spawn_blockchain_service, BlockchainService, ReplayRange, RuntimeApiCacheConfig,
+    SafeModeConfig,
+};
+use shc_common::types::{ParachainClient, StorageProviderId};
+use shc_config_watcher::{spawn_config_watcher_service, ConfigWatcherConfig, ConfigWatcherService};
+use shc_disk_watchdog::{spawn_disk_watchdog_service, DiskWatchdogConfig, DiskWatchdogService};
 use shc_file_manager::{in_memory::InMemoryFileStorage, rocksdb::RocksDbFileStorage};
-use shc_file_transfer_service::{spawn_file_transfer_service, FileTransferService};
-use shc_forest_manager::traits::ForestStorageHandler;
+use shc_file_transfer_service::{
+    spawn_file_transfer_service, BandwidthLimits, FileTransferService, PeerScoreStore,
+    PeerScoringConfig, TransferChunkSizeBounds, TransferStateStore,
+};
+use shc_forest_manager::{rocksdb::ForestStorageConfig, traits::ForestStorageHandler};
+use shc_http_gateway::{HttpGateway, HttpGatewayConfig};
+use shc_job_queue::{JobQueue, JobStore};
+use shc_provider_earnings::{ProviderEarningsConfig, ProviderEarningsService};
 use shc_rpc::StorageHubClientRpcConfig;
+use shc_scrubber_service::{spawn_scrubber_service, ScrubberConfig, ScrubberService};
+use shc_telemetry::{init_open_telemetry, OpenTelemetryConfig};
+use shc_volunteer_policy::{VolunteerPolicy, VolunteerPolicyEngine};
 
 const DEFAULT_EXTRINSIC_RETRY_TIMEOUT_SECONDS: u64 = 60;
+const DEFAULT_BUCKET_GC_RETENTION_PERIOD_SECONDS: u64 = 86400;
 
 use super::{
-    handler::{ProviderConfig, StorageHubHandler},
+    handler::{FleetCoordinationConfig, ProviderConfig, StorageHubHandler},
     types::{
         BspForestStorageHandlerT, BspProvider, InMemoryStorageLayer, MspForestStorageHandlerT,
         MspProvider, NoStorageLayer, RocksDbStorageLayer, ShNodeType, ShRole, ShStorageLayer,
@@ -41,12 +57,28 @@ where
     blockchain: Option<ActorHandle<BlockchainService<<(R, S) as ShNodeType>::FSH>>>,
     storage_path: Option<String>,
     file_storage: Option<Arc<RwLock<<(R, S) as ShNodeType>::FL>>>,
+    scrubber: Option<ActorHandle<ScrubberService<<(R, S) as ShNodeType>::FL>>>,
+    disk_watchdog: Option<ActorHandle<DiskWatchdogService>>,
+    config_watcher: Option<ActorHandle<ConfigWatcherService>>,
+    auto_reconcile_capacity: bool,
+    bucket_gc_retention_period: u64,
     forest_storage_handler: Option<<(R, S) as ShNodeType>::FSH>,
+    forest_storage_config: ForestStorageConfig,
     max_storage_capacity: Option<StorageDataUnit>,
     jump_capacity: Option<StorageDataUnit>,
     extrinsic_retry_timeout: u64,
     indexer_db_pool: Option<DbPool>,
     notify_period: Option<u32>,
+    forest_root_write_lock_warn_threshold: Duration,
+    replay_range: Option<ReplayRange>,
+    runtime_api_cache_config: RuntimeApiCacheConfig,
+    safe_mode_config: SafeModeConfig,
+    open_telemetry_config: Option<OpenTelemetryConfig>,
+    provider_earnings_config: Option<ProviderEarningsConfig>,
+    http_gateway_config: Option<HttpGatewayConfig>,
+    job_queue: Option<JobQueue>,
+    fleet_coordination: Option<FleetCoordinationConfig>,
+    volunteer_policy: Option<VolunteerPolicyEngine>,
 }
 
 /// Common components to build for any given configuration of [`ShRole`] and [`ShStorageLayer`].
@@ -61,12 +93,28 @@ where
             blockchain: None,
             storage_path: None,
             file_storage: None,
+            scrubber: None,
+            disk_watchdog: None,
+            config_watcher: None,
+            auto_reconcile_capacity: false,
+            bucket_gc_retention_period: DEFAULT_BUCKET_GC_RETENTION_PERIOD_SECONDS,
             forest_storage_handler: None,
+            forest_storage_config: ForestStorageConfig::default(),
             max_storage_capacity: None,
             jump_capacity: None,
             extrinsic_retry_timeout: DEFAULT_EXTRINSIC_RETRY_TIMEOUT_SECONDS,
             indexer_db_pool: None,
             notify_period: None,
+            forest_root_write_lock_warn_threshold: DEFAULT_FOREST_ROOT_WRITE_LOCK_WARN_THRESHOLD,
+            replay_range: None,
+            runtime_api_cache_config: RuntimeApiCacheConfig::default(),
+            safe_mode_config: SafeModeConfig::default(),
+            open_telemetry_config: None,
+            provider_earnings_config: None,
+            http_gateway_config: None,
+            job_queue: None,
+            fleet_coordination: None,
+            volunteer_policy: None,
         }
     }
 
@@ -76,6 +124,11 @@ where
         file_transfer_request_receiver: Receiver<IncomingRequest>,
         file_transfer_request_protocol_name: ProtocolName,
         network: Arc<dyn NetworkService>,
+        transfer_chunk_size_bounds: TransferChunkSizeBounds,
+        transfer_states: TransferStateStore,
+        bandwidth_limits: BandwidthLimits,
+        peer_scores: PeerScoreStore,
+        peer_scoring_config: PeerScoringConfig,
     ) -> &mut Self {
         let file_transfer_service_handle = spawn_file_transfer_service(
             self.task_spawner
@@ -84,6 +137,11 @@ where
             file_transfer_request_receiver,
             file_transfer_request_protocol_name,
             network,
+            transfer_chunk_size_bounds,
+            transfer_states,
+            bandwidth_limits,
+            peer_scores,
+            peer_scoring_config,
         )
         .await;
 
@@ -91,6 +149,90 @@ where
         self
     }
 
+    /// Spawn the Scrubber Service, checking the integrity of the stored files in the background,
+    /// if configured.
+    ///
+    /// Cannot be called before setting up the File Storage.
+    /// Call [`setup_storage_layer`](StorageHubBuilder::setup_storage_layer) before calling this method.
+    pub async fn with_scrubber(&mut self, scrubber_config: Option<ScrubberConfig>) -> &mut Self {
+        let Some(scrubber_config) = scrubber_config else {
+            return self;
+        };
+
+        let file_storage = self
+            .file_storage
+            .clone()
+            .expect("`with_scrubber` should be called after setting up the File Storage. Use `setup_storage_layer` first.");
+        let scrubber_service_handle = spawn_scrubber_service(
+            self.task_spawner
+                .as_ref()
+                .expect("Task spawner is not set."),
+            file_storage,
+            scrubber_config,
+        )
+        .await;
+
+        self.scrubber = Some(scrubber_service_handle);
+        self
+    }
+
+    /// Spawn the Disk Watchdog Service, monitoring the space of the disk the files are stored in,
+    /// if configured.
+    pub async fn with_disk_watchdog(
+        &mut self,
+        disk_watchdog_config: Option<DiskWatchdogConfig>,
+    ) -> &mut Self {
+        let Some(disk_watchdog_config) = disk_watchdog_config else {
+            return self;
+        };
+
+        let disk_watchdog_service_handle = spawn_disk_watchdog_service(
+            self.task_spawner
+                .as_ref()
+                .expect("Task spawner is not set."),
+            disk_watchdog_config,
+        )
+        .await;
+
+        self.disk_watchdog = Some(disk_watchdog_service_handle);
+        self
+    }
+
+    /// Spawn the Config Watcher Service, reloading the settings of the node from its configuration
+    /// file while it runs, if configured.
+    ///
+    /// The configuration file can be reloaded through the RPC methods, so this method has to be
+    /// called before [`create_rpc_config`](StorageHubBuilder::create_rpc_config).
+    pub async fn with_config_watcher(
+        &mut self,
+        config_watcher_config: Option<ConfigWatcherConfig>,
+    ) -> &mut Self {
+        let Some(config_watcher_config) = config_watcher_config else {
+            return self;
+        };
+
+        let config_watcher_service_handle = spawn_config_watcher_service(
+            self.task_spawner
+                .as_ref()
+                .expect("Task spawner is not set."),
+            config_watcher_config,
+        )
+        .await;
+
+        self.config_watcher = Some(config_watcher_service_handle);
+        self
+    }
+
+    /// Set whether to lower the on-chain capacity automatically when it exceeds what the disk can
+    /// hold.
+    ///
+    /// Only has an effect if the Disk Watchdog Service is spawned, with
+    /// [`with_disk_watchdog`](StorageHubBuilder::with_disk_watchdog).
+    pub fn with_auto_reconcile_capacity(&mut self, auto_reconcile_capacity: bool) -> &mut Self {
+        self.auto_reconcile_capacity = auto_reconcile_capacity;
+        self
+    }
+
     /// Set the maximum storage capacity.
     ///
     /// The node will not increase its on-chain capacity above this value.
@@ -114,6 +256,28 @@ where
         self
     }
 
+    /// Coordinate the volunteering of this BSP with the other BSPs of the same operator.
+    ///
+    /// Only used by BSPs.
+    pub fn with_fleet_coordination(
+        &mut self,
+        fleet_coordination: Option<FleetCoordinationConfig>,
+    ) -> &mut Self {
+        self.fleet_coordination = fleet_coordination;
+        self
+    }
+
+    /// Set the policy deciding which storage requests this BSP volunteers for.
+    ///
+    /// The policy can be replaced while the node runs through the RPC methods, so this method has
+    /// to be called before [`create_rpc_config`](StorageHubBuilder::create_rpc_config).
+    ///
+    /// Only used by BSPs.
+    pub fn with_volunteer_policy(&mut self, volunteer_policy: VolunteerPolicy) -> &mut Self {
+        self.volunteer_policy = Some(VolunteerPolicyEngine::new(volunteer_policy));
+        self
+    }
+
     /// Set the timeout for retrying extrinsics.
     ///
     /// The default value is `60` seconds.
@@ -122,6 +286,18 @@ where
         self
     }
 
+    /// Set the time the files and forest of a bucket moved away from an MSP are kept for after the
+    /// move is finalised, in seconds.
+    ///
+    /// The default value is `86400` seconds (a day).
+    pub fn with_bucket_gc_retention_period(
+        &mut self,
+        bucket_gc_retention_period: u64,
+    ) -> &mut Self {
+        self.bucket_gc_retention_period = bucket_gc_retention_period;
+        self
+    }
+
     /// Add an alert notification for every X blocks to the Blockchain Service.
     ///
     /// Cannot be added if the Blockchain Service has already been spawned.
@@ -133,6 +309,74 @@ where
         self
     }
 
+    /// Set the configuration of the Forest Storages on disk.
+    ///
+    /// Cannot be set if the Forest Storage Handler has already been set up.
+    pub fn with_forest_storage_config(
+        &mut self,
+        forest_storage_config: ForestStorageConfig,
+    ) -> &mut Self {
+        if self.forest_storage_handler.is_some() {
+            panic!("`with_forest_storage_config` should be called before setting up the Forest Storage Handler. Use `setup_storage_layer` after calling `with_forest_storage_config`.");
+        }
+        self.forest_storage_config = forest_storage_config;
+        self
+    }
+
+    /// Set how long a task can hold the Forest root write lock before the Blockchain Service
+    /// warns that it might be stuck.
+    ///
+    /// Cannot be set if the Blockchain Service has already been spawned.
+    pub fn with_forest_root_write_lock_warn_threshold(
+        &mut self,
+        forest_root_write_lock_warn_threshold: Duration,
+    ) -> &mut Self {
+        if self.blockchain.is_some() {
+            panic!("`with_forest_root_write_lock_warn_threshold` should be called before starting the Blockchain Service. Use `with_blockchain` after calling `with_forest_root_write_lock_warn_threshold`.");
+        }
+        self.forest_root_write_lock_warn_threshold = forest_root_write_lock_warn_threshold;
+        self
+    }
+
+    /// Run the Blockchain Service in replay mode, replaying the given range of finalised blocks
+    /// instead of following the chain, and without submitting extrinsics.
+    ///
+    /// Cannot be set if the Blockchain Service has already been spawned.
+    pub fn with_replay_range(&mut self, replay_range: Option<ReplayRange>) -> &mut Self {
+        if self.blockchain.is_some() {
+            panic!("`with_replay_range` should be called before starting the Blockchain Service. Use `with_blockchain` after calling `with_replay_range`.");
+        }
+        self.replay_range = replay_range;
+        self
+    }
+
+    /// Set the TTLs of the cached responses of the runtime API queries made by the Blockchain
+    /// Service on behalf of tasks.
+    ///
+    /// Cannot be set if the Blockchain Service has already been spawned.
+    pub fn with_runtime_api_cache_config(
+        &mut self,
+        runtime_api_cache_config: RuntimeApiCacheConfig,
+    ) -> &mut Self {
+        if self.blockchain.is_some() {
+            panic!("`with_runtime_api_cache_config` should be called before starting the Blockchain Service. Use `with_blockchain` after calling `with_runtime_api_cache_config`.");
+        }
+        self.runtime_api_cache_config = runtime_api_cache_config;
+        self
+    }
+
+    /// Set how long the Blockchain Service waits for new finalised blocks before entering safe
+    /// mode.
+    ///
+    /// Cannot be set if the Blockchain Service has already been spawned.
+    pub fn with_safe_mode_config(&mut self, safe_mode_config: SafeModeConfig) -> &mut Self {
+        if self.blockchain.is_some() {
+            panic!("`with_safe_mode_config` should be called before starting the Blockchain Service. Use `with_blockchain` after calling `with_safe_mode_config`.");
+        }
+        self.safe_mode_config = safe_mode_config;
+        self
+    }
+
     /// Spawn the Blockchain Service.
     ///
     /// Cannot be called before setting the Forest Storage Handler.
@@ -164,6 +408,10 @@ where
             forest_storage_handler,
             rocksdb_root_path,
             self.notify_period,
+            self.forest_root_write_lock_warn_threshold,
+            self.replay_range,
+            self.runtime_api_cache_config.clone(),
+            self.safe_mode_config,
         )
         .await;
 
@@ -171,10 +419,154 @@ where
         self
     }
 
+    /// Set the configuration to export OpenTelemetry traces and metrics.
+    ///
+    /// The export is started with [`init_open_telemetry`](StorageHubBuilder::init_open_telemetry).
+    pub fn with_open_telemetry(
+        &mut self,
+        open_telemetry_config: Option<OpenTelemetryConfig>,
+    ) -> &mut Self {
+        self.open_telemetry_config = open_telemetry_config;
+        self
+    }
+
+    /// Start exporting OpenTelemetry traces and metrics, if configured.
+    ///
+    /// The Provider ID of the node is added to the resource attributes if it is already
+    /// registered as a Provider, so it needs the Blockchain Service to be spawned.
+    /// Call [`with_blockchain`](StorageHubBuilder::with_blockchain) before calling this method.
+    pub async fn init_open_telemetry(&mut self) -> &mut Self {
+        let mut open_telemetry_config = match self.open_telemetry_config.take() {
+            Some(config) => config,
+            None => return self,
+        };
+
+        let blockchain = self
+            .blockchain
+            .as_ref()
+            .expect("`init_open_telemetry` should be called after spawning the Blockchain Service. Use `with_blockchain` first.");
+        open_telemetry_config.provider_id = match blockchain.query_storage_provider_id(None).await {
+            Ok(Some(StorageProviderId::BackupStorageProvider(id)))
+            | Ok(Some(StorageProviderId::MainStorageProvider(id))) => Some(format!("{:?}", id)),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!(
+                    "Failed to query the Provider ID of this node for OpenTelemetry: {:?}",
+                    e
+                );
+                None
+            }
+        };
+
+        if let Err(e) = init_open_telemetry(&open_telemetry_config) {
+            log::error!("Failed to initialise OpenTelemetry export: {}", e);
+        }
+
+        self
+    }
+
+    /// Set the configuration to aggregate the earnings of this Provider locally.
+    ///
+    /// The aggregation is started with
+    /// [`spawn_provider_earnings`](StorageHubBuilder::spawn_provider_earnings).
+    pub fn with_provider_earnings(
+        &mut self,
+        provider_earnings_config: Option<ProviderEarningsConfig>,
+    ) -> &mut Self {
+        self.provider_earnings_config = provider_earnings_config;
+        self
+    }
+
+    /// Start aggregating the earnings of this Provider from finalised blocks, if configured.
+    ///
+    /// The Provider ID is taken from the Blockchain Service, so it needs to be spawned.
+    /// Call [`with_blockchain`](StorageHubBuilder::with_blockchain) before calling this method.
+    pub fn spawn_provider_earnings(&mut self, client: Arc<ParachainClient>) -> &mut Self {
+        let Some(provider_earnings_config) = self.provider_earnings_config.take() else {
+            return self;
+        };
+
+        let blockchain = self
+            .blockchain
+            .clone()
+            .expect("`spawn_provider_earnings` should be called after spawning the Blockchain Service. Use `with_blockchain` first.");
+        let provider_earnings_service =
+            ProviderEarningsService::new(client, blockchain, provider_earnings_config);
+
+        self.task_spawner
+            .as_ref()
+            .expect("Task spawner is not set.")
+            .spawn(provider_earnings_service.run());
+
+        self
+    }
+
+    /// Set the configuration of the HTTP Gateway serving the files stored by this MSP.
+    ///
+    /// The HTTP Gateway is started with
+    /// [`spawn_http_gateway`](StorageHubBuilder::spawn_http_gateway).
+    pub fn with_http_gateway(
+        &mut self,
+        http_gateway_config: Option<HttpGatewayConfig>,
+    ) -> &mut Self {
+        self.http_gateway_config = http_gateway_config;
+        self
+    }
+
+    /// Start serving the files stored by this MSP over HTTP(S), if configured.
+    ///
+    /// The read access to the files is checked through the Blockchain Service, so it needs to be
+    /// spawned. Call [`with_blockchain`](StorageHubBuilder::with_blockchain) before calling this
+    /// method.
+    pub fn spawn_http_gateway(&mut self) -> &mut Self {
+        let Some(http_gateway_config) = self.http_gateway_config.take() else {
+            return self;
+        };
+
+        let blockchain = self
+            .blockchain
+            .clone()
+            .expect("`spawn_http_gateway` should be called after spawning the Blockchain Service. Use `with_blockchain` first.");
+        let file_storage = self
+            .file_storage
+            .clone()
+            .expect("`spawn_http_gateway` should be called after setting up the File Storage. Use `setup_storage_layer` first.");
+        let forest_storage_handler = self
+            .forest_storage_handler
+            .clone()
+            .expect("`spawn_http_gateway` should be called after setting up the Forest Storage. Use `setup_storage_layer` first.");
+        let http_gateway = HttpGateway::new(
+            blockchain,
+            file_storage,
+            forest_storage_handler,
+            http_gateway_config,
+        );
+
+        self.task_spawner
+            .as_ref()
+            .expect("Task spawner is not set.")
+            .spawn(http_gateway.run());
+
+        self
+    }
+
+    /// Set up the [`JobQueue`] running the long-running operations of the node, persisting its
+    /// jobs in `store`.
+    pub fn with_job_queue(&mut self, store: JobStore) -> &mut Self {
+        let task_spawner = self
+            .task_spawner
+            .as_ref()
+            .expect("Task Spawner not set")
+            .with_name("sh-job-queue");
+        self.job_queue = Some(JobQueue::new(store, task_spawner));
+        self
+    }
+
     /// Set the database pool for the Indexer Service.
     ///
     /// The Indexer Service is used by MSP nodes to retrieve information about files
-    /// they are not storing, like which are the BSPs storing them.
+    /// they are not storing, like which are the BSPs storing them. It is also queried by the
+    /// indexer RPC methods.
     pub fn with_indexer_db_pool(&mut self, indexer_db_pool: Option<DbPool>) -> &mut Self {
         self.indexer_db_pool = indexer_db_pool;
         self
@@ -188,7 +580,7 @@ where
         &self,
         keystore: KeystorePtr,
     ) -> StorageHubClientRpcConfig<<(R, S) as ShNodeType>::FL, <(R, S) as ShNodeType>::FSH> {
-        StorageHubClientRpcConfig::new(
+        let rpc_config = StorageHubClientRpcConfig::new(
             self.file_storage
                 .clone()
                 .expect("File Storage not initialized. Use `setup_storage_layer` before calling `create_rpc_config`."),
@@ -196,7 +588,32 @@ where
                 .clone()
                 .expect("Forest Storage Handler not initialized. Use `setup_storage_layer` before calling `create_rpc_config`."),
             keystore,
-        )
+        );
+
+        let rpc_config = match &self.file_transfer {
+            Some(file_transfer) => rpc_config.with_file_transfer(file_transfer.clone()),
+            None => rpc_config,
+        };
+
+        let rpc_config = match &self.volunteer_policy {
+            Some(volunteer_policy) => rpc_config.with_volunteer_policy(volunteer_policy.clone()),
+            None => rpc_config,
+        };
+
+        let rpc_config = match &self.indexer_db_pool {
+            Some(indexer_db_pool) => rpc_config.with_indexer_db_pool(indexer_db_pool.clone()),
+            None => rpc_config,
+        };
+
+        let rpc_config = match &self.config_watcher {
+            Some(config_watcher) => rpc_config.with_config_watcher(config_watcher.clone()),
+            None => rpc_config,
+        };
+
+        match &self.job_queue {
+            Some(job_queue) => rpc_config.with_job_queue(job_queue.clone()),
+            None => rpc_config,
+        }
     }
 }
 
@@ -231,8 +648,12 @@ impl StorageLayerBuilder for StorageHubBuilder<BspProvider, RocksDbStorageLayer>
                 .expect("Failed to create RocksDB");
         self.file_storage = Some(Arc::new(RwLock::new(RocksDbFileStorage::new(file_storage))));
 
-        self.forest_storage_handler =
-            Some(<(BspProvider, RocksDbStorageLayer) as ShNodeType>::FSH::new(storage_path));
+        self.forest_storage_handler = Some(
+            <(BspProvider, RocksDbStorageLayer) as ShNodeType>::FSH::new(
+                storage_path,
+                self.forest_storage_config,
+            ),
+        );
 
         self
     }
@@ -258,8 +679,12 @@ impl StorageLayerBuilder for StorageHubBuilder<MspProvider, RocksDbStorageLayer>
                 .expect("Failed to create RocksDB");
         self.file_storage = Some(Arc::new(RwLock::new(RocksDbFileStorage::new(file_storage))));
 
-        self.forest_storage_handler =
-            Some(<(MspProvider, RocksDbStorageLayer) as ShNodeType>::FSH::new(storage_path));
+        self.forest_storage_handler = Some(
+            <(MspProvider, RocksDbStorageLayer) as ShNodeType>::FSH::new(
+                storage_path,
+                self.forest_storage_config,
+            ),
+        );
 
         self
     }
@@ -306,6 +731,9 @@ where
                 .as_ref()
                 .expect("File Storage not set.")
                 .clone(),
+            self.scrubber.clone(),
+            self.disk_watchdog.clone(),
+            self.config_watcher.clone(),
             self.forest_storage_handler
                 .as_ref()
                 .expect("Forest Storage Handler not set.")
@@ -316,8 +744,16 @@ where
                     .expect("Max Storage Capacity not set"),
                 jump_capacity: self.jump_capacity.expect("Jump Capacity not set"),
                 extrinsic_retry_timeout: self.extrinsic_retry_timeout,
+                fleet_coordination: self.fleet_coordination.clone(),
+                auto_reconcile_capacity: self.auto_reconcile_capacity,
+                bucket_gc_retention_period: self.bucket_gc_retention_period,
+                volunteer_policy: self.volunteer_policy.clone(),
             },
             self.indexer_db_pool.clone(),
+            self.job_queue
+                .as_ref()
+                .expect("Job Queue not set. Use `with_job_queue` before calling `build`.")
+                .clone(),
         )
     }
 }
@@ -345,6 +781,9 @@ where
                 .as_ref()
                 .expect("File Storage not set.")
                 .clone(),
+            self.scrubber.clone(),
+            self.disk_watchdog.clone(),
+            self.config_watcher.clone(),
             self.forest_storage_handler
                 .as_ref()
                 .expect("Forest Storage Handler not set.")
@@ -355,8 +794,16 @@ where
                     .expect("Max Storage Capacity not set"),
                 jump_capacity: self.jump_capacity.expect("Jump Capacity not set"),
                 extrinsic_retry_timeout: self.extrinsic_retry_timeout,
+                fleet_coordination: None,
+                auto_reconcile_capacity: false,
+                bucket_gc_retention_period: self.bucket_gc_retention_period,
+                volunteer_policy: None,
             },
             self.indexer_db_pool.clone(),
+            self.job_queue
+                .as_ref()
+                .expect("Job Queue not set. Use `with_job_queue` before calling `build`.")
+                .clone(),
         )
     }
 }
@@ -385,6 +832,9 @@ where
                 .as_ref()
                 .expect("File Storage not set.")
                 .clone(),
+            self.scrubber.clone(),
+            self.disk_watchdog.clone(),
+            self.config_watcher.clone(),
             // Not used by the user role
             <(UserRole, NoStorageLayer) as ShNodeType>::FSH::new(),
             // Not used by the user role
@@ -392,8 +842,16 @@ where
                 max_storage_capacity: 0,
                 jump_capacity: 0,
                 extrinsic_retry_timeout: self.extrinsic_retry_timeout,
+                fleet_coordination: None,
+                auto_reconcile_capacity: false,
+                bucket_gc_retention_period: 0,
+                volunteer_policy: None,
             },
             self.indexer_db_pool.clone(),
+            self.job_queue
+                .as_ref()
+                .expect("Job Queue not set. Use `with_job_queue` before calling `build`.")
+                .clone(),
         )
     }
 }
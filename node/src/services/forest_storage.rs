@@ -5,7 +5,7 @@ use log::error;
 use shc_common::types::StorageProofsMerkleTrieLayout;
 use shc_forest_manager::{
     in_memory::InMemoryForestStorage,
-    rocksdb::{self, RocksDBForestStorage},
+    rocksdb::{self, ForestStorageConfig, RocksDBForestStorage},
     traits::{ForestStorage, ForestStorageHandler},
 };
 use tokio::sync::RwLock;
@@ -19,6 +19,7 @@ where
     FS: ForestStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
 {
     storage_path: Option<String>,
+    config: ForestStorageConfig,
     fs_instance: Arc<RwLock<FS>>,
 }
 
@@ -29,6 +30,7 @@ where
     fn clone(&self) -> Self {
         Self {
             storage_path: self.storage_path.clone(),
+            config: self.config,
             fs_instance: self.fs_instance.clone(),
         }
     }
@@ -38,6 +40,7 @@ impl ForestStorageSingle<InMemoryForestStorage<StorageProofsMerkleTrieLayout>> {
     pub fn new() -> Self {
         Self {
             storage_path: None,
+            config: ForestStorageConfig::default(),
             fs_instance: Arc::new(RwLock::new(InMemoryForestStorage::new())),
         }
     }
@@ -47,14 +50,16 @@ impl
     ForestStorageSingle<RocksDBForestStorage<StorageProofsMerkleTrieLayout, kvdb_rocksdb::Database>>
 {
     #[allow(dead_code)]
-    pub fn new(storage_path: String) -> Self {
+    pub fn new(storage_path: String, config: ForestStorageConfig) -> Self {
         let fs = rocksdb::create_db::<StorageProofsMerkleTrieLayout>(storage_path.clone())
             .expect("Failed to create RocksDB");
 
-        let fs = RocksDBForestStorage::new(fs).expect("Failed to create Forest Storage");
+        let fs =
+            RocksDBForestStorage::with_config(fs, config).expect("Failed to create Forest Storage");
 
         Self {
             storage_path: Some(storage_path),
+            config,
             fs_instance: Arc::new(RwLock::new(fs)),
         }
     }
@@ -121,7 +126,8 @@ impl ForestStorageHandler
         )
         .expect("Failed to create RocksDB");
 
-        let fs = RocksDBForestStorage::new(fs).expect("Failed to create Forest Storage");
+        let fs = RocksDBForestStorage::with_config(fs, self.config)
+            .expect("Failed to create Forest Storage");
 
         let fs = Arc::new(RwLock::new(fs));
         self.fs_instance = fs.clone();
@@ -149,6 +155,7 @@ where
     FS: ForestStorage<StorageProofsMerkleTrieLayout> + Send + Sync,
 {
     storage_path: Option<String>,
+    config: ForestStorageConfig,
     fs_instances: Arc<RwLock<HashMap<K, Arc<RwLock<FS>>>>>,
 }
 
@@ -160,6 +167,7 @@ where
     fn clone(&self) -> Self {
         Self {
             storage_path: self.storage_path.clone(),
+            config: self.config,
             fs_instances: self.fs_instances.clone(),
         }
     }
@@ -172,6 +180,7 @@ where
     pub fn new() -> Self {
         Self {
             storage_path: None,
+            config: ForestStorageConfig::default(),
             fs_instances: Arc::new(RwLock::new(HashMap::new())),
         }
     }
@@ -185,9 +194,10 @@ impl<K>
 where
     K: Eq + Hash + Send + Sync,
 {
-    pub fn new(storage_path: String) -> Self {
+    pub fn new(storage_path: String, config: ForestStorageConfig) -> Self {
         Self {
             storage_path: Some(storage_path),
+            config,
             fs_instances: Arc::new(RwLock::new(HashMap::new())),
         }
     }
@@ -290,8 +300,8 @@ where
             rocksdb::create_db::<StorageProofsMerkleTrieLayout>(new_db_storage_path)
                 .expect("Failed to create RocksDB");
 
-        let forest_storage =
-            RocksDBForestStorage::new(underlying_db).expect("Failed to create Forest Storage");
+        let forest_storage = RocksDBForestStorage::with_config(underlying_db, self.config)
+            .expect("Failed to create Forest Storage");
 
         let forest_storage = Arc::new(RwLock::new(forest_storage));
 
@@ -340,8 +350,8 @@ where
         drop(src_fs);
 
         // Create and insert new Forest Storage instance for the destination Forest Storage.
-        let forest_storage =
-            RocksDBForestStorage::new(underlying_db).expect("Failed to create Forest Storage");
+        let forest_storage = RocksDBForestStorage::with_config(underlying_db, self.config)
+            .expect("Failed to create Forest Storage");
         let forest_storage = Arc::new(RwLock::new(forest_storage));
         fs_instances.insert(dest_key.clone(), forest_storage.clone());
 
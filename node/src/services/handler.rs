@@ -1,37 +1,47 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tokio::sync::RwLock;
 
 use shc_actors_framework::{
     actor::{ActorHandle, TaskSpawner},
-    event_bus::{EventBusListener, EventHandler},
+    event_bus::{EventBusListener, EventHandler, OverflowPolicy},
 };
 use shc_blockchain_service::{
     events::{
-        AcceptedBspVolunteer, BspConfirmStoppedStoring, FinalisedBspConfirmStoppedStoring,
+        AcceptedBspVolunteer, BspConfirmStoppedStoring, BspConfirmedStoring,
+        FinalisedBspConfirmStoppedStoring, FinalisedMoveBucketAccepted,
         FinalisedMspStoppedStoringBucket, LastChargeableInfoUpdated, MoveBucketAccepted,
         MoveBucketExpired, MoveBucketRejected, MoveBucketRequested, MoveBucketRequestedForNewMsp,
         MultipleNewChallengeSeeds, NewStorageRequest, NotifyPeriod, ProcessConfirmStoringRequest,
         ProcessMspRespondStoringRequest, ProcessStopStoringForInsolventUserRequest,
-        ProcessSubmitProofRequest, SlashableProvider, SpStopStoringInsolventUser, UserWithoutFunds,
+        ProcessSubmitProofRequest, Reverted, SlashableProvider, SpStopStoringInsolventUser,
+        UserWithoutFunds,
     },
     BlockchainService,
 };
-use shc_common::consts::CURRENT_FOREST_KEY;
+use shc_common::{consts::CURRENT_FOREST_KEY, types::BackupStorageProviderId};
+use shc_config_watcher::{events::ConfigUpdated, ConfigWatcherService};
+use shc_disk_watchdog::{events::DiskSpaceChecked, DiskWatchdogService};
 use shc_file_transfer_service::{
     events::{RemoteDownloadRequest, RemoteUploadRequest},
     FileTransferService,
 };
 use shc_forest_manager::traits::ForestStorageHandler;
 use shc_indexer_db::DbPool;
+use shc_job_queue::JobQueue;
+use shc_scrubber_service::{events::CorruptChunkDetected, ScrubberService};
+use shc_volunteer_policy::VolunteerPolicyEngine;
 use storage_hub_runtime::StorageDataUnit;
 
 use crate::tasks::{
     bsp_charge_fees::BspChargeFeesTask, bsp_delete_file::BspDeleteFileTask,
     bsp_download_file::BspDownloadFileTask, bsp_move_bucket::BspMoveBucketTask,
-    bsp_submit_proof::BspSubmitProofTask, bsp_upload_file::BspUploadFileTask,
+    bsp_reconcile_capacity::BspReconcileCapacityTask, bsp_submit_proof::BspSubmitProofTask,
+    bsp_upload_file::BspUploadFileTask, msp_bucket_gc::MspBucketGcTask,
     msp_charge_fees::MspChargeFeesTask, msp_delete_bucket::MspStoppedStoringTask,
     msp_move_bucket::MspMoveBucketTask, msp_upload_file::MspUploadFileTask,
+    reload_config::ReloadConfigTask, sp_repair_file::SpRepairFileTask,
     sp_slash_provider::SlashProviderTask, user_sends_file::UserSendsFileTask,
+    user_upload_eta::UserUploadEtaTask,
 };
 
 use super::types::{
@@ -39,6 +49,11 @@ use super::types::{
     ShStorageLayer, UserRole,
 };
 
+/// Maximum amount of NewStorageRequest events handled at the same time by an MSP.
+const MSP_MAX_CONCURRENT_NEW_STORAGE_REQUESTS: usize = 100;
+/// Maximum amount of NewStorageRequest events waiting to be handled by an MSP.
+const MSP_NEW_STORAGE_REQUEST_QUEUE_CAPACITY: usize = 1000;
+
 /// Configuration parameters for Storage Providers.
 #[derive(Clone)]
 pub struct ProviderConfig {
@@ -52,6 +67,29 @@ pub struct ProviderConfig {
     pub jump_capacity: StorageDataUnit,
     /// The time in seconds to wait before retrying an extrinsic.
     pub extrinsic_retry_timeout: u64,
+    /// Coordination of the volunteering with the other BSPs of the same operator, if enabled.
+    pub fleet_coordination: Option<FleetCoordinationConfig>,
+    /// Whether to lower the on-chain capacity of a BSP automatically when it exceeds what its disk
+    /// can hold, as checked by the Disk Watchdog Service.
+    pub auto_reconcile_capacity: bool,
+    /// Time the files and forest of a bucket moved away from an MSP are kept for after the move is
+    /// finalised, before being deleted (seconds).
+    pub bucket_gc_retention_period: u64,
+    /// Policy deciding which storage requests a BSP volunteers for. Only used by BSPs.
+    pub volunteer_policy: Option<VolunteerPolicyEngine>,
+}
+
+/// Configuration to coordinate the volunteering of the BSPs run by the same operator, so that no
+/// more than [`max_volunteers_per_file`](Self::max_volunteers_per_file) of them store the same file.
+///
+/// The BSPs of the fleet do not talk to each other: each one looks up which BSPs of the fleet
+/// volunteered for a file on-chain before volunteering itself.
+#[derive(Clone, Debug)]
+pub struct FleetCoordinationConfig {
+    /// The BSPs of the fleet. It can include this BSP.
+    pub fleet_bsp_ids: HashSet<BackupStorageProviderId>,
+    /// Maximum amount of BSPs of the fleet that volunteer for the same file.
+    pub max_volunteers_per_file: u32,
 }
 
 /// Represents the handler for the Storage Hub service.
@@ -67,12 +105,20 @@ where
     pub blockchain: ActorHandle<BlockchainService<NT::FSH>>,
     /// The file storage layer which stores all files in chunks.
     pub file_storage: Arc<RwLock<NT::FL>>,
+    /// The actor handle for the scrubber service, if the integrity of the stored files is checked.
+    pub scrubber: Option<ActorHandle<ScrubberService<NT::FL>>>,
+    /// The actor handle for the disk watchdog service, if the space of the disk is monitored.
+    pub disk_watchdog: Option<ActorHandle<DiskWatchdogService>>,
+    /// The actor handle for the config watcher service, if the configuration file is reloadable.
+    pub config_watcher: Option<ActorHandle<ConfigWatcherService>>,
     /// The forest storage layer which tracks all complete files stored in the file storage layer.
     pub forest_storage_handler: NT::FSH,
     /// The configuration parameters for the provider.
     pub provider_config: ProviderConfig,
     /// The indexer database pool.
     pub indexer_db_pool: Option<DbPool>,
+    /// The queue running the long-running operations of the node, like downloading moved buckets.
+    pub job_queue: JobQueue,
 }
 
 impl<NT> Clone for StorageHubHandler<NT>
@@ -85,9 +131,13 @@ where
             file_transfer: self.file_transfer.clone(),
             blockchain: self.blockchain.clone(),
             file_storage: self.file_storage.clone(),
+            scrubber: self.scrubber.clone(),
+            disk_watchdog: self.disk_watchdog.clone(),
+            config_watcher: self.config_watcher.clone(),
             forest_storage_handler: self.forest_storage_handler.clone(),
             provider_config: self.provider_config.clone(),
             indexer_db_pool: self.indexer_db_pool.clone(),
+            job_queue: self.job_queue.clone(),
         }
     }
 }
@@ -101,18 +151,26 @@ where
         file_transfer: ActorHandle<FileTransferService>,
         blockchain: ActorHandle<BlockchainService<NT::FSH>>,
         file_storage: Arc<RwLock<NT::FL>>,
+        scrubber: Option<ActorHandle<ScrubberService<NT::FL>>>,
+        disk_watchdog: Option<ActorHandle<DiskWatchdogService>>,
+        config_watcher: Option<ActorHandle<ConfigWatcherService>>,
         forest_storage_handler: NT::FSH,
         provider_config: ProviderConfig,
         indexer_db_pool: Option<DbPool>,
+        job_queue: JobQueue,
     ) -> Self {
         Self {
             task_spawner,
             file_transfer,
             blockchain,
             file_storage,
+            scrubber,
+            disk_watchdog,
+            config_watcher,
             forest_storage_handler,
             provider_config,
             indexer_db_pool,
+            job_queue,
         }
     }
 }
@@ -176,6 +234,28 @@ where
                 .clone()
                 .subscribe_to(&self.task_spawner, &self.blockchain);
         accepted_bsp_volunteer_event_bus_listener.start();
+
+        // UserUploadEtaTask gives feedback on how long until the storage requests of this user
+        // reach their replication target.
+        let user_upload_eta_task = UserUploadEtaTask::new(self.clone());
+        // Subscribing to NewStorageRequest event from the BlockchainService.
+        let new_storage_request_event_bus_listener: EventBusListener<NewStorageRequest, _> =
+            user_upload_eta_task
+                .clone()
+                .subscribe_to(&self.task_spawner, &self.blockchain);
+        new_storage_request_event_bus_listener.start();
+        // Subscribing to BspConfirmedStoring event from the BlockchainService.
+        let bsp_confirmed_storing_event_bus_listener: EventBusListener<BspConfirmedStoring, _> =
+            user_upload_eta_task
+                .clone()
+                .subscribe_to(&self.task_spawner, &self.blockchain);
+        bsp_confirmed_storing_event_bus_listener.start();
+
+        // Generate load to benchmark the Providers, if a load scenario is set.
+        #[cfg(feature = "load-generator")]
+        crate::load_generator::spawn_if_configured(self.clone());
+
+        self.start_reload_config_task();
     }
 }
 
@@ -193,10 +273,15 @@ where
         // Finally once the ProcessMspRespondStoringRequest event is emitted, the MSP will respond to the user with a confirmation.
         let msp_upload_file_task = MspUploadFileTask::new(self.clone());
         // Subscribing to NewStorageRequest event from the BlockchainService.
+        // Storage requests can come in bursts, so their handling is bounded to keep the memory of the
+        // MSP in check. Requests that don't fit are dropped, and the MSP doesn't accept them.
         let new_storage_request_event_bus_listener: EventBusListener<NewStorageRequest, _> =
             msp_upload_file_task
                 .clone()
-                .subscribe_to(&self.task_spawner, &self.blockchain);
+                .subscribe_to(&self.task_spawner, &self.blockchain)
+                .with_max_concurrent_handlers(MSP_MAX_CONCURRENT_NEW_STORAGE_REQUESTS)
+                .with_queue_capacity(MSP_NEW_STORAGE_REQUEST_QUEUE_CAPACITY)
+                .with_overflow_policy(OverflowPolicy::DropNewest);
         new_storage_request_event_bus_listener.start();
         // Subscribing to RemoteUploadRequest event from the FileTransferService.
         let remote_upload_request_event_bus_listener: EventBusListener<RemoteUploadRequest, _> =
@@ -212,6 +297,15 @@ where
             .clone()
             .subscribe_to(&self.task_spawner, &self.blockchain);
         process_confirm_storing_request_event_bus_listener.start();
+        // Subscribing to the Reverted NewStorageRequest event from the BlockchainService, to undo
+        // the registration of files whose storage request was reorged out.
+        let reverted_new_storage_request_event_bus_listener: EventBusListener<
+            Reverted<NewStorageRequest>,
+            _,
+        > = msp_upload_file_task
+            .clone()
+            .subscribe_to(&self.task_spawner, &self.blockchain);
+        reverted_new_storage_request_event_bus_listener.start();
 
         // MspStoppedStoringTask handles events for handling data deletion.
         let msp_stopped_storing_task = MspStoppedStoringTask::new(self.clone());
@@ -224,6 +318,25 @@ where
             .subscribe_to(&self.task_spawner, &self.blockchain);
         finalised_msp_stopped_storing_bucket_event_bus_listener.start();
 
+        // MspBucketGcTask handles the deletion of the buckets moved away from this MSP.
+        let msp_bucket_gc_task = MspBucketGcTask::new(self.clone());
+        // Subscribing to FinalisedMoveBucketAccepted event from the BlockchainService.
+        let finalised_move_bucket_accepted_event_bus_listener: EventBusListener<
+            FinalisedMoveBucketAccepted,
+            _,
+        > = msp_bucket_gc_task
+            .clone()
+            .subscribe_to(&self.task_spawner, &self.blockchain);
+        finalised_move_bucket_accepted_event_bus_listener.start();
+        // Running the jobs deleting the buckets moved away from this MSP once their retention
+        // period has passed, and resuming the ones interrupted by a restart.
+        if let Err(e) = self.job_queue.register(msp_bucket_gc_task.clone()) {
+            log::error!(
+                "Failed to resume the jobs deleting moved away buckets: {:?}",
+                e
+            );
+        }
+
         // MspMoveBucketTask handles events for moving buckets to a new MSP.
         let msp_move_bucket_task = MspMoveBucketTask::new(self.clone());
         // Subscribing to MoveBucketRequestedForNewMsp event from the FileTransferService.
@@ -234,6 +347,14 @@ where
             .clone()
             .subscribe_to(&self.task_spawner, &self.blockchain);
         move_bucket_requested_for_new_msp_event_bus_listener.start();
+        // Running the jobs downloading the files of the buckets moved to this MSP, and resuming
+        // the ones interrupted by a restart.
+        if let Err(e) = self.job_queue.register(msp_move_bucket_task.clone()) {
+            log::error!(
+                "Failed to resume the jobs downloading moved buckets: {:?}",
+                e
+            );
+        }
         let msp_charge_fees_task = MspChargeFeesTask::new(self.clone());
 
         // Subscribing to NewStorageRequest event from the BlockchainService.
@@ -242,6 +363,9 @@ where
                 .clone()
                 .subscribe_to(&self.task_spawner, &self.blockchain);
         notify_period_event_bus_listener.start();
+
+        self.start_sp_repair_file_task();
+        self.start_reload_config_task();
     }
 }
 
@@ -291,6 +415,15 @@ where
             .clone()
             .subscribe_to(&self.task_spawner, &self.blockchain);
         process_confirm_storing_request_event_bus_listener.start();
+        // Subscribing to the Reverted NewStorageRequest event from the BlockchainService, to
+        // unvolunteer the files whose storage request was reorged out.
+        let reverted_new_storage_request_event_bus_listener: EventBusListener<
+            Reverted<NewStorageRequest>,
+            _,
+        > = bsp_upload_file_task
+            .clone()
+            .subscribe_to(&self.task_spawner, &self.blockchain);
+        reverted_new_storage_request_event_bus_listener.start();
 
         // The BspDownloadFileTask
         let bsp_download_file_task = BspDownloadFileTask::new(self.clone());
@@ -416,5 +549,58 @@ where
             .clone()
             .subscribe_to(&self.task_spawner, &self.blockchain);
         finalised_bsp_confirm_stopped_storing_event_bus_listener.start();
+
+        // BspReconcileCapacityTask is triggered by a DiskSpaceChecked event, to which it responds
+        // by checking that the on-chain capacity of the BSP fits in its disk, lowering it if it
+        // doesn't and automatic reconciliation is enabled.
+        if let Some(disk_watchdog) = &self.disk_watchdog {
+            let bsp_reconcile_capacity_task = BspReconcileCapacityTask::new(self.clone());
+            // Subscribing to DiskSpaceChecked event from the DiskWatchdogService.
+            let disk_space_checked_event_bus_listener: EventBusListener<DiskSpaceChecked, _> =
+                bsp_reconcile_capacity_task
+                    .clone()
+                    .subscribe_to(&self.task_spawner, disk_watchdog);
+            disk_space_checked_event_bus_listener.start();
+        }
+
+        self.start_sp_repair_file_task();
+        self.start_reload_config_task();
+    }
+}
+
+impl<NT> StorageHubHandler<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    /// Starts the task repairing the files found to be corrupt by the Scrubber Service, if the
+    /// integrity of the stored files is checked.
+    fn start_sp_repair_file_task(&self) {
+        let Some(scrubber) = &self.scrubber else {
+            return;
+        };
+
+        let sp_repair_file_task = SpRepairFileTask::new(self.clone());
+        // Subscribing to CorruptChunkDetected event from the ScrubberService.
+        let corrupt_chunk_detected_event_bus_listener: EventBusListener<CorruptChunkDetected, _> =
+            sp_repair_file_task
+                .clone()
+                .subscribe_to(&self.task_spawner, scrubber);
+        corrupt_chunk_detected_event_bus_listener.start();
+    }
+
+    /// Starts the task applying the settings reloaded from the configuration file, if it is
+    /// reloadable.
+    fn start_reload_config_task(&self) {
+        let Some(config_watcher) = &self.config_watcher else {
+            return;
+        };
+
+        let reload_config_task = ReloadConfigTask::new(self.clone());
+        // Subscribing to ConfigUpdated event from the ConfigWatcherService.
+        let config_updated_event_bus_listener: EventBusListener<ConfigUpdated, _> =
+            reload_config_task
+                .clone()
+                .subscribe_to(&self.task_spawner, config_watcher);
+        config_updated_event_bus_listener.start();
     }
 }
@@ -10,13 +10,17 @@ use shc_blockchain_service::{
         LastChargeableInfoUpdated, ProcessStopStoringForInsolventUserRequest,
         SpStopStoringInsolventUser, UserWithoutFunds,
     },
+    pending_operations::OperationKey,
     types::{StopStoringForInsolventUserRequest, Tip},
 };
 use shc_common::{
     consts::CURRENT_FOREST_KEY,
     types::{MaxUsersToCharge, ProofsDealerProviderId},
 };
-use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_forest_manager::{
+    traits::{ForestStorage, ForestStorageHandler},
+    write_batch::ForestWriteBatch,
+};
 use sp_core::{Get, H256};
 use storage_hub_runtime::Balance;
 
@@ -82,7 +86,7 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: LastChargeableInfoUpdated) -> anyhow::Result<()> {
-        info!(target: LOG_TARGET, "A proof was accepted for provider {:?} and users' fees are going to be charged.", event.provider_id);
+        info!(target: LOG_TARGET, provider_id = ?event.provider_id, "A proof was accepted and users' fees are going to be charged.");
 
         // TODO: Allow for customizable threshold, for example using YAML files.
         // Retrieves users with debt over the `min_debt` threshold
@@ -275,8 +279,35 @@ where
             .get_files_by_user(&insolvent_user)
             .map_err(|e| anyhow!("Failed to get metadata from Forest: {:?}", e))?;
 
-        if !user_files.is_empty() {
-            let (file_key, metadata) = user_files.first().expect("User files is not empty");
+        // Skip the files for which this node still has an extrinsic in flight (i.e. it is still
+        // confirming to store them), as stopping to store them would conflict with it.
+        let mut file_to_stop_storing = None;
+        for (file_key, metadata) in user_files.iter() {
+            if self
+                .storage_hub_handler
+                .blockchain
+                .is_operation_pending(OperationKey::FileKey(*file_key))
+                .await
+            {
+                debug!(target: LOG_TARGET, "Skipping file key {:?} of insolvent user {:?}, there is an operation in flight for it", file_key, insolvent_user);
+                continue;
+            }
+            file_to_stop_storing = Some((file_key, metadata));
+            break;
+        }
+
+        if file_to_stop_storing.is_none() && !user_files.is_empty() {
+            // Every file of the user has an operation in flight, so try again later.
+            info!(target: LOG_TARGET, "All files of insolvent user {:?} have operations in flight, deferring stop storing", insolvent_user);
+            self.storage_hub_handler
+                .blockchain
+                .queue_stop_storing_for_insolvent_user_request(
+                    StopStoringForInsolventUserRequest::new(insolvent_user.clone()),
+                )
+                .await?;
+        }
+
+        if let Some((file_key, metadata)) = file_to_stop_storing {
             let bucket_id = H256::from_slice(metadata.bucket_id.as_ref());
             let location = sp_runtime::BoundedVec::truncate_from(metadata.location.clone());
             let owner = insolvent_user.clone();
@@ -391,13 +422,20 @@ where
                 .await
                 .ok_or_else(|| anyhow!("Failed to get forest storage."))?;
 
-            fs.write().await.delete_file_key(file_key).map_err(|e| {
+            let mut batch = ForestWriteBatch::lock(fs).await;
+            batch.delete_file_key(file_key).map_err(|e| {
                 error!(target: LOG_TARGET, "CRITICAL❗️❗️ Failed to apply mutation to Forest storage. This may result in a mismatch between the Forest root on-chain and in this node. \nThis is a critical bug. Please report it to the StorageHub team. \nError: {:?}", e);
                 anyhow!(
                     "Failed to remove file key from Forest storage: {:?}",
                     e
                 )
             })?;
+
+            // Write the mutation before checking the new Forest root against the one on-chain.
+            batch.flush().map_err(|e| {
+                error!(target: LOG_TARGET, "CRITICAL❗️❗️ Failed to write mutations to Forest storage. This may result in a mismatch between the Forest root on-chain and in this node. \nThis is a critical bug. Please report it to the StorageHub team. \nError: {:?}", e);
+                anyhow!("Failed to write mutations to Forest storage: {:?}", e)
+            })?;
         };
 
         Ok(())
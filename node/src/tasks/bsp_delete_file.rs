@@ -93,9 +93,9 @@ where
     async fn handle_event(&mut self, event: BspConfirmStoppedStoring) -> anyhow::Result<()> {
         info!(
             target: LOG_TARGET,
-            "Deleting file {:?} for BSP {:?}",
-            event.file_key,
-            event.bsp_id
+            provider_id = ?event.bsp_id,
+            file_key = ?event.file_key,
+            "Deleting file"
         );
 
         // Remove the file from the forest.
@@ -103,8 +103,9 @@ where
 
         info!(
             target: LOG_TARGET,
-            "File {:?} successfully removed from forest",
-            event.file_key,
+            provider_id = ?event.bsp_id,
+            file_key = ?event.file_key,
+            "File successfully removed from forest"
         );
 
         Ok(())
@@ -122,9 +123,9 @@ where
     ) -> anyhow::Result<()> {
         info!(
             target: LOG_TARGET,
-            "Deleting file {:?} for BSP {:?}",
-            event.file_key,
-            event.bsp_id
+            provider_id = ?event.bsp_id,
+            file_key = ?event.file_key,
+            "Deleting file"
         );
 
         // Check that the file_key is not in the Forest.
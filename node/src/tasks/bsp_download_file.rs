@@ -54,7 +54,7 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: RemoteDownloadRequest) -> anyhow::Result<()> {
-        trace!(target: LOG_TARGET, "Received remote download request with id {:?} for file {:?}", event.request_id, event.file_key);
+        trace!(target: LOG_TARGET, file_key = ?event.file_key, "Received remote download request with id {:?}", event.request_id);
 
         let RemoteDownloadRequest {
             chunk_id,
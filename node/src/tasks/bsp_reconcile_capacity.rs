@@ -0,0 +1,197 @@
+use anyhow::anyhow;
+use std::{sync::Arc, time::Duration};
+
+use sc_tracing::tracing::*;
+use tokio::sync::Mutex;
+
+use shc_actors_framework::event_bus::EventHandler;
+use shc_blockchain_service::{commands::BlockchainServiceInterface, types::Tip};
+use shc_common::types::{BackupStorageProviderId, StorageProviderId};
+use shc_disk_watchdog::{events::DiskSpaceChecked, DiskSpace};
+use storage_hub_runtime::StorageDataUnit;
+
+use crate::services::{handler::StorageHubHandler, types::ShNodeType};
+
+const LOG_TARGET: &str = "bsp-reconcile-capacity-task";
+
+/// BSP Reconcile Capacity Task: Keeps the on-chain capacity of a BSP consistent with the space of
+/// its disk.
+///
+/// The BSP can physically store the files it already stores, plus as much data as the space
+/// available in its disk. When its on-chain capacity exceeds that, this task:
+/// - Warns about it, since the BSP could volunteer for files it can't store.
+/// - If automatic reconciliation is enabled, submits a `change_capacity` extrinsic lowering the
+///   capacity to what the disk can hold, once the capacity can be changed.
+pub struct BspReconcileCapacityTask<NT>
+where
+    NT: ShNodeType,
+{
+    storage_hub_handler: StorageHubHandler<NT>,
+    /// Held while a capacity change is in flight, so that the checks of the disk arriving in the
+    /// meantime don't submit another one.
+    reconciling: Arc<Mutex<()>>,
+}
+
+impl<NT> Clone for BspReconcileCapacityTask<NT>
+where
+    NT: ShNodeType,
+{
+    fn clone(&self) -> BspReconcileCapacityTask<NT> {
+        Self {
+            storage_hub_handler: self.storage_hub_handler.clone(),
+            reconciling: self.reconciling.clone(),
+        }
+    }
+}
+
+impl<NT> BspReconcileCapacityTask<NT>
+where
+    NT: ShNodeType,
+{
+    pub fn new(storage_hub_handler: StorageHubHandler<NT>) -> Self {
+        Self {
+            storage_hub_handler,
+            reconciling: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+/// Handles the [`DiskSpaceChecked`] event.
+///
+/// This event is triggered periodically by the Disk Watchdog Service with the space of the disk
+/// the files are stored in.
+impl<NT> EventHandler<DiskSpaceChecked> for BspReconcileCapacityTask<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    async fn handle_event(&mut self, event: DiskSpaceChecked) -> anyhow::Result<()> {
+        let Ok(_reconciling) = self.reconciling.try_lock() else {
+            debug!(
+                target: LOG_TARGET,
+                "Capacity change already in flight, skipping disk space check"
+            );
+            return Ok(());
+        };
+
+        let own_bsp_id = match self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_provider_id(None)
+            .await?
+        {
+            Some(StorageProviderId::BackupStorageProvider(id)) => id,
+            // Not registered as a BSP (yet), so there is no on-chain capacity to reconcile.
+            _ => return Ok(()),
+        };
+
+        let exceeded = self
+            .check_capacity(own_bsp_id, event.disk_space)
+            .await?
+            .is_some();
+
+        if !exceeded
+            || !self
+                .storage_hub_handler
+                .provider_config
+                .auto_reconcile_capacity
+        {
+            return Ok(());
+        }
+
+        let earliest_change_capacity_block = self
+            .storage_hub_handler
+            .blockchain
+            .query_earliest_change_capacity_block(own_bsp_id)
+            .await
+            .map_err(|e| anyhow!("Failed to query earliest change capacity block: {:?}", e))?;
+
+        // Wait for the earliest block where the capacity can be changed.
+        self.storage_hub_handler
+            .blockchain
+            .wait_for_block(earliest_change_capacity_block)
+            .await?;
+
+        // The capacity might have changed while waiting.
+        let Some(new_capacity) = self.check_capacity(own_bsp_id, event.disk_space).await? else {
+            return Ok(());
+        };
+
+        let call = storage_hub_runtime::RuntimeCall::Providers(
+            pallet_storage_providers::Call::change_capacity { new_capacity },
+        );
+
+        self.storage_hub_handler
+            .blockchain
+            .send_extrinsic(call, Tip::from(0))
+            .await?
+            .with_timeout(Duration::from_secs(
+                self.storage_hub_handler
+                    .provider_config
+                    .extrinsic_retry_timeout,
+            ))
+            .watch_for_success(&self.storage_hub_handler.blockchain)
+            .await?;
+
+        info!(
+            target: LOG_TARGET,
+            "Decreased storage capacity to {:?} bytes to match the space of the disk",
+            new_capacity
+        );
+
+        Ok(())
+    }
+}
+
+impl<NT> BspReconcileCapacityTask<NT>
+where
+    NT: ShNodeType,
+{
+    /// Compares the on-chain capacity of the BSP with the capacity its disk can hold, warning if it
+    /// exceeds it.
+    ///
+    /// Returns the capacity the disk can hold if the on-chain capacity exceeds it, `None`
+    /// otherwise.
+    async fn check_capacity(
+        &self,
+        own_bsp_id: BackupStorageProviderId,
+        disk_space: DiskSpace,
+    ) -> anyhow::Result<Option<StorageDataUnit>> {
+        let current_capacity = self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_provider_capacity(own_bsp_id)
+            .await
+            .map_err(|e| anyhow!("Failed to query storage provider capacity: {:?}", e))?;
+        let available_capacity = self
+            .storage_hub_handler
+            .blockchain
+            .query_available_storage_capacity(own_bsp_id)
+            .await
+            .map_err(|e| anyhow!("Failed to query available storage capacity: {:?}", e))?;
+
+        // The data already stored is in the disk, so it counts towards what the disk can hold.
+        let used_capacity = current_capacity.saturating_sub(available_capacity);
+        let physical_capacity = used_capacity.saturating_add(disk_space.available_space);
+
+        if current_capacity <= physical_capacity {
+            debug!(
+                target: LOG_TARGET,
+                "On-chain capacity of {} bytes fits in the disk, which can hold {} bytes",
+                current_capacity,
+                physical_capacity
+            );
+            return Ok(None);
+        }
+
+        warn!(
+            target: LOG_TARGET,
+            "⚠️ On-chain capacity of {} bytes exceeds the {} bytes the disk can hold ({} bytes used, {} bytes available in the disk)",
+            current_capacity,
+            physical_capacity,
+            used_capacity,
+            disk_space.available_space
+        );
+
+        Ok(Some(physical_capacity))
+    }
+}
@@ -1,10 +1,13 @@
-use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use anyhow::anyhow;
+use codec::Encode;
+use futures::{stream, StreamExt, TryStreamExt};
 use sc_tracing::tracing::*;
 use shc_file_manager::traits::FileStorage;
 use shp_file_metadata::ChunkId;
 use sp_core::H256;
+use tokio::sync::Mutex;
 
 use shc_actors_framework::{actor::ActorHandle, event_bus::EventHandler};
 use shc_blockchain_service::{
@@ -22,7 +25,11 @@ use shc_common::{
         RandomnessOutput, StorageProof, TrieRemoveMutation,
     },
 };
-use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_forest_manager::{
+    traits::{ForestStorage, ForestStorageHandler},
+    write_batch::ForestWriteBatch,
+};
+use shc_telemetry::{end_flow, flow_span, Flow};
 
 use crate::services::{
     handler::StorageHubHandler,
@@ -31,6 +38,21 @@ use crate::services::{
 
 const LOG_TARGET: &str = "bsp-submit-proof-task";
 const MAX_PROOF_SUBMISSION_ATTEMPTS: u32 = 3;
+/// Maximum amount of key proofs generated at the same time for a single storage proof.
+const MAX_CONCURRENT_KEY_PROOFS: usize = 8;
+
+/// A storage proof generated for a [`SubmitProofRequest`]. It is only valid while the Forest root
+/// it was generated against is the current one.
+struct GeneratedProof {
+    forest_root: H256,
+    proof: StorageProof,
+    /// The keys proven by the Forest proof.
+    proven_keys: Vec<H256>,
+}
+
+/// Where the proof pre-generated for a [`SubmitProofRequest`] is left. It stays locked while the
+/// proof is being generated.
+type PregeneratedProofSlot = Arc<Mutex<Option<GeneratedProof>>>;
 
 /// BSP Submit Proof Task: Handles the submission of proof for BSP (Backup Storage Provider) to the runtime.
 ///
@@ -41,11 +63,15 @@ const MAX_PROOF_SUBMISSION_ATTEMPTS: u32 = 3;
 ///     - Derives forest challenges from the seed.
 ///     - Checks for any checkpoint challenges and adds them to the forest challenges.
 ///     - Queues the challenges for submission to the runtime, to be processed when the Forest write lock is released.
+///     - Starts generating the proof for the challenges in the background, while waiting for the Forest write lock.
 ///
 /// - **[`ProcessSubmitProofRequest`] Event:**
 ///   - Triggered when the Blockchain Service detects that the Forest write lock has been released.
-///   - Generates proofs for the queued challenges derived from the seed in the [`MultipleNewChallengeSeeds`] event.
-///   - Constructs key proofs for each file key involved in the challenges.
+///   - Uses the proof pre-generated for the queued challenges, waiting for it if still in progress. If the Forest
+///     root changed since it was generated, generates it again, reading the trie nodes already warmed up by the
+///     pre-generation.
+///   - Constructs key proofs for each file key involved in the challenges, up to [`MAX_CONCURRENT_KEY_PROOFS`] at
+///     the same time.
 ///   - Submits the proofs to the runtime, with up to [`MAX_PROOF_SUBMISSION_ATTEMPTS`] retries on failure.
 ///   - Applies any necessary mutations to the Forest Storage (but not the File Storage).
 ///   - Verifies that the new Forest root matches the one recorded on-chain to ensure consistency.
@@ -63,6 +89,9 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     storage_hub_handler: StorageHubHandler<NT>,
+    /// The proofs pre-generated for the queued requests, by Provider and tick.
+    pregenerated_proofs:
+        Arc<Mutex<HashMap<(ProofsDealerProviderId, BlockNumber), PregeneratedProofSlot>>>,
 }
 
 impl<NT> Clone for BspSubmitProofTask<NT>
@@ -73,6 +102,7 @@ where
     fn clone(&self) -> BspSubmitProofTask<NT> {
         Self {
             storage_hub_handler: self.storage_hub_handler.clone(),
+            pregenerated_proofs: self.pregenerated_proofs.clone(),
         }
     }
 }
@@ -85,6 +115,7 @@ where
     pub fn new(storage_hub_handler: StorageHubHandler<NT>) -> Self {
         Self {
             storage_hub_handler,
+            pregenerated_proofs: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -97,6 +128,7 @@ where
 /// - Derives forest challenges from the seed.
 /// - Checks for checkpoint challenges and adds them to the forest challenges.
 /// - Queues the challenges for submission to the runtime, for when the Forest write lock is released.
+/// - Starts generating the proof for the challenges in the background.
 impl<NT> EventHandler<MultipleNewChallengeSeeds> for BspSubmitProofTask<NT>
 where
     NT: ShNodeType + 'static,
@@ -105,8 +137,8 @@ where
     async fn handle_event(&mut self, event: MultipleNewChallengeSeeds) -> anyhow::Result<()> {
         info!(
             target: LOG_TARGET,
-            "Initiating BSP multiple proof submissions for BSP ID: {:?}, with seeds: {:?}",
-            event.provider_id,
+            provider_id = ?event.provider_id,
+            "Initiating BSP multiple proof submissions with seeds: {:?}",
             event.seeds
         );
 
@@ -114,8 +146,16 @@ where
             let provider_id = event.provider_id;
             let tick = seed.0;
             let seed = seed.1;
-            self.queue_submit_proof_request(provider_id, tick, seed)
-                .await?;
+            let mut span = flow_span(
+                Flow::ProofSubmission,
+                "queue_challenges",
+                (provider_id, tick),
+            );
+            let result = self
+                .queue_submit_proof_request(provider_id, tick, seed)
+                .await;
+            span.record_result(&result);
+            result?;
         }
 
         Ok(())
@@ -128,8 +168,8 @@ where
 /// giving this task the opportunity to generate proofs and submit them to the runtime.
 ///
 /// This task performs the following actions:
-/// - Generates proofs for the challenges.
-/// - Constructs key proofs and submits the proof to the runtime.
+/// - Takes the proof pre-generated for the challenges, or generates it if the Forest root changed since.
+/// - Submits the proof to the runtime.
 ///   - Retries up to [`MAX_PROOF_SUBMISSION_ATTEMPTS`] times if the submission fails.
 /// - Applies any necessary mutations to the Forest Storage (not the File Storage).
 /// - Ensures the new Forest root matches the one on-chain.
@@ -139,8 +179,88 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: ProcessSubmitProofRequest) -> anyhow::Result<()> {
+        let correlation_id = (event.data.provider_id, event.data.tick);
+        let mut span = flow_span(Flow::ProofSubmission, "submit_proof", correlation_id);
+
+        let result = self.handle_process_submit_proof_request_event(event).await;
+        span.record_result(&result);
+        drop(span);
+        end_flow(Flow::ProofSubmission, correlation_id);
+        result
+    }
+}
+
+/// Handles the [`FinalisedTrieRemoveMutationsApplied`] event.
+///
+/// This event is triggered when mutations applied to the Forest of this BSP have been finalised,
+/// signalling that certain keys (representing files) should be removed from the File Storage if they are
+/// not present in the Forest Storage. If the key is still present in the Forest Storage, it sends out
+/// a warning, since it could indicate that the key has been re-added after being deleted.
+///
+/// This task performs the following actions:
+/// - Iterates over each removed file key.
+/// - Checks if the file key is present in the Forest Storage.
+///   - If the key is still present, it logs a warning,
+///     since this could indicate that the key has been re-added after being deleted.
+///   - If the key is not present in the Forest Storage, it safely removes the key from the File Storage.
+impl<NT> EventHandler<FinalisedTrieRemoveMutationsApplied> for BspSubmitProofTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: BspForestStorageHandlerT,
+{
+    async fn handle_event(
+        &mut self,
+        event: FinalisedTrieRemoveMutationsApplied,
+    ) -> anyhow::Result<()> {
+        info!(
+            target: LOG_TARGET,
+            "Processing finalised mutations applied for provider [{:?}] with mutations: {:?}",
+            event.provider_id,
+            event.mutations
+        );
+
+        // For each mutation...
+        for mutation in event.mutations {
+            let file_key = FileKey::from(mutation.0);
+
+            // Check that the file_key is not in the Forest.
+            let current_forest_key = CURRENT_FOREST_KEY.to_vec();
+            let read_fs = self
+                .storage_hub_handler
+                .forest_storage_handler
+                .get(&current_forest_key)
+                .await
+                .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
+            if read_fs.read().await.contains_file_key(&file_key.into())? {
+                warn!(
+                    target: LOG_TARGET,
+                    "TrieRemoveMutation applied and finalised for file key {:?}, but file key is still in Forest. This can only happen if the same file key was added again after deleted by the user.\n Mutation: {:?}",
+                    file_key,
+                    mutation
+                );
+            } else {
+                // If file key is not in Forest, we can now safely remove it from the File Storage.
+                self.remove_file_from_file_storage(&file_key.into()).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<NT> BspSubmitProofTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: BspForestStorageHandlerT,
+{
+    async fn handle_process_submit_proof_request_event(
+        &mut self,
+        event: ProcessSubmitProofRequest,
+    ) -> anyhow::Result<()> {
         info!(
             target: LOG_TARGET,
+            provider_id = ?event.data.provider_id,
+            correlation_id = ?(event.data.provider_id, event.data.tick),
             "Processing SubmitProofRequest {:?}",
             event.data
         );
@@ -162,74 +282,43 @@ where
         // Exiting early in this case is important so that the provider doesn't get stuck trying to submit an outdated proof.
         Self::check_if_proof_is_outdated(&self.storage_hub_handler.blockchain, &event).await?;
 
-        // Get the current Forest key of the Provider running this node.
-        let current_forest_key = CURRENT_FOREST_KEY.to_vec();
-
-        // Generate the Forest proof, i.e. the proof that some file keys belong to this Provider's Forest.
-        let proven_file_keys = {
-            let fs = self
-                .storage_hub_handler
-                .forest_storage_handler
-                .get(&current_forest_key)
-                .await
-                .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
-
-            let p = fs
-                .read()
-                .await
-                .generate_proof(event.data.forest_challenges.clone())
-                .map_err(|e| anyhow!("Failed to generate forest proof: {:?}", e))?;
-
-            p
-        };
-
-        // Get the keys that were proven.
-        let mut proven_keys = Vec::new();
-        for key in proven_file_keys.proven {
-            match key {
-                Proven::ExactKey(leaf) => proven_keys.push(leaf.key),
-                Proven::NeighbourKeys((left, right)) => match (left, right) {
-                    (Some(left), Some(right)) => {
-                        proven_keys.push(left.key);
-                        proven_keys.push(right.key);
-                    }
-                    (Some(left), None) => proven_keys.push(left.key),
-                    (None, Some(right)) => proven_keys.push(right.key),
-                    (None, None) => {
-                        error!(target: LOG_TARGET, "Both left and right leaves in forest proof are None. This should not be possible.");
-                    }
-                },
-                Proven::Empty => {
-                    error!(target: LOG_TARGET, "Forest proof generated with empty forest. This should not be possible, as this provider shouldn't have been challenged with an empty forest.");
-                }
+        // Use the proof pre-generated when the challenges were queued, if it was generated against
+        // the current Forest root. Otherwise, generate it again.
+        let current_forest_root = self.current_forest_root().await?;
+        let GeneratedProof {
+            forest_root,
+            proof,
+            proven_keys,
+        } = match self.take_pregenerated_proof(&event.data).await {
+            Some(generated_proof) if generated_proof.forest_root == current_forest_root => {
+                debug!(target: LOG_TARGET, "Using the proof pre-generated for tick {:?}", event.data.tick);
+                generated_proof
             }
-        }
-
-        // Construct key challenges and generate key proofs for them.
-        let mut key_proofs = KeyProofs::new();
-        for file_key in &proven_keys {
-            // If the file key is a checkpoint challenge for a file deletion, we should NOT generate a key proof for it.
-            let should_generate_key_proof = !event
-                .data
-                .checkpoint_challenges
-                .contains(&(*file_key, Some(TrieRemoveMutation::default())));
-
-            if should_generate_key_proof {
-                // Generate the key proof for each file key.
-                let key_proof = self
-                    .generate_key_proof(*file_key, event.data.seed, event.data.provider_id)
-                    .await?;
+            Some(_) => {
+                debug!(target: LOG_TARGET, "Forest root changed since the proof for tick {:?} was pre-generated, generating it again", event.data.tick);
+                self.generate_proof(&event.data).await?
+            }
+            None => self.generate_proof(&event.data).await?,
+        };
 
-                key_proofs.insert(*file_key, key_proof);
-            };
+        // Check that the proof fits within the maximum proof size accepted by the runtime, since
+        // submitting it would otherwise fail and waste the fees.
+        let max_proof_size = self
+            .storage_hub_handler
+            .blockchain
+            .query_max_proof_size()
+            .await
+            .map_err(|e| anyhow!("Failed to query max proof size: {:?}", e))?;
+        let proof_size = proof.encoded_size();
+        if proof_size > max_proof_size as usize {
+            return Err(anyhow!(
+                "Generated proof of {} bytes exceeds the maximum proof size of {} bytes. Proven keys: {:?}",
+                proof_size,
+                max_proof_size,
+                proven_keys
+            ));
         }
 
-        // Construct full proof.
-        let proof = StorageProof {
-            forest_proof: proven_file_keys.proof,
-            key_proofs,
-        };
-
         // Submit proof to the runtime.
         // Provider is `None` since we're submitting with the account linked to the BSP.
         let call = storage_hub_runtime::RuntimeCall::ProofsDealer(
@@ -252,16 +341,7 @@ where
         // Get necessary data for the retry check.
         let cloned_sh_handler = Arc::new(self.storage_hub_handler.clone());
         let cloned_event = Arc::new(event.clone());
-        let cloned_forest_root = {
-            let fs = self
-                .storage_hub_handler
-                .forest_storage_handler
-                .get(&current_forest_key)
-                .await
-                .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
-            let root = fs.read().await.root();
-            root
-        };
+        let cloned_forest_root = forest_root;
 
         // This function is a check to see if we should continue to retry the submission of the proof.
         // If this proof submission is invalid, we should not retry it, and release the forest write lock.
@@ -334,7 +414,7 @@ where
 
         // TODO: Don't do this in this task any more.
         // Apply mutations, if any.
-        let mut mutations_applied = false;
+        let mut file_keys_to_remove = Vec::new();
         for (file_key, maybe_mutation) in &event.data.checkpoint_challenges {
             if proven_keys.contains(file_key) {
                 // If the file key is proven, it means that this provider had an exact match for a checkpoint challenge.
@@ -350,13 +430,13 @@ where
                     // a proof, it will be against the Forest root with this change applied.
                     // We will remove the file from the File Storage only after finality is reached.
                     // This gives us the opportunity to put the file back in the Forest if this block is re-orged.
-                    self.remove_file_from_forest(file_key).await?;
-                    mutations_applied = true;
+                    file_keys_to_remove.push(*file_key);
                 }
             }
         }
 
-        if mutations_applied {
+        if !file_keys_to_remove.is_empty() {
+            self.remove_files_from_forest(&file_keys_to_remove).await?;
             trace!(target: LOG_TARGET, "Mutations applied successfully");
 
             // Check that the new Forest root matches the one on-chain.
@@ -369,71 +449,7 @@ where
             .release_forest_root_write_lock(forest_root_write_tx)
             .await
     }
-}
 
-/// Handles the [`FinalisedTrieRemoveMutationsApplied`] event.
-///
-/// This event is triggered when mutations applied to the Forest of this BSP have been finalised,
-/// signalling that certain keys (representing files) should be removed from the File Storage if they are
-/// not present in the Forest Storage. If the key is still present in the Forest Storage, it sends out
-/// a warning, since it could indicate that the key has been re-added after being deleted.
-///
-/// This task performs the following actions:
-/// - Iterates over each removed file key.
-/// - Checks if the file key is present in the Forest Storage.
-///   - If the key is still present, it logs a warning,
-///     since this could indicate that the key has been re-added after being deleted.
-///   - If the key is not present in the Forest Storage, it safely removes the key from the File Storage.
-impl<NT> EventHandler<FinalisedTrieRemoveMutationsApplied> for BspSubmitProofTask<NT>
-where
-    NT: ShNodeType + 'static,
-    NT::FSH: BspForestStorageHandlerT,
-{
-    async fn handle_event(
-        &mut self,
-        event: FinalisedTrieRemoveMutationsApplied,
-    ) -> anyhow::Result<()> {
-        info!(
-            target: LOG_TARGET,
-            "Processing finalised mutations applied for provider [{:?}] with mutations: {:?}",
-            event.provider_id,
-            event.mutations
-        );
-
-        // For each mutation...
-        for mutation in event.mutations {
-            let file_key = FileKey::from(mutation.0);
-
-            // Check that the file_key is not in the Forest.
-            let current_forest_key = CURRENT_FOREST_KEY.to_vec();
-            let read_fs = self
-                .storage_hub_handler
-                .forest_storage_handler
-                .get(&current_forest_key)
-                .await
-                .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
-            if read_fs.read().await.contains_file_key(&file_key.into())? {
-                warn!(
-                    target: LOG_TARGET,
-                    "TrieRemoveMutation applied and finalised for file key {:?}, but file key is still in Forest. This can only happen if the same file key was added again after deleted by the user.\n Mutation: {:?}",
-                    file_key,
-                    mutation
-                );
-            } else {
-                // If file key is not in Forest, we can now safely remove it from the File Storage.
-                self.remove_file_from_file_storage(&file_key.into()).await?;
-            }
-        }
-
-        Ok(())
-    }
-}
-
-impl<NT> BspSubmitProofTask<NT>
-where
-    NT: ShNodeType,
-    NT::FSH: BspForestStorageHandlerT,
-{
     async fn queue_submit_proof_request(
         &self,
         provider_id: ProofsDealerProviderId,
@@ -453,20 +469,162 @@ where
             .await?;
         trace!(target: LOG_TARGET, "Checkpoint challenges to respond to: {:?}", checkpoint_challenges);
 
+        let request = SubmitProofRequest::new(
+            provider_id,
+            tick,
+            seed,
+            forest_challenges,
+            checkpoint_challenges,
+        );
+
+        // Start generating the proof right away, instead of when the Forest write lock is
+        // released, which can be close to the submission deadline.
+        self.pregenerate_proof(request.clone()).await;
+
         self.storage_hub_handler
             .blockchain
-            .queue_submit_proof_request(SubmitProofRequest::new(
-                provider_id,
-                tick,
-                seed,
-                forest_challenges,
-                checkpoint_challenges,
-            ))
+            .queue_submit_proof_request(request)
             .await?;
 
         Ok(())
     }
 
+    /// Generates the proof for `request` in the background, leaving it for when the request is
+    /// processed.
+    ///
+    /// Besides overlapping the generation of the proof with the wait for the Forest write lock, it
+    /// warms up the caches of the Forest and File Storage with the trie nodes to prove, in case the
+    /// proof has to be generated again because the Forest root changed in the meantime.
+    async fn pregenerate_proof(&self, request: SubmitProofRequest) {
+        let slot = PregeneratedProofSlot::default();
+        // Locked before being shared, so that processing the request waits for the proof to be
+        // generated, instead of generating it again.
+        let mut generated_proof = slot.clone().lock_owned().await;
+        self.pregenerated_proofs
+            .lock()
+            .await
+            .insert((request.provider_id, request.tick), slot);
+
+        let task = self.clone();
+        self.storage_hub_handler.task_spawner.spawn(async move {
+            match task.generate_proof(&request).await {
+                Ok(proof) => {
+                    debug!(target: LOG_TARGET, "Proof for tick {:?} pre-generated", request.tick);
+                    *generated_proof = Some(proof);
+                }
+                Err(e) => warn!(
+                    target: LOG_TARGET,
+                    "Failed to pre-generate the proof for tick {:?}, it will be generated when submitted: {:?}",
+                    request.tick,
+                    e
+                ),
+            }
+        });
+    }
+
+    /// Takes the proof pre-generated for `request`, waiting for it if it is still being generated.
+    async fn take_pregenerated_proof(
+        &self,
+        request: &SubmitProofRequest,
+    ) -> Option<GeneratedProof> {
+        let slot = {
+            let mut pregenerated_proofs = self.pregenerated_proofs.lock().await;
+            let slot = pregenerated_proofs.remove(&(request.provider_id, request.tick));
+            // The proofs for earlier ticks are not going to be submitted anymore.
+            pregenerated_proofs.retain(|(provider_id, tick), _| {
+                *provider_id != request.provider_id || *tick > request.tick
+            });
+            slot
+        }?;
+
+        let generated_proof = slot.lock().await.take();
+        generated_proof
+    }
+
+    /// Generates the proof for the challenges of `request` against the current Forest root.
+    async fn generate_proof(&self, request: &SubmitProofRequest) -> anyhow::Result<GeneratedProof> {
+        // Get the current Forest key of the Provider running this node.
+        let current_forest_key = CURRENT_FOREST_KEY.to_vec();
+
+        // Generate the Forest proof, i.e. the proof that some file keys belong to this Provider's Forest.
+        let (forest_root, proven_file_keys) = {
+            let fs = self
+                .storage_hub_handler
+                .forest_storage_handler
+                .get(&current_forest_key)
+                .await
+                .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
+
+            let fs = fs.read().await;
+            let p = fs
+                .generate_proof(request.forest_challenges.clone())
+                .map_err(|e| anyhow!("Failed to generate forest proof: {:?}", e))?;
+
+            (fs.root(), p)
+        };
+
+        // Get the keys that were proven.
+        let mut proven_keys = Vec::new();
+        for key in proven_file_keys.proven {
+            match key {
+                Proven::ExactKey(leaf) => proven_keys.push(leaf.key),
+                Proven::NeighbourKeys((left, right)) => match (left, right) {
+                    (Some(left), Some(right)) => {
+                        proven_keys.push(left.key);
+                        proven_keys.push(right.key);
+                    }
+                    (Some(left), None) => proven_keys.push(left.key),
+                    (None, Some(right)) => proven_keys.push(right.key),
+                    (None, None) => {
+                        error!(target: LOG_TARGET, "Both left and right leaves in forest proof are None. This should not be possible.");
+                    }
+                },
+                Proven::Empty => {
+                    error!(target: LOG_TARGET, "Forest proof generated with empty forest. This should not be possible, as this provider shouldn't have been challenged with an empty forest.");
+                }
+            }
+        }
+
+        // Construct key challenges and generate key proofs for them, concurrently.
+        let key_proofs = stream::iter(proven_keys.iter().copied().filter(|file_key| {
+            // If the file key is a checkpoint challenge for a file deletion, we should NOT generate a key proof for it.
+            !request
+                .checkpoint_challenges
+                .contains(&(*file_key, Some(TrieRemoveMutation::default())))
+        }))
+        .map(|file_key| async move {
+            let key_proof = self
+                .generate_key_proof(file_key, request.seed, request.provider_id)
+                .await?;
+            anyhow::Ok((file_key, key_proof))
+        })
+        .buffer_unordered(MAX_CONCURRENT_KEY_PROOFS)
+        .try_collect::<KeyProofs>()
+        .await?;
+
+        Ok(GeneratedProof {
+            forest_root,
+            proof: StorageProof {
+                forest_proof: proven_file_keys.proof,
+                key_proofs,
+            },
+            proven_keys,
+        })
+    }
+
+    async fn current_forest_root(&self) -> anyhow::Result<H256> {
+        let current_forest_key = CURRENT_FOREST_KEY.to_vec();
+        let fs = self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&current_forest_key)
+            .await
+            .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
+
+        let root = fs.read().await.root();
+        Ok(root)
+    }
+
     async fn derive_forest_challenges_from_seed(
         &self,
         seed: RandomnessOutput,
@@ -585,13 +743,17 @@ where
             .map(|challenge| ChunkId::from_challenge(challenge.as_ref(), chunks_count))
             .collect::<Vec<_>>();
 
-        // Construct file key proofs for the challenges.
-        let read_file_storage = self.storage_hub_handler.file_storage.read().await;
-        let file_key_proof = read_file_storage
-            .generate_proof(&file_key, &chunks_to_prove)
-            .map_err(|e| anyhow!("File is not in storage, or proof does not exist: {:?}", e))?;
-        // Release the file storage read lock as soon as possible.
-        drop(read_file_storage);
+        // Construct file key proofs for the challenges, reading the file trie out of the async
+        // runtime, since several key proofs are generated at the same time.
+        let file_storage = self.storage_hub_handler.file_storage.clone();
+        let file_key_proof = tokio::task::spawn_blocking(move || {
+            file_storage
+                .blocking_read()
+                .generate_proof(&file_key, &chunks_to_prove)
+        })
+        .await
+        .map_err(|e| anyhow!("Failed to generate file key proof: {:?}", e))?
+        .map_err(|e| anyhow!("File is not in storage, or proof does not exist: {:?}", e))?;
 
         // Return the key proof.
         Ok(KeyProof {
@@ -600,28 +762,33 @@ where
         })
     }
 
-    async fn remove_file_from_forest(&self, file_key: &H256) -> anyhow::Result<()> {
-        // Remove the file key from the Forest.
-        // Check that the new Forest root matches the one on-chain.
-        {
-            let current_forest_key = CURRENT_FOREST_KEY.to_vec();
-            let fs = self
-                .storage_hub_handler
-                .forest_storage_handler
-                .get(&current_forest_key)
-                .await
-                .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
+    async fn remove_files_from_forest(&self, file_keys: &[H256]) -> anyhow::Result<()> {
+        // Remove the file keys from the Forest.
+        let current_forest_key = CURRENT_FOREST_KEY.to_vec();
+        let fs = self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&current_forest_key)
+            .await
+            .ok_or_else(|| anyhow!("CRITICAL❗️❗️ Failed to get forest storage."))?;
 
-            fs.write().await.delete_file_key(file_key).map_err(|e| {
+        // Batch the mutations, so that they are written to the Forest storage at once, before
+        // the Forest is unlocked.
+        let mut batch = ForestWriteBatch::lock(fs).await;
+        for file_key in file_keys {
+            batch.delete_file_key(file_key).map_err(|e| {
                 error!(target: LOG_TARGET, "CRITICAL❗️❗️ Failed to apply mutation to Forest storage. This may result in a mismatch between the Forest root on-chain and in this node. \nThis is a critical bug. Please report it to the StorageHub team. \nError: {:?}", e);
                 anyhow!(
                     "Failed to remove file key from Forest storage: {:?}",
                     e
                 )
             })?;
-        };
+        }
 
-        Ok(())
+        batch.flush().map_err(|e| {
+            error!(target: LOG_TARGET, "CRITICAL❗️❗️ Failed to write mutations to Forest storage. This may result in a mismatch between the Forest root on-chain and in this node. \nThis is a critical bug. Please report it to the StorageHub team. \nError: {:?}", e);
+            anyhow!("Failed to write mutations to Forest storage: {:?}", e)
+        })
     }
 
     async fn remove_file_from_file_storage(&self, file_key: &H256) -> anyhow::Result<()> {
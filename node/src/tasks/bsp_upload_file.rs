@@ -9,6 +9,7 @@ use std::{
 
 use anyhow::anyhow;
 use frame_support::BoundedVec;
+use pallet_payment_streams_runtime_api::GetUsersWithDebtOverThresholdError;
 use sc_network::PeerId;
 use sc_tracing::tracing::*;
 use sp_core::H256;
@@ -18,24 +19,29 @@ use tokio::sync::Mutex;
 use shc_actors_framework::event_bus::EventHandler;
 use shc_blockchain_service::{
     commands::BlockchainServiceInterface,
-    events::{NewStorageRequest, ProcessConfirmStoringRequest},
+    events::{NewStorageRequest, ProcessConfirmStoringRequest, Reverted},
+    pending_operations::OperationKey,
     types::{ConfirmStoringRequest, RetryStrategy, Tip},
 };
 use shc_common::{
     consts::CURRENT_FOREST_KEY,
     types::{
-        Balance, FileKey, FileMetadata, HashT, StorageProofsMerkleTrieLayout, StorageProviderId,
+        BackupStorageProviderId, Balance, BlockNumber, FileKey, FileMetadata, HashT,
+        StorageProofsMerkleTrieLayout, StorageProviderId, FILE_CHUNK_SIZE,
     },
 };
 use shc_file_manager::traits::{FileStorage, FileStorageWriteError, FileStorageWriteOutcome};
 use shc_file_transfer_service::{
-    commands::FileTransferServiceInterface, events::RemoteUploadRequest,
+    chunk_size::MAX_TRANSFER_CHUNK_SIZE, commands::FileTransferServiceInterface,
+    events::RemoteUploadRequest, TransferOutcome,
 };
 use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_telemetry::{end_flow, flow_span, Flow};
+use shc_volunteer_policy::{VolunteerCandidate, VolunteerPolicy};
 use storage_hub_runtime::{StorageDataUnit, MILLIUNIT};
 
 use crate::services::{
-    handler::StorageHubHandler,
+    handler::{FleetCoordinationConfig, StorageHubHandler},
     types::{BspForestStorageHandlerT, ShNodeType},
 };
 
@@ -59,6 +65,9 @@ const MAX_CONFIRM_STORING_REQUEST_TIP: Balance = 500 * MILLIUNIT;
 /// - [`ProcessConfirmStoringRequest`] event: The third part of the flow. It is triggered by the
 ///   runtime when the BSP should construct a proof for the new file(s) and submit a confirm storing
 ///   before updating it's local Forest storage root.
+///
+/// If the block with the storage request is reorged out, the [`Reverted`] [`NewStorageRequest`]
+/// event undoes the first part of the flow.
 pub struct BspUploadFileTask<NT>
 where
     NT: ShNodeType,
@@ -110,24 +119,57 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: NewStorageRequest) -> anyhow::Result<()> {
+        let file_key: H256 = event.file_key.into();
         info!(
             target: LOG_TARGET,
-            "Initiating BSP volunteer for file_key {:?}, location {:?}, fingerprint {:?}",
-            event.file_key,
+            file_key = ?file_key,
+            correlation_id = ?file_key,
+            "Initiating BSP volunteer for location {:?}, fingerprint {:?}",
             event.location,
             event.fingerprint
         );
 
+        let mut span = flow_span(Flow::StorageRequest, "bsp_volunteer", file_key);
+
         let result = self.handle_new_storage_request_event(event).await;
+        span.record_result(&result);
         if result.is_err() {
             if let Some(file_key) = &self.file_key_cleanup {
                 self.unvolunteer_file(*file_key).await;
             }
+            end_flow(Flow::StorageRequest, file_key);
         }
         result
     }
 }
 
+/// Handles the [`Reverted`] [`NewStorageRequest`] event.
+///
+/// This event is triggered when the block with a storage request is reorged out. Unless the storage
+/// request is still open in the new best chain, or the file is already in this BSP's Forest, the
+/// file is unvolunteered: it is unregistered from the File Transfer Service and whatever was
+/// received of it is deleted.
+impl<NT> EventHandler<Reverted<NewStorageRequest>> for BspUploadFileTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: BspForestStorageHandlerT,
+{
+    async fn handle_event(&mut self, event: Reverted<NewStorageRequest>) -> anyhow::Result<()> {
+        let file_key: H256 = event.event.file_key.into();
+        info!(
+            target: LOG_TARGET,
+            file_key = ?file_key,
+            correlation_id = ?file_key,
+            "Storage request reverted, since block #{} ({:?}) was reorged out",
+            event.block_number,
+            event.block_hash
+        );
+
+        self.handle_reverted_new_storage_request_event(file_key)
+            .await
+    }
+}
+
 /// Handles the [`RemoteUploadRequest`] event.
 ///
 /// This event is triggered by a user sending a chunk of the file to the BSP. It checks the proof
@@ -138,6 +180,10 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: RemoteUploadRequest) -> anyhow::Result<()> {
+        let file_key: H256 = event.file_key.into();
+        let mut span = flow_span(Flow::FileTransfer, "bsp_receive_chunk", file_key);
+        span.fail_unless_completed();
+
         trace!(target: LOG_TARGET, "Received remote upload request for file {:?} and peer {:?}", event.file_key, event.peer);
 
         let proven = match event
@@ -145,13 +191,16 @@ where
             .proven::<StorageProofsMerkleTrieLayout>()
         {
             Ok(proven) => {
-                if proven.len() != 1 {
+                if proven.is_empty()
+                    || proven.len() as u64 > MAX_TRANSFER_CHUNK_SIZE / FILE_CHUNK_SIZE
+                {
                     Err(anyhow::anyhow!(
-                        "Expected exactly one proven chunk but got {}.",
+                        "Expected between 1 and {} proven chunks but got {}.",
+                        MAX_TRANSFER_CHUNK_SIZE / FILE_CHUNK_SIZE,
                         proven.len()
                     ))
                 } else {
-                    Ok(proven[0].clone())
+                    Ok(proven)
                 }
             }
             Err(e) => Err(anyhow::anyhow!(
@@ -165,88 +214,117 @@ where
             Err(e) => {
                 warn!(target: LOG_TARGET, "{}", e);
 
+                // The peer sent chunks that don't match the fingerprint of the file.
+                self.storage_hub_handler
+                    .file_transfer
+                    .report_peer(event.peer, TransferOutcome::Corrupt)
+                    .await;
+
                 // Unvolunteer the file.
                 self.unvolunteer_file(event.file_key.into()).await;
                 return Err(e);
             }
         };
 
-        let mut write_file_storage = self.storage_hub_handler.file_storage.write().await;
-        let write_chunk_result =
-            write_file_storage.write_chunk(&event.file_key.into(), &proven.key, &proven.data);
-        // Release the file storage write lock as soon as possible.
-        drop(write_file_storage);
-
-        match write_chunk_result {
-            Ok(outcome) => match outcome {
-                FileStorageWriteOutcome::FileComplete => {
-                    self.on_file_complete(&event.file_key.into()).await?
-                }
-                FileStorageWriteOutcome::FileIncomplete => {}
-            },
-            Err(error) => match error {
-                FileStorageWriteError::FileChunkAlreadyExists => {
-                    warn!(
-                        target: LOG_TARGET,
-                        "Received duplicate chunk with key: {:?}",
-                        proven.key
-                    );
+        // Write the chunks of the transfer chunk, one file chunk at a time.
+        let mut received_chunk_ids = Vec::with_capacity(proven.len());
+        for chunk in proven {
+            let mut write_file_storage = self.storage_hub_handler.file_storage.write().await;
+            let write_chunk_result =
+                write_file_storage.write_chunk(&event.file_key.into(), &chunk.key, &chunk.data);
+            // Release the file storage write lock as soon as possible.
+            drop(write_file_storage);
+
+            match write_chunk_result {
+                Ok(outcome) => match outcome {
+                    FileStorageWriteOutcome::FileComplete => {
+                        self.on_file_complete(&event.file_key.into()).await?
+                    }
+                    FileStorageWriteOutcome::FileIncomplete => {
+                        received_chunk_ids.push(chunk.key);
+                    }
+                },
+                Err(error) => match error {
+                    FileStorageWriteError::FileChunkAlreadyExists => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Received duplicate chunk with key: {:?}",
+                            chunk.key
+                        );
+                        received_chunk_ids.push(chunk.key);
 
-                    // TODO: Consider informing this to the file transfer service so that it can handle reputation for this peer id.
-                }
-                FileStorageWriteError::FileDoesNotExist => {
-                    // Unvolunteer the file.
-                    self.unvolunteer_file(event.file_key.into()).await;
+                        // TODO: Consider informing this to the file transfer service so that it can handle reputation for this peer id.
+                    }
+                    FileStorageWriteError::FileDoesNotExist => {
+                        // Unvolunteer the file.
+                        self.unvolunteer_file(event.file_key.into()).await;
 
-                    return Err(anyhow::anyhow!(format!("File does not exist for key {:?}. Maybe we forgot to unregister before deleting?", event.file_key)));
-                }
-                FileStorageWriteError::FailedToGetFileChunk
-                | FileStorageWriteError::FailedToInsertFileChunk
-                | FileStorageWriteError::FailedToDeleteChunk
-                | FileStorageWriteError::FailedToPersistChanges
-                | FileStorageWriteError::FailedToParseFileMetadata
-                | FileStorageWriteError::FailedToParseFingerprint
-                | FileStorageWriteError::FailedToReadStorage
-                | FileStorageWriteError::FailedToUpdatePartialRoot
-                | FileStorageWriteError::FailedToParsePartialRoot
-                | FileStorageWriteError::FailedToGetStoredChunksCount => {
-                    // This internal error should not happen.
-
-                    // Unvolunteer the file.
-                    self.unvolunteer_file(event.file_key.into()).await;
-
-                    return Err(anyhow::anyhow!(format!(
-                        "Internal trie read/write error {:?}:{:?}",
-                        event.file_key, proven.key
-                    )));
-                }
-                FileStorageWriteError::FingerprintAndStoredFileMismatch => {
-                    // This should never happen, given that the first check in the handler is verifying the proof.
-                    // This means that something is seriously wrong, so we error out the whole task.
+                        return Err(anyhow::anyhow!(format!("File does not exist for key {:?}. Maybe we forgot to unregister before deleting?", event.file_key)));
+                    }
+                    FileStorageWriteError::FailedToGetFileChunk
+                    | FileStorageWriteError::FailedToInsertFileChunk
+                    | FileStorageWriteError::FailedToDeleteChunk
+                    | FileStorageWriteError::FailedToPersistChanges
+                    | FileStorageWriteError::FailedToParseFileMetadata
+                    | FileStorageWriteError::FailedToParseFingerprint
+                    | FileStorageWriteError::FailedToReadStorage
+                    | FileStorageWriteError::FailedToUpdatePartialRoot
+                    | FileStorageWriteError::FailedToParsePartialRoot
+                    | FileStorageWriteError::FailedToGetStoredChunksCount => {
+                        // This internal error should not happen.
+
+                        // Unvolunteer the file.
+                        self.unvolunteer_file(event.file_key.into()).await;
+
+                        return Err(anyhow::anyhow!(format!(
+                            "Internal trie read/write error {:?}:{:?}",
+                            event.file_key, chunk.key
+                        )));
+                    }
+                    FileStorageWriteError::FingerprintAndStoredFileMismatch => {
+                        // This should never happen, given that the first check in the handler is verifying the proof.
+                        // This means that something is seriously wrong, so we error out the whole task.
 
-                    // Unvolunteer the file.
-                    self.unvolunteer_file(event.file_key.into()).await;
+                        // Unvolunteer the file.
+                        self.unvolunteer_file(event.file_key.into()).await;
 
-                    return Err(anyhow::anyhow!(format!(
+                        return Err(anyhow::anyhow!(format!(
                         "Invariant broken! This is a bug! Fingerprint and stored file mismatch for key {:?}.",
                         event.file_key
                     )));
-                }
-                FileStorageWriteError::FailedToConstructTrieIter => {
-                    // This should never happen for a well constructed trie.
-                    // This means that something is seriously wrong, so we error out the whole task.
+                    }
+                    FileStorageWriteError::FailedToConstructTrieIter => {
+                        // This should never happen for a well constructed trie.
+                        // This means that something is seriously wrong, so we error out the whole task.
 
-                    // Unvolunteer the file.
-                    self.unvolunteer_file(event.file_key.into()).await;
+                        // Unvolunteer the file.
+                        self.unvolunteer_file(event.file_key.into()).await;
 
-                    return Err(anyhow::anyhow!(format!(
-                        "This is a bug! Failed to construct trie iter for key {:?}.",
-                        event.file_key
-                    )));
-                }
-            },
+                        return Err(anyhow::anyhow!(format!(
+                            "This is a bug! Failed to construct trie iter for key {:?}.",
+                            event.file_key
+                        )));
+                    }
+                },
+            }
+        }
+
+        // Let the upload resume after these chunks if it is interrupted.
+        if let Err(e) = self
+            .storage_hub_handler
+            .file_transfer
+            .record_received_chunks(
+                event.peer,
+                event.file_key,
+                event.file_key_proof.file_metadata.chunks_count(),
+                received_chunk_ids,
+            )
+            .await
+        {
+            warn!(target: LOG_TARGET, "Failed to record the chunks received for file {:?}: {:?}", event.file_key, e);
         }
 
+        span.complete();
         Ok(())
     }
 }
@@ -261,6 +339,21 @@ where
     NT::FSH: BspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: ProcessConfirmStoringRequest) -> anyhow::Result<()> {
+        let request_file_keys = event
+            .data
+            .confirm_storing_requests
+            .iter()
+            .map(|confirm_storing_request| confirm_storing_request.file_key)
+            .collect::<Vec<_>>();
+        let mut spans = request_file_keys
+            .iter()
+            .map(|file_key| flow_span(Flow::StorageRequest, "bsp_confirm_storing", file_key))
+            .collect::<Vec<_>>();
+
+        for span in spans.iter_mut() {
+            span.fail_unless_completed();
+        }
+
         info!(
             target: LOG_TARGET,
             "Processing ConfirmStoringRequest: {:?}",
@@ -302,7 +395,25 @@ where
 
         // Query runtime for the chunks to prove for the file.
         let mut confirm_storing_requests_with_chunks_to_prove = Vec::new();
+        let mut deferred_requests = 0;
         for confirm_storing_request in event.data.confirm_storing_requests.iter() {
+            // Defer the files for which this node still has an extrinsic in flight, as confirming
+            // them now would conflict with it.
+            if self
+                .storage_hub_handler
+                .blockchain
+                .is_operation_pending(OperationKey::FileKey(confirm_storing_request.file_key))
+                .await
+            {
+                debug!(target: LOG_TARGET, "There is an operation in flight for file key {:?}, enqueuing it again", confirm_storing_request.file_key);
+                self.storage_hub_handler
+                    .blockchain
+                    .queue_confirm_bsp_request(confirm_storing_request.clone())
+                    .await?;
+                deferred_requests += 1;
+                continue;
+            }
+
             match self
                 .storage_hub_handler
                 .blockchain
@@ -332,6 +443,15 @@ where
             }
         }
 
+        // Nothing to confirm for now if all the requests were deferred.
+        if deferred_requests == event.data.confirm_storing_requests.len() {
+            return self
+                .storage_hub_handler
+                .blockchain
+                .release_forest_root_write_lock(forest_root_write_tx)
+                .await;
+        }
+
         // Generate the proof for the files and get metadatas.
         let read_file_storage = self.storage_hub_handler.file_storage.read().await;
         let mut file_keys_and_proofs = Vec::new();
@@ -495,7 +615,17 @@ where
         self.storage_hub_handler
             .blockchain
             .release_forest_root_write_lock(forest_root_write_tx)
-            .await
+            .await?;
+
+        for span in spans.iter_mut() {
+            span.complete();
+        }
+        drop(spans);
+        for file_key in request_file_keys {
+            end_flow(Flow::StorageRequest, file_key);
+        }
+
+        Ok(())
     }
 }
 
@@ -558,6 +688,26 @@ where
             }
         };
 
+        // Skip volunteering if the storage request does not meet the volunteering policy of this BSP.
+        if let Some(volunteer_policy) = &self.storage_hub_handler.provider_config.volunteer_policy {
+            let candidate = self
+                .volunteer_candidate(&volunteer_policy.policy(), own_bsp_id, &event)
+                .await?;
+
+            if let Err(rejection) = volunteer_policy.evaluate(&candidate) {
+                info!(
+                    target: LOG_TARGET,
+                    provider_id = ?own_bsp_id,
+                    file_key = ?event.file_key,
+                    correlation_id = ?event.file_key,
+                    "Skipping volunteering, storage request {}",
+                    rejection
+                );
+                end_flow(Flow::StorageRequest, H256::from(event.file_key));
+                return Ok(());
+            }
+        }
+
         let available_capacity = self
             .storage_hub_handler
             .blockchain
@@ -741,6 +891,33 @@ where
             return Err(anyhow::anyhow!(err_msg));
         }
 
+        // Skip volunteering if enough BSPs of our fleet already volunteered for the file.
+        if let Some(fleet_coordination) =
+            &self.storage_hub_handler.provider_config.fleet_coordination
+        {
+            let fleet_volunteers = self
+                .count_fleet_volunteers(
+                    fleet_coordination,
+                    own_bsp_id,
+                    file_key.into(),
+                    earliest_volunteer_tick,
+                )
+                .await?;
+
+            if fleet_volunteers >= fleet_coordination.max_volunteers_per_file {
+                info!(
+                    target: LOG_TARGET,
+                    provider_id = ?own_bsp_id,
+                    file_key = ?file_key,
+                    correlation_id = ?file_key,
+                    "Skipping volunteering, {} BSPs of the fleet already volunteer for the file",
+                    fleet_volunteers
+                );
+                end_flow(Flow::StorageRequest, H256::from(file_key));
+                return Ok(());
+            }
+        }
+
         // Optimistically register the file for upload in the file transfer service.
         // This solves the race condition between the user and the BSP, where the user could react faster
         // to the BSP volunteering than the BSP, and therefore initiate a new upload request before the
@@ -835,8 +1012,187 @@ where
         Ok(new_capacity)
     }
 
+    /// Counts the other BSPs of the fleet that volunteered for `file_key`, or are going to volunteer
+    /// for it at `volunteer_tick` (the tick at which this BSP volunteers) with precedence over it.
+    ///
+    /// BSPs of the fleet that can volunteer at the same tick take precedence by their ID, so that
+    /// all of them agree on which ones volunteer without having to talk to each other.
+    async fn count_fleet_volunteers(
+        &self,
+        fleet_coordination: &FleetCoordinationConfig,
+        own_bsp_id: BackupStorageProviderId,
+        file_key: H256,
+        volunteer_tick: BlockNumber,
+    ) -> anyhow::Result<u32> {
+        let volunteers: HashSet<BackupStorageProviderId> = self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_request(file_key)
+            .await
+            .map_err(|e| anyhow!("Failed to query storage request: {:?}", e))?
+            .ok_or_else(|| anyhow!("Storage request for file {:?} not found", file_key))?
+            .volunteers
+            .into_iter()
+            .map(|(bsp_id, _)| bsp_id)
+            .collect();
+
+        let mut fleet_volunteers = 0;
+        for bsp_id in fleet_coordination
+            .fleet_bsp_ids
+            .iter()
+            .filter(|bsp_id| **bsp_id != own_bsp_id)
+        {
+            if volunteers.contains(bsp_id) {
+                fleet_volunteers += 1;
+                continue;
+            }
+
+            if *bsp_id > own_bsp_id {
+                continue;
+            }
+
+            // A BSP that could have volunteered before this one but did not is not going to.
+            match self
+                .storage_hub_handler
+                .blockchain
+                .query_file_earliest_volunteer_tick(*bsp_id, file_key)
+                .await
+            {
+                Ok(tick) if tick == volunteer_tick => fleet_volunteers += 1,
+                Ok(_) => {}
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        provider_id = ?bsp_id,
+                        file_key = ?file_key,
+                        "Failed to query earliest volunteer tick of fleet BSP: {:?}",
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(fleet_volunteers)
+    }
+
+    /// Builds the [`VolunteerCandidate`] evaluated by the volunteering policy for the storage
+    /// request of `event`.
+    ///
+    /// Only the data needed by the rules set in `policy` is looked up.
+    async fn volunteer_candidate(
+        &self,
+        policy: &VolunteerPolicy,
+        own_bsp_id: BackupStorageProviderId,
+        event: &NewStorageRequest,
+    ) -> anyhow::Result<VolunteerCandidate> {
+        let file_size = event.size as u64;
+
+        let capacity_headroom = match policy.min_capacity_headroom {
+            Some(_) => {
+                let current_capacity = self
+                    .storage_hub_handler
+                    .blockchain
+                    .query_storage_provider_capacity(own_bsp_id)
+                    .await
+                    .map_err(|e| anyhow!("Failed to query storage provider capacity: {:?}", e))?;
+                let available_capacity = self
+                    .storage_hub_handler
+                    .blockchain
+                    .query_available_storage_capacity(own_bsp_id)
+                    .await
+                    .map_err(|e| anyhow!("Failed to query available storage capacity: {:?}", e))?;
+                let used_capacity = current_capacity.saturating_sub(available_capacity);
+
+                Some(
+                    self.storage_hub_handler
+                        .provider_config
+                        .max_storage_capacity
+                        .saturating_sub(used_capacity)
+                        .saturating_sub(file_size),
+                )
+            }
+            None => None,
+        };
+
+        let owner_over_debt_limit = match policy.max_owner_debt {
+            Some(max_owner_debt) => {
+                let users_with_debt = match self
+                    .storage_hub_handler
+                    .blockchain
+                    .query_users_with_debt(own_bsp_id, max_owner_debt)
+                    .await
+                {
+                    Ok(users_with_debt) => users_with_debt,
+                    // No user has debt with a BSP without payment streams.
+                    Err(GetUsersWithDebtOverThresholdError::ProviderWithoutPaymentStreams) => {
+                        Vec::new()
+                    }
+                    Err(e) => return Err(anyhow!("Failed to query users with debt: {:?}", e)),
+                };
+                Some(users_with_debt.contains(&event.who))
+            }
+            None => None,
+        };
+
+        let price_per_giga_unit_per_tick = match policy.min_price_per_giga_unit_per_tick {
+            Some(_) => Some(
+                self.storage_hub_handler
+                    .blockchain
+                    .query_current_price_per_giga_unit_per_tick()
+                    .await
+                    .map_err(|e| anyhow!("Failed to query current price: {:?}", e))?,
+            ),
+            None => None,
+        };
+
+        Ok(VolunteerCandidate {
+            file_key: event.file_key.into(),
+            bucket_id: event.bucket_id,
+            owner: event.who.clone(),
+            file_size,
+            capacity_headroom,
+            owner_over_debt_limit,
+            price_per_giga_unit_per_tick,
+        })
+    }
+
+    async fn handle_reverted_new_storage_request_event(
+        &mut self,
+        file_key: H256,
+    ) -> anyhow::Result<()> {
+        // The storage request might have been included in the new best chain as well.
+        let storage_request = self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_request(file_key)
+            .await
+            .map_err(|e| anyhow!("Failed to query storage request {:?}: {:?}", file_key, e))?;
+        if storage_request.is_some() {
+            info!(target: LOG_TARGET, "Storage request for file key {:?} is still open in the new best chain. Keeping the file.", file_key);
+            return Ok(());
+        }
+
+        let current_forest_key = CURRENT_FOREST_KEY.to_vec();
+        let fs = self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&current_forest_key)
+            .await
+            .ok_or_else(|| anyhow!("Failed to get forest storage."))?;
+        if fs.read().await.contains_file_key(&file_key)? {
+            info!(target: LOG_TARGET, "File key {:?} is already in the Forest. Keeping the file.", file_key);
+            return Ok(());
+        }
+
+        self.unvolunteer_file(file_key).await;
+
+        Ok(())
+    }
+
     async fn unvolunteer_file(&self, file_key: H256) {
         warn!(target: LOG_TARGET, "Unvolunteering file {:?}", file_key);
+        end_flow(Flow::FileTransfer, file_key);
+        end_flow(Flow::StorageRequest, file_key);
 
         // Unregister the file from the file transfer service.
         // The error is ignored, as the file might already be unregistered.
@@ -862,6 +1218,7 @@ where
 
     async fn on_file_complete(&self, file_key: &H256) -> anyhow::Result<()> {
         info!(target: LOG_TARGET, "File upload complete ({:?})", file_key);
+        end_flow(Flow::FileTransfer, file_key);
 
         // Unregister the file from the file transfer service.
         self.storage_hub_handler
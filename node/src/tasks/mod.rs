@@ -1,14 +1,30 @@
+//! The tasks of a StorageHub client, reacting to the events emitted by its services.
+//!
+//! Every task logs under its own target, named after the task (i.e. `bsp-submit-proof-task`), so
+//! that the verbosity of a single task can be raised at runtime with the `setLogFilter` RPC,
+//! instead of the whole node's. Task logs include the following structured fields, whenever they
+//! apply to what is being logged:
+//! - `provider_id`: the ID of the Provider the task acts for, or on.
+//! - `file_key`: the key of the file the task handles.
+//! - `correlation_id`: the correlation ID of the [`Flow`](shc_telemetry::Flow) the task is a step
+//!   of, as recorded in its traces.
+
 pub mod bsp_charge_fees;
 pub mod bsp_delete_file;
 pub mod bsp_download_file;
 pub mod bsp_move_bucket;
+pub mod bsp_reconcile_capacity;
 pub mod bsp_submit_proof;
 pub mod bsp_upload_file;
 pub mod mock_bsp_volunteer;
 pub mod mock_sp_react_to_event;
+pub mod msp_bucket_gc;
 pub mod msp_charge_fees;
 pub mod msp_delete_bucket;
 pub mod msp_move_bucket;
 pub mod msp_upload_file;
+pub mod reload_config;
+pub mod sp_repair_file;
 pub mod sp_slash_provider;
 pub mod user_sends_file;
+pub mod user_upload_eta;
@@ -0,0 +1,254 @@
+use anyhow::anyhow;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use codec::{Decode, Encode};
+use sc_tracing::tracing::*;
+
+use shc_actors_framework::event_bus::EventHandler;
+use shc_blockchain_service::{
+    commands::BlockchainServiceInterface, events::FinalisedMoveBucketAccepted,
+};
+use shc_common::types::{BucketId, StorageProviderId};
+use shc_file_manager::traits::FileStorage;
+use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_job_queue::{JobContext, JobDefinition, JobHandler};
+
+use crate::services::{
+    handler::StorageHubHandler,
+    types::{MspForestStorageHandlerT, ShNodeType},
+};
+
+const LOG_TARGET: &str = "msp-bucket-gc-task";
+
+/// Maximum time the job waits for before checking whether it was cancelled.
+const CANCELLATION_CHECK_PERIOD: Duration = Duration::from_secs(60);
+
+/// Job deleting the files and forest of a bucket moved away from this MSP. See [`MspBucketGcTask`].
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct MspBucketGcJob {
+    pub bucket_id: BucketId,
+    /// Milliseconds since the UNIX epoch after which the bucket is deleted.
+    pub delete_after: u64,
+}
+
+impl JobDefinition for MspBucketGcJob {
+    const KIND: &'static str = "msp-bucket-gc";
+}
+
+/// [`MspBucketGcTask`]: Handles the [`FinalisedMoveBucketAccepted`] event, and runs the
+/// [`MspBucketGcJob`]s submitted for the buckets moved away from this MSP.
+///
+/// The old MSP of a moved bucket is not notified on-chain, so every bucket moved to another MSP is
+/// checked against the forests stored by this MSP.
+pub struct MspBucketGcTask<NT>
+where
+    NT: ShNodeType,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    storage_hub_handler: StorageHubHandler<NT>,
+}
+
+impl<NT> Clone for MspBucketGcTask<NT>
+where
+    NT: ShNodeType,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    fn clone(&self) -> MspBucketGcTask<NT> {
+        Self {
+            storage_hub_handler: self.storage_hub_handler.clone(),
+        }
+    }
+}
+
+impl<NT> MspBucketGcTask<NT>
+where
+    NT: ShNodeType,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    pub fn new(storage_hub_handler: StorageHubHandler<NT>) -> Self {
+        Self {
+            storage_hub_handler,
+        }
+    }
+}
+
+/// Handles the [`FinalisedMoveBucketAccepted`] event.
+///
+/// This event is triggered by an on-chain event which is part of a finalized block, so the move
+/// can't be reverted by a reorg.
+///
+/// If this MSP stores the bucket, a [`MspBucketGcJob`] is submitted to delete it once the
+/// configured retention period has passed, so that it is deleted even if the node restarts in the
+/// meantime.
+impl<NT> EventHandler<FinalisedMoveBucketAccepted> for MspBucketGcTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    async fn handle_event(&mut self, event: FinalisedMoveBucketAccepted) -> anyhow::Result<()> {
+        let bucket = event.bucket_id.as_ref().to_vec();
+        if self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&bucket)
+            .await
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        let retention_period = self
+            .storage_hub_handler
+            .provider_config
+            .bucket_gc_retention_period;
+        let delete_after = now_millis()?.saturating_add(retention_period.saturating_mul(1000));
+
+        let job_id = self.storage_hub_handler.job_queue.submit(MspBucketGcJob {
+            bucket_id: event.bucket_id,
+            delete_after,
+        })?;
+
+        info!(
+            target: LOG_TARGET,
+            "Bucket {:?} was moved to MSP {:?}, deleting it in {} seconds in job {}",
+            event.bucket_id,
+            event.new_msp_id,
+            retention_period,
+            job_id,
+        );
+
+        Ok(())
+    }
+}
+
+/// Deletes the files and forest of a bucket moved away from this MSP, once its retention period
+/// has passed.
+///
+/// The bucket is kept if it was moved back to this MSP in the meantime.
+impl<NT> JobHandler for MspBucketGcTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    type Job = MspBucketGcJob;
+
+    async fn run(&self, job: MspBucketGcJob, context: JobContext) -> anyhow::Result<()> {
+        // Wait for the retention period to pass, picking up from where it was if the node restarted.
+        loop {
+            if context.is_cancelled() {
+                info!(
+                    target: LOG_TARGET,
+                    "Not deleting bucket {:?}, job {} was cancelled",
+                    job.bucket_id,
+                    context.id(),
+                );
+                return Ok(());
+            }
+
+            let remaining = job.delete_after.saturating_sub(now_millis()?);
+            if remaining == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(remaining).min(CANCELLATION_CHECK_PERIOD))
+                .await;
+        }
+
+        if self.is_bucket_stored_by_this_msp(job.bucket_id).await? {
+            info!(
+                target: LOG_TARGET,
+                "Not deleting bucket {:?}, it was moved back to this MSP",
+                job.bucket_id,
+            );
+            return Ok(());
+        }
+
+        let bucket = job.bucket_id.as_ref().to_vec();
+
+        // Add up the size of the files in the bucket before deleting them.
+        let Some(forest_storage) = self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&bucket)
+            .await
+        else {
+            debug!(
+                target: LOG_TARGET,
+                "Bucket {:?} is no longer stored, nothing to delete",
+                job.bucket_id,
+            );
+            return Ok(());
+        };
+        let files = forest_storage
+            .read()
+            .await
+            .get_all_files()
+            .map_err(|e| anyhow!("Failed to get the files of the bucket: {:?}", e))?;
+        drop(forest_storage);
+        let reclaimed_bytes: u64 = files
+            .iter()
+            .map(|(_, file_metadata)| file_metadata.file_size)
+            .sum();
+
+        self.storage_hub_handler
+            .file_storage
+            .write()
+            .await
+            .delete_files_with_prefix(
+                &job.bucket_id
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid bucket id"))?,
+            )
+            .map_err(|e| anyhow!("Failed to delete files with prefix: {:?}", e))?;
+
+        self.storage_hub_handler
+            .forest_storage_handler
+            .clone()
+            .remove_forest_storage(&bucket)
+            .await;
+
+        shc_telemetry::record_bucket_reclaimed(reclaimed_bytes);
+
+        info!(
+            target: LOG_TARGET,
+            "Deleted bucket {:?} moved away from this MSP: {} files, {} bytes reclaimed",
+            job.bucket_id,
+            files.len(),
+            reclaimed_bytes,
+        );
+
+        Ok(())
+    }
+}
+
+impl<NT> MspBucketGcTask<NT>
+where
+    NT: ShNodeType,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    /// Whether the bucket is currently stored by this MSP on-chain.
+    async fn is_bucket_stored_by_this_msp(&self, bucket_id: BucketId) -> anyhow::Result<bool> {
+        let Some(StorageProviderId::MainStorageProvider(own_msp_id)) = self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_provider_id(None)
+            .await?
+        else {
+            return Err(anyhow!("Failed to get own MSP ID"));
+        };
+
+        // The bucket might have been deleted on-chain, in which case it is deleted here too.
+        let msp_id = self
+            .storage_hub_handler
+            .blockchain
+            .query_msp_id_of_bucket_id(bucket_id)
+            .await
+            .unwrap_or_default();
+
+        Ok(msp_id == Some(own_msp_id))
+    }
+}
+
+fn now_millis() -> anyhow::Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64)
+}
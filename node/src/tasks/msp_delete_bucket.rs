@@ -68,9 +68,9 @@ where
     ) -> anyhow::Result<()> {
         info!(
             target: LOG_TARGET,
-            "Deleting bucket {:?} for MSP {:?}",
-            event.bucket_id,
-            event.msp_id
+            provider_id = ?event.msp_id,
+            "Deleting bucket {:?}",
+            event.bucket_id
         );
 
         let file_storage = self.storage_hub_handler.file_storage.clone();
@@ -1,9 +1,11 @@
+use anyhow::anyhow;
 use rand::seq::SliceRandom;
 use shc_file_manager::traits::FileStorage;
-use std::time::Duration;
+use std::{collections::BTreeSet, time::Duration};
 
-use codec::Decode;
+use codec::{Decode, Encode};
 use sc_tracing::tracing::*;
+use sp_core::H256;
 
 use pallet_file_system::types::BucketMoveRequestResponse;
 use shc_actors_framework::event_bus::EventHandler;
@@ -11,9 +13,16 @@ use shc_blockchain_service::types::Tip;
 use shc_blockchain_service::{
     commands::BlockchainServiceInterface, events::MoveBucketRequestedForNewMsp,
 };
-use shc_common::types::{FileKeyProof, HashT, StorageProofsMerkleTrieLayout};
+use shc_common::types::{
+    BucketId, FileKeyProof, FileMetadata, HashT, StorageProofsMerkleTrieLayout,
+};
 use shc_file_transfer_service::commands::FileTransferServiceInterface;
-use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_forest_manager::{
+    traits::{ForestStorage, ForestStorageHandler},
+    write_batch::ForestWriteBatch,
+};
+use shc_indexer_db::DbConnection;
+use shc_job_queue::{JobContext, JobDefinition, JobHandler};
 use shp_file_metadata::ChunkId;
 
 use crate::services::{
@@ -24,7 +33,18 @@ const LOG_TARGET: &str = "msp-move-bucket-task";
 
 const DOWNLOAD_REQUEST_RETRY_COUNT: usize = 30;
 
-/// [`MspMoveBucketTask`]: Handles the [`MoveBucketRequestedForNewMsp`] event.
+/// Job downloading the files of a bucket moved to this MSP. See [`MspMoveBucketTask`].
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct MspMoveBucketJob {
+    pub bucket_id: BucketId,
+}
+
+impl JobDefinition for MspMoveBucketJob {
+    const KIND: &'static str = "msp-move-bucket";
+}
+
+/// [`MspMoveBucketTask`]: Handles the [`MoveBucketRequestedForNewMsp`] event, and runs the
+/// [`MspMoveBucketJob`]s submitted when accepting a move.
 pub struct MspMoveBucketTask<NT>
 where
     NT: ShNodeType,
@@ -64,8 +84,8 @@ where
 /// (i.e. have a track record of the files and their BSPs, enough size, etc.) and then accept
 /// or reject the request.
 ///
-/// If we accept the request, we need to start downloading the files from the bucket and insert
-/// them into our forest storage.
+/// If we accept the request, we submit a [`MspMoveBucketJob`] to download the files from the
+/// bucket and insert them into our forest storage.
 impl<NT> EventHandler<MoveBucketRequestedForNewMsp> for MspMoveBucketTask<NT>
 where
     NT: ShNodeType + 'static,
@@ -140,16 +160,56 @@ where
             .watch_for_success(&self.storage_hub_handler.blockchain)
             .await?;
 
-        let bucket = event.bucket_id.as_ref().to_vec();
+        // Download the files of the bucket in a job, so that it is resumed if the node restarts.
+        let job_id = self
+            .storage_hub_handler
+            .job_queue
+            .submit(MspMoveBucketJob {
+                bucket_id: event.bucket_id,
+            })?;
+
+        info!(
+            target: LOG_TARGET,
+            "MSP: downloading the files of bucket {:?} in job {}",
+            event.bucket_id,
+            job_id,
+        );
+
+        Ok(())
+    }
+}
+
+/// Downloads the files of a bucket moved to this MSP and inserts them into its forest storage.
+///
+/// The checkpoint of the job is the set of files already downloaded, which are skipped when it is
+/// retried or resumed. The job fails if any file could not be downloaded, so that it is retried.
+impl<NT> JobHandler for MspMoveBucketTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    type Job = MspMoveBucketJob;
+
+    async fn run(&self, job: MspMoveBucketJob, context: JobContext) -> anyhow::Result<()> {
+        let indexer_db_pool = self
+            .storage_hub_handler
+            .indexer_db_pool
+            .clone()
+            .ok_or_else(|| anyhow!("Indexer is disabled, cannot download the files of a bucket"))?;
+
+        let bucket = job.bucket_id.as_ref().to_vec();
 
         let mut indexer_connection = indexer_db_pool.get().await?;
 
         let forest_storage = self
             .storage_hub_handler
             .forest_storage_handler
-            .get_or_create(&event.bucket_id.as_ref().to_vec())
+            .get_or_create(&bucket)
             .await;
 
+        let mut downloaded_files = context.checkpoint::<BTreeSet<H256>>()?.unwrap_or_default();
+        let mut failed_files = 0;
+
         // TODO(improvement): Parallelize this.
         for file in shc_indexer_db::models::File::get_by_onchain_bucket_id(
             &mut indexer_connection,
@@ -157,75 +217,177 @@ where
         )
         .await?
         {
+            if context.is_cancelled() {
+                info!(
+                    target: LOG_TARGET,
+                    "MSP: stopped downloading the files of bucket {:?}, job {} was cancelled",
+                    job.bucket_id,
+                    context.id(),
+                );
+                return Ok(());
+            }
+
+            let file_metadata = file.to_file_metadata(bucket.clone());
+            let file_key = file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>();
+
+            if downloaded_files.contains(&file_key) {
+                continue;
+            }
+
             info!(
                 target: LOG_TARGET,
                 "MSP: downloading file {:?} of bucket {:?}",
-                file.file_key,
-                event.bucket_id,
+                file_key,
+                job.bucket_id,
             );
 
-            let file_metadata = file.to_file_metadata(bucket.clone());
-            let file_key = file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>();
+            if let Err(error) = self
+                .download_file(
+                    &file,
+                    &file_metadata,
+                    job.bucket_id,
+                    &mut indexer_connection,
+                )
+                .await
+            {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to download file {:?} of bucket {:?}: {:?}",
+                    file_key, job.bucket_id, error
+                );
+                failed_files += 1;
+                continue;
+            }
+
+            info!(
+                target: LOG_TARGET,
+                "MSP: inserting downloaded file {:?} of bucket {:?} to forest storage",
+                file_key,
+                job.bucket_id,
+            );
 
             // TODO: Check and insert before accepting the bucket move request.
-            self.storage_hub_handler
-                .file_storage
-                .write()
-                .await
+            let mut batch = ForestWriteBatch::lock(forest_storage.clone()).await;
+            if let Err(error) = batch.insert_files_metadata(&[file_metadata]) {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to insert file {:?} to forest storage: {:?}",
+                    file_key, error
+                );
+                failed_files += 1;
+                continue;
+            }
+            // Write it before checkpointing the file as downloaded.
+            if let Err(error) = batch.flush() {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to write file {:?} to forest storage: {:?}",
+                    file_key, error
+                );
+                failed_files += 1;
+                continue;
+            }
+
+            downloaded_files.insert(file_key);
+            context.save_checkpoint(&downloaded_files)?;
+        }
+
+        if failed_files > 0 {
+            return Err(anyhow!(
+                "Failed to download {} files of bucket {:?}",
+                failed_files,
+                job.bucket_id
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<NT> MspMoveBucketTask<NT>
+where
+    NT: ShNodeType,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    /// Downloads every chunk of `file` that is not in the file storage yet from the BSPs storing it.
+    async fn download_file(
+        &self,
+        file: &shc_indexer_db::models::File,
+        file_metadata: &FileMetadata,
+        bucket_id: BucketId,
+        indexer_connection: &mut DbConnection<'_>,
+    ) -> anyhow::Result<()> {
+        let file_key = file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>();
+
+        // The file might have been inserted already by a previous attempt of the job.
+        let mut file_storage = self.storage_hub_handler.file_storage.write().await;
+        if file_storage
+            .get_metadata(&file_key)
+            .map_err(|error| anyhow!("Failed to get file metadata: {:?}", error))?
+            .is_none()
+        {
+            // TODO: Check and insert before accepting the bucket move request.
+            file_storage
                 .insert_file(file_key, file_metadata.clone())
-                .expect("Failed to insert file into file storage");
+                .map_err(|error| anyhow!("Failed to insert file into file storage: {:?}", error))?;
+        }
+        drop(file_storage);
 
-            let chunks_count = file_metadata.chunks_count();
+        let chunks_count = file_metadata.chunks_count();
 
-            let mut bsp_peer_ids = file.get_bsp_peer_ids(&mut indexer_connection).await?;
+        let mut bsp_peer_ids = file.get_bsp_peer_ids(indexer_connection).await?;
 
-            // Shuffle in order to avoid consecutive requests to the same BSP node.
-            bsp_peer_ids.shuffle(&mut rand::thread_rng());
+        // Shuffle in order to avoid consecutive requests to the same BSP node.
+        bsp_peer_ids.shuffle(&mut rand::thread_rng());
 
-            if bsp_peer_ids.is_empty() {
-                error!(
-                    target: LOG_TARGET,
-                    "No BSP peer IDs found for file {:?} of bucket {:?}",
-                    file_key, event.bucket_id,
-                );
+        if bsp_peer_ids.is_empty() {
+            return Err(anyhow!("No BSP peer IDs found"));
+        }
+
+        // We will cycle through all the BSP peer IDs for each chunk until we successfully
+        // download the file.
+        let mut bsp_peer_ids_iter = bsp_peer_ids.iter().cycle();
+
+        for chunk in 0..chunks_count {
+            let chunk_id = ChunkId::new(chunk);
+
+            // Skip the chunks downloaded by a previous attempt of the job.
+            if self
+                .storage_hub_handler
+                .file_storage
+                .read()
+                .await
+                .get_chunk(&file_key, &chunk_id)
+                .is_ok()
+            {
                 continue;
             }
 
-            // We will cycle through all the BSP peer IDs for each chunk until we successfully
-            // download the file.
-            let mut bsp_peer_ids_iter = bsp_peer_ids.iter().cycle();
-
-            for chunk in 0..chunks_count {
-                for _ in 0..DOWNLOAD_REQUEST_RETRY_COUNT {
-                    // This can fail only if the BSP peer IDs are empty - which we already checked.
-                    let peer_id = bsp_peer_ids_iter.next().unwrap();
-
-                    let download_request = self
-                        .storage_hub_handler
-                        .file_transfer
-                        .download_request(
-                            *peer_id,
-                            file_key.into(),
-                            ChunkId::new(chunk),
-                            Some(event.bucket_id),
-                        )
-                        .await;
-
-                    let download_request = match download_request {
-                        Ok(download_request) => download_request,
-                        Err(error) => {
-                            error!(
-                                target: LOG_TARGET,
-                                "Failed to download chunk {:?} of file {:?} from peer {:?}: {:?}",
-                                chunk, file_key, peer_id, error
-                            );
-                            continue;
-                        }
-                    };
+            let mut downloaded = false;
+            for _ in 0..DOWNLOAD_REQUEST_RETRY_COUNT {
+                // This can fail only if the BSP peer IDs are empty - which we already checked.
+                let peer_id = bsp_peer_ids_iter.next().unwrap();
+
+                let download_request = self
+                    .storage_hub_handler
+                    .file_transfer
+                    .download_request(*peer_id, file_key.into(), chunk_id, Some(bucket_id))
+                    .await;
 
-                    let file_key_proof = match FileKeyProof::decode(
-                        &mut download_request.file_key_proof.as_ref(),
-                    ) {
+                let download_request = match download_request {
+                    Ok(download_request) => download_request,
+                    Err(error) => {
+                        error!(
+                            target: LOG_TARGET,
+                            "Failed to download chunk {:?} of file {:?} from peer {:?}: {:?}",
+                            chunk, file_key, peer_id, error
+                        );
+                        continue;
+                    }
+                };
+
+                let file_key_proof =
+                    match FileKeyProof::decode(&mut download_request.file_key_proof.as_ref()) {
                         Ok(file_key_proof) => file_key_proof,
                         Err(error) => {
                             error!(
@@ -237,78 +399,63 @@ where
                         }
                     };
 
-                    let proven = match file_key_proof.proven::<StorageProofsMerkleTrieLayout>() {
-                        Ok(chunk_data) => chunk_data,
-                        Err(error) => {
-                            error!(
-                                target: LOG_TARGET,
-                                "Failed to get proven data for file key proof: {:?}",
-                                error
-                            );
-                            continue;
-                        }
-                    };
-
-                    if proven.len() != 1 {
+                let proven = match file_key_proof.proven::<StorageProofsMerkleTrieLayout>() {
+                    Ok(chunk_data) => chunk_data,
+                    Err(error) => {
                         error!(
                             target: LOG_TARGET,
-                            "Expected exactly one proven chunk but got {}.",
-                            proven.len()
+                            "Failed to get proven data for file key proof: {:?}",
+                            error
                         );
                         continue;
                     }
+                };
+
+                if proven.len() != 1 {
+                    error!(
+                        target: LOG_TARGET,
+                        "Expected exactly one proven chunk but got {}.",
+                        proven.len()
+                    );
+                    continue;
+                }
 
-                    let chunk_data = proven[0].data.clone();
-                    let chunk_id = ChunkId::new(chunk);
+                let chunk_data = proven[0].data.clone();
 
-                    if chunk_id != proven[0].key {
-                        error!(
-                            target: LOG_TARGET,
-                            "Expected chunk id {:?} but got {:?}",
-                            chunk, proven[0].key
-                        );
-                        continue;
-                    }
+                if chunk_id != proven[0].key {
+                    error!(
+                        target: LOG_TARGET,
+                        "Expected chunk id {:?} but got {:?}",
+                        chunk, proven[0].key
+                    );
+                    continue;
+                }
 
-                    if let Err(error) = self
-                        .storage_hub_handler
-                        .file_storage
-                        .write()
-                        .await
-                        .write_chunk(&file_key, &chunk_id, &chunk_data)
-                    {
-                        // TODO: Handle this error better.
-                        error!(
-                            target: LOG_TARGET,
-                            "Failed to write chunk {:?} of file {:?} to storage: {:?}",
-                            chunk, file_key, error
-                        );
-                    } else {
-                        // We successfully downloaded the chunk, so we can break out of the retry loop.
-                        break;
-                    }
+                if let Err(error) = self
+                    .storage_hub_handler
+                    .file_storage
+                    .write()
+                    .await
+                    .write_chunk(&file_key, &chunk_id, &chunk_data)
+                {
+                    error!(
+                        target: LOG_TARGET,
+                        "Failed to write chunk {:?} of file {:?} to storage: {:?}",
+                        chunk, file_key, error
+                    );
+                } else {
+                    // We successfully downloaded the chunk, so we can break out of the retry loop.
+                    downloaded = true;
+                    break;
                 }
             }
 
-            info!(
-                target: LOG_TARGET,
-                "MSP: inserting downloaded file {:?} of bucket {:?} to forest storage",
-                file_key,
-                event.bucket_id,
-            );
-
-            // TODO: Check and insert before accepting the bucket move request.
-            if let Err(error) = forest_storage
-                .write()
-                .await
-                .insert_files_metadata(&[file_metadata])
-            {
-                // TODO: Handle this error better.
-                error!(
-                    target: LOG_TARGET,
-                    "Failed to insert file {:?} to forest storage: {:?}",
-                    file_key, error
-                );
+            if !downloaded {
+                return Err(anyhow!(
+                    "Failed to download chunk {:?} after {} attempts",
+                    chunk,
+                    DOWNLOAD_REQUEST_RETRY_COUNT
+                ));
             }
         }
 
@@ -9,18 +9,20 @@ use sp_core::{bounded_vec, H256};
 use sp_runtime::AccountId32;
 
 use shc_actors_framework::event_bus::EventHandler;
-use shc_blockchain_service::events::ProcessMspRespondStoringRequest;
+use shc_blockchain_service::events::{ProcessMspRespondStoringRequest, Reverted};
 use shc_blockchain_service::{commands::BlockchainServiceInterface, events::NewStorageRequest};
 use shc_common::types::{
     FileKey, FileKeyWithProof, FileMetadata, HashT, RejectedStorageRequestReason,
     StorageProofsMerkleTrieLayout, StorageProviderId, StorageRequestMspAcceptedFileKeys,
-    StorageRequestMspBucketResponse,
+    StorageRequestMspBucketResponse, FILE_CHUNK_SIZE,
 };
 use shc_file_manager::traits::{FileStorage, FileStorageWriteError, FileStorageWriteOutcome};
 use shc_file_transfer_service::{
-    commands::FileTransferServiceInterface, events::RemoteUploadRequest,
+    chunk_size::MAX_TRANSFER_CHUNK_SIZE, commands::FileTransferServiceInterface,
+    events::RemoteUploadRequest, TransferOutcome,
 };
 use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_telemetry::{end_flow, flow_span, Flow};
 use storage_hub_runtime::StorageDataUnit;
 
 use crate::services::types::ShNodeType;
@@ -50,6 +52,9 @@ const LOG_TARGET: &str = "msp-upload-file-task";
 ///   which will emit an event that describes the final result of the batch response (i.e. all accepted,
 ///   rejected and/or failed file keys). The MSP will then apply the necessary deltas to each one of the bucket's
 ///   forest storage to reflect the result.
+///
+/// If the block with the storage request is reorged out, the [`Reverted`] [`NewStorageRequest`]
+/// event undoes the first part of the flow.
 pub struct MspUploadFileTask<NT>
 where
     NT: ShNodeType,
@@ -99,16 +104,22 @@ where
     NT::FSH: MspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: NewStorageRequest) -> anyhow::Result<()> {
+        let file_key: H256 = event.file_key.into();
         info!(
             target: LOG_TARGET,
-            "Registering user peer for file_key {:?}, location {:?}, fingerprint {:?}",
-            event.file_key,
+            file_key = ?file_key,
+            correlation_id = ?file_key,
+            "Registering user peer for location {:?}, fingerprint {:?}",
             event.location,
             event.fingerprint
         );
 
+        let mut span = flow_span(Flow::StorageRequest, "msp_register_upload", file_key);
+
         let result = self.handle_new_storage_request_event(event).await;
+        span.record_result(&result);
         if result.is_err() {
+            end_flow(Flow::StorageRequest, file_key);
             if let Some(file_key) = &self.file_key_cleanup {
                 self.unregister_file(*file_key).await?;
             }
@@ -117,6 +128,32 @@ where
     }
 }
 
+/// Handles the [`Reverted`] [`NewStorageRequest`] event.
+///
+/// This event is triggered when the block with a storage request is reorged out. Unless the storage
+/// request is still open in the new best chain, or the file is already in its bucket's Forest, the
+/// file is unregistered from the File Transfer Service and whatever was received of it is deleted.
+impl<NT> EventHandler<Reverted<NewStorageRequest>> for MspUploadFileTask<NT>
+where
+    NT: ShNodeType + 'static,
+    NT::FSH: MspForestStorageHandlerT,
+{
+    async fn handle_event(&mut self, event: Reverted<NewStorageRequest>) -> anyhow::Result<()> {
+        let file_key: H256 = event.event.file_key.into();
+        info!(
+            target: LOG_TARGET,
+            file_key = ?file_key,
+            correlation_id = ?file_key,
+            "Storage request reverted, since block #{} ({:?}) was reorged out",
+            event.block_number,
+            event.block_hash
+        );
+
+        self.handle_reverted_new_storage_request_event(event.event)
+            .await
+    }
+}
+
 /// Handles the [`RemoteUploadRequest`] event.
 ///
 /// This event is triggered by a user sending a chunk of the file to the MSP. It checks the proof
@@ -127,6 +164,10 @@ where
     NT::FSH: MspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: RemoteUploadRequest) -> anyhow::Result<()> {
+        let file_key: H256 = event.file_key.into();
+        let mut span = flow_span(Flow::FileTransfer, "msp_receive_chunk", file_key);
+        span.fail_unless_completed();
+
         trace!(target: LOG_TARGET, "Received remote upload request for file {:?} and peer {:?}", event.file_key, event.peer);
 
         let proven = match event
@@ -134,13 +175,16 @@ where
             .proven::<StorageProofsMerkleTrieLayout>()
         {
             Ok(proven) => {
-                if proven.len() != 1 {
+                if proven.is_empty()
+                    || proven.len() as u64 > MAX_TRANSFER_CHUNK_SIZE / FILE_CHUNK_SIZE
+                {
                     Err(anyhow::anyhow!(
-                        "Expected exactly one proven chunk but got {}.",
+                        "Expected between 1 and {} proven chunks but got {}.",
+                        MAX_TRANSFER_CHUNK_SIZE / FILE_CHUNK_SIZE,
                         proven.len()
                     ))
                 } else {
-                    Ok(proven[0].clone())
+                    Ok(proven)
                 }
             }
             Err(e) => Err(anyhow::anyhow!(
@@ -177,6 +221,12 @@ where
             Err(e) => {
                 warn!(target: LOG_TARGET, "{}", e);
 
+                // The peer sent chunks that don't match the fingerprint of the file.
+                self.storage_hub_handler
+                    .file_transfer
+                    .report_peer(event.peer, TransferOutcome::Corrupt)
+                    .await;
+
                 let call = storage_hub_runtime::RuntimeCall::FileSystem(
                     pallet_file_system::Call::msp_respond_storage_requests_multiple_buckets {
                         storage_request_msp_response: bounded_vec![
@@ -206,31 +256,37 @@ where
             }
         };
 
-        let mut write_file_storage = self.storage_hub_handler.file_storage.write().await;
-        let write_chunk_result =
-            write_file_storage.write_chunk(&event.file_key.into(), &proven.key, &proven.data);
-        // Release the file storage write lock as soon as possible.
-        drop(write_file_storage);
-
-        match write_chunk_result {
-            Ok(outcome) => match outcome {
-                FileStorageWriteOutcome::FileComplete => {
-                    self.on_file_complete(&event.file_key.into()).await?;
-                }
-                FileStorageWriteOutcome::FileIncomplete => {}
-            },
-            Err(error) => match error {
-                FileStorageWriteError::FileChunkAlreadyExists => {
-                    warn!(
-                        target: LOG_TARGET,
-                        "Received duplicate chunk with key: {:?}",
-                        proven.key
-                    );
-
-                    // TODO: Consider informing this to the file transfer service so that it can handle reputation for this peer id.
-                }
-                FileStorageWriteError::FileDoesNotExist => {
-                    let call = storage_hub_runtime::RuntimeCall::FileSystem(
+        // Write the chunks of the transfer chunk, one file chunk at a time.
+        let mut received_chunk_ids = Vec::with_capacity(proven.len());
+        for chunk in proven {
+            let mut write_file_storage = self.storage_hub_handler.file_storage.write().await;
+            let write_chunk_result =
+                write_file_storage.write_chunk(&event.file_key.into(), &chunk.key, &chunk.data);
+            // Release the file storage write lock as soon as possible.
+            drop(write_file_storage);
+
+            match write_chunk_result {
+                Ok(outcome) => match outcome {
+                    FileStorageWriteOutcome::FileComplete => {
+                        self.on_file_complete(&event.file_key.into()).await?;
+                    }
+                    FileStorageWriteOutcome::FileIncomplete => {
+                        received_chunk_ids.push(chunk.key);
+                    }
+                },
+                Err(error) => match error {
+                    FileStorageWriteError::FileChunkAlreadyExists => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Received duplicate chunk with key: {:?}",
+                            chunk.key
+                        );
+                        received_chunk_ids.push(chunk.key);
+
+                        // TODO: Consider informing this to the file transfer service so that it can handle reputation for this peer id.
+                    }
+                    FileStorageWriteError::FileDoesNotExist => {
+                        let call = storage_hub_runtime::RuntimeCall::FileSystem(
                         pallet_file_system::Call::msp_respond_storage_requests_multiple_buckets {
                             storage_request_msp_response: bounded_vec![
                                 StorageRequestMspBucketResponse {
@@ -245,31 +301,31 @@ where
                         },
                     );
 
-                    self.storage_hub_handler
-                        .blockchain
-                        .send_extrinsic(call, Tip::from(0))
-                        .await?
-                        .with_timeout(Duration::from_secs(60))
-                        .watch_for_success(&self.storage_hub_handler.blockchain)
-                        .await?;
+                        self.storage_hub_handler
+                            .blockchain
+                            .send_extrinsic(call, Tip::from(0))
+                            .await?
+                            .with_timeout(Duration::from_secs(60))
+                            .watch_for_success(&self.storage_hub_handler.blockchain)
+                            .await?;
 
-                    // Unregister the file.
-                    self.unregister_file(event.file_key.into()).await?;
+                        // Unregister the file.
+                        self.unregister_file(event.file_key.into()).await?;
 
-                    return Err(anyhow::anyhow!(format!("File does not exist for key {:?}. Maybe we forgot to unregister before deleting?", event.file_key)));
-                }
-                FileStorageWriteError::FailedToGetFileChunk
-                | FileStorageWriteError::FailedToInsertFileChunk
-                | FileStorageWriteError::FailedToDeleteChunk
-                | FileStorageWriteError::FailedToPersistChanges
-                | FileStorageWriteError::FailedToParseFileMetadata
-                | FileStorageWriteError::FailedToParseFingerprint
-                | FileStorageWriteError::FailedToReadStorage
-                | FileStorageWriteError::FailedToUpdatePartialRoot
-                | FileStorageWriteError::FailedToParsePartialRoot
-                | FileStorageWriteError::FailedToGetStoredChunksCount => {
-                    // This internal error should not happen.
-                    let call = storage_hub_runtime::RuntimeCall::FileSystem(
+                        return Err(anyhow::anyhow!(format!("File does not exist for key {:?}. Maybe we forgot to unregister before deleting?", event.file_key)));
+                    }
+                    FileStorageWriteError::FailedToGetFileChunk
+                    | FileStorageWriteError::FailedToInsertFileChunk
+                    | FileStorageWriteError::FailedToDeleteChunk
+                    | FileStorageWriteError::FailedToPersistChanges
+                    | FileStorageWriteError::FailedToParseFileMetadata
+                    | FileStorageWriteError::FailedToParseFingerprint
+                    | FileStorageWriteError::FailedToReadStorage
+                    | FileStorageWriteError::FailedToUpdatePartialRoot
+                    | FileStorageWriteError::FailedToParsePartialRoot
+                    | FileStorageWriteError::FailedToGetStoredChunksCount => {
+                        // This internal error should not happen.
+                        let call = storage_hub_runtime::RuntimeCall::FileSystem(
                         pallet_file_system::Call::msp_respond_storage_requests_multiple_buckets {
                             storage_request_msp_response: bounded_vec![
                                 StorageRequestMspBucketResponse {
@@ -284,26 +340,26 @@ where
                         },
                     );
 
-                    self.storage_hub_handler
-                        .blockchain
-                        .send_extrinsic(call, Tip::from(0))
-                        .await?
-                        .with_timeout(Duration::from_secs(60))
-                        .watch_for_success(&self.storage_hub_handler.blockchain)
-                        .await?;
-
-                    // Unregister the file.
-                    self.unregister_file(event.file_key.into()).await?;
-
-                    return Err(anyhow::anyhow!(format!(
-                        "Internal trie read/write error {:?}:{:?}",
-                        event.file_key, proven.key
-                    )));
-                }
-                FileStorageWriteError::FingerprintAndStoredFileMismatch => {
-                    // This should never happen, given that the first check in the handler is verifying the proof.
-                    // This means that something is seriously wrong, so we error out the whole task.
-                    let call = storage_hub_runtime::RuntimeCall::FileSystem(
+                        self.storage_hub_handler
+                            .blockchain
+                            .send_extrinsic(call, Tip::from(0))
+                            .await?
+                            .with_timeout(Duration::from_secs(60))
+                            .watch_for_success(&self.storage_hub_handler.blockchain)
+                            .await?;
+
+                        // Unregister the file.
+                        self.unregister_file(event.file_key.into()).await?;
+
+                        return Err(anyhow::anyhow!(format!(
+                            "Internal trie read/write error {:?}:{:?}",
+                            event.file_key, chunk.key
+                        )));
+                    }
+                    FileStorageWriteError::FingerprintAndStoredFileMismatch => {
+                        // This should never happen, given that the first check in the handler is verifying the proof.
+                        // This means that something is seriously wrong, so we error out the whole task.
+                        let call = storage_hub_runtime::RuntimeCall::FileSystem(
                         pallet_file_system::Call::msp_respond_storage_requests_multiple_buckets {
                             storage_request_msp_response: bounded_vec![
                                 StorageRequestMspBucketResponse {
@@ -318,26 +374,26 @@ where
                         },
                     );
 
-                    self.storage_hub_handler
-                        .blockchain
-                        .send_extrinsic(call, Tip::from(0))
-                        .await?
-                        .with_timeout(Duration::from_secs(60))
-                        .watch_for_success(&self.storage_hub_handler.blockchain)
-                        .await?;
+                        self.storage_hub_handler
+                            .blockchain
+                            .send_extrinsic(call, Tip::from(0))
+                            .await?
+                            .with_timeout(Duration::from_secs(60))
+                            .watch_for_success(&self.storage_hub_handler.blockchain)
+                            .await?;
 
-                    // Unregister the file.
-                    self.unregister_file(event.file_key.into()).await?;
+                        // Unregister the file.
+                        self.unregister_file(event.file_key.into()).await?;
 
-                    return Err(anyhow::anyhow!(format!(
+                        return Err(anyhow::anyhow!(format!(
                         "Invariant broken! This is a bug! Fingerprint and stored file mismatch for key {:?}.",
                         event.file_key
                     )));
-                }
-                FileStorageWriteError::FailedToConstructTrieIter => {
-                    // This should never happen for a well constructed trie.
-                    // This means that something is seriously wrong, so we error out the whole task.
-                    let call = storage_hub_runtime::RuntimeCall::FileSystem(
+                    }
+                    FileStorageWriteError::FailedToConstructTrieIter => {
+                        // This should never happen for a well constructed trie.
+                        // This means that something is seriously wrong, so we error out the whole task.
+                        let call = storage_hub_runtime::RuntimeCall::FileSystem(
                         pallet_file_system::Call::msp_respond_storage_requests_multiple_buckets {
                             storage_request_msp_response: bounded_vec![
                                 StorageRequestMspBucketResponse {
@@ -352,25 +408,42 @@ where
                         },
                     );
 
-                    self.storage_hub_handler
-                        .blockchain
-                        .send_extrinsic(call, Tip::from(0))
-                        .await?
-                        .with_timeout(Duration::from_secs(60))
-                        .watch_for_success(&self.storage_hub_handler.blockchain)
-                        .await?;
-
-                    // Unregister the file.
-                    self.unregister_file(event.file_key.into()).await?;
+                        self.storage_hub_handler
+                            .blockchain
+                            .send_extrinsic(call, Tip::from(0))
+                            .await?
+                            .with_timeout(Duration::from_secs(60))
+                            .watch_for_success(&self.storage_hub_handler.blockchain)
+                            .await?;
+
+                        // Unregister the file.
+                        self.unregister_file(event.file_key.into()).await?;
+
+                        return Err(anyhow::anyhow!(format!(
+                            "This is a bug! Failed to construct trie iter for key {:?}.",
+                            event.file_key
+                        )));
+                    }
+                },
+            }
+        }
 
-                    return Err(anyhow::anyhow!(format!(
-                        "This is a bug! Failed to construct trie iter for key {:?}.",
-                        event.file_key
-                    )));
-                }
-            },
+        // Let the upload resume after these chunks if it is interrupted.
+        if let Err(e) = self
+            .storage_hub_handler
+            .file_transfer
+            .record_received_chunks(
+                event.peer,
+                event.file_key,
+                event.file_key_proof.file_metadata.chunks_count(),
+                received_chunk_ids,
+            )
+            .await
+        {
+            warn!(target: LOG_TARGET, "Failed to record the chunks received for file {:?}: {:?}", event.file_key, e);
         }
 
+        span.complete();
         Ok(())
     }
 }
@@ -389,6 +462,21 @@ where
     NT::FSH: MspForestStorageHandlerT,
 {
     async fn handle_event(&mut self, event: ProcessMspRespondStoringRequest) -> anyhow::Result<()> {
+        let request_file_keys = event
+            .data
+            .respond_storing_requests
+            .iter()
+            .map(|respond_storing_request| respond_storing_request.file_key)
+            .collect::<Vec<_>>();
+        let mut spans = request_file_keys
+            .iter()
+            .map(|file_key| flow_span(Flow::StorageRequest, "msp_respond_storing", file_key))
+            .collect::<Vec<_>>();
+
+        for span in spans.iter_mut() {
+            span.fail_unless_completed();
+        }
+
         info!(
             target: LOG_TARGET,
             "Processing ProcessMspRespondStoringRequest: {:?}",
@@ -610,7 +698,17 @@ where
         self.storage_hub_handler
             .blockchain
             .release_forest_root_write_lock(forest_root_write_tx)
-            .await
+            .await?;
+
+        for span in spans.iter_mut() {
+            span.complete();
+        }
+        drop(spans);
+        for file_key in request_file_keys {
+            end_flow(Flow::StorageRequest, file_key);
+        }
+
+        Ok(())
     }
 }
 
@@ -919,8 +1017,43 @@ where
         Ok(new_capacity)
     }
 
+    async fn handle_reverted_new_storage_request_event(
+        &mut self,
+        event: NewStorageRequest,
+    ) -> anyhow::Result<()> {
+        let file_key: H256 = event.file_key.into();
+
+        // The storage request might have been included in the new best chain as well.
+        let storage_request = self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_request(file_key)
+            .await
+            .map_err(|e| anyhow!("Failed to query storage request {:?}: {:?}", file_key, e))?;
+        if storage_request.is_some() {
+            info!(target: LOG_TARGET, "Storage request for file key {:?} is still open in the new best chain. Keeping the file.", file_key);
+            return Ok(());
+        }
+
+        if let Some(fs) = self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&event.bucket_id.as_ref().to_vec())
+            .await
+        {
+            if fs.read().await.contains_file_key(&file_key)? {
+                info!(target: LOG_TARGET, "File key {:?} is already in the Forest of bucket {:?}. Keeping the file.", file_key, event.bucket_id);
+                return Ok(());
+            }
+        }
+
+        self.unregister_file(file_key).await
+    }
+
     async fn unregister_file(&self, file_key: H256) -> anyhow::Result<()> {
         warn!(target: LOG_TARGET, "Unregistering file {:?}", file_key);
+        end_flow(Flow::FileTransfer, file_key);
+        end_flow(Flow::StorageRequest, file_key);
 
         // Unregister the file from the file transfer service.
         // The error is ignored, as the file might already be unregistered.
@@ -941,6 +1074,7 @@ where
 
     async fn on_file_complete(&self, file_key: &H256) -> anyhow::Result<()> {
         info!(target: LOG_TARGET, "File upload complete ({:?})", file_key);
+        end_flow(Flow::FileTransfer, file_key);
 
         // Unregister the file from the file transfer service.
         self.storage_hub_handler
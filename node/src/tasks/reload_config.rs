@@ -0,0 +1,149 @@
+use anyhow::anyhow;
+
+use sc_tracing::tracing::*;
+
+use shc_actors_framework::event_bus::EventHandler;
+use shc_config_watcher::events::ConfigUpdated;
+use shc_file_transfer_service::{commands::FileTransferServiceInterface, BandwidthLimits};
+
+use crate::services::{handler::StorageHubHandler, types::ShNodeType};
+
+const LOG_TARGET: &str = "reload-config-task";
+
+/// Reload Config Task: Applies the settings reloaded from the configuration file of the node to
+/// the services they belong to, while it runs.
+///
+/// It listens for the [`ConfigUpdated`] event emitted by the Config Watcher Service and applies
+/// the settings that changed:
+/// - The upload bandwidth caps, to the File Transfer Service.
+/// - The volunteering policy, to the volunteering policy engine of a BSP.
+/// - The log filter, replacing the one set before on top of the `--log` flag.
+pub struct ReloadConfigTask<NT>
+where
+    NT: ShNodeType,
+{
+    storage_hub_handler: StorageHubHandler<NT>,
+}
+
+impl<NT> Clone for ReloadConfigTask<NT>
+where
+    NT: ShNodeType,
+{
+    fn clone(&self) -> ReloadConfigTask<NT> {
+        Self {
+            storage_hub_handler: self.storage_hub_handler.clone(),
+        }
+    }
+}
+
+impl<NT> ReloadConfigTask<NT>
+where
+    NT: ShNodeType,
+{
+    pub fn new(storage_hub_handler: StorageHubHandler<NT>) -> Self {
+        Self {
+            storage_hub_handler,
+        }
+    }
+}
+
+/// Handles the [`ConfigUpdated`] event.
+///
+/// This event is triggered by the Config Watcher Service when the configuration file is reloaded
+/// and any of its reloadable settings changed. Every setting is applied independently, so one
+/// failing to apply does not prevent the others from being applied.
+impl<NT> EventHandler<ConfigUpdated> for ReloadConfigTask<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    async fn handle_event(&mut self, event: ConfigUpdated) -> anyhow::Result<()> {
+        let ConfigUpdated { previous, config } = event;
+        let mut failed = Vec::new();
+
+        if (
+            previous.max_upload_bandwidth,
+            previous.max_peer_upload_bandwidth,
+        ) != (
+            config.max_upload_bandwidth,
+            config.max_peer_upload_bandwidth,
+        ) {
+            let limits = BandwidthLimits {
+                global: config.max_upload_bandwidth,
+                per_peer: config.max_peer_upload_bandwidth,
+            };
+            match self
+                .storage_hub_handler
+                .file_transfer
+                .set_bandwidth_limit(limits)
+                .await
+            {
+                Ok(()) => info!(
+                    target: LOG_TARGET,
+                    "Upload bandwidth limits set to {:?}", limits
+                ),
+                Err(e) => {
+                    error!(
+                        target: LOG_TARGET,
+                        "Failed to set the upload bandwidth limits: {:?}", e
+                    );
+                    failed.push("upload bandwidth limits");
+                }
+            }
+        }
+
+        if previous.volunteer_policy != config.volunteer_policy {
+            match (
+                &self.storage_hub_handler.provider_config.volunteer_policy,
+                config.volunteer_policy,
+            ) {
+                (Some(volunteer_policy), Some(policy)) => {
+                    info!(target: LOG_TARGET, "Volunteering policy set to {:?}", policy);
+                    volunteer_policy.set_policy(policy);
+                }
+                _ => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Volunteering policy changed, but this node does not volunteer. Ignoring it."
+                    );
+                }
+            }
+        }
+
+        if previous.log_filter != config.log_filter {
+            match set_log_filter(config.log_filter.as_deref()) {
+                Ok(()) => info!(
+                    target: LOG_TARGET,
+                    "Log filter set to {:?}", config.log_filter
+                ),
+                Err(e) => {
+                    error!(target: LOG_TARGET, "Failed to set the log filter: {}", e);
+                    failed.push("log filter");
+                }
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Failed to apply the reloaded {}",
+                failed.join(" and ")
+            ))
+        }
+    }
+}
+
+/// Sets the log filter directives `log_filter` on top of the ones of the `--log` flag, replacing
+/// the ones set before (including those set with the `setLogFilter` RPC method).
+///
+/// Requires the node to be started with `--enable-log-reloading`.
+pub fn set_log_filter(log_filter: Option<&str>) -> Result<(), String> {
+    sc_tracing::logging::reset_log_filter()?;
+
+    if let Some(log_filter) = log_filter {
+        sc_tracing::logging::add_directives(log_filter);
+        sc_tracing::logging::reload_filter()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use anyhow::anyhow;
+use std::time::Duration;
+
+use codec::Decode;
+use pallet_storage_providers::types::StorageProviderId;
+use rand::seq::SliceRandom;
+use sc_tracing::tracing::*;
+use sp_core::H256;
+use sp_runtime::AccountId32;
+
+use shc_actors_framework::event_bus::EventHandler;
+use shc_blockchain_service::{commands::BlockchainServiceInterface, types::Tip};
+use shc_common::{
+    consts::CURRENT_FOREST_KEY,
+    types::{ChunkId, FileKeyProof, FileMetadata, StorageProofsMerkleTrieLayout},
+};
+use shc_file_manager::traits::FileStorage;
+use shc_file_transfer_service::commands::FileTransferServiceInterface;
+use shc_forest_manager::traits::{ForestStorage, ForestStorageHandler};
+use shc_scrubber_service::events::CorruptChunkDetected;
+
+use crate::services::{handler::StorageHubHandler, types::ShNodeType};
+
+const LOG_TARGET: &str = "sp-repair-file-task";
+
+/// Number of download requests made for each chunk of a file being fetched again, cycling through
+/// the providers storing it.
+const DOWNLOAD_REQUEST_RETRY_COUNT: usize = 10;
+
+/// Repair file task.
+///
+/// This task is responsible for dealing with the files of this provider found to be corrupt by the
+/// Scrubber Service. It listens for the [`CorruptChunkDetected`] event and fetches the whole file
+/// again from the BSPs storing it, as known by the indexer. If that is not possible, a BSP requests
+/// to stop storing the file instead, since it would fail the proofs of the corrupt chunks anyway.
+pub struct SpRepairFileTask<NT>
+where
+    NT: ShNodeType,
+{
+    storage_hub_handler: StorageHubHandler<NT>,
+}
+
+impl<NT> Clone for SpRepairFileTask<NT>
+where
+    NT: ShNodeType,
+{
+    fn clone(&self) -> SpRepairFileTask<NT> {
+        Self {
+            storage_hub_handler: self.storage_hub_handler.clone(),
+        }
+    }
+}
+
+impl<NT> SpRepairFileTask<NT>
+where
+    NT: ShNodeType,
+{
+    pub fn new(storage_hub_handler: StorageHubHandler<NT>) -> Self {
+        Self {
+            storage_hub_handler,
+        }
+    }
+}
+
+/// Handles the [`CorruptChunkDetected`] event.
+///
+/// This event is triggered by the Scrubber Service when chunks of a stored file can't be proven
+/// against its fingerprint anymore.
+impl<NT> EventHandler<CorruptChunkDetected> for SpRepairFileTask<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    async fn handle_event(&mut self, event: CorruptChunkDetected) -> anyhow::Result<()> {
+        warn!(
+            target: LOG_TARGET,
+            file_key = ?event.file_key,
+            "Repairing file with {} corrupt chunks",
+            event.chunk_ids.len()
+        );
+
+        match self
+            .fetch_file_again(event.file_key, &event.file_metadata)
+            .await
+        {
+            Ok(()) => {
+                info!(
+                    target: LOG_TARGET,
+                    file_key = ?event.file_key,
+                    "Repaired corrupt file by fetching it again"
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    file_key = ?event.file_key,
+                    "Failed to fetch corrupt file again: {:?}",
+                    e
+                );
+            }
+        }
+
+        self.stop_storing_file(event.file_key, &event.file_metadata)
+            .await
+    }
+}
+
+impl<NT> SpRepairFileTask<NT>
+where
+    NT: ShNodeType,
+{
+    /// Replaces the file in the file storage with all of its chunks fetched again from the BSPs
+    /// storing it.
+    ///
+    /// The BSPs only serve the file if they allow this provider to download it.
+    async fn fetch_file_again(
+        &self,
+        file_key: H256,
+        file_metadata: &FileMetadata,
+    ) -> anyhow::Result<()> {
+        // Get the indexer database pool. If we don't have it, we can't find the BSPs storing the file.
+        let indexer_db_pool = self
+            .storage_hub_handler
+            .indexer_db_pool
+            .clone()
+            .ok_or_else(|| anyhow!("Indexer is disabled, no BSPs to fetch the file from"))?;
+
+        let mut indexer_connection = indexer_db_pool.get().await?;
+        let file = shc_indexer_db::models::File::get_by_file_key(&mut indexer_connection, file_key)
+            .await?;
+        let mut bsp_peer_ids = file.get_bsp_peer_ids(&mut indexer_connection).await?;
+        drop(indexer_connection);
+
+        if bsp_peer_ids.is_empty() {
+            return Err(anyhow!("No BSP peer IDs found"));
+        }
+
+        // Shuffle in order to avoid consecutive requests to the same BSP node.
+        bsp_peer_ids.shuffle(&mut rand::thread_rng());
+        let mut bsp_peer_ids_iter = bsp_peer_ids.iter().cycle();
+
+        // Start over with an empty file. The chunks of the corrupt one can't be trusted.
+        {
+            let mut file_storage = self.storage_hub_handler.file_storage.write().await;
+            if let Err(e) = file_storage.delete_file(&file_key) {
+                warn!(
+                    target: LOG_TARGET,
+                    file_key = ?file_key,
+                    "Failed to delete corrupt file, overwriting it: {:?}",
+                    e
+                );
+            }
+            file_storage
+                .insert_file(file_key, file_metadata.clone())
+                .map_err(|e| anyhow!("Failed to insert file into file storage: {:?}", e))?;
+        }
+
+        let bucket_id = H256::from_slice(file_metadata.bucket_id.as_ref());
+
+        for chunk in 0..file_metadata.chunks_count() {
+            let chunk_id = ChunkId::new(chunk);
+
+            let mut downloaded = false;
+            for _ in 0..DOWNLOAD_REQUEST_RETRY_COUNT {
+                // This can fail only if the BSP peer IDs are empty - which we already checked.
+                let peer_id = bsp_peer_ids_iter.next().unwrap();
+
+                let download_request = match self
+                    .storage_hub_handler
+                    .file_transfer
+                    .download_request(*peer_id, file_key.into(), chunk_id, Some(bucket_id))
+                    .await
+                {
+                    Ok(download_request) => download_request,
+                    Err(e) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Failed to download chunk {:?} of file {:?} from peer {:?}: {:?}",
+                            chunk_id, file_key, peer_id, e
+                        );
+                        continue;
+                    }
+                };
+
+                // Only the chunks proven against the fingerprint of the file are written.
+                let proven = FileKeyProof::decode(&mut download_request.file_key_proof.as_ref())
+                    .map_err(|e| anyhow!("Failed to decode file key proof: {:?}", e))
+                    .and_then(|file_key_proof| {
+                        if file_key_proof.file_metadata.fingerprint != file_metadata.fingerprint {
+                            return Err(anyhow!("File key proof is for another file"));
+                        }
+                        file_key_proof
+                            .proven::<StorageProofsMerkleTrieLayout>()
+                            .map_err(|e| anyhow!("Failed to verify file key proof: {:?}", e))
+                    });
+                let chunk_data = match proven.as_deref() {
+                    Ok([proven_chunk]) if proven_chunk.key == chunk_id => proven_chunk.data.clone(),
+                    Ok(proven) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Expected only chunk {:?} of file {:?} to be proven but got chunks {:?}",
+                            chunk_id,
+                            file_key,
+                            proven.iter().map(|chunk| chunk.key).collect::<Vec<_>>()
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Invalid chunk {:?} of file {:?} from peer {:?}: {:?}",
+                            chunk_id, file_key, peer_id, e
+                        );
+                        continue;
+                    }
+                };
+
+                if let Err(e) = self
+                    .storage_hub_handler
+                    .file_storage
+                    .write()
+                    .await
+                    .write_chunk(&file_key, &chunk_id, &chunk_data)
+                {
+                    error!(
+                        target: LOG_TARGET,
+                        "Failed to write chunk {:?} of file {:?} to storage: {:?}",
+                        chunk_id, file_key, e
+                    );
+                } else {
+                    downloaded = true;
+                    break;
+                }
+            }
+
+            if !downloaded {
+                return Err(anyhow!(
+                    "Failed to download chunk {:?} after {} attempts",
+                    chunk_id,
+                    DOWNLOAD_REQUEST_RETRY_COUNT
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Requests to stop storing the file, if this provider is a BSP. The file can't be dropped by
+    /// an MSP on its own, so it is only reported.
+    async fn stop_storing_file(
+        &self,
+        file_key: H256,
+        file_metadata: &FileMetadata,
+    ) -> anyhow::Result<()> {
+        match self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_provider_id(None)
+            .await?
+        {
+            Some(StorageProviderId::BackupStorageProvider(_)) => {}
+            _ => {
+                error!(
+                    target: LOG_TARGET,
+                    file_key = ?file_key,
+                    "CRITICAL❗️❗️ Corrupt file could not be repaired. It needs to be uploaded again by its owner."
+                );
+                return Ok(());
+            }
+        }
+
+        let current_forest_key = CURRENT_FOREST_KEY.to_vec().into();
+        let inclusion_forest_proof = self
+            .storage_hub_handler
+            .forest_storage_handler
+            .get(&current_forest_key)
+            .await
+            .ok_or_else(|| anyhow!("Failed to get forest storage."))?
+            .read()
+            .await
+            .generate_proof(vec![file_key])
+            .map_err(|e| anyhow!("Failed to generate proof from Forest: {:?}", e))?
+            .proof;
+
+        // The file can't be served while it is corrupt.
+        let call = storage_hub_runtime::RuntimeCall::FileSystem(
+            pallet_file_system::Call::bsp_request_stop_storing {
+                file_key,
+                bucket_id: H256::from_slice(file_metadata.bucket_id.as_ref()),
+                location: sp_runtime::BoundedVec::truncate_from(file_metadata.location.clone()),
+                owner: AccountId32::try_from(file_metadata.owner.as_slice())
+                    .map_err(|_| anyhow!("Invalid owner in file metadata"))?,
+                fingerprint: H256(file_metadata.fingerprint.into()),
+                size: file_metadata.file_size,
+                can_serve: false,
+                inclusion_forest_proof,
+            },
+        );
+
+        self.storage_hub_handler
+            .blockchain
+            .send_extrinsic(call, Tip::from(0))
+            .await?
+            .with_timeout(Duration::from_secs(
+                self.storage_hub_handler
+                    .provider_config
+                    .extrinsic_retry_timeout,
+            ))
+            .watch_for_success(&self.storage_hub_handler.blockchain)
+            .await?;
+
+        warn!(
+            target: LOG_TARGET,
+            file_key = ?file_key,
+            "Requested to stop storing corrupt file"
+        );
+
+        Ok(())
+    }
+}
@@ -54,8 +54,8 @@ where
     async fn handle_event(&mut self, event: SlashableProvider) -> anyhow::Result<()> {
         info!(
             target: LOG_TARGET,
-            "Slashing provider {:?}",
-            event.provider,
+            provider_id = ?event.provider,
+            "Slashing provider"
         );
 
         self.handle_slashable_provider_event(event).await
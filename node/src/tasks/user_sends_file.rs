@@ -5,11 +5,17 @@ use shc_blockchain_service::{
     commands::BlockchainServiceInterface,
     events::{AcceptedBspVolunteer, NewStorageRequest},
 };
-use shc_common::types::{FileMetadata, HashT, StorageProofsMerkleTrieLayout};
+use shc_common::types::{FileMetadata, HashT, StorageProofsMerkleTrieLayout, FILE_CHUNK_SIZE};
 use shc_file_manager::traits::FileStorage;
-use shc_file_transfer_service::commands::{FileTransferServiceInterface, RequestError};
+use shc_file_transfer_service::{
+    chunk_size::{AdaptiveChunkSizer, LEGACY_TRANSFER_CHUNK_SIZE, MIN_TRANSFER_CHUNK_SIZE},
+    commands::{FileTransferServiceInterface, RequestError},
+    TransferChunkSizeBounds,
+};
+use shc_telemetry::{end_flow, flow_span, Flow};
 use shp_file_metadata::ChunkId;
 use sp_runtime::AccountId32;
+use std::time::Instant;
 
 use crate::services::{handler::StorageHubHandler, types::ShNodeType};
 
@@ -190,10 +196,32 @@ impl<NT> UserSendsFileTask<NT>
 where
     NT: ShNodeType,
 {
+    /// Sends the chunks of the file to the provider, tracing it as a file transfer session.
     async fn send_chunks_to_provider(
         &mut self,
         peer_ids: Vec<PeerId>,
         file_metadata: &FileMetadata,
+    ) -> Result<(), anyhow::Error> {
+        let file_key = file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>();
+        let mut span = flow_span(Flow::FileTransfer, "user_send_chunks", file_key);
+        span.set_attribute(
+            "storagehub.file.chunks",
+            file_metadata.chunks_count() as i64,
+        );
+
+        let result = self
+            .try_send_chunks_to_provider(peer_ids, file_metadata)
+            .await;
+        span.record_result(&result);
+        drop(span);
+        end_flow(Flow::FileTransfer, file_key);
+        result
+    }
+
+    async fn try_send_chunks_to_provider(
+        &mut self,
+        peer_ids: Vec<PeerId>,
+        file_metadata: &FileMetadata,
     ) -> Result<(), anyhow::Error> {
         let file_key = file_metadata.file_key::<HashT<StorageProofsMerkleTrieLayout>>();
         let chunk_count = file_metadata.chunks_count();
@@ -203,20 +231,64 @@ where
         for peer_id in peer_ids {
             debug!(target: LOG_TARGET, "Attempting to send chunks of file key {:?} to peer {:?}", file_key, peer_id);
 
-            for chunk_id in 0..chunk_count {
-                debug!(target: LOG_TARGET, "Trying to send chunk id {:?} of file {:?} to peer {:?}", chunk_id, file_key, peer_id);
+            // Agree on the size of the chunks to send with the peer, falling back to sending one file
+            // chunk at a time if the peer doesn't support it.
+            let chunk_size = match self
+                .storage_hub_handler
+                .file_transfer
+                .negotiate_chunk_size(peer_id, file_key.as_ref().into(), None)
+                .await
+            {
+                Ok(chunk_size) => chunk_size,
+                Err(e) => {
+                    debug!(target: LOG_TARGET, "Failed to negotiate transfer chunk size with peer {:?}, using {} bytes\n Error: {:?}", peer_id, LEGACY_TRANSFER_CHUNK_SIZE, e);
+                    LEGACY_TRANSFER_CHUNK_SIZE
+                }
+            };
+            let mut chunk_sizer = AdaptiveChunkSizer::new(
+                TransferChunkSizeBounds::new(MIN_TRANSFER_CHUNK_SIZE, chunk_size),
+                chunk_size,
+            );
+
+            // Skip the chunks the peer already has, if a previous upload to it was interrupted, i.e.
+            // because it restarted. Peers that don't support resuming get the whole file.
+            let mut next_chunk_id = match self
+                .storage_hub_handler
+                .file_transfer
+                .negotiate_resume_offset(peer_id, file_key.as_ref().into(), None)
+                .await
+            {
+                Ok(next_chunk_id) => next_chunk_id.min(chunk_count),
+                Err(e) => {
+                    debug!(target: LOG_TARGET, "Failed to negotiate resume offset with peer {:?}, sending the whole file\n Error: {:?}", peer_id, e);
+                    0
+                }
+            };
+            if next_chunk_id > 0 {
+                info!(target: LOG_TARGET, "Resuming upload of file {:?} to peer {:?} from chunk {} of {}", file_key, peer_id, next_chunk_id, chunk_count);
+            }
+
+            while next_chunk_id < chunk_count {
+                // Map the transfer chunk to the IDs of the file chunks it contains.
+                let chunk_ids = file_metadata.chunk_ids_in_transfer_chunk(
+                    ChunkId::new(next_chunk_id),
+                    chunk_sizer.chunk_size(),
+                );
+                let last_chunk_id = next_chunk_id + chunk_ids.len() as u64 - 1;
+
+                debug!(target: LOG_TARGET, "Trying to send chunk ids {:?} to {:?} of file {:?} to peer {:?}", next_chunk_id, last_chunk_id, file_key, peer_id);
                 let proof = match self
                     .storage_hub_handler
                     .file_storage
                     .read()
                     .await
-                    .generate_proof(&file_key, &vec![ChunkId::new(chunk_id)])
+                    .generate_proof(&file_key, &chunk_ids)
                 {
                     Ok(proof) => proof,
                     Err(e) => {
                         return Err(anyhow::anyhow!(
-                            "Failed to generate proof for chunk id {:?} of file {:?}\n Error: {:?}",
-                            chunk_id,
+                            "Failed to generate proof for chunk ids {:?} of file {:?}\n Error: {:?}",
+                            chunk_ids,
                             file_key,
                             e
                         ));
@@ -225,15 +297,28 @@ where
 
                 let mut retry_attempts = 0;
                 loop {
+                    let started_at = Instant::now();
                     let upload_response = self
                         .storage_hub_handler
                         .file_transfer
                         .upload_request(peer_id, file_key.as_ref().into(), proof.clone(), None)
                         .await;
 
+                    #[cfg(feature = "load-generator")]
+                    crate::load_generator::record_sample(
+                        crate::load_generator::Subsystem::ChunkUpload,
+                        started_at.elapsed(),
+                        upload_response.is_ok(),
+                        chunk_ids.len() as u64 * FILE_CHUNK_SIZE,
+                    );
+
                     match upload_response {
                         Ok(_) => {
-                            debug!(target: LOG_TARGET, "Successfully uploaded chunk id {:?} of file {:?} to peer {:?}", chunk_id, file_metadata.fingerprint, peer_id);
+                            debug!(target: LOG_TARGET, "Successfully uploaded chunk ids {:?} to {:?} of file {:?} to peer {:?}", next_chunk_id, last_chunk_id, file_metadata.fingerprint, peer_id);
+                            chunk_sizer.record_transfer(
+                                chunk_ids.len() as u64 * FILE_CHUNK_SIZE,
+                                started_at.elapsed(),
+                            );
                             break;
                         }
                         // Retry if the request was refused by the peer (MSP). This could happen if the user was too fast
@@ -241,7 +326,7 @@ where
                         Err(RequestError::RequestFailure(RequestFailure::Refused))
                             if retry_attempts < 3 =>
                         {
-                            warn!(target: LOG_TARGET, "Chunk id {:?} upload rejected by peer {:?}, retrying... (attempt {})", chunk_id, peer_id, retry_attempts + 1);
+                            warn!(target: LOG_TARGET, "Chunk ids {:?} to {:?} upload rejected by peer {:?}, retrying... (attempt {})", next_chunk_id, last_chunk_id, peer_id, retry_attempts + 1);
                             retry_attempts += 1;
 
                             // Wait for a short time before retrying.
@@ -251,13 +336,16 @@ where
                             // TODO: Handle MSP not receiving file after multiple retries.
                         }
                         Err(e) => {
-                            error!(target: LOG_TARGET, "Failed to upload chunk_id {:?} to peer {:?}\n Error: {:?}", chunk_id, peer_id, e);
+                            error!(target: LOG_TARGET, "Failed to upload chunk ids {:?} to {:?} to peer {:?}\n Error: {:?}", next_chunk_id, last_chunk_id, peer_id, e);
+                            chunk_sizer.record_failure();
                             // In case of an error, we break the inner loop
                             // and try to connect to the next peer id.
                             break;
                         }
                     }
                 }
+
+                next_chunk_id += chunk_ids.len() as u64;
             }
             info!(target: LOG_TARGET, "Successfully sent file {:?} to peer {:?}", file_metadata.fingerprint, peer_id);
             return Ok(());
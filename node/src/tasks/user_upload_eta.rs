@@ -0,0 +1,237 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use log::{debug, info, warn};
+use pallet_file_system_runtime_api::QueryStorageRequestReplicationStatusError;
+use tokio::sync::Mutex;
+
+use shc_actors_framework::event_bus::EventHandler;
+use shc_blockchain_service::{
+    commands::BlockchainServiceInterface,
+    events::{BspConfirmedStoring, NewStorageRequest},
+};
+use shc_common::{
+    types::FileKey,
+    upload_eta::{estimate_upload_eta, FulfilmentHistory, ReplicationProgress},
+};
+use storage_hub_runtime::MILLISECS_PER_BLOCK;
+
+use crate::services::{handler::StorageHubHandler, types::ShNodeType};
+
+const LOG_TARGET: &str = "user-upload-eta-task";
+
+/// Maximum amount of recently fulfilled storage requests taken from the indexer to estimate how long
+/// storage requests take to be fulfilled.
+const FULFILMENT_HISTORY_SIZE: i64 = 100;
+
+/// [`UserUploadEtaTask`]: Gives feedback to the user on how long until its storage requests reach
+/// their replication target.
+///
+/// - [`NewStorageRequest`] event: when a storage request issued by this user node is admitted,
+///   logs an initial estimate and starts tracking it.
+/// - [`BspConfirmedStoring`] event: every time a BSP confirms storing a tracked file, logs an
+///   updated estimate, until the storage request is no longer open.
+///
+/// The estimate takes into account the number of BSPs and the threshold progression parameters
+/// given by the runtime, and how long recent storage requests took to be fulfilled according to
+/// the indexer, if this node has access to its database.
+pub struct UserUploadEtaTask<NT>
+where
+    NT: ShNodeType,
+{
+    storage_hub_handler: StorageHubHandler<NT>,
+    tracked_file_keys: Arc<Mutex<HashSet<FileKey>>>,
+}
+
+impl<NT> Clone for UserUploadEtaTask<NT>
+where
+    NT: ShNodeType,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage_hub_handler: self.storage_hub_handler.clone(),
+            tracked_file_keys: Arc::clone(&self.tracked_file_keys),
+        }
+    }
+}
+
+impl<NT> UserUploadEtaTask<NT>
+where
+    NT: ShNodeType,
+{
+    pub fn new(storage_hub_handler: StorageHubHandler<NT>) -> Self {
+        Self {
+            storage_hub_handler,
+            tracked_file_keys: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+}
+
+impl<NT> EventHandler<NewStorageRequest> for UserUploadEtaTask<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    async fn handle_event(&mut self, event: NewStorageRequest) -> anyhow::Result<()> {
+        let node_pub_key = self
+            .storage_hub_handler
+            .blockchain
+            .get_node_public_key()
+            .await;
+
+        if event.who != node_pub_key.into() {
+            // Skip if the storage request was not created by this user node.
+            return Ok(());
+        }
+
+        self.tracked_file_keys.lock().await.insert(event.file_key);
+
+        self.report_eta(event.file_key).await
+    }
+}
+
+impl<NT> EventHandler<BspConfirmedStoring> for UserUploadEtaTask<NT>
+where
+    NT: ShNodeType + 'static,
+{
+    async fn handle_event(&mut self, event: BspConfirmedStoring) -> anyhow::Result<()> {
+        for file_key in event.confirmed_file_keys {
+            if !self.tracked_file_keys.lock().await.contains(&file_key) {
+                continue;
+            }
+
+            debug!(
+                target: LOG_TARGET,
+                "BSP [{:?}] confirmed storing file key [{:?}]", event.bsp_id, file_key
+            );
+
+            self.report_eta(file_key).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<NT> UserUploadEtaTask<NT>
+where
+    NT: ShNodeType,
+{
+    /// Logs the estimated time left until the storage request of `file_key` reaches its replication
+    /// target, and stops tracking it once it is no longer open.
+    async fn report_eta(&self, file_key: FileKey) -> anyhow::Result<()> {
+        let status = match self
+            .storage_hub_handler
+            .blockchain
+            .query_storage_request_replication_status(file_key.into())
+            .await
+        {
+            Ok(status) => status,
+            Err(QueryStorageRequestReplicationStatusError::StorageRequestNotFound) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Storage request for file key [{:?}] is no longer open: it either reached its replication target, expired or was revoked",
+                    file_key
+                );
+                self.tracked_file_keys.lock().await.remove(&file_key);
+                return Ok(());
+            }
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Failed to query replication status of storage request for file key {:?}: {:?}",
+                    file_key,
+                    e
+                ));
+            }
+        };
+
+        let progress = ReplicationProgress {
+            bsps_required: status.bsps_required,
+            bsps_confirmed: status.bsps_confirmed,
+            ticks_since_requested: status
+                .current_tick
+                .saturating_sub(status.requested_at)
+                .into(),
+            number_of_bsps: status.number_of_bsps,
+            max_replication_target: status.max_replication_target,
+            tick_range_to_maximum_threshold: status.tick_range_to_maximum_threshold.into(),
+        };
+        let history = self.fulfilment_history().await;
+        let tick_duration = Duration::from_millis(MILLISECS_PER_BLOCK);
+
+        match estimate_upload_eta(&progress, &history, tick_duration) {
+            Some(_) if status.bsps_required == 0 => {
+                info!(
+                    target: LOG_TARGET,
+                    "Storage request for file key [{:?}] is MSP-only: the file will not be replicated to any BSP",
+                    file_key
+                );
+                self.tracked_file_keys.lock().await.remove(&file_key);
+            }
+            Some(eta) if eta.expected.is_zero() => {
+                info!(
+                    target: LOG_TARGET,
+                    "Storage request for file key [{:?}] reached its replication target of {} BSPs",
+                    file_key,
+                    status.bsps_required
+                );
+                self.tracked_file_keys.lock().await.remove(&file_key);
+            }
+            Some(eta) => {
+                info!(
+                    target: LOG_TARGET,
+                    "⏳ Storage request for file key [{:?}]: {}/{} BSPs confirmed, {} volunteered. Expected to reach its replication target in ~{}s (between {}s and {}s)",
+                    file_key,
+                    status.bsps_confirmed,
+                    status.bsps_required,
+                    status.bsps_volunteered,
+                    eta.expected.as_secs(),
+                    eta.earliest.as_secs(),
+                    eta.latest.as_secs()
+                );
+            }
+            None => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Storage request for file key [{:?}] requires {} BSPs but only {} are registered: it will not reach its replication target before expiring",
+                    file_key,
+                    status.bsps_required,
+                    status.number_of_bsps
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// How long recently fulfilled storage requests took, according to the indexer.
+    ///
+    /// Empty if this node has no access to the indexer database, or if it fails to query it.
+    async fn fulfilment_history(&self) -> FulfilmentHistory {
+        let Some(indexer_db_pool) = self.storage_hub_handler.indexer_db_pool.clone() else {
+            return FulfilmentHistory::default();
+        };
+
+        let durations = match indexer_db_pool.get().await {
+            Ok(mut conn) => {
+                shc_indexer_db::models::File::recent_fulfilment_durations(
+                    &mut conn,
+                    FULFILMENT_HISTORY_SIZE,
+                )
+                .await
+            }
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to connect to the indexer database: {:?}", e);
+                return FulfilmentHistory::default();
+            }
+        };
+
+        match durations {
+            Ok(durations) => FulfilmentHistory::new(durations),
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to query recently fulfilled storage requests from the indexer: {:?}", e
+                );
+                FulfilmentHistory::default()
+            }
+        }
+    }
+}
@@ -15,14 +15,14 @@ use shp_traits::{
     ReadUserSolvencyInterface, StorageHubTickGetter, TrieMutation, TrieRemoveMutation,
 };
 use shp_treasury_funding::NoCutTreasuryCutCalculator;
-use sp_core::{hashing::blake2_256, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
+use sp_core::{hashing::blake2_256, ConstBool, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
 use sp_keyring::sr25519::Keyring;
 use sp_runtime::{
     traits::{
         BlakeTwo256, BlockNumberProvider, Convert, ConvertBack, IdentifyAccount, IdentityLookup,
         Verify,
     },
-    BuildStorage, MultiSignature, SaturatedConversion,
+    BuildStorage, MultiSignature, Perbill, SaturatedConversion,
 };
 use sp_std::collections::btree_set::BTreeSet;
 use sp_trie::{LayoutV1, TrieConfiguration, TrieLayout};
@@ -148,6 +148,9 @@ pub(crate) type ThresholdType = u32;
 parameter_types! {
     pub const MinWaitForStopStoring: BlockNumber = 1;
     pub const StorageRequestCreationDeposit: Balance = 10;
+    pub const MspOnlyStorageRequestCreationDeposit: Balance = 5;
+    pub const DeletionListItemDeposit: Balance = 1;
+    pub const StorageRequestRevocationCompensation: Perbill = Perbill::from_percent(50);
     pub const FileSystemHoldReason: RuntimeHoldReason = RuntimeHoldReason::FileSystem(pallet_file_system::HoldReason::StorageRequestCreationHold);
 }
 
@@ -267,13 +270,35 @@ impl pallet_file_system::Config for Test {
     type MaxDataServerMultiAddresses = ConstU32<5>;
     type MaxExpiredItemsInBlock = ConstU32<100u32>;
     type StorageRequestTtl = ConstU32<40u32>;
+    type StorageRequestExpirationNotificationLeadTime = ConstU32<10u32>;
+    type MaxStorageRequestExpirationNotificationsPerBlock = ConstU32<100u32>;
     type PendingFileDeletionRequestTtl = ConstU32<40u32>;
     type MoveBucketRequestTtl = ConstU32<40u32>;
     type MaxUserPendingDeletionRequests = ConstU32<5u32>;
     type MaxUserPendingMoveBucketRequests = ConstU32<10u32>;
     type MinWaitForStopStoring = MinWaitForStopStoring;
     type StorageRequestCreationDeposit = StorageRequestCreationDeposit;
+    type MspOnlyStorageRequestCreationDeposit = MspOnlyStorageRequestCreationDeposit;
+    type StorageRequestRevocationCompensation = StorageRequestRevocationCompensation;
+    type StorageRequestRevocationCompensationPeriod = ConstU32<20>;
+    type FileDeletionFee = ConstU128<0>;
+    type MaxFileDeletionFee = ConstU128<100>;
     type DefaultReplicationTarget = ConstU32<2>;
+    type PriorityChallengesMeteringPeriod = ConstU64<10>;
+    type PriorityChallengesSoftLimitPerIssuer = ConstU32<5>;
+    type MaxDeferredPriorityChallengesPerIssuer = ConstU32<10>;
+    type MaxDeferredPriorityChallengeIssuers = ConstU32<10>;
+    type MaxDeferredPriorityChallengesQueuedPerBlock = ConstU32<3>;
+    type MaxProviderStatusChangeItemsPerBlock = ConstU32<10>;
+    type MaxDepositTransferRetries = ConstU32<10>;
+    type MaxDepositTransferRetryAttempts = ConstU32<3>;
+    type DepositTransferRetryBaseDelay = ConstU64<2>;
+    type MaxNotificationPreferencesLength = ConstU32<32>;
+    type MaxFileAccessListSize = ConstU32<3>;
+    type MinBillableFileSize = ConstU64<0>;
+    type MaxDeletionListProofDepth = ConstU32<16>;
+    type DeletionListItemDeposit = DeletionListItemDeposit;
+    type MaxAccessGrantsPerTarget = ConstU32<3>;
 }
 
 pub struct MockUserSolvency;
@@ -320,6 +345,10 @@ impl pallet_nfts::Config for Test {
 }
 
 // Payment streams pallet:
+parameter_types! {
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(5);
+}
+
 impl pallet_payment_streams::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -335,6 +364,10 @@ impl pallet_payment_streams::Config for Test {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = ();
+    type MaxOraclePriceAge = ConstU64<10>;
+    type OraclePriceAdjustmentPeriod = ConstU64<10>;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 // Converter from the BlockNumber type to the Balance type for math
 pub struct BlockNumberToBalance;
@@ -392,6 +425,11 @@ impl StorageHubTickGetter for MockStorageHubTickGetter {
     }
 }
 
+parameter_types! {
+    pub const SlashEscalationPerMissedDeadline: Perbill = Perbill::from_percent(10);
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
+}
+
 impl pallet_storage_providers::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -403,6 +441,7 @@ impl pallet_storage_providers::Config for Test {
         { shp_constants::FILE_CHUNK_SIZE },
         { shp_constants::FILE_SIZE_TO_CHALLENGES },
     >;
+    type OnProviderStatusChange = FileSystem;
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type StorageDataUnit = StorageDataUnit;
@@ -428,16 +467,31 @@ impl pallet_storage_providers::Config for Test {
     type MaxProtocols = ConstU32<100>;
     type BucketDeposit = ConstU128<10>;
     type BucketNameLimit = ConstU32<100>;
+    type EncryptionMetadataLimit = ConstU32<256>;
     type MaxBlocksForRandomness = ConstU64<{ EPOCH_DURATION_IN_BLOCKS * 2 }>;
     type MinBlocksBetweenCapacityChanges = ConstU64<10>;
     type DefaultMerkleRoot = DefaultMerkleRoot<LayoutV1<BlakeTwo256>>;
     type SlashAmountPerMaxFileSize = ConstU128<10>;
+    type SlashReferenceDataSize = ConstU64<{ u64::MAX }>;
+    type SlashEscalationPerMissedDeadline = SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier = ConstU32<5>;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = ConstU64<10>;
     type MaxCommitmentSize = ConstU32<1000>;
     type ZeroSizeBucketFixedRate = ConstU128<1>;
     type ProviderTopUpTtl = ProviderTopUpTtl;
     type MaxExpiredItemsInBlock = ConstU32<100u32>;
+    type MaxCapacityUtilizationSamples = ConstU32<10>;
+    type CapacityUtilizationSamplingPeriod = ConstU64<10>;
+    type RequireCapacityAttestation = ConstBool<false>;
+    type CapacityAttestationChunkSize = ConstU64<10>;
+    type CapacityAttestationSpotChecks = ConstU32<4>;
+    type CapacityAttestationWindow = ConstU64<10>;
+    type CapacityDecreaseGracePeriod = ConstU64<20>;
+    type ReputationGrowthStreak = ConstU32<5>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<10>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelpers = ();
 }
@@ -462,6 +516,10 @@ impl ProofSubmittersInterface for MockSubmittingProviders {
         None
     }
 
+    fn get_consecutive_missed_proof_deadlines(_provider_id: &Self::ProviderId) -> u32 {
+        0
+    }
+
     fn clear_accrued_failed_proof_submissions(_provider_id: &Self::ProviderId) {}
 }
 
@@ -44,19 +44,108 @@ pub enum QueryConfirmChunksToProveForFileError {
     ChallengedChunkToChunkIdError,
 }
 
+/// Error type for the `query_storage_request_replication_status` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum QueryStorageRequestReplicationStatusError {
+    StorageRequestNotFound,
+    InternalError,
+}
+
+/// Error type for the `estimate_storage_request_cost` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum EstimateStorageRequestCostError {
+    FileSizeCannotBeZero,
+    ReplicationTargetExceedsMaximum,
+    MspOnlyStorageRequestWithoutMsp,
+    NotAMsp,
+    ArithmeticOverflow,
+}
+
+/// What issuing a storage request would cost its User, at the current prices.
+///
+/// The MSP is paid through the payment stream of the bucket, at the price of its value proposition,
+/// so it is not included.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct StorageRequestCostEstimate<Balance> {
+    /// Deposit held when issuing the storage request, returned once it is fulfilled, revoked or
+    /// expires.
+    pub creation_deposit: Balance,
+    /// Deposit held upfront for the payment streams of the BSPs, covering their payment for a fixed
+    /// amount of ticks. Assumes the User does not pay any of them yet, as it then only grows.
+    pub payment_streams_deposit: Balance,
+    /// What the User pays per tick to all the BSPs, once the replication target is reached.
+    pub bsps_rate_per_tick: Balance,
+}
+
+/// Replication progress of a storage request, together with the parameters that govern how fast BSPs
+/// become eligible to volunteer for it.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct StorageRequestReplicationStatus<TickNumber, ReplicationTarget> {
+    /// The tick at which the storage request was issued.
+    pub requested_at: TickNumber,
+    /// The current tick.
+    pub current_tick: TickNumber,
+    pub bsps_required: ReplicationTarget,
+    pub bsps_confirmed: ReplicationTarget,
+    pub bsps_volunteered: ReplicationTarget,
+    /// The number of BSPs currently registered.
+    pub number_of_bsps: u32,
+    pub max_replication_target: ReplicationTarget,
+    /// Number of ticks it takes for every BSP to become eligible to volunteer.
+    pub tick_range_to_maximum_threshold: TickNumber,
+}
+
+/// An open storage request, along with the BSPs that volunteered to store its file.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct OpenStorageRequest<FileKey, StorageRequestMetadata, BackupStorageProviderId> {
+    pub file_key: FileKey,
+    /// The storage request, which includes how many BSPs are required, volunteered and confirmed.
+    pub metadata: StorageRequestMetadata,
+    /// The BSPs that volunteered to store the file, and whether they already confirmed storing it.
+    pub volunteers: Vec<(BackupStorageProviderId, bool)>,
+}
+
 sp_api::decl_runtime_apis! {
-    #[api_version(1)]
-    pub trait FileSystemApi<BackupStorageProviderId, MainStorageProviderId, FileKey, TickNumber, ChunkId>
+    #[api_version(2)]
+    pub trait FileSystemApi<BackupStorageProviderId, MainStorageProviderId, FileKey, TickNumber, ChunkId, BucketId, ReplicationTarget, ProviderId, StorageRequestMetadata, AccountId, Balance>
     where
         BackupStorageProviderId: Codec,
         MainStorageProviderId: Codec,
         FileKey: Codec,
         TickNumber: Codec,
         ChunkId: Codec,
+        BucketId: Codec,
+        ReplicationTarget: Codec,
+        ProviderId: Codec,
+        StorageRequestMetadata: Codec,
+        AccountId: Codec,
+        Balance: Codec,
     {
         fn is_storage_request_open_to_volunteers(file_key: FileKey) -> Result<bool, IsStorageRequestOpenToVolunteersError>;
         fn query_earliest_file_volunteer_tick(bsp_id: BackupStorageProviderId, file_key: FileKey) -> Result<TickNumber, QueryFileEarliestVolunteerTickError>;
         fn query_bsp_confirm_chunks_to_prove_for_file(bsp_id: BackupStorageProviderId, file_key: FileKey) -> Result<Vec<ChunkId>, QueryBspConfirmChunksToProveForFileError>;
         fn query_msp_confirm_chunks_to_prove_for_file(msp_id: MainStorageProviderId, file_key: FileKey) -> Result<Vec<ChunkId>, QueryMspConfirmChunksToProveForFileError>;
+        #[api_version(2)]
+        fn query_bucket_default_replication_target(bucket_id: BucketId) -> ReplicationTarget;
+        #[api_version(2)]
+        fn is_msp_eligible(msp_id: MainStorageProviderId) -> bool;
+        #[api_version(2)]
+        fn query_storage_request_replication_status(file_key: FileKey) -> Result<StorageRequestReplicationStatus<TickNumber, ReplicationTarget>, QueryStorageRequestReplicationStatusError>;
+        #[api_version(2)]
+        fn pending_storage_requests_for_provider(provider_id: ProviderId, start_after: Option<FileKey>, limit: u32) -> Vec<OpenStorageRequest<FileKey, StorageRequestMetadata, BackupStorageProviderId>>;
+        #[api_version(2)]
+        fn storage_requests_by_bucket(bucket_id: BucketId, start_after: Option<FileKey>, limit: u32) -> Vec<OpenStorageRequest<FileKey, StorageRequestMetadata, BackupStorageProviderId>>;
+        #[api_version(2)]
+        fn query_notification_preferences(account: AccountId) -> Option<Vec<u8>>;
+        #[api_version(2)]
+        fn query_file_access_list(file_key: FileKey) -> Vec<AccountId>;
+        #[api_version(2)]
+        fn has_file_read_access(bucket_id: BucketId, file_key: FileKey, account: AccountId) -> bool;
+        #[api_version(2)]
+        fn query_billable_size(size: u64) -> u64;
+        #[api_version(2)]
+        fn query_storage_request(file_key: FileKey) -> Option<OpenStorageRequest<FileKey, StorageRequestMetadata, BackupStorageProviderId>>;
+        #[api_version(2)]
+        fn estimate_storage_request_cost(size: u64, replication_target: ReplicationTarget, msp_id: Option<MainStorageProviderId>) -> Result<StorageRequestCostEstimate<Balance>, EstimateStorageRequestCostError>;
     }
 }
@@ -94,6 +94,7 @@ mod benchmarks {
             name,
             true,
             Some(initial_value_prop_id),
+            None,
         )?;
 
         /*********** Call the extrinsic to benchmark: ***********/
@@ -157,6 +158,7 @@ mod benchmarks {
             name,
             true,
             Some(initial_value_prop_id),
+            None,
         )?;
 
         // Request the move of the bucket to the new MSP
@@ -219,6 +221,7 @@ mod benchmarks {
             name,
             true,
             Some(value_prop_id),
+            None,
         )?;
 
         // The worst-case scenario is when the bucket has an associated collection but it doesn't exist in storage,
@@ -285,6 +288,7 @@ mod benchmarks {
             name,
             true,
             Some(value_prop_id),
+            None,
         )?;
 
         // The worst-case scenario is when the bucket has an associated collection but it doesn't exist in storage,
@@ -350,6 +354,7 @@ mod benchmarks {
             name,
             true,
             Some(value_prop_id),
+            None,
         )?;
 
         // Get the collection ID of the bucket
@@ -415,6 +420,7 @@ mod benchmarks {
             name,
             true,
             Some(value_prop_id),
+            None,
         )?;
 
         #[extrinsic_call]
@@ -427,6 +433,7 @@ mod benchmarks {
             Some(msp_id),
             peer_ids,
             None,
+            true,
         );
 
         Ok(())
@@ -466,6 +473,7 @@ mod benchmarks {
             name,
             true,
             Some(value_prop_id),
+            None,
         )?;
 
         // Issue the storage request from the user
@@ -491,6 +499,7 @@ mod benchmarks {
             Some(msp_id),
             peer_ids,
             None,
+            false,
         )?;
 
         // Compute the file key
@@ -590,7 +599,7 @@ mod benchmarks {
             Some(msp_id),
             name,
             true,
-            Some(value_prop_id),
+            Some(value_prop_id), None,
         )?;
 
         // Issue the storage request from the user
@@ -615,6 +624,8 @@ mod benchmarks {
             size,
             Some(msp_id),
             peer_ids,
+            None,
+            false,
         )?;
 
         // Compute the file key
@@ -20,7 +20,7 @@
 //!
 //! ## Hooks
 //!
-//! - `on_idle`: Cleanup all expired storage requests.
+//! - `on_idle`: Cleanup all expired storage requests and access grants.
 //!
 //! ## Dependencies
 //!
@@ -55,19 +55,20 @@ pub mod pallet {
         sp_runtime::traits::{CheckEqual, Convert, MaybeDisplay, SimpleBitOps},
         traits::{
             fungible::*,
-            nonfungibles_v2::{Create, Destroy, Inspect as NonFungiblesInspect},
+            nonfungibles_v2::{Create, Destroy, Inspect as NonFungiblesInspect, InspectEnumerable},
         },
         Blake2_128Concat,
     };
     use frame_system::pallet_prelude::{BlockNumberFor, *};
     use scale_info::prelude::fmt::Debug;
     use shp_file_metadata::ChunkId;
+    use shp_traits::ProviderStatusChange;
     use sp_runtime::{
         traits::{
             Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, ConvertBack, One, Saturating,
             Zero,
         },
-        BoundedVec,
+        BoundedVec, Perbill,
     };
     use sp_weights::WeightMeter;
 
@@ -103,6 +104,7 @@ pub mod pallet {
                 ProviderId = <Self::Providers as shp_traits::ReadProvidersInterface>::ProviderId,
                 ReadAccessGroupId = CollectionIdFor<Self>,
                 StorageDataUnit = <Self::Providers as shp_traits::ReadStorageProvidersInterface>::StorageDataUnit,
+                EncryptionMetadataLimit = <Self::Providers as shp_traits::ReadBucketsInterface>::EncryptionMetadataLimit,
             > + shp_traits::SystemMetricsInterface<
                 ProvidedUnit = <Self::Providers as shp_traits::ReadStorageProvidersInterface>::StorageDataUnit,
             >;
@@ -115,11 +117,19 @@ pub mod pallet {
 
         /// The trait to create, update, delete and inspect payment streams.
         type PaymentStreams: shp_traits::PaymentStreamsInterface<
+            Balance = Self::Currency,
             AccountId = Self::AccountId,
             ProviderId = <Self::Providers as shp_traits::ReadProvidersInterface>::ProviderId,
             Units = <Self::Providers as shp_traits::ReadStorageProvidersInterface>::StorageDataUnit,
         >
-        + shp_traits::MutatePricePerGigaUnitPerTickInterface<PricePerGigaUnitPerTick = BalanceOf<Self>>;
+        + shp_traits::MutatePricePerGigaUnitPerTickInterface<PricePerGigaUnitPerTick = BalanceOf<Self>>
+        + shp_traits::PaymentEscrowInterface<
+            AccountId = Self::AccountId,
+            ProviderId = <Self::Providers as shp_traits::ReadProvidersInterface>::ProviderId,
+            EscrowId = <Self::Providers as shp_traits::ReadProvidersInterface>::MerkleHash,
+            Units = <Self::Providers as shp_traits::ReadStorageProvidersInterface>::StorageDataUnit,
+            TickNumber = BlockNumberFor<Self>,
+        >;
 
         /// The trait to initialise a Provider's randomness commit-reveal cycle.
         type CrRandomness: shp_traits::CommitRevealRandomnessInterface<
@@ -229,6 +239,7 @@ pub mod pallet {
 
         /// Registry for minted NFTs.
         type Nfts: NonFungiblesInspect<Self::AccountId>
+            + InspectEnumerable<Self::AccountId>
             + Create<Self::AccountId, CollectionConfigFor<Self>>
             + Destroy<Self::AccountId>;
 
@@ -277,6 +288,21 @@ pub mod pallet {
         #[pallet::constant]
         type StorageRequestTtl: Get<u32>;
 
+        /// Number of blocks before a storage request expires at which a [`Event::StorageRequestExpiringSoon`]
+        /// event is emitted. Zero disables these notifications.
+        ///
+        /// Should be lower than [`Config::StorageRequestTtl`], otherwise storage requests could be
+        /// created too late to be notified.
+        #[pallet::constant]
+        type StorageRequestExpirationNotificationLeadTime: Get<u32>;
+
+        /// Maximum number of [`Event::StorageRequestExpiringSoon`] events to emit per block.
+        ///
+        /// The storage requests expiring in the same block are notified together, so this can be
+        /// exceeded by up to [`Config::MaxExpiredItemsInBlock`].
+        #[pallet::constant]
+        type MaxStorageRequestExpirationNotificationsPerBlock: Get<u32>;
+
         /// Time-to-live for a pending file deletion request, after which a priority challenge is sent out to enforce the deletion.
         #[pallet::constant]
         type PendingFileDeletionRequestTtl: Get<u32>;
@@ -301,9 +327,118 @@ pub mod pallet {
         #[pallet::constant]
         type StorageRequestCreationDeposit: Get<BalanceOf<Self>>;
 
+        /// Deposit held from the User when creating a new MSP-only storage request (with a replication
+        /// target of zero), instead of [`Config::StorageRequestCreationDeposit`].
+        #[pallet::constant]
+        type MspOnlyStorageRequestCreationDeposit: Get<BalanceOf<Self>>;
+
+        /// Maximum share of the storage request creation deposit paid to the BSPs that confirmed storing the
+        /// file, as compensation for the data they already downloaded, when the User revokes the storage request.
+        ///
+        /// The share grows linearly with the ticks elapsed since the storage request was issued, reaching this
+        /// maximum after [`Config::StorageRequestRevocationCompensationPeriod`] ticks. It is then scaled by the
+        /// ratio of confirmed to required BSPs, and split equally among the confirmed ones. The rest of the
+        /// deposit is returned to the User, who gets all of it back if no BSP confirmed yet.
+        #[pallet::constant]
+        type StorageRequestRevocationCompensation: Get<Perbill>;
+
+        /// Amount of ticks after issuing a storage request that it takes for the compensation paid to the BSPs
+        /// when it is revoked to reach the full [`Config::StorageRequestRevocationCompensation`] share.
+        #[pallet::constant]
+        type StorageRequestRevocationCompensationPeriod: Get<u32>;
+
+        /// Fee held from the User when requesting to delete a file without a proof of inclusion.
+        ///
+        /// It pays whoever executes the deletion by submitting the proof for the pending file
+        /// deletion request, and goes to the treasury if the request expires instead. It is capped at
+        /// [`Config::MaxFileDeletionFee`].
+        #[pallet::constant]
+        type FileDeletionFee: Get<BalanceOf<Self>>;
+
+        /// Upper bound of the [`Config::FileDeletionFee`].
+        #[pallet::constant]
+        type MaxFileDeletionFee: Get<BalanceOf<Self>>;
+
         /// Default replication target
         #[pallet::constant]
         type DefaultReplicationTarget: Get<ReplicationTargetType<Self>>;
+
+        /// Number of blocks over which the priority challenges for file deletions queued by each issuer are metered.
+        #[pallet::constant]
+        type PriorityChallengesMeteringPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of priority challenges for file deletions an issuer can queue directly in a metering period.
+        ///
+        /// Once reached, the issuer's priority challenges are deferred to the fair-share queue instead.
+        #[pallet::constant]
+        type PriorityChallengesSoftLimitPerIssuer: Get<u32>;
+
+        /// Maximum number of priority challenges for file deletions an issuer can have in the fair-share queue.
+        #[pallet::constant]
+        type MaxDeferredPriorityChallengesPerIssuer: Get<u32>;
+
+        /// Maximum number of issuers that can have priority challenges in the fair-share queue at the same time.
+        #[pallet::constant]
+        type MaxDeferredPriorityChallengeIssuers: Get<u32>;
+
+        /// Maximum number of priority challenges moved from the fair-share queue to the priority challenges queue per block.
+        #[pallet::constant]
+        type MaxDeferredPriorityChallengesQueuedPerBlock: Get<u32>;
+
+        /// Maximum number of storage requests and move bucket requests checked per block when processing
+        /// the Providers that became insolvent or were deregistered.
+        #[pallet::constant]
+        type MaxProviderStatusChangeItemsPerBlock: Get<u32>;
+
+        /// Maximum number of failed deposit transfers that can be pending to be retried at the same time.
+        ///
+        /// Failed deposit transfers that don't fit are only notified, and have to be followed up manually.
+        #[pallet::constant]
+        type MaxDepositTransferRetries: Get<u32>;
+
+        /// Number of times a failed deposit transfer is retried before giving up on it.
+        #[pallet::constant]
+        type MaxDepositTransferRetryAttempts: Get<u32>;
+
+        /// Number of ticks to wait before retrying a failed deposit transfer for the first time.
+        ///
+        /// The wait doubles after every failed attempt.
+        #[pallet::constant]
+        type DepositTransferRetryBaseDelay: Get<TickNumber<Self>>;
+
+        /// Maximum length of the notification preferences that a user can set, in bytes.
+        #[pallet::constant]
+        type MaxNotificationPreferencesLength: Get<u32>;
+
+        /// Maximum amount of accounts that the owner of a file can grant read access to.
+        #[pallet::constant]
+        type MaxFileAccessListSize: Get<u32>;
+
+        /// Minimum size a file is billed as by the BSPs storing it.
+        ///
+        /// Files smaller than this are paid for as if they were of this size, both in the payment
+        /// streams with their BSPs and in the escrowed payment of storage requests, since every file
+        /// costs Providers roughly the same overhead (i.e. in their Forest and in the challenges they
+        /// have to answer) regardless of its size.
+        #[pallet::constant]
+        type MinBillableFileSize: Get<StorageData<Self>>;
+
+        /// Maximum depth of the Merkle tree of a deletion list, which bounds the size of the proofs
+        /// that a file is part of it. A deletion list holds at most `2^MaxDeletionListProofDepth` files.
+        #[pallet::constant]
+        type MaxDeletionListProofDepth: Get<u32>;
+
+        /// Deposit held from the owner of a deletion list for each of its files, for the storage the
+        /// list takes while it is pending.
+        ///
+        /// The deposit of each file is returned to the owner once the file is processed, whether it
+        /// was deleted or skipped, and the deposit of the remaining files when the list is cancelled.
+        #[pallet::constant]
+        type DeletionListItemDeposit: Get<BalanceOf<Self>>;
+
+        /// Maximum amount of unexpired access grants a bucket, or a file, can have at once.
+        #[pallet::constant]
+        type MaxAccessGrantsPerTarget: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -330,6 +465,15 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// The deposits held for the automatic renewal of storage requests whose owner opted into it.
+    ///
+    /// When such a storage request expires without reaching its replication target, the deposit is
+    /// used to issue a new storage request for the missing replicas. It is returned to the owner if
+    /// the storage request is fulfilled, revoked or rejected instead.
+    #[pallet::storage]
+    pub type StorageRequestAutoRenewals<T: Config> =
+        StorageMap<_, Blake2_128Concat, MerkleHash<T>, BalanceOf<T>>;
+
     /// Bookkeeping of the buckets containing open storage requests.
     #[pallet::storage]
     pub type BucketsWithStorageRequests<T: Config> = StorageDoubleMap<
@@ -393,6 +537,14 @@ pub mod pallet {
     pub type NextAvailableMoveBucketRequestExpirationBlock<T: Config> =
         StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
+    /// A pointer to the next block whose expiring storage requests have not been notified yet.
+    ///
+    /// Advanced in `on_poll` up to current block + [`Config::StorageRequestExpirationNotificationLeadTime`],
+    /// notifying at most [`Config::MaxStorageRequestExpirationNotificationsPerBlock`] storage requests per block.
+    #[pallet::storage]
+    pub type NextBlockToNotifyExpiringStorageRequests<T: Config> =
+        StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
     /// A pointer to the starting block to clean up expired items.
     ///
     /// If this block is behind the current block number, the cleanup algorithm in `on_idle` will
@@ -413,6 +565,66 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Pending file move requests.
+    ///
+    /// A mapping from the file key of a file to the move to a new location requested by its owner, waiting
+    /// for the MSP of the bucket to apply it to the bucket's forest.
+    #[pallet::storage]
+    pub type PendingFileMoveRequests<T: Config> =
+        StorageMap<_, Blake2_128Concat, MerkleHash<T>, FileMove<T>>;
+
+    /// Files moved to a new location.
+    ///
+    /// A mapping from the previous file key of a moved file to its move, once applied to the bucket's forest
+    /// by its MSP. BSPs storing the file use it to apply the same move to their forest.
+    #[pallet::storage]
+    pub type MovedFiles<T: Config> = StorageMap<_, Blake2_128Concat, MerkleHash<T>, FileMove<T>>;
+
+    /// Pointer to the notification preferences of each user, as set by them.
+    ///
+    /// Its content is opaque to the runtime: it is expected to be a hash or a URI that off-chain services
+    /// resolve to the user's contact preferences, to alert them of events concerning their files (i.e. expired
+    /// or rejected storage requests, or the risk of becoming insolvent), without storing any of it on-chain.
+    #[pallet::storage]
+    pub type UserNotificationPreferences<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, NotificationPreferences<T>>;
+
+    /// Accounts granted read access to a file by its owner.
+    ///
+    /// Complements the read-access NFT collection of private buckets, which gates access to the whole bucket,
+    /// so that owners can share single files. MSPs are expected to check it when serving downloads of files
+    /// of private buckets.
+    ///
+    /// Removed when the owner requests the deletion of the file.
+    #[pallet::storage]
+    pub type FileAccessLists<T: Config> =
+        StorageMap<_, Blake2_128Concat, MerkleHash<T>, FileAccessList<T>>;
+
+    /// Fees held for pending file deletion requests.
+    ///
+    /// A mapping from the file key of a pending file deletion request to the fee held from the user that
+    /// requested it, to be paid out once the request is either executed or expired.
+    #[pallet::storage]
+    pub type PendingFileDeletionFees<T: Config> =
+        StorageMap<_, Blake2_128Concat, MerkleHash<T>, BalanceOf<T>>;
+
+    /// The size each BSP bills the owner of a file for through their payment stream, by BSP and
+    /// file key.
+    ///
+    /// It is recorded when the BSP confirms storing the file and is paid through the payment
+    /// stream, so that exactly the same amount is removed from the stream when the BSP stops
+    /// storing the file, even if [`Config::MinBillableFileSize`] changed in between. Files confirmed
+    /// before it was recorded were billed by their actual size.
+    #[pallet::storage]
+    pub type BspBilledFileSizes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ProviderIdFor<T>,
+        Blake2_128Concat,
+        MerkleHash<T>,
+        StorageData<T>,
+    >;
+
     /// Pending file stop storing requests.
     ///
     /// A double mapping from BSP IDs to a list of file keys pending stop storing requests to the block in which those requests were opened,
@@ -455,10 +667,164 @@ pub mod pallet {
     pub type MaxReplicationTarget<T: Config> =
         StorageValue<_, ReplicationTargetType<T>, ValueQuery>;
 
+    /// Default replication target of a bucket, set by its owner.
+    ///
+    /// Used for storage requests of files in the bucket that don't specify a replication target.
+    /// If not set, [`Config::DefaultReplicationTarget`] is used instead.
+    #[pallet::storage]
+    pub type BucketDefaultReplicationTarget<T: Config> =
+        StorageMap<_, Blake2_128Concat, BucketIdFor<T>, ReplicationTargetType<T>>;
+
+    /// Buckets frozen by governance pending the resolution of a dispute, with the block they were
+    /// frozen at.
+    ///
+    /// Frozen buckets can't take new files, be moved or deleted, and files can't be deleted from
+    /// them, until they are unfrozen.
+    #[pallet::storage]
+    pub type FrozenBuckets<T: Config> =
+        StorageMap<_, Blake2_128Concat, BucketIdFor<T>, BlockNumberFor<T>>;
+
     /// Number of ticks until all BSPs would reach the [`Config::MaximumThreshold`] to ensure that all BSPs are able to volunteer.
     #[pallet::storage]
     pub type TickRangeToMaximumThreshold<T: Config> = StorageValue<_, TickNumber<T>, ValueQuery>;
 
+    /// How [`MspEligibilityList`] is interpreted: as a denylist (the default) or as an allowlist.
+    #[pallet::storage]
+    pub type MspEligibilityMode<T: Config> = StorageValue<_, MspEligibilityListMode, ValueQuery>;
+
+    /// MSPs in the governance-managed eligibility list.
+    ///
+    /// Depending on [`MspEligibilityMode`], these are the only MSPs that can take new buckets, or the
+    /// ones that cannot. MSPs that are not eligible keep the buckets they already store, but cannot
+    /// be assigned new buckets (on creation or by moving them) nor new storage requests.
+    #[pallet::storage]
+    pub type MspEligibilityList<T: Config> = StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ()>;
+
+    /// Priority challenges for file deletions queued directly by each issuer in its current metering period.
+    ///
+    /// Once an issuer reaches [`Config::PriorityChallengesSoftLimitPerIssuer`] in a period, its priority
+    /// challenges are deferred to the fair-share queue until the next period starts.
+    #[pallet::storage]
+    pub type PriorityChallengesUsage<T: Config> =
+        StorageMap<_, Blake2_128Concat, EitherAccountIdOrMspId<T>, PriorityChallengeUsage<T>>;
+
+    /// Fair-share queue of priority challenges for file deletions, by issuer.
+    ///
+    /// Holds the file keys of the priority challenges deferred because their issuer exceeded its soft limit,
+    /// in the order they were issued.
+    #[pallet::storage]
+    pub type DeferredPriorityChallenges<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        EitherAccountIdOrMspId<T>,
+        BoundedVec<MerkleHash<T>, T::MaxDeferredPriorityChallengesPerIssuer>,
+        ValueQuery,
+    >;
+
+    /// Issuers with priority challenges in [`DeferredPriorityChallenges`], in the order they are served.
+    ///
+    /// The fair-share queue is drained in turns: one priority challenge of the first issuer is queued, and the
+    /// issuer is moved to the back if it has more deferred priority challenges.
+    #[pallet::storage]
+    pub type DeferredPriorityChallengeIssuers<T: Config> = StorageValue<
+        _,
+        BoundedVec<EitherAccountIdOrMspId<T>, T::MaxDeferredPriorityChallengeIssuers>,
+        ValueQuery,
+    >;
+
+    /// Providers that became insolvent or were deregistered, pending to be processed.
+    ///
+    /// Filled by the Providers pallet through [`shp_traits::OnProviderStatusChange`], and processed one
+    /// Provider at a time in `on_idle`, cancelling or rerouting the storage requests and move bucket
+    /// requests that were waiting on them.
+    #[pallet::storage]
+    pub type ProvidersWithStatusChange<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ProviderStatusChange>;
+
+    /// The Provider in [`ProvidersWithStatusChange`] currently being processed, and how far along it is.
+    #[pallet::storage]
+    pub type ProviderStatusChangeInProgress<T: Config> =
+        StorageValue<_, ProviderStatusChangeProgress<T>>;
+
+    /// Failed deposit transfers pending to be retried in `on_idle`, by retry ID.
+    ///
+    /// Bounded by [`Config::MaxDepositTransferRetries`].
+    #[pallet::storage]
+    pub type DepositTransferRetries<T: Config> =
+        CountedStorageMap<_, Blake2_128Concat, DepositTransferRetryId, DepositTransferRetry<T>>;
+
+    /// The ID to assign to the next failed deposit transfer queued in [`DepositTransferRetries`].
+    #[pallet::storage]
+    pub type NextDepositTransferRetryId<T: Config> =
+        StorageValue<_, DepositTransferRetryId, ValueQuery>;
+
+    /// Deletion lists registered by each account, by the root of their Merkle tree.
+    ///
+    /// A list is removed once all its files have been deleted, or when its owner cancels it.
+    #[pallet::storage]
+    pub type DeletionLists<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        MerkleHash<T>,
+        DeletionList<T>,
+    >;
+
+    /// Upload grants of each bucket, by grantee.
+    ///
+    /// A grant lets its grantee issue storage requests for files of the bucket on behalf of its owner, who
+    /// pays for them, until it expires or its size budget runs out. Removed when the owner revokes it.
+    #[pallet::storage]
+    pub type UploadGrants<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BucketIdFor<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        UploadGrant<T>,
+    >;
+
+    /// Offers to transfer a bucket to another MSP made by the MSP storing it, waiting for the owner of
+    /// the bucket to respond.
+    ///
+    /// Once accepted by the owner, the offer becomes a regular move bucket request that the new MSP
+    /// accepts or rejects. Replaced if the MSP makes a new offer for the bucket.
+    #[pallet::storage]
+    pub type BucketTransferOffers<T: Config> =
+        StorageMap<_, Blake2_128Concat, BucketIdFor<T>, BucketTransferOffer<T>>;
+
+    /// Access grants of each bucket, or file of a bucket.
+    ///
+    /// A grant gives its grantee read access to the files of a private bucket, or to a single one, until
+    /// it expires, without making the bucket public or holding an item of its read-access NFT collection.
+    /// MSPs are expected to check it when serving downloads of files of private buckets.
+    ///
+    /// Expired grants are removed in `on_idle`, and can be revoked by the owner of the bucket before.
+    #[pallet::storage]
+    pub type AccessGrants<T: Config> =
+        StorageMap<_, Blake2_128Concat, AccessGrantTarget<T>, AccessGrantList<T>>;
+
+    /// Targets of the access grants expiring at each tick, to remove the grants once they expire.
+    ///
+    /// Entries of grants that were revoked or extended are left as is, and skipped when processed.
+    #[pallet::storage]
+    pub type AccessGrantExpirations<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        TickNumber<T>,
+        Blake2_128Concat,
+        AccessGrantTarget<T>,
+        (),
+    >;
+
+    /// Next tick whose expired access grants are to be removed.
+    ///
+    /// Zero until the first access grant is created, from whose tick the cleanup starts.
+    #[pallet::storage]
+    pub type NextAccessGrantExpirationTickToCleanUp<T: Config> =
+        StorageValue<_, TickNumber<T>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub max_replication_target: ReplicationTargetType<T>,
@@ -522,6 +888,18 @@ pub mod pallet {
             collection_id: Option<CollectionIdFor<T>>,
             private: bool,
         },
+        /// Notifies that a bucket's default replication target has been updated.
+        BucketDefaultReplicationTargetUpdated {
+            who: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            replication_target: Option<ReplicationTargetType<T>>,
+        },
+        /// Notifies that the encryption metadata of a bucket has been set or, if `None`, removed.
+        BucketEncryptionMetadataUpdated {
+            who: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            encryption_metadata: Option<EncryptionMetadataFor<T>>,
+        },
         /// Notifies that a new collection has been created and associated with a bucket.
         NewCollectionAndAssociation {
             who: T::AccountId,
@@ -538,6 +916,15 @@ pub mod pallet {
             size: StorageData<T>,
             peer_ids: PeerIds<T>,
         },
+        /// Notifies that a new storage request has been issued with a replication target of zero, so it
+        /// will not be replicated to any BSP.
+        ///
+        /// The file is only stored by the MSP, which means it has lower durability guarantees than
+        /// a file backed up by BSPs. Emitted alongside [`Event::NewStorageRequest`].
+        NewMspOnlyStorageRequest {
+            file_key: MerkleHash<T>,
+            msp_id: ProviderIdFor<T>,
+        },
         /// Notifies that a Main Storage Provider (MSP) has accepted a storage request for a specific file key.
         ///
         /// This event is emitted when an MSP agrees to store a file, but the storage request
@@ -575,10 +962,35 @@ pub mod pallet {
         /// Note: This is a valid storage outcome, the user being responsible to track the number
         /// of BSPs and choose to either delete the file and re-issue a storage request or continue.
         StorageRequestExpired { file_key: MerkleHash<T> },
+        /// Notifies that an expired storage request, whose owner opted into its automatic renewal,
+        /// has been issued again for the `bsps_required` replicas it was missing.
+        StorageRequestRenewed {
+            file_key: MerkleHash<T>,
+            bsps_required: ReplicationTargetType<T>,
+        },
+        /// Notifies that an expired storage request could not be renewed automatically, i.e.
+        /// because its bucket is being moved. Its renewal deposit is returned to its owner.
+        StorageRequestRenewalFailed {
+            file_key: MerkleHash<T>,
+            error: DispatchError,
+        },
+        /// Notifies that a storage request will expire at block `expires_at`, which is
+        /// [`Config::StorageRequestExpirationNotificationLeadTime`] blocks away or less.
+        StorageRequestExpiringSoon {
+            file_key: MerkleHash<T>,
+            expires_at: BlockNumberFor<T>,
+        },
         /// Notifies that a storage request has been revoked by the user who initiated it.
         /// Note: the BSPs who confirmed the file are also issued a priority challenge to delete the
         /// file.
         StorageRequestRevoked { file_key: MerkleHash<T> },
+        /// Notifies that a BSP that confirmed storing a file was paid a compensation, out of the storage
+        /// request creation deposit, because its owner revoked the storage request.
+        BspCompensatedForRevokedStorageRequest {
+            file_key: MerkleHash<T>,
+            bsp_id: ProviderIdFor<T>,
+            amount: BalanceOf<T>,
+        },
         /// Notifies that a storage request has either been directly rejected by the MSP or
         /// the MSP did not respond to the storage request in time.
         /// Note: There might be BSPs that have volunteered and confirmed the file already, for
@@ -612,6 +1024,18 @@ pub mod pallet {
             location: FileLocation<T>,
             new_root: MerkleHash<T>,
         },
+        /// Notifies that a priority challenge for file deletion has been deferred to the fair-share queue,
+        /// since its issuer exceeded its soft limit for the current metering period.
+        PriorityChallengeForFileDeletionDeferred {
+            issuer: EitherAccountIdOrMspId<T>,
+            file_key: MerkleHash<T>,
+        },
+        /// Notifies that a deferred priority challenge for file deletion has been moved from the fair-share
+        /// queue to the priority challenges queue.
+        DeferredPriorityChallengeForFileDeletionQueued {
+            issuer: EitherAccountIdOrMspId<T>,
+            file_key: MerkleHash<T>,
+        },
         /// Notifies that a priority challenge failed to be queued for pending file deletion.
         FailedToQueuePriorityChallenge {
             user: T::AccountId,
@@ -625,6 +1049,56 @@ pub mod pallet {
             msp_id: Option<ProviderIdFor<T>>,
             proof_of_inclusion: bool,
         },
+        /// Notifies that a batch of files will be deleted.
+        FileDeletionRequestsBatch {
+            user: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            msp_id: Option<ProviderIdFor<T>>,
+            file_keys: BoundedVec<MerkleHash<T>, MaxUserPendingDeletionRequests<T>>,
+        },
+        /// Notifies that the owner of a file requested to move it to a new location within its bucket.
+        FileMoveRequested {
+            owner: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            msp_id: Option<ProviderIdFor<T>>,
+            file_key: MerkleHash<T>,
+            new_file_key: MerkleHash<T>,
+            new_location: FileLocation<T>,
+        },
+        /// Notifies that the MSP of a bucket moved a file to its new location in the bucket's forest.
+        FileMoved {
+            msp_id: ProviderIdFor<T>,
+            bucket_id: BucketIdFor<T>,
+            file_key: MerkleHash<T>,
+            new_file_key: MerkleHash<T>,
+            new_bucket_root: MerkleHash<T>,
+        },
+        /// Notifies that a BSP moved a file to its new location in its forest.
+        BspConfirmedFileMove {
+            bsp_id: ProviderIdFor<T>,
+            file_key: MerkleHash<T>,
+            new_file_key: MerkleHash<T>,
+            new_root: MerkleHash<T>,
+        },
+        /// Notifies that a user set or, if `None`, cleared their notification preferences.
+        NotificationPreferencesSet {
+            who: T::AccountId,
+            preferences: Option<NotificationPreferences<T>>,
+        },
+        /// Notifies that the owner of a file granted read access to it to an account.
+        FileAccessGranted {
+            owner: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            file_key: MerkleHash<T>,
+            account: T::AccountId,
+        },
+        /// Notifies that the owner of a file revoked the read access to it of an account.
+        FileAccessRevoked {
+            owner: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            file_key: MerkleHash<T>,
+            account: T::AccountId,
+        },
         /// Notifies that a proof has been submitted for a pending file deletion request.
         ProofSubmittedForPendingFileDeletionRequest {
             msp_id: ProviderIdFor<T>,
@@ -668,46 +1142,253 @@ pub mod pallet {
             file_size: StorageData<T>,
             error: DispatchError,
         },
-    }
-
-    // Errors inform users that something went wrong.
-    #[pallet::error]
-    pub enum Error<T> {
-        /// Storage request already registered for the given file.
-        StorageRequestAlreadyRegistered,
-        /// Storage request not registered for the given file.
-        StorageRequestNotFound,
-        /// Operation not allowed while the storage request is not being revoked.
-        StorageRequestNotRevoked,
-        /// Operation not allowed while the storage request exists.
-        StorageRequestExists,
-        /// Replication target cannot be zero.
-        ReplicationTargetCannotBeZero,
-        /// BSPs required for storage request cannot exceed the maximum allowed.
-        ReplicationTargetExceedsMaximum,
-        /// Max replication target cannot be smaller than default replication target.
-        MaxReplicationTargetSmallerThanDefault,
-        /// Account is not a BSP.
-        NotABsp,
-        /// Account is not a MSP.
-        NotAMsp,
-        /// Account is not a SP.
-        NotASp,
-        /// BSP has not volunteered to store the given file.
-        BspNotVolunteered,
-        /// BSP has not confirmed storing the given file.
-        BspNotConfirmed,
-        /// BSP has already confirmed storing the given file.
-        BspAlreadyConfirmed,
-        /// Number of BSPs required for storage request has been reached.
-        StorageRequestBspsRequiredFulfilled,
-        /// BSP already volunteered to store the given file.
-        BspAlreadyVolunteered,
-        /// SP does not have enough storage capacity to store the file.
-        InsufficientAvailableCapacity,
-        /// Number of removed BSPs volunteered from storage request prefix did not match the expected number.
-        UnexpectedNumberOfRemovedVolunteeredBsps,
-        /// BSP cannot volunteer at this current tick.
+        /// Notifies that the MSP eligibility list is now interpreted with the given mode.
+        MspEligibilityModeSet { mode: MspEligibilityListMode },
+        /// Notifies that an MSP has been added to the MSP eligibility list.
+        MspAddedToEligibilityList { msp_id: ProviderIdFor<T> },
+        /// Notifies that an MSP has been removed from the MSP eligibility list.
+        MspRemovedFromEligibilityList { msp_id: ProviderIdFor<T> },
+        /// Notifies that a storage request has been cancelled because the MSP it selected became
+        /// insolvent or was deregistered before accepting it.
+        ///
+        /// The storage request creation deposit is returned to the owner, who can issue it again
+        /// with another MSP.
+        StorageRequestCancelledByUnavailableMsp {
+            file_key: MerkleHash<T>,
+            owner: T::AccountId,
+            msp_id: ProviderIdFor<T>,
+            status: ProviderStatusChange,
+        },
+        /// Notifies that a BSP that volunteered for a storage request, without confirming storing the
+        /// file, has been removed from it because it became insolvent or was deregistered.
+        ///
+        /// This frees its slot so that another BSP can volunteer.
+        UnavailableBspVolunteerRemoved {
+            file_key: MerkleHash<T>,
+            bsp_id: ProviderIdFor<T>,
+            status: ProviderStatusChange,
+        },
+        /// Notifies that a move bucket request has been cancelled because the MSP it targeted became
+        /// insolvent or was deregistered.
+        MoveBucketRequestCancelledByUnavailableMsp {
+            bucket_id: BucketIdFor<T>,
+            msp_id: ProviderIdFor<T>,
+            status: ProviderStatusChange,
+        },
+        /// Notifies that all the storage requests and move bucket requests waiting on a Provider that
+        /// became insolvent or was deregistered have been processed.
+        ProviderStatusChangeProcessed {
+            provider_id: ProviderIdFor<T>,
+            status: ProviderStatusChange,
+        },
+        /// Notifies that a transfer of funds held or escrowed by this pallet failed.
+        ///
+        /// If there was room in the retry queue, it is retried in the next blocks under `retry_id`.
+        /// Otherwise, it has to be followed up manually.
+        DepositTransferFailed {
+            transfer: DepositTransfer<T>,
+            retry_id: Option<DepositTransferRetryId>,
+            error: DispatchError,
+        },
+        /// Notifies that a failed deposit transfer was retried successfully.
+        DepositTransferRetrySucceeded {
+            retry_id: DepositTransferRetryId,
+            transfer: DepositTransfer<T>,
+        },
+        /// Notifies that a retry of a failed deposit transfer failed, and when it will be retried again.
+        DepositTransferRetryFailed {
+            retry_id: DepositTransferRetryId,
+            attempts: u32,
+            next_attempt_at: TickNumber<T>,
+            error: DispatchError,
+        },
+        /// Notifies that a failed deposit transfer was given up on after
+        /// [`Config::MaxDepositTransferRetryAttempts`] retries. It has to be followed up manually.
+        DepositTransferAbandoned {
+            retry_id: DepositTransferRetryId,
+            transfer: DepositTransfer<T>,
+            error: DispatchError,
+        },
+        /// Notifies that a failed deposit transfer was removed from the retry queue by governance,
+        /// without executing it.
+        DepositTransferWrittenOff {
+            retry_id: DepositTransferRetryId,
+            transfer: DepositTransfer<T>,
+        },
+        /// Notifies that the owner of an open storage request changed its replication target.
+        ///
+        /// If the new target was already reached, it is followed by [`Event::StorageRequestFulfilled`].
+        StorageRequestReplicationTargetChanged {
+            file_key: MerkleHash<T>,
+            owner: T::AccountId,
+            previous_replication_target: ReplicationTargetType<T>,
+            replication_target: ReplicationTargetType<T>,
+        },
+        /// Notifies that a bucket has been frozen by governance pending the resolution of a dispute.
+        BucketFrozen {
+            bucket_id: BucketIdFor<T>,
+            owner: T::AccountId,
+            msp_id: Option<ProviderIdFor<T>>,
+        },
+        /// Notifies that a frozen bucket has been unfrozen by governance.
+        BucketUnfrozen {
+            bucket_id: BucketIdFor<T>,
+            owner: T::AccountId,
+            msp_id: Option<ProviderIdFor<T>>,
+        },
+        /// Notifies that an account authorised the deletion of a list of `length` files, identified
+        /// by the root of its Merkle tree, holding `deposit` for them.
+        DeletionListRegistered {
+            owner: T::AccountId,
+            list_root: MerkleHash<T>,
+            length: u32,
+            deposit: BalanceOf<T>,
+        },
+        /// Notifies that the file at `index` of a deletion list will be deleted. Behaves as
+        /// [`Event::FileDeletionRequest`] for the file.
+        DeletionListFileDeletionRequest {
+            owner: T::AccountId,
+            list_root: MerkleHash<T>,
+            index: u32,
+            file_key: MerkleHash<T>,
+            bucket_id: BucketIdFor<T>,
+            msp_id: Option<ProviderIdFor<T>>,
+            proof_of_inclusion: bool,
+        },
+        /// Notifies that the file at `index` of a deletion list was skipped, since requesting its
+        /// deletion failed with `error`, i.e. because it no longer belongs to the owner of the list.
+        DeletionListItemSkipped {
+            owner: T::AccountId,
+            list_root: MerkleHash<T>,
+            index: u32,
+            file_key: MerkleHash<T>,
+            error: DispatchError,
+        },
+        /// Notifies that all the files of a deletion list have been processed.
+        DeletionListCompleted {
+            owner: T::AccountId,
+            list_root: MerkleHash<T>,
+        },
+        /// Notifies that the owner of a deletion list cancelled it, after `executed` of its files
+        /// were requested to be deleted.
+        DeletionListCancelled {
+            owner: T::AccountId,
+            list_root: MerkleHash<T>,
+            executed: u32,
+        },
+        /// Notifies that the owner of a bucket allowed `grantee` to issue storage requests for files
+        /// of up to `size_budget` in total into it until block `expires_at`, replacing any previous
+        /// grant.
+        UploadGrantCreated {
+            owner: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            grantee: T::AccountId,
+            size_budget: StorageData<T>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// Notifies that the owner of a bucket revoked the upload grant of `grantee`.
+        UploadGrantRevoked {
+            owner: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            grantee: T::AccountId,
+        },
+        /// Notifies that `grantee` issued a storage request with its upload grant. It follows the
+        /// [`Event::NewStorageRequest`] of the file, which is owned by the owner of the bucket.
+        UploadGrantUsed {
+            owner: T::AccountId,
+            bucket_id: BucketIdFor<T>,
+            grantee: T::AccountId,
+            file_key: MerkleHash<T>,
+            size: StorageData<T>,
+            remaining_budget: StorageData<T>,
+        },
+        /// Notifies that the MSP storing a bucket offered to transfer it to `new_msp_id`. The owner
+        /// of the bucket can accept the offer until block `expires_at`.
+        BucketTransferOffered {
+            bucket_id: BucketIdFor<T>,
+            msp_id: ProviderIdFor<T>,
+            new_msp_id: ProviderIdFor<T>,
+            owner: T::AccountId,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// Notifies that the owner of a bucket accepted the offer of its MSP to transfer it to
+        /// `new_msp_id`. It is followed by the [`Event::MoveBucketRequested`] that `new_msp_id` has
+        /// to accept for the bucket to be moved.
+        BucketTransferOfferAccepted {
+            bucket_id: BucketIdFor<T>,
+            msp_id: ProviderIdFor<T>,
+            new_msp_id: ProviderIdFor<T>,
+        },
+        /// Notifies that the owner of a bucket rejected the offer of its MSP to transfer it to
+        /// `new_msp_id`.
+        BucketTransferOfferRejected {
+            bucket_id: BucketIdFor<T>,
+            msp_id: ProviderIdFor<T>,
+            new_msp_id: ProviderIdFor<T>,
+        },
+        /// Notifies that the owner of a bucket gave `grantee` read access to `target` until tick
+        /// `expires_at`, replacing any previous grant.
+        AccessGrantCreated {
+            owner: T::AccountId,
+            target: AccessGrantTarget<T>,
+            grantee: T::AccountId,
+            expires_at: TickNumber<T>,
+        },
+        /// Notifies that the owner of a bucket revoked the access grant of `grantee` to `target`.
+        AccessGrantRevoked {
+            owner: T::AccountId,
+            target: AccessGrantTarget<T>,
+            grantee: T::AccountId,
+        },
+        /// Notifies that the access grant of `grantee` to `target` expired and was removed.
+        AccessGrantExpired {
+            target: AccessGrantTarget<T>,
+            grantee: T::AccountId,
+        },
+    }
+
+    // Errors inform users that something went wrong.
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Storage request already registered for the given file.
+        StorageRequestAlreadyRegistered,
+        /// Storage request not registered for the given file.
+        StorageRequestNotFound,
+        /// Operation not allowed while the storage request is not being revoked.
+        StorageRequestNotRevoked,
+        /// Operation not allowed while the storage request exists.
+        StorageRequestExists,
+        /// Replication target cannot be zero.
+        ReplicationTargetCannotBeZero,
+        /// BSPs required for storage request cannot exceed the maximum allowed.
+        ReplicationTargetExceedsMaximum,
+        /// Max replication target cannot be smaller than default replication target.
+        MaxReplicationTargetSmallerThanDefault,
+        /// Storage requests with a replication target of zero (MSP-only) must specify an MSP.
+        MspOnlyStorageRequestWithoutMsp,
+        /// BSPs cannot volunteer for a storage request with a replication target of zero (MSP-only).
+        StorageRequestIsMspOnly,
+        /// Account is not a BSP.
+        NotABsp,
+        /// Account is not a MSP.
+        NotAMsp,
+        /// Account is not a SP.
+        NotASp,
+        /// BSP has not volunteered to store the given file.
+        BspNotVolunteered,
+        /// BSP has not confirmed storing the given file.
+        BspNotConfirmed,
+        /// BSP has already confirmed storing the given file.
+        BspAlreadyConfirmed,
+        /// Number of BSPs required for storage request has been reached.
+        StorageRequestBspsRequiredFulfilled,
+        /// BSP already volunteered to store the given file.
+        BspAlreadyVolunteered,
+        /// SP does not have enough storage capacity to store the file.
+        InsufficientAvailableCapacity,
+        /// Number of removed BSPs volunteered from storage request prefix did not match the expected number.
+        UnexpectedNumberOfRemovedVolunteeredBsps,
+        /// BSP cannot volunteer at this current tick.
         BspNotEligibleToVolunteer,
         /// No slot available found in blocks to insert storage request expiration time.
         StorageRequestExpiredNoSlotAvailable,
@@ -758,6 +1439,18 @@ pub mod pallet {
         FileKeyAlreadyPendingDeletion,
         /// Max number of user pending deletion requests reached.
         MaxUserPendingDeletionRequestsReached,
+        /// Batch of files to delete is empty.
+        EmptyFileDeletionBatch,
+        /// The new location of the file is the same as its current one.
+        FileMoveToSameLocation,
+        /// A move of the file to a new location was already requested.
+        FileMoveAlreadyRequested,
+        /// No move of the file to a new location was requested.
+        FileMoveRequestNotFound,
+        /// The file was not moved to a new location.
+        FileNotMoved,
+        /// Files paid for through an escrow can't be moved, since the escrow is bound to their file key.
+        FileHasEscrowedPayment,
         /// Unauthorized operation, signer is not an MSP of the bucket id.
         MspNotStoringBucket,
         /// File key not found in pending deletion requests.
@@ -790,6 +1483,8 @@ pub mod pallet {
         MoveBucketRequestNotFound,
         /// Action not allowed while the bucket is being moved.
         BucketIsBeingMoved,
+        /// The MSP is not eligible to take new buckets or storage requests, as per the MSP eligibility list.
+        MspNotEligible,
         /// BSP is already a data server for the move bucket request.
         BspAlreadyDataServer,
         /// Too many registered data servers for the move bucket request.
@@ -822,6 +1517,60 @@ pub mod pallet {
         NoPrivacyChange,
         /// Operations not allowed for insolvent provider
         OperationNotAllowedForInsolventProvider,
+        /// The issuer has reached the maximum number of deferred priority challenges for file deletions
+        DeferredPriorityChallengesQueueFull,
+        /// There is no failed deposit transfer pending to be retried with the given ID
+        DepositTransferRetryNotFound,
+        /// The new replication target of the storage request is the same as the current one
+        ReplicationTargetUnchanged,
+        /// The replication target cannot be lower than the amount of BSPs that already confirmed storing the file
+        ReplicationTargetBelowConfirmedBsps,
+        /// The bucket is frozen pending the resolution of a dispute
+        BucketIsFrozen,
+        /// The bucket is already frozen
+        BucketAlreadyFrozen,
+        /// The bucket is not frozen
+        BucketNotFrozen,
+        /// The access list of the file is full
+        FileAccessListFull,
+        /// The account already has read access to the file
+        FileAccessAlreadyGranted,
+        /// The account does not have read access to the file
+        FileAccessNotGranted,
+        /// Operations not allowed for a provider suspended by governance
+        OperationNotAllowedForSuspendedProvider,
+        /// Operations not allowed for a provider that scheduled its sign off
+        OperationNotAllowedForExitingProvider,
+        /// A deletion list must hold at least one file
+        DeletionListEmpty,
+        /// The deletion list holds more files than its Merkle tree can with the maximum proof depth
+        DeletionListTooLong,
+        /// The account already registered a deletion list with this root
+        DeletionListAlreadyRegistered,
+        /// The account has no deletion list with this root
+        DeletionListNotFound,
+        /// The proof that the file is part of the deletion list is invalid
+        InvalidDeletionListProof,
+        /// The expiration block of the upload grant has already been reached
+        UploadGrantExpirationInThePast,
+        /// The size budget of the upload grant cannot be zero
+        UploadGrantBudgetCannotBeZero,
+        /// The account has no upload grant for the bucket
+        UploadGrantNotFound,
+        /// The upload grant has expired
+        UploadGrantExpired,
+        /// The size of the file exceeds the remaining budget of the upload grant
+        UploadGrantBudgetExceeded,
+        /// There is no offer to transfer the bucket to another MSP
+        BucketTransferOfferNotFound,
+        /// The offer to transfer the bucket to another MSP has expired
+        BucketTransferOfferExpired,
+        /// The expiration tick of the access grant has already been reached
+        AccessGrantExpirationInThePast,
+        /// The bucket or file already has the maximum amount of access grants
+        AccessGrantsFull,
+        /// The account has no access grant for the bucket or file
+        AccessGrantNotFound,
     }
 
     /// This enum holds the HoldReasons for this pallet, allowing the runtime to identify each held balance with different reasons separately
@@ -832,6 +1581,13 @@ pub mod pallet {
     pub enum HoldReason {
         /// Deposit that a user has to pay to create a new storage request
         StorageRequestCreationHold,
+        /// Fee that a user has to pay to request the deletion of a file without a proof of inclusion
+        FileDeletionFeeHold,
+        /// Deposit that a user pre-funds to automatically renew a storage request that expires
+        /// without reaching its replication target
+        StorageRequestRenewalHold,
+        /// Deposit that a user has to pay for each file of a deletion list it registers
+        DeletionListDepositHold,
         // Only for testing, another unrelated hold reason
         #[cfg(test)]
         AnotherUnrelatedHold,
@@ -839,6 +1595,10 @@ pub mod pallet {
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Create a bucket, optionally stored by an MSP.
+        ///
+        /// `encryption_metadata` describes how the files of the bucket are encrypted client-side,
+        /// if they are. See [`Pallet::set_bucket_encryption_metadata`].
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::create_bucket())]
         pub fn create_bucket(
@@ -847,14 +1607,21 @@ pub mod pallet {
             name: BucketNameFor<T>,
             private: bool,
             value_prop_id: Option<ValuePropId<T>>,
+            encryption_metadata: Option<EncryptionMetadataFor<T>>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            let (bucket_id, maybe_collection_id) =
-                Self::do_create_bucket(who.clone(), msp_id, name.clone(), private, value_prop_id)?;
+            let (bucket_id, maybe_collection_id) = Self::do_create_bucket(
+                who.clone(),
+                msp_id,
+                name.clone(),
+                private,
+                value_prop_id,
+                encryption_metadata.clone(),
+            )?;
 
             Self::deposit_event(Event::NewBucket {
-                who,
+                who: who.clone(),
                 msp_id,
                 bucket_id,
                 name,
@@ -864,6 +1631,14 @@ pub mod pallet {
                 value_prop_id,
             });
 
+            if let Some(encryption_metadata) = encryption_metadata {
+                Self::deposit_event(Event::BucketEncryptionMetadataUpdated {
+                    who,
+                    bucket_id,
+                    encryption_metadata: Some(encryption_metadata),
+                });
+            }
+
             Ok(())
         }
 
@@ -981,6 +1756,14 @@ pub mod pallet {
         }
 
         /// Issue a new storage request for a file
+        ///
+        /// If `replication_target` is `None`, the bucket's default replication target is used. A
+        /// replication target of zero issues an MSP-only storage request: no BSP will store the file,
+        /// and the storage request is fulfilled as soon as the MSP accepts it. An MSP is required.
+        ///
+        /// If `auto_renew` is `true`, a second storage request creation deposit is held, so that if
+        /// the storage request expires without reaching its replication target, a new one is issued
+        /// for the missing replicas, paying its deposit with it.
         #[pallet::call_index(6)]
         #[pallet::weight(T::WeightInfo::issue_storage_request())]
         pub fn issue_storage_request(
@@ -992,12 +1775,13 @@ pub mod pallet {
             msp_id: Option<ProviderIdFor<T>>,
             peer_ids: PeerIds<T>,
             replication_target: Option<ReplicationTargetType<T>>,
+            auto_renew: bool,
         ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer
             let who = ensure_signed(origin)?;
 
             // Perform validations and register storage request
-            Self::do_request_storage(
+            let file_key = Self::do_request_storage(
                 who.clone(),
                 bucket_id,
                 location.clone(),
@@ -1008,6 +1792,10 @@ pub mod pallet {
                 Some(peer_ids.clone()),
             )?;
 
+            if auto_renew {
+                Self::do_enable_storage_request_auto_renewal(&who, file_key)?;
+            }
+
             Ok(())
         }
 
@@ -1344,32 +2132,849 @@ pub mod pallet {
 
             Ok(().into())
         }
-    }
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_poll(_n: BlockNumberFor<T>, weight: &mut frame_support::weights::WeightMeter) {
-            // TODO: Benchmark computational weight cost of this hook.
+        /// Set (or clear, if `None`) the default replication target of a bucket.
+        ///
+        /// Storage requests for files in this bucket that don't specify a replication target
+        /// will use this value instead of [`Config::DefaultReplicationTarget`].
+        ///
+        /// *Callable only by the owner of the bucket.*
+        #[pallet::call_index(18)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_bucket_default_replication_target(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            replication_target: Option<ReplicationTargetType<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            Self::do_on_poll(weight);
+            Self::do_set_bucket_default_replication_target(
+                who.clone(),
+                bucket_id,
+                replication_target,
+            )?;
+
+            Self::deposit_event(Event::BucketDefaultReplicationTargetUpdated {
+                who,
+                bucket_id,
+                replication_target,
+            });
+
+            Ok(())
         }
 
-        fn on_idle(current_block: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
-            let mut meter = WeightMeter::with_limit(remaining_weight);
-            Self::do_on_idle(current_block, &mut meter);
+        /// Set how the MSP eligibility list is interpreted: as a denylist of MSPs that cannot take
+        /// new buckets, or as an allowlist of the only MSPs that can.
+        ///
+        /// *Callable only by root.*
+        #[pallet::call_index(19)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_msp_eligibility_mode(
+            origin: OriginFor<T>,
+            mode: MspEligibilityListMode,
+        ) -> DispatchResult {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
 
-            meter.consumed()
+            MspEligibilityMode::<T>::put(mode);
+
+            Self::deposit_event(Event::MspEligibilityModeSet { mode });
+
+            Ok(())
         }
 
-        /// Any code located in this hook is placed in an auto-generated test, and generated as a part
-        /// of crate::construct_runtime's expansion.
-        /// Look for a test case with a name along the lines of: __construct_runtime_integrity_test.
-        fn integrity_test() {
-            let default_replication_target = T::DefaultReplicationTarget::get();
+        /// Add an MSP to (`listed` = `true`) or remove it from (`listed` = `false`) the MSP
+        /// eligibility list.
+        ///
+        /// This does not affect the buckets the MSP already stores.
+        ///
+        /// *Callable only by root.*
+        #[pallet::call_index(20)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn update_msp_eligibility_list(
+            origin: OriginFor<T>,
+            msp_id: ProviderIdFor<T>,
+            listed: bool,
+        ) -> DispatchResult {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
 
-            assert!(
-                default_replication_target > T::ReplicationTargetType::zero(),
-                "Default replication target cannot be zero."
+            if listed {
+                MspEligibilityList::<T>::insert(&msp_id, ());
+                Self::deposit_event(Event::MspAddedToEligibilityList { msp_id });
+            } else {
+                MspEligibilityList::<T>::remove(&msp_id);
+                Self::deposit_event(Event::MspRemovedFromEligibilityList { msp_id });
+            }
+
+            Ok(())
+        }
+
+        /// Issue a new storage request for a file, escrowing upfront the payment of all the BSPs
+        /// required to fulfill it for `escrow_ticks` ticks.
+        ///
+        /// The cost is projected with the current price per giga-unit per tick. Each BSP that confirms
+        /// storing the file is paid from the escrow (instead of through its payment stream with the
+        /// User) as it proves storing it, and is moved to its payment stream once paid for all
+        /// `escrow_ticks`. The share of the BSPs missing when the storage request is fulfilled, expires
+        /// or is revoked is refunded to the User.
+        #[pallet::call_index(21)]
+        #[pallet::weight(T::WeightInfo::issue_storage_request() + T::DbWeight::get().writes(1))]
+        pub fn issue_escrowed_storage_request(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            location: FileLocation<T>,
+            fingerprint: Fingerprint<T>,
+            size: StorageData<T>,
+            msp_id: Option<ProviderIdFor<T>>,
+            peer_ids: PeerIds<T>,
+            replication_target: Option<ReplicationTargetType<T>>,
+            escrow_ticks: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            // Check that the extrinsic was signed and get the signer
+            let who = ensure_signed(origin)?;
+
+            // Perform validations, register storage request and escrow its payment
+            Self::do_request_escrowed_storage(
+                who,
+                bucket_id,
+                location,
+                fingerprint,
+                size,
+                msp_id,
+                replication_target,
+                peer_ids,
+                escrow_ticks,
+            )?;
+
+            Ok(())
+        }
+
+        /// Resolve a failed deposit transfer pending to be retried, either retrying it right away or
+        /// writing it off (removing it from the retry queue without executing it).
+        ///
+        /// Meant to follow up on deposit transfers that keep failing, or to write off the ones that
+        /// were already settled by other means.
+        ///
+        /// *Callable only by root.*
+        #[pallet::call_index(22)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn resolve_deposit_transfer_retry(
+            origin: OriginFor<T>,
+            retry_id: DepositTransferRetryId,
+            resolution: DepositTransferResolution,
+        ) -> DispatchResult {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
+
+            Self::do_resolve_deposit_transfer_retry(retry_id, resolution)
+        }
+
+        /// Migrate a bucket to the latest version of its value proposition.
+        ///
+        /// Buckets keep the version of the value proposition they were created with when their MSP
+        /// publishes a new one, until their owner opts in to the new terms with this extrinsic. The
+        /// fixed rate payment stream of the owner with the MSP is updated to the new price.
+        ///
+        /// *Callable only by the owner of the bucket.*
+        #[pallet::call_index(23)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 2))]
+        pub fn migrate_bucket_value_prop(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_migrate_bucket_value_prop(who, bucket_id)
+        }
+
+        /// Change the replication target of an open storage request.
+        ///
+        /// The target can be raised up to the maximum replication target, or lowered down to the
+        /// amount of BSPs that already confirmed storing the file. If the new target is already
+        /// reached (and the MSP, if any, accepted the storage request), the storage request is
+        /// fulfilled right away. The storage request creation deposit is adjusted to the new target.
+        ///
+        /// If the payment of the BSPs was escrowed, the escrow is left as is: BSPs beyond the escrowed
+        /// ones are paid through their payment streams with the owner, and the share of the missing
+        /// ones is refunded once the storage request is closed.
+        ///
+        /// *Callable only by the owner of the storage request.*
+        #[pallet::call_index(24)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 3))]
+        pub fn change_storage_request_replication_target(
+            origin: OriginFor<T>,
+            file_key: MerkleHash<T>,
+            replication_target: ReplicationTargetType<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_change_storage_request_replication_target(who, file_key, replication_target)
+        }
+
+        /// Freeze a bucket pending the resolution of a dispute.
+        ///
+        /// While frozen, no new files can be added to the bucket, files can't be deleted from it, and
+        /// the bucket can't be moved to another MSP, stopped being stored by its MSP or deleted. Files
+        /// already stored are left untouched.
+        ///
+        /// *Callable only by root.*
+        #[pallet::call_index(25)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn freeze_bucket(origin: OriginFor<T>, bucket_id: BucketIdFor<T>) -> DispatchResult {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
+
+            let (owner, msp_id) = Self::do_freeze_bucket(bucket_id)?;
+
+            Self::deposit_event(Event::BucketFrozen {
+                bucket_id,
+                owner,
+                msp_id,
+            });
+
+            Ok(())
+        }
+
+        /// Unfreeze a bucket frozen with [`Pallet::freeze_bucket`], lifting all its restrictions.
+        ///
+        /// *Callable only by root.*
+        #[pallet::call_index(26)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn unfreeze_bucket(origin: OriginFor<T>, bucket_id: BucketIdFor<T>) -> DispatchResult {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
+
+            let (owner, msp_id) = Self::do_unfreeze_bucket(bucket_id)?;
+
+            Self::deposit_event(Event::BucketUnfrozen {
+                bucket_id,
+                owner,
+                msp_id,
+            });
+
+            Ok(())
+        }
+
+        /// Request the deletion of a batch of files from a bucket owned by the caller.
+        ///
+        /// Behaves as calling [`Pallet::delete_file`] without a proof of inclusion for each file,
+        /// with a single signed extrinsic covering all of them: the pending file deletion requests
+        /// are either all created or none is.
+        #[pallet::call_index(27)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 3).saturating_mul(files.len() as u64))]
+        pub fn delete_files(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            files: BoundedVec<FileDeletionRequestItem<T>, MaxUserPendingDeletionRequests<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (file_keys, msp_id) = Self::do_delete_files(who.clone(), bucket_id, files)?;
+
+            Self::deposit_event(Event::FileDeletionRequestsBatch {
+                user: who,
+                bucket_id,
+                msp_id,
+                file_keys,
+            });
+
+            Ok(())
+        }
+
+        /// Request to move a file to a new location within its bucket, keeping its size and fingerprint.
+        ///
+        /// Since the location is part of the file's metadata, the file gets a new file key. The move is
+        /// applied to the bucket's forest by its MSP with [`Pallet::msp_confirm_file_move`], after which
+        /// BSPs storing the file can apply it to their forest with [`Pallet::bsp_confirm_file_move`].
+        ///
+        /// *Callable only by the owner of the bucket.*
+        #[pallet::call_index(28)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 1))]
+        pub fn request_move_file(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            file_key: MerkleHash<T>,
+            location: FileLocation<T>,
+            size: StorageData<T>,
+            fingerprint: Fingerprint<T>,
+            new_location: FileLocation<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (new_file_key, msp_id) = Self::do_request_move_file(
+                who.clone(),
+                bucket_id,
+                file_key,
+                location,
+                size,
+                fingerprint,
+                new_location.clone(),
+            )?;
+
+            Self::deposit_event(Event::FileMoveRequested {
+                owner: who,
+                bucket_id,
+                msp_id,
+                file_key,
+                new_file_key,
+                new_location,
+            });
+
+            Ok(())
+        }
+
+        /// Used by the MSP of a bucket to move a file to the new location requested by its owner.
+        ///
+        /// The forest proof must prove that the current file key is in the bucket's forest and the new
+        /// one is not. The former is replaced by the latter, holding the metadata of the file at its new
+        /// location.
+        #[pallet::call_index(29)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 3))]
+        pub fn msp_confirm_file_move(
+            origin: OriginFor<T>,
+            file_key: MerkleHash<T>,
+            forest_proof: ForestProof<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (msp_id, file_move, new_bucket_root) =
+                Self::do_msp_confirm_file_move(who, file_key, forest_proof)?;
+
+            Self::deposit_event(Event::FileMoved {
+                msp_id,
+                bucket_id: file_move.bucket_id,
+                file_key,
+                new_file_key: file_move.new_file_key,
+                new_bucket_root,
+            });
+
+            Ok(())
+        }
+
+        /// Used by a BSP storing a file moved by the MSP of its bucket to apply the same move to its forest.
+        ///
+        /// The forest proof must prove that the previous file key is in the BSP's forest and the new one
+        /// is not. The former is replaced by the latter, holding the metadata of the file at its new location.
+        #[pallet::call_index(30)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn bsp_confirm_file_move(
+            origin: OriginFor<T>,
+            file_key: MerkleHash<T>,
+            forest_proof: ForestProof<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (bsp_id, new_file_key, new_root) =
+                Self::do_bsp_confirm_file_move(who, file_key, forest_proof)?;
+
+            Self::deposit_event(Event::BspConfirmedFileMove {
+                bsp_id,
+                file_key,
+                new_file_key,
+                new_root,
+            });
+
+            Ok(())
+        }
+
+        /// Set the pointer to the caller's notification preferences, or clear it if `None`.
+        ///
+        /// The preferences are expected to be a hash or a URI, which off-chain services resolve to the
+        /// caller's contact preferences to alert them of events concerning their files.
+        #[pallet::call_index(31)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_notification_preferences(
+            origin: OriginFor<T>,
+            preferences: Option<NotificationPreferences<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_set_notification_preferences(&who, preferences.clone());
+
+            Self::deposit_event(Event::NotificationPreferencesSet { who, preferences });
+
+            Ok(())
+        }
+
+        /// Grant `account` read access to a file owned by the caller.
+        ///
+        /// The file is identified by its metadata, from which its file key is computed, so that only its owner
+        /// can manage its access list.
+        #[pallet::call_index(32)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn grant_file_access(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            location: FileLocation<T>,
+            size: StorageData<T>,
+            fingerprint: Fingerprint<T>,
+            account: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let file_key = Self::do_grant_file_access(
+                &who,
+                bucket_id,
+                location,
+                size,
+                fingerprint,
+                account.clone(),
+            )?;
+
+            Self::deposit_event(Event::FileAccessGranted {
+                owner: who,
+                bucket_id,
+                file_key,
+                account,
+            });
+
+            Ok(())
+        }
+
+        /// Revoke the read access of `account` to a file owned by the caller.
+        ///
+        /// The file is identified by its metadata, the same way as in [`Pallet::grant_file_access`].
+        #[pallet::call_index(33)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn revoke_file_access(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            location: FileLocation<T>,
+            size: StorageData<T>,
+            fingerprint: Fingerprint<T>,
+            account: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let file_key = Self::do_revoke_file_access(
+                &who,
+                bucket_id,
+                location,
+                size,
+                fingerprint,
+                &account,
+            )?;
+
+            Self::deposit_event(Event::FileAccessRevoked {
+                owner: who,
+                bucket_id,
+                file_key,
+                account,
+            });
+
+            Ok(())
+        }
+
+        /// Authorise the deletion of a list of `length` files owned by the caller with a single
+        /// signature, by registering the root of a Merkle tree over their file keys.
+        ///
+        /// Anyone can then request the deletion of the files of the list, in order, with
+        /// [`Pallet::execute_deletion_list_item`] and a proof that each file is part of it. See
+        /// [`Pallet::verify_deletion_list_proof`] for how the tree is built.
+        ///
+        /// Holds [`Config::DeletionListItemDeposit`] from the caller for each file of the list.
+        #[pallet::call_index(34)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn register_deletion_list(
+            origin: OriginFor<T>,
+            list_root: MerkleHash<T>,
+            length: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let deposit = Self::do_register_deletion_list(&who, list_root, length)?;
+
+            Self::deposit_event(Event::DeletionListRegistered {
+                owner: who,
+                list_root,
+                length,
+                deposit,
+            });
+
+            Ok(())
+        }
+
+        /// Request the deletion of the next file of a deletion list registered by `owner`.
+        ///
+        /// Behaves as `owner` calling [`Pallet::delete_file`] for the file, once `merkle_proof` proves
+        /// that its file key is the next one of the list. Each file of the list can only be processed
+        /// once, and the list is removed after its last file.
+        ///
+        /// If requesting the deletion of the file fails without a proof of inclusion because it can
+        /// never succeed, i.e. the bucket no longer exists or no longer belongs to `owner`, the file is
+        /// skipped so that it does not block the rest of the list. Any other failure, or a failure with
+        /// a proof of inclusion, fails the call instead and the file can be retried, so that neither a
+        /// temporary failure nor a wrong proof can be used to skip a file. The deposit of a processed
+        /// file is returned to `owner`.
+        ///
+        /// *Callable by anyone.*
+        #[pallet::call_index(35)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 4))]
+        pub fn execute_deletion_list_item(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            list_root: MerkleHash<T>,
+            bucket_id: BucketIdFor<T>,
+            location: FileLocation<T>,
+            size: StorageData<T>,
+            fingerprint: Fingerprint<T>,
+            merkle_proof: DeletionListProof<T>,
+            maybe_inclusion_forest_proof: Option<ForestProof<T>>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let (index, file_key, deletion_result, completed) =
+                Self::do_execute_deletion_list_item(
+                    &owner,
+                    list_root,
+                    bucket_id,
+                    location,
+                    size,
+                    fingerprint,
+                    merkle_proof,
+                    maybe_inclusion_forest_proof,
+                )?;
+
+            match deletion_result {
+                Ok((proof_of_inclusion, msp_id)) => {
+                    Self::deposit_event(Event::DeletionListFileDeletionRequest {
+                        owner: owner.clone(),
+                        list_root,
+                        index,
+                        file_key,
+                        bucket_id,
+                        msp_id,
+                        proof_of_inclusion,
+                    });
+                }
+                Err(error) => {
+                    Self::deposit_event(Event::DeletionListItemSkipped {
+                        owner: owner.clone(),
+                        list_root,
+                        index,
+                        file_key,
+                        error,
+                    });
+                }
+            }
+
+            if completed {
+                Self::deposit_event(Event::DeletionListCompleted { owner, list_root });
+            }
+
+            Ok(())
+        }
+
+        /// Cancel a deletion list registered by the caller, so that none of its remaining files can be
+        /// deleted through it. The deposit of its remaining files is returned to the caller.
+        #[pallet::call_index(36)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn cancel_deletion_list(
+            origin: OriginFor<T>,
+            list_root: MerkleHash<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let executed = Self::do_cancel_deletion_list(&who, list_root)?;
+
+            Self::deposit_event(Event::DeletionListCancelled {
+                owner: who,
+                list_root,
+                executed,
+            });
+
+            Ok(())
+        }
+
+        /// Allow `grantee` to issue storage requests for files of up to `size_budget` in total into a
+        /// bucket owned by the caller, until block `expires_at`, with
+        /// [`Pallet::issue_storage_request_with_grant`].
+        ///
+        /// The storage requests are issued on behalf of the caller, who owns the files and pays their
+        /// deposits and storage. Replaces any previous grant of `grantee` for the bucket.
+        #[pallet::call_index(37)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn create_upload_grant(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            grantee: T::AccountId,
+            size_budget: StorageData<T>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_create_upload_grant(
+                &who,
+                bucket_id,
+                grantee.clone(),
+                size_budget,
+                expires_at,
+            )?;
+
+            Self::deposit_event(Event::UploadGrantCreated {
+                owner: who,
+                bucket_id,
+                grantee,
+                size_budget,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Revoke the upload grant of `grantee` for a bucket owned by the caller.
+        ///
+        /// Storage requests already issued with the grant are not affected.
+        #[pallet::call_index(38)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn revoke_upload_grant(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            grantee: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_revoke_upload_grant(&who, bucket_id, &grantee)?;
+
+            Self::deposit_event(Event::UploadGrantRevoked {
+                owner: who,
+                bucket_id,
+                grantee,
+            });
+
+            Ok(())
+        }
+
+        /// Issue a storage request for a file of a bucket the caller was given an upload grant for.
+        ///
+        /// Behaves as the owner of the bucket calling [`Pallet::issue_storage_request`] for the file,
+        /// without auto-renewal, after deducting its size from the remaining budget of the grant.
+        #[pallet::call_index(39)]
+        #[pallet::weight(T::WeightInfo::issue_storage_request() + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn issue_storage_request_with_grant(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            location: FileLocation<T>,
+            fingerprint: Fingerprint<T>,
+            size: StorageData<T>,
+            msp_id: Option<ProviderIdFor<T>>,
+            peer_ids: PeerIds<T>,
+            replication_target: Option<ReplicationTargetType<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (owner, file_key, remaining_budget) = Self::do_request_storage_with_grant(
+                &who,
+                bucket_id,
+                location,
+                fingerprint,
+                size,
+                msp_id,
+                peer_ids,
+                replication_target,
+            )?;
+
+            Self::deposit_event(Event::UploadGrantUsed {
+                owner,
+                bucket_id,
+                grantee: who,
+                file_key,
+                size,
+                remaining_budget,
+            });
+
+            Ok(())
+        }
+
+        /// Offer to transfer a bucket stored by the caller MSP to `new_msp_id`, i.e. when winding down.
+        ///
+        /// The owner of the bucket has [`Config::MoveBucketRequestTtl`] blocks to accept the offer with
+        /// [`Pallet::respond_bucket_transfer_offer`], after which `new_msp_id` accepts or rejects the
+        /// move as with any move bucket request. Replaces any previous offer for the bucket.
+        #[pallet::call_index(40)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn msp_offer_bucket_transfer(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            new_msp_id: ProviderIdFor<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (msp_id, owner, expires_at) =
+                Self::do_msp_offer_bucket_transfer(who, bucket_id, new_msp_id)?;
+
+            Self::deposit_event(Event::BucketTransferOffered {
+                bucket_id,
+                msp_id,
+                new_msp_id,
+                owner,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Accept or reject, as the owner of a bucket, the offer of its MSP to transfer it to another
+        /// MSP.
+        ///
+        /// Accepting the offer requests the move of the bucket to the new MSP, as
+        /// [`Pallet::request_move_bucket`] would.
+        #[pallet::call_index(41)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 3))]
+        pub fn respond_bucket_transfer_offer(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            response: BucketMoveRequestResponse,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let offer =
+                Self::do_respond_bucket_transfer_offer(who.clone(), bucket_id, response.clone())?;
+
+            match response {
+                BucketMoveRequestResponse::Accepted => {
+                    Self::deposit_event(Event::BucketTransferOfferAccepted {
+                        bucket_id,
+                        msp_id: offer.msp_id,
+                        new_msp_id: offer.new_msp_id,
+                    });
+                    Self::deposit_event(Event::MoveBucketRequested {
+                        who,
+                        bucket_id,
+                        new_msp_id: offer.new_msp_id,
+                    });
+                }
+                BucketMoveRequestResponse::Rejected => {
+                    Self::deposit_event(Event::BucketTransferOfferRejected {
+                        bucket_id,
+                        msp_id: offer.msp_id,
+                        new_msp_id: offer.new_msp_id,
+                    });
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Set (or clear, if `None`) the metadata describing how the contents of a bucket are
+        /// encrypted.
+        ///
+        /// Files are encrypted client-side, so the runtime does not interpret this metadata. It is
+        /// a standard place for the owner to record the encryption scheme and key-wrapping
+        /// details, so that the SDKs reading the files of the bucket can discover how to decrypt
+        /// them.
+        ///
+        /// *Callable only by the owner of the bucket.*
+        #[pallet::call_index(42)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_bucket_encryption_metadata(
+            origin: OriginFor<T>,
+            bucket_id: BucketIdFor<T>,
+            encryption_metadata: Option<EncryptionMetadataFor<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_set_bucket_encryption_metadata(
+                who.clone(),
+                bucket_id,
+                encryption_metadata.clone(),
+            )?;
+
+            Self::deposit_event(Event::BucketEncryptionMetadataUpdated {
+                who,
+                bucket_id,
+                encryption_metadata,
+            });
+
+            Ok(())
+        }
+
+        /// Give `grantee` read access to a bucket owned by the caller, or to a single file of it,
+        /// until tick `expires_at`.
+        ///
+        /// Lets the owner of a private bucket share its files temporarily, without making the bucket
+        /// public or transferring items of its read-access NFT collection. Replaces any previous
+        /// grant of `grantee` for `target`. The grant is removed once it expires.
+        #[pallet::call_index(43)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn create_access_grant(
+            origin: OriginFor<T>,
+            target: AccessGrantTarget<T>,
+            grantee: T::AccountId,
+            expires_at: TickNumber<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_create_access_grant(&who, target.clone(), grantee.clone(), expires_at)?;
+
+            Self::deposit_event(Event::AccessGrantCreated {
+                owner: who,
+                target,
+                grantee,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Revoke the access grant of `grantee` to a bucket owned by the caller, or to a file of it,
+        /// before it expires.
+        #[pallet::call_index(44)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn revoke_access_grant(
+            origin: OriginFor<T>,
+            target: AccessGrantTarget<T>,
+            grantee: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::do_revoke_access_grant(&who, &target, &grantee)?;
+
+            Self::deposit_event(Event::AccessGrantRevoked {
+                owner: who,
+                target,
+                grantee,
+            });
+
+            Ok(())
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_poll(n: BlockNumberFor<T>, weight: &mut frame_support::weights::WeightMeter) {
+            // TODO: Benchmark computational weight cost of this hook.
+
+            Self::do_on_poll(n, weight);
+        }
+
+        fn on_idle(current_block: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            let mut meter = WeightMeter::with_limit(remaining_weight);
+            Self::do_on_idle(current_block, &mut meter);
+
+            meter.consumed()
+        }
+
+        /// Any code located in this hook is placed in an auto-generated test, and generated as a part
+        /// of crate::construct_runtime's expansion.
+        /// Look for a test case with a name along the lines of: __construct_runtime_integrity_test.
+        fn integrity_test() {
+            let default_replication_target = T::DefaultReplicationTarget::get();
+
+            assert!(
+                default_replication_target > T::ReplicationTargetType::zero(),
+                "Default replication target cannot be zero."
+            );
+
+            assert!(
+                T::StorageRequestExpirationNotificationLeadTime::get() < T::StorageRequestTtl::get(),
+                "Storage request expiration notification lead time must be lower than the storage request TTL."
+            );
+
+            assert!(
+                !T::PriorityChallengesMeteringPeriod::get().is_zero(),
+                "Priority challenges metering period cannot be zero."
             );
         }
     }
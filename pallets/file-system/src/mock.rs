@@ -20,7 +20,7 @@ use shp_traits::{
     TrieMutation, TrieProofDeltaApplier,
 };
 use shp_treasury_funding::NoCutTreasuryCutCalculator;
-use sp_core::{hashing::blake2_256, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
+use sp_core::{hashing::blake2_256, ConstBool, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
 use sp_keyring::sr25519::Keyring;
 use sp_runtime::{
     traits::{
@@ -296,6 +296,7 @@ impl pallet_cr_randomness::SeedGenerator for MockSeedGenerator {
 // Payment streams pallet:
 parameter_types! {
     pub const PaymentStreamHoldReason: RuntimeHoldReason = RuntimeHoldReason::PaymentStreams(pallet_payment_streams::HoldReason::PaymentStreamDeposit);
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(5);
 }
 
 impl pallet_payment_streams::Config for Test {
@@ -313,6 +314,10 @@ impl pallet_payment_streams::Config for Test {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = ();
+    type MaxOraclePriceAge = ConstU64<10>;
+    type OraclePriceAdjustmentPeriod = ConstU64<10>;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 // Converter from the BlockNumber type to the Balance type for math
 pub struct BlockNumberToBalance;
@@ -368,6 +373,11 @@ parameter_types! {
     pub const ProviderTopUpTtl: u64 = 10;
 }
 
+parameter_types! {
+    pub const SlashEscalationPerMissedDeadline: Perbill = Perbill::from_percent(10);
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
+}
+
 impl pallet_storage_providers::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -379,6 +389,7 @@ impl pallet_storage_providers::Config for Test {
         { shp_constants::FILE_CHUNK_SIZE },
         { shp_constants::FILE_SIZE_TO_CHALLENGES },
     >;
+    type OnProviderStatusChange = FileSystem;
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type StorageDataUnit = StorageDataUnit;
@@ -404,16 +415,31 @@ impl pallet_storage_providers::Config for Test {
     type MaxProtocols = ConstU32<100>;
     type BucketDeposit = ConstU128<10>;
     type BucketNameLimit = ConstU32<100>;
+    type EncryptionMetadataLimit = ConstU32<256>;
     type MaxBlocksForRandomness = ConstU64<{ EPOCH_DURATION_IN_BLOCKS * 2 }>;
     type MinBlocksBetweenCapacityChanges = ConstU64<10>;
     type DefaultMerkleRoot = DefaultMerkleRoot<LayoutV1<BlakeTwo256>>;
     type SlashAmountPerMaxFileSize = ConstU128<10>;
+    type SlashReferenceDataSize = ConstU64<{ u64::MAX }>;
+    type SlashEscalationPerMissedDeadline = SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier = ConstU32<5>;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = ConstU64<10>;
     type MaxCommitmentSize = ConstU32<1000>;
     type ZeroSizeBucketFixedRate = ConstU128<1>;
     type ProviderTopUpTtl = ProviderTopUpTtl;
     type MaxExpiredItemsInBlock = ConstU32<100>;
+    type MaxCapacityUtilizationSamples = ConstU32<10>;
+    type CapacityUtilizationSamplingPeriod = ConstU64<10>;
+    type RequireCapacityAttestation = ConstBool<false>;
+    type CapacityAttestationChunkSize = ConstU64<10>;
+    type CapacityAttestationSpotChecks = ConstU32<4>;
+    type CapacityAttestationWindow = ConstU64<10>;
+    type CapacityDecreaseGracePeriod = ConstU64<20>;
+    type ReputationGrowthStreak = ConstU32<5>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<10>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelpers = ();
 }
@@ -438,6 +464,10 @@ impl ProofSubmittersInterface for MockSubmittingProviders {
         None
     }
 
+    fn get_consecutive_missed_proof_deadlines(_provider_id: &Self::ProviderId) -> u32 {
+        0
+    }
+
     fn clear_accrued_failed_proof_submissions(_provider_id: &Self::ProviderId) {}
 }
 
@@ -503,6 +533,14 @@ impl pallet_proofs_dealer::Config for Test {
     type BlockFullnessHeadroom = BlockFullnessHeadroom;
     type MinNotFullBlocksRatio = MinNotFullBlocksRatio;
     type MaxSlashableProvidersPerTick = ConstU32<100>;
+    type MaxCatchUpTicksPerBlock = ConstU32<10>;
+    type MissedDeadlinesAllowance = ConstU32<0>;
+    type MissedDeadlineWarningWindow = ConstU64<100>;
+    type MaxProofSize = ConstU32<{ 1024 * 1024 }>;
+    type MaxProofsPerBatch = ConstU32<5>;
+    type OffchainSignature = Signature;
+    type OffchainPublic = AccountPublic;
+    type UnsignedProofPriority = ConstU64<{ u64::MAX }>;
 }
 
 /// Structure to mock a verifier that returns `true` when `proof` is not empty
@@ -580,6 +618,13 @@ pub(crate) type ThresholdType = u32;
 parameter_types! {
     pub const MinWaitForStopStoring: BlockNumber = 1;
     pub const StorageRequestCreationDeposit: Balance = 10;
+    pub const MspOnlyStorageRequestCreationDeposit: Balance = 5;
+    pub const DeletionListItemDeposit: Balance = 1;
+    pub const StorageRequestRevocationCompensation: Perbill = Perbill::from_percent(50);
+    // No fee by default, so that file deletion tests don't have to account for it.
+    pub storage FileDeletionFee: Balance = 0;
+    // Files are billed by their actual size by default, so that payment tests don't have to account for it.
+    pub storage MinBillableFileSize: u64 = 0;
     pub const FileSystemHoldReason: RuntimeHoldReason = RuntimeHoldReason::FileSystem(pallet_file_system::HoldReason::StorageRequestCreationHold);
 }
 
@@ -613,13 +658,35 @@ impl crate::Config for Test {
     type MaxDataServerMultiAddresses = ConstU32<5>;
     type MaxExpiredItemsInBlock = ConstU32<100u32>;
     type StorageRequestTtl = ConstU32<40u32>;
+    type StorageRequestExpirationNotificationLeadTime = ConstU32<10u32>;
+    type MaxStorageRequestExpirationNotificationsPerBlock = ConstU32<100u32>;
     type PendingFileDeletionRequestTtl = ConstU32<40u32>;
     type MoveBucketRequestTtl = ConstU32<40u32>;
     type MaxUserPendingDeletionRequests = ConstU32<10u32>;
     type MaxUserPendingMoveBucketRequests = ConstU32<10u32>;
     type MinWaitForStopStoring = MinWaitForStopStoring;
     type StorageRequestCreationDeposit = StorageRequestCreationDeposit;
+    type MspOnlyStorageRequestCreationDeposit = MspOnlyStorageRequestCreationDeposit;
+    type StorageRequestRevocationCompensation = StorageRequestRevocationCompensation;
+    type StorageRequestRevocationCompensationPeriod = ConstU32<20>;
+    type FileDeletionFee = FileDeletionFee;
+    type MaxFileDeletionFee = ConstU128<100>;
     type DefaultReplicationTarget = ConstU32<2>;
+    type PriorityChallengesMeteringPeriod = ConstU64<10>;
+    type PriorityChallengesSoftLimitPerIssuer = ConstU32<5>;
+    type MaxDeferredPriorityChallengesPerIssuer = ConstU32<10>;
+    type MaxDeferredPriorityChallengeIssuers = ConstU32<10>;
+    type MaxDeferredPriorityChallengesQueuedPerBlock = ConstU32<3>;
+    type MaxProviderStatusChangeItemsPerBlock = ConstU32<10>;
+    type MaxDepositTransferRetries = ConstU32<10>;
+    type MaxDepositTransferRetryAttempts = ConstU32<3>;
+    type DepositTransferRetryBaseDelay = ConstU64<2>;
+    type MaxNotificationPreferencesLength = ConstU32<32>;
+    type MaxFileAccessListSize = ConstU32<3>;
+    type MinBillableFileSize = MinBillableFileSize;
+    type MaxDeletionListProofDepth = ConstU32<16>;
+    type DeletionListItemDeposit = DeletionListItemDeposit;
+    type MaxAccessGrantsPerTarget = ConstU32<3>;
 }
 
 // If we ever require a better mock that doesn't just return true if it is Eve, change this.
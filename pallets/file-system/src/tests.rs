@@ -2,15 +2,23 @@ use crate::{
     self as file_system,
     mock::*,
     types::{
-        BucketIdFor, BucketMoveRequestResponse, BucketNameFor, FileDeletionRequestExpirationItem,
-        FileKeyWithProof, FileLocation, MoveBucketRequestMetadata, PeerIds,
+        AccessGrantTarget, BalanceOf, BucketIdFor, BucketMoveRequestResponse, BucketNameFor,
+        BucketTransferOffer, DeletionList, DeletionListProof, DepositTransfer,
+        DepositTransferResolution, EitherAccountIdOrMspId, EncryptionMetadataFor,
+        FileDeletionRequestExpirationItem, FileDeletionRequestItem, FileKeyWithProof, FileLocation,
+        MoveBucketRequestMetadata, MspEligibilityListMode, NotificationPreferences, PeerIds,
         PendingFileDeletionRequest, PendingFileDeletionRequestTtl, ProviderIdFor, StorageData,
         StorageRequestBspsMetadata, StorageRequestMetadata, StorageRequestMspAcceptedFileKeys,
         StorageRequestMspBucketResponse, StorageRequestTtl, ThresholdType, ValuePropId,
     },
-    Config, Error, Event, MaxReplicationTarget, PendingBucketsToMove, PendingMoveBucketRequests,
-    PendingStopStoringRequests, StorageRequestExpirations, StorageRequests,
-    TickRangeToMaximumThreshold,
+    AccessGrantExpirations, AccessGrants, BspBilledFileSizes, BucketDefaultReplicationTarget,
+    BucketTransferOffers, BucketsWithStorageRequests, Config, DeferredPriorityChallengeIssuers,
+    DeferredPriorityChallenges, DeletionLists, DepositTransferRetries, Error, Event,
+    FileAccessLists, FrozenBuckets, MaxReplicationTarget, MovedFiles, MspEligibilityList,
+    MspEligibilityMode, PendingBucketsToMove, PendingFileDeletionFees, PendingFileMoveRequests,
+    PendingMoveBucketRequests, PendingStopStoringRequests, PriorityChallengesUsage,
+    ProviderStatusChangeInProgress, ProvidersWithStatusChange, StorageRequestExpirations,
+    StorageRequests, TickRangeToMaximumThreshold, UploadGrants, UserNotificationPreferences,
 };
 use frame_support::{
     assert_noop, assert_ok,
@@ -20,22 +28,25 @@ use frame_support::{
         nonfungibles_v2::Destroy,
         Hooks, OriginTrait,
     },
-    weights::Weight,
+    weights::{Weight, WeightMeter},
 };
 use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_file_system_runtime_api::{
+    EstimateStorageRequestCostError, OpenStorageRequest, StorageRequestCostEstimate,
+};
 use pallet_proofs_dealer::{PriorityChallengesQueue, ProviderToProofSubmissionRecord};
 use pallet_storage_providers::types::{Bucket, StorageProviderId, ValueProposition};
 use shp_traits::{
-    MutateBucketsInterface, MutateStorageProvidersInterface, PaymentStreamsInterface,
-    ReadBucketsInterface, ReadProvidersInterface, ReadStorageProvidersInterface,
-    TrieRemoveMutation,
+    MutateBucketsInterface, MutateStorageProvidersInterface, OnProviderStatusChange,
+    PaymentStreamsInterface, ProviderStatusChange, ReadBucketsInterface, ReadProvidersInterface,
+    ReadStorageProvidersInterface, TrieRemoveMutation,
 };
 use sp_core::{ByteArray, Hasher, H256};
 use sp_keyring::sr25519::Keyring;
 use sp_runtime::{
     bounded_vec,
     traits::{BlakeTwo256, Get, Zero},
-    BoundedVec, DispatchError,
+    BoundedVec, DispatchError, DispatchResult, Perbill,
 };
 use sp_trie::CompactProof;
 
@@ -61,7 +72,8 @@ mod create_bucket_tests {
                         Some(H256::from_slice(&msp.as_slice())),
                         name,
                         true,
-                        Some(ValuePropId::<Test>::default())
+                        Some(ValuePropId::<Test>::default()),
+                        None
                     ),
                     Error::<Test>::NotAMsp
                 );
@@ -85,7 +97,8 @@ mod create_bucket_tests {
                         Some(msp_id),
                         name.clone(),
                         private,
-                        Some(value_prop_id)
+                        Some(value_prop_id),
+                        None
                     ),
                     pallet_storage_providers::Error::<Test>::NotEnoughBalance
                 );
@@ -116,12 +129,56 @@ mod create_bucket_tests {
                         Some(msp_id),
                         name.clone(),
                         private,
-                        Some(value_prop_id)
+                        Some(value_prop_id),
+                        None
                     ),
                     Error::<Test>::OperationNotAllowedForInsolventProvider
                 );
             });
         }
+
+        #[test]
+        fn create_public_bucket_fails_with_suspended_or_exiting_provider() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let private = false;
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                // An MSP that scheduled its sign off can't take on new buckets
+                assert_ok!(Providers::schedule_sign_off(RuntimeOrigin::signed(
+                    msp.clone()
+                )));
+                assert_noop!(
+                    FileSystem::create_bucket(
+                        origin.clone(),
+                        Some(msp_id),
+                        name.clone(),
+                        private,
+                        Some(value_prop_id),
+                        None
+                    ),
+                    Error::<Test>::OperationNotAllowedForExitingProvider
+                );
+
+                // Neither can a suspended one
+                assert_ok!(Providers::suspend_provider(RuntimeOrigin::root(), msp_id));
+                assert_noop!(
+                    FileSystem::create_bucket(
+                        origin,
+                        Some(msp_id),
+                        name.clone(),
+                        private,
+                        Some(value_prop_id),
+                        None
+                    ),
+                    Error::<Test>::OperationNotAllowedForSuspendedProvider
+                );
+            });
+        }
     }
 
     mod success {
@@ -154,7 +211,7 @@ mod create_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id), None
                 ));
 
                 // Check if collection was created
@@ -226,7 +283,7 @@ mod create_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id), None
                 ));
 
                 // Check that the bucket does not have a corresponding collection
@@ -308,7 +365,8 @@ mod delete_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id),
+                    None
                 ));
 
                 assert_noop!(
@@ -340,7 +398,8 @@ mod delete_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id),
+                    None
                 ));
 
                 // Dispatch a signed extrinsic of a storage request.
@@ -352,7 +411,8 @@ mod delete_bucket_tests {
                     4,
                     Some(msp_id),
                     BoundedVec::try_from(vec![BoundedVec::try_from(vec![1]).unwrap()]).unwrap(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Accept the storage request to store the file, so the bucket is not empty.
@@ -420,7 +480,8 @@ mod delete_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id),
+                    None
                 ));
 
                 // Dispatch a signed extrinsic of a storage request.
@@ -432,7 +493,8 @@ mod delete_bucket_tests {
                     4,
                     Some(msp_id),
                     BoundedVec::try_from(vec![BoundedVec::try_from(vec![1]).unwrap()]).unwrap(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Simulate insolvent provider
@@ -498,7 +560,8 @@ mod delete_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id),
+                    None
                 ));
 
                 // Dispatch a signed extrinsic.
@@ -545,7 +608,8 @@ mod delete_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id),
+                    None
                 ));
 
                 // Get the bucket's collection ID.
@@ -603,7 +667,7 @@ mod delete_bucket_tests {
 					Some(msp_id),
 					name.clone(),
 					private,
-					Some(value_prop_id)
+					Some(value_prop_id), None
 				));
 
 				// Dispatch a signed extrinsic of a storage request.
@@ -615,7 +679,8 @@ mod delete_bucket_tests {
 					4,
 					Some(msp_id),
 					BoundedVec::try_from(vec![BoundedVec::try_from(vec![1]).unwrap()]).unwrap(),
-                    None
+                    None,
+                    false
 				));
 
 				// Accept the storage request to store the file, so the bucket is not empty.
@@ -732,7 +797,8 @@ mod request_move_bucket {
                     4,
                     Some(msp_charlie_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 assert_noop!(
@@ -875,7 +941,8 @@ mod request_move_bucket {
                     size,
                     Some(msp_charlie_id),
                     peer_ids.clone(),
-                    Some(1)
+                    Some(1),
+                    false
                 ));
 
                 // Compute the file key.
@@ -1312,7 +1379,7 @@ mod update_bucket_privacy_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id), None
                 ));
 
                 // Check if collection was created
@@ -1386,7 +1453,7 @@ mod update_bucket_privacy_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id), None
                 ));
 
                 // Check if collection was created
@@ -1487,7 +1554,7 @@ mod update_bucket_privacy_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id), None
                 ));
 
                 // Check that the bucket does not have a corresponding collection
@@ -1623,7 +1690,8 @@ mod create_and_associate_collection_with_bucket_tests {
                     Some(msp_id),
                     name.clone(),
                     private,
-                    Some(value_prop_id)
+                    Some(value_prop_id),
+                    None
                 ));
 
                 // Check if collection was created
@@ -1701,7 +1769,8 @@ mod request_storage {
                         4,
                         Some(msp_id),
                         peer_ids.clone(),
-                        None
+                        None,
+                        false
                     ),
                     pallet_storage_providers::Error::<Test>::BucketNotFound
                 );
@@ -1735,7 +1804,8 @@ mod request_storage {
                         4,
                         Some(msp_id),
                         peer_ids.clone(),
-                        None
+                        None,
+                        false
                     ),
                     Error::<Test>::NotBucketOwner
                 );
@@ -1792,7 +1862,8 @@ mod request_storage {
                         4,
                         Some(msp_charlie_id),
                         peer_ids.clone(),
-                        None
+                        None,
+                        false
                     ),
                     Error::<Test>::BucketIsBeingMoved
                 );
@@ -1849,7 +1920,8 @@ mod request_storage {
                         size,
                         Some(msp_id),
                         peer_ids.clone(),
-                        None
+                        None,
+                        false
                     ),
                     Error::<Test>::CannotHoldDeposit
                 );
@@ -1868,10 +1940,10 @@ mod request_storage {
         }
 
         #[test]
-        fn request_storage_replication_target_cannot_be_zero() {
+        fn request_storage_msp_only_without_msp_fails() {
             new_test_ext().execute_with(|| {
-                let owner_without_funds = Keyring::Alice.to_account_id();
-                let user = RuntimeOrigin::signed(owner_without_funds.clone());
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let user = RuntimeOrigin::signed(owner_account_id.clone());
                 let msp = Keyring::Charlie.to_account_id();
                 let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
                 let size = 4;
@@ -1883,14 +1955,10 @@ mod request_storage {
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
 
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(
-                    &owner_without_funds.clone(),
-                    name.clone(),
-                    msp_id,
-                    value_prop_id,
-                );
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
-                // Dispatch a signed extrinsic.
+                // A replication target of zero means the file is only stored by the MSP, so one is required.
                 assert_noop!(
                     FileSystem::issue_storage_request(
                         user.clone(),
@@ -1898,11 +1966,12 @@ mod request_storage {
                         location.clone(),
                         fingerprint,
                         size,
-                        Some(msp_id),
+                        None,
                         peer_ids.clone(),
-                        Some(0)
+                        Some(0),
+                        false
                     ),
-                    Error::<Test>::ReplicationTargetCannotBeZero
+                    Error::<Test>::MspOnlyStorageRequestWithoutMsp
                 );
             });
         }
@@ -1940,7 +2009,8 @@ mod request_storage {
                         size,
                         Some(msp_id),
                         peer_ids.clone(),
-                        Some(MaxReplicationTarget::<Test>::get() + 1)
+                        Some(MaxReplicationTarget::<Test>::get() + 1),
+                        false
                     ),
                     Error::<Test>::ReplicationTargetExceedsMaximum
                 );
@@ -1986,7 +2056,8 @@ mod request_storage {
                         size,
                         Some(msp_id),
                         peer_ids.clone(),
-                        None
+                        None,
+                        false
                     ),
                     Error::<Test>::OperationNotAllowedForInsolventProvider
                 );
@@ -2034,7 +2105,8 @@ mod request_storage {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -2126,7 +2198,8 @@ mod request_storage {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    Some(MaxReplicationTarget::<Test>::get())
+                    Some(MaxReplicationTarget::<Test>::get()),
+                    false
                 ),);
             });
         }
@@ -2164,7 +2237,8 @@ mod request_storage {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -2202,7 +2276,8 @@ mod request_storage {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -2280,7 +2355,8 @@ mod request_storage {
                         size,
                         Some(msp_id),
                         peer_ids.clone(),
-                        None
+                        None,
+                        false
                     ),
                     Error::<Test>::FileSizeCannotBeZero
                 );
@@ -2319,7 +2395,8 @@ mod request_storage {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -2435,7 +2512,8 @@ mod request_storage {
                     4,
                     Some(msp_id),
                     peer_ids,
-                    None
+                    None,
+                    false
                 ));
 
                 // Assert that the storage request expirations storage is at max capacity
@@ -2514,7 +2592,8 @@ mod request_storage {
                     4,
                     Some(msp_id),
                     peer_ids,
-                    None
+                    None,
+                    false
                 ));
 
                 let expected_expiration_block_number: u32 = StorageRequestTtl::<Test>::get();
@@ -2565,6 +2644,168 @@ mod request_storage {
     }
 }
 
+mod storage_request_expiring_soon {
+    use super::*;
+
+    mod success {
+        use super::*;
+
+        /// Issues a storage request and returns its file key.
+        fn issue_storage_request() -> H256 {
+            let owner_account_id = Keyring::Alice.to_account_id();
+            let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+            let msp = Keyring::Charlie.to_account_id();
+            let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+            let fingerprint = BlakeTwo256::hash(b"test");
+            let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+            let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+            let size = 4;
+
+            let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+            let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+            let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+            assert_ok!(FileSystem::issue_storage_request(
+                owner_signed,
+                bucket_id,
+                location.clone(),
+                fingerprint,
+                size,
+                Some(msp_id),
+                peer_ids,
+                None,
+                false
+            ));
+
+            FileSystem::compute_file_key(owner_account_id, bucket_id, location, size, fingerprint)
+        }
+
+        fn poll(block: BlockNumberFor<Test>) {
+            FileSystem::on_poll(block, &mut WeightMeter::new());
+        }
+
+        #[test]
+        fn storage_request_expiring_soon_is_notified_once_lead_time_before_expiration() {
+            new_test_ext().execute_with(|| {
+                let file_key = issue_storage_request();
+
+                let storage_request_ttl: u32 = StorageRequestTtl::<Test>::get();
+                let lead_time: u32 =
+                    <Test as Config>::StorageRequestExpirationNotificationLeadTime::get();
+                let expiration_block = System::block_number() + storage_request_ttl as u64;
+                let notification_block = expiration_block - lead_time as u64;
+
+                let expiring_soon_event =
+                    RuntimeEvent::FileSystem(Event::StorageRequestExpiringSoon {
+                        file_key,
+                        expires_at: expiration_block,
+                    });
+
+                // Not notified before the lead time.
+                poll(notification_block - 1);
+                assert!(!System::events()
+                    .iter()
+                    .any(|record| record.event == expiring_soon_event));
+
+                // Notified once the expiration block is within the lead time.
+                poll(notification_block);
+                System::assert_last_event(expiring_soon_event.clone());
+
+                // Not notified again.
+                poll(notification_block + 1);
+                assert_eq!(
+                    System::events()
+                        .iter()
+                        .filter(|record| record.event == expiring_soon_event)
+                        .count(),
+                    1
+                );
+                assert_eq!(
+                    file_system::NextBlockToNotifyExpiringStorageRequests::<Test>::get(),
+                    expiration_block + 2
+                );
+            });
+        }
+
+        #[test]
+        fn revoked_storage_request_is_not_notified() {
+            new_test_ext().execute_with(|| {
+                let file_key = issue_storage_request();
+
+                assert_ok!(FileSystem::revoke_storage_request(
+                    RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                    file_key
+                ));
+
+                let storage_request_ttl: u32 = StorageRequestTtl::<Test>::get();
+                poll(System::block_number() + storage_request_ttl as u64);
+
+                assert!(!System::events().iter().any(|record| matches!(
+                    record.event,
+                    RuntimeEvent::FileSystem(Event::StorageRequestExpiringSoon { .. })
+                )));
+            });
+        }
+
+        #[test]
+        fn storage_requests_expiring_soon_are_notified_with_bounded_work_per_block() {
+            new_test_ext().execute_with(|| {
+                let storage_request_ttl: u32 = StorageRequestTtl::<Test>::get();
+                let lead_time: u32 =
+                    <Test as Config>::StorageRequestExpirationNotificationLeadTime::get();
+                let max_notifications: u32 =
+                    <Test as Config>::MaxStorageRequestExpirationNotificationsPerBlock::get();
+
+                // Fill the storage request's expiration block, and add it to the next one as well,
+                // so that there are more expirations within the lead time than can be notified in a
+                // single block.
+                let file_key = issue_storage_request();
+                let first_expiration_block = System::block_number() + storage_request_ttl as u64;
+                let second_expiration_block = first_expiration_block + 1;
+                StorageRequestExpirations::<Test>::mutate(first_expiration_block, |expirations| {
+                    while expirations.try_push(file_key).is_ok() {}
+                });
+                StorageRequestExpirations::<Test>::mutate(second_expiration_block, |expirations| {
+                    expirations.try_push(file_key).unwrap();
+                });
+                let first_block_expirations =
+                    StorageRequestExpirations::<Test>::get(first_expiration_block).len() as u32;
+                assert!(first_block_expirations >= max_notifications);
+
+                let count_notifications = || {
+                    System::events()
+                        .iter()
+                        .filter(|record| {
+                            matches!(
+                                record.event,
+                                RuntimeEvent::FileSystem(Event::StorageRequestExpiringSoon { .. })
+                            )
+                        })
+                        .count() as u32
+                };
+
+                // Polling a block with both expiration blocks within the lead time stops after the first one,
+                // since it already reaches the maximum number of notifications.
+                let poll_block = second_expiration_block - lead_time as u64;
+                poll(poll_block);
+                assert_eq!(count_notifications(), first_block_expirations);
+
+                // The rest is notified in the next block.
+                poll(poll_block + 1);
+                assert_eq!(count_notifications(), first_block_expirations + 1);
+                System::assert_last_event(
+                    Event::StorageRequestExpiringSoon {
+                        file_key,
+                        expires_at: second_expiration_block,
+                    }
+                    .into(),
+                );
+            });
+        }
+    }
+}
+
 mod revoke_storage_request {
     use super::*;
     mod failure {
@@ -2613,7 +2854,8 @@ mod revoke_storage_request {
                     4,
                     Some(msp_id),
                     Default::default(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -2664,7 +2906,8 @@ mod revoke_storage_request {
                     4,
                     Some(msp_id),
                     Default::default(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -2717,7 +2960,8 @@ mod revoke_storage_request {
                     4,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let bsp_account_id = Keyring::Bob.to_account_id();
@@ -2784,7 +3028,8 @@ mod revoke_storage_request {
                     4,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let bsp_account_id = Keyring::Bob.to_account_id();
@@ -2830,89 +3075,388 @@ mod revoke_storage_request {
                 System::assert_last_event(Event::StorageRequestRevoked { file_key }.into());
             });
         }
-    }
-}
-
-mod msp_respond_storage_request {
-    use super::*;
-
-    mod success {
-        use super::*;
 
         #[test]
-        fn msp_respond_storage_request_works() {
+        fn revoke_storage_request_refunds_whole_deposit_if_no_bsp_confirmed() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let owner_account = Keyring::Alice.to_account_id();
+                let owner = RuntimeOrigin::signed(owner_account.clone());
                 let msp = Keyring::Charlie.to_account_id();
                 let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let fingerprint = H256::zero();
+                let fingerprint = BlakeTwo256::hash(&b"test".to_vec());
                 let peer_id = BoundedVec::try_from(vec![1]).unwrap();
                 let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
 
-                // Register the MSP.
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
 
-                // Create the bucket that will hold the file.
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id =
-                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                    create_bucket(&owner_account.clone(), name.clone(), msp_id, value_prop_id);
+
+                let owner_balance_before = Balances::free_balance(&owner_account);
 
-                // Dispatch a storage request.
                 assert_ok!(FileSystem::issue_storage_request(
-                    owner_signed.clone(),
+                    owner.clone(),
                     bucket_id,
                     location.clone(),
                     fingerprint,
-                    size,
+                    4,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
-                // Compute the file key.
-                // Compute the file key.
+                let bsp_account_id = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), 100));
+
                 let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
+                    owner_account.clone(),
                     bucket_id,
                     location.clone(),
-                    size,
+                    4,
                     fingerprint,
                 );
 
-                // Dispatch the MSP accept request.
-                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
-                    RuntimeOrigin::signed(msp.clone()),
-                    bounded_vec![StorageRequestMspBucketResponse {
-                        bucket_id,
-                        accept: Some(StorageRequestMspAcceptedFileKeys {
-                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
-                                file_key,
-                                proof: CompactProof {
-                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                                }
-                            }],
-                            non_inclusion_forest_proof: CompactProof {
-                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                            },
-                        }),
-                        reject: bounded_vec![],
-                    }],
-                ));
+                // A BSP that only volunteered is not compensated.
+                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key));
+                let bsp_balance_before = Balances::free_balance(&bsp_account_id);
 
-                // Assert that the storage was updated
+                assert_ok!(FileSystem::revoke_storage_request(owner.clone(), file_key));
+
+                assert_eq!(Balances::free_balance(&owner_account), owner_balance_before);
+                assert_eq!(Balances::free_balance(&bsp_account_id), bsp_balance_before);
                 assert_eq!(
-                    file_system::StorageRequests::<Test>::get(file_key)
-                        .unwrap()
-                        .msp,
-                    Some((msp_id, true))
+                    <Test as Config>::Currency::balance_on_hold(
+                        &RuntimeHoldReason::FileSystem(
+                            file_system::HoldReason::StorageRequestCreationHold
+                        ),
+                        &owner_account
+                    ),
+                    0
                 );
             });
         }
 
         #[test]
-        fn msp_respond_storage_request_works_multiple_times_for_same_user_same_bucket() {
+        fn revoke_storage_request_compensates_confirmed_bsps_pro_rata_to_elapsed_ticks() {
+            new_test_ext().execute_with(|| {
+                let compensation_period: u32 =
+                    <Test as Config>::StorageRequestRevocationCompensationPeriod::get();
+
+                // Halfway through the compensation period, the BSPs get half of the full share.
+                revoke_confirmed_storage_request_after(
+                    (compensation_period / 2).into(),
+                    Perbill::from_rational(compensation_period / 2, compensation_period),
+                );
+            });
+        }
+
+        #[test]
+        fn revoke_storage_request_compensates_confirmed_bsps_with_full_share_after_period() {
+            new_test_ext().execute_with(|| {
+                let compensation_period: u32 =
+                    <Test as Config>::StorageRequestRevocationCompensationPeriod::get();
+
+                // Past the compensation period, the BSPs get the full share.
+                revoke_confirmed_storage_request_after(
+                    (compensation_period + 5).into(),
+                    Perbill::one(),
+                );
+            });
+        }
+
+        /// Issues a storage request confirmed by one BSP, revokes it `elapsed_ticks` after issuing it, and checks
+        /// that the BSP gets `elapsed_share` of the full compensation.
+        fn revoke_confirmed_storage_request_after(elapsed_ticks: u64, elapsed_share: Perbill) {
+            let owner_account = Keyring::Alice.to_account_id();
+            let owner = RuntimeOrigin::signed(owner_account.clone());
+            let msp = Keyring::Charlie.to_account_id();
+            let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+            let fingerprint = BlakeTwo256::hash(&b"test".to_vec());
+            let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+            let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+            let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+            let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+            let bucket_id =
+                create_bucket(&owner_account.clone(), name.clone(), msp_id, value_prop_id);
+
+            assert_ok!(FileSystem::issue_storage_request(
+                owner.clone(),
+                bucket_id,
+                location.clone(),
+                fingerprint,
+                4,
+                Some(msp_id),
+                peer_ids.clone(),
+                None,
+                false
+            ));
+
+            let bsp_account_id = Keyring::Bob.to_account_id();
+            let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+            assert_ok!(bsp_sign_up(bsp_signed.clone(), 100));
+            let bsp_id =
+                <<Test as file_system::Config>::Providers as ReadProvidersInterface>::get_provider_id(
+                    bsp_account_id.clone(),
+                )
+                .unwrap();
+
+            let file_key = FileSystem::compute_file_key(
+                owner_account.clone(),
+                bucket_id,
+                location.clone(),
+                4,
+                fingerprint,
+            );
+
+            assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key));
+            assert_ok!(FileSystem::bsp_confirm_storing(
+                bsp_signed.clone(),
+                CompactProof {
+                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                },
+                BoundedVec::try_from(vec![(
+                    file_key,
+                    CompactProof {
+                        encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                    }
+                )])
+                .unwrap(),
+            ));
+
+            let storage_request = StorageRequests::<Test>::get(file_key).unwrap();
+            assert_eq!(storage_request.bsps_confirmed, 1);
+
+            // Advance until `elapsed_ticks` have passed since the storage request was issued.
+            roll_to(
+                System::block_number() + storage_request.requested_at + elapsed_ticks
+                    - ProofsDealer::get_current_tick(),
+            );
+            assert_eq!(
+                ProofsDealer::get_current_tick() - storage_request.requested_at,
+                elapsed_ticks
+            );
+
+            // The BSP gets its share of the compensation, scaled by the ticks elapsed and by the ratio of confirmed
+            // to required BSPs.
+            let deposit: u128 = StorageRequestCreationDeposit::get();
+            let expected_compensation = Perbill::from_rational(1, storage_request.bsps_required)
+                .mul_floor(
+                    elapsed_share
+                        .mul_floor(StorageRequestRevocationCompensation::get().mul_floor(deposit)),
+                );
+            assert!(expected_compensation > 0);
+
+            let owner_balance_before = Balances::free_balance(&owner_account);
+            let bsp_balance_before = Balances::free_balance(&bsp_account_id);
+
+            assert_ok!(FileSystem::revoke_storage_request(owner.clone(), file_key));
+
+            System::assert_has_event(
+                Event::BspCompensatedForRevokedStorageRequest {
+                    file_key,
+                    bsp_id,
+                    amount: expected_compensation,
+                }
+                .into(),
+            );
+            assert_eq!(
+                Balances::free_balance(&bsp_account_id),
+                bsp_balance_before + expected_compensation
+            );
+
+            // The owner gets the rest of the deposit back.
+            assert_eq!(
+                Balances::free_balance(&owner_account),
+                owner_balance_before + deposit - expected_compensation
+            );
+            assert_eq!(
+                <Test as Config>::Currency::balance_on_hold(
+                    &RuntimeHoldReason::FileSystem(
+                        file_system::HoldReason::StorageRequestCreationHold
+                    ),
+                    &owner_account
+                ),
+                0
+            );
+        }
+    }
+}
+
+mod msp_respond_storage_request {
+    use super::*;
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn msp_respond_storage_request_works() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+                // Register the MSP.
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                // Create the bucket that will hold the file.
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Dispatch a storage request.
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    None,
+                    false
+                ));
+
+                // Compute the file key.
+                // Compute the file key.
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Dispatch the MSP accept request.
+                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
+                    RuntimeOrigin::signed(msp.clone()),
+                    bounded_vec![StorageRequestMspBucketResponse {
+                        bucket_id,
+                        accept: Some(StorageRequestMspAcceptedFileKeys {
+                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
+                                file_key,
+                                proof: CompactProof {
+                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                                }
+                            }],
+                            non_inclusion_forest_proof: CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            },
+                        }),
+                        reject: bounded_vec![],
+                    }],
+                ));
+
+                // Assert that the storage was updated
+                assert_eq!(
+                    file_system::StorageRequests::<Test>::get(file_key)
+                        .unwrap()
+                        .msp,
+                    Some((msp_id, true))
+                );
+            });
+        }
+
+        #[test]
+        fn msp_respond_msp_only_storage_request_fulfills_it() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let deposit_hold_reason = RuntimeHoldReason::FileSystem(
+                    file_system::HoldReason::StorageRequestCreationHold,
+                );
+
+                // Register the MSP.
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                // Create the bucket that will hold the file.
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Dispatch an MSP-only storage request.
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    Some(0),
+                    false
+                ));
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // The lower durability is made explicit and the MSP-only deposit is held.
+                System::assert_has_event(
+                    Event::NewMspOnlyStorageRequest { file_key, msp_id }.into(),
+                );
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &deposit_hold_reason,
+                        &owner_account_id
+                    ),
+                    <Test as Config>::MspOnlyStorageRequestCreationDeposit::get()
+                );
+
+                // Dispatch the MSP accept request.
+                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
+                    RuntimeOrigin::signed(msp.clone()),
+                    bounded_vec![StorageRequestMspBucketResponse {
+                        bucket_id,
+                        accept: Some(StorageRequestMspAcceptedFileKeys {
+                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
+                                file_key,
+                                proof: CompactProof {
+                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                                }
+                            }],
+                            non_inclusion_forest_proof: CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            },
+                        }),
+                        reject: bounded_vec![],
+                    }],
+                ));
+
+                // The storage request is fulfilled without any BSP and the deposit is returned.
+                System::assert_has_event(Event::StorageRequestFulfilled { file_key }.into());
+                assert!(file_system::StorageRequests::<Test>::get(file_key).is_none());
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &deposit_hold_reason,
+                        &owner_account_id
+                    ),
+                    0
+                );
+            });
+        }
+
+        #[test]
+        fn msp_respond_storage_request_works_multiple_times_for_same_user_same_bucket() {
             new_test_ext().execute_with(|| {
                 let owner_account_id = Keyring::Alice.to_account_id();
                 let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
@@ -2961,7 +3505,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Dispatch a storage request for the second file.
@@ -2973,7 +3518,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Dispatch the MSP accept request for the first file.
@@ -3088,7 +3634,8 @@ mod msp_respond_storage_request {
                     first_size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Dispatch a storage request for the second file.
@@ -3100,7 +3647,8 @@ mod msp_respond_storage_request {
                     second_size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Dispatch the MSP accept request for the second file.
@@ -3235,7 +3783,8 @@ mod msp_respond_storage_request {
                     first_size,
                     Some(msp_id),
                     first_peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Dispatch a storage request for the second file.
@@ -3247,7 +3796,8 @@ mod msp_respond_storage_request {
                     second_size,
                     Some(msp_id),
                     second_peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Dispatch the MSP accept request for the second file.
@@ -3340,7 +3890,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    Some(1)
+                    Some(1),
+                    false
                 ));
 
                 // Compute the file key.
@@ -3478,7 +4029,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -3546,7 +4098,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -3760,7 +4313,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(expected_msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -3824,7 +4378,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -4052,7 +4607,8 @@ mod msp_respond_storage_request {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -4125,7 +4681,8 @@ mod bsp_volunteer {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -4144,24 +4701,78 @@ mod bsp_volunteer {
         }
 
         #[test]
-        fn bsp_volunteer_storage_request_not_found_fail() {
+        fn bsp_volunteer_msp_only_storage_request_fail() {
             new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
                 let bsp_account_id = Keyring::Bob.to_account_id();
                 let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
                 let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
                 let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(vec![1]).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
                 assert_ok!(bsp_sign_up(bsp_signed.clone(), 100,));
 
+                // Dispatch an MSP-only storage request.
+                assert_ok!(FileSystem::issue_storage_request(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    Some(0),
+                    false
+                ));
+
                 let file_key = FileSystem::compute_file_key(
-                    bsp_account_id.clone(),
-                    H256::zero(),
+                    owner_account_id.clone(),
+                    bucket_id,
                     location.clone(),
-                    4,
+                    size,
                     fingerprint,
                 );
 
-                assert_noop!(
+                assert_eq!(
+                    FileSystem::is_storage_request_open_to_volunteers(file_key),
+                    Ok(false)
+                );
+
+                assert_noop!(
+                    FileSystem::bsp_volunteer(bsp_signed.clone(), file_key),
+                    Error::<Test>::StorageRequestIsMspOnly
+                );
+            });
+        }
+
+        #[test]
+        fn bsp_volunteer_storage_request_not_found_fail() {
+            new_test_ext().execute_with(|| {
+                let bsp_account_id = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let fingerprint = H256::zero();
+
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), 100,));
+
+                let file_key = FileSystem::compute_file_key(
+                    bsp_account_id.clone(),
+                    H256::zero(),
+                    location.clone(),
+                    4,
+                    fingerprint,
+                );
+
+                assert_noop!(
                     FileSystem::bsp_volunteer(bsp_signed.clone(), file_key),
                     Error::<Test>::StorageRequestNotFound
                 );
@@ -4199,7 +4810,8 @@ mod bsp_volunteer {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -4254,7 +4866,8 @@ mod bsp_volunteer {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -4314,7 +4927,8 @@ mod bsp_volunteer {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -4380,7 +4994,8 @@ mod bsp_volunteer {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Compute the file key to volunteer for.
@@ -4446,7 +5061,8 @@ mod bsp_volunteer {
                     4,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -4510,7 +5126,8 @@ mod bsp_volunteer {
                     4,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -4592,7 +5209,8 @@ mod bsp_volunteer {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Compute the file key to volunteer for.
@@ -4689,7 +5307,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -4787,7 +5406,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -4853,7 +5473,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -4996,7 +5617,8 @@ mod bsp_confirm {
                         size,
                         Some(msp_id),
                         Default::default(),
-                        None
+                        None,
+                        false
                     ));
 
                     let file_key = FileSystem::compute_file_key(
@@ -5111,7 +5733,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    Some(1)
+                    Some(1),
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -5180,7 +5803,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -5261,7 +5885,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -5452,7 +6077,8 @@ mod bsp_confirm {
                             size,
                             Some(msp_id),
                             peer_ids.clone(),
-                            Some(1)
+                            Some(1),
+                            false
                         ));
 
                         let file_key = FileSystem::compute_file_key(
@@ -5557,7 +6183,8 @@ mod bsp_confirm {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -5686,7 +6313,8 @@ mod bsp_confirm {
                     new_size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -5771,6 +6399,93 @@ mod bsp_confirm {
                 );
             });
         }
+
+        #[test]
+        fn bsp_confirm_storing_bills_small_file_as_min_billable_size() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let bsp_account_id = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let storage_amount: StorageData<Test> = 100;
+
+                let min_billable_file_size = 16;
+                MinBillableFileSize::set(&min_billable_file_size);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Dispatch storage request.
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    None,
+                    false
+                ));
+
+                // Sign up account as a Backup Storage Provider
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), storage_amount));
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let bsp_id = Providers::get_provider_id(bsp_account_id.clone()).unwrap();
+
+                // Dispatch BSP volunteer.
+                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key,));
+
+                // Dispatch BSP confirm storing.
+                assert_ok!(FileSystem::bsp_confirm_storing(
+                    bsp_signed.clone(),
+                    CompactProof {
+                        encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                    },
+                    BoundedVec::try_from(vec![(
+                        file_key,
+                        CompactProof {
+                            encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                        }
+                    )])
+                    .unwrap(),
+                ));
+
+                // The BSP is paid for the minimum billable size, but only uses the actual size of the file.
+                assert_eq!(
+                    PaymentStreams::get_dynamic_rate_payment_stream_amount_provided(
+                        &bsp_id,
+                        &owner_account_id,
+                    ),
+                    Some(min_billable_file_size)
+                );
+                assert_eq!(
+                    <Providers as ReadStorageProvidersInterface>::get_used_capacity(&bsp_id),
+                    size
+                );
+                assert_eq!(
+                    FileSystem::query_billable_size(size),
+                    min_billable_file_size
+                );
+            });
+        }
     }
 }
 
@@ -5807,7 +6522,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -5868,7 +6584,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -5988,7 +6705,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -6111,7 +6829,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -6249,7 +6968,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -6420,7 +7140,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -6578,7 +7299,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -6761,68 +7483,196 @@ mod bsp_stop_storing {
         }
 
         #[test]
-        fn bsp_confirm_stop_storing_success_and_deletes_payment_stream_for_last_file() {
+        fn bsp_confirm_stop_storing_removes_billed_size_after_min_billable_size_changes() {
             new_test_ext().execute_with(|| {
                 let owner_account_id = Keyring::Alice.to_account_id();
                 let owner = RuntimeOrigin::signed(owner_account_id.clone());
                 let bsp_account_id = Keyring::Bob.to_account_id();
                 let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
                 let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
                 let size = 4;
                 let fingerprint = H256::zero();
                 let peer_id = BoundedVec::try_from(vec![1]).unwrap();
                 let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
                 let storage_amount: StorageData<Test> = 100;
 
+                let min_billable_file_size = 16;
+                MinBillableFileSize::set(&min_billable_file_size);
+
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
 
                 let name = BoundedVec::try_from(vec![1]).unwrap();
                 let bucket_id =
                     create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
-                // Dispatch storage request.
-                assert_ok!(FileSystem::issue_storage_request(
-                    owner.clone(),
-                    bucket_id,
-                    location.clone(),
-                    fingerprint,
-                    size,
-                    Some(msp_id),
-                    peer_ids.clone(),
-					None
-                ));
-
                 // Sign up account as a Backup Storage Provider
                 assert_ok!(bsp_sign_up(bsp_signed.clone(), storage_amount));
+                let bsp_id = Providers::get_provider_id(bsp_account_id.clone()).unwrap();
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
+                // The BSP confirms storing two small files, each billed as the minimum billable size.
+                let mut file_keys = Vec::new();
+                for location in [b"test".to_vec(), b"test2".to_vec()] {
+                    let location = FileLocation::<Test>::try_from(location).unwrap();
+                    assert_ok!(FileSystem::issue_storage_request(
+                        owner.clone(),
+                        bucket_id,
+                        location.clone(),
+                        fingerprint,
+                        size,
+                        Some(msp_id),
+                        peer_ids.clone(),
+                        None,
+                        false
+                    ));
+                    let file_key = FileSystem::compute_file_key(
+                        owner_account_id.clone(),
+                        bucket_id,
+                        location.clone(),
+                        size,
+                        fingerprint,
+                    );
+                    assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key));
+                    assert_ok!(FileSystem::bsp_confirm_storing(
+                        bsp_signed.clone(),
+                        CompactProof {
+                            encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                        },
+                        BoundedVec::try_from(vec![(
+                            file_key,
+                            CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            }
+                        )])
+                        .unwrap(),
+                    ));
+                    assert_eq!(
+                        BspBilledFileSizes::<Test>::get(&bsp_id, &file_key),
+                        Some(min_billable_file_size)
+                    );
+                    file_keys.push((file_key, location));
+                }
+                assert_eq!(
+                    PaymentStreams::get_dynamic_rate_payment_stream_amount_provided(
+                        &bsp_id,
+                        &owner_account_id,
+                    ),
+                    Some(2 * min_billable_file_size)
                 );
 
-                let bsp_id = Providers::get_provider_id(bsp_account_id).unwrap();
-
-                // Check that the dynamic-rate payment stream between the user and the provider doesn't exist
-                assert!(<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_info(
-					&bsp_id,
-					&owner_account_id
-				).is_none());
-
-                // Dispatch BSP volunteer.
-                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key,));
+                // Governance lowers the minimum billable size before the BSP stops storing a file.
+                MinBillableFileSize::set(&0);
 
-                // Dispatch BSP confirm storing.
-                assert_ok!(FileSystem::bsp_confirm_storing(
+                let (file_key, location) = file_keys[0].clone();
+                <Test as Config>::Currency::mint_into(
+                    &bsp_account_id,
+                    <Test as Config>::BspStopStoringFilePenalty::get(),
+                )
+                .unwrap();
+                assert_ok!(FileSystem::bsp_request_stop_storing(
                     bsp_signed.clone(),
-                    CompactProof {
-                        encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                    },
-                    BoundedVec::try_from(vec![(
-                        file_key,
+                    file_key,
+                    bucket_id,
+                    location,
+                    owner_account_id.clone(),
+                    fingerprint,
+                    size,
+                    false,
+                    CompactProof {
+                        encoded_nodes: vec![file_key.as_ref().to_vec()],
+                    },
+                ));
+                roll_to(
+                    frame_system::Pallet::<Test>::block_number() + MinWaitForStopStoring::get(),
+                );
+                assert_ok!(FileSystem::bsp_confirm_stop_storing(
+                    bsp_signed.clone(),
+                    file_key,
+                    CompactProof {
+                        encoded_nodes: vec![file_key.as_ref().to_vec()],
+                    },
+                ));
+
+                // Exactly what the file was billed as is removed from the payment stream, so the other
+                // file is still billed as the minimum billable size it was confirmed with.
+                assert_eq!(
+                    PaymentStreams::get_dynamic_rate_payment_stream_amount_provided(
+                        &bsp_id,
+                        &owner_account_id,
+                    ),
+                    Some(min_billable_file_size)
+                );
+                assert!(BspBilledFileSizes::<Test>::get(&bsp_id, &file_key).is_none());
+                assert_eq!(
+                    BspBilledFileSizes::<Test>::get(&bsp_id, &file_keys[1].0),
+                    Some(min_billable_file_size)
+                );
+            });
+        }
+
+        #[test]
+        fn bsp_confirm_stop_storing_success_and_deletes_payment_stream_for_last_file() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner = RuntimeOrigin::signed(owner_account_id.clone());
+                let bsp_account_id = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let storage_amount: StorageData<Test> = 100;
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(vec![1]).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Dispatch storage request.
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+					None,
+					false
+                ));
+
+                // Sign up account as a Backup Storage Provider
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), storage_amount));
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let bsp_id = Providers::get_provider_id(bsp_account_id).unwrap();
+
+                // Check that the dynamic-rate payment stream between the user and the provider doesn't exist
+                assert!(<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_info(
+					&bsp_id,
+					&owner_account_id
+				).is_none());
+
+                // Dispatch BSP volunteer.
+                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key,));
+
+                // Dispatch BSP confirm storing.
+                assert_ok!(FileSystem::bsp_confirm_storing(
+                    bsp_signed.clone(),
+                    CompactProof {
+                        encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                    },
+                    BoundedVec::try_from(vec![(
+                        file_key,
                         CompactProof {
                             encoded_nodes: vec![H256::default().as_ref().to_vec()],
                         }
@@ -7001,7 +7851,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-					None
+					None,
+					false
                 ));
 
 				// Dispatch second storage request.
@@ -7013,7 +7864,8 @@ mod bsp_stop_storing {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-					None
+					None,
+					false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -7273,6 +8125,7 @@ mod bsp_stop_storing {
                     Some(msp_id),
                     Default::default(),
                     None,
+                    false,
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -7392,6 +8245,7 @@ mod bsp_stop_storing {
                     Some(msp_id),
                     Default::default(),
                     None,
+                    false,
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -7543,6 +8397,110 @@ mod bsp_stop_storing {
     }
 }
 
+mod issue_escrowed_storage_request_tests {
+    use super::*;
+
+    fn issue_escrowed_storage_request(
+        escrow_ticks: BlockNumberFor<Test>,
+    ) -> Result<H256, DispatchError> {
+        let owner_account_id = Keyring::Alice.to_account_id();
+        let msp = Keyring::Charlie.to_account_id();
+        let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+        let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+        let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+        let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+        let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+        let fingerprint = BlakeTwo256::hash(b"test");
+        let size = 4;
+
+        FileSystem::issue_escrowed_storage_request(
+            RuntimeOrigin::signed(owner_account_id.clone()),
+            bucket_id,
+            location.clone(),
+            fingerprint,
+            size,
+            Some(msp_id),
+            peer_ids,
+            None,
+            escrow_ticks,
+        )?;
+
+        Ok(FileSystem::compute_file_key(
+            owner_account_id,
+            bucket_id,
+            location,
+            size,
+            fingerprint,
+        ))
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn issue_escrowed_storage_request_zero_ticks_fail() {
+            new_test_ext().execute_with(|| {
+                assert_noop!(
+                    issue_escrowed_storage_request(0),
+                    pallet_payment_streams::Error::<Test>::EscrowParamsCantBeZero
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn issue_escrowed_storage_request_holds_payment_until_revoked() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                let file_key = issue_escrowed_storage_request(10).unwrap();
+                let escrow = pallet_payment_streams::PaymentEscrows::<Test>::get(&file_key)
+                    .expect("Escrow should exist after issuing an escrowed storage request");
+                assert_eq!(escrow.user_account, owner_account_id);
+                assert_eq!(
+                    escrow.providers_target,
+                    StorageRequests::<Test>::get(&file_key)
+                        .unwrap()
+                        .bsps_required
+                );
+                assert_eq!(escrow.providers_count, 0);
+                assert!(escrow.amount_held > 0);
+
+                // Revoking the storage request before any BSP confirms refunds the whole escrow.
+                assert_ok!(FileSystem::revoke_storage_request(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    file_key
+                ));
+                assert!(!pallet_payment_streams::PaymentEscrows::<Test>::contains_key(&file_key));
+                System::assert_has_event(
+                    pallet_payment_streams::Event::<Test>::EscrowClosed {
+                        escrow_id: file_key,
+                        user_account: owner_account_id.clone(),
+                        amount_refunded: escrow.amount_held,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &RuntimeHoldReason::PaymentStreams(
+                            pallet_payment_streams::HoldReason::EscrowedPayment
+                        ),
+                        &owner_account_id
+                    ),
+                    0
+                );
+            });
+        }
+    }
+}
+
 mod set_global_parameters_tests {
     use super::*;
 
@@ -7618,1011 +8576,5149 @@ mod set_global_parameters_tests {
     }
 }
 
-mod delete_file_and_pending_deletions_tests {
+mod msp_eligibility_list_tests {
     use super::*;
 
     mod failure {
-
         use super::*;
 
         #[test]
-        fn delete_file_bucket_not_owned_by_user_fail() {
+        fn msp_eligibility_list_non_root_signer_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
-
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let _ = create_bucket(
-                    &owner_account_id.clone(),
-                    name.clone(),
-                    msp_id,
-                    value_prop_id,
-                );
-
-                let other_user = Keyring::Bob.to_account_id();
-                let bucket_id = create_bucket(&other_user.clone(), name, msp_id, value_prop_id);
-
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
-                );
-
-                let forest_proof = CompactProof {
-                    encoded_nodes: vec![file_key.as_ref().to_vec()],
-                };
+                let non_root_signed = RuntimeOrigin::signed(Keyring::Bob.to_account_id());
+                let (msp_id, _) = add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
 
-                // Assert that the user does not own the bucket
                 assert_noop!(
-                    FileSystem::delete_file(
-                        owner_signed,
-                        bucket_id,
-                        file_key,
-                        location,
-                        size,
-                        fingerprint,
-                        Some(forest_proof),
+                    FileSystem::set_msp_eligibility_mode(
+                        non_root_signed.clone(),
+                        MspEligibilityListMode::Allowlist
                     ),
-                    Error::<Test>::NotBucketOwner
+                    DispatchError::BadOrigin
+                );
+                assert_noop!(
+                    FileSystem::update_msp_eligibility_list(non_root_signed, msp_id, true),
+                    DispatchError::BadOrigin
                 );
             });
         }
 
         #[test]
-        fn delete_file_beyond_maximum_limit_allowed_fail() {
+        fn create_bucket_with_denylisted_msp_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = u64::MAX;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let owner = Keyring::Alice.to_account_id();
+                let name: BucketNameFor<Test> = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let (msp_id, value_prop_id) =
+                    add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(
-                    &owner_account_id.clone(),
-                    name.clone(),
+                assert_ok!(FileSystem::update_msp_eligibility_list(
+                    RuntimeOrigin::root(),
                     msp_id,
-                    value_prop_id,
-                );
-
-                // For loop to create 1 over maximum of MaxUserPendingDeletionRequests
-                for i in 0..<Test as file_system::Config>::MaxUserPendingDeletionRequests::get() {
-                    let file_key = FileSystem::compute_file_key(
-                        owner_account_id.clone(),
-                        bucket_id,
-                        location.clone(),
-                        i as u64,
-                        fingerprint,
-                    );
-
-                    assert_ok!(FileSystem::delete_file(
-                        owner_signed.clone(),
-                        bucket_id,
-                        file_key,
-                        location.clone(),
-                        i as u64,
-                        fingerprint,
-                        None,
-                    ));
-                }
-
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
-                );
+                    true
+                ));
 
                 assert_noop!(
-                    FileSystem::delete_file(
-                        owner_signed,
-                        bucket_id,
-                        file_key,
-                        location,
-                        size,
-                        fingerprint,
+                    FileSystem::create_bucket(
+                        RuntimeOrigin::signed(owner),
+                        Some(msp_id),
+                        name,
+                        false,
+                        Some(value_prop_id),
                         None
                     ),
-                    Error::<Test>::MaxUserPendingDeletionRequestsReached
+                    Error::<Test>::MspNotEligible
                 );
             });
         }
 
         #[test]
-        fn delete_file_pending_file_deletion_request_submit_proof_not_msp_of_bucket_fail() {
+        fn create_bucket_with_msp_not_in_allowlist_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let owner = Keyring::Alice.to_account_id();
+                let name: BucketNameFor<Test> = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let (msp_id, value_prop_id) =
+                    add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                assert_ok!(FileSystem::set_msp_eligibility_mode(
+                    RuntimeOrigin::root(),
+                    MspEligibilityListMode::Allowlist
+                ));
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
+                assert_noop!(
+                    FileSystem::create_bucket(
+                        RuntimeOrigin::signed(owner),
+                        Some(msp_id),
+                        name,
+                        false,
+                        Some(value_prop_id),
+                        None
+                    ),
+                    Error::<Test>::MspNotEligible
                 );
+            });
+        }
 
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-					owner_signed.clone(),
-					bucket_id,
-					file_key,
-					location,
-					size,
-					fingerprint,
-					None,
-				));
+        #[test]
+        fn request_move_bucket_to_denylisted_msp_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let name: BucketNameFor<Test> = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let (msp_charlie_id, value_prop_id) =
+                    add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+                let (msp_dave_id, _) = add_msp_to_provider_storage(&Keyring::Dave.to_account_id());
+                let bucket_id = create_bucket(&owner, name, msp_charlie_id, value_prop_id);
 
-                // Assert that the pending file deletion request was added to storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id.clone(),
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
+                assert_ok!(FileSystem::update_msp_eligibility_list(
+                    RuntimeOrigin::root(),
+                    msp_dave_id,
+                    true
+                ));
+
+                assert_noop!(
+                    FileSystem::request_move_bucket(
+                        RuntimeOrigin::signed(owner),
+                        bucket_id,
+                        msp_dave_id
+                    ),
+                    Error::<Test>::MspNotEligible
                 );
+            });
+        }
 
-                let forest_proof = CompactProof {
-                    encoded_nodes: vec![vec![0]],
-                };
+        #[test]
+        fn issue_storage_request_to_denylisted_msp_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let name: BucketNameFor<Test> = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let (msp_id, value_prop_id) =
+                    add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
 
-                let msp_dave = Keyring::Dave.to_account_id();
-                add_msp_to_provider_storage(&msp_dave);
-                let msp_origin = RuntimeOrigin::signed(msp_dave.clone());
+                // Denylisting the MSP does not affect the buckets it already stores, but it can no
+                // longer take new storage requests.
+                assert_ok!(FileSystem::update_msp_eligibility_list(
+                    RuntimeOrigin::root(),
+                    msp_id,
+                    true
+                ));
+                assert!(Providers::is_bucket_stored_by_msp(&msp_id, &bucket_id));
 
                 assert_noop!(
-					FileSystem::pending_file_deletion_request_submit_proof(
-						msp_origin,
-						owner_account_id.clone(),
-						file_key,
-						size,
-						bucket_id,
-						forest_proof
-					),
-					Error::<Test>::MspNotStoringBucket
-				);
+                    FileSystem::issue_storage_request(
+                        RuntimeOrigin::signed(owner),
+                        bucket_id,
+                        FileLocation::<Test>::try_from(b"test".to_vec()).unwrap(),
+                        BlakeTwo256::hash(&b"test".to_vec()),
+                        4,
+                        Some(msp_id),
+                        BoundedVec::try_from(vec![BoundedVec::try_from(vec![1]).unwrap()]).unwrap(),
+                        None,
+                        false
+                    ),
+                    Error::<Test>::MspNotEligible
+                );
+            });
+        }
+    }
 
-                // Assert that the pending file deletion request was not removed from storage
+    mod success {
+        use super::*;
+
+        #[test]
+        fn update_msp_eligibility_list_emits_events() {
+            new_test_ext().execute_with(|| {
+                let (msp_id, _) = add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+
+                // The list is a denylist by default, so every MSP is eligible.
                 assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id,
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
+                    MspEligibilityMode::<Test>::get(),
+                    MspEligibilityListMode::Denylist
                 );
+                assert!(FileSystem::is_msp_eligible(&msp_id));
+
+                assert_ok!(FileSystem::update_msp_eligibility_list(
+                    RuntimeOrigin::root(),
+                    msp_id,
+                    true
+                ));
+                System::assert_last_event(Event::MspAddedToEligibilityList { msp_id }.into());
+                assert!(MspEligibilityList::<Test>::contains_key(&msp_id));
+                assert!(!FileSystem::is_msp_eligible(&msp_id));
+
+                assert_ok!(FileSystem::update_msp_eligibility_list(
+                    RuntimeOrigin::root(),
+                    msp_id,
+                    false
+                ));
+                System::assert_last_event(Event::MspRemovedFromEligibilityList { msp_id }.into());
+                assert!(!MspEligibilityList::<Test>::contains_key(&msp_id));
+                assert!(FileSystem::is_msp_eligible(&msp_id));
             });
         }
 
         #[test]
-        fn submit_proof_pending_file_deletion_not_found_fail() {
+        fn create_bucket_with_allowlisted_msp() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner = Keyring::Alice.to_account_id();
+                let name: BucketNameFor<Test> = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let (msp_id, value_prop_id) =
+                    add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
 
-                let msp = Keyring::Charlie.to_account_id();
-                let msp_origin = RuntimeOrigin::signed(msp.clone());
+                assert_ok!(FileSystem::set_msp_eligibility_mode(
+                    RuntimeOrigin::root(),
+                    MspEligibilityListMode::Allowlist
+                ));
+                System::assert_last_event(
+                    Event::MspEligibilityModeSet {
+                        mode: MspEligibilityListMode::Allowlist,
+                    }
+                    .into(),
+                );
 
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                assert_ok!(FileSystem::update_msp_eligibility_list(
+                    RuntimeOrigin::root(),
+                    msp_id,
+                    true
+                ));
 
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                create_bucket(&owner, name, msp_id, value_prop_id);
+            });
+        }
+    }
+}
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id =
-                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+mod set_bucket_default_replication_target_tests {
+    use super::*;
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
-                );
+    mod failure {
+        use super::*;
 
-                let forest_proof = CompactProof {
-                    encoded_nodes: vec![vec![0]],
-                };
+        #[test]
+        fn set_bucket_default_replication_target_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let not_owner = RuntimeOrigin::signed(Keyring::Bob.to_account_id());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
 
                 assert_noop!(
-                    FileSystem::pending_file_deletion_request_submit_proof(
-                        msp_origin,
-                        owner_account_id.clone(),
-                        file_key,
-                        size,
+                    FileSystem::set_bucket_default_replication_target(
+                        not_owner,
                         bucket_id,
-                        forest_proof
+                        Some(3)
                     ),
-                    Error::<Test>::FileKeyNotPendingDeletion
+                    Error::<Test>::NotBucketOwner
                 );
             });
         }
 
         #[test]
-        fn delete_file_expired_pending_file_deletion_request_with_bucket_does_not_exist() {
+        fn set_bucket_default_replication_target_invalid_value_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
                 let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
-                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
 
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_noop!(
+                    FileSystem::set_bucket_default_replication_target(
+                        origin.clone(),
+                        bucket_id,
+                        Some(0)
+                    ),
+                    Error::<Test>::ReplicationTargetCannotBeZero
+                );
+
+                assert_noop!(
+                    FileSystem::set_bucket_default_replication_target(
+                        origin,
+                        bucket_id,
+                        Some(MaxReplicationTarget::<Test>::get() + 1)
+                    ),
+                    Error::<Test>::ReplicationTargetExceedsMaximum
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
 
+        #[test]
+        fn set_bucket_default_replication_target_success() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                // Without a bucket default, the global default is used.
+                assert_eq!(
+                    FileSystem::query_bucket_default_replication_target(bucket_id),
+                    <Test as Config>::DefaultReplicationTarget::get()
                 );
 
-                // Issue storage request
-                assert_ok!(FileSystem::issue_storage_request(
-                    owner_signed.clone(),
+                assert_ok!(FileSystem::set_bucket_default_replication_target(
+                    origin.clone(),
                     bucket_id,
-                    location.clone(),
-                    fingerprint,
-                    size,
-                    Some(msp_id),
-                    peer_ids,
-                    None
+                    Some(5)
                 ));
 
-                // Dispatch MSP confirm storing.
-                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
-                    RuntimeOrigin::signed(msp.clone()),
-                    bounded_vec![StorageRequestMspBucketResponse {
+                System::assert_last_event(
+                    Event::BucketDefaultReplicationTargetUpdated {
+                        who: owner.clone(),
                         bucket_id,
-                        accept: Some(StorageRequestMspAcceptedFileKeys {
-                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
-                                file_key,
-                                proof: CompactProof {
-                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                                }
-                            }],
-                            non_inclusion_forest_proof: CompactProof {
-                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                            },
-                        }),
-                        reject: bounded_vec![],
-                    }],
-                ));
-
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-					owner_signed.clone(),
-					bucket_id,
-					file_key,
-					location,
-					size,
-					fingerprint,
-					None,
-				));
-
-                // Assert that the correct event was deposited
-                System::assert_last_event(
-                    Event::FileDeletionRequest {
-                        user: owner_account_id.clone(),
-                        file_key,
-                        bucket_id,
-                        msp_id: Some(msp_id),
-                        proof_of_inclusion: false,
+                        replication_target: Some(5),
                     }
-                        .into(),
-                );
-
-                // Assert that the pending file deletion request was added to storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id.clone(),
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
+                    .into(),
                 );
-
-                let pending_file_deletion_request_ttl: u32 =
-                    PendingFileDeletionRequestTtl::<Test>::get();
-                let pending_file_deletion_request_ttl: BlockNumberFor<Test> =
-                    pending_file_deletion_request_ttl.into();
-                let expiration_block = pending_file_deletion_request_ttl + System::block_number();
-
-                // Assert that the pending file deletion request was added to storage
                 assert_eq!(
-                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
-                    vec![FileDeletionRequestExpirationItem {
-                        user: owner_account_id.clone(),
-                        file_key,
-                        bucket_id,
-                        file_size: size,
-                    }]
+                    BucketDefaultReplicationTarget::<Test>::get(bucket_id),
+                    Some(5)
                 );
-
-                // Delete the bucket manually
-                pallet_storage_providers::Buckets::<Test>::remove(bucket_id);
-
-                // Roll past the expiration block
-                roll_to(pending_file_deletion_request_ttl + 1);
-
-                // Item expiration should be removed
                 assert_eq!(
-                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
-                    vec![]
+                    FileSystem::query_bucket_default_replication_target(bucket_id),
+                    5
                 );
 
-                // Assert that the pending file deletion request was removed from storage
+                // Clearing the bucket default falls back to the global default.
+                assert_ok!(FileSystem::set_bucket_default_replication_target(
+                    origin, bucket_id, None
+                ));
+                assert_eq!(BucketDefaultReplicationTarget::<Test>::get(bucket_id), None);
                 assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                    FileSystem::query_bucket_default_replication_target(bucket_id),
+                    <Test as Config>::DefaultReplicationTarget::get()
                 );
-
-                // Assert that the bucket size was not decreased because the bucket was not found
-                System::assert_has_event(Event::FailedToDecreaseBucketSize {
-                    user: owner_account_id.clone(),
-                    bucket_id,
-                    file_key,
-                    file_size: size,
-                    error: pallet_storage_providers::Error::<Test>::BucketNotFound.into(),
-                }.into());
-
-                // Assert that there was no queued priority challenge for file key in proofs dealer pallet
-                assert!(!pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
-                .iter()
-                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
             });
         }
-    }
 
-    mod success {
-        use super::*;
         #[test]
-        fn delete_file_with_proof_of_inclusion_success() {
+        fn storage_request_inherits_bucket_default_replication_target() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
                 let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 1024 * 1024 * 1024;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let size = 4;
                 let peer_id = BoundedVec::try_from(vec![1]).unwrap();
                 let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
 
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id =
-                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                assert_ok!(FileSystem::set_bucket_default_replication_target(
+                    origin.clone(),
+                    bucket_id,
+                    Some(5)
+                ));
 
-				// Increase bucket size and payment stream rate to simulate it storing the file
-				let initial_bucket_size = 2 * size;
-				assert_ok!(<<Test as crate::Config>::Providers as MutateBucketsInterface>::increase_bucket_size(&bucket_id, initial_bucket_size));
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
+                // A storage request without a replication target inherits the bucket default.
+                let location = FileLocation::<Test>::try_from(b"default".to_vec()).unwrap();
+                let fingerprint = BlakeTwo256::hash(b"default");
+                assert_ok!(FileSystem::issue_storage_request(
+                    origin.clone(),
                     bucket_id,
                     location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    None,
+                    false
+                ));
+                let file_key = FileSystem::compute_file_key(
+                    owner.clone(),
+                    bucket_id,
+                    location,
                     size,
                     fingerprint,
                 );
+                assert_eq!(
+                    StorageRequests::<Test>::get(file_key)
+                        .unwrap()
+                        .bsps_required,
+                    5
+                );
 
-                // Issue storage request
+                // An explicit replication target overrides the bucket default.
+                let location = FileLocation::<Test>::try_from(b"explicit".to_vec()).unwrap();
+                let fingerprint = BlakeTwo256::hash(b"explicit");
                 assert_ok!(FileSystem::issue_storage_request(
-                    owner_signed.clone(),
+                    origin,
                     bucket_id,
                     location.clone(),
                     fingerprint,
                     size,
                     Some(msp_id),
                     peer_ids,
-                    None
-                ));
-
-                // Dispatch MSP confirm storing.
-                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
-                    RuntimeOrigin::signed(msp.clone()),
-                    bounded_vec![StorageRequestMspBucketResponse {
-                        bucket_id,
-                        accept: Some(StorageRequestMspAcceptedFileKeys {
-                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
-                                file_key,
-                                proof: CompactProof {
-                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                                }
-                            }],
-                            non_inclusion_forest_proof: CompactProof {
-                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                            },
-                        }),
-                        reject: bounded_vec![],
-                    }],
+                    Some(3),
+                    false
                 ));
-
-				let bucket_size_after_confirm = pallet_storage_providers::Buckets::<Test>::get(bucket_id).unwrap().size;
-				let payment_stream_rate_after_confirm = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
-
-                let forest_proof = CompactProof {
-                    encoded_nodes: vec![file_key.as_ref().to_vec()],
-                };
-
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-                    owner_signed.clone(),
+                let file_key = FileSystem::compute_file_key(
+                    owner.clone(),
                     bucket_id,
-                    file_key,
                     location,
                     size,
                     fingerprint,
-                    Some(forest_proof),
-                ));
-
-                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
-                assert!(
-                    // Find file key in vec of queued priority challenges
-                    pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
-                        .iter()
-                        .any(|x| *x == (file_key, Some(TrieRemoveMutation))),
                 );
+                assert_eq!(
+                    StorageRequests::<Test>::get(file_key)
+                        .unwrap()
+                        .bsps_required,
+                    3
+                );
+            });
+        }
+    }
+}
 
-				// Assert that the Bucket root was correctly updated
-				let bucket_info = pallet_storage_providers::Buckets::<Test>::get(bucket_id).unwrap();
-				let bucket_root = bucket_info.root;
-				assert_eq!(bucket_root, file_key); // This is because our mocked apply delta sets the root as the last mutation
+mod set_bucket_encryption_metadata_tests {
+    use super::*;
 
-				// Assert that the Bucket's size was decreased by the file size
-				let new_bucket_size = bucket_info.size;
-				assert_eq!(new_bucket_size, bucket_size_after_confirm - size);
+    mod failure {
+        use super::*;
 
-				// Assert that the payment stream rate decrease
-				let new_payment_stream_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
-				assert!(new_payment_stream_rate < payment_stream_rate_after_confirm);
+        #[test]
+        fn set_bucket_encryption_metadata_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let not_owner = RuntimeOrigin::signed(Keyring::Bob.to_account_id());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let encryption_metadata = BoundedVec::try_from(b"aes-256-gcm".to_vec()).unwrap();
 
-                // Assert that the correct event was deposited
-                System::assert_last_event(
-                    Event::FileDeletionRequest {
-                        user: owner_account_id.clone(),
-                        file_key,
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_noop!(
+                    FileSystem::set_bucket_encryption_metadata(
+                        not_owner,
                         bucket_id,
-                        msp_id: Some(msp_id),
-                        proof_of_inclusion: true,
-                    }
-                    .into(),
+                        Some(encryption_metadata)
+                    ),
+                    Error::<Test>::NotBucketOwner
                 );
             });
         }
 
         #[test]
-        fn delete_file_expired_pending_file_deletion_request_success() {
+        fn set_bucket_encryption_metadata_bucket_not_found_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
-                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let origin = RuntimeOrigin::signed(Keyring::Alice.to_account_id());
+                let encryption_metadata = BoundedVec::try_from(b"aes-256-gcm".to_vec()).unwrap();
 
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                assert_noop!(
+                    FileSystem::set_bucket_encryption_metadata(
+                        origin,
+                        H256::zero(),
+                        Some(encryption_metadata)
+                    ),
+                    pallet_storage_providers::Error::<Test>::BucketNotFound
+                );
+            });
+        }
+    }
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+    mod success {
+        use super::*;
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
-                );
+        #[test]
+        fn create_bucket_with_encryption_metadata_success() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let name: BucketNameFor<Test> = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let encryption_metadata: EncryptionMetadataFor<Test> =
+                    BoundedVec::try_from(b"aes-256-gcm".to_vec()).unwrap();
 
-                // Issue storage request
-                assert_ok!(FileSystem::issue_storage_request(
-                    owner_signed.clone(),
-                    bucket_id,
-                    location.clone(),
-                    fingerprint,
-                    size,
-                    Some(msp_id),
-                    peer_ids,
-                    None
-                ));
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = <Test as file_system::Config>::Providers::derive_bucket_id(
+                    &owner,
+                    name.clone(),
+                );
 
-                // Dispatch MSP confirm storing.
-                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
-                    RuntimeOrigin::signed(msp.clone()),
-                    bounded_vec![StorageRequestMspBucketResponse {
-                        bucket_id,
-                        accept: Some(StorageRequestMspAcceptedFileKeys {
-                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
-                                file_key,
-                                proof: CompactProof {
-                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                                }
-                            }],
-                            non_inclusion_forest_proof: CompactProof {
-                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                            },
-                        }),
-                        reject: bounded_vec![],
-                    }],
+                assert_ok!(FileSystem::create_bucket(
+                    origin,
+                    Some(msp_id),
+                    name,
+                    false,
+                    Some(value_prop_id),
+                    Some(encryption_metadata.clone())
                 ));
 
-                // Query providers pallet Buckets storage
-                let bucket_size = Providers::get_bucket_size(&bucket_id).unwrap();
-
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-					owner_signed.clone(),
-					bucket_id,
-					file_key,
-					location,
-					size,
-					fingerprint,
-					None,
-				));
-
-                // Assert that the correct event was deposited
                 System::assert_last_event(
-                    Event::FileDeletionRequest {
-                        user: owner_account_id.clone(),
-                        file_key,
+                    Event::BucketEncryptionMetadataUpdated {
+                        who: owner,
                         bucket_id,
-                        msp_id: Some(msp_id),
-                        proof_of_inclusion: false,
+                        encryption_metadata: Some(encryption_metadata.clone()),
                     }
-                        .into(),
+                    .into(),
                 );
-
-                // Assert that the pending file deletion request was added to storage
                 assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id.clone(),
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
+                    <Test as file_system::Config>::Providers::get_bucket_encryption_metadata(
+                        &bucket_id
+                    ),
+                    Ok(Some(encryption_metadata))
                 );
+            });
+        }
 
-                let pending_file_deletion_request_ttl: u32 =
-                    PendingFileDeletionRequestTtl::<Test>::get();
-                let pending_file_deletion_request_ttl: BlockNumberFor<Test> =
-                    pending_file_deletion_request_ttl.into();
-                let expiration_block = pending_file_deletion_request_ttl + System::block_number();
+        #[test]
+        fn set_bucket_encryption_metadata_success() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let encryption_metadata: EncryptionMetadataFor<Test> =
+                    BoundedVec::try_from(b"aes-256-gcm".to_vec()).unwrap();
 
-                // Assert that the pending file deletion request was added to storage
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                // Buckets are created without encryption metadata by default.
                 assert_eq!(
-                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
-                    vec![FileDeletionRequestExpirationItem {
-                        user: owner_account_id.clone(),
-                        file_key,
-                        bucket_id,
-                        file_size: size,
-                    }]
+                    <Test as file_system::Config>::Providers::get_bucket_encryption_metadata(
+                        &bucket_id
+                    ),
+                    Ok(None)
                 );
 
-                // Roll past the expiration block
-                roll_to(pending_file_deletion_request_ttl + 1);
+                assert_ok!(FileSystem::set_bucket_encryption_metadata(
+                    origin.clone(),
+                    bucket_id,
+                    Some(encryption_metadata.clone())
+                ));
 
-                // Item expiration should be removed
-                assert_eq!(
-                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
-                    vec![]
+                System::assert_last_event(
+                    Event::BucketEncryptionMetadataUpdated {
+                        who: owner.clone(),
+                        bucket_id,
+                        encryption_metadata: Some(encryption_metadata.clone()),
+                    }
+                    .into(),
                 );
-
-                // Asser that the pending file deletion request was removed from storage
                 assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                    <Test as file_system::Config>::Providers::get_bucket_encryption_metadata(
+                        &bucket_id
+                    ),
+                    Ok(Some(encryption_metadata))
                 );
 
-                // Check that the bucket_size was reduced by the file size
+                // Clearing it removes it from the bucket.
+                assert_ok!(FileSystem::set_bucket_encryption_metadata(
+                    origin, bucket_id, None
+                ));
                 assert_eq!(
-                    Providers::get_bucket_size(&bucket_id).unwrap(),
-                    bucket_size - size
+                    <Test as file_system::Config>::Providers::get_bucket_encryption_metadata(
+                        &bucket_id
+                    ),
+                    Ok(None)
                 );
-
-                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
-                assert!(pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
-                .iter()
-                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
             });
         }
+    }
+}
+
+mod change_storage_request_replication_target_tests {
+    use super::*;
+
+    /// Issues a storage request of `owner` with the given MSP and replication target, returning its
+    /// file key.
+    fn issue_storage_request_with_target(
+        owner: &sp_runtime::AccountId32,
+        msp_id: Option<ProviderIdFor<Test>>,
+        bucket_id: BucketIdFor<Test>,
+        replication_target: u32,
+    ) -> H256 {
+        let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+        let fingerprint = BlakeTwo256::hash(b"test");
+
+        assert_ok!(FileSystem::issue_storage_request(
+            RuntimeOrigin::signed(owner.clone()),
+            bucket_id,
+            location.clone(),
+            fingerprint,
+            4,
+            msp_id,
+            Default::default(),
+            Some(replication_target),
+            false
+        ));
+
+        FileSystem::compute_file_key(owner.clone(), bucket_id, location, 4, fingerprint)
+    }
+
+    fn storage_request_deposit_held(owner: &sp_runtime::AccountId32) -> BalanceOf<Test> {
+        <Test as Config>::Currency::balance_on_hold(
+            &RuntimeHoldReason::FileSystem(file_system::HoldReason::StorageRequestCreationHold),
+            owner,
+        )
+    }
+
+    mod failure {
+        use super::*;
 
         #[test]
-        fn delete_file_pending_file_deletion_request_submit_proof_of_inclusion_success() {
+        fn change_replication_target_not_owner_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let owner = Keyring::Alice.to_account_id();
                 let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 1024 * 1024 * 1024; // One gigabyte
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
 
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key =
+                    issue_storage_request_with_target(&owner, Some(msp_id), bucket_id, 2);
+
+                assert_noop!(
+                    FileSystem::change_storage_request_replication_target(
+                        RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
+                        file_key,
+                        3
+                    ),
+                    Error::<Test>::StorageRequestNotAuthorized
+                );
+
+                assert_noop!(
+                    FileSystem::change_storage_request_replication_target(
+                        RuntimeOrigin::signed(owner),
+                        H256::zero(),
+                        3
+                    ),
+                    Error::<Test>::StorageRequestNotFound
+                );
+            });
+        }
 
+        #[test]
+        fn change_replication_target_invalid_value_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
-				// Increase bucket size and payment stream rate to simulate it storing the file
-				let initial_bucket_size = 2 * size;
-				assert_ok!(<<Test as crate::Config>::Providers as MutateBucketsInterface>::increase_bucket_size(&bucket_id, initial_bucket_size));
-				let initial_payment_stream_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key = issue_storage_request_with_target(&owner, None, bucket_id, 2);
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
+                assert_noop!(
+                    FileSystem::change_storage_request_replication_target(
+                        origin.clone(),
+                        file_key,
+                        2
+                    ),
+                    Error::<Test>::ReplicationTargetUnchanged
                 );
 
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-					owner_signed.clone(),
-					bucket_id,
-					file_key,
-					location,
-					size,
-					fingerprint,
-					None,
-				));
+                assert_noop!(
+                    FileSystem::change_storage_request_replication_target(
+                        origin.clone(),
+                        file_key,
+                        MaxReplicationTarget::<Test>::get() + 1
+                    ),
+                    Error::<Test>::ReplicationTargetExceedsMaximum
+                );
 
-                // Assert that the pending file deletion request was added to storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id.clone(),
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
+                // Without an MSP, the file cannot be stored only by it.
+                assert_noop!(
+                    FileSystem::change_storage_request_replication_target(
+                        origin.clone(),
+                        file_key,
+                        0
+                    ),
+                    Error::<Test>::MspOnlyStorageRequestWithoutMsp
                 );
 
-                let forest_proof = CompactProof {
-                    encoded_nodes: vec![file_key.as_ref().to_vec()],
-                };
+                // BSPs that already confirmed storing the file cannot be dropped.
+                StorageRequests::<Test>::mutate(file_key, |storage_request| {
+                    storage_request.as_mut().unwrap().bsps_confirmed = 1;
+                });
+                assert_noop!(
+                    FileSystem::change_storage_request_replication_target(origin, file_key, 0),
+                    Error::<Test>::ReplicationTargetBelowConfirmedBsps
+                );
+            });
+        }
+    }
 
-                let msp_origin = RuntimeOrigin::signed(msp.clone());
+    mod success {
+        use super::*;
 
-                assert_ok!(FileSystem::pending_file_deletion_request_submit_proof(
-					msp_origin,
-					owner_account_id.clone(),
-					file_key,
-					size,
-					bucket_id,
-					forest_proof
-				));
+        #[test]
+        fn raise_replication_target_success() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
 
-				// Assert that the Bucket root was correctly updated
-				let bucket_info = pallet_storage_providers::Buckets::<Test>::get(bucket_id).unwrap();
-				let bucket_root = bucket_info.root;
-				assert_eq!(bucket_root, file_key); // This is because our mocked apply delta sets the root as the last mutation
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key =
+                    issue_storage_request_with_target(&owner, Some(msp_id), bucket_id, 0);
+                assert_eq!(
+                    storage_request_deposit_held(&owner),
+                    <Test as Config>::MspOnlyStorageRequestCreationDeposit::get()
+                );
 
-				// Assert that the Bucket's size was decreased by the file size
-				let new_bucket_size = bucket_info.size;
-				assert_eq!(new_bucket_size, initial_bucket_size - size);
+                assert_ok!(FileSystem::change_storage_request_replication_target(
+                    RuntimeOrigin::signed(owner.clone()),
+                    file_key,
+                    3
+                ));
 
-				// Assert that the payment stream rate decrease
-				let new_payment_stream_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
+                System::assert_last_event(
+                    Event::StorageRequestReplicationTargetChanged {
+                        file_key,
+                        owner: owner.clone(),
+                        previous_replication_target: 0,
+                        replication_target: 3,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    StorageRequests::<Test>::get(file_key)
+                        .unwrap()
+                        .bsps_required,
+                    3
+                );
+
+                // The deposit is topped up to the one of a regular storage request.
+                assert_eq!(
+                    storage_request_deposit_held(&owner),
+                    <Test as Config>::StorageRequestCreationDeposit::get()
+                );
+            });
+        }
+
+        #[test]
+        fn lower_replication_target_to_confirmed_bsps_fulfils_storage_request() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key =
+                    issue_storage_request_with_target(&owner, Some(msp_id), bucket_id, 3);
+
+                // The MSP already accepted the storage request, but no BSP confirmed storing the file.
+                StorageRequests::<Test>::mutate(file_key, |storage_request| {
+                    storage_request.as_mut().unwrap().msp = Some((msp_id, true));
+                });
+
+                assert_ok!(FileSystem::change_storage_request_replication_target(
+                    RuntimeOrigin::signed(owner.clone()),
+                    file_key,
+                    0
+                ));
+
+                System::assert_has_event(
+                    Event::StorageRequestReplicationTargetChanged {
+                        file_key,
+                        owner: owner.clone(),
+                        previous_replication_target: 3,
+                        replication_target: 0,
+                    }
+                    .into(),
+                );
+                System::assert_last_event(Event::StorageRequestFulfilled { file_key }.into());
+                assert!(StorageRequests::<Test>::get(file_key).is_none());
+                assert!(!BucketsWithStorageRequests::<Test>::contains_key(
+                    bucket_id, file_key
+                ));
+
+                // The whole deposit is returned to the owner.
+                assert_eq!(storage_request_deposit_held(&owner), 0);
+            });
+        }
+    }
+}
+
+mod storage_request_auto_renewal_tests {
+    use super::*;
+
+    /// Issues a storage request of `owner` without an MSP and with the given replication target and
+    /// automatic renewal flag, returning its file key.
+    fn issue_storage_request_with_auto_renew(
+        owner: &sp_runtime::AccountId32,
+        bucket_id: BucketIdFor<Test>,
+        replication_target: u32,
+        auto_renew: bool,
+    ) -> H256 {
+        let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+        let fingerprint = BlakeTwo256::hash(b"test");
+
+        assert_ok!(FileSystem::issue_storage_request(
+            RuntimeOrigin::signed(owner.clone()),
+            bucket_id,
+            location.clone(),
+            fingerprint,
+            4,
+            None,
+            Default::default(),
+            Some(replication_target),
+            auto_renew
+        ));
+
+        FileSystem::compute_file_key(owner.clone(), bucket_id, location, 4, fingerprint)
+    }
+
+    fn renewal_deposit_held(owner: &sp_runtime::AccountId32) -> BalanceOf<Test> {
+        <Test as Config>::Currency::balance_on_hold(
+            &RuntimeHoldReason::FileSystem(file_system::HoldReason::StorageRequestRenewalHold),
+            owner,
+        )
+    }
+
+    fn roll_past_expiration() {
+        let storage_request_ttl: u32 = StorageRequestTtl::<Test>::get();
+        roll_to(System::block_number() + storage_request_ttl as u64 + 1);
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn auto_renew_holds_renewal_deposit() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key = issue_storage_request_with_auto_renew(&owner, bucket_id, 2, true);
+
+                let deposit = <Test as Config>::StorageRequestCreationDeposit::get();
+                assert_eq!(renewal_deposit_held(&owner), deposit);
+                assert_eq!(
+                    file_system::StorageRequestAutoRenewals::<Test>::get(file_key),
+                    Some(deposit)
+                );
+            });
+        }
+
+        #[test]
+        fn expired_storage_request_is_renewed_for_missing_replicas() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key = issue_storage_request_with_auto_renew(&owner, bucket_id, 3, true);
+
+                // Simulate a BSP having confirmed storing the file.
+                StorageRequests::<Test>::mutate(file_key, |storage_request| {
+                    storage_request.as_mut().unwrap().bsps_confirmed = 1;
+                });
+
+                roll_past_expiration();
+
+                System::assert_has_event(Event::StorageRequestExpired { file_key }.into());
+                System::assert_has_event(
+                    Event::StorageRequestRenewed {
+                        file_key,
+                        bsps_required: 2,
+                    }
+                    .into(),
+                );
+
+                // The renewed storage request is open for the missing replicas only.
+                let storage_request = StorageRequests::<Test>::get(file_key).unwrap();
+                assert_eq!(storage_request.bsps_required, 2);
+                assert_eq!(storage_request.bsps_confirmed, 0);
+                assert_eq!(storage_request.msp, None);
+
+                // The renewal deposit was used up, and the renewed storage request is not renewed again.
+                assert_eq!(renewal_deposit_held(&owner), 0);
+                assert!(!file_system::StorageRequestAutoRenewals::<Test>::contains_key(file_key));
+            });
+        }
+
+        #[test]
+        fn expired_storage_request_without_auto_renew_is_not_renewed() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key = issue_storage_request_with_auto_renew(&owner, bucket_id, 2, false);
+                assert_eq!(renewal_deposit_held(&owner), 0);
+
+                roll_past_expiration();
+
+                System::assert_has_event(Event::StorageRequestExpired { file_key }.into());
+                assert!(!System::events().iter().any(|record| matches!(
+                    record.event,
+                    RuntimeEvent::FileSystem(Event::StorageRequestRenewed { .. })
+                )));
+                assert!(StorageRequests::<Test>::get(file_key).is_none());
+            });
+        }
+
+        #[test]
+        fn revoked_storage_request_returns_renewal_deposit() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let file_key = issue_storage_request_with_auto_renew(&owner, bucket_id, 2, true);
+
+                assert_ok!(FileSystem::revoke_storage_request(
+                    RuntimeOrigin::signed(owner.clone()),
+                    file_key
+                ));
+
+                assert_eq!(renewal_deposit_held(&owner), 0);
+                assert!(!file_system::StorageRequestAutoRenewals::<Test>::contains_key(file_key));
+
+                // Nothing is renewed once the storage request would have expired.
+                roll_past_expiration();
+                assert!(StorageRequests::<Test>::get(file_key).is_none());
+            });
+        }
+    }
+}
+
+mod freeze_bucket_tests {
+    use super::*;
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn freeze_bucket_not_root_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_noop!(
+                    FileSystem::freeze_bucket(RuntimeOrigin::signed(owner.clone()), bucket_id),
+                    DispatchError::BadOrigin
+                );
+
+                assert_ok!(FileSystem::freeze_bucket(RuntimeOrigin::root(), bucket_id));
+
+                assert_noop!(
+                    FileSystem::unfreeze_bucket(RuntimeOrigin::signed(owner), bucket_id),
+                    DispatchError::BadOrigin
+                );
+            });
+        }
+
+        #[test]
+        fn freeze_bucket_invalid_state_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_noop!(
+                    FileSystem::freeze_bucket(RuntimeOrigin::root(), H256::zero()),
+                    Error::<Test>::BucketNotFound
+                );
+
+                assert_noop!(
+                    FileSystem::unfreeze_bucket(RuntimeOrigin::root(), bucket_id),
+                    Error::<Test>::BucketNotFrozen
+                );
+
+                assert_ok!(FileSystem::freeze_bucket(RuntimeOrigin::root(), bucket_id));
+
+                assert_noop!(
+                    FileSystem::freeze_bucket(RuntimeOrigin::root(), bucket_id),
+                    Error::<Test>::BucketAlreadyFrozen
+                );
+            });
+        }
+
+        #[test]
+        fn frozen_bucket_cannot_be_modified_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = BlakeTwo256::hash(b"test");
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_ok!(FileSystem::freeze_bucket(RuntimeOrigin::root(), bucket_id));
+
+                // No new files.
+                assert_noop!(
+                    FileSystem::issue_storage_request(
+                        origin.clone(),
+                        bucket_id,
+                        location.clone(),
+                        fingerprint,
+                        size,
+                        Some(msp_id),
+                        Default::default(),
+                        None,
+                        false
+                    ),
+                    Error::<Test>::BucketIsFrozen
+                );
+
+                // No deletions.
+                let file_key = FileSystem::compute_file_key(
+                    owner.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+                assert_noop!(
+                    FileSystem::delete_file(
+                        origin.clone(),
+                        bucket_id,
+                        file_key,
+                        location,
+                        size,
+                        fingerprint,
+                        None,
+                    ),
+                    Error::<Test>::BucketIsFrozen
+                );
+                assert_noop!(
+                    FileSystem::delete_bucket(origin.clone(), bucket_id),
+                    Error::<Test>::BucketIsFrozen
+                );
+
+                // No moves.
+                assert_noop!(
+                    FileSystem::request_move_bucket(origin, bucket_id, msp_id),
+                    Error::<Test>::BucketIsFrozen
+                );
+                assert_noop!(
+                    FileSystem::msp_stop_storing_bucket(RuntimeOrigin::signed(msp), bucket_id),
+                    Error::<Test>::BucketIsFrozen
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn freeze_and_unfreeze_bucket_success() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_ok!(FileSystem::freeze_bucket(RuntimeOrigin::root(), bucket_id));
+
+                assert_eq!(
+                    FrozenBuckets::<Test>::get(bucket_id),
+                    Some(System::block_number())
+                );
+                System::assert_last_event(
+                    Event::BucketFrozen {
+                        bucket_id,
+                        owner: owner.clone(),
+                        msp_id: Some(msp_id),
+                    }
+                    .into(),
+                );
+
+                assert_ok!(FileSystem::unfreeze_bucket(
+                    RuntimeOrigin::root(),
+                    bucket_id
+                ));
+
+                assert!(!FrozenBuckets::<Test>::contains_key(bucket_id));
+                System::assert_last_event(
+                    Event::BucketUnfrozen {
+                        bucket_id,
+                        owner: owner.clone(),
+                        msp_id: Some(msp_id),
+                    }
+                    .into(),
+                );
+
+                // The bucket can take new files again.
+                assert_ok!(FileSystem::issue_storage_request(
+                    RuntimeOrigin::signed(owner),
+                    bucket_id,
+                    FileLocation::<Test>::try_from(b"test".to_vec()).unwrap(),
+                    BlakeTwo256::hash(b"test"),
+                    4,
+                    Some(msp_id),
+                    Default::default(),
+                    None,
+                    false
+                ));
+            });
+        }
+    }
+}
+
+mod migrate_bucket_value_prop_tests {
+    use super::*;
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn migrate_bucket_value_prop_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let not_owner = RuntimeOrigin::signed(Keyring::Bob.to_account_id());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_ok!(Providers::publish_value_prop_version(
+                    RuntimeOrigin::signed(msp),
+                    value_prop_id,
+                    2,
+                    bounded_vec![],
+                    10 * 1024 * 1024 * 1024
+                ));
+
+                assert_noop!(
+                    FileSystem::migrate_bucket_value_prop(not_owner, bucket_id),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn migrate_bucket_value_prop_already_on_latest_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                assert_noop!(
+                    FileSystem::migrate_bucket_value_prop(origin, bucket_id),
+                    pallet_storage_providers::Error::<Test>::BucketAlreadyOnLatestValueProposition
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn migrate_bucket_value_prop_success() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let origin = RuntimeOrigin::signed(owner.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+                let new_value_prop =
+                    ValueProposition::<Test>::new(2, bounded_vec![], 10 * 1024 * 1024 * 1024);
+                let new_value_prop_id = new_value_prop.derive_id();
+                assert_ok!(Providers::publish_value_prop_version(
+                    RuntimeOrigin::signed(msp),
+                    value_prop_id,
+                    new_value_prop.price_per_giga_unit_of_data_per_block,
+                    new_value_prop.commitment,
+                    new_value_prop.bucket_data_limit
+                ));
+
+                // The bucket keeps its value proposition until its owner migrates it.
+                assert_eq!(
+                    pallet_storage_providers::Buckets::<Test>::get(bucket_id)
+                        .unwrap()
+                        .value_prop_id,
+                    Some(value_prop_id)
+                );
+
+                assert_ok!(FileSystem::migrate_bucket_value_prop(origin, bucket_id));
+
+                System::assert_last_event(
+                    pallet_storage_providers::Event::BucketValuePropMigrated {
+                        bucket_id,
+                        msp_id,
+                        previous_value_prop_id: value_prop_id,
+                        value_prop_id: new_value_prop_id,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    pallet_storage_providers::Buckets::<Test>::get(bucket_id)
+                        .unwrap()
+                        .value_prop_id,
+                    Some(new_value_prop_id)
+                );
+            });
+        }
+    }
+}
+
+mod delete_file_and_pending_deletions_tests {
+    use super::*;
+
+    mod failure {
+
+        use super::*;
+
+        #[test]
+        fn delete_file_bucket_not_owned_by_user_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let _ = create_bucket(
+                    &owner_account_id.clone(),
+                    name.clone(),
+                    msp_id,
+                    value_prop_id,
+                );
+
+                let other_user = Keyring::Bob.to_account_id();
+                let bucket_id = create_bucket(&other_user.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let forest_proof = CompactProof {
+                    encoded_nodes: vec![file_key.as_ref().to_vec()],
+                };
+
+                // Assert that the user does not own the bucket
+                assert_noop!(
+                    FileSystem::delete_file(
+                        owner_signed,
+                        bucket_id,
+                        file_key,
+                        location,
+                        size,
+                        fingerprint,
+                        Some(forest_proof),
+                    ),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn delete_file_beyond_maximum_limit_allowed_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = u64::MAX;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(
+                    &owner_account_id.clone(),
+                    name.clone(),
+                    msp_id,
+                    value_prop_id,
+                );
+
+                // For loop to create 1 over maximum of MaxUserPendingDeletionRequests
+                for i in 0..<Test as file_system::Config>::MaxUserPendingDeletionRequests::get() {
+                    let file_key = FileSystem::compute_file_key(
+                        owner_account_id.clone(),
+                        bucket_id,
+                        location.clone(),
+                        i as u64,
+                        fingerprint,
+                    );
+
+                    assert_ok!(FileSystem::delete_file(
+                        owner_signed.clone(),
+                        bucket_id,
+                        file_key,
+                        location.clone(),
+                        i as u64,
+                        fingerprint,
+                        None,
+                    ));
+                }
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                assert_noop!(
+                    FileSystem::delete_file(
+                        owner_signed,
+                        bucket_id,
+                        file_key,
+                        location,
+                        size,
+                        fingerprint,
+                        None
+                    ),
+                    Error::<Test>::MaxUserPendingDeletionRequestsReached
+                );
+            });
+        }
+
+        #[test]
+        fn delete_file_pending_file_deletion_request_submit_proof_not_msp_of_bucket_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+					owner_signed.clone(),
+					bucket_id,
+					file_key,
+					location,
+					size,
+					fingerprint,
+					None,
+				));
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id.clone(),
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+
+                let forest_proof = CompactProof {
+                    encoded_nodes: vec![vec![0]],
+                };
+
+                let msp_dave = Keyring::Dave.to_account_id();
+                add_msp_to_provider_storage(&msp_dave);
+                let msp_origin = RuntimeOrigin::signed(msp_dave.clone());
+
+                assert_noop!(
+					FileSystem::pending_file_deletion_request_submit_proof(
+						msp_origin,
+						owner_account_id.clone(),
+						file_key,
+						size,
+						bucket_id,
+						forest_proof
+					),
+					Error::<Test>::MspNotStoringBucket
+				);
+
+                // Assert that the pending file deletion request was not removed from storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id,
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn submit_proof_pending_file_deletion_not_found_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                let msp = Keyring::Charlie.to_account_id();
+                let msp_origin = RuntimeOrigin::signed(msp.clone());
+
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let forest_proof = CompactProof {
+                    encoded_nodes: vec![vec![0]],
+                };
+
+                assert_noop!(
+                    FileSystem::pending_file_deletion_request_submit_proof(
+                        msp_origin,
+                        owner_account_id.clone(),
+                        file_key,
+                        size,
+                        bucket_id,
+                        forest_proof
+                    ),
+                    Error::<Test>::FileKeyNotPendingDeletion
+                );
+            });
+        }
+
+        #[test]
+        fn delete_file_expired_pending_file_deletion_request_with_bucket_does_not_exist() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Issue storage request
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids,
+                    None,
+                    false
+                ));
+
+                // Dispatch MSP confirm storing.
+                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
+                    RuntimeOrigin::signed(msp.clone()),
+                    bounded_vec![StorageRequestMspBucketResponse {
+                        bucket_id,
+                        accept: Some(StorageRequestMspAcceptedFileKeys {
+                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
+                                file_key,
+                                proof: CompactProof {
+                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                                }
+                            }],
+                            non_inclusion_forest_proof: CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            },
+                        }),
+                        reject: bounded_vec![],
+                    }],
+                ));
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+					owner_signed.clone(),
+					bucket_id,
+					file_key,
+					location,
+					size,
+					fingerprint,
+					None,
+				));
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::FileDeletionRequest {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        proof_of_inclusion: false,
+                    }
+                        .into(),
+                );
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id.clone(),
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+
+                let pending_file_deletion_request_ttl: u32 =
+                    PendingFileDeletionRequestTtl::<Test>::get();
+                let pending_file_deletion_request_ttl: BlockNumberFor<Test> =
+                    pending_file_deletion_request_ttl.into();
+                let expiration_block = pending_file_deletion_request_ttl + System::block_number();
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
+                    vec![FileDeletionRequestExpirationItem {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        file_size: size,
+                    }]
+                );
+
+                // Delete the bucket manually
+                pallet_storage_providers::Buckets::<Test>::remove(bucket_id);
+
+                // Roll past the expiration block
+                roll_to(pending_file_deletion_request_ttl + 1);
+
+                // Item expiration should be removed
+                assert_eq!(
+                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
+                    vec![]
+                );
+
+                // Assert that the pending file deletion request was removed from storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                );
+
+                // Assert that the bucket size was not decreased because the bucket was not found
+                System::assert_has_event(Event::FailedToDecreaseBucketSize {
+                    user: owner_account_id.clone(),
+                    bucket_id,
+                    file_key,
+                    file_size: size,
+                    error: pallet_storage_providers::Error::<Test>::BucketNotFound.into(),
+                }.into());
+
+                // Assert that there was no queued priority challenge for file key in proofs dealer pallet
+                assert!(!pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
+                .iter()
+                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+        #[test]
+        fn delete_file_with_proof_of_inclusion_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 1024 * 1024 * 1024;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+				// Increase bucket size and payment stream rate to simulate it storing the file
+				let initial_bucket_size = 2 * size;
+				assert_ok!(<<Test as crate::Config>::Providers as MutateBucketsInterface>::increase_bucket_size(&bucket_id, initial_bucket_size));
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Issue storage request
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids,
+                    None,
+                    false
+                ));
+
+                // Dispatch MSP confirm storing.
+                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
+                    RuntimeOrigin::signed(msp.clone()),
+                    bounded_vec![StorageRequestMspBucketResponse {
+                        bucket_id,
+                        accept: Some(StorageRequestMspAcceptedFileKeys {
+                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
+                                file_key,
+                                proof: CompactProof {
+                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                                }
+                            }],
+                            non_inclusion_forest_proof: CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            },
+                        }),
+                        reject: bounded_vec![],
+                    }],
+                ));
+
+				let bucket_size_after_confirm = pallet_storage_providers::Buckets::<Test>::get(bucket_id).unwrap().size;
+				let payment_stream_rate_after_confirm = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
+
+                let forest_proof = CompactProof {
+                    encoded_nodes: vec![file_key.as_ref().to_vec()],
+                };
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+                    owner_signed.clone(),
+                    bucket_id,
+                    file_key,
+                    location,
+                    size,
+                    fingerprint,
+                    Some(forest_proof),
+                ));
+
+                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
+                assert!(
+                    // Find file key in vec of queued priority challenges
+                    pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
+                        .iter()
+                        .any(|x| *x == (file_key, Some(TrieRemoveMutation))),
+                );
+
+				// Assert that the Bucket root was correctly updated
+				let bucket_info = pallet_storage_providers::Buckets::<Test>::get(bucket_id).unwrap();
+				let bucket_root = bucket_info.root;
+				assert_eq!(bucket_root, file_key); // This is because our mocked apply delta sets the root as the last mutation
+
+				// Assert that the Bucket's size was decreased by the file size
+				let new_bucket_size = bucket_info.size;
+				assert_eq!(new_bucket_size, bucket_size_after_confirm - size);
+
+				// Assert that the payment stream rate decrease
+				let new_payment_stream_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
+				assert!(new_payment_stream_rate < payment_stream_rate_after_confirm);
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::FileDeletionRequest {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        proof_of_inclusion: true,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn delete_file_expired_pending_file_deletion_request_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Issue storage request
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids,
+                    None,
+                    false
+                ));
+
+                // Dispatch MSP confirm storing.
+                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
+                    RuntimeOrigin::signed(msp.clone()),
+                    bounded_vec![StorageRequestMspBucketResponse {
+                        bucket_id,
+                        accept: Some(StorageRequestMspAcceptedFileKeys {
+                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
+                                file_key,
+                                proof: CompactProof {
+                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                                }
+                            }],
+                            non_inclusion_forest_proof: CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            },
+                        }),
+                        reject: bounded_vec![],
+                    }],
+                ));
+
+                // Query providers pallet Buckets storage
+                let bucket_size = Providers::get_bucket_size(&bucket_id).unwrap();
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+					owner_signed.clone(),
+					bucket_id,
+					file_key,
+					location,
+					size,
+					fingerprint,
+					None,
+				));
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::FileDeletionRequest {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        proof_of_inclusion: false,
+                    }
+                        .into(),
+                );
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id.clone(),
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+
+                let pending_file_deletion_request_ttl: u32 =
+                    PendingFileDeletionRequestTtl::<Test>::get();
+                let pending_file_deletion_request_ttl: BlockNumberFor<Test> =
+                    pending_file_deletion_request_ttl.into();
+                let expiration_block = pending_file_deletion_request_ttl + System::block_number();
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
+                    vec![FileDeletionRequestExpirationItem {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        file_size: size,
+                    }]
+                );
+
+                // Roll past the expiration block
+                roll_to(pending_file_deletion_request_ttl + 1);
+
+                // Item expiration should be removed
+                assert_eq!(
+                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
+                    vec![]
+                );
+
+                // Asser that the pending file deletion request was removed from storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                );
+
+                // Check that the bucket_size was reduced by the file size
+                assert_eq!(
+                    Providers::get_bucket_size(&bucket_id).unwrap(),
+                    bucket_size - size
+                );
+
+                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
+                assert!(pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
+                .iter()
+                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
+            });
+        }
+
+        #[test]
+        fn delete_file_pending_file_deletion_request_submit_proof_of_inclusion_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 1024 * 1024 * 1024; // One gigabyte
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+				// Increase bucket size and payment stream rate to simulate it storing the file
+				let initial_bucket_size = 2 * size;
+				assert_ok!(<<Test as crate::Config>::Providers as MutateBucketsInterface>::increase_bucket_size(&bucket_id, initial_bucket_size));
+				let initial_payment_stream_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+					owner_signed.clone(),
+					bucket_id,
+					file_key,
+					location,
+					size,
+					fingerprint,
+					None,
+				));
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id.clone(),
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+
+                let forest_proof = CompactProof {
+                    encoded_nodes: vec![file_key.as_ref().to_vec()],
+                };
+
+                let msp_origin = RuntimeOrigin::signed(msp.clone());
+
+                assert_ok!(FileSystem::pending_file_deletion_request_submit_proof(
+					msp_origin,
+					owner_account_id.clone(),
+					file_key,
+					size,
+					bucket_id,
+					forest_proof
+				));
+
+				// Assert that the Bucket root was correctly updated
+				let bucket_info = pallet_storage_providers::Buckets::<Test>::get(bucket_id).unwrap();
+				let bucket_root = bucket_info.root;
+				assert_eq!(bucket_root, file_key); // This is because our mocked apply delta sets the root as the last mutation
+
+				// Assert that the Bucket's size was decreased by the file size
+				let new_bucket_size = bucket_info.size;
+				assert_eq!(new_bucket_size, initial_bucket_size - size);
+
+				// Assert that the payment stream rate decrease
+				let new_payment_stream_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(&msp_id, &owner_account_id).unwrap();
 				assert!(new_payment_stream_rate < initial_payment_stream_rate);
 
-                // Assert that the correct event was deposited
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::ProofSubmittedForPendingFileDeletionRequest {
+                        msp_id,
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        proof_of_inclusion: true,
+                    }
+                        .into(),
+                );
+
+                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
+                assert!(pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
+                .iter()
+                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
+
+                // Assert that the pending file deletion request was removed from storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                );
+            });
+        }
+
+        #[test]
+        fn delete_file_pending_file_deletion_request_submit_proof_of_non_inclusion_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+					owner_signed.clone(),
+					bucket_id,
+					file_key,
+					location,
+					size,
+					fingerprint,
+					None,
+				));
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id.clone(),
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+
+                let forest_proof = CompactProof {
+                    encoded_nodes: vec![H256::zero().as_bytes().to_vec()],
+                };
+
+                let msp_origin = RuntimeOrigin::signed(msp.clone());
+
+                assert_ok!(FileSystem::pending_file_deletion_request_submit_proof(
+					msp_origin,
+					owner_account_id.clone(),
+					file_key,
+					size,
+					bucket_id,
+					forest_proof
+				));
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::ProofSubmittedForPendingFileDeletionRequest {
+                        msp_id,
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        proof_of_inclusion: false,
+                    }
+                        .into(),
+                );
+
+                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
+                assert!(
+					!pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
+						.iter()
+						.any(|x| *x == (file_key, Some(TrieRemoveMutation))),
+				);
+
+                // Assert that the pending file deletion request was removed from storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                );
+            });
+        }
+
+        #[test]
+        fn delete_file_expired_pending_file_deletion_request_with_insolvent_user() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+				let (msp_id, _) = add_msp_to_provider_storage(&msp);
+
+				// Create a new value proposition with a high price per gigabyte per tick so deleting the 4 byte file will
+				// actually make a difference in the rate of the fixed-rate payment stream between the user and the MSP.
+				let value_prop = ValueProposition::<Test>::new(1024 * 1024 * 1024, bounded_vec![], 10000);
+    			let value_prop_id = value_prop.derive_id();
+    			pallet_storage_providers::MainStorageProviderIdsToValuePropositions::<Test>::insert(
+        			msp_id,
+        			value_prop_id,
+        			value_prop,
+    			);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Issue storage request
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids,
+                    Some(1),
+                    false
+                ));
+
+                // Dispatch MSP confirm storing.
+                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
+                    RuntimeOrigin::signed(msp.clone()),
+                    bounded_vec![StorageRequestMspBucketResponse {
+                        bucket_id,
+                        accept: Some(StorageRequestMspAcceptedFileKeys {
+                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
+                                file_key,
+                                proof: CompactProof {
+                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                                }
+                            }],
+                            non_inclusion_forest_proof: CompactProof {
+                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                            },
+                        }),
+                        reject: bounded_vec![],
+                    }],
+                ));
+
+                // Delete file
+                assert_ok!(FileSystem::delete_file(
+					owner_signed.clone(),
+					bucket_id,
+					file_key,
+					location,
+					size,
+					fingerprint,
+					None,
+				));
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::FileDeletionRequest {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        proof_of_inclusion: false,
+                    }
+                        .into(),
+                );
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
+                        vec![PendingFileDeletionRequest {
+							file_key,
+							user: owner_account_id.clone(),
+							bucket_id,
+							file_size: size,
+						}]
+                    )
+                        .unwrap()
+                );
+
+                let pending_file_deletion_request_ttl: u32 =
+                    PendingFileDeletionRequestTtl::<Test>::get();
+                let pending_file_deletion_request_ttl: BlockNumberFor<Test> =
+                    pending_file_deletion_request_ttl.into();
+                let expiration_block = pending_file_deletion_request_ttl + System::block_number();
+
+                // Assert that the pending file deletion request was added to storage
+                assert_eq!(
+                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
+                    vec![FileDeletionRequestExpirationItem {
+                        user: owner_account_id.clone(),
+                        file_key,
+                        bucket_id,
+                        file_size: size,
+                    }]
+                );
+
+                pallet_payment_streams::UsersWithoutFunds::<Test>::insert(owner_account_id.clone(), System::block_number());
+
+                // Roll past the expiration block
+                roll_to(pending_file_deletion_request_ttl + 1);
+
+                // Item expiration should be removed
+                assert_eq!(
+                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
+                    vec![]
+                );
+
+                // Assert that the pending file deletion request was removed from storage
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
+                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                );
+
+                // Assert that the payment stream was correctly deleted since the user is without funds
+                assert!(pallet_payment_streams::FixedRatePaymentStreams::<Test>::get(msp_id, owner_account_id.clone()).is_none());
+
+                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
+                assert!(pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
+                .iter()
+                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
+            });
+        }
+
+        #[test]
+        fn delete_file_pending_file_deletion_request_fee_paid_to_msp_submitting_proof() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                let fee: BalanceOf<Test> = 10;
+                FileDeletionFee::set(&fee);
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Increase bucket size to simulate it storing the file
+                assert_ok!(<<Test as crate::Config>::Providers as MutateBucketsInterface>::increase_bucket_size(&bucket_id, size));
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let owner_balance = <Test as Config>::Currency::free_balance(&owner_account_id);
+                let msp_balance = <Test as Config>::Currency::free_balance(&msp);
+
+                // Delete file without a proof of inclusion
+                assert_ok!(FileSystem::delete_file(
+                    owner_signed.clone(),
+                    bucket_id,
+                    file_key,
+                    location,
+                    size,
+                    fingerprint,
+                    None,
+                ));
+
+                // Assert that the fee was held from the owner
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &RuntimeHoldReason::FileSystem(file_system::HoldReason::FileDeletionFeeHold),
+                        &owner_account_id
+                    ),
+                    fee
+                );
+                assert_eq!(
+                    <Test as Config>::Currency::free_balance(&owner_account_id),
+                    owner_balance - fee
+                );
+                assert_eq!(PendingFileDeletionFees::<Test>::get(file_key), Some(fee));
+
+                // MSP submits the proof for the pending file deletion request
+                assert_ok!(FileSystem::pending_file_deletion_request_submit_proof(
+                    RuntimeOrigin::signed(msp.clone()),
+                    owner_account_id.clone(),
+                    file_key,
+                    size,
+                    bucket_id,
+                    CompactProof {
+                        encoded_nodes: vec![file_key.as_ref().to_vec()],
+                    }
+                ));
+
+                // Assert that the fee was paid to the MSP
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &RuntimeHoldReason::FileSystem(file_system::HoldReason::FileDeletionFeeHold),
+                        &owner_account_id
+                    ),
+                    0
+                );
+                assert_eq!(
+                    <Test as Config>::Currency::free_balance(&msp),
+                    msp_balance + fee
+                );
+                assert_eq!(PendingFileDeletionFees::<Test>::get(file_key), None);
+            });
+        }
+
+        #[test]
+        fn delete_file_expired_pending_file_deletion_request_fee_paid_to_treasury() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let file_content = b"test".to_vec();
+                let fingerprint = BlakeTwo256::hash(&file_content);
+                // Above the maximum fee, so it is capped.
+                let max_fee: BalanceOf<Test> = <Test as Config>::MaxFileDeletionFee::get();
+                FileDeletionFee::set(&(max_fee + 1));
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Increase bucket size to simulate it storing the file
+                assert_ok!(<<Test as crate::Config>::Providers as MutateBucketsInterface>::increase_bucket_size(&bucket_id, size));
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let treasury_balance = <Test as Config>::Currency::free_balance(&TreasuryAccount::get());
+
+                // Delete file without a proof of inclusion
+                assert_ok!(FileSystem::delete_file(
+                    owner_signed.clone(),
+                    bucket_id,
+                    file_key,
+                    location,
+                    size,
+                    fingerprint,
+                    None,
+                ));
+                assert_eq!(PendingFileDeletionFees::<Test>::get(file_key), Some(max_fee));
+
+                // Roll past the expiration block
+                let pending_file_deletion_request_ttl: u32 =
+                    PendingFileDeletionRequestTtl::<Test>::get();
+                roll_to(System::block_number() + pending_file_deletion_request_ttl as u64 + 1);
+
+                // Assert that the fee was paid to the treasury
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &RuntimeHoldReason::FileSystem(file_system::HoldReason::FileDeletionFeeHold),
+                        &owner_account_id
+                    ),
+                    0
+                );
+                assert_eq!(
+                    <Test as Config>::Currency::free_balance(&TreasuryAccount::get()),
+                    treasury_balance + max_fee
+                );
+                assert_eq!(PendingFileDeletionFees::<Test>::get(file_key), None);
+            });
+        }
+    }
+}
+
+mod delete_files_tests {
+    use super::*;
+
+    fn file_deletion_request_item(
+        owner: &sp_runtime::AccountId32,
+        bucket_id: BucketIdFor<Test>,
+        path: &[u8],
+    ) -> FileDeletionRequestItem<Test> {
+        let location = FileLocation::<Test>::try_from(path.to_vec()).unwrap();
+        let size = 4;
+        let fingerprint = BlakeTwo256::hash(path);
+        let file_key = FileSystem::compute_file_key(
+            owner.clone(),
+            bucket_id,
+            location.clone(),
+            size,
+            fingerprint,
+        );
+
+        FileDeletionRequestItem {
+            file_key,
+            location,
+            size,
+            fingerprint,
+        }
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn delete_files_empty_batch_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                assert_noop!(
+                    FileSystem::delete_files(
+                        RuntimeOrigin::signed(owner_account_id),
+                        bucket_id,
+                        BoundedVec::default(),
+                    ),
+                    Error::<Test>::EmptyFileDeletionBatch
+                );
+            });
+        }
+
+        #[test]
+        fn delete_files_bucket_not_owned_by_user_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let not_owner = Keyring::Bob.to_account_id();
+                let files = BoundedVec::try_from(vec![file_deletion_request_item(
+                    &not_owner, bucket_id, b"test",
+                )])
+                .unwrap();
+
+                assert_noop!(
+                    FileSystem::delete_files(RuntimeOrigin::signed(not_owner), bucket_id, files),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn delete_files_invalid_metadata_creates_no_request_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                // The second file does not match its file key, so the first one is not queued either.
+                let mut invalid_file =
+                    file_deletion_request_item(&owner_account_id, bucket_id, b"invalid");
+                invalid_file.size = 8;
+                let files = BoundedVec::try_from(vec![
+                    file_deletion_request_item(&owner_account_id, bucket_id, b"valid"),
+                    invalid_file,
+                ])
+                .unwrap();
+
+                assert_noop!(
+                    FileSystem::delete_files(
+                        RuntimeOrigin::signed(owner_account_id.clone()),
+                        bucket_id,
+                        files,
+                    ),
+                    Error::<Test>::InvalidFileKeyMetadata
+                );
+                assert!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id)
+                        .is_empty()
+                );
+            });
+        }
+
+        #[test]
+        fn delete_files_duplicated_file_key_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let file = file_deletion_request_item(&owner_account_id, bucket_id, b"test");
+                let files = BoundedVec::try_from(vec![file.clone(), file]).unwrap();
+
+                assert_noop!(
+                    FileSystem::delete_files(
+                        RuntimeOrigin::signed(owner_account_id),
+                        bucket_id,
+                        files,
+                    ),
+                    Error::<Test>::FileKeyAlreadyPendingDeletion
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn delete_files_queues_all_pending_file_deletion_requests() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let first_file = file_deletion_request_item(&owner_account_id, bucket_id, b"first");
+                let second_file =
+                    file_deletion_request_item(&owner_account_id, bucket_id, b"second");
+                let files =
+                    BoundedVec::try_from(vec![first_file.clone(), second_file.clone()]).unwrap();
+
+                assert_ok!(FileSystem::delete_files(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    bucket_id,
+                    files,
+                ));
+
+                // Assert that a pending file deletion request was added for every file.
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone())
+                        .into_inner(),
+                    vec![
+                        PendingFileDeletionRequest {
+                            user: owner_account_id.clone(),
+                            file_key: first_file.file_key,
+                            bucket_id,
+                            file_size: first_file.size,
+                        },
+                        PendingFileDeletionRequest {
+                            user: owner_account_id.clone(),
+                            file_key: second_file.file_key,
+                            bucket_id,
+                            file_size: second_file.size,
+                        },
+                    ]
+                );
+
+                System::assert_last_event(
+                    Event::FileDeletionRequestsBatch {
+                        user: owner_account_id,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        file_keys: BoundedVec::try_from(vec![
+                            first_file.file_key,
+                            second_file.file_key,
+                        ])
+                        .unwrap(),
+                    }
+                    .into(),
+                );
+            });
+        }
+    }
+}
+
+mod deletion_list_tests {
+    use super::*;
+
+    fn deletion_list_file(
+        owner: &sp_runtime::AccountId32,
+        bucket_id: BucketIdFor<Test>,
+        path: &[u8],
+    ) -> FileDeletionRequestItem<Test> {
+        let location = FileLocation::<Test>::try_from(path.to_vec()).unwrap();
+        let size = 4;
+        let fingerprint = BlakeTwo256::hash(path);
+        let file_key = FileSystem::compute_file_key(
+            owner.clone(),
+            bucket_id,
+            location.clone(),
+            size,
+            fingerprint,
+        );
+
+        FileDeletionRequestItem {
+            file_key,
+            location,
+            size,
+            fingerprint,
+        }
+    }
+
+    /// Builds the Merkle tree of a deletion list, returning its root and the proof of every file.
+    fn build_deletion_list(
+        files: &[FileDeletionRequestItem<Test>],
+    ) -> (H256, Vec<DeletionListProof<Test>>) {
+        use sp_runtime::traits::Hash;
+
+        let mut level: Vec<H256> = files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| BlakeTwo256::hash_of(&(index as u32, file.file_key)))
+            .collect();
+        level.resize(files.len().next_power_of_two(), H256::default());
+
+        let mut proofs = vec![Vec::new(); files.len()];
+        let mut positions: Vec<usize> = (0..files.len()).collect();
+        while level.len() > 1 {
+            for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+                proof.push(level[*position ^ 1]);
+                *position >>= 1;
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| BlakeTwo256::hash_of(&(pair[0], pair[1])))
+                .collect();
+        }
+
+        let proofs = proofs
+            .into_iter()
+            .map(|proof| BoundedVec::try_from(proof).unwrap())
+            .collect();
+
+        (level[0], proofs)
+    }
+
+    fn execute_item(
+        owner: &sp_runtime::AccountId32,
+        list_root: H256,
+        bucket_id: BucketIdFor<Test>,
+        file: &FileDeletionRequestItem<Test>,
+        proof: DeletionListProof<Test>,
+    ) -> DispatchResult {
+        FileSystem::execute_deletion_list_item(
+            RuntimeOrigin::signed(Keyring::Dave.to_account_id()),
+            owner.clone(),
+            list_root,
+            bucket_id,
+            file.location.clone(),
+            file.size,
+            file.fingerprint,
+            proof,
+            None,
+        )
+    }
+
+    fn deletion_list_deposit_held(owner: &sp_runtime::AccountId32) -> BalanceOf<Test> {
+        <Test as Config>::Currency::balance_on_hold(
+            &RuntimeHoldReason::FileSystem(file_system::HoldReason::DeletionListDepositHold),
+            owner,
+        )
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn register_deletion_list_empty_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                assert_noop!(
+                    FileSystem::register_deletion_list(
+                        RuntimeOrigin::signed(owner_account_id),
+                        H256::random(),
+                        0,
+                    ),
+                    Error::<Test>::DeletionListEmpty
+                );
+            });
+        }
+
+        #[test]
+        fn register_deletion_list_too_long_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let max_depth: u32 = <Test as Config>::MaxDeletionListProofDepth::get();
+
+                assert_noop!(
+                    FileSystem::register_deletion_list(
+                        RuntimeOrigin::signed(owner_account_id),
+                        H256::random(),
+                        2u32.pow(max_depth) + 1,
+                    ),
+                    Error::<Test>::DeletionListTooLong
+                );
+            });
+        }
+
+        #[test]
+        fn register_deletion_list_already_registered_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let list_root = H256::random();
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    2,
+                ));
+
+                assert_noop!(
+                    FileSystem::register_deletion_list(
+                        RuntimeOrigin::signed(owner_account_id),
+                        list_root,
+                        2,
+                    ),
+                    Error::<Test>::DeletionListAlreadyRegistered
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_not_registered_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_noop!(
+                    execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        &files[0],
+                        proofs[0].clone()
+                    ),
+                    Error::<Test>::DeletionListNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_out_of_order_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"third"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+
+                // The second file has a valid proof, but the first one has not been deleted yet.
+                assert_noop!(
+                    execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        &files[1],
+                        proofs[1].clone()
+                    ),
+                    Error::<Test>::InvalidDeletionListProof
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_replay_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+                assert_ok!(execute_item(
+                    &owner_account_id,
+                    list_root,
+                    bucket_id,
+                    &files[0],
+                    proofs[0].clone()
+                ));
+
+                assert_noop!(
+                    execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        &files[0],
+                        proofs[0].clone()
+                    ),
+                    Error::<Test>::InvalidDeletionListProof
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_file_not_in_list_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+                let other_file = deletion_list_file(&owner_account_id, bucket_id, b"other");
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+
+                assert_noop!(
+                    execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        &other_file,
+                        proofs[0].clone()
+                    ),
+                    Error::<Test>::InvalidDeletionListProof
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_failing_with_inclusion_proof_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let other_account_id = Keyring::Bob.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let other_bucket_id = create_bucket(&other_account_id, name, msp_id, value_prop_id);
+
+                // The file is in a bucket that the owner of the list does not own.
+                let files = vec![
+                    deletion_list_file(&owner_account_id, other_bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, other_bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+
+                // With a proof of inclusion, the failure could be caused by the proof, so the file is
+                // not skipped.
+                assert_noop!(
+                    FileSystem::execute_deletion_list_item(
+                        RuntimeOrigin::signed(Keyring::Dave.to_account_id()),
+                        owner_account_id.clone(),
+                        list_root,
+                        other_bucket_id,
+                        files[0].location.clone(),
+                        files[0].size,
+                        files[0].fingerprint,
+                        proofs[0].clone(),
+                        Some(CompactProof {
+                            encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                        }),
+                    ),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_pending_deletion_queue_full_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    owner_signed.clone(),
+                    list_root,
+                    files.len() as u32,
+                ));
+
+                // Fill the queue of pending deletion requests of the owner.
+                for i in 0..<Test as file_system::Config>::MaxUserPendingDeletionRequests::get() {
+                    let file = deletion_list_file(
+                        &owner_account_id,
+                        bucket_id,
+                        format!("other/{i}").as_bytes(),
+                    );
+                    assert_ok!(FileSystem::delete_file(
+                        owner_signed.clone(),
+                        bucket_id,
+                        file.file_key,
+                        file.location,
+                        file.size,
+                        file.fingerprint,
+                        None,
+                    ));
+                }
+
+                // A full queue can be emptied later, so the file is not skipped.
+                assert_noop!(
+                    execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        &files[0],
+                        proofs[0].clone()
+                    ),
+                    Error::<Test>::MaxUserPendingDeletionRequestsReached
+                );
+                assert_eq!(
+                    DeletionLists::<Test>::get(&owner_account_id, list_root)
+                        .unwrap()
+                        .next_index,
+                    0
+                );
+
+                // Once there is room in the queue, the file can be retried.
+                file_system::PendingFileDeletionRequests::<Test>::mutate(
+                    &owner_account_id,
+                    |requests| {
+                        requests.pop();
+                    },
+                );
+                assert_ok!(execute_item(
+                    &owner_account_id,
+                    list_root,
+                    bucket_id,
+                    &files[0],
+                    proofs[0].clone()
+                ));
+                System::assert_has_event(
+                    Event::DeletionListFileDeletionRequest {
+                        owner: owner_account_id.clone(),
+                        list_root,
+                        index: 0,
+                        file_key: files[0].file_key,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        proof_of_inclusion: false,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn cancel_deletion_list_not_registered_fail() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                assert_noop!(
+                    FileSystem::cancel_deletion_list(
+                        RuntimeOrigin::signed(owner_account_id),
+                        H256::random(),
+                    ),
+                    Error::<Test>::DeletionListNotFound
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn register_deletion_list_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let list_root = H256::random();
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    3,
+                ));
+
+                let item_deposit: BalanceOf<Test> = DeletionListItemDeposit::get();
+                assert_eq!(
+                    DeletionLists::<Test>::get(&owner_account_id, list_root),
+                    Some(DeletionList {
+                        length: 3,
+                        next_index: 0,
+                        item_deposit,
+                    })
+                );
+                assert_eq!(
+                    deletion_list_deposit_held(&owner_account_id),
+                    3 * item_deposit
+                );
+
+                System::assert_last_event(
+                    Event::DeletionListRegistered {
+                        owner: owner_account_id,
+                        list_root,
+                        length: 3,
+                        deposit: 3 * item_deposit,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_items_in_order_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"third"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+
+                for (index, (file, proof)) in files.iter().zip(proofs).enumerate() {
+                    assert_ok!(execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        file,
+                        proof
+                    ));
+
+                    System::assert_has_event(
+                        Event::DeletionListFileDeletionRequest {
+                            owner: owner_account_id.clone(),
+                            list_root,
+                            index: index as u32,
+                            file_key: file.file_key,
+                            bucket_id,
+                            msp_id: Some(msp_id),
+                            proof_of_inclusion: false,
+                        }
+                        .into(),
+                    );
+                }
+
+                // Every file of the list is pending deletion, on behalf of its owner.
+                assert_eq!(
+                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone())
+                        .into_iter()
+                        .map(|request| request.file_key)
+                        .collect::<Vec<_>>(),
+                    files.iter().map(|file| file.file_key).collect::<Vec<_>>()
+                );
+
+                // The list is removed once all its files have been deleted, and its deposit returned.
+                assert!(DeletionLists::<Test>::get(&owner_account_id, list_root).is_none());
+                assert_eq!(deletion_list_deposit_held(&owner_account_id), 0);
+                System::assert_last_event(
+                    Event::DeletionListCompleted {
+                        owner: owner_account_id,
+                        list_root,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn cancel_deletion_list_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+
+                let files = vec![
+                    deletion_list_file(&owner_account_id, bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+                assert_ok!(execute_item(
+                    &owner_account_id,
+                    list_root,
+                    bucket_id,
+                    &files[0],
+                    proofs[0].clone()
+                ));
+
+                assert_ok!(FileSystem::cancel_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                ));
+
+                System::assert_last_event(
+                    Event::DeletionListCancelled {
+                        owner: owner_account_id.clone(),
+                        list_root,
+                        executed: 1,
+                    }
+                    .into(),
+                );
+
+                // The deposit of both the processed and the remaining file is returned.
+                assert_eq!(deletion_list_deposit_held(&owner_account_id), 0);
+
+                // The remaining files can no longer be deleted through the list.
+                assert_noop!(
+                    execute_item(
+                        &owner_account_id,
+                        list_root,
+                        bucket_id,
+                        &files[1],
+                        proofs[1].clone()
+                    ),
+                    Error::<Test>::DeletionListNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn execute_deletion_list_item_skips_failing_file_success() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let other_account_id = Keyring::Bob.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id, name, msp_id, value_prop_id);
+                let other_name = BoundedVec::try_from(b"other-bucket".to_vec()).unwrap();
+                let other_bucket_id =
+                    create_bucket(&other_account_id, other_name, msp_id, value_prop_id);
+
+                // The first file is in a bucket that the owner of the list does not own.
+                let files = vec![
+                    deletion_list_file(&owner_account_id, other_bucket_id, b"first"),
+                    deletion_list_file(&owner_account_id, bucket_id, b"second"),
+                ];
+                let (list_root, proofs) = build_deletion_list(&files);
+
+                assert_ok!(FileSystem::register_deletion_list(
+                    RuntimeOrigin::signed(owner_account_id.clone()),
+                    list_root,
+                    files.len() as u32,
+                ));
+                let item_deposit: BalanceOf<Test> = DeletionListItemDeposit::get();
+
+                // The first file is skipped, and its deposit returned.
+                assert_ok!(execute_item(
+                    &owner_account_id,
+                    list_root,
+                    other_bucket_id,
+                    &files[0],
+                    proofs[0].clone()
+                ));
+                System::assert_last_event(
+                    Event::DeletionListItemSkipped {
+                        owner: owner_account_id.clone(),
+                        list_root,
+                        index: 0,
+                        file_key: files[0].file_key,
+                        error: Error::<Test>::NotBucketOwner.into(),
+                    }
+                    .into(),
+                );
+                assert!(file_system::PendingFileDeletionRequests::<Test>::get(
+                    owner_account_id.clone()
+                )
+                .is_empty());
+                assert_eq!(deletion_list_deposit_held(&owner_account_id), item_deposit);
+
+                // Which does not block the rest of the list.
+                assert_ok!(execute_item(
+                    &owner_account_id,
+                    list_root,
+                    bucket_id,
+                    &files[1],
+                    proofs[1].clone()
+                ));
+                System::assert_has_event(
+                    Event::DeletionListFileDeletionRequest {
+                        owner: owner_account_id.clone(),
+                        list_root,
+                        index: 1,
+                        file_key: files[1].file_key,
+                        bucket_id,
+                        msp_id: Some(msp_id),
+                        proof_of_inclusion: false,
+                    }
+                    .into(),
+                );
+                System::assert_last_event(
+                    Event::DeletionListCompleted {
+                        owner: owner_account_id.clone(),
+                        list_root,
+                    }
+                    .into(),
+                );
+                assert_eq!(deletion_list_deposit_held(&owner_account_id), 0);
+            });
+        }
+    }
+}
+
+mod move_file_tests {
+    use super::*;
+
+    struct FileToMove {
+        bucket_id: BucketIdFor<Test>,
+        file_key: H256,
+        location: FileLocation<Test>,
+        size: StorageData<Test>,
+        fingerprint: H256,
+        new_location: FileLocation<Test>,
+        new_file_key: H256,
+    }
+
+    /// Creates a bucket of `owner` stored by `msp`, and computes the file keys of a file in it before and after moving it.
+    fn setup_file_to_move(
+        owner: &sp_runtime::AccountId32,
+        msp: &sp_runtime::AccountId32,
+    ) -> FileToMove {
+        let (msp_id, value_prop_id) = add_msp_to_provider_storage(msp);
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+        let bucket_id = create_bucket(owner, name, msp_id, value_prop_id);
+
+        let location = FileLocation::<Test>::try_from(b"old/path".to_vec()).unwrap();
+        let new_location = FileLocation::<Test>::try_from(b"new/path".to_vec()).unwrap();
+        let size = 4;
+        let fingerprint = BlakeTwo256::hash(b"test");
+
+        FileToMove {
+            bucket_id,
+            file_key: FileSystem::compute_file_key(
+                owner.clone(),
+                bucket_id,
+                location.clone(),
+                size,
+                fingerprint,
+            ),
+            new_file_key: FileSystem::compute_file_key(
+                owner.clone(),
+                bucket_id,
+                new_location.clone(),
+                size,
+                fingerprint,
+            ),
+            location,
+            size,
+            fingerprint,
+            new_location,
+        }
+    }
+
+    fn request_move_file(owner: &sp_runtime::AccountId32, file: &FileToMove) -> DispatchResult {
+        FileSystem::request_move_file(
+            RuntimeOrigin::signed(owner.clone()),
+            file.bucket_id,
+            file.file_key,
+            file.location.clone(),
+            file.size,
+            file.fingerprint,
+            file.new_location.clone(),
+        )
+    }
+
+    /// A forest proof that, in the mocked verifier, proves exactly the given keys.
+    fn forest_proof_of(keys: &[H256]) -> CompactProof {
+        CompactProof {
+            encoded_nodes: keys.iter().map(|key| key.as_ref().to_vec()).collect(),
+        }
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn request_move_file_to_same_location_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let mut file = setup_file_to_move(&owner, &Keyring::Charlie.to_account_id());
+                file.new_location = file.location.clone();
+
+                assert_noop!(
+                    request_move_file(&owner, &file),
+                    Error::<Test>::FileMoveToSameLocation
+                );
+            });
+        }
+
+        #[test]
+        fn request_move_file_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let mut file = setup_file_to_move(&owner, &Keyring::Charlie.to_account_id());
+
+                let not_owner = Keyring::Bob.to_account_id();
+                file.file_key = FileSystem::compute_file_key(
+                    not_owner.clone(),
+                    file.bucket_id,
+                    file.location.clone(),
+                    file.size,
+                    file.fingerprint,
+                );
+
+                assert_noop!(
+                    request_move_file(&not_owner, &file),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn request_move_file_already_requested_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let file = setup_file_to_move(&owner, &Keyring::Charlie.to_account_id());
+
+                assert_ok!(request_move_file(&owner, &file));
+
+                assert_noop!(
+                    request_move_file(&owner, &file),
+                    Error::<Test>::FileMoveAlreadyRequested
+                );
+            });
+        }
+
+        #[test]
+        fn msp_confirm_file_move_not_requested_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let file = setup_file_to_move(&owner, &msp);
+
+                assert_noop!(
+                    FileSystem::msp_confirm_file_move(
+                        RuntimeOrigin::signed(msp),
+                        file.file_key,
+                        forest_proof_of(&[file.file_key]),
+                    ),
+                    Error::<Test>::FileMoveRequestNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn msp_confirm_file_move_new_file_key_in_bucket_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let file = setup_file_to_move(&owner, &msp);
+
+                assert_ok!(request_move_file(&owner, &file));
+
+                assert_noop!(
+                    FileSystem::msp_confirm_file_move(
+                        RuntimeOrigin::signed(msp),
+                        file.file_key,
+                        forest_proof_of(&[file.file_key, file.new_file_key]),
+                    ),
+                    Error::<Test>::ExpectedNonInclusionProof
+                );
+            });
+        }
+
+        #[test]
+        fn bsp_confirm_file_move_not_moved_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let file = setup_file_to_move(&owner, &Keyring::Charlie.to_account_id());
+
+                let bsp = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp.clone());
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), 100));
+
+                assert_ok!(request_move_file(&owner, &file));
+
+                assert_noop!(
+                    FileSystem::bsp_confirm_file_move(
+                        bsp_signed,
+                        file.file_key,
+                        forest_proof_of(&[file.file_key]),
+                    ),
+                    Error::<Test>::FileNotMoved
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn move_file_applied_by_msp_and_bsp() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let file = setup_file_to_move(&owner, &msp);
+                let msp_id = Providers::get_provider_id(msp.clone()).unwrap();
+
+                let bsp = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp.clone());
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), 100));
+                let bsp_id = Providers::get_provider_id(bsp).unwrap();
+
+                // The owner requests the move.
+                assert_ok!(request_move_file(&owner, &file));
+                System::assert_last_event(
+                    Event::FileMoveRequested {
+                        owner: owner.clone(),
+                        bucket_id: file.bucket_id,
+                        msp_id: Some(msp_id),
+                        file_key: file.file_key,
+                        new_file_key: file.new_file_key,
+                        new_location: file.new_location.clone(),
+                    }
+                    .into(),
+                );
+
+                // The MSP applies it to the bucket's forest.
+                assert_ok!(FileSystem::msp_confirm_file_move(
+                    RuntimeOrigin::signed(msp),
+                    file.file_key,
+                    forest_proof_of(&[file.file_key]),
+                ));
+
+                // In the mocked verifier, the new root is the last key mutated.
+                System::assert_last_event(
+                    Event::FileMoved {
+                        msp_id,
+                        bucket_id: file.bucket_id,
+                        file_key: file.file_key,
+                        new_file_key: file.new_file_key,
+                        new_bucket_root: file.new_file_key,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    Providers::get_root_bucket(&file.bucket_id),
+                    Some(file.new_file_key)
+                );
+                assert!(!PendingFileMoveRequests::<Test>::contains_key(
+                    &file.file_key
+                ));
+                assert_eq!(
+                    MovedFiles::<Test>::get(&file.file_key).map(|file_move| file_move.new_file_key),
+                    Some(file.new_file_key)
+                );
+
+                // The BSP applies it to its own forest.
+                assert_ok!(FileSystem::bsp_confirm_file_move(
+                    bsp_signed,
+                    file.file_key,
+                    forest_proof_of(&[file.file_key]),
+                ));
+                System::assert_last_event(
+                    Event::BspConfirmedFileMove {
+                        bsp_id,
+                        file_key: file.file_key,
+                        new_file_key: file.new_file_key,
+                        new_root: file.new_file_key,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    <Providers as ReadProvidersInterface>::get_root(bsp_id),
+                    Some(file.new_file_key)
+                );
+            });
+        }
+    }
+}
+
+mod priority_challenge_metering_tests {
+    use super::*;
+
+    fn file_key(n: u64) -> H256 {
+        H256::from_low_u64_be(n)
+    }
+
+    fn queue_for(account: &<Test as frame_system::Config>::AccountId, n: u64) -> DispatchResult {
+        FileSystem::queue_priority_challenge_for_file_deletion(
+            EitherAccountIdOrMspId::AccountId(account.clone()),
+            file_key(n),
+        )
+    }
+
+    fn is_in_priority_challenges_queue(n: u64) -> bool {
+        PriorityChallengesQueue::<Test>::get()
+            .iter()
+            .any(|x| *x == (file_key(n), Some(TrieRemoveMutation)))
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn queue_priority_challenge_beyond_deferred_limit_fail() {
+            new_test_ext().execute_with(|| {
+                let alice = Keyring::Alice.to_account_id();
+                let soft_limit: u32 = <Test as Config>::PriorityChallengesSoftLimitPerIssuer::get();
+                let max_deferred: u32 =
+                    <Test as Config>::MaxDeferredPriorityChallengesPerIssuer::get();
+
+                for n in 0..(soft_limit + max_deferred) as u64 {
+                    assert_ok!(queue_for(&alice, n));
+                }
+
+                assert_noop!(
+                    queue_for(&alice, (soft_limit + max_deferred) as u64),
+                    Error::<Test>::DeferredPriorityChallengesQueueFull
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn queue_priority_challenge_within_soft_limit_success() {
+            new_test_ext().execute_with(|| {
+                let alice = Keyring::Alice.to_account_id();
+                let issuer = EitherAccountIdOrMspId::<Test>::AccountId(alice.clone());
+
+                assert_ok!(queue_for(&alice, 1));
+
+                assert!(is_in_priority_challenges_queue(1));
+                assert_eq!(
+                    PriorityChallengesUsage::<Test>::get(&issuer).unwrap().count,
+                    1
+                );
+                System::assert_last_event(
+                    Event::PriorityChallengeForFileDeletionQueued {
+                        issuer,
+                        file_key: file_key(1),
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn queue_priority_challenge_beyond_soft_limit_is_deferred() {
+            new_test_ext().execute_with(|| {
+                let alice = Keyring::Alice.to_account_id();
+                let issuer = EitherAccountIdOrMspId::<Test>::AccountId(alice.clone());
+                let soft_limit: u32 = <Test as Config>::PriorityChallengesSoftLimitPerIssuer::get();
+
+                for n in 0..soft_limit as u64 {
+                    assert_ok!(queue_for(&alice, n));
+                }
+
+                let deferred = soft_limit as u64;
+                assert_ok!(queue_for(&alice, deferred));
+
+                // The priority challenge is deferred to the fair-share queue.
+                assert!(!is_in_priority_challenges_queue(deferred));
+                assert_eq!(
+                    DeferredPriorityChallenges::<Test>::get(&issuer).into_inner(),
+                    vec![file_key(deferred)]
+                );
+                assert_eq!(
+                    DeferredPriorityChallengeIssuers::<Test>::get().into_inner(),
+                    vec![issuer.clone()]
+                );
+                System::assert_last_event(
+                    Event::PriorityChallengeForFileDeletionDeferred {
+                        issuer,
+                        file_key: file_key(deferred),
+                    }
+                    .into(),
+                );
+
+                // Other issuers are not affected.
+                let bob = Keyring::Bob.to_account_id();
+                assert_ok!(queue_for(&bob, 100));
+                assert!(is_in_priority_challenges_queue(100));
+            });
+        }
+
+        #[test]
+        fn queue_priority_challenge_usage_resets_in_new_period() {
+            new_test_ext().execute_with(|| {
+                let alice = Keyring::Alice.to_account_id();
+                let issuer = EitherAccountIdOrMspId::<Test>::AccountId(alice.clone());
+                let soft_limit: u32 = <Test as Config>::PriorityChallengesSoftLimitPerIssuer::get();
+                let period: BlockNumberFor<Test> =
+                    <Test as Config>::PriorityChallengesMeteringPeriod::get();
+
+                for n in 0..soft_limit as u64 {
+                    assert_ok!(queue_for(&alice, n));
+                }
+
+                System::set_block_number(System::block_number() + period);
+
+                let n = soft_limit as u64;
+                assert_ok!(queue_for(&alice, n));
+
+                assert!(is_in_priority_challenges_queue(n));
+                assert_eq!(
+                    PriorityChallengesUsage::<Test>::get(&issuer).unwrap().count,
+                    1
+                );
+                assert!(DeferredPriorityChallenges::<Test>::get(&issuer).is_empty());
+            });
+        }
+
+        #[test]
+        fn deferred_priority_challenges_are_queued_in_turns() {
+            new_test_ext().execute_with(|| {
+                let alice = Keyring::Alice.to_account_id();
+                let bob = Keyring::Bob.to_account_id();
+                let alice_issuer = EitherAccountIdOrMspId::<Test>::AccountId(alice.clone());
+                let bob_issuer = EitherAccountIdOrMspId::<Test>::AccountId(bob.clone());
+                let soft_limit: u32 = <Test as Config>::PriorityChallengesSoftLimitPerIssuer::get();
+                let max_queued_per_block: u32 =
+                    <Test as Config>::MaxDeferredPriorityChallengesQueuedPerBlock::get();
+
+                // Use up the soft limit of both issuers.
+                for n in 0..soft_limit as u64 {
+                    assert_ok!(queue_for(&alice, n));
+                    assert_ok!(queue_for(&bob, 100 + n));
+                }
+
+                // Alice defers three priority challenges, and Bob one.
+                assert_ok!(queue_for(&alice, 10));
+                assert_ok!(queue_for(&alice, 11));
+                assert_ok!(queue_for(&alice, 12));
+                assert_ok!(queue_for(&bob, 110));
+
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+
+                // Bob's priority challenge is queued before Alice's second one.
+                assert_eq!(max_queued_per_block, 3);
+                System::assert_has_event(
+                    Event::DeferredPriorityChallengeForFileDeletionQueued {
+                        issuer: bob_issuer.clone(),
+                        file_key: file_key(110),
+                    }
+                    .into(),
+                );
+                System::assert_last_event(
+                    Event::DeferredPriorityChallengeForFileDeletionQueued {
+                        issuer: alice_issuer.clone(),
+                        file_key: file_key(11),
+                    }
+                    .into(),
+                );
+                assert!(is_in_priority_challenges_queue(10));
+                assert!(!is_in_priority_challenges_queue(12));
+                assert!(DeferredPriorityChallenges::<Test>::get(&bob_issuer).is_empty());
+                assert_eq!(
+                    DeferredPriorityChallengeIssuers::<Test>::get().into_inner(),
+                    vec![alice_issuer.clone()]
+                );
+
+                // The rest is queued in the next block.
+                System::set_block_number(System::block_number() + 1);
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+
+                assert!(is_in_priority_challenges_queue(12));
+                assert!(DeferredPriorityChallenges::<Test>::get(&alice_issuer).is_empty());
+                assert!(DeferredPriorityChallengeIssuers::<Test>::get().is_empty());
+            });
+        }
+    }
+}
+
+mod open_storage_requests_query_tests {
+    use super::*;
+
+    /// Issues a storage request for a file at each of `locations` in `bucket_id`, returning their file keys.
+    fn issue_storage_requests(
+        owner: &sp_runtime::AccountId32,
+        bucket_id: BucketIdFor<Test>,
+        msp_id: ProviderIdFor<Test>,
+        locations: &[&[u8]],
+    ) -> Vec<H256> {
+        let size = 4;
+        let fingerprint = BlakeTwo256::hash(b"test");
+        let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+        let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+        locations
+            .iter()
+            .map(|location| {
+                let location = FileLocation::<Test>::try_from(location.to_vec()).unwrap();
+                assert_ok!(FileSystem::issue_storage_request(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    None,
+                    false
+                ));
+
+                FileSystem::compute_file_key(owner.clone(), bucket_id, location, size, fingerprint)
+            })
+            .collect()
+    }
+
+    fn file_keys_of(
+        storage_requests: Vec<
+            OpenStorageRequest<H256, StorageRequestMetadata<Test>, ProviderIdFor<Test>>,
+        >,
+    ) -> Vec<H256> {
+        storage_requests
+            .into_iter()
+            .map(|storage_request| storage_request.file_key)
+            .collect()
+    }
+
+    #[test]
+    fn storage_requests_by_bucket_paginated() {
+        new_test_ext().execute_with(|| {
+            let owner = Keyring::Alice.to_account_id();
+            let (msp_id, value_prop_id) =
+                add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+
+            let bucket_id = create_bucket(
+                &owner,
+                BoundedVec::try_from(b"bucket".to_vec()).unwrap(),
+                msp_id,
+                value_prop_id,
+            );
+            let other_bucket_id = create_bucket(
+                &owner,
+                BoundedVec::try_from(b"other-bucket".to_vec()).unwrap(),
+                msp_id,
+                value_prop_id,
+            );
+
+            let mut file_keys =
+                issue_storage_requests(&owner, bucket_id, msp_id, &[b"a", b"b", b"c"]);
+            issue_storage_requests(&owner, other_bucket_id, msp_id, &[b"d"]);
+
+            let first_page =
+                file_keys_of(FileSystem::storage_requests_by_bucket(bucket_id, None, 2));
+            assert_eq!(first_page.len(), 2);
+
+            let second_page = file_keys_of(FileSystem::storage_requests_by_bucket(
+                bucket_id,
+                first_page.last().copied(),
+                2,
+            ));
+            assert_eq!(second_page.len(), 1);
+
+            let mut all_file_keys = [first_page, second_page].concat();
+            all_file_keys.sort();
+            file_keys.sort();
+            assert_eq!(all_file_keys, file_keys);
+        });
+    }
+
+    #[test]
+    fn pending_storage_requests_for_msp() {
+        new_test_ext().execute_with(|| {
+            let owner = Keyring::Alice.to_account_id();
+            let (msp_id, value_prop_id) =
+                add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+
+            let bucket_id = create_bucket(
+                &owner,
+                BoundedVec::try_from(b"bucket".to_vec()).unwrap(),
+                msp_id,
+                value_prop_id,
+            );
+
+            let mut file_keys =
+                issue_storage_requests(&owner, bucket_id, msp_id, &[b"a", b"b", b"c"]);
+
+            let mut pending = file_keys_of(FileSystem::pending_storage_requests_for_provider(
+                msp_id, None, 10,
+            ));
+            pending.sort();
+            file_keys.sort();
+            assert_eq!(pending, file_keys);
+
+            // Providers that are not registered have no pending storage requests.
+            assert!(
+                FileSystem::pending_storage_requests_for_provider(H256::random(), None, 10)
+                    .is_empty()
+            );
+        });
+    }
+
+    #[test]
+    fn pending_storage_requests_for_bsp_only_volunteered() {
+        new_test_ext().execute_with(|| {
+            let owner = Keyring::Alice.to_account_id();
+            let (msp_id, value_prop_id) =
+                add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+
+            let bucket_id = create_bucket(
+                &owner,
+                BoundedVec::try_from(b"bucket".to_vec()).unwrap(),
+                msp_id,
+                value_prop_id,
+            );
+
+            let file_keys = issue_storage_requests(&owner, bucket_id, msp_id, &[b"a", b"b"]);
+
+            let bsp_account_id = Keyring::Bob.to_account_id();
+            let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+            assert_ok!(bsp_sign_up(bsp_signed.clone(), 100));
+            let bsp_id = Providers::get_provider_id(bsp_account_id).unwrap();
+
+            assert_ok!(FileSystem::bsp_volunteer(bsp_signed, file_keys[0]));
+
+            let pending = FileSystem::pending_storage_requests_for_provider(bsp_id, None, 10);
+            assert_eq!(pending.len(), 1);
+            assert_eq!(pending[0].file_key, file_keys[0]);
+            assert_eq!(pending[0].volunteers, vec![(bsp_id, false)]);
+            assert_eq!(pending[0].metadata.bsps_volunteered, 1);
+        });
+    }
+
+    #[test]
+    fn query_storage_request_includes_volunteers() {
+        new_test_ext().execute_with(|| {
+            let owner = Keyring::Alice.to_account_id();
+            let (msp_id, value_prop_id) =
+                add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+
+            let bucket_id = create_bucket(
+                &owner,
+                BoundedVec::try_from(b"bucket".to_vec()).unwrap(),
+                msp_id,
+                value_prop_id,
+            );
+
+            let file_keys = issue_storage_requests(&owner, bucket_id, msp_id, &[b"a"]);
+
+            let bsp_account_id = Keyring::Bob.to_account_id();
+            let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+            assert_ok!(bsp_sign_up(bsp_signed.clone(), 100));
+            let bsp_id = Providers::get_provider_id(bsp_account_id).unwrap();
+
+            let storage_request = FileSystem::query_storage_request(file_keys[0]).unwrap();
+            assert!(storage_request.volunteers.is_empty());
+
+            assert_ok!(FileSystem::bsp_volunteer(bsp_signed, file_keys[0]));
+
+            let storage_request = FileSystem::query_storage_request(file_keys[0]).unwrap();
+            assert_eq!(storage_request.file_key, file_keys[0]);
+            assert_eq!(storage_request.volunteers, vec![(bsp_id, false)]);
+
+            // There is no storage request for unknown file keys.
+            assert!(FileSystem::query_storage_request(H256::random()).is_none());
+        });
+    }
+}
+
+mod estimate_storage_request_cost_tests {
+    use super::*;
+
+    const GIGAUNIT: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn estimate_storage_request_cost_works() {
+        new_test_ext().execute_with(|| {
+            pallet_payment_streams::CurrentPricePerGigaUnitPerTick::<Test>::put(100);
+            let (msp_id, _) = add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+
+            // Each BSP is paid 100 per tick per giga-unit, and the deposit of its payment stream covers
+            // `NewStreamDeposit` ticks plus the `BaseDeposit`.
+            let estimate =
+                FileSystem::estimate_storage_request_cost(2 * GIGAUNIT, 3, None).unwrap();
+            assert_eq!(
+                estimate,
+                StorageRequestCostEstimate {
+                    creation_deposit: <Test as Config>::StorageRequestCreationDeposit::get(),
+                    payment_streams_deposit: 3 * (200 * 10 + 10),
+                    bsps_rate_per_tick: 3 * 200,
+                }
+            );
+
+            // MSP-only storage requests have no BSP to pay.
+            let estimate =
+                FileSystem::estimate_storage_request_cost(2 * GIGAUNIT, 0, Some(msp_id)).unwrap();
+            assert_eq!(
+                estimate,
+                StorageRequestCostEstimate {
+                    creation_deposit: <Test as Config>::MspOnlyStorageRequestCreationDeposit::get(),
+                    payment_streams_deposit: 0,
+                    bsps_rate_per_tick: 0,
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn estimate_storage_request_cost_invalid_parameters_fail() {
+        new_test_ext().execute_with(|| {
+            let max_replication_target = MaxReplicationTarget::<Test>::get();
+
+            assert_eq!(
+                FileSystem::estimate_storage_request_cost(0, 1, None),
+                Err(EstimateStorageRequestCostError::FileSizeCannotBeZero)
+            );
+            assert_eq!(
+                FileSystem::estimate_storage_request_cost(4, max_replication_target + 1, None),
+                Err(EstimateStorageRequestCostError::ReplicationTargetExceedsMaximum)
+            );
+            assert_eq!(
+                FileSystem::estimate_storage_request_cost(4, 0, None),
+                Err(EstimateStorageRequestCostError::MspOnlyStorageRequestWithoutMsp)
+            );
+            assert_eq!(
+                FileSystem::estimate_storage_request_cost(4, 1, Some(H256::repeat_byte(1))),
+                Err(EstimateStorageRequestCostError::NotAMsp)
+            );
+        });
+    }
+}
+
+mod notification_preferences_tests {
+    use super::*;
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn set_notification_preferences_unsigned_fail() {
+            new_test_ext().execute_with(|| {
+                let preferences: NotificationPreferences<Test> =
+                    BoundedVec::try_from(b"ipfs://preferences".to_vec()).unwrap();
+
+                assert_noop!(
+                    FileSystem::set_notification_preferences(
+                        RuntimeOrigin::none(),
+                        Some(preferences)
+                    ),
+                    DispatchError::BadOrigin
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn set_and_clear_notification_preferences() {
+            new_test_ext().execute_with(|| {
+                let user = Keyring::Alice.to_account_id();
+                let preferences: NotificationPreferences<Test> =
+                    BoundedVec::try_from(b"ipfs://preferences".to_vec()).unwrap();
+
+                assert_ok!(FileSystem::set_notification_preferences(
+                    RuntimeOrigin::signed(user.clone()),
+                    Some(preferences.clone())
+                ));
+                System::assert_last_event(
+                    Event::NotificationPreferencesSet {
+                        who: user.clone(),
+                        preferences: Some(preferences.clone()),
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    UserNotificationPreferences::<Test>::get(&user),
+                    Some(preferences.clone())
+                );
+                assert_eq!(
+                    FileSystem::query_notification_preferences(&user),
+                    Some(preferences.into_inner())
+                );
+
+                // Other users are not affected.
+                assert_eq!(
+                    FileSystem::query_notification_preferences(&Keyring::Bob.to_account_id()),
+                    None
+                );
+
+                assert_ok!(FileSystem::set_notification_preferences(
+                    RuntimeOrigin::signed(user.clone()),
+                    None
+                ));
+                System::assert_last_event(
+                    Event::NotificationPreferencesSet {
+                        who: user.clone(),
+                        preferences: None,
+                    }
+                    .into(),
+                );
+                assert!(!UserNotificationPreferences::<Test>::contains_key(&user));
+            });
+        }
+    }
+}
+
+mod file_access_tests {
+    use super::*;
+
+    /// Creates a private bucket owned by Alice and returns it with the metadata of one of its files.
+    fn setup_private_file() -> (
+        BucketIdFor<Test>,
+        FileLocation<Test>,
+        StorageData<Test>,
+        H256,
+        H256,
+    ) {
+        let owner = Keyring::Alice.to_account_id();
+        let msp = Keyring::Charlie.to_account_id();
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+        let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+        let bucket_id =
+            <Test as file_system::Config>::Providers::derive_bucket_id(&owner, name.clone());
+        assert_ok!(FileSystem::create_bucket(
+            RuntimeOrigin::signed(owner.clone()),
+            Some(msp_id),
+            name,
+            true,
+            Some(value_prop_id),
+            None
+        ));
+
+        let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+        let size = 4;
+        let fingerprint = BlakeTwo256::hash(&b"test".to_vec());
+        let file_key =
+            FileSystem::compute_file_key(owner, bucket_id, location.clone(), size, fingerprint);
+
+        (bucket_id, location, size, fingerprint, file_key)
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn grant_file_access_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, location, size, fingerprint, _) = setup_private_file();
+
+                assert_noop!(
+                    FileSystem::grant_file_access(
+                        RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
+                        bucket_id,
+                        location,
+                        size,
+                        fingerprint,
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn grant_file_access_already_granted_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = RuntimeOrigin::signed(Keyring::Alice.to_account_id());
+                let (bucket_id, location, size, fingerprint, _) = setup_private_file();
+
+                assert_ok!(FileSystem::grant_file_access(
+                    owner.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                    Keyring::Bob.to_account_id(),
+                ));
+
+                assert_noop!(
+                    FileSystem::grant_file_access(
+                        owner,
+                        bucket_id,
+                        location,
+                        size,
+                        fingerprint,
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::FileAccessAlreadyGranted
+                );
+            });
+        }
+
+        #[test]
+        fn grant_file_access_list_full_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = RuntimeOrigin::signed(Keyring::Alice.to_account_id());
+                let (bucket_id, location, size, fingerprint, _) = setup_private_file();
+
+                let max_accounts: u32 = <Test as file_system::Config>::MaxFileAccessListSize::get();
+                for i in 0..max_accounts {
+                    assert_ok!(FileSystem::grant_file_access(
+                        owner.clone(),
+                        bucket_id,
+                        location.clone(),
+                        size,
+                        fingerprint,
+                        sp_runtime::AccountId32::new([i as u8 + 100; 32]),
+                    ));
+                }
+
+                assert_noop!(
+                    FileSystem::grant_file_access(
+                        owner,
+                        bucket_id,
+                        location,
+                        size,
+                        fingerprint,
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::FileAccessListFull
+                );
+            });
+        }
+
+        #[test]
+        fn revoke_file_access_not_granted_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, location, size, fingerprint, _) = setup_private_file();
+
+                assert_noop!(
+                    FileSystem::revoke_file_access(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        bucket_id,
+                        location,
+                        size,
+                        fingerprint,
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::FileAccessNotGranted
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn grant_and_revoke_file_access() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let reader = Keyring::Bob.to_account_id();
+                let (bucket_id, location, size, fingerprint, file_key) = setup_private_file();
+
+                // Only the owner can read the files of the private bucket.
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &owner
+                ));
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+
+                assert_ok!(FileSystem::grant_file_access(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                    reader.clone(),
+                ));
+                System::assert_last_event(
+                    Event::FileAccessGranted {
+                        owner: owner.clone(),
+                        bucket_id,
+                        file_key,
+                        account: reader.clone(),
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    FileSystem::query_file_access_list(&file_key),
+                    vec![reader.clone()]
+                );
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+
+                // The access is only granted to that file.
+                let other_file_key = FileSystem::compute_file_key(
+                    owner.clone(),
+                    bucket_id,
+                    FileLocation::<Test>::try_from(b"other".to_vec()).unwrap(),
+                    size,
+                    fingerprint,
+                );
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id,
+                    &other_file_key,
+                    &reader
+                ));
+
+                assert_ok!(FileSystem::revoke_file_access(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    location,
+                    size,
+                    fingerprint,
+                    reader.clone(),
+                ));
+                System::assert_last_event(
+                    Event::FileAccessRevoked {
+                        owner,
+                        bucket_id,
+                        file_key,
+                        account: reader.clone(),
+                    }
+                    .into(),
+                );
+                assert!(!FileAccessLists::<Test>::contains_key(&file_key));
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+            });
+        }
+
+        #[test]
+        fn holders_of_the_bucket_collection_have_read_access() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let reader = Keyring::Bob.to_account_id();
+                let (bucket_id, _, _, _, file_key) = setup_private_file();
+
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+
+                let collection_id =
+                    <Test as file_system::Config>::Providers::get_read_access_group_id_of_bucket(
+                        &bucket_id,
+                    )
+                    .unwrap()
+                    .expect("Collection ID should exist");
+                assert_ok!(Nfts::mint(
+                    RuntimeOrigin::signed(owner),
+                    collection_id,
+                    0,
+                    reader.clone(),
+                    None
+                ));
+
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+            });
+        }
+
+        #[test]
+        fn everyone_has_read_access_to_files_of_public_buckets() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let (bucket_id, _, _, _, file_key) = setup_private_file();
+
+                assert_ok!(FileSystem::update_bucket_privacy(
+                    RuntimeOrigin::signed(owner),
+                    bucket_id,
+                    false
+                ));
+
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id,
+                    &file_key,
+                    &Keyring::Bob.to_account_id()
+                ));
+            });
+        }
+    }
+}
+
+mod access_grant_tests {
+    use super::*;
+
+    /// Creates a private bucket owned by Alice and returns it with the key of one of its files.
+    fn setup_private_bucket() -> (BucketIdFor<Test>, H256) {
+        let owner = Keyring::Alice.to_account_id();
+        let msp = Keyring::Charlie.to_account_id();
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+        let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+        let bucket_id =
+            <Test as file_system::Config>::Providers::derive_bucket_id(&owner, name.clone());
+        assert_ok!(FileSystem::create_bucket(
+            RuntimeOrigin::signed(owner.clone()),
+            Some(msp_id),
+            name,
+            true,
+            Some(value_prop_id),
+            None
+        ));
+
+        let file_key = FileSystem::compute_file_key(
+            owner,
+            bucket_id,
+            FileLocation::<Test>::try_from(b"test".to_vec()).unwrap(),
+            4,
+            BlakeTwo256::hash(&b"test".to_vec()),
+        );
+
+        (bucket_id, file_key)
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn create_access_grant_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_private_bucket();
+                let expires_at = ProofsDealer::get_current_tick() + 10;
+
+                assert_noop!(
+                    FileSystem::create_access_grant(
+                        RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
+                        AccessGrantTarget::Bucket(bucket_id),
+                        Keyring::Bob.to_account_id(),
+                        expires_at,
+                    ),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn create_access_grant_expiration_in_the_past_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_private_bucket();
+                roll_to(5);
+                let current_tick = ProofsDealer::get_current_tick();
+
+                assert_noop!(
+                    FileSystem::create_access_grant(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        AccessGrantTarget::Bucket(bucket_id),
+                        Keyring::Bob.to_account_id(),
+                        current_tick,
+                    ),
+                    Error::<Test>::AccessGrantExpirationInThePast
+                );
+            });
+        }
+
+        #[test]
+        fn create_access_grant_full_fail() {
+            new_test_ext().execute_with(|| {
+                let owner = RuntimeOrigin::signed(Keyring::Alice.to_account_id());
+                let (bucket_id, _) = setup_private_bucket();
+                let expires_at = ProofsDealer::get_current_tick() + 10;
+
+                let max_grants: u32 =
+                    <Test as file_system::Config>::MaxAccessGrantsPerTarget::get();
+                for i in 0..max_grants {
+                    assert_ok!(FileSystem::create_access_grant(
+                        owner.clone(),
+                        AccessGrantTarget::Bucket(bucket_id),
+                        sp_runtime::AccountId32::new([i as u8 + 100; 32]),
+                        expires_at,
+                    ));
+                }
+
+                assert_noop!(
+                    FileSystem::create_access_grant(
+                        owner,
+                        AccessGrantTarget::Bucket(bucket_id),
+                        Keyring::Bob.to_account_id(),
+                        expires_at,
+                    ),
+                    Error::<Test>::AccessGrantsFull
+                );
+            });
+        }
+
+        #[test]
+        fn revoke_access_grant_not_found_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_private_bucket();
+
+                assert_noop!(
+                    FileSystem::revoke_access_grant(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        AccessGrantTarget::Bucket(bucket_id),
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::AccessGrantNotFound
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn create_and_revoke_bucket_access_grant() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let reader = Keyring::Bob.to_account_id();
+                let (bucket_id, file_key) = setup_private_bucket();
+                let target = AccessGrantTarget::<Test>::Bucket(bucket_id);
+                let expires_at = ProofsDealer::get_current_tick() + 10;
+
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+
+                assert_ok!(FileSystem::create_access_grant(
+                    RuntimeOrigin::signed(owner.clone()),
+                    target.clone(),
+                    reader.clone(),
+                    expires_at,
+                ));
+                System::assert_last_event(
+                    Event::AccessGrantCreated {
+                        owner: owner.clone(),
+                        target: target.clone(),
+                        grantee: reader.clone(),
+                        expires_at,
+                    }
+                    .into(),
+                );
+                assert!(AccessGrantExpirations::<Test>::contains_key(
+                    expires_at, &target
+                ));
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+
+                assert_ok!(FileSystem::revoke_access_grant(
+                    RuntimeOrigin::signed(owner.clone()),
+                    target.clone(),
+                    reader.clone(),
+                ));
                 System::assert_last_event(
-                    Event::ProofSubmittedForPendingFileDeletionRequest {
-                        msp_id,
-                        user: owner_account_id.clone(),
+                    Event::AccessGrantRevoked {
+                        owner,
+                        target: target.clone(),
+                        grantee: reader.clone(),
+                    }
+                    .into(),
+                );
+                assert!(!AccessGrants::<Test>::contains_key(&target));
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+            });
+        }
+
+        #[test]
+        fn file_access_grant_only_gives_access_to_that_file() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let reader = Keyring::Bob.to_account_id();
+                let (bucket_id, file_key) = setup_private_bucket();
+                let expires_at = ProofsDealer::get_current_tick() + 10;
+
+                assert_ok!(FileSystem::create_access_grant(
+                    RuntimeOrigin::signed(owner),
+                    AccessGrantTarget::File {
+                        bucket_id,
+                        file_key,
+                    },
+                    reader.clone(),
+                    expires_at,
+                ));
+
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+                let other_file_key = H256::repeat_byte(1);
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id,
+                    &other_file_key,
+                    &reader
+                ));
+            });
+        }
+
+        #[test]
+        fn expired_access_grants_are_removed_on_idle() {
+            new_test_ext().execute_with(|| {
+                let owner = RuntimeOrigin::signed(Keyring::Alice.to_account_id());
+                let reader = Keyring::Bob.to_account_id();
+                let (bucket_id, file_key) = setup_private_bucket();
+                let target = AccessGrantTarget::<Test>::Bucket(bucket_id);
+                let expires_at = ProofsDealer::get_current_tick() + 3;
+
+                assert_ok!(FileSystem::create_access_grant(
+                    owner.clone(),
+                    target.clone(),
+                    reader.clone(),
+                    expires_at,
+                ));
+                // A longer grant for another account is kept.
+                let other_reader = Keyring::Dave.to_account_id();
+                assert_ok!(FileSystem::create_access_grant(
+                    owner,
+                    target.clone(),
+                    other_reader.clone(),
+                    expires_at + 10,
+                ));
+
+                roll_to(System::block_number() + 3);
+                assert!(ProofsDealer::get_current_tick() >= expires_at);
+
+                // The expired grant no longer gives access, even before being removed.
+                assert!(!FileSystem::has_file_read_access(
+                    &bucket_id, &file_key, &reader
+                ));
+
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+
+                System::assert_has_event(
+                    Event::AccessGrantExpired {
+                        target: target.clone(),
+                        grantee: reader.clone(),
+                    }
+                    .into(),
+                );
+                assert!(!AccessGrantExpirations::<Test>::contains_key(
+                    expires_at, &target
+                ));
+                assert_eq!(
+                    AccessGrants::<Test>::get(&target)
+                        .unwrap()
+                        .into_iter()
+                        .map(|grant| grant.grantee)
+                        .collect::<Vec<_>>(),
+                    vec![other_reader.clone()]
+                );
+                assert!(FileSystem::has_file_read_access(
+                    &bucket_id,
+                    &file_key,
+                    &other_reader
+                ));
+            });
+        }
+    }
+}
+
+mod upload_grant_tests {
+    use super::*;
+
+    /// Creates a bucket owned by Alice and returns it with the ID of its MSP.
+    fn setup_bucket() -> (BucketIdFor<Test>, ProviderIdFor<Test>) {
+        let owner = Keyring::Alice.to_account_id();
+        let msp = Keyring::Charlie.to_account_id();
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+        let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+        let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+
+        (bucket_id, msp_id)
+    }
+
+    /// Issues a storage request for a file of `size` at `location` into `bucket_id` with the upload
+    /// grant of Bob.
+    fn issue_storage_request_with_grant(
+        bucket_id: BucketIdFor<Test>,
+        msp_id: ProviderIdFor<Test>,
+        location: &[u8],
+        size: StorageData<Test>,
+    ) -> DispatchResult {
+        let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+        FileSystem::issue_storage_request_with_grant(
+            RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
+            bucket_id,
+            FileLocation::<Test>::try_from(location.to_vec()).unwrap(),
+            BlakeTwo256::hash(location),
+            size,
+            Some(msp_id),
+            BoundedVec::try_from(vec![peer_id]).unwrap(),
+            None,
+        )
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn create_upload_grant_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_bucket();
+
+                assert_noop!(
+                    FileSystem::create_upload_grant(
+                        RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
+                        bucket_id,
+                        Keyring::Bob.to_account_id(),
+                        100,
+                        10,
+                    ),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn create_upload_grant_zero_budget_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_bucket();
+
+                assert_noop!(
+                    FileSystem::create_upload_grant(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        bucket_id,
+                        Keyring::Bob.to_account_id(),
+                        0,
+                        10,
+                    ),
+                    Error::<Test>::UploadGrantBudgetCannotBeZero
+                );
+            });
+        }
+
+        #[test]
+        fn create_upload_grant_expiration_in_the_past_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_bucket();
+
+                assert_noop!(
+                    FileSystem::create_upload_grant(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        bucket_id,
+                        Keyring::Bob.to_account_id(),
+                        100,
+                        System::block_number(),
+                    ),
+                    Error::<Test>::UploadGrantExpirationInThePast
+                );
+            });
+        }
+
+        #[test]
+        fn revoke_upload_grant_not_found_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_bucket();
+
+                assert_noop!(
+                    FileSystem::revoke_upload_grant(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        bucket_id,
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::UploadGrantNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn revoke_upload_grant_not_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _) = setup_bucket();
+
+                assert_ok!(FileSystem::create_upload_grant(
+                    RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                    bucket_id,
+                    Keyring::Bob.to_account_id(),
+                    100,
+                    10,
+                ));
+
+                assert_noop!(
+                    FileSystem::revoke_upload_grant(
+                        RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
+                        bucket_id,
+                        Keyring::Bob.to_account_id(),
+                    ),
+                    Error::<Test>::NotBucketOwner
+                );
+            });
+        }
+
+        #[test]
+        fn issue_storage_request_with_grant_not_found_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_id) = setup_bucket();
+
+                assert_noop!(
+                    issue_storage_request_with_grant(bucket_id, msp_id, b"test", 4),
+                    Error::<Test>::UploadGrantNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn issue_storage_request_with_grant_expired_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_id) = setup_bucket();
+                let expires_at = System::block_number() + 5;
+
+                assert_ok!(FileSystem::create_upload_grant(
+                    RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                    bucket_id,
+                    Keyring::Bob.to_account_id(),
+                    100,
+                    expires_at,
+                ));
+
+                roll_to(expires_at);
+
+                assert_noop!(
+                    issue_storage_request_with_grant(bucket_id, msp_id, b"test", 4),
+                    Error::<Test>::UploadGrantExpired
+                );
+            });
+        }
+
+        #[test]
+        fn issue_storage_request_with_grant_budget_exceeded_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_id) = setup_bucket();
+
+                assert_ok!(FileSystem::create_upload_grant(
+                    RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                    bucket_id,
+                    Keyring::Bob.to_account_id(),
+                    6,
+                    10,
+                ));
+
+                assert_ok!(issue_storage_request_with_grant(
+                    bucket_id, msp_id, b"first", 4
+                ));
+                assert_noop!(
+                    issue_storage_request_with_grant(bucket_id, msp_id, b"second", 4),
+                    Error::<Test>::UploadGrantBudgetExceeded
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn issue_storage_request_with_grant_on_behalf_of_owner() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let grantee = Keyring::Bob.to_account_id();
+                let (bucket_id, msp_id) = setup_bucket();
+                let expires_at = System::block_number() + 10;
+
+                assert_ok!(FileSystem::create_upload_grant(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    grantee.clone(),
+                    10,
+                    expires_at,
+                ));
+                System::assert_last_event(
+                    Event::UploadGrantCreated {
+                        owner: owner.clone(),
+                        bucket_id,
+                        grantee: grantee.clone(),
+                        size_budget: 10,
+                        expires_at,
+                    }
+                    .into(),
+                );
+
+                assert_ok!(issue_storage_request_with_grant(
+                    bucket_id, msp_id, b"test", 4
+                ));
+
+                // The storage request belongs to the owner of the bucket, who pays its deposit.
+                let file_key = FileSystem::compute_file_key(
+                    owner.clone(),
+                    bucket_id,
+                    FileLocation::<Test>::try_from(b"test".to_vec()).unwrap(),
+                    4,
+                    BlakeTwo256::hash(b"test"),
+                );
+                assert_eq!(StorageRequests::<Test>::get(file_key).unwrap().owner, owner);
+                let hold_reason = RuntimeHoldReason::FileSystem(
+                    file_system::HoldReason::StorageRequestCreationHold,
+                );
+                assert!(
+                    !<Test as Config>::Currency::balance_on_hold(&hold_reason, &owner).is_zero()
+                );
+                assert!(
+                    <Test as Config>::Currency::balance_on_hold(&hold_reason, &grantee).is_zero()
+                );
+
+                System::assert_last_event(
+                    Event::UploadGrantUsed {
+                        owner: owner.clone(),
+                        bucket_id,
+                        grantee: grantee.clone(),
                         file_key,
+                        size: 4,
+                        remaining_budget: 6,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    UploadGrants::<Test>::get(bucket_id, &grantee)
+                        .unwrap()
+                        .remaining_budget,
+                    6
+                );
+            });
+        }
+
+        #[test]
+        fn revoked_upload_grant_cannot_be_used() {
+            new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
+                let grantee = Keyring::Bob.to_account_id();
+                let (bucket_id, msp_id) = setup_bucket();
+
+                assert_ok!(FileSystem::create_upload_grant(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    grantee.clone(),
+                    10,
+                    10,
+                ));
+                assert_ok!(FileSystem::revoke_upload_grant(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    grantee.clone(),
+                ));
+                System::assert_last_event(
+                    Event::UploadGrantRevoked {
+                        owner,
+                        bucket_id,
+                        grantee: grantee.clone(),
+                    }
+                    .into(),
+                );
+                assert!(!UploadGrants::<Test>::contains_key(bucket_id, &grantee));
+
+                assert_noop!(
+                    issue_storage_request_with_grant(bucket_id, msp_id, b"test", 4),
+                    Error::<Test>::UploadGrantNotFound
+                );
+            });
+        }
+    }
+}
+
+mod bucket_transfer_offer_tests {
+    use super::*;
+
+    /// Creates a bucket owned by Alice stored by Charlie, and registers Dave as another MSP.
+    ///
+    /// Returns the bucket with the IDs of Charlie and Dave.
+    fn setup_bucket() -> (BucketIdFor<Test>, ProviderIdFor<Test>, ProviderIdFor<Test>) {
+        let owner = Keyring::Alice.to_account_id();
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+
+        let (msp_charlie_id, _) = add_msp_to_provider_storage(&Keyring::Charlie.to_account_id());
+        let (msp_dave_id, value_prop_id) =
+            add_msp_to_provider_storage(&Keyring::Dave.to_account_id());
+        let bucket_id = create_bucket(&owner, name, msp_charlie_id, value_prop_id);
+
+        (bucket_id, msp_charlie_id, msp_dave_id)
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn offer_bucket_transfer_not_storing_bucket_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_charlie_id, _) = setup_bucket();
+
+                assert_noop!(
+                    FileSystem::msp_offer_bucket_transfer(
+                        RuntimeOrigin::signed(Keyring::Dave.to_account_id()),
+                        bucket_id,
+                        msp_charlie_id,
+                    ),
+                    Error::<Test>::MspNotStoringBucket
+                );
+            });
+        }
+
+        #[test]
+        fn offer_bucket_transfer_to_itself_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_charlie_id, _) = setup_bucket();
+
+                assert_noop!(
+                    FileSystem::msp_offer_bucket_transfer(
+                        RuntimeOrigin::signed(Keyring::Charlie.to_account_id()),
+                        bucket_id,
+                        msp_charlie_id,
+                    ),
+                    Error::<Test>::MspAlreadyStoringBucket
+                );
+            });
+        }
+
+        #[test]
+        fn respond_bucket_transfer_offer_not_bucket_owner_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _, msp_dave_id) = setup_bucket();
+
+                assert_ok!(FileSystem::msp_offer_bucket_transfer(
+                    RuntimeOrigin::signed(Keyring::Charlie.to_account_id()),
+                    bucket_id,
+                    msp_dave_id,
+                ));
+
+                assert_noop!(
+                    FileSystem::respond_bucket_transfer_offer(
+                        RuntimeOrigin::signed(Keyring::Bob.to_account_id()),
                         bucket_id,
-                        proof_of_inclusion: true,
-                    }
-                        .into(),
+                        BucketMoveRequestResponse::Accepted,
+                    ),
+                    Error::<Test>::NotBucketOwner
                 );
+            });
+        }
 
-                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
-                assert!(pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
-                .iter()
-                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
+        #[test]
+        fn respond_bucket_transfer_offer_not_found_fail() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, _, _) = setup_bucket();
 
-                // Assert that the pending file deletion request was removed from storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                assert_noop!(
+                    FileSystem::respond_bucket_transfer_offer(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        bucket_id,
+                        BucketMoveRequestResponse::Accepted,
+                    ),
+                    Error::<Test>::BucketTransferOfferNotFound
                 );
             });
         }
 
         #[test]
-        fn delete_file_pending_file_deletion_request_submit_proof_of_non_inclusion_success() {
+        fn respond_bucket_transfer_offer_expired_fail() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
+                let (bucket_id, _, msp_dave_id) = setup_bucket();
 
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                assert_ok!(FileSystem::msp_offer_bucket_transfer(
+                    RuntimeOrigin::signed(Keyring::Charlie.to_account_id()),
+                    bucket_id,
+                    msp_dave_id,
+                ));
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                let expires_at = BucketTransferOffers::<Test>::get(bucket_id)
+                    .unwrap()
+                    .expires_at;
+                roll_to(expires_at);
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
+                assert_noop!(
+                    FileSystem::respond_bucket_transfer_offer(
+                        RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                        bucket_id,
+                        BucketMoveRequestResponse::Accepted,
+                    ),
+                    Error::<Test>::BucketTransferOfferExpired
                 );
+            });
+        }
+    }
 
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-					owner_signed.clone(),
-					bucket_id,
-					file_key,
-					location,
-					size,
-					fingerprint,
-					None,
-				));
+    mod success {
+        use super::*;
 
-                // Assert that the pending file deletion request was added to storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id.clone(),
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
-                );
+        #[test]
+        fn offer_bucket_transfer_works() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_charlie_id, msp_dave_id) = setup_bucket();
 
-                let forest_proof = CompactProof {
-                    encoded_nodes: vec![H256::zero().as_bytes().to_vec()],
-                };
+                assert_ok!(FileSystem::msp_offer_bucket_transfer(
+                    RuntimeOrigin::signed(Keyring::Charlie.to_account_id()),
+                    bucket_id,
+                    msp_dave_id,
+                ));
 
-                let msp_origin = RuntimeOrigin::signed(msp.clone());
+                let move_bucket_request_ttl: u32 = <Test as Config>::MoveBucketRequestTtl::get();
+                let expires_at = System::block_number() + move_bucket_request_ttl as BlockNumber;
+                assert_eq!(
+                    BucketTransferOffers::<Test>::get(bucket_id),
+                    Some(BucketTransferOffer {
+                        msp_id: msp_charlie_id,
+                        new_msp_id: msp_dave_id,
+                        expires_at,
+                    })
+                );
 
-                assert_ok!(FileSystem::pending_file_deletion_request_submit_proof(
-					msp_origin,
-					owner_account_id.clone(),
-					file_key,
-					size,
-					bucket_id,
-					forest_proof
-				));
+                // The offer does not lock the bucket until the owner accepts it.
+                assert!(!PendingBucketsToMove::<Test>::contains_key(&bucket_id));
 
-                // Assert that the correct event was deposited
                 System::assert_last_event(
-                    Event::ProofSubmittedForPendingFileDeletionRequest {
-                        msp_id,
-                        user: owner_account_id.clone(),
-                        file_key,
+                    Event::BucketTransferOffered {
                         bucket_id,
-                        proof_of_inclusion: false,
+                        msp_id: msp_charlie_id,
+                        new_msp_id: msp_dave_id,
+                        owner: Keyring::Alice.to_account_id(),
+                        expires_at,
                     }
-                        .into(),
-                );
-
-                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
-                assert!(
-					!pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
-						.iter()
-						.any(|x| *x == (file_key, Some(TrieRemoveMutation))),
-				);
-
-                // Assert that the pending file deletion request was removed from storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
+                    .into(),
                 );
             });
         }
 
         #[test]
-        fn delete_file_expired_pending_file_deletion_request_with_insolvent_user() {
+        fn accepted_bucket_transfer_offer_moves_bucket_once_new_msp_accepts() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
-                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let msp = Keyring::Charlie.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let file_content = b"test".to_vec();
-                let fingerprint = BlakeTwo256::hash(&file_content);
-                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
-                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
-
-				let (msp_id, _) = add_msp_to_provider_storage(&msp);
-
-				// Create a new value proposition with a high price per gigabyte per tick so deleting the 4 byte file will
-				// actually make a difference in the rate of the fixed-rate payment stream between the user and the MSP.
-				let value_prop = ValueProposition::<Test>::new(1024 * 1024 * 1024, bounded_vec![], 10000);
-    			let value_prop_id = value_prop.derive_id();
-    			pallet_storage_providers::MainStorageProviderIdsToValuePropositions::<Test>::insert(
-        			msp_id,
-        			value_prop_id,
-        			value_prop,
-    			);
-
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                let owner = Keyring::Alice.to_account_id();
+                let (bucket_id, msp_charlie_id, msp_dave_id) = setup_bucket();
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
+                assert_ok!(FileSystem::msp_offer_bucket_transfer(
+                    RuntimeOrigin::signed(Keyring::Charlie.to_account_id()),
                     bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
-                );
-
-                // Issue storage request
-                assert_ok!(FileSystem::issue_storage_request(
-                    owner_signed.clone(),
+                    msp_dave_id,
+                ));
+                assert_ok!(FileSystem::respond_bucket_transfer_offer(
+                    RuntimeOrigin::signed(owner.clone()),
                     bucket_id,
-                    location.clone(),
-                    fingerprint,
-                    size,
-                    Some(msp_id),
-                    peer_ids,
-                    Some(1)
+                    BucketMoveRequestResponse::Accepted,
                 ));
 
-                // Dispatch MSP confirm storing.
-                assert_ok!(FileSystem::msp_respond_storage_requests_multiple_buckets(
-                    RuntimeOrigin::signed(msp.clone()),
-                    bounded_vec![StorageRequestMspBucketResponse {
+                System::assert_has_event(
+                    Event::BucketTransferOfferAccepted {
                         bucket_id,
-                        accept: Some(StorageRequestMspAcceptedFileKeys {
-                            file_keys_and_proofs: bounded_vec![FileKeyWithProof {
-                                file_key,
-                                proof: CompactProof {
-                                    encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                                }
-                            }],
-                            non_inclusion_forest_proof: CompactProof {
-                                encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                            },
-                        }),
-                        reject: bounded_vec![],
-                    }],
-                ));
-
-                // Delete file
-                assert_ok!(FileSystem::delete_file(
-					owner_signed.clone(),
-					bucket_id,
-					file_key,
-					location,
-					size,
-					fingerprint,
-					None,
-				));
-
-                // Assert that the correct event was deposited
+                        msp_id: msp_charlie_id,
+                        new_msp_id: msp_dave_id,
+                    }
+                    .into(),
+                );
                 System::assert_last_event(
-                    Event::FileDeletionRequest {
-                        user: owner_account_id.clone(),
-                        file_key,
+                    Event::MoveBucketRequested {
+                        who: owner.clone(),
                         bucket_id,
-                        msp_id: Some(msp_id),
-                        proof_of_inclusion: false,
+                        new_msp_id: msp_dave_id,
                     }
-                        .into(),
-                );
-
-                // Assert that the pending file deletion request was added to storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::try_from(
-                        vec![PendingFileDeletionRequest {
-							file_key,
-							user: owner_account_id.clone(),
-							bucket_id,
-							file_size: size,
-						}]
-                    )
-                        .unwrap()
+                    .into(),
                 );
 
-                let pending_file_deletion_request_ttl: u32 =
-                    PendingFileDeletionRequestTtl::<Test>::get();
-                let pending_file_deletion_request_ttl: BlockNumberFor<Test> =
-                    pending_file_deletion_request_ttl.into();
-                let expiration_block = pending_file_deletion_request_ttl + System::block_number();
-
-                // Assert that the pending file deletion request was added to storage
+                // The offer became a regular move bucket request.
+                assert!(!BucketTransferOffers::<Test>::contains_key(bucket_id));
+                assert!(PendingBucketsToMove::<Test>::contains_key(&bucket_id));
                 assert_eq!(
-                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
-                    vec![FileDeletionRequestExpirationItem {
-                        user: owner_account_id.clone(),
-                        file_key,
-                        bucket_id,
-                        file_size: size,
-                    }]
+                    PendingMoveBucketRequests::<Test>::get(&msp_dave_id, bucket_id),
+                    Some(MoveBucketRequestMetadata { requester: owner })
                 );
 
-                pallet_payment_streams::UsersWithoutFunds::<Test>::insert(owner_account_id.clone(), System::block_number());
+                assert_ok!(FileSystem::msp_respond_move_bucket_request(
+                    RuntimeOrigin::signed(Keyring::Dave.to_account_id()),
+                    bucket_id,
+                    BucketMoveRequestResponse::Accepted
+                ));
 
-                // Roll past the expiration block
-                roll_to(pending_file_deletion_request_ttl + 1);
+                assert!(Providers::is_bucket_stored_by_msp(&msp_dave_id, &bucket_id));
+                assert!(!Providers::is_bucket_stored_by_msp(
+                    &msp_charlie_id,
+                    &bucket_id
+                ));
+            });
+        }
 
-                // Item expiration should be removed
-                assert_eq!(
-                    file_system::FileDeletionRequestExpirations::<Test>::get(expiration_block),
-                    vec![]
-                );
+        #[test]
+        fn rejected_bucket_transfer_offer_is_removed() {
+            new_test_ext().execute_with(|| {
+                let (bucket_id, msp_charlie_id, msp_dave_id) = setup_bucket();
 
-                // Assert that the pending file deletion request was removed from storage
-                assert_eq!(
-                    file_system::PendingFileDeletionRequests::<Test>::get(owner_account_id.clone()),
-                    BoundedVec::<_, <Test as file_system::Config>::MaxUserPendingDeletionRequests>::default()
-                );
+                assert_ok!(FileSystem::msp_offer_bucket_transfer(
+                    RuntimeOrigin::signed(Keyring::Charlie.to_account_id()),
+                    bucket_id,
+                    msp_dave_id,
+                ));
+                assert_ok!(FileSystem::respond_bucket_transfer_offer(
+                    RuntimeOrigin::signed(Keyring::Alice.to_account_id()),
+                    bucket_id,
+                    BucketMoveRequestResponse::Rejected,
+                ));
 
-                // Assert that the payment stream was correctly deleted since the user is without funds
-                assert!(pallet_payment_streams::FixedRatePaymentStreams::<Test>::get(msp_id, owner_account_id.clone()).is_none());
+                System::assert_last_event(
+                    Event::BucketTransferOfferRejected {
+                        bucket_id,
+                        msp_id: msp_charlie_id,
+                        new_msp_id: msp_dave_id,
+                    }
+                    .into(),
+                );
 
-                // Assert that there is a queued priority challenge for file key in proofs dealer pallet
-                assert!(pallet_proofs_dealer::PriorityChallengesQueue::<Test>::get()
-                .iter()
-                .any(|x| *x == (file_key, Some(TrieRemoveMutation))),);
+                assert!(!BucketTransferOffers::<Test>::contains_key(bucket_id));
+                assert!(!PendingBucketsToMove::<Test>::contains_key(&bucket_id));
+                assert!(Providers::is_bucket_stored_by_msp(
+                    &msp_charlie_id,
+                    &bucket_id
+                ));
             });
         }
     }
@@ -8664,7 +13760,8 @@ mod compute_threshold {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -8723,7 +13820,8 @@ mod compute_threshold {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -8857,7 +13955,8 @@ mod compute_threshold {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 let file_key = FileSystem::compute_file_key(
@@ -9128,7 +14227,8 @@ mod stop_storing_for_insolvent_user {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -9322,7 +14422,8 @@ mod stop_storing_for_insolvent_user {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -9487,7 +14588,8 @@ mod stop_storing_for_insolvent_user {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -9685,18 +14787,230 @@ mod stop_storing_for_insolvent_user {
                         fingerprint,
                         size,
                         CompactProof {
-                            encoded_nodes: vec![H256::zero().as_ref().to_vec()],
+                            encoded_nodes: vec![H256::zero().as_ref().to_vec()],
+                        },
+                    ),
+                    Error::<Test>::NotASp
+                );
+            });
+        }
+
+        #[test]
+        fn stop_storing_for_insolvent_user_fails_if_user_not_insolvent() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let bsp_account_id = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let storage_amount: StorageData<Test> = 100;
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Dispatch storage request.
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    None,
+                    false
+                ));
+
+                // Sign up account as a Backup Storage Provider
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), storage_amount));
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let bsp_id = Providers::get_provider_id(bsp_account_id.clone()).unwrap();
+
+                // Dispatch BSP volunteer.
+                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key,));
+
+                // Get the current tick number.
+                let tick_when_confirming = ProofsDealer::get_current_tick();
+
+                // Dispatch BSP confirm storing.
+                assert_ok!(FileSystem::bsp_confirm_storing(
+                    bsp_signed.clone(),
+                    CompactProof {
+                        encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                    },
+                    BoundedVec::try_from(vec![(
+                        file_key,
+                        CompactProof {
+                            encoded_nodes: vec![H256::default().as_ref().to_vec()],
+                        }
+                    )])
+                    .unwrap(),
+                ));
+
+                // Assert that the storage was updated
+                assert_eq!(
+                    file_system::StorageRequests::<Test>::get(file_key),
+                    Some(StorageRequestMetadata {
+                        requested_at: 1,
+                        owner: owner_account_id.clone(),
+                        bucket_id,
+                        location: location.clone(),
+                        fingerprint,
+                        size,
+                        msp: Some((msp_id, false)),
+                        user_peer_ids: peer_ids.clone(),
+                        bsps_required: <Test as Config>::DefaultReplicationTarget::get(),
+                        bsps_confirmed: 1,
+                        bsps_volunteered: 1,
+                    })
+                );
+
+                // Assert that the RequestStorageBsps was updated
+                assert_eq!(
+                    file_system::StorageRequestBsps::<Test>::get(file_key, bsp_id)
+                        .expect("BSP should exist in storage"),
+                    StorageRequestBspsMetadata::<Test> {
+                        confirmed: true,
+                        _phantom: Default::default()
+                    }
+                );
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                let new_root = Providers::get_root(bsp_id).unwrap();
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::BspConfirmedStoring {
+                        who: bsp_account_id.clone(),
+                        bsp_id,
+                        confirmed_file_keys: BoundedVec::try_from(vec![file_key]).unwrap(),
+                        skipped_file_keys: Default::default(),
+                        new_root,
+                    }
+                    .into(),
+                );
+
+                // Assert that the proving cycle was initialised for this BSP.
+                let proof_record = ProviderToProofSubmissionRecord::<Test>::get(&bsp_id).unwrap();
+                assert_eq!(proof_record.last_tick_proven, tick_when_confirming);
+
+                // Assert that the correct event was deposited.
+                System::assert_has_event(
+                    Event::BspChallengeCycleInitialised {
+                        who: bsp_account_id,
+                        bsp_id,
+                    }
+                    .into(),
+                );
+
+                // Assert that the randomness cycle was initialised for this BSP.
+                let maybe_first_randomness_provider_deadline =
+                    pallet_cr_randomness::ProvidersWithoutCommitment::<Test>::get(&bsp_id);
+                assert!(maybe_first_randomness_provider_deadline.is_some());
+                assert!(pallet_cr_randomness::ActiveProviders::<Test>::get(&bsp_id).is_some());
+
+                // Assert that the correct event was deposited.
+                let first_randomness_provider_deadline =
+                    maybe_first_randomness_provider_deadline.unwrap();
+                System::assert_has_event(
+                    pallet_cr_randomness::Event::ProviderCycleInitialised {
+                        provider_id: bsp_id,
+                        first_seed_commitment_deadline_tick: first_randomness_provider_deadline,
+                    }
+                    .into(),
+                );
+
+                // Now that the BSP has confirmed storing, we can simulate the user being not insolvent
+                // and the BSP trying to stop storing a file for the user using the stop_storing_for_insolvent_user function.
+                assert_noop!(
+                    FileSystem::stop_storing_for_insolvent_user(
+                        bsp_signed.clone(),
+                        file_key,
+                        bucket_id,
+                        location.clone(),
+                        owner_account_id.clone(),
+                        fingerprint,
+                        size,
+                        CompactProof {
+                            encoded_nodes: vec![file_key.as_ref().to_vec()],
+                        },
+                    ),
+                    Error::<Test>::UserNotInsolvent
+                );
+            });
+        }
+
+        #[test]
+        fn stop_storing_for_insolvent_user_fails_if_caller_not_owner_of_bucket() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Eve.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let another_msp = Keyring::Dave.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                add_msp_to_provider_storage(&another_msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let file_key = FileSystem::compute_file_key(
+                    owner_account_id.clone(),
+                    bucket_id,
+                    location.clone(),
+                    size,
+                    fingerprint,
+                );
+
+                // Try to stop storing for the insolvent user using another MSP account.
+                assert_noop!(
+                    FileSystem::stop_storing_for_insolvent_user(
+                        RuntimeOrigin::signed(another_msp.clone()),
+                        file_key,
+                        bucket_id,
+                        location.clone(),
+                        owner_account_id.clone(),
+                        fingerprint,
+                        size,
+                        CompactProof {
+                            encoded_nodes: vec![file_key.as_ref().to_vec()],
                         },
                     ),
-                    Error::<Test>::NotASp
+                    Error::<Test>::MspNotStoringBucket
                 );
             });
         }
 
         #[test]
-        fn stop_storing_for_insolvent_user_fails_if_user_not_insolvent() {
+        fn stop_storing_for_insolvent_user_fails_if_proof_does_not_contain_file_key() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_account_id = Keyring::Eve.to_account_id();
                 let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
                 let bsp_account_id = Keyring::Bob.to_account_id();
                 let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
@@ -9723,7 +15037,8 @@ mod stop_storing_for_insolvent_user {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
 
                 // Sign up account as a Backup Storage Provider
@@ -9840,8 +15155,8 @@ mod stop_storing_for_insolvent_user {
                     .into(),
                 );
 
-                // Now that the BSP has confirmed storing, we can simulate the user being not insolvent
-                // and the BSP trying to stop storing a file for the user using the stop_storing_for_insolvent_user function.
+                // Now that the BSP has confirmed storing, we can simulate the user being insolvent
+                // and the BSP trying to stop storing for that user with an incorrect inclusion proof.
                 assert_noop!(
                     FileSystem::stop_storing_for_insolvent_user(
                         bsp_signed.clone(),
@@ -9852,80 +15167,176 @@ mod stop_storing_for_insolvent_user {
                         fingerprint,
                         size,
                         CompactProof {
-                            encoded_nodes: vec![file_key.as_ref().to_vec()],
+                            encoded_nodes: vec![H256::zero().as_ref().to_vec()],
                         },
                     ),
-                    Error::<Test>::UserNotInsolvent
+                    Error::<Test>::ExpectedInclusionProof
                 );
             });
         }
+    }
+}
+
+mod msp_stop_storing_bucket {
+    use super::*;
+    mod failure {
+        use super::*;
 
         #[test]
-        fn stop_storing_for_insolvent_user_fails_if_caller_not_owner_of_bucket() {
+        fn msp_not_registered() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Eve.to_account_id();
                 let msp = Keyring::Charlie.to_account_id();
-                let another_msp = Keyring::Dave.to_account_id();
-                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
-                let size = 4;
-                let fingerprint = H256::zero();
+                let owner_account_id = Keyring::Alice.to_account_id();
 
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
-                add_msp_to_provider_storage(&another_msp);
 
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id =
                     create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
-                let file_key = FileSystem::compute_file_key(
-                    owner_account_id.clone(),
-                    bucket_id,
-                    location.clone(),
-                    size,
-                    fingerprint,
+                let none_registered_msp = Keyring::Dave.to_account_id();
+                let none_registered_msp_signed = RuntimeOrigin::signed(none_registered_msp.clone());
+
+                // Try to stop storing for the bucket.
+                assert_noop!(
+                    FileSystem::msp_stop_storing_bucket(none_registered_msp_signed, bucket_id),
+                    Error::<Test>::NotAMsp
                 );
+            });
+        }
 
-                // Try to stop storing for the insolvent user using another MSP account.
+        #[test]
+        fn msp_not_storing_bucket() {
+            new_test_ext().execute_with(|| {
+                let msp = Keyring::Charlie.to_account_id();
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let another_msp = Keyring::Dave.to_account_id();
+                add_msp_to_provider_storage(&another_msp);
+                let another_msp_signed = RuntimeOrigin::signed(another_msp.clone());
+
+                // Try to stop storing for the bucket.
                 assert_noop!(
-                    FileSystem::stop_storing_for_insolvent_user(
-                        RuntimeOrigin::signed(another_msp.clone()),
-                        file_key,
-                        bucket_id,
-                        location.clone(),
-                        owner_account_id.clone(),
-                        fingerprint,
-                        size,
-                        CompactProof {
-                            encoded_nodes: vec![file_key.as_ref().to_vec()],
-                        },
-                    ),
+                    FileSystem::msp_stop_storing_bucket(another_msp_signed, bucket_id),
                     Error::<Test>::MspNotStoringBucket
                 );
             });
         }
+    }
+
+    mod success {
+        use super::*;
 
         #[test]
-        fn stop_storing_for_insolvent_user_fails_if_proof_does_not_contain_file_key() {
+        fn msp_stop_storing_bucket_works_payment_stream_deleted() {
             new_test_ext().execute_with(|| {
-                let owner_account_id = Keyring::Eve.to_account_id();
+                let msp = Keyring::Charlie.to_account_id();
+                let msp_signed = RuntimeOrigin::signed(msp.clone());
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Dispatch MSP stop storing bucket.
+                assert_ok!(FileSystem::msp_stop_storing_bucket(msp_signed, bucket_id));
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::MspStoppedStoringBucket {
+                        msp_id,
+                        bucket_id,
+                        owner: owner_account_id.clone(),
+                    }
+                    .into(),
+                );
+
+                // Check that the payment stream between the user and the MSP was deleted since there are no more buckets stored by the MSP for the user.
+                assert!(!<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(&msp_id, &owner_account_id));
+            });
+        }
+
+        #[test]
+        fn msp_stop_storing_bucket_works_payment_stream_updated() {
+            new_test_ext().execute_with(|| {
+                let msp = Keyring::Charlie.to_account_id();
+                let msp_signed = RuntimeOrigin::signed(msp.clone());
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let owner_account_id = Keyring::Alice.to_account_id();
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                let another_name = BoundedVec::try_from(b"another_bucket".to_vec()).unwrap();
+                create_bucket(&owner_account_id.clone(), another_name, msp_id, value_prop_id);
+
+                // Dispatch MSP stop storing bucket.
+                assert_ok!(FileSystem::msp_stop_storing_bucket(msp_signed, bucket_id));
+
+                // Assert that the correct event was deposited
+                System::assert_last_event(
+                    Event::MspStoppedStoringBucket {
+                        msp_id,
+                        bucket_id,
+                        owner: owner_account_id.clone(),
+                    }
+                    .into(),
+                );
+
+                // Check that the payment stream between the user and the MSP was updated since there are still buckets stored by the MSP for the user.
+                assert!(
+                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(&msp_id, &owner_account_id)
+                );
+            });
+        }
+    }
+}
+
+mod provider_status_change_tests {
+    use super::*;
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn unavailable_msp_storage_requests_and_move_bucket_requests_are_cancelled() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
                 let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
-                let bsp_account_id = Keyring::Bob.to_account_id();
-                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
                 let msp = Keyring::Charlie.to_account_id();
+                let other_msp = Keyring::Dave.to_account_id();
                 let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
                 let size = 4;
                 let fingerprint = H256::zero();
                 let peer_id = BoundedVec::try_from(vec![1]).unwrap();
                 let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
-                let storage_amount: StorageData<Test> = 100;
+                let deposit_hold_reason = RuntimeHoldReason::FileSystem(
+                    file_system::HoldReason::StorageRequestCreationHold,
+                );
 
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let (other_msp_id, other_value_prop_id) = add_msp_to_provider_storage(&other_msp);
 
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id =
                     create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                let other_name = BoundedVec::try_from(b"other-bucket".to_vec()).unwrap();
+                let other_bucket_id = create_bucket(
+                    &owner_account_id.clone(),
+                    other_name,
+                    other_msp_id,
+                    other_value_prop_id,
+                );
 
-                // Dispatch storage request.
+                // Issue a storage request that the MSP does not accept before becoming insolvent.
                 assert_ok!(FileSystem::issue_storage_request(
                     owner_signed.clone(),
                     bucket_id,
@@ -9934,12 +15345,9 @@ mod stop_storing_for_insolvent_user {
                     size,
                     Some(msp_id),
                     peer_ids.clone(),
-                    None
+                    None,
+                    false
                 ));
-
-                // Sign up account as a Backup Storage Provider
-                assert_ok!(bsp_sign_up(bsp_signed.clone(), storage_amount));
-
                 let file_key = FileSystem::compute_file_key(
                     owner_account_id.clone(),
                     bucket_id,
@@ -9948,57 +15356,96 @@ mod stop_storing_for_insolvent_user {
                     fingerprint,
                 );
 
-                let bsp_id = Providers::get_provider_id(bsp_account_id.clone()).unwrap();
+                // Request to move the other bucket to the MSP.
+                assert_ok!(FileSystem::request_move_bucket(
+                    owner_signed.clone(),
+                    other_bucket_id,
+                    msp_id
+                ));
 
-                // Dispatch BSP volunteer.
-                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key,));
+                FileSystem::on_provider_status_change(&msp_id, ProviderStatusChange::Insolvent);
+                assert_eq!(
+                    ProvidersWithStatusChange::<Test>::get(msp_id),
+                    Some(ProviderStatusChange::Insolvent)
+                );
 
-                // Get the current tick number.
-                let tick_when_confirming = ProofsDealer::get_current_tick();
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
 
-                // Dispatch BSP confirm storing.
-                assert_ok!(FileSystem::bsp_confirm_storing(
-                    bsp_signed.clone(),
-                    CompactProof {
-                        encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                    },
-                    BoundedVec::try_from(vec![(
+                // The storage request is cancelled and its deposit returned.
+                System::assert_has_event(
+                    Event::StorageRequestCancelledByUnavailableMsp {
                         file_key,
-                        CompactProof {
-                            encoded_nodes: vec![H256::default().as_ref().to_vec()],
-                        }
-                    )])
-                    .unwrap(),
-                ));
-
-                // Assert that the storage was updated
-                assert_eq!(
-                    file_system::StorageRequests::<Test>::get(file_key),
-                    Some(StorageRequestMetadata {
-                        requested_at: 1,
                         owner: owner_account_id.clone(),
-                        bucket_id,
-                        location: location.clone(),
-                        fingerprint,
-                        size,
-                        msp: Some((msp_id, false)),
-                        user_peer_ids: peer_ids.clone(),
-                        bsps_required: <Test as Config>::DefaultReplicationTarget::get(),
-                        bsps_confirmed: 1,
-                        bsps_volunteered: 1,
-                    })
+                        msp_id,
+                        status: ProviderStatusChange::Insolvent,
+                    }
+                    .into(),
+                );
+                assert!(StorageRequests::<Test>::get(file_key).is_none());
+                assert_eq!(
+                    <Test as Config>::Currency::balance_on_hold(
+                        &deposit_hold_reason,
+                        &owner_account_id
+                    ),
+                    0
+                );
+
+                // The move bucket request is cancelled, so the bucket can be moved somewhere else.
+                System::assert_has_event(
+                    Event::MoveBucketRequestCancelledByUnavailableMsp {
+                        bucket_id: other_bucket_id,
+                        msp_id,
+                        status: ProviderStatusChange::Insolvent,
+                    }
+                    .into(),
+                );
+                assert!(PendingMoveBucketRequests::<Test>::get(msp_id, other_bucket_id).is_none());
+                assert!(!PendingBucketsToMove::<Test>::contains_key(other_bucket_id));
+
+                System::assert_last_event(
+                    Event::ProviderStatusChangeProcessed {
+                        provider_id: msp_id,
+                        status: ProviderStatusChange::Insolvent,
+                    }
+                    .into(),
                 );
+                assert!(ProvidersWithStatusChange::<Test>::get(msp_id).is_none());
+                assert!(ProviderStatusChangeInProgress::<Test>::get().is_none());
+            });
+        }
+
+        #[test]
+        fn unavailable_bsp_volunteer_is_removed_from_storage_request() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let bsp_account_id = Keyring::Bob.to_account_id();
+                let bsp_signed = RuntimeOrigin::signed(bsp_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let storage_amount: StorageData<Test> = 100;
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
 
-                // Assert that the RequestStorageBsps was updated
-                assert_eq!(
-                    file_system::StorageRequestBsps::<Test>::get(file_key, bsp_id)
-                        .expect("BSP should exist in storage"),
-                    StorageRequestBspsMetadata::<Test> {
-                        confirmed: true,
-                        _phantom: Default::default()
-                    }
-                );
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
 
+                assert_ok!(FileSystem::issue_storage_request(
+                    owner_signed.clone(),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids.clone(),
+                    None,
+                    false
+                ));
                 let file_key = FileSystem::compute_file_key(
                     owner_account_id.clone(),
                     bucket_id,
@@ -10007,190 +15454,349 @@ mod stop_storing_for_insolvent_user {
                     fingerprint,
                 );
 
-                let new_root = Providers::get_root(bsp_id).unwrap();
+                assert_ok!(bsp_sign_up(bsp_signed.clone(), storage_amount));
+                let bsp_id = Providers::get_provider_id(bsp_account_id).unwrap();
 
-                // Assert that the correct event was deposited
-                System::assert_last_event(
-                    Event::BspConfirmedStoring {
-                        who: bsp_account_id.clone(),
-                        bsp_id,
-                        confirmed_file_keys: BoundedVec::try_from(vec![file_key]).unwrap(),
-                        skipped_file_keys: Default::default(),
-                        new_root,
-                    }
-                    .into(),
-                );
+                assert_ok!(FileSystem::bsp_volunteer(bsp_signed.clone(), file_key));
 
-                // Assert that the proving cycle was initialised for this BSP.
-                let proof_record = ProviderToProofSubmissionRecord::<Test>::get(&bsp_id).unwrap();
-                assert_eq!(proof_record.last_tick_proven, tick_when_confirming);
+                FileSystem::on_provider_status_change(&bsp_id, ProviderStatusChange::Deregistered);
 
-                // Assert that the correct event was deposited.
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+
+                // The BSP is removed, freeing its slot, but the storage request stays open.
                 System::assert_has_event(
-                    Event::BspChallengeCycleInitialised {
-                        who: bsp_account_id,
+                    Event::UnavailableBspVolunteerRemoved {
+                        file_key,
                         bsp_id,
+                        status: ProviderStatusChange::Deregistered,
                     }
                     .into(),
                 );
+                assert!(file_system::StorageRequestBsps::<Test>::get(file_key, bsp_id).is_none());
+                let storage_request_metadata = StorageRequests::<Test>::get(file_key)
+                    .expect("Storage request should still exist");
+                assert_eq!(storage_request_metadata.bsps_volunteered, 0);
+                assert_eq!(storage_request_metadata.msp, Some((msp_id, false)));
 
-                // Assert that the randomness cycle was initialised for this BSP.
-                let maybe_first_randomness_provider_deadline =
-                    pallet_cr_randomness::ProvidersWithoutCommitment::<Test>::get(&bsp_id);
-                assert!(maybe_first_randomness_provider_deadline.is_some());
-                assert!(pallet_cr_randomness::ActiveProviders::<Test>::get(&bsp_id).is_some());
-
-                // Assert that the correct event was deposited.
-                let first_randomness_provider_deadline =
-                    maybe_first_randomness_provider_deadline.unwrap();
-                System::assert_has_event(
-                    pallet_cr_randomness::Event::ProviderCycleInitialised {
+                System::assert_last_event(
+                    Event::ProviderStatusChangeProcessed {
                         provider_id: bsp_id,
-                        first_seed_commitment_deadline_tick: first_randomness_provider_deadline,
+                        status: ProviderStatusChange::Deregistered,
                     }
                     .into(),
                 );
+            });
+        }
 
-                // Now that the BSP has confirmed storing, we can simulate the user being insolvent
-                // and the BSP trying to stop storing for that user with an incorrect inclusion proof.
-                assert_noop!(
-                    FileSystem::stop_storing_for_insolvent_user(
-                        bsp_signed.clone(),
-                        file_key,
+        #[test]
+        fn provider_status_change_is_processed_over_several_blocks() {
+            new_test_ext().execute_with(|| {
+                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner_signed = RuntimeOrigin::signed(owner_account_id.clone());
+                let msp = Keyring::Charlie.to_account_id();
+                let size = 4;
+                let fingerprint = H256::zero();
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+                let max_items: u32 = <Test as Config>::MaxProviderStatusChangeItemsPerBlock::get();
+
+                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+
+                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id =
+                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+
+                // Issue more storage requests than can be checked in a single block.
+                let storage_requests = max_items + 2;
+                for i in 0..storage_requests {
+                    let location =
+                        FileLocation::<Test>::try_from(format!("test-{}", i).into_bytes()).unwrap();
+                    assert_ok!(FileSystem::issue_storage_request(
+                        owner_signed.clone(),
                         bucket_id,
-                        location.clone(),
-                        owner_account_id.clone(),
+                        location,
                         fingerprint,
                         size,
-                        CompactProof {
-                            encoded_nodes: vec![H256::zero().as_ref().to_vec()],
-                        },
-                    ),
-                    Error::<Test>::ExpectedInclusionProof
+                        Some(msp_id),
+                        peer_ids.clone(),
+                        None,
+                        false
+                    ));
+                }
+
+                FileSystem::on_provider_status_change(&msp_id, ProviderStatusChange::Deregistered);
+
+                // Picking up the MSP takes one item, and the rest are used to check storage requests.
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+                assert_eq!(
+                    StorageRequests::<Test>::iter().count() as u32,
+                    storage_requests - (max_items - 1)
+                );
+                assert!(ProviderStatusChangeInProgress::<Test>::get().is_some());
+
+                // The remaining storage requests are checked the next time the hook runs.
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+                assert_eq!(StorageRequests::<Test>::iter().count(), 0);
+                assert!(ProviderStatusChangeInProgress::<Test>::get().is_none());
+                System::assert_last_event(
+                    Event::ProviderStatusChangeProcessed {
+                        provider_id: msp_id,
+                        status: ProviderStatusChange::Deregistered,
+                    }
+                    .into(),
                 );
             });
         }
     }
 }
 
-mod msp_stop_storing_bucket {
+mod deposit_transfer_retry_tests {
     use super::*;
-    mod failure {
+
+    /// Issues a storage request from `owner`, holding the storage request creation deposit, and returns
+    /// the deposit.
+    fn issue_storage_request_holding_deposit(owner: &sp_runtime::AccountId32) -> BalanceOf<Test> {
+        let msp = Keyring::Charlie.to_account_id();
+        let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+        let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+        let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
+
+        let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+        let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+        let bucket_id = create_bucket(owner, name, msp_id, value_prop_id);
+
+        assert_ok!(FileSystem::issue_storage_request(
+            RuntimeOrigin::signed(owner.clone()),
+            bucket_id,
+            location,
+            H256::zero(),
+            4,
+            Some(msp_id),
+            peer_ids,
+            None,
+            false
+        ));
+
+        <Test as Config>::StorageRequestCreationDeposit::get()
+    }
+
+    fn creation_deposit_held(owner: &sp_runtime::AccountId32) -> BalanceOf<Test> {
+        <Test as Config>::Currency::balance_on_hold(
+            &RuntimeHoldReason::FileSystem(file_system::HoldReason::StorageRequestCreationHold),
+            owner,
+        )
+    }
+
+    mod success {
         use super::*;
 
         #[test]
-        fn msp_not_registered() {
+        fn due_deposit_transfer_retries_are_executed_in_on_idle() {
             new_test_ext().execute_with(|| {
-                let msp = Keyring::Charlie.to_account_id();
-                let owner_account_id = Keyring::Alice.to_account_id();
+                let owner = Keyring::Alice.to_account_id();
+                let deposit = issue_storage_request_holding_deposit(&owner);
+                assert_eq!(creation_deposit_held(&owner), deposit);
 
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let transfer = DepositTransfer::StorageRequestCreationDeposit {
+                    owner: owner.clone(),
+                    amount: deposit,
+                };
+                let retry_id = FileSystem::queue_deposit_transfer_retry(transfer.clone()).unwrap();
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id =
-                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                // The retry is not due yet.
+                FileSystem::on_idle(System::block_number(), Weight::MAX);
+                assert!(DepositTransferRetries::<Test>::contains_key(retry_id));
+                assert_eq!(creation_deposit_held(&owner), deposit);
 
-                let none_registered_msp = Keyring::Dave.to_account_id();
-                let none_registered_msp_signed = RuntimeOrigin::signed(none_registered_msp.clone());
+                // Once due, it is retried and removed from the queue.
+                let base_delay: u64 = <Test as Config>::DepositTransferRetryBaseDelay::get();
+                roll_to(System::block_number() + base_delay);
 
-                // Try to stop storing for the bucket.
-                assert_noop!(
-                    FileSystem::msp_stop_storing_bucket(none_registered_msp_signed, bucket_id),
-                    Error::<Test>::NotAMsp
+                assert!(!DepositTransferRetries::<Test>::contains_key(retry_id));
+                assert_eq!(creation_deposit_held(&owner), 0);
+                System::assert_has_event(
+                    Event::DepositTransferRetrySucceeded { retry_id, transfer }.into(),
                 );
             });
         }
 
         #[test]
-        fn msp_not_storing_bucket() {
+        fn resolve_deposit_transfer_retry_retries_or_writes_off() {
             new_test_ext().execute_with(|| {
-                let msp = Keyring::Charlie.to_account_id();
-                let owner_account_id = Keyring::Alice.to_account_id();
-
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
+                let owner = Keyring::Alice.to_account_id();
+                let deposit = issue_storage_request_holding_deposit(&owner);
 
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id =
-                    create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                let transfer = DepositTransfer::StorageRequestCreationDeposit {
+                    owner: owner.clone(),
+                    amount: deposit,
+                };
+                let written_off_id =
+                    FileSystem::queue_deposit_transfer_retry(transfer.clone()).unwrap();
+                let retried_id =
+                    FileSystem::queue_deposit_transfer_retry(transfer.clone()).unwrap();
 
-                let another_msp = Keyring::Dave.to_account_id();
-                add_msp_to_provider_storage(&another_msp);
-                let another_msp_signed = RuntimeOrigin::signed(another_msp.clone());
+                // Writing off a retry removes it without executing it.
+                assert_ok!(FileSystem::resolve_deposit_transfer_retry(
+                    RuntimeOrigin::root(),
+                    written_off_id,
+                    DepositTransferResolution::WriteOff
+                ));
+                assert!(!DepositTransferRetries::<Test>::contains_key(
+                    written_off_id
+                ));
+                assert_eq!(creation_deposit_held(&owner), deposit);
+                System::assert_last_event(
+                    Event::DepositTransferWrittenOff {
+                        retry_id: written_off_id,
+                        transfer: transfer.clone(),
+                    }
+                    .into(),
+                );
 
-                // Try to stop storing for the bucket.
-                assert_noop!(
-                    FileSystem::msp_stop_storing_bucket(another_msp_signed, bucket_id),
-                    Error::<Test>::MspNotStoringBucket
+                // Retrying it executes it right away, even if it is not due yet.
+                assert_ok!(FileSystem::resolve_deposit_transfer_retry(
+                    RuntimeOrigin::root(),
+                    retried_id,
+                    DepositTransferResolution::Retry
+                ));
+                assert!(!DepositTransferRetries::<Test>::contains_key(retried_id));
+                assert_eq!(creation_deposit_held(&owner), 0);
+                System::assert_last_event(
+                    Event::DepositTransferRetrySucceeded {
+                        retry_id: retried_id,
+                        transfer,
+                    }
+                    .into(),
                 );
             });
         }
     }
 
-    mod success {
+    mod failure {
         use super::*;
 
         #[test]
-        fn msp_stop_storing_bucket_works_payment_stream_deleted() {
+        fn resolve_deposit_transfer_retry_fails_if_not_root_or_not_found() {
             new_test_ext().execute_with(|| {
-                let msp = Keyring::Charlie.to_account_id();
-                let msp_signed = RuntimeOrigin::signed(msp.clone());
-                let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
-
-                let owner_account_id = Keyring::Alice.to_account_id();
-
-                let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
-
-                // Dispatch MSP stop storing bucket.
-                assert_ok!(FileSystem::msp_stop_storing_bucket(msp_signed, bucket_id));
+                let owner = Keyring::Alice.to_account_id();
+                let retry_id = FileSystem::queue_deposit_transfer_retry(
+                    DepositTransfer::StorageRequestEscrow {
+                        file_key: H256::zero(),
+                    },
+                )
+                .unwrap();
 
-                // Assert that the correct event was deposited
-                System::assert_last_event(
-                    Event::MspStoppedStoringBucket {
-                        msp_id,
-                        bucket_id,
-                        owner: owner_account_id.clone(),
-                    }
-                    .into(),
+                assert_noop!(
+                    FileSystem::resolve_deposit_transfer_retry(
+                        RuntimeOrigin::signed(owner),
+                        retry_id,
+                        DepositTransferResolution::WriteOff
+                    ),
+                    DispatchError::BadOrigin
                 );
 
-                // Check that the payment stream between the user and the MSP was deleted since there are no more buckets stored by the MSP for the user.
-                assert!(!<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(&msp_id, &owner_account_id));
+                assert_noop!(
+                    FileSystem::resolve_deposit_transfer_retry(
+                        RuntimeOrigin::root(),
+                        retry_id + 1,
+                        DepositTransferResolution::WriteOff
+                    ),
+                    Error::<Test>::DepositTransferRetryNotFound
+                );
             });
         }
 
         #[test]
-        fn msp_stop_storing_bucket_works_payment_stream_updated() {
+        fn deposit_transfer_failing_partway_is_rolled_back_before_being_queued() {
             new_test_ext().execute_with(|| {
+                let owner = Keyring::Alice.to_account_id();
                 let msp = Keyring::Charlie.to_account_id();
-                let msp_signed = RuntimeOrigin::signed(msp.clone());
+                let peer_id = BoundedVec::try_from(vec![1]).unwrap();
+                let peer_ids: PeerIds<Test> = BoundedVec::try_from(vec![peer_id]).unwrap();
                 let (msp_id, value_prop_id) = add_msp_to_provider_storage(&msp);
-
-                let owner_account_id = Keyring::Alice.to_account_id();
-
                 let name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = create_bucket(&owner_account_id.clone(), name, msp_id, value_prop_id);
+                let bucket_id = create_bucket(&owner, name, msp_id, value_prop_id);
+                let location = FileLocation::<Test>::try_from(b"test".to_vec()).unwrap();
+                let fingerprint = BlakeTwo256::hash(b"test");
+                let size = 4;
 
-                let another_name = BoundedVec::try_from(b"another_bucket".to_vec()).unwrap();
-                create_bucket(&owner_account_id.clone(), another_name, msp_id, value_prop_id);
+                assert_ok!(FileSystem::issue_escrowed_storage_request(
+                    RuntimeOrigin::signed(owner.clone()),
+                    bucket_id,
+                    location.clone(),
+                    fingerprint,
+                    size,
+                    Some(msp_id),
+                    peer_ids,
+                    None,
+                    10,
+                ));
+                let file_key = FileSystem::compute_file_key(
+                    owner.clone(),
+                    bucket_id,
+                    location,
+                    size,
+                    fingerprint,
+                );
+                let escrow_held = || {
+                    <Test as Config>::Currency::balance_on_hold(
+                        &RuntimeHoldReason::PaymentStreams(
+                            pallet_payment_streams::HoldReason::EscrowedPayment,
+                        ),
+                        &owner,
+                    )
+                };
+                let held = escrow_held();
+                assert!(held > 0);
 
-                // Dispatch MSP stop storing bucket.
-                assert_ok!(FileSystem::msp_stop_storing_bucket(msp_signed, bucket_id));
+                // Settling the escrow refunds the share of the missing BSPs, and then fails to refund
+                // what is left since that can't be deposited.
+                pallet_payment_streams::PaymentEscrows::<Test>::mutate(&file_key, |escrow| {
+                    escrow.as_mut().unwrap().amount_held = BalanceOf::<Test>::MAX;
+                });
+                let escrow = pallet_payment_streams::PaymentEscrows::<Test>::get(&file_key);
 
-                // Assert that the correct event was deposited
-                System::assert_last_event(
-                    Event::MspStoppedStoringBucket {
-                        msp_id,
-                        bucket_id,
-                        owner: owner_account_id.clone(),
-                    }
-                    .into(),
-                );
+                let transfer = DepositTransfer::StorageRequestEscrow { file_key };
+                FileSystem::execute_deposit_transfer_or_queue_retry(transfer.clone());
 
-                // Check that the payment stream between the user and the MSP was updated since there are still buckets stored by the MSP for the user.
-                assert!(
-                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(&msp_id, &owner_account_id)
+                // The refund made before failing is rolled back, so the retry does not repeat it.
+                assert_eq!(escrow_held(), held);
+                assert_eq!(
+                    pallet_payment_streams::PaymentEscrows::<Test>::get(&file_key),
+                    escrow
                 );
+                assert!(!System::events().iter().any(|record| matches!(
+                    record.event,
+                    RuntimeEvent::PaymentStreams(
+                        pallet_payment_streams::Event::EscrowShortfallRefunded { .. }
+                    )
+                )));
+                assert_eq!(DepositTransferRetries::<Test>::count(), 1);
+                assert!(matches!(
+                    System::events().last().map(|record| &record.event),
+                    Some(RuntimeEvent::FileSystem(Event::DepositTransferFailed {
+                        transfer: failed,
+                        retry_id: Some(_),
+                        ..
+                    })) if *failed == transfer
+                ));
+            });
+        }
+
+        #[test]
+        fn failed_deposit_transfers_are_not_queued_if_the_retry_queue_is_full() {
+            new_test_ext().execute_with(|| {
+                let max_retries: u32 = <Test as Config>::MaxDepositTransferRetries::get();
+                let transfer = DepositTransfer::StorageRequestEscrow {
+                    file_key: H256::zero(),
+                };
+
+                for _ in 0..max_retries {
+                    assert!(FileSystem::queue_deposit_transfer_retry(transfer.clone()).is_some());
+                }
+
+                assert_eq!(FileSystem::queue_deposit_transfer_retry(transfer), None);
+                assert_eq!(DepositTransferRetries::<Test>::count(), max_retries);
             });
         }
     }
@@ -10283,7 +15889,8 @@ fn create_bucket(
         Some(msp_id),
         name.clone(),
         false,
-        Some(value_prop_id)
+        Some(value_prop_id),
+        None
     ));
 
     // Assert bucket was created
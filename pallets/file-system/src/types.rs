@@ -9,7 +9,7 @@ use frame_system::pallet_prelude::BlockNumberFor;
 use pallet_nfts::CollectionConfig;
 use scale_info::TypeInfo;
 use shp_file_metadata::FileMetadata;
-use shp_traits::{MutateBucketsInterface, ReadProvidersInterface};
+use shp_traits::{MutateBucketsInterface, ProviderStatusChange, ReadProvidersInterface};
 use sp_runtime::{traits::CheckedAdd, DispatchError};
 use sp_std::fmt::Debug;
 
@@ -134,6 +134,29 @@ impl<T: Config> Debug for StorageRequestMspAcceptedFileKeys<T> {
     }
 }
 
+/// The metadata of a file to delete, as part of a batch of deletion requests.
+///
+/// Carries everything needed to recompute the file key, so that the batch only covers files
+/// owned by the user submitting it.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct FileDeletionRequestItem<T: Config> {
+    pub file_key: MerkleHash<T>,
+    pub location: FileLocation<T>,
+    pub size: StorageData<T>,
+    pub fingerprint: Fingerprint<T>,
+}
+
+impl<T: Config> Debug for FileDeletionRequestItem<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "FileDeletionRequestItem(file_key: {:?}, location: {:?}, size: {:?}, fingerprint: {:?})",
+            self.file_key, self.location, self.size, self.fingerprint
+        )
+    }
+}
+
 #[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
 pub enum RejectedStorageRequestReason {
     ReachedMaximumCapacity,
@@ -222,6 +245,40 @@ pub struct PendingStopStoringRequest<T: Config> {
     pub file_size: StorageData<T>,
 }
 
+/// A move of a file to a new location within its bucket, requested by its owner.
+///
+/// Since the location of a file is part of its metadata, moving it changes its file key. This holds the
+/// metadata of the file at its new location, under which it is stored once the move is applied.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct FileMove<T: Config> {
+    pub owner: T::AccountId,
+    pub bucket_id: BucketIdFor<T>,
+    pub new_file_key: MerkleHash<T>,
+    pub new_location: FileLocation<T>,
+    pub size: StorageData<T>,
+    pub fingerprint: Fingerprint<T>,
+}
+
+impl<T: Config> FileMove<T> {
+    /// The metadata of the file at its new location.
+    pub fn to_file_metadata(
+        self,
+    ) -> FileMetadata<
+        { shp_constants::H_LENGTH },
+        { shp_constants::FILE_CHUNK_SIZE },
+        { shp_constants::FILE_SIZE_TO_CHALLENGES },
+    > {
+        FileMetadata {
+            owner: self.owner.encode(),
+            bucket_id: self.bucket_id.as_ref().to_vec(),
+            location: self.new_location.to_vec(),
+            file_size: self.size.into() as u64,
+            fingerprint: self.fingerprint.as_ref().into(),
+        }
+    }
+}
+
 #[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
 #[scale_info(skip_type_params(T))]
 pub enum ExpirationItem<T: Config> {
@@ -308,6 +365,16 @@ pub enum BucketMoveRequestResponse {
     Rejected,
 }
 
+/// How the MSP eligibility list is interpreted when checking if an MSP can take new buckets.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MspEligibilityListMode {
+    /// Every MSP is eligible except the ones in the list.
+    #[default]
+    Denylist,
+    /// Only the MSPs in the list are eligible.
+    Allowlist,
+}
+
 /// Move bucket request metadata
 #[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
 #[scale_info(skip_type_params(T))]
@@ -336,6 +403,181 @@ impl<T: Config> Debug for EitherAccountIdOrMspId<T> {
     }
 }
 
+/// Priority challenges for file deletions queued directly by an issuer in a metering period.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct PriorityChallengeUsage<T: Config> {
+    /// Block at which the metering period started.
+    pub period_start: BlockNumberFor<T>,
+    /// Number of priority challenges queued directly in the metering period.
+    pub count: u32,
+}
+
+/// Progress of the processing of a Provider that became insolvent or was deregistered.
+///
+/// Its pending move bucket requests are cancelled first, and then every open storage request is
+/// checked, in the order in which they are stored, to find the ones waiting on the Provider.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct ProviderStatusChangeProgress<T: Config> {
+    pub provider_id: ProviderIdFor<T>,
+    pub status: ProviderStatusChange,
+    /// Whether all the move bucket requests targeting the Provider have been cancelled.
+    pub move_bucket_requests_processed: bool,
+    /// The last storage request checked, if any.
+    pub last_checked_storage_request: Option<MerkleHash<T>>,
+}
+
+/// A transfer of funds held or escrowed by this pallet.
+///
+/// Failed transfers are queued to be retried in `on_idle`, instead of being left for manual
+/// follow-up.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub enum DepositTransfer<T: Config> {
+    /// Return to its owner the deposit held when a storage request was created.
+    StorageRequestCreationDeposit {
+        owner: T::AccountId,
+        amount: BalanceOf<T>,
+    },
+    /// Settle the escrowed payment of the BSPs of a storage request that is no longer open,
+    /// refunding to its owner the share of the BSPs that did not confirm storing the file.
+    StorageRequestEscrow { file_key: MerkleHash<T> },
+    /// Pay a BSP that confirmed storing a file its compensation for the revocation of the storage
+    /// request, out of the deposit held from its owner when the storage request was created.
+    StorageRequestRevocationCompensation {
+        owner: T::AccountId,
+        bsp: T::AccountId,
+        amount: BalanceOf<T>,
+    },
+    /// Return to its owner the deposit held for the automatic renewal of a storage request that no
+    /// longer needs to be renewed.
+    StorageRequestRenewalDeposit {
+        owner: T::AccountId,
+        amount: BalanceOf<T>,
+    },
+    /// Pay out the fee held from a user when it requested to delete a file, to whoever executed the
+    /// deletion or to the treasury.
+    FileDeletionFee {
+        payer: T::AccountId,
+        recipient: T::AccountId,
+        amount: BalanceOf<T>,
+    },
+    /// Return to its owner the deposit held for files of a deletion list that were processed, or
+    /// that will no longer be since the list was cancelled.
+    DeletionListDeposit {
+        owner: T::AccountId,
+        amount: BalanceOf<T>,
+    },
+}
+
+/// A failed [`DepositTransfer`] pending to be retried.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct DepositTransferRetry<T: Config> {
+    pub transfer: DepositTransfer<T>,
+    /// Number of times it has been retried.
+    pub attempts: u32,
+    /// The tick from which it can be retried again.
+    pub next_attempt_at: TickNumber<T>,
+}
+
+/// How to resolve a failed deposit transfer pending to be retried.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DepositTransferResolution {
+    /// Retry it right away.
+    Retry,
+    /// Remove it from the retry queue without executing it.
+    WriteOff,
+}
+
+/// Identifier of a failed deposit transfer in the retry queue.
+pub type DepositTransferRetryId = u32;
+
+/// A list of files whose deletion their owner authorised at once, by registering the root of a
+/// Merkle tree over their file keys. See [`crate::Pallet::register_deletion_list`].
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct DeletionList<T: Config> {
+    /// Amount of files in the list.
+    pub length: u32,
+    /// Index of the next file of the list to process, since they are processed in order.
+    pub next_index: u32,
+    /// Deposit held from the owner for each file of the list.
+    pub item_deposit: BalanceOf<T>,
+}
+
+/// Proof that a file key is part of a deletion list: the siblings of the nodes in the path from its
+/// leaf to the root of the list's Merkle tree.
+pub type DeletionListProof<T> =
+    BoundedVec<MerkleHash<T>, <T as crate::Config>::MaxDeletionListProofDepth>;
+
+/// Authorisation given by the owner of a bucket to another account to issue storage requests for
+/// files of the bucket on its behalf. See [`crate::Pallet::create_upload_grant`].
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct UploadGrant<T: Config> {
+    /// Owner of the bucket, who pays for the storage requests issued with the grant.
+    pub owner: T::AccountId,
+    /// Total size of the files that can still be requested to be stored with the grant.
+    pub remaining_budget: StorageData<T>,
+    /// Block from which the grant can no longer be used.
+    pub expires_at: BlockNumberFor<T>,
+}
+
+/// What an access grant gives read access to. See [`crate::Pallet::create_access_grant`].
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub enum AccessGrantTarget<T: Config> {
+    /// Every file of the bucket.
+    Bucket(BucketIdFor<T>),
+    /// A single file of the bucket.
+    File {
+        bucket_id: BucketIdFor<T>,
+        file_key: MerkleHash<T>,
+    },
+}
+
+impl<T: Config> AccessGrantTarget<T> {
+    /// The bucket whose owner manages the grants of this target.
+    pub fn bucket_id(&self) -> &BucketIdFor<T> {
+        match self {
+            AccessGrantTarget::Bucket(bucket_id) => bucket_id,
+            AccessGrantTarget::File { bucket_id, .. } => bucket_id,
+        }
+    }
+}
+
+/// Temporary read access to the files of a private bucket, given by its owner to another account.
+/// See [`crate::Pallet::create_access_grant`].
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct AccessGrant<T: Config> {
+    /// Account given read access.
+    pub grantee: T::AccountId,
+    /// Tick from which the grant no longer gives read access.
+    pub expires_at: TickNumber<T>,
+}
+
+/// Alias for the `MaxAccessGrantsPerTarget` type used in the FileSystem pallet.
+pub type MaxAccessGrantsPerTarget<T> = <T as crate::Config>::MaxAccessGrantsPerTarget;
+
+/// Access grants of a bucket or file.
+pub type AccessGrantList<T> = BoundedVec<AccessGrant<T>, MaxAccessGrantsPerTarget<T>>;
+
+/// Offer made by the MSP storing a bucket to transfer it to another MSP. See
+/// [`crate::Pallet::msp_offer_bucket_transfer`].
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct BucketTransferOffer<T: Config> {
+    /// MSP storing the bucket, which made the offer.
+    pub msp_id: ProviderIdFor<T>,
+    /// MSP the bucket is offered to.
+    pub new_msp_id: ProviderIdFor<T>,
+    /// Block from which the offer can no longer be accepted by the owner of the bucket.
+    pub expires_at: BlockNumberFor<T>,
+}
+
 /// Alias for the `MerkleHash` type used in the ProofsDealerInterface representing file keys.
 pub type MerkleHash<T> =
     <<T as crate::Config>::ProofDealer as shp_traits::ProofsDealerInterface>::MerkleHash;
@@ -359,6 +601,10 @@ pub type MaxBatchConfirmStorageRequests<T> = <T as crate::Config>::MaxBatchConfi
 pub type MaxBatchMspRespondStorageRequests<T> =
     <T as crate::Config>::MaxBatchMspRespondStorageRequests;
 
+/// Alias for the `MaxUserPendingDeletionRequests` type used in the FileSystem pallet, which also
+/// bounds the amount of files deleted in a single batch.
+pub type MaxUserPendingDeletionRequests<T> = <T as crate::Config>::MaxUserPendingDeletionRequests;
+
 /// Alias for the `MaxFilePathSize` type used in the FileSystem pallet.
 pub type MaxFilePathSize<T> = <T as crate::Config>::MaxFilePathSize;
 
@@ -381,6 +627,20 @@ pub type PendingFileDeletionRequestTtl<T> = <T as crate::Config>::PendingFileDel
 /// Byte array representing the file path.
 pub type FileLocation<T> = BoundedVec<u8, MaxFilePathSize<T>>;
 
+/// Alias for the `MaxNotificationPreferencesLength` type used in the FileSystem pallet.
+pub type MaxNotificationPreferencesLength<T> =
+    <T as crate::Config>::MaxNotificationPreferencesLength;
+
+/// Opaque pointer (i.e. a hash or a URI) to the notification preferences of a user.
+pub type NotificationPreferences<T> = BoundedVec<u8, MaxNotificationPreferencesLength<T>>;
+
+/// Alias for the `MaxFileAccessListSize` type used in the FileSystem pallet.
+pub type MaxFileAccessListSize<T> = <T as crate::Config>::MaxFileAccessListSize;
+
+/// Accounts granted read access to a file by its owner.
+pub type FileAccessList<T> =
+    BoundedVec<<T as frame_system::Config>::AccountId, MaxFileAccessListSize<T>>;
+
 /// Alias for the `MaxPeerIdSize` type used in the FileSystem pallet.
 pub type MaxPeerIdSize<T> = <T as crate::Config>::MaxPeerIdSize;
 
@@ -434,6 +694,13 @@ pub type ProviderIdFor<T> = <<T as crate::Config>::Providers as ReadProvidersInt
 /// Alias for the bucket name.
 pub type BucketNameFor<T> = BoundedVec<u8, BucketNameLimitFor<T>>;
 
+/// Alias for the `EncryptionMetadataLimit` type used in the ReadBucketsInterface.
+pub(super) type EncryptionMetadataLimitFor<T> =
+    <<T as crate::Config>::Providers as shp_traits::ReadBucketsInterface>::EncryptionMetadataLimit;
+
+/// Alias for the metadata describing how the contents of a bucket are encrypted.
+pub type EncryptionMetadataFor<T> = BoundedVec<u8, EncryptionMetadataLimitFor<T>>;
+
 /// Alias for the type of the storage request expiration item.
 pub type StorageRequestExpirationItem<T> = MerkleHash<T>;
 
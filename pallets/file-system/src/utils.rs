@@ -4,8 +4,8 @@ use frame_support::{
     pallet_prelude::DispatchResult,
     traits::{
         fungible::{InspectHold, Mutate, MutateHold},
-        nonfungibles_v2::{Create, Destroy},
-        tokens::{Precision, Preservation},
+        nonfungibles_v2::{Create, Destroy, InspectEnumerable},
+        tokens::{Fortitude, Precision, Preservation, Restriction},
         Get,
     },
 };
@@ -13,41 +13,57 @@ use frame_system::pallet_prelude::BlockNumberFor;
 use num_bigint::BigUint;
 use sp_runtime::{
     traits::{
-        Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Convert, ConvertBack, Hash, One,
-        Saturating, Zero,
+        Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Convert, ConvertBack,
+        Hash, One, SaturatedConversion, Saturating, Zero,
     },
     ArithmeticError, BoundedBTreeSet, BoundedVec, DispatchError,
 };
 use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
 use pallet_file_system_runtime_api::{
-    IsStorageRequestOpenToVolunteersError, QueryBspConfirmChunksToProveForFileError,
-    QueryConfirmChunksToProveForFileError, QueryFileEarliestVolunteerTickError,
-    QueryMspConfirmChunksToProveForFileError,
+    EstimateStorageRequestCostError, IsStorageRequestOpenToVolunteersError, OpenStorageRequest,
+    QueryBspConfirmChunksToProveForFileError, QueryConfirmChunksToProveForFileError,
+    QueryFileEarliestVolunteerTickError, QueryMspConfirmChunksToProveForFileError,
+    QueryStorageRequestReplicationStatusError, StorageRequestCostEstimate,
+    StorageRequestReplicationStatus,
 };
 use pallet_nfts::{CollectionConfig, CollectionSettings, ItemSettings, MintSettings, MintType};
 use shp_file_metadata::ChunkId;
 use shp_traits::{
     CommitRevealRandomnessInterface, MutateBucketsInterface, MutateStorageProvidersInterface,
-    PaymentStreamsInterface, ReadBucketsInterface, ReadProvidersInterface,
+    OnProviderStatusChange, PaymentEscrowInterface, PaymentStreamsInterface, ProviderStatus,
+    ProviderStatusChange, ReadBucketsInterface, ReadProvidersInterface,
     ReadStorageProvidersInterface, ReadUserSolvencyInterface, TrieAddMutation, TrieRemoveMutation,
 };
 
 use crate::{
     pallet,
     types::{
-        BucketIdFor, BucketMoveRequestResponse, BucketNameFor, CollectionConfigFor,
-        CollectionIdFor, EitherAccountIdOrMspId, ExpirationItem, FileDeletionRequestExpirationItem,
-        FileKeyHasher, FileLocation, Fingerprint, ForestProof, KeyProof,
-        MaxBatchMspRespondStorageRequests, MerkleHash, MoveBucketRequestMetadata, MultiAddresses,
-        PeerIds, PendingFileDeletionRequest, PendingStopStoringRequest, ProviderIdFor,
-        RejectedStorageRequest, ReplicationTargetType, StorageData, StorageRequestBspsMetadata,
-        StorageRequestMetadata, StorageRequestMspAcceptedFileKeys, StorageRequestMspBucketResponse,
-        StorageRequestMspResponse, TickNumber, ValuePropId,
+        AccessGrant, AccessGrantList, AccessGrantTarget, BalanceOf, BucketIdFor,
+        BucketMoveRequestResponse, BucketNameFor, BucketTransferOffer, CollectionConfigFor,
+        CollectionIdFor, DeletionList, DeletionListProof, DepositTransfer,
+        DepositTransferResolution, DepositTransferRetry, DepositTransferRetryId,
+        EitherAccountIdOrMspId, EncryptionMetadataFor, ExpirationItem, FileAccessList,
+        FileDeletionRequestExpirationItem, FileDeletionRequestItem, FileKeyHasher, FileLocation,
+        FileMove, Fingerprint, ForestProof, KeyProof, MaxBatchMspRespondStorageRequests,
+        MaxUserPendingDeletionRequests, MerkleHash, MoveBucketRequestMetadata,
+        MspEligibilityListMode, MultiAddresses, NotificationPreferences, PeerIds,
+        PendingFileDeletionRequest, PendingStopStoringRequest, PriorityChallengeUsage,
+        ProviderIdFor, RejectedStorageRequest, ReplicationTargetType, StorageData,
+        StorageRequestBspsMetadata, StorageRequestMetadata, StorageRequestMspAcceptedFileKeys,
+        StorageRequestMspBucketResponse, StorageRequestMspResponse, TickNumber, UploadGrant,
+        ValuePropId,
     },
-    BucketsWithStorageRequests, Error, Event, HoldReason, MaxReplicationTarget, Pallet,
-    PendingBucketsToMove, PendingFileDeletionRequests, PendingMoveBucketRequests,
-    PendingStopStoringRequests, StorageRequestBsps, StorageRequests, TickRangeToMaximumThreshold,
+    AccessGrantExpirations, AccessGrants, BspBilledFileSizes, BucketDefaultReplicationTarget,
+    BucketTransferOffers, BucketsWithStorageRequests, DeferredPriorityChallengeIssuers,
+    DeferredPriorityChallenges, DeletionLists, DepositTransferRetries, Error, Event,
+    FileAccessLists, FrozenBuckets, HoldReason, MaxReplicationTarget, MovedFiles,
+    MspEligibilityList, MspEligibilityMode, NextAccessGrantExpirationTickToCleanUp,
+    NextDepositTransferRetryId, Pallet, PendingBucketsToMove, PendingFileDeletionFees,
+    PendingFileDeletionRequests, PendingFileMoveRequests, PendingMoveBucketRequests,
+    PendingStopStoringRequests, PriorityChallengesUsage, ProvidersWithStatusChange,
+    StorageRequestAutoRenewals, StorageRequestBsps, StorageRequests, TickRangeToMaximumThreshold,
+    UploadGrants, UserNotificationPreferences,
 };
 
 macro_rules! expect_or_err {
@@ -118,6 +134,266 @@ where
         Ok(storage_request.bsps_confirmed < storage_request.bsps_required)
     }
 
+    /// Get the replication target used for storage requests in a bucket that don't specify one.
+    ///
+    /// This is the bucket's default replication target if its owner set one, or
+    /// [`Config::DefaultReplicationTarget`] otherwise.
+    pub fn query_bucket_default_replication_target(
+        bucket_id: BucketIdFor<T>,
+    ) -> ReplicationTargetType<T> {
+        <BucketDefaultReplicationTarget<T>>::get(&bucket_id)
+            .unwrap_or_else(|| T::DefaultReplicationTarget::get())
+    }
+
+    /// Whether governance allows the MSP to take new buckets and storage requests, as per the MSP
+    /// eligibility list and its mode.
+    pub fn is_msp_eligible(msp_id: &ProviderIdFor<T>) -> bool {
+        let listed = <MspEligibilityList<T>>::contains_key(msp_id);
+        match <MspEligibilityMode<T>>::get() {
+            MspEligibilityListMode::Denylist => !listed,
+            MspEligibilityListMode::Allowlist => listed,
+        }
+    }
+
+    /// Get the replication progress of a storage request, along with the parameters needed to estimate
+    /// how long it will take to reach its replication target.
+    pub fn query_storage_request_replication_status(
+        file_key: MerkleHash<T>,
+    ) -> Result<
+        StorageRequestReplicationStatus<TickNumber<T>, ReplicationTargetType<T>>,
+        QueryStorageRequestReplicationStatusError,
+    > {
+        let storage_request = <StorageRequests<T>>::get(&file_key)
+            .ok_or(QueryStorageRequestReplicationStatusError::StorageRequestNotFound)?;
+
+        Ok(StorageRequestReplicationStatus {
+            requested_at: storage_request.requested_at,
+            current_tick: <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick(),
+            bsps_required: storage_request.bsps_required,
+            bsps_confirmed: storage_request.bsps_confirmed,
+            bsps_volunteered: storage_request.bsps_volunteered,
+            number_of_bsps: <T::Providers as ReadStorageProvidersInterface>::get_number_of_bsps()
+                .saturated_into(),
+            max_replication_target: MaxReplicationTarget::<T>::get(),
+            tick_range_to_maximum_threshold: TickRangeToMaximumThreshold::<T>::get(),
+        })
+    }
+
+    /// Get a page of the open storage requests that are waiting on `provider_id`, in storage order.
+    ///
+    /// For an MSP, these are the storage requests for its buckets that it has not accepted yet. For a
+    /// BSP, these are the storage requests it volunteered for but has not confirmed storing yet.
+    ///
+    /// The page starts after the storage request of `start_after`, if any, and has at most `limit`
+    /// storage requests. To get the next page, pass the file key of the last storage request of this
+    /// one as `start_after`.
+    pub fn pending_storage_requests_for_provider(
+        provider_id: ProviderIdFor<T>,
+        start_after: Option<MerkleHash<T>>,
+        limit: u32,
+    ) -> Vec<OpenStorageRequest<MerkleHash<T>, StorageRequestMetadata<T>, ProviderIdFor<T>>> {
+        let is_msp = <T::Providers as ReadStorageProvidersInterface>::is_msp(&provider_id);
+        let is_bsp = <T::Providers as ReadStorageProvidersInterface>::is_bsp(&provider_id);
+        if !is_msp && !is_bsp {
+            return Vec::new();
+        }
+
+        let storage_requests = match start_after {
+            Some(file_key) => {
+                <StorageRequests<T>>::iter_from(<StorageRequests<T>>::hashed_key_for(file_key))
+            }
+            None => <StorageRequests<T>>::iter(),
+        };
+
+        storage_requests
+            .filter(|(file_key, storage_request)| {
+                if is_msp {
+                    storage_request.msp == Some((provider_id, false))
+                } else {
+                    <StorageRequestBsps<T>>::get(file_key, &provider_id)
+                        .is_some_and(|bsp| !bsp.confirmed)
+                }
+            })
+            .take(limit as usize)
+            .map(|(file_key, storage_request)| {
+                Self::open_storage_request(file_key, storage_request)
+            })
+            .collect()
+    }
+
+    /// Get a page of the open storage requests for files of `bucket_id`, in storage order.
+    ///
+    /// The page starts after the storage request of `start_after`, if any, and has at most `limit`
+    /// storage requests. To get the next page, pass the file key of the last storage request of this
+    /// one as `start_after`.
+    pub fn storage_requests_by_bucket(
+        bucket_id: BucketIdFor<T>,
+        start_after: Option<MerkleHash<T>>,
+        limit: u32,
+    ) -> Vec<OpenStorageRequest<MerkleHash<T>, StorageRequestMetadata<T>, ProviderIdFor<T>>> {
+        let file_keys = match start_after {
+            Some(file_key) => <BucketsWithStorageRequests<T>>::iter_key_prefix_from(
+                bucket_id,
+                <BucketsWithStorageRequests<T>>::hashed_key_for(bucket_id, file_key),
+            ),
+            None => <BucketsWithStorageRequests<T>>::iter_key_prefix(bucket_id),
+        };
+
+        file_keys
+            .filter_map(|file_key| {
+                <StorageRequests<T>>::get(&file_key)
+                    .map(|storage_request| Self::open_storage_request(file_key, storage_request))
+            })
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Get the open storage request for `file_key`, along with the BSPs that volunteered for it, if any.
+    pub fn query_storage_request(
+        file_key: MerkleHash<T>,
+    ) -> Option<OpenStorageRequest<MerkleHash<T>, StorageRequestMetadata<T>, ProviderIdFor<T>>>
+    {
+        <StorageRequests<T>>::get(&file_key)
+            .map(|storage_request| Self::open_storage_request(file_key, storage_request))
+    }
+
+    /// Get the pointer to the notification preferences set by `who`, if any.
+    pub fn query_notification_preferences(who: &T::AccountId) -> Option<Vec<u8>> {
+        <UserNotificationPreferences<T>>::get(who).map(|preferences| preferences.into_inner())
+    }
+
+    /// Get the accounts that the owner of `file_key` granted read access to.
+    pub fn query_file_access_list(file_key: &MerkleHash<T>) -> Vec<T::AccountId> {
+        <FileAccessLists<T>>::get(file_key)
+            .map(|access_list| access_list.into_inner())
+            .unwrap_or_default()
+    }
+
+    /// Check if `account` has read access to the file `file_key` of `bucket_id`.
+    ///
+    /// Every account has read access to the files of public buckets. For private buckets, only the
+    /// owner of the bucket, the accounts in the access list of the file, the holders of an item of
+    /// the bucket's read-access NFT collection and the accounts with an unexpired access grant to
+    /// the bucket or the file do.
+    pub fn has_file_read_access(
+        bucket_id: &BucketIdFor<T>,
+        file_key: &MerkleHash<T>,
+        account: &T::AccountId,
+    ) -> bool {
+        match <T::Providers as ReadBucketsInterface>::is_bucket_private(bucket_id) {
+            Ok(false) => true,
+            Ok(true) => {
+                <T::Providers as ReadBucketsInterface>::is_bucket_owner(account, bucket_id)
+                    .unwrap_or(false)
+                    || <FileAccessLists<T>>::get(file_key)
+                        .is_some_and(|access_list| access_list.contains(account))
+                    || <T::Providers as ReadBucketsInterface>::get_read_access_group_id_of_bucket(
+                        bucket_id,
+                    )
+                    .ok()
+                    .flatten()
+                    .is_some_and(|collection_id| {
+                        T::Nfts::owned_in_collection(&collection_id, account)
+                            .next()
+                            .is_some()
+                    })
+                    || Self::has_access_grant(&AccessGrantTarget::Bucket(*bucket_id), account)
+                    || Self::has_access_grant(
+                        &AccessGrantTarget::File {
+                            bucket_id: *bucket_id,
+                            file_key: *file_key,
+                        },
+                        account,
+                    )
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Whether `account` has an access grant to `target` that has not expired yet.
+    fn has_access_grant(target: &AccessGrantTarget<T>, account: &T::AccountId) -> bool {
+        let current_tick =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick();
+
+        <AccessGrants<T>>::get(target).is_some_and(|grants| {
+            grants
+                .iter()
+                .any(|grant| &grant.grantee == account && grant.expires_at > current_tick)
+        })
+    }
+
+    /// The size `size` bytes of a file are billed as, which is at least [`pallet::Config::MinBillableFileSize`].
+    pub fn query_billable_size(size: StorageData<T>) -> StorageData<T> {
+        size.max(T::MinBillableFileSize::get())
+    }
+
+    /// Estimate what issuing a storage request for a file of `size` bytes, to be stored by
+    /// `replication_target` BSPs and optionally `msp_id`, would cost at the current prices.
+    ///
+    /// The estimate assumes that the User does not pay any of the BSPs that will store the file yet,
+    /// so the deposit of each of their payment streams is held in full.
+    pub fn estimate_storage_request_cost(
+        size: StorageData<T>,
+        replication_target: ReplicationTargetType<T>,
+        msp_id: Option<ProviderIdFor<T>>,
+    ) -> Result<StorageRequestCostEstimate<BalanceOf<T>>, EstimateStorageRequestCostError> {
+        if size.is_zero() {
+            return Err(EstimateStorageRequestCostError::FileSizeCannotBeZero);
+        }
+        if replication_target > MaxReplicationTarget::<T>::get().into() {
+            return Err(EstimateStorageRequestCostError::ReplicationTargetExceedsMaximum);
+        }
+        match msp_id {
+            Some(msp_id) => {
+                if !<T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id) {
+                    return Err(EstimateStorageRequestCostError::NotAMsp);
+                }
+            }
+            None if replication_target.is_zero() => {
+                return Err(EstimateStorageRequestCostError::MspOnlyStorageRequestWithoutMsp);
+            }
+            None => {}
+        }
+
+        let billable_size = Self::query_billable_size(size);
+        let bsps: BalanceOf<T> = Into::<u64>::into(replication_target).saturated_into();
+        let rate_per_bsp =
+            <T::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_per_tick(
+                &billable_size,
+            )
+            .map_err(|_| EstimateStorageRequestCostError::ArithmeticOverflow)?;
+        let deposit_per_bsp = <T::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_deposit(
+            &billable_size,
+        )
+        .map_err(|_| EstimateStorageRequestCostError::ArithmeticOverflow)?;
+
+        Ok(StorageRequestCostEstimate {
+            creation_deposit: Self::storage_request_creation_deposit(replication_target),
+            payment_streams_deposit: deposit_per_bsp
+                .checked_mul(&bsps)
+                .ok_or(EstimateStorageRequestCostError::ArithmeticOverflow)?,
+            bsps_rate_per_tick: rate_per_bsp
+                .checked_mul(&bsps)
+                .ok_or(EstimateStorageRequestCostError::ArithmeticOverflow)?,
+        })
+    }
+
+    /// Builds the view of an open storage request exposed by the runtime API, with its volunteers.
+    fn open_storage_request(
+        file_key: MerkleHash<T>,
+        metadata: StorageRequestMetadata<T>,
+    ) -> OpenStorageRequest<MerkleHash<T>, StorageRequestMetadata<T>, ProviderIdFor<T>> {
+        let volunteers = <StorageRequestBsps<T>>::iter_prefix(&file_key)
+            .map(|(bsp_id, bsp)| (bsp_id, bsp.confirmed))
+            .collect();
+
+        OpenStorageRequest {
+            file_key,
+            metadata,
+            volunteers,
+        }
+    }
+
     /// Compute the tick number at which the BSP is eligible to volunteer for a storage request.
     pub fn query_earliest_file_volunteer_tick(
         bsp_id: ProviderIdFor<T>,
@@ -270,6 +546,7 @@ where
         name: BucketNameFor<T>,
         private: bool,
         value_prop_id: Option<ValuePropId<T>>,
+        encryption_metadata: Option<EncryptionMetadataFor<T>>,
     ) -> Result<(BucketIdFor<T>, Option<CollectionIdFor<T>>), DispatchError> {
         // Check if the MSP is indeed an MSP.
         if let Some(msp_id) = msp_id {
@@ -278,11 +555,11 @@ where
                 Error::<T>::NotAMsp
             );
 
-            // Check if MSP is insolvent
-            ensure!(
-                !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(msp_id),
-                Error::<T>::OperationNotAllowedForInsolventProvider
-            );
+            // Check if the MSP can take on new buckets
+            Self::ensure_provider_active(msp_id)?;
+
+            // Check if governance allows the MSP to take new buckets
+            ensure!(Self::is_msp_eligible(&msp_id), Error::<T>::MspNotEligible);
         }
 
         // Create collection only if bucket is private
@@ -304,6 +581,13 @@ where
             value_prop_id,
         )?;
 
+        if encryption_metadata.is_some() {
+            <T::Providers as MutateBucketsInterface>::update_bucket_encryption_metadata(
+                bucket_id,
+                encryption_metadata,
+            )?;
+        }
+
         Ok((bucket_id, maybe_collection_id))
     }
 
@@ -322,16 +606,22 @@ where
             Error::<T>::NotBucketOwner
         );
 
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
         // Check if the new MSP is indeed an MSP.
         ensure!(
             <T::Providers as ReadStorageProvidersInterface>::is_msp(&new_msp_id),
             Error::<T>::NotAMsp
         );
 
-        // Check if the newly selected MSP is not insolvent
+        // Check if the newly selected MSP can take on new buckets
+        Self::ensure_provider_active(new_msp_id)?;
+
+        // Check if governance allows the newly selected MSP to take new buckets
         ensure!(
-            !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(new_msp_id),
-            Error::<T>::OperationNotAllowedForInsolventProvider
+            Self::is_msp_eligible(&new_msp_id),
+            Error::<T>::MspNotEligible
         );
 
         // Check if the bucket is already stored by the new MSP.
@@ -382,7 +672,8 @@ where
 
         // Check if MSP is insolvent.
         ensure!(
-            !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(msp_id),
+            <T::Providers as ReadProvidersInterface>::get_provider_status(msp_id)
+                != Some(ProviderStatus::Insolvent),
             Error::<T>::OperationNotAllowedForInsolventProvider
         );
 
@@ -400,6 +691,12 @@ where
         );
 
         if response == BucketMoveRequestResponse::Accepted {
+            // Rejecting is always allowed, but only an MSP that can take on new buckets can accept.
+            Self::ensure_provider_active(msp_id)?;
+
+            // A bucket frozen after the move was requested can't be moved until it is unfrozen.
+            Self::ensure_bucket_not_frozen(&bucket_id)?;
+
             let bucket_size = <T::Providers as ReadBucketsInterface>::get_bucket_size(&bucket_id)?;
 
             let previous_msp_id =
@@ -481,6 +778,137 @@ where
         Ok(collection_id)
     }
 
+    /// Set or clear the default replication target of a bucket.
+    ///
+    /// The replication target, if any, must be greater than zero and not exceed [`MaxReplicationTarget`].
+    pub(crate) fn do_set_bucket_default_replication_target(
+        sender: T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        replication_target: Option<ReplicationTargetType<T>>,
+    ) -> DispatchResult {
+        // Ensure the sender is the owner of the bucket.
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(&sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+
+        match replication_target {
+            Some(replication_target) => {
+                ensure!(
+                    !replication_target.is_zero(),
+                    Error::<T>::ReplicationTargetCannotBeZero
+                );
+                ensure!(
+                    replication_target <= MaxReplicationTarget::<T>::get(),
+                    Error::<T>::ReplicationTargetExceedsMaximum
+                );
+
+                <BucketDefaultReplicationTarget<T>>::insert(&bucket_id, replication_target);
+            }
+            None => <BucketDefaultReplicationTarget<T>>::remove(&bucket_id),
+        }
+
+        Ok(())
+    }
+
+    /// Set or clear the encryption metadata of a bucket.
+    pub(crate) fn do_set_bucket_encryption_metadata(
+        sender: T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        encryption_metadata: Option<EncryptionMetadataFor<T>>,
+    ) -> DispatchResult {
+        // Ensure the sender is the owner of the bucket.
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(&sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+
+        <T::Providers as MutateBucketsInterface>::update_bucket_encryption_metadata(
+            bucket_id,
+            encryption_metadata,
+        )
+    }
+
+    pub(crate) fn do_migrate_bucket_value_prop(
+        sender: T::AccountId,
+        bucket_id: BucketIdFor<T>,
+    ) -> DispatchResult {
+        // Ensure the sender is the owner of the bucket.
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(&sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+
+        <T::Providers as MutateBucketsInterface>::migrate_bucket_to_latest_value_prop(&bucket_id)?;
+
+        Ok(())
+    }
+
+    /// Freeze a bucket, returning its owner and the MSP storing it, if any.
+    pub(crate) fn do_freeze_bucket(
+        bucket_id: BucketIdFor<T>,
+    ) -> Result<(T::AccountId, Option<ProviderIdFor<T>>), DispatchError> {
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::bucket_exists(&bucket_id),
+            Error::<T>::BucketNotFound
+        );
+        ensure!(
+            !<FrozenBuckets<T>>::contains_key(&bucket_id),
+            Error::<T>::BucketAlreadyFrozen
+        );
+
+        <FrozenBuckets<T>>::insert(&bucket_id, frame_system::Pallet::<T>::block_number());
+
+        let owner = <T::Providers as ReadBucketsInterface>::get_bucket_owner(&bucket_id)?;
+        let msp_id = <T::Providers as ReadBucketsInterface>::get_msp_of_bucket(&bucket_id)?;
+
+        Ok((owner, msp_id))
+    }
+
+    /// Unfreeze a frozen bucket, returning its owner and the MSP storing it, if any.
+    pub(crate) fn do_unfreeze_bucket(
+        bucket_id: BucketIdFor<T>,
+    ) -> Result<(T::AccountId, Option<ProviderIdFor<T>>), DispatchError> {
+        ensure!(
+            <FrozenBuckets<T>>::take(&bucket_id).is_some(),
+            Error::<T>::BucketNotFrozen
+        );
+
+        let owner = <T::Providers as ReadBucketsInterface>::get_bucket_owner(&bucket_id)?;
+        let msp_id = <T::Providers as ReadBucketsInterface>::get_msp_of_bucket(&bucket_id)?;
+
+        Ok((owner, msp_id))
+    }
+
+    /// Ensure a Provider can take on new work (buckets, storage requests or files), that is, that its
+    /// status is [`ProviderStatus::Active`].
+    ///
+    /// Unregistered Providers are let through, so the caller can report its own error for them.
+    pub(crate) fn ensure_provider_active(provider_id: ProviderIdFor<T>) -> DispatchResult {
+        match <T::Providers as ReadProvidersInterface>::get_provider_status(provider_id) {
+            Some(ProviderStatus::Insolvent) => {
+                Err(Error::<T>::OperationNotAllowedForInsolventProvider.into())
+            }
+            Some(ProviderStatus::Suspended) => {
+                Err(Error::<T>::OperationNotAllowedForSuspendedProvider.into())
+            }
+            Some(ProviderStatus::Exiting) => {
+                Err(Error::<T>::OperationNotAllowedForExitingProvider.into())
+            }
+            Some(ProviderStatus::Active) | None => Ok(()),
+        }
+    }
+
+    /// Ensure a bucket is not frozen, which would forbid adding, deleting or moving its files.
+    pub(crate) fn ensure_bucket_not_frozen(bucket_id: &BucketIdFor<T>) -> DispatchResult {
+        ensure!(
+            !<FrozenBuckets<T>>::contains_key(bucket_id),
+            Error::<T>::BucketIsFrozen
+        );
+
+        Ok(())
+    }
+
     /// Create and associate collection with a bucket.
     ///
     /// *Callable only by the owner of the bucket.*
@@ -532,6 +960,9 @@ where
             Error::<T>::NotBucketOwner
         );
 
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
         // Check if the bucket is empty, both by checking its size and that its root is the default one
         // (the root of an empty trie).
         ensure!(
@@ -555,6 +986,9 @@ where
         // Delete the bucket.
         <T::Providers as MutateBucketsInterface>::remove_root_bucket(bucket_id)?;
 
+        // Clean up the bucket's default replication target, if any.
+        <BucketDefaultReplicationTarget<T>>::remove(&bucket_id);
+
         // Delete the collection associated with the bucket if it existed.
         if let Some(collection_id) = maybe_collection_id.clone() {
             let destroy_witness = expect_or_err!(
@@ -600,8 +1034,27 @@ where
             Error::<T>::BucketIsBeingMoved
         );
 
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
+        // If no replication target was specified, use the bucket's default (or the global one).
+        let replication_target = replication_target
+            .unwrap_or_else(|| Self::query_bucket_default_replication_target(bucket_id));
+
+        // A replication target of zero means the file will only be stored by the MSP, so one is required.
+        if replication_target.is_zero() {
+            ensure!(
+                msp_id.is_some(),
+                Error::<T>::MspOnlyStorageRequestWithoutMsp
+            );
+        }
+
+        if replication_target > MaxReplicationTarget::<T>::get().into() {
+            return Err(Error::<T>::ReplicationTargetExceedsMaximum)?;
+        }
+
         // Check if we can hold the storage request creation deposit from the user
-        let deposit = T::StorageRequestCreationDeposit::get();
+        let deposit = Self::storage_request_creation_deposit(replication_target);
         ensure!(
             T::Currency::can_hold(
                 &HoldReason::StorageRequestCreationHold.into(),
@@ -619,11 +1072,11 @@ where
                 Error::<T>::NotAMsp
             );
 
-            // Check if the MSP is insolvent
-            ensure!(
-                !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(*msp_id),
-                Error::<T>::OperationNotAllowedForInsolventProvider
-            );
+            // Check if the MSP can take on new storage requests
+            Self::ensure_provider_active(*msp_id)?;
+
+            // Check if governance allows the MSP to take new storage requests
+            ensure!(Self::is_msp_eligible(msp_id), Error::<T>::MspNotEligible);
 
             // Check that the MSP received is the one storing the bucket.
             ensure!(
@@ -636,16 +1089,6 @@ where
             None
         };
 
-        let replication_target = replication_target.unwrap_or(T::DefaultReplicationTarget::get());
-
-        if replication_target.is_zero() {
-            return Err(Error::<T>::ReplicationTargetCannotBeZero)?;
-        }
-
-        if replication_target > MaxReplicationTarget::<T>::get().into() {
-            return Err(Error::<T>::ReplicationTargetExceedsMaximum)?;
-        }
-
         let current_tick =
             <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick();
 
@@ -705,64 +1148,215 @@ where
             peer_ids: user_peer_ids.unwrap_or_default(),
         });
 
+        // Make it explicit that the file will not be backed up by any BSP.
+        if let Some(msp_id) = msp_id.filter(|_| replication_target.is_zero()) {
+            Self::deposit_event(Event::NewMspOnlyStorageRequest { file_key, msp_id });
+        }
+
         Ok(file_key)
     }
 
-    /// Accepts or rejects batches of storage requests assumed to be grouped by bucket.
-    ///
-    /// This is using a best-effort strategy to process as many file keys as possible, returning
-    /// the ones that were accepted, rejected, or failed to be processed.
-    ///
-    /// File keys that are not part of the bucket they belong to will be skipped (failed).
+    /// Request storage for a file, escrowing upfront the payment of all the BSPs required to fulfill
+    /// the storage request for `escrow_ticks` ticks.
     ///
-    /// All file keys will be processed (unless there are duplicates, they are simply skipped) and any errors
-    /// while processing them will be marked as a failed key and continue processing the rest. It is up to the
-    /// caller to verify the final result and apply only the file keys that have been successfully accepted.
-    pub(crate) fn do_msp_respond_storage_request(
+    /// The escrow is identified by the file key.
+    pub(crate) fn do_request_escrowed_storage(
         sender: T::AccountId,
-        storage_request_msp_response: StorageRequestMspResponse<T>,
-    ) -> Result<(), DispatchError> {
-        // Check that the sender is a Storage Provider and get its MSP ID
-        let msp_id =
-            <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
-                .ok_or(Error::<T>::NotASp)?;
+        bucket_id: BucketIdFor<T>,
+        location: FileLocation<T>,
+        fingerprint: Fingerprint<T>,
+        size: StorageData<T>,
+        msp_id: Option<ProviderIdFor<T>>,
+        replication_target: Option<ReplicationTargetType<T>>,
+        user_peer_ids: PeerIds<T>,
+        escrow_ticks: BlockNumberFor<T>,
+    ) -> Result<MerkleHash<T>, DispatchError> {
+        let file_key = Self::do_request_storage(
+            sender.clone(),
+            bucket_id,
+            location,
+            fingerprint,
+            size,
+            msp_id,
+            replication_target,
+            Some(user_peer_ids),
+        )?;
 
-        // Check that the sender is an MSP
-        ensure!(
-            <T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id),
-            Error::<T>::NotAMsp
+        let storage_request_metadata = expect_or_err!(
+            <StorageRequests<T>>::get(&file_key),
+            "Storage request should exist after being created",
+            Error::<T>::StorageRequestNotFound
         );
 
-        // Preliminary check to ensure that the MSP is the one storing each bucket in the responses
-        for StorageRequestMspBucketResponse { bucket_id, .. } in storage_request_msp_response.iter()
-        {
-            ensure!(
-                <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(
-                    &msp_id, &bucket_id
-                ),
-                Error::<T>::MspNotStoringBucket
-            );
+        // MSP-only storage requests have no BSP to pay, so there is nothing to escrow.
+        if storage_request_metadata.bsps_required.is_zero() {
+            return Ok(file_key);
         }
 
-        // Process each bucket's responses
-        for StorageRequestMspBucketResponse {
-            bucket_id,
-            accept,
-            reject,
-        } in storage_request_msp_response.into_iter()
-        {
-            if let Some(accepted_file_keys) = accept {
-                Self::do_msp_accept_storage_request(msp_id, bucket_id, accepted_file_keys)?;
-            }
+        // Escrow the payment of every BSP required to fulfill the storage request.
+        let bsps_required: u64 = storage_request_metadata.bsps_required.into();
+        let bsps_required = u32::try_from(bsps_required).map_err(|_| ArithmeticError::Overflow)?;
+        <T::PaymentStreams as PaymentEscrowInterface>::open_escrow(
+            &file_key,
+            &sender,
+            bsps_required,
+            &Self::query_billable_size(size),
+            escrow_ticks,
+        )?;
 
-            for RejectedStorageRequest { file_key, reason } in reject {
-                let storage_request_metadata = <StorageRequests<T>>::get(file_key)
-                    .ok_or(Error::<T>::StorageRequestNotFound)?;
+        Ok(file_key)
+    }
+
+    /// Opts the storage request `file_key`, just issued by `sender`, into its automatic renewal,
+    /// holding from `sender` the deposit to pay for the creation of the renewed storage request.
+    pub(crate) fn do_enable_storage_request_auto_renewal(
+        sender: &T::AccountId,
+        file_key: MerkleHash<T>,
+    ) -> DispatchResult {
+        // The renewed storage request will be for at least one BSP.
+        let deposit = Self::storage_request_creation_deposit(ReplicationTargetType::<T>::one());
+        T::Currency::hold(
+            &HoldReason::StorageRequestRenewalHold.into(),
+            sender,
+            deposit,
+        )
+        .map_err(|_| Error::<T>::CannotHoldDeposit)?;
+
+        <StorageRequestAutoRenewals<T>>::insert(&file_key, deposit);
+
+        Ok(())
+    }
+
+    /// Returns to the owner of the storage request `file_key` the deposit held for its automatic
+    /// renewal, if any, since it is no longer going to be renewed.
+    fn release_storage_request_renewal_deposit(file_key: &MerkleHash<T>, owner: &T::AccountId) {
+        if let Some(amount) = <StorageRequestAutoRenewals<T>>::take(file_key) {
+            Self::execute_deposit_transfer_or_queue_retry(
+                DepositTransfer::StorageRequestRenewalDeposit {
+                    owner: owner.clone(),
+                    amount,
+                },
+            );
+        }
+    }
+
+    /// Issues again the expired storage request `file_key` for the replicas it is missing, if its
+    /// owner opted into its automatic renewal. Returns whether the storage request was renewed.
+    ///
+    /// The renewal deposit is released to be held again as the creation deposit of the new storage
+    /// request, so it is returned to the owner if the storage request can't be renewed.
+    fn renew_expired_storage_request(
+        file_key: MerkleHash<T>,
+        storage_request_metadata: &StorageRequestMetadata<T>,
+    ) -> bool {
+        let Some(deposit) = <StorageRequestAutoRenewals<T>>::take(&file_key) else {
+            return false;
+        };
+
+        let owner = &storage_request_metadata.owner;
+        if let Err(error) = T::Currency::release(
+            &HoldReason::StorageRequestRenewalHold.into(),
+            owner,
+            deposit,
+            Precision::BestEffort,
+        ) {
+            Self::deposit_event(Event::StorageRequestRenewalFailed { file_key, error });
+            return false;
+        }
+
+        let bsps_required = storage_request_metadata
+            .bsps_required
+            .saturating_sub(storage_request_metadata.bsps_confirmed);
+        if bsps_required.is_zero() {
+            return false;
+        }
+
+        // The new storage request is not tied to the MSP, which already accepted the file. Its
+        // creation is rolled back as a whole if it fails.
+        let result = frame_support::storage::with_storage_layer(|| {
+            Self::do_request_storage(
+                owner.clone(),
+                storage_request_metadata.bucket_id,
+                storage_request_metadata.location.clone(),
+                storage_request_metadata.fingerprint,
+                storage_request_metadata.size,
+                None,
+                Some(bsps_required),
+                Some(storage_request_metadata.user_peer_ids.clone()),
+            )
+        });
+
+        match result {
+            Ok(_) => {
+                Self::deposit_event(Event::StorageRequestRenewed {
+                    file_key,
+                    bsps_required,
+                });
+                true
+            }
+            Err(error) => {
+                Self::deposit_event(Event::StorageRequestRenewalFailed { file_key, error });
+                false
+            }
+        }
+    }
+
+    /// Accepts or rejects batches of storage requests assumed to be grouped by bucket.
+    ///
+    /// This is using a best-effort strategy to process as many file keys as possible, returning
+    /// the ones that were accepted, rejected, or failed to be processed.
+    ///
+    /// File keys that are not part of the bucket they belong to will be skipped (failed).
+    ///
+    /// All file keys will be processed (unless there are duplicates, they are simply skipped) and any errors
+    /// while processing them will be marked as a failed key and continue processing the rest. It is up to the
+    /// caller to verify the final result and apply only the file keys that have been successfully accepted.
+    pub(crate) fn do_msp_respond_storage_request(
+        sender: T::AccountId,
+        storage_request_msp_response: StorageRequestMspResponse<T>,
+    ) -> Result<(), DispatchError> {
+        // Check that the sender is a Storage Provider and get its MSP ID
+        let msp_id =
+            <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
+                .ok_or(Error::<T>::NotASp)?;
+
+        // Check that the sender is an MSP
+        ensure!(
+            <T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id),
+            Error::<T>::NotAMsp
+        );
+
+        // Preliminary check to ensure that the MSP is the one storing each bucket in the responses
+        for StorageRequestMspBucketResponse { bucket_id, .. } in storage_request_msp_response.iter()
+        {
+            ensure!(
+                <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(
+                    &msp_id, &bucket_id
+                ),
+                Error::<T>::MspNotStoringBucket
+            );
+        }
+
+        // Process each bucket's responses
+        for StorageRequestMspBucketResponse {
+            bucket_id,
+            accept,
+            reject,
+        } in storage_request_msp_response.into_iter()
+        {
+            if let Some(accepted_file_keys) = accept {
+                Self::do_msp_accept_storage_request(msp_id, bucket_id, accepted_file_keys)?;
+            }
+
+            for RejectedStorageRequest { file_key, reason } in reject {
+                let storage_request_metadata = <StorageRequests<T>>::get(file_key)
+                    .ok_or(Error::<T>::StorageRequestNotFound)?;
 
                 Self::cleanup_storage_request(
                     EitherAccountIdOrMspId::MspId(msp_id),
                     file_key,
                     &storage_request_metadata,
+                    Zero::zero(),
                 )?;
 
                 Self::deposit_event(Event::StorageRequestRejected { file_key, reason });
@@ -793,6 +1387,9 @@ where
             Error::<T>::MspNotStoringBucket
         );
 
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
         let bucket_owner = <T::Providers as ReadBucketsInterface>::get_bucket_owner(&bucket_id)?;
 
         // Decrease the used capacity of the MSP.
@@ -824,11 +1421,11 @@ where
         bucket_id: BucketIdFor<T>,
         accepted_file_keys: StorageRequestMspAcceptedFileKeys<T>,
     ) -> Result<MerkleHash<T>, DispatchError> {
-        // Check if MSP is insolvent.
-        ensure!(
-            !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(msp_id),
-            Error::<T>::OperationNotAllowedForInsolventProvider
-        );
+        // Check if the MSP can take on new files.
+        Self::ensure_provider_active(msp_id)?;
+
+        // Check that the bucket is not frozen, since accepting would add the files to it.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
 
         let file_keys = accepted_file_keys
             .file_keys_and_proofs
@@ -943,37 +1540,10 @@ where
 
             // Check if all BSPs have confirmed storing the file.
             if storage_request_metadata.bsps_confirmed == storage_request_metadata.bsps_required {
-                // Remove storage request metadata.
-                <StorageRequests<T>>::remove(&file_key_with_proof.file_key);
-                <BucketsWithStorageRequests<T>>::remove(
-                    &storage_request_metadata.bucket_id,
-                    &file_key_with_proof.file_key,
-                );
-
-                // Remove storage request bsps
-                let removed = <StorageRequestBsps<T>>::drain_prefix(&file_key_with_proof.file_key)
-                    .fold(0, |acc, _| acc.saturating_add(One::one()));
-
-                // Make sure that the expected number of bsps were removed.
-                expect_or_err!(
-                    storage_request_metadata.bsps_volunteered == removed.into(),
-                    "Number of volunteered bsps for storage request should have been removed",
-                    Error::<T>::UnexpectedNumberOfRemovedVolunteeredBsps,
-                    bool
-                );
-
-                // Return the storage request creation deposit to the user
-                T::Currency::release(
-                    &HoldReason::StorageRequestCreationHold.into(),
-                    &storage_request_metadata.owner,
-                    T::StorageRequestCreationDeposit::get(),
-                    Precision::BestEffort,
+                Self::fulfil_storage_request(
+                    file_key_with_proof.file_key,
+                    &storage_request_metadata,
                 )?;
-
-                // Notify that the storage request has been fulfilled.
-                Self::deposit_event(Event::StorageRequestFulfilled {
-                    file_key: file_key_with_proof.file_key,
-                });
             } else {
                 // Set as confirmed the MSP in the storage request metadata.
                 storage_request_metadata.msp = Some((msp_id, true));
@@ -1047,11 +1617,8 @@ where
             <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
                 .ok_or(Error::<T>::NotABsp)?;
 
-        // Check if BSP is insolvent.
-        ensure!(
-            !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(bsp_id),
-            Error::<T>::OperationNotAllowedForInsolventProvider
-        );
+        // Check if the BSP can take on new files.
+        Self::ensure_provider_active(bsp_id)?;
 
         // Check that the provider is indeed a BSP.
         ensure!(
@@ -1063,6 +1630,12 @@ where
         let mut storage_request_metadata =
             <StorageRequests<T>>::get(&file_key).ok_or(Error::<T>::StorageRequestNotFound)?;
 
+        // MSP-only storage requests are not replicated to any BSP.
+        ensure!(
+            !storage_request_metadata.bsps_required.is_zero(),
+            Error::<T>::StorageRequestIsMspOnly
+        );
+
         expect_or_err!(
             storage_request_metadata.bsps_confirmed < storage_request_metadata.bsps_required,
             "Storage request should never have confirmed bsps equal to or greater than required bsps, since they are deleted when it is reached.",
@@ -1155,11 +1728,8 @@ where
             <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
                 .ok_or(Error::<T>::NotABsp)?;
 
-        // Check if BSP is insolvent.
-        ensure!(
-            !<T::Providers as ReadProvidersInterface>::is_provider_insolvent(bsp_id),
-            Error::<T>::OperationNotAllowedForInsolventProvider
-        );
+        // Check if the BSP can take on new files.
+        Self::ensure_provider_active(bsp_id)?;
 
         // Check that the provider is indeed a BSP.
         ensure!(
@@ -1274,27 +1844,41 @@ where
                 storage_request_metadata.size,
             )?;
 
-            // Check if a payment stream between the user and provider already exists.
-            // If it does not, create it. If it does, update it.
-            match <T::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_amount_provided(&bsp_id, &storage_request_metadata.owner) {
-				Some(previous_amount_provided) => {
-					// Update the payment stream.
-                    let new_amount_provided = &previous_amount_provided.checked_add(&storage_request_metadata.size).ok_or(ArithmeticError::Overflow)?;
-					<T::PaymentStreams as PaymentStreamsInterface>::update_dynamic_rate_payment_stream(
-						&bsp_id,
-						&storage_request_metadata.owner,
-						new_amount_provided,
-					)?;
-				},
-				None => {
-					// Create the payment stream.
-					<T::PaymentStreams as PaymentStreamsInterface>::create_dynamic_rate_payment_stream(
-						&bsp_id,
-						&storage_request_metadata.owner,
-						&storage_request_metadata.size,
-					)?;
-				}
-			}
+            // If the payment of the BSPs storing this file was escrowed and there are funds left in the escrow,
+            // the BSP gets paid from it. Otherwise, it gets paid through its payment stream with the user.
+            if <T::PaymentStreams as PaymentEscrowInterface>::can_add_escrow_provider(&file_key.0) {
+                <T::PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                    &file_key.0,
+                    &bsp_id,
+                )?;
+            } else {
+                // Check if a payment stream between the user and provider already exists.
+                // If it does not, create it. If it does, update it.
+                let billable_size = Self::query_billable_size(storage_request_metadata.size);
+                match <T::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_amount_provided(&bsp_id, &storage_request_metadata.owner) {
+                    Some(previous_amount_provided) => {
+                        // Update the payment stream.
+                        let new_amount_provided = &previous_amount_provided.checked_add(&billable_size).ok_or(ArithmeticError::Overflow)?;
+                        <T::PaymentStreams as PaymentStreamsInterface>::update_dynamic_rate_payment_stream(
+                            &bsp_id,
+                            &storage_request_metadata.owner,
+                            new_amount_provided,
+                        )?;
+                    },
+                    None => {
+                        // Create the payment stream.
+                        <T::PaymentStreams as PaymentStreamsInterface>::create_dynamic_rate_payment_stream(
+                            &bsp_id,
+                            &storage_request_metadata.owner,
+                            &billable_size,
+                        )?;
+                    }
+                }
+
+                // Record what the file was billed as, to remove exactly that from the payment stream
+                // when the BSP stops storing it.
+                <BspBilledFileSizes<T>>::insert(&bsp_id, &file_key.0, billable_size);
+            }
 
             // Get the file metadata to insert into the Provider's trie under the file key.
             let file_metadata = storage_request_metadata.clone().to_file_metadata();
@@ -1313,37 +1897,7 @@ where
                     .map(|(_, confirmed)| confirmed)
                     .unwrap_or(true)
             {
-                // Remove storage request metadata.
-                <StorageRequests<T>>::remove(&file_key.0);
-                <BucketsWithStorageRequests<T>>::remove(
-                    &storage_request_metadata.bucket_id,
-                    &file_key.0,
-                );
-
-                // Remove storage request bsps
-                let removed = <StorageRequestBsps<T>>::drain_prefix(&file_key.0)
-                    .fold(0, |acc, _| acc.saturating_add(One::one()));
-
-                // Make sure that the expected number of bsps were removed.
-                expect_or_err!(
-                    storage_request_metadata.bsps_volunteered == removed.into(),
-                    "Number of volunteered bsps for storage request should have been removed",
-                    Error::<T>::UnexpectedNumberOfRemovedVolunteeredBsps,
-                    bool
-                );
-
-                // Return the storage request creation deposit to the user
-                T::Currency::release(
-                    &HoldReason::StorageRequestCreationHold.into(),
-                    &storage_request_metadata.owner,
-                    T::StorageRequestCreationDeposit::get(),
-                    Precision::BestEffort,
-                )?;
-
-                // Notify that the storage request has been fulfilled.
-                Self::deposit_event(Event::StorageRequestFulfilled {
-                    file_key: file_key.0,
-                });
+                Self::fulfil_storage_request(file_key.0, &storage_request_metadata)?;
             } else {
                 // Update storage request metadata.
                 <StorageRequests<T>>::set(&file_key.0, Some(storage_request_metadata.clone()));
@@ -1462,39 +2016,262 @@ where
             Error::<T>::StorageRequestNotAuthorized
         );
 
+        // Check that the bucket is not frozen, since revoking deletes the file from the BSPs that
+        // already confirmed storing it.
+        Self::ensure_bucket_not_frozen(&storage_request_metadata.bucket_id)?;
+
+        // Compensate the BSPs that already downloaded the file out of the creation deposit.
+        let compensation =
+            Self::compensate_bsps_for_revocation(file_key, &storage_request_metadata);
+
         Self::cleanup_storage_request(
             EitherAccountIdOrMspId::AccountId(sender),
             file_key,
             &storage_request_metadata,
+            compensation,
         )?;
 
         Ok(())
     }
 
-    /// When a storage request is revoked and has already been confirmed by some BSPs, a challenge (with priority) is
-    /// issued to force the BSPs to update their storage root to uninclude the file from their storage.
+    /// Pays the BSPs that confirmed storing the file of a storage request being revoked their
+    /// compensation, out of its creation deposit, returning the total paid.
     ///
-    /// All BSPs that have volunteered to store the file are removed from the storage request and the storage request is deleted.
-    fn cleanup_storage_request(
-        revoker: EitherAccountIdOrMspId<T>,
+    /// See [`pallet::Config::StorageRequestRevocationCompensation`] and
+    /// [`pallet::Config::StorageRequestRevocationCompensationPeriod`].
+    fn compensate_bsps_for_revocation(
         file_key: MerkleHash<T>,
         storage_request_metadata: &StorageRequestMetadata<T>,
+    ) -> BalanceOf<T> {
+        let bsps_required: u64 = storage_request_metadata.bsps_required.into();
+        let bsps_confirmed: u64 = storage_request_metadata.bsps_confirmed.into();
+        if bsps_required.is_zero() || bsps_confirmed.is_zero() {
+            return Zero::zero();
+        }
+
+        // The longer the storage request has been open, the more of its deposit the BSPs get, up to the
+        // full share after the compensation period.
+        let ticks_since_requested: u32 =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick()
+                .saturating_sub(storage_request_metadata.requested_at)
+                .saturated_into();
+        let compensation_period = T::StorageRequestRevocationCompensationPeriod::get();
+        let elapsed_share = if compensation_period.is_zero() {
+            Perbill::one()
+        } else {
+            Perbill::from_rational(
+                ticks_since_requested.min(compensation_period),
+                compensation_period,
+            )
+        };
+
+        let deposit =
+            Self::storage_request_creation_deposit(storage_request_metadata.bsps_required);
+        let compensation = Perbill::from_rational(bsps_confirmed.min(bsps_required), bsps_required)
+            .mul_floor(
+                elapsed_share
+                    .mul_floor(T::StorageRequestRevocationCompensation::get().mul_floor(deposit)),
+            );
+        let amount_per_bsp = compensation / bsps_confirmed.saturated_into::<BalanceOf<T>>();
+        if amount_per_bsp.is_zero() {
+            return Zero::zero();
+        }
+
+        let mut paid = BalanceOf::<T>::zero();
+        for (bsp_id, _) in <StorageRequestBsps<T>>::iter_prefix(&file_key)
+            .filter(|(_, bsp_metadata)| bsp_metadata.confirmed)
+        {
+            let Some(bsp) = <T::Providers as ReadProvidersInterface>::get_payment_account(bsp_id)
+            else {
+                continue;
+            };
+
+            Self::execute_deposit_transfer_or_queue_retry(
+                DepositTransfer::StorageRequestRevocationCompensation {
+                    owner: storage_request_metadata.owner.clone(),
+                    bsp,
+                    amount: amount_per_bsp,
+                },
+            );
+            Self::deposit_event(Event::BspCompensatedForRevokedStorageRequest {
+                file_key,
+                bsp_id,
+                amount: amount_per_bsp,
+            });
+            paid = paid.saturating_add(amount_per_bsp);
+        }
+
+        paid
+    }
+
+    /// Change the replication target of an open storage request.
+    ///
+    /// The target can be lowered down to the amount of BSPs that already confirmed storing the file,
+    /// in which case the storage request is fulfilled right away if its MSP (if any) accepted it. The
+    /// storage request creation deposit held from the owner is adjusted to the new target.
+    ///
+    /// *Callable by the owner of the storage request*
+    pub(crate) fn do_change_storage_request_replication_target(
+        sender: T::AccountId,
+        file_key: MerkleHash<T>,
+        replication_target: ReplicationTargetType<T>,
     ) -> DispatchResult {
-        // Check if there are already BSPs who have confirmed to store the file.
-        if storage_request_metadata.bsps_confirmed >= ReplicationTargetType::<T>::one() {
-            // Apply Remove mutation of the file key to the BSPs that have confirmed storing the file (proofs of inclusion).
+        let mut storage_request_metadata =
+            <StorageRequests<T>>::get(&file_key).ok_or(Error::<T>::StorageRequestNotFound)?;
+
+        // Check that the sender is the owner of the storage request.
+        ensure!(
+            storage_request_metadata.owner == sender,
+            Error::<T>::StorageRequestNotAuthorized
+        );
+
+        let previous_replication_target = storage_request_metadata.bsps_required;
+        ensure!(
+            replication_target != previous_replication_target,
+            Error::<T>::ReplicationTargetUnchanged
+        );
+
+        ensure!(
+            replication_target <= MaxReplicationTarget::<T>::get().into(),
+            Error::<T>::ReplicationTargetExceedsMaximum
+        );
+
+        // BSPs that already confirmed storing the file cannot be dropped from it.
+        ensure!(
+            replication_target >= storage_request_metadata.bsps_confirmed,
+            Error::<T>::ReplicationTargetBelowConfirmedBsps
+        );
+
+        // A replication target of zero means the file will only be stored by the MSP, so one is required.
+        if replication_target.is_zero() {
+            ensure!(
+                storage_request_metadata.msp.is_some(),
+                Error::<T>::MspOnlyStorageRequestWithoutMsp
+            );
+        }
+
+        // Adjust the storage request creation deposit to the new replication target.
+        let previous_deposit = Self::storage_request_creation_deposit(previous_replication_target);
+        let deposit = Self::storage_request_creation_deposit(replication_target);
+        if deposit > previous_deposit {
+            T::Currency::hold(
+                &HoldReason::StorageRequestCreationHold.into(),
+                &sender,
+                deposit.saturating_sub(previous_deposit),
+            )
+            .map_err(|_| Error::<T>::CannotHoldDeposit)?;
+        } else if deposit < previous_deposit {
+            T::Currency::release(
+                &HoldReason::StorageRequestCreationHold.into(),
+                &sender,
+                previous_deposit.saturating_sub(deposit),
+                Precision::BestEffort,
+            )?;
+        }
+
+        storage_request_metadata.bsps_required = replication_target;
+
+        Self::deposit_event(Event::StorageRequestReplicationTargetChanged {
+            file_key,
+            owner: sender,
+            previous_replication_target,
+            replication_target,
+        });
+
+        // Fulfil the storage request if it already reached the new replication target.
+        if storage_request_metadata.bsps_confirmed == replication_target
+            && storage_request_metadata
+                .msp
+                .map(|(_, confirmed)| confirmed)
+                .unwrap_or(true)
+        {
+            Self::fulfil_storage_request(file_key, &storage_request_metadata)?;
+        } else {
+            <StorageRequests<T>>::insert(&file_key, storage_request_metadata);
+        }
+
+        Ok(())
+    }
+
+    /// Queues a priority challenge to remove `file_key` from the Forests of the Providers storing it, on behalf of `issuer`.
+    ///
+    /// Priority challenges are metered per issuer over [`pallet::Config::PriorityChallengesMeteringPeriod`] blocks. Once
+    /// an issuer has queued [`pallet::Config::PriorityChallengesSoftLimitPerIssuer`] priority challenges in the current
+    /// period, the rest are deferred to the fair-share queue, which is drained in `on_idle` taking one priority
+    /// challenge from each issuer in turns. This way, a single issuer deleting many files can't starve the rest.
+    pub(crate) fn queue_priority_challenge_for_file_deletion(
+        issuer: EitherAccountIdOrMspId<T>,
+        file_key: MerkleHash<T>,
+    ) -> DispatchResult {
+        let current_block = frame_system::Pallet::<T>::block_number();
+        let period_start = current_block.saturating_sub(
+            current_block
+                .checked_rem(&T::PriorityChallengesMeteringPeriod::get())
+                .unwrap_or_else(Zero::zero),
+        );
+
+        let mut usage = PriorityChallengesUsage::<T>::get(&issuer)
+            .filter(|usage| usage.period_start == period_start)
+            .unwrap_or(PriorityChallengeUsage {
+                period_start,
+                count: 0,
+            });
+
+        if usage.count < T::PriorityChallengesSoftLimitPerIssuer::get() {
             <T::ProofDealer as shp_traits::ProofsDealerInterface>::challenge_with_priority(
                 &file_key,
                 Some(TrieRemoveMutation),
             )?;
 
+            usage.count.saturating_inc();
+            PriorityChallengesUsage::<T>::insert(&issuer, usage);
+
+            // Emit event.
+            Self::deposit_event(Event::PriorityChallengeForFileDeletionQueued { issuer, file_key });
+        } else {
+            let mut deferred = DeferredPriorityChallenges::<T>::get(&issuer);
+
+            // Issuers are only in the list of issuers to serve while they have deferred priority challenges.
+            if deferred.is_empty() {
+                DeferredPriorityChallengeIssuers::<T>::try_mutate(|issuers| {
+                    issuers.try_push(issuer.clone())
+                })
+                .map_err(|_| Error::<T>::DeferredPriorityChallengesQueueFull)?;
+            }
+
+            deferred
+                .try_push(file_key)
+                .map_err(|_| Error::<T>::DeferredPriorityChallengesQueueFull)?;
+            DeferredPriorityChallenges::<T>::insert(&issuer, deferred);
+
             // Emit event.
-            Self::deposit_event(Event::PriorityChallengeForFileDeletionQueued {
-                issuer: revoker,
+            Self::deposit_event(Event::PriorityChallengeForFileDeletionDeferred {
+                issuer,
                 file_key,
             });
         }
 
+        Ok(())
+    }
+
+    /// When a storage request is revoked and has already been confirmed by some BSPs, a challenge (with priority) is
+    /// issued to force the BSPs to update their storage root to uninclude the file from their storage.
+    ///
+    /// All BSPs that have volunteered to store the file are removed from the storage request and the storage request is deleted.
+    ///
+    /// The creation deposit is returned to the user, minus the `compensation` already paid out of it to the BSPs.
+    fn cleanup_storage_request(
+        revoker: EitherAccountIdOrMspId<T>,
+        file_key: MerkleHash<T>,
+        storage_request_metadata: &StorageRequestMetadata<T>,
+        compensation: BalanceOf<T>,
+    ) -> DispatchResult {
+        // Check if there are already BSPs who have confirmed to store the file.
+        if storage_request_metadata.bsps_confirmed >= ReplicationTargetType::<T>::one() {
+            // Apply Remove mutation of the file key to the BSPs that have confirmed storing the file (proofs of inclusion).
+            Self::queue_priority_challenge_for_file_deletion(revoker, file_key)?;
+        }
+
         // Remove storage request bsps
         let removed = <StorageRequestBsps<T>>::drain_prefix(&file_key)
             .fold(0, |acc, _| acc.saturating_add(One::one()));
@@ -1510,16 +2287,232 @@ where
         // Remove storage request.
         <StorageRequests<T>>::remove(&file_key);
 
-        // Return the storage request creation deposit to the user
-        T::Currency::release(
-            &HoldReason::StorageRequestCreationHold.into(),
-            &storage_request_metadata.owner,
-            T::StorageRequestCreationDeposit::get(),
-            Precision::BestEffort,
-        )?;
+        Self::release_storage_request_renewal_deposit(&file_key, &storage_request_metadata.owner);
 
-        // A revoked storage request is not considered active anymore.
-        <BucketsWithStorageRequests<T>>::remove(&storage_request_metadata.bucket_id, &file_key);
+        // Return the storage request creation deposit to the user, minus the compensation paid to its BSPs.
+        Self::execute_deposit_transfer_or_queue_retry(
+            DepositTransfer::StorageRequestCreationDeposit {
+                owner: storage_request_metadata.owner.clone(),
+                amount: Self::storage_request_creation_deposit(
+                    storage_request_metadata.bsps_required,
+                )
+                .saturating_sub(compensation),
+            },
+        );
+
+        // A revoked storage request is not considered active anymore.
+        <BucketsWithStorageRequests<T>>::remove(&storage_request_metadata.bucket_id, &file_key);
+
+        // Refund the escrowed payment of the BSPs that did not confirm storing the file, if any. The ones
+        // that did are removed from the escrow once they remove the file from their forest.
+        Self::execute_deposit_transfer_or_queue_retry(DepositTransfer::StorageRequestEscrow {
+            file_key,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a storage request that reached its replication target and was accepted by its MSP
+    /// (if any), returning the creation deposit to its owner and settling the escrowed payment of
+    /// its BSPs.
+    fn fulfil_storage_request(
+        file_key: MerkleHash<T>,
+        storage_request_metadata: &StorageRequestMetadata<T>,
+    ) -> DispatchResult {
+        // Remove storage request metadata.
+        <StorageRequests<T>>::remove(&file_key);
+        <BucketsWithStorageRequests<T>>::remove(&storage_request_metadata.bucket_id, &file_key);
+
+        // Remove storage request bsps
+        let removed = <StorageRequestBsps<T>>::drain_prefix(&file_key)
+            .fold(0, |acc, _| acc.saturating_add(One::one()));
+
+        // Make sure that the expected number of bsps were removed.
+        expect_or_err!(
+            storage_request_metadata.bsps_volunteered == removed.into(),
+            "Number of volunteered bsps for storage request should have been removed",
+            Error::<T>::UnexpectedNumberOfRemovedVolunteeredBsps,
+            bool
+        );
+
+        Self::release_storage_request_renewal_deposit(&file_key, &storage_request_metadata.owner);
+
+        // Return the storage request creation deposit to the user
+        Self::execute_deposit_transfer_or_queue_retry(
+            DepositTransfer::StorageRequestCreationDeposit {
+                owner: storage_request_metadata.owner.clone(),
+                amount: Self::storage_request_creation_deposit(
+                    storage_request_metadata.bsps_required,
+                ),
+            },
+        );
+
+        // Settle the escrowed payment of the BSPs, if any.
+        Self::execute_deposit_transfer_or_queue_retry(DepositTransfer::StorageRequestEscrow {
+            file_key,
+        });
+
+        // Notify that the storage request has been fulfilled.
+        Self::deposit_event(Event::StorageRequestFulfilled { file_key });
+
+        Ok(())
+    }
+
+    /// The deposit held from the User when creating a storage request with the given replication target.
+    ///
+    /// MSP-only storage requests (with a replication target of zero) use a different deposit.
+    fn storage_request_creation_deposit(
+        replication_target: ReplicationTargetType<T>,
+    ) -> BalanceOf<T> {
+        if replication_target.is_zero() {
+            T::MspOnlyStorageRequestCreationDeposit::get()
+        } else {
+            T::StorageRequestCreationDeposit::get()
+        }
+    }
+
+    /// Executes `transfer`. If it fails, it is queued to be retried in `on_idle`, if there is room for
+    /// it, and a [`Event::DepositTransferFailed`] is emitted.
+    ///
+    /// A failing deposit transfer does not prevent the operation that triggered it from going through.
+    pub(crate) fn execute_deposit_transfer_or_queue_retry(transfer: DepositTransfer<T>) {
+        if let Err(error) = Self::execute_deposit_transfer(&transfer) {
+            let retry_id = Self::queue_deposit_transfer_retry(transfer.clone());
+            Self::deposit_event(Event::DepositTransferFailed {
+                transfer,
+                retry_id,
+                error,
+            });
+        }
+    }
+
+    /// Executes `transfer` in its own storage layer, so that whatever it changed before failing is
+    /// rolled back and it can be safely retried.
+    fn execute_deposit_transfer(transfer: &DepositTransfer<T>) -> DispatchResult {
+        frame_support::storage::with_storage_layer(|| Self::do_execute_deposit_transfer(transfer))
+    }
+
+    fn do_execute_deposit_transfer(transfer: &DepositTransfer<T>) -> DispatchResult {
+        match transfer {
+            DepositTransfer::StorageRequestCreationDeposit { owner, amount } => {
+                T::Currency::release(
+                    &HoldReason::StorageRequestCreationHold.into(),
+                    owner,
+                    *amount,
+                    Precision::BestEffort,
+                )?;
+            }
+            DepositTransfer::StorageRequestEscrow { file_key } => {
+                Self::settle_storage_request_escrow(file_key)?;
+            }
+            DepositTransfer::StorageRequestRenewalDeposit { owner, amount } => {
+                T::Currency::release(
+                    &HoldReason::StorageRequestRenewalHold.into(),
+                    owner,
+                    *amount,
+                    Precision::BestEffort,
+                )?;
+            }
+            DepositTransfer::StorageRequestRevocationCompensation { owner, bsp, amount } => {
+                T::Currency::transfer_on_hold(
+                    &HoldReason::StorageRequestCreationHold.into(),
+                    owner,
+                    bsp,
+                    *amount,
+                    Precision::BestEffort,
+                    Restriction::Free,
+                    Fortitude::Polite,
+                )?;
+            }
+            DepositTransfer::FileDeletionFee {
+                payer,
+                recipient,
+                amount,
+            } => {
+                T::Currency::transfer_on_hold(
+                    &HoldReason::FileDeletionFeeHold.into(),
+                    payer,
+                    recipient,
+                    *amount,
+                    Precision::BestEffort,
+                    Restriction::Free,
+                    Fortitude::Polite,
+                )?;
+            }
+            DepositTransfer::DeletionListDeposit { owner, amount } => {
+                T::Currency::release(
+                    &HoldReason::DeletionListDepositHold.into(),
+                    owner,
+                    *amount,
+                    Precision::BestEffort,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queues a failed deposit transfer to be retried after [`pallet::Config::DepositTransferRetryBaseDelay`]
+    /// ticks, returning its retry ID.
+    ///
+    /// Returns `None` if the retry queue is full.
+    pub(crate) fn queue_deposit_transfer_retry(
+        transfer: DepositTransfer<T>,
+    ) -> Option<DepositTransferRetryId> {
+        if DepositTransferRetries::<T>::count() >= T::MaxDepositTransferRetries::get() {
+            return None;
+        }
+
+        let retry_id = NextDepositTransferRetryId::<T>::get();
+        NextDepositTransferRetryId::<T>::put(retry_id.wrapping_add(1));
+
+        let current_tick =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick();
+        DepositTransferRetries::<T>::insert(
+            retry_id,
+            DepositTransferRetry {
+                transfer,
+                attempts: 0,
+                next_attempt_at: current_tick
+                    .saturating_add(T::DepositTransferRetryBaseDelay::get()),
+            },
+        );
+
+        Some(retry_id)
+    }
+
+    pub(crate) fn do_resolve_deposit_transfer_retry(
+        retry_id: DepositTransferRetryId,
+        resolution: DepositTransferResolution,
+    ) -> DispatchResult {
+        let retry = DepositTransferRetries::<T>::take(retry_id)
+            .ok_or(Error::<T>::DepositTransferRetryNotFound)?;
+
+        match resolution {
+            DepositTransferResolution::Retry => {
+                Self::execute_deposit_transfer(&retry.transfer)?;
+                Self::deposit_event(Event::DepositTransferRetrySucceeded {
+                    retry_id,
+                    transfer: retry.transfer,
+                });
+            }
+            DepositTransferResolution::WriteOff => {
+                Self::deposit_event(Event::DepositTransferWrittenOff {
+                    retry_id,
+                    transfer: retry.transfer,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Settles the escrowed payment of the BSPs of a storage request that is no longer open, if there is one,
+    /// refunding to the user the share of the BSPs that did not confirm storing the file.
+    fn settle_storage_request_escrow(file_key: &MerkleHash<T>) -> DispatchResult {
+        if <T::PaymentStreams as PaymentEscrowInterface>::escrow_exists(file_key) {
+            <T::PaymentStreams as PaymentEscrowInterface>::settle_escrow_replication(file_key)?;
+        }
 
         Ok(())
     }
@@ -1763,21 +2756,31 @@ where
             &bsp_id, file_size,
         )?;
 
-        // Update the payment stream between the user and the BSP. If the new amount provided is zero, delete it instead.
-        let new_amount_provided = <T::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_amount_provided(&bsp_id, &file_owner)
-			.ok_or(Error::<T>::DynamicRatePaymentStreamNotFound)?
-			.saturating_sub(file_size);
-        if new_amount_provided == Zero::zero() {
-            <T::PaymentStreams as PaymentStreamsInterface>::delete_dynamic_rate_payment_stream(
-                &bsp_id,
-                &file_owner,
+        // If the BSP is being paid for this file from an escrow, stop paying it and refund the rest of its share to the user.
+        // Otherwise, update the payment stream between the user and the BSP. If the new amount provided is zero, delete it instead.
+        if <T::PaymentStreams as PaymentEscrowInterface>::is_escrow_provider(&file_key, &bsp_id) {
+            <T::PaymentStreams as PaymentEscrowInterface>::remove_escrow_provider(
+                &file_key, &bsp_id,
             )?;
         } else {
-            <T::PaymentStreams as PaymentStreamsInterface>::update_dynamic_rate_payment_stream(
-                &bsp_id,
-                &file_owner,
-                &new_amount_provided,
-            )?;
+            // Files confirmed before their billed size was recorded were billed by their actual size.
+            let billed_size =
+                <BspBilledFileSizes<T>>::take(&bsp_id, &file_key).unwrap_or(file_size);
+            let new_amount_provided = <T::PaymentStreams as PaymentStreamsInterface>::get_dynamic_rate_payment_stream_amount_provided(&bsp_id, &file_owner)
+                .ok_or(Error::<T>::DynamicRatePaymentStreamNotFound)?
+                .saturating_sub(billed_size);
+            if new_amount_provided == Zero::zero() {
+                <T::PaymentStreams as PaymentStreamsInterface>::delete_dynamic_rate_payment_stream(
+                    &bsp_id,
+                    &file_owner,
+                )?;
+            } else {
+                <T::PaymentStreams as PaymentStreamsInterface>::update_dynamic_rate_payment_stream(
+                    &bsp_id,
+                    &file_owner,
+                    &new_amount_provided,
+                )?;
+            }
         }
 
         // If the new capacity used for this BSP is 0, stop its randomness cycle.
@@ -1879,6 +2882,15 @@ where
                     &sp_id, &owner,
                 )?;
             }
+            <BspBilledFileSizes<T>>::remove(&sp_id, &file_key);
+
+            // Stop paying the BSP for this file from an escrow, if it was.
+            if <T::PaymentStreams as PaymentEscrowInterface>::is_escrow_provider(&file_key, &sp_id)
+            {
+                <T::PaymentStreams as PaymentEscrowInterface>::remove_escrow_provider(
+                    &file_key, &sp_id,
+                )?;
+            }
 
             new_root
         } else {
@@ -1985,44 +2997,20 @@ where
             Error::<T>::NotBucketOwner
         );
 
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
         let msp_id = <T::Providers as ReadBucketsInterface>::get_msp_of_bucket(&bucket_id)?;
 
+        // The file is going away, so nobody should be granted access to it anymore.
+        <FileAccessLists<T>>::remove(file_key);
+
         let file_key_included = match maybe_inclusion_forest_proof {
             // If the user did not supply a proof of inclusion, queue a pending deletion file request.
             // This will leave a window of time for the MSP to provide the proof of (non-)inclusion.
             // If the proof is not provided within the TTL, the hook will queue a priority challenge to remove the file key from all the providers.
             None => {
-                let pending_file_deletion_requests = <PendingFileDeletionRequests<T>>::get(&sender);
-
-                // Check if the file key is already in the pending deletion requests.
-                let pending_file_deletion_request = PendingFileDeletionRequest {
-                    user: sender.clone(),
-                    file_key,
-                    bucket_id,
-                    file_size: size,
-                };
-                ensure!(
-                    !pending_file_deletion_requests.contains(&pending_file_deletion_request),
-                    Error::<T>::FileKeyAlreadyPendingDeletion
-                );
-
-                // Add the file key to the pending deletion requests.
-                PendingFileDeletionRequests::<T>::try_append(
-                    &sender,
-                    pending_file_deletion_request,
-                )
-                .map_err(|_| Error::<T>::MaxUserPendingDeletionRequestsReached)?;
-
-                // Queue the expiration item.
-                let expiration_item = ExpirationItem::PendingFileDeletionRequests(
-                    FileDeletionRequestExpirationItem::<T> {
-                        user: sender.clone(),
-                        file_key,
-                        bucket_id,
-                        file_size: size,
-                    },
-                );
-                Self::enqueue_expiration_item(expiration_item)?;
+                Self::queue_pending_file_deletion_request(&sender, bucket_id, file_key, size)?;
 
                 false
             }
@@ -2064,16 +3052,10 @@ where
                 <T::Providers as MutateBucketsInterface>::decrease_bucket_size(&bucket_id, size)?;
 
                 // Initiate the priority challenge to remove the file key from all the providers.
-                <T::ProofDealer as shp_traits::ProofsDealerInterface>::challenge_with_priority(
-                    &file_key,
-                    Some(TrieRemoveMutation),
-                )?;
-
-                // Emit event.
-                Self::deposit_event(Event::PriorityChallengeForFileDeletionQueued {
-                    issuer: EitherAccountIdOrMspId::<T>::AccountId(sender.clone()),
+                Self::queue_priority_challenge_for_file_deletion(
+                    EitherAccountIdOrMspId::<T>::AccountId(sender.clone()),
                     file_key,
-                });
+                )?;
 
                 true
             }
@@ -2082,90 +3064,963 @@ where
         Ok((file_key_included, msp_id))
     }
 
-    pub(crate) fn do_pending_file_deletion_request_submit_proof(
+    /// Deletes a batch of files from a bucket owned by `sender`, queuing a pending file deletion
+    /// request for each of them.
+    ///
+    /// Either all the requests are queued or none of them is.
+    pub(crate) fn do_delete_files(
         sender: T::AccountId,
-        user: T::AccountId,
-        file_key: MerkleHash<T>,
-        file_size: StorageData<T>,
         bucket_id: BucketIdFor<T>,
-        forest_proof: ForestProof<T>,
-    ) -> Result<(bool, ProviderIdFor<T>), DispatchError> {
-        let msp_id =
-            <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
-                .ok_or(Error::<T>::NotAMsp)?;
+        files: BoundedVec<FileDeletionRequestItem<T>, MaxUserPendingDeletionRequests<T>>,
+    ) -> Result<
+        (
+            BoundedVec<MerkleHash<T>, MaxUserPendingDeletionRequests<T>>,
+            Option<ProviderIdFor<T>>,
+        ),
+        DispatchError,
+    > {
+        ensure!(!files.is_empty(), Error::<T>::EmptyFileDeletionBatch);
 
-        // Check that the provider is indeed an MSP.
+        // Check if sender is the owner of the bucket.
         ensure!(
-            <T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id),
-            Error::<T>::NotAMsp
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(&sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
         );
 
-        ensure!(
-            <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(&msp_id, &bucket_id),
-            Error::<T>::MspNotStoringBucket
-        );
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
 
-        let pending_file_deletion_requests = <PendingFileDeletionRequests<T>>::get(&user);
+        let msp_id = <T::Providers as ReadBucketsInterface>::get_msp_of_bucket(&bucket_id)?;
 
-        // Check if the file key is in the pending deletion requests.
-        let pending_file_deletion_request_to_prove = PendingFileDeletionRequest {
-            user: user.clone(),
-            file_key,
-            bucket_id,
-            file_size,
-        };
+        let mut file_keys = BoundedVec::new();
+        for file in files {
+            // Check that the metadata corresponds to the expected file key.
+            let computed_file_key = Self::compute_file_key(
+                sender.clone(),
+                bucket_id,
+                file.location,
+                file.size,
+                file.fingerprint,
+            );
+            ensure!(
+                file.file_key == computed_file_key,
+                Error::<T>::InvalidFileKeyMetadata
+            );
+
+            // A file key repeated in the batch is rejected here, since its first occurrence is
+            // already pending deletion.
+            Self::queue_pending_file_deletion_request(
+                &sender,
+                bucket_id,
+                file.file_key,
+                file.size,
+            )?;
+
+            file_keys
+                .try_push(file.file_key)
+                .map_err(|_| Error::<T>::MaxUserPendingDeletionRequestsReached)?;
+        }
+
+        Ok((file_keys, msp_id))
+    }
 
+    /// Registers a deletion list of `length` files owned by `sender`, identified by the root of its
+    /// Merkle tree, returning the deposit held for it.
+    pub(crate) fn do_register_deletion_list(
+        sender: &T::AccountId,
+        list_root: MerkleHash<T>,
+        length: u32,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        ensure!(!length.is_zero(), Error::<T>::DeletionListEmpty);
+
+        let max_length = 1u64
+            .checked_shl(T::MaxDeletionListProofDepth::get())
+            .unwrap_or(u64::MAX);
         ensure!(
-            pending_file_deletion_requests.contains(&pending_file_deletion_request_to_prove),
-            Error::<T>::FileKeyNotPendingDeletion
+            u64::from(length) <= max_length,
+            Error::<T>::DeletionListTooLong
         );
 
-        // Get the root of the bucket.
-        let bucket_root =
-            <T::Providers as shp_traits::ReadBucketsInterface>::get_root_bucket(&bucket_id)
-                .ok_or(Error::<T>::BucketNotFound)?;
+        ensure!(
+            !<DeletionLists<T>>::contains_key(sender, list_root),
+            Error::<T>::DeletionListAlreadyRegistered
+        );
 
-        // Verify the proof of inclusion.
-        let proven_keys =
-            <T::ProofDealer as shp_traits::ProofsDealerInterface>::verify_generic_forest_proof(
-                &bucket_root,
-                &[file_key],
-                &forest_proof,
-            )?;
+        let item_deposit = T::DeletionListItemDeposit::get();
+        let deposit = item_deposit.saturating_mul(length.into());
+        T::Currency::hold(&HoldReason::DeletionListDepositHold.into(), sender, deposit)
+            .map_err(|_| Error::<T>::CannotHoldDeposit)?;
+
+        <DeletionLists<T>>::insert(
+            sender,
+            list_root,
+            DeletionList {
+                length,
+                next_index: 0,
+                item_deposit,
+            },
+        );
 
-        let file_key_included = proven_keys.contains(&file_key);
+        Ok(deposit)
+    }
 
-        // If the file key was part of the forest, remove it from the forest and update the root of the bucket.
-        if file_key_included {
-            // Compute new root after removing file key from forest partial trie.
-            let new_root =
-                <T::ProofDealer as shp_traits::ProofsDealerInterface>::generic_apply_delta(
-                    &bucket_root,
-                    &[(file_key, TrieRemoveMutation::default().into())],
-                    &forest_proof,
-                )?;
+    /// Requests the deletion of the next file of the deletion list of `owner` with root `list_root`,
+    /// on behalf of `owner`, and returns the deposit held for the file to `owner`.
+    ///
+    /// Returns the index of the file in the list, its file key, the result of requesting its
+    /// deletion (whether a proof of inclusion was supplied and the MSP of the bucket) and whether it
+    /// was the last file of the list. If requesting the deletion fails without a proof of inclusion
+    /// and for a reason that can never change, its changes are rolled back and the file is skipped.
+    /// Any other failure is returned, leaving the file as the next one of the list.
+    pub(crate) fn do_execute_deletion_list_item(
+        owner: &T::AccountId,
+        list_root: MerkleHash<T>,
+        bucket_id: BucketIdFor<T>,
+        location: FileLocation<T>,
+        size: StorageData<T>,
+        fingerprint: Fingerprint<T>,
+        merkle_proof: DeletionListProof<T>,
+        maybe_inclusion_forest_proof: Option<ForestProof<T>>,
+    ) -> Result<
+        (
+            u32,
+            MerkleHash<T>,
+            Result<(bool, Option<ProviderIdFor<T>>), DispatchError>,
+            bool,
+        ),
+        DispatchError,
+    > {
+        let mut deletion_list =
+            <DeletionLists<T>>::get(owner, list_root).ok_or(Error::<T>::DeletionListNotFound)?;
 
-            // Update root of the Bucket.
-            <T::Providers as shp_traits::MutateBucketsInterface>::change_root_bucket(
-                bucket_id, new_root,
-            )?;
+        let file_key = Self::compute_file_key(
+            owner.clone(),
+            bucket_id,
+            location.clone(),
+            size,
+            fingerprint,
+        );
 
-            // Decrease size of the bucket.
-            <T::Providers as MutateBucketsInterface>::decrease_bucket_size(&bucket_id, file_size)?;
+        // Files are deleted in order, so that each of them can only be deleted once.
+        let index = deletion_list.next_index;
+        ensure!(
+            Self::verify_deletion_list_proof(list_root, index, file_key, &merkle_proof),
+            Error::<T>::InvalidDeletionListProof
+        );
 
-            // Initiate the priority challenge to remove the file key from all the providers.
-            <T::ProofDealer as shp_traits::ProofsDealerInterface>::challenge_with_priority(
-                &file_key,
-                Some(TrieRemoveMutation),
-            )?;
+        // A failure with a proof of inclusion could be caused by a wrong proof, so it does not skip
+        // the file. Without one, the file is only skipped if it could never be deleted, which would
+        // otherwise block the rest of the list. Failures that can go away, such as a full queue of
+        // pending deletion requests or a fee that cannot be held, are left to be retried.
+        let with_proof_of_inclusion = maybe_inclusion_forest_proof.is_some();
+        let deletion_result = if <T::Providers as ReadBucketsInterface>::bucket_exists(&bucket_id) {
+            frame_support::storage::with_storage_layer(|| {
+                Self::do_delete_file(
+                    owner.clone(),
+                    bucket_id,
+                    file_key,
+                    location,
+                    fingerprint,
+                    size,
+                    maybe_inclusion_forest_proof,
+                )
+            })
+        } else {
+            Err(Error::<T>::BucketNotFound.into())
+        };
+        if let Err(error) = deletion_result {
+            if with_proof_of_inclusion || !Self::is_permanent_deletion_failure(&error) {
+                return Err(error);
+            }
+        }
 
-            // Emit event.
-            Self::deposit_event(Event::PriorityChallengeForFileDeletionQueued {
-                issuer: EitherAccountIdOrMspId::<T>::MspId(msp_id),
-                file_key,
+        if !deletion_list.item_deposit.is_zero() {
+            Self::execute_deposit_transfer_or_queue_retry(DepositTransfer::DeletionListDeposit {
+                owner: owner.clone(),
+                amount: deletion_list.item_deposit,
             });
         }
 
+        deletion_list.next_index.saturating_inc();
+        let completed = deletion_list.next_index >= deletion_list.length;
+        if completed {
+            <DeletionLists<T>>::remove(owner, list_root);
+        } else {
+            <DeletionLists<T>>::insert(owner, list_root, deletion_list);
+        }
+
+        Ok((index, file_key, deletion_result, completed))
+    }
+
+    /// Whether requesting the deletion of a file failed for a reason that no later attempt can
+    /// change: the bucket is not owned by the requester, it no longer exists or the metadata does not
+    /// match the file key.
+    fn is_permanent_deletion_failure(error: &DispatchError) -> bool {
+        [
+            Error::<T>::NotBucketOwner,
+            Error::<T>::BucketNotFound,
+            Error::<T>::InvalidFileKeyMetadata,
+        ]
+        .into_iter()
+        .any(|permanent| *error == permanent.into())
+    }
+
+    /// Cancels the deletion list of `sender` with root `list_root` and returns the deposit of its
+    /// remaining files to `sender`.
+    ///
+    /// Returns how many of its files were already processed.
+    pub(crate) fn do_cancel_deletion_list(
+        sender: &T::AccountId,
+        list_root: MerkleHash<T>,
+    ) -> Result<u32, DispatchError> {
+        let deletion_list =
+            <DeletionLists<T>>::take(sender, list_root).ok_or(Error::<T>::DeletionListNotFound)?;
+
+        let remaining = deletion_list
+            .length
+            .saturating_sub(deletion_list.next_index);
+        let deposit = deletion_list.item_deposit.saturating_mul(remaining.into());
+        if !deposit.is_zero() {
+            Self::execute_deposit_transfer_or_queue_retry(DepositTransfer::DeletionListDeposit {
+                owner: sender.clone(),
+                amount: deposit,
+            });
+        }
+
+        Ok(deletion_list.next_index)
+    }
+
+    /// Registers an upload grant for `grantee` into the bucket `bucket_id` owned by `sender`, replacing
+    /// any previous one.
+    pub(crate) fn do_create_upload_grant(
+        sender: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        grantee: T::AccountId,
+        size_budget: StorageData<T>,
+        expires_at: BlockNumberFor<T>,
+    ) -> DispatchResult {
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+        ensure!(
+            !size_budget.is_zero(),
+            Error::<T>::UploadGrantBudgetCannotBeZero
+        );
+        ensure!(
+            expires_at > frame_system::Pallet::<T>::block_number(),
+            Error::<T>::UploadGrantExpirationInThePast
+        );
+
+        <UploadGrants<T>>::insert(
+            bucket_id,
+            grantee,
+            UploadGrant {
+                owner: sender.clone(),
+                remaining_budget: size_budget,
+                expires_at,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Removes the upload grant of `grantee` into the bucket `bucket_id`, which has to have been given
+    /// by `sender`.
+    pub(crate) fn do_revoke_upload_grant(
+        sender: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        grantee: &T::AccountId,
+    ) -> DispatchResult {
+        let grant =
+            <UploadGrants<T>>::get(bucket_id, grantee).ok_or(Error::<T>::UploadGrantNotFound)?;
+        ensure!(&grant.owner == sender, Error::<T>::NotBucketOwner);
+
+        <UploadGrants<T>>::remove(bucket_id, grantee);
+
+        Ok(())
+    }
+
+    /// Gives `grantee` read access to `target` until tick `expires_at`, replacing any previous grant
+    /// of `grantee` for it. The bucket of `target` has to be owned by `sender`.
+    pub(crate) fn do_create_access_grant(
+        sender: &T::AccountId,
+        target: AccessGrantTarget<T>,
+        grantee: T::AccountId,
+        expires_at: TickNumber<T>,
+    ) -> DispatchResult {
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(sender, target.bucket_id())?,
+            Error::<T>::NotBucketOwner
+        );
+        let current_tick =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick();
+        ensure!(
+            expires_at > current_tick,
+            Error::<T>::AccessGrantExpirationInThePast
+        );
+
+        <AccessGrants<T>>::try_mutate(&target, |maybe_grants| {
+            let grants = maybe_grants.get_or_insert_with(AccessGrantList::<T>::default);
+            // Expired grants not removed yet don't count towards the limit.
+            grants.retain(|grant| grant.expires_at > current_tick);
+
+            match grants.iter_mut().find(|grant| grant.grantee == grantee) {
+                Some(grant) => grant.expires_at = expires_at,
+                None => grants
+                    .try_push(AccessGrant {
+                        grantee,
+                        expires_at,
+                    })
+                    .map_err(|_| Error::<T>::AccessGrantsFull)?,
+            }
+
+            Ok::<_, DispatchError>(())
+        })?;
+
+        // The expired grants are removed starting from the tick the first one was created at (zero
+        // meaning that no grant has been created yet).
+        if <NextAccessGrantExpirationTickToCleanUp<T>>::get().is_zero() {
+            <NextAccessGrantExpirationTickToCleanUp<T>>::put(current_tick.max(One::one()));
+        }
+        <AccessGrantExpirations<T>>::insert(expires_at, target, ());
+
+        Ok(())
+    }
+
+    /// Removes the access grant of `grantee` to `target`, whose bucket has to be owned by `sender`.
+    pub(crate) fn do_revoke_access_grant(
+        sender: &T::AccountId,
+        target: &AccessGrantTarget<T>,
+        grantee: &T::AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(sender, target.bucket_id())?,
+            Error::<T>::NotBucketOwner
+        );
+
+        <AccessGrants<T>>::try_mutate_exists(target, |maybe_grants| {
+            let grants = maybe_grants
+                .as_mut()
+                .ok_or(Error::<T>::AccessGrantNotFound)?;
+            let position = grants
+                .iter()
+                .position(|grant| &grant.grantee == grantee)
+                .ok_or(Error::<T>::AccessGrantNotFound)?;
+            grants.remove(position);
+
+            if grants.is_empty() {
+                *maybe_grants = None;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Issues a storage request on behalf of the owner of the bucket `bucket_id`, with the upload grant
+    /// of `grantee`, deducting the size of the file from its budget.
+    ///
+    /// Returns the owner of the bucket, the file key and the remaining budget of the grant.
+    pub(crate) fn do_request_storage_with_grant(
+        grantee: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        location: FileLocation<T>,
+        fingerprint: Fingerprint<T>,
+        size: StorageData<T>,
+        msp_id: Option<ProviderIdFor<T>>,
+        peer_ids: PeerIds<T>,
+        replication_target: Option<ReplicationTargetType<T>>,
+    ) -> Result<(T::AccountId, MerkleHash<T>, StorageData<T>), DispatchError> {
+        let mut grant =
+            <UploadGrants<T>>::get(bucket_id, grantee).ok_or(Error::<T>::UploadGrantNotFound)?;
+
+        ensure!(
+            frame_system::Pallet::<T>::block_number() < grant.expires_at,
+            Error::<T>::UploadGrantExpired
+        );
+        grant.remaining_budget = grant
+            .remaining_budget
+            .checked_sub(&size)
+            .ok_or(Error::<T>::UploadGrantBudgetExceeded)?;
+
+        // The owner of the bucket is checked again here, in case the grant outlived the bucket.
+        let file_key = Self::do_request_storage(
+            grant.owner.clone(),
+            bucket_id,
+            location,
+            fingerprint,
+            size,
+            msp_id,
+            replication_target,
+            Some(peer_ids),
+        )?;
+
+        let owner = grant.owner.clone();
+        let remaining_budget = grant.remaining_budget;
+        <UploadGrants<T>>::insert(bucket_id, grantee, grant);
+
+        Ok((owner, file_key, remaining_budget))
+    }
+
+    /// Registers the offer of the MSP of `sender` to transfer the bucket `bucket_id` to `new_msp_id`,
+    /// replacing any previous offer for the bucket.
+    ///
+    /// Returns the ID of the MSP making the offer, the owner of the bucket and the block at which the
+    /// offer expires.
+    pub(crate) fn do_msp_offer_bucket_transfer(
+        sender: T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        new_msp_id: ProviderIdFor<T>,
+    ) -> Result<(ProviderIdFor<T>, T::AccountId, BlockNumberFor<T>), DispatchError> {
+        // Check if the sender is a Provider.
+        let msp_id = <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender)
+            .ok_or(Error::<T>::NotAMsp)?;
+
+        // Check if the MSP is indeed an MSP.
+        ensure!(
+            <T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id),
+            Error::<T>::NotAMsp
+        );
+
+        // Check if the MSP is storing the bucket.
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(&msp_id, &bucket_id),
+            Error::<T>::MspNotStoringBucket
+        );
+
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
+        // Check if the new MSP is indeed an MSP, that can take on new buckets and is allowed to by governance.
+        ensure!(
+            <T::Providers as ReadStorageProvidersInterface>::is_msp(&new_msp_id),
+            Error::<T>::NotAMsp
+        );
+        Self::ensure_provider_active(new_msp_id)?;
+        ensure!(
+            Self::is_msp_eligible(&new_msp_id),
+            Error::<T>::MspNotEligible
+        );
+
+        // Check if the bucket is already stored by the new MSP, which also covers offering it to itself.
+        ensure!(
+            !<T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(
+                &new_msp_id,
+                &bucket_id
+            ),
+            Error::<T>::MspAlreadyStoringBucket
+        );
+
+        ensure!(
+            !<PendingBucketsToMove<T>>::contains_key(&bucket_id),
+            Error::<T>::BucketIsBeingMoved
+        );
+
+        let owner = <T::Providers as ReadBucketsInterface>::get_bucket_owner(&bucket_id)?;
+        let expires_at = frame_system::Pallet::<T>::block_number()
+            .saturating_add(T::MoveBucketRequestTtl::get().into());
+
+        <BucketTransferOffers<T>>::insert(
+            bucket_id,
+            BucketTransferOffer {
+                msp_id,
+                new_msp_id,
+                expires_at,
+            },
+        );
+
+        Ok((msp_id, owner, expires_at))
+    }
+
+    /// Accepts or rejects, as the owner `sender` of the bucket `bucket_id`, the offer of its MSP to
+    /// transfer it. Accepting the offer requests the move of the bucket to the new MSP.
+    ///
+    /// Returns the offer that was responded to.
+    pub(crate) fn do_respond_bucket_transfer_offer(
+        sender: T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        response: BucketMoveRequestResponse,
+    ) -> Result<BucketTransferOffer<T>, DispatchError> {
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(&sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+
+        let offer = <BucketTransferOffers<T>>::take(bucket_id)
+            .ok_or(Error::<T>::BucketTransferOfferNotFound)?;
+
+        if response == BucketMoveRequestResponse::Accepted {
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < offer.expires_at,
+                Error::<T>::BucketTransferOfferExpired
+            );
+
+            // The MSP that made the offer might no longer be storing the bucket.
+            ensure!(
+                <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(
+                    &offer.msp_id,
+                    &bucket_id
+                ),
+                Error::<T>::MspNotStoringBucket
+            );
+
+            Self::do_request_move_bucket(sender, bucket_id, offer.new_msp_id)?;
+        }
+
+        Ok(offer)
+    }
+
+    /// Checks that `file_key` is the leaf at `index` of the deletion list Merkle tree with root
+    /// `list_root`.
+    ///
+    /// Leaves are the hash of the SCALE encoded `(index, file_key)` pair, and inner nodes the hash of
+    /// the encoded `(left, right)` pair of their children, using the same hasher as file keys. The
+    /// bits of `index`, starting from the least significant one, tell whether each node in the path
+    /// from the leaf to the root is a left (`0`) or right (`1`) child, so the tree of a list of `n`
+    /// files has `2^d` leaves for the smallest `d` such that `n <= 2^d`, padded with default hashes.
+    pub fn verify_deletion_list_proof(
+        list_root: MerkleHash<T>,
+        index: u32,
+        file_key: MerkleHash<T>,
+        proof: &[MerkleHash<T>],
+    ) -> bool {
+        let mut node = FileKeyHasher::<T>::hash_of(&(index, file_key));
+        let mut position = index;
+
+        for sibling in proof {
+            node = if position & 1 == 0 {
+                FileKeyHasher::<T>::hash_of(&(node, *sibling))
+            } else {
+                FileKeyHasher::<T>::hash_of(&(*sibling, node))
+            };
+            position >>= 1;
+        }
+
+        // A proof shorter than the path to the leaf at `index` would prove a different leaf.
+        position.is_zero() && node == list_root
+    }
+
+    /// Queues a pending file deletion request for `file_key`, holding the deletion fee from `sender`.
+    ///
+    /// This leaves a window of time for the MSP to provide the proof of (non-)inclusion. If the
+    /// proof is not provided within the TTL, the hook will queue a priority challenge to remove the
+    /// file key from all the providers.
+    fn queue_pending_file_deletion_request(
+        sender: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        file_key: MerkleHash<T>,
+        size: StorageData<T>,
+    ) -> DispatchResult {
+        let pending_file_deletion_requests = <PendingFileDeletionRequests<T>>::get(sender);
+
+        // Check if the file key is already in the pending deletion requests.
+        let pending_file_deletion_request = PendingFileDeletionRequest {
+            user: sender.clone(),
+            file_key,
+            bucket_id,
+            file_size: size,
+        };
+        ensure!(
+            !pending_file_deletion_requests.contains(&pending_file_deletion_request),
+            Error::<T>::FileKeyAlreadyPendingDeletion
+        );
+
+        // Add the file key to the pending deletion requests.
+        PendingFileDeletionRequests::<T>::try_append(sender, pending_file_deletion_request)
+            .map_err(|_| Error::<T>::MaxUserPendingDeletionRequestsReached)?;
+
+        // Queue the expiration item.
+        let expiration_item =
+            ExpirationItem::PendingFileDeletionRequests(FileDeletionRequestExpirationItem::<T> {
+                user: sender.clone(),
+                file_key,
+                bucket_id,
+                file_size: size,
+            });
+        Self::enqueue_expiration_item(expiration_item)?;
+
+        // Hold the fee paying whoever executes the deletion.
+        let fee = T::FileDeletionFee::get().min(T::MaxFileDeletionFee::get());
+        if !fee.is_zero() {
+            T::Currency::hold(&HoldReason::FileDeletionFeeHold.into(), sender, fee)?;
+            PendingFileDeletionFees::<T>::insert(&file_key, fee);
+        }
+
+        Ok(())
+    }
+
+    /// Set the pointer to the notification preferences of `who`, or clear it if `None`.
+    pub(crate) fn do_set_notification_preferences(
+        who: &T::AccountId,
+        preferences: Option<NotificationPreferences<T>>,
+    ) {
+        match preferences {
+            Some(preferences) => <UserNotificationPreferences<T>>::insert(who, preferences),
+            None => <UserNotificationPreferences<T>>::remove(who),
+        }
+    }
+
+    /// Add `account` to the access list of the file owned by `sender` with the given metadata,
+    /// returning its file key.
+    pub(crate) fn do_grant_file_access(
+        sender: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        location: FileLocation<T>,
+        size: StorageData<T>,
+        fingerprint: Fingerprint<T>,
+        account: T::AccountId,
+    ) -> Result<MerkleHash<T>, DispatchError> {
+        let file_key = Self::owned_file_key(sender, bucket_id, location, size, fingerprint)?;
+
+        <FileAccessLists<T>>::try_mutate(file_key, |maybe_access_list| {
+            let access_list = maybe_access_list.get_or_insert_with(FileAccessList::<T>::default);
+            ensure!(
+                !access_list.contains(&account),
+                Error::<T>::FileAccessAlreadyGranted
+            );
+            access_list
+                .try_push(account)
+                .map_err(|_| Error::<T>::FileAccessListFull)?;
+
+            Ok::<_, DispatchError>(())
+        })?;
+
+        Ok(file_key)
+    }
+
+    /// Remove `account` from the access list of the file owned by `sender` with the given
+    /// metadata, returning its file key.
+    pub(crate) fn do_revoke_file_access(
+        sender: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        location: FileLocation<T>,
+        size: StorageData<T>,
+        fingerprint: Fingerprint<T>,
+        account: &T::AccountId,
+    ) -> Result<MerkleHash<T>, DispatchError> {
+        let file_key = Self::owned_file_key(sender, bucket_id, location, size, fingerprint)?;
+
+        <FileAccessLists<T>>::try_mutate_exists(file_key, |maybe_access_list| {
+            let access_list = maybe_access_list
+                .as_mut()
+                .ok_or(Error::<T>::FileAccessNotGranted)?;
+            let position = access_list
+                .iter()
+                .position(|granted| granted == account)
+                .ok_or(Error::<T>::FileAccessNotGranted)?;
+            access_list.remove(position);
+
+            if access_list.is_empty() {
+                *maybe_access_list = None;
+            }
+
+            Ok::<_, DispatchError>(())
+        })?;
+
+        Ok(file_key)
+    }
+
+    /// Computes the file key of the file owned by `sender` with the given metadata, checking that
+    /// `sender` still owns the bucket.
+    fn owned_file_key(
+        sender: &T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        location: FileLocation<T>,
+        size: StorageData<T>,
+        fingerprint: Fingerprint<T>,
+    ) -> Result<MerkleHash<T>, DispatchError> {
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+
+        Ok(Self::compute_file_key(
+            sender.clone(),
+            bucket_id,
+            location,
+            size,
+            fingerprint,
+        ))
+    }
+
+    pub(crate) fn do_request_move_file(
+        sender: T::AccountId,
+        bucket_id: BucketIdFor<T>,
+        file_key: MerkleHash<T>,
+        location: FileLocation<T>,
+        size: StorageData<T>,
+        fingerprint: Fingerprint<T>,
+        new_location: FileLocation<T>,
+    ) -> Result<(MerkleHash<T>, Option<ProviderIdFor<T>>), DispatchError> {
+        // Check that the metadata corresponds to the expected file key.
+        ensure!(
+            file_key
+                == Self::compute_file_key(sender.clone(), bucket_id, location, size, fingerprint),
+            Error::<T>::InvalidFileKeyMetadata
+        );
+
+        // Check if sender is the owner of the bucket.
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_owner(&sender, &bucket_id)?,
+            Error::<T>::NotBucketOwner
+        );
+
+        // Check that the bucket is not frozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
+        // The file can't be moved while it is still being stored.
+        ensure!(
+            !<StorageRequests<T>>::contains_key(&file_key),
+            Error::<T>::StorageRequestExists
+        );
+
+        // The escrow paying for the file is bound to its file key, so it would no longer match it.
+        ensure!(
+            !<T::PaymentStreams as PaymentEscrowInterface>::escrow_exists(&file_key),
+            Error::<T>::FileHasEscrowedPayment
+        );
+
+        ensure!(
+            !<PendingFileMoveRequests<T>>::contains_key(&file_key),
+            Error::<T>::FileMoveAlreadyRequested
+        );
+
+        let new_file_key = Self::compute_file_key(
+            sender.clone(),
+            bucket_id,
+            new_location.clone(),
+            size,
+            fingerprint,
+        );
+        ensure!(new_file_key != file_key, Error::<T>::FileMoveToSameLocation);
+
+        let msp_id = <T::Providers as ReadBucketsInterface>::get_msp_of_bucket(&bucket_id)?;
+
+        <PendingFileMoveRequests<T>>::insert(
+            &file_key,
+            FileMove {
+                owner: sender,
+                bucket_id,
+                new_file_key,
+                new_location,
+                size,
+                fingerprint,
+            },
+        );
+
+        Ok((new_file_key, msp_id))
+    }
+
+    pub(crate) fn do_msp_confirm_file_move(
+        sender: T::AccountId,
+        file_key: MerkleHash<T>,
+        forest_proof: ForestProof<T>,
+    ) -> Result<(ProviderIdFor<T>, FileMove<T>, MerkleHash<T>), DispatchError> {
+        let msp_id =
+            <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
+                .ok_or(Error::<T>::NotAMsp)?;
+
+        // Check that the provider is indeed an MSP.
+        ensure!(
+            <T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id),
+            Error::<T>::NotAMsp
+        );
+
+        let file_move = <PendingFileMoveRequests<T>>::get(&file_key)
+            .ok_or(Error::<T>::FileMoveRequestNotFound)?;
+        let bucket_id = file_move.bucket_id;
+
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(&msp_id, &bucket_id),
+            Error::<T>::MspNotStoringBucket
+        );
+
+        // Check that the bucket is not frozen. The request is kept pending until it is unfrozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
+        // Get the root of the bucket.
+        let bucket_root =
+            <T::Providers as shp_traits::ReadBucketsInterface>::get_root_bucket(&bucket_id)
+                .ok_or(Error::<T>::BucketNotFound)?;
+
+        // Verify the proof of inclusion of the current file key and non-inclusion of the new one.
+        let proven_keys =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::verify_generic_forest_proof(
+                &bucket_root,
+                &[file_key, file_move.new_file_key],
+                &forest_proof,
+            )?;
+        ensure!(
+            proven_keys.contains(&file_key),
+            Error::<T>::ExpectedInclusionProof
+        );
+        ensure!(
+            !proven_keys.contains(&file_move.new_file_key),
+            Error::<T>::ExpectedNonInclusionProof
+        );
+
+        // Compute the new root after replacing the current file key with the new one in the forest partial trie.
+        let new_bucket_root =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::generic_apply_delta(
+                &bucket_root,
+                &[
+                    (file_key, TrieRemoveMutation::default().into()),
+                    (
+                        file_move.new_file_key,
+                        TrieAddMutation::new(file_move.clone().to_file_metadata().encode()).into(),
+                    ),
+                ],
+                &forest_proof,
+            )?;
+
+        // Update root of the Bucket.
+        <T::Providers as shp_traits::MutateBucketsInterface>::change_root_bucket(
+            bucket_id,
+            new_bucket_root,
+        )?;
+
+        // Let the BSPs storing the file apply the move to their forest.
+        <PendingFileMoveRequests<T>>::remove(&file_key);
+        <MovedFiles<T>>::insert(&file_key, file_move.clone());
+
+        Ok((msp_id, file_move, new_bucket_root))
+    }
+
+    pub(crate) fn do_bsp_confirm_file_move(
+        sender: T::AccountId,
+        file_key: MerkleHash<T>,
+        forest_proof: ForestProof<T>,
+    ) -> Result<(ProviderIdFor<T>, MerkleHash<T>, MerkleHash<T>), DispatchError> {
+        // Get the SP ID of the sender
+        let bsp_id =
+            <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
+                .ok_or(Error::<T>::NotASp)?;
+
+        // Ensure the ID belongs to a BSP, not a MSP
+        ensure!(
+            <T::Providers as ReadStorageProvidersInterface>::is_bsp(&bsp_id),
+            Error::<T>::NotABsp
+        );
+
+        let file_move = <MovedFiles<T>>::get(&file_key).ok_or(Error::<T>::FileNotMoved)?;
+
+        // Verify the proof of inclusion of the previous file key and non-inclusion of the new one.
+        let proven_keys =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::verify_forest_proof(
+                &bsp_id,
+                &[file_key, file_move.new_file_key],
+                &forest_proof,
+            )?;
+        ensure!(
+            proven_keys.contains(&file_key),
+            Error::<T>::ExpectedInclusionProof
+        );
+        ensure!(
+            !proven_keys.contains(&file_move.new_file_key),
+            Error::<T>::ExpectedNonInclusionProof
+        );
+
+        // Compute the new root after replacing the previous file key with the new one in the forest partial trie.
+        let new_file_key = file_move.new_file_key;
+        let new_root = <T::ProofDealer as shp_traits::ProofsDealerInterface>::apply_delta(
+            &bsp_id,
+            &[
+                (file_key, TrieRemoveMutation::default().into()),
+                (
+                    new_file_key,
+                    TrieAddMutation::new(file_move.to_file_metadata().encode()).into(),
+                ),
+            ],
+            &forest_proof,
+        )?;
+
+        // Update root of BSP.
+        <T::Providers as shp_traits::MutateProvidersInterface>::update_root(bsp_id, new_root)?;
+
+        Ok((bsp_id, new_file_key, new_root))
+    }
+
+    pub(crate) fn do_pending_file_deletion_request_submit_proof(
+        sender: T::AccountId,
+        user: T::AccountId,
+        file_key: MerkleHash<T>,
+        file_size: StorageData<T>,
+        bucket_id: BucketIdFor<T>,
+        forest_proof: ForestProof<T>,
+    ) -> Result<(bool, ProviderIdFor<T>), DispatchError> {
+        let msp_id =
+            <T::Providers as shp_traits::ReadProvidersInterface>::get_provider_id(sender.clone())
+                .ok_or(Error::<T>::NotAMsp)?;
+
+        // Check that the provider is indeed an MSP.
+        ensure!(
+            <T::Providers as ReadStorageProvidersInterface>::is_msp(&msp_id),
+            Error::<T>::NotAMsp
+        );
+
+        ensure!(
+            <T::Providers as ReadBucketsInterface>::is_bucket_stored_by_msp(&msp_id, &bucket_id),
+            Error::<T>::MspNotStoringBucket
+        );
+
+        let pending_file_deletion_requests = <PendingFileDeletionRequests<T>>::get(&user);
+
+        // Check if the file key is in the pending deletion requests.
+        let pending_file_deletion_request_to_prove = PendingFileDeletionRequest {
+            user: user.clone(),
+            file_key,
+            bucket_id,
+            file_size,
+        };
+
+        ensure!(
+            pending_file_deletion_requests.contains(&pending_file_deletion_request_to_prove),
+            Error::<T>::FileKeyNotPendingDeletion
+        );
+
+        // Check that the bucket is not frozen. The request is kept pending until it is unfrozen.
+        Self::ensure_bucket_not_frozen(&bucket_id)?;
+
+        // Get the root of the bucket.
+        let bucket_root =
+            <T::Providers as shp_traits::ReadBucketsInterface>::get_root_bucket(&bucket_id)
+                .ok_or(Error::<T>::BucketNotFound)?;
+
+        // Verify the proof of inclusion.
+        let proven_keys =
+            <T::ProofDealer as shp_traits::ProofsDealerInterface>::verify_generic_forest_proof(
+                &bucket_root,
+                &[file_key],
+                &forest_proof,
+            )?;
+
+        let file_key_included = proven_keys.contains(&file_key);
+
+        // If the file key was part of the forest, remove it from the forest and update the root of the bucket.
+        if file_key_included {
+            // Compute new root after removing file key from forest partial trie.
+            let new_root =
+                <T::ProofDealer as shp_traits::ProofsDealerInterface>::generic_apply_delta(
+                    &bucket_root,
+                    &[(file_key, TrieRemoveMutation::default().into())],
+                    &forest_proof,
+                )?;
+
+            // Update root of the Bucket.
+            <T::Providers as shp_traits::MutateBucketsInterface>::change_root_bucket(
+                bucket_id, new_root,
+            )?;
+
+            // Decrease size of the bucket.
+            <T::Providers as MutateBucketsInterface>::decrease_bucket_size(&bucket_id, file_size)?;
+
+            // Initiate the priority challenge to remove the file key from all the providers.
+            Self::queue_priority_challenge_for_file_deletion(
+                EitherAccountIdOrMspId::<T>::MspId(msp_id),
+                file_key,
+            )?;
+        }
+
         // Delete the pending deletion request.
         <PendingFileDeletionRequests<T>>::mutate(&user, |requests| {
             requests.retain(|pending_file_deletion_request| {
@@ -2173,6 +4028,15 @@ where
             });
         });
 
+        // Pay the deletion fee to the MSP for executing the deletion.
+        if let Some(amount) = PendingFileDeletionFees::<T>::take(&file_key) {
+            Self::execute_deposit_transfer_or_queue_retry(DepositTransfer::FileDeletionFee {
+                payer: user,
+                recipient: sender,
+                amount,
+            });
+        }
+
         Ok((file_key_included, msp_id))
     }
 
@@ -2328,6 +4192,14 @@ where
     }
 }
 
+impl<T: pallet::Config> OnProviderStatusChange<ProviderIdFor<T>> for Pallet<T> {
+    /// Queues the Provider to cancel or reroute, in `on_idle`, the storage requests and move bucket
+    /// requests waiting on it.
+    fn on_provider_status_change(provider_id: &ProviderIdFor<T>, status: ProviderStatusChange) {
+        ProvidersWithStatusChange::<T>::insert(provider_id, status);
+    }
+}
+
 mod hooks {
     use crate::types::RejectedStorageRequestReason;
     use crate::{
@@ -2336,21 +4208,34 @@ mod hooks {
         utils::{
             BucketIdFor, EitherAccountIdOrMspId, FileDeletionRequestExpirationItem, ProviderIdFor,
         },
-        Event, FileDeletionRequestExpirations, MaxReplicationTarget, NextStartingBlockToCleanUp,
-        Pallet, PendingFileDeletionRequests, PendingMoveBucketRequests, StorageRequestBsps,
+        DeferredPriorityChallengeIssuers, DeferredPriorityChallenges, Event,
+        FileDeletionRequestExpirations, MaxReplicationTarget,
+        NextBlockToNotifyExpiringStorageRequests, NextStartingBlockToCleanUp, Pallet,
+        PendingFileDeletionRequests, PendingMoveBucketRequests, StorageRequestBsps,
         StorageRequestExpirations, StorageRequests,
     };
-    use crate::{MoveBucketRequestExpirations, PendingBucketsToMove};
+    use crate::{
+        types::{
+            AccessGrantTarget, DepositTransfer, ProviderStatusChangeProgress,
+            StorageRequestMetadata, TickNumber,
+        },
+        AccessGrantExpirations, AccessGrants, DepositTransferRetries, MoveBucketRequestExpirations,
+        NextAccessGrantExpirationTickToCleanUp, PendingBucketsToMove,
+        ProviderStatusChangeInProgress, ProvidersWithStatusChange,
+    };
     use frame_system::pallet_prelude::BlockNumberFor;
-    use shp_traits::TrieRemoveMutation;
+    use shp_traits::{ProviderStatusChange, TrieRemoveMutation};
     use sp_runtime::{
         traits::{Get, One, Zero},
         Saturating,
     };
+    use sp_std::vec::Vec;
     use sp_weights::WeightMeter;
 
+    use crate::weights::WeightInfo;
+
     impl<T: pallet::Config> Pallet<T> {
-        pub(crate) fn do_on_poll(weight: &mut WeightMeter) {
+        pub(crate) fn do_on_poll(current_block: BlockNumberFor<T>, weight: &mut WeightMeter) {
             let current_data_price_per_giga_unit =
                 <T::PaymentStreams as shp_traits::MutatePricePerGigaUnitPerTickInterface>::get_price_per_giga_unit_per_tick();
             weight.consume(T::DbWeight::get().reads(1));
@@ -2368,6 +4253,70 @@ mod hooks {
                 );
                 weight.consume(T::DbWeight::get().writes(1));
             }
+
+            Self::notify_storage_requests_expiring_soon(current_block, weight);
+        }
+
+        /// Emit a [`Event::StorageRequestExpiringSoon`] for the open storage requests expiring within
+        /// the next [`pallet::Config::StorageRequestExpirationNotificationLeadTime`] blocks, that
+        /// were not notified yet.
+        ///
+        /// Expiration blocks are processed as a whole, and processing stops once
+        /// [`pallet::Config::MaxStorageRequestExpirationNotificationsPerBlock`] notifications have been
+        /// emitted or there is not enough weight left to process a whole expiration block. The rest
+        /// is picked up in the next blocks.
+        fn notify_storage_requests_expiring_soon(
+            current_block: BlockNumberFor<T>,
+            weight: &mut WeightMeter,
+        ) {
+            let lead_time = T::StorageRequestExpirationNotificationLeadTime::get();
+            if lead_time.is_zero() {
+                return;
+            }
+
+            let db_weight = T::DbWeight::get();
+            if !weight.can_consume(db_weight.reads_writes(1, 1)) {
+                return;
+            }
+
+            // Storage requests expiring in the current block or before are not notified anymore.
+            let mut block_to_notify = NextBlockToNotifyExpiringStorageRequests::<T>::get()
+                .max(current_block.saturating_add(BlockNumberFor::<T>::one()));
+            weight.consume(db_weight.reads(1));
+
+            let last_block_to_notify = current_block.saturating_add(lead_time.into());
+            let max_notifications = T::MaxStorageRequestExpirationNotificationsPerBlock::get();
+            // Reading the expirations of a block and checking whether each storage request is still open.
+            let worst_case_block_weight =
+                db_weight.reads(T::MaxExpiredItemsInBlock::get().saturating_add(1).into());
+
+            let mut notifications = 0u32;
+            while block_to_notify <= last_block_to_notify
+                && notifications < max_notifications
+                && weight.can_consume(worst_case_block_weight.saturating_add(db_weight.writes(1)))
+            {
+                let expiring_storage_requests =
+                    StorageRequestExpirations::<T>::get(&block_to_notify);
+                weight.consume(db_weight.reads(1));
+
+                for file_key in expiring_storage_requests {
+                    // Storage requests are not removed from the expirations list when fulfilled or
+                    // revoked, so only notify the ones that are still open.
+                    weight.consume(db_weight.reads(1));
+                    if StorageRequests::<T>::contains_key(&file_key) {
+                        Self::deposit_event(Event::StorageRequestExpiringSoon {
+                            file_key,
+                            expires_at: block_to_notify,
+                        });
+                        notifications.saturating_inc();
+                    }
+                }
+
+                block_to_notify.saturating_inc();
+            }
+
+            NextBlockToNotifyExpiringStorageRequests::<T>::put(block_to_notify);
+            weight.consume(db_weight.writes(1));
         }
 
         pub(crate) fn do_on_idle(
@@ -2393,9 +4342,437 @@ mod hooks {
                 meter.consume(db_weight.writes(1));
             }
 
+            Self::queue_deferred_priority_challenges(meter);
+
+            Self::process_provider_status_changes(meter);
+
+            Self::process_deposit_transfer_retries(meter);
+
+            Self::process_expired_access_grants(meter);
+
             meter
         }
 
+        /// Removes the access grants that expired, going through the ticks they expire at from
+        /// [`NextAccessGrantExpirationTickToCleanUp`] up to the current one.
+        fn process_expired_access_grants(meter: &mut WeightMeter) {
+            let db_weight = T::DbWeight::get();
+
+            // Reading the next tick to clean up.
+            if !meter.can_consume(db_weight.reads(1)) {
+                return;
+            }
+            meter.consume(db_weight.reads(1));
+            let starting_tick = <NextAccessGrantExpirationTickToCleanUp<T>>::get();
+            // No access grant has been created yet.
+            if starting_tick.is_zero() {
+                return;
+            }
+
+            let current_tick =
+                <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick();
+
+            // Every expiration takes draining it, and reading and writing the grants of its target.
+            // Checking whether a tick has any expiration left takes one more read.
+            let weight_per_expiration = db_weight.reads_writes(2, 2);
+            let mut tick = starting_tick;
+            'ticks: while tick <= current_tick && meter.can_consume(db_weight.reads(1)) {
+                meter.consume(db_weight.reads(1));
+
+                let mut expirations = <AccessGrantExpirations<T>>::drain_prefix(tick);
+                loop {
+                    // The rest of the expirations of the tick are left for the next block.
+                    if !meter.can_consume(weight_per_expiration) {
+                        break 'ticks;
+                    }
+                    let Some((target, ())) = expirations.next() else {
+                        break;
+                    };
+                    meter.consume(weight_per_expiration);
+
+                    Self::remove_expired_access_grants(target, current_tick);
+                }
+
+                tick.saturating_inc();
+            }
+
+            if tick != starting_tick {
+                <NextAccessGrantExpirationTickToCleanUp<T>>::put(tick);
+                meter.consume(db_weight.writes(1));
+            }
+        }
+
+        /// Removes the grants of `target` expired as of `current_tick`.
+        fn remove_expired_access_grants(target: AccessGrantTarget<T>, current_tick: TickNumber<T>) {
+            <AccessGrants<T>>::mutate_exists(&target, |maybe_grants| {
+                let Some(grants) = maybe_grants.as_mut() else {
+                    return;
+                };
+
+                grants.retain(|grant| {
+                    if grant.expires_at > current_tick {
+                        return true;
+                    }
+
+                    Self::deposit_event(Event::AccessGrantExpired {
+                        target: target.clone(),
+                        grantee: grant.grantee.clone(),
+                    });
+                    false
+                });
+
+                if grants.is_empty() {
+                    *maybe_grants = None;
+                }
+            });
+        }
+
+        /// Retries the failed deposit transfers in [`DepositTransferRetries`] that are due.
+        ///
+        /// After every failed attempt, the wait until the next one doubles. Deposit transfers are given
+        /// up on after [`pallet::Config::MaxDepositTransferRetryAttempts`] retries.
+        fn process_deposit_transfer_retries(meter: &mut WeightMeter) {
+            let db_weight = T::DbWeight::get();
+
+            // Reading the count of pending retries.
+            if !meter.can_consume(db_weight.reads(1)) {
+                return;
+            }
+            meter.consume(db_weight.reads(1));
+            if DepositTransferRetries::<T>::count().is_zero() {
+                return;
+            }
+
+            let current_tick =
+                <T::ProofDealer as shp_traits::ProofsDealerInterface>::get_current_tick();
+
+            // Reading each retry, and executing and updating the due ones.
+            let weight_per_retry = db_weight.reads_writes(3, 3);
+            let mut retries = DepositTransferRetries::<T>::iter();
+            while meter.can_consume(weight_per_retry) {
+                let Some((retry_id, mut retry)) = retries.next() else {
+                    break;
+                };
+                meter.consume(db_weight.reads(1));
+
+                if retry.next_attempt_at > current_tick {
+                    continue;
+                }
+                meter.consume(weight_per_retry.saturating_sub(db_weight.reads(1)));
+
+                let error = match Self::execute_deposit_transfer(&retry.transfer) {
+                    Ok(()) => {
+                        DepositTransferRetries::<T>::remove(retry_id);
+                        Self::deposit_event(Event::DepositTransferRetrySucceeded {
+                            retry_id,
+                            transfer: retry.transfer,
+                        });
+                        continue;
+                    }
+                    Err(error) => error,
+                };
+
+                retry.attempts.saturating_inc();
+                if retry.attempts >= T::MaxDepositTransferRetryAttempts::get() {
+                    DepositTransferRetries::<T>::remove(retry_id);
+                    Self::deposit_event(Event::DepositTransferAbandoned {
+                        retry_id,
+                        transfer: retry.transfer,
+                        error,
+                    });
+                    continue;
+                }
+
+                let backoff = 1u32.checked_shl(retry.attempts).unwrap_or(u32::MAX);
+                retry.next_attempt_at = current_tick.saturating_add(
+                    T::DepositTransferRetryBaseDelay::get().saturating_mul(backoff.into()),
+                );
+                let next_attempt_at = retry.next_attempt_at;
+                DepositTransferRetries::<T>::insert(retry_id, retry);
+                Self::deposit_event(Event::DepositTransferRetryFailed {
+                    retry_id,
+                    attempts: retry.attempts,
+                    next_attempt_at,
+                    error,
+                });
+            }
+        }
+
+        /// Cancels or reroutes the storage requests and move bucket requests waiting on the Providers
+        /// that became insolvent or were deregistered, one Provider at a time:
+        /// - The move bucket requests to the Provider are cancelled.
+        /// - The storage requests that selected the Provider as MSP, and were not accepted by it yet,
+        ///   are cancelled.
+        /// - If the Provider volunteered for storage requests without confirming storing the file, it
+        ///   is removed from them so that other BSPs can volunteer instead.
+        ///
+        /// At most [`pallet::Config::MaxProviderStatusChangeItemsPerBlock`] storage requests and move
+        /// bucket requests are checked per block. The rest are picked up in the next blocks.
+        fn process_provider_status_changes(meter: &mut WeightMeter) {
+            let db_weight = T::DbWeight::get();
+            let mut items_left = T::MaxProviderStatusChangeItemsPerBlock::get();
+
+            // Reading the progress and the next Provider to process, and writing them back.
+            while !items_left.is_zero() && meter.can_consume(db_weight.reads_writes(2, 2)) {
+                let mut progress = match ProviderStatusChangeInProgress::<T>::get() {
+                    Some(progress) => progress,
+                    None => {
+                        let Some((provider_id, status)) =
+                            ProvidersWithStatusChange::<T>::iter().next()
+                        else {
+                            meter.consume(db_weight.reads(2));
+                            return;
+                        };
+                        ProvidersWithStatusChange::<T>::remove(&provider_id);
+                        meter.consume(db_weight.writes(1));
+
+                        ProviderStatusChangeProgress {
+                            provider_id,
+                            status,
+                            move_bucket_requests_processed: false,
+                            last_checked_storage_request: None,
+                        }
+                    }
+                };
+                meter.consume(db_weight.reads(2));
+                items_left.saturating_dec();
+
+                if !progress.move_bucket_requests_processed {
+                    progress.move_bucket_requests_processed =
+                        Self::cancel_move_bucket_requests_to_unavailable_msp(
+                            &progress,
+                            &mut items_left,
+                            meter,
+                        );
+                }
+
+                let processed = progress.move_bucket_requests_processed
+                    && Self::process_storage_requests_waiting_on_unavailable_provider(
+                        &mut progress,
+                        &mut items_left,
+                        meter,
+                    );
+                meter.consume(db_weight.writes(1));
+
+                if !processed {
+                    ProviderStatusChangeInProgress::<T>::put(progress);
+                    return;
+                }
+
+                ProviderStatusChangeInProgress::<T>::kill();
+
+                Self::deposit_event(Event::ProviderStatusChangeProcessed {
+                    provider_id: progress.provider_id,
+                    status: progress.status,
+                });
+            }
+        }
+
+        /// Cancels the move bucket requests to the Provider being processed, if it is an MSP.
+        ///
+        /// Returns whether all of them have been cancelled.
+        fn cancel_move_bucket_requests_to_unavailable_msp(
+            progress: &ProviderStatusChangeProgress<T>,
+            items_left: &mut u32,
+            meter: &mut WeightMeter,
+        ) -> bool {
+            let db_weight = T::DbWeight::get();
+            let weight_per_request = db_weight.writes(2);
+
+            // Fetch one more than can be processed, to know if there are any left.
+            let bucket_ids = PendingMoveBucketRequests::<T>::iter_key_prefix(&progress.provider_id)
+                .take((*items_left as usize).saturating_add(1))
+                .collect::<Vec<_>>();
+            meter.consume(db_weight.reads(bucket_ids.len() as u64));
+
+            for bucket_id in bucket_ids {
+                if items_left.is_zero() || !meter.can_consume(weight_per_request) {
+                    return false;
+                }
+
+                PendingMoveBucketRequests::<T>::remove(&progress.provider_id, &bucket_id);
+                PendingBucketsToMove::<T>::remove(&bucket_id);
+
+                items_left.saturating_dec();
+                meter.consume(weight_per_request);
+
+                Self::deposit_event(Event::MoveBucketRequestCancelledByUnavailableMsp {
+                    bucket_id,
+                    msp_id: progress.provider_id,
+                    status: progress.status,
+                });
+            }
+
+            true
+        }
+
+        /// Checks the open storage requests, starting after the last one checked, cancelling or
+        /// rerouting the ones waiting on the Provider being processed.
+        ///
+        /// Returns whether all the open storage requests have been checked.
+        fn process_storage_requests_waiting_on_unavailable_provider(
+            progress: &mut ProviderStatusChangeProgress<T>,
+            items_left: &mut u32,
+            meter: &mut WeightMeter,
+        ) -> bool {
+            let db_weight = T::DbWeight::get();
+            // Checking whether the Provider volunteered for the storage request, and cleaning it up
+            // in the worst case, which removes every BSP that volunteered for it.
+            let weight_per_request = db_weight.reads(1).saturating_add(
+                db_weight.writes(
+                    MaxReplicationTarget::<T>::get()
+                        .saturating_plus_one()
+                        .saturating_plus_one()
+                        .into(),
+                ),
+            );
+
+            let storage_requests = match progress.last_checked_storage_request {
+                Some(file_key) => {
+                    StorageRequests::<T>::iter_from(StorageRequests::<T>::hashed_key_for(file_key))
+                }
+                None => StorageRequests::<T>::iter(),
+            };
+            // Fetch one more than can be processed, to know if there are any left.
+            let storage_requests = storage_requests
+                .take((*items_left as usize).saturating_add(1))
+                .collect::<Vec<_>>();
+            meter.consume(db_weight.reads(storage_requests.len() as u64));
+
+            for (file_key, storage_request_metadata) in storage_requests {
+                if items_left.is_zero() || !meter.can_consume(weight_per_request) {
+                    return false;
+                }
+
+                Self::process_storage_request_waiting_on_unavailable_provider(
+                    progress.provider_id,
+                    progress.status,
+                    file_key,
+                    storage_request_metadata,
+                );
+                progress.last_checked_storage_request = Some(file_key);
+
+                items_left.saturating_dec();
+                meter.consume(weight_per_request);
+            }
+
+            true
+        }
+
+        fn process_storage_request_waiting_on_unavailable_provider(
+            provider_id: ProviderIdFor<T>,
+            status: ProviderStatusChange,
+            file_key: MerkleHash<T>,
+            mut storage_request_metadata: StorageRequestMetadata<T>,
+        ) {
+            // The storage request can't be fulfilled without its MSP accepting it, so it is cancelled.
+            if storage_request_metadata.msp == Some((provider_id, false)) {
+                // Error should not happen, we ignore it.
+                let _ = Self::cleanup_storage_request(
+                    EitherAccountIdOrMspId::MspId(provider_id),
+                    file_key,
+                    &storage_request_metadata,
+                    Zero::zero(),
+                );
+
+                Self::deposit_event(Event::StorageRequestCancelledByUnavailableMsp {
+                    file_key,
+                    owner: storage_request_metadata.owner,
+                    msp_id: provider_id,
+                    status,
+                });
+                return;
+            }
+
+            // BSPs that already confirmed storing the file keep it, but the ones that didn't are
+            // removed so that other BSPs can take their place.
+            let volunteered_without_confirming =
+                StorageRequestBsps::<T>::get(&file_key, &provider_id)
+                    .is_some_and(|bsp| !bsp.confirmed);
+            if volunteered_without_confirming {
+                StorageRequestBsps::<T>::remove(&file_key, &provider_id);
+                storage_request_metadata.bsps_volunteered.saturating_dec();
+                StorageRequests::<T>::insert(&file_key, storage_request_metadata);
+
+                Self::deposit_event(Event::UnavailableBspVolunteerRemoved {
+                    file_key,
+                    bsp_id: provider_id,
+                    status,
+                });
+            }
+        }
+
+        /// Moves deferred priority challenges for file deletions from the fair-share queue to the priority
+        /// challenges queue, taking one from each issuer in turns, up to
+        /// [`pallet::Config::MaxDeferredPriorityChallengesQueuedPerBlock`].
+        ///
+        /// Stops early if the priority challenges queue is full, leaving the rest for the next block.
+        fn queue_deferred_priority_challenges(meter: &mut WeightMeter) {
+            let db_weight = T::DbWeight::get();
+            // Reading and writing the issuers.
+            let minimum_required_weight = db_weight.reads_writes(1, 1);
+            // Reading and writing the deferred priority challenges of the issuer and the priority challenges queue.
+            let weight_per_challenge = db_weight.reads_writes(2, 2);
+
+            if !meter.can_consume(minimum_required_weight) {
+                return;
+            }
+
+            let mut issuers = DeferredPriorityChallengeIssuers::<T>::get();
+            meter.consume(db_weight.reads(1));
+
+            if issuers.is_empty() {
+                return;
+            }
+
+            let mut queued = 0u32;
+            while queued < T::MaxDeferredPriorityChallengesQueuedPerBlock::get()
+                && !issuers.is_empty()
+                && meter.can_consume(weight_per_challenge.saturating_add(db_weight.writes(1)))
+            {
+                let issuer = issuers.remove(0);
+                let mut deferred = DeferredPriorityChallenges::<T>::get(&issuer);
+
+                let Some(file_key) = deferred.first().copied() else {
+                    meter.consume(db_weight.reads(1));
+                    continue;
+                };
+
+                if <T::ProofDealer as shp_traits::ProofsDealerInterface>::challenge_with_priority(
+                    &file_key,
+                    Some(TrieRemoveMutation),
+                )
+                .is_err()
+                {
+                    // The priority challenges queue is full, so the issuer keeps its turn for the next block.
+                    // This can't fail, since the issuer was just removed from the list.
+                    let _ = issuers.try_insert(0, issuer);
+                    meter.consume(db_weight.reads(2));
+                    break;
+                }
+
+                deferred.remove(0);
+                if deferred.is_empty() {
+                    DeferredPriorityChallenges::<T>::remove(&issuer);
+                } else {
+                    DeferredPriorityChallenges::<T>::insert(&issuer, deferred);
+                    // Move the issuer to the back of the list. This can't fail, since the issuer was just removed from it.
+                    let _ = issuers.try_push(issuer.clone());
+                }
+                meter.consume(weight_per_challenge);
+
+                Self::deposit_event(Event::DeferredPriorityChallengeForFileDeletionQueued {
+                    issuer,
+                    file_key,
+                });
+
+                queued.saturating_inc();
+            }
+
+            DeferredPriorityChallengeIssuers::<T>::put(issuers);
+            meter.consume(db_weight.writes(1));
+        }
+
         fn process_block_expired_items(block: BlockNumberFor<T>, meter: &mut WeightMeter) {
             let db_weight = T::DbWeight::get();
             let minimum_required_weight_processing_expired_items = db_weight.reads_writes(1, 1);
@@ -2464,7 +4841,14 @@ mod hooks {
                     .into(),
             );
 
-            if !meter.can_consume(potential_weight) {
+            // Renewing the storage request, if its owner opted into it, costs as much as issuing a new one.
+            let renewal_weight = T::WeightInfo::issue_storage_request();
+
+            if !meter.can_consume(
+                potential_weight
+                    .saturating_add(db_weight.reads(1))
+                    .saturating_add(renewal_weight),
+            ) {
                 return;
             }
 
@@ -2480,13 +4864,26 @@ mod hooks {
                 Some(storage_request_metadata) => match storage_request_metadata.msp {
                     Some((msp_id, msp_confirmed)) => {
                         if msp_confirmed {
+                            Self::execute_deposit_transfer_or_queue_retry(
+                                DepositTransfer::StorageRequestEscrow { file_key },
+                            );
+
                             Self::deposit_event(Event::StorageRequestExpired { file_key });
+
+                            meter.consume(db_weight.reads(1));
+                            if Self::renew_expired_storage_request(
+                                file_key,
+                                &storage_request_metadata,
+                            ) {
+                                meter.consume(renewal_weight);
+                            }
                         } else {
                             // Error should not happen, we ignore it.
                             let _ = Self::cleanup_storage_request(
                                 EitherAccountIdOrMspId::MspId(msp_id),
                                 file_key,
                                 &storage_request_metadata,
+                                Zero::zero(),
                             );
 
                             Self::deposit_event(Event::StorageRequestRejected {
@@ -2496,7 +4893,17 @@ mod hooks {
                         }
                     }
                     None => {
+                        Self::execute_deposit_transfer_or_queue_retry(
+                            DepositTransfer::StorageRequestEscrow { file_key },
+                        );
+
                         Self::deposit_event(Event::StorageRequestExpired { file_key });
+
+                        meter.consume(db_weight.reads(1));
+                        if Self::renew_expired_storage_request(file_key, &storage_request_metadata)
+                        {
+                            meter.consume(renewal_weight);
+                        }
                     }
                 },
                 None => {
@@ -2510,7 +4917,7 @@ mod hooks {
             meter: &mut WeightMeter,
         ) {
             let db_weight = T::DbWeight::get();
-            let potential_weight = db_weight.reads_writes(2, 3);
+            let potential_weight = db_weight.reads_writes(4, 7);
 
             if !meter.can_consume(potential_weight) {
                 return;
@@ -2529,6 +4936,17 @@ mod hooks {
                     None => return,
                 };
 
+            // Files of a frozen bucket can't be deleted, so keep the request pending until the
+            // next expiration. If it can't be requeued, it stays pending for the MSP to prove it
+            // once the bucket is unfrozen.
+            if <FrozenBuckets<T>>::contains_key(&expired_file_deletion_request.bucket_id) {
+                let _ = Self::enqueue_expiration_item(ExpirationItem::PendingFileDeletionRequests(
+                    expired_file_deletion_request,
+                ));
+                meter.consume(potential_weight);
+                return;
+            }
+
             // Remove the file key from the pending deletion requests.
             PendingFileDeletionRequests::<T>::mutate(
                 &expired_file_deletion_request.user,
@@ -2539,6 +4957,17 @@ mod hooks {
 
             let user = expired_file_deletion_request.user.clone();
 
+            // Nobody executed the deletion, so its fee goes to the treasury.
+            if let Some(amount) =
+                PendingFileDeletionFees::<T>::take(&expired_file_deletion_request.file_key)
+            {
+                Self::execute_deposit_transfer_or_queue_retry(DepositTransfer::FileDeletionFee {
+                    payer: user.clone(),
+                    recipient: T::TreasuryAccount::get(),
+                    amount,
+                });
+            }
+
             // Attempt to decrease the bucket size while also reducing the fixed rate payment stream between the user and the MSP
             if let Err(e) =
                 <T::Providers as shp_traits::MutateBucketsInterface>::decrease_bucket_size(
@@ -2563,21 +4992,17 @@ mod hooks {
             }
 
             // Queue a priority challenge to remove the file key from all the providers.
-            let _ = <T::ProofDealer as shp_traits::ProofsDealerInterface>::challenge_with_priority(
-                &expired_file_deletion_request.file_key,
-                Some(TrieRemoveMutation),
+            if Self::queue_priority_challenge_for_file_deletion(
+                EitherAccountIdOrMspId::<T>::AccountId(user.clone()),
+                expired_file_deletion_request.file_key,
             )
-            .map_err(|_| {
+            .is_err()
+            {
                 Self::deposit_event(Event::FailedToQueuePriorityChallenge {
                     user: user.clone(),
                     file_key: expired_file_deletion_request.file_key,
                 });
-            });
-
-            Self::deposit_event(Event::PriorityChallengeForFileDeletionQueued {
-                issuer: EitherAccountIdOrMspId::<T>::AccountId(user.clone()),
-                file_key: expired_file_deletion_request.file_key,
-            });
+            }
 
             meter.consume(potential_weight);
         }
@@ -6,7 +6,7 @@ use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
-    #[api_version(1)]
+    #[api_version(2)]
     pub trait PaymentStreamsApi<ProviderId, Balance, AccountId>
     where
         ProviderId: codec::Codec,
@@ -16,6 +16,8 @@ sp_api::decl_runtime_apis! {
         fn get_users_with_debt_over_threshold(provider_id: &ProviderId, threshold: Balance) -> Result<Vec<AccountId>, GetUsersWithDebtOverThresholdError>;
         fn get_users_of_payment_streams_of_provider(provider_id: &ProviderId) -> Vec<AccountId>;
         fn get_providers_with_payment_streams_with_user(user_account: &AccountId) -> Vec<ProviderId>;
+        #[api_version(2)]
+        fn get_current_price_per_giga_unit_per_tick() -> Balance;
     }
 }
 
@@ -21,7 +21,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-use frame_support::StorageDoubleMap;
+use frame_support::{traits::fungible::InspectHold, StorageDoubleMap};
 use frame_system::pallet_prelude::BlockNumberFor;
 pub use pallet::*;
 use scale_info::prelude::vec::Vec;
@@ -37,8 +37,14 @@ pub mod pallet {
         Blake2_128Concat,
     };
     use frame_system::pallet_prelude::{BlockNumberFor, *};
-    use shp_traits::{ProofSubmittersInterface, ReadProvidersInterface, SystemMetricsInterface};
-    use sp_runtime::traits::{AtLeast32BitUnsigned, Convert, MaybeDisplay, One, Saturating};
+    use shp_traits::{
+        NativeTokenPriceOracle, ProofSubmittersInterface, ReadProvidersInterface,
+        SystemMetricsInterface,
+    };
+    use sp_runtime::{
+        traits::{AtLeast32BitUnsigned, Convert, MaybeDisplay, One, Saturating},
+        FixedU128, Perbill,
+    };
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
@@ -115,6 +121,25 @@ pub mod pallet {
         /// This is used to prevent a Provider from charging too many Users in a single block, which could lead to a DoS attack.
         #[pallet::constant]
         type MaxUsersToCharge: Get<u32>;
+
+        /// The on-chain oracle feed of the native token price, used to make the price per giga-unit per tick
+        /// track the target set in [`FiatPriceTarget`] when it is enabled.
+        type PriceOracle: NativeTokenPriceOracle<BlockNumberFor<Self>>;
+
+        /// The maximum amount of blocks since the last update of the oracle feed for its price to be used.
+        /// Older prices are considered stale, and the price per giga-unit per tick is then left as is.
+        #[pallet::constant]
+        type MaxOraclePriceAge: Get<BlockNumberFor<Self>>;
+
+        /// The number of ticks between two adjustments of the price per giga-unit per tick towards the target
+        /// set in [`FiatPriceTarget`].
+        #[pallet::constant]
+        type OraclePriceAdjustmentPeriod: Get<BlockNumberFor<Self>>;
+
+        /// The maximum relative change of the price per giga-unit per tick in a single adjustment towards the
+        /// target set in [`FiatPriceTarget`].
+        #[pallet::constant]
+        type MaxOraclePriceAdjustment: Get<Perbill>;
     }
 
     #[pallet::pallet]
@@ -247,6 +272,80 @@ pub mod pallet {
     #[pallet::storage]
     pub type PrivilegedProviders<T: Config> = StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ()>;
 
+    /// The mapping from an escrow ID to the escrow holding payments from a User for a set of Providers.
+    ///
+    /// This storage is updated in:
+    /// - [open_escrow](crate::PaymentEscrowInterface::open_escrow), which adds a new entry to the map.
+    /// - [add_escrow_provider](crate::PaymentEscrowInterface::add_escrow_provider) and [remove_escrow_provider](crate::PaymentEscrowInterface::remove_escrow_provider),
+    /// which update the entry's Providers.
+    /// - [release_escrowed_payment](crate::dispatchables::release_escrowed_payment), which updates the entry's `amount_held`.
+    /// - [settle_escrow_replication](crate::PaymentEscrowInterface::settle_escrow_replication), which refunds the share of the missing Providers.
+    ///
+    /// Escrows are removed once their replication has been settled and no Provider is left in them.
+    #[pallet::storage]
+    pub type PaymentEscrows<T: Config> =
+        StorageMap<_, Blake2_128Concat, EscrowIdFor<T>, PaymentEscrow<T>>;
+
+    /// The double mapping from an escrow ID, to the Providers it pays, to the ticks they are paid for.
+    ///
+    /// This storage is updated alongside [PaymentEscrows].
+    #[pallet::storage]
+    pub type EscrowedProviders<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        EscrowIdFor<T>,
+        Blake2_128Concat,
+        ProviderIdFor<T>,
+        EscrowedProvider<T>,
+    >;
+
+    /// The target price per giga-unit per tick, in the smallest unit of the reference currency of the
+    /// [`Config::PriceOracle`] feed.
+    ///
+    /// When set, the price per giga-unit per tick tracks this target: every [`Config::OraclePriceAdjustmentPeriod`]
+    /// ticks it is moved towards the target converted to the native token using the oracle feed, by at most
+    /// [`Config::MaxOraclePriceAdjustment`]. When not set, the price is only updated by the system manager.
+    ///
+    /// This storage is updated in:
+    /// - [set_fiat_price_target](crate::dispatchables::set_fiat_price_target), which sets or clears the target.
+    #[pallet::storage]
+    pub type FiatPriceTarget<T: Config> = StorageValue<_, BalanceOf<T>>;
+
+    /// The price per giga-unit per tick resulting from the last adjustment towards [`FiatPriceTarget`].
+    ///
+    /// While the oracle feed is fresh, this price is enforced every tick, taking precedence over the one set by the
+    /// system manager. It is cleared when the target is.
+    #[pallet::storage]
+    pub type FiatTrackedPrice<T: Config> = StorageValue<_, BalanceOf<T>>;
+
+    /// The last tick at which the price per giga-unit per tick was adjusted towards [`FiatPriceTarget`], or at which
+    /// the adjustment was skipped because of a stale oracle feed.
+    #[pallet::storage]
+    pub type LastOraclePriceAdjustmentTick<T: Config> =
+        StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// The mapping from a User to the free balance it wants to keep to pay for its payment streams, for Users that enabled
+    /// the automatic top-up of their free balance from their auto top-up reserve.
+    ///
+    /// The reserve is the balance held from the User with the [`HoldReason::AutoTopUpReserve`] reason.
+    ///
+    /// This storage is updated in:
+    /// - [set_auto_top_up_threshold](crate::dispatchables::set_auto_top_up_threshold), which sets or clears the threshold.
+    #[pallet::storage]
+    pub type AutoTopUpThresholds<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>>;
+
+    /// The mapping from a User to the amount it was found short of to pay one of its payment streams, for Users whose free
+    /// balance has to be topped up from their auto top-up reserve in the next `on_poll` hook executions.
+    ///
+    /// This storage is updated in:
+    /// - [charge_payment_streams](crate::dispatchables::charge_payment_streams), which adds the User if it can't pay one of its
+    /// payment streams or its free balance falls below its threshold after being charged.
+    /// - [do_process_pending_auto_top_ups](crate::utils::do_process_pending_auto_top_ups), which removes the Users it tops up.
+    #[pallet::storage]
+    pub type PendingAutoTopUps<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>>;
+
     // Genesis config:
 
     #[pallet::genesis_config]
@@ -349,6 +448,94 @@ pub mod pallet {
         /// Event emitted when a User that has been flagged as not having enough funds to pay for their contracted services has waited the cooldown period,
         /// correctly paid all their outstanding debt and can now contract new services again.
         UserSolvent { who: T::AccountId },
+        /// Event emitted when a User escrows the payment for a set of Providers. Provides information about the escrow,
+        /// the amount of Providers it pays, the units each of them provides, at which rate and for how long, and the total
+        /// amount held from the User.
+        EscrowOpened {
+            escrow_id: EscrowIdFor<T>,
+            user_account: T::AccountId,
+            providers_target: u32,
+            amount_provided: UnitsProvidedFor<T>,
+            rate_per_provider: BalanceOf<T>,
+            duration: BlockNumberFor<T>,
+            amount_held: BalanceOf<T>,
+        },
+        /// Event emitted when a Provider starts being paid by an escrow. Provides information about the ticks it will be paid for.
+        EscrowProviderAdded {
+            escrow_id: EscrowIdFor<T>,
+            provider_id: ProviderIdFor<T>,
+            start_tick: BlockNumberFor<T>,
+            end_tick: BlockNumberFor<T>,
+        },
+        /// Event emitted when escrowed funds are released to a Provider that proved it kept providing its service.
+        /// Provides information about the amount released and the tick up to which the Provider has been paid.
+        EscrowPaymentReleased {
+            escrow_id: EscrowIdFor<T>,
+            user_account: T::AccountId,
+            provider_id: ProviderIdFor<T>,
+            amount: BalanceOf<T>,
+            released_until_tick: BlockNumberFor<T>,
+        },
+        /// Event emitted when a Provider stops being paid by an escrow before the end of its escrowed ticks.
+        /// Provides information about the amount of its share that was refunded to the User.
+        EscrowProviderRemoved {
+            escrow_id: EscrowIdFor<T>,
+            provider_id: ProviderIdFor<T>,
+            amount_refunded: BalanceOf<T>,
+        },
+        /// Event emitted when an escrow is settled with less Providers than its target. Provides information about the
+        /// amount of missing Providers and the amount refunded to the User for them.
+        EscrowShortfallRefunded {
+            escrow_id: EscrowIdFor<T>,
+            user_account: T::AccountId,
+            missing_providers: u32,
+            amount_refunded: BalanceOf<T>,
+        },
+        /// Event emitted when an escrow is closed. Provides information about the amount left in it that was refunded to the User.
+        EscrowClosed {
+            escrow_id: EscrowIdFor<T>,
+            user_account: T::AccountId,
+            amount_refunded: BalanceOf<T>,
+        },
+        /// Event emitted when the target price per giga-unit per tick in the reference currency of the oracle feed is set
+        /// or cleared. Provides information about the new target, if any.
+        FiatPriceTargetSet { target: Option<BalanceOf<T>> },
+        /// Event emitted when the price per giga-unit per tick is adjusted towards the target price in the reference currency.
+        /// Provides information about the previous and new prices, and the oracle price of the native token used to compute it.
+        PriceAdjustedToFiatTarget {
+            previous_price: BalanceOf<T>,
+            new_price: BalanceOf<T>,
+            oracle_price: FixedU128,
+        },
+        /// Event emitted when an adjustment of the price per giga-unit per tick towards the target price in the reference currency
+        /// is skipped because the oracle feed is stale or has no price. Provides information about the last update of the feed, if any.
+        /// The price per giga-unit per tick is left to the system manager until the feed is fresh again.
+        OraclePriceStale {
+            last_updated_at: Option<BlockNumberFor<T>>,
+        },
+        /// Event emitted when a User adds funds to its auto top-up reserve. Provides information about the amount added.
+        AutoTopUpReserveDeposited {
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// Event emitted when a User withdraws funds from its auto top-up reserve. Provides information about the amount withdrawn.
+        AutoTopUpReserveWithdrawn {
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// Event emitted when a User enables, updates or disables the automatic top-up of its free balance. Provides information
+        /// about the new threshold, if any.
+        AutoTopUpThresholdSet {
+            who: T::AccountId,
+            threshold: Option<BalanceOf<T>>,
+        },
+        /// Event emitted when the free balance of a User is topped up from its auto top-up reserve. Provides information about
+        /// the amount moved to its free balance and the amount left in the reserve.
+        AutoTopUpExecuted {
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+            remaining_reserve: BalanceOf<T>,
+        },
         /// Event emitted when the `on_poll` hook detects that the tick of the proof submitters that needs to process is not the one immediately after the last processed tick.
         InconsistentTickProcessing {
             last_processed_tick: BlockNumberFor<T>,
@@ -395,6 +582,32 @@ pub mod pallet {
         UserHasRemainingDebt,
         /// Error thrown when a charge is attempted when the provider is marked as insolvent
         ProviderInsolvent,
+        /// Error thrown when trying to open an escrow with an ID that is already in use
+        EscrowAlreadyExists,
+        /// Error thrown when trying to operate on an escrow that does not exist
+        EscrowNotFound,
+        /// Error thrown when trying to open an escrow for zero Providers, for zero ticks or for a zero amount provided
+        EscrowParamsCantBeZero,
+        /// Error thrown when the system can't hold the escrowed funds from the User
+        CannotHoldEscrow,
+        /// Error thrown when trying to add a Provider to an escrow that already pays its target amount of Providers
+        EscrowProvidersTargetReached,
+        /// Error thrown when trying to add a Provider to an escrow whose replication has already been settled
+        EscrowReplicationAlreadySettled,
+        /// Error thrown when trying to add a Provider to an escrow that already pays it
+        ProviderAlreadyInEscrow,
+        /// Error thrown when trying to release or remove the escrowed payment of a Provider that is not in the escrow
+        ProviderNotInEscrow,
+        /// Error thrown when trying to set a target price in the reference currency of zero (should clear it instead)
+        FiatPriceTargetCantBeZero,
+        /// Error thrown when trying to deposit or withdraw zero funds to or from the auto top-up reserve
+        AutoTopUpAmountCantBeZero,
+        /// Error thrown when the system can't hold the funds the User wants to add to its auto top-up reserve
+        CannotHoldAutoTopUpReserve,
+        /// Error thrown when trying to withdraw more funds than there are in the auto top-up reserve
+        InsufficientAutoTopUpReserve,
+        /// Error thrown when trying to set an auto top-up threshold of zero (should clear it instead)
+        AutoTopUpThresholdCantBeZero,
     }
 
     /// This enum holds the HoldReasons for this pallet, allowing the runtime to identify each held balance with different reasons separately
@@ -405,6 +618,10 @@ pub mod pallet {
     pub enum HoldReason {
         /// Deposit that a user has to pay to open payment streams
         PaymentStreamDeposit,
+        /// Funds that a user escrows upfront to pay a set of Providers
+        EscrowedPayment,
+        /// Funds that a user sets aside to top up its free balance when it can't pay for its payment streams
+        AutoTopUpReserve,
         // Only for testing, another unrelated hold reason
         #[cfg(test)]
         AnotherUnrelatedHold,
@@ -420,11 +637,17 @@ pub mod pallet {
         /// documentation](https://paritytech.github.io/polkadot-sdk/master/frame_support/traits/trait.Hooks.html#method.on_poll).
         fn on_poll(_n: BlockNumberFor<T>, meter: &mut sp_weights::WeightMeter) {
             // Update the current tick since we are executing the `on_poll` hook
-            let (previous_tick, _new_tick) = Self::do_advance_tick(meter);
+            let (previous_tick, new_tick) = Self::do_advance_tick(meter);
 
             // Update the last chargeable info of Providers that have sent a valid proof in the previous tick
             Self::do_update_last_chargeable_info(previous_tick, meter);
 
+            // Make the current price track the target price in the reference currency, if enabled
+            Self::do_apply_oracle_price_adjustment(new_tick, meter);
+
+            // Top up the free balance of Users that could not pay their payment streams from their auto top-up reserve
+            Self::do_process_pending_auto_top_ups(meter);
+
             // Update the global price index of the system
             Self::do_update_price_index(meter);
         }
@@ -881,6 +1104,195 @@ pub mod pallet {
             // Return a successful DispatchResultWithPostInfo
             Ok(().into())
         }
+
+        /// Dispatchable extrinsic that allows a Provider to release the payment escrowed for it, up to its last chargeable tick.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        /// The origin must be the account of a Provider that is being paid by the escrow.
+        ///
+        /// Parameters:
+        /// - `escrow_id`: The ID of the escrow to release the payment from.
+        ///
+        /// This extrinsic will perform the following checks and logic:
+        /// 1. Check that the extrinsic was signed and get the signer.
+        /// 2. Check that the signer is a registered Provider that is being paid by the escrow.
+        /// 3. Release to the Provider the escrowed payment for the ticks between the last one it was paid for and its last
+        /// chargeable tick (capped at the end of its escrowed ticks), transferring a cut of it to the treasury.
+        /// 4. If the Provider has been paid for all its escrowed ticks, remove it from the escrow, closing the escrow if it
+        /// was the last one and its replication has been settled.
+        ///
+        /// Emits a `EscrowPaymentReleased` event when successful if there was something to release, and a `EscrowClosed` event if
+        /// the escrow gets closed.
+        #[pallet::call_index(10)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn release_escrowed_payment(
+            origin: OriginFor<T>,
+            escrow_id: EscrowIdFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer
+            let provider_account = ensure_signed(origin)?;
+
+            // Get the Provider ID of the signer
+            let provider_id =
+                <T::ProvidersPallet as ReadProvidersInterface>::get_provider_id(provider_account)
+                    .ok_or(Error::<T>::NotAProvider)?;
+
+            // Execute checks and logic, update storage. Events are emitted inside.
+            Self::do_release_escrowed_payment_to_provider(&escrow_id, &provider_id)?;
+
+            // Return a successful DispatchResultWithPostInfo
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows root to enable, update or disable the tracking of a target price per giga-unit
+        /// per tick in the reference currency of the oracle feed.
+        ///
+        /// The dispatch origin for this call must be Root.
+        ///
+        /// Parameters:
+        /// - `target`: The target price per giga-unit per tick, in the smallest unit of the reference currency of the
+        /// oracle feed. `None` disables the tracking, leaving the price per giga-unit per tick to the system manager.
+        ///
+        /// This extrinsic will perform the following checks and logic:
+        /// 1. Check that the extrinsic was executed by the root origin
+        /// 2. Check that the target is not zero
+        /// 3. Set or clear the target, clearing the price resulting from previous adjustments when disabling it
+        ///
+        /// The price per giga-unit per tick is adjusted towards the new target starting from the next adjustment period.
+        ///
+        /// Emits a `FiatPriceTargetSet` event when successful.
+        #[pallet::call_index(11)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn set_fiat_price_target(
+            origin: OriginFor<T>,
+            target: Option<BalanceOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was executed by the root origin
+            ensure_root(origin)?;
+
+            // Execute checks and logic, update storage
+            Self::do_set_fiat_price_target(target)?;
+
+            // Emit the corresponding event
+            Self::deposit_event(Event::<T>::FiatPriceTargetSet { target });
+
+            // Return a successful DispatchResultWithPostInfo
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a User to add funds to its auto top-up reserve.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// Parameters:
+        /// - `amount`: The amount to hold from the User's free balance and add to the reserve.
+        ///
+        /// This extrinsic will perform the following checks and logic:
+        /// 1. Check that the extrinsic was signed and get the signer.
+        /// 2. Check that the amount is not zero.
+        /// 3. Check that the User has enough funds and hold them.
+        ///
+        /// The reserve is only used if the User enables the automatic top-up with [`set_auto_top_up_threshold`](Self::set_auto_top_up_threshold).
+        ///
+        /// Emits a `AutoTopUpReserveDeposited` event when successful.
+        #[pallet::call_index(12)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn deposit_auto_top_up_reserve(
+            origin: OriginFor<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer
+            let user_account = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            Self::do_deposit_auto_top_up_reserve(&user_account, amount)?;
+
+            // Emit the corresponding event
+            Self::deposit_event(Event::<T>::AutoTopUpReserveDeposited {
+                who: user_account,
+                amount,
+            });
+
+            // Return a successful DispatchResultWithPostInfo
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a User to withdraw funds from its auto top-up reserve back to its free balance.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// Parameters:
+        /// - `amount`: The amount to release from the reserve.
+        ///
+        /// This extrinsic will perform the following checks and logic:
+        /// 1. Check that the extrinsic was signed and get the signer.
+        /// 2. Check that the amount is not zero.
+        /// 3. Check that the reserve of the User has at least that amount and release it.
+        ///
+        /// Emits a `AutoTopUpReserveWithdrawn` event when successful.
+        #[pallet::call_index(13)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn withdraw_auto_top_up_reserve(
+            origin: OriginFor<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer
+            let user_account = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            Self::do_withdraw_auto_top_up_reserve(&user_account, amount)?;
+
+            // Emit the corresponding event
+            Self::deposit_event(Event::<T>::AutoTopUpReserveWithdrawn {
+                who: user_account,
+                amount,
+            });
+
+            // Return a successful DispatchResultWithPostInfo
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a User to enable, update or disable the automatic top-up of its free balance
+        /// from its auto top-up reserve.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// Parameters:
+        /// - `threshold`: The free balance the User wants to keep to pay for its payment streams. `None` disables the
+        /// automatic top-up, leaving the funds in the reserve until they are withdrawn.
+        ///
+        /// This extrinsic will perform the following checks and logic:
+        /// 1. Check that the extrinsic was signed and get the signer.
+        /// 2. Check that the threshold is not zero.
+        /// 3. Set or clear the threshold of the User.
+        ///
+        /// While enabled, whenever a Provider charges the User and it can't pay (or its free balance falls below the threshold),
+        /// its free balance is topped up from the reserve in the `on_poll` hook of the next block, up to the greater of the
+        /// threshold and the amount it could not pay. This happens well before the User is flagged as without funds, which
+        /// only happens after it failed to pay for [`Config::NewStreamDeposit`] ticks.
+        ///
+        /// Emits a `AutoTopUpThresholdSet` event when successful.
+        #[pallet::call_index(14)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_auto_top_up_threshold(
+            origin: OriginFor<T>,
+            threshold: Option<BalanceOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer
+            let user_account = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            Self::do_set_auto_top_up_threshold(&user_account, threshold)?;
+
+            // Emit the corresponding event
+            Self::deposit_event(Event::<T>::AutoTopUpThresholdSet {
+                who: user_account,
+                threshold,
+            });
+
+            // Return a successful DispatchResultWithPostInfo
+            Ok(().into())
+        }
     }
 }
 
@@ -988,6 +1400,11 @@ impl<T: Config> Pallet<T> {
         UsersWithoutFunds::<T>::contains_key(user_account)
     }
 
+    /// A helper function to get the amount of funds in the auto top-up reserve of a user
+    pub fn get_auto_top_up_reserve(user_account: &T::AccountId) -> BalanceOf<T> {
+        T::NativeBalance::balance_on_hold(&HoldReason::AutoTopUpReserve.into(), user_account)
+    }
+
     /// A helper function to get the current Tick of the system
     pub fn get_current_tick() -> BlockNumberFor<T> {
         OnPollTicker::<T>::get()
@@ -12,15 +12,15 @@ use frame_system::pallet_prelude::BlockNumberFor;
 use pallet_nfts::PalletFeatures;
 use shp_constants::GIGAUNIT;
 use shp_traits::{
-    CommitmentVerifier, MaybeDebug, ProofSubmittersInterface, ReadProvidersInterface, TrieMutation,
-    TrieProofDeltaApplier,
+    CommitmentVerifier, MaybeDebug, NativeTokenPriceOracle, ProofSubmittersInterface,
+    ReadProvidersInterface, TrieMutation, TrieProofDeltaApplier,
 };
 use shp_treasury_funding::NoCutTreasuryCutCalculator;
-use sp_core::{hashing::blake2_256, ConstU128, ConstU32, ConstU64, Hasher, H256};
+use sp_core::{hashing::blake2_256, ConstBool, ConstU128, ConstU32, ConstU64, Hasher, H256};
 use sp_runtime::{
     testing::TestSignature,
     traits::{BlakeTwo256, BlockNumberProvider, ConvertBack, IdentityLookup},
-    BuildStorage, DispatchError, Perbill, SaturatedConversion,
+    BuildStorage, DispatchError, FixedU128, Perbill, SaturatedConversion,
 };
 use sp_runtime::{traits::Convert, BoundedBTreeSet};
 use sp_trie::{CompactProof, LayoutV1, MemoryDB, TrieConfiguration, TrieLayout};
@@ -217,11 +217,17 @@ parameter_types! {
     pub const ProviderTopUpTtl: u64 = 10;
 }
 
+parameter_types! {
+    pub const SlashEscalationPerMissedDeadline: Perbill = Perbill::from_percent(10);
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
+}
+
 impl pallet_storage_providers::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
     type ProvidersRandomness = MockRandomness;
     type FileMetadataManager = MockFileMetadataManager;
+    type OnProviderStatusChange = ();
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type StorageDataUnit = StorageDataUnit;
@@ -249,16 +255,31 @@ impl pallet_storage_providers::Config for Test {
     type MaxProtocols = ConstU32<100>;
     type BucketDeposit = ConstU128<10>;
     type BucketNameLimit = ConstU32<100>;
+    type EncryptionMetadataLimit = ConstU32<256>;
     type MaxBlocksForRandomness = ConstU64<{ EPOCH_DURATION_IN_BLOCKS * 2 }>;
     type MinBlocksBetweenCapacityChanges = ConstU64<10>;
     type DefaultMerkleRoot = DefaultMerkleRoot<LayoutV1<BlakeTwo256>>;
     type SlashAmountPerMaxFileSize = ConstU128<10>;
+    type SlashReferenceDataSize = ConstU64<{ u64::MAX }>;
+    type SlashEscalationPerMissedDeadline = SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier = ConstU32<5>;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = ConstU64<10>;
     type MaxCommitmentSize = ConstU32<1000>;
     type ZeroSizeBucketFixedRate = ConstU128<1>;
     type ProviderTopUpTtl = ProviderTopUpTtl;
     type MaxExpiredItemsInBlock = ConstU32<10>;
+    type MaxCapacityUtilizationSamples = ConstU32<10>;
+    type CapacityUtilizationSamplingPeriod = ConstU64<10>;
+    type RequireCapacityAttestation = ConstBool<false>;
+    type CapacityAttestationChunkSize = ConstU64<10>;
+    type CapacityAttestationSpotChecks = ConstU32<4>;
+    type CapacityAttestationWindow = ConstU64<10>;
+    type CapacityDecreaseGracePeriod = ConstU64<20>;
+    type ReputationGrowthStreak = ConstU32<5>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<10>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelpers = ();
 }
@@ -422,10 +443,28 @@ impl pallet_proofs_dealer::Config for Test {
     type BlockFullnessHeadroom = BlockFullnessHeadroom;
     type MinNotFullBlocksRatio = MinNotFullBlocksRatio;
     type MaxSlashableProvidersPerTick = ConstU32<100>;
+    type MaxCatchUpTicksPerBlock = ConstU32<10>;
+    type MissedDeadlinesAllowance = ConstU32<0>;
+    type MissedDeadlineWarningWindow = ConstU64<100>;
+    type MaxProofSize = ConstU32<{ 1024 * 1024 }>;
+    type MaxProofsPerBatch = ConstU32<5>;
+    type OffchainSignature = TestSignature;
+    type OffchainPublic = <TestSignature as sp_runtime::traits::Verify>::Signer;
+    type UnsignedProofPriority = ConstU64<{ u64::MAX }>;
 }
 
 parameter_types! {
     pub const PaymentStreamHoldReason: RuntimeHoldReason = RuntimeHoldReason::PaymentStreams(pallet_payment_streams::HoldReason::PaymentStreamDeposit);
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(10);
+    pub static OraclePrice: Option<(FixedU128, BlockNumberFor<Test>)> = None;
+}
+
+// Mocked oracle feed of the native token price. It just returns the price and last update set in `OraclePrice`.
+pub struct MockPriceOracle;
+impl NativeTokenPriceOracle<BlockNumberFor<Test>> for MockPriceOracle {
+    fn native_token_price() -> Option<(FixedU128, BlockNumberFor<Test>)> {
+        OraclePrice::get()
+    }
 }
 
 // Converter from the BlockNumber type to the Balance type for math
@@ -465,6 +504,10 @@ impl ProofSubmittersInterface for MockSubmittingProviders {
         None
     }
 
+    fn get_consecutive_missed_proof_deadlines(_provider_id: &Self::ProviderId) -> u32 {
+        0
+    }
+
     fn clear_accrued_failed_proof_submissions(_provider_id: &Self::ProviderId) {}
 }
 
@@ -483,6 +526,10 @@ impl crate::Config for Test {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = MockPriceOracle;
+    type MaxOraclePriceAge = ConstU64<10>;
+    type OraclePriceAdjustmentPeriod = ConstU64<5>;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 
 // Build genesis storage according to the mock runtime.
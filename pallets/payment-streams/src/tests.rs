@@ -1,15 +1,18 @@
 use crate::{
     mock::*,
     types::{BalanceOf, ProviderLastChargeableInfo},
-    AccumulatedPriceIndex, CurrentPricePerGigaUnitPerTick, DynamicRatePaymentStreams, Error, Event,
-    LastChargeableInfo, RegisteredUsers, UsersWithoutFunds,
+    AccumulatedPriceIndex, AutoTopUpThresholds, CurrentPricePerGigaUnitPerTick,
+    DynamicRatePaymentStreams, Error, Event, FiatPriceTarget, FiatTrackedPrice,
+    FixedRatePaymentStreams, HoldReason, LastChargeableInfo, PaymentEscrows, PendingAutoTopUps,
+    RegisteredUsers, UsersWithoutFunds,
 };
 
 use frame_support::{
     assert_noop, assert_ok,
     pallet_prelude::Weight,
     traits::{
-        fungible::{InspectHold, Mutate},
+        fungible::{Inspect, InspectHold, Mutate},
+        tokens::{Fortitude, Preservation},
         Get, Hooks, OnFinalize, OnIdle, OnInitialize,
     },
     weights::WeightMeter,
@@ -17,9 +20,13 @@ use frame_support::{
 };
 use pallet_storage_providers::types::StorageProviderId;
 use shp_constants::GIGAUNIT;
-use shp_traits::{PaymentStreamsInterface, ReadProvidersInterface};
+use shp_traits::{PaymentEscrowInterface, PaymentStreamsInterface, ReadProvidersInterface};
 use sp_core::H256;
-use sp_runtime::{bounded_vec, traits::Convert, DispatchError};
+use sp_runtime::{
+    bounded_vec,
+    traits::{Convert, One},
+    DispatchError, FixedPointNumber, FixedU128, PerThing,
+};
 
 // `payment-streams` types:
 type NativeBalance = <Test as crate::Config>::NativeBalance;
@@ -6083,6 +6090,944 @@ mod user_without_funds {
     }
 }
 
+/// This module holds the tests for payments escrowed for a set of Providers
+mod escrowed_payments {
+    use super::*;
+
+    const PROVIDERS_TARGET: u32 = 3;
+    const DURATION: u64 = 20;
+    const AMOUNT_PROVIDED: u64 = 100;
+
+    fn escrow_hold_reason() -> RuntimeHoldReason {
+        RuntimeHoldReason::PaymentStreams(crate::HoldReason::EscrowedPayment)
+    }
+
+    /// Sets the price so each Provider of the escrow is paid 1000 per tick, and opens an escrow from `user_account`.
+    fn open_escrow(escrow_id: &H256, user_account: &AccountId) -> BalanceOf<Test> {
+        CurrentPricePerGigaUnitPerTick::<Test>::put(10 * GIGAUNIT_BALANCE);
+
+        assert_ok!(<PaymentStreams as PaymentEscrowInterface>::open_escrow(
+            escrow_id,
+            user_account,
+            PROVIDERS_TARGET,
+            &AMOUNT_PROVIDED,
+            DURATION,
+        ));
+
+        1000
+    }
+
+    /// Sets the last tick for which a Provider has submitted a valid proof.
+    fn set_last_chargeable_tick(provider_id: &H256, last_chargeable_tick: u64) {
+        LastChargeableInfo::<Test>::insert(
+            provider_id,
+            ProviderLastChargeableInfo {
+                last_chargeable_tick,
+                price_index: AccumulatedPriceIndex::<Test>::get(),
+            },
+        );
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn open_escrow_holds_the_payment_of_all_providers() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+                let bob_initial_balance = NativeBalance::free_balance(&bob);
+                let escrow_id = H256::from_low_u64_be(1);
+
+                let rate = open_escrow(&escrow_id, &bob);
+
+                let amount_held = rate * DURATION as u128 * PROVIDERS_TARGET as u128;
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&escrow_hold_reason(), &bob),
+                    amount_held
+                );
+                assert_eq!(
+                    NativeBalance::free_balance(&bob),
+                    bob_initial_balance - amount_held
+                );
+                System::assert_last_event(
+                    Event::<Test>::EscrowOpened {
+                        escrow_id,
+                        user_account: bob,
+                        providers_target: PROVIDERS_TARGET,
+                        amount_provided: AMOUNT_PROVIDED,
+                        rate_per_provider: rate,
+                        duration: DURATION,
+                        amount_held,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn release_escrowed_payment_pays_the_proven_ticks() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id =
+                    <StorageProviders as ReadProvidersInterface>::get_provider_id(alice).unwrap();
+                let rate = open_escrow(&escrow_id, &bob);
+
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                        &escrow_id,
+                        &alice_bsp_id,
+                    )
+                );
+                let start_tick = crate::OnPollTicker::<Test>::get();
+
+                // Alice proves storing for 10 ticks
+                run_to_block(System::block_number() + 10);
+                set_last_chargeable_tick(&alice_bsp_id, start_tick + 10);
+
+                let alice_balance_before = NativeBalance::free_balance(&alice);
+                let held_before = NativeBalance::balance_on_hold(&escrow_hold_reason(), &bob);
+                assert_ok!(PaymentStreams::release_escrowed_payment(
+                    RuntimeOrigin::signed(alice),
+                    escrow_id
+                ));
+
+                assert_eq!(
+                    NativeBalance::free_balance(&alice),
+                    alice_balance_before + 10 * rate
+                );
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&escrow_hold_reason(), &bob),
+                    held_before - 10 * rate
+                );
+                System::assert_has_event(
+                    Event::<Test>::EscrowPaymentReleased {
+                        escrow_id,
+                        user_account: bob,
+                        provider_id: alice_bsp_id,
+                        amount: 10 * rate,
+                        released_until_tick: start_tick + 10,
+                    }
+                    .into(),
+                );
+
+                // Releasing again without new proofs pays nothing
+                assert_ok!(PaymentStreams::release_escrowed_payment(
+                    RuntimeOrigin::signed(alice),
+                    escrow_id
+                ));
+                assert_eq!(
+                    NativeBalance::free_balance(&alice),
+                    alice_balance_before + 10 * rate
+                );
+            });
+        }
+
+        #[test]
+        fn settled_escrow_refunds_shortfall_and_closes_after_last_provider_is_paid() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id =
+                    <StorageProviders as ReadProvidersInterface>::get_provider_id(alice).unwrap();
+                let rate = open_escrow(&escrow_id, &bob);
+
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                        &escrow_id,
+                        &alice_bsp_id,
+                    )
+                );
+                let start_tick = crate::OnPollTicker::<Test>::get();
+
+                // Only one of the three Providers was found, so the share of the other two is refunded
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::settle_escrow_replication(
+                        &escrow_id
+                    )
+                );
+                System::assert_last_event(
+                    Event::<Test>::EscrowShortfallRefunded {
+                        escrow_id,
+                        user_account: bob,
+                        missing_providers: 2,
+                        amount_refunded: 2 * rate * DURATION as u128,
+                    }
+                    .into(),
+                );
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&escrow_hold_reason(), &bob),
+                    rate * DURATION as u128
+                );
+
+                // Alice proves storing past the end of the escrow, and gets paid its whole share
+                run_to_block(System::block_number() + DURATION + 5);
+                set_last_chargeable_tick(&alice_bsp_id, start_tick + DURATION + 5);
+                assert_ok!(PaymentStreams::release_escrowed_payment(
+                    RuntimeOrigin::signed(alice),
+                    escrow_id
+                ));
+                System::assert_has_event(
+                    Event::<Test>::EscrowPaymentReleased {
+                        escrow_id,
+                        user_account: bob,
+                        provider_id: alice_bsp_id,
+                        amount: rate * DURATION as u128,
+                        released_until_tick: start_tick + DURATION,
+                    }
+                    .into(),
+                );
+
+                // From now on, Alice is paid through her payment stream with Bob, and the escrow is closed
+                assert_eq!(
+                    DynamicRatePaymentStreams::<Test>::get(&alice_bsp_id, &bob)
+                        .unwrap()
+                        .amount_provided,
+                    AMOUNT_PROVIDED
+                );
+                System::assert_has_event(
+                    Event::<Test>::EscrowClosed {
+                        escrow_id,
+                        user_account: bob,
+                        amount_refunded: 0,
+                    }
+                    .into(),
+                );
+                assert!(!PaymentEscrows::<Test>::contains_key(&escrow_id));
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&escrow_hold_reason(), &bob),
+                    0
+                );
+            });
+        }
+
+        #[test]
+        fn remove_escrow_provider_refunds_its_unreleased_share() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id =
+                    <StorageProviders as ReadProvidersInterface>::get_provider_id(alice).unwrap();
+                let rate = open_escrow(&escrow_id, &bob);
+
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                        &escrow_id,
+                        &alice_bsp_id,
+                    )
+                );
+                let start_tick = crate::OnPollTicker::<Test>::get();
+
+                // Alice proves storing for 5 ticks and then stops storing
+                run_to_block(System::block_number() + 5);
+                set_last_chargeable_tick(&alice_bsp_id, start_tick + 5);
+                let alice_balance_before = NativeBalance::free_balance(&alice);
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::remove_escrow_provider(
+                        &escrow_id,
+                        &alice_bsp_id,
+                    )
+                );
+
+                assert_eq!(
+                    NativeBalance::free_balance(&alice),
+                    alice_balance_before + 5 * rate
+                );
+                System::assert_last_event(
+                    Event::<Test>::EscrowProviderRemoved {
+                        escrow_id,
+                        provider_id: alice_bsp_id,
+                        amount_refunded: (DURATION as u128 - 5) * rate,
+                    }
+                    .into(),
+                );
+
+                // Another Provider can't take Alice's place, as her share was refunded
+                let escrow = PaymentEscrows::<Test>::get(&escrow_id).unwrap();
+                assert_eq!(escrow.providers_target, PROVIDERS_TARGET - 1);
+                assert_eq!(escrow.providers_count, 0);
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&escrow_hold_reason(), &bob),
+                    rate * DURATION as u128 * (PROVIDERS_TARGET as u128 - 1)
+                );
+            });
+        }
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn open_escrow_fails_if_it_already_exists() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                open_escrow(&escrow_id, &bob);
+
+                assert_noop!(
+                    <PaymentStreams as PaymentEscrowInterface>::open_escrow(
+                        &escrow_id,
+                        &bob,
+                        PROVIDERS_TARGET,
+                        &AMOUNT_PROVIDED,
+                        DURATION,
+                    ),
+                    Error::<Test>::EscrowAlreadyExists
+                );
+            });
+        }
+
+        #[test]
+        fn open_escrow_fails_if_params_are_zero() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                assert_noop!(
+                    <PaymentStreams as PaymentEscrowInterface>::open_escrow(
+                        &escrow_id,
+                        &bob,
+                        0,
+                        &AMOUNT_PROVIDED,
+                        DURATION,
+                    ),
+                    Error::<Test>::EscrowParamsCantBeZero
+                );
+                assert_noop!(
+                    <PaymentStreams as PaymentEscrowInterface>::open_escrow(
+                        &escrow_id,
+                        &bob,
+                        PROVIDERS_TARGET,
+                        &AMOUNT_PROVIDED,
+                        0,
+                    ),
+                    Error::<Test>::EscrowParamsCantBeZero
+                );
+            });
+        }
+
+        #[test]
+        fn add_escrow_provider_fails_after_replication_is_settled() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let charlie: AccountId = 2;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                register_account_as_bsp(alice, 100);
+                register_account_as_bsp(charlie, 100);
+                let alice_bsp_id =
+                    <StorageProviders as ReadProvidersInterface>::get_provider_id(alice).unwrap();
+                let charlie_bsp_id =
+                    <StorageProviders as ReadProvidersInterface>::get_provider_id(charlie).unwrap();
+                open_escrow(&escrow_id, &bob);
+
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                        &escrow_id,
+                        &alice_bsp_id,
+                    )
+                );
+                assert_noop!(
+                    <PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                        &escrow_id,
+                        &alice_bsp_id,
+                    ),
+                    Error::<Test>::ProviderAlreadyInEscrow
+                );
+
+                assert_ok!(
+                    <PaymentStreams as PaymentEscrowInterface>::settle_escrow_replication(
+                        &escrow_id
+                    )
+                );
+                assert!(
+                    !<PaymentStreams as PaymentEscrowInterface>::can_add_escrow_provider(
+                        &escrow_id
+                    )
+                );
+                assert_noop!(
+                    <PaymentStreams as PaymentEscrowInterface>::add_escrow_provider(
+                        &escrow_id,
+                        &charlie_bsp_id,
+                    ),
+                    Error::<Test>::EscrowReplicationAlreadySettled
+                );
+            });
+        }
+
+        #[test]
+        fn release_escrowed_payment_fails_if_provider_not_in_escrow() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let escrow_id = H256::from_low_u64_be(1);
+
+                register_account_as_bsp(alice, 100);
+                open_escrow(&escrow_id, &bob);
+
+                assert_noop!(
+                    PaymentStreams::release_escrowed_payment(
+                        RuntimeOrigin::signed(alice),
+                        escrow_id
+                    ),
+                    Error::<Test>::ProviderNotInEscrow
+                );
+            });
+        }
+    }
+}
+
+mod fiat_price_tracking {
+    use super::*;
+
+    /// Price per giga-unit per tick the current one moves to in a single adjustment towards a higher target.
+    fn price_after_one_adjustment_up(price: BalanceOf<Test>) -> BalanceOf<Test> {
+        price + MaxOraclePriceAdjustment::get().mul_ceil(price)
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn price_moves_towards_target_by_at_most_the_maximum_adjustment() {
+            ExtBuilder::build().execute_with(|| {
+                // One unit of the reference currency per unit of the native token, and a target twice the current price.
+                OraclePrice::set(Some((FixedU128::one(), System::block_number())));
+                assert_ok!(PaymentStreams::set_fiat_price_target(
+                    RuntimeOrigin::root(),
+                    Some(2 * GIGAUNIT_BALANCE)
+                ));
+                System::assert_last_event(
+                    Event::<Test>::FiatPriceTargetSet {
+                        target: Some(2 * GIGAUNIT_BALANCE),
+                    }
+                    .into(),
+                );
+
+                // The first adjustment happens at the end of the first adjustment period.
+                let period: u64 = <Test as crate::Config>::OraclePriceAdjustmentPeriod::get();
+                run_to_block(period - 1);
+                assert_eq!(
+                    CurrentPricePerGigaUnitPerTick::<Test>::get(),
+                    GIGAUNIT_BALANCE
+                );
+
+                run_to_block(period);
+                let new_price = price_after_one_adjustment_up(GIGAUNIT_BALANCE);
+                assert_eq!(CurrentPricePerGigaUnitPerTick::<Test>::get(), new_price);
+                assert_eq!(FiatTrackedPrice::<Test>::get(), Some(new_price));
+                System::assert_has_event(
+                    Event::<Test>::PriceAdjustedToFiatTarget {
+                        previous_price: GIGAUNIT_BALANCE,
+                        new_price,
+                        oracle_price: FixedU128::one(),
+                    }
+                    .into(),
+                );
+
+                // A price set by the system manager in between adjustments is overridden.
+                CurrentPricePerGigaUnitPerTick::<Test>::put(1);
+                run_to_block(period + 1);
+                assert_eq!(CurrentPricePerGigaUnitPerTick::<Test>::get(), new_price);
+            });
+        }
+
+        #[test]
+        fn price_reaches_target_within_the_maximum_adjustment() {
+            ExtBuilder::build().execute_with(|| {
+                // Two units of the reference currency per unit of the native token.
+                let oracle_price = FixedU128::saturating_from_integer(2);
+                OraclePrice::set(Some((oracle_price, System::block_number())));
+                assert_ok!(PaymentStreams::set_fiat_price_target(
+                    RuntimeOrigin::root(),
+                    Some(2 * (GIGAUNIT_BALANCE + 100))
+                ));
+
+                run_to_block(<Test as crate::Config>::OraclePriceAdjustmentPeriod::get());
+
+                assert_eq!(
+                    CurrentPricePerGigaUnitPerTick::<Test>::get(),
+                    GIGAUNIT_BALANCE + 100
+                );
+                System::assert_has_event(
+                    Event::<Test>::PriceAdjustedToFiatTarget {
+                        previous_price: GIGAUNIT_BALANCE,
+                        new_price: GIGAUNIT_BALANCE + 100,
+                        oracle_price,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn price_is_left_as_is_when_oracle_is_stale() {
+            ExtBuilder::build().execute_with(|| {
+                // The oracle was last updated long before the adjustment is due.
+                OraclePrice::set(Some((FixedU128::one(), System::block_number())));
+                assert_ok!(PaymentStreams::set_fiat_price_target(
+                    RuntimeOrigin::root(),
+                    Some(2 * GIGAUNIT_BALANCE)
+                ));
+                let max_oracle_price_age: u64 = <Test as crate::Config>::MaxOraclePriceAge::get();
+                System::set_block_number(max_oracle_price_age + 10);
+
+                run_to_block(
+                    System::block_number()
+                        + <Test as crate::Config>::OraclePriceAdjustmentPeriod::get(),
+                );
+
+                assert_eq!(
+                    CurrentPricePerGigaUnitPerTick::<Test>::get(),
+                    GIGAUNIT_BALANCE
+                );
+                assert_eq!(FiatTrackedPrice::<Test>::get(), None);
+                System::assert_has_event(
+                    Event::<Test>::OraclePriceStale {
+                        last_updated_at: Some(1),
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn price_is_left_as_is_when_oracle_has_no_price() {
+            ExtBuilder::build().execute_with(|| {
+                assert_ok!(PaymentStreams::set_fiat_price_target(
+                    RuntimeOrigin::root(),
+                    Some(2 * GIGAUNIT_BALANCE)
+                ));
+
+                run_to_block(<Test as crate::Config>::OraclePriceAdjustmentPeriod::get());
+
+                assert_eq!(
+                    CurrentPricePerGigaUnitPerTick::<Test>::get(),
+                    GIGAUNIT_BALANCE
+                );
+                System::assert_has_event(
+                    Event::<Test>::OraclePriceStale {
+                        last_updated_at: None,
+                    }
+                    .into(),
+                );
+            });
+        }
+
+        #[test]
+        fn clearing_target_leaves_price_to_system_manager() {
+            ExtBuilder::build().execute_with(|| {
+                OraclePrice::set(Some((FixedU128::one(), System::block_number())));
+                assert_ok!(PaymentStreams::set_fiat_price_target(
+                    RuntimeOrigin::root(),
+                    Some(2 * GIGAUNIT_BALANCE)
+                ));
+                let period: u64 = <Test as crate::Config>::OraclePriceAdjustmentPeriod::get();
+                run_to_block(period);
+                assert!(FiatTrackedPrice::<Test>::get().is_some());
+
+                assert_ok!(PaymentStreams::set_fiat_price_target(
+                    RuntimeOrigin::root(),
+                    None
+                ));
+                System::assert_last_event(
+                    Event::<Test>::FiatPriceTargetSet { target: None }.into(),
+                );
+                assert_eq!(FiatPriceTarget::<Test>::get(), None);
+                assert_eq!(FiatTrackedPrice::<Test>::get(), None);
+
+                // The price set by the system manager is no longer overridden.
+                CurrentPricePerGigaUnitPerTick::<Test>::put(1);
+                run_to_block(2 * period);
+                assert_eq!(CurrentPricePerGigaUnitPerTick::<Test>::get(), 1);
+            });
+        }
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn set_fiat_price_target_fails_if_not_root() {
+            ExtBuilder::build().execute_with(|| {
+                assert_noop!(
+                    PaymentStreams::set_fiat_price_target(
+                        RuntimeOrigin::signed(1),
+                        Some(GIGAUNIT_BALANCE)
+                    ),
+                    DispatchError::BadOrigin
+                );
+            });
+        }
+
+        #[test]
+        fn set_fiat_price_target_fails_if_zero() {
+            ExtBuilder::build().execute_with(|| {
+                assert_noop!(
+                    PaymentStreams::set_fiat_price_target(RuntimeOrigin::root(), Some(0)),
+                    Error::<Test>::FiatPriceTargetCantBeZero
+                );
+            });
+        }
+    }
+}
+
+mod auto_top_up {
+    use super::*;
+
+    /// Balance of `account` that can be used to pay for its payment streams.
+    fn reducible_balance(account: &AccountId) -> BalanceOf<Test> {
+        NativeBalance::reducible_balance(account, Preservation::Preserve, Fortitude::Polite)
+    }
+
+    /// Creates a fixed-rate payment stream of `rate` from Bob to Alice, registering Alice as a MSP, and leaves Bob with only
+    /// `free_balance` to pay for it, holding the rest in its auto top-up reserve. Returns the MSP ID of Alice.
+    fn setup_stream_with_reserve(rate: BalanceOf<Test>, free_balance: BalanceOf<Test>) -> H256 {
+        let alice: AccountId = 0;
+        let bob: AccountId = 1;
+
+        register_account_as_msp(alice, 100);
+        let alice_msp_id =
+            <StorageProviders as ReadProvidersInterface>::get_provider_id(alice).unwrap();
+
+        assert_ok!(
+            <PaymentStreams as PaymentStreamsInterface>::create_fixed_rate_payment_stream(
+                &alice_msp_id,
+                &bob,
+                rate
+            )
+        );
+
+        assert_ok!(PaymentStreams::deposit_auto_top_up_reserve(
+            RuntimeOrigin::signed(bob),
+            reducible_balance(&bob) - free_balance
+        ));
+        assert_eq!(reducible_balance(&bob), free_balance);
+
+        alice_msp_id
+    }
+
+    /// Lets `provider_id` charge its payment streams up to `ticks` ticks from now.
+    fn advance_last_chargeable_tick(provider_id: &H256, ticks: u64) {
+        run_to_block(System::block_number() + ticks);
+        LastChargeableInfo::<Test>::insert(
+            provider_id,
+            ProviderLastChargeableInfo {
+                last_chargeable_tick: System::block_number(),
+                price_index: 100,
+            },
+        );
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn deposit_and_withdraw_auto_top_up_reserve_works() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+                let bob_initial_balance = NativeBalance::free_balance(&bob);
+
+                assert_ok!(PaymentStreams::deposit_auto_top_up_reserve(
+                    RuntimeOrigin::signed(bob),
+                    1000
+                ));
+                System::assert_last_event(
+                    Event::<Test>::AutoTopUpReserveDeposited {
+                        who: bob,
+                        amount: 1000,
+                    }
+                    .into(),
+                );
+                assert_eq!(PaymentStreams::get_auto_top_up_reserve(&bob), 1000);
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&HoldReason::AutoTopUpReserve.into(), &bob),
+                    1000
+                );
+                assert_eq!(
+                    NativeBalance::free_balance(&bob),
+                    bob_initial_balance - 1000
+                );
+
+                assert_ok!(PaymentStreams::withdraw_auto_top_up_reserve(
+                    RuntimeOrigin::signed(bob),
+                    400
+                ));
+                System::assert_last_event(
+                    Event::<Test>::AutoTopUpReserveWithdrawn {
+                        who: bob,
+                        amount: 400,
+                    }
+                    .into(),
+                );
+                assert_eq!(PaymentStreams::get_auto_top_up_reserve(&bob), 600);
+                assert_eq!(NativeBalance::free_balance(&bob), bob_initial_balance - 600);
+            });
+        }
+
+        #[test]
+        fn set_and_clear_auto_top_up_threshold_works() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+
+                assert_ok!(PaymentStreams::set_auto_top_up_threshold(
+                    RuntimeOrigin::signed(bob),
+                    Some(100)
+                ));
+                System::assert_last_event(
+                    Event::<Test>::AutoTopUpThresholdSet {
+                        who: bob,
+                        threshold: Some(100),
+                    }
+                    .into(),
+                );
+                assert_eq!(AutoTopUpThresholds::<Test>::get(&bob), Some(100));
+
+                assert_ok!(PaymentStreams::set_auto_top_up_threshold(
+                    RuntimeOrigin::signed(bob),
+                    None
+                ));
+                System::assert_last_event(
+                    Event::<Test>::AutoTopUpThresholdSet {
+                        who: bob,
+                        threshold: None,
+                    }
+                    .into(),
+                );
+                assert_eq!(AutoTopUpThresholds::<Test>::get(&bob), None);
+            });
+        }
+
+        #[test]
+        fn user_short_of_funds_is_topped_up_and_pays_before_being_flagged() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let rate: BalanceOf<Test> = 10;
+
+                // Bob is left with 50 units, not enough to pay for 10 ticks of the stream.
+                let alice_msp_id = setup_stream_with_reserve(rate, 50);
+                let reserve = PaymentStreams::get_auto_top_up_reserve(&bob);
+                assert_ok!(PaymentStreams::set_auto_top_up_threshold(
+                    RuntimeOrigin::signed(bob),
+                    Some(100)
+                ));
+                advance_last_chargeable_tick(&alice_msp_id, 10);
+
+                // Bob can't pay, so the top-up is queued.
+                assert_ok!(PaymentStreams::charge_payment_streams(
+                    RuntimeOrigin::signed(alice),
+                    bob
+                ));
+                assert_eq!(reducible_balance(&bob), 50);
+                assert_eq!(PendingAutoTopUps::<Test>::get(&bob), Some(10 * rate));
+
+                // The top-up is executed in the next `on_poll`, up to the amount due.
+                run_to_block(System::block_number() + 1);
+                System::assert_has_event(
+                    Event::<Test>::AutoTopUpExecuted {
+                        who: bob,
+                        amount: 50,
+                        remaining_reserve: reserve - 50,
+                    }
+                    .into(),
+                );
+                assert_eq!(PendingAutoTopUps::<Test>::get(&bob), None);
+                assert_eq!(reducible_balance(&bob), 10 * rate);
+
+                // Bob can now pay for the stream, which is no longer flagged as out of funds.
+                assert_ok!(PaymentStreams::charge_payment_streams(
+                    RuntimeOrigin::signed(alice),
+                    bob
+                ));
+                assert_eq!(reducible_balance(&bob), 0);
+                assert_eq!(
+                    FixedRatePaymentStreams::<Test>::get(&alice_msp_id, &bob)
+                        .unwrap()
+                        .out_of_funds_tick,
+                    None
+                );
+                assert!(!UsersWithoutFunds::<Test>::contains_key(&bob));
+
+                // Having been left below the threshold, Bob is topped up back to it.
+                assert_eq!(PendingAutoTopUps::<Test>::get(&bob), Some(0));
+                run_to_block(System::block_number() + 1);
+                System::assert_has_event(
+                    Event::<Test>::AutoTopUpExecuted {
+                        who: bob,
+                        amount: 100,
+                        remaining_reserve: reserve - 150,
+                    }
+                    .into(),
+                );
+                assert_eq!(reducible_balance(&bob), 100);
+            });
+        }
+
+        #[test]
+        fn top_up_is_capped_by_the_reserve() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+                let rate: BalanceOf<Test> = 10;
+
+                // Bob only keeps 20 units in its reserve.
+                let alice_msp_id = setup_stream_with_reserve(rate, 50);
+                let reserve = PaymentStreams::get_auto_top_up_reserve(&bob);
+                assert_ok!(PaymentStreams::withdraw_auto_top_up_reserve(
+                    RuntimeOrigin::signed(bob),
+                    reserve - 20
+                ));
+                assert_ok!(NativeBalance::transfer(
+                    &bob,
+                    &3,
+                    reducible_balance(&bob) - 50,
+                    Preservation::Preserve
+                ));
+                assert_ok!(PaymentStreams::set_auto_top_up_threshold(
+                    RuntimeOrigin::signed(bob),
+                    Some(100)
+                ));
+                advance_last_chargeable_tick(&alice_msp_id, 10);
+
+                assert_ok!(PaymentStreams::charge_payment_streams(
+                    RuntimeOrigin::signed(alice),
+                    bob
+                ));
+                run_to_block(System::block_number() + 1);
+
+                System::assert_has_event(
+                    Event::<Test>::AutoTopUpExecuted {
+                        who: bob,
+                        amount: 20,
+                        remaining_reserve: 0,
+                    }
+                    .into(),
+                );
+                assert_eq!(reducible_balance(&bob), 70);
+                assert_eq!(PaymentStreams::get_auto_top_up_reserve(&bob), 0);
+            });
+        }
+
+        #[test]
+        fn user_without_threshold_is_not_topped_up() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+
+                let alice_msp_id = setup_stream_with_reserve(10, 50);
+                let reserve = PaymentStreams::get_auto_top_up_reserve(&bob);
+                advance_last_chargeable_tick(&alice_msp_id, 10);
+
+                assert_ok!(PaymentStreams::charge_payment_streams(
+                    RuntimeOrigin::signed(alice),
+                    bob
+                ));
+                assert_eq!(PendingAutoTopUps::<Test>::get(&bob), None);
+
+                run_to_block(System::block_number() + 1);
+                assert_eq!(PaymentStreams::get_auto_top_up_reserve(&bob), reserve);
+                assert_eq!(reducible_balance(&bob), 50);
+            });
+        }
+
+        #[test]
+        fn clearing_threshold_drops_pending_top_up() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = 0;
+                let bob: AccountId = 1;
+
+                let alice_msp_id = setup_stream_with_reserve(10, 50);
+                let reserve = PaymentStreams::get_auto_top_up_reserve(&bob);
+                assert_ok!(PaymentStreams::set_auto_top_up_threshold(
+                    RuntimeOrigin::signed(bob),
+                    Some(100)
+                ));
+                advance_last_chargeable_tick(&alice_msp_id, 10);
+
+                assert_ok!(PaymentStreams::charge_payment_streams(
+                    RuntimeOrigin::signed(alice),
+                    bob
+                ));
+                assert!(PendingAutoTopUps::<Test>::contains_key(&bob));
+
+                assert_ok!(PaymentStreams::set_auto_top_up_threshold(
+                    RuntimeOrigin::signed(bob),
+                    None
+                ));
+                assert!(!PendingAutoTopUps::<Test>::contains_key(&bob));
+
+                run_to_block(System::block_number() + 1);
+                assert_eq!(PaymentStreams::get_auto_top_up_reserve(&bob), reserve);
+            });
+        }
+    }
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn deposit_auto_top_up_reserve_fails_if_amount_is_zero() {
+            ExtBuilder::build().execute_with(|| {
+                assert_noop!(
+                    PaymentStreams::deposit_auto_top_up_reserve(RuntimeOrigin::signed(1), 0),
+                    Error::<Test>::AutoTopUpAmountCantBeZero
+                );
+            });
+        }
+
+        #[test]
+        fn deposit_auto_top_up_reserve_fails_if_user_does_not_have_enough_funds() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+                assert_noop!(
+                    PaymentStreams::deposit_auto_top_up_reserve(
+                        RuntimeOrigin::signed(bob),
+                        NativeBalance::free_balance(&bob) + 1
+                    ),
+                    Error::<Test>::CannotHoldAutoTopUpReserve
+                );
+            });
+        }
+
+        #[test]
+        fn withdraw_auto_top_up_reserve_fails_if_reserve_is_insufficient() {
+            ExtBuilder::build().execute_with(|| {
+                let bob: AccountId = 1;
+                assert_ok!(PaymentStreams::deposit_auto_top_up_reserve(
+                    RuntimeOrigin::signed(bob),
+                    1000
+                ));
+                assert_noop!(
+                    PaymentStreams::withdraw_auto_top_up_reserve(RuntimeOrigin::signed(bob), 1001),
+                    Error::<Test>::InsufficientAutoTopUpReserve
+                );
+            });
+        }
+
+        #[test]
+        fn set_auto_top_up_threshold_fails_if_zero() {
+            ExtBuilder::build().execute_with(|| {
+                assert_noop!(
+                    PaymentStreams::set_auto_top_up_threshold(RuntimeOrigin::signed(1), Some(0)),
+                    Error::<Test>::AutoTopUpThresholdCantBeZero
+                );
+            });
+        }
+    }
+}
+
 mod users_with_debt_over_threshold {
 
     use super::*;
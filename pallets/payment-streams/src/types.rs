@@ -53,6 +53,34 @@ impl<T: pallet::Config> Default for ProviderLastChargeableInfo<T> {
     }
 }
 
+/// Structure that has the information of a payment escrowed by a User for a set of Providers.
+///
+/// The amount held is what is left to pay the Providers currently in the escrow for the rest of their
+/// escrowed ticks, plus `rate_per_provider * duration` for each Provider still missing to reach the target.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct PaymentEscrow<T: Config> {
+    pub user_account: T::AccountId,
+    pub amount_provided: UnitsProvidedFor<T>,
+    /// The rate at which each Provider is paid, fixed to the price per giga-unit per tick when the escrow was opened.
+    pub rate_per_provider: BalanceOf<T>,
+    pub duration: BlockNumberFor<T>,
+    pub providers_target: u32,
+    pub providers_count: u32,
+    pub amount_held: BalanceOf<T>,
+    /// Whether the shortfall of Providers has already been refunded, after which no more Providers can be added.
+    pub replication_settled: bool,
+}
+
+/// Structure that has the information of a Provider being paid by an escrow
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct EscrowedProvider<T: Config> {
+    pub start_tick: BlockNumberFor<T>,
+    pub end_tick: BlockNumberFor<T>,
+    pub released_until_tick: BlockNumberFor<T>,
+}
+
 // Type aliases:
 
 /// BalanceOf is the balance type of the runtime.
@@ -66,5 +94,10 @@ pub type UnitsProvidedFor<T> = <T as Config>::Units;
 pub type ProviderIdFor<T> =
     <<T as crate::Config>::ProvidersPallet as ReadProvidersInterface>::ProviderId;
 
+/// Syntactic sugar for the type used to identify escrows, which is the one of the keys of the Providers' forests
+/// (i.e. file keys), so an escrow can be opened for each file.
+pub type EscrowIdFor<T> =
+    <<T as crate::Config>::ProvidersPallet as ReadProvidersInterface>::MerkleHash;
+
 /// Syntactic sugar for the maximum amount of Users a Provider can charge in a batch.
 pub type MaxUsersToChargeFor<T> = <T as Config>::MaxUsersToCharge;
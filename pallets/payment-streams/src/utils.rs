@@ -6,20 +6,20 @@ use frame_support::sp_runtime::{
 };
 use frame_support::traits::{
     fungible::{Inspect, InspectHold, Mutate, MutateHold},
-    tokens::{Fortitude, Precision, Preservation},
+    tokens::{Fortitude, Precision, Preservation, Restriction},
     Get,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
 use pallet_payment_streams_runtime_api::GetUsersWithDebtOverThresholdError;
 use shp_constants::GIGAUNIT;
 use shp_traits::{
-    MutatePricePerGigaUnitPerTickInterface, PaymentStreamsInterface, ProofSubmittersInterface,
-    ReadProvidersInterface, ReadUserSolvencyInterface, SystemMetricsInterface,
-    TreasuryCutCalculator,
+    MutatePricePerGigaUnitPerTickInterface, NativeTokenPriceOracle, PaymentEscrowInterface,
+    PaymentStreamsInterface, ProofSubmittersInterface, ReadProvidersInterface,
+    ReadUserSolvencyInterface, SystemMetricsInterface, TreasuryCutCalculator,
 };
 use sp_runtime::{
     traits::{CheckedDiv, Convert, One},
-    Saturating,
+    FixedPointNumber, PerThing, SaturatedConversion, Saturating,
 };
 
 use crate::{weights::WeightInfo, *};
@@ -349,16 +349,7 @@ where
             Preservation::Preserve,
             Fortitude::Polite,
         );
-        let current_price_per_giga_unit_per_tick = CurrentPricePerGigaUnitPerTick::<T>::get();
-        let deposit = current_price_per_giga_unit_per_tick
-            .checked_mul(&amount_provided.into())
-            .ok_or(ArithmeticError::Overflow)?
-            .checked_mul(&T::BlockNumberToBalance::convert(T::NewStreamDeposit::get()))
-            .ok_or(ArithmeticError::Overflow)?
-            .checked_div(&GIGAUNIT.into())
-            .unwrap_or_default()
-            .checked_add(&T::BaseDeposit::get())
-            .ok_or(ArithmeticError::Overflow)?;
+        let deposit = Self::dynamic_rate_payment_stream_deposit(amount_provided)?;
         ensure!(user_balance >= deposit, Error::<T>::CannotHoldDeposit);
 
         // Check if we can hold the deposit from the User
@@ -475,17 +466,7 @@ where
         }
 
         // Update the user's deposit based on the new amount provided
-        let current_price_per_giga_unit_per_tick = CurrentPricePerGigaUnitPerTick::<T>::get();
-        let new_deposit = new_amount_provided
-            .into()
-            .checked_mul(&current_price_per_giga_unit_per_tick)
-            .ok_or(ArithmeticError::Overflow)?
-            .checked_mul(&T::BlockNumberToBalance::convert(T::NewStreamDeposit::get()))
-            .ok_or(ArithmeticError::Overflow)?
-            .checked_div(&GIGAUNIT.into())
-            .unwrap_or_default()
-            .checked_add(&T::BaseDeposit::get())
-            .ok_or(ArithmeticError::Overflow)?;
+        let new_deposit = Self::dynamic_rate_payment_stream_deposit(new_amount_provided)?;
         Self::update_user_deposit(&user_account, payment_stream.user_deposit, new_deposit)?;
 
         // Update the payment stream in the DynamicRatePaymentStreams mapping
@@ -653,6 +634,10 @@ where
 
                         // If the user does not have enough balance to pay for its storage:
                         if user_balance < amount_to_charge {
+                            // If the user enabled the automatic top-up, its free balance gets topped up from its reserve in the next
+                            // `on_poll` hook execution, so it can pay for this payment stream before being flagged as without funds
+                            Self::queue_auto_top_up(user_account, amount_to_charge);
+
                             // Check if this payment stream was already flagged as without funds and, if so, how many ticks have passed since then
                             let out_of_funds_tick = fixed_rate_payment_stream.out_of_funds_tick;
                             let current_tick = OnPollTicker::<T>::get();
@@ -808,6 +793,10 @@ where
 
                         // If the user does not have enough balance to pay for its storage:
                         if user_balance < amount_to_charge {
+                            // If the user enabled the automatic top-up, its free balance gets topped up from its reserve in the next
+                            // `on_poll` hook execution, so it can pay for this payment stream before being flagged as without funds
+                            Self::queue_auto_top_up(user_account, amount_to_charge);
+
                             // Check if this payment stream was already flagged as without funds and, if so, how many ticks have passed since then
                             let out_of_funds_tick = dynamic_rate_payment_stream.out_of_funds_tick;
                             let current_tick = OnPollTicker::<T>::get();
@@ -929,6 +918,11 @@ where
             }
         }
 
+        // If charging the user left its free balance below its auto top-up threshold, top it up in the next `on_poll` hook execution
+        if !total_amount_charged.is_zero() {
+            Self::queue_auto_top_up(user_account, Zero::zero());
+        }
+
         Ok((total_amount_charged, last_chargeable_tick))
     }
 
@@ -1279,6 +1273,118 @@ where
         meter.consume(required_weight);
     }
 
+    /// This function sets or clears the target price per giga-unit per tick in the reference currency of the oracle feed.
+    pub fn do_set_fiat_price_target(target: Option<BalanceOf<T>>) -> DispatchResult {
+        match target {
+            Some(target) => {
+                // Check that the target is not zero
+                ensure!(!target.is_zero(), Error::<T>::FiatPriceTargetCantBeZero);
+
+                FiatPriceTarget::<T>::put(target);
+            }
+            None => {
+                // Clear the target and the price resulting from the previous adjustments, leaving the price
+                // to the system manager
+                FiatPriceTarget::<T>::kill();
+                FiatTrackedPrice::<T>::kill();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function makes the current price per giga-unit per tick track the target set in [`FiatPriceTarget`], if any.
+    ///
+    /// Every [`Config::OraclePriceAdjustmentPeriod`] ticks, the target is converted to the native token using the price of the
+    /// oracle feed, and the price is moved towards it by at most [`Config::MaxOraclePriceAdjustment`]. In between, the price
+    /// resulting from the last adjustment is enforced, overriding the one set by the system manager.
+    ///
+    /// If the oracle feed is stale or has no price, the price is left as is, and an `OraclePriceStale` event is emitted
+    /// once per adjustment period.
+    pub fn do_apply_oracle_price_adjustment(
+        current_tick: BlockNumberFor<T>,
+        meter: &mut sp_weights::WeightMeter,
+    ) {
+        // Get the target price, if the tracking is enabled
+        let Some(target) = FiatPriceTarget::<T>::get() else {
+            meter.consume(T::DbWeight::get().reads(1));
+            return;
+        };
+
+        // Get the price of the oracle feed, discarding it if it is stale. A zero price can't be used to convert the
+        // target to the native token either.
+        let oracle_price = T::PriceOracle::native_token_price();
+        let current_block = frame_system::Pallet::<T>::block_number();
+        let fresh_oracle_price = oracle_price.and_then(|(price, last_updated_at)| {
+            let is_fresh =
+                current_block.saturating_sub(last_updated_at) <= T::MaxOraclePriceAge::get();
+            (is_fresh && !price.is_zero()).then_some(price)
+        });
+
+        // Check if an adjustment is due in this tick
+        let adjustment_due = current_tick
+            >= LastOraclePriceAdjustmentTick::<T>::get()
+                .saturating_add(T::OraclePriceAdjustmentPeriod::get());
+
+        meter.consume(T::DbWeight::get().reads(4));
+
+        let Some(oracle_price) = fresh_oracle_price else {
+            // Fall back to the price set by the system manager, letting the network know once per adjustment period
+            if adjustment_due {
+                LastOraclePriceAdjustmentTick::<T>::put(current_tick);
+                meter.consume(T::DbWeight::get().writes(1));
+
+                Self::deposit_event(Event::<T>::OraclePriceStale {
+                    last_updated_at: oracle_price.map(|(_, last_updated_at)| last_updated_at),
+                });
+            }
+            return;
+        };
+
+        // Get the price resulting from the last adjustment, starting from the current price if there was none
+        let current_price = CurrentPricePerGigaUnitPerTick::<T>::get();
+        let tracked_price = FiatTrackedPrice::<T>::get().unwrap_or(current_price);
+
+        let new_price = if adjustment_due {
+            // Convert the target to the native token using the oracle price. If the native token is worth so little
+            // that the conversion overflows, the target in the native token is as high as it can get.
+            let target_price: BalanceOf<T> = oracle_price
+                .reciprocal()
+                .map(|reciprocal| reciprocal.saturating_mul_int(target.saturated_into::<u128>()))
+                .unwrap_or(u128::MAX)
+                .saturated_into();
+
+            // Move the price towards the target by at most the maximum adjustment, never letting it reach zero
+            let max_adjustment = T::MaxOraclePriceAdjustment::get().mul_ceil(tracked_price);
+            let new_price = if target_price > tracked_price {
+                target_price.min(tracked_price.saturating_add(max_adjustment))
+            } else {
+                target_price.max(tracked_price.saturating_sub(max_adjustment))
+            }
+            .max(One::one());
+
+            FiatTrackedPrice::<T>::put(new_price);
+            LastOraclePriceAdjustmentTick::<T>::put(current_tick);
+            meter.consume(T::DbWeight::get().writes(2));
+
+            Self::deposit_event(Event::<T>::PriceAdjustedToFiatTarget {
+                previous_price: tracked_price,
+                new_price,
+                oracle_price,
+            });
+
+            new_price
+        } else {
+            tracked_price
+        };
+
+        // Enforce the tracked price, in case it was overridden by the system manager
+        if new_price != current_price {
+            CurrentPricePerGigaUnitPerTick::<T>::put(new_price);
+            meter.consume(T::DbWeight::get().writes(1));
+        }
+    }
+
     /// This function advances the current tick and returns the previous and now-current tick.
     pub fn do_advance_tick(
         meter: &mut sp_weights::WeightMeter,
@@ -1302,6 +1408,37 @@ where
         (current_tick, next_tick)
     }
 
+    /// The rate per tick of a dynamic-rate payment stream for `amount_provided` units, at the current price.
+    ///
+    /// Rate is: `amount_provided * current_price_per_giga_unit_per_tick / giga_units`, as charged to the User.
+    pub fn dynamic_rate_per_tick(
+        amount_provided: UnitsProvidedFor<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Ok(CurrentPricePerGigaUnitPerTick::<T>::get()
+            .checked_mul(&amount_provided.into())
+            .ok_or(ArithmeticError::Overflow)?
+            .checked_div(&GIGAUNIT.into())
+            .unwrap_or_default())
+    }
+
+    /// The deposit held from a User for a dynamic-rate payment stream for `amount_provided` units, at the
+    /// current price.
+    ///
+    /// Deposit is: `(amount_provided * current_price_per_giga_unit_per_tick * NewStreamDeposit) / giga_units + BaseDeposit`.
+    pub fn dynamic_rate_payment_stream_deposit(
+        amount_provided: UnitsProvidedFor<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Ok(CurrentPricePerGigaUnitPerTick::<T>::get()
+            .checked_mul(&amount_provided.into())
+            .ok_or(ArithmeticError::Overflow)?
+            .checked_mul(&T::BlockNumberToBalance::convert(T::NewStreamDeposit::get()))
+            .ok_or(ArithmeticError::Overflow)?
+            .checked_div(&GIGAUNIT.into())
+            .unwrap_or_default()
+            .checked_add(&T::BaseDeposit::get())
+            .ok_or(ArithmeticError::Overflow)?)
+    }
+
     /// This function holds the logic that updates the deposit of a User based on the new deposit that should be held from them.
     ///
     /// It checks if the new deposit is greater or smaller than the old deposit and holds/releases the difference in deposit from the User accordingly.
@@ -1478,6 +1615,659 @@ where
 
         Ok(())
     }
+
+    /// This function holds the logic that adds funds to the auto top-up reserve of a User, holding them from its free balance.
+    pub fn do_deposit_auto_top_up_reserve(
+        user_account: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        // Check that the amount is not zero
+        ensure!(!amount.is_zero(), Error::<T>::AutoTopUpAmountCantBeZero);
+
+        // Check if we can hold the amount from the user
+        ensure!(
+            T::NativeBalance::can_hold(&HoldReason::AutoTopUpReserve.into(), user_account, amount),
+            Error::<T>::CannotHoldAutoTopUpReserve
+        );
+
+        // Hold the amount from the user
+        T::NativeBalance::hold(&HoldReason::AutoTopUpReserve.into(), user_account, amount)?;
+
+        Ok(())
+    }
+
+    /// This function holds the logic that withdraws funds from the auto top-up reserve of a User, releasing them to its free balance.
+    pub fn do_withdraw_auto_top_up_reserve(
+        user_account: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        // Check that the amount is not zero
+        ensure!(!amount.is_zero(), Error::<T>::AutoTopUpAmountCantBeZero);
+
+        // Check that the reserve of the user has enough funds
+        ensure!(
+            T::NativeBalance::balance_on_hold(&HoldReason::AutoTopUpReserve.into(), user_account)
+                >= amount,
+            Error::<T>::InsufficientAutoTopUpReserve
+        );
+
+        // Release the amount to the user
+        T::NativeBalance::release(
+            &HoldReason::AutoTopUpReserve.into(),
+            user_account,
+            amount,
+            Precision::Exact,
+        )?;
+
+        Ok(())
+    }
+
+    /// This function sets or clears the threshold of the automatic top-up of the free balance of a User.
+    ///
+    /// Clearing it disables the automatic top-up, dropping any pending top-up of the User. The funds in its reserve are
+    /// left there until it withdraws them.
+    pub fn do_set_auto_top_up_threshold(
+        user_account: &T::AccountId,
+        threshold: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        match threshold {
+            Some(threshold) => {
+                // Check that the threshold is not zero
+                ensure!(
+                    !threshold.is_zero(),
+                    Error::<T>::AutoTopUpThresholdCantBeZero
+                );
+
+                AutoTopUpThresholds::<T>::insert(user_account, threshold);
+            }
+            None => {
+                AutoTopUpThresholds::<T>::remove(user_account);
+                PendingAutoTopUps::<T>::remove(user_account);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function queues the top-up of the free balance of a User from its auto top-up reserve, to be executed in the
+    /// next `on_poll` hook execution, if it enabled the automatic top-up and its free balance is below its threshold or
+    /// short of `amount_due`.
+    pub(crate) fn queue_auto_top_up(user_account: &T::AccountId, amount_due: BalanceOf<T>) {
+        let Some(threshold) = AutoTopUpThresholds::<T>::get(user_account) else {
+            return;
+        };
+
+        let user_balance = T::NativeBalance::reducible_balance(
+            user_account,
+            Preservation::Preserve,
+            Fortitude::Polite,
+        );
+        if user_balance >= threshold.max(amount_due) {
+            return;
+        }
+
+        // Add up the amounts due of the payment streams the user could not pay since the last top-up
+        PendingAutoTopUps::<T>::mutate(user_account, |pending_amount_due| {
+            *pending_amount_due = Some(
+                pending_amount_due
+                    .unwrap_or_default()
+                    .saturating_add(amount_due),
+            );
+        });
+    }
+
+    /// This function tops up the free balance of the Users in [`PendingAutoTopUps`] from their auto top-up reserves, for as
+    /// many of them as the remaining weight of the block allows. The rest are topped up in the next `on_poll` hook executions.
+    pub fn do_process_pending_auto_top_ups(meter: &mut sp_weights::WeightMeter) {
+        // Reading, releasing and removing each pending top-up
+        let weight_per_top_up = T::DbWeight::get().reads_writes(4, 3);
+
+        meter.consume(T::DbWeight::get().reads(1));
+
+        let mut pending_auto_top_ups = PendingAutoTopUps::<T>::drain();
+        while meter.can_consume(weight_per_top_up) {
+            let Some((user_account, amount_due)) = pending_auto_top_ups.next() else {
+                break;
+            };
+            meter.consume(weight_per_top_up);
+
+            Self::do_auto_top_up(&user_account, amount_due);
+        }
+    }
+
+    /// This function tops up the free balance of a User from its auto top-up reserve, up to the greater of its threshold and
+    /// `amount_due`, or as much as the reserve allows.
+    ///
+    /// Emits a `AutoTopUpExecuted` event if any funds were moved to the free balance of the User.
+    fn do_auto_top_up(user_account: &T::AccountId, amount_due: BalanceOf<T>) {
+        // The user could have disabled the automatic top-up since it was queued
+        let Some(threshold) = AutoTopUpThresholds::<T>::get(user_account) else {
+            return;
+        };
+
+        // Get how much the free balance of the user is short of, capped by what is left in its reserve
+        let user_balance = T::NativeBalance::reducible_balance(
+            user_account,
+            Preservation::Preserve,
+            Fortitude::Polite,
+        );
+        let missing = threshold.max(amount_due).saturating_sub(user_balance);
+        let reserve =
+            T::NativeBalance::balance_on_hold(&HoldReason::AutoTopUpReserve.into(), user_account);
+        let amount_to_top_up = missing.min(reserve);
+        if amount_to_top_up.is_zero() {
+            return;
+        }
+
+        // Release the funds from the reserve to the free balance of the user
+        if let Ok(amount) = T::NativeBalance::release(
+            &HoldReason::AutoTopUpReserve.into(),
+            user_account,
+            amount_to_top_up,
+            Precision::BestEffort,
+        ) {
+            Self::deposit_event(Event::<T>::AutoTopUpExecuted {
+                who: user_account.clone(),
+                amount,
+                remaining_reserve: reserve.saturating_sub(amount),
+            });
+        }
+    }
+
+    /// This function holds the logic that opens an escrow for the payment of `providers_target` Providers, each of them
+    /// providing `amount_provided` units for `duration` ticks.
+    ///
+    /// The rate at which each Provider is paid is fixed to the current price per giga-unit per tick, and what it would cost
+    /// to pay all of them for all the ticks is held from the User upfront.
+    ///
+    /// Returns the rate per Provider and the amount held from the User.
+    pub fn do_open_escrow(
+        escrow_id: &EscrowIdFor<T>,
+        user_account: &T::AccountId,
+        providers_target: u32,
+        amount_provided: UnitsProvidedFor<T>,
+        duration: BlockNumberFor<T>,
+    ) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        // Check that the escrow does not already exist
+        ensure!(
+            !PaymentEscrows::<T>::contains_key(escrow_id),
+            Error::<T>::EscrowAlreadyExists
+        );
+
+        // Check that the escrow pays someone for something for some time
+        ensure!(
+            providers_target > 0 && !amount_provided.is_zero() && !duration.is_zero(),
+            Error::<T>::EscrowParamsCantBeZero
+        );
+
+        // Check that the user is not flagged as without funds
+        ensure!(
+            !UsersWithoutFunds::<T>::contains_key(user_account),
+            Error::<T>::UserWithoutFunds
+        );
+
+        // Rate is: `amount_provided * current_price_per_giga_unit_per_tick / giga_units`, rounded up so
+        // Providers of small amounts still get paid.
+        let rate_per_provider = CurrentPricePerGigaUnitPerTick::<T>::get()
+            .checked_mul(&amount_provided.into())
+            .ok_or(ArithmeticError::Overflow)?
+            .checked_add(&GIGAUNIT.saturating_sub(1).into())
+            .ok_or(ArithmeticError::Overflow)?
+            .checked_div(&GIGAUNIT.into())
+            .unwrap_or_default();
+
+        // Calculate the amount to hold, which is the share of each Provider times the amount of Providers
+        let amount_to_hold = Self::escrowed_share(rate_per_provider, duration)?
+            .checked_mul(&BalanceOf::<T>::from(providers_target))
+            .ok_or(Error::<T>::ChargeOverflow)?;
+
+        // Check if we can hold the escrowed funds from the user and, if so, hold them
+        ensure!(
+            T::NativeBalance::can_hold(
+                &HoldReason::EscrowedPayment.into(),
+                user_account,
+                amount_to_hold
+            ),
+            Error::<T>::CannotHoldEscrow
+        );
+        T::NativeBalance::hold(
+            &HoldReason::EscrowedPayment.into(),
+            user_account,
+            amount_to_hold,
+        )?;
+
+        // Store the escrow
+        PaymentEscrows::<T>::insert(
+            escrow_id,
+            PaymentEscrow {
+                user_account: user_account.clone(),
+                amount_provided,
+                rate_per_provider,
+                duration,
+                providers_target,
+                providers_count: 0,
+                amount_held: amount_to_hold,
+                replication_settled: false,
+            },
+        );
+
+        Ok((rate_per_provider, amount_to_hold))
+    }
+
+    /// This function holds the logic that adds a Provider to an escrow, which will pay it for the escrowed
+    /// amount of ticks starting from the current tick.
+    pub fn do_add_escrow_provider(
+        escrow_id: &EscrowIdFor<T>,
+        provider_id: &ProviderIdFor<T>,
+    ) -> Result<EscrowedProvider<T>, DispatchError> {
+        let mut escrow = PaymentEscrows::<T>::get(escrow_id).ok_or(Error::<T>::EscrowNotFound)?;
+
+        // Check that the funds for the Providers missing to reach the target have not been refunded yet
+        ensure!(
+            !escrow.replication_settled,
+            Error::<T>::EscrowReplicationAlreadySettled
+        );
+
+        // Check that the Provider is not already being paid by this escrow
+        ensure!(
+            !EscrowedProviders::<T>::contains_key(escrow_id, provider_id),
+            Error::<T>::ProviderAlreadyInEscrow
+        );
+
+        // Check that there are funds left in the escrow for another Provider
+        ensure!(
+            escrow.providers_count < escrow.providers_target,
+            Error::<T>::EscrowProvidersTargetReached
+        );
+
+        let start_tick = OnPollTicker::<T>::get();
+        let end_tick = start_tick
+            .checked_add(&escrow.duration)
+            .ok_or(ArithmeticError::Overflow)?;
+        let escrowed_provider = EscrowedProvider {
+            start_tick,
+            end_tick,
+            released_until_tick: start_tick,
+        };
+
+        EscrowedProviders::<T>::insert(escrow_id, provider_id, escrowed_provider.clone());
+        escrow.providers_count = escrow.providers_count.saturating_add(1);
+        PaymentEscrows::<T>::insert(escrow_id, escrow);
+
+        Ok(escrowed_provider)
+    }
+
+    /// This function holds the logic that releases to a Provider the payment escrowed for it, from the last tick it
+    /// was paid for up to its last chargeable tick (that is, the last tick for which it has proven to keep providing
+    /// its service), capped at the end of its escrowed ticks.
+    ///
+    /// If the Provider has been paid for all its escrowed ticks, it is removed from the escrow, and the escrow is closed
+    /// if it was the last Provider in it and its replication has been settled.
+    pub fn do_release_escrowed_payment_to_provider(
+        escrow_id: &EscrowIdFor<T>,
+        provider_id: &ProviderIdFor<T>,
+    ) -> DispatchResult {
+        // Check that the provider is not insolvent
+        ensure!(
+            !<T::ProvidersPallet as ReadProvidersInterface>::is_provider_insolvent(*provider_id),
+            Error::<T>::ProviderInsolvent
+        );
+
+        let mut escrow = PaymentEscrows::<T>::get(escrow_id).ok_or(Error::<T>::EscrowNotFound)?;
+        let mut escrowed_provider = EscrowedProviders::<T>::get(escrow_id, provider_id)
+            .ok_or(Error::<T>::ProviderNotInEscrow)?;
+
+        Self::release_escrowed_payment(
+            escrow_id,
+            &mut escrow,
+            provider_id,
+            &mut escrowed_provider,
+        )?;
+
+        if escrowed_provider.released_until_tick >= escrowed_provider.end_tick {
+            // The Provider has been paid its whole share, so from now on it gets paid for the escrowed units
+            // through its payment stream with the User, and it no longer counts towards the escrow's Providers.
+            Self::add_escrowed_units_to_payment_stream(provider_id, &escrow)?;
+            EscrowedProviders::<T>::remove(escrow_id, provider_id);
+            escrow.providers_count = escrow.providers_count.saturating_sub(1);
+            escrow.providers_target = escrow.providers_target.saturating_sub(1);
+        } else {
+            EscrowedProviders::<T>::insert(escrow_id, provider_id, escrowed_provider);
+        }
+
+        Self::update_or_close_escrow(escrow_id, escrow)
+    }
+
+    /// This function holds the logic that removes a Provider from an escrow before the end of its escrowed ticks,
+    /// releasing to it what it has proven so far (unless it is insolvent) and refunding the rest of its share to the User.
+    pub fn do_remove_escrow_provider(
+        escrow_id: &EscrowIdFor<T>,
+        provider_id: &ProviderIdFor<T>,
+    ) -> DispatchResult {
+        let mut escrow = PaymentEscrows::<T>::get(escrow_id).ok_or(Error::<T>::EscrowNotFound)?;
+        let mut escrowed_provider = EscrowedProviders::<T>::take(escrow_id, provider_id)
+            .ok_or(Error::<T>::ProviderNotInEscrow)?;
+
+        if !<T::ProvidersPallet as ReadProvidersInterface>::is_provider_insolvent(*provider_id) {
+            Self::release_escrowed_payment(
+                escrow_id,
+                &mut escrow,
+                provider_id,
+                &mut escrowed_provider,
+            )?;
+        }
+
+        // Refund to the User the part of the Provider's share that it has not been paid
+        let unreleased_ticks = escrowed_provider
+            .end_tick
+            .saturating_sub(escrowed_provider.released_until_tick);
+        let unreleased_share = Self::escrowed_share(escrow.rate_per_provider, unreleased_ticks)?
+            .min(escrow.amount_held);
+        let amount_refunded = T::NativeBalance::release(
+            &HoldReason::EscrowedPayment.into(),
+            &escrow.user_account,
+            unreleased_share,
+            Precision::BestEffort,
+        )?;
+        escrow.amount_held = escrow.amount_held.saturating_sub(amount_refunded);
+
+        // The funds for this Provider are gone, so another one can't take its place.
+        escrow.providers_count = escrow.providers_count.saturating_sub(1);
+        escrow.providers_target = escrow.providers_target.saturating_sub(1);
+
+        Self::deposit_event(Event::<T>::EscrowProviderRemoved {
+            escrow_id: *escrow_id,
+            provider_id: *provider_id,
+            amount_refunded,
+        });
+
+        Self::update_or_close_escrow(escrow_id, escrow)
+    }
+
+    /// This function holds the logic that settles the amount of Providers of an escrow to the ones currently in it,
+    /// refunding to the User the share of the Providers missing to reach the target.
+    ///
+    /// Settling an escrow whose replication has already been settled does nothing.
+    pub fn do_settle_escrow_replication(escrow_id: &EscrowIdFor<T>) -> DispatchResult {
+        let mut escrow = PaymentEscrows::<T>::get(escrow_id).ok_or(Error::<T>::EscrowNotFound)?;
+
+        if escrow.replication_settled {
+            return Ok(());
+        }
+
+        let missing_providers = escrow
+            .providers_target
+            .saturating_sub(escrow.providers_count);
+        if missing_providers > 0 {
+            let missing_share = Self::escrowed_share(escrow.rate_per_provider, escrow.duration)?
+                .checked_mul(&BalanceOf::<T>::from(missing_providers))
+                .ok_or(Error::<T>::ChargeOverflow)?
+                .min(escrow.amount_held);
+            let amount_refunded = T::NativeBalance::release(
+                &HoldReason::EscrowedPayment.into(),
+                &escrow.user_account,
+                missing_share,
+                Precision::BestEffort,
+            )?;
+            escrow.amount_held = escrow.amount_held.saturating_sub(amount_refunded);
+            escrow.providers_target = escrow.providers_count;
+
+            Self::deposit_event(Event::<T>::EscrowShortfallRefunded {
+                escrow_id: *escrow_id,
+                user_account: escrow.user_account.clone(),
+                missing_providers,
+                amount_refunded,
+            });
+        }
+
+        escrow.replication_settled = true;
+
+        Self::update_or_close_escrow(escrow_id, escrow)
+    }
+
+    /// Releases to a Provider the escrowed payment for the ticks it has proven since it was last paid by the escrow,
+    /// transferring a cut of it to the treasury, and updates `escrow` and `escrowed_provider` accordingly.
+    ///
+    /// Storage is not updated, that is left to the caller.
+    fn release_escrowed_payment(
+        escrow_id: &EscrowIdFor<T>,
+        escrow: &mut PaymentEscrow<T>,
+        provider_id: &ProviderIdFor<T>,
+        escrowed_provider: &mut EscrowedProvider<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        // Providers are paid up to the last tick for which they submitted a valid proof
+        let last_chargeable_tick = LastChargeableInfo::<T>::get(provider_id).last_chargeable_tick;
+        let release_until_tick = last_chargeable_tick.min(escrowed_provider.end_tick);
+
+        let ticks_to_release =
+            release_until_tick.saturating_sub(escrowed_provider.released_until_tick);
+        if ticks_to_release.is_zero() {
+            return Ok(Zero::zero());
+        }
+
+        let amount_to_release = Self::escrowed_share(escrow.rate_per_provider, ticks_to_release)?
+            .min(escrow.amount_held);
+
+        // Get the payment account of the SP
+        let provider_payment_account = expect_or_err!(
+            <T::ProvidersPallet as ReadProvidersInterface>::get_payment_account(*provider_id),
+            "Provider should exist and have a payment account if its ID exists.",
+            Error::<T>::ProviderInconsistencyError
+        );
+
+        // Get, from the amount to release, the cut for the treasury and the cut for the provider
+        let total_provided_amount =
+            <T::ProvidersPallet as SystemMetricsInterface>::get_total_capacity();
+        let used_provided_amount =
+            <T::ProvidersPallet as SystemMetricsInterface>::get_total_used_capacity();
+        let treasury_cut =
+            <T::TreasuryCutCalculator as TreasuryCutCalculator>::calculate_treasury_cut(
+                total_provided_amount,
+                used_provided_amount,
+                amount_to_release,
+            );
+        let provider_cut = amount_to_release.saturating_sub(treasury_cut);
+
+        // Pay the Provider and the treasury from the escrowed funds
+        T::NativeBalance::transfer_on_hold(
+            &HoldReason::EscrowedPayment.into(),
+            &escrow.user_account,
+            &provider_payment_account,
+            provider_cut,
+            Precision::Exact,
+            Restriction::Free,
+            Fortitude::Polite,
+        )?;
+        if !treasury_cut.is_zero() {
+            T::NativeBalance::transfer_on_hold(
+                &HoldReason::EscrowedPayment.into(),
+                &escrow.user_account,
+                &T::TreasuryAccount::get(),
+                treasury_cut,
+                Precision::Exact,
+                Restriction::Free,
+                Fortitude::Polite,
+            )?;
+        }
+
+        escrowed_provider.released_until_tick = release_until_tick;
+        escrow.amount_held = escrow.amount_held.saturating_sub(amount_to_release);
+
+        Self::deposit_event(Event::<T>::EscrowPaymentReleased {
+            escrow_id: *escrow_id,
+            user_account: escrow.user_account.clone(),
+            provider_id: *provider_id,
+            amount: amount_to_release,
+            released_until_tick: release_until_tick,
+        });
+
+        Ok(amount_to_release)
+    }
+
+    /// Adds the units provided for an escrow to the dynamic-rate payment stream between its User and a Provider,
+    /// creating the payment stream if it does not exist.
+    fn add_escrowed_units_to_payment_stream(
+        provider_id: &ProviderIdFor<T>,
+        escrow: &PaymentEscrow<T>,
+    ) -> DispatchResult {
+        match DynamicRatePaymentStreams::<T>::get(provider_id, &escrow.user_account) {
+            Some(payment_stream) => {
+                let new_amount_provided = payment_stream
+                    .amount_provided
+                    .checked_add(&escrow.amount_provided)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Self::do_update_dynamic_rate_payment_stream(
+                    provider_id,
+                    &escrow.user_account,
+                    new_amount_provided,
+                )?;
+                Self::deposit_event(Event::<T>::DynamicRatePaymentStreamUpdated {
+                    user_account: escrow.user_account.clone(),
+                    provider_id: *provider_id,
+                    new_amount_provided,
+                });
+            }
+            None => {
+                Self::do_create_dynamic_rate_payment_stream(
+                    provider_id,
+                    &escrow.user_account,
+                    escrow.amount_provided,
+                )?;
+                Self::deposit_event(Event::<T>::DynamicRatePaymentStreamCreated {
+                    user_account: escrow.user_account.clone(),
+                    provider_id: *provider_id,
+                    amount_provided: escrow.amount_provided,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stores the updated escrow, or closes it if its replication has been settled and no Provider is left in it.
+    fn update_or_close_escrow(
+        escrow_id: &EscrowIdFor<T>,
+        escrow: PaymentEscrow<T>,
+    ) -> DispatchResult {
+        if escrow.replication_settled && escrow.providers_count == 0 {
+            Self::refund_and_remove_escrow(escrow_id, escrow)
+        } else {
+            PaymentEscrows::<T>::insert(escrow_id, escrow);
+            Ok(())
+        }
+    }
+
+    /// Refunds to the User what is left in an escrow and removes it.
+    fn refund_and_remove_escrow(
+        escrow_id: &EscrowIdFor<T>,
+        escrow: PaymentEscrow<T>,
+    ) -> DispatchResult {
+        let amount_refunded = T::NativeBalance::release(
+            &HoldReason::EscrowedPayment.into(),
+            &escrow.user_account,
+            escrow.amount_held,
+            Precision::BestEffort,
+        )?;
+        PaymentEscrows::<T>::remove(escrow_id);
+
+        Self::deposit_event(Event::<T>::EscrowClosed {
+            escrow_id: *escrow_id,
+            user_account: escrow.user_account,
+            amount_refunded,
+        });
+
+        Ok(())
+    }
+
+    /// The amount that paying a single Provider of an escrow at `rate` for `ticks` costs.
+    fn escrowed_share(
+        rate: BalanceOf<T>,
+        ticks: BlockNumberFor<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        rate.checked_mul(&T::BlockNumberToBalance::convert(ticks))
+            .ok_or(Error::<T>::ChargeOverflow.into())
+    }
+}
+
+impl<T: pallet::Config> PaymentEscrowInterface for pallet::Pallet<T> {
+    type AccountId = T::AccountId;
+    type ProviderId = ProviderIdFor<T>;
+    type EscrowId = EscrowIdFor<T>;
+    type Units = UnitsProvidedFor<T>;
+    type TickNumber = BlockNumberFor<T>;
+
+    fn open_escrow(
+        escrow_id: &Self::EscrowId,
+        user_account: &Self::AccountId,
+        providers_target: u32,
+        amount_provided: &Self::Units,
+        duration: Self::TickNumber,
+    ) -> DispatchResult {
+        // Execute the logic to open the escrow
+        let (rate_per_provider, amount_held) = Self::do_open_escrow(
+            escrow_id,
+            user_account,
+            providers_target,
+            *amount_provided,
+            duration,
+        )?;
+
+        // Emit the corresponding event
+        Self::deposit_event(Event::<T>::EscrowOpened {
+            escrow_id: *escrow_id,
+            user_account: user_account.clone(),
+            providers_target,
+            amount_provided: *amount_provided,
+            rate_per_provider,
+            duration,
+            amount_held,
+        });
+
+        Ok(())
+    }
+
+    fn escrow_exists(escrow_id: &Self::EscrowId) -> bool {
+        PaymentEscrows::<T>::contains_key(escrow_id)
+    }
+
+    fn can_add_escrow_provider(escrow_id: &Self::EscrowId) -> bool {
+        PaymentEscrows::<T>::get(escrow_id).is_some_and(|escrow| {
+            !escrow.replication_settled && escrow.providers_count < escrow.providers_target
+        })
+    }
+
+    fn is_escrow_provider(escrow_id: &Self::EscrowId, provider_id: &Self::ProviderId) -> bool {
+        EscrowedProviders::<T>::contains_key(escrow_id, provider_id)
+    }
+
+    fn add_escrow_provider(
+        escrow_id: &Self::EscrowId,
+        provider_id: &Self::ProviderId,
+    ) -> DispatchResult {
+        // Execute the logic to add the Provider to the escrow
+        let escrowed_provider = Self::do_add_escrow_provider(escrow_id, provider_id)?;
+
+        // Emit the corresponding event
+        Self::deposit_event(Event::<T>::EscrowProviderAdded {
+            escrow_id: *escrow_id,
+            provider_id: *provider_id,
+            start_tick: escrowed_provider.start_tick,
+            end_tick: escrowed_provider.end_tick,
+        });
+
+        Ok(())
+    }
+
+    fn remove_escrow_provider(
+        escrow_id: &Self::EscrowId,
+        provider_id: &Self::ProviderId,
+    ) -> DispatchResult {
+        Self::do_remove_escrow_provider(escrow_id, provider_id)
+    }
+
+    fn settle_escrow_replication(escrow_id: &Self::EscrowId) -> DispatchResult {
+        Self::do_settle_escrow_replication(escrow_id)
+    }
 }
 
 impl<T: pallet::Config> PaymentStreamsInterface for pallet::Pallet<T> {
@@ -1642,6 +2432,18 @@ impl<T: pallet::Config> PaymentStreamsInterface for pallet::Pallet<T> {
             .map(|stream| stream.amount_provided)
     }
 
+    fn get_dynamic_rate_per_tick(
+        amount_provided: &Self::Units,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::dynamic_rate_per_tick(*amount_provided)
+    }
+
+    fn get_dynamic_rate_payment_stream_deposit(
+        amount_provided: &Self::Units,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::dynamic_rate_payment_stream_deposit(*amount_provided)
+    }
+
     fn has_active_payment_stream_with_user(
         provider_id: &Self::ProviderId,
         user_account: &Self::AccountId,
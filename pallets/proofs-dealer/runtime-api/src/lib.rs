@@ -6,7 +6,7 @@ use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
-    #[api_version(1)]
+    #[api_version(2)]
     pub trait ProofsDealerApi<ProviderId, BlockNumber, Key, RandomnessOutput, TrieRemoveMutation>
     where
         ProviderId: codec::Codec,
@@ -24,13 +24,40 @@ sp_api::decl_runtime_apis! {
         fn get_challenge_seed(tick: BlockNumber) -> Result<RandomnessOutput, GetChallengeSeedError>;
         fn get_challenge_period(provider_id: &ProviderId) -> Result<BlockNumber, GetChallengePeriodError>;
         fn get_checkpoint_challenge_period() -> BlockNumber;
+        #[api_version(2)]
+        fn get_max_proof_size() -> u32;
         fn get_challenges_from_seed(seed: &RandomnessOutput, provider_id: &ProviderId, count: u32) -> Vec<Key>;
         fn get_forest_challenges_from_seed(seed: &RandomnessOutput, provider_id: &ProviderId) -> Vec<Key>;
         fn get_current_tick() -> BlockNumber;
         fn get_next_deadline_tick(provider_id: &ProviderId) -> Result<BlockNumber, GetNextDeadlineTickError>;
+        #[api_version(2)]
+        fn get_ticks_catch_up_backlog() -> BlockNumber;
+        #[api_version(2)]
+        fn provider_challenge_schedule(provider_id: &ProviderId) -> Result<ProviderChallengeSchedule<BlockNumber, RandomnessOutput>, GetProviderChallengeScheduleError>;
     }
 }
 
+/// The challenge schedule of a Provider, as returned by the `provider_challenge_schedule` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ProviderChallengeSchedule<BlockNumber, RandomnessOutput> {
+    /// The last tick for which the Provider submitted a valid proof.
+    pub last_tick_proven: BlockNumber,
+    /// The next tick for which the Provider has to submit a proof.
+    pub next_tick_to_submit_proof_for: BlockNumber,
+    /// The last tick in which the proof for `next_tick_to_submit_proof_for` is accepted.
+    pub next_deadline_tick: BlockNumber,
+    /// The number of ticks between the proofs the Provider has to submit.
+    pub challenge_period: BlockNumber,
+    /// The current tick.
+    pub current_tick: BlockNumber,
+    /// The last tick in which checkpoint challenges were emitted.
+    pub last_checkpoint_tick: BlockNumber,
+    /// The seeds of the ticks the Provider has to submit proofs for, from `next_tick_to_submit_proof_for`
+    /// up to the current tick, one every `challenge_period` ticks. Ticks whose seed is no longer stored
+    /// are skipped.
+    pub seeds: Vec<(BlockNumber, RandomnessOutput)>,
+}
+
 /// Error type for the `get_last_tick_provider_submitted_proof` and `get_next_tick_to_submit_proof_for` runtime API calls.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum GetProofSubmissionRecordError {
@@ -70,3 +97,12 @@ pub enum GetNextDeadlineTickError {
     ArithmeticOverflow,
     InternalApiError,
 }
+
+/// Error type for the `provider_challenge_schedule` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum GetProviderChallengeScheduleError {
+    ProviderNotRegistered,
+    ProviderNotInitialised,
+    ArithmeticOverflow,
+    InternalApiError,
+}
@@ -56,11 +56,12 @@ mod benchmarks {
         pallet,
         types::{
             ChallengeTicksToleranceFor, CheckpointChallengePeriodFor, KeyFor,
-            MaxCustomChallengesPerBlockFor, MerkleTrieHashingFor, Proof, ProofSubmissionRecord,
-            ProvidersPalletFor,
+            MaxCustomChallengesPerBlockFor, MerkleTrieHashingFor, MinChallengePeriodFor, Proof,
+            ProofSubmissionRecord, ProvidersPalletFor, RetentionPolicy,
         },
-        Call, ChallengesQueue, ChallengesTicker, ChallengesTickerPaused, Config, Event,
-        LastCheckpointTick, LastDeletedTick, NotFullBlocksCount, Pallet, PastBlocksWeight,
+        Call, ChallengePeriodOverrides, ChallengesQueue, ChallengesTicker, ChallengesTickerPaused,
+        Config, Event, HistoryRetentionPolicy, LastCheckpointTick, LastDeletedTick,
+        LastPrunedChallengeSeedTick, NotFullBlocksCount, Pallet, PastBlocksWeight,
         ProviderToProofSubmissionRecord, SlashableProviders, TickToChallengesSeed,
         TickToCheckForSlashableProviders, TickToCheckpointChallenges, TickToProvidersDeadlines,
         ValidProofSubmittersLastTicks,
@@ -577,6 +578,113 @@ mod benchmarks {
         Ok(())
     }
 
+    /// * Case:
+    /// - Any case has the same complexity, so we'll just set an override for a Provider without one.
+    #[benchmark]
+    fn set_provider_challenge_period_override() -> Result<(), BenchmarkError> {
+        // Setup initial conditions.
+        let provider_id = <T as frame_system::Config>::Hashing::hash(
+            sp_runtime::format!("provider_id_{:?}", 0u32).as_bytes(),
+        );
+        register_providers::<T>(1u32)?;
+        let period = MinChallengePeriodFor::<T>::get();
+
+        #[extrinsic_call]
+        Pallet::<T>::set_provider_challenge_period_override(
+            RawOrigin::Root,
+            provider_id,
+            Some(period),
+        );
+
+        // Check that the override was set.
+        assert_eq!(
+            ChallengePeriodOverrides::<T>::get(provider_id),
+            Some(period)
+        );
+
+        Ok(())
+    }
+
+    /// * Case:
+    /// - The number of challenge seeds to prune is 0. This means that the loop never executes, but that loop
+    ///   execution is benchmarked in the `prune_challenge_seeds_loop` benchmark.
+    #[benchmark]
+    fn prune_challenge_seeds_constant_execution() -> Result<(), BenchmarkError> {
+        // `ChallengesTicker` and `LastPrunedChallengeSeedTick` are 0 by default, so there is nothing to prune.
+
+        // Pass the whole available weight for the Normal dispatch class.
+        let weights = T::BlockWeights::get();
+        let max_weight_for_class = weights
+            .get(DispatchClass::Normal)
+            .max_total
+            .unwrap_or(weights.max_block);
+
+        #[block]
+        {
+            Pallet::<T>::do_prune_challenge_seeds(max_weight_for_class);
+        }
+
+        // Check that `LastPrunedChallengeSeedTick` is still 0.
+        assert_eq!(
+            LastPrunedChallengeSeedTick::<T>::get(),
+            0u32.into(),
+            "LastPrunedChallengeSeedTick should still be 0."
+        );
+
+        Ok(())
+    }
+
+    /// * Case:
+    /// - Any case has the same complexity, so we'll just remove a stored seed.
+    #[benchmark]
+    fn prune_challenge_seeds_loop() -> Result<(), BenchmarkError> {
+        // Store a challenge seed for the tick to remove.
+        let tick: BlockNumberFor<T> = 181222u32.into();
+        let encoded_seed = get_seed();
+        let seed = <T as frame_system::Config>::Hash::decode(&mut encoded_seed.as_ref())
+            .expect("Seed should be decodable as it is a hash");
+        TickToChallengesSeed::<T>::insert(tick, seed);
+
+        #[block]
+        {
+            Pallet::<T>::remove_challenge_seed_for_tick(tick);
+        }
+
+        // Check that the seed has been removed.
+        assert!(
+            TickToChallengesSeed::<T>::get(tick).is_none(),
+            "The challenge seed should have been removed."
+        );
+
+        // Check that the `LastPrunedChallengeSeedTick` storage element has been updated.
+        assert_eq!(
+            LastPrunedChallengeSeedTick::<T>::get(),
+            tick,
+            "The `LastPrunedChallengeSeedTick` storage element should have been updated."
+        );
+
+        Ok(())
+    }
+
+    /// * Case:
+    /// - Any case has the same complexity, so we'll just set a policy when there is none.
+    #[benchmark]
+    fn set_retention_policy() -> Result<(), BenchmarkError> {
+        // Setup initial conditions.
+        let policy = RetentionPolicy::<T> {
+            challenge_seeds: ChallengeTicksToleranceFor::<T>::get() + One::one(),
+            valid_proof_submitters: T::TargetTicksStorageOfSubmitters::get(),
+        };
+
+        #[extrinsic_call]
+        Pallet::<T>::set_retention_policy(RawOrigin::Root, Some(policy.clone()));
+
+        // Check that the policy was set.
+        assert_eq!(HistoryRetentionPolicy::<T>::get(), Some(policy));
+
+        Ok(())
+    }
+
     impl_benchmark_test_suite! {
             Pallet,
             crate::mock::new_test_ext(),
@@ -13,6 +13,7 @@ pub mod benchmark_proofs;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
 pub mod types;
 pub mod utils;
 pub mod weights;
@@ -34,7 +35,7 @@ pub mod pallet {
         TrieRemoveMutation,
     };
     use sp_runtime::{
-        traits::{CheckedSub, Convert, Saturating, Zero},
+        traits::{CheckedSub, Convert, IdentifyAccount, Saturating, Verify, Zero},
         Perbill, SaturatedConversion,
     };
     use sp_std::vec::Vec;
@@ -234,19 +235,94 @@ pub mod pallet {
         /// the execution of the `on_poll` hook bounded.
         #[pallet::constant]
         type MaxSlashableProvidersPerTick: Get<u32>;
+
+        /// The maximum number of ticks whose deadlines can be checked in a single block.
+        ///
+        /// The [`ChallengesTicker`] advances one tick per block regardless of how many Providers
+        /// have to be marked as slashable. When the deadline checks fall behind (see
+        /// [`TickToCheckForSlashableProviders`]), they catch up by checking up to this many ticks per
+        /// block, keeping the execution of the `on_poll` hook bounded. The number of ticks still to
+        /// be checked is recorded in [`TicksCatchUpBacklog`].
+        ///
+        /// Must be at least 2, so that the checks can catch up while the ticker keeps advancing.
+        #[pallet::constant]
+        type MaxCatchUpTicksPerBlock: Get<u32>;
+
+        /// The number of proof submission deadlines a Provider can miss within a
+        /// [`Config::MissedDeadlineWarningWindow`] before being marked as slashable.
+        ///
+        /// Missing one of these deadlines only warns the Provider: its deadline is pushed like when
+        /// it is marked as slashable, but no failed proof submissions are accrued. Set to zero to
+        /// mark Providers as slashable from the first missed deadline.
+        #[pallet::constant]
+        type MissedDeadlinesAllowance: Get<u32>;
+
+        /// The number of ticks since a Provider is first warned during which its missed deadlines
+        /// count towards the [`Config::MissedDeadlinesAllowance`].
+        ///
+        /// Once the window is over, the next missed deadline starts a new window.
+        #[pallet::constant]
+        type MissedDeadlineWarningWindow: Get<BlockNumberFor<Self>>;
+
+        /// The maximum encoded size, in bytes, of a proof submitted by a Provider.
+        ///
+        /// Bounds the contribution of a proof submission to the PoV size of a block. Providers
+        /// whose proofs would exceed it (i.e. because of many checkpoint challenges) are expected
+        /// to check it before submitting, since the submission would fail.
+        #[pallet::constant]
+        type MaxProofSize: Get<u32>;
+
+        /// The maximum number of proofs a Provider can submit in a single batch, with
+        /// [`Pallet::submit_proofs`].
+        ///
+        /// Each proof in a batch counts towards the weight of the submission as much as if it were
+        /// submitted on its own.
+        #[pallet::constant]
+        type MaxProofsPerBatch: Get<u32>;
+
+        /// The signature with which the owner of a Provider authorises a proof submitted as an
+        /// unsigned transaction (see [`Pallet::submit_proof_unsigned`]).
+        type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+        /// The public key of an [`Config::OffchainSignature`], identifying the owner of a Provider.
+        type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
+        /// The priority in the transaction pool of proofs submitted as unsigned transactions.
+        #[pallet::constant]
+        type UnsignedProofPriority: Get<TransactionPriority>;
     }
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// A mapping from challenges tick to a random seed used for generating the challenges in that tick.
     ///
     /// This is used to keep track of the challenges' seed in the past.
-    /// This mapping goes back only [`ChallengeHistoryLengthFor`] blocks. Previous challenges are removed.
+    /// This mapping goes back only as many ticks as the retention policy allows (see
+    /// [`HistoryRetentionPolicy`]). Previous challenges are removed.
     #[pallet::storage]
     pub type TickToChallengesSeed<T: Config> =
         StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, RandomnessOutputFor<T>>;
 
+    /// The last tick whose challenge seed was pruned from [`TickToChallengesSeed`].
+    ///
+    /// Seeds are pruned in order, so all seeds up to this tick are gone.
+    #[pallet::storage]
+    pub type LastPrunedChallengeSeedTick<T: Config> =
+        StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// The retention policy set by governance for the history kept by this pallet.
+    ///
+    /// If not set, the retention is given by [`Config::ChallengeHistoryLength`] and
+    /// [`Config::TargetTicksStorageOfSubmitters`]. History that falls out of a shortened retention
+    /// is pruned lazily, in the `on_idle` hook.
+    #[pallet::storage]
+    pub type HistoryRetentionPolicy<T: Config> = StorageValue<_, RetentionPolicy<T>>;
+
     /// A mapping from challenges tick to a vector of custom challenged keys for that tick.
     ///
     /// This is used to keep track of the challenges that have been made in the past, specifically
@@ -303,6 +379,17 @@ pub mod pallet {
     pub type ProviderToProofSubmissionRecord<T: Config> =
         StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ProofSubmissionRecord<T>>;
 
+    /// The challenges tick of the last proof each Provider submitted as an unsigned transaction
+    /// (see [`Pallet::submit_proof_unsigned`]).
+    ///
+    /// It is recorded right before the transaction is dispatched, so it is kept even if the proof
+    /// then fails verification. This way, a Provider gets at most one free proof submission
+    /// included per challenges tick, instead of being able to fill blocks with failing proofs for
+    /// free. A Provider's entry is removed when its challenge cycle is stopped.
+    #[pallet::storage]
+    pub type UnsignedProofAttempts<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, BlockNumberFor<T>>;
+
     /// A queue of keys that have been challenged manually.
     ///
     /// The elements in this queue will be challenged in the coming blocks,
@@ -339,8 +426,39 @@ pub mod pallet {
     #[pallet::storage]
     pub type ChallengesTicker<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
+    /// A mapping from Providers that missed a proof submission deadline to their record of failed
+    /// proof submissions (see [`SlashableProviderRecord`]).
+    ///
+    /// The record is removed once the Provider is slashed.
     #[pallet::storage]
-    pub type SlashableProviders<T: Config> = StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, u32>;
+    pub type SlashableProviders<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, SlashableProviderRecord>;
+
+    /// A mapping from Providers to the number of proof submission deadlines they missed in a row.
+    ///
+    /// Unlike the record in [`SlashableProviders`], it is kept when the Provider is slashed, so
+    /// that slashes keep escalating while the Provider keeps missing deadlines. It is only reset
+    /// when the Provider submits a valid proof, or stops being challenged.
+    #[pallet::storage]
+    pub type ConsecutiveMissedDeadlines<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, u32, ValueQuery>;
+
+    /// A mapping from Providers that were warned for missing a proof submission deadline to their
+    /// current warning window (see [`ProviderWarningRecord`]).
+    ///
+    /// The record is replaced when a deadline is missed after the window is over.
+    #[pallet::storage]
+    pub type WarnedProviders<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ProviderWarningRecord<T>>;
+
+    /// A mapping from Providers to the challenge period set for them by governance, which is used
+    /// instead of the one derived from their stake.
+    ///
+    /// Meant for Providers whose proofs are particularly expensive to generate, like MSPs storing
+    /// huge buckets. Removed when the Provider's challenge cycle is stopped.
+    #[pallet::storage]
+    pub type ChallengePeriodOverrides<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, BlockNumberFor<T>>;
 
     /// A mapping from tick to Providers, which is set if the Provider submitted a valid proof in that tick.
     ///
@@ -401,6 +519,15 @@ pub mod pallet {
     pub type TickToCheckForSlashableProviders<T: Config> =
         StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
+    /// The number of ticks, up to and including the current [`ChallengesTicker`], whose deadlines are
+    /// yet to be checked, as of the end of the last challenges round.
+    ///
+    /// It is zero while [`TickToCheckForSlashableProviders`] is caught up with [`ChallengesTicker`].
+    /// Otherwise, it is the backlog that subsequent blocks will work through, at most
+    /// [`Config::MaxCatchUpTicksPerBlock`] ticks per block.
+    #[pallet::storage]
+    pub type TicksCatchUpBacklog<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         /// The checkpoint challenges that will be registered for the first checkpoint challenge (i.e. tick 0).
@@ -466,6 +593,15 @@ pub mod pallet {
             next_challenge_deadline: BlockNumberFor<T>,
         },
 
+        /// A provider missed a proof submission deadline within its allowance, so it was warned
+        /// instead of being marked as slashable. Its challenge deadline was forcefully pushed.
+        ProviderWarned {
+            provider: ProviderIdFor<T>,
+            next_challenge_deadline: BlockNumberFor<T>,
+            missed_deadlines: u32,
+            window_end: BlockNumberFor<T>,
+        },
+
         /// No record of the last tick the Provider submitted a proof for.
         NoRecordOfLastSubmittedProof { provider: ProviderIdFor<T> },
 
@@ -486,6 +622,46 @@ pub mod pallet {
 
         /// The [`ChallengesTicker`] has been paused or unpaused.
         ChallengesTickerSet { paused: bool },
+
+        /// Summary of the keys removed from a Provider's Forest by the remove mutations of a checkpoint
+        /// challenge round, emitted once the Provider submits the proof covering that round.
+        CheckpointMutationsSummary {
+            provider: ProviderIdFor<T>,
+            checkpoint_tick: BlockNumberFor<T>,
+            removed_keys: BoundedVec<KeyFor<T>, MaxCustomChallengesPerBlockFor<T>>,
+            new_root: KeyFor<T>,
+        },
+
+        /// The deadline checks fell further behind the [`ChallengesTicker`]. `backlog` is the number
+        /// of ticks whose deadlines are yet to be checked, starting at `next_tick_to_check`.
+        TicksCatchUpBacklogGrew {
+            backlog: BlockNumberFor<T>,
+            next_tick_to_check: BlockNumberFor<T>,
+        },
+
+        /// The deadline checks caught up with the [`ChallengesTicker`] after having fallen behind.
+        TicksCatchUpBacklogCleared {
+            challenges_ticker: BlockNumberFor<T>,
+        },
+
+        /// The retention policy for the history kept by this pallet was set. `policy` is the one in
+        /// effect from now on.
+        RetentionPolicySet { policy: RetentionPolicy<T> },
+
+        /// History that fell out of the retention policy was pruned, catching up after the retention
+        /// was shortened. `caught_up` is `false` while there is still history left to prune, which
+        /// continues in the following blocks.
+        HistoryPruned {
+            challenge_seeds_pruned_up_to: BlockNumberFor<T>,
+            valid_proof_submitters_pruned_up_to: BlockNumberFor<T>,
+            caught_up: bool,
+        },
+
+        /// The challenge period override of a Provider was set, or removed if `period` is `None`.
+        ChallengePeriodOverrideSet {
+            provider_id: ProviderIdFor<T>,
+            period: Option<BlockNumberFor<T>>,
+        },
     }
 
     // Errors inform users that something went wrong.
@@ -589,6 +765,35 @@ pub mod pallet {
 
         /// The limit of Providers that can submit a proof in a single tick has been reached.
         TooManyValidProofSubmitters,
+
+        /// The encoded size of the submitted proof exceeds [`Config::MaxProofSize`].
+        ProofTooLarge,
+
+        /// The Provider is registered but its status does not allow it to take on new duties
+        /// (e.g. it is insolvent, suspended or exiting).
+        ProviderNotActive,
+
+        /// The signature of a proof submitted as an unsigned transaction is not from the owner of
+        /// the Provider, or not for the tick the Provider has to submit a proof for.
+        InvalidProofSignature,
+
+        /// The challenge period override is shorter than [`Config::MinChallengePeriod`].
+        ChallengePeriodOverrideTooShort,
+
+        /// The challenge period override plus [`Config::ChallengeTicksTolerance`] is not shorter
+        /// than [`Config::CheckpointChallengePeriod`].
+        ChallengePeriodOverrideTooLong,
+
+        /// The retention policy would not keep challenge seeds for longer than
+        /// [`Config::ChallengeTicksTolerance`], or would not keep any valid proof submitters.
+        RetentionTooShort,
+
+        /// A batch of proofs was submitted without any proof.
+        EmptyProofBatch,
+
+        /// A proof in a batch is paired with a tick other than the one the Provider has to submit a
+        /// proof for after the previous proofs in the batch.
+        UnexpectedChallengesTick,
     }
 
     #[pallet::call]
@@ -643,26 +848,7 @@ pub mod pallet {
         ///
         /// Execution of this extrinsic should be refunded if the proof is valid.
         #[pallet::call_index(1)]
-        #[pallet::weight({
-            let max_random_key_proofs = T::RandomChallengesPerBlock::get().saturating_mul(2u32.into());
-            let max_custom_key_proofs = T::MaxCustomChallengesPerBlock::get().saturating_mul(2u32.into());
-
-            let max_key_proofs = max_random_key_proofs.saturating_add(max_custom_key_proofs);
-
-            let key_proofs_len = SaturatedConversion::saturated_into::<u32>(
-                proof.key_proofs.len()
-            );
-            match key_proofs_len {
-                n if n <= max_random_key_proofs => {
-                    T::WeightInfo::submit_proof_no_checkpoint_challenges_key_proofs(n)
-                }
-                n if n <= max_key_proofs => {
-                    T::WeightInfo::submit_proof_with_checkpoint_challenges_key_proofs(n)
-                }
-                // More key proofs than `max_key_proofs` would inevitably fail the transaction.
-                n => T::WeightInfo::submit_proof_with_checkpoint_challenges_key_proofs(n),
-            }
-        })]
+        #[pallet::weight(Pallet::<T>::submit_proof_weight(proof))]
         pub fn submit_proof(
             origin: OriginFor<T>,
             proof: Proof<T>,
@@ -738,6 +924,201 @@ pub mod pallet {
             // Return a successful DispatchResultWithPostInfo.
             Ok(Pays::No.into())
         }
+
+        /// Submit a proof for `provider` as an unsigned transaction.
+        ///
+        /// Works as [`Pallet::submit_proof`], but does not require the Provider to keep an account
+        /// funded and track its nonce to submit proofs. Instead, the owner of the Provider signs the
+        /// payload built by [`Pallet::unsigned_proof_payload`], which binds the proof to the tick
+        /// the Provider has to submit a proof for, so that it cannot be replayed.
+        ///
+        /// The transaction is only valid while the proof is due, i.e. from the tick the Provider
+        /// has to submit a proof for until its deadline, and only one can be included for each
+        /// tick, even if its proof fails verification (see [`UnsignedProofAttempts`]). A Provider
+        /// whose unsigned proof failed has to use [`Pallet::submit_proof`] instead.
+        #[pallet::call_index(4)]
+        #[pallet::weight(Pallet::<T>::submit_proof_weight(proof))]
+        pub fn submit_proof_unsigned(
+            origin: OriginFor<T>,
+            proof: Proof<T>,
+            provider: ProviderIdFor<T>,
+            signature: T::OffchainSignature,
+        ) -> DispatchResultWithPostInfo {
+            // Unsigned transaction, authorised by the signature of the owner of the Provider.
+            ensure_none(origin)?;
+
+            // The signature is already checked when validating the transaction, but it is cheap
+            // compared to verifying the proof, so make sure the call cannot be dispatched without it.
+            Self::check_unsigned_proof_signature(&provider, &proof, &signature)?;
+
+            let last_tick_proven = Self::do_submit_proof(&provider, &proof)?;
+
+            // Emit event.
+            Self::deposit_event(Event::ProofAccepted {
+                provider_id: provider,
+                proof,
+                last_tick_proven,
+            });
+
+            Ok(Pays::No.into())
+        }
+
+        /// Set the challenge period of a Provider, overriding the one derived from its stake, or
+        /// remove its override if `period` is `None`.
+        ///
+        /// Only callable by sudo.
+        ///
+        /// The new period applies from the next time the Provider's next challenge tick is
+        /// calculated (i.e. its next proof submission), so its current deadline is not changed.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::set_provider_challenge_period_override())]
+        pub fn set_provider_challenge_period_override(
+            origin: OriginFor<T>,
+            provider_id: ProviderIdFor<T>,
+            period: Option<BlockNumberFor<T>>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was executed by the root origin.
+            ensure_root(origin)?;
+
+            Self::do_set_provider_challenge_period_override(&provider_id, period)?;
+
+            // Emit event.
+            Self::deposit_event(Event::<T>::ChallengePeriodOverrideSet {
+                provider_id,
+                period,
+            });
+
+            // Return a successful DispatchResultWithPostInfo.
+            Ok(Pays::No.into())
+        }
+
+        /// Set the retention policy for the history kept by this pallet, or go back to the one given
+        /// by the pallet's constants if `policy` is `None`.
+        ///
+        /// Only callable by sudo.
+        ///
+        /// History that falls out of a shortened retention is pruned lazily, in the `on_idle` hook.
+        /// Seeds that were already pruned are not restored when the retention is lengthened.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_retention_policy())]
+        pub fn set_retention_policy(
+            origin: OriginFor<T>,
+            policy: Option<RetentionPolicy<T>>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was executed by the root origin.
+            ensure_root(origin)?;
+
+            let policy = Self::do_set_retention_policy(policy)?;
+
+            // Emit event.
+            Self::deposit_event(Event::<T>::RetentionPolicySet { policy });
+
+            // Return a successful DispatchResultWithPostInfo.
+            Ok(Pays::No.into())
+        }
+
+        /// For a Provider to submit proofs for several consecutive challenge ticks at once, i.e.
+        /// after falling behind by more than one of its challenge periods.
+        ///
+        /// Works as [`Pallet::submit_proof`] for each of the `proofs`, in order. Each proof is
+        /// paired with the challenges tick it is a response to, which has to be the tick the
+        /// Provider should be submitting a proof for after the previous proofs in the batch are
+        /// accepted. The Provider's [`ProviderToProofSubmissionRecord`] and deadline are only
+        /// updated once, after all the proofs are verified.
+        ///
+        /// Either all the proofs are accepted or none is: the first one failing verification
+        /// fails the whole submission, without accepting the proofs before it.
+        ///
+        /// Execution of this extrinsic should be refunded if all the proofs are valid.
+        #[pallet::call_index(7)]
+        #[pallet::weight(Pallet::<T>::submit_proofs_weight(proofs))]
+        pub fn submit_proofs(
+            origin: OriginFor<T>,
+            proofs: ProofBatch<T>,
+            provider: Option<ProviderIdFor<T>>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Getting provider from the origin if none is provided.
+            let provider = match provider {
+                Some(provider) => provider,
+                None => T::ProvidersPallet::get_provider_id(who.clone())
+                    .ok_or(Error::<T>::NotProvider)?,
+            };
+
+            Self::do_submit_proofs(&provider, &proofs)?;
+
+            // Emit an event for each proof accepted.
+            for (last_tick_proven, proof) in proofs {
+                Self::deposit_event(Event::ProofAccepted {
+                    provider_id: provider,
+                    proof,
+                    last_tick_proven,
+                });
+            }
+
+            // Return a successful DispatchResultWithPostInfo.
+            // If the proofs are valid, the execution of this extrinsic should be refunded.
+            Ok(Pays::No.into())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        /// Validates proofs submitted through [`Pallet::submit_proof_unsigned`].
+        ///
+        /// Only one proof per Provider and challenges tick is kept in the transaction pool, and
+        /// it is dropped once the deadline to submit it has passed, or once a proof for that tick
+        /// was included in a block.
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::submit_proof_unsigned {
+                proof,
+                provider,
+                signature,
+            } = call
+            else {
+                return InvalidTransaction::Call.into();
+            };
+
+            let (challenges_tick, deadline) =
+                Self::validate_unsigned_proof_submission(provider, proof, signature)?;
+
+            let longevity = deadline
+                .saturating_sub(ChallengesTicker::<T>::get())
+                .saturated_into::<u64>();
+
+            ValidTransaction::with_tag_prefix("ProofsDealerUnsignedProof")
+                .priority(T::UnsignedProofPriority::get())
+                .and_provides((provider, challenges_tick))
+                .longevity(longevity)
+                .propagate(true)
+                .build()
+        }
+
+        /// Validates a proof submitted through [`Pallet::submit_proof_unsigned`] right before it
+        /// is dispatched, and records the attempt in [`UnsignedProofAttempts`].
+        ///
+        /// Unlike the changes made by the call itself, this record is not rolled back if the
+        /// dispatch fails, so a failing proof cannot be included again for the same tick.
+        fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+            let Call::submit_proof_unsigned {
+                proof,
+                provider,
+                signature,
+            } = call
+            else {
+                return Err(InvalidTransaction::Call.into());
+            };
+
+            let (challenges_tick, _) =
+                Self::validate_unsigned_proof_submission(provider, proof, signature)?;
+            UnsignedProofAttempts::<T>::insert(provider, challenges_tick);
+
+            Ok(())
+        }
     }
 
     #[pallet::hooks]
@@ -763,13 +1144,15 @@ pub mod pallet {
             Self::do_check_spamming_condition(weight);
         }
 
-        /// This hook is used to trim down the `ValidProofSubmittersLastTicks` StorageMap up to the `TargetTicksOfProofsStorage`.
+        /// This hook is used to prune the history that falls out of the retention policy: it trims down the
+        /// `ValidProofSubmittersLastTicks` StorageMap, and the `TickToChallengesSeed` StorageMap if the retention
+        /// was shortened.
         ///
         /// It runs when the block is being finalized (but before the `on_finalize` hook) and can consume all remaining weight.
         /// It returns the used weight, so it can be used to calculate the remaining weight for the block for any other
         /// pallets that have `on_idle` hooks.
         fn on_idle(n: BlockNumberFor<T>, weight: Weight) -> Weight {
-            Self::do_trim_valid_proof_submitters_last_ticks(n, weight)
+            Self::do_prune_history(n, weight)
         }
 
         /// This hook is called on block initialization and returns the Weight of the `on_finalize` hook to
@@ -801,6 +1184,7 @@ pub mod pallet {
         /// This integrity test checks that:
         /// 1. `CheckpointChallengePeriod` is greater or equal to the longest period a Provider can have.
         /// 2. `BlockFullnessPeriod` is smaller or equal than `ChallengeTicksTolerance`.
+        /// 3. `MaxCatchUpTicksPerBlock` is at least 2.
         ///
         /// Any code located in this hook is placed in an auto-generated test, and generated as a part
         /// of crate::construct_runtime's expansion.
@@ -826,6 +1210,13 @@ pub mod pallet {
                 T::BlockFullnessPeriod::get(),
                 T::ChallengeTicksTolerance::get()
             );
+
+            // Check that the deadline checks can catch up while the ticker keeps advancing.
+            assert!(
+                T::MaxCatchUpTicksPerBlock::get() >= 2,
+                "MaxCatchUpTicksPerBlock const ({:?}) in ProofsDealer pallet should be at least 2.",
+                T::MaxCatchUpTicksPerBlock::get()
+            );
         }
     }
 }
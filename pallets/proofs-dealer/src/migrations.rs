@@ -0,0 +1,135 @@
+pub mod v1 {
+    use frame_support::{
+        migrations::VersionedMigration,
+        traits::{Get, UncheckedOnRuntimeUpgrade},
+        weights::Weight,
+    };
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+    #[cfg(feature = "try-runtime")]
+    use sp_std::vec::Vec;
+
+    use crate::{
+        types::SlashableProviderRecord, Config, ConsecutiveMissedDeadlines, Pallet,
+        SlashableProviders,
+    };
+
+    /// Migrates [`SlashableProviders`] from storing the number of accrued failed proof submissions
+    /// (`u32`) to storing a [`SlashableProviderRecord`].
+    ///
+    /// Migrated Providers are considered to have missed a single deadline in a row (see
+    /// [`ConsecutiveMissedDeadlines`]), since the number of missed deadlines was not tracked before.
+    pub struct InnerMigrateV0ToV1<T: Config>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            SlashableProviders::<T>::translate::<u32, _>(
+                |provider_id, accrued_failed_proof_submissions| {
+                    translated += 1;
+                    ConsecutiveMissedDeadlines::<T>::insert(provider_id, 1);
+                    Some(SlashableProviderRecord {
+                        accrued_failed_proof_submissions,
+                    })
+                },
+            );
+
+            T::DbWeight::get().reads_writes(translated, translated.saturating_mul(2))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            use codec::Encode;
+
+            Ok((SlashableProviders::<T>::iter_keys().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            use codec::Decode;
+
+            let count_before = u32::decode(&mut &state[..])
+                .map_err(|_| "Failed to decode the number of slashable Providers")?;
+            let count_after = SlashableProviders::<T>::iter_values().count() as u32;
+            frame_support::ensure!(
+                count_before == count_after,
+                "Slashable Providers were lost in the migration"
+            );
+
+            Ok(())
+        }
+    }
+
+    /// [`InnerMigrateV0ToV1`] wrapped in a [`VersionedMigration`], so it only runs if the
+    /// on-chain storage version is 0, and bumps it to 1.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v2 {
+    use frame_support::{
+        migrations::VersionedMigration,
+        traits::{Get, UncheckedOnRuntimeUpgrade},
+        weights::Weight,
+    };
+    use sp_runtime::Saturating;
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+    #[cfg(feature = "try-runtime")]
+    use sp_std::vec::Vec;
+
+    use crate::{
+        types::ChallengeHistoryLengthFor, ChallengesTicker, Config, LastPrunedChallengeSeedTick,
+        Pallet,
+    };
+
+    /// Initialises [`LastPrunedChallengeSeedTick`], the cursor used to prune challenge seeds that
+    /// fall out of the retention policy.
+    ///
+    /// Before, the seed of each tick was removed exactly [`crate::Config::ChallengeHistoryLength`]
+    /// ticks later, so all seeds up to `ChallengesTicker - ChallengeHistoryLength` are already gone.
+    /// Without this, the cursor would start at 0 and the `on_idle` hook would go through every tick
+    /// since genesis looking for seeds to prune.
+    pub struct InnerMigrateV1ToV2<T: Config>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let last_pruned_tick =
+                ChallengesTicker::<T>::get().saturating_sub(ChallengeHistoryLengthFor::<T>::get());
+            LastPrunedChallengeSeedTick::<T>::put(last_pruned_tick);
+
+            T::DbWeight::get().reads_writes(1, 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let last_pruned_tick = LastPrunedChallengeSeedTick::<T>::get();
+            frame_support::ensure!(
+                crate::TickToChallengesSeed::<T>::get(last_pruned_tick).is_none(),
+                "The seed of the last pruned tick should have been removed"
+            );
+
+            Ok(())
+        }
+    }
+
+    /// [`InnerMigrateV1ToV2`] wrapped in a [`VersionedMigration`], so it only runs if the
+    /// on-chain storage version is 1, and bumps it to 2.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        InnerMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
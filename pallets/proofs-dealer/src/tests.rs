@@ -16,8 +16,14 @@ use frame_support::{
 use frame_system::{
     limits::BlockWeights, pallet_prelude::BlockNumberFor, BlockWeight, ConsumedWeight,
 };
+use pallet_proofs_dealer_runtime_api::{
+    GetProviderChallengeScheduleError, ProviderChallengeSchedule,
+};
 use pallet_storage_providers::HoldReason;
-use shp_traits::{ProofsDealerInterface, ReadChallengeableProvidersInterface, TrieRemoveMutation};
+use shp_traits::{
+    ProofSubmittersInterface, ProofsDealerInterface, ReadChallengeableProvidersInterface,
+    TrieRemoveMutation,
+};
 use sp_core::{blake2_256, Get, Hasher, H256};
 use sp_runtime::{
     traits::{BlakeTwo256, Zero},
@@ -31,15 +37,17 @@ use crate::{
     types::{
         BalanceFor, BlockFullnessHeadroomFor, BlockFullnessPeriodFor, ChallengeHistoryLengthFor,
         ChallengeTicksToleranceFor, ChallengesQueueLengthFor, CheckpointChallengePeriodFor,
-        KeyProof, MaxCustomChallengesPerBlockFor, MaxSlashableProvidersPerTickFor,
-        MaxSubmittersPerTickFor, MinChallengePeriodFor, MinNotFullBlocksRatioFor, Proof,
-        ProofSubmissionRecord, ProviderIdFor, ProvidersPalletFor, RandomChallengesPerBlockFor,
-        StakeToChallengePeriodFor, TargetTicksStorageOfSubmittersFor,
+        KeyProof, MaxCatchUpTicksPerBlockFor, MaxCustomChallengesPerBlockFor,
+        MaxSlashableProvidersPerTickFor, MaxSubmittersPerTickFor, MinChallengePeriodFor,
+        MinNotFullBlocksRatioFor, Proof, ProofSubmissionRecord, ProviderIdFor,
+        ProviderWarningRecord, ProvidersPalletFor, RandomChallengesPerBlockFor, RetentionPolicy,
+        SlashableProviderRecord, StakeToChallengePeriodFor, TargetTicksStorageOfSubmittersFor,
     },
-    ChallengesTicker, ChallengesTickerPaused, LastCheckpointTick, LastDeletedTick,
+    ChallengePeriodOverrides, ChallengesTicker, ChallengesTickerPaused, ConsecutiveMissedDeadlines,
+    HistoryRetentionPolicy, LastCheckpointTick, LastDeletedTick, LastPrunedChallengeSeedTick,
     NotFullBlocksCount, ProviderToProofSubmissionRecord, SlashableProviders, TickToChallengesSeed,
     TickToCheckForSlashableProviders, TickToCheckpointChallenges, TickToProvidersDeadlines,
-    ValidProofSubmittersLastTicks,
+    TicksCatchUpBacklog, UnsignedProofAttempts, ValidProofSubmittersLastTicks, WarnedProviders,
 };
 
 fn run_to_block(n: u64) {
@@ -785,6 +793,62 @@ fn proofs_dealer_trait_initialise_challenge_cycle_not_provider_fail() {
     });
 }
 
+#[test]
+fn proofs_dealer_trait_initialise_challenge_cycle_suspended_provider_fail() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        // Mock a Provider ID.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+
+        // Register user as a Provider in Providers pallet.
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: Default::default(),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Add balance to that Provider and hold some so it has a stake.
+        let provider_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            provider_balance
+        ));
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            provider_balance / 100
+        ));
+
+        // Suspend the Provider.
+        assert_ok!(pallet_storage_providers::Pallet::<Test>::suspend_provider(
+            RuntimeOrigin::root(),
+            provider_id
+        ));
+
+        // Expect failure since the Provider can't take on new duties.
+        assert_noop!(
+            ProofsDealer::force_initialise_challenge_cycle(RuntimeOrigin::root(), provider_id),
+            crate::Error::<Test>::ProviderNotActive
+        );
+    });
+}
+
 #[test]
 fn submit_proof_success() {
     new_test_ext().execute_with(|| {
@@ -944,6 +1008,186 @@ fn submit_proof_success() {
     });
 }
 
+#[test]
+fn submit_proof_resets_consecutive_missed_deadlines() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        // Create user and add funds to the account.
+        let user = RuntimeOrigin::signed(1);
+        let user_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            user_balance
+        ));
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: Default::default(),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Hold some of the Provider's balance so it simulates it having a stake.
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            user_balance / 100
+        ));
+
+        // Set Provider's root to be an arbitrary value, different than the default root,
+        // to simulate that it is actually providing a service.
+        let root = BlakeTwo256::hash(b"1234");
+        pallet_storage_providers::BackupStorageProviders::<Test>::mutate(
+            &provider_id,
+            |provider| {
+                provider.as_mut().expect("Provider should exist").root = root;
+            },
+        );
+
+        // Mock that Provider submitted its last proof in this tick.
+        let current_tick = ChallengesTicker::<Test>::get();
+        let last_tick_provider_submitted_proof = current_tick;
+
+        // Calculate next Provider's deadline for submitting a proof.
+        // It is the sum of this Provider's challenge period and the `ChallengesTicksTolerance`.
+        let providers_stake =
+            <ProvidersPalletFor<Test> as ReadChallengeableProvidersInterface>::get_stake(
+                provider_id,
+            )
+            .unwrap();
+        let challenge_period = crate::Pallet::<Test>::stake_to_challenge_period(providers_stake);
+        let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+        let challenge_period_plus_tolerance = challenge_period + challenge_ticks_tolerance;
+        let prev_deadline = current_tick + challenge_period_plus_tolerance;
+
+        // Set Provider's proof record.
+        let proof_record = ProofSubmissionRecord {
+            last_tick_proven: last_tick_provider_submitted_proof,
+            next_tick_to_submit_proof_for: last_tick_provider_submitted_proof + challenge_period,
+        };
+        ProviderToProofSubmissionRecord::<Test>::insert(&provider_id, proof_record);
+
+        // Set Provider's deadline.
+        TickToProvidersDeadlines::<Test>::insert(prev_deadline, provider_id, ());
+
+        // Advance to the next challenge the Provider should listen to.
+        let providers_stake =
+            <ProvidersPalletFor<Test> as ReadChallengeableProvidersInterface>::get_stake(
+                provider_id,
+            )
+            .unwrap();
+        let challenge_period = crate::Pallet::<Test>::stake_to_challenge_period(providers_stake);
+        let current_block = System::block_number();
+        let challenge_block = current_block + challenge_period;
+        run_to_block(challenge_block);
+        // Advance less than `ChallengeTicksTolerance` blocks.
+        let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+        let current_block = System::block_number();
+        run_to_block(current_block + challenge_ticks_tolerance - 1);
+
+        // Get the seed for challenge block.
+        let seed = TickToChallengesSeed::<Test>::get(challenge_block).unwrap();
+
+        // Calculate challenges from seed, so that we can mock a key proof for each.
+        let challenges = crate::Pallet::<Test>::generate_challenges_from_seed(
+            seed,
+            &provider_id,
+            RandomChallengesPerBlockFor::<Test>::get(),
+        );
+
+        // Creating a vec of proofs with some content to pass verification.
+        let mut key_proofs = BTreeMap::new();
+        for challenge in challenges {
+            key_proofs.insert(
+                challenge,
+                KeyProof::<Test> {
+                    proof: CompactProof {
+                        encoded_nodes: vec![vec![0]],
+                    },
+                    challenge_count: Default::default(),
+                },
+            );
+        }
+
+        // Mock a proof.
+        let proof = Proof::<Test> {
+            forest_proof: CompactProof {
+                encoded_nodes: vec![vec![0]],
+            },
+            key_proofs,
+        };
+
+        // Mock that the Provider is slashable after missing a few deadlines in a row.
+        SlashableProviders::<Test>::insert(
+            &provider_id,
+            SlashableProviderRecord {
+                accrued_failed_proof_submissions: 6,
+            },
+        );
+        ConsecutiveMissedDeadlines::<Test>::insert(&provider_id, 3);
+
+        // Dispatch submit proof extrinsic.
+        assert_ok!(ProofsDealer::submit_proof(user, proof, None));
+
+        // Check that the streak of missed deadlines is reset, but the accrued failed proof
+        // submissions are kept until the Provider is slashed.
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&provider_id), 0);
+        assert_eq!(
+            SlashableProviders::<Test>::get(&provider_id),
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions: 6,
+            })
+        );
+    });
+}
+
+#[test]
+fn slashing_provider_keeps_consecutive_missed_deadlines() {
+    new_test_ext().execute_with(|| {
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+
+        // Mock that the Provider is slashable after missing a few deadlines in a row.
+        SlashableProviders::<Test>::insert(
+            &provider_id,
+            SlashableProviderRecord {
+                accrued_failed_proof_submissions: 6,
+            },
+        );
+        ConsecutiveMissedDeadlines::<Test>::insert(&provider_id, 3);
+
+        // Slashing the Provider clears its accrued failed proof submissions...
+        <ProofsDealer as ProofSubmittersInterface>::clear_accrued_failed_proof_submissions(
+            &provider_id,
+        );
+        assert_eq!(SlashableProviders::<Test>::get(&provider_id), None);
+
+        // ...but not its streak of missed deadlines, so the next slash keeps escalating.
+        assert_eq!(
+            <ProofsDealer as ProofSubmittersInterface>::get_consecutive_missed_proof_deadlines(
+                &provider_id
+            ),
+            3
+        );
+    });
+}
+
 #[test]
 fn submit_proof_adds_provider_to_valid_submitters_set() {
     new_test_ext().execute_with(|| {
@@ -1526,6 +1770,23 @@ fn submit_proof_with_checkpoint_challenges_mutations_success() {
             .into(),
         );
 
+        // Check that the summary of the keys removed in the checkpoint challenge round is emitted.
+        System::assert_has_event(
+            Event::CheckpointMutationsSummary {
+                provider: provider_id,
+                checkpoint_tick: checkpoint_challenge_block,
+                removed_keys: BoundedVec::try_from(
+                    custom_challenges
+                        .iter()
+                        .map(|(key, _)| *key)
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap(),
+                new_root: challenges.last().unwrap().clone(),
+            }
+            .into(),
+        );
+
         // Check if root of the provider was updated the last challenge key
         // Note: The apply_delta method is applying the mutation the root of the provider for every challenge key.
         // This is to avoid having to construct valid tries and proofs.
@@ -2380,7 +2641,7 @@ fn submit_proof_empty_key_proofs_fail() {
 }
 
 #[test]
-fn submit_proof_no_record_of_last_proof_fail() {
+fn submit_proof_too_large_fail() {
     new_test_ext().execute_with(|| {
         // Go past genesis block so events get deposited.
         run_to_block(1);
@@ -2393,12 +2654,14 @@ fn submit_proof_no_record_of_last_proof_fail() {
             user_balance
         ));
 
+        // Mock a key proof whose encoded size exceeds the maximum proof size.
+        let max_proof_size = <Test as crate::Config>::MaxProofSize::get() as usize;
         let mut key_proofs = BTreeMap::new();
         key_proofs.insert(
             BlakeTwo256::hash(b"key"),
             KeyProof::<Test> {
                 proof: CompactProof {
-                    encoded_nodes: vec![vec![0]],
+                    encoded_nodes: vec![vec![0; max_proof_size]],
                 },
                 challenge_count: Default::default(),
             },
@@ -2411,6 +2674,7 @@ fn submit_proof_no_record_of_last_proof_fail() {
             },
             key_proofs,
         };
+        assert!(proof.encoded_size() > max_proof_size);
 
         // Register user as a Provider in Providers pallet.
         let provider_id = BlakeTwo256::hash(b"provider_id");
@@ -2447,13 +2711,13 @@ fn submit_proof_no_record_of_last_proof_fail() {
         // Dispatch challenge extrinsic.
         assert_noop!(
             ProofsDealer::submit_proof(user, proof, None),
-            crate::Error::<Test>::NoRecordOfLastSubmittedProof
+            crate::Error::<Test>::ProofTooLarge
         );
     });
 }
 
 #[test]
-fn submit_proof_challenges_block_not_reached_fail() {
+fn submit_proof_no_record_of_last_proof_fail() {
     new_test_ext().execute_with(|| {
         // Go past genesis block so events get deposited.
         run_to_block(1);
@@ -2507,13 +2771,6 @@ fn submit_proof_challenges_block_not_reached_fail() {
             },
         );
 
-        // Hold some of the Provider's balance so it simulates it having a stake.
-        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
-            &HoldReason::StorageProviderDeposit.into(),
-            &1,
-            user_balance / 100
-        ));
-
         // Set Provider's root to be an arbitrary value, different than the default root,
         // to simulate that it is actually providing a service.
         let root = BlakeTwo256::hash(b"1234");
@@ -2524,26 +2781,16 @@ fn submit_proof_challenges_block_not_reached_fail() {
             },
         );
 
-        // Set Provider's last submitted proof block.
-        let proof_record = ProofSubmissionRecord {
-            last_tick_proven: 1,
-            next_tick_to_submit_proof_for: 10,
-        };
-        ProviderToProofSubmissionRecord::<Test>::insert(&provider_id, proof_record);
-
         // Dispatch challenge extrinsic.
         assert_noop!(
             ProofsDealer::submit_proof(user, proof, None),
-            crate::Error::<Test>::ChallengesTickNotReached
+            crate::Error::<Test>::NoRecordOfLastSubmittedProof
         );
     });
 }
 
 #[test]
-#[should_panic(
-    expected = "internal error: entered unreachable code: Challenges tick is too old, beyond the history this pallet keeps track of. This should not be possible."
-)]
-fn submit_proof_challenges_block_too_old_fail() {
+fn submit_proof_challenges_block_not_reached_fail() {
     new_test_ext().execute_with(|| {
         // Go past genesis block so events get deposited.
         run_to_block(1);
@@ -2581,7 +2828,6 @@ fn submit_proof_challenges_block_too_old_fail() {
             &1,
             provider_id,
         );
-
         pallet_storage_providers::BackupStorageProviders::<Test>::insert(
             &provider_id,
             pallet_storage_providers::types::BackupStorageProvider {
@@ -2622,20 +2868,111 @@ fn submit_proof_challenges_block_too_old_fail() {
         };
         ProviderToProofSubmissionRecord::<Test>::insert(&provider_id, proof_record);
 
-        // Advance more than `ChallengeHistoryLength` blocks.
-        let challenge_history_length: u64 = ChallengeHistoryLengthFor::<Test>::get();
-        run_to_block(challenge_history_length * 2);
-
         // Dispatch challenge extrinsic.
-        let _ = ProofsDealer::submit_proof(user, proof, None);
+        assert_noop!(
+            ProofsDealer::submit_proof(user, proof, None),
+            crate::Error::<Test>::ChallengesTickNotReached
+        );
     });
 }
 
 #[test]
 #[should_panic(
-    expected = "internal error: entered unreachable code: Seed for challenges tick not found, when checked it should be within history."
+    expected = "internal error: entered unreachable code: Challenges tick is too old, beyond the history this pallet keeps track of. This should not be possible."
 )]
-fn submit_proof_seed_not_found_fail() {
+fn submit_proof_challenges_block_too_old_fail() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        // Create user and add funds to the account.
+        let user = RuntimeOrigin::signed(1);
+        let user_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            user_balance
+        ));
+
+        let mut key_proofs = BTreeMap::new();
+        key_proofs.insert(
+            BlakeTwo256::hash(b"key"),
+            KeyProof::<Test> {
+                proof: CompactProof {
+                    encoded_nodes: vec![vec![0]],
+                },
+                challenge_count: Default::default(),
+            },
+        );
+
+        // Mock a proof.
+        let proof = Proof::<Test> {
+            forest_proof: CompactProof {
+                encoded_nodes: vec![vec![0]],
+            },
+            key_proofs,
+        };
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: Default::default(),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Hold some of the Provider's balance so it simulates it having a stake.
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            user_balance / 100
+        ));
+
+        // Set Provider's root to be an arbitrary value, different than the default root,
+        // to simulate that it is actually providing a service.
+        let root = BlakeTwo256::hash(b"1234");
+        pallet_storage_providers::BackupStorageProviders::<Test>::mutate(
+            &provider_id,
+            |provider| {
+                provider.as_mut().expect("Provider should exist").root = root;
+            },
+        );
+
+        // Set Provider's last submitted proof block.
+        let proof_record = ProofSubmissionRecord {
+            last_tick_proven: 1,
+            next_tick_to_submit_proof_for: 10,
+        };
+        ProviderToProofSubmissionRecord::<Test>::insert(&provider_id, proof_record);
+
+        // Advance more than `ChallengeHistoryLength` blocks.
+        let challenge_history_length: u64 = ChallengeHistoryLengthFor::<Test>::get();
+        run_to_block(challenge_history_length * 2);
+
+        // Dispatch challenge extrinsic.
+        let _ = ProofsDealer::submit_proof(user, proof, None);
+    });
+}
+
+#[test]
+#[should_panic(
+    expected = "internal error: entered unreachable code: Seed for challenges tick not found, when checked it should be within history."
+)]
+fn submit_proof_seed_not_found_fail() {
     new_test_ext().execute_with(|| {
         // Go past genesis block so events get deposited.
         run_to_block(1);
@@ -3885,8 +4222,12 @@ fn new_challenges_round_provider_marked_as_slashable() {
         assert!(SlashableProviders::<Test>::contains_key(&provider_id));
         assert_eq!(
             SlashableProviders::<Test>::get(&provider_id),
-            Some(<Test as crate::Config>::RandomChallengesPerBlock::get())
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions:
+                    <Test as crate::Config>::RandomChallengesPerBlock::get(),
+            })
         );
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&provider_id), 1);
 
         // Check that the last time this Provider submitted a proof for is not updated.
         // But the next tick to submit a proof for is updated.
@@ -3914,6 +4255,221 @@ fn new_challenges_round_provider_marked_as_slashable() {
     });
 }
 
+#[test]
+fn new_challenges_round_provider_warned_before_marked_as_slashable() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        // Allow Providers to miss one deadline per warning window without being slashable.
+        MissedDeadlinesAllowance::set(&1);
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: BlakeTwo256::hash(b"1234"),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Add balance to that Provider and hold some so it has a stake.
+        let provider_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            provider_balance
+        ));
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            provider_balance / 100
+        ));
+
+        // Set Provider's proof record and deadline, as if it submitted its last proof in this tick.
+        let current_tick = ChallengesTicker::<Test>::get();
+        let providers_stake =
+            <ProvidersPalletFor<Test> as ReadChallengeableProvidersInterface>::get_stake(
+                provider_id,
+            )
+            .unwrap();
+        let challenge_period = crate::Pallet::<Test>::stake_to_challenge_period(providers_stake);
+        let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+        let prev_deadline = current_tick + challenge_period + challenge_ticks_tolerance;
+        ProviderToProofSubmissionRecord::<Test>::insert(
+            &provider_id,
+            ProofSubmissionRecord {
+                last_tick_proven: current_tick,
+                next_tick_to_submit_proof_for: current_tick + challenge_period,
+            },
+        );
+        TickToProvidersDeadlines::<Test>::insert(prev_deadline, provider_id, ());
+
+        // Advance to the deadline block for this Provider.
+        run_to_block(prev_deadline);
+
+        // The Provider is warned instead of marked as slashable.
+        let window_end =
+            prev_deadline + <Test as crate::Config>::MissedDeadlineWarningWindow::get();
+        System::assert_has_event(
+            Event::ProviderWarned {
+                provider: provider_id,
+                next_challenge_deadline: prev_deadline + challenge_period,
+                missed_deadlines: 1,
+                window_end,
+            }
+            .into(),
+        );
+        assert!(!SlashableProviders::<Test>::contains_key(&provider_id));
+        assert_eq!(
+            WarnedProviders::<Test>::get(&provider_id),
+            Some(ProviderWarningRecord {
+                window_start: prev_deadline,
+                missed_deadlines: 1,
+            })
+        );
+
+        // The Provider's deadline is still pushed forward.
+        assert_eq!(
+            TickToProvidersDeadlines::<Test>::get(prev_deadline, provider_id),
+            None
+        );
+        let new_deadline = prev_deadline + challenge_period;
+        assert_eq!(
+            TickToProvidersDeadlines::<Test>::get(new_deadline, provider_id),
+            Some(())
+        );
+        assert!(new_deadline < window_end);
+
+        // Missing the next deadline within the warning window marks the Provider as slashable.
+        run_to_block(new_deadline);
+
+        System::assert_has_event(
+            Event::SlashableProvider {
+                provider: provider_id,
+                next_challenge_deadline: new_deadline + challenge_period,
+            }
+            .into(),
+        );
+        assert_eq!(
+            SlashableProviders::<Test>::get(&provider_id),
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions:
+                    <Test as crate::Config>::RandomChallengesPerBlock::get(),
+            })
+        );
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&provider_id), 1);
+    });
+}
+
+#[test]
+fn new_challenges_round_provider_warned_again_after_warning_window() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        // Allow Providers to miss one deadline per warning window without being slashable.
+        MissedDeadlinesAllowance::set(&1);
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: BlakeTwo256::hash(b"1234"),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Add balance to that Provider and hold some so it has a stake.
+        let provider_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            provider_balance
+        ));
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            provider_balance / 100
+        ));
+
+        // Set Provider's proof record and deadline, as if it submitted its last proof in this tick.
+        let current_tick = ChallengesTicker::<Test>::get();
+        let providers_stake =
+            <ProvidersPalletFor<Test> as ReadChallengeableProvidersInterface>::get_stake(
+                provider_id,
+            )
+            .unwrap();
+        let challenge_period = crate::Pallet::<Test>::stake_to_challenge_period(providers_stake);
+        let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+        let prev_deadline = current_tick + challenge_period + challenge_ticks_tolerance;
+        ProviderToProofSubmissionRecord::<Test>::insert(
+            &provider_id,
+            ProofSubmissionRecord {
+                last_tick_proven: current_tick,
+                next_tick_to_submit_proof_for: current_tick + challenge_period,
+            },
+        );
+        TickToProvidersDeadlines::<Test>::insert(prev_deadline, provider_id, ());
+
+        // Mock that the Provider used up its allowance in a warning window that is already over.
+        let window = <Test as crate::Config>::MissedDeadlineWarningWindow::get();
+        WarnedProviders::<Test>::insert(
+            &provider_id,
+            ProviderWarningRecord {
+                window_start: prev_deadline.saturating_sub(window),
+                missed_deadlines: 1,
+            },
+        );
+
+        // Advance to the deadline block for this Provider.
+        run_to_block(prev_deadline);
+
+        // A new warning window starts, so the Provider is warned again.
+        System::assert_has_event(
+            Event::ProviderWarned {
+                provider: provider_id,
+                next_challenge_deadline: prev_deadline + challenge_period,
+                missed_deadlines: 1,
+                window_end: prev_deadline + window,
+            }
+            .into(),
+        );
+        assert!(!SlashableProviders::<Test>::contains_key(&provider_id));
+        assert_eq!(
+            WarnedProviders::<Test>::get(&provider_id),
+            Some(ProviderWarningRecord {
+                window_start: prev_deadline,
+                missed_deadlines: 1,
+            })
+        );
+    });
+}
+
 #[test]
 fn multiple_new_challenges_round_provider_accrued_many_failed_proof_submissions() {
     new_test_ext().execute_with(|| {
@@ -4034,8 +4590,11 @@ fn multiple_new_challenges_round_provider_accrued_many_failed_proof_submissions(
 
         assert_eq!(
             SlashableProviders::<Test>::get(&provider_id),
-            Some(missed_proof_submissions)
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions: missed_proof_submissions,
+            })
         );
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&provider_id), 1);
 
         // New challenges round
         let current_tick = ChallengesTicker::<Test>::get();
@@ -4060,8 +4619,12 @@ fn multiple_new_challenges_round_provider_accrued_many_failed_proof_submissions(
         assert!(SlashableProviders::<Test>::contains_key(&provider_id));
         assert_eq!(
             SlashableProviders::<Test>::get(&provider_id),
-            Some(random_challenges_per_block.saturating_add(missed_proof_submissions + 2))
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions: random_challenges_per_block
+                    .saturating_add(missed_proof_submissions + 2),
+            })
         );
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&provider_id), 2);
     });
 }
 
@@ -4266,8 +4829,12 @@ fn new_challenges_round_bad_provider_marked_as_slashable_but_good_no() {
         assert!(SlashableProviders::<Test>::contains_key(&bob_provider_id));
         assert_eq!(
             SlashableProviders::<Test>::get(&bob_provider_id),
-            Some(<Test as crate::Config>::RandomChallengesPerBlock::get())
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions:
+                    <Test as crate::Config>::RandomChallengesPerBlock::get(),
+            })
         );
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&bob_provider_id), 1);
 
         // Check the new last tick proven for Alice and Bob.
         // For Alice it should have been updated since she did submit a proof.
@@ -4926,8 +5493,12 @@ fn challenges_ticker_provider_not_slashed_if_network_spammed() {
         assert!(SlashableProviders::<Test>::contains_key(&provider_id));
         assert_eq!(
             SlashableProviders::<Test>::get(&provider_id),
-            Some(<Test as crate::Config>::RandomChallengesPerBlock::get())
+            Some(SlashableProviderRecord {
+                accrued_failed_proof_submissions:
+                    <Test as crate::Config>::RandomChallengesPerBlock::get(),
+            })
         );
+        assert_eq!(ConsecutiveMissedDeadlines::<Test>::get(&provider_id), 1);
 
         // Check that the Provider's last tick for a proof submission is still the same.
         let new_proof_record = ProviderToProofSubmissionRecord::<Test>::get(provider_id).unwrap();
@@ -5129,16 +5700,118 @@ fn slashable_providers_beyond_max_per_tick() {
 }
 
 #[test]
-fn stake_to_challenge_period_saturates_properly() {
+fn ticks_catch_up_backlog_grows_and_clears_with_slashable_providers_beyond_max_per_tick() {
     new_test_ext().execute_with(|| {
         // Go past genesis block so events get deposited.
         run_to_block(1);
 
-        let stake_to_challenge_period = StakeToChallengePeriodFor::<Test>::get();
-        let min_challenge_period: BlockNumberFor<Test> = MinChallengePeriodFor::<Test>::get();
-
-        // A provider with stake equal to `StakeToChallengePeriod` should have a challenge period equal to `MinChallengePeriod`.
-        assert_eq!(
+        // Add more than `MaxSlashableProvidersPerTick` Providers to the next tick.
+        let current_tick = ChallengesTicker::<Test>::get();
+        let max_slashable_providers_per_tick: u32 = MaxSlashableProvidersPerTickFor::<Test>::get();
+        for i in 0..max_slashable_providers_per_tick + 1 {
+            let provider = BlakeTwo256::hash(format!("provider_{}", i).as_bytes());
+            TickToProvidersDeadlines::<Test>::insert(current_tick + 1, provider, ());
+            ProviderToProofSubmissionRecord::<Test>::insert(
+                provider,
+                ProofSubmissionRecord {
+                    last_tick_proven: current_tick,
+                    next_tick_to_submit_proof_for: current_tick + 1,
+                },
+            );
+        }
+        assert_eq!(crate::Pallet::<Test>::get_ticks_catch_up_backlog(), 0);
+
+        // Run to the next tick. Not all Providers fit, so that tick is left to be checked.
+        run_to_block(System::block_number() + 1);
+        assert_eq!(crate::Pallet::<Test>::get_ticks_catch_up_backlog(), 1);
+        System::assert_has_event(
+            Event::TicksCatchUpBacklogGrew {
+                backlog: 1,
+                next_tick_to_check: current_tick + 1,
+            }
+            .into(),
+        );
+
+        // Run to the next tick. The last Provider is marked as slashable and the checks catch up.
+        run_to_block(System::block_number() + 1);
+        assert_eq!(crate::Pallet::<Test>::get_ticks_catch_up_backlog(), 0);
+        System::assert_has_event(
+            Event::TicksCatchUpBacklogCleared {
+                challenges_ticker: current_tick + 2,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn ticks_catch_up_is_bounded_per_block() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(30);
+
+        // Simulate the deadline checks having fallen behind by more than a block's worth of catch-up.
+        let current_tick = ChallengesTicker::<Test>::get();
+        let max_catch_up_ticks: u32 = MaxCatchUpTicksPerBlockFor::<Test>::get();
+        let lag = 2 * max_catch_up_ticks as u64 + 4;
+        TickToCheckForSlashableProviders::<Test>::set(current_tick - lag);
+
+        // Run to the next tick. Only `MaxCatchUpTicksPerBlock` ticks are checked.
+        run_to_block(System::block_number() + 1);
+        let next_tick_to_check = current_tick - lag + max_catch_up_ticks as u64;
+        assert_eq!(
+            TickToCheckForSlashableProviders::<Test>::get(),
+            next_tick_to_check
+        );
+        let backlog = current_tick + 2 - next_tick_to_check;
+        assert_eq!(TicksCatchUpBacklog::<Test>::get(), backlog);
+        System::assert_has_event(
+            Event::TicksCatchUpBacklogGrew {
+                backlog,
+                next_tick_to_check,
+            }
+            .into(),
+        );
+
+        // Run to the next tick. The backlog shrinks, but it is not cleared yet.
+        run_to_block(System::block_number() + 1);
+        assert_eq!(
+            TickToCheckForSlashableProviders::<Test>::get(),
+            next_tick_to_check + max_catch_up_ticks as u64
+        );
+        assert_eq!(
+            TicksCatchUpBacklog::<Test>::get(),
+            backlog + 1 - max_catch_up_ticks as u64
+        );
+
+        // Run to the next tick. The checks finally catch up with the ticker.
+        run_to_block(System::block_number() + 1);
+        let current_tick = ChallengesTicker::<Test>::get();
+        assert_eq!(
+            TickToCheckForSlashableProviders::<Test>::get(),
+            current_tick + 1
+        );
+        assert_eq!(TicksCatchUpBacklog::<Test>::get(), 0);
+        System::assert_has_event(
+            Event::TicksCatchUpBacklogCleared {
+                challenges_ticker: current_tick,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn stake_to_challenge_period_saturates_properly() {
+    new_test_ext().execute_with(|| {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        let stake_to_challenge_period = StakeToChallengePeriodFor::<Test>::get();
+        let min_challenge_period: BlockNumberFor<Test> = MinChallengePeriodFor::<Test>::get();
+
+        // A provider with stake equal to `StakeToChallengePeriod` should have a challenge period equal to `MinChallengePeriod`.
+        assert_eq!(
             crate::Pallet::<Test>::stake_to_challenge_period(stake_to_challenge_period),
             min_challenge_period
         );
@@ -5165,6 +5838,1220 @@ fn stake_to_challenge_period_saturates_properly() {
     });
 }
 
+mod submit_proof_unsigned_tests {
+    use frame_support::pallet_prelude::{
+        InvalidTransaction, TransactionSource, TransactionValidityError, ValidateUnsigned,
+    };
+    use sp_runtime::testing::TestSignature;
+
+    use super::*;
+
+    /// Registers account `1` as a Provider and advances to a tick at which its proof is due,
+    /// returning the Provider ID and a proof that passes verification.
+    fn setup_provider_with_proof_due() -> (ProviderIdFor<Test>, Proof<Test>) {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        let user_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            user_balance
+        ));
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: BlakeTwo256::hash(b"1234"),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Hold some of the Provider's balance so it simulates it having a stake.
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            user_balance / 100
+        ));
+
+        // Set Provider's proof record and deadline.
+        let current_tick = ChallengesTicker::<Test>::get();
+        let providers_stake =
+            <ProvidersPalletFor<Test> as ReadChallengeableProvidersInterface>::get_stake(
+                provider_id,
+            )
+            .unwrap();
+        let challenge_period = crate::Pallet::<Test>::stake_to_challenge_period(providers_stake);
+        let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+        ProviderToProofSubmissionRecord::<Test>::insert(
+            &provider_id,
+            ProofSubmissionRecord {
+                last_tick_proven: current_tick,
+                next_tick_to_submit_proof_for: current_tick + challenge_period,
+            },
+        );
+        TickToProvidersDeadlines::<Test>::insert(
+            current_tick + challenge_period + challenge_ticks_tolerance,
+            provider_id,
+            (),
+        );
+
+        // Advance to the challenge tick, and less than `ChallengeTicksTolerance` blocks past it.
+        let challenge_block = System::block_number() + challenge_period;
+        run_to_block(challenge_block + challenge_ticks_tolerance - 1);
+
+        // Mock a key proof for each challenge, so that the proof passes verification.
+        let seed = TickToChallengesSeed::<Test>::get(challenge_block).unwrap();
+        let challenges = crate::Pallet::<Test>::generate_challenges_from_seed(
+            seed,
+            &provider_id,
+            RandomChallengesPerBlockFor::<Test>::get(),
+        );
+        let key_proofs = challenges
+            .into_iter()
+            .map(|challenge| {
+                (
+                    challenge,
+                    KeyProof::<Test> {
+                        proof: CompactProof {
+                            encoded_nodes: vec![vec![0]],
+                        },
+                        challenge_count: Default::default(),
+                    },
+                )
+            })
+            .collect();
+
+        let proof = Proof::<Test> {
+            forest_proof: CompactProof {
+                encoded_nodes: vec![vec![0]],
+            },
+            key_proofs,
+        };
+
+        (provider_id, proof)
+    }
+
+    /// Signs `proof` for the tick the Provider currently has to submit a proof for, as `signer`.
+    fn sign_proof(
+        signer: u64,
+        provider_id: ProviderIdFor<Test>,
+        proof: &Proof<Test>,
+    ) -> TestSignature {
+        let challenges_tick = ProviderToProofSubmissionRecord::<Test>::get(provider_id)
+            .unwrap()
+            .next_tick_to_submit_proof_for;
+
+        TestSignature(
+            signer,
+            crate::Pallet::<Test>::unsigned_proof_payload(&provider_id, challenges_tick, proof),
+        )
+    }
+
+    fn validate(
+        provider_id: ProviderIdFor<Test>,
+        proof: &Proof<Test>,
+        signature: TestSignature,
+    ) -> Result<(), TransactionValidityError> {
+        crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::submit_proof_unsigned {
+                proof: proof.clone(),
+                provider: provider_id,
+                signature,
+            },
+        )
+        .map(|_| ())
+    }
+
+    /// Runs the checks made right before applying the unsigned transaction in a block.
+    fn pre_dispatch(
+        provider_id: ProviderIdFor<Test>,
+        proof: &Proof<Test>,
+        signature: TestSignature,
+    ) -> Result<(), TransactionValidityError> {
+        crate::Pallet::<Test>::pre_dispatch(&crate::Call::submit_proof_unsigned {
+            proof: proof.clone(),
+            provider: provider_id,
+            signature,
+        })
+    }
+
+    #[test]
+    fn submit_proof_unsigned_success() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, proof) = setup_provider_with_proof_due();
+            let signature = sign_proof(1, provider_id, &proof);
+            let challenges_tick = ProviderToProofSubmissionRecord::<Test>::get(provider_id)
+                .unwrap()
+                .next_tick_to_submit_proof_for;
+
+            // The transaction is valid, and provides a tag for the Provider and challenges tick.
+            let validity = crate::Pallet::<Test>::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::submit_proof_unsigned {
+                    proof: proof.clone(),
+                    provider: provider_id,
+                    signature: signature.clone(),
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                validity.provides,
+                vec![("ProofsDealerUnsignedProof", (provider_id, challenges_tick)).encode()]
+            );
+
+            assert_ok!(pre_dispatch(provider_id, &proof, signature.clone()));
+            assert_eq!(
+                UnsignedProofAttempts::<Test>::get(provider_id),
+                Some(challenges_tick)
+            );
+            assert_ok!(ProofsDealer::submit_proof_unsigned(
+                RuntimeOrigin::none(),
+                proof.clone(),
+                provider_id,
+                signature
+            ));
+
+            System::assert_last_event(
+                Event::ProofAccepted {
+                    provider_id,
+                    proof,
+                    last_tick_proven: challenges_tick,
+                }
+                .into(),
+            );
+            assert_eq!(
+                ProviderToProofSubmissionRecord::<Test>::get(provider_id)
+                    .unwrap()
+                    .last_tick_proven,
+                challenges_tick
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_replay_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, proof) = setup_provider_with_proof_due();
+            let signature = sign_proof(1, provider_id, &proof);
+
+            assert_ok!(ProofsDealer::submit_proof_unsigned(
+                RuntimeOrigin::none(),
+                proof.clone(),
+                provider_id,
+                signature.clone()
+            ));
+
+            // The signature was for the tick that was just proven, so it cannot be used again.
+            assert!(validate(provider_id, &proof, signature.clone()).is_err());
+            assert_noop!(
+                ProofsDealer::submit_proof_unsigned(
+                    RuntimeOrigin::none(),
+                    proof,
+                    provider_id,
+                    signature
+                ),
+                crate::Error::<Test>::InvalidProofSignature
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_failed_attempt_cannot_be_repeated_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, mut proof) = setup_provider_with_proof_due();
+            let challenges_tick = ProviderToProofSubmissionRecord::<Test>::get(provider_id)
+                .unwrap()
+                .next_tick_to_submit_proof_for;
+
+            // A proof signed by the owner of the Provider, but missing its key proofs.
+            proof.key_proofs.clear();
+            let signature = sign_proof(1, provider_id, &proof);
+
+            // The proof passes validation, since it is not verified until it is dispatched, where it
+            // fails.
+            assert_ok!(pre_dispatch(provider_id, &proof, signature.clone()));
+            assert!(ProofsDealer::submit_proof_unsigned(
+                RuntimeOrigin::none(),
+                proof.clone(),
+                provider_id,
+                signature.clone()
+            )
+            .is_err());
+
+            // The attempt is still recorded, so the proof cannot be included again for free.
+            assert_eq!(
+                UnsignedProofAttempts::<Test>::get(provider_id),
+                Some(challenges_tick)
+            );
+            assert_eq!(
+                validate(provider_id, &proof, signature.clone()),
+                Err(InvalidTransaction::Stale.into())
+            );
+            assert_eq!(
+                pre_dispatch(provider_id, &proof, signature),
+                Err(InvalidTransaction::Stale.into())
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_not_signed_by_owner_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, proof) = setup_provider_with_proof_due();
+            let signature = sign_proof(2, provider_id, &proof);
+
+            assert_eq!(
+                validate(provider_id, &proof, signature.clone()),
+                Err(InvalidTransaction::BadProof.into())
+            );
+            assert_noop!(
+                ProofsDealer::submit_proof_unsigned(
+                    RuntimeOrigin::none(),
+                    proof,
+                    provider_id,
+                    signature
+                ),
+                crate::Error::<Test>::InvalidProofSignature
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_not_a_provider_fail() {
+        new_test_ext().execute_with(|| {
+            let (_, proof) = setup_provider_with_proof_due();
+            let not_provider_id = BlakeTwo256::hash(b"not_provider_id");
+            let signature = TestSignature(1, Vec::new());
+
+            assert_eq!(
+                validate(not_provider_id, &proof, signature),
+                Err(InvalidTransaction::BadSigner.into())
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_before_challenges_tick_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, proof) = setup_provider_with_proof_due();
+
+            // Push the challenges tick of the Provider into the future.
+            ProviderToProofSubmissionRecord::<Test>::mutate(provider_id, |record| {
+                let record = record.as_mut().unwrap();
+                record.next_tick_to_submit_proof_for = ChallengesTicker::<Test>::get() + 1;
+            });
+            let signature = sign_proof(1, provider_id, &proof);
+
+            assert_eq!(
+                validate(provider_id, &proof, signature),
+                Err(InvalidTransaction::Future.into())
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_after_deadline_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, proof) = setup_provider_with_proof_due();
+
+            // Move the challenges tick of the Provider back so that its deadline has passed.
+            let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+            ProviderToProofSubmissionRecord::<Test>::mutate(provider_id, |record| {
+                let record = record.as_mut().unwrap();
+                record.next_tick_to_submit_proof_for =
+                    ChallengesTicker::<Test>::get() - challenge_ticks_tolerance;
+            });
+            let signature = sign_proof(1, provider_id, &proof);
+
+            assert_eq!(
+                validate(provider_id, &proof, signature),
+                Err(InvalidTransaction::Stale.into())
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_unsigned_signed_origin_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, proof) = setup_provider_with_proof_due();
+            let signature = sign_proof(1, provider_id, &proof);
+
+            assert_noop!(
+                ProofsDealer::submit_proof_unsigned(
+                    RuntimeOrigin::signed(1),
+                    proof,
+                    provider_id,
+                    signature
+                ),
+                DispatchError::BadOrigin
+            );
+        });
+    }
+}
+
+mod submit_proofs_tests {
+    use super::*;
+
+    /// The challenge period of the Provider in these tests, short enough compared to the
+    /// `ChallengeTicksTolerance` for it to have several proofs due at once.
+    const CHALLENGE_PERIOD: u64 = 4;
+
+    /// Registers account `1` as a Provider with a challenge period of [`CHALLENGE_PERIOD`], and
+    /// advances to a tick at which two of its proofs are due, returning the Provider ID and the
+    /// ticks of those two proofs.
+    fn setup_provider_with_two_proofs_due() -> (ProviderIdFor<Test>, u64, u64) {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        let user_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            user_balance
+        ));
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: BlakeTwo256::hash(b"1234"),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Hold some of the Provider's balance so it simulates it having a stake.
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            user_balance / 100
+        ));
+
+        assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+            RuntimeOrigin::root(),
+            provider_id,
+            Some(CHALLENGE_PERIOD)
+        ));
+        assert_ok!(ProofsDealer::force_initialise_challenge_cycle(
+            RuntimeOrigin::root(),
+            provider_id
+        ));
+
+        // Advance past the next two ticks the Provider has to submit a proof for, but not past the
+        // deadline of the first one.
+        let first_tick = ChallengesTicker::<Test>::get() + CHALLENGE_PERIOD;
+        let second_tick = first_tick + CHALLENGE_PERIOD;
+        run_to_block(System::block_number() + 2 * CHALLENGE_PERIOD + 1);
+        assert!(ChallengesTicker::<Test>::get() > second_tick);
+        assert!(
+            ChallengesTicker::<Test>::get()
+                < first_tick + ChallengeTicksToleranceFor::<Test>::get()
+        );
+
+        (provider_id, first_tick, second_tick)
+    }
+
+    /// Mocks a proof that passes verification for the challenges of `tick`.
+    fn proof_for_tick(provider_id: ProviderIdFor<Test>, tick: u64) -> Proof<Test> {
+        let seed = TickToChallengesSeed::<Test>::get(tick).unwrap();
+        let challenges = crate::Pallet::<Test>::generate_challenges_from_seed(
+            seed,
+            &provider_id,
+            RandomChallengesPerBlockFor::<Test>::get(),
+        );
+        let key_proofs = challenges
+            .into_iter()
+            .map(|challenge| {
+                (
+                    challenge,
+                    KeyProof::<Test> {
+                        proof: CompactProof {
+                            encoded_nodes: vec![vec![0]],
+                        },
+                        challenge_count: Default::default(),
+                    },
+                )
+            })
+            .collect();
+
+        Proof::<Test> {
+            forest_proof: CompactProof {
+                encoded_nodes: vec![vec![0]],
+            },
+            key_proofs,
+        }
+    }
+
+    #[test]
+    fn submit_proofs_success() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, first_tick, second_tick) = setup_provider_with_two_proofs_due();
+            let first_proof = proof_for_tick(provider_id, first_tick);
+            let second_proof = proof_for_tick(provider_id, second_tick);
+
+            assert_ok!(ProofsDealer::submit_proofs(
+                RuntimeOrigin::signed(1),
+                BoundedVec::try_from(vec![
+                    (first_tick, first_proof.clone()),
+                    (second_tick, second_proof.clone())
+                ])
+                .unwrap(),
+                None
+            ));
+
+            // Both proofs were accepted, in order.
+            System::assert_has_event(
+                Event::ProofAccepted {
+                    provider_id,
+                    proof: first_proof,
+                    last_tick_proven: first_tick,
+                }
+                .into(),
+            );
+            System::assert_last_event(
+                Event::ProofAccepted {
+                    provider_id,
+                    proof: second_proof,
+                    last_tick_proven: second_tick,
+                }
+                .into(),
+            );
+
+            // The record skips straight to the tick after the last proof.
+            assert_eq!(
+                ProviderToProofSubmissionRecord::<Test>::get(provider_id),
+                Some(ProofSubmissionRecord {
+                    last_tick_proven: second_tick,
+                    next_tick_to_submit_proof_for: second_tick + CHALLENGE_PERIOD,
+                })
+            );
+
+            // The Provider's deadline moved from the one of the first proof to the one after the
+            // last proof, without going through the ones in between.
+            let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+            assert_eq!(
+                TickToProvidersDeadlines::<Test>::get(
+                    first_tick + challenge_ticks_tolerance,
+                    provider_id
+                ),
+                None
+            );
+            assert_eq!(
+                TickToProvidersDeadlines::<Test>::get(
+                    second_tick + challenge_ticks_tolerance,
+                    provider_id
+                ),
+                None
+            );
+            assert_eq!(
+                TickToProvidersDeadlines::<Test>::get(
+                    second_tick + CHALLENGE_PERIOD + challenge_ticks_tolerance,
+                    provider_id
+                ),
+                Some(())
+            );
+
+            // The Provider is a valid proof submitter for the current tick.
+            assert!(
+                ValidProofSubmittersLastTicks::<Test>::get(ChallengesTicker::<Test>::get())
+                    .unwrap()
+                    .contains(&provider_id)
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proofs_partial_failure_accepts_none() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, first_tick, second_tick) = setup_provider_with_two_proofs_due();
+            let first_proof = proof_for_tick(provider_id, first_tick);
+
+            // The second proof responds to the challenges of the first tick, so it fails to verify
+            // after the first proof was verified.
+            assert_noop!(
+                ProofsDealer::submit_proofs(
+                    RuntimeOrigin::signed(1),
+                    BoundedVec::try_from(vec![
+                        (first_tick, first_proof.clone()),
+                        (second_tick, first_proof.clone())
+                    ])
+                    .unwrap(),
+                    None
+                ),
+                crate::Error::<Test>::KeyProofNotFound
+            );
+
+            // The valid proofs can still be submitted on their own afterwards.
+            assert_ok!(ProofsDealer::submit_proofs(
+                RuntimeOrigin::signed(1),
+                BoundedVec::try_from(vec![(first_tick, first_proof)]).unwrap(),
+                None
+            ));
+            assert_eq!(
+                ProviderToProofSubmissionRecord::<Test>::get(provider_id),
+                Some(ProofSubmissionRecord {
+                    last_tick_proven: first_tick,
+                    next_tick_to_submit_proof_for: second_tick,
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proofs_unexpected_tick_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, first_tick, second_tick) = setup_provider_with_two_proofs_due();
+            let first_proof = proof_for_tick(provider_id, first_tick);
+            let second_proof = proof_for_tick(provider_id, second_tick);
+
+            // Proofs can't skip a tick the Provider has to submit a proof for...
+            assert_noop!(
+                ProofsDealer::submit_proofs(
+                    RuntimeOrigin::signed(1),
+                    BoundedVec::try_from(vec![(second_tick, second_proof.clone())]).unwrap(),
+                    None
+                ),
+                crate::Error::<Test>::UnexpectedChallengesTick
+            );
+
+            // ...nor be out of order...
+            assert_noop!(
+                ProofsDealer::submit_proofs(
+                    RuntimeOrigin::signed(1),
+                    BoundedVec::try_from(vec![
+                        (second_tick, second_proof),
+                        (first_tick, first_proof.clone())
+                    ])
+                    .unwrap(),
+                    None
+                ),
+                crate::Error::<Test>::UnexpectedChallengesTick
+            );
+
+            // ...nor prove the same tick twice.
+            assert_noop!(
+                ProofsDealer::submit_proofs(
+                    RuntimeOrigin::signed(1),
+                    BoundedVec::try_from(vec![
+                        (first_tick, first_proof.clone()),
+                        (first_tick, first_proof)
+                    ])
+                    .unwrap(),
+                    None
+                ),
+                crate::Error::<Test>::UnexpectedChallengesTick
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proofs_empty_batch_fail() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, _, _) = setup_provider_with_two_proofs_due();
+
+            assert_noop!(
+                ProofsDealer::submit_proofs(
+                    RuntimeOrigin::signed(1),
+                    BoundedVec::new(),
+                    Some(provider_id)
+                ),
+                crate::Error::<Test>::EmptyProofBatch
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proofs_weight_scales_with_each_proof() {
+        new_test_ext().execute_with(|| {
+            let (provider_id, first_tick, second_tick) = setup_provider_with_two_proofs_due();
+            let first_proof = proof_for_tick(provider_id, first_tick);
+            let second_proof = proof_for_tick(provider_id, second_tick);
+
+            assert_eq!(
+                crate::Pallet::<Test>::submit_proofs_weight(&[
+                    (first_tick, first_proof.clone()),
+                    (second_tick, second_proof.clone())
+                ]),
+                crate::Pallet::<Test>::submit_proof_weight(&first_proof)
+                    + crate::Pallet::<Test>::submit_proof_weight(&second_proof)
+            );
+        });
+    }
+}
+
+mod challenge_period_override_tests {
+    use super::*;
+
+    /// Registers account `1` as a Provider with some stake, returning its Provider ID.
+    fn register_provider() -> ProviderIdFor<Test> {
+        // Go past genesis block so events get deposited.
+        run_to_block(1);
+
+        let user_balance = 1_000_000_000_000_000;
+        assert_ok!(<Test as crate::Config>::NativeBalance::mint_into(
+            &1,
+            user_balance
+        ));
+
+        // Register user as a Provider in Providers pallet.
+        let provider_id = BlakeTwo256::hash(b"provider_id");
+        pallet_storage_providers::AccountIdToBackupStorageProviderId::<Test>::insert(
+            &1,
+            provider_id,
+        );
+        pallet_storage_providers::BackupStorageProviders::<Test>::insert(
+            &provider_id,
+            pallet_storage_providers::types::BackupStorageProvider {
+                capacity: Default::default(),
+                capacity_used: Default::default(),
+                multiaddresses: Default::default(),
+                root: BlakeTwo256::hash(b"1234"),
+                last_capacity_change: Default::default(),
+                owner_account: 1u64,
+                payment_account: Default::default(),
+                reputation_weight:
+                    <Test as pallet_storage_providers::Config>::StartingReputationWeight::get(),
+                sign_up_block: Default::default(),
+            },
+        );
+
+        // Hold some of the Provider's balance so it simulates it having a stake.
+        assert_ok!(<Test as crate::Config>::NativeBalance::hold(
+            &HoldReason::StorageProviderDeposit.into(),
+            &1,
+            user_balance / 100
+        ));
+
+        provider_id
+    }
+
+    fn stake_challenge_period(provider_id: ProviderIdFor<Test>) -> u64 {
+        let stake = <ProvidersPalletFor<Test> as ReadChallengeableProvidersInterface>::get_stake(
+            provider_id,
+        )
+        .unwrap();
+        crate::Pallet::<Test>::stake_to_challenge_period(stake)
+    }
+
+    #[test]
+    fn set_provider_challenge_period_override_success() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let period = stake_challenge_period(provider_id) + 5;
+
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                Some(period)
+            ));
+
+            // Check that the override was stored and the event emitted.
+            assert_eq!(
+                ChallengePeriodOverrides::<Test>::get(provider_id),
+                Some(period)
+            );
+            System::assert_last_event(
+                Event::ChallengePeriodOverrideSet {
+                    provider_id,
+                    period: Some(period),
+                }
+                .into(),
+            );
+
+            // The runtime API used by the proof scheduler returns the override.
+            assert_eq!(
+                crate::Pallet::<Test>::get_challenge_period(&provider_id),
+                Ok(period)
+            );
+        });
+    }
+
+    #[test]
+    fn challenge_period_override_is_used_for_next_challenge_tick() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let period = stake_challenge_period(provider_id) + 5;
+
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                Some(period)
+            ));
+            assert_ok!(ProofsDealer::force_initialise_challenge_cycle(
+                RuntimeOrigin::root(),
+                provider_id
+            ));
+
+            // The next tick to submit a proof for is one overridden period from now.
+            let current_tick = ChallengesTicker::<Test>::get();
+            assert_eq!(
+                ProviderToProofSubmissionRecord::<Test>::get(provider_id)
+                    .unwrap()
+                    .next_tick_to_submit_proof_for,
+                current_tick + period
+            );
+            let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+            assert!(TickToProvidersDeadlines::<Test>::contains_key(
+                current_tick + period + challenge_ticks_tolerance,
+                provider_id
+            ));
+        });
+    }
+
+    #[test]
+    fn removing_challenge_period_override_restores_stake_period() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let stake_period = stake_challenge_period(provider_id);
+
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                Some(stake_period + 5)
+            ));
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                None
+            ));
+
+            assert_eq!(ChallengePeriodOverrides::<Test>::get(provider_id), None);
+            System::assert_last_event(
+                Event::ChallengePeriodOverrideSet {
+                    provider_id,
+                    period: None,
+                }
+                .into(),
+            );
+            assert_eq!(
+                crate::Pallet::<Test>::get_challenge_period(&provider_id),
+                Ok(stake_period)
+            );
+        });
+    }
+
+    #[test]
+    fn set_provider_challenge_period_override_fails_if_too_short() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let min_challenge_period: u64 = MinChallengePeriodFor::<Test>::get();
+
+            assert_noop!(
+                ProofsDealer::set_provider_challenge_period_override(
+                    RuntimeOrigin::root(),
+                    provider_id,
+                    Some(min_challenge_period - 1)
+                ),
+                crate::Error::<Test>::ChallengePeriodOverrideTooShort
+            );
+        });
+    }
+
+    #[test]
+    fn set_provider_challenge_period_override_fails_if_too_long() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let checkpoint_challenge_period: u64 = CheckpointChallengePeriodFor::<Test>::get();
+            let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+
+            // The Provider would not have to submit a proof before the next checkpoint challenge round.
+            assert_noop!(
+                ProofsDealer::set_provider_challenge_period_override(
+                    RuntimeOrigin::root(),
+                    provider_id,
+                    Some(checkpoint_challenge_period - challenge_ticks_tolerance)
+                ),
+                crate::Error::<Test>::ChallengePeriodOverrideTooLong
+            );
+
+            // One tick less is fine.
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                Some(checkpoint_challenge_period - challenge_ticks_tolerance - 1)
+            ));
+        });
+    }
+
+    #[test]
+    fn set_provider_challenge_period_override_fails_if_not_provider() {
+        new_test_ext().execute_with(|| {
+            let min_challenge_period: u64 = MinChallengePeriodFor::<Test>::get();
+
+            assert_noop!(
+                ProofsDealer::set_provider_challenge_period_override(
+                    RuntimeOrigin::root(),
+                    BlakeTwo256::hash(b"not_a_provider"),
+                    Some(min_challenge_period)
+                ),
+                crate::Error::<Test>::NotProvider
+            );
+        });
+    }
+
+    #[test]
+    fn set_provider_challenge_period_override_fails_if_not_root() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let min_challenge_period: u64 = MinChallengePeriodFor::<Test>::get();
+
+            assert_noop!(
+                ProofsDealer::set_provider_challenge_period_override(
+                    RuntimeOrigin::signed(1),
+                    provider_id,
+                    Some(min_challenge_period)
+                ),
+                DispatchError::BadOrigin
+            );
+        });
+    }
+
+    #[test]
+    fn stopping_challenge_cycle_removes_challenge_period_override() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let min_challenge_period: u64 = MinChallengePeriodFor::<Test>::get();
+
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                Some(min_challenge_period)
+            ));
+            assert_ok!(ProofsDealer::force_initialise_challenge_cycle(
+                RuntimeOrigin::root(),
+                provider_id
+            ));
+
+            assert_ok!(<ProofsDealer as ProofsDealerInterface>::stop_challenge_cycle(&provider_id));
+
+            assert_eq!(ChallengePeriodOverrides::<Test>::get(provider_id), None);
+        });
+    }
+
+    #[test]
+    fn provider_challenge_schedule_works() {
+        new_test_ext().execute_with(|| {
+            let provider_id = register_provider();
+            let period = stake_challenge_period(provider_id) + 5;
+
+            assert_ok!(ProofsDealer::set_provider_challenge_period_override(
+                RuntimeOrigin::root(),
+                provider_id,
+                Some(period)
+            ));
+            assert_ok!(ProofsDealer::force_initialise_challenge_cycle(
+                RuntimeOrigin::root(),
+                provider_id
+            ));
+            let initial_tick = ChallengesTicker::<Test>::get();
+            let next_tick_to_submit_proof_for = initial_tick + period;
+            let challenge_ticks_tolerance: u64 = ChallengeTicksToleranceFor::<Test>::get();
+
+            // No seeds are returned until the tick to submit a proof for is reached.
+            let schedule = crate::Pallet::<Test>::provider_challenge_schedule(&provider_id)
+                .expect("Provider is initialised");
+            assert_eq!(
+                schedule,
+                ProviderChallengeSchedule {
+                    last_tick_proven: initial_tick,
+                    next_tick_to_submit_proof_for,
+                    next_deadline_tick: next_tick_to_submit_proof_for + challenge_ticks_tolerance,
+                    challenge_period: period,
+                    current_tick: initial_tick,
+                    last_checkpoint_tick: LastCheckpointTick::<Test>::get(),
+                    seeds: vec![],
+                }
+            );
+
+            // Once it is reached, the seed of that tick is returned.
+            run_to_block(System::block_number() + period);
+            assert_eq!(
+                ChallengesTicker::<Test>::get(),
+                next_tick_to_submit_proof_for
+            );
+            let schedule = crate::Pallet::<Test>::provider_challenge_schedule(&provider_id)
+                .expect("Provider is initialised");
+            assert_eq!(schedule.current_tick, next_tick_to_submit_proof_for);
+            assert_eq!(
+                schedule.seeds,
+                vec![(
+                    next_tick_to_submit_proof_for,
+                    TickToChallengesSeed::<Test>::get(next_tick_to_submit_proof_for).unwrap()
+                )]
+            );
+        });
+    }
+
+    #[test]
+    fn provider_challenge_schedule_fails_if_not_initialised() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                crate::Pallet::<Test>::provider_challenge_schedule(&BlakeTwo256::hash(
+                    b"not_a_provider"
+                )),
+                Err(GetProviderChallengeScheduleError::ProviderNotRegistered)
+            );
+
+            let provider_id = register_provider();
+            assert_eq!(
+                crate::Pallet::<Test>::provider_challenge_schedule(&provider_id),
+                Err(GetProviderChallengeScheduleError::ProviderNotInitialised)
+            );
+        });
+    }
+}
+
+mod retention_policy_tests {
+    use super::*;
+
+    #[test]
+    fn set_retention_policy_success() {
+        new_test_ext().execute_with(|| {
+            // Go past genesis block so events get deposited.
+            run_to_block(1);
+
+            let policy = RetentionPolicy::<Test> {
+                challenge_seeds: 20,
+                valid_proof_submitters: 2,
+            };
+            assert_ok!(ProofsDealer::set_retention_policy(
+                RuntimeOrigin::root(),
+                Some(policy.clone())
+            ));
+
+            // Check that the policy was stored and is the one in effect.
+            assert_eq!(HistoryRetentionPolicy::<Test>::get(), Some(policy.clone()));
+            assert_eq!(crate::Pallet::<Test>::retention_policy(), policy.clone());
+            System::assert_last_event(Event::RetentionPolicySet { policy }.into());
+        });
+    }
+
+    #[test]
+    fn set_retention_policy_none_goes_back_to_constants() {
+        new_test_ext().execute_with(|| {
+            // Go past genesis block so events get deposited.
+            run_to_block(1);
+
+            assert_ok!(ProofsDealer::set_retention_policy(
+                RuntimeOrigin::root(),
+                Some(RetentionPolicy::<Test> {
+                    challenge_seeds: 20,
+                    valid_proof_submitters: 2,
+                })
+            ));
+            assert_ok!(ProofsDealer::set_retention_policy(
+                RuntimeOrigin::root(),
+                None
+            ));
+
+            // Check that the policy was removed and the constants are in effect again.
+            let constants_policy = RetentionPolicy::<Test> {
+                challenge_seeds: ChallengeHistoryLengthFor::<Test>::get(),
+                valid_proof_submitters: TargetTicksStorageOfSubmittersFor::<Test>::get(),
+            };
+            assert_eq!(HistoryRetentionPolicy::<Test>::get(), None);
+            assert_eq!(
+                crate::Pallet::<Test>::retention_policy(),
+                constants_policy.clone()
+            );
+            System::assert_last_event(
+                Event::RetentionPolicySet {
+                    policy: constants_policy,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn set_retention_policy_fails_if_too_short() {
+        new_test_ext().execute_with(|| {
+            // Challenge seeds have to be kept for longer than the tolerance to submit proofs.
+            assert_noop!(
+                ProofsDealer::set_retention_policy(
+                    RuntimeOrigin::root(),
+                    Some(RetentionPolicy::<Test> {
+                        challenge_seeds: ChallengeTicksToleranceFor::<Test>::get(),
+                        valid_proof_submitters: 2,
+                    })
+                ),
+                crate::Error::<Test>::RetentionTooShort
+            );
+
+            // At least one tick of valid proof submitters has to be kept.
+            assert_noop!(
+                ProofsDealer::set_retention_policy(
+                    RuntimeOrigin::root(),
+                    Some(RetentionPolicy::<Test> {
+                        challenge_seeds: 20,
+                        valid_proof_submitters: 0,
+                    })
+                ),
+                crate::Error::<Test>::RetentionTooShort
+            );
+        });
+    }
+
+    #[test]
+    fn set_retention_policy_fails_if_not_root() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(
+                ProofsDealer::set_retention_policy(
+                    RuntimeOrigin::signed(1),
+                    Some(RetentionPolicy::<Test> {
+                        challenge_seeds: 20,
+                        valid_proof_submitters: 2,
+                    })
+                ),
+                DispatchError::BadOrigin
+            );
+        });
+    }
+
+    #[test]
+    fn shortened_challenge_seeds_retention_is_pruned_lazily() {
+        new_test_ext().execute_with(|| {
+            run_to_block(40);
+            let current_tick = ChallengesTicker::<Test>::get();
+
+            // Seeds out of the default retention are pruned as the ticker advances.
+            let last_pruned_tick = current_tick - ChallengeHistoryLengthFor::<Test>::get();
+            assert_eq!(LastPrunedChallengeSeedTick::<Test>::get(), last_pruned_tick);
+            assert!(TickToChallengesSeed::<Test>::get(last_pruned_tick).is_none());
+            assert!(TickToChallengesSeed::<Test>::get(last_pruned_tick + 1).is_some());
+
+            // Shorten the retention of challenge seeds.
+            let challenge_seeds = 15;
+            assert_ok!(ProofsDealer::set_retention_policy(
+                RuntimeOrigin::root(),
+                Some(RetentionPolicy::<Test> {
+                    challenge_seeds,
+                    valid_proof_submitters: TargetTicksStorageOfSubmittersFor::<Test>::get(),
+                })
+            ));
+
+            // Prune only as many seeds as the weight allows.
+            let weight_for_five_seeds =
+                <() as crate::weights::WeightInfo>::prune_challenge_seeds_loop().saturating_mul(5);
+            crate::Pallet::<Test>::do_prune_challenge_seeds(weight_for_five_seeds);
+            assert_eq!(
+                LastPrunedChallengeSeedTick::<Test>::get(),
+                last_pruned_tick + 5
+            );
+            assert!(TickToChallengesSeed::<Test>::get(last_pruned_tick + 5).is_none());
+            assert!(TickToChallengesSeed::<Test>::get(last_pruned_tick + 6).is_some());
+
+            // The rest are pruned in the `on_idle` hook.
+            ProofsDealer::on_idle(System::block_number(), Weight::MAX);
+            let new_last_pruned_tick = current_tick - challenge_seeds;
+            assert_eq!(
+                LastPrunedChallengeSeedTick::<Test>::get(),
+                new_last_pruned_tick
+            );
+            assert!(TickToChallengesSeed::<Test>::get(new_last_pruned_tick).is_none());
+            assert!(TickToChallengesSeed::<Test>::get(new_last_pruned_tick + 1).is_some());
+            System::assert_last_event(
+                Event::HistoryPruned {
+                    challenge_seeds_pruned_up_to: new_last_pruned_tick,
+                    valid_proof_submitters_pruned_up_to: LastDeletedTick::<Test>::get(),
+                    caught_up: true,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn history_pruned_reports_not_caught_up_without_weight() {
+        new_test_ext().execute_with(|| {
+            run_to_block(40);
+            let last_pruned_tick = LastPrunedChallengeSeedTick::<Test>::get();
+
+            // Shorten the retention of challenge seeds.
+            assert_ok!(ProofsDealer::set_retention_policy(
+                RuntimeOrigin::root(),
+                Some(RetentionPolicy::<Test> {
+                    challenge_seeds: 15,
+                    valid_proof_submitters: TargetTicksStorageOfSubmittersFor::<Test>::get(),
+                })
+            ));
+
+            // Without weight left in the block, nothing is pruned.
+            ProofsDealer::on_idle(System::block_number(), Weight::zero());
+            assert_eq!(LastPrunedChallengeSeedTick::<Test>::get(), last_pruned_tick);
+            System::assert_last_event(
+                Event::HistoryPruned {
+                    challenge_seeds_pruned_up_to: last_pruned_tick,
+                    valid_proof_submitters_pruned_up_to: LastDeletedTick::<Test>::get(),
+                    caught_up: false,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn shortened_valid_proof_submitters_retention_is_pruned_lazily() {
+        new_test_ext().execute_with(|| {
+            run_to_block(40);
+            let current_tick = ChallengesTicker::<Test>::get();
+
+            // Trim the valid proof submitters down to the default retention.
+            ProofsDealer::on_idle(System::block_number(), Weight::MAX);
+            assert_eq!(
+                LastDeletedTick::<Test>::get(),
+                current_tick - TargetTicksStorageOfSubmittersFor::<Test>::get() as u64
+            );
+
+            // Shorten the retention of valid proof submitters to a single tick.
+            assert_ok!(ProofsDealer::set_retention_policy(
+                RuntimeOrigin::root(),
+                Some(RetentionPolicy::<Test> {
+                    challenge_seeds: ChallengeHistoryLengthFor::<Test>::get(),
+                    valid_proof_submitters: 1,
+                })
+            ));
+
+            ProofsDealer::on_idle(System::block_number(), Weight::MAX);
+            assert_eq!(LastDeletedTick::<Test>::get(), current_tick - 1);
+            System::assert_last_event(
+                Event::HistoryPruned {
+                    challenge_seeds_pruned_up_to: LastPrunedChallengeSeedTick::<Test>::get(),
+                    valid_proof_submitters_pruned_up_to: current_tick - 1,
+                    caught_up: true,
+                }
+                .into(),
+            );
+        });
+    }
+}
+
 mod on_idle_hook_tests {
     use super::*;
 
@@ -1,5 +1,5 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::traits::fungible;
+use frame_support::{traits::fungible, BoundedVec};
 use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
 use shp_traits::{CommitmentVerifier, ReadChallengeableProvidersInterface};
@@ -95,6 +95,41 @@ pub struct ProofSubmissionRecord<T: crate::Config> {
     pub next_tick_to_submit_proof_for: BlockNumberFor<T>,
 }
 
+/// Record of the failed proof submissions of a Provider that has been marked as slashable.
+///
+/// Used by the Providers pallet to compute the amount to slash.
+#[derive(Debug, Default, Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Copy, MaxEncodedLen)]
+pub struct SlashableProviderRecord {
+    /// The number of challenges the Provider failed to respond to since it was last slashed.
+    pub accrued_failed_proof_submissions: u32,
+}
+
+/// Record of the proof submission deadlines a Provider missed within its current warning window.
+#[derive(Debug, Encode, Decode, TypeInfo, PartialEq, Eq, Clone, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct ProviderWarningRecord<T: crate::Config> {
+    /// The challenges tick at which the Provider was first warned in this window.
+    pub window_start: BlockNumberFor<T>,
+    /// The number of deadlines the Provider missed in this window without being marked as slashable.
+    pub missed_deadlines: u32,
+}
+
+/// For how many ticks the history kept by this pallet is retained.
+///
+/// Set by governance to override the retention given by [`crate::Config::ChallengeHistoryLength`]
+/// and [`crate::Config::TargetTicksStorageOfSubmitters`].
+#[derive(Debug, Encode, Decode, TypeInfo, PartialEq, Eq, Clone, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct RetentionPolicy<T: crate::Config> {
+    /// The number of ticks for which challenge seeds are kept in [`crate::TickToChallengesSeed`].
+    ///
+    /// Providers can only submit proofs for ticks within this range.
+    pub challenge_seeds: BlockNumberFor<T>,
+    /// The number of ticks for which the Providers that submitted valid proofs are kept in
+    /// [`crate::ValidProofSubmittersLastTicks`].
+    pub valid_proof_submitters: u32,
+}
+
 // ****************************************************************************
 // ********************* Syntactic sugar for types ****************************
 // ****************************************************************************
@@ -198,3 +233,26 @@ pub type MinNotFullBlocksRatioFor<T> = <T as crate::Config>::MinNotFullBlocksRat
 
 /// Syntactic sugar for MaxSlashableProvidersPerTick type used in the ProofsDealer pallet.
 pub type MaxSlashableProvidersPerTickFor<T> = <T as crate::Config>::MaxSlashableProvidersPerTick;
+
+/// Syntactic sugar for MaxCatchUpTicksPerBlock type used in the ProofsDealer pallet.
+pub type MaxCatchUpTicksPerBlockFor<T> = <T as crate::Config>::MaxCatchUpTicksPerBlock;
+
+/// Syntactic sugar for the MissedDeadlinesAllowance type used in the proofs pallet.
+pub type MissedDeadlinesAllowanceFor<T> = <T as crate::Config>::MissedDeadlinesAllowance;
+
+/// Syntactic sugar for the MissedDeadlineWarningWindow type used in the proofs pallet.
+pub type MissedDeadlineWarningWindowFor<T> = <T as crate::Config>::MissedDeadlineWarningWindow;
+
+/// Syntactic sugar for the OffchainSignature type used in the proofs pallet.
+pub type OffchainSignatureFor<T> = <T as crate::Config>::OffchainSignature;
+
+/// Syntactic sugar for the MaxProofsPerBatch type used in the proofs pallet.
+pub type MaxProofsPerBatchFor<T> = <T as crate::Config>::MaxProofsPerBatch;
+
+/// The proofs submitted by a Provider in a batch, each paired with the challenges tick it is a
+/// response to, in ascending order of tick.
+pub type ProofBatch<T> = BoundedVec<(BlockNumberFor<T>, Proof<T>), MaxProofsPerBatchFor<T>>;
+
+/// Context prepended to the payload signed by the owner of a Provider to submit a proof as an
+/// unsigned transaction, so that the signature cannot be used for anything else.
+pub const UNSIGNED_PROOF_CONTEXT: &[u8] = b"storagehub:unsigned-proof";
@@ -1,7 +1,7 @@
 use codec::Encode;
 use frame_support::{
     ensure,
-    pallet_prelude::{DispatchClass, DispatchResult},
+    pallet_prelude::{DispatchClass, DispatchResult, InvalidTransaction},
     traits::{fungible::Mutate, tokens::Preservation, Get, Randomness},
     weights::{Weight, WeightMeter},
     BoundedBTreeSet,
@@ -9,15 +9,16 @@ use frame_support::{
 use frame_system::pallet_prelude::BlockNumberFor;
 use pallet_proofs_dealer_runtime_api::{
     GetChallengePeriodError, GetChallengeSeedError, GetCheckpointChallengesError,
-    GetNextDeadlineTickError, GetProofSubmissionRecordError,
+    GetNextDeadlineTickError, GetProofSubmissionRecordError, GetProviderChallengeScheduleError,
+    ProviderChallengeSchedule,
 };
 use shp_traits::{
     CommitmentVerifier, MutateChallengeableProvidersInterface, ProofSubmittersInterface,
-    ProofsDealerInterface, ReadChallengeableProvidersInterface, StorageHubTickGetter, TrieMutation,
-    TrieProofDeltaApplier, TrieRemoveMutation,
+    ProofsDealerInterface, ProviderStatus, ReadChallengeableProvidersInterface,
+    StorageHubTickGetter, TrieMutation, TrieProofDeltaApplier, TrieRemoveMutation,
 };
 use sp_runtime::{
-    traits::{CheckedAdd, CheckedDiv, CheckedSub, Convert, Hash, One, Zero},
+    traits::{CheckedAdd, CheckedDiv, CheckedSub, Convert, Hash, One, Verify, Zero},
     ArithmeticError, BoundedVec, DispatchError, SaturatedConversion, Saturating,
 };
 use sp_std::{
@@ -31,18 +32,23 @@ use crate::{
         AccountIdFor, BalanceFor, BalancePalletFor, ChallengeHistoryLengthFor,
         ChallengeTicksToleranceFor, ChallengesFeeFor, ChallengesQueueLengthFor,
         CheckpointChallengePeriodFor, ForestVerifierFor, ForestVerifierProofFor, KeyFor,
-        KeyVerifierFor, KeyVerifierProofFor, MaxCustomChallengesPerBlockFor,
-        MaxSlashableProvidersPerTickFor, MaxSubmittersPerTickFor, MinChallengePeriodFor, Proof,
-        ProofSubmissionRecord, ProviderIdFor, ProvidersPalletFor, RandomChallengesPerBlockFor,
-        RandomnessOutputFor, RandomnessProviderFor, StakeToChallengePeriodFor,
-        TargetTicksStorageOfSubmittersFor, TreasuryAccountFor,
+        KeyVerifierFor, KeyVerifierProofFor, MaxCatchUpTicksPerBlockFor,
+        MaxCustomChallengesPerBlockFor, MaxSlashableProvidersPerTickFor, MaxSubmittersPerTickFor,
+        MerkleTrieHashingFor, MinChallengePeriodFor, MissedDeadlineWarningWindowFor,
+        MissedDeadlinesAllowanceFor, OffchainSignatureFor, Proof, ProofSubmissionRecord,
+        ProviderIdFor, ProviderWarningRecord, ProvidersPalletFor, RandomChallengesPerBlockFor,
+        RandomnessOutputFor, RandomnessProviderFor, RetentionPolicy, SlashableProviderRecord,
+        StakeToChallengePeriodFor, TargetTicksStorageOfSubmittersFor, TreasuryAccountFor,
+        UNSIGNED_PROOF_CONTEXT,
     },
     weights::WeightInfo,
-    ChallengesQueue, ChallengesTicker, ChallengesTickerPaused, Error, Event, LastCheckpointTick,
-    LastDeletedTick, NotFullBlocksCount, Pallet, PastBlocksWeight, PriorityChallengesQueue,
-    ProviderToProofSubmissionRecord, SlashableProviders, TickToChallengesSeed,
-    TickToCheckForSlashableProviders, TickToCheckpointChallenges, TickToProvidersDeadlines,
-    ValidProofSubmittersLastTicks,
+    ChallengePeriodOverrides, ChallengesQueue, ChallengesTicker, ChallengesTickerPaused,
+    ConsecutiveMissedDeadlines, Error, Event, HistoryRetentionPolicy, LastCheckpointTick,
+    LastDeletedTick, LastPrunedChallengeSeedTick, NotFullBlocksCount, Pallet, PastBlocksWeight,
+    PriorityChallengesQueue, ProviderToProofSubmissionRecord, SlashableProviders,
+    TickToChallengesSeed, TickToCheckForSlashableProviders, TickToCheckpointChallenges,
+    TickToProvidersDeadlines, TicksCatchUpBacklog, UnsignedProofAttempts,
+    ValidProofSubmittersLastTicks, WarnedProviders,
 };
 
 macro_rules! expect_or_err {
@@ -94,6 +100,125 @@ impl<T> Pallet<T>
 where
     T: pallet::Config,
 {
+    /// The weight of submitting `proof`, signed or unsigned.
+    pub fn submit_proof_weight(proof: &Proof<T>) -> Weight {
+        let max_random_key_proofs = T::RandomChallengesPerBlock::get().saturating_mul(2u32.into());
+        let max_custom_key_proofs =
+            T::MaxCustomChallengesPerBlock::get().saturating_mul(2u32.into());
+
+        let max_key_proofs = max_random_key_proofs.saturating_add(max_custom_key_proofs);
+
+        let key_proofs_len = SaturatedConversion::saturated_into::<u32>(proof.key_proofs.len());
+
+        // The proof is part of the PoV, up to the maximum size of a proof that can be accepted.
+        let proof_size = SaturatedConversion::saturated_into::<u64>(proof.encoded_size())
+            .min(T::MaxProofSize::get().into());
+
+        let weight = match key_proofs_len {
+            n if n <= max_random_key_proofs => {
+                T::WeightInfo::submit_proof_no_checkpoint_challenges_key_proofs(n)
+            }
+            n if n <= max_key_proofs => {
+                T::WeightInfo::submit_proof_with_checkpoint_challenges_key_proofs(n)
+            }
+            // More key proofs than `max_key_proofs` would inevitably fail the transaction.
+            n => T::WeightInfo::submit_proof_with_checkpoint_challenges_key_proofs(n),
+        };
+        weight.saturating_add(Weight::from_parts(0, proof_size))
+    }
+
+    /// The weight of submitting `proofs` in a batch, which scales with the weight of each proof.
+    pub fn submit_proofs_weight(proofs: &[(BlockNumberFor<T>, Proof<T>)]) -> Weight {
+        proofs.iter().fold(Weight::zero(), |weight, (_, proof)| {
+            weight.saturating_add(Self::submit_proof_weight(proof))
+        })
+    }
+
+    /// The payload the owner of `provider` signs to submit `proof` as an unsigned transaction, for
+    /// the proof due at `challenges_tick`.
+    ///
+    /// It is the SCALE encoding of [`UNSIGNED_PROOF_CONTEXT`], the Provider ID, the challenges tick
+    /// and the hash of the encoded proof.
+    pub fn unsigned_proof_payload(
+        provider: &ProviderIdFor<T>,
+        challenges_tick: BlockNumberFor<T>,
+        proof: &Proof<T>,
+    ) -> Vec<u8> {
+        (
+            UNSIGNED_PROOF_CONTEXT,
+            provider,
+            challenges_tick,
+            MerkleTrieHashingFor::<T>::hash_of(proof),
+        )
+            .encode()
+    }
+
+    /// Checks that `signature` is the owner of `provider` signing `proof` for the tick the Provider
+    /// has to submit a proof for.
+    pub fn check_unsigned_proof_signature(
+        provider: &ProviderIdFor<T>,
+        proof: &Proof<T>,
+        signature: &OffchainSignatureFor<T>,
+    ) -> DispatchResult {
+        let owner =
+            ProvidersPalletFor::<T>::get_owner_account(*provider).ok_or(Error::<T>::NotProvider)?;
+        let challenges_tick = ProviderToProofSubmissionRecord::<T>::get(*provider)
+            .ok_or(Error::<T>::NoRecordOfLastSubmittedProof)?
+            .next_tick_to_submit_proof_for;
+
+        let payload = Self::unsigned_proof_payload(provider, challenges_tick, proof);
+        ensure!(
+            signature.verify(&payload[..], &owner),
+            Error::<T>::InvalidProofSignature
+        );
+
+        Ok(())
+    }
+
+    /// Validates a proof submitted as an unsigned transaction by `provider`, before it gets into
+    /// the transaction pool or a block.
+    ///
+    /// Checks that the proof is due, that no unsigned proof for the same tick was included before,
+    /// and that it is signed by the owner of the Provider, without verifying the proof itself.
+    /// Returns the tick the proof is for and the tick by which it has to be included.
+    pub fn validate_unsigned_proof_submission(
+        provider: &ProviderIdFor<T>,
+        proof: &Proof<T>,
+        signature: &OffchainSignatureFor<T>,
+    ) -> Result<(BlockNumberFor<T>, BlockNumberFor<T>), InvalidTransaction> {
+        let owner = ProvidersPalletFor::<T>::get_owner_account(*provider)
+            .ok_or(InvalidTransaction::BadSigner)?;
+
+        let challenges_tick = ProviderToProofSubmissionRecord::<T>::get(*provider)
+            .ok_or(InvalidTransaction::Call)?
+            .next_tick_to_submit_proof_for;
+
+        // The proof can only be submitted after the challenges tick, and before its deadline.
+        let current_tick = ChallengesTicker::<T>::get();
+        ensure!(challenges_tick < current_tick, InvalidTransaction::Future);
+        let deadline = challenges_tick.saturating_add(ChallengeTicksToleranceFor::<T>::get());
+        ensure!(deadline > current_tick, InvalidTransaction::Stale);
+
+        // Only one unsigned proof per tick can be included, whether it passed verification or not.
+        ensure!(
+            UnsignedProofAttempts::<T>::get(provider) != Some(challenges_tick),
+            InvalidTransaction::Stale
+        );
+
+        ensure!(
+            proof.encoded_size() <= T::MaxProofSize::get() as usize,
+            InvalidTransaction::ExhaustsResources
+        );
+
+        let payload = Self::unsigned_proof_payload(provider, challenges_tick, proof);
+        ensure!(
+            signature.verify(&payload[..], &owner),
+            InvalidTransaction::BadProof
+        );
+
+        Ok((challenges_tick, deadline))
+    }
+
     /// Add custom challenge to ChallengesQueue.
     ///
     /// Charges a fee for the challenge.
@@ -132,17 +257,58 @@ where
         submitter: &ProviderIdFor<T>,
         proof: &Proof<T>,
     ) -> Result<BlockNumberFor<T>, DispatchError> {
-        let forest_proof = &proof.forest_proof;
-        let key_proofs = &proof.key_proofs;
+        Self::do_submit_proofs_in_order(submitter, &[(None, proof)])
+    }
+
+    /// Submit proofs for several consecutive challenge ticks at once.
+    ///
+    /// Each proof is verified as in [`Self::do_submit_proof`], in order, for the challenges tick it
+    /// is paired with. That tick has to be the one the `submitter` should be submitting a proof for
+    /// after the previous proofs in `proofs` are accepted. The proof submission record and deadline of
+    /// the `submitter` are only updated once, after all the proofs are verified.
+    ///
+    /// Either all the proofs are accepted or none is: the first one failing verification fails the
+    /// whole submission.
+    ///
+    /// Returns the last tick proven.
+    pub fn do_submit_proofs(
+        submitter: &ProviderIdFor<T>,
+        proofs: &[(BlockNumberFor<T>, Proof<T>)],
+    ) -> Result<BlockNumberFor<T>, DispatchError> {
+        ensure!(!proofs.is_empty(), Error::<T>::EmptyProofBatch);
+
+        let proofs = proofs
+            .iter()
+            .map(|(challenges_tick, proof)| (Some(*challenges_tick), proof))
+            .collect::<Vec<_>>();
+        Self::do_submit_proofs_in_order(submitter, &proofs)
+    }
 
+    /// Verify `proofs` in order, each for the challenges tick it is paired with or, if `None`, for
+    /// the tick the `submitter` should be submitting a proof for at that point, and register them as
+    /// submitted.
+    ///
+    /// Returns the last tick proven.
+    fn do_submit_proofs_in_order(
+        submitter: &ProviderIdFor<T>,
+        proofs: &[(Option<BlockNumberFor<T>>, &Proof<T>)],
+    ) -> Result<BlockNumberFor<T>, DispatchError> {
         // Check if submitter is a registered Provider.
         ensure!(
             ProvidersPalletFor::<T>::is_provider(*submitter),
             Error::<T>::NotProvider
         );
 
-        // Check that key_proofs is not empty.
-        ensure!(!key_proofs.is_empty(), Error::<T>::EmptyKeyProofs);
+        for (_, proof) in proofs {
+            // Check that key_proofs is not empty.
+            ensure!(!proof.key_proofs.is_empty(), Error::<T>::EmptyKeyProofs);
+
+            // Check that the proof does not exceed the maximum size, before verifying any of it.
+            ensure!(
+                proof.encoded_size() <= T::MaxProofSize::get() as usize,
+                Error::<T>::ProofTooLarge
+            );
+        }
 
         // The check for whether forest_proof and each key_proof is not empty is handled by the corresponding
         // verifiers for each. We do not preemptively check for this here, since the `CommitmentVerifier::Proof`
@@ -164,13 +330,11 @@ where
 
         // Get last tick for which the submitter submitted a proof, as well as the tick for which
         // now it should be submitting the proof.
-        let ProofSubmissionRecord {
-            last_tick_proven,
-            next_tick_to_submit_proof_for: challenges_tick,
-        } = match ProviderToProofSubmissionRecord::<T>::get(*submitter) {
-            Some(record) => record,
-            None => return Err(Error::<T>::NoRecordOfLastSubmittedProof.into()),
-        };
+        let mut proof_submission_record =
+            match ProviderToProofSubmissionRecord::<T>::get(*submitter) {
+                Some(record) => record,
+                None => return Err(Error::<T>::NoRecordOfLastSubmittedProof.into()),
+            };
 
         // Get stake for submitter.
         // If a submitter is a registered Provider, it must have a stake, so this shouldn't happen.
@@ -181,19 +345,114 @@ where
         // Check that the stake is non-zero.
         ensure!(stake > BalanceFor::<T>::zero(), Error::<T>::ZeroStake);
 
-        // Check that the challenges tick is lower than the current tick.
+        // The deadline the submitter is currently registered with, for the first tick to prove.
+        let challenges_tick_deadline = proof_submission_record
+            .next_tick_to_submit_proof_for
+            .checked_add(&T::ChallengeTicksTolerance::get())
+            .ok_or(DispatchError::Arithmetic(ArithmeticError::Overflow))?;
+
         let current_tick = ChallengesTicker::<T>::get();
+        for (challenges_tick, proof) in proofs {
+            if let Some(challenges_tick) = challenges_tick {
+                ensure!(
+                    *challenges_tick == proof_submission_record.next_tick_to_submit_proof_for,
+                    Error::<T>::UnexpectedChallengesTick
+                );
+            }
+
+            proof_submission_record = Self::verify_proof_for_next_tick(
+                submitter,
+                proof,
+                &proof_submission_record,
+                stake,
+                current_tick,
+            )?;
+        }
+
+        let challenges_tick = proof_submission_record.last_tick_proven;
+        let next_challenges_tick = proof_submission_record.next_tick_to_submit_proof_for;
+
+        // Update `ProviderToProofSubmissionRecord` to the challenge tick the Provider has just
+        // submitted a proof for, and the next tick for which the Provider should submit a proof for.
+        ProviderToProofSubmissionRecord::<T>::set(*submitter, Some(proof_submission_record));
+
+        // A valid proof ends the streak of missed deadlines. Failed proof submissions accrued so
+        // far, if the submitter is slashable, are kept until it is slashed.
+        ConsecutiveMissedDeadlines::<T>::remove(submitter);
+
+        // Remove the submitter from its current deadline registered in `TickToProvidersDeadlines`.
+        TickToProvidersDeadlines::<T>::remove(challenges_tick_deadline, submitter);
+
+        // Add tolerance to `next_challenges_tick` to know when is the next deadline for submitting a
+        // proof, for this Provider.
+        let next_challenges_tick_deadline = next_challenges_tick
+            .checked_add(&T::ChallengeTicksTolerance::get())
+            .ok_or(DispatchError::Arithmetic(ArithmeticError::Overflow))?;
+
+        // Add this Provider to the `TickToProvidersDeadlines` StorageMap, with its new deadline.
+        TickToProvidersDeadlines::<T>::set(next_challenges_tick_deadline, submitter, Some(()));
+
+        // Add this Provider to the `ValidProofSubmittersLastTicks` StorageMap, with the current tick number.
+        let current_tick_valid_submitters = ValidProofSubmittersLastTicks::<T>::get(current_tick);
+        match current_tick_valid_submitters {
+            // If the set already exists and has valid submitters, we just insert the new submitter.
+            Some(mut valid_submitters) => {
+                let did_not_already_exist = expect_or_err!(valid_submitters.try_insert(*submitter), "The set should never be full as the limit we set should be greater than the implicit limit given by max block weight.", Error::<T>::TooManyValidProofSubmitters, result);
+                // We only update storage if the Provider ID wasn't yet in the set to avoid unnecessary writes.
+                if did_not_already_exist {
+                    ValidProofSubmittersLastTicks::<T>::insert(current_tick, valid_submitters);
+                }
+            }
+            // If the set doesn't exist, we create it and insert the submitter.
+            None => {
+                let mut new_valid_submitters =
+                    BoundedBTreeSet::<ProviderIdFor<T>, MaxSubmittersPerTickFor<T>>::new();
+                expect_or_err!(
+                    new_valid_submitters.try_insert(*submitter),
+                    "The set has just been created, it's empty and as such won't be full. qed",
+                    Error::<T>::TooManyValidProofSubmitters,
+                    result
+                );
+                ValidProofSubmittersLastTicks::<T>::insert(current_tick, new_valid_submitters);
+            }
+        }
+
+        Ok(challenges_tick)
+    }
+
+    /// Verify `proof` for the tick the `submitter` should be submitting a proof for according to its
+    /// `proof_submission_record`, applying the mutations of the checkpoint challenges it responds to.
+    ///
+    /// Returns the proof submission record of the `submitter` after the proof is accepted.
+    fn verify_proof_for_next_tick(
+        submitter: &ProviderIdFor<T>,
+        proof: &Proof<T>,
+        proof_submission_record: &ProofSubmissionRecord<T>,
+        stake: BalanceFor<T>,
+        current_tick: BlockNumberFor<T>,
+    ) -> Result<ProofSubmissionRecord<T>, DispatchError> {
+        let forest_proof = &proof.forest_proof;
+        let key_proofs = &proof.key_proofs;
+        let last_tick_proven = proof_submission_record.last_tick_proven;
+        let challenges_tick = proof_submission_record.next_tick_to_submit_proof_for;
+
+        // Get root for submitter, which changes with the mutations applied by previous proofs.
+        let root = ProvidersPalletFor::<T>::get_root(*submitter)
+            .ok_or(Error::<T>::ProviderRootNotFound)?;
+
+        // Check that the challenges tick is lower than the current tick.
         ensure!(
             challenges_tick < current_tick,
             Error::<T>::ChallengesTickNotReached
         );
 
-        // Check that the challenges tick is greater than current tick minus `ChallengeHistoryLength`,
-        // i.e. that the challenges tick is within the ticks this pallet keeps track of.
+        // Check that the challenges tick is within the ticks this pallet keeps track of, i.e. that it is
+        // greater than current tick minus the challenge seeds retention, and its seed was not pruned.
+        let oldest_tick_not_kept = current_tick
+            .saturating_sub(Self::retention_policy().challenge_seeds)
+            .max(LastPrunedChallengeSeedTick::<T>::get());
         expect_or_err!(
-            challenges_tick
-                > current_tick
-                    .saturating_sub(ChallengeHistoryLengthFor::<T>::get()),
+            challenges_tick > oldest_tick_not_kept,
             "Challenges tick is too old, beyond the history this pallet keeps track of. This should not be possible.",
             Error::<T>::ChallengesTickTooOld,
             bool
@@ -289,7 +548,7 @@ where
                     // after the key removal if the key had a value.
                     if let Some(trie_value) = maybe_value {
                         ProvidersPalletFor::<T>::update_provider_after_key_removal(
-                            submitter, trie_value,
+                            submitter, key, trie_value,
                         )
                         .map_err(|_| Error::<T>::FailedToApplyDelta)?;
                     }
@@ -306,6 +565,20 @@ where
                 <T::ProvidersPallet as MutateChallengeableProvidersInterface>::update_root(
                     *submitter, new_root,
                 )?;
+
+                // Emit the summary of the keys removed in this checkpoint challenge round. There are at most
+                // as many removed keys as checkpoint challenges, so none are truncated.
+                Self::deposit_event(Event::<T>::CheckpointMutationsSummary {
+                    provider: *submitter,
+                    checkpoint_tick: last_checkpoint_tick,
+                    removed_keys: BoundedVec::truncate_from(
+                        mutated_keys_and_values
+                            .iter()
+                            .map(|(key, _)| *key)
+                            .collect(),
+                    ),
+                    new_root,
+                });
             }
         };
 
@@ -333,55 +606,13 @@ where
 
         // Calculate the next tick for which the submitter should submit a proof.
         let next_challenges_tick = challenges_tick
-            .checked_add(&Self::stake_to_challenge_period(stake))
+            .checked_add(&Self::challenge_period_for(submitter, stake))
             .ok_or(DispatchError::Arithmetic(ArithmeticError::Overflow))?;
 
-        // Update `ProviderToProofSubmissionRecord` to the challenge tick the Provider has just
-        // submitted a proof for, and the next tick for which the Provider should submit a proof for.
-        let proof_submission_record = ProofSubmissionRecord {
+        Ok(ProofSubmissionRecord {
             last_tick_proven: challenges_tick,
             next_tick_to_submit_proof_for: next_challenges_tick,
-        };
-        ProviderToProofSubmissionRecord::<T>::set(*submitter, Some(proof_submission_record));
-
-        // Remove the submitter from its current deadline registered in `TickToProvidersDeadlines`.
-        TickToProvidersDeadlines::<T>::remove(challenges_tick_deadline, submitter);
-
-        // Add tolerance to `next_challenges_tick` to know when is the next deadline for submitting a
-        // proof, for this Provider.
-        let next_challenges_tick_deadline = next_challenges_tick
-            .checked_add(&T::ChallengeTicksTolerance::get())
-            .ok_or(DispatchError::Arithmetic(ArithmeticError::Overflow))?;
-
-        // Add this Provider to the `TickToProvidersDeadlines` StorageMap, with its new deadline.
-        TickToProvidersDeadlines::<T>::set(next_challenges_tick_deadline, submitter, Some(()));
-
-        // Add this Provider to the `ValidProofSubmittersLastTicks` StorageMap, with the current tick number.
-        let current_tick_valid_submitters = ValidProofSubmittersLastTicks::<T>::get(current_tick);
-        match current_tick_valid_submitters {
-            // If the set already exists and has valid submitters, we just insert the new submitter.
-            Some(mut valid_submitters) => {
-                let did_not_already_exist = expect_or_err!(valid_submitters.try_insert(*submitter), "The set should never be full as the limit we set should be greater than the implicit limit given by max block weight.", Error::<T>::TooManyValidProofSubmitters, result);
-                // We only update storage if the Provider ID wasn't yet in the set to avoid unnecessary writes.
-                if did_not_already_exist {
-                    ValidProofSubmittersLastTicks::<T>::insert(current_tick, valid_submitters);
-                }
-            }
-            // If the set doesn't exist, we create it and insert the submitter.
-            None => {
-                let mut new_valid_submitters =
-                    BoundedBTreeSet::<ProviderIdFor<T>, MaxSubmittersPerTickFor<T>>::new();
-                expect_or_err!(
-                    new_valid_submitters.try_insert(*submitter),
-                    "The set has just been created, it's empty and as such won't be full. qed",
-                    Error::<T>::TooManyValidProofSubmitters,
-                    result
-                );
-                ValidProofSubmittersLastTicks::<T>::insert(current_tick, new_valid_submitters);
-            }
-        }
-
-        Ok(challenges_tick)
+        })
     }
 
     /// Generate a new round of challenges, both random and checkpoint if corresponding.
@@ -400,7 +631,7 @@ where
     /// to have failed to submit a proof, it is subject to slashing.
     ///
     /// Finally, it cleans up:
-    /// - The [`TickToChallengesSeed`] StorageMap, removing entries older than `ChallengeHistoryLength`.
+    /// - The [`TickToChallengesSeed`] StorageMap, removing the oldest entry if it is out of the retention policy.
     /// - The [`TickToCheckpointChallenges`] StorageMap, removing the previous checkpoint challenge block.
     /// - The [`TickToProvidersDeadlines`] StorageMap, removing entries for the current challenges tick.
     pub fn do_new_challenges_round(weight: &mut WeightMeter) {
@@ -413,10 +644,13 @@ where
         let (seed, _) = RandomnessProviderFor::<T>::random(challenges_ticker.encode().as_ref());
         TickToChallengesSeed::<T>::set(challenges_ticker, Some(seed));
 
-        // Remove the oldest challenge seed stored, to clean up the storage.
-        let tick_to_remove = challenges_ticker.checked_sub(&ChallengeHistoryLengthFor::<T>::get());
-        if let Some(tick_to_remove) = tick_to_remove {
-            TickToChallengesSeed::<T>::remove(tick_to_remove);
+        // Remove the oldest challenge seed stored if it is out of the retention policy, to clean up the storage.
+        // If there are more, because the retention was shortened, they are pruned in the `on_idle` hook.
+        let last_pruned_challenge_seed_tick = LastPrunedChallengeSeedTick::<T>::get();
+        if last_pruned_challenge_seed_tick < Self::challenge_seeds_prunable_up_to() {
+            Self::remove_challenge_seed_for_tick(
+                last_pruned_challenge_seed_tick.saturating_add(One::one()),
+            );
         }
 
         // Emit new challenge seed event.
@@ -454,12 +688,16 @@ where
         // This loop is expected to run for a low number of iterations, given that normally, there should
         // be little to no Providers in the `TickToProvidersDeadlines` StorageMap for the `TickToCheckedForSlashableProviders`.
         // However, in the extreme scenario where a large number of Providers are missing the proof submissions,
-        // this is bounded by the `MaxSlashableProvidersPerTick` configuration.
+        // this is bounded by the `MaxSlashableProvidersPerTick` configuration. The number of ticks checked,
+        // which can be more than one if the checks fell behind, is bounded by `MaxCatchUpTicksPerBlock`.
         let max_slashable_providers = MaxSlashableProvidersPerTickFor::<T>::get();
+        let max_catch_up_ticks = MaxCatchUpTicksPerBlockFor::<T>::get();
         let challenge_ticks_tolerance = T::ChallengeTicksTolerance::get();
         let mut slashable_providers_count = 0;
+        let mut ticks_checked = 0;
         while tick_to_check_for_slashable_providers <= challenges_ticker
             && slashable_providers_count < max_slashable_providers
+            && ticks_checked < max_catch_up_ticks
         {
             // If there are Providers left in `TickToProvidersDeadlines` for `TickToCheckedForSlashableProviders`,
             // they are marked as slashable.
@@ -489,26 +727,37 @@ where
                     };
                 let last_tick_proven = proof_submission_record.last_tick_proven;
 
-                // Accrue number of failed proof submission for this slashable provider.
+                // If the Provider is still within its allowance of missed deadlines, it is only warned.
+                let warning = Self::try_warn_provider(&provider, challenges_ticker);
+
+                // Otherwise, accrue number of failed proof submission for this slashable provider.
                 // Add custom checkpoint challenges if the provider needed to respond to them.
-                SlashableProviders::<T>::mutate(provider, |slashable| {
-                    let mut accrued = slashable.unwrap_or(0);
-
-                    let challenge_ticker_provider_should_have_responded_to =
-                        challenges_ticker.saturating_sub(challenge_ticks_tolerance);
-
-                    if checkpoint_challenges_count != 0
-                        && last_tick_proven < last_checkpoint_tick
-                        && last_checkpoint_tick
-                            <= challenge_ticker_provider_should_have_responded_to
-                    {
-                        accrued = accrued.saturating_add(checkpoint_challenges_count as u32);
-                    }
+                if warning.is_none() {
+                    SlashableProviders::<T>::mutate(provider, |slashable| {
+                        let record = slashable.unwrap_or_default();
+                        let mut accrued = record.accrued_failed_proof_submissions;
+
+                        let challenge_ticker_provider_should_have_responded_to =
+                            challenges_ticker.saturating_sub(challenge_ticks_tolerance);
+
+                        if checkpoint_challenges_count != 0
+                            && last_tick_proven < last_checkpoint_tick
+                            && last_checkpoint_tick
+                                <= challenge_ticker_provider_should_have_responded_to
+                        {
+                            accrued = accrued.saturating_add(checkpoint_challenges_count as u32);
+                        }
 
-                    accrued = accrued.saturating_add(RandomChallengesPerBlockFor::<T>::get());
+                        accrued = accrued.saturating_add(RandomChallengesPerBlockFor::<T>::get());
 
-                    *slashable = Some(accrued);
-                });
+                        *slashable = Some(SlashableProviderRecord {
+                            accrued_failed_proof_submissions: accrued,
+                        });
+                    });
+                    ConsecutiveMissedDeadlines::<T>::mutate(provider, |missed_deadlines| {
+                        *missed_deadlines = missed_deadlines.saturating_add(1)
+                    });
+                }
 
                 // Get the stake for this Provider, to know its challenge period.
                 // If a submitter is a registered Provider, it must have a stake, so there shouldn't be an error.
@@ -535,7 +784,7 @@ where
                 // Therefore, the next deadline is one period from now:
                 // next_challenge_deadline = challenge_ticker + provider_period
                 let next_challenge_deadline =
-                    challenges_ticker.saturating_add(Self::stake_to_challenge_period(stake));
+                    challenges_ticker.saturating_add(Self::challenge_period_for(&provider, stake));
 
                 // Update this Provider's next challenge deadline.
                 TickToProvidersDeadlines::<T>::set(next_challenge_deadline, provider, Some(()));
@@ -550,11 +799,23 @@ where
                     next_tick_to_submit_proof_for;
                 ProviderToProofSubmissionRecord::<T>::set(provider, Some(proof_submission_record));
 
-                // Emit slashable provider event.
-                Self::deposit_event(Event::SlashableProvider {
-                    provider,
-                    next_challenge_deadline,
-                });
+                match warning {
+                    Some((missed_deadlines, window_end)) => {
+                        Self::deposit_event(Event::ProviderWarned {
+                            provider,
+                            next_challenge_deadline,
+                            missed_deadlines,
+                            window_end,
+                        });
+                    }
+                    None => {
+                        // Emit slashable provider event.
+                        Self::deposit_event(Event::SlashableProvider {
+                            provider,
+                            next_challenge_deadline,
+                        });
+                    }
+                }
 
                 // Increment the number of slashable providers.
                 slashable_providers_count += 1;
@@ -564,6 +825,7 @@ where
                 // goes beyond `ChallengesTicker`, this loop will exit, leaving everything ready for the next tick.
                 tick_to_check_for_slashable_providers =
                     tick_to_check_for_slashable_providers.saturating_add(One::one());
+                ticks_checked += 1;
                 slashable_providers = TickToProvidersDeadlines::<T>::drain_prefix(
                     tick_to_check_for_slashable_providers,
                 );
@@ -573,12 +835,87 @@ where
         // Update `TickToCheckedForSlashableProviders` to the value resulting from the last iteration of the loop.
         TickToCheckForSlashableProviders::<T>::set(tick_to_check_for_slashable_providers);
 
+        // Record how far behind the deadline checks are, if at all.
+        Self::update_ticks_catch_up_backlog(
+            challenges_ticker,
+            tick_to_check_for_slashable_providers,
+        );
+
         // Consume weight.
         weight.consume(T::WeightInfo::new_challenges_round(
             slashable_providers_count,
         ));
     }
 
+    /// Updates [`TicksCatchUpBacklog`] with the number of ticks up to `challenges_ticker` whose
+    /// deadlines are yet to be checked, starting at `next_tick_to_check`.
+    ///
+    /// Emits [`Event::TicksCatchUpBacklogGrew`] if the backlog grew, and
+    /// [`Event::TicksCatchUpBacklogCleared`] if it was just cleared.
+    fn update_ticks_catch_up_backlog(
+        challenges_ticker: BlockNumberFor<T>,
+        next_tick_to_check: BlockNumberFor<T>,
+    ) {
+        let backlog = challenges_ticker
+            .saturating_add(One::one())
+            .saturating_sub(next_tick_to_check);
+        let previous_backlog = TicksCatchUpBacklog::<T>::get();
+
+        if backlog == previous_backlog {
+            return;
+        }
+
+        if backlog > previous_backlog {
+            Self::deposit_event(Event::TicksCatchUpBacklogGrew {
+                backlog,
+                next_tick_to_check,
+            });
+        } else if backlog.is_zero() {
+            Self::deposit_event(Event::TicksCatchUpBacklogCleared { challenges_ticker });
+        }
+
+        TicksCatchUpBacklog::<T>::set(backlog);
+    }
+
+    /// Warns `provider` for missing a proof submission deadline at `challenges_ticker`, if it has not
+    /// used up its [`Config::MissedDeadlinesAllowance`](crate::Config::MissedDeadlinesAllowance) in
+    /// its current warning window.
+    ///
+    /// Returns the number of deadlines missed in the window and the tick at which the window ends,
+    /// or `None` if the Provider should be marked as slashable instead.
+    pub(crate) fn try_warn_provider(
+        provider: &ProviderIdFor<T>,
+        challenges_ticker: BlockNumberFor<T>,
+    ) -> Option<(u32, BlockNumberFor<T>)> {
+        let allowance = MissedDeadlinesAllowanceFor::<T>::get();
+        if allowance == 0 {
+            return None;
+        }
+
+        let window = MissedDeadlineWarningWindowFor::<T>::get();
+        let mut record = match WarnedProviders::<T>::get(provider) {
+            Some(record) if challenges_ticker < record.window_start.saturating_add(window) => {
+                record
+            }
+            // A missed deadline after the window is over starts a new one.
+            _ => ProviderWarningRecord {
+                window_start: challenges_ticker,
+                missed_deadlines: 0,
+            },
+        };
+
+        if record.missed_deadlines >= allowance {
+            return None;
+        }
+
+        record.missed_deadlines = record.missed_deadlines.saturating_add(1);
+        let window_end = record.window_start.saturating_add(window);
+        let missed_deadlines = record.missed_deadlines;
+        WarnedProviders::<T>::insert(provider, record);
+
+        Some((missed_deadlines, window_end))
+    }
+
     /// Check if the network is presumably under a spam attack.
     ///
     /// The function looks at the weight used in the past `BlockFullnessPeriod` blocks, comparing it
@@ -780,22 +1117,144 @@ where
         ));
     }
 
+    /// Prune the history that falls out of the retention policy, as far as `usable_weight` allows.
+    ///
+    /// Normally, this trims one tick of valid proof submitters per tick, while challenge seeds are
+    /// pruned as the ticker advances. After the retention is shortened though, there is a backlog of
+    /// both to prune, which is worked through over as many blocks as needed, reporting the progress
+    /// with [`Event::HistoryPruned`].
+    pub fn do_prune_history(n: BlockNumberFor<T>, usable_weight: Weight) -> Weight {
+        let last_deleted_tick_before = LastDeletedTick::<T>::get();
+        let mut used_weight = Self::do_trim_valid_proof_submitters_last_ticks(n, usable_weight);
+        let valid_proof_submitters_pruned =
+            LastDeletedTick::<T>::get().saturating_sub(last_deleted_tick_before);
+
+        let last_pruned_challenge_seed_tick_before = LastPrunedChallengeSeedTick::<T>::get();
+        used_weight = used_weight.saturating_add(Self::do_prune_challenge_seeds(
+            usable_weight.saturating_sub(used_weight),
+        ));
+        let challenge_seeds_pruned = LastPrunedChallengeSeedTick::<T>::get()
+            .saturating_sub(last_pruned_challenge_seed_tick_before);
+
+        let valid_proof_submitters_pruned_up_to = LastDeletedTick::<T>::get();
+        let challenge_seeds_pruned_up_to = LastPrunedChallengeSeedTick::<T>::get();
+        let caught_up = valid_proof_submitters_pruned_up_to
+            >= Self::valid_proof_submitters_prunable_up_to()
+            && challenge_seeds_pruned_up_to >= Self::challenge_seeds_prunable_up_to();
+
+        // Only report when catching up, not the one tick of submitters trimmed in a normal block.
+        if !caught_up
+            || !challenge_seeds_pruned.is_zero()
+            || valid_proof_submitters_pruned > One::one()
+        {
+            Self::deposit_event(Event::HistoryPruned {
+                challenge_seeds_pruned_up_to,
+                valid_proof_submitters_pruned_up_to,
+                caught_up,
+            });
+        }
+
+        used_weight
+    }
+
+    /// Remove the challenge seeds that fall out of the retention policy, oldest first, as many as
+    /// `usable_weight` allows.
+    ///
+    /// Challenge seeds are normally pruned as the ticker advances, so this only removes seeds after the
+    /// retention is shortened.
+    pub fn do_prune_challenge_seeds(usable_weight: Weight) -> Weight {
+        let last_pruned_tick = LastPrunedChallengeSeedTick::<T>::get();
+        let ticks_to_remove =
+            Self::challenge_seeds_prunable_up_to().saturating_sub(last_pruned_tick);
+
+        // Check how many ticks can be removed considering weight limitations.
+        let weight_for_one_iteration = T::WeightInfo::prune_challenge_seeds_loop();
+        let removable_ticks = usable_weight.checked_div_per_component(&weight_for_one_iteration);
+
+        let ticks_removed: u64 = if let Some(removable_ticks) = removable_ticks {
+            // Take the minimum between all the ticks that we want to remove, and the ones we can.
+            let removable_ticks: BlockNumberFor<T> = removable_ticks.saturated_into();
+            let removable_ticks = removable_ticks.min(ticks_to_remove);
+
+            let mut tick_to_remove = last_pruned_tick.saturating_add(One::one());
+            let end_tick = last_pruned_tick.saturating_add(removable_ticks);
+            while tick_to_remove <= end_tick {
+                tick_to_remove = Self::remove_challenge_seed_for_tick(tick_to_remove);
+            }
+
+            removable_ticks.saturated_into()
+        } else {
+            Zero::zero()
+        };
+
+        // Return the weight used by this function.
+        T::WeightInfo::prune_challenge_seeds_constant_execution()
+            .saturating_add(weight_for_one_iteration.saturating_mul(ticks_removed))
+    }
+
+    /// The retention policy in effect: the one set by governance in [`HistoryRetentionPolicy`], or the
+    /// one given by the pallet's constants.
+    pub fn retention_policy() -> RetentionPolicy<T> {
+        HistoryRetentionPolicy::<T>::get().unwrap_or_else(|| RetentionPolicy {
+            challenge_seeds: ChallengeHistoryLengthFor::<T>::get(),
+            valid_proof_submitters: TargetTicksStorageOfSubmittersFor::<T>::get(),
+        })
+    }
+
+    /// Set the retention policy, or remove it to go back to the pallet's constants if `policy` is
+    /// `None`. Returns the retention policy in effect.
+    ///
+    /// Providers have up to [`ChallengeTicksToleranceFor`] ticks to submit a proof for a tick, so
+    /// challenge seeds have to be kept for longer than that.
+    pub(crate) fn do_set_retention_policy(
+        policy: Option<RetentionPolicy<T>>,
+    ) -> Result<RetentionPolicy<T>, DispatchError> {
+        match policy {
+            Some(policy) => {
+                ensure!(
+                    policy.challenge_seeds > ChallengeTicksToleranceFor::<T>::get(),
+                    Error::<T>::RetentionTooShort
+                );
+                ensure!(
+                    policy.valid_proof_submitters > 0,
+                    Error::<T>::RetentionTooShort
+                );
+
+                HistoryRetentionPolicy::<T>::put(policy);
+            }
+            None => HistoryRetentionPolicy::<T>::kill(),
+        }
+
+        Ok(Self::retention_policy())
+    }
+
+    /// The last tick whose challenge seed is out of the retention policy.
+    fn challenge_seeds_prunable_up_to() -> BlockNumberFor<T> {
+        ChallengesTicker::<T>::get().saturating_sub(Self::retention_policy().challenge_seeds)
+    }
+
+    /// The last tick whose valid proof submitters are out of the retention policy.
+    fn valid_proof_submitters_prunable_up_to() -> BlockNumberFor<T> {
+        ChallengesTicker::<T>::get()
+            .saturating_sub(Self::retention_policy().valid_proof_submitters.into())
+    }
+
     /// Trim the storage that holds the Providers that submitted valid proofs in the last ticks until there's
-    /// `TargetTicksOfProofsStorage` ticks left (or until the remaining weight allows it).
+    /// as many ticks left as the retention policy allows (or until the remaining weight allows it).
     ///
     /// This function is called in the `on_idle` hook, which means it's only called when the block has
     /// unused weight.
     ///
     /// It removes the oldest tick from the storage that holds the providers that submitted valid proofs
     /// in the last ticks as many times as the remaining weight allows it, but at most until the storage
-    /// has `TargetTicksOfProofsStorage` ticks left.
+    /// has as many ticks left as the retention policy allows.
     pub fn do_trim_valid_proof_submitters_last_ticks(
         _n: BlockNumberFor<T>,
         usable_weight: Weight,
     ) -> Weight {
         // Check how many ticks should be removed to keep the storage at the target amount.
         let last_deleted_tick = LastDeletedTick::<T>::get();
-        let target_ticks_to_keep = TargetTicksStorageOfSubmittersFor::<T>::get();
+        let target_ticks_to_keep = Self::retention_policy().valid_proof_submitters;
         let current_tick = ChallengesTicker::<T>::get();
         let ticks_to_remove: BlockNumberFor<T> = current_tick
             .saturating_sub(last_deleted_tick)
@@ -846,6 +1305,53 @@ where
         min_challenge_period.max(challenge_period)
     }
 
+    /// The challenge period of a Provider with `stake`.
+    ///
+    /// This is the period set for the Provider in [`ChallengePeriodOverrides`], if any, or the one
+    /// derived from its stake otherwise.
+    pub(crate) fn challenge_period_for(
+        provider_id: &ProviderIdFor<T>,
+        stake: BalanceFor<T>,
+    ) -> BlockNumberFor<T> {
+        ChallengePeriodOverrides::<T>::get(provider_id)
+            .unwrap_or_else(|| Self::stake_to_challenge_period(stake))
+    }
+
+    /// Set or remove the challenge period override of a Provider.
+    ///
+    /// The override has to be at least [`MinChallengePeriodFor`], and short enough for the Provider
+    /// to have to submit a proof between checkpoint challenge rounds. It applies from the next time
+    /// the Provider's next challenge tick is calculated, so its current deadline is kept.
+    pub(crate) fn do_set_provider_challenge_period_override(
+        provider_id: &ProviderIdFor<T>,
+        period: Option<BlockNumberFor<T>>,
+    ) -> DispatchResult {
+        // Check that `provider_id` is a registered Provider.
+        ensure!(
+            ProvidersPalletFor::<T>::is_provider(*provider_id),
+            Error::<T>::NotProvider
+        );
+
+        match period {
+            Some(period) => {
+                ensure!(
+                    period >= MinChallengePeriodFor::<T>::get(),
+                    Error::<T>::ChallengePeriodOverrideTooShort
+                );
+                ensure!(
+                    period.saturating_add(ChallengeTicksToleranceFor::<T>::get())
+                        < CheckpointChallengePeriodFor::<T>::get(),
+                    Error::<T>::ChallengePeriodOverrideTooLong
+                );
+
+                ChallengePeriodOverrides::<T>::insert(provider_id, period);
+            }
+            None => ChallengePeriodOverrides::<T>::remove(provider_id),
+        }
+
+        Ok(())
+    }
+
     /// Add challenge to ChallengesQueue.
     ///
     /// Check if challenge is already queued. If it is, just return. Otherwise, add the challenge
@@ -935,6 +1441,17 @@ where
         min_non_full_blocks_ratio.mul_floor(T::BlockFullnessPeriod::get())
     }
 
+    /// Remove the challenge seed for a given tick and update the `LastPrunedChallengeSeedTick` storage
+    /// element to the given tick.
+    ///
+    /// Returns the next tick to remove.
+    pub(crate) fn remove_challenge_seed_for_tick(tick: BlockNumberFor<T>) -> BlockNumberFor<T> {
+        TickToChallengesSeed::<T>::remove(tick);
+        LastPrunedChallengeSeedTick::<T>::set(tick);
+
+        tick.saturating_add(One::one())
+    }
+
     /// Remove all the proof submitters for a given tick and update the `LastDeletedTick` storage element
     /// to the given tick.
     ///
@@ -1091,13 +1608,27 @@ impl<T: pallet::Config> ProofsDealerInterface for Pallet<T> {
             ProviderToProofSubmissionRecord::<T>::remove(*provider_id);
         }
 
+        // Remove the provider's warning window, if any.
+        WarnedProviders::<T>::remove(*provider_id);
+
+        // Remove the provider's last unsigned proof attempt, if any.
+        UnsignedProofAttempts::<T>::remove(*provider_id);
+
+        // Remove the provider's streak of missed deadlines, if any.
+        ConsecutiveMissedDeadlines::<T>::remove(*provider_id);
+
+        // Remove the provider's challenge period override, if any.
+        ChallengePeriodOverrides::<T>::remove(*provider_id);
+
         Ok(())
     }
 
     fn initialise_challenge_cycle(provider_id: &Self::ProviderId) -> DispatchResult {
-        // Check that `provider_id` is a registered Provider.
-        if !ProvidersPalletFor::<T>::is_provider(*provider_id) {
-            return Err(Error::<T>::NotProvider.into());
+        // Check that `provider_id` is a registered Provider that can take on new duties.
+        match ProvidersPalletFor::<T>::get_provider_status(*provider_id) {
+            Some(ProviderStatus::Active) => {}
+            Some(_) => return Err(Error::<T>::ProviderNotActive.into()),
+            None => return Err(Error::<T>::NotProvider.into()),
         }
 
         // Get stake for submitter.
@@ -1129,7 +1660,7 @@ impl<T: pallet::Config> ProofsDealerInterface for Pallet<T> {
         // next_tick_to_submit_proof_for = current_tick + provider_challenge_period
         let current_tick = ChallengesTicker::<T>::get();
         let next_challenge_tick = current_tick
-            .checked_add(&Self::stake_to_challenge_period(stake))
+            .checked_add(&Self::challenge_period_for(provider_id, stake))
             .ok_or(DispatchError::Arithmetic(ArithmeticError::Overflow))?;
 
         let proof_submission_record = ProofSubmissionRecord {
@@ -1179,6 +1710,11 @@ impl<T: pallet::Config> ProofSubmittersInterface for Pallet<T> {
 
     fn get_accrued_failed_proof_submissions(provider_id: &Self::ProviderId) -> Option<u32> {
         SlashableProviders::<T>::get(provider_id)
+            .map(|record| record.accrued_failed_proof_submissions)
+    }
+
+    fn get_consecutive_missed_proof_deadlines(provider_id: &Self::ProviderId) -> u32 {
+        ConsecutiveMissedDeadlines::<T>::get(provider_id)
     }
 
     fn clear_accrued_failed_proof_submissions(provider_id: &Self::ProviderId) {
@@ -1257,13 +1793,17 @@ where
         let stake = ProvidersPalletFor::<T>::get_stake(*provider_id)
             .ok_or(GetChallengePeriodError::ProviderNotRegistered)?;
 
-        Ok(Self::stake_to_challenge_period(stake))
+        Ok(Self::challenge_period_for(provider_id, stake))
     }
 
     pub fn get_checkpoint_challenge_period() -> BlockNumberFor<T> {
         CheckpointChallengePeriodFor::<T>::get()
     }
 
+    pub fn get_max_proof_size() -> u32 {
+        T::MaxProofSize::get()
+    }
+
     pub fn get_challenges_from_seed(
         seed: &RandomnessOutputFor<T>,
         provider_id: &ProviderIdFor<T>,
@@ -1287,6 +1827,10 @@ where
         ChallengesTicker::<T>::get()
     }
 
+    pub fn get_ticks_catch_up_backlog() -> BlockNumberFor<T> {
+        TicksCatchUpBacklog::<T>::get()
+    }
+
     pub fn get_next_deadline_tick(
         provider_id: &ProviderIdFor<T>,
     ) -> Result<BlockNumberFor<T>, GetNextDeadlineTickError> {
@@ -1306,4 +1850,50 @@ where
 
         Ok(next_deadline_tick)
     }
+
+    pub fn provider_challenge_schedule(
+        provider_id: &ProviderIdFor<T>,
+    ) -> Result<
+        ProviderChallengeSchedule<BlockNumberFor<T>, RandomnessOutputFor<T>>,
+        GetProviderChallengeScheduleError,
+    > {
+        // Check if the provider is indeed a registered Provider.
+        let stake = ProvidersPalletFor::<T>::get_stake(*provider_id)
+            .ok_or(GetProviderChallengeScheduleError::ProviderNotRegistered)?;
+
+        // Get this Provider's proof submission record.
+        let record = ProviderToProofSubmissionRecord::<T>::get(provider_id)
+            .ok_or(GetProviderChallengeScheduleError::ProviderNotInitialised)?;
+
+        let next_deadline_tick = record
+            .next_tick_to_submit_proof_for
+            .checked_add(&ChallengeTicksToleranceFor::<T>::get())
+            .ok_or(GetProviderChallengeScheduleError::ArithmeticOverflow)?;
+
+        let challenge_period = Self::challenge_period_for(provider_id, stake);
+        let current_tick = ChallengesTicker::<T>::get();
+
+        // Collect the seeds of the ticks the Provider has to submit proofs for, advancing by
+        // `challenge_period` ticks, in the same way the blockchain service catches up with them.
+        let mut seeds = Vec::new();
+        let mut tick = record.next_tick_to_submit_proof_for;
+        while tick <= current_tick {
+            if let Some(seed) = TickToChallengesSeed::<T>::get(tick) {
+                seeds.push((tick, seed));
+            }
+            tick = tick
+                .checked_add(&challenge_period)
+                .ok_or(GetProviderChallengeScheduleError::ArithmeticOverflow)?;
+        }
+
+        Ok(ProviderChallengeSchedule {
+            last_tick_proven: record.last_tick_proven,
+            next_tick_to_submit_proof_for: record.next_tick_to_submit_proof_for,
+            next_deadline_tick,
+            challenge_period,
+            current_tick,
+            last_checkpoint_tick: LastCheckpointTick::<T>::get(),
+            seeds,
+        })
+    }
 }
@@ -51,6 +51,10 @@ pub trait WeightInfo {
 	fn on_finalize() -> Weight;
 	fn force_initialise_challenge_cycle() -> Weight;
 	fn set_paused() -> Weight;
+	fn set_provider_challenge_period_override() -> Weight;
+	fn prune_challenge_seeds_constant_execution() -> Weight;
+	fn prune_challenge_seeds_loop() -> Weight;
+	fn set_retention_policy() -> Weight;
 }
 
 /// Weights for `pallet_proofs_dealer` using the Substrate node and recommended hardware.
@@ -207,6 +211,10 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: `Providers::BackupStorageProviders` (`max_values`: None, `max_size`: Some(683), added: 3158, mode: `MaxEncodedLen`)
 	/// Storage: `Balances::Holds` (r:1000 w:0)
 	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(175), added: 2650, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::LastPrunedChallengeSeedTick` (r:1 w:1)
+	/// Proof: `ProofsDealer::LastPrunedChallengeSeedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:1 w:0)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	/// Storage: `ProofsDealer::TickToChallengesSeed` (r:0 w:1)
 	/// Proof: `ProofsDealer::TickToChallengesSeed` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
 	/// The range of component `n` is `[0, 1000]`.
@@ -218,9 +226,9 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(26_000_000, 8523)
 			// Standard Error: 15_069
 			.saturating_add(Weight::from_parts(26_601_146, 0).saturating_mul(n.into()))
-			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().reads(12_u64))
 			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(n.into())))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
 			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
 			.saturating_add(Weight::from_parts(0, 3158).saturating_mul(n.into()))
 	}
@@ -265,13 +273,15 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: `ProofsDealer::LastDeletedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
 	/// Storage: `ProofsDealer::ChallengesTicker` (r:1 w:0)
 	/// Proof: `ProofsDealer::ChallengesTicker` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:1 w:0)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	fn trim_valid_proof_submitters_last_ticks_constant_execution() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `80`
-		//  Estimated: `1489`
-		// Minimum execution time: 4_000_000 picoseconds.
-		Weight::from_parts(5_000_000, 1489)
-			.saturating_add(T::DbWeight::get().reads(2_u64))
+		//  Estimated: `1493`
+		// Minimum execution time: 5_000_000 picoseconds.
+		Weight::from_parts(6_000_000, 1493)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
 	}
 	/// Storage: `ProofsDealer::LastDeletedTick` (r:0 w:1)
 	/// Proof: `ProofsDealer::LastDeletedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
@@ -326,6 +336,57 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(4_000_000, 0)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `Providers::MainStorageProviders` (r:1 w:0)
+	/// Proof: `Providers::MainStorageProviders` (`max_values`: None, `max_size`: Some(647), added: 3122, mode: `MaxEncodedLen`)
+	/// Storage: `Providers::BackupStorageProviders` (r:1 w:0)
+	/// Proof: `Providers::BackupStorageProviders` (`max_values`: None, `max_size`: Some(683), added: 3158, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::ChallengePeriodOverrides` (r:0 w:1)
+	/// Proof: `ProofsDealer::ChallengePeriodOverrides` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	fn set_provider_challenge_period_override() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `527`
+		//  Estimated: `4148`
+		// Minimum execution time: 12_000_000 picoseconds.
+		Weight::from_parts(13_000_000, 4148)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ProofsDealer::LastPrunedChallengeSeedTick` (r:1 w:0)
+	/// Proof: `ProofsDealer::LastPrunedChallengeSeedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::ChallengesTicker` (r:1 w:0)
+	/// Proof: `ProofsDealer::ChallengesTicker` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:1 w:0)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	fn prune_challenge_seeds_constant_execution() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `80`
+		//  Estimated: `1493`
+		// Minimum execution time: 5_000_000 picoseconds.
+		Weight::from_parts(6_000_000, 1493)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+	}
+	/// Storage: `ProofsDealer::LastPrunedChallengeSeedTick` (r:0 w:1)
+	/// Proof: `ProofsDealer::LastPrunedChallengeSeedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::TickToChallengesSeed` (r:0 w:1)
+	/// Proof: `ProofsDealer::TickToChallengesSeed` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	fn prune_challenge_seeds_loop() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 1_000_000 picoseconds.
+		Weight::from_parts(2_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:0 w:1)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	fn set_retention_policy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 4_000_000 picoseconds.
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests.
@@ -481,6 +542,10 @@ impl WeightInfo for () {
 	/// Proof: `Providers::BackupStorageProviders` (`max_values`: None, `max_size`: Some(683), added: 3158, mode: `MaxEncodedLen`)
 	/// Storage: `Balances::Holds` (r:1000 w:0)
 	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(175), added: 2650, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::LastPrunedChallengeSeedTick` (r:1 w:1)
+	/// Proof: `ProofsDealer::LastPrunedChallengeSeedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:1 w:0)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	/// Storage: `ProofsDealer::TickToChallengesSeed` (r:0 w:1)
 	/// Proof: `ProofsDealer::TickToChallengesSeed` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
 	/// The range of component `n` is `[0, 1000]`.
@@ -492,9 +557,9 @@ impl WeightInfo for () {
 		Weight::from_parts(26_000_000, 8523)
 			// Standard Error: 15_069
 			.saturating_add(Weight::from_parts(26_601_146, 0).saturating_mul(n.into()))
-			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
 			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(n.into())))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
 			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(n.into())))
 			.saturating_add(Weight::from_parts(0, 3158).saturating_mul(n.into()))
 	}
@@ -539,13 +604,15 @@ impl WeightInfo for () {
 	/// Proof: `ProofsDealer::LastDeletedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
 	/// Storage: `ProofsDealer::ChallengesTicker` (r:1 w:0)
 	/// Proof: `ProofsDealer::ChallengesTicker` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:1 w:0)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	fn trim_valid_proof_submitters_last_ticks_constant_execution() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `80`
-		//  Estimated: `1489`
-		// Minimum execution time: 4_000_000 picoseconds.
-		Weight::from_parts(5_000_000, 1489)
-			.saturating_add(RocksDbWeight::get().reads(2_u64))
+		//  Estimated: `1493`
+		// Minimum execution time: 5_000_000 picoseconds.
+		Weight::from_parts(6_000_000, 1493)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
 	}
 	/// Storage: `ProofsDealer::LastDeletedTick` (r:0 w:1)
 	/// Proof: `ProofsDealer::LastDeletedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
@@ -600,4 +667,55 @@ impl WeightInfo for () {
 		Weight::from_parts(4_000_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: `Providers::MainStorageProviders` (r:1 w:0)
+	/// Proof: `Providers::MainStorageProviders` (`max_values`: None, `max_size`: Some(647), added: 3122, mode: `MaxEncodedLen`)
+	/// Storage: `Providers::BackupStorageProviders` (r:1 w:0)
+	/// Proof: `Providers::BackupStorageProviders` (`max_values`: None, `max_size`: Some(683), added: 3158, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::ChallengePeriodOverrides` (r:0 w:1)
+	/// Proof: `ProofsDealer::ChallengePeriodOverrides` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	fn set_provider_challenge_period_override() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `527`
+		//  Estimated: `4148`
+		// Minimum execution time: 12_000_000 picoseconds.
+		Weight::from_parts(13_000_000, 4148)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ProofsDealer::LastPrunedChallengeSeedTick` (r:1 w:0)
+	/// Proof: `ProofsDealer::LastPrunedChallengeSeedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::ChallengesTicker` (r:1 w:0)
+	/// Proof: `ProofsDealer::ChallengesTicker` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:1 w:0)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	fn prune_challenge_seeds_constant_execution() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `80`
+		//  Estimated: `1493`
+		// Minimum execution time: 5_000_000 picoseconds.
+		Weight::from_parts(6_000_000, 1493)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+	}
+	/// Storage: `ProofsDealer::LastPrunedChallengeSeedTick` (r:0 w:1)
+	/// Proof: `ProofsDealer::LastPrunedChallengeSeedTick` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `ProofsDealer::TickToChallengesSeed` (r:0 w:1)
+	/// Proof: `ProofsDealer::TickToChallengesSeed` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	fn prune_challenge_seeds_loop() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 1_000_000 picoseconds.
+		Weight::from_parts(2_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ProofsDealer::HistoryRetentionPolicy` (r:0 w:1)
+	/// Proof: `ProofsDealer::HistoryRetentionPolicy` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	fn set_retention_policy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 4_000_000 picoseconds.
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
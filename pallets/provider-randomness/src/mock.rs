@@ -17,8 +17,9 @@ use shp_traits::{
     TrieProofDeltaApplier,
 };
 use shp_treasury_funding::NoCutTreasuryCutCalculator;
-use sp_core::{blake2_256, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
+use sp_core::{blake2_256, ConstBool, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
 use sp_runtime::{
+    testing::TestSignature,
     traits::{BlakeTwo256, Convert, ConvertBack, IdentityLookup},
     BoundedBTreeSet, BoundedVec, BuildStorage, DispatchError, Perbill, SaturatedConversion,
 };
@@ -129,6 +130,11 @@ parameter_types! {
 }
 
 // Storage Providers pallet:
+parameter_types! {
+    pub const SlashEscalationPerMissedDeadline: Perbill = Perbill::from_percent(10);
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
+}
+
 impl pallet_storage_providers::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -136,6 +142,7 @@ impl pallet_storage_providers::Config for Test {
     type PaymentStreams = PaymentStreams;
     type ProofDealer = ProofsDealer;
     type FileMetadataManager = MockFileMetadataManager;
+    type OnProviderStatusChange = ();
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type StorageDataUnit = StorageDataUnit;
@@ -161,10 +168,14 @@ impl pallet_storage_providers::Config for Test {
     type MaxProtocols = ConstU32<100>;
     type BucketDeposit = ConstU128<10>;
     type BucketNameLimit = ConstU32<100>;
+    type EncryptionMetadataLimit = ConstU32<256>;
     type MaxBlocksForRandomness = ConstU64<{ EPOCH_DURATION_IN_BLOCKS * 2 }>;
     type MinBlocksBetweenCapacityChanges = ConstU64<10>;
     type DefaultMerkleRoot = DefaultMerkleRoot<LayoutV1<BlakeTwo256>>;
     type SlashAmountPerMaxFileSize = ConstU128<10>;
+    type SlashReferenceDataSize = ConstU64<{ u64::MAX }>;
+    type SlashEscalationPerMissedDeadline = SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier = ConstU32<5>;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = ConstU64<10>;
     type MaxCommitmentSize = ConstU32<1000>;
@@ -173,6 +184,17 @@ impl pallet_storage_providers::Config for Test {
     type BenchmarkHelpers = ();
     type ProviderTopUpTtl = ProviderTopUpTtl;
     type MaxExpiredItemsInBlock = ConstU32<10>;
+    type MaxCapacityUtilizationSamples = ConstU32<10>;
+    type CapacityUtilizationSamplingPeriod = ConstU64<10>;
+    type RequireCapacityAttestation = ConstBool<false>;
+    type CapacityAttestationChunkSize = ConstU64<10>;
+    type CapacityAttestationSpotChecks = ConstU32<4>;
+    type CapacityAttestationWindow = ConstU64<10>;
+    type CapacityDecreaseGracePeriod = ConstU64<20>;
+    type ReputationGrowthStreak = ConstU32<5>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<10>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
 }
 
 // Mock the Randomness trait to use a simple randomness function when testing the pallet
@@ -251,6 +273,10 @@ impl ProofSubmittersInterface for MockSubmittingProviders {
         None
     }
 
+    fn get_consecutive_missed_proof_deadlines(_provider_id: &Self::ProviderId) -> u32 {
+        0
+    }
+
     fn clear_accrued_failed_proof_submissions(_provider_id: &Self::ProviderId) {}
 }
 
@@ -294,6 +320,10 @@ impl ConvertBack<StorageDataUnit, Balance> for StorageDataUnitAndBalanceConverte
 }
 
 // Payment streams pallet:
+parameter_types! {
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(5);
+}
+
 impl pallet_payment_streams::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -309,6 +339,10 @@ impl pallet_payment_streams::Config for Test {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = ();
+    type MaxOraclePriceAge = ConstU64<10>;
+    type OraclePriceAdjustmentPeriod = ConstU64<10>;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 
 parameter_types! {
@@ -353,6 +387,14 @@ impl pallet_proofs_dealer::Config for Test {
     type BlockFullnessHeadroom = BlockFullnessHeadroom;
     type MinNotFullBlocksRatio = MinNotFullBlocksRatio;
     type MaxSlashableProvidersPerTick = ConstU32<100>;
+    type MaxCatchUpTicksPerBlock = ConstU32<10>;
+    type MissedDeadlinesAllowance = ConstU32<0>;
+    type MissedDeadlineWarningWindow = ConstU64<100>;
+    type MaxProofSize = ConstU32<{ 1024 * 1024 }>;
+    type MaxProofsPerBatch = ConstU32<5>;
+    type OffchainSignature = TestSignature;
+    type OffchainPublic = <TestSignature as sp_runtime::traits::Verify>::Signer;
+    type UnsignedProofPriority = ConstU64<{ u64::MAX }>;
 }
 
 /// Structure to mock a verifier that returns `true` when `proof` is not empty
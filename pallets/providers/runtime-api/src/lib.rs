@@ -5,8 +5,8 @@ use scale_info::TypeInfo;
 use sp_runtime::RuntimeDebug;
 
 sp_api::decl_runtime_apis! {
-    #[api_version(1)]
-    pub trait StorageProvidersApi<BlockNumber, BspId, BspInfo, AccountId, ProviderId, StorageProviderId, StorageDataUnit, Balance, BucketId, Multiaddresses, ValuePropositionWithId>
+    #[api_version(2)]
+    pub trait StorageProvidersApi<BlockNumber, BspId, BspInfo, AccountId, ProviderId, StorageProviderId, StorageDataUnit, Balance, BucketId, Multiaddresses, ValuePropId, ValuePropositionWithId, ValuePropositionVersion, CapacityUtilization, ProviderStatus, ReputationWeight>
     where
         BlockNumber: Codec,
         BspId: Codec,
@@ -18,20 +18,39 @@ sp_api::decl_runtime_apis! {
         Balance: Codec,
         BucketId: Codec,
         Multiaddresses: Codec,
+        ValuePropId: Codec,
         ValuePropositionWithId: Codec,
+        ValuePropositionVersion: Codec,
+        CapacityUtilization: Codec,
+        ProviderStatus: Codec,
+        ReputationWeight: Codec,
     {
         fn get_bsp_info(bsp_id: &BspId) -> Result<BspInfo, GetBspInfoError>;
         fn get_storage_provider_id(who: &AccountId) -> Option<StorageProviderId>;
         fn query_provider_multiaddresses(provider_id: &ProviderId) -> Result<Multiaddresses, QueryProviderMultiaddressesError>;
         fn query_msp_id_of_bucket_id(bucket_id: &BucketId) -> Result<Option<ProviderId>, QueryMspIdOfBucketIdError>;
+        #[api_version(2)]
+        fn query_bucket_encryption_metadata(bucket_id: &BucketId) -> Result<Option<sp_runtime::Vec<u8>>, QueryBucketEncryptionMetadataError>;
         fn query_storage_provider_capacity(provider_id: &ProviderId) -> Result<StorageDataUnit, QueryStorageProviderCapacityError>;
         fn query_available_storage_capacity(provider_id: &ProviderId) -> Result<StorageDataUnit, QueryAvailableStorageCapacityError>;
         fn query_earliest_change_capacity_block(bsp_id: &BspId) -> Result<BlockNumber, QueryEarliestChangeCapacityBlockError>;
         fn get_worst_case_scenario_slashable_amount(provider_id: ProviderId) -> Option<Balance>;
         fn get_slash_amount_per_max_file_size() -> Balance;
         fn query_value_propositions_for_msp(who: &ProviderId) -> sp_runtime::Vec<ValuePropositionWithId>;
+        #[api_version(2)]
+        fn query_value_proposition_version(msp_id: &ProviderId, value_prop_id: &ValuePropId) -> Result<ValuePropositionVersion, QueryValuePropositionVersionError>;
+        #[api_version(2)]
+        fn query_latest_value_proposition(msp_id: &ProviderId, value_prop_id: &ValuePropId) -> Result<ValuePropositionWithId, QueryValuePropositionVersionError>;
         fn get_bsp_stake(bsp_id: &BspId) -> Result<Balance, GetStakeError>;
         fn can_delete_provider(provider_id: &ProviderId) -> bool;
+        #[api_version(2)]
+        fn capacity_utilization() -> CapacityUtilization;
+        #[api_version(2)]
+        fn query_capacity_challenge(who: &AccountId) -> Result<sp_runtime::Vec<u64>, QueryCapacityChallengeError>;
+        #[api_version(2)]
+        fn get_provider_status(provider_id: &ProviderId) -> Option<ProviderStatus>;
+        #[api_version(2)]
+        fn query_bsp_reputation_weight(bsp_id: &BspId) -> Result<ReputationWeight, QueryBspReputationWeightError>;
     }
 }
 
@@ -70,6 +89,12 @@ pub enum QueryMspIdOfBucketIdError {
     InternalError,
 }
 
+/// Error type for the `query_bucket_encryption_metadata` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum QueryBucketEncryptionMetadataError {
+    BucketNotFound,
+}
+
 /// Error type for the `query_provider_multiaddresses` runtime API call.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum QueryProviderMultiaddressesError {
@@ -77,6 +102,27 @@ pub enum QueryProviderMultiaddressesError {
     InternalError,
 }
 
+/// Error type for the `query_capacity_challenge` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum QueryCapacityChallengeError {
+    AttestationNotFound,
+    RandomnessNotValidYet,
+    AttestationExpired,
+    PlotNotCommitted,
+}
+
+/// Error type for the `query_value_proposition_version` and `query_latest_value_proposition` runtime API calls.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum QueryValuePropositionVersionError {
+    ValuePropositionNotFound,
+}
+
+/// Error type for the `query_bsp_reputation_weight` runtime API call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum QueryBspReputationWeightError {
+    BspNotRegistered,
+}
+
 /// Error type for the `get_stake` runtime API call.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum GetStakeError {
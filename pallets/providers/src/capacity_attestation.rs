@@ -0,0 +1,260 @@
+//! Proof-of-capacity attestations for Backup Storage Providers.
+//!
+//! Staking a deposit proportional to the claimed capacity does not prove that a BSP actually has
+//! that much disk. To attest its capacity, an account goes through these steps:
+//! 1. It requests an attestation for the capacity it claims.
+//! 2. Once the randomness generated after the request is available, it draws the seed of its
+//!    "plot" from it. The seed is stored on-chain, and could not be known when requesting.
+//! 3. It builds the plot, sealing it with the seed: one leaf for every
+//!    [`Config::CapacityAttestationChunkSize`] of claimed capacity, where every leaf seals the one
+//!    before it, so the leaves can only be computed in order starting from the first one. It
+//!    commits to the Merkle root of the plot (padded with empty leaves up to the next power of two)
+//!    within [`Config::CapacityAttestationWindow`] blocks of drawing the seed.
+//! 4. Once the randomness for the commitment block is available, it is challenged on
+//!    [`Config::CapacityAttestationSpotChecks`] randomly chosen leaves. It answers with each of
+//!    them and the leaf before it, along with their Merkle proofs, within
+//!    [`Config::CapacityAttestationWindow`] blocks of committing.
+//!
+//! The answer is checked to prove that every challenged leaf is in the plot and seals the leaf
+//! before it. As every leaf depends on all the ones before it, a challenged leaf cannot be derived
+//! without recomputing the plot up to it, so answering in time requires keeping the plot stored,
+//! and a plot made up of leaves that do not seal each other is caught by the spot checks.
+//!
+//! The client side of the protocol is implemented by [`CapacityPlot`]: it generates the plot and
+//! answers the challenged leaves, which clients get from the `query_capacity_challenge` runtime
+//! API.
+//!
+//! [`Config::CapacityAttestationChunkSize`]: crate::Config::CapacityAttestationChunkSize
+//! [`Config::CapacityAttestationSpotChecks`]: crate::Config::CapacityAttestationSpotChecks
+//! [`Config::CapacityAttestationWindow`]: crate::Config::CapacityAttestationWindow
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::{ConstU32, H256};
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash},
+    BoundedVec, RuntimeDebug,
+};
+use sp_std::vec::Vec;
+
+/// Domain separator for the leaves of a plot.
+const PLOT_LEAF_DOMAIN: &[u8] = b"shplot";
+
+/// Domain separator for the derivation of the challenged leaves.
+const PLOT_CHALLENGE_DOMAIN: &[u8] = b"shplot-challenge";
+
+/// The maximum depth of a plot's Merkle tree, which is also the maximum amount of siblings in the
+/// proof of a leaf.
+pub const MAX_PLOT_DEPTH: u32 = 64;
+
+/// The Merkle proof of a leaf of a plot: its siblings from the leaf level up to the level right
+/// below the root.
+pub type PlotMerkleProof = BoundedVec<H256, ConstU32<MAX_PLOT_DEPTH>>;
+
+/// The proof of a challenged leaf of a plot, along with the leaf before it that it seals.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct PlotLeafProof {
+    /// The challenged leaf.
+    pub leaf: H256,
+    /// The Merkle proof of the challenged leaf.
+    pub siblings: PlotMerkleProof,
+    /// The leaf before the challenged one. Zero if the first leaf is challenged.
+    pub previous_leaf: H256,
+    /// The Merkle proof of the leaf before the challenged one. Empty if the first leaf is
+    /// challenged.
+    pub previous_siblings: PlotMerkleProof,
+}
+
+/// Returns the leaf at `index` of the plot of `account` sealed with `seed`, given the leaf before
+/// it (zero for the first leaf).
+pub fn plot_leaf(seed: &H256, account: &[u8], index: u64, previous_leaf: &H256) -> H256 {
+    BlakeTwo256::hash(&(PLOT_LEAF_DOMAIN, seed, account, index, previous_leaf).encode())
+}
+
+/// Returns the amount of leaves of a plot attesting `capacity`, one for every `chunk_size` of it
+/// (rounding up). A plot always has at least one leaf.
+pub fn plot_size(capacity: u64, chunk_size: u64) -> u64 {
+    let chunk_size = chunk_size.max(1);
+    (capacity / chunk_size + (capacity % chunk_size != 0) as u64).max(1)
+}
+
+/// Returns the depth of the Merkle tree of a plot with `plot_size` leaves.
+pub fn plot_depth(plot_size: u64) -> u32 {
+    plot_size
+        .checked_next_power_of_two()
+        .map(|size| size.trailing_zeros())
+        .unwrap_or(MAX_PLOT_DEPTH)
+}
+
+/// Returns the indexes of the leaves that a plot with `plot_size` leaves is challenged on, given
+/// the random `seed`.
+///
+/// The same leaf can be challenged more than once.
+pub fn challenged_leaf_indexes(seed: &[u8], plot_size: u64, spot_checks: u32) -> Vec<u64> {
+    (0..spot_checks)
+        .map(|i| {
+            let hash = BlakeTwo256::hash(&(PLOT_CHALLENGE_DOMAIN, seed, i).encode());
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&hash.as_bytes()[..8]);
+            u64::from_le_bytes(bytes) % plot_size.max(1)
+        })
+        .collect()
+}
+
+/// Verifies that `proof` proves that the leaf at `index` of the plot of `account` sealed with
+/// `seed`, which has `plot_size` leaves, is included under `root` and seals the leaf before it.
+pub fn verify_leaf_proof(
+    root: &H256,
+    seed: &H256,
+    account: &[u8],
+    plot_size: u64,
+    index: u64,
+    proof: &PlotLeafProof,
+) -> bool {
+    if index >= plot_size || !verify_inclusion(root, plot_size, index, &proof.leaf, &proof.siblings)
+    {
+        return false;
+    }
+
+    if index == 0 {
+        if proof.previous_leaf != H256::zero() {
+            return false;
+        }
+    } else if !verify_inclusion(
+        root,
+        plot_size,
+        index - 1,
+        &proof.previous_leaf,
+        &proof.previous_siblings,
+    ) {
+        return false;
+    }
+
+    proof.leaf == plot_leaf(seed, account, index, &proof.previous_leaf)
+}
+
+/// Verifies that `siblings` proves that `leaf` is at `index` of a plot with `plot_size` leaves
+/// whose root is `root`.
+fn verify_inclusion(
+    root: &H256,
+    plot_size: u64,
+    index: u64,
+    leaf: &H256,
+    siblings: &[H256],
+) -> bool {
+    if siblings.len() != plot_depth(plot_size) as usize {
+        return false;
+    }
+
+    let mut node = *leaf;
+    let mut position = index;
+    for sibling in siblings {
+        node = if position % 2 == 0 {
+            hash_pair(&node, sibling)
+        } else {
+            hash_pair(sibling, &node)
+        };
+        position /= 2;
+    }
+
+    &node == root
+}
+
+fn hash_pair(left: &H256, right: &H256) -> H256 {
+    BlakeTwo256::hash(&[left.as_bytes(), right.as_bytes()].concat())
+}
+
+/// A plot generated by a BSP to attest its capacity, keeping every level of its Merkle tree so
+/// that challenges can be answered right away.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapacityPlot {
+    /// The amount of (non-padding) leaves of the plot.
+    size: u64,
+    /// The levels of the Merkle tree, from the (padded) leaves to the root.
+    levels: Vec<Vec<H256>>,
+}
+
+#[cfg(feature = "std")]
+impl CapacityPlot {
+    /// Generates the plot of `account` sealed with `seed`, attesting `capacity` with one leaf for
+    /// every `chunk_size`.
+    pub fn generate(seed: &H256, account: &[u8], capacity: u64, chunk_size: u64) -> Self {
+        let size = plot_size(capacity, chunk_size);
+        let padded_size = 1u64 << plot_depth(size);
+
+        let mut level = Vec::with_capacity(padded_size as usize);
+        let mut previous_leaf = H256::zero();
+        for index in 0..size {
+            previous_leaf = plot_leaf(seed, account, index, &previous_leaf);
+            level.push(previous_leaf);
+        }
+        level.resize(padded_size as usize, H256::zero());
+
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            let next = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+            level = next;
+        }
+        levels.push(level);
+
+        Self { size, levels }
+    }
+
+    /// The root of the plot, which is what the BSP commits to on-chain.
+    pub fn root(&self) -> H256 {
+        self.levels
+            .last()
+            .expect("A plot always has a root level; qed")[0]
+    }
+
+    /// The amount of (non-padding) leaves of the plot.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the proof of the leaf at `index`, or `None` if it is out of the plot.
+    pub fn prove(&self, index: u64) -> Option<PlotLeafProof> {
+        if index >= self.size {
+            return None;
+        }
+
+        let (previous_leaf, previous_siblings) = match index.checked_sub(1) {
+            Some(previous_index) => (
+                self.levels[0][previous_index as usize],
+                self.siblings(previous_index),
+            ),
+            None => (H256::zero(), PlotMerkleProof::default()),
+        };
+
+        Some(PlotLeafProof {
+            leaf: self.levels[0][index as usize],
+            siblings: self.siblings(index),
+            previous_leaf,
+            previous_siblings,
+        })
+    }
+
+    /// Answers a challenge on the leaves at `indexes`, returning the proof of each of them in the
+    /// same order, or `None` if any of them is out of the plot.
+    pub fn answer_challenge(&self, indexes: &[u64]) -> Option<Vec<PlotLeafProof>> {
+        indexes.iter().map(|index| self.prove(*index)).collect()
+    }
+
+    fn siblings(&self, index: u64) -> PlotMerkleProof {
+        let mut position = index as usize;
+        let mut siblings = Vec::with_capacity(self.levels.len() - 1);
+        for level in &self.levels[..self.levels.len() - 1] {
+            siblings.push(level[position ^ 1]);
+            position /= 2;
+        }
+
+        siblings
+            .try_into()
+            .expect("A plot is at most MAX_PLOT_DEPTH levels deep; qed")
+    }
+}
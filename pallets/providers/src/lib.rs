@@ -8,6 +8,7 @@
 //! the network and get rewarded for it.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod capacity_attestation;
 pub mod types;
 mod utils;
 pub mod weights;
@@ -46,10 +47,15 @@ pub mod pallet {
     use frame_system::pallet_prelude::{BlockNumberFor, *};
     use scale_info::prelude::fmt::Debug;
     use shp_traits::{
-        FileMetadataInterface, PaymentStreamsInterface, ProofSubmittersInterface,
+        FileMetadataInterface, MutatePricePerGigaUnitPerTickInterface, OnProviderStatusChange,
+        PaymentEscrowInterface, PaymentStreamsInterface, ProofSubmittersInterface, ProviderStatus,
         ReadUserSolvencyInterface, StorageHubTickGetter,
     };
-    use sp_runtime::traits::{Bounded, CheckedDiv, ConvertBack, Hash};
+    use sp_core::H256;
+    use sp_runtime::{
+        traits::{Bounded, CheckedDiv, ConvertBack, Hash},
+        Perbill,
+    };
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
@@ -64,13 +70,22 @@ pub mod pallet {
         type ProvidersRandomness: Randomness<ProviderIdFor<Self>, BlockNumberFor<Self>>;
 
         /// Trait that allows the pallet to update payment streams of its Providers and Users
+        /// and to read the current price per giga-unit of data per tick.
         type PaymentStreams: PaymentStreamsInterface<
                 Balance = Self::NativeBalance,
                 AccountId = Self::AccountId,
                 ProviderId = ProviderIdFor<Self>,
                 Units = Self::StorageDataUnit,
                 TickNumber = BlockNumberFor<Self>,
-            > + ReadUserSolvencyInterface<AccountId = Self::AccountId>;
+            > + ReadUserSolvencyInterface<AccountId = Self::AccountId>
+            + MutatePricePerGigaUnitPerTickInterface<PricePerGigaUnitPerTick = BalanceOf<Self>>
+            + PaymentEscrowInterface<
+                AccountId = Self::AccountId,
+                ProviderId = ProviderIdFor<Self>,
+                EscrowId = Self::MerklePatriciaRoot,
+                Units = Self::StorageDataUnit,
+                TickNumber = BlockNumberFor<Self>,
+            >;
 
         /// The trait for stopping challenge cycles of providers.
         type ProofDealer: shp_traits::ProofsDealerInterface<ProviderId = ProviderIdFor<Self>>;
@@ -81,6 +96,10 @@ pub mod pallet {
             StorageDataUnit = Self::StorageDataUnit,
         >;
 
+        /// Hook called when a Provider becomes insolvent or is deregistered, so that other pallets can
+        /// cancel or reroute the operations that were waiting on it.
+        type OnProviderStatusChange: OnProviderStatusChange<ProviderIdFor<Self>>;
+
         /// Type to access the Balances pallet (using the fungible trait from frame_support)
         type NativeBalance: Inspect<Self::AccountId>
             + Mutate<Self::AccountId>
@@ -202,7 +221,8 @@ pub mod pallet {
             + One
             + CheckedAdd
             + Ord
-            + Bounded;
+            + Bounded
+            + AtLeast32BitUnsigned;
 
         /// Interface to get the current Storage Hub tick number.
         type StorageHubTickGetter: StorageHubTickGetter<TickNumber = BlockNumberFor<Self>>;
@@ -252,6 +272,10 @@ pub mod pallet {
         #[pallet::constant]
         type BucketNameLimit: Get<u32>;
 
+        /// Type that represents the byte limit of the encryption metadata of a bucket.
+        #[pallet::constant]
+        type EncryptionMetadataLimit: Get<u32>;
+
         /// The maximum amount of blocks after which a sign up request expires so the randomness cannot be chosen
         #[pallet::constant]
         type MaxBlocksForRandomness: Get<BlockNumberFor<Self>>;
@@ -265,9 +289,28 @@ pub mod pallet {
         type DefaultMerkleRoot: Get<Self::MerklePatriciaRoot>;
 
         /// The slash factor deducted from a Storage Provider's deposit for every single storage proof they fail to provide.
+        ///
+        /// This is the amount for Storage Providers storing exactly [`Config::SlashReferenceDataSize`] of data.
+        /// It is scaled proportionally to the data stored by the Storage Provider, so it is lower for those
+        /// storing less than that and higher for those storing more.
         #[pallet::constant]
         type SlashAmountPerMaxFileSize: Get<BalanceOf<Self>>;
 
+        /// The amount of data for which a failed proof submission is slashed exactly
+        /// [`Config::SlashAmountPerMaxFileSize`].
+        #[pallet::constant]
+        type SlashReferenceDataSize: Get<StorageDataUnit<Self>>;
+
+        /// The extra slash applied for every proof submission deadline missed in a row after the
+        /// first one, as a fraction of the base slash amount.
+        #[pallet::constant]
+        type SlashEscalationPerMissedDeadline: Get<Perbill>;
+
+        /// The maximum multiplier that can be applied to the base slash amount due to consecutive
+        /// missed proof submission deadlines.
+        #[pallet::constant]
+        type MaxSlashEscalationMultiplier: Get<u32>;
+
         /// Starting reputation weight for a newly registered BSP.
         #[pallet::constant]
         type StartingReputationWeight: Get<Self::ReputationWeightType>;
@@ -303,6 +346,62 @@ pub mod pallet {
         /// Maximum number of expired items (per type) to clean up in a single block.
         #[pallet::constant]
         type MaxExpiredItemsInBlock: Get<u32>;
+
+        /// Maximum number of capacity utilization samples kept on-chain.
+        ///
+        /// Once this limit is reached, the oldest sample is discarded to make room for the new one.
+        #[pallet::constant]
+        type MaxCapacityUtilizationSamples: Get<u32>;
+
+        /// The amount of Storage Hub ticks between two consecutive capacity utilization samples.
+        ///
+        /// Setting this to zero disables sampling.
+        #[pallet::constant]
+        type CapacityUtilizationSamplingPeriod: Get<StorageHubTickNumber<Self>>;
+
+        /// Whether BSPs must attest their capacity (see [`crate::capacity_attestation`]) before
+        /// signing up or increasing it.
+        #[pallet::constant]
+        type RequireCapacityAttestation: Get<bool>;
+
+        /// The amount of capacity that each leaf of a capacity attestation plot accounts for.
+        #[pallet::constant]
+        type CapacityAttestationChunkSize: Get<StorageDataUnit<Self>>;
+
+        /// The amount of leaves of a capacity attestation plot that are challenged.
+        #[pallet::constant]
+        type CapacityAttestationSpotChecks: Get<u32>;
+
+        /// The amount of blocks that a BSP has to commit to its capacity attestation plot, counting from
+        /// the block in which it drew the plot's seed, and to answer its challenge, counting from the block
+        /// in which it committed to it.
+        #[pallet::constant]
+        type CapacityAttestationWindow: Get<BlockNumberFor<Self>>;
+
+        /// The amount of blocks that a Storage Provider has to offload its data after requesting to decrease
+        /// its capacity, before it can confirm the decrease.
+        #[pallet::constant]
+        type CapacityDecreaseGracePeriod: Get<BlockNumberFor<Self>>;
+
+        /// The amount of proofs that a BSP has to submit in a row, without becoming slashable, to gain
+        /// [`Config::ReputationWeightIncrease`] reputation weight.
+        ///
+        /// Setting this to zero disables the growth of reputation weights.
+        #[pallet::constant]
+        type ReputationGrowthStreak: Get<u32>;
+
+        /// The reputation weight that a BSP gains every [`Config::ReputationGrowthStreak`] proofs submitted in a row.
+        #[pallet::constant]
+        type ReputationWeightIncrease: Get<Self::ReputationWeightType>;
+
+        /// The maximum reputation weight that a BSP can reach by submitting proofs.
+        #[pallet::constant]
+        type MaxReputationWeight: Get<Self::ReputationWeightType>;
+
+        /// The fraction of the reputation weight earned by a BSP (i.e. above [`Config::StartingReputationWeight`])
+        /// that it loses every time it is slashed.
+        #[pallet::constant]
+        type ReputationDecayOnSlash: Get<Perbill>;
     }
 
     #[pallet::pallet]
@@ -360,6 +459,18 @@ pub mod pallet {
     #[pallet::storage]
     pub type Buckets<T: Config> = StorageMap<_, Blake2_128Concat, BucketId<T>, Bucket<T>>;
 
+    /// The mapping from a BucketId to the metadata describing how that bucket's contents are encrypted.
+    ///
+    /// Set by the owner of the bucket, so that the users reading its files know how to decrypt them.
+    /// The runtime does not interpret it.
+    ///
+    /// This storage is updated in:
+    /// - [update_bucket_encryption_metadata](shp_traits::MutateBucketsInterface::update_bucket_encryption_metadata), which sets or removes the entry of the corresponding bucket.
+    /// - [remove_root_bucket](shp_traits::MutateBucketsInterface::remove_root_bucket), which removes the entry of the corresponding bucket.
+    #[pallet::storage]
+    pub type BucketEncryptionMetadata<T: Config> =
+        StorageMap<_, Blake2_128Concat, BucketId<T>, EncryptionMetadata<T>>;
+
     /// The double mapping from a MainStorageProviderId to a BucketIds.
     ///
     /// This is used to efficiently retrieve the list of buckets that a Main Storage Provider is currently storing.
@@ -459,6 +570,38 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Double mapping from a [`MainStorageProviderId`] to the [`ValuePropositionVersion`] of each of its value propositions
+    /// that were published as a new version of a previous one.
+    ///
+    /// Value propositions not present in this mapping are the first version of their own lineage.
+    #[pallet::storage]
+    pub type ValuePropositionVersions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        MainStorageProviderId<T>,
+        Blake2_128Concat,
+        ValuePropIdFor<T>,
+        ValuePropositionVersion<T>,
+        OptionQuery,
+    >;
+
+    /// Double mapping from a [`MainStorageProviderId`] and the lineage of a value proposition (the ID of its first version)
+    /// to the ID of its latest version.
+    ///
+    /// Only lineages with more than one version are present in this mapping. New buckets can only be created with the latest
+    /// version of a value proposition, while existing buckets keep the version they were created with until their owner
+    /// migrates them.
+    #[pallet::storage]
+    pub type LatestValuePropositionVersions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        MainStorageProviderId<T>,
+        Blake2_128Concat,
+        ValuePropIdFor<T>,
+        ValuePropIdFor<T>,
+        OptionQuery,
+    >;
+
     /// Storage providers currently awaited for to top up their deposit (providers whom have been slashed and as
     /// a result have a capacity deficit, i.e. their capacity is below their used capacity).
     ///
@@ -514,6 +657,100 @@ pub mod pallet {
     pub type InsolventProviders<T: Config> =
         StorageMap<_, Blake2_128Concat, StorageProviderId<T>, ()>;
 
+    /// The latest samples of the global BSP capacity utilization and storage price, ordered from oldest to newest.
+    ///
+    /// A new sample is taken in the `on_poll` hook every [`Config::CapacityUtilizationSamplingPeriod`] ticks.
+    /// At most [`Config::MaxCapacityUtilizationSamples`] samples are kept, discarding the oldest one when full.
+    ///
+    /// This is exposed through the `capacity_utilization` runtime API so that external tools can follow how
+    /// the system utilisation (and with it, the price of storage) evolves over time.
+    #[pallet::storage]
+    pub type CapacityUtilizationSamples<T: Config> =
+        StorageValue<_, CapacityUtilizationSamplesFor<T>, ValueQuery>;
+
+    /// The mapping from an AccountId to the capacity attestation that it has requested and not completed yet.
+    ///
+    /// This storage is updated in:
+    /// - [request_capacity_attestation](crate::dispatchables::request_capacity_attestation), which adds (or replaces)
+    /// an entry.
+    /// - [draw_capacity_plot_seed](crate::dispatchables::draw_capacity_plot_seed) and
+    /// [commit_capacity_plot](crate::dispatchables::commit_capacity_plot), which move it to its next stage.
+    /// - [answer_capacity_challenge](crate::dispatchables::answer_capacity_challenge), which removes it once answered.
+    #[pallet::storage]
+    pub type PendingCapacityAttestations<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, PendingCapacityAttestation<T>>;
+
+    /// The mapping from an AccountId to the capacity that it has successfully attested.
+    ///
+    /// When [`Config::RequireCapacityAttestation`] is enabled, a BSP can only sign up or increase its capacity
+    /// up to this amount.
+    ///
+    /// An attested capacity is only valid for the next capacity the BSP is set up with, so it is removed once the BSP
+    /// signs up or changes its capacity (in any way), and when the BSP is removed.
+    ///
+    /// This storage is updated in:
+    /// - [answer_capacity_challenge](crate::dispatchables::answer_capacity_challenge), which sets the attested capacity.
+    /// - [confirm_sign_up](crate::dispatchables::confirm_sign_up), [change_capacity](crate::dispatchables::change_capacity),
+    /// [confirm_capacity_decrease](crate::dispatchables::confirm_capacity_decrease),
+    /// [bsp_sign_off](crate::dispatchables::bsp_sign_off) and [delete_provider](crate::dispatchables::delete_provider),
+    /// as well as when the capacity of the BSP is reduced after being slashed or topping up its deposit, which remove it.
+    #[pallet::storage]
+    pub type AttestedCapacities<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, StorageDataUnit<T>>;
+
+    /// The mapping from a Storage Provider to the capacity decrease that it has requested and not confirmed yet.
+    ///
+    /// This storage is updated in:
+    /// - [request_capacity_decrease](crate::dispatchables::request_capacity_decrease), which adds an entry.
+    /// - [confirm_capacity_decrease](crate::dispatchables::confirm_capacity_decrease), which removes it, either applying
+    /// or cancelling the decrease.
+    /// - [msp_sign_off](crate::dispatchables::msp_sign_off) and [bsp_sign_off](crate::dispatchables::bsp_sign_off), which
+    /// remove the entry of the signed off Storage Provider, if any.
+    #[pallet::storage]
+    pub type PendingCapacityDecreases<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, PendingCapacityDecrease<T>>;
+
+    /// The set of Storage Providers that have been suspended by governance.
+    ///
+    /// Suspended Storage Providers keep their current duties, but can't take on new work until they are resumed.
+    ///
+    /// This storage is updated in:
+    /// - [suspend_provider](crate::dispatchables::suspend_provider), which adds an entry.
+    /// - [resume_provider](crate::dispatchables::resume_provider), which removes it.
+    /// - [msp_sign_off](crate::dispatchables::msp_sign_off), [bsp_sign_off](crate::dispatchables::bsp_sign_off) and
+    /// [delete_provider](crate::dispatchables::delete_provider), which remove the entry of the removed Storage Provider, if any.
+    #[pallet::storage]
+    pub type SuspendedProviders<T: Config> = StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ()>;
+
+    /// The set of Storage Providers that have scheduled their sign off.
+    ///
+    /// Exiting Storage Providers keep their current duties until they sign off, but can't take on new work.
+    ///
+    /// This storage is updated in:
+    /// - [schedule_sign_off](crate::dispatchables::schedule_sign_off), which adds an entry.
+    /// - [cancel_scheduled_sign_off](crate::dispatchables::cancel_scheduled_sign_off), which removes it.
+    /// - [msp_sign_off](crate::dispatchables::msp_sign_off), [bsp_sign_off](crate::dispatchables::bsp_sign_off) and
+    /// [delete_provider](crate::dispatchables::delete_provider), which remove the entry of the removed Storage Provider, if any.
+    #[pallet::storage]
+    pub type ExitingProviders<T: Config> = StorageMap<_, Blake2_128Concat, ProviderIdFor<T>, ()>;
+
+    /// The amount of proofs that each BSP has submitted in a row since it was last slashed.
+    ///
+    /// Every [`Config::ReputationGrowthStreak`] proofs in a row, the reputation weight of the BSP increases.
+    ///
+    /// This storage is updated in:
+    /// - The `on_poll` hook, which accounts for the proofs submitted by BSPs in the previous tick.
+    /// - [slash](crate::dispatchables::slash), which resets the streak of the slashed BSP.
+    /// - [bsp_sign_off](crate::dispatchables::bsp_sign_off) and [delete_provider](crate::dispatchables::delete_provider),
+    /// which remove the entry of the removed BSP, if any.
+    #[pallet::storage]
+    pub type BspProofSubmissionStreaks<T: Config> =
+        StorageMap<_, Blake2_128Concat, BackupStorageProviderId<T>, u32, ValueQuery>;
+
+    /// The last tick whose proof submitters have been accounted for in the reputation weights of BSPs.
+    #[pallet::storage]
+    pub type LastReputationUpdateTick<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
     // Events & Errors:
 
     /// The events that can be emitted by this pallet
@@ -585,10 +822,50 @@ pub mod pallet {
             next_block_when_change_allowed: BlockNumberFor<T>,
         },
 
+        /// Event emitted when a SP has requested to decrease its capacity. Provides information about the capacity
+        /// it wants to decrease to, and the block from which it can confirm the decrease.
+        CapacityDecreaseRequested {
+            who: T::AccountId,
+            provider_id: StorageProviderId<T>,
+            current_capacity: StorageDataUnit<T>,
+            new_capacity: StorageDataUnit<T>,
+            confirmable_at: BlockNumberFor<T>,
+        },
+
+        /// Event emitted when a SP confirmed a capacity decrease but it was cancelled because its used capacity
+        /// still exceeded the requested capacity.
+        CapacityDecreaseCancelled {
+            who: T::AccountId,
+            provider_id: StorageProviderId<T>,
+            new_capacity: StorageDataUnit<T>,
+            used_capacity: StorageDataUnit<T>,
+        },
+
+        /// Event emitted when the status of a SP changed because it was suspended or resumed by governance, or because
+        /// it scheduled or cancelled its sign off.
+        ///
+        /// `status` is the status of the SP after the change, as returned by the `get_provider_status` runtime API.
+        /// Insolvency is signaled by the `AwaitingTopUp` and `ProviderInsolvent` events instead.
+        ProviderStatusChanged {
+            provider_id: ProviderIdFor<T>,
+            status: ProviderStatus,
+        },
+
+        /// Event emitted when the reputation weight of a BSP changed, either because it submitted enough proofs in a row
+        /// or because it was slashed.
+        BspReputationWeightChanged {
+            bsp_id: BackupStorageProviderId<T>,
+            old_weight: ReputationWeightType<T>,
+            new_weight: ReputationWeightType<T>,
+        },
+
         /// Event emitted when a SP has been slashed.
         Slashed {
             provider_id: ProviderIdFor<T>,
             amount: BalanceOf<T>,
+            accrued_failed_proof_submissions: u32,
+            consecutive_missed_deadlines: u32,
+            used_capacity: StorageDataUnit<T>,
         },
 
         /// Event emitted when a provider has been slashed and they have reached a capacity deficit (i.e. the provider's capacity fell below their used capacity)
@@ -643,11 +920,60 @@ pub mod pallet {
             value_prop_id: ValuePropIdFor<T>,
         },
 
+        /// Event emitted when an MSP publishes a new version of one of its value propositions.
+        ///
+        /// The previous version is made unavailable, but the buckets using it keep it until their owners migrate them.
+        ValuePropVersionPublished {
+            msp_id: MainStorageProviderId<T>,
+            previous_value_prop_id: ValuePropIdFor<T>,
+            value_prop_id: ValuePropIdFor<T>,
+            value_prop: ValueProposition<T>,
+            version: ValuePropositionVersion<T>,
+        },
+
+        /// Event emitted when a bucket is migrated to the latest version of its value proposition.
+        BucketValuePropMigrated {
+            bucket_id: BucketId<T>,
+            msp_id: MainStorageProviderId<T>,
+            previous_value_prop_id: ValuePropIdFor<T>,
+            value_prop_id: ValuePropIdFor<T>,
+        },
+
         /// Event emitted when an MSP has been deleted.
         MspDeleted { provider_id: ProviderIdFor<T> },
 
         /// Event emitted when a BSP has been deleted.
         BspDeleted { provider_id: ProviderIdFor<T> },
+
+        /// Event emitted when an account has requested to attest its capacity.
+        CapacityAttestationRequested {
+            who: T::AccountId,
+            capacity: StorageDataUnit<T>,
+        },
+
+        /// Event emitted when the seed of the capacity attestation plot of an account has been drawn. Provides
+        /// the block number after which the account can no longer commit to the plot.
+        CapacityPlotSeedDrawn {
+            who: T::AccountId,
+            seed: H256,
+            deadline: BlockNumberFor<T>,
+        },
+
+        /// Event emitted when an account has committed to a capacity attestation plot. Provides the block
+        /// number after which the challenge has to be answered.
+        CapacityPlotCommitted {
+            who: T::AccountId,
+            plot_root: H256,
+            capacity: StorageDataUnit<T>,
+            deadline: BlockNumberFor<T>,
+        },
+
+        /// Event emitted when an account has answered the challenge of its capacity attestation, attesting
+        /// the given capacity.
+        CapacityAttested {
+            who: T::AccountId,
+            capacity: StorageDataUnit<T>,
+        },
     }
 
     /// The errors that can be thrown by this pallet to inform users about what went wrong
@@ -678,6 +1004,10 @@ pub mod pallet {
         StorageStillInUse,
         /// Error thrown when a user tries to sign off as a BSP but the sign off period has not passed yet.
         SignOffPeriodNotPassed,
+        /// Error thrown when a SP tries to schedule its sign off but it has already scheduled it.
+        SignOffAlreadyScheduled,
+        /// Error thrown when a SP tries to cancel its scheduled sign off but it has not scheduled it.
+        SignOffNotScheduled,
 
         // Randomness errors:
         /// Error thrown when a user tries to confirm a sign up but the randomness is too fresh to be used yet.
@@ -698,6 +1028,20 @@ pub mod pallet {
         NewUsedCapacityExceedsStorageCapacity,
         /// Deposit too low to determine capacity.
         DepositTooLow,
+        /// Error thrown when a SP requests to decrease its capacity to a value that is not less than its current capacity.
+        NewCapacityNotLessThanCurrentCapacity,
+        /// Error thrown when a SP requests to decrease its capacity while it already has a pending capacity decrease.
+        CapacityDecreaseAlreadyRequested,
+        /// Error thrown when a SP tries to confirm a capacity decrease that it has not requested.
+        CapacityDecreaseNotRequested,
+        /// Error thrown when a SP tries to confirm a capacity decrease before its grace period has passed.
+        CapacityDecreaseGracePeriodNotPassed,
+
+        // Suspension errors:
+        /// Error thrown when trying to suspend a SP that is already suspended.
+        ProviderAlreadySuspended,
+        /// Error thrown when trying to resume a SP that is not suspended.
+        ProviderNotSuspended,
 
         // General errors:
         /// Error thrown when a user tries to interact as a SP but is not registered as a MSP or BSP.
@@ -736,6 +1080,10 @@ pub mod pallet {
         ValuePropositionAlreadyExists,
         /// Error thrown when a value proposition is not available.
         ValuePropositionNotAvailable,
+        /// Error thrown when a value proposition has been superseded by a newer version.
+        ValuePropositionSuperseded,
+        /// Error thrown when a bucket is already using the latest version of its value proposition.
+        BucketAlreadyOnLatestValueProposition,
         /// Error thrown when a fixed payment stream is not found.
         FixedRatePaymentStreamNotFound,
         /// Error thrown when changing the MSP of a bucket to the same assigned MSP.
@@ -760,6 +1108,24 @@ pub mod pallet {
         InvalidEncodedAccountId,
         /// Error thrown when trying to update a payment stream that does not exist.
         PaymentStreamNotFound,
+
+        // Capacity attestation errors:
+        /// Error thrown when a BSP tries to sign up or increase its capacity beyond the capacity it has attested.
+        CapacityNotAttested,
+        /// Error thrown when going on with a capacity attestation without having requested one.
+        CapacityAttestationNotFound,
+        /// Error thrown when drawing the seed of a capacity attestation plot, committing to it or answering its
+        /// challenge after the time to do so has passed.
+        CapacityAttestationExpired,
+        /// Error thrown when drawing the seed of a capacity attestation plot that was already drawn.
+        CapacityPlotSeedAlreadyDrawn,
+        /// Error thrown when committing to a capacity attestation plot before drawing its seed, or after committing
+        /// to it already.
+        CapacityPlotSeedNotDrawn,
+        /// Error thrown when answering a capacity challenge without having committed to a capacity attestation plot.
+        CapacityPlotNotCommitted,
+        /// Error thrown when the proofs answering a capacity challenge are invalid or do not match the challenged leaves.
+        InvalidCapacityAttestationProof,
     }
 
     /// This enum holds the HoldReasons for this pallet, allowing the runtime to identify each held balance with different reasons separately
@@ -1450,6 +1816,364 @@ pub mod pallet {
 
             Ok(Pays::No.into())
         }
+
+        /// Dispatchable extrinsic that allows an account to request to attest that it has `capacity` of storage
+        /// available.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// Parameters:
+        /// - `capacity`: The capacity to attest.
+        ///
+        /// Requesting replaces any previous pending attestation of the signer. Once the randomness for this block is
+        /// available, the signer has to draw the seed of its plot with [draw_capacity_plot_seed](crate::dispatchables::draw_capacity_plot_seed)
+        /// before [`Config::MaxBlocksForRandomness`] blocks have passed. See [`crate::capacity_attestation`] for the
+        /// whole protocol.
+        ///
+        /// Emits `CapacityAttestationRequested` event when successful.
+        #[pallet::call_index(16)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn request_capacity_attestation(
+            origin: OriginFor<T>,
+            capacity: StorageDataUnit<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            Self::do_request_capacity_attestation(&who, capacity)?;
+
+            Self::deposit_event(Event::<T>::CapacityAttestationRequested { who, capacity });
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows an account to draw the seed of the plot of the capacity attestation it
+        /// requested, from the randomness generated after requesting it.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// The signer then has to build its plot sealed with the seed, as [`CapacityPlot`](crate::capacity_attestation::CapacityPlot)
+        /// does, and commit to it with [commit_capacity_plot](crate::dispatchables::commit_capacity_plot) before
+        /// [`Config::CapacityAttestationWindow`] blocks have passed.
+        ///
+        /// Emits `CapacityPlotSeedDrawn` event when successful.
+        #[pallet::call_index(25)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn draw_capacity_plot_seed(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            let (seed, deadline) = Self::do_draw_capacity_plot_seed(&who)?;
+
+            Self::deposit_event(Event::<T>::CapacityPlotSeedDrawn {
+                who,
+                seed,
+                deadline,
+            });
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows an account to commit to the plot of the capacity attestation it
+        /// requested.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// Parameters:
+        /// - `plot_root`: The root of the plot of the signer for the capacity to attest, sealed with the seed drawn
+        /// with [draw_capacity_plot_seed](crate::dispatchables::draw_capacity_plot_seed), as built by
+        /// [`CapacityPlot`](crate::capacity_attestation::CapacityPlot).
+        ///
+        /// Once the randomness for this block is available, the signer has to answer the challenge with
+        /// [answer_capacity_challenge](crate::dispatchables::answer_capacity_challenge) before
+        /// [`Config::CapacityAttestationWindow`] blocks have passed.
+        ///
+        /// Emits `CapacityPlotCommitted` event when successful.
+        #[pallet::call_index(26)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn commit_capacity_plot(
+            origin: OriginFor<T>,
+            plot_root: H256,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            let (capacity, deadline) = Self::do_commit_capacity_plot(&who, plot_root)?;
+
+            Self::deposit_event(Event::<T>::CapacityPlotCommitted {
+                who,
+                plot_root,
+                capacity,
+                deadline,
+            });
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows an account to answer the challenge of the capacity attestation it
+        /// committed to.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        ///
+        /// Parameters:
+        /// - `answer`: The proof of each challenged leaf of the plot and the leaf before it, in the order they are
+        /// challenged in, as returned by [`CapacityPlot::answer_challenge`](crate::capacity_attestation::CapacityPlot::answer_challenge) for the
+        /// leaves returned by the `query_capacity_challenge` runtime API.
+        ///
+        /// If every proof is valid, the committed capacity becomes the signer's attested capacity, which lets it sign
+        /// up as a BSP or increase its capacity up to that amount when [`Config::RequireCapacityAttestation`] is enabled.
+        ///
+        /// Emits `CapacityAttested` event when successful.
+        #[pallet::call_index(17)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(2))]
+        pub fn answer_capacity_challenge(
+            origin: OriginFor<T>,
+            answer: CapacityChallengeAnswer<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            let capacity = Self::do_answer_capacity_challenge(&who, answer)?;
+
+            Self::deposit_event(Event::<T>::CapacityAttested { who, capacity });
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic only callable by an MSP that allows it to publish a new version of one of its
+        /// value propositions.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        /// The origin must be the account of the MSP that owns the value proposition.
+        ///
+        /// Parameters:
+        /// - `value_prop_id`: The ID of the latest version of the value proposition to supersede.
+        /// - `price_per_giga_unit_of_data_per_block`, `commitment` and `bucket_data_limit`: The terms of the new version.
+        ///
+        /// The previous version is made unavailable, so new buckets can only be created with the new one. Existing buckets
+        /// keep the version they were created with (they are grandfathered) until their owners migrate them to the latest
+        /// version.
+        ///
+        /// Emits `ValuePropVersionPublished` event when successful.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::add_value_prop() + T::DbWeight::get().reads_writes(2, 3))]
+        pub fn publish_value_prop_version(
+            origin: OriginFor<T>,
+            value_prop_id: ValuePropIdFor<T>,
+            price_per_giga_unit_of_data_per_block: BalanceOf<T>,
+            commitment: Commitment<T>,
+            bucket_data_limit: StorageDataUnit<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            let (msp_id, value_prop, version) = Self::do_publish_value_prop_version(
+                &who,
+                value_prop_id,
+                price_per_giga_unit_of_data_per_block,
+                commitment,
+                bucket_data_limit,
+            )?;
+
+            // Emit event
+            Self::deposit_event(Event::<T>::ValuePropVersionPublished {
+                msp_id,
+                previous_value_prop_id: value_prop_id,
+                value_prop_id: value_prop.derive_id(),
+                value_prop,
+                version,
+            });
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a Storage Provider to request to decrease its capacity, even below the
+        /// storage it currently uses.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        /// The origin must be the account of the Storage Provider.
+        ///
+        /// Parameters:
+        /// - `new_capacity`: The total amount of data that the Storage Provider wants to be able to store after the decrease.
+        ///
+        /// Unlike [change_capacity](crate::dispatchables::change_capacity), the capacity is not changed right away: the
+        /// Storage Provider has [`Config::CapacityDecreaseGracePeriod`] blocks to offload the data above `new_capacity`,
+        /// after which it can apply the decrease with [confirm_capacity_decrease](crate::dispatchables::confirm_capacity_decrease).
+        ///
+        /// Emits `CapacityDecreaseRequested` event when successful.
+        #[pallet::call_index(19)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 1))]
+        pub fn request_capacity_decrease(
+            origin: OriginFor<T>,
+            new_capacity: StorageDataUnit<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            let (provider_id, current_capacity, confirmable_at) =
+                Self::do_request_capacity_decrease(&who, new_capacity)?;
+
+            // Emit the corresponding event
+            Self::deposit_event(Event::<T>::CapacityDecreaseRequested {
+                who,
+                provider_id,
+                current_capacity,
+                new_capacity,
+                confirmable_at,
+            });
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a Storage Provider to apply the capacity decrease it requested, once
+        /// its grace period has passed.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        /// The origin must be the account of the Storage Provider.
+        ///
+        /// The pending decrease is removed either way: if the Storage Provider still uses more storage than the requested
+        /// capacity, the decrease is cancelled and its capacity is left as is. Otherwise, its capacity is changed as in
+        /// [change_capacity](crate::dispatchables::change_capacity), releasing the corresponding deposit.
+        ///
+        /// Emits `CapacityChanged` event when the decrease is applied, or `CapacityDecreaseCancelled` when it is cancelled.
+        #[pallet::call_index(20)]
+        #[pallet::weight({
+			let weight_msp_less_deposit = T::WeightInfo::change_capacity_msp_less_deposit();
+			let weight_bsp_less_deposit = T::WeightInfo::change_capacity_bsp_less_deposit();
+			weight_msp_less_deposit
+				.max(weight_bsp_less_deposit)
+				.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		})]
+        pub fn confirm_capacity_decrease(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            match Self::do_confirm_capacity_decrease(&who)? {
+                CapacityDecreaseOutcome::Applied {
+                    provider_id,
+                    old_capacity,
+                    new_capacity,
+                } => Self::deposit_event(Event::<T>::CapacityChanged {
+                    who,
+                    provider_id,
+                    old_capacity,
+                    new_capacity,
+                    next_block_when_change_allowed: frame_system::Pallet::<T>::block_number()
+                        + T::MinBlocksBetweenCapacityChanges::get(),
+                }),
+                CapacityDecreaseOutcome::Cancelled {
+                    provider_id,
+                    new_capacity,
+                    used_capacity,
+                } => Self::deposit_event(Event::<T>::CapacityDecreaseCancelled {
+                    who,
+                    provider_id,
+                    new_capacity,
+                    used_capacity,
+                }),
+            }
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a Storage Provider to announce that it will sign off.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        /// The origin must be the account of the Storage Provider.
+        ///
+        /// The Storage Provider is marked as [`ProviderStatus::Exiting`]: it keeps its current duties (e.g. submitting
+        /// proofs for the files it stores), but can't take on new work, so it can offload its data and then sign off with
+        /// [msp_sign_off](crate::dispatchables::msp_sign_off) or [bsp_sign_off](crate::dispatchables::bsp_sign_off).
+        ///
+        /// Emits `ProviderStatusChanged` event when successful.
+        #[pallet::call_index(21)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 1))]
+        pub fn schedule_sign_off(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            let provider_id = Self::do_schedule_sign_off(&who)?;
+
+            // Emit the corresponding event
+            Self::deposit_provider_status_changed(&provider_id);
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows a Storage Provider to cancel its scheduled sign off, so it can take on
+        /// new work again.
+        ///
+        /// The dispatch origin for this call must be Signed.
+        /// The origin must be the account of the Storage Provider.
+        ///
+        /// Emits `ProviderStatusChanged` event when successful.
+        #[pallet::call_index(22)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 1))]
+        pub fn cancel_scheduled_sign_off(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Execute checks and logic, update storage
+            let provider_id = Self::do_cancel_scheduled_sign_off(&who)?;
+
+            // Emit the corresponding event
+            Self::deposit_provider_status_changed(&provider_id);
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows governance to suspend a Storage Provider.
+        ///
+        /// The dispatch origin for this call must be Root.
+        ///
+        /// The Storage Provider is marked as [`ProviderStatus::Suspended`]: it keeps its current duties, but can't take
+        /// on new work until it is resumed with [resume_provider](crate::dispatchables::resume_provider).
+        ///
+        /// Emits `ProviderStatusChanged` event when successful.
+        #[pallet::call_index(23)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 1))]
+        pub fn suspend_provider(
+            origin: OriginFor<T>,
+            provider_id: ProviderIdFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
+
+            // Execute checks and logic, update storage
+            Self::do_suspend_provider(&provider_id)?;
+
+            // Emit the corresponding event
+            Self::deposit_provider_status_changed(&provider_id);
+
+            Ok(().into())
+        }
+
+        /// Dispatchable extrinsic that allows governance to resume a suspended Storage Provider.
+        ///
+        /// The dispatch origin for this call must be Root.
+        ///
+        /// Emits `ProviderStatusChanged` event when successful.
+        #[pallet::call_index(24)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 1))]
+        pub fn resume_provider(
+            origin: OriginFor<T>,
+            provider_id: ProviderIdFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            // Check that the extrinsic was sent with root origin.
+            ensure_root(origin)?;
+
+            // Execute checks and logic, update storage
+            Self::do_resume_provider(&provider_id)?;
+
+            // Emit the corresponding event
+            Self::deposit_provider_status_changed(&provider_id);
+
+            Ok(().into())
+        }
     }
 
     #[pallet::hooks]
@@ -1457,6 +2181,10 @@ pub mod pallet {
     where
         u32: TryFrom<BlockNumberFor<T>>,
     {
+        fn on_poll(_n: BlockNumberFor<T>, weight: &mut WeightMeter) {
+            Self::do_on_poll(weight);
+        }
+
         fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
             let mut meter = WeightMeter::with_limit(remaining_weight);
             Self::do_on_idle(&mut meter);
@@ -8,7 +8,7 @@ use frame_support::{
     BoundedBTreeSet,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
-use pallet_proofs_dealer::SlashableProviders;
+use pallet_proofs_dealer::{ConsecutiveMissedDeadlines, SlashableProviders};
 use pallet_randomness::GetBabeData;
 use shp_file_metadata::FileMetadata;
 use shp_traits::{
@@ -18,6 +18,7 @@ use shp_traits::{
 use shp_treasury_funding::NoCutTreasuryCutCalculator;
 use sp_core::{hashing::blake2_256, ConstU128, ConstU32, ConstU64, Get, Hasher, H256};
 use sp_runtime::{
+    testing::TestSignature,
     traits::{BlakeTwo256, BlockNumberProvider, Convert, ConvertBack, IdentityLookup},
     BuildStorage, DispatchError, Perbill, SaturatedConversion,
 };
@@ -198,6 +199,14 @@ impl pallet_proofs_dealer::Config for Test {
     type BlockFullnessHeadroom = BlockFullnessHeadroom;
     type MinNotFullBlocksRatio = MinNotFullBlocksRatio;
     type MaxSlashableProvidersPerTick = ConstU32<100>;
+    type MaxCatchUpTicksPerBlock = ConstU32<10>;
+    type MissedDeadlinesAllowance = ConstU32<0>;
+    type MissedDeadlineWarningWindow = ConstU64<100>;
+    type MaxProofSize = ConstU32<{ 1024 * 1024 }>;
+    type MaxProofsPerBatch = ConstU32<5>;
+    type OffchainSignature = TestSignature;
+    type OffchainPublic = <TestSignature as sp_runtime::traits::Verify>::Signer;
+    type UnsignedProofPriority = ConstU64<{ u64::MAX }>;
 }
 
 // Converter from the Balance type to the BlockNumber type for math.
@@ -283,6 +292,10 @@ where
 }
 
 // Payment streams pallet:
+parameter_types! {
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(5);
+}
+
 impl pallet_payment_streams::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -298,6 +311,10 @@ impl pallet_payment_streams::Config for Test {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = ();
+    type MaxOraclePriceAge = ConstU64<10>;
+    type OraclePriceAdjustmentPeriod = ConstU64<10>;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 // Converter from the BlockNumber type to the Balance type for math
 pub struct BlockNumberToBalance;
@@ -337,6 +354,12 @@ impl shp_traits::StorageHubTickGetter for MockStorageHubTickGetter {
 }
 
 // Storage providers pallet:
+parameter_types! {
+    pub const SlashEscalationPerMissedDeadline: Perbill = Perbill::from_percent(10);
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
+    pub static RequireCapacityAttestation: bool = false;
+}
+
 impl crate::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
@@ -344,6 +367,7 @@ impl crate::Config for Test {
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type FileMetadataManager = MockFileMetadataManager;
+    type OnProviderStatusChange = ();
     type StorageDataUnit = StorageDataUnit;
     type StorageDataUnitAndBalanceConvert = StorageDataUnitAndBalanceConverter;
     type SpCount = u32;
@@ -369,16 +393,31 @@ impl crate::Config for Test {
     type MaxProtocols = ConstU32<100>;
     type BucketDeposit = ConstU128<10>;
     type BucketNameLimit = ConstU32<100>;
+    type EncryptionMetadataLimit = ConstU32<256>;
     type MaxBlocksForRandomness = ConstU64<{ EPOCH_DURATION_IN_BLOCKS * 2 }>;
     type MinBlocksBetweenCapacityChanges = ConstU64<10>;
     type DefaultMerkleRoot = DefaultMerkleRoot<LayoutV1<BlakeTwo256>>;
     type SlashAmountPerMaxFileSize = ConstU128<10>;
+    type SlashReferenceDataSize = ConstU64<50>;
+    type SlashEscalationPerMissedDeadline = SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier = ConstU32<5>;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = ConstU64<10>;
     type MaxCommitmentSize = ConstU32<1000>;
     type ZeroSizeBucketFixedRate = ConstU128<1>;
     type ProviderTopUpTtl = ProviderTopUpTtl;
     type MaxExpiredItemsInBlock = ConstU32<10>;
+    type MaxCapacityUtilizationSamples = ConstU32<10>;
+    type CapacityUtilizationSamplingPeriod = ConstU64<10>;
+    type RequireCapacityAttestation = RequireCapacityAttestation;
+    type CapacityAttestationChunkSize = ConstU64<10>;
+    type CapacityAttestationSpotChecks = ConstU32<4>;
+    type CapacityAttestationWindow = ConstU64<10>;
+    type CapacityDecreaseGracePeriod = ConstU64<20>;
+    type ReputationGrowthStreak = ConstU32<5>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<10>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelpers = ();
 }
@@ -475,6 +514,11 @@ impl ProofSubmittersInterface for MockSubmittingProviders {
 
     fn get_accrued_failed_proof_submissions(provider_id: &Self::ProviderId) -> Option<u32> {
         SlashableProviders::<Test>::get(provider_id)
+            .map(|record| record.accrued_failed_proof_submissions)
+    }
+
+    fn get_consecutive_missed_proof_deadlines(provider_id: &Self::ProviderId) -> u32 {
+        ConsecutiveMissedDeadlines::<Test>::get(provider_id)
     }
 
     fn clear_accrued_failed_proof_submissions(provider_id: &Self::ProviderId) {
@@ -4,10 +4,10 @@ use crate::{
         BackupStorageProvider, BalanceOf, Bucket, HashId, MainStorageProvider,
         MainStorageProviderId, MaxMultiAddressAmount, MultiAddress, ProviderTopUpTtl, ShTickGetter,
         SignUpRequestSpParams, StorageDataUnit, StorageProviderId, ValueProposition,
-        ValuePropositionWithId,
+        ValuePropositionVersion, ValuePropositionWithId,
     },
-    AwaitingTopUpFromProviders, Error, Event, InsolventProviders, MainStorageProviders,
-    ProviderTopUpExpirations,
+    AwaitingTopUpFromProviders, CapacityUtilizationSamples, Error, Event, InsolventProviders,
+    MainStorageProviders, ProviderTopUpExpirations,
 };
 
 use core::u32;
@@ -18,18 +18,20 @@ use frame_support::{
     traits::{
         fungible::{Inspect, InspectHold, Mutate},
         tokens::{Fortitude, Precision},
-        Get, OnFinalize, OnIdle, OnInitialize,
+        Get, Hooks, OnFinalize, OnIdle, OnInitialize,
     },
+    weights::WeightMeter,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_proofs_dealer::types::SlashableProviderRecord;
 use shp_constants::GIGAUNIT;
 use shp_traits::{
     MutateBucketsInterface, MutateStorageProvidersInterface, PaymentStreamsInterface,
-    ReadBucketsInterface, ReadProvidersInterface, StorageHubTickGetter,
+    ProviderStatus, ReadBucketsInterface, ReadProvidersInterface, StorageHubTickGetter,
 };
 use sp_arithmetic::{MultiplyRational, Rounding};
 use sp_core::H256;
-use sp_runtime::{bounded_vec, traits::ConvertBack};
+use sp_runtime::{bounded_vec, traits::ConvertBack, PerThing};
 
 type NativeBalance = <Test as crate::Config>::NativeBalance;
 type AccountId = <Test as frame_system::Config>::AccountId;
@@ -3837,349 +3839,522 @@ mod change_capacity {
     }
 }
 
-mod add_bucket {
+mod capacity_decrease {
     use super::*;
-    mod failure {
+
+    type CapacityDecreaseGracePeriod = <Test as crate::Config>::CapacityDecreaseGracePeriod;
+
+    mod success {
         use super::*;
 
         #[test]
-        fn add_bucket_already_exists() {
+        fn bsp_capacity_decrease_below_used_storage_applied_after_offloading() {
             ExtBuilder::build().execute_with(|| {
+                // Register Alice as BSP:
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                let old_storage_amount: StorageDataUnit<Test> = 100;
+                let decreased_storage_amount: StorageDataUnit<Test> = 50;
+                let (_old_deposit_amount, _alice_bsp) =
+                    register_account_as_bsp(alice, old_storage_amount);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                // Use more storage than the capacity Alice wants to decrease to
+                assert_ok!(
+                    <StorageProviders as MutateStorageProvidersInterface>::increase_capacity_used(
+                        &alice_bsp_id,
+                        60
+                    )
                 );
 
-                // Add a bucket for Alice
-                assert_ok!(StorageProviders::add_bucket(
-                    Some(msp_id),
-                    bucket_owner,
-                    bucket_id,
-                    false,
-                    None,
-                    Some(value_prop_id)
+                // Request the decrease, which is allowed even if Alice uses more storage than the new capacity
+                assert_ok!(StorageProviders::request_capacity_decrease(
+                    RuntimeOrigin::signed(alice),
+                    decreased_storage_amount
                 ));
 
-                // Try to add the bucket for Alice with the same bucket id
-                assert_noop!(
-                    StorageProviders::add_bucket(
-                        Some(msp_id),
-                        bucket_owner,
-                        bucket_id,
-                        false,
-                        None,
-                        Some(value_prop_id)
-                    ),
-                    Error::<Test>::BucketAlreadyExists
+                let confirmable_at = frame_system::Pallet::<Test>::block_number()
+                    + <CapacityDecreaseGracePeriod as Get<BlockNumberFor<Test>>>::get();
+                System::assert_last_event(
+                    Event::<Test>::CapacityDecreaseRequested {
+                        who: alice,
+                        provider_id: StorageProviderId::BackupStorageProvider(alice_bsp_id),
+                        current_capacity: old_storage_amount,
+                        new_capacity: decreased_storage_amount,
+                        confirmable_at,
+                    }
+                    .into(),
                 );
-            });
-        }
 
-        #[test]
-        fn add_bucket_msp_not_registered() {
-            ExtBuilder::build().execute_with(|| {
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                // The capacity does not change until the decrease is confirmed
+                assert_eq!(
+                    StorageProviders::get_total_capacity_of_sp(&alice).unwrap(),
+                    old_storage_amount
                 );
 
-                // Try to add a bucket to a non-registered MSP
-                assert_noop!(
-                    StorageProviders::add_bucket(
-                        Some(MainStorageProviderId::<Test>::default()),
-                        bucket_owner,
-                        bucket_id,
-                        false,
-                        None,
-                        Some(HashId::<Test>::default())
-                    ),
-                    Error::<Test>::NotRegistered
+                // Offload data during the grace period
+                assert_ok!(
+                    <StorageProviders as MutateStorageProvidersInterface>::decrease_capacity_used(
+                        &alice_bsp_id,
+                        20
+                    )
                 );
-            });
-        }
-    }
+                run_to_block(confirmable_at);
 
-    mod success {
-        use crate::Config;
+                assert_ok!(StorageProviders::confirm_capacity_decrease(
+                    RuntimeOrigin::signed(alice)
+                ));
 
-        use super::*;
+                // The deposit for the decreased capacity is the only one left on hold
+                let deposit_for_decreased_storage: BalanceOf<Test> =
+                    <SpMinDeposit as Get<u128>>::get().saturating_add(
+                        <DepositPerData as Get<u128>>::get().saturating_mul(
+                            (decreased_storage_amount - <SpMinCapacity as Get<u64>>::get()).into(),
+                        ),
+                    );
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&StorageProvidersHoldReason::get(), &alice),
+                    deposit_for_decreased_storage
+                );
+                assert_eq!(
+                    StorageProviders::get_total_capacity_of_sp(&alice).unwrap(),
+                    decreased_storage_amount
+                );
+                assert_eq!(
+                    StorageProviders::get_total_bsp_capacity(),
+                    decreased_storage_amount
+                );
+                assert!(crate::PendingCapacityDecreases::<Test>::get(&alice_bsp_id).is_none());
+
+                System::assert_last_event(
+                    Event::<Test>::CapacityChanged {
+                        who: alice,
+                        provider_id: StorageProviderId::BackupStorageProvider(alice_bsp_id),
+                        old_capacity: old_storage_amount,
+                        new_capacity: decreased_storage_amount,
+                        next_block_when_change_allowed: confirmable_at
+                            + <MinBlocksBetweenCapacityChanges as Get<BlockNumberFor<Test>>>::get(),
+                    }
+                    .into(),
+                );
+            });
+        }
 
         #[test]
-        fn add_bucket() {
+        fn msp_capacity_decrease_cancelled_if_used_storage_still_exceeds_it() {
             ExtBuilder::build().execute_with(|| {
+                // Register Alice as MSP:
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                let old_storage_amount: StorageDataUnit<Test> = 100;
+                let decreased_storage_amount: StorageDataUnit<Test> = 50;
+                let (old_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, old_storage_amount, None, None);
+                let alice_msp_id =
+                    crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                assert_ok!(
+                    <StorageProviders as MutateStorageProvidersInterface>::increase_capacity_used(
+                        &alice_msp_id,
+                        60
+                    )
                 );
 
-                // Add a bucket for Alice
-                assert_ok!(StorageProviders::add_bucket(
-                    Some(msp_id),
-                    bucket_owner,
-                    bucket_id,
-                    false,
-                    None,
-                    Some(value_prop_id)
+                assert_ok!(StorageProviders::request_capacity_decrease(
+                    RuntimeOrigin::signed(alice),
+                    decreased_storage_amount
                 ));
 
-                // Check payment stream was added
-                assert!(
-                    <<Test as Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &msp_id,
-                        &bucket_owner
-                    )
+                // Do not offload any data during the grace period
+                run_to_block(
+                    frame_system::Pallet::<Test>::block_number()
+                        + <CapacityDecreaseGracePeriod as Get<BlockNumberFor<Test>>>::get(),
                 );
 
-                let new_stream_deposit: u64 = <Test as pallet_payment_streams::Config>::NewStreamDeposit::get();
-				let base_deposit: u128 = <Test as pallet_payment_streams::Config>::BaseDeposit::get();
+                assert_ok!(StorageProviders::confirm_capacity_decrease(
+                    RuntimeOrigin::signed(alice)
+                ));
+
+                // The decrease is cancelled, leaving the capacity and deposit as they were
+                System::assert_last_event(
+                    Event::<Test>::CapacityDecreaseCancelled {
+                        who: alice,
+                        provider_id: StorageProviderId::MainStorageProvider(alice_msp_id),
+                        new_capacity: decreased_storage_amount,
+                        used_capacity: 60,
+                    }
+                    .into(),
+                );
                 assert_eq!(
-                    NativeBalance::free_balance(&bucket_owner),
-                    accounts::BOB.1 - <BucketDeposit as Get<u128>>::get() - new_stream_deposit as u128 - base_deposit
+                    StorageProviders::get_total_capacity_of_sp(&alice).unwrap(),
+                    old_storage_amount
                 );
-
-                let new_rate = <<Test as Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
-                    &msp_id,
-                    &bucket_owner
-                ).unwrap_or_default();
-
-                let zero_size_bucket_rate: u128 = <Test as Config>::ZeroSizeBucketFixedRate::get();
-
-                // Check that the fixed rate payment stream increased by 10 zero size bucket rates
-                assert_eq!(zero_size_bucket_rate, new_rate);
-
                 assert_eq!(
-                    NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
-                    BucketDeposit::get()
+                    NativeBalance::balance_on_hold(&StorageProvidersHoldReason::get(), &alice),
+                    old_deposit_amount
                 );
+                assert!(crate::PendingCapacityDecreases::<Test>::get(&alice_msp_id).is_none());
+            });
+        }
+    }
 
-                assert!(
-                    crate::MainStorageProviderIdsToBuckets::<Test>::get(&msp_id, bucket_id)
-                    .is_some()
-                );
+    mod failure {
+        use super::*;
 
-                let bucket = crate::Buckets::<Test>::get(&bucket_id).unwrap();
+        #[test]
+        fn request_capacity_decrease_fails_when_not_registered() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
 
-                assert_eq!(
-                    bucket,
-                    Bucket::<Test> {
-                        root: DefaultMerkleRoot::get(),
-                        user_id: bucket_owner,
-                        msp_id: Some(msp_id),
-                        private: false,
-                        read_access_group_id: None,
-                        size: 0,
-                        value_prop_id: Some(value_prop_id),
-                    }
+                assert_noop!(
+                    StorageProviders::request_capacity_decrease(RuntimeOrigin::signed(alice), 50),
+                    Error::<Test>::NotRegistered
                 );
-
             });
         }
 
         #[test]
-        fn add_multiple_buckets() {
+        fn request_capacity_decrease_fails_if_not_a_decrease() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                register_account_as_bsp(alice, storage_amount);
 
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                assert_noop!(
+                    StorageProviders::request_capacity_decrease(
+                        RuntimeOrigin::signed(alice),
+                        storage_amount
+                    ),
+                    Error::<Test>::NewCapacityNotLessThanCurrentCapacity
+                );
+            });
+        }
 
-                let bucket_owner = accounts::BOB.0;
+        #[test]
+        fn request_capacity_decrease_fails_if_already_requested() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
 
-                // Add the maximum amount of buckets for Alice
-                let num_buckets = 10;
-                for i in 0..10 {
-                    let bucket_name =
-                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
-                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                        &bucket_owner,
-                        bucket_name,
-                    );
-                    assert_ok!(StorageProviders::add_bucket(
-                        Some(msp_id),
-                        bucket_owner,
-                        bucket_id,
-                        false,
-                        None,
-                        Some(value_prop_id)
-                    ));
+                assert_ok!(StorageProviders::request_capacity_decrease(
+                    RuntimeOrigin::signed(alice),
+                    50
+                ));
 
-                    let expected_hold_amount =
-                        (i + 1) as u128 * <BucketDeposit as Get<u128>>::get();
-                    assert_eq!(
-                        NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
-                        expected_hold_amount
-                    );
+                assert_noop!(
+                    StorageProviders::request_capacity_decrease(RuntimeOrigin::signed(alice), 40),
+                    Error::<Test>::CapacityDecreaseAlreadyRequested
+                );
+            });
+        }
 
+        #[test]
+        fn confirm_capacity_decrease_fails_if_not_requested() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
 
-                    // Check that the fixed rate payment stream matches the expected zero size bucket rate * the current number of buckets
-                    let zero_size_bucket_rate: u128 = <Test as crate::Config>::ZeroSizeBucketFixedRate::get();
-                    let expected_fixed_payment_stream_value = zero_size_bucket_rate * (i + 1) as u128;
-                    let fixed_payment_stream_value = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
-                        &msp_id,
-                        &bucket_owner
-                    ).unwrap_or_default();
-                    assert_eq!(fixed_payment_stream_value, expected_fixed_payment_stream_value);
-                }
+                assert_noop!(
+                    StorageProviders::confirm_capacity_decrease(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::CapacityDecreaseNotRequested
+                );
+            });
+        }
 
-                let buckets =
-                    crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
-                        .collect::<Vec<_>>();
+        #[test]
+        fn confirm_capacity_decrease_fails_before_grace_period_passed() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
 
-                assert_eq!(buckets.len(), num_buckets);
+                assert_ok!(StorageProviders::request_capacity_decrease(
+                    RuntimeOrigin::signed(alice),
+                    50
+                ));
+
+                run_to_block(
+                    frame_system::Pallet::<Test>::block_number()
+                        + <CapacityDecreaseGracePeriod as Get<BlockNumberFor<Test>>>::get()
+                        - 1,
+                );
+
+                assert_noop!(
+                    StorageProviders::confirm_capacity_decrease(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::CapacityDecreaseGracePeriodNotPassed
+                );
+                assert_eq!(
+                    StorageProviders::get_total_capacity_of_sp(&alice).unwrap(),
+                    100
+                );
             });
         }
     }
 }
 
-mod unassign_msp_from_bucket {
+mod provider_status {
     use super::*;
-    mod failure {
+
+    mod success {
         use super::*;
 
         #[test]
-        fn bucket_not_found() {
+        fn registered_provider_is_active() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
-
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                assert_eq!(
+                    StorageProviders::get_provider_status(&alice_bsp_id),
+                    Some(ProviderStatus::Active)
                 );
-
-                // Try to change a bucket that does not exist
-                assert_noop!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(
-                        &bucket_id
-                    ),
-                    Error::<Test>::BucketNotFound
+                assert_eq!(
+                    StorageProviders::get_provider_status(&H256::from_slice(&[1; 32])),
+                    None
                 );
             });
         }
-    }
 
-    mod success {
-        use super::*;
+        #[test]
+        fn schedule_and_cancel_sign_off_changes_status() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_ok!(StorageProviders::schedule_sign_off(RuntimeOrigin::signed(
+                    alice
+                )));
+
+                assert_eq!(
+                    StorageProviders::get_provider_status(&alice_bsp_id),
+                    Some(ProviderStatus::Exiting)
+                );
+                System::assert_last_event(
+                    Event::<Test>::ProviderStatusChanged {
+                        provider_id: alice_bsp_id,
+                        status: ProviderStatus::Exiting,
+                    }
+                    .into(),
+                );
+
+                assert_ok!(StorageProviders::cancel_scheduled_sign_off(
+                    RuntimeOrigin::signed(alice)
+                ));
+
+                assert_eq!(
+                    StorageProviders::get_provider_status(&alice_bsp_id),
+                    Some(ProviderStatus::Active)
+                );
+                System::assert_last_event(
+                    Event::<Test>::ProviderStatusChanged {
+                        provider_id: alice_bsp_id,
+                        status: ProviderStatus::Active,
+                    }
+                    .into(),
+                );
+            });
+        }
 
         #[test]
-        fn unassign_msp_from_bucket_works() {
+        fn suspend_and_resume_provider_changes_status() {
             ExtBuilder::build().execute_with(|| {
-                // Register Alice as MSP
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                register_account_as_msp(alice, 100, None, None);
+                let alice_msp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                // Suspension takes precedence over a scheduled sign off
+                assert_ok!(StorageProviders::schedule_sign_off(RuntimeOrigin::signed(
+                    alice
+                )));
+                assert_ok!(StorageProviders::suspend_provider(
+                    RuntimeOrigin::root(),
+                    alice_msp_id
+                ));
 
-                // Create bucket
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                assert_eq!(
+                    StorageProviders::get_provider_status(&alice_msp_id),
+                    Some(ProviderStatus::Suspended)
+                );
+                System::assert_last_event(
+                    Event::<Test>::ProviderStatusChanged {
+                        provider_id: alice_msp_id,
+                        status: ProviderStatus::Suspended,
+                    }
+                    .into(),
                 );
 
-                assert_ok!(StorageProviders::add_bucket(
-                    Some(msp_id),
-                    bucket_owner,
-                    bucket_id,
-                    false,
-                    None,
-                    Some(value_prop_id)
+                assert_ok!(StorageProviders::resume_provider(
+                    RuntimeOrigin::root(),
+                    alice_msp_id
                 ));
 
-                assert!(
-                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &msp_id,
-                        &bucket_owner
-                    )
+                assert_eq!(
+                    StorageProviders::get_provider_status(&alice_msp_id),
+                    Some(ProviderStatus::Exiting)
                 );
+            });
+        }
 
-                assert_ok!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(&bucket_id),
+        #[test]
+        fn insolvency_takes_precedence_over_other_statuses() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_ok!(StorageProviders::suspend_provider(
+                    RuntimeOrigin::root(),
+                    alice_bsp_id
+                ));
+
+                // Simulate insolvent provider
+                InsolventProviders::<Test>::insert(
+                    StorageProviderId::<Test>::BackupStorageProvider(alice_bsp_id),
+                    (),
                 );
 
+                assert_eq!(
+                    StorageProviders::get_provider_status(&alice_bsp_id),
+                    Some(ProviderStatus::Insolvent)
+                );
                 assert!(
-                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &msp_id,
-                        &bucket_owner
+                    <StorageProviders as ReadProvidersInterface>::is_provider_insolvent(
+                        alice_bsp_id
                     )
                 );
+            });
+        }
 
-                assert_noop!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(&bucket_id),
-                    Error::<Test>::BucketMustHaveMspForOperation
-                );
+        #[test]
+        fn sign_off_clears_status() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_msp(alice, 100, None, None);
+                let alice_msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_ok!(StorageProviders::schedule_sign_off(RuntimeOrigin::signed(
+                    alice
+                )));
+                assert_ok!(StorageProviders::suspend_provider(
+                    RuntimeOrigin::root(),
+                    alice_msp_id
+                ));
+
+                assert_ok!(StorageProviders::msp_sign_off(RuntimeOrigin::signed(alice)));
+
+                assert_eq!(StorageProviders::get_provider_status(&alice_msp_id), None);
+                assert!(!crate::ExitingProviders::<Test>::contains_key(
+                    &alice_msp_id
+                ));
+                assert!(!crate::SuspendedProviders::<Test>::contains_key(
+                    &alice_msp_id
+                ));
             });
         }
     }
-}
 
-mod assign_msp_to_bucket {
-    use super::*;
     mod failure {
         use super::*;
 
         #[test]
-        fn bucket_not_found() {
+        fn suspend_provider_fails_if_not_root() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                assert_noop!(
+                    StorageProviders::suspend_provider(RuntimeOrigin::signed(alice), alice_bsp_id),
+                    DispatchError::BadOrigin
                 );
+            });
+        }
 
-                // Try to change a bucket that does not exist
+        #[test]
+        fn suspend_provider_fails_if_not_registered() {
+            ExtBuilder::build().execute_with(|| {
                 assert_noop!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::assign_msp_to_bucket(
-                        &bucket_id, &msp_id,
+                    StorageProviders::suspend_provider(
+                        RuntimeOrigin::root(),
+                        H256::from_slice(&[1; 32])
                     ),
-                    Error::<Test>::BucketNotFound
+                    Error::<Test>::NotRegistered
                 );
             });
         }
 
         #[test]
-        fn msp_already_assigned_to_bucket() {
+        fn suspend_provider_fails_if_already_suspended() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_ok!(StorageProviders::suspend_provider(
+                    RuntimeOrigin::root(),
+                    alice_bsp_id
+                ));
+
+                assert_noop!(
+                    StorageProviders::suspend_provider(RuntimeOrigin::root(), alice_bsp_id),
+                    Error::<Test>::ProviderAlreadySuspended
+                );
+            });
+        }
+
+        #[test]
+        fn resume_provider_fails_if_not_suspended() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_noop!(
+                    StorageProviders::resume_provider(RuntimeOrigin::root(), alice_bsp_id),
+                    Error::<Test>::ProviderNotSuspended
+                );
+            });
+        }
+
+        #[test]
+        fn schedule_sign_off_fails_if_already_scheduled() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
+
+                assert_ok!(StorageProviders::schedule_sign_off(RuntimeOrigin::signed(
+                    alice
+                )));
+
+                assert_noop!(
+                    StorageProviders::schedule_sign_off(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::SignOffAlreadyScheduled
+                );
+            });
+        }
+
+        #[test]
+        fn cancel_scheduled_sign_off_fails_if_not_scheduled() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                register_account_as_bsp(alice, 100);
+
+                assert_noop!(
+                    StorageProviders::cancel_scheduled_sign_off(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::SignOffNotScheduled
+                );
+            });
+        }
+    }
+}
+
+mod add_bucket {
+    use super::*;
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn add_bucket_already_exists() {
             ExtBuilder::build().execute_with(|| {
-                // Register Alice as MSP
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
                 let (_deposit_amount, _alice_msp, value_prop_id) =
@@ -4187,7 +4362,6 @@ mod assign_msp_to_bucket {
 
                 let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
-                // Create bucket
                 let bucket_owner = accounts::BOB.0;
                 let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
@@ -4195,7 +4369,7 @@ mod assign_msp_to_bucket {
                     bucket_name,
                 );
 
-                // Add bucket
+                // Add a bucket for Alice
                 assert_ok!(StorageProviders::add_bucket(
                     Some(msp_id),
                     bucket_owner,
@@ -4205,41 +4379,62 @@ mod assign_msp_to_bucket {
                     Some(value_prop_id)
                 ));
 
+                // Try to add the bucket for Alice with the same bucket id
                 assert_noop!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::assign_msp_to_bucket(
-                        &bucket_id, &msp_id,
+                    StorageProviders::add_bucket(
+                        Some(msp_id),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(value_prop_id)
                     ),
-                    Error::<Test>::MspAlreadyAssignedToBucket
+                    Error::<Test>::BucketAlreadyExists
+                );
+            });
+        }
+
+        #[test]
+        fn add_bucket_msp_not_registered() {
+            ExtBuilder::build().execute_with(|| {
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Try to add a bucket to a non-registered MSP
+                assert_noop!(
+                    StorageProviders::add_bucket(
+                        Some(MainStorageProviderId::<Test>::default()),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(HashId::<Test>::default())
+                    ),
+                    Error::<Test>::NotRegistered
                 );
             });
         }
     }
 
     mod success {
+        use crate::Config;
+
         use super::*;
 
         #[test]
-        fn assign_msp_to_bucket() {
+        fn add_bucket() {
             ExtBuilder::build().execute_with(|| {
-                // Register Alice as MSP
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
                 let (_deposit_amount, _alice_msp, value_prop_id) =
                     register_account_as_msp(alice, storage_amount, None, None);
 
-                let alice_msp_id =
-                    crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
-
-                // Register Charlie as MSP
-                let charlie: AccountId = accounts::CHARLIE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _charlie_msp, _) =
-                    register_account_as_msp(charlie, storage_amount, None, None);
-
-                let charlie_msp_id =
-                    crate::AccountIdToMainStorageProviderId::<Test>::get(&charlie).unwrap();
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
-                // Create bucket
                 let bucket_owner = accounts::BOB.0;
                 let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
@@ -4247,9 +4442,9 @@ mod assign_msp_to_bucket {
                     bucket_name,
                 );
 
-                // Add bucket
+                // Add a bucket for Alice
                 assert_ok!(StorageProviders::add_bucket(
-                    Some(alice_msp_id),
+                    Some(msp_id),
                     bucket_owner,
                     bucket_id,
                     false,
@@ -4257,162 +4452,61 @@ mod assign_msp_to_bucket {
                     Some(value_prop_id)
                 ));
 
-                // check payment stream exists for alice
+                // Check payment stream was added
                 assert!(
-                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &alice_msp_id,
+                    <<Test as Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                        &msp_id,
                         &bucket_owner
                     )
                 );
 
-                // Change MSP of bucket
-                assert_ok!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::assign_msp_to_bucket(
-                        &bucket_id,
-                        &charlie_msp_id,
-                    )
+                let new_stream_deposit: u64 = <Test as pallet_payment_streams::Config>::NewStreamDeposit::get();
+				let base_deposit: u128 = <Test as pallet_payment_streams::Config>::BaseDeposit::get();
+                assert_eq!(
+                    NativeBalance::free_balance(&bucket_owner),
+                    accounts::BOB.1 - <BucketDeposit as Get<u128>>::get() - new_stream_deposit as u128 - base_deposit
                 );
 
-                // Check that the bucket was removed from alice
-                assert!(crate::MainStorageProviderIdsToBuckets::<Test>::get(
-                    &alice_msp_id,
-                    bucket_id
-                )
-                .is_none());
-
-                // Check that the bucket was added to the default MSP
-                assert!(crate::MainStorageProviderIdsToBuckets::<Test>::get(
-                    &charlie_msp_id,
-                    bucket_id
-                )
-                .is_some());
-
-                // Check that the bucket was updated
-                let bucket = crate::Buckets::<Test>::get(&bucket_id).unwrap();
-                assert_eq!(
-                    bucket.msp_id,
-                    Some(charlie_msp_id)
-                );
-
-                // check payment stream exists for charlie
-                assert!(
-                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &charlie_msp_id,
-                        &bucket_owner
-                    )
-                );
-
-                // check payment stream does not exist for alice
-                assert!(
-                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &alice_msp_id,
-                        &bucket_owner
-                    )
-                );
-            });
-        }
-    }
-}
-
-mod remove_root_bucket {
-    use super::*;
-
-    mod failure {
-        use super::*;
-
-        #[test]
-        fn remove_root_bucket_when_bucket_does_not_exist() {
-            ExtBuilder::build().execute_with(|| {
-                let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
-                );
-
-                // Try to remove a bucket that does not exist
-                assert_noop!(
-                    StorageProviders::remove_root_bucket(bucket_id),
-                    Error::<Test>::BucketNotFound
-                );
-            });
-        }
-    }
-
-    mod success {
-        use super::*;
+                let new_rate = <<Test as Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                    &msp_id,
+                    &bucket_owner
+                ).unwrap_or_default();
 
-        #[test]
-        fn remove_root_bucket() {
-            ExtBuilder::build().execute_with(|| {
-                let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                let zero_size_bucket_rate: u128 = <Test as Config>::ZeroSizeBucketFixedRate::get();
 
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                // Check that the fixed rate payment stream increased by 10 zero size bucket rates
+                assert_eq!(zero_size_bucket_rate, new_rate);
 
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
+                    BucketDeposit::get()
                 );
 
-                // Add a bucket for Alice
-                assert_ok!(StorageProviders::add_bucket(
-                    Some(msp_id),
-                    bucket_owner,
-                    bucket_id,
-                    false,
-                    None,
-                    Some(value_prop_id)
-                ));
-
-                // Check that the bucket was added to the MSP
                 assert!(
                     crate::MainStorageProviderIdsToBuckets::<Test>::get(&msp_id, bucket_id)
-                        .is_some()
+                    .is_some()
                 );
 
-                // Remove the bucket
-                assert_ok!(StorageProviders::remove_root_bucket(bucket_id));
-
-                // Check that the bucket deposit is returned to the bucket owner
-                assert_eq!(NativeBalance::free_balance(&bucket_owner), accounts::BOB.1);
+                let bucket = crate::Buckets::<Test>::get(&bucket_id).unwrap();
 
-                // Check that the bucket deposit is no longer on hold
                 assert_eq!(
-                    NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
-                    0
-                );
-
-                // Check that the bucket was removed
-                assert_eq!(crate::Buckets::<Test>::get(&bucket_id), None);
-
-                // Check that the bucket was removed from the MSP
-                assert!(
-                    crate::MainStorageProviderIdsToBuckets::<Test>::get(&msp_id, bucket_id)
-                        .is_none()
+                    bucket,
+                    Bucket::<Test> {
+                        root: DefaultMerkleRoot::get(),
+                        user_id: bucket_owner,
+                        msp_id: Some(msp_id),
+                        private: false,
+                        read_access_group_id: None,
+                        size: 0,
+                        value_prop_id: Some(value_prop_id),
+                    }
                 );
 
-                // Check payment stream was removed
-                assert!(
-                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &msp_id,
-                        &bucket_owner
-                    )
-                );
             });
         }
 
         #[test]
-        fn remove_root_buckets_multiple() {
+        fn add_multiple_buckets() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
@@ -4447,6 +4541,16 @@ mod remove_root_bucket {
                         NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
                         expected_hold_amount
                     );
+
+
+                    // Check that the fixed rate payment stream matches the expected zero size bucket rate * the current number of buckets
+                    let zero_size_bucket_rate: u128 = <Test as crate::Config>::ZeroSizeBucketFixedRate::get();
+                    let expected_fixed_payment_stream_value = zero_size_bucket_rate * (i + 1) as u128;
+                    let fixed_payment_stream_value = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ).unwrap_or_default();
+                    assert_eq!(fixed_payment_stream_value, expected_fixed_payment_stream_value);
                 }
 
                 let buckets =
@@ -4454,78 +4558,26 @@ mod remove_root_bucket {
                         .collect::<Vec<_>>();
 
                 assert_eq!(buckets.len(), num_buckets);
-
-                // Remove all the buckets
-                for i in 0..num_buckets {
-                    let bucket_name =
-                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
-                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                        &bucket_owner,
-                        bucket_name,
-                    );
-                    assert_ok!(StorageProviders::remove_root_bucket(bucket_id));
-                    if i < num_buckets - 1 {
-                        // Check that the payment streams still exists if we haven't removed the last bucket
-                        assert!(
-                            <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                                &msp_id,
-                                &bucket_owner
-                            )
-                        );
-
-                        // Check that the fixed rate payment stream matches the expected zero size bucket rate * the current number of buckets
-                        let zero_size_bucket_rate: u128 = <Test as crate::Config>::ZeroSizeBucketFixedRate::get();
-                        let expected_fixed_payment_stream_value = zero_size_bucket_rate * (num_buckets - i - 1) as u128;
-                        let fixed_payment_stream_value = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
-                            &msp_id,
-                            &bucket_owner
-                        ).unwrap_or_default();
-                        assert_eq!(fixed_payment_stream_value, expected_fixed_payment_stream_value);
-                    }
-                }
-
-                // Check that the bucket deposits are returned to the bucket owner
-                assert_eq!(NativeBalance::free_balance(&bucket_owner), accounts::BOB.1);
-
-                // Check that the bucket deposits are no longer on hold
-                assert_eq!(
-                    NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
-                    0
-                );
-
-                // Check that all the buckets were removed
-                assert_eq!(
-                    crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
-                        .count(),
-                    0
-                );
-
-                // Check that the payment streams was removed
-                assert!(
-                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
-                        &msp_id,
-                        &bucket_owner
-                    )
-                );
             });
         }
     }
 }
 
-mod increase_bucket_size {
+mod unassign_msp_from_bucket {
     use super::*;
-
     mod failure {
         use super::*;
 
         #[test]
-        fn bucket_does_not_exist() {
+        fn bucket_not_found() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
                     register_account_as_msp(alice, storage_amount, None, None);
 
+                crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
                 let bucket_owner = accounts::BOB.0;
                 let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
@@ -4533,10 +4585,10 @@ mod increase_bucket_size {
                     bucket_name,
                 );
 
-                // Try to remove a bucket that does not exist
+                // Try to change a bucket that does not exist
                 assert_noop!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
-                        &bucket_id, 100
+                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(
+                        &bucket_id
                     ),
                     Error::<Test>::BucketNotFound
                 );
@@ -4545,93 +4597,99 @@ mod increase_bucket_size {
     }
 
     mod success {
-        use crate::MainStorageProviderIdsToValuePropositions;
-
         use super::*;
 
         #[test]
-        fn increase_bucket_size_works() {
+        fn unassign_msp_from_bucket_works() {
             ExtBuilder::build().execute_with(|| {
+                // Register Alice as MSP
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
-
-                let num_buckets = 10;
-                let delta_increase = 100 * 1024 * 1024;
                 let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, Some(100), Some(num_buckets * delta_increase));
+                    register_account_as_msp(alice, storage_amount, None, None);
 
                 let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
+                // Create bucket
                 let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
 
-                // Add the maximum amount of buckets for Alice
-                for i in 0..10 {
-                    let bucket_name =
-                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
-                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                        &bucket_owner,
-                        bucket_name,
-                    );
-                    assert_ok!(StorageProviders::add_bucket(
-                        Some(msp_id),
-                        bucket_owner,
-                        bucket_id,
-                        false,
-                        None,
-                        Some(value_prop_id)
-                    ));
-
-                    let expected_hold_amount =
-                        (i + 1) as u128 * <BucketDeposit as Get<u128>>::get();
-                    assert_eq!(
-                        NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
-                        expected_hold_amount
-                    );
-                }
-
-                let buckets =
-                    crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
-                        .collect::<Vec<_>>();
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
 
-                assert_eq!(buckets.len(), num_buckets as usize);
+                assert!(
+                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                        &msp_id,
+                        &bucket_owner
+                    )
+                );
 
-                // Increment the size of all buckets
-                for i in 0..num_buckets {
-                    let bucket_name =
-                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
-                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                        &bucket_owner,
-                        bucket_name,
-                    );
+                assert_ok!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(&bucket_id),
+                );
 
-                    let current_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                assert!(
+                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
                         &msp_id,
                         &bucket_owner
-                    ).unwrap_or_default();
+                    )
+                );
 
-                    assert_ok!(<crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
-                        &bucket_id,
-                        delta_increase
-                    ));
+                assert_noop!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(&bucket_id),
+                    Error::<Test>::BucketMustHaveMspForOperation
+                );
+            });
+        }
+    }
+}
 
-                    // Check that the fixed rate payment stream matches the expected rate
-                    let value_prop = MainStorageProviderIdsToValuePropositions::<Test>::get(&msp_id, value_prop_id).unwrap();
-                    let delta_rate = value_prop.price_per_giga_unit_of_data_per_block.multiply_rational(delta_increase as u128, GIGAUNIT as u128, Rounding::NearestPrefUp).unwrap();
-                    let expected_rate = current_rate + delta_rate;
+mod assign_msp_to_bucket {
+    use super::*;
+    mod failure {
+        use super::*;
 
-                    let actual_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
-                        &msp_id,
-                        &bucket_owner
-                    ).unwrap_or_default();
+        #[test]
+        fn bucket_not_found() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
 
-                    assert_eq!(actual_rate, expected_rate);
-                }
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Try to change a bucket that does not exist
+                assert_noop!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::assign_msp_to_bucket(
+                        &bucket_id, &msp_id,
+                    ),
+                    Error::<Test>::BucketNotFound
+                );
             });
         }
 
         #[test]
-        fn increase_bucket_size_without_msp() {
+        fn msp_already_assigned_to_bucket() {
             ExtBuilder::build().execute_with(|| {
+                // Register Alice as MSP
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
                 let (_deposit_amount, _alice_msp, value_prop_id) =
@@ -4639,6 +4697,7 @@ mod increase_bucket_size {
 
                 let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
+                // Create bucket
                 let bucket_owner = accounts::BOB.0;
                 let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
                 let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
@@ -4646,7 +4705,7 @@ mod increase_bucket_size {
                     bucket_name,
                 );
 
-                // Add a bucket for Alice
+                // Add bucket
                 assert_ok!(StorageProviders::add_bucket(
                     Some(msp_id),
                     bucket_owner,
@@ -4656,17 +4715,108 @@ mod increase_bucket_size {
                     Some(value_prop_id)
                 ));
 
-                // Remove the MSP from the bucket
-                assert_ok!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(
-                        &bucket_id
+                assert_noop!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::assign_msp_to_bucket(
+                        &bucket_id, &msp_id,
+                    ),
+                    Error::<Test>::MspAlreadyAssignedToBucket
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn assign_msp_to_bucket() {
+            ExtBuilder::build().execute_with(|| {
+                // Register Alice as MSP
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let alice_msp_id =
+                    crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                // Register Charlie as MSP
+                let charlie: AccountId = accounts::CHARLIE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _charlie_msp, _) =
+                    register_account_as_msp(charlie, storage_amount, None, None);
+
+                let charlie_msp_id =
+                    crate::AccountIdToMainStorageProviderId::<Test>::get(&charlie).unwrap();
+
+                // Create bucket
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Add bucket
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(alice_msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
+
+                // check payment stream exists for alice
+                assert!(
+                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                        &alice_msp_id,
+                        &bucket_owner
                     )
                 );
 
-                // Try to increase the size of a bucket that does not have an MSP
+                // Change MSP of bucket
                 assert_ok!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
-                        &bucket_id, 100
+                    <crate::Pallet<Test> as MutateBucketsInterface>::assign_msp_to_bucket(
+                        &bucket_id,
+                        &charlie_msp_id,
+                    )
+                );
+
+                // Check that the bucket was removed from alice
+                assert!(crate::MainStorageProviderIdsToBuckets::<Test>::get(
+                    &alice_msp_id,
+                    bucket_id
+                )
+                .is_none());
+
+                // Check that the bucket was added to the default MSP
+                assert!(crate::MainStorageProviderIdsToBuckets::<Test>::get(
+                    &charlie_msp_id,
+                    bucket_id
+                )
+                .is_some());
+
+                // Check that the bucket was updated
+                let bucket = crate::Buckets::<Test>::get(&bucket_id).unwrap();
+                assert_eq!(
+                    bucket.msp_id,
+                    Some(charlie_msp_id)
+                );
+
+                // check payment stream exists for charlie
+                assert!(
+                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                        &charlie_msp_id,
+                        &bucket_owner
+                    )
+                );
+
+                // check payment stream does not exist for alice
+                assert!(
+                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                        &alice_msp_id,
+                        &bucket_owner
                     )
                 );
             });
@@ -4674,14 +4824,14 @@ mod increase_bucket_size {
     }
 }
 
-mod decrease_bucket_size {
+mod remove_root_bucket {
     use super::*;
 
     mod failure {
         use super::*;
 
         #[test]
-        fn bucket_does_not_exist() {
+        fn remove_root_bucket_when_bucket_does_not_exist() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
@@ -4697,9 +4847,7 @@ mod decrease_bucket_size {
 
                 // Try to remove a bucket that does not exist
                 assert_noop!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::decrease_bucket_size(
-                        &bucket_id, 100
-                    ),
+                    StorageProviders::remove_root_bucket(bucket_id),
                     Error::<Test>::BucketNotFound
                 );
             });
@@ -4707,57 +4855,117 @@ mod decrease_bucket_size {
     }
 
     mod success {
-        use crate::MainStorageProviderIdsToValuePropositions;
-
         use super::*;
 
         #[test]
-        fn increase_bucket_size_works() {
+        fn remove_root_bucket() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
-
-                let num_buckets = 10;
-                let delta_increase = 100;
                 let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, Some(10), Some(num_buckets * delta_increase));
+                    register_account_as_msp(alice, storage_amount, None, None);
 
                 let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
                 let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
 
-                // Add the maximum amount of buckets for Alice
-                for i in 0..10 {
-                    let bucket_name =
-                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
-                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                        &bucket_owner,
-                        bucket_name,
-                    );
-                    assert_ok!(StorageProviders::add_bucket(
-                        Some(msp_id),
-                        bucket_owner,
-                        bucket_id,
-                        false,
-                        None,
-                        Some(value_prop_id)
-                    ));
+                // Add a bucket for Alice
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
 
-                    let expected_hold_amount =
-                        (i + 1) as u128 * <BucketDeposit as Get<u128>>::get();
-                    assert_eq!(
-                        NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
-                        expected_hold_amount
-                    );
+                // Check that the bucket was added to the MSP
+                assert!(
+                    crate::MainStorageProviderIdsToBuckets::<Test>::get(&msp_id, bucket_id)
+                        .is_some()
+                );
+
+                // Remove the bucket
+                assert_ok!(StorageProviders::remove_root_bucket(bucket_id));
+
+                // Check that the bucket deposit is returned to the bucket owner
+                assert_eq!(NativeBalance::free_balance(&bucket_owner), accounts::BOB.1);
+
+                // Check that the bucket deposit is no longer on hold
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
+                    0
+                );
+
+                // Check that the bucket was removed
+                assert_eq!(crate::Buckets::<Test>::get(&bucket_id), None);
+
+                // Check that the bucket was removed from the MSP
+                assert!(
+                    crate::MainStorageProviderIdsToBuckets::<Test>::get(&msp_id, bucket_id)
+                        .is_none()
+                );
+
+                // Check payment stream was removed
+                assert!(
+                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                        &msp_id,
+                        &bucket_owner
+                    )
+                );
+            });
+        }
+
+        #[test]
+        fn remove_root_buckets_multiple() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+
+                // Add the maximum amount of buckets for Alice
+                let num_buckets = 10;
+                for i in 0..10 {
+                    let bucket_name =
+                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
+                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                        &bucket_owner,
+                        bucket_name,
+                    );
+                    assert_ok!(StorageProviders::add_bucket(
+                        Some(msp_id),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(value_prop_id)
+                    ));
+
+                    let expected_hold_amount =
+                        (i + 1) as u128 * <BucketDeposit as Get<u128>>::get();
+                    assert_eq!(
+                        NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
+                        expected_hold_amount
+                    );
                 }
 
                 let buckets =
                     crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
                         .collect::<Vec<_>>();
 
-                assert_eq!(buckets.len(), num_buckets as usize);
+                assert_eq!(buckets.len(), num_buckets);
 
-                // Increase the bucket size of all buckets
+                // Remove all the buckets
                 for i in 0..num_buckets {
                     let bucket_name =
                         BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
@@ -4765,30 +4973,139 @@ mod decrease_bucket_size {
                         &bucket_owner,
                         bucket_name,
                     );
+                    assert_ok!(StorageProviders::remove_root_bucket(bucket_id));
+                    if i < num_buckets - 1 {
+                        // Check that the payment streams still exists if we haven't removed the last bucket
+                        assert!(
+                            <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
+                                &msp_id,
+                                &bucket_owner
+                            )
+                        );
 
-                    let current_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
-                        &msp_id,
-                        &bucket_owner
-                    ).unwrap_or_default();
+                        // Check that the fixed rate payment stream matches the expected zero size bucket rate * the current number of buckets
+                        let zero_size_bucket_rate: u128 = <Test as crate::Config>::ZeroSizeBucketFixedRate::get();
+                        let expected_fixed_payment_stream_value = zero_size_bucket_rate * (num_buckets - i - 1) as u128;
+                        let fixed_payment_stream_value = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                            &msp_id,
+                            &bucket_owner
+                        ).unwrap_or_default();
+                        assert_eq!(fixed_payment_stream_value, expected_fixed_payment_stream_value);
+                    }
+                }
 
-                    assert_ok!(<crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
-                        &bucket_id,
-                        delta_increase
-                    ));
+                // Check that the bucket deposits are returned to the bucket owner
+                assert_eq!(NativeBalance::free_balance(&bucket_owner), accounts::BOB.1);
 
-					// Check that the fixed rate payment stream matches the expected rate
-                    let value_prop = MainStorageProviderIdsToValuePropositions::<Test>::get(&msp_id, value_prop_id).unwrap();
-                    let delta_rate = value_prop.price_per_giga_unit_of_data_per_block.multiply_rational(delta_increase as u128, GIGAUNIT as u128, Rounding::NearestPrefUp).unwrap();
-                    let expected_rate = current_rate + delta_rate;
-                    let actual_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                // Check that the bucket deposits are no longer on hold
+                assert_eq!(
+                    NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
+                    0
+                );
+
+                // Check that all the buckets were removed
+                assert_eq!(
+                    crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
+                        .count(),
+                    0
+                );
+
+                // Check that the payment streams was removed
+                assert!(
+                    !<<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::fixed_rate_payment_stream_exists(
                         &msp_id,
                         &bucket_owner
-                    ).unwrap_or_default();
+                    )
+                );
+            });
+        }
+    }
+}
 
-                    assert_eq!(actual_rate, expected_rate);
+mod increase_bucket_size {
+    use super::*;
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn bucket_does_not_exist() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Try to remove a bucket that does not exist
+                assert_noop!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
+                        &bucket_id, 100
+                    ),
+                    Error::<Test>::BucketNotFound
+                );
+            });
+        }
+    }
+
+    mod success {
+        use crate::MainStorageProviderIdsToValuePropositions;
+
+        use super::*;
+
+        #[test]
+        fn increase_bucket_size_works() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+
+                let num_buckets = 10;
+                let delta_increase = 100 * 1024 * 1024;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, Some(100), Some(num_buckets * delta_increase));
+
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+
+                // Add the maximum amount of buckets for Alice
+                for i in 0..10 {
+                    let bucket_name =
+                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
+                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                        &bucket_owner,
+                        bucket_name,
+                    );
+                    assert_ok!(StorageProviders::add_bucket(
+                        Some(msp_id),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(value_prop_id)
+                    ));
+
+                    let expected_hold_amount =
+                        (i + 1) as u128 * <BucketDeposit as Get<u128>>::get();
+                    assert_eq!(
+                        NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
+                        expected_hold_amount
+                    );
                 }
 
-                // Decrease the bucket size of all buckets
+                let buckets =
+                    crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
+                        .collect::<Vec<_>>();
+
+                assert_eq!(buckets.len(), num_buckets as usize);
+
+                // Increment the size of all buckets
                 for i in 0..num_buckets {
                     let bucket_name =
                         BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
@@ -4802,15 +5119,16 @@ mod decrease_bucket_size {
                         &bucket_owner
                     ).unwrap_or_default();
 
-                    assert_ok!(<crate::Pallet<Test> as MutateBucketsInterface>::decrease_bucket_size(
+                    assert_ok!(<crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
                         &bucket_id,
                         delta_increase
                     ));
 
-					// Check that the fixed rate payment stream matches the expected rate
+                    // Check that the fixed rate payment stream matches the expected rate
                     let value_prop = MainStorageProviderIdsToValuePropositions::<Test>::get(&msp_id, value_prop_id).unwrap();
                     let delta_rate = value_prop.price_per_giga_unit_of_data_per_block.multiply_rational(delta_increase as u128, GIGAUNIT as u128, Rounding::NearestPrefUp).unwrap();
-                    let expected_rate = current_rate - delta_rate;
+                    let expected_rate = current_rate + delta_rate;
+
                     let actual_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
                         &msp_id,
                         &bucket_owner
@@ -4822,7 +5140,7 @@ mod decrease_bucket_size {
         }
 
         #[test]
-        fn decrease_bucket_size_without_msp() {
+        fn increase_bucket_size_without_msp() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
@@ -4855,40 +5173,232 @@ mod decrease_bucket_size {
                     )
                 );
 
+                // Try to increase the size of a bucket that does not have an MSP
                 assert_ok!(
-                    <crate::Pallet<Test> as MutateBucketsInterface>::decrease_bucket_size(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
                         &bucket_id, 100
-                    ),
+                    )
                 );
             });
         }
     }
 }
 
-mod storage_data_unit_and_balance_converters {
+mod decrease_bucket_size {
     use super::*;
 
-    #[test]
-    fn ensure_conversion_results_match() {
-        ExtBuilder::build().execute_with(|| {
-            let sp_min_capacity = SpMinCapacity::get();
-            let capacities = vec![
-                sp_min_capacity,
-                sp_min_capacity + 100,
-                sp_min_capacity + 500,
-                sp_min_capacity * 2,
-            ];
-
-            for storage_data_unit in capacities {
-                let computed_deposit =
-                    StorageProviders::compute_deposit_needed_for_capacity(storage_data_unit)
-                        .unwrap();
-
-                let computed_capacity =
-                    StorageProviders::compute_capacity_from_held_deposit(computed_deposit).unwrap();
+    mod failure {
+        use super::*;
 
-                assert_eq!(storage_data_unit, computed_capacity);
-            }
+        #[test]
+        fn bucket_does_not_exist() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Try to remove a bucket that does not exist
+                assert_noop!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::decrease_bucket_size(
+                        &bucket_id, 100
+                    ),
+                    Error::<Test>::BucketNotFound
+                );
+            });
+        }
+    }
+
+    mod success {
+        use crate::MainStorageProviderIdsToValuePropositions;
+
+        use super::*;
+
+        #[test]
+        fn increase_bucket_size_works() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+
+                let num_buckets = 10;
+                let delta_increase = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, Some(10), Some(num_buckets * delta_increase));
+
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+
+                // Add the maximum amount of buckets for Alice
+                for i in 0..10 {
+                    let bucket_name =
+                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
+                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                        &bucket_owner,
+                        bucket_name,
+                    );
+                    assert_ok!(StorageProviders::add_bucket(
+                        Some(msp_id),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(value_prop_id)
+                    ));
+
+                    let expected_hold_amount =
+                        (i + 1) as u128 * <BucketDeposit as Get<u128>>::get();
+                    assert_eq!(
+                        NativeBalance::balance_on_hold(&BucketHoldReason::get(), &bucket_owner),
+                        expected_hold_amount
+                    );
+                }
+
+                let buckets =
+                    crate::MainStorageProviderIdsToBuckets::<Test>::iter_key_prefix(&msp_id)
+                        .collect::<Vec<_>>();
+
+                assert_eq!(buckets.len(), num_buckets as usize);
+
+                // Increase the bucket size of all buckets
+                for i in 0..num_buckets {
+                    let bucket_name =
+                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
+                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                        &bucket_owner,
+                        bucket_name,
+                    );
+
+                    let current_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ).unwrap_or_default();
+
+                    assert_ok!(<crate::Pallet<Test> as MutateBucketsInterface>::increase_bucket_size(
+                        &bucket_id,
+                        delta_increase
+                    ));
+
+					// Check that the fixed rate payment stream matches the expected rate
+                    let value_prop = MainStorageProviderIdsToValuePropositions::<Test>::get(&msp_id, value_prop_id).unwrap();
+                    let delta_rate = value_prop.price_per_giga_unit_of_data_per_block.multiply_rational(delta_increase as u128, GIGAUNIT as u128, Rounding::NearestPrefUp).unwrap();
+                    let expected_rate = current_rate + delta_rate;
+                    let actual_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ).unwrap_or_default();
+
+                    assert_eq!(actual_rate, expected_rate);
+                }
+
+                // Decrease the bucket size of all buckets
+                for i in 0..num_buckets {
+                    let bucket_name =
+                        BoundedVec::try_from(format!("bucket{}", i).as_bytes().to_vec()).unwrap();
+                    let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                        &bucket_owner,
+                        bucket_name,
+                    );
+
+                    let current_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ).unwrap_or_default();
+
+                    assert_ok!(<crate::Pallet<Test> as MutateBucketsInterface>::decrease_bucket_size(
+                        &bucket_id,
+                        delta_increase
+                    ));
+
+					// Check that the fixed rate payment stream matches the expected rate
+                    let value_prop = MainStorageProviderIdsToValuePropositions::<Test>::get(&msp_id, value_prop_id).unwrap();
+                    let delta_rate = value_prop.price_per_giga_unit_of_data_per_block.multiply_rational(delta_increase as u128, GIGAUNIT as u128, Rounding::NearestPrefUp).unwrap();
+                    let expected_rate = current_rate - delta_rate;
+                    let actual_rate = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ).unwrap_or_default();
+
+                    assert_eq!(actual_rate, expected_rate);
+                }
+            });
+        }
+
+        #[test]
+        fn decrease_bucket_size_without_msp() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Add a bucket for Alice
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
+
+                // Remove the MSP from the bucket
+                assert_ok!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::unassign_msp_from_bucket(
+                        &bucket_id
+                    )
+                );
+
+                assert_ok!(
+                    <crate::Pallet<Test> as MutateBucketsInterface>::decrease_bucket_size(
+                        &bucket_id, 100
+                    ),
+                );
+            });
+        }
+    }
+}
+
+mod storage_data_unit_and_balance_converters {
+    use super::*;
+
+    #[test]
+    fn ensure_conversion_results_match() {
+        ExtBuilder::build().execute_with(|| {
+            let sp_min_capacity = SpMinCapacity::get();
+            let capacities = vec![
+                sp_min_capacity,
+                sp_min_capacity + 100,
+                sp_min_capacity + 500,
+                sp_min_capacity * 2,
+            ];
+
+            for storage_data_unit in capacities {
+                let computed_deposit =
+                    StorageProviders::compute_deposit_needed_for_capacity(storage_data_unit)
+                        .unwrap();
+
+                let computed_capacity =
+                    StorageProviders::compute_capacity_from_held_deposit(computed_deposit).unwrap();
+
+                assert_eq!(storage_data_unit, computed_capacity);
+            }
         });
     }
 }
@@ -5093,7 +5603,12 @@ mod slash_and_top_up {
                     &self.account,
                 );
 
-                pallet_proofs_dealer::SlashableProviders::<Test>::insert(&self.provider_id, 1);
+                pallet_proofs_dealer::SlashableProviders::<Test>::insert(
+                    &self.provider_id,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: 1,
+                    },
+                );
 
                 let computed_slash_amount_for_single_accrued_slash =
                     StorageProviders::compute_worst_case_scenario_slashable_amount(
@@ -5112,7 +5627,9 @@ mod slash_and_top_up {
 
                 pallet_proofs_dealer::SlashableProviders::<Test>::insert(
                     &self.provider_id,
-                    accrued_slashes as u32,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: accrued_slashes as u32,
+                    },
                 );
 
                 // Compute slash amount based on actual accrued slashes
@@ -5159,6 +5676,7 @@ mod slash_and_top_up {
                         if let RuntimeEvent::StorageProviders(Event::<Test>::Slashed {
                             provider_id,
                             amount,
+                            ..
                         }) = event.event
                         {
                             Some((provider_id, amount))
@@ -5447,638 +5965,1939 @@ mod slash_and_top_up {
         }
 
         #[test]
-        fn slash_storage_provider_automatic_top_up() {
+        fn slash_storage_provider_automatic_top_up() {
+            ExtBuilder::build().execute_with(|| {
+                let mut test_setup = TestSetup::default();
+
+                test_setup.induce_capacity_deficit = true;
+                test_setup.automatic_top_up = true;
+                test_setup.slash_and_verify();
+            });
+        }
+
+        #[test]
+        fn slash_storage_provider_automatic_top_up_after_first_slash() {
+            ExtBuilder::build().execute_with(|| {
+                let mut test_setup = TestSetup::default();
+
+                test_setup.induce_capacity_deficit = true;
+                test_setup.slash_and_verify();
+
+                test_setup.automatic_top_up = true;
+                test_setup.slash_and_verify();
+            });
+        }
+
+        #[test]
+        fn slash_storage_provider_manual_top_up() {
+            ExtBuilder::build().execute_with(|| {
+                let mut test_setup = TestSetup::default();
+
+                test_setup.induce_capacity_deficit = true;
+                test_setup.slash_and_verify();
+
+                test_setup.manual_top_up();
+            });
+        }
+
+        #[test]
+        fn slash_amount_scales_with_used_capacity() {
+            ExtBuilder::build().execute_with(|| {
+                let slash_amount_per_max_file_size: BalanceOf<Test> =
+                    <Test as crate::Config>::SlashAmountPerMaxFileSize::get();
+                let reference_data_size: StorageDataUnit<Test> =
+                    <Test as crate::Config>::SlashReferenceDataSize::get();
+
+                // Storing exactly the reference data size is slashed the full amount.
+                assert_eq!(
+                    StorageProviders::compute_slash_amount_per_failed_proof_submission(
+                        reference_data_size
+                    ),
+                    slash_amount_per_max_file_size
+                );
+
+                // Storing more than the reference data size is slashed proportionally more.
+                assert_eq!(
+                    StorageProviders::compute_slash_amount_per_failed_proof_submission(
+                        reference_data_size * 2
+                    ),
+                    slash_amount_per_max_file_size * 2
+                );
+                assert_eq!(
+                    StorageProviders::compute_slash_amount_per_failed_proof_submission(
+                        reference_data_size * 10
+                    ),
+                    slash_amount_per_max_file_size * 10
+                );
+
+                // Storing less than the reference data size is slashed proportionally.
+                assert_eq!(
+                    StorageProviders::compute_slash_amount_per_failed_proof_submission(
+                        reference_data_size / 2
+                    ),
+                    slash_amount_per_max_file_size / 2
+                );
+                assert_eq!(
+                    StorageProviders::compute_slash_amount_per_failed_proof_submission(0),
+                    0
+                );
+            });
+        }
+
+        #[test]
+        fn slashes_keep_escalating_while_deadlines_keep_being_missed() {
+            ExtBuilder::build().execute_with(|| {
+                let test_setup = TestSetup::default();
+                let provider_id = test_setup.provider_id;
+                let used_capacity = MainStorageProviders::<Test>::get(provider_id)
+                    .unwrap()
+                    .capacity_used;
+                let base_slash_amount =
+                    StorageProviders::compute_slash_amount_per_failed_proof_submission(
+                        used_capacity,
+                    ) * 2;
+
+                // The Provider is slashed after missing two deadlines in a row.
+                pallet_proofs_dealer::SlashableProviders::<Test>::insert(
+                    &provider_id,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: 1,
+                    },
+                );
+                pallet_proofs_dealer::ConsecutiveMissedDeadlines::<Test>::insert(&provider_id, 2);
+                let first_slash_amount =
+                    StorageProviders::compute_worst_case_scenario_slashable_amount(&provider_id)
+                        .unwrap();
+                assert_eq!(
+                    first_slash_amount,
+                    StorageProviders::apply_slash_escalation(base_slash_amount, 2)
+                );
+                assert_ok!(StorageProviders::slash(
+                    RuntimeOrigin::signed(test_setup.account),
+                    provider_id
+                ));
+
+                // Slashing clears the accrued failed proof submissions, but not the streak.
+                assert!(
+                    !pallet_proofs_dealer::SlashableProviders::<Test>::contains_key(&provider_id)
+                );
+                assert_eq!(
+                    pallet_proofs_dealer::ConsecutiveMissedDeadlines::<Test>::get(&provider_id),
+                    2
+                );
+
+                // Missing the next deadline escalates the next slash further.
+                pallet_proofs_dealer::SlashableProviders::<Test>::insert(
+                    &provider_id,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: 1,
+                    },
+                );
+                pallet_proofs_dealer::ConsecutiveMissedDeadlines::<Test>::insert(&provider_id, 3);
+                let second_slash_amount =
+                    StorageProviders::compute_worst_case_scenario_slashable_amount(&provider_id)
+                        .unwrap();
+                assert_eq!(
+                    second_slash_amount,
+                    StorageProviders::apply_slash_escalation(base_slash_amount, 3)
+                );
+                assert!(second_slash_amount > first_slash_amount);
+            });
+        }
+
+        #[test]
+        fn slash_amount_escalates_with_consecutive_missed_deadlines() {
+            ExtBuilder::build().execute_with(|| {
+                let base_slash_amount: BalanceOf<Test> = 1000;
+                let escalation_per_missed_deadline =
+                    <Test as crate::Config>::SlashEscalationPerMissedDeadline::get();
+                let max_multiplier: u32 =
+                    <Test as crate::Config>::MaxSlashEscalationMultiplier::get();
+
+                // A single missed deadline is not escalated.
+                assert_eq!(
+                    StorageProviders::apply_slash_escalation(base_slash_amount, 1),
+                    base_slash_amount
+                );
+
+                // Each extra missed deadline in a row adds a fraction of the base amount.
+                assert_eq!(
+                    StorageProviders::apply_slash_escalation(base_slash_amount, 3),
+                    base_slash_amount
+                        + escalation_per_missed_deadline.mul_floor(base_slash_amount) * 2
+                );
+
+                // The escalation is capped.
+                assert_eq!(
+                    StorageProviders::apply_slash_escalation(base_slash_amount, u32::MAX),
+                    base_slash_amount * max_multiplier as BalanceOf<Test>
+                );
+            });
+        }
+
+        #[test]
+        fn automatic_top_up_after_many_slashes() {
+            ExtBuilder::build().execute_with(|| {
+                let mut test_setup = TestSetup::default();
+                // Test accrued slashes
+                test_setup.slash_and_verify();
+                test_setup.induce_capacity_deficit = true;
+                test_setup.slash_and_verify();
+
+                // Test automatic top up when provider is slashed
+                test_setup.automatic_top_up = true;
+                test_setup.slash_and_verify();
+            });
+        }
+
+        #[test]
+        fn manual_top_up_after_many_slashes() {
+            ExtBuilder::build().execute_with(|| {
+                let mut alice_test_setup = TestSetup::default();
+
+                alice_test_setup.slash_and_verify();
+
+                alice_test_setup.induce_capacity_deficit = true;
+                alice_test_setup.slash_and_verify();
+
+                alice_test_setup.manual_top_up();
+            });
+        }
+
+        #[test]
+        fn top_up_expired_provider_marked_as_insolvent() {
+            ExtBuilder::build().execute_with(|| {
+                let mut alice_test_setup = TestSetup::default();
+
+                alice_test_setup.slash_and_verify();
+
+                alice_test_setup.induce_capacity_deficit = true;
+                alice_test_setup.slash_and_verify();
+
+                alice_test_setup.wait_for_top_up_expiration();
+
+                // Check that the provider is marked as insolvent
+                assert!(InsolventProviders::<Test>::get(
+                    StorageProviderId::<Test>::MainStorageProvider(alice_test_setup.provider_id)
+                )
+                .is_some());
+            });
+        }
+
+        #[test]
+        fn top_up_expired_provider_manual_top_up_not_insolvent() {
+            ExtBuilder::build().execute_with(|| {
+                let mut alice_test_setup = TestSetup::default();
+
+                alice_test_setup.slash_and_verify();
+
+                alice_test_setup.induce_capacity_deficit = true;
+                alice_test_setup.slash_and_verify();
+
+                alice_test_setup.manual_top_up();
+
+                alice_test_setup.wait_for_top_up_expiration();
+
+                // Check that the provider was not marked as insolvent
+                assert!(InsolventProviders::<Test>::get(
+                    StorageProviderId::<Test>::MainStorageProvider(alice_test_setup.provider_id)
+                )
+                .is_none());
+            });
+        }
+    }
+}
+
+mod multiaddresses {
+    use super::*;
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn add_multiaddress_fails_when_provider_not_registered() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                // Try to add a multiaddress to an account that is not registered as an MSP
+                assert_noop!(
+                    StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        new_multiaddress
+                    ),
+                    Error::<Test>::NotRegistered
+                );
+            });
+        }
+
+        #[test]
+        fn add_multiaddress_fails_if_multiaddress_already_exists() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                // Add a multiaddress to Alice
+                assert_ok!(StorageProviders::add_multiaddress(
+                    RuntimeOrigin::signed(alice),
+                    new_multiaddress.clone()
+                ));
+
+                // Try to add the same multiaddress to Alice
+                assert_noop!(
+                    StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        new_multiaddress
+                    ),
+                    Error::<Test>::MultiAddressAlreadyExists
+                );
+            });
+        }
+
+        #[test]
+        fn add_multiaddress_fails_if_provider_is_insolvent() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                let alice_msp_id =
+                    crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+
+                // Simulate insolvent provider
+                InsolventProviders::<Test>::insert(
+                    StorageProviderId::<Test>::MainStorageProvider(alice_msp_id),
+                    (),
+                );
+
+                assert_noop!(
+                    StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        new_multiaddress
+                    ),
+                    Error::<Test>::OperationNotAllowedForInsolventProvider
+                );
+
+                let bob: AccountId = accounts::BOB.0;
+                // Register Bob as a Backup Storage Provider
+                let (_bob_deposit, _bob_bsp) = register_account_as_bsp(bob, 100);
+
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                let bob_bsp_id =
+                    crate::AccountIdToBackupStorageProviderId::<Test>::get(&bob).unwrap();
+
+                // Simulate insolvent provider
+                InsolventProviders::<Test>::insert(
+                    StorageProviderId::<Test>::BackupStorageProvider(bob_bsp_id),
+                    (),
+                );
+
+                assert_noop!(
+                    StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(bob),
+                        new_multiaddress
+                    ),
+                    Error::<Test>::OperationNotAllowedForInsolventProvider
+                );
+            });
+        }
+
+        #[test]
+        fn add_multiaddress_fails_if_max_multiaddresses_reached() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                // Add the maximum amount of multiaddresses for Alice (we start at 1 since Alice already has a MultiAddress)
+                for i in 1..MaxMultiAddressAmount::<Test>::get() {
+                    let multiaddress: MultiAddress<Test> = format!(
+                        "/ip4/127.0.0.1/udp/1234/new/multiaddress/{}",
+                        i.to_string().as_str()
+                    )
+                    .as_bytes()
+                    .to_vec()
+                    .try_into()
+                    .unwrap();
+                    assert_ok!(StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        multiaddress
+                    ));
+                }
+
+                let multiaddress_over_limit: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                // Try to add another multiaddress for Alice
+                assert_noop!(
+                    StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        multiaddress_over_limit
+                    ),
+                    Error::<Test>::MultiAddressesMaxAmountReached
+                );
+            });
+        }
+
+        #[test]
+        fn remove_multiaddress_fails_when_provider_not_registered() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                // Try to remove a multiaddress from an account that is not registered as an MSP
+                assert_noop!(
+                    StorageProviders::remove_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        new_multiaddress
+                    ),
+                    Error::<Test>::NotRegistered
+                );
+            });
+        }
+
+        #[test]
+        fn remove_multiaddress_fails_when_multiaddress_does_not_exist() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                // Add a new multiaddress to Alice
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+                assert_ok!(StorageProviders::add_multiaddress(
+                    RuntimeOrigin::signed(alice),
+                    new_multiaddress.clone()
+                ));
+
+                // Get a multiaddress that does not exist
+                let non_saved_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/no/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                // Try to remove a multiaddress that does not exist
+                assert_noop!(
+                    StorageProviders::remove_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        non_saved_multiaddress
+                    ),
+                    Error::<Test>::MultiAddressNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn remove_multiaddress_fails_if_multiaddress_is_the_last_one() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                // Try to remove the only multiaddress of Alice
+                assert_noop!(
+                    StorageProviders::remove_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        "/ip4/127.0.0.1/udp/1234"
+                            .as_bytes()
+                            .to_vec()
+                            .try_into()
+                            .unwrap()
+                    ),
+                    Error::<Test>::LastMultiAddressCantBeRemoved
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn add_multiaddress() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                // Add a multiaddress to Alice
+                assert_ok!(StorageProviders::add_multiaddress(
+                    RuntimeOrigin::signed(alice),
+                    new_multiaddress.clone()
+                ));
+
+                // Check that the multiaddress was added to the MSP
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
+
+                assert_eq!(msp_info.multiaddresses.len(), 2);
+                assert_eq!(msp_info.multiaddresses[1], new_multiaddress);
+            });
+        }
+
+        #[test]
+        fn add_multiaddress_to_max_multiaddresses() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                // Add the maximum amount of multiaddresses for Alice (we start at 1 since Alice already has a MultiAddress)
+                for i in 1usize..<MaxMultiAddressAmount<Test> as Get<u32>>::get() as usize {
+                    let multiaddress: MultiAddress<Test> = format!(
+                        "/ip4/127.0.0.1/udp/1234/new/multiaddress/{}",
+                        i.to_string().as_str()
+                    )
+                    .as_bytes()
+                    .to_vec()
+                    .try_into()
+                    .unwrap();
+                    assert_ok!(StorageProviders::add_multiaddress(
+                        RuntimeOrigin::signed(alice),
+                        multiaddress.clone()
+                    ));
+
+                    let msp_id =
+                        crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                    let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
+
+                    assert_eq!(msp_info.multiaddresses[i], multiaddress);
+                    assert_eq!(msp_info.multiaddresses.len(), i + 1);
+                }
+            });
+        }
+
+        #[test]
+        fn remove_multiaddress() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                // We first add a multiaddress to Alice
+                let new_multiaddress: MultiAddress<Test> =
+                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
+                        .as_bytes()
+                        .to_vec()
+                        .try_into()
+                        .unwrap();
+
+                assert_ok!(StorageProviders::add_multiaddress(
+                    RuntimeOrigin::signed(alice),
+                    new_multiaddress.clone()
+                ));
+
+                // Check that the multiaddress was added to the MSP
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
+                assert_eq!(msp_info.multiaddresses.len(), 2);
+                assert_eq!(msp_info.multiaddresses[1], new_multiaddress);
+
+                // Remove the original multiaddress from Alice
+                let initial_multiaddress = msp_info.multiaddresses[0].clone();
+                assert_ok!(StorageProviders::remove_multiaddress(
+                    RuntimeOrigin::signed(alice),
+                    initial_multiaddress.clone()
+                ));
+
+                // Check that the multiaddress was removed from the MSP
+                let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
+                assert_eq!(msp_info.multiaddresses.len(), 1);
+                assert_eq!(msp_info.multiaddresses[0], new_multiaddress);
+            });
+        }
+    }
+}
+
+mod add_value_prop {
+    use super::*;
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn account_is_not_a_registered_msp() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let value_prop = ValueProposition::<Test>::new(1, bounded_vec![], 10);
+
+                // Try to add a value proposition to an account that is not a registered MSP
+                assert_noop!(
+                    StorageProviders::add_value_prop(
+                        RuntimeOrigin::signed(alice),
+                        value_prop.price_per_giga_unit_of_data_per_block,
+                        value_prop.commitment.clone(),
+                        value_prop.bucket_data_limit
+                    ),
+                    Error::<Test>::NotRegistered
+                );
+            });
+        }
+
+        #[test]
+        fn value_prop_already_exists() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                assert_ok!(StorageProviders::add_value_prop(
+                    RuntimeOrigin::signed(alice),
+                    value_prop.price_per_giga_unit_of_data_per_block,
+                    value_prop.commitment.clone(),
+                    value_prop.bucket_data_limit
+                ));
+
+                assert_noop!(
+                    StorageProviders::add_value_prop(
+                        RuntimeOrigin::signed(alice),
+                        value_prop.price_per_giga_unit_of_data_per_block,
+                        value_prop.commitment.clone(),
+                        value_prop.bucket_data_limit
+                    ),
+                    Error::<Test>::ValuePropositionAlreadyExists
+                );
+            });
+        }
+
+        #[test]
+        fn add_value_prop_fails_with_insolvent_provider() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                let alice_msp_id = StorageProviders::get_provider_id(alice).unwrap();
+                // Simulate insolvent provider
+                InsolventProviders::<Test>::insert(
+                    StorageProviderId::<Test>::MainStorageProvider(alice_msp_id),
+                    (),
+                );
+
+                assert_noop!(
+                    StorageProviders::add_value_prop(
+                        RuntimeOrigin::signed(alice),
+                        value_prop.price_per_giga_unit_of_data_per_block,
+                        value_prop.commitment.clone(),
+                        value_prop.bucket_data_limit
+                    ),
+                    Error::<Test>::OperationNotAllowedForInsolventProvider
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn add_value_prop_works() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                assert_ok!(StorageProviders::add_value_prop(
+                    RuntimeOrigin::signed(alice),
+                    value_prop.price_per_giga_unit_of_data_per_block,
+                    value_prop.commitment.clone(),
+                    value_prop.bucket_data_limit
+                ));
+
+                let value_prop_id = value_prop.derive_id();
+
+                // Check event is emitted
+                System::assert_last_event(
+                    Event::<Test>::ValuePropAdded {
+                        msp_id,
+                        value_prop_id,
+                        value_prop: value_prop.clone(),
+                    }
+                    .into(),
+                );
+
+                assert_eq!(
+                    crate::MainStorageProviderIdsToValuePropositions::<Test>::get(
+                        &msp_id,
+                        value_prop_id
+                    ),
+                    Some(value_prop)
+                );
+            });
+        }
+    }
+}
+
+mod make_value_prop_unavailable {
+    use super::*;
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn account_is_not_a_registered_msp() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let value_prop = ValueProposition::<Test>::new(1, bounded_vec![], 10);
+
+                // Try to make a value proposition unavailable to an account that is not a registered MSP
+                assert_noop!(
+                    StorageProviders::make_value_prop_unavailable(
+                        RuntimeOrigin::signed(alice),
+                        value_prop.derive_id()
+                    ),
+                    Error::<Test>::NotRegistered
+                );
+            });
+        }
+
+        #[test]
+        fn value_prop_does_not_exist() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                assert_noop!(
+                    StorageProviders::make_value_prop_unavailable(
+                        RuntimeOrigin::signed(alice),
+                        value_prop.derive_id()
+                    ),
+                    Error::<Test>::ValuePropositionNotFound
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn make_value_prop_unavailable_works() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                assert_ok!(StorageProviders::add_value_prop(
+                    RuntimeOrigin::signed(alice),
+                    value_prop.price_per_giga_unit_of_data_per_block,
+                    value_prop.commitment.clone(),
+                    value_prop.bucket_data_limit
+                ));
+
+                let value_prop_id = value_prop.derive_id();
+
+                assert_ok!(StorageProviders::make_value_prop_unavailable(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id
+                ));
+
+                // Check event is emitted
+                System::assert_last_event(
+                    Event::<Test>::ValuePropUnavailable {
+                        msp_id,
+                        value_prop_id,
+                    }
+                    .into(),
+                );
+
+                assert_eq!(
+                    crate::MainStorageProviderIdsToValuePropositions::<Test>::get(
+                        &msp_id,
+                        value_prop_id
+                    )
+                    .unwrap(),
+                    ValueProposition::<Test> {
+                        price_per_giga_unit_of_data_per_block: 999,
+                        commitment: bounded_vec![],
+                        bucket_data_limit: 999,
+                        available: false
+                    }
+                );
+            });
+        }
+
+        #[test]
+        fn create_bucket_fails_when_value_prop_is_unavailable() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                assert_ok!(StorageProviders::add_value_prop(
+                    RuntimeOrigin::signed(alice),
+                    value_prop.price_per_giga_unit_of_data_per_block,
+                    value_prop.commitment.clone(),
+                    value_prop.bucket_data_limit
+                ));
+
+                let value_prop_id = value_prop.derive_id();
+
+                assert_ok!(StorageProviders::make_value_prop_unavailable(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id
+                ));
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Try to add a bucket with an unavailable value proposition
+                assert_noop!(
+                    StorageProviders::add_bucket(
+                        Some(msp_id),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(value_prop_id)
+                    ),
+                    Error::<Test>::ValuePropositionNotAvailable
+                );
+            });
+        }
+    }
+}
+
+mod publish_value_prop_version {
+    use super::*;
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn value_prop_does_not_exist() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+
+                assert_noop!(
+                    StorageProviders::publish_value_prop_version(
+                        RuntimeOrigin::signed(alice),
+                        value_prop.derive_id(),
+                        2,
+                        bounded_vec![],
+                        100
+                    ),
+                    Error::<Test>::ValuePropositionNotFound
+                );
+            });
+        }
+
+        #[test]
+        fn value_prop_already_superseded() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+
+                assert_ok!(StorageProviders::publish_value_prop_version(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id,
+                    2,
+                    bounded_vec![],
+                    100
+                ));
+
+                // Only the latest version of a value proposition can be superseded
+                assert_noop!(
+                    StorageProviders::publish_value_prop_version(
+                        RuntimeOrigin::signed(alice),
+                        value_prop_id,
+                        3,
+                        bounded_vec![],
+                        100
+                    ),
+                    Error::<Test>::ValuePropositionSuperseded
+                );
+            });
+        }
+
+        #[test]
+        fn create_bucket_fails_with_superseded_value_prop() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_ok!(StorageProviders::publish_value_prop_version(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id,
+                    2,
+                    bounded_vec![],
+                    100
+                ));
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // New buckets always get the latest version of a value proposition
+                assert_noop!(
+                    StorageProviders::add_bucket(
+                        Some(msp_id),
+                        bucket_owner,
+                        bucket_id,
+                        false,
+                        None,
+                        Some(value_prop_id)
+                    ),
+                    Error::<Test>::ValuePropositionSuperseded
+                );
+            });
+        }
+
+        #[test]
+        fn migrate_bucket_already_on_latest_value_prop() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
+
+                assert_noop!(
+                    StorageProviders::migrate_bucket_to_latest_value_prop(&bucket_id),
+                    Error::<Test>::BucketAlreadyOnLatestValueProposition
+                );
+            });
+        }
+
+        #[test]
+        fn migrate_bucket_exceeding_latest_value_prop_data_limit() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
+                assert_ok!(StorageProviders::increase_bucket_size(&bucket_id, 50));
+
+                // The new version only allows buckets of up to 10 units of data
+                assert_ok!(StorageProviders::publish_value_prop_version(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id,
+                    1,
+                    bounded_vec![],
+                    10
+                ));
+
+                assert_noop!(
+                    StorageProviders::migrate_bucket_to_latest_value_prop(&bucket_id),
+                    Error::<Test>::BucketSizeExceedsLimit
+                );
+            });
+        }
+    }
+
+    mod success {
+        use super::*;
+
+        #[test]
+        fn publish_value_prop_version_works() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let new_value_prop = ValueProposition::<Test>::new(2, bounded_vec![], 100);
+                let new_value_prop_id = new_value_prop.derive_id();
+
+                assert_ok!(StorageProviders::publish_value_prop_version(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id,
+                    new_value_prop.price_per_giga_unit_of_data_per_block,
+                    new_value_prop.commitment.clone(),
+                    new_value_prop.bucket_data_limit
+                ));
+
+                let version = ValuePropositionVersion::<Test> {
+                    lineage: value_prop_id,
+                    version: 2,
+                };
+
+                // Check event is emitted
+                System::assert_last_event(
+                    Event::<Test>::ValuePropVersionPublished {
+                        msp_id,
+                        previous_value_prop_id: value_prop_id,
+                        value_prop_id: new_value_prop_id,
+                        value_prop: new_value_prop.clone(),
+                        version: version.clone(),
+                    }
+                    .into(),
+                );
+
+                // The previous version is no longer available, and the new one is
+                assert!(
+                    !crate::MainStorageProviderIdsToValuePropositions::<Test>::get(
+                        &msp_id,
+                        value_prop_id
+                    )
+                    .unwrap()
+                    .available
+                );
+                assert_eq!(
+                    crate::MainStorageProviderIdsToValuePropositions::<Test>::get(
+                        &msp_id,
+                        new_value_prop_id
+                    ),
+                    Some(new_value_prop.clone())
+                );
+
+                // Check the versions are reported correctly
+                assert_eq!(
+                    StorageProviders::query_value_proposition_version(&msp_id, &value_prop_id),
+                    Ok(ValuePropositionVersion::<Test>::first(value_prop_id))
+                );
+                assert_eq!(
+                    StorageProviders::query_value_proposition_version(&msp_id, &new_value_prop_id),
+                    Ok(version)
+                );
+                assert_eq!(
+                    StorageProviders::query_latest_value_proposition(&msp_id, &value_prop_id),
+                    Ok(ValuePropositionWithId::new(
+                        new_value_prop_id,
+                        new_value_prop
+                    ))
+                );
+
+                // A third version continues the same lineage
+                assert_ok!(StorageProviders::publish_value_prop_version(
+                    RuntimeOrigin::signed(alice),
+                    new_value_prop_id,
+                    3,
+                    bounded_vec![],
+                    100
+                ));
+                let third_value_prop_id =
+                    ValueProposition::<Test>::new(3, bounded_vec![], 100).derive_id();
+
+                assert_eq!(
+                    StorageProviders::query_value_proposition_version(
+                        &msp_id,
+                        &third_value_prop_id
+                    ),
+                    Ok(ValuePropositionVersion::<Test> {
+                        lineage: value_prop_id,
+                        version: 3,
+                    })
+                );
+                assert_eq!(
+                    StorageProviders::latest_value_prop_id(&msp_id, &value_prop_id),
+                    third_value_prop_id
+                );
+            });
+        }
+
+        #[test]
+        fn existing_buckets_are_grandfathered_until_migrated() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) = register_account_as_msp(
+                    alice,
+                    storage_amount,
+                    Some(GIGAUNIT.into()),
+                    None,
+                );
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
+                assert_ok!(StorageProviders::increase_bucket_size(&bucket_id, 10));
+
+                let rate_before = <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                    &msp_id,
+                    &bucket_owner
+                ).unwrap();
+
+                // Double the price of the value proposition
+                let new_value_prop =
+                    ValueProposition::<Test>::new(2 * GIGAUNIT as u128, bounded_vec![], 100);
+                let new_value_prop_id = new_value_prop.derive_id();
+                assert_ok!(StorageProviders::publish_value_prop_version(
+                    RuntimeOrigin::signed(alice),
+                    value_prop_id,
+                    new_value_prop.price_per_giga_unit_of_data_per_block,
+                    new_value_prop.commitment.clone(),
+                    new_value_prop.bucket_data_limit
+                ));
+
+                // The existing bucket keeps the previous version and its rate
+                assert_eq!(
+                    crate::Buckets::<Test>::get(&bucket_id).unwrap().value_prop_id,
+                    Some(value_prop_id)
+                );
+                assert_eq!(
+                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ),
+                    Some(rate_before)
+                );
+
+                // Until it is migrated to the latest version
+                assert_eq!(
+                    StorageProviders::migrate_bucket_to_latest_value_prop(&bucket_id),
+                    Ok((value_prop_id, new_value_prop_id))
+                );
+
+                System::assert_last_event(
+                    Event::<Test>::BucketValuePropMigrated {
+                        bucket_id,
+                        msp_id,
+                        previous_value_prop_id: value_prop_id,
+                        value_prop_id: new_value_prop_id,
+                    }
+                    .into(),
+                );
+
+                assert_eq!(
+                    crate::Buckets::<Test>::get(&bucket_id).unwrap().value_prop_id,
+                    Some(new_value_prop_id)
+                );
+
+                // The rate of the bucket is now the one of the new version
+                let zero_size_bucket_rate: u128 =
+                    <Test as crate::Config>::ZeroSizeBucketFixedRate::get();
+                assert_eq!(rate_before, zero_size_bucket_rate + 10);
+                assert_eq!(
+                    <<Test as crate::Config>::PaymentStreams as PaymentStreamsInterface>::get_inner_fixed_rate_payment_stream_value(
+                        &msp_id,
+                        &bucket_owner
+                    ),
+                    Some(zero_size_bucket_rate + 20)
+                );
+            });
+        }
+    }
+}
+
+mod delete_provider {
+    use super::*;
+
+    mod failure {
+        use super::*;
+
+        #[test]
+        fn deleting_provider_fails_if_not_insolvent() {
             ExtBuilder::build().execute_with(|| {
-                let mut test_setup = TestSetup::default();
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
 
-                test_setup.induce_capacity_deficit = true;
-                test_setup.automatic_top_up = true;
-                test_setup.slash_and_verify();
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+
+                assert_noop!(
+                    StorageProviders::delete_provider(RuntimeOrigin::signed(alice), msp_id),
+                    Error::<Test>::DeleteProviderConditionsNotMet
+                );
             });
         }
 
         #[test]
-        fn slash_storage_provider_automatic_top_up_after_first_slash() {
+        fn deleting_provider_fails_if_payment_stream_exists() {
             ExtBuilder::build().execute_with(|| {
-                let mut test_setup = TestSetup::default();
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
 
-                test_setup.induce_capacity_deficit = true;
-                test_setup.slash_and_verify();
+                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
 
-                test_setup.automatic_top_up = true;
-                test_setup.slash_and_verify();
+                let bucket_owner = accounts::BOB.0;
+                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
+                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
+                    &bucket_owner,
+                    bucket_name,
+                );
+
+                // Add a bucket for Alice which creates a payment stream
+                assert_ok!(StorageProviders::add_bucket(
+                    Some(msp_id),
+                    bucket_owner,
+                    bucket_id,
+                    false,
+                    None,
+                    Some(value_prop_id)
+                ));
+
+                InsolventProviders::<Test>::insert(
+                    StorageProviderId::<Test>::MainStorageProvider(msp_id),
+                    (),
+                );
+
+                assert_noop!(
+                    StorageProviders::delete_provider(RuntimeOrigin::signed(alice), msp_id),
+                    Error::<Test>::DeleteProviderConditionsNotMet
+                );
             });
         }
+    }
+
+    mod success {
+        use super::*;
 
         #[test]
-        fn slash_storage_provider_manual_top_up() {
+        fn deleting_provider_works() {
             ExtBuilder::build().execute_with(|| {
-                let mut test_setup = TestSetup::default();
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let (_deposit_amount, _alice_msp, _value_prop_id) =
+                    register_account_as_msp(alice, storage_amount, None, None);
 
-                test_setup.induce_capacity_deficit = true;
-                test_setup.slash_and_verify();
+                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                test_setup.manual_top_up();
+                InsolventProviders::<Test>::insert(
+                    StorageProviderId::<Test>::MainStorageProvider(msp_id),
+                    (),
+                );
+
+                assert_ok!(StorageProviders::delete_provider(
+                    RuntimeOrigin::signed(alice),
+                    msp_id
+                ));
             });
         }
+    }
+}
+
+mod capacity_utilization {
+    use super::*;
+
+    type MaxCapacityUtilizationSamples = <Test as crate::Config>::MaxCapacityUtilizationSamples;
+    type CapacityUtilizationSamplingPeriod =
+        <Test as crate::Config>::CapacityUtilizationSamplingPeriod;
+
+    mod success {
+        use super::*;
 
         #[test]
-        fn automatic_top_up_after_many_slashes() {
+        fn samples_are_taken_every_sampling_period() {
             ExtBuilder::build().execute_with(|| {
-                let mut test_setup = TestSetup::default();
-                // Test accrued slashes
-                test_setup.slash_and_verify();
-                test_setup.induce_capacity_deficit = true;
-                test_setup.slash_and_verify();
+                let alice: AccountId = accounts::ALICE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let price: BalanceOf<Test> = 50;
+                pallet_payment_streams::CurrentPricePerGigaUnitPerTick::<Test>::put(price);
 
-                // Test automatic top up when provider is slashed
-                test_setup.automatic_top_up = true;
-                test_setup.slash_and_verify();
+                // Register Alice as a BSP and make her use some of her capacity
+                register_account_as_bsp(alice, storage_amount);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+                assert_ok!(
+                    <StorageProviders as MutateStorageProvidersInterface>::increase_capacity_used(
+                        &alice_bsp_id,
+                        30
+                    )
+                );
+
+                // Advance past three sampling periods
+                let sampling_period: u64 = CapacityUtilizationSamplingPeriod::get();
+                run_to_block(3 * sampling_period + 1);
+
+                let samples = CapacityUtilizationSamples::<Test>::get();
+                assert_eq!(samples.len(), 3);
+
+                // Samples are ordered from oldest to newest and taken at multiples of the sampling period
+                for (i, sample) in samples.iter().enumerate() {
+                    assert_eq!(sample.tick, (i as u64 + 1) * sampling_period);
+                    assert_eq!(sample.total_capacity, storage_amount);
+                    assert_eq!(sample.price_per_giga_unit_of_data_per_tick, price);
+                }
+
+                // The last sample reflects the used capacity
+                assert_eq!(samples.last().unwrap().used_capacity, 30);
             });
         }
 
         #[test]
-        fn manual_top_up_after_many_slashes() {
+        fn only_one_sample_is_taken_per_tick() {
             ExtBuilder::build().execute_with(|| {
-                let mut alice_test_setup = TestSetup::default();
-
-                alice_test_setup.slash_and_verify();
+                let sampling_period: u64 = CapacityUtilizationSamplingPeriod::get();
+                run_to_block(sampling_period);
+                assert_eq!(CapacityUtilizationSamples::<Test>::get().len(), 1);
 
-                alice_test_setup.induce_capacity_deficit = true;
-                alice_test_setup.slash_and_verify();
+                // The tick is stalled, so the hook runs again in the same tick
+                StorageProviders::on_poll(System::block_number(), &mut WeightMeter::new());
+                StorageProviders::on_poll(System::block_number(), &mut WeightMeter::new());
 
-                alice_test_setup.manual_top_up();
+                let samples = CapacityUtilizationSamples::<Test>::get();
+                assert_eq!(samples.len(), 1);
+                assert_eq!(samples.first().unwrap().tick, sampling_period);
             });
         }
 
         #[test]
-        fn top_up_expired_provider_marked_as_insolvent() {
+        fn oldest_sample_is_discarded_when_full() {
             ExtBuilder::build().execute_with(|| {
-                let mut alice_test_setup = TestSetup::default();
-
-                alice_test_setup.slash_and_verify();
+                let max_samples: u32 = MaxCapacityUtilizationSamples::get();
+                let sampling_period: u64 = CapacityUtilizationSamplingPeriod::get();
 
-                alice_test_setup.induce_capacity_deficit = true;
-                alice_test_setup.slash_and_verify();
+                // Advance enough blocks to take two more samples than the maximum allowed
+                run_to_block((max_samples as u64 + 2) * sampling_period + 1);
 
-                alice_test_setup.wait_for_top_up_expiration();
+                let samples = CapacityUtilizationSamples::<Test>::get();
+                assert_eq!(samples.len(), max_samples as usize);
 
-                // Check that the provider is marked as insolvent
-                assert!(InsolventProviders::<Test>::get(
-                    StorageProviderId::<Test>::MainStorageProvider(alice_test_setup.provider_id)
-                )
-                .is_some());
+                // The first two samples have been discarded
+                assert_eq!(samples.first().unwrap().tick, 3 * sampling_period);
+                assert_eq!(
+                    samples.last().unwrap().tick,
+                    (max_samples as u64 + 2) * sampling_period
+                );
             });
         }
 
         #[test]
-        fn top_up_expired_provider_manual_top_up_not_insolvent() {
+        fn capacity_utilization_reports_bsps_per_utilization_decile() {
             ExtBuilder::build().execute_with(|| {
-                let mut alice_test_setup = TestSetup::default();
-
-                alice_test_setup.slash_and_verify();
+                let alice: AccountId = accounts::ALICE.0;
+                let bob: AccountId = accounts::BOB.0;
+                let charlie: AccountId = accounts::CHARLIE.0;
+                let storage_amount: StorageDataUnit<Test> = 100;
+                let price: BalanceOf<Test> = 50;
+                pallet_payment_streams::CurrentPricePerGigaUnitPerTick::<Test>::put(price);
 
-                alice_test_setup.induce_capacity_deficit = true;
-                alice_test_setup.slash_and_verify();
+                // Register three BSPs
+                register_account_as_bsp(alice, storage_amount);
+                register_account_as_bsp(bob, storage_amount);
+                register_account_as_bsp(charlie, storage_amount);
 
-                alice_test_setup.manual_top_up();
+                // Alice stays empty, Bob uses 55% of his capacity and Charlie is full
+                let bob_bsp_id = StorageProviders::get_provider_id(bob).unwrap();
+                let charlie_bsp_id = StorageProviders::get_provider_id(charlie).unwrap();
+                assert_ok!(
+                    <StorageProviders as MutateStorageProvidersInterface>::increase_capacity_used(
+                        &bob_bsp_id,
+                        55
+                    )
+                );
+                assert_ok!(
+                    <StorageProviders as MutateStorageProvidersInterface>::increase_capacity_used(
+                        &charlie_bsp_id,
+                        storage_amount
+                    )
+                );
 
-                alice_test_setup.wait_for_top_up_expiration();
+                let utilization = StorageProviders::capacity_utilization();
 
-                // Check that the provider was not marked as insolvent
-                assert!(InsolventProviders::<Test>::get(
-                    StorageProviderId::<Test>::MainStorageProvider(alice_test_setup.provider_id)
-                )
-                .is_none());
+                assert_eq!(utilization.total_capacity, 3 * storage_amount);
+                assert_eq!(utilization.used_capacity, 55 + storage_amount);
+                assert_eq!(
+                    utilization.bsps_per_utilization_decile,
+                    [1, 0, 0, 0, 0, 1, 0, 0, 0, 1]
+                );
+                assert_eq!(utilization.price_per_giga_unit_of_data_per_tick, price);
+                assert_eq!(
+                    utilization.samples,
+                    CapacityUtilizationSamples::<Test>::get()
+                );
             });
         }
     }
 }
 
-mod multiaddresses {
+mod reputation {
     use super::*;
+    use crate::{BspProofSubmissionStreaks, GlobalBspsReputationWeight, LastReputationUpdateTick};
+
+    type StartingReputationWeight = <Test as crate::Config>::StartingReputationWeight;
+    type ReputationGrowthStreak = <Test as crate::Config>::ReputationGrowthStreak;
+    type ReputationWeightIncrease = <Test as crate::Config>::ReputationWeightIncrease;
+    type MaxReputationWeight = <Test as crate::Config>::MaxReputationWeight;
+
+    /// Sets the reputation weight of a BSP, keeping the global reputation weight consistent.
+    fn set_reputation_weight(bsp_id: &HashId<Test>, weight: u32) {
+        crate::BackupStorageProviders::<Test>::mutate(bsp_id, |bsp| {
+            let bsp = bsp.as_mut().unwrap();
+            GlobalBspsReputationWeight::<Test>::mutate(|n| {
+                *n = n
+                    .saturating_sub(bsp.reputation_weight)
+                    .saturating_add(weight);
+            });
+            bsp.reputation_weight = weight;
+        });
+    }
 
-    mod failure {
+    fn reputation_weight(bsp_id: &HashId<Test>) -> u32 {
+        StorageProviders::query_bsp_reputation_weight(bsp_id).unwrap()
+    }
+
+    mod success {
         use super::*;
 
         #[test]
-        fn add_multiaddress_fails_when_provider_not_registered() {
+        fn reputation_weight_grows_every_streak_of_proofs() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+                let starting_weight = StartingReputationWeight::get();
+                let growth_streak: u32 = ReputationGrowthStreak::get();
+
+                // Submitting one proof less than the streak doesn't change the reputation weight
+                for _ in 0..growth_streak - 1 {
+                    StorageProviders::record_bsp_proof_submission(&alice_bsp_id);
+                }
+                assert_eq!(reputation_weight(&alice_bsp_id), starting_weight);
+                assert_eq!(
+                    BspProofSubmissionStreaks::<Test>::get(&alice_bsp_id),
+                    growth_streak - 1
+                );
 
-                // Try to add a multiaddress to an account that is not registered as an MSP
-                assert_noop!(
-                    StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        new_multiaddress
-                    ),
-                    Error::<Test>::NotRegistered
+                // Completing the streak increases it
+                StorageProviders::record_bsp_proof_submission(&alice_bsp_id);
+                let new_weight = starting_weight + ReputationWeightIncrease::get();
+                assert_eq!(reputation_weight(&alice_bsp_id), new_weight);
+                assert_eq!(GlobalBspsReputationWeight::<Test>::get(), new_weight);
+
+                System::assert_last_event(
+                    Event::<Test>::BspReputationWeightChanged {
+                        bsp_id: alice_bsp_id,
+                        old_weight: starting_weight,
+                        new_weight,
+                    }
+                    .into(),
                 );
             });
         }
 
         #[test]
-        fn add_multiaddress_fails_if_multiaddress_already_exists() {
+        fn reputation_weight_does_not_grow_above_maximum() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+                let max_weight = MaxReputationWeight::get();
+                set_reputation_weight(&alice_bsp_id, max_weight);
 
-                // Add a multiaddress to Alice
-                assert_ok!(StorageProviders::add_multiaddress(
-                    RuntimeOrigin::signed(alice),
-                    new_multiaddress.clone()
-                ));
+                for _ in 0..ReputationGrowthStreak::get() {
+                    StorageProviders::record_bsp_proof_submission(&alice_bsp_id);
+                }
 
-                // Try to add the same multiaddress to Alice
-                assert_noop!(
-                    StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        new_multiaddress
-                    ),
-                    Error::<Test>::MultiAddressAlreadyExists
-                );
+                assert_eq!(reputation_weight(&alice_bsp_id), max_weight);
+                assert_eq!(GlobalBspsReputationWeight::<Test>::get(), max_weight);
             });
         }
 
         #[test]
-        fn add_multiaddress_fails_if_provider_is_insolvent() {
+        fn slash_decays_earned_reputation_weight_and_resets_streak() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
-
-                let alice_msp_id =
-                    crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+                let bob: AccountId = accounts::BOB.0;
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
+                set_reputation_weight(&alice_bsp_id, 5);
+                BspProofSubmissionStreaks::<Test>::insert(&alice_bsp_id, 3);
 
-                // Simulate insolvent provider
-                InsolventProviders::<Test>::insert(
-                    StorageProviderId::<Test>::MainStorageProvider(alice_msp_id),
-                    (),
+                pallet_proofs_dealer::SlashableProviders::<Test>::insert(
+                    &alice_bsp_id,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: 1,
+                    },
                 );
+                assert_ok!(StorageProviders::slash(
+                    RuntimeOrigin::signed(bob),
+                    alice_bsp_id
+                ));
 
-                assert_noop!(
-                    StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        new_multiaddress
-                    ),
-                    Error::<Test>::OperationNotAllowedForInsolventProvider
+                // Half of the weight earned above the starting weight (4) is lost
+                assert_eq!(reputation_weight(&alice_bsp_id), 3);
+                assert_eq!(GlobalBspsReputationWeight::<Test>::get(), 3);
+                assert_eq!(BspProofSubmissionStreaks::<Test>::get(&alice_bsp_id), 0);
+
+                System::assert_has_event(
+                    Event::<Test>::BspReputationWeightChanged {
+                        bsp_id: alice_bsp_id,
+                        old_weight: 5,
+                        new_weight: 3,
+                    }
+                    .into(),
                 );
+            });
+        }
 
+        #[test]
+        fn slash_does_not_decay_reputation_weight_below_starting_weight() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
                 let bob: AccountId = accounts::BOB.0;
-                // Register Bob as a Backup Storage Provider
-                let (_bob_deposit, _bob_bsp) = register_account_as_bsp(bob, 100);
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
+                pallet_proofs_dealer::SlashableProviders::<Test>::insert(
+                    &alice_bsp_id,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: 1,
+                    },
+                );
+                assert_ok!(StorageProviders::slash(
+                    RuntimeOrigin::signed(bob),
+                    alice_bsp_id
+                ));
 
-                let bob_bsp_id =
-                    crate::AccountIdToBackupStorageProviderId::<Test>::get(&bob).unwrap();
+                assert_eq!(
+                    reputation_weight(&alice_bsp_id),
+                    StartingReputationWeight::get()
+                );
+            });
+        }
 
-                // Simulate insolvent provider
-                InsolventProviders::<Test>::insert(
-                    StorageProviderId::<Test>::BackupStorageProvider(bob_bsp_id),
-                    (),
+        #[test]
+        fn on_poll_accounts_for_proof_submitters_of_previous_tick() {
+            ExtBuilder::build().execute_with(|| {
+                let george: AccountId = accounts::GEORGE.0;
+                register_account_as_bsp(george, 100);
+                let george_bsp_id = StorageProviders::get_provider_id(george).unwrap();
+                BspProofSubmissionStreaks::<Test>::insert(
+                    &george_bsp_id,
+                    ReputationGrowthStreak::get() - 1,
                 );
 
-                assert_noop!(
-                    StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(bob),
-                        new_multiaddress
-                    ),
-                    Error::<Test>::OperationNotAllowedForInsolventProvider
+                // The mocked proof submitter of each tick is the Provider of the account `tick + 1`, and the
+                // submitters of a tick are accounted for in the following block.
+                run_to_block(george + 1);
+
+                assert_eq!(LastReputationUpdateTick::<Test>::get(), george);
+                assert_eq!(
+                    reputation_weight(&george_bsp_id),
+                    StartingReputationWeight::get() + ReputationWeightIncrease::get()
                 );
             });
         }
+    }
+
+    mod failure {
+        use super::*;
+        use pallet_storage_providers_runtime_api::QueryBspReputationWeightError;
 
         #[test]
-        fn add_multiaddress_fails_if_max_multiaddresses_reached() {
+        fn proofs_submitted_while_slashable_do_not_count() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                register_account_as_bsp(alice, 100);
+                let alice_bsp_id = StorageProviders::get_provider_id(alice).unwrap();
 
-                // Add the maximum amount of multiaddresses for Alice (we start at 1 since Alice already has a MultiAddress)
-                for i in 1..MaxMultiAddressAmount::<Test>::get() {
-                    let multiaddress: MultiAddress<Test> = format!(
-                        "/ip4/127.0.0.1/udp/1234/new/multiaddress/{}",
-                        i.to_string().as_str()
-                    )
-                    .as_bytes()
-                    .to_vec()
-                    .try_into()
-                    .unwrap();
-                    assert_ok!(StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        multiaddress
-                    ));
-                }
+                pallet_proofs_dealer::SlashableProviders::<Test>::insert(
+                    &alice_bsp_id,
+                    SlashableProviderRecord {
+                        accrued_failed_proof_submissions: 1,
+                    },
+                );
 
-                let multiaddress_over_limit: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
+                for _ in 0..ReputationGrowthStreak::get() {
+                    StorageProviders::record_bsp_proof_submission(&alice_bsp_id);
+                }
 
-                // Try to add another multiaddress for Alice
-                assert_noop!(
-                    StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        multiaddress_over_limit
-                    ),
-                    Error::<Test>::MultiAddressesMaxAmountReached
+                assert_eq!(BspProofSubmissionStreaks::<Test>::get(&alice_bsp_id), 0);
+                assert_eq!(
+                    reputation_weight(&alice_bsp_id),
+                    StartingReputationWeight::get()
                 );
             });
         }
 
         #[test]
-        fn remove_multiaddress_fails_when_provider_not_registered() {
+        fn query_bsp_reputation_weight_fails_if_not_bsp() {
             ExtBuilder::build().execute_with(|| {
-                let alice: AccountId = accounts::ALICE.0;
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
-
-                // Try to remove a multiaddress from an account that is not registered as an MSP
-                assert_noop!(
-                    StorageProviders::remove_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        new_multiaddress
-                    ),
-                    Error::<Test>::NotRegistered
+                assert_eq!(
+                    StorageProviders::query_bsp_reputation_weight(&H256::default()),
+                    Err(QueryBspReputationWeightError::BspNotRegistered)
                 );
             });
         }
+    }
+}
+
+mod capacity_attestation {
+    use super::*;
+    use crate::{
+        capacity_attestation::{verify_leaf_proof, CapacityPlot},
+        types::{CapacityAttestationStage, CapacityChallengeAnswer},
+        AttestedCapacities, PendingCapacityAttestations,
+    };
+    use codec::Encode;
+    use pallet_storage_providers_runtime_api::QueryCapacityChallengeError;
+
+    type CapacityAttestationChunkSize = <Test as crate::Config>::CapacityAttestationChunkSize;
+    type CapacityAttestationSpotChecks = <Test as crate::Config>::CapacityAttestationSpotChecks;
+    type CapacityAttestationWindow = <Test as crate::Config>::CapacityAttestationWindow;
+    type MaxBlocksForRandomness = <Test as crate::Config>::MaxBlocksForRandomness;
+
+    mod failure {
+        use super::*;
 
         #[test]
-        fn remove_multiaddress_fails_when_multiaddress_does_not_exist() {
+        fn bsp_sign_up_fails_without_attested_capacity_when_required() {
             ExtBuilder::build().execute_with(|| {
+                RequireCapacityAttestation::set(true);
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
 
-                // Add a new multiaddress to Alice
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
-                assert_ok!(StorageProviders::add_multiaddress(
-                    RuntimeOrigin::signed(alice),
-                    new_multiaddress.clone()
-                ));
+                // Alice attests less capacity than she signs up with
+                commit_and_answer(alice, storage_amount - 10);
 
-                // Get a multiaddress that does not exist
-                let non_saved_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/no/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
+                request_bsp_sign_up(alice, storage_amount);
+                run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
 
-                // Try to remove a multiaddress that does not exist
                 assert_noop!(
-                    StorageProviders::remove_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        non_saved_multiaddress
-                    ),
-                    Error::<Test>::MultiAddressNotFound
+                    StorageProviders::confirm_sign_up(RuntimeOrigin::signed(alice), None),
+                    Error::<Test>::CapacityNotAttested
                 );
             });
         }
 
         #[test]
-        fn remove_multiaddress_fails_if_multiaddress_is_the_last_one() {
+        fn draw_seed_fails_before_randomness_is_valid() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                request(alice, 100);
 
-                // Try to remove the only multiaddress of Alice
                 assert_noop!(
-                    StorageProviders::remove_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        "/ip4/127.0.0.1/udp/1234"
-                            .as_bytes()
-                            .to_vec()
-                            .try_into()
-                            .unwrap()
-                    ),
-                    Error::<Test>::LastMultiAddressCantBeRemoved
+                    StorageProviders::draw_capacity_plot_seed(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::RandomnessNotValidYet
                 );
             });
         }
-    }
-
-    mod success {
-        use super::*;
 
         #[test]
-        fn add_multiaddress() {
+        fn draw_seed_fails_after_max_blocks_for_randomness() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                // Add a multiaddress to Alice
-                assert_ok!(StorageProviders::add_multiaddress(
-                    RuntimeOrigin::signed(alice),
-                    new_multiaddress.clone()
-                ));
+                request(alice, 100);
 
-                // Check that the multiaddress was added to the MSP
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
-                let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
+                run_to_block(System::block_number() + MaxBlocksForRandomness::get());
 
-                assert_eq!(msp_info.multiaddresses.len(), 2);
-                assert_eq!(msp_info.multiaddresses[1], new_multiaddress);
+                assert_noop!(
+                    StorageProviders::draw_capacity_plot_seed(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::CapacityAttestationExpired
+                );
             });
         }
 
         #[test]
-        fn add_multiaddress_to_max_multiaddresses() {
+        fn draw_seed_fails_if_already_drawn() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                // Add the maximum amount of multiaddresses for Alice (we start at 1 since Alice already has a MultiAddress)
-                for i in 1usize..<MaxMultiAddressAmount<Test> as Get<u32>>::get() as usize {
-                    let multiaddress: MultiAddress<Test> = format!(
-                        "/ip4/127.0.0.1/udp/1234/new/multiaddress/{}",
-                        i.to_string().as_str()
-                    )
-                    .as_bytes()
-                    .to_vec()
-                    .try_into()
-                    .unwrap();
-                    assert_ok!(StorageProviders::add_multiaddress(
-                        RuntimeOrigin::signed(alice),
-                        multiaddress.clone()
-                    ));
-
-                    let msp_id =
-                        crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
-                    let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
+                request(alice, 100);
+                draw_seed(alice);
 
-                    assert_eq!(msp_info.multiaddresses[i], multiaddress);
-                    assert_eq!(msp_info.multiaddresses.len(), i + 1);
-                }
+                assert_noop!(
+                    StorageProviders::draw_capacity_plot_seed(RuntimeOrigin::signed(alice)),
+                    Error::<Test>::CapacityPlotSeedAlreadyDrawn
+                );
             });
         }
 
         #[test]
-        fn remove_multiaddress() {
+        fn commit_fails_before_drawing_seed() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                // We first add a multiaddress to Alice
-                let new_multiaddress: MultiAddress<Test> =
-                    "/ip4/127.0.0.1/udp/1234/new/multiaddress"
-                        .as_bytes()
-                        .to_vec()
-                        .try_into()
-                        .unwrap();
+                request(alice, 100);
 
-                assert_ok!(StorageProviders::add_multiaddress(
-                    RuntimeOrigin::signed(alice),
-                    new_multiaddress.clone()
-                ));
+                assert_noop!(
+                    StorageProviders::commit_capacity_plot(
+                        RuntimeOrigin::signed(alice),
+                        H256::repeat_byte(7)
+                    ),
+                    Error::<Test>::CapacityPlotSeedNotDrawn
+                );
+            });
+        }
 
-                // Check that the multiaddress was added to the MSP
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
-                let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
-                assert_eq!(msp_info.multiaddresses.len(), 2);
-                assert_eq!(msp_info.multiaddresses[1], new_multiaddress);
+        #[test]
+        fn commit_fails_after_window() {
+            ExtBuilder::build().execute_with(|| {
+                let alice: AccountId = accounts::ALICE.0;
+                request(alice, 100);
+                let seed = draw_seed(alice);
+                let plot = generate(&seed, alice, 100);
 
-                // Remove the original multiaddress from Alice
-                let initial_multiaddress = msp_info.multiaddresses[0].clone();
-                assert_ok!(StorageProviders::remove_multiaddress(
-                    RuntimeOrigin::signed(alice),
-                    initial_multiaddress.clone()
-                ));
+                run_to_block(System::block_number() + CapacityAttestationWindow::get() + 1);
 
-                // Check that the multiaddress was removed from the MSP
-                let msp_info = crate::MainStorageProviders::<Test>::get(&msp_id).unwrap();
-                assert_eq!(msp_info.multiaddresses.len(), 1);
-                assert_eq!(msp_info.multiaddresses[0], new_multiaddress);
+                assert_noop!(
+                    StorageProviders::commit_capacity_plot(
+                        RuntimeOrigin::signed(alice),
+                        plot.root()
+                    ),
+                    Error::<Test>::CapacityAttestationExpired
+                );
             });
         }
-    }
-}
-
-mod add_value_prop {
-    use super::*;
-    mod failure {
-        use super::*;
 
         #[test]
-        fn account_is_not_a_registered_msp() {
+        fn answer_fails_before_randomness_is_valid() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let value_prop = ValueProposition::<Test>::new(1, bounded_vec![], 10);
+                let plot = commit(alice, 100);
 
-                // Try to add a value proposition to an account that is not a registered MSP
+                assert_eq!(
+                    StorageProviders::query_capacity_challenge(&alice),
+                    Err(QueryCapacityChallengeError::RandomnessNotValidYet)
+                );
                 assert_noop!(
-                    StorageProviders::add_value_prop(
+                    StorageProviders::answer_capacity_challenge(
                         RuntimeOrigin::signed(alice),
-                        value_prop.price_per_giga_unit_of_data_per_block,
-                        value_prop.commitment.clone(),
-                        value_prop.bucket_data_limit
+                        wrong_answer_for(&plot)
                     ),
-                    Error::<Test>::NotRegistered
+                    Error::<Test>::RandomnessNotValidYet
                 );
             });
         }
 
         #[test]
-        fn value_prop_already_exists() {
+        fn answer_fails_before_committing() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+                request(alice, 100);
+                let seed = draw_seed(alice);
+                let plot = generate(&seed, alice, 100);
 
-                assert_ok!(StorageProviders::add_value_prop(
-                    RuntimeOrigin::signed(alice),
-                    value_prop.price_per_giga_unit_of_data_per_block,
-                    value_prop.commitment.clone(),
-                    value_prop.bucket_data_limit
-                ));
+                run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
 
+                assert_eq!(
+                    StorageProviders::query_capacity_challenge(&alice),
+                    Err(QueryCapacityChallengeError::PlotNotCommitted)
+                );
                 assert_noop!(
-                    StorageProviders::add_value_prop(
+                    StorageProviders::answer_capacity_challenge(
                         RuntimeOrigin::signed(alice),
-                        value_prop.price_per_giga_unit_of_data_per_block,
-                        value_prop.commitment.clone(),
-                        value_prop.bucket_data_limit
+                        wrong_answer_for(&plot)
                     ),
-                    Error::<Test>::ValuePropositionAlreadyExists
+                    Error::<Test>::CapacityPlotNotCommitted
                 );
             });
         }
 
         #[test]
-        fn add_value_prop_fails_with_insolvent_provider() {
+        fn answer_fails_after_window() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                let plot = commit(alice, 100);
 
-                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+                run_to_block(System::block_number() + CapacityAttestationWindow::get() + 1);
 
-                let alice_msp_id = StorageProviders::get_provider_id(alice).unwrap();
-                // Simulate insolvent provider
-                InsolventProviders::<Test>::insert(
-                    StorageProviderId::<Test>::MainStorageProvider(alice_msp_id),
-                    (),
+                assert_eq!(
+                    StorageProviders::query_capacity_challenge(&alice),
+                    Err(QueryCapacityChallengeError::AttestationExpired)
                 );
-
                 assert_noop!(
-                    StorageProviders::add_value_prop(
+                    StorageProviders::answer_capacity_challenge(
                         RuntimeOrigin::signed(alice),
-                        value_prop.price_per_giga_unit_of_data_per_block,
-                        value_prop.commitment.clone(),
-                        value_prop.bucket_data_limit
+                        wrong_answer_for(&plot)
                     ),
-                    Error::<Test>::OperationNotAllowedForInsolventProvider
+                    Error::<Test>::CapacityAttestationExpired
                 );
             });
         }
-    }
-
-    mod success {
-        use super::*;
 
         #[test]
-        fn add_value_prop_works() {
+        fn answer_fails_with_plot_of_another_account() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
-
-                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+                let bob: AccountId = accounts::BOB.0;
 
-                assert_ok!(StorageProviders::add_value_prop(
+                // Alice commits to the plot Bob would have with her seed, which she cannot prove as hers
+                request(alice, 100);
+                let seed = draw_seed(alice);
+                let bob_plot = generate(&seed, bob, 100);
+                assert_ok!(StorageProviders::commit_capacity_plot(
                     RuntimeOrigin::signed(alice),
-                    value_prop.price_per_giga_unit_of_data_per_block,
-                    value_prop.commitment.clone(),
-                    value_prop.bucket_data_limit
+                    bob_plot.root()
                 ));
+                run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
 
-                let value_prop_id = value_prop.derive_id();
-
-                // Check event is emitted
-                System::assert_last_event(
-                    Event::<Test>::ValuePropAdded {
-                        msp_id,
-                        value_prop_id,
-                        value_prop: value_prop.clone(),
-                    }
-                    .into(),
-                );
-
-                assert_eq!(
-                    crate::MainStorageProviderIdsToValuePropositions::<Test>::get(
-                        &msp_id,
-                        value_prop_id
+                assert_noop!(
+                    StorageProviders::answer_capacity_challenge(
+                        RuntimeOrigin::signed(alice),
+                        answer_for(alice, &bob_plot)
                     ),
-                    Some(value_prop)
+                    Error::<Test>::InvalidCapacityAttestationProof
                 );
             });
         }
-    }
-}
-
-mod make_value_prop_unavailable {
-    use super::*;
-    mod failure {
-        use super::*;
 
         #[test]
-        fn account_is_not_a_registered_msp() {
+        fn answer_fails_with_plot_not_sealed_with_drawn_seed() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let value_prop = ValueProposition::<Test>::new(1, bounded_vec![], 10);
 
-                // Try to make a value proposition unavailable to an account that is not a registered MSP
+                // Alice commits to a plot she could have precomputed before drawing the seed
+                request(alice, 100);
+                draw_seed(alice);
+                let precomputed_plot = generate(&H256::zero(), alice, 100);
+                assert_ok!(StorageProviders::commit_capacity_plot(
+                    RuntimeOrigin::signed(alice),
+                    precomputed_plot.root()
+                ));
+                run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
+
                 assert_noop!(
-                    StorageProviders::make_value_prop_unavailable(
+                    StorageProviders::answer_capacity_challenge(
                         RuntimeOrigin::signed(alice),
-                        value_prop.derive_id()
+                        answer_for(alice, &precomputed_plot)
                     ),
-                    Error::<Test>::NotRegistered
+                    Error::<Test>::InvalidCapacityAttestationProof
                 );
             });
         }
 
         #[test]
-        fn value_prop_does_not_exist() {
+        fn bsp_capacity_increase_fails_without_attested_capacity_when_required() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                register_account_as_bsp(alice, 100);
+                RequireCapacityAttestation::set(true);
 
-                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
+                run_to_block(System::block_number() + MinBlocksBetweenCapacityChanges::get());
 
                 assert_noop!(
-                    StorageProviders::make_value_prop_unavailable(
-                        RuntimeOrigin::signed(alice),
-                        value_prop.derive_id()
-                    ),
-                    Error::<Test>::ValuePropositionNotFound
+                    StorageProviders::change_capacity(RuntimeOrigin::signed(alice), 200),
+                    Error::<Test>::CapacityNotAttested
                 );
+
+                // Decreasing the capacity does not require an attestation
+                assert_ok!(StorageProviders::change_capacity(
+                    RuntimeOrigin::signed(alice),
+                    50
+                ));
             });
         }
     }
@@ -6087,192 +7906,273 @@ mod make_value_prop_unavailable {
         use super::*;
 
         #[test]
-        fn make_value_prop_unavailable_works() {
+        fn plot_proofs_verify_against_root() {
+            let alice: AccountId = accounts::ALICE.0;
+            let account = alice.encode();
+            let seed = H256::repeat_byte(1);
+            let plot = CapacityPlot::generate(&seed, &account, 95, 10);
+            assert_eq!(plot.size(), 10);
+
+            for index in 0..plot.size() {
+                let proof = plot.prove(index).unwrap();
+                assert!(verify_leaf_proof(
+                    &plot.root(),
+                    &seed,
+                    &account,
+                    plot.size(),
+                    index,
+                    &proof
+                ));
+                // A proof for a leaf doesn't prove any other one
+                assert!(!verify_leaf_proof(
+                    &plot.root(),
+                    &seed,
+                    &account,
+                    plot.size(),
+                    (index + 1) % plot.size(),
+                    &proof
+                ));
+                // Nor the same leaf of a plot sealed with another seed
+                assert!(!verify_leaf_proof(
+                    &plot.root(),
+                    &H256::repeat_byte(2),
+                    &account,
+                    plot.size(),
+                    index,
+                    &proof
+                ));
+            }
+
+            // Padding leaves cannot be proven
+            assert!(plot.prove(plot.size()).is_none());
+        }
+
+        #[test]
+        fn plot_leaves_must_seal_the_previous_leaf() {
+            let account = accounts::ALICE.0.encode();
+            let seed = H256::repeat_byte(1);
+            let plot = CapacityPlot::generate(&seed, &account, 95, 10);
+
+            // A leaf that is in the plot but does not seal the leaf before it is rejected
+            let mut proof = plot.prove(5).unwrap();
+            let other_proof = plot.prove(3).unwrap();
+            proof.previous_leaf = other_proof.leaf;
+            proof.previous_siblings = other_proof.siblings;
+            assert!(!verify_leaf_proof(
+                &plot.root(),
+                &seed,
+                &account,
+                plot.size(),
+                5,
+                &proof
+            ));
+        }
+
+        #[test]
+        fn bsp_signs_up_after_attesting_capacity() {
             ExtBuilder::build().execute_with(|| {
+                RequireCapacityAttestation::set(true);
                 let alice: AccountId = accounts::ALICE.0;
                 let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
-
-                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
 
-                assert_ok!(StorageProviders::add_value_prop(
-                    RuntimeOrigin::signed(alice),
-                    value_prop.price_per_giga_unit_of_data_per_block,
-                    value_prop.commitment.clone(),
-                    value_prop.bucket_data_limit
+                let plot = commit(alice, storage_amount);
+                assert!(matches!(
+                    PendingCapacityAttestations::<Test>::get(alice)
+                        .unwrap()
+                        .stage,
+                    CapacityAttestationStage::Committed { .. }
                 ));
 
-                let value_prop_id = value_prop.derive_id();
-
-                assert_ok!(StorageProviders::make_value_prop_unavailable(
+                run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
+                assert_ok!(StorageProviders::answer_capacity_challenge(
                     RuntimeOrigin::signed(alice),
-                    value_prop_id
+                    answer_for(alice, &plot)
                 ));
 
-                // Check event is emitted
                 System::assert_last_event(
-                    Event::<Test>::ValuePropUnavailable {
-                        msp_id,
-                        value_prop_id,
+                    Event::<Test>::CapacityAttested {
+                        who: alice,
+                        capacity: storage_amount,
                     }
                     .into(),
                 );
+                assert!(PendingCapacityAttestations::<Test>::get(alice).is_none());
+                assert_eq!(AttestedCapacities::<Test>::get(alice), Some(storage_amount));
 
-                assert_eq!(
-                    crate::MainStorageProviderIdsToValuePropositions::<Test>::get(
-                        &msp_id,
-                        value_prop_id
-                    )
-                    .unwrap(),
-                    ValueProposition::<Test> {
-                        price_per_giga_unit_of_data_per_block: 999,
-                        commitment: bounded_vec![],
-                        bucket_data_limit: 999,
-                        available: false
-                    }
-                );
+                // The BSP can now sign up with the attested capacity, which uses up the attestation
+                register_account_as_bsp(alice, storage_amount);
+                assert!(AttestedCapacities::<Test>::get(alice).is_none());
             });
         }
 
         #[test]
-        fn create_bucket_fails_when_value_prop_is_unavailable() {
+        fn attested_capacity_is_cleared_when_bsp_capacity_changes() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _) =
-                    register_account_as_msp(alice, storage_amount, None, None);
-
-                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
-
-                let value_prop = ValueProposition::<Test>::new(999, bounded_vec![], 999);
-
-                assert_ok!(StorageProviders::add_value_prop(
-                    RuntimeOrigin::signed(alice),
-                    value_prop.price_per_giga_unit_of_data_per_block,
-                    value_prop.commitment.clone(),
-                    value_prop.bucket_data_limit
-                ));
+                register_account_as_bsp(alice, 100);
+                RequireCapacityAttestation::set(true);
 
-                let value_prop_id = value_prop.derive_id();
+                commit_and_answer(alice, 300);
+                run_to_block(System::block_number() + MinBlocksBetweenCapacityChanges::get());
 
-                assert_ok!(StorageProviders::make_value_prop_unavailable(
+                // Decreasing the capacity clears the attestation as well
+                assert_ok!(StorageProviders::change_capacity(
                     RuntimeOrigin::signed(alice),
-                    value_prop_id
+                    50
                 ));
+                assert!(AttestedCapacities::<Test>::get(alice).is_none());
 
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
-                );
-
-                // Try to add a bucket with an unavailable value proposition
+                // So the BSP has to attest its capacity again to increase it
+                run_to_block(System::block_number() + MinBlocksBetweenCapacityChanges::get());
                 assert_noop!(
-                    StorageProviders::add_bucket(
-                        Some(msp_id),
-                        bucket_owner,
-                        bucket_id,
-                        false,
-                        None,
-                        Some(value_prop_id)
-                    ),
-                    Error::<Test>::ValuePropositionNotAvailable
+                    StorageProviders::change_capacity(RuntimeOrigin::signed(alice), 200),
+                    Error::<Test>::CapacityNotAttested
                 );
             });
         }
-    }
-}
-
-mod delete_provider {
-    use super::*;
-
-    mod failure {
-        use super::*;
 
         #[test]
-        fn deleting_provider_fails_if_not_insolvent() {
+        fn attested_capacity_is_cleared_when_bsp_signs_off() {
             ExtBuilder::build().execute_with(|| {
                 let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+                register_account_as_bsp(alice, 100);
 
-                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+                commit_and_answer(alice, 300);
+                assert_eq!(AttestedCapacities::<Test>::get(alice), Some(300));
 
-                assert_noop!(
-                    StorageProviders::delete_provider(RuntimeOrigin::signed(alice), msp_id),
-                    Error::<Test>::DeleteProviderConditionsNotMet
+                run_to_block(
+                    System::block_number() + <Test as crate::Config>::BspSignUpLockPeriod::get(),
                 );
+                assert_ok!(StorageProviders::bsp_sign_off(RuntimeOrigin::signed(alice)));
+
+                assert!(AttestedCapacities::<Test>::get(alice).is_none());
             });
         }
+    }
 
-        #[test]
-        fn deleting_provider_fails_if_payment_stream_exists() {
-            ExtBuilder::build().execute_with(|| {
-                let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+    /// Requests an attestation of `capacity` for `account`.
+    fn request(account: AccountId, capacity: StorageDataUnit<Test>) {
+        assert_ok!(StorageProviders::request_capacity_attestation(
+            RuntimeOrigin::signed(account),
+            capacity
+        ));
+
+        System::assert_last_event(
+            Event::<Test>::CapacityAttestationRequested {
+                who: account,
+                capacity,
+            }
+            .into(),
+        );
+    }
 
-                let msp_id = crate::AccountIdToMainStorageProviderId::<Test>::get(&alice).unwrap();
+    /// Draws the seed of the plot of `account` once the randomness for its request is valid, returning it.
+    fn draw_seed(account: AccountId) -> H256 {
+        run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
+        assert_ok!(StorageProviders::draw_capacity_plot_seed(
+            RuntimeOrigin::signed(account)
+        ));
+
+        let Some(CapacityAttestationStage::SeedDrawn { seed, deadline }) =
+            PendingCapacityAttestations::<Test>::get(account).map(|attestation| attestation.stage)
+        else {
+            panic!("The seed of the plot should have been drawn");
+        };
+        assert_eq!(
+            deadline,
+            System::block_number() + CapacityAttestationWindow::get()
+        );
+        System::assert_last_event(
+            Event::<Test>::CapacityPlotSeedDrawn {
+                who: account,
+                seed,
+                deadline,
+            }
+            .into(),
+        );
 
-                let bucket_owner = accounts::BOB.0;
-                let bucket_name = BoundedVec::try_from(b"bucket".to_vec()).unwrap();
-                let bucket_id = <StorageProviders as ReadBucketsInterface>::derive_bucket_id(
-                    &bucket_owner,
-                    bucket_name,
-                );
+        seed
+    }
 
-                // Add a bucket for Alice which creates a payment stream
-                assert_ok!(StorageProviders::add_bucket(
-                    Some(msp_id),
-                    bucket_owner,
-                    bucket_id,
-                    false,
-                    None,
-                    Some(value_prop_id)
-                ));
+    /// Generates the plot of `account` for `capacity` sealed with `seed`, as its client would.
+    fn generate(seed: &H256, account: AccountId, capacity: StorageDataUnit<Test>) -> CapacityPlot {
+        CapacityPlot::generate(
+            seed,
+            &account.encode(),
+            capacity,
+            CapacityAttestationChunkSize::get(),
+        )
+    }
 
-                InsolventProviders::<Test>::insert(
-                    StorageProviderId::<Test>::MainStorageProvider(msp_id),
-                    (),
-                );
+    /// Requests an attestation of `capacity` for `account` and commits to its plot, returning the plot.
+    fn commit(account: AccountId, capacity: StorageDataUnit<Test>) -> CapacityPlot {
+        request(account, capacity);
+        let seed = draw_seed(account);
+        let plot = generate(&seed, account, capacity);
+
+        assert_ok!(StorageProviders::commit_capacity_plot(
+            RuntimeOrigin::signed(account),
+            plot.root()
+        ));
+
+        System::assert_last_event(
+            Event::<Test>::CapacityPlotCommitted {
+                who: account,
+                plot_root: plot.root(),
+                capacity,
+                deadline: System::block_number() + CapacityAttestationWindow::get(),
+            }
+            .into(),
+        );
 
-                assert_noop!(
-                    StorageProviders::delete_provider(RuntimeOrigin::signed(alice), msp_id),
-                    Error::<Test>::DeleteProviderConditionsNotMet
-                );
-            });
-        }
+        plot
     }
 
-    mod success {
-        use super::*;
+    /// Attests `capacity` for `account`, going through the whole protocol.
+    fn commit_and_answer(account: AccountId, capacity: StorageDataUnit<Test>) {
+        let plot = commit(account, capacity);
+        run_to_block(System::block_number() + BLOCKS_BEFORE_RANDOMNESS_VALID + 1);
+        assert_ok!(StorageProviders::answer_capacity_challenge(
+            RuntimeOrigin::signed(account),
+            answer_for(account, &plot)
+        ));
+    }
 
-        #[test]
-        fn deleting_provider_works() {
-            ExtBuilder::build().execute_with(|| {
-                let alice: AccountId = accounts::ALICE.0;
-                let storage_amount: StorageDataUnit<Test> = 100;
-                let (_deposit_amount, _alice_msp, _value_prop_id) =
-                    register_account_as_msp(alice, storage_amount, None, None);
+    /// Answers the challenge for `plot` in the current block, as the client of `account` would.
+    fn answer_for(account: AccountId, plot: &CapacityPlot) -> CapacityChallengeAnswer<Test> {
+        let challenged_leaves = StorageProviders::query_capacity_challenge(&account).unwrap();
 
-                let msp_id = StorageProviders::get_provider_id(alice).unwrap();
+        plot.answer_challenge(&challenged_leaves)
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
 
-                InsolventProviders::<Test>::insert(
-                    StorageProviderId::<Test>::MainStorageProvider(msp_id),
-                    (),
-                );
+    /// Answers a challenge on the first leaves of `plot`, regardless of the actual challenge.
+    fn wrong_answer_for(plot: &CapacityPlot) -> CapacityChallengeAnswer<Test> {
+        let spot_checks: u32 = CapacityAttestationSpotChecks::get();
+        let indexes: Vec<u64> = (0..spot_checks as u64).collect();
 
-                assert_ok!(StorageProviders::delete_provider(
-                    RuntimeOrigin::signed(alice),
-                    msp_id
-                ));
-            });
-        }
+        plot.answer_challenge(&indexes).unwrap().try_into().unwrap()
+    }
+
+    fn request_bsp_sign_up(account: AccountId, capacity: StorageDataUnit<Test>) {
+        let mut multiaddresses: BoundedVec<MultiAddress<Test>, MaxMultiAddressAmount<Test>> =
+            BoundedVec::new();
+        multiaddresses.force_push(
+            "/ip4/127.0.0.1/udp/1234"
+                .as_bytes()
+                .to_vec()
+                .try_into()
+                .unwrap(),
+        );
+
+        assert_ok!(StorageProviders::request_bsp_sign_up(
+            RuntimeOrigin::signed(account),
+            capacity,
+            multiaddresses,
+            account
+        ));
     }
 }
 
@@ -6476,6 +8376,7 @@ fn run_to_block(n: u64) {
         AllPalletsWithSystem::on_finalize(System::block_number());
         System::set_block_number(System::block_number() + 1);
         AllPalletsWithSystem::on_initialize(System::block_number());
+        StorageProviders::on_poll(System::block_number(), &mut WeightMeter::new());
         AllPalletsWithSystem::on_idle(System::block_number(), Weight::MAX);
     }
 }
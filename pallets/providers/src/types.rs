@@ -6,6 +6,7 @@ use frame_support::{pallet_prelude::*, traits::fungible::Inspect};
 use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
 use shp_traits::{PaymentStreamsInterface, StorageHubTickGetter};
+use sp_core::H256;
 use sp_runtime::{traits::CheckedAdd, ArithmeticError, BoundedVec};
 use sp_std::cmp::max;
 
@@ -158,6 +159,30 @@ impl<T: Config> ValueProposition<T> {
 
 pub type Commitment<T> = BoundedVec<u8, <T as crate::Config>::MaxCommitmentSize>;
 
+/// Position of a value proposition within the versions published by an MSP.
+///
+/// Every version of a value proposition belongs to the lineage of the first one, which is
+/// identified by the ID of that first version. Value propositions without a recorded version are
+/// the first version of their own lineage.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct ValuePropositionVersion<T: Config> {
+    /// ID of the first version of the value proposition.
+    pub lineage: ValuePropIdFor<T>,
+    /// Version number, starting at 1.
+    pub version: u32,
+}
+
+impl<T: Config> ValuePropositionVersion<T> {
+    /// The version of a value proposition which has no previous versions.
+    pub fn first(value_prop_id: ValuePropIdFor<T>) -> Self {
+        Self {
+            lineage: value_prop_id,
+            version: 1,
+        }
+    }
+}
+
 /// Structure that represents a Main Storage Provider. It holds the buckets that the MSP has, the total data that the MSP is able to store,
 /// the amount of data that it is storing, and its libp2p multiaddresses.
 #[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
@@ -246,6 +271,110 @@ impl<T: Config> StorageProviderId<T> {
     }
 }
 
+/// Number of buckets in which BSPs are grouped according to their own capacity utilization
+/// when computing the distribution returned by the `capacity_utilization` runtime API.
+pub const CAPACITY_UTILIZATION_DECILES: usize = 10;
+
+/// Structure that represents a snapshot of the global BSP capacity utilization at a given tick,
+/// together with the price per giga-unit of data per tick that was in effect at that moment.
+///
+/// These samples are taken periodically in the `on_poll` hook and kept in the
+/// [`CapacityUtilizationSamples`](crate::CapacityUtilizationSamples) storage.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct CapacityUtilizationSample<T: Config> {
+    pub tick: StorageHubTickNumber<T>,
+    pub total_capacity: StorageDataUnit<T>,
+    pub used_capacity: StorageDataUnit<T>,
+    pub price_per_giga_unit_of_data_per_tick: BalanceOf<T>,
+}
+
+/// Structure returned by the `capacity_utilization` runtime API. It holds the current total and used
+/// BSP capacity, how BSPs are distributed along utilization deciles, the current price per giga-unit of
+/// data per tick and the latest samples kept on-chain.
+///
+/// `bsps_per_utilization_decile[i]` is the amount of BSPs whose used capacity is between `i * 10%`
+/// (inclusive) and `(i + 1) * 10%` (exclusive) of their total capacity. Full BSPs are counted in the last decile.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct CapacityUtilization<T: Config> {
+    pub total_capacity: StorageDataUnit<T>,
+    pub used_capacity: StorageDataUnit<T>,
+    pub bsps_per_utilization_decile: [u32; CAPACITY_UTILIZATION_DECILES],
+    pub price_per_giga_unit_of_data_per_tick: BalanceOf<T>,
+    pub samples: CapacityUtilizationSamplesFor<T>,
+}
+
+/// Structure that represents a capacity attestation that an account has requested, and that it has not
+/// completed yet.
+///
+/// See [`crate::capacity_attestation`] for how plots are built and challenged.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct PendingCapacityAttestation<T: Config> {
+    /// The capacity to attest.
+    pub capacity: StorageDataUnit<T>,
+    /// The block in which the account requested the attestation. Only randomness generated after this block
+    /// can be used as the seed of its plot.
+    pub requested_at: BlockNumberFor<T>,
+    /// The step of the attestation the account is at.
+    pub stage: CapacityAttestationStage<T>,
+}
+
+/// The step of a [`PendingCapacityAttestation`] an account is at.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub enum CapacityAttestationStage<T: Config> {
+    /// Waiting for the randomness generated after the request, to draw the seed of the plot from it.
+    Requested,
+    /// The seed of the plot was drawn, and the account has to commit to the plot sealed with it.
+    SeedDrawn {
+        /// The seed the plot is sealed with.
+        seed: H256,
+        /// The last block in which the account can commit to the plot.
+        deadline: BlockNumberFor<T>,
+    },
+    /// The account committed to the plot, and has to answer its challenge.
+    Committed {
+        /// The seed the plot is sealed with.
+        seed: H256,
+        /// The root of the plot that the account committed to.
+        plot_root: H256,
+        /// The block in which the account committed to the plot. Only randomness generated after this block
+        /// can be used to challenge it.
+        committed_at: BlockNumberFor<T>,
+        /// The last block in which the challenge can be answered.
+        deadline: BlockNumberFor<T>,
+    },
+}
+
+/// Structure that represents a capacity decrease that a Storage Provider has requested, and that it can
+/// confirm once the grace period to offload its data has passed.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebugNoBound, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct PendingCapacityDecrease<T: Config> {
+    /// The capacity that the Storage Provider wants to decrease to.
+    pub new_capacity: StorageDataUnit<T>,
+    /// The first block in which the decrease can be confirmed.
+    pub confirmable_at: BlockNumberFor<T>,
+}
+
+/// The outcome of confirming a pending capacity decrease.
+pub enum CapacityDecreaseOutcome<T: Config> {
+    /// The capacity of the Storage Provider was decreased from `old_capacity` to `new_capacity`.
+    Applied {
+        provider_id: StorageProviderId<T>,
+        old_capacity: StorageDataUnit<T>,
+        new_capacity: StorageDataUnit<T>,
+    },
+    /// The decrease was cancelled, since the Storage Provider still uses more than `new_capacity`.
+    Cancelled {
+        provider_id: StorageProviderId<T>,
+        new_capacity: StorageDataUnit<T>,
+        used_capacity: StorageDataUnit<T>,
+    },
+}
+
 /// The delta applied to a fixed rate payment stream via [`Pallet::compute_new_rate_delta`].
 pub enum RateDeltaParam<T: Config> {
     /// Variant should be used when a new bucket is associated to an MSP.
@@ -282,6 +411,11 @@ pub type MaxMultiAddressAmount<T> = <T as crate::Config>::MaxMultiAddressAmount;
 /// Its maximum size is defined in the runtime configuration, as MaxMultiAddressSize.
 pub type MultiAddress<T> = BoundedVec<u8, MaxMultiAddressSize<T>>;
 
+/// EncryptionMetadata is a byte array set by the owner of a bucket to describe how its contents are
+/// encrypted (e.g. the encryption scheme and the wrapped keys). Its maximum size is defined in the
+/// runtime configuration, as EncryptionMetadataLimit.
+pub type EncryptionMetadata<T> = BoundedVec<u8, <T as crate::Config>::EncryptionMetadataLimit>;
+
 /// MerklePatriciaRoot is the type of the root of a Merkle Patricia Trie, either the root of a BSP or a bucket from an MSP.
 pub type MerklePatriciaRoot<T> = <T as crate::Config>::MerklePatriciaRoot;
 
@@ -322,6 +456,18 @@ pub type StorageDataUnitAndBalanceConverter<T> =
 /// Type alias for the `ProviderTopUpTtl` type used in the Storage Providers pallet.
 pub type ProviderTopUpTtl<T> = <T as crate::Config>::ProviderTopUpTtl;
 
+/// Type alias for the bounded list of [`CapacityUtilizationSample`]s kept on-chain.
+pub type CapacityUtilizationSamplesFor<T> =
+    BoundedVec<CapacityUtilizationSample<T>, <T as crate::Config>::MaxCapacityUtilizationSamples>;
+
+/// Type alias for the proof of a single challenged leaf of a capacity attestation plot.
+pub type CapacityPlotLeafProof = crate::capacity_attestation::PlotLeafProof;
+
+/// Type alias for the answer to a capacity attestation challenge: the proof of each challenged leaf, in
+/// the order they are challenged in.
+pub type CapacityChallengeAnswer<T> =
+    BoundedVec<CapacityPlotLeafProof, <T as crate::Config>::CapacityAttestationSpotChecks>;
+
 /// Type alias for the `TickNumber` type used in the Storage Providers pallet.
 pub type PaymentStreamsTickNumber<T> =
     <<T as crate::Config>::PaymentStreams as PaymentStreamsInterface>::TickNumber;
@@ -16,25 +16,37 @@ use frame_support::{
 use frame_system::pallet_prelude::BlockNumberFor;
 use pallet_storage_providers_runtime_api::{
     GetBspInfoError, GetStakeError, QueryAvailableStorageCapacityError,
+    QueryBspReputationWeightError, QueryBucketEncryptionMetadataError, QueryCapacityChallengeError,
     QueryEarliestChangeCapacityBlockError, QueryMspIdOfBucketIdError,
     QueryProviderMultiaddressesError, QueryStorageProviderCapacityError,
+    QueryValuePropositionVersionError,
 };
 use shp_constants::GIGAUNIT;
 use shp_traits::{
     FileMetadataInterface, MutateBucketsInterface, MutateChallengeableProvidersInterface,
-    MutateProvidersInterface, MutateStorageProvidersInterface, PaymentStreamsInterface,
-    ProofSubmittersInterface, ReadBucketsInterface, ReadChallengeableProvidersInterface,
-    ReadProvidersInterface, ReadStorageProvidersInterface, ReadUserSolvencyInterface,
-    SystemMetricsInterface,
+    MutatePricePerGigaUnitPerTickInterface, MutateProvidersInterface,
+    MutateStorageProvidersInterface, OnProviderStatusChange, PaymentEscrowInterface,
+    PaymentStreamsInterface, ProofSubmittersInterface, ProviderStatus, ProviderStatusChange,
+    ReadBucketsInterface, ReadChallengeableProvidersInterface, ReadProvidersInterface,
+    ReadStorageProvidersInterface, ReadUserSolvencyInterface, SystemMetricsInterface,
+};
+use sp_arithmetic::{
+    rational::MultiplyRational,
+    Rounding::{self, NearestPrefUp},
+};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Bounded, ConvertBack},
+    PerThing, Perbill,
 };
-use sp_arithmetic::{rational::MultiplyRational, Rounding::NearestPrefUp};
-use sp_runtime::traits::ConvertBack;
 use sp_std::vec::Vec;
 use types::{
-    Bucket, Commitment, ExpirationItem, MainStorageProvider, MainStorageProviderSignUpRequest,
-    MultiAddress, Multiaddresses, ProviderIdFor, RateDeltaParam, SignUpRequestSpParams,
+    Bucket, CapacityAttestationStage, CapacityChallengeAnswer, CapacityDecreaseOutcome,
+    CapacityUtilization, Commitment, ExpirationItem, MainStorageProvider,
+    MainStorageProviderSignUpRequest, MultiAddress, Multiaddresses, PendingCapacityAttestation,
+    PendingCapacityDecrease, ProviderIdFor, RateDeltaParam, SignUpRequestSpParams,
     StorageDataUnitAndBalanceConverter, StorageProviderId, TopUpMetadata, ValuePropIdFor,
-    ValueProposition, ValuePropositionWithId,
+    ValueProposition, ValuePropositionVersion, ValuePropositionWithId,
 };
 
 macro_rules! expect_or_err {
@@ -345,6 +357,10 @@ where
             Error::<T>::SignUpRequestExpired
         );
 
+        // Check that the BSP has attested the capacity it signs up with, if required by the runtime
+        Self::ensure_capacity_attested(who, bsp_info.capacity)?;
+        AttestedCapacities::<T>::remove(who);
+
         // Insert the BackupStorageProviderId into the mapping
         AccountIdToBackupStorageProviderId::<T>::insert(who, bsp_id);
 
@@ -409,6 +425,9 @@ where
         // Update the MSPs storage, removing the signer as an MSP
         AccountIdToMainStorageProviderId::<T>::remove(who);
         MainStorageProviders::<T>::remove(&msp_id);
+        PendingCapacityDecreases::<T>::remove(&msp_id);
+        SuspendedProviders::<T>::remove(&msp_id);
+        ExitingProviders::<T>::remove(&msp_id);
 
         // Return the deposit to the signer (if all funds cannot be returned, it will fail and revert with the reason)
         T::NativeBalance::release_all(
@@ -431,6 +450,11 @@ where
 
         <T::PaymentStreams as PaymentStreamsInterface>::remove_privileged_provider(&msp_id)?;
 
+        T::OnProviderStatusChange::on_provider_status_change(
+            &msp_id,
+            ProviderStatusChange::Deregistered,
+        );
+
         Ok(msp_id)
     }
 
@@ -466,6 +490,11 @@ where
         // Update the BSPs storage, removing the signer as an BSP
         AccountIdToBackupStorageProviderId::<T>::remove(who);
         BackupStorageProviders::<T>::remove(&bsp_id);
+        PendingCapacityDecreases::<T>::remove(&bsp_id);
+        AttestedCapacities::<T>::remove(who);
+        SuspendedProviders::<T>::remove(&bsp_id);
+        ExitingProviders::<T>::remove(&bsp_id);
+        BspProofSubmissionStreaks::<T>::remove(&bsp_id);
 
         // Update the total capacity of the network (which is the sum of all BSPs capacities)
         TotalBspsCapacity::<T>::mutate(|n| match n.checked_sub(&bsp.capacity) {
@@ -500,6 +529,11 @@ where
             *n = n.saturating_sub(bsp.reputation_weight);
         });
 
+        T::OnProviderStatusChange::on_provider_status_change(
+            &bsp_id,
+            ProviderStatusChange::Deregistered,
+        );
+
         Ok(bsp_id)
     }
 
@@ -652,6 +686,11 @@ where
             Error::<T>::NewCapacityLessThanUsedStorage
         );
 
+        // Check that the BSP has attested the new capacity if it is increasing it, if required by the runtime
+        if new_capacity > bsp.capacity {
+            Self::ensure_capacity_attested(account_id, new_capacity)?;
+        }
+
         let new_deposit = Self::compute_deposit_needed_for_capacity(new_capacity)?;
 
         // Check how much has the used already deposited for the current capacity
@@ -707,11 +746,230 @@ where
         bsp.capacity = new_capacity;
         bsp.last_capacity_change = frame_system::Pallet::<T>::block_number();
         BackupStorageProviders::<T>::insert(&bsp_id, bsp);
+        AttestedCapacities::<T>::remove(account_id);
 
         // Return the old capacity
         Ok(old_capacity)
     }
 
+    /// This function holds the logic that checks if a Storage Provider can request to decrease its capacity and,
+    /// if so, records the pending decrease, returning the Storage Provider's ID, its current capacity and the
+    /// first block in which the decrease can be confirmed.
+    ///
+    /// The new capacity can be less than the storage used by the Storage Provider, which has until the end of the
+    /// grace period to offload the data above it.
+    pub fn do_request_capacity_decrease(
+        who: &T::AccountId,
+        new_capacity: StorageDataUnit<T>,
+    ) -> Result<(StorageProviderId<T>, StorageDataUnit<T>, BlockNumberFor<T>), DispatchError> {
+        // Check that the new capacity is not zero (there are specific functions to sign off as a SP)
+        ensure!(
+            new_capacity != T::StorageDataUnit::zero(),
+            Error::<T>::NewCapacityCantBeZero
+        );
+
+        // Check that the new capacity is bigger than the minimum required by the runtime
+        ensure!(
+            new_capacity >= T::SpMinCapacity::get(),
+            Error::<T>::StorageTooLow
+        );
+
+        let (provider_id, current_capacity, _) = Self::get_capacity_and_used_of_sp(who)?;
+
+        // Check if the Storage Provider is insolvent
+        ensure!(
+            InsolventProviders::<T>::get(&provider_id).is_none(),
+            Error::<T>::OperationNotAllowedForInsolventProvider
+        );
+
+        // Check that the new capacity is actually a decrease
+        ensure!(
+            new_capacity < current_capacity,
+            Error::<T>::NewCapacityNotLessThanCurrentCapacity
+        );
+
+        // Check that the Storage Provider does not have another decrease pending
+        ensure!(
+            !PendingCapacityDecreases::<T>::contains_key(provider_id.inner()),
+            Error::<T>::CapacityDecreaseAlreadyRequested
+        );
+
+        let confirmable_at = frame_system::Pallet::<T>::block_number()
+            .saturating_add(T::CapacityDecreaseGracePeriod::get());
+        PendingCapacityDecreases::<T>::insert(
+            provider_id.inner(),
+            PendingCapacityDecrease {
+                new_capacity,
+                confirmable_at,
+            },
+        );
+
+        Ok((provider_id, current_capacity, confirmable_at))
+    }
+
+    /// This function holds the logic that checks if a Storage Provider can confirm its pending capacity decrease
+    /// and, if so, removes it and either applies it, or cancels it if the Storage Provider still uses more storage
+    /// than the requested capacity.
+    pub fn do_confirm_capacity_decrease(
+        who: &T::AccountId,
+    ) -> Result<CapacityDecreaseOutcome<T>, DispatchError> {
+        let (provider_id, _, used_capacity) = Self::get_capacity_and_used_of_sp(who)?;
+
+        let pending_decrease = PendingCapacityDecreases::<T>::take(provider_id.inner())
+            .ok_or(Error::<T>::CapacityDecreaseNotRequested)?;
+
+        // Check that the grace period to offload the data has passed
+        ensure!(
+            frame_system::Pallet::<T>::block_number() >= pending_decrease.confirmable_at,
+            Error::<T>::CapacityDecreaseGracePeriodNotPassed
+        );
+
+        let new_capacity = pending_decrease.new_capacity;
+
+        // Cancel the decrease if the Storage Provider did not offload enough data during the grace period
+        if used_capacity > new_capacity {
+            return Ok(CapacityDecreaseOutcome::Cancelled {
+                provider_id,
+                new_capacity,
+                used_capacity,
+            });
+        }
+
+        let (provider_id, old_capacity) = Self::do_change_capacity(who, new_capacity)?;
+
+        Ok(CapacityDecreaseOutcome::Applied {
+            provider_id,
+            old_capacity,
+            new_capacity,
+        })
+    }
+
+    /// Get the ID, the capacity and the used capacity of the Storage Provider of the account `who`.
+    fn get_capacity_and_used_of_sp(
+        who: &T::AccountId,
+    ) -> Result<(StorageProviderId<T>, StorageDataUnit<T>, StorageDataUnit<T>), DispatchError> {
+        if let Some(msp_id) = AccountIdToMainStorageProviderId::<T>::get(who) {
+            let msp = MainStorageProviders::<T>::get(&msp_id)
+                .ok_or(Error::<T>::SpRegisteredButDataNotFound)?;
+            Ok((
+                StorageProviderId::MainStorageProvider(msp_id),
+                msp.capacity,
+                msp.capacity_used,
+            ))
+        } else if let Some(bsp_id) = AccountIdToBackupStorageProviderId::<T>::get(who) {
+            let bsp = BackupStorageProviders::<T>::get(&bsp_id)
+                .ok_or(Error::<T>::SpRegisteredButDataNotFound)?;
+            Ok((
+                StorageProviderId::BackupStorageProvider(bsp_id),
+                bsp.capacity,
+                bsp.capacity_used,
+            ))
+        } else {
+            Err(Error::<T>::NotRegistered.into())
+        }
+    }
+
+    /// This function holds the logic that checks if a Storage Provider can schedule its sign off and, if so,
+    /// marks it as exiting, returning its ID.
+    pub fn do_schedule_sign_off(who: &T::AccountId) -> Result<ProviderIdFor<T>, DispatchError> {
+        let provider_id = <Self as ReadProvidersInterface>::get_provider_id(who.clone())
+            .ok_or(Error::<T>::NotRegistered)?;
+
+        // Insolvent Storage Providers can't sign off, they are deleted instead
+        ensure!(
+            !<Self as ReadProvidersInterface>::is_provider_insolvent(provider_id),
+            Error::<T>::OperationNotAllowedForInsolventProvider
+        );
+
+        ensure!(
+            !ExitingProviders::<T>::contains_key(&provider_id),
+            Error::<T>::SignOffAlreadyScheduled
+        );
+
+        ExitingProviders::<T>::insert(&provider_id, ());
+
+        Ok(provider_id)
+    }
+
+    /// This function holds the logic that checks if a Storage Provider can cancel its scheduled sign off and, if so,
+    /// removes its exiting mark, returning its ID.
+    pub fn do_cancel_scheduled_sign_off(
+        who: &T::AccountId,
+    ) -> Result<ProviderIdFor<T>, DispatchError> {
+        let provider_id = <Self as ReadProvidersInterface>::get_provider_id(who.clone())
+            .ok_or(Error::<T>::NotRegistered)?;
+
+        ensure!(
+            ExitingProviders::<T>::take(&provider_id).is_some(),
+            Error::<T>::SignOffNotScheduled
+        );
+
+        Ok(provider_id)
+    }
+
+    /// This function holds the logic that checks if a Storage Provider can be suspended and, if so, marks it as suspended.
+    pub fn do_suspend_provider(provider_id: &ProviderIdFor<T>) -> DispatchResult {
+        ensure!(
+            MainStorageProviders::<T>::contains_key(provider_id)
+                || BackupStorageProviders::<T>::contains_key(provider_id),
+            Error::<T>::NotRegistered
+        );
+
+        ensure!(
+            !SuspendedProviders::<T>::contains_key(provider_id),
+            Error::<T>::ProviderAlreadySuspended
+        );
+
+        SuspendedProviders::<T>::insert(provider_id, ());
+
+        Ok(())
+    }
+
+    /// This function holds the logic that checks if a Storage Provider is suspended and, if so, resumes it.
+    pub fn do_resume_provider(provider_id: &ProviderIdFor<T>) -> DispatchResult {
+        ensure!(
+            SuspendedProviders::<T>::take(provider_id).is_some(),
+            Error::<T>::ProviderNotSuspended
+        );
+
+        Ok(())
+    }
+
+    /// Emit a `ProviderStatusChanged` event with the current status of the Storage Provider `provider_id`.
+    pub(crate) fn deposit_provider_status_changed(provider_id: &ProviderIdFor<T>) {
+        if let Some(status) = Self::get_provider_status(provider_id) {
+            Self::deposit_event(Event::<T>::ProviderStatusChanged {
+                provider_id: *provider_id,
+                status,
+            });
+        }
+    }
+
+    /// Check if the Storage Provider `provider_id` has been marked as insolvent or is awaiting a top up of its deposit.
+    fn is_insolvent_or_awaiting_top_up(provider_id: &ProviderIdFor<T>) -> bool {
+        let is_provider_insolvent = InsolventProviders::<T>::get(
+            &StorageProviderId::<T>::MainStorageProvider(*provider_id),
+        )
+        .is_some()
+            || InsolventProviders::<T>::get(&StorageProviderId::<T>::BackupStorageProvider(
+                *provider_id,
+            ))
+            .is_some();
+
+        // While provider is being awaited for top up, it is still considered insolvent, it's just that
+        // it can get out of this state.
+        let is_provider_awaiting_topup = AwaitingTopUpFromProviders::<T>::get(
+            &StorageProviderId::<T>::MainStorageProvider(*provider_id),
+        )
+        .is_some()
+            || AwaitingTopUpFromProviders::<T>::get(
+                &StorageProviderId::<T>::BackupStorageProvider(*provider_id),
+            )
+            .is_some();
+
+        is_provider_insolvent || is_provider_awaiting_topup
+    }
+
     /// This function holds the logic that checks if a user can add a new multiaddress to its storage
     /// and, if so, updates the storage to reflect the new multiaddress and returns the provider id if successful
     pub fn do_add_multiaddress(
@@ -854,6 +1112,11 @@ where
         // Calculate slashable amount for the current number of accrued failed proof submissions
         let slashable_amount = Self::compute_worst_case_scenario_slashable_amount(provider_id)?;
 
+        // Keep the inputs of the slash computation to report them in the event
+        let accrued_failed_proof_submissions = <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_accrued_failed_proof_submissions(&provider_id)
+            .unwrap_or_default();
+        let consecutive_missed_deadlines = <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_consecutive_missed_proof_deadlines(&provider_id);
+
         // Clear the accrued failed proof submissions for the Storage Provider
         <T::ProvidersProofSubmitters as ProofSubmittersInterface>::clear_accrued_failed_proof_submissions(&provider_id);
 
@@ -878,8 +1141,16 @@ where
         Self::deposit_event(Event::<T>::Slashed {
             provider_id: *provider_id,
             amount: actual_slashed,
+            accrued_failed_proof_submissions,
+            consecutive_missed_deadlines,
+            used_capacity,
         });
 
+        // Being slashed erodes the reputation a BSP earned by submitting proofs
+        if let StorageProviderId::BackupStorageProvider(bsp_id) = &typed_provider_id {
+            Self::decay_bsp_reputation_weight(bsp_id);
+        }
+
         // Capacity needed for the provider to remain active
         let needed_capacity = used_capacity.max(T::SpMinCapacity::get());
 
@@ -961,6 +1232,7 @@ where
                 let mut provider = BackupStorageProviders::<T>::get(provider_id)
                     .ok_or(Error::<T>::NotRegistered)?;
                 provider.capacity = final_capacity;
+                AttestedCapacities::<T>::remove(&provider.owner_account);
                 BackupStorageProviders::<T>::insert(*provider_id, provider);
             }
         }
@@ -968,6 +1240,87 @@ where
         Ok(())
     }
 
+    /// Accounts for a proof submitted by the BSP `bsp_id`, increasing its reputation weight by
+    /// [`Config::ReputationWeightIncrease`] every [`Config::ReputationGrowthStreak`] proofs submitted in a row, up to
+    /// [`Config::MaxReputationWeight`].
+    ///
+    /// Proofs submitted while the BSP is slashable don't count towards its streak, which is reset once it gets slashed.
+    pub(crate) fn record_bsp_proof_submission(bsp_id: &BackupStorageProviderId<T>) {
+        let growth_streak = T::ReputationGrowthStreak::get();
+        if growth_streak.is_zero() {
+            return;
+        }
+
+        let Some(bsp) = BackupStorageProviders::<T>::get(bsp_id) else {
+            return;
+        };
+
+        let is_slashable = <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_accrued_failed_proof_submissions(bsp_id)
+            .is_some_and(|failed_submissions| !failed_submissions.is_zero());
+        if is_slashable {
+            return;
+        }
+
+        let streak = BspProofSubmissionStreaks::<T>::mutate(bsp_id, |streak| {
+            streak.saturating_inc();
+            *streak
+        });
+        if !(streak % growth_streak).is_zero() {
+            return;
+        }
+
+        // Weights above the maximum (e.g. set by governance) are never lowered by this.
+        let new_weight = bsp
+            .reputation_weight
+            .saturating_add(T::ReputationWeightIncrease::get())
+            .min(T::MaxReputationWeight::get())
+            .max(bsp.reputation_weight);
+        Self::update_bsp_reputation_weight(bsp_id, bsp, new_weight);
+    }
+
+    /// Decays the reputation weight of the slashed BSP `bsp_id`, which loses [`Config::ReputationDecayOnSlash`] of the
+    /// weight it earned above [`Config::StartingReputationWeight`], and resets its streak of proofs submitted in a row.
+    pub(crate) fn decay_bsp_reputation_weight(bsp_id: &BackupStorageProviderId<T>) {
+        BspProofSubmissionStreaks::<T>::remove(bsp_id);
+
+        let Some(bsp) = BackupStorageProviders::<T>::get(bsp_id) else {
+            return;
+        };
+
+        let earned_weight = bsp
+            .reputation_weight
+            .saturating_sub(T::StartingReputationWeight::get());
+        let lost_weight = T::ReputationDecayOnSlash::get().mul_ceil(earned_weight);
+        let new_weight = bsp.reputation_weight.saturating_sub(lost_weight);
+        Self::update_bsp_reputation_weight(bsp_id, bsp, new_weight);
+    }
+
+    /// Sets the reputation weight of the BSP `bsp_id` to `new_weight`, keeping the global reputation weight of all BSPs
+    /// up to date.
+    fn update_bsp_reputation_weight(
+        bsp_id: &BackupStorageProviderId<T>,
+        mut bsp: BackupStorageProvider<T>,
+        new_weight: T::ReputationWeightType,
+    ) {
+        let old_weight = bsp.reputation_weight;
+        if new_weight == old_weight {
+            return;
+        }
+
+        GlobalBspsReputationWeight::<T>::mutate(|n| {
+            *n = n.saturating_sub(old_weight).saturating_add(new_weight);
+        });
+
+        bsp.reputation_weight = new_weight;
+        BackupStorageProviders::<T>::insert(bsp_id, bsp);
+
+        Self::deposit_event(Event::<T>::BspReputationWeightChanged {
+            bsp_id: *bsp_id,
+            old_weight,
+            new_weight,
+        });
+    }
+
     /// Allows a storage provider to manually top up their held deposit to restore capacity up to their currently used capacity.
     ///
     /// The provider must be within a grace period due to insufficient capacity.
@@ -1040,6 +1393,7 @@ where
                 let mut provider = BackupStorageProviders::<T>::get(provider_id)
                     .ok_or(Error::<T>::NotRegistered)?;
                 provider.capacity = needed_capacity;
+                AttestedCapacities::<T>::remove(&provider.owner_account);
                 BackupStorageProviders::<T>::insert(provider_id, provider);
             }
         }
@@ -1111,6 +1465,89 @@ where
         )
     }
 
+    pub(crate) fn do_publish_value_prop_version(
+        who: &T::AccountId,
+        value_prop_id: ValuePropIdFor<T>,
+        price_per_giga_unit_of_data_per_block: BalanceOf<T>,
+        commitment: Commitment<T>,
+        bucket_data_limit: StorageDataUnit<T>,
+    ) -> Result<
+        (
+            MainStorageProviderId<T>,
+            ValueProposition<T>,
+            ValuePropositionVersion<T>,
+        ),
+        DispatchError,
+    > {
+        let msp_id =
+            AccountIdToMainStorageProviderId::<T>::get(who).ok_or(Error::<T>::NotRegistered)?;
+
+        ensure!(
+            MainStorageProviderIdsToValuePropositions::<T>::contains_key(&msp_id, &value_prop_id),
+            Error::<T>::ValuePropositionNotFound
+        );
+
+        // Only the latest version of a value proposition can be superseded, so each lineage stays linear.
+        let previous_version = Self::value_prop_version(&msp_id, &value_prop_id);
+        ensure!(
+            Self::latest_value_prop_id(&msp_id, &value_prop_id) == value_prop_id,
+            Error::<T>::ValuePropositionSuperseded
+        );
+
+        // Add the new version. This also checks that the MSP is not insolvent and that the new terms differ from
+        // every value proposition of the MSP.
+        let (_, value_prop) = Self::do_add_value_prop(
+            who,
+            price_per_giga_unit_of_data_per_block,
+            commitment,
+            bucket_data_limit,
+        )?;
+        let new_value_prop_id = value_prop.derive_id();
+
+        // The previous version can no longer be used by new buckets. Buckets already using it are not affected.
+        MainStorageProviderIdsToValuePropositions::<T>::mutate(
+            &msp_id,
+            &value_prop_id,
+            |maybe_value_prop| {
+                if let Some(previous_value_prop) = maybe_value_prop {
+                    previous_value_prop.available = false;
+                }
+            },
+        );
+
+        let version = ValuePropositionVersion::<T> {
+            lineage: previous_version.lineage,
+            version: previous_version
+                .version
+                .checked_add(1)
+                .ok_or(ArithmeticError::Overflow)?,
+        };
+        ValuePropositionVersions::<T>::insert(&msp_id, &new_value_prop_id, &version);
+        LatestValuePropositionVersions::<T>::insert(&msp_id, &version.lineage, &new_value_prop_id);
+
+        Ok((msp_id, value_prop, version))
+    }
+
+    /// The version of a value proposition of an MSP.
+    ///
+    /// Value propositions without a recorded version are the first version of their own lineage.
+    pub fn value_prop_version(
+        msp_id: &MainStorageProviderId<T>,
+        value_prop_id: &ValuePropIdFor<T>,
+    ) -> ValuePropositionVersion<T> {
+        ValuePropositionVersions::<T>::get(msp_id, value_prop_id)
+            .unwrap_or_else(|| ValuePropositionVersion::<T>::first(*value_prop_id))
+    }
+
+    /// The ID of the latest version of the lineage the value proposition `value_prop_id` of an MSP belongs to.
+    pub fn latest_value_prop_id(
+        msp_id: &MainStorageProviderId<T>,
+        value_prop_id: &ValuePropIdFor<T>,
+    ) -> ValuePropIdFor<T> {
+        let lineage = Self::value_prop_version(msp_id, value_prop_id).lineage;
+        LatestValuePropositionVersions::<T>::get(msp_id, &lineage).unwrap_or(lineage)
+    }
+
     pub(crate) fn do_delete_provider(provider_id: &ProviderIdFor<T>) -> Result<(), DispatchError> {
         ensure!(
             Self::can_delete_provider(provider_id),
@@ -1124,6 +1561,9 @@ where
             ));
             MainStorageProviders::<T>::remove(&provider_id);
             AccountIdToMainStorageProviderId::<T>::remove(msp.owner_account);
+            PendingCapacityDecreases::<T>::remove(&provider_id);
+            SuspendedProviders::<T>::remove(&provider_id);
+            ExitingProviders::<T>::remove(&provider_id);
             MspCount::<T>::mutate(|n| {
                 let new_amount_of_msps = n.checked_sub(&T::SpCount::one());
                 match new_amount_of_msps {
@@ -1135,17 +1575,29 @@ where
                 }
             })?;
             MainStorageProviderIdsToValuePropositions::<T>::drain_prefix(&provider_id);
+            let _ = ValuePropositionVersions::<T>::clear_prefix(&provider_id, u32::MAX, None);
+            let _ = LatestValuePropositionVersions::<T>::clear_prefix(&provider_id, u32::MAX, None);
             MainStorageProviderIdsToBuckets::<T>::drain_prefix(&provider_id);
 
             Self::deposit_event(Event::<T>::MspDeleted {
                 provider_id: *provider_id,
             });
+
+            T::OnProviderStatusChange::on_provider_status_change(
+                provider_id,
+                ProviderStatusChange::Deregistered,
+            );
         } else if let Some(bsp) = BackupStorageProviders::<T>::get(provider_id) {
             InsolventProviders::<T>::remove(StorageProviderId::<T>::BackupStorageProvider(
                 *provider_id,
             ));
             BackupStorageProviders::<T>::remove(&provider_id);
+            AttestedCapacities::<T>::remove(&bsp.owner_account);
             AccountIdToBackupStorageProviderId::<T>::remove(bsp.owner_account);
+            PendingCapacityDecreases::<T>::remove(&provider_id);
+            SuspendedProviders::<T>::remove(&provider_id);
+            ExitingProviders::<T>::remove(&provider_id);
+            BspProofSubmissionStreaks::<T>::remove(&provider_id);
             BspCount::<T>::mutate(|n| {
                 let new_amount_of_bsps = n.checked_sub(&T::SpCount::one());
                 match new_amount_of_bsps {
@@ -1183,6 +1635,216 @@ where
             Self::deposit_event(Event::<T>::BspDeleted {
                 provider_id: *provider_id,
             });
+
+            T::OnProviderStatusChange::on_provider_status_change(
+                provider_id,
+                ProviderStatusChange::Deregistered,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// This function holds the logic that stores the request of an account to attest `capacity`, replacing any
+    /// previous pending attestation.
+    pub(crate) fn do_request_capacity_attestation(
+        who: &T::AccountId,
+        capacity: StorageDataUnit<T>,
+    ) -> DispatchResult {
+        // Check that the capacity to attest is at least the minimum capacity a SP can have
+        ensure!(
+            capacity >= T::SpMinCapacity::get(),
+            Error::<T>::StorageTooLow
+        );
+
+        PendingCapacityAttestations::<T>::insert(
+            who,
+            PendingCapacityAttestation::<T> {
+                capacity,
+                requested_at: frame_system::Pallet::<T>::block_number(),
+                stage: CapacityAttestationStage::Requested,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// This function holds the logic that draws the seed of the plot of the pending capacity attestation of an
+    /// account, from randomness that was not known when it was requested, and returns it along with the deadline
+    /// to commit to the plot.
+    pub(crate) fn do_draw_capacity_plot_seed(
+        who: &T::AccountId,
+    ) -> Result<(H256, BlockNumberFor<T>), DispatchError> {
+        let mut attestation = PendingCapacityAttestations::<T>::get(who)
+            .ok_or(Error::<T>::CapacityAttestationNotFound)?;
+
+        ensure!(
+            matches!(attestation.stage, CapacityAttestationStage::Requested),
+            Error::<T>::CapacityPlotSeedAlreadyDrawn
+        );
+
+        // Check that the seed is drawn within the maximum amount of blocks allowed to wait for valid randomness,
+        // so that an account cannot wait indefinitely for a seed that suits it
+        let current_block = frame_system::Pallet::<T>::block_number();
+        ensure!(
+            current_block < attestation.requested_at + T::MaxBlocksForRandomness::get(),
+            Error::<T>::CapacityAttestationExpired
+        );
+
+        // Check that the randomness was not known when the attestation was requested
+        let (randomness, block_number_when_random) =
+            T::ProvidersRandomness::random(&(b"capacity_plot", who).encode());
+        ensure!(
+            block_number_when_random >= attestation.requested_at,
+            Error::<T>::RandomnessNotValidYet
+        );
+
+        let seed = <BlakeTwo256 as sp_runtime::traits::Hash>::hash(&randomness.encode());
+        let deadline = current_block.saturating_add(T::CapacityAttestationWindow::get());
+        attestation.stage = CapacityAttestationStage::SeedDrawn { seed, deadline };
+        PendingCapacityAttestations::<T>::insert(who, attestation);
+
+        Ok((seed, deadline))
+    }
+
+    /// This function holds the logic that stores the commitment of an account to the plot of its pending capacity
+    /// attestation, and returns the capacity it attests along with the deadline to answer its challenge.
+    pub(crate) fn do_commit_capacity_plot(
+        who: &T::AccountId,
+        plot_root: H256,
+    ) -> Result<(StorageDataUnit<T>, BlockNumberFor<T>), DispatchError> {
+        let mut attestation = PendingCapacityAttestations::<T>::get(who)
+            .ok_or(Error::<T>::CapacityAttestationNotFound)?;
+
+        let CapacityAttestationStage::SeedDrawn { seed, deadline } = attestation.stage else {
+            return Err(Error::<T>::CapacityPlotSeedNotDrawn.into());
+        };
+
+        let committed_at = frame_system::Pallet::<T>::block_number();
+        ensure!(
+            committed_at <= deadline,
+            Error::<T>::CapacityAttestationExpired
+        );
+
+        let deadline = committed_at.saturating_add(T::CapacityAttestationWindow::get());
+        attestation.stage = CapacityAttestationStage::Committed {
+            seed,
+            plot_root,
+            committed_at,
+            deadline,
+        };
+        PendingCapacityAttestations::<T>::insert(who, &attestation);
+
+        Ok((attestation.capacity, deadline))
+    }
+
+    /// This function holds the logic that checks the answer of an account to the challenge of its pending capacity
+    /// attestation and, if valid, sets the requested capacity as its attested capacity, returning it.
+    pub(crate) fn do_answer_capacity_challenge(
+        who: &T::AccountId,
+        answer: CapacityChallengeAnswer<T>,
+    ) -> Result<StorageDataUnit<T>, DispatchError> {
+        let attestation = PendingCapacityAttestations::<T>::get(who)
+            .ok_or(Error::<T>::CapacityAttestationNotFound)?;
+
+        let challenged_leaves =
+            Self::capacity_challenge(who, &attestation).map_err(|e| match e {
+                QueryCapacityChallengeError::AttestationNotFound => {
+                    Error::<T>::CapacityAttestationNotFound
+                }
+                QueryCapacityChallengeError::PlotNotCommitted => {
+                    Error::<T>::CapacityPlotNotCommitted
+                }
+                QueryCapacityChallengeError::RandomnessNotValidYet => {
+                    Error::<T>::RandomnessNotValidYet
+                }
+                QueryCapacityChallengeError::AttestationExpired => {
+                    Error::<T>::CapacityAttestationExpired
+                }
+            })?;
+
+        let CapacityAttestationStage::Committed {
+            seed, plot_root, ..
+        } = attestation.stage
+        else {
+            return Err(Error::<T>::CapacityPlotNotCommitted.into());
+        };
+
+        let account = who.encode();
+        let plot_size = Self::capacity_plot_size(attestation.capacity);
+
+        ensure!(
+            answer.len() == challenged_leaves.len(),
+            Error::<T>::InvalidCapacityAttestationProof
+        );
+        for (index, proof) in challenged_leaves.into_iter().zip(answer.iter()) {
+            ensure!(
+                capacity_attestation::verify_leaf_proof(
+                    &plot_root, &seed, &account, plot_size, index, proof,
+                ),
+                Error::<T>::InvalidCapacityAttestationProof
+            );
+        }
+
+        PendingCapacityAttestations::<T>::remove(who);
+        AttestedCapacities::<T>::insert(who, attestation.capacity);
+
+        Ok(attestation.capacity)
+    }
+
+    /// Returns the amount of leaves of a capacity attestation plot for `capacity`.
+    fn capacity_plot_size(capacity: StorageDataUnit<T>) -> u64 {
+        capacity_attestation::plot_size(
+            capacity.into(),
+            T::CapacityAttestationChunkSize::get().into(),
+        )
+    }
+
+    /// Returns the indexes of the leaves that `who` is challenged on for its pending `attestation`.
+    ///
+    /// Fails if `who` did not commit to the plot of the attestation, if the window to answer the challenge has
+    /// passed, or if the randomness to challenge it is not available yet, i.e. it could have been known when `who`
+    /// committed to the plot.
+    fn capacity_challenge(
+        who: &T::AccountId,
+        attestation: &PendingCapacityAttestation<T>,
+    ) -> Result<Vec<u64>, QueryCapacityChallengeError> {
+        let CapacityAttestationStage::Committed {
+            committed_at,
+            deadline,
+            ..
+        } = attestation.stage
+        else {
+            return Err(QueryCapacityChallengeError::PlotNotCommitted);
+        };
+
+        if frame_system::Pallet::<T>::block_number() > deadline {
+            return Err(QueryCapacityChallengeError::AttestationExpired);
+        }
+
+        let (seed, block_number_when_random) =
+            T::ProvidersRandomness::random(&(b"capacity_attestation", who).encode());
+        if block_number_when_random < committed_at {
+            return Err(QueryCapacityChallengeError::RandomnessNotValidYet);
+        }
+
+        Ok(capacity_attestation::challenged_leaf_indexes(
+            &seed.encode(),
+            Self::capacity_plot_size(attestation.capacity),
+            T::CapacityAttestationSpotChecks::get(),
+        ))
+    }
+
+    /// Checks that `who` has attested at least `capacity`, if capacity attestations are required.
+    fn ensure_capacity_attested(
+        who: &T::AccountId,
+        capacity: StorageDataUnit<T>,
+    ) -> DispatchResult {
+        if T::RequireCapacityAttestation::get() {
+            ensure!(
+                AttestedCapacities::<T>::get(who).is_some_and(|attested| attested >= capacity),
+                Error::<T>::CapacityNotAttested
+            );
         }
 
         Ok(())
@@ -1255,18 +1917,74 @@ where
     /// being an exact match to a file key stored by the Storage Provider. The StorageHub protocol requires the Storage Provider to
     /// submit a proof of storage for the neighbouring file keys of the missing challenged file key.
     ///
-    /// The slashing amount is calculated as the product of the [`SlashAmountPerMaxFileSize`](Config::SlashAmountPerMaxFileSize)
-    /// (an assumption that most file sizes fall below this large arbitrary size) and the accrued failed proof submissions multiplied
+    /// The base slashing amount is calculated as the product of the slash amount per failed proof submission
+    /// (see [`Self::compute_slash_amount_per_failed_proof_submission`]) and the accrued failed proof submissions multiplied
     /// by `2` to account for the worst case scenario where the provider would have proved two file keys surrounding the challenged file key.
+    ///
+    /// The base amount is then escalated according to the number of proof submission deadlines the Storage Provider
+    /// missed in a row (see [`Self::apply_slash_escalation`]).
     pub fn compute_worst_case_scenario_slashable_amount(
         provider_id: &ProviderIdFor<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
         let accrued_failed_submission_count = <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_accrued_failed_proof_submissions(&provider_id)
             .ok_or(Error::<T>::ProviderNotSlashable)?.into();
+        let consecutive_missed_deadlines = <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_consecutive_missed_proof_deadlines(&provider_id);
+
+        let (_, _, used_capacity) = Self::get_provider_details(*provider_id)?;
+
+        let base_slash_amount =
+            Self::compute_slash_amount_per_failed_proof_submission(used_capacity)
+                .saturating_mul(accrued_failed_submission_count)
+                .saturating_mul(2u32.into());
 
-        Ok(T::SlashAmountPerMaxFileSize::get()
-            .saturating_mul(accrued_failed_submission_count)
-            .saturating_mul(2u32.into()))
+        Ok(Self::apply_slash_escalation(
+            base_slash_amount,
+            consecutive_missed_deadlines,
+        ))
+    }
+
+    /// Compute the amount to slash for a single failed proof submission of a Storage Provider using `used_capacity`.
+    ///
+    /// The more data a Storage Provider stores, the more data a failed proof submission puts at risk. So the
+    /// [`SlashAmountPerMaxFileSize`](Config::SlashAmountPerMaxFileSize) is scaled proportionally to the data stored
+    /// relative to [`SlashReferenceDataSize`](Config::SlashReferenceDataSize): down for Storage Providers storing less
+    /// than it, and up for those storing more.
+    pub(crate) fn compute_slash_amount_per_failed_proof_submission(
+        used_capacity: StorageDataUnit<T>,
+    ) -> BalanceOf<T> {
+        let reference_data_size = T::SlashReferenceDataSize::get();
+        let slash_amount_per_max_file_size = T::SlashAmountPerMaxFileSize::get();
+
+        if reference_data_size.is_zero() {
+            return slash_amount_per_max_file_size;
+        }
+
+        slash_amount_per_max_file_size
+            .multiply_rational(
+                used_capacity.into(),
+                reference_data_size.into(),
+                Rounding::Up,
+            )
+            .unwrap_or_else(BalanceOf::<T>::max_value)
+    }
+
+    /// Escalate `base_slash_amount` based on the number of proof submission deadlines missed in a row.
+    ///
+    /// Every deadline missed after the first one adds [`SlashEscalationPerMissedDeadline`](Config::SlashEscalationPerMissedDeadline)
+    /// of the base amount, up to [`MaxSlashEscalationMultiplier`](Config::MaxSlashEscalationMultiplier) times the base amount.
+    pub(crate) fn apply_slash_escalation(
+        base_slash_amount: BalanceOf<T>,
+        consecutive_missed_deadlines: u32,
+    ) -> BalanceOf<T> {
+        let escalation = T::SlashEscalationPerMissedDeadline::get()
+            .mul_floor(base_slash_amount)
+            .saturating_mul(consecutive_missed_deadlines.saturating_sub(1).into());
+        let max_slash_amount =
+            base_slash_amount.saturating_mul(T::MaxSlashEscalationMultiplier::get().max(1).into());
+
+        base_slash_amount
+            .saturating_add(escalation)
+            .min(max_slash_amount)
     }
 
     /// Adjust the fixed rate payment stream between a user and an MSP based on the [`RateDeltaParam`].
@@ -1563,6 +2281,7 @@ impl<T: pallet::Config> ReadBucketsInterface for pallet::Pallet<T> {
     type AccountId = T::AccountId;
     type BucketId = BucketId<T>;
     type BucketNameLimit = T::BucketNameLimit;
+    type EncryptionMetadataLimit = T::EncryptionMetadataLimit;
     type ProviderId = ProviderIdFor<T>;
     type ReadAccessGroupId = T::ReadAccessGroupId;
     type MerkleHash = MerklePatriciaRoot<T>;
@@ -1640,6 +2359,16 @@ impl<T: pallet::Config> ReadBucketsInterface for pallet::Pallet<T> {
         let bucket = Buckets::<T>::get(bucket_id).ok_or(Error::<T>::BucketNotFound)?;
         Ok(bucket.msp_id)
     }
+
+    fn get_bucket_encryption_metadata(
+        bucket_id: &Self::BucketId,
+    ) -> Result<Option<BoundedVec<u8, Self::EncryptionMetadataLimit>>, DispatchError> {
+        ensure!(
+            Buckets::<T>::contains_key(bucket_id),
+            Error::<T>::BucketNotFound
+        );
+        Ok(BucketEncryptionMetadata::<T>::get(bucket_id))
+    }
 }
 
 /// Implement the MutateBucketsInterface trait for the Storage Providers pallet.
@@ -1648,6 +2377,7 @@ impl<T: pallet::Config> MutateBucketsInterface for pallet::Pallet<T> {
     type BucketId = BucketId<T>;
     type ProviderId = ProviderIdFor<T>;
     type ReadAccessGroupId = T::ReadAccessGroupId;
+    type EncryptionMetadataLimit = T::EncryptionMetadataLimit;
     type MerkleHash = MerklePatriciaRoot<T>;
     type StorageDataUnit = T::StorageDataUnit;
     type ValuePropId = ValuePropIdFor<T>;
@@ -1686,6 +2416,12 @@ impl<T: pallet::Config> MutateBucketsInterface for pallet::Pallet<T> {
                 )
                 .ok_or(Error::<T>::ValuePropositionNotFound)?;
 
+                // New buckets always get the latest version of a value proposition.
+                ensure!(
+                    Self::latest_value_prop_id(&provider_id, &value_prop_id) == value_prop_id,
+                    Error::<T>::ValuePropositionSuperseded
+                );
+
                 ensure!(
                     value_prop.available,
                     Error::<T>::ValuePropositionNotAvailable
@@ -1790,6 +2526,65 @@ impl<T: pallet::Config> MutateBucketsInterface for pallet::Pallet<T> {
         })
     }
 
+    fn migrate_bucket_to_latest_value_prop(
+        bucket_id: &Self::BucketId,
+    ) -> Result<(Self::ValuePropId, Self::ValuePropId), DispatchError> {
+        let bucket = Buckets::<T>::get(bucket_id).ok_or(Error::<T>::BucketNotFound)?;
+
+        let msp_id = bucket
+            .msp_id
+            .ok_or(Error::<T>::BucketMustHaveMspForOperation)?;
+        let previous_value_prop_id = bucket
+            .value_prop_id
+            .ok_or(Error::<T>::BucketHasNoValueProposition)?;
+
+        let value_prop_id = Self::latest_value_prop_id(&msp_id, &previous_value_prop_id);
+        ensure!(
+            value_prop_id != previous_value_prop_id,
+            Error::<T>::BucketAlreadyOnLatestValueProposition
+        );
+
+        let value_prop =
+            MainStorageProviderIdsToValuePropositions::<T>::get(&msp_id, &value_prop_id)
+                .ok_or(Error::<T>::ValuePropositionNotFound)?;
+        ensure!(
+            bucket.size <= value_prop.bucket_data_limit,
+            Error::<T>::BucketSizeExceedsLimit
+        );
+
+        // Remove the rate of the bucket under its previous value proposition and add it back under the new one.
+        Self::apply_delta_fixed_rate_payment_stream(
+            &msp_id,
+            bucket_id,
+            &bucket.user_id,
+            RateDeltaParam::RemoveBucket,
+        )?;
+
+        Buckets::<T>::insert(
+            bucket_id,
+            Bucket {
+                value_prop_id: Some(value_prop_id),
+                ..bucket.clone()
+            },
+        );
+
+        Self::apply_delta_fixed_rate_payment_stream(
+            &msp_id,
+            bucket_id,
+            &bucket.user_id,
+            RateDeltaParam::NewBucket,
+        )?;
+
+        Self::deposit_event(Event::<T>::BucketValuePropMigrated {
+            bucket_id: *bucket_id,
+            msp_id,
+            previous_value_prop_id,
+            value_prop_id,
+        });
+
+        Ok((previous_value_prop_id, value_prop_id))
+    }
+
     fn change_root_bucket(bucket_id: Self::BucketId, new_root: Self::MerkleHash) -> DispatchResult {
         Buckets::<T>::try_mutate(&bucket_id, |bucket| {
             let bucket = bucket.as_mut().ok_or(Error::<T>::BucketNotFound)?;
@@ -1827,6 +2622,7 @@ impl<T: pallet::Config> MutateBucketsInterface for pallet::Pallet<T> {
         };
 
         Buckets::<T>::remove(&bucket_id);
+        BucketEncryptionMetadata::<T>::remove(&bucket_id);
 
         // Release the bucket deposit hold
         T::NativeBalance::release(
@@ -1862,6 +2658,20 @@ impl<T: pallet::Config> MutateBucketsInterface for pallet::Pallet<T> {
         })
     }
 
+    fn update_bucket_encryption_metadata(
+        bucket_id: Self::BucketId,
+        maybe_encryption_metadata: Option<BoundedVec<u8, Self::EncryptionMetadataLimit>>,
+    ) -> DispatchResult {
+        ensure!(
+            Buckets::<T>::contains_key(&bucket_id),
+            Error::<T>::BucketNotFound
+        );
+
+        BucketEncryptionMetadata::<T>::set(&bucket_id, maybe_encryption_metadata);
+
+        Ok(())
+    }
+
     fn increase_bucket_size(
         bucket_id: &Self::BucketId,
         delta: Self::StorageDataUnit,
@@ -2140,25 +2950,11 @@ impl<T: pallet::Config> ReadProvidersInterface for pallet::Pallet<T> {
     }
 
     fn is_provider_insolvent(who: Self::ProviderId) -> bool {
-        let is_provider_insolvent =
-            InsolventProviders::<T>::get(&StorageProviderId::<T>::MainStorageProvider(who))
-                .is_some()
-                || InsolventProviders::<T>::get(&StorageProviderId::<T>::BackupStorageProvider(
-                    who,
-                ))
-                .is_some();
-
-        // While provider is being awaited for top up, it is still considered insolvent, it's just that
-        // it can get out of this state.
-        let is_provider_awaiting_topup =
-            AwaitingTopUpFromProviders::<T>::get(&StorageProviderId::<T>::MainStorageProvider(who))
-                .is_some()
-                || AwaitingTopUpFromProviders::<T>::get(
-                    &StorageProviderId::<T>::BackupStorageProvider(who),
-                )
-                .is_some();
+        Self::is_insolvent_or_awaiting_top_up(&who)
+    }
 
-        is_provider_insolvent || is_provider_awaiting_topup
+    fn get_provider_status(who: Self::ProviderId) -> Option<ProviderStatus> {
+        Pallet::<T>::get_provider_status(&who)
     }
 }
 
@@ -2244,6 +3040,14 @@ impl<T: pallet::Config> ReadChallengeableProvidersInterface for pallet::Pallet<T
         BackupStorageProviders::<T>::contains_key(&who)
     }
 
+    fn get_provider_status(who: Self::ProviderId) -> Option<ProviderStatus> {
+        if BackupStorageProviders::<T>::contains_key(&who) {
+            Pallet::<T>::get_provider_status(&who)
+        } else {
+            None
+        }
+    }
+
     fn get_min_stake(
     ) -> <Self::Balance as frame_support::traits::fungible::Inspect<Self::AccountId>>::Balance {
         T::SpMinDeposit::get()
@@ -2272,6 +3076,7 @@ impl<T: pallet::Config> MutateChallengeableProvidersInterface for pallet::Pallet
 
     fn update_provider_after_key_removal(
         provider_id: &Self::ProviderId,
+        removed_key: &Self::MerkleHash,
         removed_trie_value: &Vec<u8>,
     ) -> DispatchResult {
         // Get the removed file's metadata
@@ -2295,8 +3100,18 @@ impl<T: pallet::Config> MutateChallengeableProvidersInterface for pallet::Pallet
         // Decrease the used capacity of the provider
         Self::decrease_capacity_used(provider_id, file_size)?;
 
-        // If the user is insolvent, delete the payment stream between the user and the provider if it still exists.
-        if <T::PaymentStreams as ReadUserSolvencyInterface>::is_user_insolvent(&owner) {
+        // If the provider is being paid for the file from an escrow, stop paying it and refund the rest of its share
+        // to the user, since the file is not part of the payment stream between them.
+        if <T::PaymentStreams as PaymentEscrowInterface>::is_escrow_provider(
+            removed_key,
+            provider_id,
+        ) {
+            <T::PaymentStreams as PaymentEscrowInterface>::remove_escrow_provider(
+                removed_key,
+                provider_id,
+            )?;
+        } else if <T::PaymentStreams as ReadUserSolvencyInterface>::is_user_insolvent(&owner) {
+            // If the user is insolvent, delete the payment stream between the user and the provider if it still exists.
             if <T::PaymentStreams as PaymentStreamsInterface>::has_active_payment_stream_with_user(
                 &provider_id,
                 &owner,
@@ -2400,6 +3215,14 @@ where
         }
     }
 
+    pub fn query_capacity_challenge(
+        who: &T::AccountId,
+    ) -> Result<Vec<u64>, QueryCapacityChallengeError> {
+        let attestation = PendingCapacityAttestations::<T>::get(who)
+            .ok_or(QueryCapacityChallengeError::AttestationNotFound)?;
+        Self::capacity_challenge(who, &attestation)
+    }
+
     pub fn query_earliest_change_capacity_block(
         provider_id: &BackupStorageProviderId<T>,
     ) -> Result<BlockNumberFor<T>, QueryEarliestChangeCapacityBlockError> {
@@ -2426,6 +3249,15 @@ where
         Ok(bucket.msp_id)
     }
 
+    pub fn query_bucket_encryption_metadata(
+        bucket_id: &BucketId<T>,
+    ) -> Result<Option<Vec<u8>>, QueryBucketEncryptionMetadataError> {
+        if !Buckets::<T>::contains_key(bucket_id) {
+            return Err(QueryBucketEncryptionMetadataError::BucketNotFound);
+        }
+        Ok(BucketEncryptionMetadata::<T>::get(bucket_id).map(|metadata| metadata.into_inner()))
+    }
+
     pub fn query_provider_multiaddresses(
         provider_id: &ProviderIdFor<T>,
     ) -> Result<Multiaddresses<T>, QueryProviderMultiaddressesError> {
@@ -2446,6 +3278,38 @@ where
             .collect::<Vec<ValuePropositionWithId<T>>>()
     }
 
+    pub fn query_value_proposition_version(
+        msp_id: &MainStorageProviderId<T>,
+        value_prop_id: &ValuePropIdFor<T>,
+    ) -> Result<ValuePropositionVersion<T>, QueryValuePropositionVersionError> {
+        ensure!(
+            MainStorageProviderIdsToValuePropositions::<T>::contains_key(msp_id, value_prop_id),
+            QueryValuePropositionVersionError::ValuePropositionNotFound
+        );
+
+        Ok(Self::value_prop_version(msp_id, value_prop_id))
+    }
+
+    pub fn query_latest_value_proposition(
+        msp_id: &MainStorageProviderId<T>,
+        value_prop_id: &ValuePropIdFor<T>,
+    ) -> Result<ValuePropositionWithId<T>, QueryValuePropositionVersionError> {
+        ensure!(
+            MainStorageProviderIdsToValuePropositions::<T>::contains_key(msp_id, value_prop_id),
+            QueryValuePropositionVersionError::ValuePropositionNotFound
+        );
+
+        let latest_value_prop_id = Self::latest_value_prop_id(msp_id, value_prop_id);
+        let value_prop =
+            MainStorageProviderIdsToValuePropositions::<T>::get(msp_id, &latest_value_prop_id)
+                .ok_or(QueryValuePropositionVersionError::ValuePropositionNotFound)?;
+
+        Ok(ValuePropositionWithId::new(
+            latest_value_prop_id,
+            value_prop,
+        ))
+    }
+
     pub fn get_bsp_stake(
         bsp_id: &BackupStorageProviderId<T>,
     ) -> Result<BalanceOf<T>, GetStakeError> {
@@ -2481,6 +3345,83 @@ where
         true
     }
 
+    /// Returns the reputation weight of the BSP `bsp_id`, which is the weight used to compute its threshold to
+    /// volunteer for storage requests.
+    pub fn query_bsp_reputation_weight(
+        bsp_id: &BackupStorageProviderId<T>,
+    ) -> Result<T::ReputationWeightType, QueryBspReputationWeightError> {
+        BackupStorageProviders::<T>::get(bsp_id)
+            .map(|bsp| bsp.reputation_weight)
+            .ok_or(QueryBspReputationWeightError::BspNotRegistered)
+    }
+
+    /// Returns the status of the Storage Provider `provider_id`, or `None` if it is not a registered MSP or BSP.
+    ///
+    /// If more than one status applies to the Storage Provider, `Insolvent` takes precedence over `Suspended`,
+    /// which takes precedence over `Exiting`. Storage Providers awaiting a top up of their deposit are reported
+    /// as `Insolvent` until they top it up.
+    pub fn get_provider_status(provider_id: &ProviderIdFor<T>) -> Option<ProviderStatus> {
+        if !MainStorageProviders::<T>::contains_key(provider_id)
+            && !BackupStorageProviders::<T>::contains_key(provider_id)
+        {
+            return None;
+        }
+
+        let status = if Self::is_insolvent_or_awaiting_top_up(provider_id) {
+            ProviderStatus::Insolvent
+        } else if SuspendedProviders::<T>::contains_key(provider_id) {
+            ProviderStatus::Suspended
+        } else if ExitingProviders::<T>::contains_key(provider_id) {
+            ProviderStatus::Exiting
+        } else {
+            ProviderStatus::Active
+        };
+
+        Some(status)
+    }
+
+    /// Returns the current global BSP capacity utilization, the distribution of BSPs along utilization
+    /// deciles, the current price per giga-unit of data per tick and the samples kept on-chain.
+    ///
+    /// Computing the distribution iterates over all registered BSPs, so this should only be called
+    /// from the runtime API and never from within an extrinsic or hook.
+    pub fn capacity_utilization() -> CapacityUtilization<T> {
+        let mut bsps_per_utilization_decile = [0u32; types::CAPACITY_UTILIZATION_DECILES];
+        for (_, bsp) in BackupStorageProviders::<T>::iter() {
+            let decile = Self::utilization_decile(bsp.capacity_used, bsp.capacity);
+            bsps_per_utilization_decile[decile].saturating_inc();
+        }
+
+        CapacityUtilization {
+            total_capacity: Self::get_total_bsp_capacity(),
+            used_capacity: Self::get_used_bsp_capacity(),
+            bsps_per_utilization_decile,
+            price_per_giga_unit_of_data_per_tick:
+                <T::PaymentStreams as MutatePricePerGigaUnitPerTickInterface>::get_price_per_giga_unit_per_tick(),
+            samples: CapacityUtilizationSamples::<T>::get(),
+        }
+    }
+
+    /// Returns the index of the utilization decile in which a provider with the given used and total
+    /// capacity falls. Providers with zero capacity are considered to be in the first decile, and full
+    /// (or over-used) providers in the last one.
+    pub(crate) fn utilization_decile(
+        used_capacity: StorageDataUnit<T>,
+        total_capacity: StorageDataUnit<T>,
+    ) -> usize {
+        if total_capacity.is_zero() {
+            return 0;
+        }
+
+        let utilization = Perbill::from_rational::<u64>(
+            used_capacity.min(total_capacity).into(),
+            total_capacity.into(),
+        );
+        let decile = utilization.deconstruct() / (Perbill::ACCURACY / 10);
+
+        (decile as usize).min(types::CAPACITY_UTILIZATION_DECILES - 1)
+    }
+
     /// Compute the next block number to insert an expiring item, and insert it in the corresponding expiration queue.
     ///
     /// This function attempts to insert a the expiration item at the next available block starting from
@@ -2499,10 +3440,11 @@ where
 mod hooks {
     use crate::{
         pallet,
-        types::{ShTickGetter, StorageHubTickNumber},
+        types::{CapacityUtilizationSample, ShTickGetter, StorageHubTickNumber},
         utils::StorageProviderId,
-        AwaitingTopUpFromProviders, BackupStorageProviders, Event, HoldReason, InsolventProviders,
-        MainStorageProviders, NextStartingShTickToCleanUp, Pallet, ProviderTopUpExpirations,
+        AwaitingTopUpFromProviders, BackupStorageProviders, CapacityUtilizationSamples, Event,
+        HoldReason, InsolventProviders, LastReputationUpdateTick, MainStorageProviders,
+        NextStartingShTickToCleanUp, Pallet, ProviderTopUpExpirations,
     };
 
     use frame_support::{
@@ -2513,13 +3455,105 @@ mod hooks {
         },
         weights::WeightMeter,
     };
-    use shp_traits::StorageHubTickGetter;
+    use shp_traits::{
+        MutatePricePerGigaUnitPerTickInterface, OnProviderStatusChange, ProofSubmittersInterface,
+        ProviderStatusChange, StorageHubTickGetter,
+    };
     use sp_runtime::{
         traits::{One, Zero},
         Saturating,
     };
 
     impl<T: pallet::Config> Pallet<T> {
+        pub(crate) fn do_on_poll(weight: &mut WeightMeter) {
+            Self::sample_capacity_utilization(weight);
+            Self::update_reputation_weights(weight);
+        }
+
+        fn sample_capacity_utilization(weight: &mut WeightMeter) {
+            let db_weight = T::DbWeight::get();
+            let sampling_period = T::CapacityUtilizationSamplingPeriod::get();
+            let current_sh_tick = ShTickGetter::<T>::get_current_tick();
+
+            // Only take a sample every `CapacityUtilizationSamplingPeriod` ticks.
+            if sampling_period.is_zero() || !(current_sh_tick % sampling_period).is_zero() {
+                return;
+            }
+
+            // The tick does not advance while it is stalled (e.g. if proofs can't be submitted), so
+            // this runs again in the same tick on every block until it does. Only take one sample
+            // per tick.
+            let mut samples = CapacityUtilizationSamples::<T>::get();
+            weight.consume(db_weight.reads(1));
+            if samples
+                .last()
+                .is_some_and(|sample| sample.tick == current_sh_tick)
+            {
+                return;
+            }
+
+            let sample = CapacityUtilizationSample {
+                tick: current_sh_tick,
+                total_capacity: Self::get_total_bsp_capacity(),
+                used_capacity: Self::get_used_bsp_capacity(),
+                price_per_giga_unit_of_data_per_tick:
+                    <T::PaymentStreams as MutatePricePerGigaUnitPerTickInterface>::get_price_per_giga_unit_per_tick(),
+            };
+
+            // Discard the oldest sample if the bounded list is full.
+            if samples.is_full() && !samples.is_empty() {
+                samples.remove(0);
+            }
+            // This only fails if the maximum amount of samples is zero, in which case sampling is a no-op.
+            let _ = samples.try_push(sample);
+            CapacityUtilizationSamples::<T>::put(samples);
+
+            weight.consume(db_weight.reads_writes(3, 1));
+        }
+
+        /// Accounts for the proofs submitted by BSPs in the previous tick in their reputation weights.
+        ///
+        /// The proof submitters of each tick are only accounted for once. If there is not enough weight left to
+        /// account for all of them, they are accounted for in a later block instead.
+        fn update_reputation_weights(weight: &mut WeightMeter) {
+            let db_weight = T::DbWeight::get();
+            let minimum_required_weight = db_weight.reads(2);
+            if !weight.can_consume(minimum_required_weight) {
+                return;
+            }
+            weight.consume(minimum_required_weight);
+
+            let tick_to_process =
+                <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_current_tick()
+                    .saturating_sub(One::one());
+            if tick_to_process <= LastReputationUpdateTick::<T>::get() {
+                return;
+            }
+
+            let submitters =
+                <T::ProvidersProofSubmitters as ProofSubmittersInterface>::get_proof_submitters_for_tick(
+                    &tick_to_process,
+                )
+                .unwrap_or_default();
+
+            // For each submitter, the BSP, its slashable record and its streak are read, and the streak, the BSP
+            // and the global reputation weight are written.
+            let required_weight = db_weight
+                .reads_writes(3, 3)
+                .saturating_mul(submitters.len() as u64)
+                .saturating_add(db_weight.writes(1));
+            if !weight.can_consume(required_weight) {
+                return;
+            }
+
+            for provider_id in submitters.iter() {
+                Self::record_bsp_proof_submission(provider_id);
+            }
+
+            LastReputationUpdateTick::<T>::put(tick_to_process);
+            weight.consume(required_weight);
+        }
+
         pub(crate) fn do_on_idle(mut meter: &mut WeightMeter) -> &mut WeightMeter {
             let db_weight = T::DbWeight::get();
             let current_sh_tick = ShTickGetter::<T>::get_current_tick();
@@ -2598,6 +3632,11 @@ mod hooks {
                     provider_id: *typed_provider_id.inner(),
                 });
 
+                T::OnProviderStatusChange::on_provider_status_change(
+                    typed_provider_id.inner(),
+                    ProviderStatusChange::Insolvent,
+                );
+
                 let account_id = if let Some(bsp) =
                     BackupStorageProviders::<T>::get(&typed_provider_id.inner())
                 {
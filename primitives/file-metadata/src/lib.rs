@@ -71,6 +71,27 @@ impl<const H_LENGTH: usize, const CHUNK_SIZE: u64, const SIZE_TO_CHALLENGES: u64
     pub fn last_chunk_id(&self) -> ChunkId {
         ChunkId::new(self.chunks_count() - 1)
     }
+
+    /// Returns the IDs of the chunks of the file that make up a transfer chunk of `transfer_chunk_size`
+    /// bytes, starting at `first_chunk_id`.
+    ///
+    /// Peers transfer files in chunks of a negotiated size, which can be bigger than `CHUNK_SIZE`.
+    /// This maps a transfer chunk back to the chunks of the file, as identified on-chain and in the
+    /// file's Merkle trie. `transfer_chunk_size` is rounded down to a multiple of `CHUNK_SIZE`, with a
+    /// minimum of one chunk, and the transfer chunk is cut at the end of the file.
+    pub fn chunk_ids_in_transfer_chunk(
+        &self,
+        first_chunk_id: ChunkId,
+        transfer_chunk_size: u64,
+    ) -> Vec<ChunkId> {
+        let chunks_per_transfer_chunk = (transfer_chunk_size / CHUNK_SIZE).max(1);
+        let first_chunk_id = first_chunk_id.as_u64();
+        let end = first_chunk_id
+            .saturating_add(chunks_per_transfer_chunk)
+            .min(self.chunks_count());
+
+        (first_chunk_id..end).map(ChunkId::new).collect()
+    }
 }
 
 /// Interface for encoding and decoding FileMetadata, used by the runtime.
@@ -16,7 +16,7 @@ use sp_runtime::{
         AtLeast32BitUnsigned, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Hash, One,
         Saturating, Zero,
     },
-    BoundedVec, DispatchError,
+    BoundedVec, DispatchError, FixedU128,
 };
 use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
@@ -153,6 +153,9 @@ pub trait ReadBucketsInterface {
     /// Byte limit of a bucket's name.
     type BucketNameLimit: Get<u32>;
 
+    /// Byte limit of a bucket's encryption metadata.
+    type EncryptionMetadataLimit: Get<u32>;
+
     /// Check if a bucket exists.
     fn bucket_exists(bucket_id: &Self::BucketId) -> bool;
 
@@ -197,6 +200,12 @@ pub trait ReadBucketsInterface {
     fn get_msp_bucket(
         bucket_id: &Self::BucketId,
     ) -> Result<Option<Self::ProviderId>, DispatchError>;
+
+    /// Get the metadata describing how the contents of a bucket are encrypted, if its owner set
+    /// any.
+    fn get_bucket_encryption_metadata(
+        bucket_id: &Self::BucketId,
+    ) -> Result<Option<BoundedVec<u8, Self::EncryptionMetadataLimit>>, DispatchError>;
 }
 
 /// A trait to change the state of buckets registered in the system, such as updating their privacy
@@ -243,6 +252,9 @@ pub trait MutateBucketsInterface {
     /// Type of a bucket's read-access group's ID (which is the read-access NFT collection's ID).
     type ReadAccessGroupId: Member + Parameter + MaxEncodedLen + Copy + Incrementable;
 
+    /// Byte limit of a bucket's encryption metadata.
+    type EncryptionMetadataLimit: Get<u32>;
+
     /// Type of the root and keys in the Merkle Patricia Forest of a
     /// registered Provider.
     type MerkleHash: Parameter
@@ -285,6 +297,13 @@ pub trait MutateBucketsInterface {
         maybe_read_access_group_id: Option<Self::ReadAccessGroupId>,
     ) -> DispatchResult;
 
+    /// Update the metadata describing how the contents of a bucket are encrypted. If None is
+    /// passed, the metadata of the bucket is removed.
+    fn update_bucket_encryption_metadata(
+        bucket_id: Self::BucketId,
+        maybe_encryption_metadata: Option<BoundedVec<u8, Self::EncryptionMetadataLimit>>,
+    ) -> DispatchResult;
+
     /// Add a new bucket under the MSP corresponding to `provider_id`, that will be owned by the account `user_id`.
     /// If `privacy` is true, the bucket will be private and optionally the `read_access_group_id` will be used to
     /// determine the collection of NFTs that can access the bucket.
@@ -306,6 +325,14 @@ pub trait MutateBucketsInterface {
     /// Set a bucket's `msp_id` to `None` and also removing the element from the list in `MainStorageProviderIdsToBuckets`
     fn unassign_msp_from_bucket(bucket_id: &Self::BucketId) -> DispatchResult;
 
+    /// Migrate a bucket to the latest version of its value proposition, updating the fixed rate payment stream
+    /// of its owner with its MSP accordingly.
+    ///
+    /// Returns the IDs of the previous and the new value proposition of the bucket.
+    fn migrate_bucket_to_latest_value_prop(
+        bucket_id: &Self::BucketId,
+    ) -> Result<(Self::ValuePropId, Self::ValuePropId), DispatchError>;
+
     /// Change the root of a bucket.
     fn change_root_bucket(bucket_id: Self::BucketId, new_root: Self::MerkleHash) -> DispatchResult;
 
@@ -495,6 +522,9 @@ pub trait ReadChallengeableProvidersInterface {
     /// Check if an account is a registered challengeable Provider.
     fn is_provider(who: Self::ProviderId) -> bool;
 
+    /// Get the status of a registered challengeable Provider, or `None` if it is not one.
+    fn get_provider_status(who: Self::ProviderId) -> Option<ProviderStatus>;
+
     /// Get the Provider Id from Account Id, if it is a registered challengeable Provider.
     fn get_provider_id(who: Self::AccountId) -> Option<Self::ProviderId>;
 
@@ -557,6 +587,7 @@ pub trait MutateChallengeableProvidersInterface {
     /// Update the information of a registered challengeable Provider after a successful trie element removal.
     fn update_provider_after_key_removal(
         who: &Self::ProviderId,
+        removed_key: &Self::MerkleHash,
         removed_trie_value: &Vec<u8>,
     ) -> DispatchResult;
 }
@@ -628,6 +659,9 @@ pub trait ReadProvidersInterface {
 
     /// Check if the provider is insolvent.
     fn is_provider_insolvent(who: Self::ProviderId) -> bool;
+
+    /// Get the status of a registered Provider, or `None` if it is not a registered Provider.
+    fn get_provider_status(who: Self::ProviderId) -> Option<ProviderStatus>;
 }
 
 /// A trait to mutate the state of a generic Provider, such as updating their root.
@@ -669,6 +703,70 @@ pub trait MutateProvidersInterface {
     fn update_root(who: Self::ProviderId, new_root: Self::MerkleHash) -> DispatchResult;
 }
 
+/// The status of a registered Provider, maintained by the Providers pallet.
+///
+/// Only [`ProviderStatus::Active`] Providers can take on new work (e.g. new buckets, storage requests
+/// or files). If more than one status applies to a Provider, the first one in this order is reported:
+/// `Insolvent`, `Suspended`, `Exiting`.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    TypeInfo,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
+pub enum ProviderStatus {
+    /// The Provider is in good standing.
+    Active,
+    /// The Provider has a deposit deficit, either waiting for it to be topped up or because it failed
+    /// to do so in time.
+    Insolvent,
+    /// The Provider scheduled its sign off. It keeps its current duties until it signs off.
+    Exiting,
+    /// The Provider was suspended by governance.
+    Suspended,
+}
+
+/// A change in the status of a Provider that makes it unable to take on new work.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    TypeInfo,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
+pub enum ProviderStatusChange {
+    /// The Provider failed to top up its deposit in time and was marked as insolvent.
+    Insolvent,
+    /// The Provider signed off or was deleted, so it is no longer registered.
+    Deregistered,
+}
+
+/// A hook called by the Providers pallet when a Provider becomes insolvent or is deregistered.
+///
+/// This allows other pallets to cancel or reroute the operations that were waiting on such Provider.
+/// Implementations should do as little work as possible when called (e.g. queueing the Provider to
+/// process it later), since they run in the middle of extrinsics and hooks of the Providers pallet.
+pub trait OnProviderStatusChange<ProviderId> {
+    fn on_provider_status_change(provider_id: &ProviderId, status: ProviderStatusChange);
+}
+
+impl<ProviderId> OnProviderStatusChange<ProviderId> for () {
+    fn on_provider_status_change(_provider_id: &ProviderId, _status: ProviderStatusChange) {}
+}
+
 /// A trait to get system-wide metrics, such as the total available capacity of the network and
 /// its total used capacity.
 pub trait SystemMetricsInterface {
@@ -1034,6 +1132,18 @@ pub trait PaymentStreamsInterface {
         user_account: &Self::AccountId,
     ) -> Option<Self::Units>;
 
+    /// Get the rate per tick a User pays for `amount_provided` units through a dynamic-rate payment
+    /// stream, at the current price.
+    fn get_dynamic_rate_per_tick(
+        amount_provided: &Self::Units,
+    ) -> Result<<Self::Balance as fungible::Inspect<Self::AccountId>>::Balance, DispatchError>;
+
+    /// Get the deposit held from a User to open a dynamic-rate payment stream for `amount_provided`
+    /// units, at the current price.
+    fn get_dynamic_rate_payment_stream_deposit(
+        amount_provided: &Self::Units,
+    ) -> Result<<Self::Balance as fungible::Inspect<Self::AccountId>>::Balance, DispatchError>;
+
     /// Check if a user has an active payment stream with a provider.
     fn has_active_payment_stream_with_user(
         provider_id: &Self::ProviderId,
@@ -1053,6 +1163,75 @@ pub trait PaymentStreamsInterface {
     fn current_tick() -> Self::TickNumber;
 }
 
+/// The interface of the Payment Streams pallet to escrow payments for a set of Providers.
+///
+/// An escrow holds upfront, from the User, what it would cost at the current price to pay a target amount
+/// of Providers for providing some units for a fixed amount of ticks. The held funds are released to each
+/// Provider as it proves it keeps providing them, and whatever is not released is refunded to the User.
+///
+/// While a Provider is being paid by an escrow, the escrowed units are not part of the dynamic-rate payment
+/// stream between the User and the Provider. They are added to it once the Provider has been paid its whole share.
+pub trait PaymentEscrowInterface {
+    /// The type which represents a User account identifier.
+    type AccountId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
+    /// The type which represents a Provider identifier.
+    type ProviderId: Parameter
+        + Member
+        + MaybeSerializeDeserialize
+        + Debug
+        + Ord
+        + MaxEncodedLen
+        + Copy;
+    /// The type which identifies an escrow.
+    type EscrowId: Parameter
+        + Member
+        + MaybeSerializeDeserialize
+        + Debug
+        + Ord
+        + MaxEncodedLen
+        + Copy;
+    /// The type of the units that the Provider provides to the User.
+    type Units: NumericalParam;
+    /// The type which represents ticks.
+    type TickNumber: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
+
+    /// Open an escrow to pay `providers_target` Providers for providing `amount_provided` units each,
+    /// for `duration` ticks.
+    fn open_escrow(
+        escrow_id: &Self::EscrowId,
+        user_account: &Self::AccountId,
+        providers_target: u32,
+        amount_provided: &Self::Units,
+        duration: Self::TickNumber,
+    ) -> DispatchResult;
+
+    /// Check if an escrow exists.
+    fn escrow_exists(escrow_id: &Self::EscrowId) -> bool;
+
+    /// Check if an escrow exists and still has funds for another Provider.
+    fn can_add_escrow_provider(escrow_id: &Self::EscrowId) -> bool;
+
+    /// Check if a Provider is being paid by an escrow.
+    fn is_escrow_provider(escrow_id: &Self::EscrowId, provider_id: &Self::ProviderId) -> bool;
+
+    /// Start paying a Provider from an escrow, for its duration from the current tick.
+    fn add_escrow_provider(
+        escrow_id: &Self::EscrowId,
+        provider_id: &Self::ProviderId,
+    ) -> DispatchResult;
+
+    /// Stop paying a Provider from an escrow, paying it what it has proven so far and refunding
+    /// the rest of its share to the User.
+    fn remove_escrow_provider(
+        escrow_id: &Self::EscrowId,
+        provider_id: &Self::ProviderId,
+    ) -> DispatchResult;
+
+    /// Settle the amount of Providers paid by an escrow to the ones currently in it, refunding the
+    /// share of the missing ones to the User. No Provider can be added to the escrow afterwards.
+    fn settle_escrow_replication(escrow_id: &Self::EscrowId) -> DispatchResult;
+}
+
 /// The interface of the Payment Streams pallet that allows for the reading of user's solvency.
 pub trait ReadUserSolvencyInterface {
     /// The type which represents a User account identifier.
@@ -1104,6 +1283,9 @@ pub trait ProofSubmittersInterface {
 
     fn get_accrued_failed_proof_submissions(provider_id: &Self::ProviderId) -> Option<u32>;
 
+    /// Get the number of proof submission deadlines a Provider has missed in a row.
+    fn get_consecutive_missed_proof_deadlines(provider_id: &Self::ProviderId) -> u32;
+
     fn clear_accrued_failed_proof_submissions(provider_id: &Self::ProviderId);
 }
 
@@ -1167,6 +1349,25 @@ pub trait TreasuryCutCalculator {
     ) -> Self::Balance;
 }
 
+/// A trait to read the price of the native token from an on-chain oracle feed.
+///
+/// This is used by the Payment Streams pallet to make the price per giga-unit per tick track a
+/// target price set in a reference currency (usually a fiat currency), so that storage costs remain
+/// stable in that currency.
+pub trait NativeTokenPriceOracle<BlockNumber> {
+    /// Get the price of the smallest unit of the native balance, in the smallest unit of the
+    /// reference currency of the feed, alongside the block at which the feed was last updated.
+    ///
+    /// Returns `None` if the feed has no price available.
+    fn native_token_price() -> Option<(FixedU128, BlockNumber)>;
+}
+
+impl<BlockNumber> NativeTokenPriceOracle<BlockNumber> for () {
+    fn native_token_price() -> Option<(FixedU128, BlockNumber)> {
+        None
+    }
+}
+
 /// The interface for the Commit-Reveal Randomness pallet.
 pub trait CommitRevealRandomnessInterface {
     /// The type which represents a Provider's ID.
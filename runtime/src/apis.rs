@@ -4,6 +4,7 @@ use frame_support::{
     weights::Weight,
 };
 use pallet_aura::Authorities;
+use pallet_file_system::types::{ReplicationTargetType, StorageRequestMetadata};
 use pallet_file_system_runtime_api::*;
 use pallet_payment_streams_runtime_api::*;
 use pallet_proofs_dealer::types::{
@@ -11,12 +12,13 @@ use pallet_proofs_dealer::types::{
 };
 use pallet_proofs_dealer_runtime_api::*;
 use pallet_storage_providers::types::{
-    BackupStorageProvider, BackupStorageProviderId, BucketId, MainStorageProviderId,
-    Multiaddresses, ProviderIdFor, StorageDataUnit, StorageProviderId, ValuePropositionWithId,
+    BackupStorageProvider, BackupStorageProviderId, BucketId, CapacityUtilization,
+    MainStorageProviderId, Multiaddresses, ProviderIdFor, ReputationWeightType, StorageDataUnit,
+    StorageProviderId, ValuePropIdFor, ValuePropositionVersion, ValuePropositionWithId,
 };
 use pallet_storage_providers_runtime_api::*;
 use shp_file_metadata::ChunkId;
-use shp_traits::TrieRemoveMutation;
+use shp_traits::{ProviderStatus, TrieRemoveMutation};
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata, H256};
@@ -329,7 +331,7 @@ impl_runtime_apis! {
         }
     }
 
-    impl pallet_file_system_runtime_api::FileSystemApi<Block, BackupStorageProviderId<Runtime>, MainStorageProviderId<Runtime>, H256, BlockNumber, ChunkId> for Runtime {
+    impl pallet_file_system_runtime_api::FileSystemApi<Block, BackupStorageProviderId<Runtime>, MainStorageProviderId<Runtime>, H256, BlockNumber, ChunkId, BucketId<Runtime>, ReplicationTargetType<Runtime>, ProviderIdFor<Runtime>, StorageRequestMetadata<Runtime>, AccountId, Balance> for Runtime {
         fn is_storage_request_open_to_volunteers(file_key: H256) -> Result<bool, IsStorageRequestOpenToVolunteersError> {
             FileSystem::is_storage_request_open_to_volunteers(file_key)
         }
@@ -345,6 +347,50 @@ impl_runtime_apis! {
         fn query_msp_confirm_chunks_to_prove_for_file(msp_id: MainStorageProviderId<Runtime>, file_key: H256) -> Result<Vec<ChunkId>, QueryMspConfirmChunksToProveForFileError> {
             FileSystem::query_msp_confirm_chunks_to_prove_for_file(msp_id, file_key)
         }
+
+        fn query_bucket_default_replication_target(bucket_id: BucketId<Runtime>) -> ReplicationTargetType<Runtime> {
+            FileSystem::query_bucket_default_replication_target(bucket_id)
+        }
+
+        fn is_msp_eligible(msp_id: MainStorageProviderId<Runtime>) -> bool {
+            FileSystem::is_msp_eligible(&msp_id)
+        }
+
+        fn query_storage_request_replication_status(file_key: H256) -> Result<StorageRequestReplicationStatus<BlockNumber, ReplicationTargetType<Runtime>>, QueryStorageRequestReplicationStatusError> {
+            FileSystem::query_storage_request_replication_status(file_key)
+        }
+
+        fn pending_storage_requests_for_provider(provider_id: ProviderIdFor<Runtime>, start_after: Option<H256>, limit: u32) -> Vec<OpenStorageRequest<H256, StorageRequestMetadata<Runtime>, BackupStorageProviderId<Runtime>>> {
+            FileSystem::pending_storage_requests_for_provider(provider_id, start_after, limit)
+        }
+
+        fn storage_requests_by_bucket(bucket_id: BucketId<Runtime>, start_after: Option<H256>, limit: u32) -> Vec<OpenStorageRequest<H256, StorageRequestMetadata<Runtime>, BackupStorageProviderId<Runtime>>> {
+            FileSystem::storage_requests_by_bucket(bucket_id, start_after, limit)
+        }
+
+        fn query_notification_preferences(account: AccountId) -> Option<Vec<u8>> {
+            FileSystem::query_notification_preferences(&account)
+        }
+
+        fn query_file_access_list(file_key: H256) -> Vec<AccountId> {
+            FileSystem::query_file_access_list(&file_key)
+        }
+
+        fn has_file_read_access(bucket_id: BucketId<Runtime>, file_key: H256, account: AccountId) -> bool {
+            FileSystem::has_file_read_access(&bucket_id, &file_key, &account)
+        }
+
+        fn query_billable_size(size: StorageDataUnit<Runtime>) -> StorageDataUnit<Runtime> {
+            FileSystem::query_billable_size(size)
+        }
+
+        fn query_storage_request(file_key: H256) -> Option<OpenStorageRequest<H256, StorageRequestMetadata<Runtime>, BackupStorageProviderId<Runtime>>> {
+            FileSystem::query_storage_request(file_key)
+        }
+
+        fn estimate_storage_request_cost(size: StorageDataUnit<Runtime>, replication_target: ReplicationTargetType<Runtime>, msp_id: Option<MainStorageProviderId<Runtime>>) -> Result<StorageRequestCostEstimate<Balance>, EstimateStorageRequestCostError> {
+            FileSystem::estimate_storage_request_cost(size, replication_target, msp_id)
+        }
     }
 
     impl pallet_payment_streams_runtime_api::PaymentStreamsApi<Block, ProviderIdFor<Runtime>, Balance, AccountId> for Runtime {
@@ -357,6 +403,9 @@ impl_runtime_apis! {
         fn get_providers_with_payment_streams_with_user(user_account: &AccountId) -> Vec<ProviderIdFor<Runtime>> {
             PaymentStreams::get_providers_with_payment_streams_with_user(user_account)
         }
+        fn get_current_price_per_giga_unit_per_tick() -> Balance {
+            PaymentStreams::get_current_price_per_giga_unit_per_tick()
+        }
     }
 
     impl pallet_proofs_dealer_runtime_api::ProofsDealerApi<Block, ProofsDealerProviderIdFor<Runtime>, BlockNumber, KeyFor<Runtime>, RandomnessOutputFor<Runtime>, TrieRemoveMutation> for Runtime {
@@ -390,6 +439,10 @@ impl_runtime_apis! {
             ProofsDealer::get_checkpoint_challenge_period()
         }
 
+        fn get_max_proof_size() -> u32 {
+            ProofsDealer::get_max_proof_size()
+        }
+
         fn get_challenges_from_seed(seed: &RandomnessOutputFor<Runtime>, provider_id: &ProofsDealerProviderIdFor<Runtime>, count: u32) -> Vec<KeyFor<Runtime>> {
             ProofsDealer::get_challenges_from_seed(seed, provider_id, count)
         }
@@ -405,10 +458,18 @@ impl_runtime_apis! {
         fn get_next_deadline_tick(provider_id: &ProofsDealerProviderIdFor<Runtime>) -> Result<BlockNumber, GetNextDeadlineTickError> {
             ProofsDealer::get_next_deadline_tick(provider_id)
         }
+
+        fn get_ticks_catch_up_backlog() -> BlockNumber {
+            ProofsDealer::get_ticks_catch_up_backlog()
+        }
+
+        fn provider_challenge_schedule(provider_id: &ProofsDealerProviderIdFor<Runtime>) -> Result<ProviderChallengeSchedule<BlockNumber, RandomnessOutputFor<Runtime>>, GetProviderChallengeScheduleError> {
+            ProofsDealer::provider_challenge_schedule(provider_id)
+        }
     }
 
 
-    impl pallet_storage_providers_runtime_api::StorageProvidersApi<Block, BlockNumber, BackupStorageProviderId<Runtime>, BackupStorageProvider<Runtime>, AccountId, ProviderIdFor<Runtime>, StorageProviderId<Runtime>, StorageDataUnit<Runtime>, Balance, BucketId<Runtime>, Multiaddresses<Runtime>, ValuePropositionWithId<Runtime>> for Runtime {
+    impl pallet_storage_providers_runtime_api::StorageProvidersApi<Block, BlockNumber, BackupStorageProviderId<Runtime>, BackupStorageProvider<Runtime>, AccountId, ProviderIdFor<Runtime>, StorageProviderId<Runtime>, StorageDataUnit<Runtime>, Balance, BucketId<Runtime>, Multiaddresses<Runtime>, ValuePropIdFor<Runtime>, ValuePropositionWithId<Runtime>, ValuePropositionVersion<Runtime>, CapacityUtilization<Runtime>, ProviderStatus, ReputationWeightType<Runtime>> for Runtime {
         fn get_bsp_info(bsp_id: &BackupStorageProviderId<Runtime>) -> Result<BackupStorageProvider<Runtime>, GetBspInfoError> {
             Providers::get_bsp_info(bsp_id)
         }
@@ -421,6 +482,10 @@ impl_runtime_apis! {
             Providers::query_msp_id_of_bucket_id(bucket_id)
         }
 
+        fn query_bucket_encryption_metadata(bucket_id: &BucketId<Runtime>) -> Result<Option<Vec<u8>>, QueryBucketEncryptionMetadataError> {
+            Providers::query_bucket_encryption_metadata(bucket_id)
+        }
+
         fn query_provider_multiaddresses(provider_id: &ProviderIdFor<Runtime>) -> Result<Multiaddresses<Runtime>, QueryProviderMultiaddressesError> {
             Providers::query_provider_multiaddresses(provider_id)
         }
@@ -449,6 +514,14 @@ impl_runtime_apis! {
             Providers::query_value_propositions_for_msp(who)
         }
 
+        fn query_value_proposition_version(msp_id: &ProviderIdFor<Runtime>, value_prop_id: &ValuePropIdFor<Runtime>) -> Result<ValuePropositionVersion<Runtime>, QueryValuePropositionVersionError> {
+            Providers::query_value_proposition_version(msp_id, value_prop_id)
+        }
+
+        fn query_latest_value_proposition(msp_id: &ProviderIdFor<Runtime>, value_prop_id: &ValuePropIdFor<Runtime>) -> Result<ValuePropositionWithId<Runtime>, QueryValuePropositionVersionError> {
+            Providers::query_latest_value_proposition(msp_id, value_prop_id)
+        }
+
         fn get_bsp_stake(bsp_id: &BackupStorageProviderId<Runtime>) -> Result<Balance, GetStakeError> {
             Providers::get_bsp_stake(bsp_id)
         }
@@ -456,5 +529,21 @@ impl_runtime_apis! {
         fn can_delete_provider(provider_id: &ProviderIdFor<Runtime>) -> bool {
             Providers::can_delete_provider(provider_id)
         }
+
+        fn capacity_utilization() -> CapacityUtilization<Runtime> {
+            Providers::capacity_utilization()
+        }
+
+        fn query_capacity_challenge(who: &AccountId) -> Result<Vec<u64>, QueryCapacityChallengeError> {
+            Providers::query_capacity_challenge(who)
+        }
+
+        fn get_provider_status(provider_id: &ProviderIdFor<Runtime>) -> Option<ProviderStatus> {
+            Providers::get_provider_status(provider_id)
+        }
+
+        fn query_bsp_reputation_weight(bsp_id: &BackupStorageProviderId<Runtime>) -> Result<ReputationWeightType<Runtime>, QueryBspReputationWeightError> {
+            Providers::query_bsp_reputation_weight(bsp_id)
+        }
     }
 }
@@ -476,7 +476,12 @@ parameter_types! {
     pub const SpMinDeposit: Balance = 100 * UNIT;
     pub const BucketDeposit: Balance = 100 * UNIT;
     pub const BspSignUpLockPeriod: BlockNumber = 90 * DAYS; // ~3 months
+    pub const CapacityUtilizationSamplingPeriod: BlockNumber = HOURS;
+    pub const MaxCapacityUtilizationSamples: u32 = 7 * 24; // ~1 week of hourly samples
     pub const MaxBlocksForRandomness: BlockNumber = prod_or_fast!(2 * HOURS, 2 * MINUTES);
+    pub const CapacityAttestationChunkSize: StorageDataUnit = shp_constants::GIGAUNIT as StorageDataUnit; // 1 GiB per plot leaf
+    pub const CapacityDecreaseGracePeriod: BlockNumber = prod_or_fast!(7 * DAYS, 10 * MINUTES);
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
     // TODO: If the next line is uncommented (which should be eventually, replacing the line above), compilation breaks (most likely because of mismatched dependency issues)
     // pub const MaxBlocksForRandomness: BlockNumber = prod_or_fast!(2 * runtime_constants::time::EPOCH_DURATION_IN_SLOTS, 2 * MINUTES);
 }
@@ -492,6 +497,7 @@ impl pallet_storage_providers::Config for Runtime {
         { shp_constants::FILE_CHUNK_SIZE },
         { shp_constants::FILE_SIZE_TO_CHALLENGES },
     >;
+    type OnProviderStatusChange = FileSystem;
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type StorageDataUnit = StorageDataUnit;
@@ -517,11 +523,18 @@ impl pallet_storage_providers::Config for Runtime {
     type MaxProtocols = ConstU32<100>;
     type BucketDeposit = BucketDeposit;
     type BucketNameLimit = ConstU32<100>;
+    type EncryptionMetadataLimit = ConstU32<1024>;
     type MaxBlocksForRandomness = MaxBlocksForRandomness;
     type MinBlocksBetweenCapacityChanges = ConstU32<10>;
     type DefaultMerkleRoot = DefaultMerkleRoot<StorageProofsMerkleTrieLayout>;
     type SlashAmountPerMaxFileSize =
         runtime_params::dynamic_params::runtime_config::SlashAmountPerMaxFileSize;
+    type SlashReferenceDataSize =
+        runtime_params::dynamic_params::runtime_config::SlashReferenceDataSize;
+    type SlashEscalationPerMissedDeadline =
+        runtime_params::dynamic_params::runtime_config::SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier =
+        runtime_params::dynamic_params::runtime_config::MaxSlashEscalationMultiplier;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = BspSignUpLockPeriod;
     type MaxCommitmentSize = ConstU32<1000>;
@@ -529,6 +542,19 @@ impl pallet_storage_providers::Config for Runtime {
         runtime_params::dynamic_params::runtime_config::ZeroSizeBucketFixedRate;
     type ProviderTopUpTtl = runtime_params::dynamic_params::runtime_config::ProviderTopUpTtl;
     type MaxExpiredItemsInBlock = ConstU32<100>;
+    type MaxCapacityUtilizationSamples = MaxCapacityUtilizationSamples;
+    type CapacityUtilizationSamplingPeriod = CapacityUtilizationSamplingPeriod;
+    type RequireCapacityAttestation =
+        runtime_params::dynamic_params::runtime_config::RequireCapacityAttestation;
+    type CapacityAttestationChunkSize = CapacityAttestationChunkSize;
+    type CapacityAttestationSpotChecks = ConstU32<32>;
+    // The challenge can only be answered once the randomness is valid, so give the same margin as for sign ups.
+    type CapacityAttestationWindow = MaxBlocksForRandomness;
+    type CapacityDecreaseGracePeriod = CapacityDecreaseGracePeriod;
+    type ReputationGrowthStreak = ConstU32<30>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<100>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelpers = ProvidersBenchmarkHelpers;
 }
@@ -562,11 +588,18 @@ impl pallet_storage_providers::benchmarking::BenchmarkHelpers<Runtime>
 {
     type ProviderId = <Runtime as pallet_storage_providers::Config>::ProviderId;
     fn set_accrued_failed_proofs(provider_id: Self::ProviderId, value: u32) {
-        pallet_proofs_dealer::SlashableProviders::<Runtime>::insert(provider_id, value);
+        pallet_proofs_dealer::SlashableProviders::<Runtime>::insert(
+            provider_id,
+            pallet_proofs_dealer::types::SlashableProviderRecord {
+                accrued_failed_proof_submissions: value,
+            },
+        );
     }
 
     fn get_accrued_failed_proofs(provider_id: Self::ProviderId) -> u32 {
-        pallet_proofs_dealer::SlashableProviders::<Runtime>::get(provider_id).unwrap_or(0)
+        pallet_proofs_dealer::SlashableProviders::<Runtime>::get(provider_id)
+            .map(|record| record.accrued_failed_proof_submissions)
+            .unwrap_or(0)
     }
 }
 /****** ****** ****** ******/
@@ -575,6 +608,9 @@ impl pallet_storage_providers::benchmarking::BenchmarkHelpers<Runtime>
 parameter_types! {
     pub const PaymentStreamHoldReason: RuntimeHoldReason = RuntimeHoldReason::PaymentStreams(pallet_payment_streams::HoldReason::PaymentStreamDeposit);
     pub const UserWithoutFundsCooldown: BlockNumber = 100;
+    pub const MaxOraclePriceAge: BlockNumber = HOURS;
+    pub const OraclePriceAdjustmentPeriod: BlockNumber = DAYS;
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(5);
 }
 
 impl pallet_payment_streams::Config for Runtime {
@@ -592,6 +628,10 @@ impl pallet_payment_streams::Config for Runtime {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = (); // No oracle feed yet, so the price per giga-unit per tick can't track a fiat target
+    type MaxOraclePriceAge = MaxOraclePriceAge;
+    type OraclePriceAdjustmentPeriod = OraclePriceAdjustmentPeriod;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 
 // Converter from the BlockNumber type to the Balance type for math
@@ -674,6 +714,17 @@ impl pallet_proofs_dealer::Config for Runtime {
     type BlockFullnessHeadroom = BlockFullnessHeadroom;
     type MinNotFullBlocksRatio = MinNotFullBlocksRatio;
     type MaxSlashableProvidersPerTick = MaxSlashableProvidersPerTick;
+    type MaxCatchUpTicksPerBlock = ConstU32<100>;
+    type MissedDeadlinesAllowance =
+        runtime_params::dynamic_params::runtime_config::MissedDeadlinesAllowance;
+    type MissedDeadlineWarningWindow =
+        runtime_params::dynamic_params::runtime_config::MissedDeadlineWarningWindow;
+    type MaxProofSize = ConstU32<{ 1024 * 1024 }>;
+    type MaxProofsPerBatch = ConstU32<10>;
+    type OffchainSignature = Signature;
+    type OffchainPublic = <Signature as Verify>::Signer;
+    // Proofs are time-sensitive, so they take precedence over any other transaction.
+    type UnsignedProofPriority = ConstU64<{ u64::MAX }>;
 }
 
 // Converter from the Balance type to the BlockNumber type for math.
@@ -799,6 +850,10 @@ type ThresholdType = u32;
 parameter_types! {
     pub const MinWaitForStopStoring: BlockNumber = 10;
     pub const StorageRequestCreationDeposit: Balance = 10;
+    pub const MspOnlyStorageRequestCreationDeposit: Balance = 5;
+    pub const DeletionListItemDeposit: Balance = 1;
+    pub const StorageRequestRevocationCompensation: Perbill = Perbill::from_percent(50);
+    pub const MaxFileDeletionFee: Balance = UNIT;
     pub const FileSystemHoldReason: RuntimeHoldReason = RuntimeHoldReason::FileSystem(pallet_file_system::HoldReason::StorageRequestCreationHold);
 }
 
@@ -835,14 +890,36 @@ impl pallet_file_system::Config for Runtime {
     type MaxDataServerMultiAddresses = ConstU32<10>;
     type MaxExpiredItemsInBlock = ConstU32<100>;
     type StorageRequestTtl = ConstU32<40>;
+    type StorageRequestExpirationNotificationLeadTime = ConstU32<10>;
+    type MaxStorageRequestExpirationNotificationsPerBlock = ConstU32<100>;
     type PendingFileDeletionRequestTtl = ConstU32<40u32>;
     type MoveBucketRequestTtl = ConstU32<40u32>;
     type MaxUserPendingDeletionRequests = ConstU32<10u32>;
     type MaxUserPendingMoveBucketRequests = ConstU32<10u32>;
     type MinWaitForStopStoring = MinWaitForStopStoring;
     type StorageRequestCreationDeposit = StorageRequestCreationDeposit;
+    type MspOnlyStorageRequestCreationDeposit = MspOnlyStorageRequestCreationDeposit;
+    type StorageRequestRevocationCompensation = StorageRequestRevocationCompensation;
+    type StorageRequestRevocationCompensationPeriod = ConstU32<20>;
+    type FileDeletionFee = runtime_params::dynamic_params::runtime_config::FileDeletionFee;
+    type MaxFileDeletionFee = MaxFileDeletionFee;
     type DefaultReplicationTarget =
         runtime_params::dynamic_params::runtime_config::DefaultReplicationTarget;
+    type PriorityChallengesMeteringPeriod = ConstU32<600>;
+    type PriorityChallengesSoftLimitPerIssuer = ConstU32<100>;
+    type MaxDeferredPriorityChallengesPerIssuer = ConstU32<1000>;
+    type MaxDeferredPriorityChallengeIssuers = ConstU32<1000>;
+    type MaxDeferredPriorityChallengesQueuedPerBlock = ConstU32<10>;
+    type MaxProviderStatusChangeItemsPerBlock = ConstU32<100>;
+    type MaxDepositTransferRetries = ConstU32<100>;
+    type MaxDepositTransferRetryAttempts = ConstU32<8>;
+    type DepositTransferRetryBaseDelay = ConstU32<10>;
+    type MaxNotificationPreferencesLength = ConstU32<256>;
+    type MaxFileAccessListSize = ConstU32<100>;
+    type MinBillableFileSize = runtime_params::dynamic_params::runtime_config::MinBillableFileSize;
+    type MaxDeletionListProofDepth = ConstU32<32>;
+    type DeletionListItemDeposit = DeletionListItemDeposit;
+    type MaxAccessGrantsPerTarget = ConstU32<100>;
 }
 
 impl MostlyStablePriceIndexUpdaterConfig for Runtime {
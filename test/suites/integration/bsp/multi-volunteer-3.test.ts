@@ -68,7 +68,8 @@ describeBspNet("BSPNet: Mulitple BSP Volunteering - 3", ({ before, it, createUse
           fileMetadata.file_size,
           ShConsts.DUMMY_MSP_ID,
           [ShConsts.NODE_INFOS.user.expectedPeerId],
-          null
+          null,
+          false
         )
         .signAsync(signer);
 
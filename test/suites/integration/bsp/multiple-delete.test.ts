@@ -55,7 +55,8 @@ describeBspNet("Single BSP Volunteering", ({ before, createBspApi, it, createUse
           file_size,
           userApi.shConsts.DUMMY_MSP_ID,
           [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-          1
+          1,
+          false
         )
       );
     }
@@ -191,7 +192,8 @@ describeBspNet("Single BSP Volunteering", ({ before, createBspApi, it, createUse
             file_size,
             userApi.shConsts.DUMMY_MSP_ID,
             [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-            1
+            1,
+            false
           )
         );
       }
@@ -80,7 +80,8 @@ describeBspNet("Single BSP Volunteering", ({ before, createBspApi, it, createUse
         file_size,
         userApi.shConsts.DUMMY_MSP_ID,
         [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -202,7 +203,8 @@ describeBspNet("Single BSP multi-volunteers", ({ before, createBspApi, createUse
           file_size,
           userApi.shConsts.DUMMY_MSP_ID,
           [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-          null
+          null,
+          false
         )
       );
     }
@@ -80,7 +80,8 @@ describeMspNet("Single MSP collecting debt", ({ before, createMsp1Api, it, creat
           file_size,
           userApi.shConsts.DUMMY_MSP_ID,
           [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-          null
+          null,
+          false
         )
       );
     }
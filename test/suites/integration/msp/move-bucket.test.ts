@@ -121,7 +121,8 @@ describeMspNet(
             file_size,
             userApi.shConsts.DUMMY_MSP_ID,
             [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-            2
+            2,
+            false
           )
         );
       }
@@ -42,7 +42,8 @@ describeMspNet(
           userApi.shConsts.TEST_ARTEFACTS[source].size,
           userApi.shConsts.DUMMY_MSP_ID,
           [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-          null
+          null,
+          false
         ),
         shUser
       );
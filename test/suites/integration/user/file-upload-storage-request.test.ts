@@ -28,7 +28,8 @@ describeBspNet("User: Issue Storage Requests", ({ before, createUserApi, it }) =
         userApi.shConsts.TEST_ARTEFACTS["res/empty-file"].size,
         userApi.shConsts.DUMMY_MSP_ID,
         [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -83,7 +84,8 @@ describeBspNet("User: Issue Storage Requests", ({ before, createUserApi, it }) =
         userApi.shConsts.TEST_ARTEFACTS["res/half-chunk-file"].size,
         userApi.shConsts.DUMMY_MSP_ID,
         [],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -126,7 +128,8 @@ describeBspNet("User: Issue Storage Requests", ({ before, createUserApi, it }) =
         userApi.shConsts.TEST_ARTEFACTS["res/empty-file"].size,
         userApi.shConsts.DUMMY_MSP_ID,
         [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -157,7 +160,8 @@ describeBspNet("User: Issue Storage Requests", ({ before, createUserApi, it }) =
         userApi.shConsts.TEST_ARTEFACTS["res/adolphus.jpg"].size,
         INVALID_MSP_ID,
         [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -185,7 +189,8 @@ describeBspNet("User: Issue Storage Requests", ({ before, createUserApi, it }) =
         userApi.shConsts.TEST_ARTEFACTS["res/smile.jpg"].size,
         userApi.shConsts.DUMMY_MSP_ID,
         [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -219,7 +224,8 @@ describeBspNet("User: Issue Storage Requests", ({ before, createUserApi, it }) =
         userApi.shConsts.TEST_ARTEFACTS["res/smile.jpg"].size,
         userApi.shConsts.DUMMY_MSP_ID,
         [userApi.shConsts.NODE_INFOS.user.expectedPeerId],
-        null
+        null,
+        false
       ),
       shUser
     );
@@ -36,7 +36,8 @@ export const sendNewStorageRequest = async (
       fileMetadata.file_size,
       mspId ?? ShConsts.DUMMY_MSP_ID,
       [ShConsts.NODE_INFOS.user.expectedPeerId],
-      null
+      null,
+      false
     ),
     issueOwner
   );
@@ -119,7 +120,8 @@ export const createBucketAndSendNewStorageRequest = async (
       fileMetadata.file_size,
       mspId ?? null,
       [ShConsts.NODE_INFOS.user.expectedPeerId],
-      replicationTarget ?? null
+      replicationTarget ?? null,
+      false
     ),
     owner ?? shUser
   );
@@ -169,7 +171,7 @@ export const createBucket = async (
 
   const createBucketResult = await sealBlock(
     api,
-    api.tx.fileSystem.createBucket(mspId, bucketName, false, localValuePropId),
+    api.tx.fileSystem.createBucket(mspId, bucketName, false, localValuePropId, null),
     owner ?? undefined
   );
   const { event } = assertEventPresent(api, "fileSystem", "NewBucket", createBucketResult.events);
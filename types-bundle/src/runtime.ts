@@ -55,6 +55,32 @@ const FILE_SYSTEM_V1: Record<string, DefinitionCall> = {
   }
 };
 
+const FILE_SYSTEM_V2: Record<string, DefinitionCall> = {
+  ...FILE_SYSTEM_V1,
+  query_bucket_default_replication_target: {
+    description:
+      "Get the replication target used for storage requests in a bucket that don't specify one.",
+    params: [
+      {
+        name: "bucketId",
+        type: "H256"
+      }
+    ],
+    type: "u32"
+  },
+  query_storage_request_replication_status: {
+    description:
+      "Get the replication progress of a storage request and the parameters to estimate how long until it reaches its replication target.",
+    params: [
+      {
+        name: "fileKey",
+        type: "H256"
+      }
+    ],
+    type: "Result<StorageRequestReplicationStatus, QueryStorageRequestReplicationStatusError>"
+  }
+};
+
 const PROOFS_DEALER_V1: Record<string, DefinitionCall> = {
   get_last_tick_provider_submitted_proof: {
     description: "Get the last tick for which the submitter submitted a proof.",
@@ -165,6 +191,21 @@ const PROOFS_DEALER_V1: Record<string, DefinitionCall> = {
   }
 };
 
+const PROOFS_DEALER_V2: Record<string, DefinitionCall> = {
+  ...PROOFS_DEALER_V1,
+  provider_challenge_schedule: {
+    description:
+      "Get the challenge schedule of a Provider, with the seeds of the ticks it has to submit proofs for.",
+    params: [
+      {
+        name: "providerId",
+        type: "ProviderId"
+      }
+    ],
+    type: "Result<ProviderChallengeSchedule, GetProviderChallengeScheduleError>"
+  }
+};
+
 const STORAGE_PROVIDERS_V1: Record<string, DefinitionCall> = {
   get_bsp_info: {
     description: "Get the BSP info for a given BSP ID.",
@@ -273,6 +314,64 @@ const STORAGE_PROVIDERS_V1: Record<string, DefinitionCall> = {
   }
 };
 
+const STORAGE_PROVIDERS_V2: Record<string, DefinitionCall> = {
+  ...STORAGE_PROVIDERS_V1,
+  query_bucket_encryption_metadata: {
+    description: "Query the metadata describing how the contents of a bucket are encrypted.",
+    params: [
+      {
+        name: "bucketId",
+        type: "H256"
+      }
+    ],
+    type: "Result<Option<Vec<u8>>, QueryBucketEncryptionMetadataError>"
+  },
+  query_value_proposition_version: {
+    description: "Query the version of a value proposition of a MSP.",
+    params: [
+      {
+        name: "mspId",
+        type: "MainStorageProviderId"
+      },
+      {
+        name: "valuePropId",
+        type: "ValuePropId"
+      }
+    ],
+    type: "Result<ValuePropositionVersion, QueryValuePropositionVersionError>"
+  },
+  query_latest_value_proposition: {
+    description: "Query the latest version of a value proposition of a MSP.",
+    params: [
+      {
+        name: "mspId",
+        type: "MainStorageProviderId"
+      },
+      {
+        name: "valuePropId",
+        type: "ValuePropId"
+      }
+    ],
+    type: "Result<ValuePropositionWithId, QueryValuePropositionVersionError>"
+  },
+  capacity_utilization: {
+    description:
+      "Get the global BSP capacity utilization, its distribution per decile, the current price and the latest samples.",
+    params: [],
+    type: "CapacityUtilization"
+  },
+  query_bsp_reputation_weight: {
+    description: "Get the reputation weight of a BSP, used to compute its threshold to volunteer.",
+    params: [
+      {
+        name: "bspId",
+        type: "BackupStorageProviderId"
+      }
+    ],
+    type: "Result<u32, QueryBspReputationWeightError>"
+  }
+};
+
 const PAYMENT_STREAMS_V1: Record<string, DefinitionCall> = {
   get_users_with_debt_over_threshold: {
     description: "Get the users that have a debt to the provider greater than the threshold.",
@@ -310,26 +409,51 @@ const PAYMENT_STREAMS_V1: Record<string, DefinitionCall> = {
   }
 };
 
+const PAYMENT_STREAMS_V2: Record<string, DefinitionCall> = {
+  ...PAYMENT_STREAMS_V1,
+  get_current_price_per_giga_unit_per_tick: {
+    description: "Get the current price per giga-unit of data stored per tick.",
+    params: [],
+    type: "Balance"
+  }
+};
+
 export const runtime: DefinitionsCall = {
   FileSystemApi: [
+    {
+      methods: FILE_SYSTEM_V2,
+      version: 2
+    },
     {
       methods: FILE_SYSTEM_V1,
       version: 1
     }
   ],
   ProofsDealerApi: [
+    {
+      methods: PROOFS_DEALER_V2,
+      version: 2
+    },
     {
       methods: PROOFS_DEALER_V1,
       version: 1
     }
   ],
   StorageProvidersApi: [
+    {
+      methods: STORAGE_PROVIDERS_V2,
+      version: 2
+    },
     {
       methods: STORAGE_PROVIDERS_V1,
       version: 1
     }
   ],
   PaymentStreamsApi: [
+    {
+      methods: PAYMENT_STREAMS_V2,
+      version: 2
+    },
     {
       methods: PAYMENT_STREAMS_V1,
       version: 1
@@ -57,6 +57,10 @@ export const ALL_TYPES: RegistryTypes = {
     id: "ValuePropId",
     value_prop: "ValueProposition"
   },
+  ValuePropositionVersion: {
+    lineage: "ValuePropId",
+    version: "u32"
+  },
   BackupStorageProvider: {
     capacity: "StorageData",
     data_used: "StorageData",
@@ -66,6 +70,19 @@ export const ALL_TYPES: RegistryTypes = {
     owner_account: "AccountId",
     payment_account: "AccountId"
   },
+  CapacityUtilizationSample: {
+    tick: "BlockNumber",
+    total_capacity: "StorageDataUnit",
+    used_capacity: "StorageDataUnit",
+    price_per_giga_unit_of_data_per_tick: "Balance"
+  },
+  CapacityUtilization: {
+    total_capacity: "StorageDataUnit",
+    used_capacity: "StorageDataUnit",
+    bsps_per_utilization_decile: "[u32; 10]",
+    price_per_giga_unit_of_data_per_tick: "Balance",
+    samples: "Vec<CapacityUtilizationSample>"
+  },
   StorageProviderId: {
     _enum: {
       BackupStorageProvider: "BackupStorageProviderId",
@@ -113,6 +130,23 @@ export const ALL_TYPES: RegistryTypes = {
       InternalApiError: null
     }
   },
+  GetProviderChallengeScheduleError: {
+    _enum: {
+      ProviderNotRegistered: null,
+      ProviderNotInitialised: null,
+      ArithmeticOverflow: null,
+      InternalApiError: null
+    }
+  },
+  ProviderChallengeSchedule: {
+    last_tick_proven: "BlockNumber",
+    next_tick_to_submit_proof_for: "BlockNumber",
+    next_deadline_tick: "BlockNumber",
+    challenge_period: "BlockNumber",
+    current_tick: "BlockNumber",
+    last_checkpoint_tick: "BlockNumber",
+    seeds: "Vec<(BlockNumber, RandomnessOutput)>"
+  },
   IsStorageRequestOpenToVolunteersError: {
     _enum: {
       StorageRequestNotFound: null,
@@ -142,6 +176,32 @@ export const ALL_TYPES: RegistryTypes = {
       InternalError: null
     }
   },
+  QueryStorageRequestReplicationStatusError: {
+    _enum: {
+      StorageRequestNotFound: null,
+      InternalError: null
+    }
+  },
+  StorageRequestReplicationStatus: {
+    requested_at: "BlockNumber",
+    current_tick: "BlockNumber",
+    bsps_required: "u32",
+    bsps_confirmed: "u32",
+    bsps_volunteered: "u32",
+    number_of_bsps: "u32",
+    max_replication_target: "u32",
+    tick_range_to_maximum_threshold: "BlockNumber"
+  },
+  QueryValuePropositionVersionError: {
+    _enum: {
+      ValuePropositionNotFound: null
+    }
+  },
+  QueryBspReputationWeightError: {
+    _enum: {
+      BspNotRegistered: null
+    }
+  },
   QueryProviderMultiaddressesError: {
     _enum: {
       ProviderNotRegistered: null,
@@ -186,5 +246,10 @@ export const ALL_TYPES: RegistryTypes = {
       BucketNotFound: null,
       InternalApiError: null
     }
+  },
+  QueryBucketEncryptionMetadataError: {
+    _enum: {
+      BucketNotFound: null
+    }
   }
 };
@@ -480,6 +480,8 @@ parameter_types! {
     pub const MinBlocksBetweenCapacityChanges: u32 = 10;
     pub const SlashAmountPerChunkOfStorageData: Balance = 20 * UNIT;
     pub const BspSignUpLockPeriod: BlockNumber = 50;
+    pub const CapacityDecreaseGracePeriod: BlockNumber = 20;
+    pub const ReputationDecayOnSlash: Perbill = Perbill::from_percent(50);
 }
 
 pub type HasherOutT<T> = <<T as TrieLayout>::Hash as Hasher>::Out;
@@ -511,11 +513,18 @@ impl pallet_storage_providers::benchmarking::BenchmarkHelpers<Runtime>
 {
     type ProviderId = <Runtime as pallet_storage_providers::Config>::ProviderId;
     fn set_accrued_failed_proofs(provider_id: Self::ProviderId, value: u32) {
-        pallet_proofs_dealer::SlashableProviders::<Runtime>::insert(provider_id, value);
+        pallet_proofs_dealer::SlashableProviders::<Runtime>::insert(
+            provider_id,
+            pallet_proofs_dealer::types::SlashableProviderRecord {
+                accrued_failed_proof_submissions: value,
+            },
+        );
     }
 
     fn get_accrued_failed_proofs(provider_id: Self::ProviderId) -> u32 {
-        pallet_proofs_dealer::SlashableProviders::<Runtime>::get(provider_id).unwrap_or(0)
+        pallet_proofs_dealer::SlashableProviders::<Runtime>::get(provider_id)
+            .map(|record| record.accrued_failed_proof_submissions)
+            .unwrap_or(0)
     }
 }
 
@@ -530,6 +539,7 @@ impl pallet_storage_providers::Config for Runtime {
         { shp_constants::FILE_CHUNK_SIZE },
         { shp_constants::FILE_SIZE_TO_CHALLENGES },
     >;
+    type OnProviderStatusChange = FileSystem;
     type NativeBalance = Balances;
     type RuntimeHoldReason = RuntimeHoldReason;
     type StorageDataUnit = StorageDataUnit;
@@ -555,17 +565,36 @@ impl pallet_storage_providers::Config for Runtime {
     type MaxProtocols = MaxProtocols;
     type BucketDeposit = BucketDeposit;
     type BucketNameLimit = BucketNameLimit;
+    type EncryptionMetadataLimit = ConstU32<1024>;
     type MaxBlocksForRandomness = MaxBlocksForRandomness;
     type MinBlocksBetweenCapacityChanges = MinBlocksBetweenCapacityChanges;
     type DefaultMerkleRoot = DefaultMerkleRoot<StorageProofsMerkleTrieLayout>;
     type SlashAmountPerMaxFileSize =
         runtime_params::dynamic_params::runtime_config::SlashAmountPerMaxFileSize;
+    type SlashReferenceDataSize =
+        runtime_params::dynamic_params::runtime_config::SlashReferenceDataSize;
+    type SlashEscalationPerMissedDeadline =
+        runtime_params::dynamic_params::runtime_config::SlashEscalationPerMissedDeadline;
+    type MaxSlashEscalationMultiplier =
+        runtime_params::dynamic_params::runtime_config::MaxSlashEscalationMultiplier;
     type StartingReputationWeight = ConstU32<1>;
     type BspSignUpLockPeriod = BspSignUpLockPeriod;
     type MaxCommitmentSize = ConstU32<1000>;
     type ZeroSizeBucketFixedRate = ConstU128<1>;
     type ProviderTopUpTtl = ConstU32<10>;
     type MaxExpiredItemsInBlock = ConstU32<100>;
+    type MaxCapacityUtilizationSamples = ConstU32<100>;
+    type CapacityUtilizationSamplingPeriod = ConstU32<10>;
+    type RequireCapacityAttestation =
+        runtime_params::dynamic_params::runtime_config::RequireCapacityAttestation;
+    type CapacityAttestationChunkSize = ConstU64<10>;
+    type CapacityAttestationSpotChecks = ConstU32<4>;
+    type CapacityAttestationWindow = MaxBlocksForRandomness;
+    type CapacityDecreaseGracePeriod = CapacityDecreaseGracePeriod;
+    type ReputationGrowthStreak = ConstU32<30>;
+    type ReputationWeightIncrease = ConstU32<1>;
+    type MaxReputationWeight = ConstU32<100>;
+    type ReputationDecayOnSlash = ReputationDecayOnSlash;
     #[cfg(feature = "runtime-benchmarks")]
     type BenchmarkHelpers = ProvidersBenchmarkHelpers;
 }
@@ -573,6 +602,9 @@ impl pallet_storage_providers::Config for Runtime {
 parameter_types! {
     pub const PaymentStreamHoldReason: RuntimeHoldReason = RuntimeHoldReason::PaymentStreams(pallet_payment_streams::HoldReason::PaymentStreamDeposit);
     pub const UserWithoutFundsCooldown: BlockNumber = 100;
+    pub const MaxOraclePriceAge: BlockNumber = HOURS;
+    pub const OraclePriceAdjustmentPeriod: BlockNumber = DAYS;
+    pub const MaxOraclePriceAdjustment: Perbill = Perbill::from_percent(5);
 }
 
 // Converter from the BlockNumber type to the Balance type for math
@@ -609,6 +641,10 @@ impl pallet_payment_streams::Config for Runtime {
     type TreasuryAccount = TreasuryAccount;
     type MaxUsersToCharge = ConstU32<10>;
     type BaseDeposit = ConstU128<10>;
+    type PriceOracle = (); // No oracle feed yet, so the price per giga-unit per tick can't track a fiat target
+    type MaxOraclePriceAge = MaxOraclePriceAge;
+    type OraclePriceAdjustmentPeriod = OraclePriceAdjustmentPeriod;
+    type MaxOraclePriceAdjustment = MaxOraclePriceAdjustment;
 }
 
 // TODO: remove this and replace with pallet treasury
@@ -776,6 +812,17 @@ impl pallet_proofs_dealer::Config for Runtime {
     type BlockFullnessHeadroom = BlockFullnessHeadroom;
     type MinNotFullBlocksRatio = MinNotFullBlocksRatio;
     type MaxSlashableProvidersPerTick = MaxSlashableProvidersPerTick;
+    type MaxCatchUpTicksPerBlock = ConstU32<100>;
+    type MissedDeadlinesAllowance =
+        runtime_params::dynamic_params::runtime_config::MissedDeadlinesAllowance;
+    type MissedDeadlineWarningWindow =
+        runtime_params::dynamic_params::runtime_config::MissedDeadlineWarningWindow;
+    type MaxProofSize = ConstU32<{ 1024 * 1024 }>;
+    type MaxProofsPerBatch = ConstU32<10>;
+    type OffchainSignature = Signature;
+    type OffchainPublic = <Signature as Verify>::Signer;
+    // Proofs are time-sensitive, so they take precedence over any other transaction.
+    type UnsignedProofPriority = ConstU64<{ u64::MAX }>;
 }
 
 /// Structure to mock a verifier that returns `true` when `proof` is not empty
@@ -847,6 +894,10 @@ parameter_types! {
     pub const MaxBatchConfirmStorageRequests: u32 = 10;
     pub const MinWaitForStopStoring: BlockNumber = 10;
     pub const StorageRequestCreationDeposit: Balance = 10;
+    pub const MspOnlyStorageRequestCreationDeposit: Balance = 5;
+    pub const DeletionListItemDeposit: Balance = 1;
+    pub const StorageRequestRevocationCompensation: Perbill = Perbill::from_percent(50);
+    pub const MaxFileDeletionFee: Balance = UNIT;
     pub const FileSystemHoldReason: RuntimeHoldReason = RuntimeHoldReason::FileSystem(pallet_file_system::HoldReason::StorageRequestCreationHold);
 }
 
@@ -883,13 +934,35 @@ impl pallet_file_system::Config for Runtime {
     type MaxDataServerMultiAddresses = ConstU32<10>;
     type MaxExpiredItemsInBlock = ConstU32<100>;
     type StorageRequestTtl = ConstU32<40>;
+    type StorageRequestExpirationNotificationLeadTime = ConstU32<10>;
+    type MaxStorageRequestExpirationNotificationsPerBlock = ConstU32<100>;
     type PendingFileDeletionRequestTtl = ConstU32<40u32>;
     type MoveBucketRequestTtl = ConstU32<40u32>;
     type MaxUserPendingDeletionRequests = ConstU32<10u32>;
     type MaxUserPendingMoveBucketRequests = ConstU32<10u32>;
     type MinWaitForStopStoring = MinWaitForStopStoring;
     type StorageRequestCreationDeposit = StorageRequestCreationDeposit;
+    type MspOnlyStorageRequestCreationDeposit = MspOnlyStorageRequestCreationDeposit;
+    type StorageRequestRevocationCompensation = StorageRequestRevocationCompensation;
+    type StorageRequestRevocationCompensationPeriod = ConstU32<20>;
+    type FileDeletionFee = runtime_params::dynamic_params::runtime_config::FileDeletionFee;
+    type MaxFileDeletionFee = MaxFileDeletionFee;
     type DefaultReplicationTarget = ConstU32<2>;
+    type PriorityChallengesMeteringPeriod = ConstU32<600>;
+    type PriorityChallengesSoftLimitPerIssuer = ConstU32<100>;
+    type MaxDeferredPriorityChallengesPerIssuer = ConstU32<1000>;
+    type MaxDeferredPriorityChallengeIssuers = ConstU32<1000>;
+    type MaxDeferredPriorityChallengesQueuedPerBlock = ConstU32<10>;
+    type MaxProviderStatusChangeItemsPerBlock = ConstU32<100>;
+    type MaxDepositTransferRetries = ConstU32<100>;
+    type MaxDepositTransferRetryAttempts = ConstU32<8>;
+    type DepositTransferRetryBaseDelay = ConstU32<10>;
+    type MaxNotificationPreferencesLength = ConstU32<256>;
+    type MaxFileAccessListSize = ConstU32<100>;
+    type MinBillableFileSize = runtime_params::dynamic_params::runtime_config::MinBillableFileSize;
+    type MaxDeletionListProofDepth = ConstU32<32>;
+    type DeletionListItemDeposit = DeletionListItemDeposit;
+    type MaxAccessGrantsPerTarget = ConstU32<100>;
 }
 
 // Converter from the Balance type to the BlockNumber type for math.
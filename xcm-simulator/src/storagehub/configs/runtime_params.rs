@@ -1,8 +1,9 @@
 use crate::storagehub::{
-    configs::{ChallengeTicksTolerance, SpMinDeposit},
+    configs::{ChallengeTicksTolerance, SpMinDeposit, StorageDataUnit},
     Balance, BlockNumber, Runtime, UNIT,
 };
 use frame_support::dynamic_params::{dynamic_pallet_params, dynamic_params};
+use shp_constants::GIGAUNIT;
 use sp_runtime::Perbill;
 
 #[dynamic_params(RuntimeParameters, pallet_parameters::Parameters::<Runtime>)]
@@ -72,6 +73,50 @@ pub mod dynamic_params {
         ///  This can be interpreted as "a Provider with 10k UNITs of stake would get the minimum seed period".
         pub static StakeToSeedPeriod: Balance =
             10_000 * UNIT * Into::<u128>::into(MinSeedPeriod::get());
+
+        #[codec(index = 19)]
+        #[allow(non_upper_case_globals)]
+        /// 1 GiB. Providers storing less (or more) than this are slashed proportionally less (or
+        /// more) than [`SlashAmountPerMaxFileSize`] for every failed proof submission.
+        pub static SlashReferenceDataSize: StorageDataUnit = GIGAUNIT as StorageDataUnit;
+
+        #[codec(index = 20)]
+        #[allow(non_upper_case_globals)]
+        /// Every proof submission deadline missed in a row after the first one adds 10% to the slash.
+        pub static SlashEscalationPerMissedDeadline: Perbill = Perbill::from_percent(10);
+
+        #[codec(index = 21)]
+        #[allow(non_upper_case_globals)]
+        /// Slashes can escalate up to 5 times the base slash amount.
+        pub static MaxSlashEscalationMultiplier: u32 = 5;
+
+        #[codec(index = 22)]
+        #[allow(non_upper_case_globals)]
+        /// BSPs are not required to attest their capacity until this is enabled.
+        pub static RequireCapacityAttestation: bool = false;
+
+        #[codec(index = 23)]
+        #[allow(non_upper_case_globals)]
+        /// The first missed proof submission deadline in a warning window only gets the Provider a
+        /// warning, instead of marking it as slashable.
+        pub static MissedDeadlinesAllowance: u32 = 1;
+
+        #[codec(index = 24)]
+        #[allow(non_upper_case_globals)]
+        /// 14400 blocks = 24 hours. Window after a warning in which missing another proof submission
+        /// deadline beyond the [`MissedDeadlinesAllowance`] marks the Provider as slashable.
+        pub static MissedDeadlineWarningWindow: BlockNumber = 14_400;
+
+        #[codec(index = 25)]
+        #[allow(non_upper_case_globals)]
+        /// Fee paid by users requesting to delete a file without a proof of inclusion, to whoever
+        /// executes the deletion. Capped at `MaxFileDeletionFee`.
+        pub static FileDeletionFee: Balance = UNIT / 100;
+
+        #[codec(index = 26)]
+        #[allow(non_upper_case_globals)]
+        /// 1 KiB, the size of a file chunk. Minimum size files are billed as by the BSPs storing them.
+        pub static MinBillableFileSize: StorageDataUnit = 1024;
     }
 }
 
@@ -1247,6 +1247,7 @@ mod users {
                     name: bucket_name.clone(),
                     private: false,
                     value_prop_id: Some(value_prop_id),
+                    encryption_metadata: None,
                 });
             let estimated_weight = bucket_creation_call.get_dispatch_info().weight;
             // Remember, this message will be executed from the context of StorageHub
@@ -1316,6 +1317,7 @@ mod users {
                     msp_id: Some(alice_msp_id.clone()),
                     peer_ids: parachain_peer_id,
                     replication_target: None,
+                    auto_renew: false,
                 });
             let estimated_weight = file_creation_call.get_dispatch_info().weight;
             // Remember, this message will be executed from the context of StorageHub
@@ -1607,6 +1609,7 @@ mod users {
                     name: bucket_name.clone(),
                     private: false,
                     value_prop_id: Some(value_prop_id),
+                    encryption_metadata: None,
                 });
             let estimated_weight = bucket_creation_call.get_dispatch_info().weight;
             // Remember, this message will be executed from the context of StorageHub
@@ -1688,6 +1691,7 @@ mod users {
                     msp_id: Some(alice_msp_id.clone()),
                     peer_ids: parachain_peer_id,
                     replication_target: None,
+                    auto_renew: false,
                 });
             let estimated_weight = file_creation_call.get_dispatch_info().weight;
             // Remember, this message will be executed from the context of StorageHub